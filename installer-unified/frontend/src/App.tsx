@@ -1,17 +1,49 @@
 import { useEffect, useMemo, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
-import { open } from '@tauri-apps/plugin-dialog';
-import { listenToEvent, preflightDataSource, type DiscoveredColumnDto, type ProgressEvent } from './lib/api';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import {
+  autoMap,
+  estimateBackfill,
+  exportMappingCsv,
+  getFreeSpaceBytes,
+  getLicenseStatus,
+  getProxySettings,
+  importMappingCsv,
+  LICENSE_FEATURE_ARCHIVE,
+  LICENSE_FEATURE_MULTI_DATASOURCE,
+  listenToEvent,
+  listSourceObjects,
+  notIncludedInLicenseMessage,
+  preflightDataSource,
+  preflightDependencies,
+  projectDiskSpace,
+  inspectStorageFilesystem,
+  saveProxySettings,
+  verifySetup,
+  type BackfillEstimateDto,
+  type DiscoveredColumnDto,
+  type FileSourceConfig,
+  type MappingSuggestion,
+  type PreflightDependencyCheckDto,
+  type ProgressEvent,
+  type RemoteTarget,
+  type SetupVerifyResponse,
+  type SpaceProjectionDto,
+  type StorageFilesystemDto,
+} from './lib/api';
 import PlatformChooser from './components/PlatformChooser';
 import WizardFrame from './components/WizardFrame';
 import Modal, { type ModalState, emptyModal } from './components/Modal';
+import TransformsModal, { type FieldTransform } from './components/TransformsModal';
 import {
   PlatformStep,
   WelcomeStep,
   LicenseStep,
+  NetworkStep,
   InstallTypeStep,
   DestinationStep,
+  LAYOUT_ROLES,
   DataSourceStep,
   DatabaseStep,
   StorageStep,
@@ -22,7 +54,12 @@ import {
   ReadyStep,
   InstallingStep,
   CompleteStep,
+  type InstallLayoutPaths,
+  type LayoutFreeSpace,
+  type LayoutRole,
+  type ConnectionStage,
 } from './components/steps';
+import { t } from './lib/i18n';
 import './App.css';
 
 type ScreenMode = 'chooser' | 'installer';
@@ -45,6 +82,7 @@ type WizardPage =
   | 'platform'
   | 'welcome'
   | 'license'
+  | 'network'
   | 'installType'
   | 'destination'
   | 'dataSource'
@@ -62,6 +100,7 @@ type WizardPage =
 const WIZARD_PAGES: WizardPage[] = [
   'welcome',
   'license',
+  'network',
   'installType',
   'destination',
   'dataSource',
@@ -76,24 +115,6 @@ const WIZARD_PAGES: WizardPage[] = [
   'complete',
 ];
 
-const WIZARD_STEP_NAMES: Record<WizardPage, string> = {
-  platform: 'Platform',
-  welcome: 'Welcome',
-  license: 'License',
-  installType: 'Installation Type',
-  destination: 'Destination',
-  dataSource: 'Data Source',
-  database: 'Database',
-  storage: 'Storage',
-  retention: 'Retention',
-  archive: 'Archive',
-  consent: 'Consent',
-  mapping: 'Mapping',
-  ready: 'Review',
-  installing: 'Installing',
-  complete: 'Complete',
-};
-
 function getStepInfo(page: WizardPage): { currentStep: number; totalSteps: number } {
   const index = WIZARD_PAGES.indexOf(page);
   if (index === -1) return { currentStep: 0, totalSteps: WIZARD_PAGES.length };
@@ -117,12 +138,42 @@ interface InstallResultEvent {
     manifestPath?: string;
     mappingPath?: string;
     configPath?: string;
+    planPath?: string;
+    errorCode?: string;
   } | null;
 }
 
+/** Pre-seeded defaults from CADALYTIX_INSTALLER_* (see utils::env_defaults); unset fields keep the built-in ones. */
+interface WizardDefaults {
+  destinationPath?: string | null;
+  dbHost?: string | null;
+  dbPort?: number | null;
+  dbName?: string | null;
+  dbUser?: string | null;
+  sourceHost?: string | null;
+  sourcePort?: number | null;
+  archiveDestination?: string | null;
+  archiveMaxUsageGb?: number | null;
+  retentionMonths?: number | null;
+  /** `--instance` the installer runs for; the default install path gets a `-<name>` suffix. */
+  instanceName?: string | null;
+}
+
 interface TestDbConnectionResponse {
   success: boolean;
   message: string;
+  missingPermissions?: string[];
+  failure?:
+    | 'input'
+    | 'hostNotFound'
+    | 'network'
+    | 'tls'
+    | 'authentication'
+    | 'databaseMissing'
+    | 'query'
+    | 'permissions'
+    | null;
+  stages?: ConnectionStage[];
 }
 
 interface TargetField {
@@ -144,9 +195,46 @@ function normalizeWindowsPath(path: string): string {
   return path.trim().split('/').join('\\');
 }
 
-function defaultInstallPath(mode: InstallMode): string {
-  if (mode === 'windows') return 'C:\\\\Program Files\\\\CADalytix';
-  return '/opt/cadalytix';
+/** Quotes an ADO.NET connection string value (same rules as database::conn_string::ado_value). */
+function adoValue(v: string): string {
+  return /[;="']/.test(v) || v.trim() !== v || v.startsWith('{') ? `"${v.replace(/"/g, '""')}"` : v;
+}
+
+function defaultInstallPath(mode: InstallMode | 'linux', instanceName?: string | null): string {
+  // Mirrors `utils::instance::qualify`: lowercase names on the lowercase Linux path.
+  if (mode === 'windows') return `C:\\\\Program Files\\\\CADalytix${instanceName ? `-${instanceName}` : ''}`;
+  return `/opt/cadalytix${instanceName ? `-${instanceName.toLowerCase()}` : ''}`;
+}
+
+const GIB = 1024 * 1024 * 1024;
+
+/** Default folders for an install path; mirrors `installation::layout::default_layout`. */
+function defaultLayout(mode: InstallMode | 'linux', engine: 'sqlserver' | 'postgres', root: string): InstallLayoutPaths {
+  const windows = mode === 'windows';
+  const sep = windows ? '\\' : '/';
+  const base = root.trim().replace(/[\\/]+$/, '');
+  const engineDir = engine === 'postgres' ? (windows ? 'PostgreSQL' : 'postgresql') : windows ? 'SQLServer' : 'mssql';
+  const [data, logs, temp] = windows ? ['Data', 'Logs', 'Temp'] : ['data', 'logs', 'tmp'];
+  return {
+    binaries: base,
+    data: [base, data, engineDir].join(sep),
+    logs: [base, logs].join(sep),
+    temp: [base, temp].join(sep),
+  };
+}
+
+/** Recommended free space per folder; mirrors `installation::layout::min_free_bytes`. */
+function layoutMinFreeBytes(role: LayoutRole, maxDbSizeGb: number): number {
+  switch (role) {
+    case 'binaries': return 5 * GIB;
+    case 'data': return maxDbSizeGb > 0 ? maxDbSizeGb * GIB : 10 * GIB;
+    case 'logs': return GIB;
+    default: return 2 * GIB;
+  }
+}
+
+function isAbsolutePath(p: string): boolean {
+  return p.startsWith('/') || p.startsWith('\\\\') || /^[a-zA-Z]:[\\/]/.test(p);
 }
 
 function disambiguateSourceColumns(cols: DiscoveredColumnDto[]): SourceField[] {
@@ -170,7 +258,13 @@ function disambiguateSourceColumns(cols: DiscoveredColumnDto[]): SourceField[] {
     const displayName = total > 1 ? `${c.name} (${n})` : c.name;
     const base = sanitizeBase(c.name) || 'col';
     const ordinal = n - 1; // 0-based ordinal per duplicate group
-    return { id: `${base}__${ordinal}`, rawName: c.name, displayName };
+    return {
+      id: `${base}__${ordinal}`,
+      rawName: c.name,
+      displayName,
+      dataType: c.dataType,
+      sampleValues: c.sampleValues ?? [],
+    };
   });
 }
 
@@ -212,6 +306,12 @@ export default function App() {
   const licenseText =
     'LICENSE TEXT NOT PROVIDED.\n\nPlace your license text (EULA) under Prod_Install_Wizard_Deployment/licenses/ and wire the loader to display it here.';
 
+  // Feature flags of the verified license (null = no verified license; nothing is gated).
+  const [licensedFeatures, setLicensedFeatures] = useState<string[] | null>(null);
+  const isLicensed = (feature: string) => licensedFeatures === null || licensedFeatures.includes(feature);
+  const archiveLicensed = isLicensed(LICENSE_FEATURE_ARCHIVE);
+  const multiDatasourceLicensed = isLicensed(LICENSE_FEATURE_MULTI_DATASOURCE);
+
   // Global wizard settings
   const [installationType, setInstallationType] = useState<InstallationType>('typical');
   const [importConfigPath, setImportConfigPath] = useState('');
@@ -219,10 +319,26 @@ export default function App() {
 
   const [destinationFolder, setDestinationFolder] = useState(defaultInstallPath('windows'));
   const [destinationError, setDestinationError] = useState<string | null>(null);
+  // Advanced layout: separate binaries / data / logs / temp folders (empty = default).
+  const [layoutAdvanced, setLayoutAdvanced] = useState(false);
+  const [layoutPaths, setLayoutPaths] = useState<InstallLayoutPaths>({ binaries: '', data: '', logs: '', temp: '' });
+  const [layoutFreeSpace, setLayoutFreeSpace] = useState<Partial<Record<LayoutRole, LayoutFreeSpace>>>({});
+  // Remote install over SSH (Linux-native on that server); null installs on this machine.
+  const [remoteTarget, setRemoteTarget] = useState<RemoteTarget | null>(null);
+  const remoteInstall = remoteTarget !== null;
+  const targetMode = remoteInstall ? 'linux' : installMode;
 
   // Data source/environment
-  const [dataSourceKind, setDataSourceKind] = useState<'local' | 'remote'>('local');
+  const [dataSourceKind, setDataSourceKind] = useState<'local' | 'remote' | 'odbc' | 'oracle' | 'file'>('local');
   const [sourceObjectName, setSourceObjectName] = useState('dbo.CallData');
+  // ODBC source (dataSourceKind === 'odbc'): DSN or driver connection string
+  const [odbcConnectionString, setOdbcConnectionString] = useState('DSN=');
+  // Oracle source (dataSourceKind === 'oracle'): Instant Client ODBC driver; blank driver = default
+  const [oracleDriver, setOracleDriver] = useState('');
+  const [oracleAddress, setOracleAddress] = useState('');
+  // Export folder source (dataSourceKind === 'file')
+  const [fileSourceFolder, setFileSourceFolder] = useState('');
+  const [fileSourceDelimiter, setFileSourceDelimiter] = useState('auto');
 
   // Call data connection (SQL Server; used for schema scan)
   const [callDataHost, setCallDataHost] = useState('localhost');
@@ -230,6 +346,8 @@ export default function App() {
   const [callDataDbName, setCallDataDbName] = useState('');
   const [callDataUser, setCallDataUser] = useState('');
   const [callDataPassword, setCallDataPassword] = useState('');
+  // Windows Authentication (SSPI on Windows, Kerberos on Linux): no SQL login.
+  const [callDataWindowsAuth, setCallDataWindowsAuth] = useState(false);
 
   // Database setup
   // D2 Database Setup Wizard (New vs Existing)
@@ -252,9 +370,16 @@ export default function App() {
   const [newDbLogFilegrowth, setNewDbLogFilegrowth] = useState('-10'); // -10 = 10%
   // Phase 9: PostgreSQL owner field
   const [newDbPgOwner, setNewDbPgOwner] = useState('');
+  // SQL Server collation or PostgreSQL locale (blank = server default)
+  const [newDbCollation, setNewDbCollation] = useState('');
+  // Dedicated least-privilege login for the application (blank name = cadalytix_app)
+  const [appAccountEnabled, setAppAccountEnabled] = useState(false);
+  const [appLoginName, setAppLoginName] = useState('');
   // Phase 9: Create NEW - privilege test state
   const [newDbPrivTestStatus, setNewDbPrivTestStatus] = useState<'idle' | 'testing' | 'success' | 'fail'>('idle');
   const [newDbPrivTestMessage, setNewDbPrivTestMessage] = useState('');
+  // Least-privilege path: grant script for a DBA when the admin login cannot create databases.
+  const [newDbGrantScript, setNewDbGrantScript] = useState<string | null>(null);
   const [existingHostedWhere, setExistingHostedWhere] = useState<
     'on_prem' | 'aws_rds' | 'azure_sql' | 'gcp_cloud_sql' | 'neon' | 'supabase' | 'other'
   >('on_prem');
@@ -262,6 +387,7 @@ export default function App() {
   const [dbName, setDbName] = useState('cadalytix');
   const [dbUser, setDbUser] = useState('cadalytix_admin');
   const [dbPassword, setDbPassword] = useState('');
+  const [dbWindowsAuth, setDbWindowsAuth] = useState(false);
   const [dbHost, setDbHost] = useState('localhost');
   const [dbPort, setDbPort] = useState('1433');
   const [dbSslMode, setDbSslMode] = useState<'disable' | 'prefer' | 'require'>('prefer');
@@ -270,6 +396,7 @@ export default function App() {
 
   const [dbTestStatus, setDbTestStatus] = useState<'idle' | 'testing' | 'success' | 'fail'>('idle');
   const [dbTestMessage, setDbTestMessage] = useState<string>('');
+  const [dbTestStages, setDbTestStages] = useState<ConnectionStage[]>([]);
 
   // Storage policy
   const [storageMode, setStorageMode] = useState<'defaults' | 'custom'>('defaults');
@@ -277,6 +404,7 @@ export default function App() {
   const [storageCustomPath, setStorageCustomPath] = useState('');
   const [retentionPolicy, setRetentionPolicy] = useState<'18' | '12' | 'max' | 'keep'>('18');
   const [maxDiskGb, setMaxDiskGb] = useState('100');
+  const [storageFilesystem, setStorageFilesystem] = useState<StorageFilesystemDto | null>(null);
 
   // Retention + archive policy (Phase 5 extension)
   const [hotRetentionChoice, setHotRetentionChoice] = useState<'12' | '18' | 'custom'>('18');
@@ -288,11 +416,34 @@ export default function App() {
   const [archiveScheduleDayOfMonth, setArchiveScheduleDayOfMonth] = useState('1');
   const [archiveScheduleTimeLocal, setArchiveScheduleTimeLocal] = useState('00:05');
   const [archiveCatchUpOnStartup, setArchiveCatchUpOnStartup] = useState(true);
-
-  // Consent to sync (OFF by default; stored only)
+  const [archiveEncrypt, setArchiveEncrypt] = useState(false);
+
+  // Network page: optional explicit proxy (saved by the backend; password encrypted)
+  const [proxyHost, setProxyHost] = useState('');
+  const [proxyPort, setProxyPort] = useState('');
+  const [proxyUsername, setProxyUsername] = useState('');
+  const [proxyPassword, setProxyPassword] = useState('');
+  const [proxyHasSavedPassword, setProxyHasSavedPassword] = useState(false);
+  const [bypassSystemProxy, setBypassSystemProxy] = useState(false);
+  const [envProxy, setEnvProxy] = useState<string | null>(null);
+  const [proxyError, setProxyError] = useState<string | null>(null);
+
+  // Consent to sync (OFF by default)
   const [consentToSync, setConsentToSync] = useState(false);
   const [consentDetailsExpanded, setConsentDetailsExpanded] = useState(false);
 
+  // Ready page: plan only (dry run); nothing is changed
+  const [dryRun, setDryRun] = useState(false);
+  const [backfillEstimate, setBackfillEstimate] = useState<BackfillEstimateDto | null>(null);
+  const [backfillEstimateError, setBackfillEstimateError] = useState<string | null>(null);
+  const [backfillEstimating, setBackfillEstimating] = useState(false);
+  // Ready page: projected disk use per volume; a shortfall blocks Install
+  const [diskSpace, setDiskSpace] = useState<SpaceProjectionDto | null>(null);
+  const [diskSpaceError, setDiskSpaceError] = useState<string | null>(null);
+  const [diskSpaceChecking, setDiskSpaceChecking] = useState(false);
+  const [diskSpaceCheckCount, setDiskSpaceCheckCount] = useState(0);
+  const [installPaused, setInstallPaused] = useState(false);
+
   // Schema mapping
   const [mappingOverride, setMappingOverride] = useState(false);
   const [mappingDemoMode, setMappingDemoMode] = useState(false);
@@ -304,8 +455,18 @@ export default function App() {
   const [selectedTargetId, setSelectedTargetId] = useState<string | null>(null);
   const [sourceToTargets, setSourceToTargets] = useState<Record<string, string[]>>({});
   const [targetToSource, setTargetToSource] = useState<Record<string, string>>({});
+  // Per-target value transforms (keyed by target id) and the target whose transforms modal is open.
+  const [mappingTransforms, setMappingTransforms] = useState<Record<string, FieldTransform[]>>({});
+  const [transformsTargetId, setTransformsTargetId] = useState<string | null>(null);
+  // Auto-map suggestions by target id; pending until confirmed (see pendingSuggestions).
+  const [suggestedMappings, setSuggestedMappings] = useState<Record<string, MappingSuggestion>>({});
+  const [autoMapRunning, setAutoMapRunning] = useState(false);
 
   const [mappingScanError, setMappingScanError] = useState<string | null>(null);
+  const [dependencyChecks, setDependencyChecks] = useState<PreflightDependencyCheckDto[] | null>(null);
+  const [dependencyStatus, setDependencyStatus] = useState<string | null>(null);
+  const [dependencyChecking, setDependencyChecking] = useState(false);
+  const [dependencyError, setDependencyError] = useState<string | null>(null);
   const [mappingScanning, setMappingScanning] = useState(false);
   const mappingAutoScanKeyRef = useRef<string>('');
 
@@ -316,11 +477,18 @@ export default function App() {
   const [installManifestPath, setInstallManifestPath] = useState<string | null>(null);
   const [installMappingPath, setInstallMappingPath] = useState<string | null>(null);
   const [installConfigPath, setInstallConfigPath] = useState<string | null>(null);
+  const [archiveProgress, setArchiveProgress] = useState<ProgressEvent | null>(null);
+  const [archiveRunning, setArchiveRunning] = useState(false);
+  const [archiveResult, setArchiveResult] = useState<string | null>(null);
+  const [health, setHealth] = useState<SetupVerifyResponse | null>(null);
+  const [healthChecking, setHealthChecking] = useState(false);
+  const [healthError, setHealthError] = useState<string | null>(null);
   const [installDetailLines, setInstallDetailLines] = useState<string[]>([]);
   const isInstalling = page === 'installing';
 
   const platformFocus = useRef<'windows' | 'docker'>('windows');
   const dbPortTouchedRef = useRef(false);
+  const [wizardDefaults, setWizardDefaults] = useState<WizardDefaults | null>(null);
 
   // Derived engine inference for DB test. We do NOT ask the user to pick a provider/engine;
   // we infer it from hosted-where, port defaults, or the connection string itself.
@@ -359,6 +527,8 @@ export default function App() {
     return installMode === 'windows' ? 'sqlserver' : 'postgres';
   }, [dbConnString, dbPort, dbSetupMode, dbUseConnString, existingHostedWhere, installMode, newDbAdminPort]);
 
+  const dbUsesWindowsAuth = dbWindowsAuth && dbEngine !== 'postgres';
+
   const computedConfigDbConnectionString = useMemo(() => {
     if (dbUseConnString && dbConnString.trim()) return dbConnString.trim();
 
@@ -380,18 +550,149 @@ export default function App() {
     const user = dbUser.trim();
     const pass = dbPassword;
     const encrypt = dbSslMode === 'disable' ? 'false' : 'true';
-    return `Server=${server};Database=${db};User Id=${user};Password=${pass};TrustServerCertificate=true;Encrypt=${encrypt};`;
-  }, [dbConnString, dbEngine, dbHost, dbName, dbPassword, dbPort, dbSslMode, dbUseConnString, dbUser]);
+    const auth = dbUsesWindowsAuth ? 'Integrated Security=true' : `User Id=${adoValue(user)};Password=${adoValue(pass)}`;
+    return `Server=${adoValue(server)};Database=${adoValue(db)};${auth};TrustServerCertificate=true;Encrypt=${encrypt};`;
+  }, [dbConnString, dbEngine, dbHost, dbName, dbPassword, dbPort, dbSslMode, dbUseConnString, dbUser, dbUsesWindowsAuth]);
 
   const computedCallDataConnectionString = useMemo(() => {
+    if (dataSourceKind === 'odbc') return odbcConnectionString.trim();
+    if (dataSourceKind === 'oracle') {
+      // Same shape as datasource::oracle::connection_string.
+      const odbcValue = (v: string) => (/[;{} ]/.test(v) ? `{${v.replace(/}/g, '}}')}}` : v);
+      const address = oracleAddress.trim();
+      const slash = address.indexOf('/');
+      const dbq = slash > 0 && !address.slice(0, slash).includes(':') ? `${address.slice(0, slash)}:1521${address.slice(slash)}` : address;
+      const driver = oracleDriver.trim() || 'Oracle 21 ODBC driver';
+      return `Driver=${odbcValue(driver)};DBQ=${odbcValue(dbq)};UID=${odbcValue(callDataUser.trim())};PWD=${odbcValue(callDataPassword)};`;
+    }
     const host = callDataHost.trim() || 'localhost';
     const port = callDataPort.trim() || '1433';
     const server = port ? `${host},${port}` : host;
     const db = callDataDbName.trim();
     const user = callDataUser.trim();
     const pass = callDataPassword;
-    return `Server=${server};Database=${db};User Id=${user};Password=${pass};TrustServerCertificate=true;Encrypt=false;`;
-  }, [callDataDbName, callDataHost, callDataPassword, callDataPort, callDataUser]);
+    const auth = callDataWindowsAuth ? 'Integrated Security=true' : `User Id=${adoValue(user)};Password=${adoValue(pass)}`;
+    return `Server=${adoValue(server)};Database=${adoValue(db)};${auth};TrustServerCertificate=true;Encrypt=false;`;
+  }, [
+    callDataDbName,
+    callDataHost,
+    callDataPassword,
+    callDataPort,
+    callDataUser,
+    callDataWindowsAuth,
+    dataSourceKind,
+    odbcConnectionString,
+    oracleAddress,
+    oracleDriver,
+  ]);
+  const callDataDriver = dataSourceKind === 'odbc' || dataSourceKind === 'oracle' ? dataSourceKind : 'sqlserver';
+
+  // Saved connection profiles use the `--tui-script` answer keys (shared with the TUI).
+  const dataSourceProfileValues = useMemo<Record<string, string>>(
+    () => ({
+      kind: dataSourceKind,
+      object: sourceObjectName,
+      connection_string: odbcConnectionString,
+      driver: oracleDriver,
+      address: oracleAddress,
+      folder: fileSourceFolder,
+      delimiter: fileSourceDelimiter,
+      host: callDataHost,
+      port: callDataPort,
+      database: callDataDbName,
+      user: callDataUser,
+      password: callDataPassword,
+      windows_auth: callDataWindowsAuth ? 'yes' : 'no',
+    }),
+    [
+      callDataDbName,
+      callDataHost,
+      callDataPassword,
+      callDataPort,
+      callDataUser,
+      callDataWindowsAuth,
+      dataSourceKind,
+      fileSourceDelimiter,
+      fileSourceFolder,
+      odbcConnectionString,
+      oracleAddress,
+      oracleDriver,
+      sourceObjectName,
+    ]
+  );
+  const loadDataSourceProfile = (v: Record<string, string>) => {
+    if (['local', 'remote', 'odbc', 'oracle', 'file'].includes(v.kind)) {
+      setDataSourceKind(v.kind as typeof dataSourceKind);
+    }
+    if (v.object !== undefined) setSourceObjectName(v.object);
+    if (v.connection_string !== undefined) setOdbcConnectionString(v.connection_string);
+    if (v.driver !== undefined) setOracleDriver(v.driver);
+    if (v.address !== undefined) setOracleAddress(v.address);
+    if (v.folder !== undefined) setFileSourceFolder(v.folder);
+    if (v.delimiter !== undefined) setFileSourceDelimiter(v.delimiter);
+    if (v.host !== undefined) setCallDataHost(v.host);
+    if (v.port !== undefined) setCallDataPort(v.port);
+    if (v.database !== undefined) setCallDataDbName(v.database);
+    if (v.user !== undefined) setCallDataUser(v.user);
+    if (v.password !== undefined) setCallDataPassword(v.password);
+    if (v.windows_auth !== undefined) setCallDataWindowsAuth(v.windows_auth === 'yes');
+  };
+
+  const databaseProfileValues = useMemo<Record<string, string>>(
+    () => ({
+      hosted: existingHostedWhere,
+      host: dbHost,
+      port: dbPort,
+      database: dbName,
+      user: dbUser,
+      password: dbPassword,
+      tls: dbSslMode,
+      windows_auth: dbWindowsAuth ? 'yes' : 'no',
+      ...(dbUseConnString ? { connection_string: dbConnString } : {}),
+    }),
+    [dbConnString, dbHost, dbName, dbPassword, dbPort, dbSslMode, dbUseConnString, dbUser, dbWindowsAuth, existingHostedWhere]
+  );
+  const loadDatabaseProfile = (v: Record<string, string>) => {
+    if (['on_prem', 'aws_rds', 'azure_sql', 'gcp_cloud_sql', 'neon', 'supabase', 'other'].includes(v.hosted)) {
+      setExistingHostedWhere(v.hosted as typeof existingHostedWhere);
+    }
+    if (v.host !== undefined) setDbHost(v.host);
+    if (v.port !== undefined) {
+      dbPortTouchedRef.current = true;
+      setDbPort(v.port);
+    }
+    if (v.database !== undefined) setDbName(v.database);
+    if (v.user !== undefined) setDbUser(v.user);
+    if (v.password !== undefined) setDbPassword(v.password);
+    if (v.tls === 'disable' || v.tls === 'prefer' || v.tls === 'require') setDbSslMode(v.tls);
+    if (v.windows_auth !== undefined) setDbWindowsAuth(v.windows_auth === 'yes');
+    setDbUseConnString(v.connection_string !== undefined);
+    setDbConnString(v.connection_string ?? '');
+    setDbTestStatus('idle');
+    setDbTestMessage('');
+  };
+
+  const fileSource = useMemo<FileSourceConfig | null>(
+    () => (dataSourceKind === 'file' ? { folder: fileSourceFolder.trim(), delimiter: fileSourceDelimiter } : null),
+    [dataSourceKind, fileSourceDelimiter, fileSourceFolder]
+  );
+  const dataSourceSetupError = useMemo(() => {
+    if (fileSource && !fileSource.folder) return t('validation.exportFolderRequired');
+    if (dataSourceKind === 'odbc') {
+      const conn = odbcConnectionString.trim();
+      if (!conn || conn === 'DSN=') return t('validation.odbcConnectionRequired');
+      if (!sourceObjectName.trim()) return t('validation.sourceObjectRequired');
+    }
+    if (dataSourceKind === 'oracle') {
+      const address = oracleAddress.trim();
+      if (!address) return t('validation.oracleAddressRequired');
+      if (address.includes('/') && (address.startsWith('/') || address.endsWith('/'))) {
+        return t('validation.oracleAddressFormat');
+      }
+      if (!sourceObjectName.trim()) return t('validation.sourceObjectRequired');
+    }
+    return null;
+  }, [dataSourceKind, fileSource, odbcConnectionString, oracleAddress, sourceObjectName]);
 
   // Phase 9: Compute maintenance/admin connection string for Create NEW mode
   // Points to master (SQL Server) or postgres (PostgreSQL) database
@@ -410,26 +711,33 @@ export default function App() {
     const server = port ? `${host},${port}` : host;
     const user = newDbAdminUser.trim();
     const pass = newDbAdminPassword;
-    return `Server=${server};Database=master;User Id=${user};Password=${pass};TrustServerCertificate=true;Encrypt=false;`;
+    return `Server=${adoValue(server)};Database=master;User Id=${adoValue(user)};Password=${adoValue(pass)};TrustServerCertificate=true;Encrypt=false;`;
   }, [dbEngine, newDbAdminHost, newDbAdminPassword, newDbAdminPort, newDbAdminUser]);
 
   const dbCreateValidationError = useMemo(() => {
     if (dbSetupMode !== 'createNew') return null;
     // Validate database name
-    if (!newDbName.trim()) return 'New database name is required.';
-    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(newDbName.trim())) return 'Database name must start with letter/underscore and contain only letters, digits, underscores.';
-    if (newDbName.trim().length > 128) return 'Database name too long (max 128 characters).';
+    if (!newDbName.trim()) return t('validation.dbNameRequired');
+    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(newDbName.trim())) return t('validation.dbNameFormat');
+    if (newDbName.trim().length > 128) return t('validation.dbNameTooLong');
     // Validate admin connection fields
-    if (!newDbAdminHost.trim()) return 'Admin host is required.';
-    if (!newDbAdminPort.trim()) return 'Admin port is required.';
-    if (!newDbAdminUser.trim()) return 'Admin username is required.';
-    if (!newDbAdminPassword.trim()) return 'Admin password is required.';
+    if (!newDbAdminHost.trim()) return t('validation.adminHostRequired');
+    if (!newDbAdminPort.trim()) return t('validation.adminPortRequired');
+    if (!newDbAdminUser.trim()) return t('validation.adminUserRequired');
+    if (!newDbAdminPassword.trim()) return t('validation.adminPasswordRequired');
     // Validate sizing
     const gb = parseInt(newDbMaxSizeGb.trim(), 10);
-    if (!Number.isFinite(gb) || gb <= 0) return 'Max DB size must be a positive number.';
-    if (newDbLocation === 'specificPath' && !newDbSpecificPath.trim()) return 'Database path is required.';
+    if (!Number.isFinite(gb) || gb <= 0) return t('validation.maxDbSizePositive');
+    if (newDbLocation === 'specificPath' && !newDbSpecificPath.trim()) return t('validation.dbPathRequired');
+    if (newDbCollation.trim() && !/^[A-Za-z0-9_.@-]{1,128}$/.test(newDbCollation.trim())) return t('validation.collationFormat');
+    return null;
+  }, [dbSetupMode, newDbLocation, newDbMaxSizeGb, newDbSpecificPath, newDbCollation, newDbName, newDbAdminHost, newDbAdminPort, newDbAdminUser, newDbAdminPassword]);
+
+  const appAccountValidationError = useMemo(() => {
+    if (!appAccountEnabled || !appLoginName.trim()) return null;
+    if (!/^[A-Za-z_][A-Za-z0-9_]{0,62}$/.test(appLoginName.trim())) return t('validation.appLoginFormat');
     return null;
-  }, [dbSetupMode, newDbLocation, newDbMaxSizeGb, newDbSpecificPath, newDbName, newDbAdminHost, newDbAdminPort, newDbAdminUser, newDbAdminPassword]);
+  }, [appAccountEnabled, appLoginName]);
 
   const dbExistingMissingInputs = useMemo(() => {
     if (dbSetupMode !== 'existing') return [] as string[];
@@ -443,8 +751,8 @@ export default function App() {
       if (!dbHost.trim()) missing.push('Host');
       if (!dbPort.trim()) missing.push('Port');
       if (!dbName.trim()) missing.push('Database');
-      if (!dbUser.trim()) missing.push('Username');
-      if (!dbPassword.trim()) missing.push('Password');
+      if (!dbUsesWindowsAuth && !dbUser.trim()) missing.push('Username');
+      if (!dbUsesWindowsAuth && !dbPassword.trim()) missing.push('Password');
     }
     return missing;
   }, [
@@ -457,6 +765,7 @@ export default function App() {
     dbName,
     dbUser,
     dbPassword,
+    dbUsesWindowsAuth,
     dbEngine,
   ]);
 
@@ -494,25 +803,98 @@ export default function App() {
 
   const retentionValidationError = useMemo(() => {
     if (hotRetentionChoice !== 'custom') return null;
-    if (hotRetentionMonths <= 0) return 'Enter a valid number of months.';
-    if (hotRetentionMonths > 240) return 'Custom months is too large.';
+    if (hotRetentionMonths <= 0) return t('validation.retentionMonthsInvalid');
+    if (hotRetentionMonths > 240) return t('validation.retentionMonthsTooLarge');
     return null;
   }, [hotRetentionChoice, hotRetentionMonths]);
 
   const archiveValidationError = useMemo(() => {
-    if (!archiveDestinationPath.trim()) return 'Archive destination folder is required.';
+    if (!archiveLicensed) return null;
+    if (!archiveDestinationPath.trim()) return t('validation.archiveDestinationRequired');
     const gb = parseInt(archiveMaxUsageGb.trim(), 10);
-    if (!Number.isFinite(gb) || gb <= 0) return 'Max archive usage must be a positive number.';
+    if (!Number.isFinite(gb) || gb <= 0) return t('validation.archiveMaxUsagePositive');
     const day = parseInt(archiveScheduleDayOfMonth.trim(), 10);
-    if (!Number.isFinite(day) || day < 1 || day > 28) return 'Schedule day must be between 1 and 28.';
-    const t = archiveScheduleTimeLocal.trim();
-    const m = /^(\d{2}):(\d{2})$/.exec(t);
-    if (!m) return 'Schedule time must be HH:MM.';
+    if (!Number.isFinite(day) || day < 1 || day > 28) return t('validation.scheduleDayRange');
+    const time = archiveScheduleTimeLocal.trim();
+    const m = /^(\d{2}):(\d{2})$/.exec(time);
+    if (!m) return t('validation.scheduleTimeFormat');
     const hh = parseInt(m[1], 10);
     const mm = parseInt(m[2], 10);
-    if (hh < 0 || hh > 23 || mm < 0 || mm > 59) return 'Schedule time must be HH:MM.';
+    if (hh < 0 || hh > 23 || mm < 0 || mm > 59) return t('validation.scheduleTimeFormat');
+    return null;
+  }, [archiveDestinationPath, archiveLicensed, archiveMaxUsageGb, archiveScheduleDayOfMonth, archiveScheduleTimeLocal]);
+
+  useEffect(() => {
+    if (screenMode !== 'installer') return;
+    void invoke<WizardDefaults>('get_wizard_defaults')
+      .then((d) => {
+        setWizardDefaults(d);
+        if (d.dbHost) {
+          setDbHost(d.dbHost);
+          setNewDbAdminHost(d.dbHost);
+        }
+        if (d.dbPort) {
+          dbPortTouchedRef.current = true;
+          setDbPort(String(d.dbPort));
+          setNewDbAdminPort(String(d.dbPort));
+        }
+        if (d.dbName) {
+          setDbName(d.dbName);
+          setNewDbName(d.dbName);
+        }
+        if (d.dbUser) setDbUser(d.dbUser);
+        if (d.sourceHost) setCallDataHost(d.sourceHost);
+        if (d.sourcePort) setCallDataPort(String(d.sourcePort));
+        if (d.archiveDestination) setArchiveDestinationPath(d.archiveDestination);
+        if (d.archiveMaxUsageGb) setArchiveMaxUsageGb(String(d.archiveMaxUsageGb));
+        if (d.retentionMonths === 12 || d.retentionMonths === 18) {
+          setHotRetentionChoice(String(d.retentionMonths) as '12' | '18');
+        } else if (d.retentionMonths) {
+          setHotRetentionChoice('custom');
+          setHotRetentionCustomMonths(String(d.retentionMonths));
+        }
+      })
+      .catch(() => setWizardDefaults(null));
+  }, [screenMode]);
+
+  useEffect(() => {
+    if (screenMode !== 'installer') return;
+    void getLicenseStatus()
+      .then((res) => {
+        const entitlement = res.success ? res.data?.entitlement : null;
+        setLicensedFeatures(entitlement ? entitlement.features.map((f) => f.trim().toLowerCase()) : null);
+      })
+      .catch(() => setLicensedFeatures(null));
+  }, [screenMode]);
+
+  useEffect(() => {
+    if (screenMode !== 'installer') return;
+    void getProxySettings()
+      .then((res) => {
+        if (!res.success || !res.data) return;
+        setProxyHost(res.data.host);
+        setProxyPort(res.data.port ? String(res.data.port) : '');
+        setProxyUsername(res.data.username ?? '');
+        setProxyHasSavedPassword(res.data.hasPassword);
+        setBypassSystemProxy(res.data.bypassSystemProxy);
+        setEnvProxy(res.data.envProxy ?? null);
+      })
+      .catch(() => {});
+  }, [screenMode]);
+
+  const proxyValidationError = useMemo(() => {
+    if (!proxyHost.trim()) return null;
+    const port = Number(proxyPort.trim());
+    if (!Number.isInteger(port) || port < 1 || port > 65535) return t('validation.proxyPortRange');
     return null;
-  }, [archiveDestinationPath, archiveMaxUsageGb, archiveScheduleDayOfMonth, archiveScheduleTimeLocal]);
+  }, [proxyHost, proxyPort]);
+
+  // An unlicensed data source kind selected before the license loaded falls back to local.
+  useEffect(() => {
+    if (!multiDatasourceLicensed && dataSourceKind !== 'local' && dataSourceKind !== 'remote') {
+      setDataSourceKind('local');
+    }
+  }, [dataSourceKind, multiDatasourceLicensed]);
 
   const requiredTargetsUnmapped = useMemo(() => {
     const required = targetFields.filter((t) => t.required);
@@ -522,29 +904,73 @@ export default function App() {
 
   const mappedCount = useMemo(() => Object.keys(targetToSource).length, [targetToSource]);
 
+  // A suggestion stays pending while its target is still mapped to the suggested source.
+  const pendingSuggestions = useMemo(() => {
+    const out: Record<string, MappingSuggestion> = {};
+    for (const [targetId, s] of Object.entries(suggestedMappings)) {
+      if (targetToSource[targetId] === s.sourceId) out[targetId] = s;
+    }
+    return out;
+  }, [suggestedMappings, targetToSource]);
+  const pendingSuggestionCount = Object.keys(pendingSuggestions).length;
+
   useEffect(() => {
     let unlistenReady: (() => void) | null = null;
     let unlistenProgress: (() => void) | null = null;
     let unlistenInstallComplete: (() => void) | null = null;
     let unlistenInstallError: (() => void) | null = null;
+    let unlistenArchiveComplete: (() => void) | null = null;
+    let unlistenArchiveError: (() => void) | null = null;
 
     (async () => {
       unlistenReady = await listenToEvent<InstallerReadyEvent>('installer-ready', (event) => {
         void event;
       });
       unlistenProgress = await listenToEvent<ProgressEvent>('progress', (evt) => {
+        // Archive runs share the progress channel; keep them off the install page.
+        if (evt.phase === 'archive') {
+          setArchiveProgress(evt);
+          return;
+        }
         setProgress(evt);
+        if (evt.step === 'paused') setInstallPaused(true);
+        if (evt.step === 'resumed') setInstallPaused(false);
         if (evt.message && evt.message.trim()) {
           setInstallDetailLines((prev) => [...prev, evt.message as string].slice(-20));
         }
       });
       unlistenInstallComplete = await listenToEvent<InstallResultEvent>('install-complete', (evt) => {
+        const planPath = evt.details?.planPath ?? null;
+        if (planPath) {
+          // Dry run: nothing was installed, so go back to Ready instead of Complete.
+          setModal({
+            kind: 'error',
+            title: t('modal.dryRun.title'),
+            body: t('modal.dryRun.body', { message: evt.message || 'No changes were made.', planPath }),
+            primaryLabel: t('button.ok'),
+            onPrimary: () => setModal({ kind: 'none' }),
+            onSecondary: null,
+            secondaryLabel: undefined,
+            onTertiary: null,
+            tertiaryLabel: undefined,
+          });
+          goTo('ready');
+          return;
+        }
         setInstallLogFolder(evt.details?.logFolder ?? null);
         setInstallManifestPath(evt.details?.manifestPath ?? null);
         setInstallMappingPath(evt.details?.mappingPath ?? null);
         setInstallConfigPath(evt.details?.configPath ?? null);
         goTo('complete');
       });
+      unlistenArchiveComplete = await listenToEvent<InstallResultEvent>('archive-complete', (evt) => {
+        setArchiveRunning(false);
+        setArchiveResult(evt.message || 'Archive run complete.');
+      });
+      unlistenArchiveError = await listenToEvent<InstallResultEvent>('archive-error', (evt) => {
+        setArchiveRunning(false);
+        setArchiveResult(`Archive run failed: ${evt.message || 'unknown error'}`);
+      });
       unlistenInstallError = await listenToEvent<InstallResultEvent>('install-error', (evt) => {
         setInstallError(evt.message || 'Installation failed.');
         const logFolder = evt.details?.logFolder ?? null;
@@ -553,41 +979,94 @@ export default function App() {
         setInstallMappingPath(evt.details?.mappingPath ?? null);
         setInstallConfigPath(evt.details?.configPath ?? null);
 
-        const bodyLines: string[] = [evt.message || 'An error occurred during installation.'];
-        if (logFolder) bodyLines.push('', `Log folder: ${logFolder}`);
+        const bodyLines: string[] = [evt.message || t('modal.installFailed.body')];
+        if (logFolder) bodyLines.push('', t('modal.installFailed.logFolder', { path: logFolder }));
+
+        const errorCode = evt.details?.errorCode ?? null;
 
         setModal({
           kind: 'error',
-          title: 'Installation failed',
+          title: errorCode ? t('modal.installFailed.titleWithCode', { code: errorCode }) : t('modal.installFailed.title'),
           body: bodyLines.join('\n'),
-          primaryLabel: 'OK',
+          primaryLabel: t('button.ok'),
           onPrimary: () => setModal({ kind: 'none' }),
-          secondaryLabel: 'Create support bundle…',
+          secondaryLabel: t('modal.installFailed.createBundle'),
           onSecondary: async () => {
             setModal({ kind: 'none' });
             try {
-              const resp = await invoke<{ bundleDir: string }>('create_support_bundle', {
-                payload: {
-                  destinationFolder: destinationFolder,
+              const resp = await invoke<{ bundleDir: string; zipPath: string; sha256: string }>(
+                'create_support_bundle',
+                {
+                  payload: {
+                    destinationFolder: destinationFolder,
+                  },
                 },
-              });
+              );
+              const closeModal = () => setModal({ kind: 'none' });
+              const showUploadResult = (title: string, body: string) =>
+                setModal({
+                  kind: 'error',
+                  title,
+                  body,
+                  primaryLabel: t('button.ok'),
+                  onPrimary: closeModal,
+                  onSecondary: null,
+                  secondaryLabel: undefined,
+                  onTertiary: null,
+                  tertiaryLabel: undefined,
+                });
+              const uploadBundle = async () => {
+                const ticketId = (window.prompt(t('modal.bundle.ticketPrompt'), '') ?? '').trim();
+                showUploadResult(t('modal.bundle.uploadingTitle'), t('modal.bundle.uploadingBody', { zipPath: resp.zipPath }));
+                try {
+                  const up = await invoke<{ uploadId: string; reference: string; bytes: number }>(
+                    'upload_support_bundle',
+                    {
+                      payload: {
+                        zipPath: resp.zipPath,
+                        consent: true,
+                        ticketId: ticketId || null,
+                      },
+                    },
+                  );
+                  const uploadedLines = [t('modal.bundle.uploadedBody', { reference: up.reference })];
+                  if (ticketId) uploadedLines.unshift(t('modal.bundle.uploadedTicket', { ticketId }));
+                  showUploadResult(t('modal.bundle.uploadedTitle'), uploadedLines.join('\n'));
+                } catch (err: any) {
+                  showUploadResult(
+                    t('modal.bundle.uploadFailedTitle'),
+                    `${String(err?.message ?? err ?? 'Upload failed.')}\n\nSupport bundle: ${resp.zipPath}`,
+                  );
+                }
+              };
               setModal({
                 kind: 'error',
-                title: 'Support bundle created',
-                body: `Support bundle folder: ${resp.bundleDir}`,
-                primaryLabel: 'OK',
-                onPrimary: () => setModal({ kind: 'none' }),
-                onSecondary: null,
-                secondaryLabel: undefined,
+                title: t('modal.bundle.createdTitle'),
+                body: t('modal.bundle.createdBody', { zipPath: resp.zipPath, sha256: resp.sha256 }),
+                primaryLabel: t('button.ok'),
+                onPrimary: closeModal,
+                secondaryLabel: t('modal.bundle.upload'),
+                onSecondary: () =>
+                  setModal({
+                    kind: 'error',
+                    title: t('modal.bundle.consentTitle'),
+                    body: t('modal.bundle.consentBody', { zipPath: resp.zipPath }),
+                    primaryLabel: t('modal.bundle.consentConfirm'),
+                    onPrimary: () => void uploadBundle(),
+                    secondaryLabel: t('button.cancel'),
+                    onSecondary: closeModal,
+                    onTertiary: null,
+                    tertiaryLabel: undefined,
+                  }),
                 onTertiary: null,
                 tertiaryLabel: undefined,
               });
             } catch (e: any) {
               setModal({
                 kind: 'error',
-                title: 'Support bundle failed',
-                body: `Unable to create support bundle.${logFolder ? `\n\nLog folder: ${logFolder}` : ''}`,
-                primaryLabel: 'OK',
+                title: t('modal.bundle.failedTitle'),
+                body: [t('modal.bundle.failedBody'), ...(logFolder ? ['', t('modal.installFailed.logFolder', { path: logFolder })] : [])].join('\n'),
+                primaryLabel: t('button.ok'),
                 onPrimary: () => setModal({ kind: 'none' }),
                 onSecondary: null,
                 secondaryLabel: undefined,
@@ -596,7 +1075,7 @@ export default function App() {
               });
             }
           },
-          tertiaryLabel: logFolder ? 'Copy log path' : undefined,
+          tertiaryLabel: logFolder ? t('modal.installFailed.copyLogPath') : undefined,
           onTertiary: logFolder
             ? () => {
                 try {
@@ -621,11 +1100,26 @@ export default function App() {
       try {
         unlistenInstallError?.();
       } catch {}
+      try {
+        unlistenArchiveComplete?.();
+      } catch {}
+      try {
+        unlistenArchiveError?.();
+      } catch {}
     };
   }, []);
 
   useEffect(() => {
     const onKeyDown = (e: KeyboardEvent) => {
+      // Transforms modal: Esc closes; other keys belong to its inputs.
+      if (transformsTargetId) {
+        if (e.key === 'Escape') {
+          e.preventDefault();
+          setTransformsTargetId(null);
+        }
+        return;
+      }
+
       // Modal keyboard behavior (spec): Esc cancels/closes; Enter accepts primary action.
       if (modal.kind !== 'none') {
         if (e.key === 'Escape') {
@@ -664,6 +1158,7 @@ export default function App() {
     return () => window.removeEventListener('keydown', onKeyDown);
   }, [
     modal.kind,
+    transformsTargetId,
     page,
     installMode,
     installationType,
@@ -676,6 +1171,9 @@ export default function App() {
     computedConfigDbConnectionString,
     computedCallDataConnectionString,
     sourceObjectName,
+    fileSource,
+    callDataDriver,
+    dataSourceSetupError,
     retentionValidationError,
     archiveValidationError,
     hotRetentionMonths,
@@ -685,16 +1183,17 @@ export default function App() {
     archiveScheduleDayOfMonth,
     archiveScheduleTimeLocal,
     archiveCatchUpOnStartup,
+    archiveEncrypt,
     consentToSync,
   ]);
 
   function openCancelConfirm() {
     setModal({
       kind: 'confirmCancel',
-      title: 'Cancel Setup?',
-      body: 'If you cancel now, the installation may be incomplete.',
-      primaryLabel: 'Yes, cancel',
-      secondaryLabel: 'No',
+      title: t('modal.cancel.title'),
+      body: t('modal.cancel.body'),
+      primaryLabel: t('modal.cancel.confirm'),
+      secondaryLabel: t('modal.cancel.decline'),
       onPrimary: async () => {
         setModal({ kind: 'none' });
         if (page === 'installing') {
@@ -737,12 +1236,26 @@ export default function App() {
     });
   }
 
+  async function toggleInstallPause() {
+    try {
+      if (installPaused) {
+        await invoke('resume_install');
+        setInstallPaused(false);
+      } else {
+        await invoke('pause_install');
+        setProgress((prev) => (prev ? { ...prev, message: 'Pausing at the next safe point...' } : prev));
+      }
+    } catch (e) {
+      openError('Pause', String(e));
+    }
+  }
+
   function openError(title: string, body: string) {
     setModal({
       kind: 'error',
       title,
       body,
-      primaryLabel: 'OK',
+      primaryLabel: t('button.ok'),
       onPrimary: () => setModal({ kind: 'none' }),
       onSecondary: null,
       secondaryLabel: undefined,
@@ -771,6 +1284,7 @@ export default function App() {
       targetFields,
       sourceToTargets,
       targetToSource,
+      transforms: mappingTransforms,
     };
   }
 
@@ -780,6 +1294,7 @@ export default function App() {
       'platform',
       'welcome',
       'license',
+      'network',
       'installType',
       'destination',
       'dataSource',
@@ -812,6 +1327,26 @@ export default function App() {
     }
 
     if (page === 'license') {
+      goTo('network');
+      return;
+    }
+
+    if (page === 'network') {
+      if (proxyValidationError) return;
+      const res = await saveProxySettings({
+        host: proxyHost.trim(),
+        port: proxyHost.trim() ? parseInt(proxyPort.trim(), 10) : 0,
+        username: proxyUsername.trim() || null,
+        password: proxyPassword || undefined,
+        bypassSystemProxy: !proxyHost.trim() && bypassSystemProxy,
+      });
+      if (!res.success) {
+        setProxyError(res.error || res.message || 'Failed to save proxy settings.');
+        return;
+      }
+      setProxyError(null);
+      setProxyPassword('');
+      setProxyHasSavedPassword(!!res.data?.hasPassword);
       goTo('installType');
       return;
     }
@@ -843,6 +1378,7 @@ export default function App() {
     }
 
     if (page === 'dataSource') {
+      if (dataSourceSetupError) return;
       goTo('database');
       return;
     }
@@ -859,6 +1395,7 @@ export default function App() {
     }
 
     if (page === 'storage') {
+      if (storageFilesystem?.blocked) return;
       goTo('retention');
       return;
     }
@@ -881,12 +1418,13 @@ export default function App() {
     }
 
     if (page === 'mapping') {
-      if (requiredTargetsUnmapped.length > 0) return;
+      if (requiredTargetsUnmapped.length > 0 || pendingSuggestionCount > 0) return;
       goTo('ready');
       return;
     }
 
     if (page === 'ready') {
+      if (diskSpaceChecking || diskSpace?.shortfall) return;
       // Begin install
       setInstallError(null);
       setProgress({
@@ -902,67 +1440,11 @@ export default function App() {
       setInstallMappingPath(null);
       setInstallConfigPath(null);
       setInstallDetailLines([]);
+      setInstallPaused(false);
       goTo('installing');
 
       try {
-        await invoke('start_install', {
-          payload: {
-            installMode,
-            installationType,
-            destinationFolder,
-            // Phase 9: For Create NEW, send maintenance connection string (master/postgres)
-            configDbConnectionString: dbSetupMode === 'createNew' ? computedCreateNewMaintenanceConnString : computedConfigDbConnectionString,
-            callDataConnectionString: computedCallDataConnectionString,
-            sourceObjectName,
-            dbSetup: {
-              mode: dbSetupMode === 'createNew' ? 'create_new' : 'existing',
-              // Phase 9: Include new database name for Create NEW mode
-              newDbName: dbSetupMode === 'createNew' ? newDbName.trim() : undefined,
-              newLocation: newDbLocation === 'thisMachine' ? 'this_machine' : 'specific_path',
-              newSpecificPath: newDbLocation === 'specificPath' ? newDbSpecificPath.trim() : '',
-              maxDbSizeGb: parseInt(newDbMaxSizeGb.trim(), 10) || 0,
-              existingHostedWhere,
-              existingConnectMode: dbUseConnString ? 'connection_string' : 'details',
-              // Phase 9: SQL Server sizing
-              sqlServerSizing: dbEngine === 'sqlserver' && dbSetupMode === 'createNew' ? {
-                initialDataSizeMb: parseInt(newDbInitialDataSizeMb.trim(), 10) || 0,
-                initialLogSizeMb: parseInt(newDbInitialLogSizeMb.trim(), 10) || 0,
-                maxDataSizeMb: parseInt(newDbMaxDataSizeMb.trim(), 10) || 0,
-                maxLogSizeMb: parseInt(newDbMaxLogSizeMb.trim(), 10) || 0,
-                dataFilegrowth: parseInt(newDbDataFilegrowth.trim(), 10) || 0,
-                logFilegrowth: parseInt(newDbLogFilegrowth.trim(), 10) || 0,
-              } : undefined,
-              // Phase 9: PostgreSQL options
-              postgresOptions: dbEngine === 'postgres' && dbSetupMode === 'createNew' ? {
-                owner: newDbPgOwner.trim() || undefined,
-              } : undefined,
-            },
-            storage: {
-              mode: storageMode,
-              location: storageLocation,
-              customPath: storageCustomPath,
-              retentionPolicy,
-              maxDiskGb,
-            },
-            hotRetention: {
-              months: hotRetentionMonths,
-            },
-            archivePolicy: {
-              format: archiveFormat,
-              destinationPath: archiveDestinationPath.trim(),
-              maxUsageGb: parseInt(archiveMaxUsageGb.trim(), 10),
-              schedule: {
-                dayOfMonth: parseInt(archiveScheduleDayOfMonth.trim(), 10),
-                timeLocal: archiveScheduleTimeLocal.trim(),
-              },
-              catchUpOnStartup: archiveCatchUpOnStartup,
-            },
-            consentToSync,
-            mappings: buildCanonicalToSourceColumnMappings(),
-            mappingOverride,
-            mappingState: buildMappingStateForPayload(),
-          },
-        });
+        await invoke('start_install', { payload: buildInstallPayload() });
       } catch (e: any) {
         const msg = e?.message || String(e);
         setInstallError(msg);
@@ -979,6 +1461,85 @@ export default function App() {
     }
   }
 
+  /** The start_install payload for the current answers (also projected on the Ready page). */
+  function buildInstallPayload() {
+    return {
+      installMode: targetMode,
+      installationType,
+      destinationFolder,
+      // Phase 9: For Create NEW, send maintenance connection string (master/postgres)
+      configDbConnectionString: dbSetupMode === 'createNew' ? computedCreateNewMaintenanceConnString : computedConfigDbConnectionString,
+      callDataConnectionString: computedCallDataConnectionString,
+      sourceObjectName,
+      fileSource,
+      callDataDriver,
+      dbSetup: {
+        mode: dbSetupMode === 'createNew' ? 'create_new' : 'existing',
+        // Phase 9: Include new database name for Create NEW mode
+        newDbName: dbSetupMode === 'createNew' ? newDbName.trim() : undefined,
+        newLocation: newDbLocation === 'thisMachine' ? 'this_machine' : 'specific_path',
+        newSpecificPath: newDbLocation === 'specificPath' ? newDbSpecificPath.trim() : '',
+        maxDbSizeGb: parseInt(newDbMaxSizeGb.trim(), 10) || 0,
+        collation: dbSetupMode === 'createNew' ? newDbCollation.trim() : '',
+        existingHostedWhere,
+        existingConnectMode: dbUseConnString ? 'connection_string' : 'details',
+        // Phase 9: SQL Server sizing
+        sqlServerSizing: dbEngine === 'sqlserver' && dbSetupMode === 'createNew' ? {
+          initialDataSizeMb: parseInt(newDbInitialDataSizeMb.trim(), 10) || 0,
+          initialLogSizeMb: parseInt(newDbInitialLogSizeMb.trim(), 10) || 0,
+          maxDataSizeMb: parseInt(newDbMaxDataSizeMb.trim(), 10) || 0,
+          maxLogSizeMb: parseInt(newDbMaxLogSizeMb.trim(), 10) || 0,
+          dataFilegrowth: parseInt(newDbDataFilegrowth.trim(), 10) || 0,
+          logFilegrowth: parseInt(newDbLogFilegrowth.trim(), 10) || 0,
+        } : undefined,
+        // Phase 9: PostgreSQL options
+        postgresOptions: dbEngine === 'postgres' && dbSetupMode === 'createNew' ? {
+          owner: newDbPgOwner.trim() || undefined,
+        } : undefined,
+      },
+      storage: {
+        mode: storageMode,
+        location: storageLocation,
+        customPath: storageCustomPath,
+        retentionPolicy,
+        maxDiskGb,
+      },
+      hotRetention: {
+        months: hotRetentionMonths,
+      },
+      archivePolicy: {
+        format: archiveFormat,
+        destinationPath: archiveDestinationPath.trim(),
+        maxUsageGb: parseInt(archiveMaxUsageGb.trim(), 10),
+        schedule: {
+          dayOfMonth: parseInt(archiveScheduleDayOfMonth.trim(), 10),
+          timeLocal: archiveScheduleTimeLocal.trim(),
+        },
+        catchUpOnStartup: archiveCatchUpOnStartup,
+        encryptArchives: archiveEncrypt,
+        enabled: archiveLicensed,
+      },
+      consentToSync,
+      mappings: buildCanonicalToSourceColumnMappings(),
+      mappingOverride,
+      mappingState: buildMappingStateForPayload(),
+      layout: layoutAdvanced
+        ? {
+            binaries: layoutPaths.binaries.trim(),
+            data: layoutPaths.data.trim(),
+            logs: layoutPaths.logs.trim(),
+            temp: layoutPaths.temp.trim(),
+          }
+        : undefined,
+      appAccount: {
+        enabled: appAccountEnabled,
+        loginName: appLoginName.trim(),
+      },
+      dryRun,
+      remote: remoteTarget ?? undefined,
+    };
+  }
+
   async function browseForFolder() {
     const selected = await open({ directory: true, multiple: false, title: 'Select Destination Folder' });
     if (typeof selected === 'string' && selected.trim()) {
@@ -1000,6 +1561,13 @@ export default function App() {
     }
   }
 
+  async function browseForExportFolder() {
+    const selected = await open({ directory: true, multiple: false, title: 'Select Export Folder' });
+    if (typeof selected === 'string' && selected.trim()) {
+      setFileSourceFolder(installMode === 'windows' ? normalizeWindowsPath(selected) : selected);
+    }
+  }
+
   async function browseForFile() {
     const selected = await open({ directory: false, multiple: false, title: 'Select Configuration File' });
     if (typeof selected === 'string' && selected.trim()) {
@@ -1008,6 +1576,47 @@ export default function App() {
     }
   }
 
+  async function runArchiveNow() {
+    if (archiveRunning) return;
+    setArchiveRunning(true);
+    setArchiveProgress(null);
+    setArchiveResult(null);
+    try {
+      await invoke('start_archive_run', {
+        payload: { configDbConnectionString: computedConfigDbConnectionString },
+      });
+    } catch (e: any) {
+      setArchiveRunning(false);
+      setArchiveResult(`Archive run failed: ${e?.message || String(e)}`);
+    }
+  }
+
+  async function runHealthCheck() {
+    if (healthChecking) return;
+    setHealthChecking(true);
+    setHealthError(null);
+    try {
+      const res = await verifySetup({ configDbConnectionString: computedConfigDbConnectionString });
+      if (res.success && res.data) {
+        setHealth(res.data);
+      } else {
+        setHealth(null);
+        setHealthError(res.message || res.error || 'unknown error');
+      }
+    } catch (e: any) {
+      setHealth(null);
+      setHealthError(e?.message || String(e));
+    } finally {
+      setHealthChecking(false);
+    }
+  }
+
+  // Complete page: run the post-install health checks on entry.
+  useEffect(() => {
+    if (page === 'complete') void runHealthCheck();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page]);
+
   async function runDbTest() {
     if (dbSetupMode !== 'existing') return;
     if (!canRunDbTest) {
@@ -1019,16 +1628,20 @@ export default function App() {
     }
     setDbTestStatus('testing');
     setDbTestMessage('');
+    setDbTestStages([]);
     try {
       const res = await invoke<TestDbConnectionResponse>('test_db_connection', {
         payload: { engine: dbEngine, connectionString: computedConfigDbConnectionString },
       });
+      setDbTestStages(res.stages ?? []);
       if (res.success) {
         setDbTestStatus('success');
         setDbTestMessage('Connection successful.');
       } else {
         setDbTestStatus('fail');
-        setDbTestMessage(`Connection failed: ${res.message}`);
+        setDbTestMessage(
+          res.missingPermissions?.length ? res.message : `Connection failed: ${res.message}`
+        );
       }
     } catch (e: any) {
       setDbTestStatus('fail');
@@ -1046,9 +1659,14 @@ export default function App() {
     setNewDbPrivTestStatus('testing');
     setNewDbPrivTestMessage('');
     try {
-      const res = await invoke<{ canCreate: boolean; reason: string; detectedRole?: string }>('db_can_create_database', {
-        payload: { engine: dbEngine, connectionString: computedCreateNewMaintenanceConnString },
+      const res = await invoke<{ canCreate: boolean; reason: string; detectedRole?: string; loginName?: string; grantScript?: string }>('db_can_create_database', {
+        payload: {
+          engine: dbEngine,
+          connectionString: computedCreateNewMaintenanceConnString,
+          owner: dbEngine === 'postgres' && newDbPgOwner.trim() ? newDbPgOwner.trim() : undefined,
+        },
       });
+      setNewDbGrantScript(res.canCreate ? null : res.grantScript ?? null);
       if (res.canCreate) {
         // Also check if database already exists
         const existsRes = await invoke<{ exists: boolean; error?: string }>('db_exists', {
@@ -1063,7 +1681,11 @@ export default function App() {
         }
       } else {
         setNewDbPrivTestStatus('fail');
-        setNewDbPrivTestMessage(res.reason || 'Insufficient privileges.');
+        setNewDbPrivTestMessage(
+          res.grantScript
+            ? `${res.reason} Ask your DBA to run the script below, then re-test.`
+            : res.reason || 'Insufficient privileges.'
+        );
       }
     } catch (e: any) {
       setNewDbPrivTestStatus('fail');
@@ -1071,6 +1693,34 @@ export default function App() {
     }
   }
 
+  async function checkDependencies() {
+    setDependencyError(null);
+    setDependencyChecking(true);
+    try {
+      const res = await preflightDependencies({ installMode });
+      if (!res.success || !res.data) {
+        setDependencyChecks(null);
+        setDependencyStatus(null);
+        setDependencyError(res.error || 'Unable to check required software.');
+        return;
+      }
+      setDependencyChecks(res.data.checks);
+      setDependencyStatus(res.data.overallStatus);
+    } catch (e: any) {
+      setDependencyChecks(null);
+      setDependencyStatus(null);
+      setDependencyError(e?.message || String(e));
+    } finally {
+      setDependencyChecking(false);
+    }
+  }
+
+  // Installation Type page: check required software for the chosen mode on entry.
+  useEffect(() => {
+    if (page === 'installType') void checkDependencies();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page, installMode]);
+
   async function scanSourceFields() {
     setMappingScanError(null);
     setMappingScanning(true);
@@ -1080,12 +1730,25 @@ export default function App() {
         sourceObjectName,
         sampleLimit: 10,
         demoMode: mappingDemoMode,
+        fileSource,
+        callDataDriver,
       });
       if (!res.success || !res.data) {
         setMappingScanError(res.error || 'Unable to scan source fields.');
         return;
       }
-      setSourceFields(disambiguateSourceColumns(res.data.discoveredColumns));
+      const fields = disambiguateSourceColumns(res.data.discoveredColumns);
+      setSourceFields(fields);
+      // Concat transforms cannot point at fields the new scan no longer has.
+      const ids = new Set(fields.map((f) => f.id));
+      setMappingTransforms((prev) => {
+        const next: Record<string, FieldTransform[]> = {};
+        for (const [targetId, list] of Object.entries(prev)) {
+          const kept = list.filter((t) => t.kind !== 'concat' || ids.has(t.sourceId));
+          if (kept.length > 0) next[targetId] = kept;
+        }
+        return next;
+      });
     } catch (e: any) {
       setMappingScanError(e?.message || String(e));
     } finally {
@@ -1107,6 +1770,83 @@ export default function App() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [page, mappingDemoMode]);
 
+  // Ready page: estimate the historical backfill from the primary source on entry.
+  useEffect(() => {
+    if (page !== 'ready') return;
+    setBackfillEstimate(null);
+    setBackfillEstimateError(null);
+    if (fileSource) return;
+    let cancelled = false;
+    setBackfillEstimating(true);
+    estimateBackfill({
+      callDataConnectionString: computedCallDataConnectionString,
+      callDataDriver,
+      sourceObjectName,
+      watermarkColumn: buildCanonicalToSourceColumnMappings()['CallReceivedAt'] ?? '',
+      hotRetentionMonths,
+      demoMode: mappingDemoMode,
+    })
+      .then((res) => {
+        if (cancelled) return;
+        if (res.success && res.data) setBackfillEstimate(res.data);
+        else setBackfillEstimateError(res.error || 'Unable to estimate the backfill.');
+      })
+      .catch((e: any) => {
+        if (!cancelled) setBackfillEstimateError(e?.message || String(e));
+      })
+      .finally(() => {
+        if (!cancelled) setBackfillEstimating(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+    // Settings cannot change while the Ready page is shown.
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page]);
+
+  // Storage page: detect the filesystem under the database folder (debounced while typing).
+  useEffect(() => {
+    if (page !== 'storage') return;
+    setStorageFilesystem(null);
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      void inspectStorageFilesystem(buildInstallPayload()).then((res) => {
+        if (!cancelled && res.success && res.data) setStorageFilesystem(res.data);
+      });
+    }, 500);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page, storageMode, storageLocation, storageCustomPath]);
+
+  // Ready page: project disk use per volume on entry and on "Check again".
+  useEffect(() => {
+    if (page !== 'ready') return;
+    setDiskSpace(null);
+    setDiskSpaceError(null);
+    let cancelled = false;
+    setDiskSpaceChecking(true);
+    projectDiskSpace(buildInstallPayload())
+      .then((res) => {
+        if (cancelled) return;
+        if (res.success && res.data) setDiskSpace(res.data);
+        else setDiskSpaceError(res.error || 'Unable to check free disk space.');
+      })
+      .catch((e: any) => {
+        if (!cancelled) setDiskSpaceError(e?.message || String(e));
+      })
+      .finally(() => {
+        if (!cancelled) setDiskSpaceChecking(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+    // Settings cannot change while the Ready page is shown.
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page, diskSpaceCheckCount]);
+
   function unassignSelected() {
     if (!selectedSourceId || !selectedTargetId) return;
     const currentSource = targetToSource[selectedTargetId];
@@ -1126,6 +1866,12 @@ export default function App() {
   }
 
   function applyMapping(sourceId: string, targetId: string, mode: 'replace' | 'add') {
+    setSuggestedMappings((prev) => {
+      if (!prev[targetId]) return prev;
+      const next = { ...prev };
+      delete next[targetId];
+      return next;
+    });
     setTargetToSource((prev) => ({ ...prev, [targetId]: sourceId }));
     setSourceToTargets((prev) => {
       const next = { ...prev };
@@ -1139,6 +1885,97 @@ export default function App() {
     });
   }
 
+  async function runAutoMap() {
+    setAutoMapRunning(true);
+    try {
+      const res = await autoMap({
+        sourceFields: sourceFields.map((s) => ({ id: s.id, rawName: s.rawName, dataType: s.dataType ?? '' })),
+        targetFields: targetFields.map((t) => ({ id: t.id, name: t.name })),
+        targetToSource,
+      });
+      if (!res.success || !res.data) {
+        setModal({ kind: 'error', title: 'Auto-map', body: res.error || 'Unable to compute suggestions.', onPrimary: () => setModal({ kind: 'none' }) });
+        return;
+      }
+      const suggestions = res.data.suggestions;
+      if (suggestions.length === 0) {
+        setModal({
+          kind: 'error',
+          title: 'Auto-map',
+          body: 'No confident matches were found for the unmapped fields.',
+          onPrimary: () => setModal({ kind: 'none' }),
+        });
+        return;
+      }
+      for (const s of suggestions) applyMapping(s.sourceId, s.targetId, 'replace');
+      setSuggestedMappings((prev) => {
+        const next = { ...prev };
+        for (const s of suggestions) next[s.targetId] = s;
+        return next;
+      });
+    } catch (e: any) {
+      setModal({ kind: 'error', title: 'Auto-map', body: e?.message || String(e), onPrimary: () => setModal({ kind: 'none' }) });
+    } finally {
+      setAutoMapRunning(false);
+    }
+  }
+
+  async function exportMappingSheet() {
+    const path = await save({
+      title: 'Export Mapping Sheet',
+      defaultPath: 'mapping-review.csv',
+      filters: [{ name: 'CSV', extensions: ['csv'] }],
+    });
+    if (!path) return;
+    const res = await exportMappingCsv({ path, mappingState: buildMappingStateForPayload() });
+    setModal({
+      kind: 'error',
+      title: 'Export Mapping',
+      body: res.success && res.data ? `${res.data.rows} row(s) written to ${res.data.path}.` : res.error || 'Unable to export the mapping.',
+      onPrimary: () => setModal({ kind: 'none' }),
+    });
+  }
+
+  async function importMappingSheet() {
+    const path = await open({
+      multiple: false,
+      directory: false,
+      title: 'Import Mapping Sheet',
+      filters: [{ name: 'CSV', extensions: ['csv'] }],
+    });
+    if (!path || Array.isArray(path)) return;
+    const res = await importMappingCsv({ path, mappingState: buildMappingStateForPayload() });
+    if (!res.success || !res.data) {
+      setModal({
+        kind: 'error',
+        title: 'Import Mapping',
+        body: res.error || 'Unable to import the mapping.',
+        onPrimary: () => setModal({ kind: 'none' }),
+      });
+      return;
+    }
+    setSourceToTargets(res.data.mappingState.sourceToTargets);
+    setTargetToSource(res.data.mappingState.targetToSource);
+    setSuggestedMappings({});
+    setSelectedSourceId(null);
+    setSelectedTargetId(null);
+    setModal({
+      kind: 'error',
+      title: 'Import Mapping',
+      body: `${res.data.mappedCount} target field(s) mapped from ${path}.`,
+      onPrimary: () => setModal({ kind: 'none' }),
+    });
+  }
+
+  function confirmSuggestions(targetId: string | null) {
+    setSuggestedMappings((prev) => {
+      if (!targetId) return {};
+      const next = { ...prev };
+      delete next[targetId];
+      return next;
+    });
+  }
+
   function removeTargetFromOldSource(targetId: string) {
     const oldSource = targetToSource[targetId];
     if (!oldSource) return;
@@ -1177,13 +2014,16 @@ export default function App() {
         if (mappingOverride) {
           setModal({
             kind: 'sourceAlreadyMapped',
-            title: 'Source already mapped',
-            body: `Target "${targetName}" is currently mapped to Source "${oldSourceName}".\n\nSource "${sourceName}" is currently mapped to: ${sourceAlreadyMappedTo
-              .map((t) => targetFields.find((x) => x.id === t)?.name ?? t)
-              .join(', ')}.\n\nWhat would you like to do?`,
-            primaryLabel: 'Add',
-            secondaryLabel: 'Replace',
-            tertiaryLabel: 'Cancel',
+            title: t('modal.mapping.sourceMappedTitle'),
+            body: t('modal.mapping.sourceAndTargetMapped', {
+              target: targetName,
+              oldSource: oldSourceName,
+              source: sourceName,
+              targets: sourceAlreadyMappedTo.map((id) => targetFields.find((x) => x.id === id)?.name ?? id).join(', '),
+            }),
+            primaryLabel: t('button.add'),
+            secondaryLabel: t('button.replace'),
+            tertiaryLabel: t('button.cancel'),
             onPrimary: () => {
               setModal({ kind: 'none' });
               removeTargetFromOldSource(targetId);
@@ -1203,10 +2043,15 @@ export default function App() {
 
         setModal({
           kind: 'replaceMapping',
-          title: 'Replace mapping?',
-          body: `Target "${targetName}" is currently mapped to Source "${oldSourceName}".\nSource "${sourceName}" is currently mapped to Target "${targetFields.find((x) => x.id === sourceAlreadyMappedTo[0])?.name ?? sourceAlreadyMappedTo[0]}".\n\nDo you want to replace these mappings with Source "${sourceName}" → Target "${targetName}"?`,
-          primaryLabel: 'Replace',
-          secondaryLabel: 'Cancel',
+          title: t('modal.mapping.replaceTitle'),
+          body: t('modal.mapping.replaceBoth', {
+            target: targetName,
+            oldSource: oldSourceName,
+            source: sourceName,
+            oldTarget: targetFields.find((x) => x.id === sourceAlreadyMappedTo[0])?.name ?? sourceAlreadyMappedTo[0],
+          }),
+          primaryLabel: t('button.replace'),
+          secondaryLabel: t('button.cancel'),
           onPrimary: () => {
             setModal({ kind: 'none' });
             removeTargetFromOldSource(targetId);
@@ -1221,10 +2066,10 @@ export default function App() {
       // CASE A (target already mapped)
       setModal({
         kind: 'replaceMapping',
-        title: 'Replace mapping?',
-        body: `Target "${targetName}" is currently mapped to Source "${oldSourceName}".\nDo you want to replace it with Source "${sourceName}"?`,
-        primaryLabel: 'Replace',
-        secondaryLabel: 'Cancel',
+        title: t('modal.mapping.replaceTitle'),
+        body: t('modal.mapping.replaceTarget', { target: targetName, oldSource: oldSourceName, source: sourceName }),
+        primaryLabel: t('button.replace'),
+        secondaryLabel: t('button.cancel'),
         onPrimary: () => {
           setModal({ kind: 'none' });
           removeTargetFromOldSource(targetId);
@@ -1246,10 +2091,10 @@ export default function App() {
       const oldTargetName = targetFields.find((t) => t.id === sourceAlreadyMappedTo[0])?.name ?? sourceAlreadyMappedTo[0];
       setModal({
         kind: 'replaceMapping',
-        title: 'Replace mapping?',
-        body: `Source "${sourceName}" is currently mapped to Target "${oldTargetName}".\nDo you want to replace it with Target "${targetName}"?`,
-        primaryLabel: 'Replace',
-        secondaryLabel: 'Cancel',
+        title: t('modal.mapping.replaceTitle'),
+        body: t('modal.mapping.replaceSource', { source: sourceName, oldTarget: oldTargetName, target: targetName }),
+        primaryLabel: t('button.replace'),
+        secondaryLabel: t('button.cancel'),
         onPrimary: () => {
           setModal({ kind: 'none' });
           // Remove old target->source mapping(s) for this source
@@ -1266,13 +2111,14 @@ export default function App() {
     if (mappingOverride && sourceAlreadyMappedTo.length > 0 && !sourceAlreadyMappedTo.includes(targetId)) {
       setModal({
         kind: 'sourceAlreadyMapped',
-        title: 'Source already mapped',
-        body: `Source "${sourceName}" is currently mapped to: ${sourceAlreadyMappedTo
-          .map((t) => targetFields.find((x) => x.id === t)?.name ?? t)
-          .join(', ')}.\nWhat would you like to do?`,
-        primaryLabel: 'Add',
-        secondaryLabel: 'Replace',
-        tertiaryLabel: 'Cancel',
+        title: t('modal.mapping.sourceMappedTitle'),
+        body: t('modal.mapping.sourceMapped', {
+          source: sourceName,
+          targets: sourceAlreadyMappedTo.map((id) => targetFields.find((x) => x.id === id)?.name ?? id).join(', '),
+        }),
+        primaryLabel: t('button.add'),
+        secondaryLabel: t('button.replace'),
+        tertiaryLabel: t('button.cancel'),
         onPrimary: () => {
           setModal({ kind: 'none' });
           applyMapping(sourceId, targetId, 'add');
@@ -1300,52 +2146,79 @@ export default function App() {
       setDestinationError('Destination folder is required.');
       return;
     }
+    // Paths of a remote install are on its Linux server.
+    const absolute = remoteInstall ? (path: string) => path.startsWith('/') : isAbsolutePath;
+    if (remoteInstall && !absolute(p)) {
+      setDestinationError('The install path must be an absolute path on the remote server.');
+      return;
+    }
+    if (layoutAdvanced) {
+      const relative = LAYOUT_ROLES.find(({ role }) => layoutPaths[role].trim() && !absolute(layoutPaths[role].trim()));
+      if (relative) {
+        setDestinationError(`The ${relative.role} folder must be an absolute path.`);
+        return;
+      }
+    }
+    if (remoteTarget && !remoteTarget.hostKeyFingerprint) {
+      setDestinationError('Check the remote server and confirm its host key.');
+      return;
+    }
     setDestinationError(null);
-  }, [destinationFolder]);
+  }, [destinationFolder, layoutAdvanced, layoutPaths, remoteInstall, remoteTarget]);
 
-  // When mode changes, update default install path if user hasn’t customized it much.
+  const layoutDefaults = useMemo(
+    () => defaultLayout(targetMode, dbEngine, destinationFolder),
+    [dbEngine, destinationFolder, targetMode]
+  );
+
+  // Advanced layout: measure each folder's volume once typing settles.
   useEffect(() => {
-    setDestinationFolder(defaultInstallPath(installMode));
-  }, [installMode]);
-
-  const wizardTitle = useMemo(() => {
-    switch (page) {
-      case 'platform':
-        return 'CADalytix Setup';
-      case 'welcome':
-        return 'Welcome to the CADalytix Setup Wizard';
-      case 'license':
-        return 'License Agreement';
-      case 'installType':
-        return 'Installation Type';
-      case 'destination':
-        return 'Destination Folder';
-      case 'dataSource':
-        return 'Data Source';
-      case 'database':
-        return 'Database Setup';
-      case 'storage':
-        return 'Database Storage';
-      case 'retention':
-        return 'Hot Retention';
-      case 'archive':
-        return 'Archive Policy';
-      case 'consent':
-        return 'Support Improvements';
-      case 'mapping':
-        return 'Schema Mapping';
-      case 'ready':
-        return 'Ready to Install';
-      case 'installing':
-        return 'Installing CADalytix';
-      case 'complete':
-        return 'Completed';
-      default:
-        return 'CADalytix Setup';
+    if (page !== 'destination' || !layoutAdvanced) return;
+    setLayoutFreeSpace({});
+    const maxDbSizeGb = dbSetupMode === 'createNew' ? parseInt(newDbMaxSizeGb.trim(), 10) || 0 : 0;
+    if (remoteInstall) {
+      // Measured on the server when the Ready page projects the install.
+      const unmeasured: Partial<Record<LayoutRole, LayoutFreeSpace>> = {};
+      for (const { role } of LAYOUT_ROLES) {
+        unmeasured[role] = { freeBytes: null, requiredBytes: layoutMinFreeBytes(role, maxDbSizeGb) };
+      }
+      setLayoutFreeSpace(unmeasured);
+      return;
     }
-  }, [page]);
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      for (const { role } of LAYOUT_ROLES) {
+        const path = layoutPaths[role].trim() || layoutDefaults[role];
+        void getFreeSpaceBytes(path).then((res) => {
+          if (cancelled) return;
+          setLayoutFreeSpace((prev) => ({
+            ...prev,
+            [role]: {
+              freeBytes: res.success && typeof res.data === 'number' ? res.data : null,
+              requiredBytes: layoutMinFreeBytes(role, maxDbSizeGb),
+            },
+          }));
+        });
+      }
+    }, 500);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [page, layoutAdvanced, layoutPaths, layoutDefaults, remoteInstall]);
+
+  // When mode changes, update default install path if user hasn’t customized it much.
+  // A pre-seeded destination (CADALYTIX_INSTALLER_DESTINATION) wins over the per-mode default.
+  useEffect(() => {
+    setDestinationFolder(
+      wizardDefaults?.destinationPath || defaultInstallPath(targetMode, wizardDefaults?.instanceName),
+    );
+  }, [targetMode, wizardDefaults]);
 
-  const nextLabel = page === 'ready' ? 'Install' : page === 'complete' ? 'Finish' : 'Next';
+  const wizardTitle = t(`page.${page}`);
+
+  const nextLabel = t(page === 'ready' ? 'button.install' : page === 'complete' ? 'button.finish' : 'button.next');
   const backDisabled = page === 'platform' || page === 'welcome' || page === 'installing' || page === 'complete';
   const cancelDisabled = page === 'complete';
 
@@ -1353,35 +2226,52 @@ export default function App() {
     if (page === 'platform') return true;
     if (page === 'welcome') return false;
     if (page === 'license') return !licenseAccepted;
+    if (page === 'network') return !!proxyValidationError;
     if (page === 'installType') {
+      // Missing required software blocks; warnings and a failed check itself do not.
+      if (dependencyChecking || dependencyStatus === 'Fail') return true;
       if (installationType === 'import') return !importConfigPath.trim() || !!importConfigError;
-      return true ? false : false;
+      return false;
     }
     if (page === 'destination') return !!destinationError;
+    if (page === 'dataSource') return !!dataSourceSetupError;
     if (page === 'database') {
       if (!dbSetupMode) return true;
-      if (dbSetupMode === 'createNew') return !!dbCreateValidationError;
+      if (appAccountValidationError) return true;
+      // Missing CREATE DATABASE privilege: wait for the DBA's grant and a passing re-test.
+      if (dbSetupMode === 'createNew') return !!dbCreateValidationError || !!newDbGrantScript;
       return dbTestStatus !== 'success';
     }
+    if (page === 'storage') return !!storageFilesystem?.blocked;
     if (page === 'retention') return !!retentionValidationError;
     if (page === 'archive') return !!archiveValidationError;
     if (page === 'consent') return false;
-    if (page === 'mapping') return requiredTargetsUnmapped.length > 0;
+    if (page === 'mapping') return requiredTargetsUnmapped.length > 0 || pendingSuggestionCount > 0;
+    if (page === 'ready') return diskSpaceChecking || !!diskSpace?.shortfall;
     if (page === 'installing') return true;
     return false;
   }, [
     page,
     destinationError,
+    dataSourceSetupError,
     dbTestStatus,
     dbSetupMode,
     dbCreateValidationError,
+    appAccountValidationError,
     requiredTargetsUnmapped.length,
+    pendingSuggestionCount,
     installationType,
     importConfigError,
     importConfigPath,
+    dependencyChecking,
+    dependencyStatus,
     licenseAccepted,
+    proxyValidationError,
     retentionValidationError,
     archiveValidationError,
+    diskSpaceChecking,
+    diskSpace,
+    storageFilesystem,
   ]);
 
   function platformKeyDown(e: React.KeyboardEvent) {
@@ -1447,6 +2337,24 @@ export default function App() {
         licenseScrollRef={licenseScrollRef}
       />
     );
+  } else if (page === 'network') {
+    body = (
+      <NetworkStep
+        proxyHost={proxyHost}
+        onProxyHostChange={setProxyHost}
+        proxyPort={proxyPort}
+        onProxyPortChange={setProxyPort}
+        proxyUsername={proxyUsername}
+        onProxyUsernameChange={setProxyUsername}
+        proxyPassword={proxyPassword}
+        onProxyPasswordChange={setProxyPassword}
+        proxyHasSavedPassword={proxyHasSavedPassword}
+        bypassSystemProxy={bypassSystemProxy}
+        onBypassSystemProxyChange={setBypassSystemProxy}
+        envProxy={envProxy}
+        proxyError={proxyError}
+      />
+    );
   } else if (page === 'installType') {
     body = (
       <InstallTypeStep
@@ -1457,6 +2365,10 @@ export default function App() {
         importConfigError={importConfigError}
         onImportConfigErrorClear={() => setImportConfigError(null)}
         onBrowseForFile={browseForFile}
+        dependencyChecks={dependencyChecks}
+        dependencyChecking={dependencyChecking}
+        dependencyError={dependencyError}
+        onRecheckDependencies={() => void checkDependencies()}
       />
     );
   } else if (page === 'destination') {
@@ -1466,6 +2378,14 @@ export default function App() {
         onDestinationChange={setDestinationFolder}
         destinationError={destinationError}
         onBrowseForFolder={browseForFolder}
+        layoutAdvanced={layoutAdvanced}
+        onLayoutAdvancedChange={setLayoutAdvanced}
+        layout={layoutPaths}
+        layoutDefaults={layoutDefaults}
+        onLayoutChange={(role, path) => setLayoutPaths((prev) => ({ ...prev, [role]: path }))}
+        layoutFreeSpace={layoutFreeSpace}
+        remote={remoteTarget}
+        onRemoteChange={setRemoteTarget}
       />
     );
   } else if (page === 'dataSource') {
@@ -1483,8 +2403,34 @@ export default function App() {
         onCallDataUserChange={setCallDataUser}
         callDataPassword={callDataPassword}
         onCallDataPasswordChange={setCallDataPassword}
+        callDataWindowsAuth={callDataWindowsAuth}
+        onCallDataWindowsAuthChange={setCallDataWindowsAuth}
         sourceObjectName={sourceObjectName}
         onSourceObjectNameChange={setSourceObjectName}
+        onListSourceObjects={() =>
+          listSourceObjects({
+            callDataConnectionString: computedCallDataConnectionString,
+            callDataDriver,
+            demoMode: mappingDemoMode,
+          })
+        }
+        odbcConnectionString={odbcConnectionString}
+        onOdbcConnectionStringChange={setOdbcConnectionString}
+        oracleDriver={oracleDriver}
+        onOracleDriverChange={setOracleDriver}
+        oracleAddress={oracleAddress}
+        onOracleAddressChange={setOracleAddress}
+        fileSourceFolder={fileSourceFolder}
+        onFileSourceFolderChange={setFileSourceFolder}
+        fileSourceDelimiter={fileSourceDelimiter}
+        onFileSourceDelimiterChange={setFileSourceDelimiter}
+        onBrowseForExportFolder={browseForExportFolder}
+        setupError={dataSourceSetupError}
+        notLicensedMessage={
+          multiDatasourceLicensed ? null : notIncludedInLicenseMessage('ODBC, Oracle and file export data sources')
+        }
+        profileValues={dataSourceProfileValues}
+        onLoadProfile={loadDataSourceProfile}
       />
     );
   } else if (page === 'database') {
@@ -1509,6 +2455,7 @@ export default function App() {
         newDbPrivTestStatus={newDbPrivTestStatus}
         newDbPrivTestMessage={newDbPrivTestMessage}
         onRunCreateNewPrivilegeTest={runCreateNewPrivilegeTest}
+        newDbGrantScript={newDbGrantScript}
         newDbLocation={newDbLocation}
         onNewDbLocationChange={setNewDbLocation}
         newDbSpecificPath={newDbSpecificPath}
@@ -1531,7 +2478,14 @@ export default function App() {
         onNewDbLogFilegrowthChange={setNewDbLogFilegrowth}
         newDbPgOwner={newDbPgOwner}
         onNewDbPgOwnerChange={setNewDbPgOwner}
+        newDbCollation={newDbCollation}
+        onNewDbCollationChange={setNewDbCollation}
         dbCreateValidationError={dbCreateValidationError}
+        appAccountEnabled={appAccountEnabled}
+        onAppAccountEnabledChange={setAppAccountEnabled}
+        appLoginName={appLoginName}
+        onAppLoginNameChange={setAppLoginName}
+        appAccountValidationError={appAccountValidationError}
         existingHostedWhere={existingHostedWhere}
         onExistingHostedWhereChange={setExistingHostedWhere}
         dbUseConnString={dbUseConnString}
@@ -1552,13 +2506,18 @@ export default function App() {
         onDbUserChange={setDbUser}
         dbPassword={dbPassword}
         onDbPasswordChange={setDbPassword}
+        dbWindowsAuth={dbUsesWindowsAuth}
+        onDbWindowsAuthChange={setDbWindowsAuth}
         dbSslMode={dbSslMode}
         onDbSslModeChange={setDbSslMode}
         dbExistingMissingInputs={dbExistingMissingInputs}
         canRunDbTest={canRunDbTest}
         dbTestStatus={dbTestStatus}
         dbTestMessage={dbTestMessage}
+        dbTestStages={dbTestStages}
         onRunDbTest={runDbTest}
+        profileValues={databaseProfileValues}
+        onLoadProfile={loadDatabaseProfile}
       />
     );
   } else if (page === 'storage') {
@@ -1574,6 +2533,7 @@ export default function App() {
         onRetentionPolicyChange={setRetentionPolicy}
         maxDiskGb={maxDiskGb}
         onMaxDiskGbChange={setMaxDiskGb}
+        storageFilesystem={storageFilesystem}
       />
     );
   } else if (page === 'retention') {
@@ -1602,7 +2562,10 @@ export default function App() {
         onArchiveScheduleTimeLocalChange={setArchiveScheduleTimeLocal}
         archiveCatchUpOnStartup={archiveCatchUpOnStartup}
         onArchiveCatchUpOnStartupChange={setArchiveCatchUpOnStartup}
+        archiveEncrypt={archiveEncrypt}
+        onArchiveEncryptChange={setArchiveEncrypt}
         archiveValidationError={archiveValidationError}
+        notLicensedMessage={archiveLicensed ? null : notIncludedInLicenseMessage('The archive module')}
       />
     );
   } else if (page === 'consent') {
@@ -1651,6 +2614,14 @@ export default function App() {
         }}
         onAttemptMap={attemptMap}
         onUnassignSelected={unassignSelected}
+        transforms={mappingTransforms}
+        onEditTransforms={() => setTransformsTargetId(selectedTargetId)}
+        pendingSuggestions={pendingSuggestions}
+        autoMapRunning={autoMapRunning}
+        onAutoMap={() => void runAutoMap()}
+        onConfirmSuggestions={confirmSuggestions}
+        onExportSheet={() => void exportMappingSheet()}
+        onImportSheet={() => void importMappingSheet()}
       />
     );
   } else if (page === 'ready') {
@@ -1673,9 +2644,20 @@ export default function App() {
         archiveScheduleDayOfMonth={archiveScheduleDayOfMonth}
         archiveScheduleTimeLocal={archiveScheduleTimeLocal}
         archiveCatchUpOnStartup={archiveCatchUpOnStartup}
+        archiveEncrypt={archiveEncrypt}
         consentToSync={consentToSync}
         mappedCount={mappedCount}
         requiredTargetsUnmappedLength={requiredTargetsUnmapped.length}
+        backfillAvailable={!fileSource}
+        backfillEstimating={backfillEstimating}
+        backfillEstimate={backfillEstimate}
+        backfillEstimateError={backfillEstimateError}
+        diskSpaceChecking={diskSpaceChecking}
+        diskSpace={diskSpace}
+        diskSpaceError={diskSpaceError}
+        onRecheckDiskSpace={() => setDiskSpaceCheckCount((n) => n + 1)}
+        dryRun={dryRun}
+        onDryRunChange={setDryRun}
       />
     );
   } else if (page === 'installing') {
@@ -1684,6 +2666,8 @@ export default function App() {
         progress={progress}
         installDetailLines={installDetailLines}
         installError={installError}
+        paused={installPaused}
+        onTogglePause={() => void toggleInstallPause()}
       />
     );
   } else if (page === 'complete') {
@@ -1693,6 +2677,14 @@ export default function App() {
         installManifestPath={installManifestPath}
         installMappingPath={installMappingPath}
         installConfigPath={installConfigPath}
+        archiveProgress={archiveProgress}
+        archiveRunning={archiveRunning}
+        archiveResult={archiveResult}
+        onRunArchive={archiveLicensed ? () => void runArchiveNow() : null}
+        health={health}
+        healthChecking={healthChecking}
+        healthError={healthError}
+        onRecheckHealth={() => void runHealthCheck()}
       />
     );
   }
@@ -1704,7 +2696,7 @@ export default function App() {
 
   // Calculate step info for progress indicator
   const stepInfo = getStepInfo(page);
-  const stepNames = WIZARD_PAGES.map((p) => WIZARD_STEP_NAMES[p]);
+  const stepNames = WIZARD_PAGES.map((p) => t(`step.${p}`));
 
   return (
     <>
@@ -1726,6 +2718,28 @@ export default function App() {
         {body}
       </WizardFrame>
       <Modal state={modal} />
+      {transformsTargetId ? (
+        <TransformsModal
+          targetName={targetFields.find((t) => t.id === transformsTargetId)?.name ?? transformsTargetId}
+          mappedSourceName={
+            targetToSource[transformsTargetId]
+              ? sourceFields.find((s) => s.id === targetToSource[transformsTargetId])?.displayName ??
+                targetToSource[transformsTargetId]
+              : null
+          }
+          sourceFields={sourceFields}
+          transforms={mappingTransforms[transformsTargetId] ?? []}
+          onChange={(list) =>
+            setMappingTransforms((prev) => {
+              const next = { ...prev };
+              if (list.length > 0) next[transformsTargetId] = list;
+              else delete next[transformsTargetId];
+              return next;
+            })
+          }
+          onClose={() => setTransformsTargetId(null)}
+        />
+      ) : null}
     </>
   );
 }
@@ -0,0 +1,113 @@
+/**
+ * ConnectionProfiles - Saved connections of the Data Source and Database pages
+ *
+ * Profiles are stored by the backend (utils::connection_profiles) with
+ * passwords and connection strings encrypted, and shared with the TUI.
+ * Values use the `--tui-script` answer keys of the page.
+ */
+import { useEffect, useState } from 'react';
+import {
+  deleteConnectionProfile,
+  listConnectionProfiles,
+  saveConnectionProfile,
+  type ApiResponse,
+  type ConnectionProfile,
+  type ConnectionProfileKind,
+} from '../lib/api';
+
+export interface ConnectionProfilesProps {
+  kind: ConnectionProfileKind;
+  /** The page's current values, saved under a name. */
+  values: Record<string, string>;
+  onLoad: (values: Record<string, string>) => void;
+}
+
+export default function ConnectionProfiles({ kind, values, onLoad }: ConnectionProfilesProps) {
+  const [profiles, setProfiles] = useState<ConnectionProfile[]>([]);
+  const [selected, setSelected] = useState('');
+  const [name, setName] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void listConnectionProfiles(kind).then((res) => {
+      if (res.success && res.data) setProfiles(res.data);
+      else setError(res.error || 'Unable to read the saved profiles.');
+    });
+  }, [kind]);
+
+  async function run(request: Promise<ApiResponse<ConnectionProfile[]>>) {
+    setBusy(true);
+    setError(null);
+    try {
+      const res = await request;
+      if (!res.success || !res.data) {
+        setError(res.error || 'Unable to update the saved profiles.');
+        return false;
+      }
+      setProfiles(res.data);
+      return true;
+    } catch (e) {
+      setError(String(e));
+      return false;
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function save() {
+    if (await run(saveConnectionProfile(kind, name.trim(), values))) {
+      setSelected(name.trim());
+      setName('');
+    }
+  }
+
+  async function remove() {
+    if (await run(deleteConnectionProfile(kind, selected))) setSelected('');
+  }
+
+  const current = profiles.find((p) => p.name === selected);
+  return (
+    <div className="wizard-row">
+      <label className="wizard-label">Saved connection profile</label>
+      <div className="wizard-inline">
+        <select
+          className="wizard-input"
+          value={current ? selected : ''}
+          onChange={(e) => {
+            setSelected(e.target.value);
+            const profile = profiles.find((p) => p.name === e.target.value);
+            if (profile) onLoad(profile.values);
+          }}
+        >
+          <option value="">{profiles.length ? 'Select a profile to load…' : 'No saved profiles'}</option>
+          {profiles.map((p) => (
+            <option key={p.name} value={p.name}>
+              {p.name}
+              {p.values.host ? ` (${p.values.host})` : ''}
+            </option>
+          ))}
+        </select>
+        <button className="wizard-button" onClick={() => void remove()} disabled={busy || !current}>
+          Delete
+        </button>
+      </div>
+      <div className="wizard-inline" style={{ marginTop: 6 }}>
+        <input
+          className="wizard-input"
+          value={name}
+          maxLength={64}
+          placeholder="Name, e.g. Staging"
+          onChange={(e) => setName(e.target.value)}
+        />
+        <button className="wizard-button" onClick={() => void save()} disabled={busy || !name.trim()}>
+          Save current
+        </button>
+      </div>
+      <div className="wizard-help">
+        Saving under an existing name replaces that profile. Passwords are stored encrypted on this machine.
+      </div>
+      {error ? <div className="wizard-error">{error}</div> : null}
+    </div>
+  );
+}
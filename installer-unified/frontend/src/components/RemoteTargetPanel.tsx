@@ -0,0 +1,127 @@
+/**
+ * RemoteTargetPanel - Install on a remote Linux server over SSH (Destination page)
+ *
+ * The install runs from this machine and deploys to the server (installation::remote).
+ * Login is key-based; the user must be root or have passwordless sudo. Check server
+ * lists the host keys the server offers: confirm the one matching `ssh-keygen -lf` on
+ * the server, and the install refuses any other key.
+ */
+import { useState } from 'react';
+import { checkRemoteTarget, type RemoteCheck, type RemoteTarget } from '../lib/api';
+
+export interface RemoteTargetPanelProps {
+  /** Null installs on this machine. */
+  target: RemoteTarget | null;
+  onChange: (target: RemoteTarget | null) => void;
+}
+
+const NEW_TARGET: RemoteTarget = { host: '', port: null, user: 'root', identityFile: '', hostKeyFingerprint: '' };
+
+export default function RemoteTargetPanel({ target, onChange }: RemoteTargetPanelProps) {
+  const [check, setCheck] = useState<RemoteCheck | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  function update(patch: Partial<RemoteTarget>) {
+    if (!target) return;
+    // Another server (or port) needs its host key confirmed again.
+    const moved = patch.host !== undefined || patch.port !== undefined;
+    onChange({ ...target, ...patch, ...(moved ? { hostKeyFingerprint: '' } : {}) });
+    if (moved) setCheck(null);
+  }
+
+  async function runCheck() {
+    if (!target) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const res = await checkRemoteTarget(target);
+      if (res.success && res.data) {
+        setCheck(res.data);
+      } else {
+        setCheck(null);
+        setError(res.error || 'Unable to check the server.');
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <div className="wizard-row">
+      <label className="wizard-inline">
+        <input
+          type="checkbox"
+          checked={!!target}
+          onChange={(e) => {
+            setCheck(null);
+            setError(null);
+            onChange(e.target.checked ? NEW_TARGET : null);
+          }}
+        />
+        Install on a remote Linux server over SSH
+      </label>
+      {target ? (
+        <div style={{ marginTop: 6 }}>
+          <label className="wizard-label">Server</label>
+          <div className="wizard-inline">
+            <input
+              className="wizard-input"
+              value={target.host}
+              placeholder="Host name or IP address"
+              onChange={(e) => update({ host: e.target.value.trim() })}
+            />
+            <input
+              className="wizard-input"
+              style={{ maxWidth: 90 }}
+              value={target.port ?? ''}
+              placeholder="22"
+              onChange={(e) => {
+                const port = parseInt(e.target.value, 10);
+                update({ port: Number.isNaN(port) ? null : port });
+              }}
+            />
+          </div>
+          <label className="wizard-label">User</label>
+          <input className="wizard-input" value={target.user} onChange={(e) => update({ user: e.target.value.trim() })} />
+          <label className="wizard-label">Private key file (optional)</label>
+          <input
+            className="wizard-input"
+            value={target.identityFile}
+            placeholder="Empty uses the SSH agent and your default keys"
+            onChange={(e) => update({ identityFile: e.target.value })}
+          />
+          <div className="wizard-inline" style={{ marginTop: 6 }}>
+            <button className="wizard-button" onClick={() => void runCheck()} disabled={busy || !target.host || !target.user}>
+              {busy ? 'Checking…' : 'Check server'}
+            </button>
+          </div>
+          {check ? (
+            <div style={{ marginTop: 6 }}>
+              <div className="wizard-help">Host keys offered by the server; confirm the one that matches the server:</div>
+              {check.hostKeys.map((key) => (
+                <label key={key.fingerprint} className="wizard-inline">
+                  <input
+                    type="radio"
+                    checked={target.hostKeyFingerprint === key.fingerprint}
+                    onChange={() => update({ hostKeyFingerprint: key.fingerprint })}
+                  />
+                  {key.keyType} {key.fingerprint}
+                </label>
+              ))}
+              <div className={check.ready ? 'wizard-help' : 'wizard-error'}>
+                {check.trusted || !target.hostKeyFingerprint ? check.message : 'Check the server again to log in with the confirmed key.'}
+              </div>
+            </div>
+          ) : null}
+          <div className="wizard-help">
+            The user must be root or have passwordless sudo. The database steps still run from this machine.
+          </div>
+          {error ? <div className="wizard-error">{error}</div> : null}
+        </div>
+      ) : null}
+    </div>
+  );
+}
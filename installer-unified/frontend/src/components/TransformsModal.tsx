@@ -0,0 +1,215 @@
+/**
+ * TransformsModal - Value transforms for one mapping target field
+ *
+ * Transforms run in list order on the mapped source value during ingestion
+ * (backend: mapping::transform). Validation here mirrors the backend so the
+ * install does not fail at the validate step.
+ */
+import { useState } from 'react';
+import type { SourceField } from './steps/MappingStep';
+
+export type FieldTransform =
+  | { kind: 'trim' }
+  | { kind: 'parseDate'; format: string }
+  | { kind: 'convertUnit'; from: string; to: string }
+  | { kind: 'concat'; sourceId: string; separator: string }
+  | { kind: 'defaultValue'; value: string };
+
+type TransformKind = FieldTransform['kind'];
+
+const KIND_LABELS: Record<TransformKind, string> = {
+  trim: 'Trim whitespace',
+  parseDate: 'Parse date',
+  convertUnit: 'Convert unit',
+  concat: 'Concat another source field',
+  defaultValue: 'Default value',
+};
+
+// Same units and dimensions as the backend UNITS table.
+const UNIT_GROUPS: string[][] = [
+  ['milliseconds', 'seconds', 'minutes', 'hours'],
+  ['meters', 'kilometers', 'feet', 'miles'],
+  ['celsius', 'fahrenheit'],
+];
+
+export function describeTransform(t: FieldTransform, sourceFields: SourceField[]): string {
+  switch (t.kind) {
+    case 'trim':
+      return 'Trim whitespace';
+    case 'parseDate':
+      return `Parse date (${t.format})`;
+    case 'convertUnit':
+      return `Convert ${t.from} -> ${t.to}`;
+    case 'concat':
+      return `Concat '${t.separator}' + ${sourceFields.find((s) => s.id === t.sourceId)?.displayName ?? t.sourceId}`;
+    case 'defaultValue':
+      return `Default '${t.value}'`;
+  }
+}
+
+interface TransformsModalProps {
+  targetName: string;
+  mappedSourceName: string | null;
+  sourceFields: SourceField[];
+  transforms: FieldTransform[];
+  onChange: (transforms: FieldTransform[]) => void;
+  onClose: () => void;
+}
+
+export default function TransformsModal({
+  targetName,
+  mappedSourceName,
+  sourceFields,
+  transforms,
+  onChange,
+  onClose,
+}: TransformsModalProps) {
+  const [kind, setKind] = useState<TransformKind>('trim');
+  const [format, setFormat] = useState('%m/%d/%Y %H:%M');
+  const [fromUnit, setFromUnit] = useState('miles');
+  const [toUnit, setToUnit] = useState('kilometers');
+  const [concatSourceId, setConcatSourceId] = useState(sourceFields[0]?.id ?? '');
+  const [separator, setSeparator] = useState(' ');
+  const [defaultValue, setDefaultValue] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  function buildTransform(): FieldTransform | string {
+    switch (kind) {
+      case 'trim':
+        return { kind };
+      case 'parseDate':
+        return format.trim() ? { kind, format: format.trim() } : 'Enter a date format.';
+      case 'convertUnit':
+        return UNIT_GROUPS.some((g) => g.includes(fromUnit) && g.includes(toUnit))
+          ? { kind, from: fromUnit, to: toUnit }
+          : `Cannot convert ${fromUnit} to ${toUnit}.`;
+      case 'concat':
+        return concatSourceId ? { kind, sourceId: concatSourceId, separator } : 'Choose a source field.';
+      case 'defaultValue':
+        return { kind, value: defaultValue };
+    }
+  }
+
+  function add() {
+    const t = buildTransform();
+    if (typeof t === 'string') {
+      setError(t);
+      return;
+    }
+    setError(null);
+    onChange([...transforms, t]);
+  }
+
+  function move(index: number, delta: number) {
+    const next = [...transforms];
+    const [item] = next.splice(index, 1);
+    next.splice(index + delta, 0, item);
+    onChange(next);
+  }
+
+  const allUnits = UNIT_GROUPS.flat();
+
+  return (
+    <div className="modal-overlay" role="dialog" aria-modal="true">
+      <div className="modal">
+        <div className="modal-header">Value Transforms — {targetName}</div>
+        <div className="modal-body">
+          <div className="wizard-help">Source: {mappedSourceName ?? '(unmapped)'}</div>
+          {transforms.length === 0 ? <div className="wizard-help">No transforms.</div> : null}
+          {transforms.map((t, i) => (
+            <div key={i} className="wizard-row wizard-inline">
+              <span>
+                {i + 1}. {describeTransform(t, sourceFields)}
+              </span>
+              <button className="wizard-button" disabled={i === 0} onClick={() => move(i, -1)}>
+                Up
+              </button>
+              <button className="wizard-button" disabled={i === transforms.length - 1} onClick={() => move(i, 1)}>
+                Down
+              </button>
+              <button className="wizard-button" onClick={() => onChange(transforms.filter((_, j) => j !== i))}>
+                Remove
+              </button>
+            </div>
+          ))}
+
+          <div className="wizard-row wizard-inline" style={{ marginTop: 10 }}>
+            <select className="wizard-input" value={kind} onChange={(e) => setKind(e.target.value as TransformKind)}>
+              {(Object.keys(KIND_LABELS) as TransformKind[]).map((k) => (
+                <option key={k} value={k}>
+                  {KIND_LABELS[k]}
+                </option>
+              ))}
+            </select>
+            {kind === 'parseDate' ? (
+              <input
+                className="wizard-input"
+                aria-label="Date format"
+                placeholder="%m/%d/%Y %H:%M"
+                value={format}
+                onChange={(e) => setFormat(e.target.value)}
+              />
+            ) : null}
+            {kind === 'convertUnit' ? (
+              <>
+                <select className="wizard-input" aria-label="From unit" value={fromUnit} onChange={(e) => setFromUnit(e.target.value)}>
+                  {allUnits.map((u) => (
+                    <option key={u} value={u}>
+                      {u}
+                    </option>
+                  ))}
+                </select>
+                <select className="wizard-input" aria-label="To unit" value={toUnit} onChange={(e) => setToUnit(e.target.value)}>
+                  {allUnits.map((u) => (
+                    <option key={u} value={u}>
+                      {u}
+                    </option>
+                  ))}
+                </select>
+              </>
+            ) : null}
+            {kind === 'concat' ? (
+              <>
+                <select
+                  className="wizard-input"
+                  aria-label="Source field"
+                  value={concatSourceId}
+                  onChange={(e) => setConcatSourceId(e.target.value)}
+                >
+                  {sourceFields.map((s) => (
+                    <option key={s.id} value={s.id}>
+                      {s.displayName}
+                    </option>
+                  ))}
+                </select>
+                <input
+                  className="wizard-input"
+                  aria-label="Separator"
+                  value={separator}
+                  onChange={(e) => setSeparator(e.target.value)}
+                />
+              </>
+            ) : null}
+            {kind === 'defaultValue' ? (
+              <input
+                className="wizard-input"
+                aria-label="Default value"
+                value={defaultValue}
+                onChange={(e) => setDefaultValue(e.target.value)}
+              />
+            ) : null}
+            <button className="wizard-button" onClick={add}>
+              Add
+            </button>
+          </div>
+          {error ? <div className="wizard-error">{error}</div> : null}
+        </div>
+        <div className="modal-footer">
+          <button className="wizard-button primary" onClick={onClose}>
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
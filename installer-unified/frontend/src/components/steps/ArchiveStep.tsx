@@ -13,8 +13,12 @@ export interface ArchiveStepProps {
   onArchiveScheduleTimeLocalChange: (value: string) => void;
   archiveCatchUpOnStartup: boolean;
   onArchiveCatchUpOnStartupChange: (value: boolean) => void;
+  archiveEncrypt: boolean;
+  onArchiveEncryptChange: (value: boolean) => void;
   archiveValidationError: string | null;
   onBrowseForArchiveFolder: () => void;
+  /** Set when the archive module is not licensed; replaces the settings. */
+  notLicensedMessage?: string | null;
 }
 
 export function ArchiveStep({
@@ -30,9 +34,20 @@ export function ArchiveStep({
   onArchiveScheduleTimeLocalChange,
   archiveCatchUpOnStartup,
   onArchiveCatchUpOnStartupChange,
+  archiveEncrypt,
+  onArchiveEncryptChange,
   archiveValidationError,
   onBrowseForArchiveFolder,
+  notLicensedMessage,
 }: ArchiveStepProps) {
+  if (notLicensedMessage) {
+    return (
+      <div>
+        <div className="wizard-row">{notLicensedMessage}</div>
+        <div className="wizard-help">Call data stays in the hot database; no archive files are written.</div>
+      </div>
+    );
+  }
   return (
     <div>
       <div className="wizard-row">Configure cold storage (archive) settings.</div>
@@ -96,6 +111,13 @@ export function ArchiveStep({
         </label>
       </div>
 
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input type="checkbox" checked={archiveEncrypt} onChange={(e) => onArchiveEncryptChange(e.target.checked)} />
+          Encrypt archives at rest (AES-256; key is kept with the installer secrets)
+        </label>
+      </div>
+
       {archiveValidationError ? <div className="wizard-error">{archiveValidationError}</div> : null}
     </div>
   );
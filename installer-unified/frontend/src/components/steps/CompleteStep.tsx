@@ -1,8 +1,32 @@
+import type { ProgressEvent, SetupVerifyResponse } from '../../lib/api';
+
+const HEALTH_SUMMARY: Record<SetupVerifyResponse['health'], string> = {
+  green: 'All health checks passed.',
+  yellow: 'CADalytix is running, but some checks need attention.',
+  red: 'One or more health checks failed.',
+};
+
+const CHECK_CLASS: Record<string, string> = {
+  pass: 'wizard-help',
+  warn: 'wizard-warning',
+  fail: 'wizard-error',
+  skip: 'wizard-help',
+};
+
 export interface CompleteStepProps {
   installLogFolder: string | null;
   installManifestPath: string | null;
   installMappingPath: string | null;
   installConfigPath: string | null;
+  archiveProgress: ProgressEvent | null;
+  archiveRunning: boolean;
+  archiveResult: string | null;
+  /** Null when the archive module is not licensed. */
+  onRunArchive: (() => void) | null;
+  health: SetupVerifyResponse | null;
+  healthChecking: boolean;
+  healthError: string | null;
+  onRecheckHealth: () => void;
 }
 
 export function CompleteStep({
@@ -10,6 +34,14 @@ export function CompleteStep({
   installManifestPath,
   installMappingPath,
   installConfigPath,
+  archiveProgress,
+  archiveRunning,
+  archiveResult,
+  onRunArchive,
+  health,
+  healthChecking,
+  healthError,
+  onRecheckHealth,
 }: CompleteStepProps) {
   return (
     <div>
@@ -18,6 +50,46 @@ export function CompleteStep({
       {installManifestPath ? <div className="wizard-help">Install manifest: {installManifestPath}</div> : null}
       {installMappingPath ? <div className="wizard-help">Mapping: {installMappingPath}</div> : null}
       {installConfigPath ? <div className="wizard-help">Install config: {installConfigPath}</div> : null}
+      <div style={{ marginTop: 18 }}>
+        <div className="wizard-inline">
+          <label className="wizard-label">System health</label>
+          <button className="wizard-button" type="button" onClick={onRecheckHealth} disabled={healthChecking}>
+            Check again
+          </button>
+        </div>
+        {healthChecking ? <div className="wizard-help">Running health checks…</div> : null}
+        {healthError ? <div className="wizard-error">Health checks could not run: {healthError}</div> : null}
+        {!healthChecking && health ? (
+          <>
+            <div
+              className={
+                health.health === 'green' ? 'wizard-success' : health.health === 'yellow' ? 'wizard-warning' : 'wizard-error'
+              }
+            >
+              {HEALTH_SUMMARY[health.health] ?? HEALTH_SUMMARY.red}
+            </div>
+            {health.checks.map((c) => (
+              <div key={c.id} className={CHECK_CLASS[c.status] ?? 'wizard-help'}>
+                <strong>{c.label}:</strong> {c.message}
+              </div>
+            ))}
+          </>
+        ) : null}
+      </div>
+      {onRunArchive ? (
+        <div className="wizard-row">
+          <button className="wizard-button" type="button" onClick={onRunArchive} disabled={archiveRunning}>
+            {archiveRunning ? 'Archiving…' : 'Run archive catch-up now'}
+          </button>
+        </div>
+      ) : null}
+      {archiveProgress ? (
+        <>
+          <progress className="progress-bar" value={archiveProgress.percent} max={100} />
+          <div className="wizard-help">Archive: {archiveProgress.message ?? ''}</div>
+        </>
+      ) : null}
+      {archiveResult ? <div className="wizard-help">{archiveResult}</div> : null}
       <div className="wizard-row wizard-inline">
         <input id="launchAfter" type="checkbox" disabled />
         <label htmlFor="launchAfter" className="wizard-label" style={{ margin: 0 }}>
@@ -27,4 +99,3 @@ export function CompleteStep({
     </div>
   );
 }
-
@@ -36,7 +36,9 @@ export function ConsentStep({
           </ul>
         </div>
       ) : null}
-      <div className="wizard-help">This setting is stored locally. Network sync is not performed in this phase.</div>
+      <div className="wizard-help">
+        When enabled, this is sent once after installation completes. If the network is unavailable it is queued locally and retried on the next installer start.
+      </div>
     </div>
   );
 }
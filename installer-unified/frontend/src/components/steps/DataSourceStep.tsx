@@ -1,4 +1,8 @@
-export type DataSourceKind = 'local' | 'remote';
+import { useState } from 'react';
+import type { ApiResponse, ListSourceObjectsResponseDto, SourceObjectDto } from '../../lib/api';
+import ConnectionProfiles from '../ConnectionProfiles';
+
+export type DataSourceKind = 'local' | 'remote' | 'odbc' | 'oracle' | 'file';
 
 export interface DataSourceStepProps {
   dataSourceKind: DataSourceKind;
@@ -13,8 +17,30 @@ export interface DataSourceStepProps {
   onCallDataUserChange: (value: string) => void;
   callDataPassword: string;
   onCallDataPasswordChange: (value: string) => void;
+  /** SQL Server only: Integrated Security instead of a SQL login. */
+  callDataWindowsAuth: boolean;
+  onCallDataWindowsAuthChange: (value: boolean) => void;
   sourceObjectName: string;
   onSourceObjectNameChange: (value: string) => void;
+  /** Lists the source's tables and views with the connection settings entered on this page. */
+  onListSourceObjects: () => Promise<ApiResponse<ListSourceObjectsResponseDto>>;
+  odbcConnectionString: string;
+  onOdbcConnectionStringChange: (value: string) => void;
+  oracleDriver: string;
+  onOracleDriverChange: (value: string) => void;
+  oracleAddress: string;
+  onOracleAddressChange: (value: string) => void;
+  fileSourceFolder: string;
+  onFileSourceFolderChange: (value: string) => void;
+  fileSourceDelimiter: string;
+  onFileSourceDelimiterChange: (value: string) => void;
+  onBrowseForExportFolder: () => void;
+  setupError: string | null;
+  /** Set when ODBC/Oracle/file sources are not licensed; those options are disabled. */
+  notLicensedMessage?: string | null;
+  /** Page values by `--tui-script` answer key, for saved connection profiles. */
+  profileValues: Record<string, string>;
+  onLoadProfile: (values: Record<string, string>) => void;
 }
 
 export function DataSourceStep({
@@ -30,11 +56,31 @@ export function DataSourceStep({
   onCallDataUserChange,
   callDataPassword,
   onCallDataPasswordChange,
+  callDataWindowsAuth,
+  onCallDataWindowsAuthChange,
   sourceObjectName,
   onSourceObjectNameChange,
+  onListSourceObjects,
+  odbcConnectionString,
+  onOdbcConnectionStringChange,
+  oracleDriver,
+  onOracleDriverChange,
+  oracleAddress,
+  onOracleAddressChange,
+  fileSourceFolder,
+  onFileSourceFolderChange,
+  fileSourceDelimiter,
+  onFileSourceDelimiterChange,
+  onBrowseForExportFolder,
+  setupError,
+  notLicensedMessage,
+  profileValues,
+  onLoadProfile,
 }: DataSourceStepProps) {
+  const unlicensed = !!notLicensedMessage;
   return (
     <div>
+      <ConnectionProfiles kind="dataSource" values={profileValues} onLoad={onLoadProfile} />
       <div className="wizard-row">
         <label className="wizard-inline">
           <input type="radio" checked={dataSourceKind === 'local'} onChange={() => onDataSourceKindChange('local')} />
@@ -47,51 +93,310 @@ export function DataSourceStep({
           Connect to an existing remote system/database
         </label>
       </div>
-      <div style={{ marginTop: 10 }}>
-        <div className="wizard-row">
-          <label className="wizard-label">Host</label>
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input
+            type="radio"
+            checked={dataSourceKind === 'odbc'}
+            disabled={unlicensed}
+            onChange={() => onDataSourceKindChange('odbc')}
+          />
+          ODBC data source (DSN or driver connection string)
+        </label>
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input
+            type="radio"
+            checked={dataSourceKind === 'oracle'}
+            disabled={unlicensed}
+            onChange={() => onDataSourceKindChange('oracle')}
+          />
+          Oracle database
+        </label>
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-inline">
           <input
-            className="wizard-input"
-            value={callDataHost}
-            onChange={(e) => onCallDataHostChange(e.target.value)}
-            disabled={dataSourceKind === 'local'}
+            type="radio"
+            checked={dataSourceKind === 'file'}
+            disabled={unlicensed}
+            onChange={() => onDataSourceKindChange('file')}
+          />
+          File (CSV/TSV) export folder
+        </label>
+      </div>
+      {notLicensedMessage ? <div className="wizard-help">{notLicensedMessage}</div> : null}
+      {dataSourceKind === 'odbc' ? (
+        <div style={{ marginTop: 10 }}>
+          <div className="wizard-row">
+            <label className="wizard-label">ODBC connection string</label>
+            <input
+              className="wizard-input"
+              value={odbcConnectionString}
+              onChange={(e) => onOdbcConnectionStringChange(e.target.value)}
+              placeholder="DSN=CAD;UID=reader;PWD=..."
+            />
+            <div className="wizard-help">
+              A DSN configured on this machine, or a driver string such as Driver={'{'}Vendor ODBC Driver{'}'};Server=...;
+            </div>
+          </div>
+          <SourceObjectField
+            value={sourceObjectName}
+            onChange={onSourceObjectNameChange}
+            onList={onListSourceObjects}
           />
         </div>
-        <div className="wizard-row wizard-inline">
-          <div style={{ flex: 1 }}>
-            <label className="wizard-label">Port</label>
+      ) : dataSourceKind === 'oracle' ? (
+        <div style={{ marginTop: 10 }}>
+          <div className="wizard-row">
+            <label className="wizard-label">ODBC driver name</label>
             <input
               className="wizard-input"
-              value={callDataPort}
-              onChange={(e) => onCallDataPortChange(e.target.value)}
-              disabled={dataSourceKind === 'local'}
+              value={oracleDriver}
+              onChange={(e) => onOracleDriverChange(e.target.value)}
+              placeholder="Oracle 21 ODBC driver"
             />
+            <div className="wizard-help">Leave blank for the Oracle Instant Client ODBC driver's default name.</div>
           </div>
-          <div style={{ flex: 2 }}>
-            <label className="wizard-label">Database</label>
-            <input className="wizard-input" value={callDataDbName} onChange={(e) => onCallDataDbNameChange(e.target.value)} />
+          <div className="wizard-row">
+            <label className="wizard-label">Address</label>
+            <input
+              className="wizard-input"
+              value={oracleAddress}
+              onChange={(e) => onOracleAddressChange(e.target.value)}
+              placeholder="cad-db:1521/CADPROD"
+            />
+            <div className="wizard-help">host[:port]/service (port defaults to 1521) or a TNS alias.</div>
           </div>
-        </div>
-        <div className="wizard-row">
-          <label className="wizard-label">Username</label>
-          <input className="wizard-input" value={callDataUser} onChange={(e) => onCallDataUserChange(e.target.value)} />
-        </div>
-        <div className="wizard-row">
-          <label className="wizard-label">Password</label>
-          <input
-            className="wizard-input"
-            type="password"
-            value={callDataPassword}
-            onChange={(e) => onCallDataPasswordChange(e.target.value)}
+          <div className="wizard-row">
+            <label className="wizard-label">Username</label>
+            <input className="wizard-input" value={callDataUser} onChange={(e) => onCallDataUserChange(e.target.value)} />
+          </div>
+          <div className="wizard-row">
+            <label className="wizard-label">Password</label>
+            <input
+              className="wizard-input"
+              type="password"
+              value={callDataPassword}
+              onChange={(e) => onCallDataPasswordChange(e.target.value)}
+            />
+          </div>
+          <SourceObjectField
+            value={sourceObjectName}
+            onChange={onSourceObjectNameChange}
+            onList={onListSourceObjects}
+            placeholder="OWNER.TABLE"
           />
         </div>
-      </div>
-      <div className="wizard-row">
-        <label className="wizard-label">Source object name</label>
-        <input className="wizard-input" value={sourceObjectName} onChange={(e) => onSourceObjectNameChange(e.target.value)} />
-      </div>
+      ) : dataSourceKind === 'file' ? (
+        <div style={{ marginTop: 10 }}>
+          <div className="wizard-row">
+            <label className="wizard-label">Export folder</label>
+            <div className="wizard-inline">
+              <input
+                className="wizard-input"
+                value={fileSourceFolder}
+                onChange={(e) => onFileSourceFolderChange(e.target.value)}
+              />
+              <button className="wizard-button" onClick={onBrowseForExportFolder}>
+                Browse…
+              </button>
+            </div>
+          </div>
+          <div className="wizard-row">
+            <label className="wizard-label">Delimiter</label>
+            <select
+              className="wizard-input"
+              value={fileSourceDelimiter}
+              onChange={(e) => onFileSourceDelimiterChange(e.target.value)}
+            >
+              <option value="auto">Detect automatically</option>
+              <option value="comma">Comma (,)</option>
+              <option value="tab">Tab</option>
+              <option value="semicolon">Semicolon (;)</option>
+              <option value="pipe">Pipe (|)</option>
+            </select>
+          </div>
+          <div className="wizard-help">
+            Headers are read from the newest .csv, .tsv or .txt export in the folder when you reach the Mapping page.
+          </div>
+        </div>
+      ) : (
+        <>
+          <div style={{ marginTop: 10 }}>
+            <div className="wizard-row">
+              <label className="wizard-label">Host</label>
+              <input
+                className="wizard-input"
+                value={callDataHost}
+                onChange={(e) => onCallDataHostChange(e.target.value)}
+                disabled={dataSourceKind === 'local'}
+              />
+            </div>
+            <div className="wizard-row wizard-inline">
+              <div style={{ flex: 1 }}>
+                <label className="wizard-label">Port</label>
+                <input
+                  className="wizard-input"
+                  value={callDataPort}
+                  onChange={(e) => onCallDataPortChange(e.target.value)}
+                  disabled={dataSourceKind === 'local'}
+                />
+              </div>
+              <div style={{ flex: 2 }}>
+                <label className="wizard-label">Database</label>
+                <input className="wizard-input" value={callDataDbName} onChange={(e) => onCallDataDbNameChange(e.target.value)} />
+              </div>
+            </div>
+            <div className="wizard-row">
+              <label className="wizard-inline">
+                <input
+                  type="checkbox"
+                  checked={callDataWindowsAuth}
+                  onChange={(e) => onCallDataWindowsAuthChange(e.target.checked)}
+                />
+                Use Windows Authentication
+              </label>
+              <div className="wizard-help">
+                Connects as the installer's Windows account (Kerberos ticket on Linux) instead of a SQL login.
+              </div>
+            </div>
+            {!callDataWindowsAuth ? (
+              <>
+                <div className="wizard-row">
+                  <label className="wizard-label">Username</label>
+                  <input className="wizard-input" value={callDataUser} onChange={(e) => onCallDataUserChange(e.target.value)} />
+                </div>
+                <div className="wizard-row">
+                  <label className="wizard-label">Password</label>
+                  <input
+                    className="wizard-input"
+                    type="password"
+                    value={callDataPassword}
+                    onChange={(e) => onCallDataPasswordChange(e.target.value)}
+                  />
+                </div>
+              </>
+            ) : null}
+          </div>
+          <SourceObjectField value={sourceObjectName} onChange={onSourceObjectNameChange} onList={onListSourceObjects} />
+        </>
+      )}
+      {setupError ? <div className="wizard-error">{setupError}</div> : null}
       <div className="wizard-help">Keep simple; do not require user to understand internal architecture.</div>
     </div>
   );
 }
 
+
+function qualifiedName(o: SourceObjectDto): string {
+  return o.schema ? `${o.schema}.${o.name}` : o.name;
+}
+
+/**
+ * "Source object name" with a Browse… button that connects and lists the source's tables and
+ * views (with row counts where the source reports them) in a searchable list. The name can
+ * still be typed when the catalog cannot be read.
+ */
+function SourceObjectField({
+  value,
+  onChange,
+  onList,
+  placeholder,
+}: {
+  value: string;
+  onChange: (value: string) => void;
+  onList: () => Promise<ApiResponse<ListSourceObjectsResponseDto>>;
+  placeholder?: string;
+}) {
+  const [objects, setObjects] = useState<SourceObjectDto[] | null>(null);
+  const [truncated, setTruncated] = useState(false);
+  const [search, setSearch] = useState('');
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function browse() {
+    setLoading(true);
+    setError(null);
+    try {
+      const res = await onList();
+      if (!res.success || !res.data) {
+        setObjects(null);
+        setError(res.error || "Unable to list the source's tables and views.");
+        return;
+      }
+      setObjects(res.data.objects);
+      setTruncated(res.data.truncated);
+      setSearch('');
+    } catch (e) {
+      setObjects(null);
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  const needle = search.trim().toLowerCase();
+  const current = value.trim().toLowerCase();
+  const shown = (objects ?? []).filter((o) => qualifiedName(o).toLowerCase().includes(needle));
+
+  return (
+    <div className="wizard-row">
+      <label className="wizard-label">Source object name</label>
+      <div className="wizard-inline">
+        <input
+          className="wizard-input"
+          value={value}
+          onChange={(e) => onChange(e.target.value)}
+          placeholder={placeholder}
+        />
+        <button className="wizard-button" onClick={() => void browse()} disabled={loading}>
+          {loading ? 'Connecting…' : 'Browse…'}
+        </button>
+      </div>
+      {error ? <div className="wizard-error">{error}</div> : null}
+      {objects ? (
+        <div className="mapping-pane" style={{ marginTop: 8, minHeight: 0, maxHeight: 260 }}>
+          <div className="mapping-pane-search">
+            <input
+              className="wizard-input"
+              placeholder="Search tables and views…"
+              value={search}
+              onChange={(e) => setSearch(e.target.value)}
+            />
+          </div>
+          <div className="mapping-list" role="listbox" aria-label="Source tables and views">
+            {shown.map((o) => {
+              const name = qualifiedName(o);
+              const selected = name.toLowerCase() === current;
+              return (
+                <div
+                  key={`${o.kind}:${name}`}
+                  role="option"
+                  aria-selected={selected}
+                  className={['mapping-row', selected ? 'selected' : ''].join(' ')}
+                  onClick={() => {
+                    onChange(name);
+                    setObjects(null);
+                  }}
+                >
+                  {name}{' '}
+                  <span className="wizard-help">
+                    ({o.kind}
+                    {o.rowCount != null ? `, ${o.rowCount.toLocaleString()} rows` : ''})
+                  </span>
+                </div>
+              );
+            })}
+            {shown.length === 0 ? <div className="mapping-row">No matching tables or views.</div> : null}
+          </div>
+          <div className="wizard-help" style={{ padding: '4px 10px' }}>
+            {shown.length} of {objects.length} object(s)
+            {truncated ? ' (list truncated; search to narrow it)' : ''}
+          </div>
+        </div>
+      ) : null}
+    </div>
+  );
+}
@@ -1,3 +1,5 @@
+import ConnectionProfiles from '../ConnectionProfiles';
+
 export type DbSetupMode = 'createNew' | 'existing' | null;
 export type NewDbLocation = 'thisMachine' | 'specificPath';
 export type DbHostedWhere = 'on_prem' | 'aws_rds' | 'azure_sql' | 'gcp_cloud_sql' | 'neon' | 'supabase' | 'other';
@@ -5,6 +7,13 @@ export type DbSslMode = 'disable' | 'prefer' | 'require';
 export type DbEngine = 'sqlserver' | 'postgres' | null;
 export type TestStatus = 'idle' | 'testing' | 'success' | 'fail';
 
+/** One stage of the connection test (dns → tcp → login → query → permissions). */
+export interface ConnectionStage {
+  stage: 'dns' | 'tcp' | 'login' | 'query' | 'permissions';
+  success: boolean;
+  elapsedMs: number;
+}
+
 export interface DatabaseStepProps {
   dbSetupMode: DbSetupMode;
   onDbSetupModeChange: (mode: DbSetupMode) => void;
@@ -22,6 +31,7 @@ export interface DatabaseStepProps {
   newDbPrivTestStatus: TestStatus;
   newDbPrivTestMessage: string;
   onRunCreateNewPrivilegeTest: () => void;
+  newDbGrantScript: string | null;
   newDbLocation: NewDbLocation;
   onNewDbLocationChange: (location: NewDbLocation) => void;
   newDbSpecificPath: string;
@@ -46,7 +56,16 @@ export interface DatabaseStepProps {
   // Postgres options
   newDbPgOwner: string;
   onNewDbPgOwnerChange: (value: string) => void;
+  // SQL Server collation / PostgreSQL locale
+  newDbCollation: string;
+  onNewDbCollationChange: (value: string) => void;
   dbCreateValidationError: string | null;
+  // Dedicated application login (both modes)
+  appAccountEnabled: boolean;
+  onAppAccountEnabledChange: (value: boolean) => void;
+  appLoginName: string;
+  onAppLoginNameChange: (value: string) => void;
+  appAccountValidationError: string | null;
   // Existing database fields
   existingHostedWhere: DbHostedWhere;
   onExistingHostedWhereChange: (where: DbHostedWhere) => void;
@@ -64,15 +83,22 @@ export interface DatabaseStepProps {
   onDbUserChange: (value: string) => void;
   dbPassword: string;
   onDbPasswordChange: (value: string) => void;
+  /** SQL Server only: Integrated Security instead of a SQL login. */
+  dbWindowsAuth: boolean;
+  onDbWindowsAuthChange: (value: boolean) => void;
   dbSslMode: DbSslMode;
   onDbSslModeChange: (mode: DbSslMode) => void;
   dbExistingMissingInputs: string[];
   canRunDbTest: boolean;
   dbTestStatus: TestStatus;
   dbTestMessage: string;
+  dbTestStages: ConnectionStage[];
   onRunDbTest: () => void;
   // Mark port as touched
   onDbPortTouched: () => void;
+  /** Existing database connection by `--tui-script` answer key, for saved connection profiles. */
+  profileValues: Record<string, string>;
+  onLoadProfile: (values: Record<string, string>) => void;
 }
 
 export function DatabaseStep(props: DatabaseStepProps) {
@@ -110,6 +136,38 @@ export function DatabaseStep(props: DatabaseStepProps) {
       {dbSetupMode === 'existing' ? (
         <DatabaseStepExisting {...props} />
       ) : null}
+
+      {dbSetupMode ? <DatabaseStepAppAccount {...props} /> : null}
+    </div>
+  );
+}
+
+function DatabaseStepAppAccount(props: DatabaseStepProps) {
+  const {
+    appAccountEnabled, onAppAccountEnabledChange,
+    appLoginName, onAppLoginNameChange,
+    appAccountValidationError,
+  } = props;
+
+  return (
+    <div>
+      <div className="wizard-row" style={{ marginTop: 12 }}>
+        <label className="wizard-inline">
+          <input type="checkbox" checked={appAccountEnabled} onChange={(e) => onAppAccountEnabledChange(e.target.checked)} />
+          Create a dedicated application login
+        </label>
+        <div className="wizard-help">
+          The installer creates a login that can only read and write the CADalytix tables, and the application connects with it instead of the admin credentials above. Its password is generated and stored encrypted.
+        </div>
+      </div>
+      {appAccountEnabled ? (
+        <div className="wizard-row">
+          <label className="wizard-label">Application login</label>
+          <input className="wizard-input" style={{ width: 240 }} value={appLoginName} onChange={(e) => onAppLoginNameChange(e.target.value)} placeholder="cadalytix_app" />
+          <div className="wizard-help">An existing login with this name gets a new password.</div>
+        </div>
+      ) : null}
+      {appAccountValidationError ? <div className="wizard-error">{appAccountValidationError}</div> : null}
     </div>
   );
 }
@@ -121,7 +179,7 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
     newDbAdminPort, onNewDbAdminPortChange,
     newDbAdminUser, onNewDbAdminUserChange,
     newDbAdminPassword, onNewDbAdminPasswordChange,
-    newDbPrivTestStatus, newDbPrivTestMessage, onRunCreateNewPrivilegeTest,
+    newDbPrivTestStatus, newDbPrivTestMessage, onRunCreateNewPrivilegeTest, newDbGrantScript,
     newDbLocation, onNewDbLocationChange,
     newDbSpecificPath, onNewDbSpecificPathChange, onBrowseForNewDbPath,
     newDbMaxSizeGb, onNewDbMaxSizeGbChange,
@@ -133,6 +191,7 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
     newDbDataFilegrowth, onNewDbDataFilegrowthChange,
     newDbLogFilegrowth, onNewDbLogFilegrowthChange,
     newDbPgOwner, onNewDbPgOwnerChange,
+    newDbCollation, onNewDbCollationChange,
     dbCreateValidationError,
   } = props;
 
@@ -181,6 +240,35 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
           {newDbPrivTestStatus === 'success' ? <span className="wizard-success" style={{ marginLeft: 10 }}>✓ {newDbPrivTestMessage}</span> : null}
           {newDbPrivTestStatus === 'fail' ? <span className="wizard-error" style={{ marginLeft: 10 }}>✗ {newDbPrivTestMessage}</span> : null}
         </div>
+        {newDbGrantScript ? (
+          <div style={{ marginTop: 8 }}>
+            <div className="wizard-help">
+              This login cannot create databases. A DBA can grant only the permission CADalytix needs with this script;
+              run it, then re-test to continue.
+            </div>
+            <textarea
+              className="wizard-textarea"
+              readOnly
+              rows={newDbGrantScript.split('\n').length}
+              style={{ width: '100%', fontFamily: 'monospace' }}
+              value={newDbGrantScript}
+            />
+            <div className="wizard-row">
+              <button className="wizard-button" type="button" onClick={() => void navigator.clipboard.writeText(newDbGrantScript)}>
+                Copy script
+              </button>
+              <button
+                className="wizard-button"
+                type="button"
+                style={{ marginLeft: 8 }}
+                disabled={newDbPrivTestStatus === 'testing'}
+                onClick={onRunCreateNewPrivilegeTest}
+              >
+                Re-test privileges
+              </button>
+            </div>
+          </div>
+        ) : null}
       </div>
 
       <div className="wizard-row" style={{ marginTop: 12 }}>
@@ -208,6 +296,11 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
             <input className="wizard-input" value={newDbSpecificPath} onChange={(e) => onNewDbSpecificPathChange(e.target.value)} />
             <button className="wizard-button" type="button" onClick={onBrowseForNewDbPath}>Browse…</button>
           </div>
+          <div className="wizard-help">
+            {dbEngine === 'postgres'
+              ? 'A tablespace is created in this folder on the PostgreSQL host. This needs a superuser, and the folder must exist, be empty and be owned by the postgres account.'
+              : 'The data and log files are created in this folder on the SQL Server host. The folder must already exist.'}
+          </div>
         </div>
       ) : null}
 
@@ -218,7 +311,11 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
 
       {dbEngine === 'sqlserver' ? (
         <div style={{ marginTop: 12, padding: 10, border: '1px solid #ccc', borderRadius: 4 }}>
-          <div className="wizard-row"><strong>SQL Server Sizing (optional)</strong></div>
+          <div className="wizard-row"><strong>SQL Server Collation and Sizing (optional)</strong></div>
+          <div className="wizard-row">
+            <label className="wizard-label">Collation (blank = server default)</label>
+            <input className="wizard-input" style={{ width: 240 }} value={newDbCollation} onChange={(e) => onNewDbCollationChange(e.target.value)} placeholder="e.g., Latin1_General_CI_AS" />
+          </div>
           <div className="wizard-row">
             <label className="wizard-label">Initial data file size (MB)</label>
             <input className="wizard-input" style={{ width: 120 }} value={newDbInitialDataSizeMb} onChange={(e) => onNewDbInitialDataSizeMbChange(e.target.value)} />
@@ -254,6 +351,10 @@ function DatabaseStepCreateNew(props: DatabaseStepProps) {
             <label className="wizard-label">Owner role (leave blank for current user)</label>
             <input className="wizard-input" style={{ width: 200 }} value={newDbPgOwner} onChange={(e) => onNewDbPgOwnerChange(e.target.value)} placeholder="e.g., cadalytix_admin" />
           </div>
+          <div className="wizard-row">
+            <label className="wizard-label">Locale (blank = server default)</label>
+            <input className="wizard-input" style={{ width: 200 }} value={newDbCollation} onChange={(e) => onNewDbCollationChange(e.target.value)} placeholder="e.g., en_US.UTF-8" />
+          </div>
           <div className="wizard-help">PostgreSQL does not support SQL Server-style sizing. The database grows with available disk space.</div>
         </div>
       ) : null}
@@ -276,13 +377,18 @@ function DatabaseStepExisting(props: DatabaseStepProps) {
     dbName, onDbNameChange,
     dbUser, onDbUserChange,
     dbPassword, onDbPasswordChange,
+    dbWindowsAuth, onDbWindowsAuthChange,
+    dbEngine,
     dbSslMode, onDbSslModeChange,
     dbExistingMissingInputs,
-    canRunDbTest, dbTestStatus, dbTestMessage, onRunDbTest,
+    canRunDbTest, dbTestStatus, dbTestMessage, dbTestStages, onRunDbTest,
+    profileValues, onLoadProfile,
   } = props;
 
   return (
     <div style={{ marginTop: 12 }}>
+      <ConnectionProfiles kind="database" values={profileValues} onLoad={onLoadProfile} />
+
       <div className="wizard-row">
         <strong>Where is the existing database hosted? (No login required)</strong>
       </div>
@@ -351,14 +457,29 @@ function DatabaseStepExisting(props: DatabaseStepProps) {
             <label className="wizard-label">Database</label>
             <input className="wizard-input" value={dbName} onChange={(e) => onDbNameChange(e.target.value)} />
           </div>
-          <div className="wizard-row">
-            <label className="wizard-label">Username</label>
-            <input className="wizard-input" value={dbUser} onChange={(e) => onDbUserChange(e.target.value)} />
-          </div>
-          <div className="wizard-row">
-            <label className="wizard-label">Password</label>
-            <input className="wizard-input" type="password" value={dbPassword} onChange={(e) => onDbPasswordChange(e.target.value)} />
-          </div>
+          {dbEngine === 'sqlserver' ? (
+            <div className="wizard-row">
+              <label className="wizard-inline">
+                <input type="checkbox" checked={dbWindowsAuth} onChange={(e) => onDbWindowsAuthChange(e.target.checked)} />
+                Use Windows Authentication
+              </label>
+              <div className="wizard-help">
+                Connects as the installer's Windows account (Kerberos ticket on Linux) instead of a SQL login.
+              </div>
+            </div>
+          ) : null}
+          {!dbWindowsAuth ? (
+            <>
+              <div className="wizard-row">
+                <label className="wizard-label">Username</label>
+                <input className="wizard-input" value={dbUser} onChange={(e) => onDbUserChange(e.target.value)} />
+              </div>
+              <div className="wizard-row">
+                <label className="wizard-label">Password</label>
+                <input className="wizard-input" type="password" value={dbPassword} onChange={(e) => onDbPasswordChange(e.target.value)} />
+              </div>
+            </>
+          ) : null}
           <div className="wizard-row">
             <label className="wizard-label">TLS</label>
             <select className="wizard-select" value={dbSslMode} onChange={(e) => onDbSslModeChange(e.target.value as DbSslMode)}>
@@ -384,6 +505,13 @@ function DatabaseStepExisting(props: DatabaseStepProps) {
 
       {dbTestStatus === 'success' ? <div className="wizard-help">{dbTestMessage || 'Connection successful.'}</div> : null}
       {dbTestStatus === 'fail' ? <div className="wizard-error">{dbTestMessage || 'Connection failed.'}</div> : null}
+      {dbTestStatus !== 'testing' && dbTestStages.length ? (
+        <div className="wizard-help">
+          {dbTestStages
+            .map((s) => (s.success ? `${s.stage} ${s.elapsedMs} ms` : `${s.stage} failed after ${s.elapsedMs} ms`))
+            .join(' → ')}
+        </div>
+      ) : null}
     </div>
   );
 }
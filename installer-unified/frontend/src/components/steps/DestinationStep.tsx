@@ -1,8 +1,43 @@
+import type { RemoteTarget } from '../../lib/api';
+import RemoteTargetPanel from '../RemoteTargetPanel';
+
+export type LayoutRole = 'binaries' | 'data' | 'logs' | 'temp';
+
+/** Folder per layout role; an empty path keeps the default. */
+export type InstallLayoutPaths = Record<LayoutRole, string>;
+
+export interface LayoutFreeSpace {
+  /** Null when the volume could not be measured. */
+  freeBytes: number | null;
+  requiredBytes: number;
+}
+
+export const LAYOUT_ROLES: { role: LayoutRole; label: string }[] = [
+  { role: 'binaries', label: 'Binaries' },
+  { role: 'data', label: 'Data' },
+  { role: 'logs', label: 'Logs' },
+  { role: 'temp', label: 'Temp' },
+];
+
 export interface DestinationStepProps {
   destinationFolder: string;
   onDestinationChange: (path: string) => void;
   destinationError: string | null;
   onBrowseForFolder: () => void;
+  layoutAdvanced: boolean;
+  onLayoutAdvancedChange: (v: boolean) => void;
+  layout: InstallLayoutPaths;
+  layoutDefaults: InstallLayoutPaths;
+  onLayoutChange: (role: LayoutRole, path: string) => void;
+  /** Per role; missing while the check runs. */
+  layoutFreeSpace: Partial<Record<LayoutRole, LayoutFreeSpace>>;
+  /** Install on this server over SSH; null installs on this machine. */
+  remote: RemoteTarget | null;
+  onRemoteChange: (target: RemoteTarget | null) => void;
+}
+
+function gb(bytes: number): string {
+  return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} GB`;
 }
 
 export function DestinationStep({
@@ -10,9 +45,18 @@ export function DestinationStep({
   onDestinationChange,
   destinationError,
   onBrowseForFolder,
+  layoutAdvanced,
+  onLayoutAdvancedChange,
+  layout,
+  layoutDefaults,
+  onLayoutChange,
+  layoutFreeSpace,
+  remote,
+  onRemoteChange,
 }: DestinationStepProps) {
   return (
     <div>
+      <RemoteTargetPanel target={remote} onChange={onRemoteChange} />
       <div className="wizard-row">
         <label className="wizard-label">Install path</label>
         <div className="wizard-inline">
@@ -21,14 +65,56 @@ export function DestinationStep({
             value={destinationFolder}
             onChange={(e) => onDestinationChange(e.target.value)}
           />
-          <button className="wizard-button" onClick={onBrowseForFolder}>
-            Browse…
-          </button>
+          {remote ? null : (
+            <button className="wizard-button" onClick={onBrowseForFolder}>
+              Browse…
+            </button>
+          )}
+        </div>
+        <div className="wizard-help">
+          {remote ? 'A path on the remote server. ' : ''}Required space is calculated on the Ready page (runtime files, new database, archive headroom).
         </div>
-        <div className="wizard-help">Required space: ~2–5 GB</div>
         {destinationError ? <div className="wizard-error">{destinationError}</div> : null}
       </div>
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input
+            type="checkbox"
+            checked={layoutAdvanced}
+            onChange={(e) => onLayoutAdvancedChange(e.target.checked)}
+          />
+          Advanced layout — separate folders for binaries, data, logs and temp files
+        </label>
+      </div>
+      {layoutAdvanced ? (
+        <div className="wizard-row">
+          {LAYOUT_ROLES.map(({ role, label }) => {
+            const space = layoutFreeSpace[role];
+            const short = !!space && space.freeBytes !== null && space.freeBytes < space.requiredBytes;
+            return (
+              <div key={role} style={{ marginBottom: 8 }}>
+                <label className="wizard-label">{label} folder</label>
+                <input
+                  className="wizard-input"
+                  value={layout[role]}
+                  placeholder={layoutDefaults[role]}
+                  onChange={(e) => onLayoutChange(role, e.target.value)}
+                />
+                <div className={short ? 'wizard-error' : 'wizard-help'}>
+                  {!space
+                    ? 'Checking free space…'
+                    : space.freeBytes === null
+                      ? 'Free space unknown'
+                      : short
+                        ? `Low free space: ${gb(space.freeBytes)} free, ${gb(space.requiredBytes)} recommended`
+                        : `${gb(space.freeBytes)} free`}
+                </div>
+              </div>
+            );
+          })}
+          <div className="wizard-help">Leave a folder empty to keep the default shown.</div>
+        </div>
+      ) : null}
     </div>
   );
 }
-
@@ -1,3 +1,5 @@
+import type { PreflightDependencyCheckDto } from '../../lib/api';
+
 export type InstallationType = 'typical' | 'custom' | 'import';
 
 export interface InstallTypeStepProps {
@@ -8,6 +10,10 @@ export interface InstallTypeStepProps {
   importConfigError: string | null;
   onImportConfigErrorClear: () => void;
   onBrowseForFile: () => void;
+  dependencyChecks: PreflightDependencyCheckDto[] | null;
+  dependencyChecking: boolean;
+  dependencyError: string | null;
+  onRecheckDependencies: () => void;
 }
 
 export function InstallTypeStep({
@@ -18,7 +24,12 @@ export function InstallTypeStep({
   importConfigError,
   onImportConfigErrorClear,
   onBrowseForFile,
+  dependencyChecks,
+  dependencyChecking,
+  dependencyError,
+  onRecheckDependencies,
 }: InstallTypeStepProps) {
+  const problems = (dependencyChecks ?? []).filter((c) => c.status !== 'Pass');
   return (
     <div>
       <div className="wizard-row">
@@ -65,6 +76,30 @@ export function InstallTypeStep({
           </div>
         </div>
       ) : null}
+
+      <div style={{ marginTop: 18 }}>
+        <div className="wizard-inline">
+          <label className="wizard-label">Required software</label>
+          <button className="wizard-button" onClick={onRecheckDependencies} disabled={dependencyChecking}>
+            Check again
+          </button>
+        </div>
+        {dependencyChecking ? <div className="wizard-help">Checking required software…</div> : null}
+        {dependencyError ? (
+          <div className="wizard-error">Required software could not be checked: {dependencyError}</div>
+        ) : null}
+        {!dependencyChecking && dependencyChecks && problems.length === 0 ? (
+          <div className="wizard-success">All required software was found.</div>
+        ) : null}
+        {!dependencyChecking
+          ? problems.map((c) => (
+              <div key={c.name} className={c.status === 'Fail' ? 'wizard-error' : 'wizard-warning'}>
+                <strong>{c.name}:</strong> {c.detail}
+                {c.remediation ? <div className="wizard-help">{c.remediation}</div> : null}
+              </div>
+            ))
+          : null}
+      </div>
     </div>
   );
 }
@@ -4,9 +4,17 @@ export interface InstallingStepProps {
   progress: ProgressEvent | null;
   installDetailLines: string[];
   installError: string | null;
+  paused: boolean;
+  onTogglePause: () => void;
 }
 
-export function InstallingStep({ progress, installDetailLines, installError }: InstallingStepProps) {
+export function InstallingStep({
+  progress,
+  installDetailLines,
+  installError,
+  paused,
+  onTogglePause,
+}: InstallingStepProps) {
   const elapsedMs = progress?.elapsedMs;
   const etaMs = progress?.etaMs;
   const fmt = (ms?: number) => {
@@ -26,6 +34,16 @@ export function InstallingStep({ progress, installDetailLines, installError }: I
         {elapsedMs ? <div className="wizard-help">Elapsed: {fmt(elapsedMs)}</div> : null}
         {etaMs ? <div className="wizard-help">Estimated remaining: {fmt(etaMs)}</div> : null}
       </div>
+      {!installError ? (
+        <div className="wizard-row">
+          <button className="wizard-button" type="button" onClick={onTogglePause}>
+            {paused ? 'Resume' : 'Pause'}
+          </button>
+          {paused ? (
+            <div className="wizard-help">Paused at a safe point. Progress is saved; resume or cancel when ready.</div>
+          ) : null}
+        </div>
+      ) : null}
       {installDetailLines.length > 0 ? (
         <div className="install-detail-log" aria-label="Installation details">
           {installDetailLines.map((l, idx) => (
@@ -1,7 +1,14 @@
+import type { FieldTransform } from '../TransformsModal';
+import type { MappingSuggestion } from '../../lib/api';
+
 export interface SourceField {
   id: string;
   rawName: string;
   displayName: string;
+  /** SQL type from the source scan (used by auto-map). */
+  dataType?: string;
+  /** Values from the preflight sample query (preview strip). */
+  sampleValues?: string[];
 }
 
 export interface TargetField {
@@ -37,6 +44,17 @@ export interface MappingStepProps {
   requiredTargetsUnmapped: TargetField[];
   onAttemptMap: (sourceId: string, targetId: string) => void;
   onUnassignSelected: () => void;
+  transforms: Record<string, FieldTransform[]>;
+  onEditTransforms: () => void;
+  /** Auto-map suggestions not confirmed yet, by target id. */
+  pendingSuggestions: Record<string, MappingSuggestion>;
+  autoMapRunning: boolean;
+  onAutoMap: () => void;
+  /** Confirm the suggestion for one target, or all of them (null). */
+  onConfirmSuggestions: (targetId: string | null) => void;
+  /** Write / read the CSV review sheet (target_id, source_column per row). */
+  onExportSheet: () => void;
+  onImportSheet: () => void;
 }
 
 export function MappingStep({
@@ -66,7 +84,16 @@ export function MappingStep({
   requiredTargetsUnmapped,
   onAttemptMap,
   onUnassignSelected,
+  transforms,
+  onEditTransforms,
+  pendingSuggestions,
+  autoMapRunning,
+  onAutoMap,
+  onConfirmSuggestions,
+  onExportSheet,
+  onImportSheet,
 }: MappingStepProps) {
+  const pendingCount = Object.keys(pendingSuggestions).length;
   return (
     <div>
       <div className="wizard-row">
@@ -86,6 +113,25 @@ export function MappingStep({
         </label>
       </div>
       {mappingScanError ? <div className="wizard-error">{mappingScanError}</div> : null}
+      <div className="wizard-row wizard-inline">
+        <button className="wizard-button" disabled={autoMapRunning || sourceFields.length === 0} onClick={onAutoMap}>
+          {autoMapRunning ? 'Auto-mapping…' : 'Auto-map'}
+        </button>
+        <button className="wizard-button" disabled={sourceFields.length === 0} onClick={onExportSheet}>
+          Export CSV…
+        </button>
+        <button className="wizard-button" disabled={sourceFields.length === 0} onClick={onImportSheet}>
+          Import CSV…
+        </button>
+        {pendingCount > 0 ? (
+          <>
+            <span className="wizard-help">{pendingCount} suggested mapping(s) need confirming.</span>
+            <button className="wizard-button" onClick={() => onConfirmSuggestions(null)}>
+              Confirm all
+            </button>
+          </>
+        ) : null}
+      </div>
 
       <div className="mapping-layout" style={{ marginTop: 10 }}>
         <div className="mapping-pane">
@@ -131,6 +177,7 @@ export function MappingStep({
           <div className="mapping-list" role="listbox" aria-label="Target fields">
             {filteredTargetFields.map((t) => {
               const mappedSource = targetToSource[t.id];
+              const suggestion = pendingSuggestions[t.id];
               const isSelected = selectedTargetId === t.id;
               const isMapped = !!mappedSource;
               const highlight =
@@ -142,6 +189,7 @@ export function MappingStep({
                     'mapping-row',
                     isSelected || highlight ? 'selected' : '',
                     isMapped ? 'mapped' : '',
+                    suggestion ? 'suggested' : '',
                   ].join(' ')}
                   onClick={() => {
                     onSelectedTargetIdChange(t.id);
@@ -152,7 +200,11 @@ export function MappingStep({
                 >
                   {t.name}
                   {t.required ? ' *' : ''}
-                  {mappedSource ? ` — mapped to ${sourceFields.find((s) => s.id === mappedSource)?.displayName ?? mappedSource}` : ''}
+                  {mappedSource
+                    ? ` — ${suggestion ? 'suggested' : 'mapped to'} ${sourceFields.find((s) => s.id === mappedSource)?.displayName ?? mappedSource}`
+                    : ''}
+                  {suggestion ? ` (${Math.round(suggestion.confidence * 100)}%)` : ''}
+                  {(transforms[t.id] ?? []).length > 0 ? ` [${transforms[t.id].length} transform(s)]` : ''}
                 </div>
               );
             })}
@@ -166,6 +218,18 @@ export function MappingStep({
         </div>
         <div style={{ marginTop: 8 }}>
           <div>Source: [{selectedSource?.displayName ?? ''}]</div>
+          {selectedSource ? (
+            <div className="mapping-samples">
+              Sample:{' '}
+              {(selectedSource.sampleValues ?? []).length === 0
+                ? '(no sample rows)'
+                : (selectedSource.sampleValues ?? []).map((v, i) => (
+                    <span key={i} className={v === 'NULL' ? 'mapping-sample null' : 'mapping-sample'}>
+                      {v}
+                    </span>
+                  ))}
+            </div>
+          ) : null}
           <div>↓</div>
           <div>
             Target(s): [{selectedTargetsForSource.map((id) => targetFields.find((t) => t.id === id)?.name ?? id).join(', ')}]
@@ -175,6 +239,14 @@ export function MappingStep({
           <button className="wizard-button" disabled={!selectedSourceId || !selectedTargetId} onClick={onUnassignSelected}>
             Unassign
           </button>
+          <button className="wizard-button" disabled={!selectedTargetId} onClick={onEditTransforms}>
+            Transforms…
+          </button>
+          {selectedTargetId && pendingSuggestions[selectedTargetId] ? (
+            <button className="wizard-button" onClick={() => onConfirmSuggestions(selectedTargetId)}>
+              Confirm suggestion
+            </button>
+          ) : null}
           <div className="wizard-help">
             Mapped: {mappedCount} / Target fields: {targetFields.length} — Unassigned source fields: {sourceFields.filter((s) => (sourceToTargets[s.id] ?? []).length === 0).length}
           </div>
@@ -0,0 +1,93 @@
+export interface NetworkStepProps {
+  proxyHost: string;
+  onProxyHostChange: (value: string) => void;
+  proxyPort: string;
+  onProxyPortChange: (value: string) => void;
+  proxyUsername: string;
+  onProxyUsernameChange: (value: string) => void;
+  proxyPassword: string;
+  onProxyPasswordChange: (value: string) => void;
+  proxyHasSavedPassword: boolean;
+  bypassSystemProxy: boolean;
+  onBypassSystemProxyChange: (value: boolean) => void;
+  envProxy: string | null;
+  proxyError: string | null;
+}
+
+export function NetworkStep({
+  proxyHost,
+  onProxyHostChange,
+  proxyPort,
+  onProxyPortChange,
+  proxyUsername,
+  onProxyUsernameChange,
+  proxyPassword,
+  onProxyPasswordChange,
+  proxyHasSavedPassword,
+  bypassSystemProxy,
+  onBypassSystemProxyChange,
+  envProxy,
+  proxyError,
+}: NetworkStepProps) {
+  const explicit = proxyHost.trim().length > 0;
+  return (
+    <div>
+      <div className="wizard-help">
+        Optional. Used for license activation, support-bundle upload and (if you consent) install telemetry.
+        Leave the host empty to use the system proxy{envProxy ? ` (${envProxy})` : ''}.
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-label">Proxy host</label>
+        <div className="wizard-inline">
+          <input
+            className="wizard-input"
+            value={proxyHost}
+            placeholder="proxy.example.local"
+            onChange={(e) => onProxyHostChange(e.target.value)}
+          />
+          <input
+            className="wizard-input"
+            value={proxyPort}
+            placeholder="3128"
+            inputMode="numeric"
+            disabled={!explicit}
+            onChange={(e) => onProxyPortChange(e.target.value)}
+          />
+        </div>
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-label">Username (optional)</label>
+        <input
+          className="wizard-input"
+          value={proxyUsername}
+          disabled={!explicit}
+          onChange={(e) => onProxyUsernameChange(e.target.value)}
+        />
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-label">Password</label>
+        <input
+          className="wizard-input"
+          type="password"
+          value={proxyPassword}
+          disabled={!explicit || !proxyUsername.trim()}
+          placeholder={proxyHasSavedPassword ? 'Saved (leave empty to keep)' : ''}
+          onChange={(e) => onProxyPasswordChange(e.target.value)}
+        />
+        <div className="wizard-help">The password is stored encrypted on this machine.</div>
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input
+            type="checkbox"
+            checked={bypassSystemProxy}
+            disabled={explicit}
+            onChange={(e) => onBypassSystemProxyChange(e.target.checked)}
+          />
+          Connect directly (ignore system proxy settings)
+        </label>
+      </div>
+      {proxyError ? <div className="wizard-error">{proxyError}</div> : null}
+    </div>
+  );
+}
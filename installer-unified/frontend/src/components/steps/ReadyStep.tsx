@@ -1,4 +1,5 @@
 import type { InstallMode } from '../../types';
+import type { BackfillEstimateDto, SpaceProjectionDto } from '../../lib/api';
 import type { StorageMode, RetentionPolicy } from './StorageStep';
 import type { ArchiveFormat } from './ArchiveStep';
 import type { DbSetupMode, DbHostedWhere, NewDbLocation } from './DatabaseStep';
@@ -21,9 +22,41 @@ export interface ReadyStepProps {
   archiveScheduleDayOfMonth: string;
   archiveScheduleTimeLocal: string;
   archiveCatchUpOnStartup: boolean;
+  archiveEncrypt: boolean;
   consentToSync: boolean;
   mappedCount: number;
   requiredTargetsUnmappedLength: number;
+  /** False for export folder sources, which cannot be counted up front. */
+  backfillAvailable: boolean;
+  backfillEstimating: boolean;
+  backfillEstimate: BackfillEstimateDto | null;
+  backfillEstimateError: string | null;
+  diskSpaceChecking: boolean;
+  diskSpace: SpaceProjectionDto | null;
+  diskSpaceError: string | null;
+  onRecheckDiskSpace: () => void;
+  dryRun: boolean;
+  onDryRunChange: (v: boolean) => void;
+}
+
+/** Bytes as "12.3 GB" (decimal units, as the installer logs them). */
+function formatBytes(bytes: number): string {
+  const units = ['B', 'KB', 'MB', 'GB', 'TB'];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1000 && unit < units.length - 1) {
+    value /= 1000;
+    unit += 1;
+  }
+  return unit === 0 ? `${bytes} B` : `${value.toFixed(1)} ${units[unit]}`;
+}
+
+/** Seconds as "2 h 05 min" / "4 min". */
+function formatDuration(secs: number): string {
+  const minutes = Math.ceil(secs / 60);
+  const h = Math.floor(minutes / 60);
+  const m = minutes % 60;
+  return h === 0 ? `${Math.max(m, 1)} min` : `${h} h ${String(m).padStart(2, '0')} min`;
 }
 
 export function ReadyStep({
@@ -44,9 +77,20 @@ export function ReadyStep({
   archiveScheduleDayOfMonth,
   archiveScheduleTimeLocal,
   archiveCatchUpOnStartup,
+  archiveEncrypt,
   consentToSync,
   mappedCount,
   requiredTargetsUnmappedLength,
+  backfillAvailable,
+  backfillEstimating,
+  backfillEstimate,
+  backfillEstimateError,
+  diskSpaceChecking,
+  diskSpace,
+  diskSpaceError,
+  onRecheckDiskSpace,
+  dryRun,
+  onDryRunChange,
 }: ReadyStepProps) {
   const hostedWhereLabel = () => {
     switch (existingHostedWhere) {
@@ -79,12 +123,73 @@ export function ReadyStep({
           <div><strong>Storage policy:</strong> {storageMode === 'defaults' ? 'Defaults' : 'Custom'} — {retentionPolicy === '18' ? 'Rolling 18 months' : retentionPolicy === '12' ? 'Rolling 12 months' : retentionPolicy === 'max' ? `Max disk ${maxDiskGb} GB` : 'Keep everything'}</div>
           <div><strong>Hot retention:</strong> {hotRetentionMonths} months</div>
           <div>
-            <strong>Archive policy:</strong> {archiveFormat === 'zip+ndjson' ? 'ZIP + NDJSON' : 'ZIP + CSV'} — {archiveDestinationPath || '(not set)'} — Cap {archiveMaxUsageGb} GB — Day {archiveScheduleDayOfMonth} at {archiveScheduleTimeLocal} — Catch-up {archiveCatchUpOnStartup ? 'Yes' : 'No'}
+            <strong>Archive policy:</strong> {archiveFormat === 'zip+ndjson' ? 'ZIP + NDJSON' : 'ZIP + CSV'} — {archiveDestinationPath || '(not set)'} — Cap {archiveMaxUsageGb} GB — Day {archiveScheduleDayOfMonth} at {archiveScheduleTimeLocal} — Catch-up {archiveCatchUpOnStartup ? 'Yes' : 'No'} — Encrypted {archiveEncrypt ? 'Yes' : 'No'}
           </div>
           <div><strong>Consent to Sync:</strong> {consentToSync ? 'Yes' : 'No'}</div>
           <div><strong>Mapping:</strong> {mappedCount} mapped — required mapped: {requiredTargetsUnmappedLength === 0 ? 'Yes' : 'No'}</div>
         </div>
       </div>
+      <div className="wizard-row">
+        <div style={{ border: '1px solid #bcbcbc', background: '#f8f8f8', padding: 12 }}>
+          <div><strong>Historical backfill (estimate)</strong></div>
+          {!backfillAvailable ? (
+            <div className="wizard-help">Not available for export folder sources.</div>
+          ) : backfillEstimating ? (
+            <div className="wizard-help">Reading row counts from the source…</div>
+          ) : backfillEstimateError ? (
+            <div className="wizard-error">{backfillEstimateError}</div>
+          ) : backfillEstimate ? (
+            <>
+              <div>
+                <strong>Rows:</strong> {backfillEstimate.totalRows.toLocaleString()} over {backfillEstimate.months.length} months — {backfillEstimate.hotRows.toLocaleString()} hot, {backfillEstimate.archiveRows.toLocaleString()} to archive
+              </div>
+              <div>
+                <strong>Projected hot DB size:</strong> {formatBytes(backfillEstimate.projectedHotDbBytes)}
+                {backfillEstimate.avgRowBytesEstimated ? ' (row size estimated)' : ''}
+              </div>
+              <div><strong>Projected archive volume:</strong> {formatBytes(backfillEstimate.projectedArchiveBytes)}</div>
+              <div><strong>Estimated backfill time:</strong> {formatDuration(backfillEstimate.estimatedDurationSecs)}</div>
+            </>
+          ) : null}
+        </div>
+      </div>
+      <div className="wizard-row">
+        <div style={{ border: '1px solid #bcbcbc', background: '#f8f8f8', padding: 12 }}>
+          <div><strong>Disk space</strong></div>
+          {diskSpaceChecking ? (
+            <div className="wizard-help">Checking free space…</div>
+          ) : diskSpaceError ? (
+            <div className="wizard-error">{diskSpaceError}</div>
+          ) : diskSpace ? (
+            <>
+              {diskSpace.volumes.map((v) => (
+                <div key={v.path}>
+                  <strong>{v.path}:</strong> {formatBytes(v.requiredBytes)} needed (
+                  {v.needs.map((n) => `${n.label} ${formatBytes(n.bytes)}`).join(', ')}) —{' '}
+                  {v.freeBytes === null ? 'free space unknown' : `${formatBytes(v.freeBytes)} free`}
+                </div>
+              ))}
+              {diskSpace.payloadEstimated && (
+                <div className="wizard-help">Runtime file size is estimated (payload not found).</div>
+              )}
+              {diskSpace.shortfall && (
+                <>
+                  <div className="wizard-error">{diskSpace.shortfall}</div>
+                  <button className="wizard-button" type="button" onClick={onRecheckDiskSpace}>
+                    Check again
+                  </button>
+                </>
+              )}
+            </>
+          ) : null}
+        </div>
+      </div>
+      <div className="wizard-row">
+        <label className="wizard-inline">
+          <input type="checkbox" checked={dryRun} onChange={(e) => onDryRunChange(e.target.checked)} />
+          Plan only (dry run) — run all checks and write an install plan to the log folder without changing anything
+        </label>
+      </div>
       <div className="wizard-help">Passwords are not shown.</div>
     </div>
   );
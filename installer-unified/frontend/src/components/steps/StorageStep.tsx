@@ -1,3 +1,5 @@
+import type { StorageFilesystemDto } from '../../lib/api';
+
 export type StorageMode = 'defaults' | 'custom';
 export type StorageLocation = 'system' | 'attached' | 'custom';
 export type RetentionPolicy = '18' | '12' | 'max' | 'keep';
@@ -13,6 +15,8 @@ export interface StorageStepProps {
   onRetentionPolicyChange: (policy: RetentionPolicy) => void;
   maxDiskGb: string;
   onMaxDiskGbChange: (value: string) => void;
+  /** Filesystem under the database folder; null while it is being detected. */
+  storageFilesystem: StorageFilesystemDto | null;
 }
 
 export function StorageStep({
@@ -26,6 +30,7 @@ export function StorageStep({
   onRetentionPolicyChange,
   maxDiskGb,
   onMaxDiskGbChange,
+  storageFilesystem,
 }: StorageStepProps) {
   return (
     <div>
@@ -104,6 +109,21 @@ export function StorageStep({
           </div>
         </div>
       ) : null}
+
+      {storageFilesystem?.fsType ? (
+        <div className="wizard-row" style={{ marginTop: 10 }}>
+          <div className="wizard-help">
+            Filesystem of {storageFilesystem.path}: {storageFilesystem.fsType}
+            {storageFilesystem.clusterBytes ? ` (${Math.round(storageFilesystem.clusterBytes / 1024)} KB allocation unit)` : ''}
+          </div>
+          {storageFilesystem.advice.map((line) => (
+            <div key={line} className="wizard-help">
+              {line}
+            </div>
+          ))}
+          {storageFilesystem.blocked ? <div className="wizard-error">{storageFilesystem.blocked}</div> : null}
+        </div>
+      ) : null}
     </div>
   );
 }
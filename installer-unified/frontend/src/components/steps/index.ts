@@ -7,11 +7,19 @@ export type { WelcomeStepProps } from './WelcomeStep';
 export { LicenseStep } from './LicenseStep';
 export type { LicenseStepProps } from './LicenseStep';
 
+export { NetworkStep } from './NetworkStep';
+export type { NetworkStepProps } from './NetworkStep';
+
 export { InstallTypeStep } from './InstallTypeStep';
 export type { InstallTypeStepProps, InstallationType } from './InstallTypeStep';
 
-export { DestinationStep } from './DestinationStep';
-export type { DestinationStepProps } from './DestinationStep';
+export { DestinationStep, LAYOUT_ROLES } from './DestinationStep';
+export type {
+  DestinationStepProps,
+  InstallLayoutPaths,
+  LayoutFreeSpace,
+  LayoutRole,
+} from './DestinationStep';
 
 export { DataSourceStep } from './DataSourceStep';
 export type { DataSourceStepProps, DataSourceKind } from './DataSourceStep';
@@ -25,6 +33,7 @@ export type {
   DbSslMode,
   DbEngine,
   TestStatus,
+  ConnectionStage,
 } from './DatabaseStep';
 
 export { StorageStep } from './StorageStep';
@@ -177,6 +177,7 @@ export interface SetupVerifyRequest {
 export interface SetupVerifyCheckResult {
   id: string;
   label: string;
+  /** 'pass' | 'warn' | 'fail' | 'skip' */
   status: string;
   message: string;
   durationMs: number;
@@ -184,6 +185,8 @@ export interface SetupVerifyCheckResult {
 
 export interface SetupVerifyResponse {
   success: boolean;
+  /** Worst check: 'green' | 'yellow' | 'red'. */
+  health: 'green' | 'yellow' | 'red';
   checks: SetupVerifyCheckResult[];
   errors: string[];
 }
@@ -306,16 +309,147 @@ export async function verifyLicense(request: LicenseVerifyRequest): Promise<ApiR
   return sendRequest<LicenseVerifyResponse>('verify_license', request);
 }
 
+export interface LicenseActivateRequest {
+  licenseKey: string;
+  opsApiBaseUrl?: string | null;
+  proxyUrl?: string | null;
+  proxyUsername?: string | null;
+  proxyPassword?: string | null;
+  bypassSystemProxy?: boolean;
+}
+
+export interface LicenseActivateResponse {
+  success: boolean;
+  message: string;
+  entitlement?: LicenseEntitlementDto | null;
+  offlineFallback: boolean;
+  correlationId: string;
+}
+
+export async function activateLicense(request: LicenseActivateRequest): Promise<ApiResponse<LicenseActivateResponse>> {
+  return sendRequest<LicenseActivateResponse>('activate_license', request);
+}
+
 export interface LicenseStatusResponse {
   isActive: boolean;
   entitlement?: LicenseEntitlementDto | null;
   message: string;
+  graceDaysRemaining?: number | null;
 }
 
 export async function getLicenseStatus(): Promise<ApiResponse<LicenseStatusResponse>> {
   return sendRequest<LicenseStatusResponse>('get_license_status');
 }
 
+export interface RotateSecretsRequest {
+  filesOnly?: boolean;
+}
+
+export interface RotateSecretsResponse {
+  keyStore: string;
+  backupKeyPath: string;
+  databaseValuesRotated: number;
+  filesRotated: string[];
+  pinnedKeys: string[];
+}
+
+export async function rotateSecrets(request: RotateSecretsRequest = {}): Promise<ApiResponse<RotateSecretsResponse>> {
+  return sendRequest<RotateSecretsResponse>('rotate_secrets', request);
+}
+
+export interface ProxySettingsResponse {
+  host: string;
+  port: number;
+  username?: string | null;
+  hasPassword: boolean;
+  bypassSystemProxy: boolean;
+  /** HTTPS_PROXY / HTTP_PROXY (credentials masked), used when no explicit proxy is set. */
+  envProxy?: string | null;
+}
+
+export interface SaveProxySettingsRequest {
+  host: string;
+  port: number;
+  username?: string | null;
+  /** Omit to keep the saved password; empty string clears it. */
+  password?: string | null;
+  bypassSystemProxy: boolean;
+}
+
+export async function getProxySettings(): Promise<ApiResponse<ProxySettingsResponse>> {
+  return sendRequest<ProxySettingsResponse>('get_proxy_settings');
+}
+
+export async function saveProxySettings(request: SaveProxySettingsRequest): Promise<ApiResponse<ProxySettingsResponse>> {
+  return sendRequest<ProxySettingsResponse>('save_proxy_settings', request);
+}
+
+export type ConnectionProfileKind = 'dataSource' | 'database';
+
+/** Saved connection of the Data Source or Database page; values use the `--tui-script` answer keys. */
+export interface ConnectionProfile {
+  name: string;
+  kind: ConnectionProfileKind;
+  values: Record<string, string>;
+  savedAt: string;
+}
+
+export async function listConnectionProfiles(kind: ConnectionProfileKind): Promise<ApiResponse<ConnectionProfile[]>> {
+  return sendRequest<ConnectionProfile[]>('list_connection_profiles', { kind });
+}
+
+export async function saveConnectionProfile(
+  kind: ConnectionProfileKind,
+  name: string,
+  values: Record<string, string>,
+): Promise<ApiResponse<ConnectionProfile[]>> {
+  return sendRequest<ConnectionProfile[]>('save_connection_profile', { kind, name, values });
+}
+
+export async function deleteConnectionProfile(kind: ConnectionProfileKind, name: string): Promise<ApiResponse<ConnectionProfile[]>> {
+  return sendRequest<ConnectionProfile[]>('delete_connection_profile', { kind, name });
+}
+
+/** Server of a remote install over SSH (installation::remote::RemoteTarget). */
+export interface RemoteTarget {
+  host: string;
+  /** Null means 22. */
+  port: number | null;
+  user: string;
+  /** Private key file; empty uses the ssh agent. */
+  identityFile: string;
+  /** Confirmed host key, `SHA256:...`. */
+  hostKeyFingerprint: string;
+}
+
+export interface RemoteHostKey {
+  keyType: string;
+  fingerprint: string;
+}
+
+export interface RemoteCheck {
+  hostKeys: RemoteHostKey[];
+  /** The confirmed fingerprint is among the offered keys. */
+  trusted: boolean;
+  /** Logged in, Linux, root or passwordless sudo. */
+  ready: boolean;
+  os: string | null;
+  message: string;
+}
+
+export async function checkRemoteTarget(target: RemoteTarget): Promise<ApiResponse<RemoteCheck>> {
+  return sendRequest<RemoteCheck>('check_remote_target', target);
+}
+
+/** License feature flags the wizard gates on (see licensing::features). */
+export const LICENSE_FEATURE_ARCHIVE = 'archive';
+export const LICENSE_FEATURE_MULTI_DATASOURCE = 'multi_datasource';
+
+/** Shown in place of an unlicensed page or option. */
+export function notIncludedInLicenseMessage(what: string): string {
+  return `${what} is not included in your license. Contact CADalytix support to add it.`;
+}
+
 // ============================================================================
 // Preflight API Endpoints
 // ============================================================================
@@ -328,6 +462,28 @@ export interface PreflightCheckDto {
 
 export interface PreflightHostRequestDto {
   strictMode: boolean;
+  /** Expected ingest for the sizing checks. */
+  expectedRowsPerDay?: number | null;
+  /** Rows the backfill will load (the backfill estimate's total). */
+  historicalRows?: number | null;
+  /** Data folder whose disk is checked. */
+  dataDir?: string | null;
+}
+
+export interface HostSizingDto {
+  tier: string;
+  rowsPerDay: number;
+  historicalRows: number;
+  recommendedCpuCores: number;
+  recommendedRamGb: number;
+  cpuCores?: number | null;
+  totalRamBytes?: number | null;
+  /** NUMA nodes (Linux) or processor sockets (Windows). */
+  numaNodes?: number | null;
+  /** 'SSD' | 'HDD' for the data folder's disk. */
+  dataDisk?: string | null;
+  underProvisioned: boolean;
+  shortfalls: string[];
 }
 
 export interface PreflightHostResponseDto {
@@ -340,6 +496,7 @@ export interface PreflightHostResponseDto {
   isContainer: boolean;
   checks: PreflightCheckDto[];
   overallStatus: string;
+  sizing?: HostSizingDto | null;
 }
 
 export async function preflightHost(request: PreflightHostRequestDto): Promise<ApiResponse<PreflightHostResponseDto>> {
@@ -371,6 +528,8 @@ export interface DiscoveredColumnDto {
   name: string;
   dataType: string;
   isNullable: boolean;
+  /** Values from the preflight sample query ('NULL' for nulls). */
+  sampleValues?: string[];
 }
 
 export interface SampleStatsDto {
@@ -379,6 +538,13 @@ export interface SampleStatsDto {
   maxCallReceivedAt?: string | null;
 }
 
+/** Call data read from a folder of CSV/TSV exports instead of SQL Server. */
+export interface FileSourceConfig {
+  folder: string;
+  /** auto | comma | tab | semicolon | pipe */
+  delimiter: string;
+}
+
 export interface PreflightDataSourceRequestDto {
   callDataConnectionString: string;
   sourceObjectName: string;
@@ -386,6 +552,9 @@ export interface PreflightDataSourceRequestDto {
   dateToIso?: string | null;
   sampleLimit?: number;
   demoMode?: boolean;
+  fileSource?: FileSourceConfig | null;
+  /** 'sqlserver' (default) | 'odbc' | 'oracle': how callDataConnectionString is opened. */
+  callDataDriver?: string;
 }
 
 export interface PreflightDataSourceResponseDto {
@@ -401,6 +570,268 @@ export async function preflightDataSource(
   return sendRequest<PreflightDataSourceResponseDto>('preflight_datasource', request);
 }
 
+/** Free bytes on the volume of `path` (or its nearest existing parent folder). */
+export async function getFreeSpaceBytes(path: string): Promise<ApiResponse<number>> {
+  return sendRequest<number>('get_free_space_bytes', { path });
+}
+
+export interface ListSourceObjectsRequestDto {
+  callDataConnectionString: string;
+  /** 'sqlserver' (default) | 'odbc' | 'oracle', as for preflightDataSource. */
+  callDataDriver?: string;
+  demoMode?: boolean;
+}
+
+/** A table or view of the call data source. */
+export interface SourceObjectDto {
+  /** Schema (SQL Server, ODBC) or owner (Oracle); empty when the source has none. */
+  schema: string;
+  name: string;
+  /** table | view */
+  kind: string;
+  /** Null for views and sources that do not report row counts. */
+  rowCount?: number | null;
+}
+
+export interface ListSourceObjectsResponseDto {
+  objects: SourceObjectDto[];
+  /** More objects exist than were listed. */
+  truncated: boolean;
+}
+
+/** Tables and views of the call data source, for the source object picker. */
+export async function listSourceObjects(
+  request: ListSourceObjectsRequestDto
+): Promise<ApiResponse<ListSourceObjectsResponseDto>> {
+  return sendRequest<ListSourceObjectsResponseDto>('list_source_objects', request);
+}
+
+export interface BackfillEstimateRequestDto {
+  callDataConnectionString: string;
+  callDataDriver?: string;
+  sourceObjectName: string;
+  /** Source column mapped to CallReceivedAt; rows are grouped by its calendar month. */
+  watermarkColumn: string;
+  /** Optional YYYY-MM-DD bounds; the whole history when omitted. */
+  dateFromIso?: string | null;
+  dateToIso?: string | null;
+  hotRetentionMonths: number;
+  demoMode?: boolean;
+}
+
+export interface BackfillMonthDto {
+  /** YYYY-MM */
+  month: string;
+  rows: number;
+  /** Inside the hot retention window (loaded into the hot DB rather than archived). */
+  hot: boolean;
+}
+
+export interface BackfillEstimateDto {
+  months: BackfillMonthDto[];
+  totalRows: number;
+  hotRows: number;
+  archiveRows: number;
+  avgRowBytes: number;
+  /** The source did not report a row size; a typical one was used. */
+  avgRowBytesEstimated: boolean;
+  projectedHotDbBytes: number;
+  projectedArchiveBytes: number;
+  estimatedDurationSecs: number;
+}
+
+/** Rows per month of the call data source and the projected backfill, for the Ready page. */
+export async function estimateBackfill(
+  request: BackfillEstimateRequestDto
+): Promise<ApiResponse<BackfillEstimateDto>> {
+  return sendRequest<BackfillEstimateDto>('estimate_backfill', request);
+}
+
+/** Space one part of the install needs (runtime files, database, archive headroom). */
+export interface SpaceNeedDto {
+  label: string;
+  path: string;
+  bytes: number;
+}
+
+/** Needs that land on one volume and that volume's free space (null when unknown). */
+export interface VolumeProjectionDto {
+  path: string;
+  needs: SpaceNeedDto[];
+  requiredBytes: number;
+  freeBytes: number | null;
+}
+
+export interface SpaceProjectionDto {
+  volumes: VolumeProjectionDto[];
+  /** The runtime payload was not found and a typical size was used. */
+  payloadEstimated: boolean;
+  /** Why Install is blocked; empty when every volume has room. */
+  shortfall: string;
+}
+
+/** Disk space the install (the start_install payload) needs per volume, for the Ready page. */
+export async function projectDiskSpace(installPayload: any): Promise<ApiResponse<SpaceProjectionDto>> {
+  return sendRequest<SpaceProjectionDto>('project_disk_space', installPayload);
+}
+
+export interface StorageFilesystemDto {
+  path: string;
+  /** As reported by the OS (`ext4`, `NTFS`, ...); empty when it could not be detected. */
+  fsType: string;
+  clusterBytes?: number | null;
+  /** Why the database cannot be stored here (FAT32/exFAT, memory-backed). */
+  blocked?: string | null;
+  advice: string[];
+}
+
+/** Filesystem under the database storage folder, with engine-specific guidance, for the Storage page. */
+export async function inspectStorageFilesystem(installPayload: any): Promise<ApiResponse<StorageFilesystemDto>> {
+  return sendRequest<StorageFilesystemDto>('inspect_storage_filesystem', installPayload);
+}
+
+export interface PreflightDependenciesRequestDto {
+  installMode: string;
+}
+
+export interface PreflightDependencyCheckDto {
+  name: string;
+  status: string;
+  detail: string;
+  remediation?: string;
+}
+
+export interface PreflightDependenciesResponseDto {
+  checks: PreflightDependencyCheckDto[];
+  overallStatus: string;
+}
+
+export async function preflightDependencies(
+  request: PreflightDependenciesRequestDto
+): Promise<ApiResponse<PreflightDependenciesResponseDto>> {
+  return sendRequest<PreflightDependenciesResponseDto>('preflight_dependencies', request);
+}
+
+export interface PreflightNetworkRequestDto {
+  dbConnectionString?: string;
+  appPorts?: number[];
+}
+
+export interface PreflightNetworkCheckDto {
+  name: string;
+  status: string;
+  detail: string;
+  remediation?: string;
+}
+
+export interface PreflightNetworkResponseDto {
+  checks: PreflightNetworkCheckDto[];
+  firewall?: string | null;
+  overallStatus: string;
+  /** Database server time minus this host's time, when the server could be queried. */
+  clockSkewMs?: number | null;
+}
+
+export async function preflightNetwork(
+  request: PreflightNetworkRequestDto
+): Promise<ApiResponse<PreflightNetworkResponseDto>> {
+  return sendRequest<PreflightNetworkResponseDto>('preflight_network', request);
+}
+
+// ============================================================================
+// Mapping API Endpoints
+// ============================================================================
+
+export interface AutoMapSourceFieldDto {
+  id: string;
+  rawName: string;
+  dataType?: string;
+}
+
+export interface AutoMapTargetFieldDto {
+  id: string;
+  name: string;
+}
+
+export interface AutoMapRequestDto {
+  sourceFields: AutoMapSourceFieldDto[];
+  targetFields: AutoMapTargetFieldDto[];
+  targetToSource?: Record<string, string>;
+}
+
+export interface MappingSuggestion {
+  sourceId: string;
+  targetId: string;
+  confidence: number;
+  reason: string;
+}
+
+export interface AutoMapResponseDto {
+  suggestions: MappingSuggestion[];
+}
+
+export async function autoMap(request: AutoMapRequestDto): Promise<ApiResponse<AutoMapResponseDto>> {
+  return sendRequest<AutoMapResponseDto>('auto_map', request);
+}
+
+export interface VerifyMappingCompatRequestDto {
+  /** Path of a persisted mapping.json. */
+  path: string;
+  /** Target fields to check against; the built-in target catalog when omitted. */
+  targetFields?: { id: string; name: string; required: boolean }[] | null;
+}
+
+export interface VerifyMappingCompatResponseDto {
+  compatible: boolean;
+  fileSchemaVersion: number;
+  currentSchemaVersion: number;
+  upgraded: boolean;
+  issues: string[];
+  warnings: string[];
+  addedTargets: string[];
+  removedTargets: string[];
+}
+
+export async function verifyMappingCompat(
+  request: VerifyMappingCompatRequestDto
+): Promise<ApiResponse<VerifyMappingCompatResponseDto>> {
+  return sendRequest<VerifyMappingCompatResponseDto>('verify_mapping_compat', request);
+}
+
+export interface MappingStateDto {
+  mappingOverride: boolean;
+  sourceFields: { id: string; rawName: string; displayName: string }[];
+  targetFields: { id: string; name: string; required: boolean }[];
+  sourceToTargets: Record<string, string[]>;
+  targetToSource: Record<string, string>;
+  transforms?: Record<string, unknown[]>;
+}
+
+export interface MappingCsvRequestDto {
+  /** CSV file to write (export) or read (import). */
+  path: string;
+  mappingState: MappingStateDto;
+}
+
+export interface ExportMappingCsvResponseDto {
+  path: string;
+  rows: number;
+}
+
+export interface ImportMappingCsvResponseDto {
+  /** The request's mapping state with the mapping from the sheet. */
+  mappingState: MappingStateDto;
+  mappedCount: number;
+}
+
+export async function exportMappingCsv(request: MappingCsvRequestDto): Promise<ApiResponse<ExportMappingCsvResponseDto>> {
+  return sendRequest<ExportMappingCsvResponseDto>('export_mapping_csv', request);
+}
+
+export async function importMappingCsv(request: MappingCsvRequestDto): Promise<ApiResponse<ImportMappingCsvResponseDto>> {
+  return sendRequest<ImportMappingCsvResponseDto>('import_mapping_csv', request);
+}
+
 // ============================================================================
 // Schema API Endpoints
 // ============================================================================
@@ -408,6 +839,8 @@ export async function preflightDataSource(
 export interface VerifySchemaRequest {
   engine?: string;
   connectionString?: string | null;
+  /** Apply safe additive fixes (missing columns/indexes) before reporting. */
+  repair?: boolean;
 }
 
 export interface VerifySchemaResponse {
@@ -420,6 +853,10 @@ export interface VerifySchemaResponse {
   missingIndexes: string[];
   typeMismatches: string[];
   nullabilityMismatches: string[];
+  repairsApplied: string[];
+  repairErrors: string[];
+  /** Path of Prod_Wizard_Log/schema-diff.json, when it could be written. */
+  reportPath?: string | null;
 }
 
 export async function verifySchema(request: VerifySchemaRequest): Promise<ApiResponse<VerifySchemaResponse>> {
@@ -431,6 +868,7 @@ export interface VerifyAllRequest {
   callDataConnectionString?: string | null;
   sourceObjectName?: string | null;
   engine?: string;
+  repair?: boolean;
 }
 
 export interface VerifyAllResponse {
@@ -0,0 +1,36 @@
+/**
+ * UI strings for the active locale.
+ *
+ * The backend resolves the locale (CADALYTIX_LOCALE, locale.json, LANG) and returns the merged
+ * bundle (English fallback already applied); main.tsx loads it once before the first render.
+ * Placeholders use `{name}`.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+interface LocaleBundle {
+  locale: string;
+  messages: Record<string, string>;
+}
+
+let messages: Record<string, string> = {};
+
+export async function loadLocaleBundle(): Promise<void> {
+  try {
+    const bundle = await invoke<LocaleBundle>('get_locale_bundle');
+    messages = bundle.messages ?? {};
+    document.documentElement.lang = bundle.locale;
+  } catch {
+    // Outside the Tauri shell (plain browser dev server) keys render as-is.
+  }
+}
+
+/** Localized text for `key`, with `{name}` placeholders filled from `vars`. */
+export function t(key: string, vars?: Record<string, string | number>): string {
+  let text = messages[key] ?? key;
+  if (vars) {
+    for (const [name, value] of Object.entries(vars)) {
+      text = text.split(`{${name}}`).join(String(value));
+    }
+  }
+  return text;
+}
@@ -10,6 +10,7 @@ export type WizardPage =
   | 'platform'
   | 'welcome'
   | 'license'
+  | 'network'
   | 'installType'
   | 'destination'
   | 'dataSource'
@@ -57,7 +58,7 @@ export type HotRetentionChoice = '12' | '18' | 'custom';
 export type ArchiveFormat = 'zip+ndjson' | 'zip+csv';
 
 /** Data source kind */
-export type DataSourceKind = 'local' | 'remote';
+export type DataSourceKind = 'local' | 'remote' | 'odbc' | 'oracle' | 'file';
 
 /** New database location */
 export type NewDbLocation = 'thisMachine' | 'specificPath';
@@ -1,3 +1,88 @@
+use std::path::{Path, PathBuf};
+
 fn main() {
+    embed_migrations();
+    require_release_signing_key();
     tauri_build::build()
 }
+
+/// Release builds must carry the release signing public key (`security::crypto` reads it with
+/// `option_env!`); without it no signed offline bundle, payload manifest or license file verifies.
+fn require_release_signing_key() {
+    println!("cargo:rerun-if-env-changed=CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY");
+    let key = std::env::var("CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY").unwrap_or_default();
+    let key = key.trim();
+    // 32 bytes of base64: 43 characters plus one `=` of padding.
+    let well_formed = key.len() == 44
+        && key.strip_suffix('=').is_some_and(|body| {
+            body.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+        });
+    if !key.is_empty() && !well_formed {
+        panic!(
+            "CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY is not a base64 Ed25519 public key (32 bytes)"
+        );
+    }
+    if key.is_empty() && std::env::var("PROFILE").as_deref() == Ok("release") {
+        panic!(
+            "CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY must be set for release builds (base64 Ed25519 public key of the release signing key)"
+        );
+    }
+}
+
+/// Generate `$OUT_DIR/embedded_migrations.rs`. When `CADALYTIX_EMBED_MIGRATIONS_DIR` points at a
+/// migrations folder (the one holding `manifest_versioned.json`), its `.sql`/`.json` files are
+/// compiled into the binary; otherwise the table is empty and migrations come from disk only.
+fn embed_migrations() {
+    println!("cargo:rerun-if-env-changed=CADALYTIX_EMBED_MIGRATIONS_DIR");
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    if let Some(dir) = std::env::var_os("CADALYTIX_EMBED_MIGRATIONS_DIR") {
+        let root = PathBuf::from(dir);
+        if root.join("manifest_versioned.json").is_file() {
+            println!("cargo:rerun-if-changed={}", root.display());
+            collect_migration_files(&root, &root, &mut files);
+        } else {
+            println!(
+                "cargo:warning=CADALYTIX_EMBED_MIGRATIONS_DIR has no manifest_versioned.json: {}",
+                root.display()
+            );
+        }
+    }
+    files.sort();
+
+    let mut src = String::from("pub static EMBEDDED_MIGRATIONS: &[(&str, &[u8])] = &[\n");
+    for (relative, path) in &files {
+        src.push_str(&format!(
+            "    ({:?}, include_bytes!({:?})),\n",
+            relative,
+            path.display().to_string()
+        ));
+    }
+    src.push_str("];\n");
+
+    let out = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"))
+        .join("embedded_migrations.rs");
+    std::fs::write(&out, src).expect("failed to write embedded_migrations.rs");
+}
+
+fn collect_migration_files(root: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_migration_files(root, &path, out);
+            continue;
+        }
+        let wanted = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("sql") || e.eq_ignore_ascii_case("json"));
+        if let (true, Ok(relative)) = (wanted, path.strip_prefix(root)) {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let absolute = path.canonicalize().unwrap_or_else(|_| path.clone());
+            out.push((relative, absolute));
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Historical backfill estimate for the Ready page.
+//!
+//! Before the operator commits to an install, the source is asked how many rows each calendar
+//! month holds (grouped on the watermark column, the one mapped to `CallReceivedAt`). Months
+//! inside the hot retention window are projected into the hot DB at the source's average row
+//! size (SQL Server partition stats; a fallback elsewhere), older months into the archive at a
+//! typical ZIP ratio, and the whole backfill at a conservative load rate. The figures are
+//! estimates for planning, not limits; nothing is written.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use futures::TryStreamExt;
+use log::{info, warn};
+use tiberius::QueryItem;
+
+use crate::api::installer::{is_odbc_driver, is_oracle_driver, StartInstallRequest};
+use crate::archiver::{retention_cutoff, FALLBACK_AVG_ROW_BYTES};
+use crate::database::connection::DatabaseConnection;
+use crate::datasource::odbc;
+use crate::models::requests::BackfillEstimateRequestDto;
+use crate::models::responses::{ApiResponse, BackfillEstimateDto, BackfillMonthDto};
+use crate::utils::logging::mask_connection_string;
+use crate::utils::validation::{validate_and_quote_sql_server_object, validate_object_parts};
+
+/// Target field whose source column dates each call row.
+pub const WATERMARK_TARGET: &str = "CallReceivedAt";
+
+/// Zipped NDJSON/CSV of call rows is typically 10-20% of their on-disk size.
+const ARCHIVE_COMPRESSION_RATIO: f64 = 0.15;
+
+/// Rows loaded per second during a backfill; deliberately on the slow side of what installs see.
+const BACKFILL_ROWS_PER_SEC: u64 = 2_000;
+
+#[tauri::command]
+pub async fn estimate_backfill(
+    payload: BackfillEstimateRequestDto,
+) -> Result<ApiResponse<BackfillEstimateDto>, String> {
+    info!(
+        "[PHASE: preflight] [STEP: backfill_estimate] Backfill estimate requested (driver={}, object={}, watermark={}, demo={})",
+        payload.call_data_driver,
+        payload.source_object_name,
+        payload.watermark_column,
+        payload.demo_mode
+    );
+    let today = chrono::Local::now().date_naive();
+    if payload.demo_mode {
+        let (counts, avg) = demo_month_counts(today);
+        return Ok(ApiResponse::ok(project(
+            &counts,
+            avg,
+            payload.hot_retention_months,
+            today,
+        )));
+    }
+    if payload.watermark_column.trim().is_empty() {
+        return Ok(ApiResponse::fail(format!(
+            "Map a source column to {} to estimate the backfill.",
+            WATERMARK_TARGET
+        )));
+    }
+
+    match month_counts(&payload).await {
+        Ok((counts, avg)) => {
+            let estimate = project(&counts, avg, payload.hot_retention_months, today);
+            info!(
+                "[PHASE: preflight] [STEP: backfill_estimate] {} month(s), {} row(s) (hot={}, archive={}), ~{} s",
+                estimate.months.len(),
+                estimate.total_rows,
+                estimate.hot_rows,
+                estimate.archive_rows,
+                estimate.estimated_duration_secs
+            );
+            Ok(ApiResponse::ok(estimate))
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: preflight] [STEP: backfill_estimate] Estimate failed (conn={}): {:#}",
+                mask_connection_string(&payload.call_data_connection_string),
+                e
+            );
+            Ok(ApiResponse::fail(format!(
+                "Unable to estimate the backfill: {:#}",
+                e
+            )))
+        }
+    }
+}
+
+/// The estimate request for an install's primary source: the whole history of the column
+/// mapped to [`WATERMARK_TARGET`]. `None` for export folders, which have no database to ask.
+pub fn request_for(
+    req: &StartInstallRequest,
+    demo_mode: bool,
+) -> Option<BackfillEstimateRequestDto> {
+    if req.file_source.is_some() {
+        return None;
+    }
+    Some(BackfillEstimateRequestDto {
+        call_data_connection_string: req.call_data_connection_string.clone(),
+        call_data_driver: req.call_data_driver.clone(),
+        source_object_name: req.source_object_name.clone(),
+        watermark_column: req
+            .mappings
+            .get(WATERMARK_TARGET)
+            .cloned()
+            .unwrap_or_default(),
+        date_from_iso: None,
+        date_to_iso: None,
+        hot_retention_months: req.hot_retention.months,
+        demo_mode,
+    })
+}
+
+fn parse_bound(value: Option<&str>) -> Result<Option<NaiveDateTime>> {
+    let Some(v) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    let date = NaiveDate::parse_from_str(v.get(..10).unwrap_or(v), "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}' (expected YYYY-MM-DD)", v))?;
+    Ok(date.and_hms_opt(0, 0, 0))
+}
+
+/// (month start, rows) per month plus the average row size, if the source reports it.
+async fn month_counts(
+    payload: &BackfillEstimateRequestDto,
+) -> Result<(Vec<(NaiveDate, u64)>, Option<u64>)> {
+    let from = parse_bound(payload.date_from_iso.as_deref())?;
+    let to = parse_bound(payload.date_to_iso.as_deref())?;
+    let (rows, avg) = if is_odbc_driver(&payload.call_data_driver)
+        || is_oracle_driver(&payload.call_data_driver)
+    {
+        let conn_str = payload.call_data_connection_string.clone();
+        let object = payload.source_object_name.trim().to_string();
+        let column = payload.watermark_column.trim().to_string();
+        let rows = tokio::task::spawn_blocking(move || {
+            odbc::month_counts(&conn_str, &object, &column, from, to)
+        })
+        .await
+        .unwrap_or_else(|e| Err(anyhow::anyhow!("Month count task failed: {}", e)))?;
+        (rows, None)
+    } else {
+        sql_server_month_counts(payload, from, to).await?
+    };
+    let counts = rows
+        .into_iter()
+        .filter_map(|(y, m, n)| NaiveDate::from_ymd_opt(y, m, 1).map(|d| (d, n)))
+        .collect();
+    Ok((counts, avg))
+}
+
+async fn sql_server_month_counts(
+    payload: &BackfillEstimateRequestDto,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<(Vec<(i32, u32, u64)>, Option<u64>)> {
+    let object = validate_and_quote_sql_server_object(&payload.source_object_name)?;
+    let column = match validate_object_parts(&payload.watermark_column)?.as_slice() {
+        [c] => format!("[{}]", c),
+        _ => anyhow::bail!("The watermark column must be a single column name"),
+    };
+    let conn = DatabaseConnection::sql_server(&payload.call_data_connection_string).await?;
+    let client_arc = conn
+        .as_sql_server()
+        .ok_or_else(|| anyhow::anyhow!("SQL Server client unavailable"))?;
+    let mut client = client_arc.lock().await;
+
+    // Open ends become the datetime2 range limits so the query keeps one shape.
+    let mut query = tiberius::Query::new(format!(
+        "SELECT YEAR({c}), MONTH({c}), COUNT_BIG(*) FROM {o} \
+         WHERE {c} >= @P1 AND {c} < @P2 GROUP BY YEAR({c}), MONTH({c})",
+        c = column,
+        o = object
+    ));
+    let day = |y, m, d| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap_or_default()
+    };
+    query.bind(from.unwrap_or_else(|| day(1, 1, 1)));
+    query.bind(to.unwrap_or_else(|| day(9999, 12, 31)));
+    let mut rows = Vec::new();
+    let mut stream = query
+        .query(&mut *client)
+        .await
+        .context("Month count query failed")?;
+    while let Some(item) = stream.try_next().await? {
+        if let QueryItem::Row(row) = item {
+            if let (Some(y), Some(m), Some(n)) = (
+                row.get::<i32, _>(0),
+                row.get::<i32, _>(1),
+                row.get::<i64, _>(2),
+            ) {
+                rows.push((y, m as u32, n.max(0) as u64));
+            }
+        }
+    }
+    drop(stream);
+
+    // Needs VIEW DATABASE STATE and a table (views have no partition stats); optional.
+    let avg_sql = format!(
+        "SELECT CAST(SUM(ps.used_page_count) * 8192.0 / NULLIF(SUM(CASE WHEN ps.index_id IN (0, 1) THEN ps.row_count ELSE 0 END), 0) AS float) \
+         FROM sys.dm_db_partition_stats ps WHERE ps.object_id = OBJECT_ID(N'{}')",
+        object
+    );
+    let avg = match client.simple_query(avg_sql).await {
+        Ok(stream) => stream
+            .into_row()
+            .await
+            .ok()
+            .flatten()
+            .and_then(|row| row.get::<f64, _>(0)),
+        Err(e) => {
+            warn!(
+                "[PHASE: preflight] [STEP: backfill_estimate] Row size stats unavailable: {}",
+                e
+            );
+            None
+        }
+    };
+    Ok((
+        rows,
+        avg.filter(|v| v.is_finite() && *v >= 1.0)
+            .map(|v| v.ceil() as u64),
+    ))
+}
+
+/// The estimate for per-month row counts; months from the retention cutoff on are hot.
+fn project(
+    counts: &[(NaiveDate, u64)],
+    avg_row_bytes: Option<u64>,
+    hot_retention_months: u32,
+    today: NaiveDate,
+) -> BackfillEstimateDto {
+    let cutoff = retention_cutoff(today, hot_retention_months.max(1)).unwrap_or(NaiveDate::MIN);
+    let mut months: Vec<BackfillMonthDto> = counts
+        .iter()
+        .map(|(start, rows)| BackfillMonthDto {
+            month: format!("{:04}-{:02}", start.year(), start.month()),
+            rows: *rows,
+            hot: *start >= cutoff,
+        })
+        .collect();
+    months.sort_by(|a, b| a.month.cmp(&b.month));
+
+    let avg = avg_row_bytes.unwrap_or(FALLBACK_AVG_ROW_BYTES);
+    let hot_rows: u64 = months.iter().filter(|m| m.hot).map(|m| m.rows).sum();
+    let archive_rows: u64 = months.iter().filter(|m| !m.hot).map(|m| m.rows).sum();
+    let total_rows = hot_rows + archive_rows;
+    BackfillEstimateDto {
+        months,
+        total_rows,
+        hot_rows,
+        archive_rows,
+        avg_row_bytes: avg,
+        avg_row_bytes_estimated: avg_row_bytes.is_none(),
+        projected_hot_db_bytes: hot_rows.saturating_mul(avg),
+        projected_archive_bytes: (archive_rows.saturating_mul(avg) as f64
+            * ARCHIVE_COMPRESSION_RATIO)
+            .ceil() as u64,
+        estimated_duration_secs: total_rows.div_ceil(BACKFILL_ROWS_PER_SEC),
+    }
+}
+
+/// Three years of made-up history ending this month (demo mode, no database).
+fn demo_month_counts(today: NaiveDate) -> (Vec<(NaiveDate, u64)>, Option<u64>) {
+    let this_month = today.with_day(1).unwrap_or(today);
+    let counts = (0..36u32)
+        .filter_map(|i| {
+            let month = this_month.checked_sub_months(chrono::Months::new(i))?;
+            // Busier summers, like most CAD call volumes.
+            let seasonal = match month.month() {
+                6..=8 => 46_000,
+                12 | 1 | 2 => 38_000,
+                _ => 41_000,
+            };
+            Some((month, seasonal + u64::from(i) * 150))
+        })
+        .collect();
+    (counts, Some(850))
+}
+
+/// Bytes as "12.3 GB" style text (decimal units).
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Seconds as "2 h 05 min" / "4 min" text.
+pub(crate) fn format_duration(secs: u64) -> String {
+    let minutes = secs.div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m.max(1)),
+        (h, m) => format!("{} h {:02} min", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn months_split_at_the_retention_cutoff_and_project_sizes() {
+        let counts = vec![
+            (ymd(2025, 4, 1), 30_000),
+            (ymd(2025, 3, 1), 20_000),
+            (ymd(2024, 1, 1), 10_000),
+        ];
+        let est = project(&counts, Some(1_000), 18, ymd(2026, 10, 16));
+        let months: Vec<(&str, bool)> = est
+            .months
+            .iter()
+            .map(|m| (m.month.as_str(), m.hot))
+            .collect();
+        assert_eq!(
+            months,
+            vec![("2024-01", false), ("2025-03", false), ("2025-04", true)]
+        );
+        assert_eq!((est.hot_rows, est.archive_rows), (30_000, 30_000));
+        assert_eq!(est.projected_hot_db_bytes, 30_000_000);
+        assert_eq!(est.projected_archive_bytes, 4_500_000);
+        assert_eq!(est.estimated_duration_secs, 30);
+        assert!(!est.avg_row_bytes_estimated);
+
+        let est = project(&counts, None, 18, ymd(2026, 10, 16));
+        assert_eq!(est.avg_row_bytes, FALLBACK_AVG_ROW_BYTES);
+        assert!(est.avg_row_bytes_estimated);
+
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(4_500_000), "4.5 MB");
+        assert_eq!(format_duration(30), "1 min");
+        assert_eq!(format_duration(7_500), "2 h 05 min");
+        assert!(parse_bound(Some("2024-13-01")).is_err());
+        assert_eq!(
+            parse_bound(Some("2024-02-01T00:00:00Z")).unwrap(),
+            ymd(2024, 2, 1).and_hms_opt(0, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,912 @@
+//! Plan-only install (`StartInstallRequest::dry_run`).
+//!
+//! Runs the same validation and discovery the real install does (policy fields, archive
+//! destination, database connectivity/privileges, pending migrations, runtime payload and
+//! destination folder) but only reads. The outcome is a human-readable plan written to
+//! `Prod_Wizard_Log/install_plan_<timestamp>.txt`, in the spirit of `terraform plan`: checks
+//! first, then every change a real run would make.
+
+use anyhow::Result;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::time::Duration;
+
+use crate::api::installer::{
+    build_instance_settings, collect_sources_from_root, connect_with_retry, database_exists,
+    detect_engine_version, ensure_can_create_database, folder_size_bytes_with_timeout,
+    guess_engine, resolve_migrations_paths, resolve_runtime_payload_roots,
+    validate_retention_and_archive_fields, InstallArtifacts, ProgressEmitter, ProgressPayload,
+    StartInstallRequest,
+};
+use crate::database::connection::DatabaseConnection;
+use crate::database::migrations::MigrationRunner;
+use crate::database::provisioning;
+use crate::installation;
+use crate::utils::logging::mask_connection_string;
+
+/// Overwritten files listed individually in the plan before it switches to a count.
+const MAX_LISTED_OVERWRITES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn tag(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "[ok]  ",
+            CheckStatus::Warn => "[warn]",
+            CheckStatus::Fail => "[FAIL]",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeAction {
+    /// Something new is created (`+`).
+    Add,
+    /// Something that already exists is written over or upserted (`~`).
+    Change,
+}
+
+impl ChangeAction {
+    fn symbol(self) -> char {
+        match self {
+            ChangeAction::Add => '+',
+            ChangeAction::Change => '~',
+        }
+    }
+}
+
+/// Checks and planned changes, in the order they were discovered.
+#[derive(Debug, Default)]
+pub(crate) struct InstallPlan {
+    checks: Vec<(CheckStatus, String)>,
+    changes: Vec<(ChangeAction, String)>,
+}
+
+impl InstallPlan {
+    pub(crate) fn check(&mut self, status: CheckStatus, message: impl Into<String>) {
+        let message = message.into();
+        match status {
+            CheckStatus::Fail => warn!("[PHASE: install] [STEP: plan] check failed: {}", message),
+            _ => info!("[PHASE: install] [STEP: plan] check: {}", message),
+        }
+        self.checks.push((status, message));
+    }
+
+    pub(crate) fn change(&mut self, action: ChangeAction, description: impl Into<String>) {
+        self.changes.push((action, description.into()));
+    }
+
+    pub(crate) fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|(s, _)| *s == CheckStatus::Fail)
+            .count()
+    }
+
+    fn count(&self, action: ChangeAction) -> usize {
+        self.changes.iter().filter(|(a, _)| *a == action).count()
+    }
+
+    /// Plain-text plan; `header` lines are printed as `key: value` under the title.
+    pub(crate) fn render(&self, header: &[(&str, String)]) -> String {
+        let mut out = String::new();
+        out.push_str("CADalytix install plan (dry run)\n");
+        out.push_str("================================\n");
+        for (k, v) in header {
+            out.push_str(&format!("{}: {}\n", k, v));
+        }
+
+        out.push_str("\nChecks:\n");
+        for (status, msg) in &self.checks {
+            out.push_str(&format!("  {} {}\n", status.tag(), msg));
+        }
+
+        out.push_str("\nChanges a real install would make:\n");
+        if self.changes.is_empty() {
+            out.push_str("  (none)\n");
+        }
+        for (action, desc) in &self.changes {
+            out.push_str(&format!("  {} {}\n", action.symbol(), desc));
+        }
+
+        out.push_str(&format!(
+            "\nPlan: {} to add, {} to change.",
+            self.count(ChangeAction::Add),
+            self.count(ChangeAction::Change)
+        ));
+        match self.failures() {
+            0 => out.push_str(" All checks passed.\n"),
+            n => out.push_str(&format!(
+                " {} check(s) failed; a real install would stop.\n",
+                n
+            )),
+        }
+        out.push_str("No changes were made.\n");
+        out
+    }
+}
+
+/// Dry-run counterpart of `run_installation`: validate and plan, write the plan, change nothing.
+pub(crate) async fn run_install_plan(
+    req: &StartInstallRequest,
+    correlation_id: &str,
+    emit_progress: &ProgressEmitter,
+) -> Result<InstallArtifacts> {
+    let started = Instant::now();
+    info!(
+        "[PHASE: install] [STEP: plan] run_install_plan entered (install_mode={}, db_mode={})",
+        req.install_mode, req.db_setup.mode
+    );
+    let emit = |step: &str, percent: i32, message: &str| {
+        emit_progress(ProgressPayload {
+            correlation_id: correlation_id.to_string(),
+            step: step.to_string(),
+            severity: "info".to_string(),
+            phase: "install".to_string(),
+            percent,
+            message: message.to_string(),
+            elapsed_ms: Some(started.elapsed().as_millis()),
+            eta_ms: None,
+        });
+    };
+
+    let mut plan = InstallPlan::default();
+
+    emit("plan_validate", 5, "Dry run: validating configuration...");
+    match validate_retention_and_archive_fields(req) {
+        Ok(()) => plan.check(
+            CheckStatus::Ok,
+            "Retention and archive policy settings are valid",
+        ),
+        Err(e) => plan.check(CheckStatus::Fail, e.to_string()),
+    }
+    if let Some(ms) = &req.mapping_state {
+        match crate::mapping::transform::validate(ms) {
+            Ok(()) => plan.check(CheckStatus::Ok, "Mapping transforms are valid"),
+            Err(e) => plan.check(CheckStatus::Fail, e.to_string()),
+        }
+    }
+
+    emit(
+        "plan_archive",
+        15,
+        "Dry run: checking archive destination...",
+    );
+    plan_archive_destination(req, &mut plan).await;
+
+    emit("plan_database", 30, "Dry run: checking database...");
+    let db = plan_database(req, &mut plan).await;
+
+    emit(
+        "plan_migrations",
+        45,
+        "Dry run: computing migration plan...",
+    );
+    if let Some((conn, engine, is_new_db)) = db {
+        plan_migrations(&conn, &engine, is_new_db, &mut plan).await;
+    } else {
+        plan.check(
+            CheckStatus::Warn,
+            "Migration plan skipped (no database connection)",
+        );
+    }
+
+    emit(
+        "plan_settings",
+        55,
+        "Dry run: listing configuration writes...",
+    );
+    let mut settings: Vec<_> = build_instance_settings(req).into_iter().collect();
+    settings.sort();
+    for (k, v) in settings {
+        plan.change(
+            ChangeAction::Change,
+            format!("set instance setting {} = {:?}", k, v),
+        );
+    }
+    let mut mappings: Vec<_> = req.mappings.iter().collect();
+    mappings.sort();
+    for (canonical, source_col) in mappings {
+        plan.change(
+            ChangeAction::Change,
+            format!("map {} <- {}", canonical, source_col),
+        );
+    }
+    if req.app_account.enabled {
+        match req.app_account.validate() {
+            Ok(()) => plan.change(
+                ChangeAction::Add,
+                format!(
+                    "create (or set a new password for) application login '{}' with runtime access only, and use it in the application config",
+                    req.app_account.login()
+                ),
+            ),
+            Err(e) => plan.check(CheckStatus::Fail, e),
+        }
+    }
+
+    emit(
+        "plan_deploy",
+        70,
+        "Dry run: checking runtime payload and destination...",
+    );
+    let layout = crate::installation::layout::resolve(req);
+    let dest_root = layout.binaries.clone();
+    if let Some(target) = &req.remote {
+        plan_remote(target, &mut plan).await;
+    }
+    plan_deploy(req, &dest_root, &mut plan).await;
+    plan_layout(req, &layout, &mut plan).await;
+
+    emit("plan_service", 85, "Dry run: planning service setup...");
+    plan_service(req, &dest_root, &mut plan);
+
+    let artifacts_dir = PathBuf::from(&req.destination_folder).join("installer-artifacts");
+    for name in [
+        "mapping.json",
+        "install-config.json",
+        "install-manifest.json",
+    ] {
+        plan.change(
+            ChangeAction::Add,
+            format!("write {:?}", artifacts_dir.join(name)),
+        );
+    }
+
+    let log_folder = crate::utils::path_resolver::resolve_log_folder()?;
+    let now = chrono::Utc::now();
+    let plan_path = log_folder.join(format!("install_plan_{}.txt", now.format("%Y%m%d_%H%M%S")));
+    let text = plan.render(&[
+        ("Generated (UTC)", now.to_rfc3339()),
+        ("Correlation ID", correlation_id.to_string()),
+        ("Install mode", req.install_mode.clone()),
+        ("Destination", req.destination_folder.clone()),
+        ("Database setup", req.db_setup.mode.clone()),
+    ]);
+    tokio::fs::create_dir_all(&log_folder).await?;
+    tokio::fs::write(&plan_path, text.as_bytes()).await?;
+
+    info!(
+        "[PHASE: install] [STEP: plan] run_install_plan exit (plan_path={:?}, failures={}, duration_ms={})",
+        plan_path,
+        plan.failures(),
+        started.elapsed().as_millis()
+    );
+
+    if plan.failures() > 0 {
+        anyhow::bail!(
+            "Dry run found {} blocking issue(s). No changes were made. See the plan: {}",
+            plan.failures(),
+            plan_path.display()
+        );
+    }
+
+    emit("complete", 100, "Dry run complete. No changes were made.");
+    Ok(InstallArtifacts {
+        log_folder: Some(log_folder.to_string_lossy().to_string()),
+        artifacts_dir: None,
+        manifest_path: None,
+        mapping_path: None,
+        config_path: None,
+        plan_path: Some(plan_path.to_string_lossy().to_string()),
+    })
+}
+
+async fn plan_archive_destination(req: &StartInstallRequest, plan: &mut InstallPlan) {
+    if !req.archive_policy.enabled {
+        plan.check(
+            CheckStatus::Ok,
+            "Archive module is not licensed; archiving stays disabled",
+        );
+        return;
+    }
+    let dest =
+        match crate::archiver::ArchiveDestination::parse(&req.archive_policy.destination_path) {
+            Ok(d) => d,
+            Err(e) => {
+                plan.check(CheckStatus::Fail, e.to_string());
+                return;
+            }
+        };
+    let dir = match dest {
+        crate::archiver::ArchiveDestination::Local(dir) => dir,
+        crate::archiver::ArchiveDestination::Cloud(loc) => {
+            plan.check(
+                CheckStatus::Ok,
+                format!(
+                    "Cloud archive destination {} accepted (the bucket is probed on the first archive run)",
+                    loc.uri()
+                ),
+            );
+            return;
+        }
+    };
+
+    let meta = match tokio::fs::metadata(&dir).await {
+        Ok(m) => m,
+        Err(_) => {
+            match nearest_existing_ancestor(&dir).await {
+                Some(a) => plan.check(
+                    CheckStatus::Ok,
+                    format!(
+                        "Archive destination {:?} will be created under {:?}",
+                        dir, a
+                    ),
+                ),
+                None => plan.check(
+                    CheckStatus::Fail,
+                    format!(
+                        "Archive destination {:?} has no existing parent folder",
+                        dir
+                    ),
+                ),
+            }
+            plan.change(
+                ChangeAction::Add,
+                format!("create archive destination folder {:?}", dir),
+            );
+            return;
+        }
+    };
+    if !meta.is_dir() {
+        plan.check(
+            CheckStatus::Fail,
+            format!("Archive destination {:?} is not a directory", dir),
+        );
+        return;
+    }
+    if meta.permissions().readonly() {
+        plan.check(
+            CheckStatus::Fail,
+            format!("Archive destination {:?} is read-only", dir),
+        );
+        return;
+    }
+
+    let cap_bytes = (req.archive_policy.max_usage_gb as u64).saturating_mul(1024_u64.pow(3));
+    match folder_size_bytes_with_timeout(&dir, Duration::from_secs(30)).await {
+        Ok(used) if cap_bytes > 0 && used > cap_bytes => plan.check(
+            CheckStatus::Fail,
+            format!(
+                "Archive destination {:?} already uses {} MiB, over the {} GB cap",
+                dir,
+                mib(used),
+                req.archive_policy.max_usage_gb
+            ),
+        ),
+        Ok(used) => plan.check(
+            CheckStatus::Ok,
+            format!(
+                "Archive destination {:?} exists ({} MiB used of {} GB cap)",
+                dir,
+                mib(used),
+                req.archive_policy.max_usage_gb
+            ),
+        ),
+        Err(e) => plan.check(
+            CheckStatus::Warn,
+            format!("Archive destination {:?} usage unknown: {}", dir, e),
+        ),
+    }
+}
+
+/// Connects read-only and records checks. Returns the connection to plan migrations with, and
+/// whether the database would be newly created (so nothing is applied yet).
+async fn plan_database(
+    req: &StartInstallRequest,
+    plan: &mut InstallPlan,
+) -> Option<(DatabaseConnection, String, bool)> {
+    let conn_str = req.config_db_connection_string.clone();
+    if conn_str.trim().is_empty() {
+        plan.check(CheckStatus::Fail, "Database connection string is required");
+        return None;
+    }
+    let engine = guess_engine(&conn_str);
+    let create_new = req.db_setup.mode.trim().eq_ignore_ascii_case("create_new");
+
+    let conn = match connect_with_retry(engine.clone(), conn_str.clone()).await {
+        Ok(c) => {
+            plan.check(
+                CheckStatus::Ok,
+                format!(
+                    "Connected to {} ({})",
+                    engine,
+                    mask_connection_string(&conn_str)
+                ),
+            );
+            c
+        }
+        Err(e) => {
+            plan.check(
+                CheckStatus::Fail,
+                format!("Cannot connect to {} database: {}", engine, e),
+            );
+            return None;
+        }
+    };
+
+    if !create_new {
+        return Some((conn, engine, false));
+    }
+
+    let db_name = match req
+        .db_setup
+        .new_db_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(n) => n.to_string(),
+        None => {
+            plan.check(
+                CheckStatus::Fail,
+                "New database name is required for Create NEW mode",
+            );
+            return None;
+        }
+    };
+    if let Err(e) = provisioning::validate_db_name(&db_name) {
+        plan.check(CheckStatus::Fail, format!("Invalid database name: {}", e));
+        return None;
+    }
+    let options = req.db_setup.create_options();
+    if let Err(e) = provisioning::validate_create_options(&options) {
+        plan.check(CheckStatus::Fail, e);
+        return None;
+    }
+
+    match ensure_can_create_database(&conn, &engine).await {
+        Ok(()) => plan.check(CheckStatus::Ok, "Login can create databases"),
+        Err(e) => plan.check(CheckStatus::Fail, e.to_string()),
+    }
+    match database_exists(&conn, &engine, &db_name).await {
+        Ok(true) => plan.check(
+            CheckStatus::Fail,
+            format!("Database already exists: {}", db_name),
+        ),
+        Ok(false) => plan.check(
+            CheckStatus::Ok,
+            format!("Database name '{}' is available", db_name),
+        ),
+        Err(e) => plan.check(CheckStatus::Fail, e.to_string()),
+    }
+    plan.change(
+        ChangeAction::Add,
+        format!("create database '{}' on {}", db_name, engine),
+    );
+    if let Some(dir) = options.files_path.as_deref() {
+        plan.change(
+            ChangeAction::Add,
+            if engine == "postgres" {
+                format!(
+                    "create tablespace '{}' in {} for '{}'",
+                    provisioning::postgres_tablespace_name(&db_name),
+                    dir,
+                    db_name
+                )
+            } else {
+                format!("place the data and log files of '{}' in {}", db_name, dir)
+            },
+        );
+    }
+    if let Some(collation) = options.collation.as_deref() {
+        plan.change(
+            ChangeAction::Change,
+            format!("create '{}' with collation / locale {}", db_name, collation),
+        );
+    }
+    if engine != "postgres" && req.db_setup.sql_server_sizing.is_some() {
+        plan.change(
+            ChangeAction::Change,
+            format!("apply data/log file sizing to '{}'", db_name),
+        );
+    }
+
+    // The manifest is read from disk; the master connection is never migrated.
+    Some((conn, engine, true))
+}
+
+async fn plan_migrations(
+    conn: &DatabaseConnection,
+    engine: &str,
+    is_new_db: bool,
+    plan: &mut InstallPlan,
+) {
+    let engine_version = detect_engine_version(engine.to_string(), conn.clone())
+        .await
+        .unwrap_or_else(|_| {
+            if engine == "postgres" {
+                "17".to_string()
+            } else {
+                "2022".to_string()
+            }
+        });
+
+    let result: Result<(Vec<String>, usize)> = async {
+        let (manifest_path, migrations_path) = resolve_migrations_paths()?;
+        let runner = MigrationRunner::new(
+            conn.clone(),
+            manifest_path,
+            migrations_path,
+            engine.to_string(),
+            engine_version.clone(),
+        )
+        .await?;
+        let manifest = runner.load_manifest().await?;
+        let applied = if is_new_db {
+            Default::default()
+        } else {
+            runner
+                .get_applied_migration_names()
+                .await
+                .unwrap_or_default()
+        };
+        let pending = manifest
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains(&m.name))
+            .map(|m| m.name.clone())
+            .collect();
+        Ok((pending, manifest.migrations.len()))
+    }
+    .await;
+
+    match result {
+        Ok((pending, total)) => {
+            plan.check(
+                CheckStatus::Ok,
+                format!(
+                    "Migration manifest for {} {}: {} of {} migration(s) pending",
+                    engine,
+                    engine_version,
+                    pending.len(),
+                    total
+                ),
+            );
+            for name in pending {
+                plan.change(ChangeAction::Add, format!("apply migration {}", name));
+            }
+        }
+        Err(e) => plan.check(
+            CheckStatus::Fail,
+            format!("Cannot build migration plan: {}", e),
+        ),
+    }
+}
+
+async fn plan_deploy(req: &StartInstallRequest, dest_root: &Path, plan: &mut InstallPlan) {
+    if !tokio::fs::try_exists(dest_root).await.unwrap_or(false) {
+        plan.change(
+            ChangeAction::Add,
+            format!("create destination folder {:?}", dest_root),
+        );
+    }
+
+    let (runtime_shared, runtime_platform) =
+        match resolve_runtime_payload_roots(&req.install_mode).await {
+            Ok(r) => r,
+            Err(e) => {
+                plan.check(CheckStatus::Fail, e.to_string());
+                return;
+            }
+        };
+    let mut sources: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for root in [&runtime_shared, &runtime_platform] {
+        if let Err(e) = collect_sources_from_root(root, dest_root, &mut sources).await {
+            plan.check(
+                CheckStatus::Fail,
+                format!("Cannot read runtime payload {:?}: {}", root, e),
+            );
+            return;
+        }
+    }
+    if sources.is_empty() {
+        plan.check(
+            CheckStatus::Fail,
+            "Runtime payload folders are present but contain no files",
+        );
+        return;
+    }
+
+    let mut total_bytes: u64 = 0;
+    let mut new_files = 0usize;
+    let mut overwrites: Vec<PathBuf> = Vec::new();
+    for (src, dst) in &sources {
+        total_bytes = total_bytes
+            .saturating_add(tokio::fs::metadata(src).await.map(|m| m.len()).unwrap_or(0));
+        if tokio::fs::try_exists(dst).await.unwrap_or(false) {
+            overwrites.push(dst.clone());
+        } else {
+            new_files += 1;
+        }
+    }
+    plan.check(
+        CheckStatus::Ok,
+        format!(
+            "Runtime payload: {} file(s), {} MiB",
+            sources.len(),
+            mib(total_bytes)
+        ),
+    );
+
+    match crate::utils::disk::nearest_existing_ancestor(dest_root).await {
+        Some(anchor) => {
+            match crate::utils::disk::get_free_space_bytes_for_path(&anchor.to_string_lossy()).await
+            {
+                Ok(free) if free < total_bytes => plan.check(
+                    CheckStatus::Fail,
+                    format!(
+                        "Not enough free space at {:?}: {} MiB needed, {} MiB free",
+                        anchor,
+                        mib(total_bytes),
+                        mib(free)
+                    ),
+                ),
+                Ok(free) => plan.check(
+                    CheckStatus::Ok,
+                    format!("Free space at {:?}: {} MiB", anchor, mib(free)),
+                ),
+                Err(e) => plan.check(
+                    CheckStatus::Warn,
+                    format!("Free space at {:?} unknown: {}", anchor, e),
+                ),
+            }
+        }
+        None => plan.check(
+            CheckStatus::Fail,
+            format!("Destination {:?} has no existing parent folder", dest_root),
+        ),
+    }
+
+    if new_files > 0 {
+        plan.change(
+            ChangeAction::Add,
+            format!(
+                "copy {} new runtime file(s) into {:?}",
+                new_files, dest_root
+            ),
+        );
+    }
+    for p in overwrites.iter().take(MAX_LISTED_OVERWRITES) {
+        plan.change(ChangeAction::Change, format!("overwrite {:?}", p));
+    }
+    if overwrites.len() > MAX_LISTED_OVERWRITES {
+        plan.change(
+            ChangeAction::Change,
+            format!(
+                "overwrite {} more existing file(s)",
+                overwrites.len() - MAX_LISTED_OVERWRITES
+            ),
+        );
+    }
+
+    use crate::installation::config_generator::{APPSETTINGS_FILE, ENV_FILE};
+    for name in [APPSETTINGS_FILE, ENV_FILE] {
+        let path = dest_root.join(name);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            plan.change(ChangeAction::Change, format!("regenerate {:?}", path));
+        } else {
+            plan.change(ChangeAction::Add, format!("generate {:?}", path));
+        }
+    }
+}
+
+fn plan_service(req: &StartInstallRequest, dest_root: &Path, plan: &mut InstallPlan) {
+    let mode = req.install_mode.trim().to_ascii_lowercase();
+    match mode.as_str() {
+        "windows" => {
+            plan.change(
+                ChangeAction::Add,
+                format!(
+                    "install and start Windows service '{}'",
+                    installation::service::WINDOWS_MAIN_SERVICE.service_name()
+                ),
+            );
+            for spec in installation::service::WINDOWS_BACKGROUND_SERVICES {
+                plan.change(
+                    ChangeAction::Add,
+                    format!(
+                        "install and start Windows service '{}' when {} is deployed",
+                        spec.service_name(),
+                        spec.exe_names.join(" / ")
+                    ),
+                );
+            }
+        }
+        "linux" => plan.change(
+            ChangeAction::Add,
+            format!(
+                "install and start systemd unit '{}'",
+                installation::service::linux_service_name()
+            ),
+        ),
+        "docker" => {
+            let template = installation::docker::locate_docker_runtime_dir()
+                .map(|d| d.join("compose").join("docker-compose.template.yml"))
+                .ok()
+                .filter(|p| p.exists());
+            match template {
+                Some(t) => plan.check(
+                    CheckStatus::Ok,
+                    format!("Docker Compose template found at {:?}", t),
+                ),
+                None => plan.check(
+                    CheckStatus::Ok,
+                    "No runtime Docker Compose template; the bundled template would be used",
+                ),
+            }
+            plan.change(
+                ChangeAction::Add,
+                format!(
+                    "render {:?} from the wizard answers, pull missing images and start containers",
+                    dest_root.join("docker-compose.yml")
+                ),
+            );
+        }
+        other => plan.check(
+            CheckStatus::Warn,
+            format!("No service setup planned for install mode '{}'", other),
+        ),
+    }
+}
+
+/// The remote server of the install is reachable with the confirmed host key and takes sudo.
+async fn plan_remote(target: &crate::installation::remote::RemoteTarget, plan: &mut InstallPlan) {
+    match crate::installation::remote::RemoteSession::open(target).await {
+        Ok(session) => plan.check(
+            CheckStatus::Ok,
+            format!(
+                "Remote server {} reached with the confirmed host key ({})",
+                target.label(),
+                session.os
+            ),
+        ),
+        Err(e) => plan.check(
+            CheckStatus::Fail,
+            format!("Remote server {}: {:#}", target.label(), e),
+        ),
+    }
+    plan.change(
+        ChangeAction::Add,
+        format!(
+            "upload the install to {} and register its systemd unit there",
+            target.label()
+        ),
+    );
+}
+
+/// Data, logs and temp folders to create, path problems and free space on every layout path,
+/// the storage folder's filesystem (FAT32/exFAT fail the plan), and the projected space per
+/// volume (a short volume fails the plan). Only the projection applies to a remote install; its
+/// folders are on the server.
+async fn plan_layout(
+    req: &StartInstallRequest,
+    layout: &crate::installation::layout::InstallLayout,
+    plan: &mut InstallPlan,
+) {
+    if req.remote.is_none() {
+        plan_local_layout(req, layout, plan).await;
+    }
+    let projection = crate::installation::space_projection::project(req).await;
+    for volume in &projection.volumes {
+        match volume.shortfall_message() {
+            Some(msg) => plan.check(CheckStatus::Fail, msg),
+            None => plan.check(CheckStatus::Ok, volume.summary()),
+        }
+    }
+}
+
+async fn plan_local_layout(
+    req: &StartInstallRequest,
+    layout: &crate::installation::layout::InstallLayout,
+    plan: &mut InstallPlan,
+) {
+    for (role, dir) in layout.entries().into_iter().skip(1) {
+        if !tokio::fs::try_exists(dir).await.unwrap_or(false) {
+            plan.change(
+                ChangeAction::Add,
+                format!("create {} folder {:?}", role, dir),
+            );
+        }
+    }
+    for finding in crate::installation::path_check::check_layout(layout, &req.install_mode).await {
+        let status = if finding.blocking {
+            CheckStatus::Fail
+        } else {
+            CheckStatus::Warn
+        };
+        plan.check(status, finding.message());
+    }
+    let storage_fs = crate::installation::storage_fs::inspect(req).await;
+    match &storage_fs.blocked {
+        Some(reason) => plan.check(CheckStatus::Fail, reason.clone()),
+        None if !storage_fs.fs_type.is_empty() => {
+            plan.check(CheckStatus::Ok, storage_fs.summary());
+            for advice in &storage_fs.advice {
+                plan.check(CheckStatus::Ok, advice.clone());
+            }
+        }
+        None => {}
+    }
+    for check in
+        crate::installation::layout::check_free_space(layout, req.db_setup.max_db_size_gb).await
+    {
+        match check.free_bytes {
+            Some(free) if check.is_short() => plan.check(
+                CheckStatus::Warn,
+                format!(
+                    "Low free space for the {} folder {:?}: {} MiB free, {} MiB recommended",
+                    check.role,
+                    check.path,
+                    mib(free),
+                    mib(check.required_bytes)
+                ),
+            ),
+            Some(free) => plan.check(
+                CheckStatus::Ok,
+                format!(
+                    "Free space for the {} folder {:?}: {} MiB",
+                    check.role,
+                    check.path,
+                    mib(free)
+                ),
+            ),
+            None => plan.check(
+                CheckStatus::Warn,
+                format!(
+                    "Free space for the {} folder {:?} unknown",
+                    check.role, check.path
+                ),
+            ),
+        }
+    }
+}
+
+fn mib(bytes: u64) -> u64 {
+    bytes.div_ceil(1024 * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_lists_checks_changes_and_summary() {
+        let mut plan = InstallPlan::default();
+        plan.check(CheckStatus::Ok, "Connected");
+        plan.check(CheckStatus::Warn, "Free space unknown");
+        plan.change(ChangeAction::Add, "apply migration 001_init");
+        plan.change(ChangeAction::Add, "apply migration 002_calls");
+        plan.change(ChangeAction::Change, "overwrite \"appsettings.json\"");
+
+        let text = plan.render(&[("Install mode", "docker".to_string())]);
+        assert!(text.contains("Install mode: docker\n"));
+        assert!(text.contains("  [ok]   Connected\n"));
+        assert!(text.contains("  [warn] Free space unknown\n"));
+        assert!(text.contains("  + apply migration 001_init\n"));
+        assert!(text.contains("  ~ overwrite \"appsettings.json\"\n"));
+        assert!(text.contains("Plan: 2 to add, 1 to change. All checks passed.\n"));
+        assert!(text.ends_with("No changes were made.\n"));
+        assert_eq!(plan.failures(), 0);
+    }
+
+    #[test]
+    fn failed_checks_are_counted_and_reported() {
+        let mut plan = InstallPlan::default();
+        plan.check(CheckStatus::Fail, "Database already exists: cadalytix");
+        plan.check(CheckStatus::Fail, "Runtime files are missing.");
+        assert_eq!(plan.failures(), 2);
+
+        let text = plan.render(&[]);
+        assert!(text.contains("  [FAIL] Database already exists: cadalytix\n"));
+        assert!(text.contains("  (none)\n"));
+        assert!(text.contains("2 check(s) failed; a real install would stop."));
+    }
+}
@@ -3,14 +3,20 @@
 
 use crate::database::connection::DatabaseConnection;
 use crate::database::platform_db::PlatformDbAdapter;
+use crate::licensing::features::LicensedFeatures;
+use crate::licensing::offline::{self as offline_license, SignedLicenseFile};
+use crate::licensing::online;
 use crate::licensing::token as token_verifier;
-use crate::models::requests::LicenseVerifyRequest;
+use crate::models::requests::{LicenseActivateRequest, LicenseVerifyRequest};
 use crate::models::responses::{
-    ApiResponse, LicenseEntitlementDto, LicenseStatusResponse, LicenseVerifyResponse,
+    ApiResponse, LicenseActivateResponse, LicenseEntitlementDto, LicenseStatusResponse,
+    LicenseVerifyResponse,
 };
 use crate::models::state::AppState;
 use crate::security::crypto::secret_fingerprint;
 use crate::security::secret_protector::SecretProtector;
+use crate::utils::http;
+use crate::utils::retry;
 
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce};
@@ -23,10 +29,10 @@ use ring::signature;
 use ring::signature::UnparsedPublicKey;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::State;
 use tokio::time::{timeout, Duration};
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tokio_retry::RetryIf;
 use uuid::Uuid;
 
@@ -100,7 +106,66 @@ pub async fn verify_license(
         }));
     }
 
+    // Installing from an offline bundle: online verification cannot reach the ops server, and
+    // the bundle's license file stands in for a pasted offline license bundle.
+    if mode == "online" {
+        if let Err(e) = crate::installation::offline_bundle::ensure_network_allowed(
+            "Online license verification",
+        ) {
+            return Ok(ApiResponse::ok(LicenseVerifyResponse {
+                success: false,
+                message: format!("{} Use offline verification.", e),
+                entitlement: None,
+                correlation_id,
+            }));
+        }
+    }
+    if mode == "offline"
+        && req
+            .offline_bundle
+            .as_ref()
+            .map(|s| s.trim().is_empty())
+            .unwrap_or(true)
+    {
+        if let Some(path) = crate::installation::offline_bundle::license_file() {
+            match tokio::fs::read_to_string(&path).await {
+                Ok(text) => req.offline_bundle = Some(text.trim().to_string()),
+                Err(e) => warn!(
+                    "[PHASE: license_verification] [STEP: verify] Failed to read offline bundle license file {:?}: {} (correlation_id={})",
+                    path, e, correlation_id
+                ),
+            }
+        }
+    }
+    // Air-gapped installs without a bundle string: a signed license file next to the installer.
+    let mut license_file: Option<(String, SignedLicenseFile)> = None;
+    if mode == "offline"
+        && req
+            .offline_bundle
+            .as_ref()
+            .map(|s| s.trim().is_empty())
+            .unwrap_or(true)
+    {
+        if let Some(path) = offline_license::find_license_file() {
+            match offline_license::load_license_file(&path).await {
+                Ok(loaded) => license_file = Some(loaded),
+                Err(e) => {
+                    warn!(
+                        "[PHASE: license_verification] [STEP: verify] License file rejected: {:#} (correlation_id={})",
+                        e, correlation_id
+                    );
+                    return Ok(ApiResponse::ok(LicenseVerifyResponse {
+                        success: false,
+                        message: format!("License file {:?} could not be verified: {}", path, e),
+                        entitlement: None,
+                        correlation_id,
+                    }));
+                }
+            }
+        }
+    }
     if mode == "offline"
+        && license_file.is_none()
         && req
             .offline_bundle
             .as_ref()
@@ -109,14 +174,19 @@ pub async fn verify_license(
     {
         return Ok(ApiResponse::ok(LicenseVerifyResponse {
             success: false,
-            message: "Offline bundle is required for offline verification".to_string(),
+            message: format!(
+                "Offline bundle or a signed license file ({} next to the installer) is required for offline verification",
+                offline_license::LICENSE_FILE_NAME
+            ),
             entitlement: None,
             correlation_id,
         }));
     }
 
-    // Verify (online/offline)
-    let verification = if mode == "online" {
+    // Verify (license file/online/offline)
+    let verification = if let Some((text, file)) = &license_file {
+        license_file_outcome(&req.license_key, text, file)
+    } else if mode == "online" {
         match verify_online_with_retry(&req.license_key, req.ops_api_base_url.as_deref()).await {
             Ok(v) => v,
             Err(e) => {
@@ -180,8 +250,12 @@ pub async fn verify_license(
     let now = Utc::now();
 
     // SECURITY: Verify the signed JWT token locally (fail-closed). This is the authoritative
-    // source of truth for expiry/features.
-    let token_payload = match token_verifier::verify_and_parse(Some(&verification.signed_token)) {
+    // source of truth for expiry/features. A verified license file is its own signed payload.
+    let token_payload = match license_file
+        .as_ref()
+        .map(|(_, file)| file.payload.clone())
+        .or_else(|| token_verifier::verify_and_parse(Some(&verification.signed_token)))
+    {
         Some(p) => p,
         None => {
             best_effort_log_event(
@@ -426,6 +500,213 @@ pub async fn verify_license(
     }))
 }
 
+const OFFLINE_ACTIVATION_HINT: &str = "If this server has no internet access, activate offline instead: place the signed license file (cadalytix.lic) next to the installer or paste an offline license bundle.";
+
+#[tauri::command]
+pub async fn activate_license(
+    app_state: State<'_, AppState>,
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<LicenseActivateRequest>,
+) -> Result<ApiResponse<LicenseActivateResponse>, String> {
+    let correlation_id = Uuid::new_v4().simple().to_string();
+    info!(
+        "[PHASE: license_verification] [STEP: activate] activate_license requested (correlation_id={})",
+        correlation_id
+    );
+    let failed = |message: String,
+                  offline_fallback: bool|
+     -> Result<ApiResponse<LicenseActivateResponse>, String> {
+        Ok(ApiResponse::ok(LicenseActivateResponse {
+            success: false,
+            message,
+            entitlement: None,
+            offline_fallback,
+            correlation_id: correlation_id.clone(),
+        }))
+    };
+
+    let Some(req) = payload else {
+        return failed(
+            "Invalid request. Request body is required.".to_string(),
+            false,
+        );
+    };
+    let license_key = req.license_key.trim().to_ascii_uppercase();
+    let key_ok = Regex::new(r"^[A-Z0-9]{4}(-[A-Z0-9]{4}){3}$")
+        .map(|re| re.is_match(&license_key))
+        .unwrap_or(false);
+    if !key_ok {
+        return failed(
+            "Invalid license key format. Expected format: XXXX-XXXX-XXXX-XXXX (A-Z0-9 only)."
+                .to_string(),
+            false,
+        );
+    }
+    if let Err(e) =
+        crate::installation::offline_bundle::ensure_network_allowed("Online license activation")
+    {
+        return failed(format!("{} {}", e, OFFLINE_ACTIVATION_HINT), true);
+    }
+
+    // Bind the entitlement to this installation when the config DB already knows it.
+    let platform_db = match app_state.get_config_db().await {
+        Some((engine, _ver, config_cs)) => connect_with_retry(&engine, &config_cs)
+            .await
+            .ok()
+            .map(|conn| PlatformDbAdapter::new(conn, Arc::clone(&secrets))),
+        None => None,
+    };
+    let local_install_id = match &platform_db {
+        Some(db) => db
+            .get_setting("Setup:InstallId")
+            .await
+            .ok()
+            .flatten()
+            .filter(|s| !s.trim().is_empty()),
+        None => None,
+    };
+
+    let base_url = req
+        .ops_api_base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(online::DEFAULT_OPS_API_BASE_URL)
+        .to_string();
+    let proxy = http::ProxyConfig {
+        url: req.proxy_url.clone(),
+        username: req.proxy_username.clone(),
+        password: req.proxy_password.clone(),
+        bypass_system: req.bypass_system_proxy,
+    };
+    let activation = match online::activate(
+        &base_url,
+        &license_key,
+        local_install_id.as_deref(),
+        &proxy,
+    )
+    .await
+    {
+        Ok(a) => a,
+        Err(e) if http::is_unreachable(&e) => {
+            warn!(
+                "[PHASE: license_verification] [STEP: activate] Licensing server unreachable: {:#} (correlation_id={})",
+                e, correlation_id
+            );
+            return failed(
+                format!(
+                    "Could not reach the licensing server at {}. Check the network and proxy settings. {}",
+                    base_url, OFFLINE_ACTIVATION_HINT
+                ),
+                true,
+            );
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: license_verification] [STEP: activate] Activation failed: {:#} (correlation_id={})",
+                e, correlation_id
+            );
+            best_effort_log_event(
+                &app_state,
+                &secrets,
+                "license_activate_failed",
+                &e.to_string(),
+            )
+            .await;
+            return failed(format!("Online activation failed: {}", e), false);
+        }
+    };
+
+    // SECURITY: the signed entitlement is authoritative (fail-closed).
+    let Some(token_payload) = token_verifier::verify_and_parse(Some(&activation.signed_token))
+    else {
+        return failed(
+            "Activation failed: the signed entitlement could not be validated.".to_string(),
+            false,
+        );
+    };
+    if let (Some(token_install_id), Some(local)) =
+        (token_payload.install_id.as_ref(), local_install_id.as_ref())
+    {
+        if !token_install_id.eq_ignore_ascii_case(local) {
+            return failed(
+                "Activation failed: the entitlement is bound to a different installation."
+                    .to_string(),
+                false,
+            );
+        }
+    }
+
+    if let Err(e) = store_entitlement(&secrets, &activation.signed_token).await {
+        error!(
+            "[PHASE: license_verification] [STEP: activate] Failed to store entitlement: {:#} (correlation_id={})",
+            e, correlation_id
+        );
+        return failed(
+            format!("Failed to store the license entitlement: {}", e),
+            false,
+        );
+    }
+
+    let now = Utc::now();
+    let status = token_verifier::determine_status(
+        now,
+        token_payload.expires_at_utc,
+        token_payload.grace_until_utc,
+    );
+    if let Some(db) = &platform_db {
+        let features_json =
+            serde_json::to_string(&token_payload.features).unwrap_or_else(|_| "{}".to_string());
+        let _ = db
+            .save_license_state(
+                "online",
+                &mask_license_key(&license_key),
+                &secret_fingerprint(&license_key),
+                &status,
+                &activation.client_name,
+                &activation.license_id,
+                token_payload.issued_at_utc,
+                token_payload.expires_at_utc,
+                token_payload.grace_until_utc,
+                &features_json,
+                now,
+                &Uuid::new_v4().simple().to_string(),
+                Some(&activation.signed_token),
+                Some(now),
+                Some(token_payload.expires_at_utc),
+            )
+            .await;
+        let _ = db
+            .log_setup_event(
+                "license_activate_success",
+                "License activated online",
+                Some("installer"),
+                Some(&serde_json::json!({ "correlationId": correlation_id }).to_string()),
+            )
+            .await;
+    }
+
+    Ok(ApiResponse::ok(LicenseActivateResponse {
+        success: true,
+        message: "License activated successfully".to_string(),
+        entitlement: Some(LicenseEntitlementDto {
+            license_mode: "online".to_string(),
+            expires_at_utc: Some(token_payload.expires_at_utc),
+            grace_until_utc: Some(token_payload.grace_until_utc),
+            features: token_payload
+                .features
+                .iter()
+                .filter(|(_, enabled)| **enabled)
+                .map(|(k, _)| k.clone())
+                .collect(),
+            client_id: Some(activation.license_id.clone()),
+            last_verified_at_utc: now,
+        }),
+        offline_fallback: false,
+        correlation_id,
+    }))
+}
+
 #[tauri::command]
 pub async fn get_license_status(
     app_state: State<'_, AppState>,
@@ -433,34 +714,52 @@ pub async fn get_license_status(
 ) -> Result<ApiResponse<LicenseStatusResponse>, String> {
     info!("[PHASE: license_verification] [STEP: status] get_license_status requested");
 
-    let Some((engine, _ver, config_cs)) = app_state.get_config_db().await else {
-        return Ok(ApiResponse::ok(LicenseStatusResponse {
-            is_active: false,
-            entitlement: None,
-            message: "No license configured".to_string(),
-        }));
+    let conn = match app_state.get_config_db().await {
+        None => None,
+        Some((engine, _ver, config_cs)) => match connect_with_retry(&engine, &config_cs).await {
+            Ok(c) => Some(c),
+            Err(_) => {
+                return Ok(ApiResponse::ok(LicenseStatusResponse {
+                    is_active: false,
+                    entitlement: None,
+                    message: "Failed to retrieve license status (database unavailable)".to_string(),
+                    grace_days_remaining: None,
+                }))
+            }
+        },
     };
+    Ok(ApiResponse::ok(license_status(conn, &secrets).await))
+}
 
-    let conn = match connect_with_retry(&engine, &config_cs).await {
-        Ok(c) => c,
-        Err(_) => {
-            return Ok(ApiResponse::ok(LicenseStatusResponse {
-                is_active: false,
-                entitlement: None,
-                message: "Failed to retrieve license status (database unavailable)".to_string(),
-            }))
+/// License status from the config DB's license state; before the config DB exists (`conn` is
+/// `None`) from the stored online entitlement or a license file. Also used by `--inspect`.
+pub(crate) async fn license_status(
+    conn: Option<DatabaseConnection>,
+    secrets: &Arc<SecretProtector>,
+) -> LicenseStatusResponse {
+    let Some(conn) = conn else {
+        if let Some((mode, payload)) = local_license(secrets).await {
+            let client_id = payload.license_id.clone();
+            return license_status_response(mode, &payload, client_id, Utc::now());
         }
+        return LicenseStatusResponse {
+            is_active: false,
+            entitlement: None,
+            message: "No license configured".to_string(),
+            grace_days_remaining: None,
+        };
     };
 
-    let platform_db = PlatformDbAdapter::new(conn, Arc::clone(&secrets));
+    let platform_db = PlatformDbAdapter::new(conn, Arc::clone(secrets));
     let license_state = platform_db.get_license_state().await.unwrap_or_default();
 
     let Some(state) = license_state else {
-        return Ok(ApiResponse::ok(LicenseStatusResponse {
+        return LicenseStatusResponse {
             is_active: false,
             entitlement: None,
             message: "No license configured".to_string(),
-        }));
+            grace_days_remaining: None,
+        };
     };
 
     // Never return secrets to UI
@@ -476,25 +775,48 @@ pub async fn get_license_status(
         .map(|dt| dt.with_timezone(&Utc))
         .unwrap_or_else(Utc::now);
 
-    let now = Utc::now();
     let signed_token = state.get("signedTokenBlob").and_then(|v| v.as_str());
-    let Some(payload) = token_verifier::verify_and_parse(signed_token) else {
-        return Ok(ApiResponse::ok(LicenseStatusResponse {
+    // Offline activations store the signed license file instead of a JWT.
+    let Some(payload) = offline_license::verify_stored(signed_token)
+        .or_else(|| token_verifier::verify_and_parse(signed_token))
+    else {
+        return LicenseStatusResponse {
             is_active: false,
             entitlement: None,
             message: "Invalid or missing signed license token".to_string(),
-        }));
+            grace_days_remaining: None,
+        };
     };
+    let client_id = state
+        .get("licenseId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    license_status_response(mode, &payload, client_id, last_verified)
+}
 
+fn license_status_response(
+    mode: String,
+    payload: &token_verifier::VerifiedLicensePayload,
+    client_id: Option<String>,
+    last_verified: DateTime<Utc>,
+) -> LicenseStatusResponse {
+    let now = Utc::now();
     let status =
         token_verifier::determine_status(now, payload.expires_at_utc, payload.grace_until_utc);
     let is_active = status != "expired";
-    let message = match status.as_str() {
-        "active" => "License is active",
-        "grace" => "License is in grace period",
-        _ => "License has expired",
-    }
-    .to_string();
+    let grace_days_remaining =
+        offline_license::grace_days_remaining(now, payload.expires_at_utc, payload.grace_until_utc);
+    let message = match (status.as_str(), grace_days_remaining) {
+        ("active", _) => "License is active".to_string(),
+        ("grace", Some(days)) => format!(
+            "License is in grace period ({} day{} remaining)",
+            days,
+            if days == 1 { "" } else { "s" }
+        ),
+        ("grace", None) => "License is in grace period".to_string(),
+        _ => "License has expired".to_string(),
+    };
 
     let features = payload
         .features
@@ -503,21 +825,19 @@ pub async fn get_license_status(
         .map(|(k, _)| k.clone())
         .collect::<Vec<_>>();
 
-    Ok(ApiResponse::ok(LicenseStatusResponse {
+    LicenseStatusResponse {
         is_active,
         entitlement: Some(LicenseEntitlementDto {
             license_mode: mode,
             expires_at_utc: Some(payload.expires_at_utc),
             grace_until_utc: Some(payload.grace_until_utc),
             features,
-            client_id: state
-                .get("licenseId")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
+            client_id,
             last_verified_at_utc: last_verified,
         }),
         message,
-    }))
+        grace_days_remaining,
+    }
 }
 
 // =========================
@@ -580,9 +900,8 @@ async fn verify_online_with_retry(
     let url = format!("{}/licensing/verify", base);
 
     let attempt = || async {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(12))
-            .build()?;
+        let client =
+            http::client_with_timeout(&http::ProxyConfig::default(), Duration::from_secs(12))?;
 
         #[derive(serde::Serialize)]
         #[serde(rename_all = "camelCase")]
@@ -686,11 +1005,7 @@ async fn verify_online_with_retry(
         })
     };
 
-    let retry_strategy = ExponentialBackoff::from_millis(150)
-        .factor(2)
-        .max_delay(Duration::from_secs(2))
-        .take(3)
-        .map(jitter);
+    let retry_strategy = retry::policy().backoff();
 
     RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
         let msg = e.to_string().to_ascii_lowercase();
@@ -702,6 +1017,117 @@ async fn verify_online_with_retry(
     .await
 }
 
+/// Outcome for a verified license file: the entered key must match when the file names one, and
+/// a file past its grace period is rejected.
+fn license_file_outcome(
+    license_key: &str,
+    text: &str,
+    file: &SignedLicenseFile,
+) -> VerificationOutcome {
+    let p = &file.payload;
+    let now = Utc::now();
+    let status = determine_status(now, p.expires_at_utc, p.grace_until_utc);
+    let message = if file
+        .license_key
+        .as_ref()
+        .is_some_and(|k| !k.trim().eq_ignore_ascii_case(license_key))
+    {
+        Some("The license file was issued for a different license key".to_string())
+    } else if status == "expired" {
+        Some(format!(
+            "The license file expired on {} (grace period ended {})",
+            p.expires_at_utc.format("%Y-%m-%d"),
+            p.grace_until_utc.format("%Y-%m-%d")
+        ))
+    } else {
+        None
+    };
+
+    VerificationOutcome {
+        is_valid: message.is_none(),
+        status: if message.is_none() {
+            status
+        } else {
+            "invalid".to_string()
+        },
+        message: message.unwrap_or_else(|| "License verified successfully".to_string()),
+        client_name: p.client_name.clone().unwrap_or_default(),
+        license_id: p.license_id.clone().unwrap_or_default(),
+        issued_at_utc: p.issued_at_utc,
+        expires_at_utc: p.expires_at_utc,
+        grace_until_utc: p.grace_until_utc,
+        features_json: serde_json::to_string(&p.features).unwrap_or_else(|_| "{}".to_string()),
+        signed_token: text.to_string(),
+        install_id: p.install_id.clone(),
+        bootstrap_secret: None,
+        branding: None,
+        constraints: None,
+    }
+}
+
+/// Encrypted copy of the activated entitlement (under the log folder's `secrets/`), so the
+/// activation survives until the config DB exists.
+fn entitlement_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::utils::path_resolver::resolve_log_folder()?
+        .join("secrets")
+        .join("license_entitlement.enc"))
+}
+
+async fn store_entitlement(secrets: &SecretProtector, signed_token: &str) -> anyhow::Result<()> {
+    let path = entitlement_path()?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let encrypted = secrets.encrypt(signed_token).await?;
+    tokio::fs::write(&path, encrypted).await?;
+    Ok(())
+}
+
+async fn load_stored_entitlement(secrets: &SecretProtector) -> Option<String> {
+    let path = entitlement_path().ok()?;
+    let encrypted = tokio::fs::read_to_string(&path).await.ok()?;
+    secrets.decrypt(encrypted.trim()).await.ok()
+}
+
+/// Verified license available without the config DB: the stored online entitlement, else a
+/// signed license file next to the installer. Returns the license mode with the payload.
+async fn local_license(
+    secrets: &SecretProtector,
+) -> Option<(String, token_verifier::VerifiedLicensePayload)> {
+    let stored = load_stored_entitlement(secrets).await;
+    if let Some(payload) = stored
+        .as_deref()
+        .and_then(|t| token_verifier::verify_and_parse(Some(t)))
+    {
+        return Some(("online".to_string(), payload));
+    }
+    let path = offline_license::find_license_file()?;
+    match offline_license::load_license_file(&path).await {
+        Ok((_, file)) => Some(("offline".to_string(), file.payload)),
+        Err(e) => {
+            warn!(
+                "[PHASE: license_verification] [STEP: license_file] Ignoring license file {:?}: {:#}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// Feature flags the terminal wizard gates pages and options on (see `licensing::features`).
+pub(crate) async fn local_licensed_features(secrets: &SecretProtector) -> LicensedFeatures {
+    match local_license(secrets).await {
+        Some((_, payload)) => LicensedFeatures::from_enabled(
+            payload
+                .features
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(k, _)| k),
+        ),
+        None => LicensedFeatures::default(),
+    }
+}
+
 fn hostname_best_effort() -> String {
     std::env::var("COMPUTERNAME")
         .or_else(|_| std::env::var("HOSTNAME"))
@@ -950,14 +1376,14 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         let timed = match engine {
             "postgres" => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::postgres(conn_str),
                 )
                 .await
             }
             _ => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::sql_server(conn_str),
                 )
                 .await
@@ -967,11 +1393,7 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         inner
     };
 
-    let retry_strategy = ExponentialBackoff::from_millis(100)
-        .factor(2)
-        .max_delay(Duration::from_secs(2))
-        .take(3)
-        .map(jitter);
+    let retry_strategy = retry::policy().backoff();
 
     RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
         let msg = e.to_string().to_ascii_lowercase();
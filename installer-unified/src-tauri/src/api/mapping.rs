@@ -0,0 +1,158 @@
+// Mapping API endpoints
+// Suggestions for the Schema Mapping page (the TUI calls `mapping::suggest` directly),
+// compatibility checks of persisted mapping files and the CSV review sheet.
+
+use crate::api::installer::MappingTargetField;
+use crate::mapping::suggest::{self, SuggestSource};
+use crate::mapping::{csv, persist};
+use crate::models::requests::{
+    AutoMapRequestDto, MappingCsvRequestDto, VerifyMappingCompatRequestDto,
+};
+use crate::models::responses::{
+    ApiResponse, AutoMapResponseDto, ExportMappingCsvResponseDto, ImportMappingCsvResponseDto,
+    VerifyMappingCompatResponseDto,
+};
+
+use log::{info, warn};
+
+#[tauri::command]
+pub async fn auto_map(
+    payload: AutoMapRequestDto,
+) -> Result<ApiResponse<AutoMapResponseDto>, String> {
+    let sources: Vec<SuggestSource> = payload
+        .source_fields
+        .iter()
+        .map(|s| SuggestSource {
+            id: &s.id,
+            name: &s.raw_name,
+            data_type: &s.data_type,
+        })
+        .collect();
+    let targets: Vec<MappingTargetField> = payload
+        .target_fields
+        .iter()
+        .map(|t| MappingTargetField {
+            id: t.id.clone(),
+            name: t.name.clone(),
+            required: false,
+        })
+        .collect();
+    let suggestions = suggest::suggest(&sources, &targets, &payload.target_to_source);
+    info!(
+        "[PHASE: mapping] [STEP: auto_map] Suggestions computed (sources={}, targets={}, suggestions={})",
+        sources.len(),
+        targets.len(),
+        suggestions.len()
+    );
+    Ok(ApiResponse::ok(AutoMapResponseDto { suggestions }))
+}
+
+#[tauri::command]
+pub async fn verify_mapping_compat(
+    payload: VerifyMappingCompatRequestDto,
+) -> Result<ApiResponse<VerifyMappingCompatResponseDto>, String> {
+    info!(
+        "[PHASE: mapping] [STEP: verify_compat] Mapping compatibility check requested (path={})",
+        payload.path
+    );
+    let bytes = match tokio::fs::read(&payload.path).await {
+        Ok(b) => b,
+        Err(e) => {
+            return Ok(ApiResponse::fail(format!(
+                "Unable to read mapping file {}: {}",
+                payload.path, e
+            )))
+        }
+    };
+    let (file, file_version) = match persist::load(&bytes) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            warn!(
+                "[PHASE: mapping] [STEP: verify_compat] Mapping file rejected (path={}): {:#}",
+                payload.path, e
+            );
+            return Ok(ApiResponse::fail(format!("{:#}", e)));
+        }
+    };
+    let targets = payload
+        .target_fields
+        .unwrap_or_else(crate::mapping::target_catalog);
+    let compat = persist::check_compat(&file, &targets);
+    info!(
+        "[PHASE: mapping] [STEP: verify_compat] Mapping compatibility checked (file_version={}, compatible={}, issues={}, warnings={})",
+        file_version,
+        compat.compatible(),
+        compat.issues.len(),
+        compat.warnings.len()
+    );
+    Ok(ApiResponse::ok(VerifyMappingCompatResponseDto {
+        compatible: compat.compatible(),
+        file_schema_version: file_version,
+        current_schema_version: persist::MAPPING_SCHEMA_VERSION,
+        upgraded: file_version != persist::MAPPING_SCHEMA_VERSION,
+        issues: compat.issues,
+        warnings: compat.warnings,
+        added_targets: compat.added_targets,
+        removed_targets: compat.removed_targets,
+    }))
+}
+
+#[tauri::command]
+pub async fn export_mapping_csv(
+    payload: MappingCsvRequestDto,
+) -> Result<ApiResponse<ExportMappingCsvResponseDto>, String> {
+    let (text, rows) = csv::export(&payload.mapping_state);
+    if let Err(e) = tokio::fs::write(&payload.path, text).await {
+        warn!(
+            "[PHASE: mapping] [STEP: export_csv] Failed to write mapping sheet (path={}): {}",
+            payload.path, e
+        );
+        return Ok(ApiResponse::fail(format!(
+            "Unable to write {}: {}",
+            payload.path, e
+        )));
+    }
+    info!(
+        "[PHASE: mapping] [STEP: export_csv] Mapping sheet written (path={}, rows={})",
+        payload.path, rows
+    );
+    Ok(ApiResponse::ok(ExportMappingCsvResponseDto {
+        path: payload.path,
+        rows,
+    }))
+}
+
+#[tauri::command]
+pub async fn import_mapping_csv(
+    payload: MappingCsvRequestDto,
+) -> Result<ApiResponse<ImportMappingCsvResponseDto>, String> {
+    let text = match tokio::fs::read_to_string(&payload.path).await {
+        Ok(t) => t,
+        Err(e) => {
+            return Ok(ApiResponse::fail(format!(
+                "Unable to read {}: {}",
+                payload.path, e
+            )))
+        }
+    };
+    match csv::import(&payload.mapping_state, &text) {
+        Ok(mapping_state) => {
+            let mapped_count = mapping_state.target_to_source.len();
+            info!(
+                "[PHASE: mapping] [STEP: import_csv] Mapping sheet imported (path={}, mapped={})",
+                payload.path, mapped_count
+            );
+            Ok(ApiResponse::ok(ImportMappingCsvResponseDto {
+                mapping_state,
+                mapped_count,
+            }))
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: mapping] [STEP: import_csv] Mapping sheet rejected (path={}): {}",
+                payload.path, e
+            );
+            Ok(ApiResponse::fail(e.to_string()))
+        }
+    }
+}
@@ -1,5 +1,13 @@
+pub mod backfill;
+pub mod install_plan;
 pub mod installer;
 pub mod license;
+pub mod mapping;
+pub mod network;
 pub mod preflight;
+pub mod preflight_report;
+pub mod profiles;
+pub mod remote;
 pub mod schema;
+pub mod secrets;
 pub mod setup;
@@ -0,0 +1,66 @@
+// Network page: explicit proxy settings for outbound HTTPS (see `utils::http`).
+
+use crate::models::requests::SaveProxySettingsRequest;
+use crate::models::responses::{ApiResponse, ProxySettingsResponse};
+use crate::security::secret_protector::SecretProtector;
+use crate::utils::http::{self, ProxySettings};
+
+use log::{error, info};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_proxy_settings() -> Result<ApiResponse<ProxySettingsResponse>, String> {
+    let env_proxy = http::env_proxy();
+    match http::load_settings().await {
+        Ok(saved) => {
+            let (settings, has_password) = saved.unwrap_or_default();
+            Ok(ApiResponse::ok(ProxySettingsResponse {
+                host: settings.host,
+                port: settings.port,
+                username: settings.username,
+                has_password,
+                bypass_system_proxy: settings.bypass_system,
+                env_proxy,
+            }))
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: network] [STEP: get_proxy] Failed to read proxy settings: {:?}",
+                e
+            );
+            Ok(ApiResponse::fail(format!(
+                "Failed to read proxy settings: {}",
+                e
+            )))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn save_proxy_settings(
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<SaveProxySettingsRequest>,
+) -> Result<ApiResponse<ProxySettingsResponse>, String> {
+    let req = payload.unwrap_or_default();
+    info!(
+        "[PHASE: network] [STEP: save_proxy] save_proxy_settings requested (explicit={}, bypass_system={})",
+        !req.host.trim().is_empty(),
+        req.bypass_system_proxy
+    );
+    let settings = ProxySettings {
+        host: req.host,
+        port: req.port,
+        username: req.username,
+        password: req.password,
+        bypass_system: req.bypass_system_proxy,
+    };
+    if let Err(e) = http::save_settings(&secrets, settings).await {
+        error!(
+            "[PHASE: network] [STEP: save_proxy] Failed to save proxy settings: {:?}",
+            e
+        );
+        return Ok(ApiResponse::fail(e.to_string()));
+    }
+    get_proxy_settings().await
+}
@@ -0,0 +1,672 @@
+//! Preflight report (`--preflight-only`).
+//!
+//! Runs every preflight check without installing anything (host, dependencies, network, and the
+//! permissions/data source checks when connection strings are supplied) and writes the results to
+//! `Prod_Wizard_Log/` twice: `preflight-report.json` for tooling, self-checksummed like the install
+//! manifest, and `preflight-report.txt` for people. Meant for pre-sales validation of a customer
+//! host before anyone schedules an install.
+//!
+//! The Storage section probes write performance on the data folder's volume
+//! (`installation::disk_probe`) and warns when it is too slow for the expected ingest volume
+//! (`--expected-rows-per-day=`, default 100,000). The data folder is `--data-dir=` or the default
+//! layout's data folder for the install mode.
+//!
+//! The Host section also compares CPU cores, RAM, NUMA nodes and the data disk with the sizing
+//! tier for that ingest plus the historical rows to backfill (`--historical-rows=`, default 0);
+//! the assessment itself is recorded as `sizing` (see `installation::host_sizing`).
+//!
+//! The Network section checks NTP sync on this host and, with a config database connection,
+//! the database server's clock and time zone (`installation::time_sync`); the measured skew is
+//! recorded as `clockSkewMs`.
+//!
+//! Connection strings come from `CADALYTIX_CONFIG_DB_CONNECTION` and
+//! `CADALYTIX_CALL_DATA_CONNECTION`, never from argv, so they do not land in process lists. A check
+//! whose inputs are missing is reported as `Skipped` and does not affect the overall status.
+//!
+//! Exit codes: 0 all checks passed, 2 warnings only, 3 at least one failure.
+
+use anyhow::{Context, Result};
+use log::info;
+use std::path::PathBuf;
+
+use crate::api::preflight;
+use crate::models::requests::{
+    PreflightDataSourceRequestDto, PreflightDependenciesRequestDto, PreflightHostRequestDto,
+    PreflightNetworkRequestDto, PreflightPermissionsRequestDto,
+};
+use crate::models::responses::{ApiResponse, HostSizingDto, PreflightCheckDto};
+
+pub const CONFIG_DB_CONNECTION_ENV: &str = "CADALYTIX_CONFIG_DB_CONNECTION";
+pub const CALL_DATA_CONNECTION_ENV: &str = "CADALYTIX_CALL_DATA_CONNECTION";
+
+const REPORT_JSON: &str = "preflight-report.json";
+const REPORT_TEXT: &str = "preflight-report.txt";
+const REPORT_SCHEMA_VERSION: u32 = 1;
+/// Calls per day assumed for the storage check when `--expected-rows-per-day` is not given.
+pub const DEFAULT_EXPECTED_ROWS_PER_DAY: u64 = 100_000;
+
+/// Arguments for `--preflight-only`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightOnlyArgs {
+    /// "windows" | "docker" | "linux" (defaults to windows on Windows, docker elsewhere).
+    pub install_mode: String,
+    /// Service ports to check; empty means the network preflight defaults.
+    pub app_ports: Vec<u16>,
+    /// Call data table/view for the permissions and data source checks.
+    pub source_object: Option<String>,
+    /// Host checks in strict mode (warnings become failures).
+    pub strict: bool,
+    /// Data folder whose volume is probed; `None` means the default layout's data folder.
+    pub data_dir: Option<PathBuf>,
+    /// Expected ingest, in call rows per day, for the storage and sizing checks.
+    pub expected_rows_per_day: u64,
+    /// Rows the backfill will load, for the sizing checks.
+    pub historical_rows: u64,
+}
+
+impl PreflightOnlyArgs {
+    /// Parse `--mode=`, `--ports=8080,8443`, `--source-object=`, `--data-dir=`,
+    /// `--expected-rows-per-day=`, `--historical-rows=`, `--strict` from argv.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let value_of = |name: &str| {
+            args.iter()
+                .find_map(|a| a.strip_prefix(name).map(|v| v.trim().to_string()))
+                .filter(|v| !v.is_empty())
+        };
+        let install_mode = value_of("--mode=")
+            .map(|m| m.to_ascii_lowercase())
+            .unwrap_or_else(|| if cfg!(windows) { "windows" } else { "docker" }.to_string());
+        if !matches!(install_mode.as_str(), "windows" | "docker" | "linux") {
+            anyhow::bail!(
+                "Invalid --mode '{}' (expected windows, docker or linux)",
+                install_mode
+            );
+        }
+        let app_ports = match value_of("--ports=") {
+            Some(list) => list
+                .split(',')
+                .map(|p| {
+                    p.trim()
+                        .parse::<u16>()
+                        .ok()
+                        .filter(|p| *p > 0)
+                        .ok_or_else(|| anyhow::anyhow!("Invalid port '{}' in --ports", p.trim()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        let count_of = |name: &str, default: u64| match value_of(name) {
+            Some(n) => n
+                .replace('_', "")
+                .parse::<u64>()
+                .map_err(|_| anyhow::anyhow!("Invalid {} '{}'", name.trim_end_matches('='), n)),
+            None => Ok(default),
+        };
+        let expected_rows_per_day =
+            count_of("--expected-rows-per-day=", DEFAULT_EXPECTED_ROWS_PER_DAY)?;
+        let historical_rows = count_of("--historical-rows=", 0)?;
+        Ok(Self {
+            install_mode,
+            app_ports,
+            source_object: value_of("--source-object="),
+            strict: args.iter().any(|a| a == "--strict"),
+            data_dir: value_of("--data-dir=").map(PathBuf::from),
+            expected_rows_per_day,
+            historical_rows,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportCheck {
+    pub name: String,
+    pub status: String, // Pass | Warn | Fail
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSection {
+    pub name: String,
+    pub status: String, // Pass | Warn | Fail | Skipped
+    /// Why the section was skipped or could not run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    pub checks: Vec<ReportCheck>,
+}
+
+impl ReportSection {
+    fn skipped(name: &str, note: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status: "Skipped".to_string(),
+            note: Some(note),
+            remediation: None,
+            checks: Vec::new(),
+        }
+    }
+
+    /// Section from a preflight command response; a failed call becomes a Fail with its error.
+    fn from_response<T>(
+        name: &str,
+        res: std::result::Result<ApiResponse<T>, String>,
+        split: impl FnOnce(T) -> (String, Vec<ReportCheck>, Option<String>),
+    ) -> Self {
+        let res = res.unwrap_or_else(ApiResponse::fail);
+        match res.data {
+            Some(data) if res.success => {
+                let (status, checks, remediation) = split(data);
+                Self {
+                    name: name.to_string(),
+                    status,
+                    note: None,
+                    remediation: remediation.filter(|r| !r.trim().is_empty()),
+                    checks,
+                }
+            }
+            _ => Self {
+                name: name.to_string(),
+                status: "Fail".to_string(),
+                note: Some(
+                    res.error
+                        .unwrap_or_else(|| "Check could not run.".to_string()),
+                ),
+                remediation: None,
+                checks: Vec::new(),
+            },
+        }
+    }
+}
+
+fn plain_checks(checks: Vec<PreflightCheckDto>) -> Vec<ReportCheck> {
+    checks
+        .into_iter()
+        .map(|c| ReportCheck {
+            name: c.name,
+            status: c.status,
+            detail: c.detail,
+            remediation: None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub schema_version: u32,
+    pub created_utc: String,
+    pub machine_name: String,
+    pub install_mode: String,
+    pub overall_status: String, // Pass | Warn | Fail
+    pub sections: Vec<ReportSection>,
+    /// Host sizing against the projected load; its checks are in the Host section.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sizing: Option<HostSizingDto>,
+    /// Database server time minus this host's time; its check is in the Network section.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_ms: Option<i64>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedPreflightReport<'a> {
+    #[serde(flatten)]
+    report: &'a PreflightReport,
+    /// SHA-256 of the report serialized compactly, in field order, without this field.
+    self_sha256: String,
+}
+
+/// Worst status across sections; skipped sections do not count.
+pub fn overall_status(sections: &[ReportSection]) -> &'static str {
+    if sections.iter().any(|s| s.status == "Fail") {
+        "Fail"
+    } else if sections.iter().any(|s| s.status == "Warn") {
+        "Warn"
+    } else {
+        "Pass"
+    }
+}
+
+/// Process exit code for an overall status (see module docs).
+pub fn exit_code_for_status(status: &str) -> i32 {
+    match status {
+        "Pass" => 0,
+        "Warn" => 2,
+        _ => 3,
+    }
+}
+
+/// Pretty JSON with `selfSha256`, and the checksum itself.
+pub fn signed_json(report: &PreflightReport) -> Result<(Vec<u8>, String)> {
+    let unsigned = serde_json::to_vec(report)?;
+    let self_sha256 = crate::security::crypto::sha256_hex(&unsigned);
+    let signed = SignedPreflightReport {
+        report,
+        self_sha256: self_sha256.clone(),
+    };
+    Ok((serde_json::to_vec_pretty(&signed)?, self_sha256))
+}
+
+pub fn render_text(report: &PreflightReport, self_sha256: &str) -> String {
+    let mut out = String::new();
+    out.push_str("CADalytix Preflight Report\n");
+    out.push_str(&format!("Created (UTC): {}\n", report.created_utc));
+    out.push_str(&format!("Machine: {}\n", report.machine_name));
+    out.push_str(&format!("Install mode: {}\n", report.install_mode));
+    out.push_str(&format!(
+        "Overall: {}\n",
+        report.overall_status.to_ascii_uppercase()
+    ));
+    if let Some(s) = &report.sizing {
+        out.push_str(&format!(
+            "Sizing: {} tier ({} rows/day, {} historical rows): {} cores and {} GB RAM recommended{}\n",
+            s.tier,
+            s.rows_per_day,
+            s.historical_rows,
+            s.recommended_cpu_cores,
+            s.recommended_ram_gb,
+            if s.under_provisioned {
+                format!("; short on {}", s.shortfalls.join(", "))
+            } else {
+                String::new()
+            }
+        ));
+    }
+    if let Some(skew) = report.clock_skew_ms {
+        out.push_str(&format!(
+            "Clock skew (database server minus this host): {:+} ms\n",
+            skew
+        ));
+    }
+    for section in &report.sections {
+        out.push_str(&format!(
+            "\n[{}] {}\n",
+            section.status.to_ascii_uppercase(),
+            section.name
+        ));
+        if let Some(note) = &section.note {
+            out.push_str(&format!("  {}\n", note));
+        }
+        for c in &section.checks {
+            out.push_str(&format!(
+                "  [{}] {}: {}\n",
+                c.status.to_ascii_uppercase(),
+                c.name,
+                c.detail
+            ));
+            if let Some(r) = &c.remediation {
+                out.push_str(&format!("         Fix: {}\n", r));
+            }
+        }
+        if let Some(r) = &section.remediation {
+            out.push_str(&format!("  Fix: {}\n", r));
+        }
+    }
+    out.push_str(&format!(
+        "\nReport SHA-256 (preflight-report.json selfSha256): {}\n",
+        self_sha256
+    ));
+    out
+}
+
+/// Storage section from a probe of the data folder's volume.
+pub fn storage_section(
+    probe: Result<crate::installation::disk_probe::DiskProbe>,
+    expected_rows_per_day: u64,
+) -> ReportSection {
+    let probe = match probe {
+        Ok(p) => p,
+        Err(e) => {
+            return ReportSection {
+                name: "Storage".to_string(),
+                status: "Warn".to_string(),
+                note: Some(format!("Disk performance probe could not run: {}", e)),
+                remediation: None,
+                checks: Vec::new(),
+            }
+        }
+    };
+    let (need_mbps, need_iops) =
+        crate::installation::disk_probe::required_rates(expected_rows_per_day);
+    let remediation = || {
+        Some(
+            "Put the data folder on faster storage (SSD, or more provisioned IOPS), or expect ingest and backfill to lag."
+                .to_string(),
+        )
+    };
+    let seq_ok = probe.seq_write_mbps >= need_mbps;
+    let random_ok = probe.random_write_iops >= need_iops;
+    let checks = vec![
+        ReportCheck {
+            name: "Sequential write".to_string(),
+            status: if seq_ok { "Pass" } else { "Warn" }.to_string(),
+            detail: format!(
+                "{:.1} MB/s in {} (about {:.0} MB/s needed)",
+                probe.seq_write_mbps,
+                probe.dir.display(),
+                need_mbps
+            ),
+            remediation: if seq_ok { None } else { remediation() },
+        },
+        ReportCheck {
+            name: "Random write".to_string(),
+            status: if random_ok { "Pass" } else { "Warn" }.to_string(),
+            detail: format!(
+                "{:.0} IOPS, 4 KiB synced writes (about {:.0} IOPS needed for {} rows/day)",
+                probe.random_write_iops, need_iops, expected_rows_per_day
+            ),
+            remediation: if random_ok { None } else { remediation() },
+        },
+    ];
+    ReportSection {
+        name: "Storage".to_string(),
+        status: if seq_ok && random_ok { "Pass" } else { "Warn" }.to_string(),
+        note: None,
+        remediation: None,
+        checks,
+    }
+}
+
+/// `--data-dir`, or the data folder of the default layout for the install mode.
+fn probe_data_dir(args: &PreflightOnlyArgs, config_db: Option<&str>) -> PathBuf {
+    if let Some(dir) = &args.data_dir {
+        return dir.clone();
+    }
+    let windows = args.install_mode == "windows";
+    let engine = match config_db {
+        Some(cs) => crate::api::installer::guess_engine(cs),
+        None if windows => "sqlserver".to_string(),
+        None => "postgres".to_string(),
+    };
+    let destination = if windows {
+        "C:\\Program Files\\CADalytix"
+    } else {
+        "/opt/cadalytix"
+    };
+    crate::installation::layout::default_layout(&args.install_mode, &engine, destination).data
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Run every preflight check for `args`.
+pub async fn collect_report(args: &PreflightOnlyArgs) -> PreflightReport {
+    let config_db = env_value(CONFIG_DB_CONNECTION_ENV);
+    let call_data = env_value(CALL_DATA_CONNECTION_ENV);
+    let mut sections: Vec<ReportSection> = Vec::new();
+    let mut machine_name = String::new();
+    let mut sizing = None;
+    let mut clock_skew_ms = None;
+    let data_dir = probe_data_dir(args, config_db.as_deref());
+
+    let host = preflight::preflight_host(Some(PreflightHostRequestDto {
+        strict_mode: args.strict,
+        expected_rows_per_day: Some(args.expected_rows_per_day),
+        historical_rows: Some(args.historical_rows),
+        data_dir: Some(data_dir.to_string_lossy().to_string()),
+    }))
+    .await;
+    sections.push(ReportSection::from_response("Host", host, |h| {
+        machine_name = h.machine_name;
+        sizing = h.sizing;
+        (h.overall_status, plain_checks(h.checks), None)
+    }));
+
+    let deps = preflight::preflight_dependencies(PreflightDependenciesRequestDto {
+        install_mode: args.install_mode.clone(),
+    })
+    .await;
+    sections.push(ReportSection::from_response("Dependencies", deps, |d| {
+        let checks = d
+            .checks
+            .into_iter()
+            .map(|c| ReportCheck {
+                name: c.name,
+                status: c.status,
+                detail: c.detail,
+                remediation: c.remediation,
+            })
+            .collect();
+        (d.overall_status, checks, None)
+    }));
+
+    let network = preflight::preflight_network(Some(PreflightNetworkRequestDto {
+        db_connection_string: config_db.clone().unwrap_or_default(),
+        app_ports: args.app_ports.clone(),
+    }))
+    .await;
+    sections.push(ReportSection::from_response("Network", network, |n| {
+        let checks = n
+            .checks
+            .into_iter()
+            .map(|c| ReportCheck {
+                name: c.name,
+                status: c.status,
+                detail: c.detail,
+                remediation: c.remediation,
+            })
+            .collect();
+        clock_skew_ms = n.clock_skew_ms;
+        (n.overall_status, checks, None)
+    }));
+
+    match (&config_db, &call_data, &args.source_object) {
+        (Some(config_db), Some(call_data), Some(source)) => {
+            let perms = preflight::preflight_permissions(Some(PreflightPermissionsRequestDto {
+                config_db_connection_string: config_db.clone(),
+                call_data_connection_string: call_data.clone(),
+                require_config_db_ddl: true,
+                require_config_db_dml: true,
+                require_call_data_read: true,
+                source_object_name: source.clone(),
+            }))
+            .await;
+            sections.push(ReportSection::from_response("Permissions", perms, |p| {
+                (
+                    p.overall_status,
+                    plain_checks(p.checks),
+                    Some(p.recommended_remediation),
+                )
+            }));
+        }
+        _ => sections.push(ReportSection::skipped(
+            "Permissions",
+            format!(
+                "Set {} and {} and pass --source-object=<schema.table> to check database permissions.",
+                CONFIG_DB_CONNECTION_ENV, CALL_DATA_CONNECTION_ENV
+            ),
+        )),
+    }
+
+    match (&call_data, &args.source_object) {
+        (Some(call_data), Some(source)) => {
+            let ds = preflight::preflight_datasource(PreflightDataSourceRequestDto {
+                call_data_connection_string: call_data.clone(),
+                source_object_name: source.clone(),
+                date_from_iso: None,
+                date_to_iso: None,
+                sample_limit: 10,
+                demo_mode: false,
+                file_source: None,
+                call_data_driver: String::new(),
+            })
+            .await;
+            sections.push(ReportSection::from_response("Data Source", ds, |d| {
+                (d.overall_status, plain_checks(d.checks), None)
+            }));
+        }
+        _ => sections.push(ReportSection::skipped(
+            "Data Source",
+            format!(
+                "Set {} and pass --source-object=<schema.table> to check the call data source.",
+                CALL_DATA_CONNECTION_ENV
+            ),
+        )),
+    }
+
+    let probe = crate::installation::disk_probe::probe(&data_dir, Default::default()).await;
+    sections.push(storage_section(probe, args.expected_rows_per_day));
+
+    PreflightReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        created_utc: chrono::Utc::now().to_rfc3339(),
+        machine_name,
+        install_mode: args.install_mode.clone(),
+        overall_status: overall_status(&sections).to_string(),
+        sections,
+        sizing,
+        clock_skew_ms,
+    }
+}
+
+/// Run the checks and write both report files; returns the report and (json, text) paths.
+pub async fn run_preflight_only(
+    args: &PreflightOnlyArgs,
+) -> Result<(PreflightReport, PathBuf, PathBuf)> {
+    info!(
+        "[PHASE: preflight] [STEP: report] Preflight-only run starting (install_mode={}, ports={:?}, source_object={:?})",
+        args.install_mode, args.app_ports, args.source_object
+    );
+    let report = collect_report(args).await;
+
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    tokio::fs::create_dir_all(&log_dir).await?;
+    let (json, self_sha256) = signed_json(&report)?;
+    let json_path = log_dir.join(REPORT_JSON);
+    let text_path = log_dir.join(REPORT_TEXT);
+    tokio::fs::write(&json_path, json)
+        .await
+        .with_context(|| format!("Failed to write preflight report: {:?}", json_path))?;
+    tokio::fs::write(&text_path, render_text(&report, &self_sha256))
+        .await
+        .with_context(|| format!("Failed to write preflight report: {:?}", text_path))?;
+
+    info!(
+        "[PHASE: preflight] [STEP: report] Preflight report written (overall_status={}, json={:?}, text={:?})",
+        report.overall_status, json_path, text_path
+    );
+    Ok((report, json_path, text_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn from_args_parses_mode_ports_and_source() {
+        let parsed = PreflightOnlyArgs::from_args(&args(&[
+            "installer",
+            "--preflight-only",
+            "--mode=Linux",
+            "--ports=8080, 8443",
+            "--source-object=dbo.Calls",
+            "--strict",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.install_mode, "linux");
+        assert_eq!(parsed.app_ports, vec![8080, 8443]);
+        assert_eq!(parsed.source_object.as_deref(), Some("dbo.Calls"));
+        assert!(parsed.strict);
+        assert_eq!(parsed.expected_rows_per_day, DEFAULT_EXPECTED_ROWS_PER_DAY);
+        let parsed = PreflightOnlyArgs::from_args(&args(&[
+            "--data-dir=/srv/data",
+            "--expected-rows-per-day=2_000_000",
+            "--historical-rows=300000000",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.data_dir, Some(PathBuf::from("/srv/data")));
+        assert_eq!(parsed.expected_rows_per_day, 2_000_000);
+        assert_eq!(parsed.historical_rows, 300_000_000);
+        assert!(PreflightOnlyArgs::from_args(&args(&["--historical-rows=-1"])).is_err());
+        assert!(PreflightOnlyArgs::from_args(&args(&["--expected-rows-per-day=lots"])).is_err());
+
+        assert!(PreflightOnlyArgs::from_args(&args(&["--mode=mac"])).is_err());
+        assert!(PreflightOnlyArgs::from_args(&args(&["--ports=80,http"])).is_err());
+        assert!(PreflightOnlyArgs::from_args(&args(&["--ports=0"])).is_err());
+    }
+
+    #[test]
+    fn signed_report_verifies_and_status_drives_exit_code() {
+        let section = |name: &str, status: &str| ReportSection {
+            name: name.to_string(),
+            status: status.to_string(),
+            note: None,
+            remediation: None,
+            checks: vec![ReportCheck {
+                name: "Docker".to_string(),
+                status: status.to_string(),
+                detail: "detail".to_string(),
+                remediation: Some("Install Docker".to_string()),
+            }],
+        };
+        let mut sections = vec![
+            section("Host", "Pass"),
+            ReportSection::skipped("Permissions", "no connection".to_string()),
+        ];
+        assert_eq!(overall_status(&sections), "Pass");
+        sections.push(section("Network", "Warn"));
+        assert_eq!(overall_status(&sections), "Warn");
+        sections.push(section("Dependencies", "Fail"));
+        let overall = overall_status(&sections);
+        assert_eq!(exit_code_for_status(overall), 3);
+        assert_eq!(exit_code_for_status("Warn"), 2);
+        assert_eq!(exit_code_for_status("Pass"), 0);
+
+        let report = PreflightReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            created_utc: "2026-01-01T00:00:00Z".to_string(),
+            machine_name: "host1".to_string(),
+            install_mode: "docker".to_string(),
+            overall_status: overall.to_string(),
+            sections,
+            sizing: None,
+            clock_skew_ms: Some(-2500),
+        };
+        let (json, self_sha256) = signed_json(&report).unwrap();
+
+        let mut value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let claimed = value.as_object_mut().unwrap().remove("selfSha256").unwrap();
+        assert_eq!(claimed, self_sha256);
+        let unsigned = serde_json::to_vec(&report).unwrap();
+        assert_eq!(crate::security::crypto::sha256_hex(&unsigned), self_sha256);
+
+        let text = render_text(&report, &self_sha256);
+        assert!(text.contains("Overall: FAIL"));
+        assert!(text.contains("[SKIPPED] Permissions"));
+        assert!(text.contains("Fix: Install Docker"));
+        assert!(text.contains("Clock skew (database server minus this host): -2500 ms"));
+    }
+
+    #[test]
+    fn storage_section_warns_when_slower_than_the_ingest_needs() {
+        let probe = |mbps: f64, iops: f64| {
+            Ok(crate::installation::disk_probe::DiskProbe {
+                dir: PathBuf::from("/opt"),
+                seq_write_mbps: mbps,
+                random_write_iops: iops,
+            })
+        };
+        let fast = storage_section(probe(400.0, 5000.0), DEFAULT_EXPECTED_ROWS_PER_DAY);
+        assert_eq!(fast.status, "Pass");
+        assert!(fast.checks[0].detail.starts_with("400.0 MB/s in /opt"));
+
+        let slow = storage_section(probe(400.0, 900.0), 2_000_000);
+        assert_eq!(slow.status, "Warn");
+        assert_eq!(slow.checks[0].status, "Pass");
+        assert_eq!(slow.checks[1].status, "Warn");
+        assert!(slow.checks[1].remediation.is_some());
+
+        let failed = storage_section(Err(anyhow::anyhow!("read-only")), 1);
+        assert!(failed.note.unwrap().contains("read-only"));
+    }
+}
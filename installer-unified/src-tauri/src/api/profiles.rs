@@ -0,0 +1,87 @@
+// Saved connection profiles of the Data Source and Database pages (see `utils::connection_profiles`).
+
+use crate::models::requests::{ConnectionProfileRequest, SaveConnectionProfileRequest};
+use crate::models::responses::ApiResponse;
+use crate::security::secret_protector::SecretProtector;
+use crate::utils::connection_profiles::{self, ConnectionProfile};
+
+use log::{error, info};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_connection_profiles(
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<ConnectionProfileRequest>,
+) -> Result<ApiResponse<Vec<ConnectionProfile>>, String> {
+    let Some(req) = payload else {
+        return Ok(ApiResponse::fail("Invalid request."));
+    };
+    match connection_profiles::list(&secrets, req.kind).await {
+        Ok(profiles) => Ok(ApiResponse::ok(profiles)),
+        Err(e) => {
+            error!(
+                "[PHASE: profiles] [STEP: list] Failed to read connection profiles: {:?}",
+                e
+            );
+            Ok(ApiResponse::fail(format!(
+                "Failed to read connection profiles: {}",
+                e
+            )))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn save_connection_profile(
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<SaveConnectionProfileRequest>,
+) -> Result<ApiResponse<Vec<ConnectionProfile>>, String> {
+    let Some(req) = payload else {
+        return Ok(ApiResponse::fail("Invalid request."));
+    };
+    info!(
+        "[PHASE: profiles] [STEP: save] save_connection_profile requested (kind={:?})",
+        req.kind
+    );
+    let kind = req.kind;
+    let profile = ConnectionProfile {
+        name: req.name,
+        kind,
+        values: req.values,
+        saved_at: String::new(),
+    };
+    if let Err(e) = connection_profiles::save(&secrets, profile).await {
+        error!(
+            "[PHASE: profiles] [STEP: save] Failed to save connection profile: {:?}",
+            e
+        );
+        return Ok(ApiResponse::fail(e.to_string()));
+    }
+    list_connection_profiles(
+        secrets,
+        Some(ConnectionProfileRequest {
+            kind,
+            name: String::new(),
+        }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_connection_profile(
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<ConnectionProfileRequest>,
+) -> Result<ApiResponse<Vec<ConnectionProfile>>, String> {
+    let Some(req) = payload else {
+        return Ok(ApiResponse::fail("Invalid request."));
+    };
+    if let Err(e) = connection_profiles::delete(req.kind, &req.name) {
+        error!(
+            "[PHASE: profiles] [STEP: delete] Failed to delete connection profile: {:?}",
+            e
+        );
+        return Ok(ApiResponse::fail(e.to_string()));
+    }
+    list_connection_profiles(secrets, Some(req)).await
+}
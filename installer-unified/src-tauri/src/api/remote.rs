@@ -0,0 +1,34 @@
+// Check server on the Destination page of a remote install (see `installation::remote`).
+
+use crate::installation::remote::{self, RemoteCheck, RemoteTarget};
+use crate::models::responses::ApiResponse;
+
+use log::{info, warn};
+
+#[tauri::command]
+pub async fn check_remote_target(
+    payload: Option<RemoteTarget>,
+) -> Result<ApiResponse<RemoteCheck>, String> {
+    let Some(target) = payload else {
+        return Ok(ApiResponse::fail("Invalid request."));
+    };
+    match remote::check(&target).await {
+        Ok(report) => {
+            info!(
+                "[PHASE: remote] [STEP: check] {} checked (trusted={}, ready={})",
+                target.label(),
+                report.trusted,
+                report.ready
+            );
+            Ok(ApiResponse::ok(report))
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: remote] [STEP: check] {} could not be checked: {:#}",
+                target.label(),
+                e
+            );
+            Ok(ApiResponse::fail(format!("{:#}", e)))
+        }
+    }
+}
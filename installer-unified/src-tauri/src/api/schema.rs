@@ -3,7 +3,7 @@
 
 use crate::database::connection::DatabaseConnection;
 use crate::database::platform_db::PlatformDbAdapter;
-use crate::database::schema_verifier::SchemaVerifier;
+use crate::database::schema_verifier::{self, SchemaVerificationResult, SchemaVerifier};
 use crate::licensing::token as token_verifier;
 use crate::models::requests::{VerifyAllRequest, VerifySchemaRequest};
 use crate::models::responses::{
@@ -11,12 +11,12 @@ use crate::models::responses::{
 };
 use crate::models::state::AppState;
 use crate::security::secret_protector::SecretProtector;
+use crate::utils::retry;
 
-use log::info;
+use log::{info, warn};
 use std::sync::Arc;
 use tauri::State;
-use tokio::time::{timeout, Duration};
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tokio::time::timeout;
 use tokio_retry::RetryIf;
 
 #[tauri::command]
@@ -30,6 +30,7 @@ pub async fn verify_schema(
     let req = payload.unwrap_or(VerifySchemaRequest {
         engine: "sqlserver".to_string(),
         connection_string: None,
+        repair: false,
     });
 
     let (engine, conn_str) = match resolve_engine_and_conn_str(
@@ -54,8 +55,8 @@ pub async fn verify_schema(
 
     // Ensure core tables exist (uses our ported SchemaVerifier expectations)
     let verifier = SchemaVerifier::new(conn.clone());
-    let results = match verifier.verify_all_schemas().await {
-        Ok(r) => r,
+    let (res, report_path) = match run_verification(&verifier, req.repair).await {
+        Ok(v) => v,
         Err(e) => {
             return Ok(ApiResponse::ok(VerifySchemaResponse {
                 is_valid: false,
@@ -67,72 +68,44 @@ pub async fn verify_schema(
                 missing_indexes: vec![],
                 type_mismatches: vec![],
                 nullability_mismatches: vec![],
+                repairs_applied: vec![],
+                repair_errors: vec![],
+                report_path: None,
             }))
         }
     };
+    let response = to_schema_response(&res, report_path);
 
-    // We only verify cadalytix_config right now.
-    let (_, res) = results.into_iter().next().unwrap_or_else(|| {
-        (
-            "cadalytix_config".to_string(),
-            crate::database::schema_verifier::SchemaVerificationResult {
-                valid: false,
-                missing_tables: vec!["<no result>".to_string()],
-                missing_columns: vec![],
-                errors: vec!["No schema verification result returned".to_string()],
-            },
-        )
-    });
-
-    let missing_tables = res
-        .missing_tables
-        .iter()
-        .map(|t| format!("cadalytix_config.{}", t))
-        .collect::<Vec<_>>();
-    let missing_columns = res
-        .missing_columns
-        .iter()
-        .map(|(t, c)| format!("cadalytix_config.{}.{}", t, c))
-        .collect::<Vec<_>>();
-
-    let total_issues = (missing_tables.len() + missing_columns.len()) as i32;
-
-    let summary = if res.valid {
-        "Schema verification passed. All expected objects exist and match the manifest.".to_string()
-    } else {
-        format!(
-            "Schema verification failed: {} missing table(s), {} missing column(s).",
-            missing_tables.len(),
-            missing_columns.len()
-        )
-    };
+    let platform_db = PlatformDbAdapter::new(conn, Arc::clone(&secrets));
+    if !res.repairs_applied.is_empty() {
+        let _ = platform_db
+            .log_setup_event(
+                "schema.repair",
+                &format!(
+                    "Applied {} additive schema fix(es).",
+                    res.repairs_applied.len()
+                ),
+                Some("installer"),
+                None,
+            )
+            .await;
+    }
 
     // Best-effort: record an audit event (safe; no secrets).
-    let platform_db = PlatformDbAdapter::new(conn, Arc::clone(&secrets));
     let _ = platform_db
         .log_setup_event(
-            if res.valid {
+            if response.is_valid {
                 "schema.verify.pass"
             } else {
                 "schema.verify.fail"
             },
-            &summary,
+            &response.summary,
             Some("installer"),
             None,
         )
         .await;
 
-    Ok(ApiResponse::ok(VerifySchemaResponse {
-        is_valid: res.valid,
-        summary,
-        total_issues,
-        missing_schemas: vec![],
-        missing_tables,
-        missing_columns,
-        missing_indexes: vec![],
-        type_mismatches: vec![],
-        nullability_mismatches: vec![],
-    }))
+    Ok(ApiResponse::ok(response))
 }
 
 #[tauri::command]
@@ -148,6 +121,7 @@ pub async fn verify_all_schemas(
         call_data_connection_string: None,
         source_object_name: None,
         engine: "sqlserver".to_string(),
+        repair: false,
     });
 
     let (engine, conn_str) = match resolve_engine_and_conn_str(
@@ -249,41 +223,10 @@ pub async fn verify_all_schemas(
 
     // Schema verification
     let schema_verifier = SchemaVerifier::new(conn.clone());
-    let schema_results = schema_verifier.verify_all_schemas().await.ok();
-    let schema_verification = schema_results.and_then(|mut v| v.pop()).map(|(_, r)| {
-        let missing_tables = r
-            .missing_tables
-            .iter()
-            .map(|t| format!("cadalytix_config.{}", t))
-            .collect::<Vec<_>>();
-        let missing_columns = r
-            .missing_columns
-            .iter()
-            .map(|(t, c)| format!("cadalytix_config.{}.{}", t, c))
-            .collect::<Vec<_>>();
-        let total_issues = (missing_tables.len() + missing_columns.len()) as i32;
-        let summary = if r.valid {
-            "Schema verification passed. All expected objects exist and match the manifest."
-                .to_string()
-        } else {
-            format!(
-                "Schema verification failed: {} missing table(s), {} missing column(s).",
-                missing_tables.len(),
-                missing_columns.len()
-            )
-        };
-        VerifySchemaResponse {
-            is_valid: r.valid,
-            summary,
-            total_issues,
-            missing_schemas: vec![],
-            missing_tables,
-            missing_columns,
-            missing_indexes: vec![],
-            type_mismatches: vec![],
-            nullability_mismatches: vec![],
-        }
-    });
+    let schema_verification = run_verification(&schema_verifier, req.repair)
+        .await
+        .ok()
+        .map(|(r, report_path)| to_schema_response(&r, report_path));
 
     let schema_valid = schema_verification
         .as_ref()
@@ -325,6 +268,117 @@ pub async fn verify_all_schemas(
     }))
 }
 
+/// Verify (or repair, then verify) `cadalytix_config` and write the diff artifact.
+async fn run_verification(
+    verifier: &SchemaVerifier,
+    repair: bool,
+) -> anyhow::Result<(SchemaVerificationResult, Option<String>)> {
+    let results = if repair {
+        verifier.repair_all_schemas().await?
+    } else {
+        verifier.verify_all_schemas().await?
+    };
+    let report_path = match schema_verifier::write_diff_report(&results).await {
+        Ok(p) => Some(p.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!(
+                "[PHASE: schema_verification] [STEP: report] Failed to write schema diff report: {}",
+                e
+            );
+            None
+        }
+    };
+
+    // We only verify cadalytix_config right now.
+    let res = results
+        .into_iter()
+        .next()
+        .map(|(_, r)| r)
+        .unwrap_or_else(|| SchemaVerificationResult {
+            missing_tables: vec!["<no result>".to_string()],
+            errors: vec!["No schema verification result returned".to_string()],
+            ..Default::default()
+        });
+    Ok((res, report_path))
+}
+
+fn to_schema_response(
+    res: &SchemaVerificationResult,
+    report_path: Option<String>,
+) -> VerifySchemaResponse {
+    let missing_tables = res
+        .missing_tables
+        .iter()
+        .map(|t| format!("cadalytix_config.{}", t))
+        .collect::<Vec<_>>();
+    let missing_columns = res
+        .missing_columns
+        .iter()
+        .map(|(t, c)| format!("cadalytix_config.{}.{}", t, c))
+        .collect::<Vec<_>>();
+    let missing_indexes = res
+        .missing_indexes
+        .iter()
+        .map(|ix| {
+            format!(
+                "cadalytix_config.{} ({}){}",
+                ix.table,
+                ix.columns.join(", "),
+                if ix.unique { " UNIQUE" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>();
+    let type_mismatches = res
+        .type_mismatches
+        .iter()
+        .map(|m| {
+            format!(
+                "cadalytix_config.{}.{}: expected {}, found {}",
+                m.table, m.column, m.expected, m.actual
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let total_issues = (missing_tables.len()
+        + missing_columns.len()
+        + missing_indexes.len()
+        + type_mismatches.len()) as i32;
+
+    let mut summary = if res.valid {
+        "Schema verification passed. All expected objects exist and match the manifest.".to_string()
+    } else {
+        format!(
+            "Schema verification failed: {} missing table(s), {} missing column(s), {} missing index(es), {} type mismatch(es).",
+            missing_tables.len(),
+            missing_columns.len(),
+            missing_indexes.len(),
+            type_mismatches.len()
+        )
+    };
+    if !res.repairs_applied.is_empty() || !res.errors.is_empty() {
+        summary.push_str(&format!(
+            " Repair applied {} fix(es), {} failed.",
+            res.repairs_applied.len(),
+            res.errors.len()
+        ));
+    }
+
+    VerifySchemaResponse {
+        is_valid: res.valid,
+        summary,
+        total_issues,
+        missing_schemas: vec![],
+        missing_tables,
+        missing_columns,
+        missing_indexes,
+        type_mismatches,
+        nullability_mismatches: vec![],
+        repairs_applied: res.repairs_applied.clone(),
+        repair_errors: res.errors.clone(),
+        report_path,
+    }
+}
+
 async fn resolve_engine_and_conn_str(
     app_state: &AppState,
     engine_hint: &str,
@@ -350,14 +404,14 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         let timed = match engine {
             "postgres" => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::postgres(conn_str),
                 )
                 .await
             }
             _ => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::sql_server(conn_str),
                 )
                 .await
@@ -367,11 +421,7 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         inner
     };
 
-    let retry_strategy = ExponentialBackoff::from_millis(100)
-        .factor(2)
-        .max_delay(Duration::from_secs(2))
-        .take(3)
-        .map(jitter);
+    let retry_strategy = retry::policy().backoff();
 
     RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
         let msg = e.to_string().to_ascii_lowercase();
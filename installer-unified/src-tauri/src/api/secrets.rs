@@ -0,0 +1,69 @@
+// Secret maintenance endpoints (master key rotation).
+
+use crate::api::installer::connect_with_retry;
+use crate::models::requests::RotateSecretsRequest;
+use crate::models::responses::{ApiResponse, RotateSecretsResponse};
+use crate::models::state::AppState;
+use crate::security::rotation::{self, RotationReport};
+use crate::security::secret_protector::SecretProtector;
+
+use log::{error, info};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn rotate_secrets(
+    app_state: State<'_, AppState>,
+    secrets: State<'_, Arc<SecretProtector>>,
+    payload: Option<RotateSecretsRequest>,
+) -> Result<ApiResponse<RotateSecretsResponse>, String> {
+    let req = payload.unwrap_or_default();
+    info!(
+        "[PHASE: security] [STEP: rotate_secrets] rotate_secrets requested (files_only={})",
+        req.files_only
+    );
+
+    let db = match app_state.get_config_db().await {
+        Some((engine, _ver, config_cs)) if !req.files_only => {
+            match connect_with_retry(engine, config_cs).await {
+                Ok(conn) => Some(conn),
+                Err(e) => {
+                    error!(
+                        "[PHASE: security] [STEP: rotate_secrets] Config DB unavailable: {:?}",
+                        e
+                    );
+                    return Ok(ApiResponse::fail(
+                        "Cannot rotate secrets: the configuration database is unavailable.",
+                    ));
+                }
+            }
+        }
+        None if !req.files_only => {
+            return Ok(ApiResponse::fail(
+                "Connect to the configuration database before rotating secrets, or rotate files only if it was never created.",
+            ));
+        }
+        _ => None,
+    };
+
+    match rotation::rotate_secrets(&secrets, db).await {
+        Ok(report) => Ok(ApiResponse::ok(to_response(report))),
+        Err(e) => {
+            error!(
+                "[PHASE: security] [STEP: rotate_secrets] Rotation failed: {:?}",
+                e
+            );
+            Ok(ApiResponse::fail(format!("Secret rotation failed: {}", e)))
+        }
+    }
+}
+
+fn to_response(report: RotationReport) -> RotateSecretsResponse {
+    RotateSecretsResponse {
+        key_store: report.key_store,
+        backup_key_path: report.backup_key_path,
+        database_values_rotated: report.database_values_rotated,
+        files_rotated: report.files_rotated,
+        pinned_keys: report.pinned_keys,
+    }
+}
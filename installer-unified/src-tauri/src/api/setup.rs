@@ -1,11 +1,13 @@
 // Setup API endpoints
 // Ported from C# InstallerSetupEndpoints.cs (installer-host plumbing/orchestration)
 
+use crate::api::installer::resolve_migrations_paths;
 use crate::database::connection::DatabaseConnection;
 use crate::database::migrations::MigrationRunner;
 use crate::database::platform_db::PlatformDbAdapter;
 use crate::database::schema_mapping;
 use crate::database::schema_verifier::SchemaVerifier;
+use crate::installation::health::{self, HealthLevel};
 use crate::models::requests::{
     AuthMode, CheckpointSaveRequest, CommitRequest, InitRequest, SetupPlanRequest,
     SetupVerifyRequest,
@@ -19,19 +21,18 @@ use crate::models::responses::{
 use crate::models::state::AppState;
 use crate::security::secret_protector::SecretProtector;
 use crate::utils::logging::mask_connection_string;
-use crate::utils::path_resolver::resolve_deployment_folder;
+use crate::utils::retry;
 use crate::utils::validation::{validate_and_quote_sql_server_object, validate_connection_string};
 
 use futures::TryStreamExt;
 use log::{info, warn};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 use tauri::async_runtime;
 use tauri::State;
-use tokio::time::{timeout, Duration};
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tokio::time::timeout;
 use tokio_retry::RetryIf;
 use uuid::Uuid;
 
@@ -576,11 +577,74 @@ pub fn apply_setup(
             .actions_performed
             .push(format!("Wrote {} instance setting(s)", settings.len()));
 
+        // Installed services read instance settings at start-up.
+        #[cfg(windows)]
+        if !restart_windows_services(&mut response).await {
+            return Ok(ApiResponse::ok(response));
+        }
+
         response.success = true;
         Ok(ApiResponse::ok(response))
     })
 }
 
+/// Stop, start and status-check each installed CADalytix Windows service so it runs with the
+/// settings just written. Services that are not installed are skipped. Returns false (with
+/// `response.errors` filled) when a service does not come back.
+#[cfg(windows)]
+async fn restart_windows_services(response: &mut SetupApplyResponse) -> bool {
+    use crate::installation::service::{
+        is_windows_service_running, start_windows_service, stop_windows_service,
+        windows_service_state, WINDOWS_BACKGROUND_SERVICES, WINDOWS_MAIN_SERVICE,
+    };
+
+    let services = std::iter::once(&WINDOWS_MAIN_SERVICE).chain(WINDOWS_BACKGROUND_SERVICES);
+    for spec in services {
+        let name = spec.service_name();
+        match windows_service_state(&name).await {
+            Ok(None) => continue,
+            Ok(Some(_)) => {}
+            Err(e) => {
+                response
+                    .warnings
+                    .push(format!("Could not query Windows service '{}': {}", name, e));
+                continue;
+            }
+        }
+        let restarted = async {
+            stop_windows_service(&name).await?;
+            start_windows_service(&name).await?;
+            if !is_windows_service_running(&name).await? {
+                anyhow::bail!("not running after start");
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+        match restarted.await {
+            Ok(()) => {
+                info!(
+                    "[PHASE: setup] [STEP: apply] Restarted Windows service (service_name={})",
+                    name
+                );
+                response
+                    .actions_performed
+                    .push(format!("Restarted Windows service '{}'", name));
+            }
+            Err(e) => {
+                warn!(
+                    "[PHASE: setup] [STEP: apply] Windows service restart failed (service_name={}): {:#}",
+                    name, e
+                );
+                response.errors.push(format!(
+                    "Windows service '{}' failed to restart: {:#}",
+                    name, e
+                ));
+                return false;
+            }
+        }
+    }
+    true
+}
+
 #[tauri::command]
 pub fn commit_setup(
     app_state: State<'_, AppState>,
@@ -836,6 +900,7 @@ pub fn verify_setup(
         let Some(config_conn_str) = config_conn_str else {
             return Ok(ApiResponse::ok(SetupVerifyResponse {
                 success: false,
+                health: HealthLevel::Red.as_str().to_string(),
                 checks,
                 errors: vec!["Config database connection string is not available.".to_string()],
             }));
@@ -854,6 +919,7 @@ pub fn verify_setup(
                 });
                 return Ok(ApiResponse::ok(SetupVerifyResponse {
                     success: false,
+                    health: HealthLevel::Red.as_str().to_string(),
                     checks,
                     errors: vec!["Unable to connect to config database.".to_string()],
                 }));
@@ -1024,6 +1090,67 @@ pub fn verify_setup(
             duration_ms: 0,
         });
 
+        // Deployed files vs. the checksums recorded in the install manifest.
+        let manifest_path = platform_db
+            .get_setting("Setup:InstallManifestPath")
+            .await
+            .ok()
+            .flatten()
+            .filter(|p| !p.trim().is_empty());
+        let (integrity_status, integrity_message) = match manifest_path {
+            None => (
+                "skip",
+                "No install manifest recorded (Setup:InstallManifestPath).".to_string(),
+            ),
+            Some(path) => {
+                match crate::installation::payload_manifest::install_manifest_drift(Path::new(
+                    &path,
+                ))
+                .await
+                {
+                    Ok(drift) if drift.is_empty() => (
+                        "pass",
+                        "All deployed files match the install manifest.".to_string(),
+                    ),
+                    Ok(drift) => (
+                        "fail",
+                        format!(
+                            "{} deployed file(s) differ from the install manifest: {}",
+                            drift.len(),
+                            drift.join(", ")
+                        ),
+                    ),
+                    Err(e) => ("fail", format!("Install manifest check failed: {}", e)),
+                }
+            }
+        };
+        if integrity_status == "fail" {
+            failures.push("payload_integrity".to_string());
+        }
+        checks.push(SetupVerifyCheckResult {
+            id: "payload_integrity".to_string(),
+            label: "Deployed files match the install manifest".to_string(),
+            status: integrity_status.to_string(),
+            message: integrity_message,
+            duration_ms: 0,
+        });
+
+        // Post-install health probes (DB round-trip, schema version, service, archive, web).
+        let settings = platform_db.get_all_settings().await.unwrap_or_default();
+        for probe in health::run_health_checks(&conn, &engine, &settings).await {
+            if probe.level == HealthLevel::Red {
+                failures.push(probe.id.to_string());
+            }
+            checks.push(SetupVerifyCheckResult {
+                id: probe.id.to_string(),
+                label: probe.label.to_string(),
+                status: probe.level.status().to_string(),
+                message: probe.message,
+                duration_ms: probe.duration_ms,
+            });
+        }
+        let health = health::overall_level(checks.iter().map(|c| c.status.as_str()));
+
         let success = failures.is_empty();
         let mut errors = Vec::new();
         if !success {
@@ -1032,6 +1159,7 @@ pub fn verify_setup(
 
         Ok(ApiResponse::ok(SetupVerifyResponse {
             success,
+            health: health.as_str().to_string(),
             checks,
             errors,
         }))
@@ -1443,13 +1571,6 @@ pub fn get_support_bundle(
 // Helpers
 // =========================
 
-fn resolve_migrations_paths() -> anyhow::Result<(PathBuf, PathBuf)> {
-    let deployment = resolve_deployment_folder()?;
-    let migrations_path = deployment.join("installer").join("migrations");
-    let manifest_path = migrations_path.join("manifest_versioned.json");
-    Ok((manifest_path, migrations_path))
-}
-
 fn is_core_migration(name: &str) -> bool {
     name.contains("_001_")
         || name.contains("_002_")
@@ -1474,14 +1595,14 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         let timed = match engine {
             "postgres" => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::postgres(conn_str),
                 )
                 .await
             }
             _ => {
                 timeout(
-                    Duration::from_secs(20),
+                    retry::policy().attempt_timeout,
                     DatabaseConnection::sql_server(conn_str),
                 )
                 .await
@@ -1492,11 +1613,7 @@ async fn connect_with_retry(engine: &str, conn_str: &str) -> anyhow::Result<Data
         inner
     };
 
-    let retry_strategy = ExponentialBackoff::from_millis(100)
-        .factor(2)
-        .max_delay(Duration::from_secs(2))
-        .take(3)
-        .map(jitter);
+    let retry_strategy = retry::policy().backoff();
 
     RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
         let msg = e.to_string().to_ascii_lowercase();
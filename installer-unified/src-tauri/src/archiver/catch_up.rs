@@ -0,0 +1,535 @@
+//! Catch-up planner + `--archive-run-once` runner.
+//!
+//! `archive_one_month` handles a single month. The runner here reads the persisted archive
+//! policy (instance settings written at install time), computes every month older than the hot
+//! retention window that the ledger does not show as complete, and archives them oldest-first,
+//! one ledger entry per month. `--startup` runs honor `Archive:CatchUpOnStartup`; a run is
+//! capped at `Archive:CatchUpMaxMonthsPerRun` months (0 = no cap) so a long backlog drains over
+//! several runs instead of one very long one. `Archive:ThrottleMBps` caps archive write
+//! bandwidth (see `utils::throttle`).
+//!
+//! Purge runs only when `Archive:PurgeEnabled` is set and the run names the month with
+//! `--confirm-purge=YYYY-MM`; every other month is archived and its purge skipped (see `purge`).
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use futures::TryStreamExt;
+use log::{error, info};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api::installer::ProgressEmitter;
+use crate::database::connection::DatabaseConnection;
+use crate::database::platform_db::PlatformDbAdapter;
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+use crate::utils::throttle;
+
+use super::progress::ArchiveProgress;
+use super::purge::{ArchivePurgeConfig, HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN};
+use super::restore::{parse_month_key, HOT_DB_CONNECTION_ENV};
+use super::{
+    archive_one_month, read_ledger, ArchiveDestination, ArchiveFormat, ArchiveLedgerEntry,
+    ArchiveRunConfig,
+};
+
+/// Default cap on months archived by a single run.
+pub(crate) const DEFAULT_MAX_MONTHS_PER_RUN: u32 = 3;
+
+/// Arguments for `--archive-run-once`.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveRunOnceArgs {
+    /// Invoked at service startup: skip unless catch-up on startup is enabled.
+    pub startup: bool,
+    /// Overrides `Archive:CatchUpMaxMonthsPerRun` for this run.
+    pub max_months: Option<u32>,
+    /// Ledger file (defaults to `B2_archive_ledger.json` under the log folder).
+    pub ledger_path: Option<PathBuf>,
+    /// Month (YYYY-MM) whose hot rows may be purged by this run, with `Archive:PurgeEnabled`.
+    pub confirm_purge: Option<String>,
+}
+
+impl ArchiveRunOnceArgs {
+    /// Parse `--startup`, `--max-months=N`, `--ledger=<path>`, `--confirm-purge=YYYY-MM` from argv.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let value_of = |name: &str| {
+            args.iter()
+                .find_map(|a| a.strip_prefix(name).map(|v| v.trim().to_string()))
+                .filter(|v| !v.is_empty())
+        };
+        let max_months = match value_of("--max-months=") {
+            Some(v) => Some(
+                v.parse::<u32>()
+                    .map_err(|_| anyhow::anyhow!("Invalid --max-months '{}'", v))?,
+            ),
+            None => None,
+        };
+        let confirm_purge = match value_of("--confirm-purge=") {
+            Some(v) => Some(
+                parse_month_key(&v)
+                    .map_err(|_| {
+                        anyhow::anyhow!("Invalid --confirm-purge '{}' (expected YYYY-MM)", v)
+                    })?
+                    .format("%Y-%m")
+                    .to_string(),
+            ),
+            None => None,
+        };
+        Ok(Self {
+            startup: args.iter().any(|a| a == "--startup"),
+            max_months,
+            ledger_path: value_of("--ledger=").map(PathBuf::from),
+            confirm_purge,
+        })
+    }
+}
+
+/// Archive policy as persisted in instance settings (see `start_install`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CatchUpPolicy {
+    pub hot_retention_months: u32,
+    pub format: ArchiveFormat,
+    pub destination: String,
+    pub max_usage_gb: u32,
+    pub catch_up_on_startup: bool,
+    pub encrypt_archives: bool,
+    pub max_months_per_run: u32,
+    pub throttle_mbps: u32,
+    pub purge_enabled: bool,
+}
+
+impl CatchUpPolicy {
+    /// Missing/unparseable values fall back to the same defaults the wizard uses.
+    pub(crate) fn from_settings(settings: &HashMap<String, String>) -> Self {
+        let get = |k: &str| settings.get(k).map(|v| v.trim()).filter(|v| !v.is_empty());
+        let flag = |k: &str, default: bool| get(k).map(|v| v == "true").unwrap_or(default);
+        let num = |k: &str, default: u32| get(k).and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            hot_retention_months: num("Retention:HotMonths", 18),
+            format: get("Archive:Format")
+                .and_then(ArchiveFormat::parse)
+                .unwrap_or(ArchiveFormat::ZipNdjson),
+            destination: get("Archive:DestinationPath").unwrap_or("").to_string(),
+            max_usage_gb: num("Archive:MaxUsageGb", 0),
+            catch_up_on_startup: flag("Archive:CatchUpOnStartup", true),
+            encrypt_archives: flag("Archive:EncryptArchives", false),
+            max_months_per_run: num("Archive:CatchUpMaxMonthsPerRun", DEFAULT_MAX_MONTHS_PER_RUN),
+            throttle_mbps: num("Archive:ThrottleMBps", throttle::default_mbps()),
+            purge_enabled: flag("Archive:PurgeEnabled", false),
+        }
+    }
+
+    /// Purge gates for a run: the persisted switch plus the run's month confirmation.
+    pub(crate) fn purge_config(&self, args: &ArchiveRunOnceArgs) -> ArchivePurgeConfig {
+        ArchivePurgeConfig {
+            enabled: self.purge_enabled,
+            confirm_month: args.confirm_purge.clone(),
+        }
+    }
+}
+
+/// First month that is still hot: months strictly before this are eligible for archiving.
+pub(crate) fn retention_cutoff(today: NaiveDate, hot_retention_months: u32) -> Option<NaiveDate> {
+    today
+        .with_day(1)?
+        .checked_sub_months(Months::new(hot_retention_months))
+}
+
+/// Months in `[earliest, cutoff)` not yet complete in the ledger, oldest first, capped at
+/// `max_months` (0 = no cap).
+pub(crate) fn plan_catch_up_months(
+    earliest: NaiveDate,
+    cutoff: NaiveDate,
+    ledger: &BTreeMap<String, ArchiveLedgerEntry>,
+    max_months: u32,
+) -> Vec<NaiveDate> {
+    let mut plan = Vec::new();
+    let mut month = match earliest.with_day(1) {
+        Some(m) => m,
+        None => return plan,
+    };
+    while month < cutoff {
+        if max_months > 0 && plan.len() as u32 >= max_months {
+            break;
+        }
+        let key = month.format("%Y-%m").to_string();
+        let complete = ledger
+            .get(&key)
+            .map(|e| e.status == "complete")
+            .unwrap_or(false);
+        if !complete {
+            plan.push(month);
+        }
+        month = match month.checked_add_months(Months::new(1)) {
+            Some(m) => m,
+            None => break,
+        };
+    }
+    plan
+}
+
+/// Adds the month named by `--confirm-purge` when it is already archived and its purge is still
+/// pending or was skipped, so the run resumes that purge (`archive_one_month` skips the export for it).
+pub(crate) fn with_confirmed_purge(
+    mut plan: Vec<NaiveDate>,
+    ledger: &BTreeMap<String, ArchiveLedgerEntry>,
+    purge: &ArchivePurgeConfig,
+) -> Vec<NaiveDate> {
+    let Some(key) = purge.confirm_month.as_deref().filter(|_| purge.enabled) else {
+        return plan;
+    };
+    let unfinished = ledger
+        .get(key)
+        .map(|e| {
+            e.status == "complete"
+                && (e.purge_status == "pending" || e.purge_status.starts_with("skipped:"))
+        })
+        .unwrap_or(false);
+    if let (true, Ok(month)) = (unfinished, parse_month_key(key)) {
+        if !plan.contains(&month) {
+            plan.push(month);
+            plan.sort();
+        }
+    }
+    plan
+}
+
+pub async fn archive_run_once(args: ArchiveRunOnceArgs) -> Result<()> {
+    let conn_str = std::env::var(HOT_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "--archive-run-once requires the {} environment variable",
+                HOT_DB_CONNECTION_ENV
+            )
+        })?;
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    archive_catch_up(conn_str, &args, &correlation_id, None)
+        .await
+        .map(|_| ())
+}
+
+/// Run a catch-up against the given DB (settings + hot table), streaming progress to `emit`
+/// when provided. Returns the number of months archived.
+#[tracing::instrument(name = "archive", skip_all, fields(correlation_id = %correlation_id))]
+pub(crate) async fn archive_catch_up(
+    conn_str: String,
+    args: &ArchiveRunOnceArgs,
+    correlation_id: &str,
+    emit: Option<ProgressEmitter>,
+) -> Result<usize> {
+    let started = Instant::now();
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    let transcript_path = log_dir.join("B2_archive_run_once_transcript.log");
+
+    let mut transcript = String::new();
+    let mut push = |line: String| {
+        transcript.push_str(&line);
+        transcript.push('\n');
+    };
+
+    push("ARCHIVE_RUN_ONCE begin".to_string());
+    crate::utils::logging::set_log_context("correlationId", correlation_id);
+    let mut progress = ArchiveProgress::new(correlation_id, emit);
+    let result = run_catch_up(conn_str, args, &log_dir, &mut progress, &mut push).await;
+    progress.finish(result.is_ok());
+    crate::utils::logging::clear_log_context();
+    crate::utils::otel::flush();
+    match &result {
+        Ok(archived) => push(format!(
+            "ARCHIVE_RUN_ONCE end archived_months={} elapsed_ms={}",
+            archived,
+            started.elapsed().as_millis()
+        )),
+        Err(e) => push(format!(
+            "ARCHIVE_RUN_ONCE failed elapsed_ms={} error={}",
+            started.elapsed().as_millis(),
+            e
+        )),
+    }
+    push(format!("ExitCode={}", if result.is_ok() { 0 } else { 1 }));
+
+    tokio::fs::write(&transcript_path, transcript).await?;
+    info!(
+        "[PHASE: archive] [STEP: run_once] Wrote transcript to {:?}",
+        transcript_path
+    );
+
+    result
+}
+
+async fn run_catch_up(
+    conn_str: String,
+    args: &ArchiveRunOnceArgs,
+    log_dir: &std::path::Path,
+    progress: &mut ArchiveProgress,
+    push: &mut dyn FnMut(String),
+) -> Result<usize> {
+    progress.report(
+        "archive_plan",
+        0,
+        "Reading archive policy...".to_string(),
+        push,
+    );
+    let engine = crate::api::installer::guess_engine(&conn_str);
+    let conn = crate::api::installer::connect_with_retry(engine, conn_str).await?;
+
+    let secrets = Arc::new(SecretProtector::new(default_key_path(log_dir)));
+    let settings = PlatformDbAdapter::new(conn.clone(), secrets.clone())
+        .get_all_settings()
+        .await
+        .with_context(|| "Failed to read archive policy from instance settings")?;
+    let policy = CatchUpPolicy::from_settings(&settings);
+    push(format!(
+        "EVENT archive-policy hot_months={} format={} catch_up_on_startup={} encrypt={} max_months_per_run={} throttle_mbps={}",
+        policy.hot_retention_months,
+        policy.format.as_str(),
+        policy.catch_up_on_startup,
+        policy.encrypt_archives,
+        policy.max_months_per_run,
+        policy.throttle_mbps
+    ));
+
+    if args.startup && !policy.catch_up_on_startup {
+        push("EVENT archive-catch-up-skip reason=catch_up_on_startup_disabled".to_string());
+        progress.report(
+            "archive_done",
+            100,
+            "Catch-up on startup is disabled.".to_string(),
+            push,
+        );
+        return Ok(0);
+    }
+    if policy.destination.is_empty() {
+        anyhow::bail!("Archive destination is not configured (Archive:DestinationPath)");
+    }
+    let destination = ArchiveDestination::parse(&policy.destination)?;
+
+    let today = Utc::now().date_naive();
+    let cutoff = retention_cutoff(today, policy.hot_retention_months)
+        .ok_or_else(|| anyhow::anyhow!("Invalid hot retention window"))?;
+    let Some(earliest) = earliest_hot_month(&conn).await? else {
+        push("EVENT archive-catch-up-skip reason=no_hot_rows".to_string());
+        progress.report(
+            "archive_done",
+            100,
+            "No call data to archive.".to_string(),
+            push,
+        );
+        return Ok(0);
+    };
+
+    let ledger_path = args
+        .ledger_path
+        .clone()
+        .unwrap_or_else(|| log_dir.join("B2_archive_ledger.json"));
+    let ledger = read_ledger(&ledger_path).await?;
+    let max_months = args.max_months.unwrap_or(policy.max_months_per_run);
+    let plan = with_confirmed_purge(
+        plan_catch_up_months(earliest, cutoff, &ledger, max_months),
+        &ledger,
+        &policy.purge_config(args),
+    );
+    push(format!(
+        "EVENT archive-catch-up-plan earliest={} cutoff={} max_months={} months={}",
+        earliest.format("%Y-%m"),
+        cutoff.format("%Y-%m"),
+        max_months,
+        plan.iter()
+            .map(|m| m.format("%Y-%m").to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    ));
+
+    if plan.is_empty() {
+        progress.report(
+            "archive_done",
+            100,
+            "All eligible months are already archived.".to_string(),
+            push,
+        );
+    }
+    for (idx, month) in plan.iter().enumerate() {
+        progress.set_month(idx, plan.len());
+        let cfg = ArchiveRunConfig {
+            correlation_id: progress.correlation_id().to_string(),
+            month: *month,
+            format: policy.format,
+            destination: destination.clone(),
+            max_usage_gb: policy.max_usage_gb,
+            // The ingestion watermark gate is still a placeholder; months here are already
+            // outside the hot window, which is the condition the watermark will formalize.
+            allow_without_watermark: true,
+            dry_run: false,
+            purge: policy.purge_config(args),
+            encryption: policy.encrypt_archives.then(|| secrets.clone()),
+            throttle_mbps: policy.throttle_mbps,
+        };
+        // Strictly in order: stop at the first failure so the ledger never has gaps.
+        if let Err(e) = archive_one_month(&cfg, Some(&conn), &ledger_path, progress, push).await {
+            error!(
+                "[PHASE: archive] [STEP: run_once] Archive failed (month={}, error={:?})",
+                month.format("%Y-%m"),
+                e
+            );
+            return Err(e);
+        }
+        push(format!(
+            "EVENT archive-catch-up-progress done={} total={}",
+            idx + 1,
+            plan.len()
+        ));
+    }
+    Ok(plan.len())
+}
+
+/// First month with hot rows (`None` when the hot table is empty).
+async fn earliest_hot_month(conn: &DatabaseConnection) -> Result<Option<NaiveDate>> {
+    let month: Option<String> = match conn {
+        DatabaseConnection::Postgres(pool) => {
+            let sql = format!(
+                "SELECT to_char(MIN({}), 'YYYY-MM') FROM {}.{}",
+                HOT_CALLS_TS_COLUMN, HOT_CALLS_SCHEMA, HOT_CALLS_TABLE
+            );
+            sqlx::query_scalar(&sql)
+                .fetch_one(pool)
+                .await
+                .with_context(|| "Failed to read earliest hot month (PostgreSQL)")?
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+            let sql = format!(
+                "SELECT CONVERT(char(7), MIN([{}]), 126) FROM [{}].[{}]",
+                HOT_CALLS_TS_COLUMN, HOT_CALLS_SCHEMA, HOT_CALLS_TABLE
+            );
+            let mut stream = client.simple_query(sql).await?;
+            let mut out = None;
+            while let Some(item) = stream.try_next().await? {
+                if let tiberius::QueryItem::Row(row) = item {
+                    out = row.get::<&str, _>(0).map(|s| s.to_string());
+                }
+            }
+            out
+        }
+    };
+    month.map(|m| parse_month_key(&m)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archiver::purge::{purge_gate, PurgeSkipReason};
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn complete(month: &str) -> ArchiveLedgerEntry {
+        serde_json::from_value(serde_json::json!({
+            "month": month, "status": "complete", "format": "zip+ndjson", "rowCount": 1,
+            "minTsUtc": "", "maxTsUtc": "", "zipSha256": "", "zipBytes": 0, "createdUtc": ""
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn cutoff_keeps_current_month_plus_retention_hot() {
+        assert_eq!(
+            retention_cutoff(ymd(2026, 10, 16), 18),
+            Some(ymd(2025, 4, 1))
+        );
+        assert_eq!(
+            retention_cutoff(ymd(2026, 1, 31), 12),
+            Some(ymd(2025, 1, 1))
+        );
+    }
+
+    #[test]
+    fn plan_skips_completed_months_and_respects_cap() {
+        let mut ledger = BTreeMap::new();
+        ledger.insert("2024-02".to_string(), complete("2024-02"));
+
+        let all = plan_catch_up_months(ymd(2024, 1, 15), ymd(2024, 6, 1), &ledger, 0);
+        let keys: Vec<String> = all.iter().map(|m| m.format("%Y-%m").to_string()).collect();
+        assert_eq!(keys, ["2024-01", "2024-03", "2024-04", "2024-05"]);
+
+        let capped = plan_catch_up_months(ymd(2024, 1, 1), ymd(2024, 6, 1), &ledger, 2);
+        assert_eq!(capped, vec![ymd(2024, 1, 1), ymd(2024, 3, 1)]);
+
+        assert!(plan_catch_up_months(ymd(2024, 6, 1), ymd(2024, 6, 1), &ledger, 0).is_empty());
+    }
+
+    #[test]
+    fn policy_reads_persisted_settings_with_defaults() {
+        let defaults = CatchUpPolicy::from_settings(&HashMap::new());
+        assert_eq!(defaults.hot_retention_months, 18);
+        assert!(defaults.catch_up_on_startup);
+        assert_eq!(defaults.max_months_per_run, DEFAULT_MAX_MONTHS_PER_RUN);
+
+        let settings: HashMap<String, String> = [
+            ("Retention:HotMonths", "12"),
+            ("Archive:Format", "zip+csv"),
+            ("Archive:CatchUpOnStartup", "false"),
+            ("Archive:EncryptArchives", "true"),
+            ("Archive:CatchUpMaxMonthsPerRun", "0"),
+            ("Archive:ThrottleMBps", "40"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let p = CatchUpPolicy::from_settings(&settings);
+        assert_eq!(p.hot_retention_months, 12);
+        assert_eq!(p.format, ArchiveFormat::ZipCsv);
+        assert!(!p.catch_up_on_startup);
+        assert!(p.encrypt_archives);
+        assert_eq!(p.max_months_per_run, 0);
+        assert_eq!(p.throttle_mbps, 40);
+        assert!(!p.purge_enabled);
+    }
+
+    #[test]
+    fn confirm_purge_flag_and_setting_open_the_purge_gate() {
+        let args: Vec<String> = ["--archive-run-once", "--confirm-purge=2024-01"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let args = ArchiveRunOnceArgs::from_args(&args).unwrap();
+        assert_eq!(args.confirm_purge.as_deref(), Some("2024-01"));
+        assert!(ArchiveRunOnceArgs::from_args(&["--confirm-purge=January".to_string()]).is_err());
+
+        let off = CatchUpPolicy::from_settings(&HashMap::new()).purge_config(&args);
+        assert_eq!(
+            purge_gate(&off, "2024-01", false, true),
+            Some(PurgeSkipReason::Disabled)
+        );
+        let settings: HashMap<String, String> =
+            [("Archive:PurgeEnabled".to_string(), "true".to_string())].into();
+        let on = CatchUpPolicy::from_settings(&settings).purge_config(&args);
+        assert_eq!(purge_gate(&on, "2024-01", false, true), None);
+        assert_eq!(
+            purge_gate(&on, "2024-02", false, true),
+            Some(PurgeSkipReason::NotConfirmed)
+        );
+
+        // An archived month whose purge was skipped runs again to finish it.
+        let mut ledger = BTreeMap::new();
+        let mut archived = complete("2024-01");
+        archived.purge_status = "skipped:not_confirmed".to_string();
+        ledger.insert("2024-01".to_string(), archived);
+        let plan = plan_catch_up_months(ymd(2024, 1, 1), ymd(2024, 3, 1), &ledger, 0);
+        assert_eq!(plan, vec![ymd(2024, 2, 1)]);
+        assert_eq!(
+            with_confirmed_purge(plan.clone(), &ledger, &on),
+            vec![ymd(2024, 1, 1), ymd(2024, 2, 1)]
+        );
+        assert_eq!(
+            with_confirmed_purge(plan, &ledger, &off),
+            vec![ymd(2024, 2, 1)]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Archive encryption at rest.
+//!
+//! Encrypted archives are the ZIP payload sealed with AES-256-GCM under a key derived from the
+//! SecretProtector master key (`SecretProtector::derive_key("archive")`). Archives are sealed in
+//! fixed-size chunks so months of any size encrypt with bounded memory. File layout:
+//!
+//! `MAGIC_V2 (8 bytes) || nonce prefix (7 bytes) || frame*`
+//! `frame = last flag (1 byte) || ciphertext length (u32 BE) || ciphertext+tag`
+//!
+//! Each chunk's nonce is `prefix || chunk counter (u32 BE) || last flag`, so reordered, dropped or
+//! truncated chunks fail authentication.
+//!
+//! The ledger records a fingerprint of the derived key (never the key) so restore/verify can
+//! fail fast with a clear message when the master key on this machine is not the one used to
+//! write the archive.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::Result;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::io::{Read, Write};
+
+use crate::security::secret_protector::SecretProtector;
+
+const MAGIC_V2: &[u8; 8] = b"CDXARCE2";
+const NONCE_BYTES: usize = 12;
+const NONCE_PREFIX_BYTES: usize = 7;
+const TAG_BYTES: usize = 16;
+/// Plaintext bytes per sealed chunk.
+const CHUNK_BYTES: usize = 1024 * 1024;
+pub(crate) const KEY_PURPOSE: &str = "archive";
+
+/// Derived archive key plus its public fingerprint.
+pub(crate) struct ArchiveKey {
+    key: [u8; 32],
+    fingerprint: String,
+}
+
+impl ArchiveKey {
+    pub(crate) async fn load(secrets: &SecretProtector) -> Result<Self> {
+        Ok(Self::from_bytes(secrets.derive_key(KEY_PURPOSE).await?))
+    }
+
+    fn from_bytes(key: [u8; 32]) -> Self {
+        Self {
+            key,
+            fingerprint: key_fingerprint(&key),
+        }
+    }
+
+    pub(crate) fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm> {
+        Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|_| anyhow::anyhow!("Internal error: invalid AES-256 key length"))
+    }
+
+    /// Seal `input` chunk by chunk into `output`; returns the number of bytes written.
+    pub(crate) fn encrypt_stream(
+        &self,
+        input: &mut dyn Read,
+        output: &mut dyn Write,
+    ) -> Result<u64> {
+        let cipher = self.cipher()?;
+        let mut prefix = [0u8; NONCE_PREFIX_BYTES];
+        SystemRandom::new()
+            .fill(&mut prefix)
+            .map_err(|_| anyhow::anyhow!("Failed to generate nonce"))?;
+        output.write_all(MAGIC_V2)?;
+        output.write_all(&prefix)?;
+        let mut written = (MAGIC_V2.len() + NONCE_PREFIX_BYTES) as u64;
+
+        // One chunk of lookahead tells us which chunk is last without knowing the input length.
+        let mut current = read_chunk(input)?;
+        let mut counter: u32 = 0;
+        loop {
+            let next = if current.len() == CHUNK_BYTES {
+                read_chunk(input)?
+            } else {
+                Vec::new()
+            };
+            let last = next.is_empty();
+            let ciphertext = cipher
+                .encrypt(
+                    Nonce::from_slice(&chunk_nonce(&prefix, counter, last)),
+                    current.as_slice(),
+                )
+                .map_err(|_| anyhow::anyhow!("Archive encryption failed"))?;
+            output.write_all(&[last as u8])?;
+            output.write_all(&(ciphertext.len() as u32).to_be_bytes())?;
+            output.write_all(&ciphertext)?;
+            written += (1 + 4 + ciphertext.len()) as u64;
+            if last {
+                break;
+            }
+            counter = counter
+                .checked_add(1)
+                .ok_or_else(|| anyhow::anyhow!("Archive is too large to encrypt"))?;
+            current = next;
+        }
+        output.flush()?;
+        Ok(written)
+    }
+
+    /// Open a chunked archive from `input` into `output`; returns the plaintext length.
+    pub(crate) fn decrypt_stream(
+        &self,
+        input: &mut dyn Read,
+        output: &mut dyn Write,
+    ) -> Result<u64> {
+        let mut magic = [0u8; 8];
+        input
+            .read_exact(&mut magic)
+            .map_err(|_| anyhow::anyhow!("Archive is not in the encrypted archive format"))?;
+        if &magic != MAGIC_V2 {
+            anyhow::bail!("Archive is not in the chunked encrypted archive format");
+        }
+        let mut prefix = [0u8; NONCE_PREFIX_BYTES];
+        input
+            .read_exact(&mut prefix)
+            .map_err(|_| anyhow::anyhow!("Encrypted archive is truncated"))?;
+
+        let cipher = self.cipher()?;
+        let mut plaintext_len = 0u64;
+        let mut counter: u32 = 0;
+        loop {
+            let mut head = [0u8; 5];
+            input
+                .read_exact(&mut head)
+                .map_err(|_| anyhow::anyhow!("Encrypted archive is truncated"))?;
+            let last = match head[0] {
+                0 => false,
+                1 => true,
+                _ => anyhow::bail!("Encrypted archive is corrupted (bad chunk header)"),
+            };
+            let len = u32::from_be_bytes([head[1], head[2], head[3], head[4]]) as usize;
+            if !(TAG_BYTES..=CHUNK_BYTES + TAG_BYTES).contains(&len) {
+                anyhow::bail!("Encrypted archive is corrupted (bad chunk length)");
+            }
+            let mut ciphertext = vec![0u8; len];
+            input
+                .read_exact(&mut ciphertext)
+                .map_err(|_| anyhow::anyhow!("Encrypted archive is truncated"))?;
+            let chunk = cipher
+                .decrypt(
+                    Nonce::from_slice(&chunk_nonce(&prefix, counter, last)),
+                    ciphertext.as_slice(),
+                )
+                .map_err(|_| {
+                    anyhow::anyhow!("Archive decryption failed (wrong key or corrupted file)")
+                })?;
+            output.write_all(&chunk)?;
+            plaintext_len += chunk.len() as u64;
+            if last {
+                break;
+            }
+            counter = counter
+                .checked_add(1)
+                .ok_or_else(|| anyhow::anyhow!("Encrypted archive is corrupted"))?;
+        }
+        if input.read(&mut [0u8; 1])? != 0 {
+            anyhow::bail!("Encrypted archive is corrupted (data after final chunk)");
+        }
+        output.flush()?;
+        Ok(plaintext_len)
+    }
+
+    /// Fail with a clear message when the ledger was written under a different key.
+    pub(crate) fn ensure_matches(&self, expected_fingerprint: &str) -> Result<()> {
+        if !expected_fingerprint.is_empty() && expected_fingerprint != self.fingerprint {
+            anyhow::bail!(
+                "Archive key fingerprint mismatch (expected={}, local={}); restore the master key used to write this archive",
+                expected_fingerprint,
+                self.fingerprint
+            );
+        }
+        Ok(())
+    }
+}
+
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_BYTES], counter: u32, last: bool) -> [u8; NONCE_BYTES] {
+    let mut nonce = [0u8; NONCE_BYTES];
+    nonce[..NONCE_PREFIX_BYTES].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_BYTES..NONCE_BYTES - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[NONCE_BYTES - 1] = last as u8;
+    nonce
+}
+
+/// Read up to `CHUNK_BYTES` (short only at end of input).
+fn read_chunk(input: &mut dyn Read) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(CHUNK_BYTES);
+    input.take(CHUNK_BYTES as u64).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Short one-way fingerprint of a key (first 16 hex chars of its sha256).
+fn key_fingerprint(key: &[u8]) -> String {
+    let mut hex = crate::security::crypto::sha256_hex(key);
+    hex.truncate(16);
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seal(key: &ArchiveKey, plaintext: &[u8]) -> Vec<u8> {
+        let mut sealed = Vec::new();
+        key.encrypt_stream(&mut &plaintext[..], &mut sealed)
+            .unwrap();
+        sealed
+    }
+
+    fn open(key: &ArchiveKey, sealed: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        key.decrypt_stream(&mut &sealed[..], &mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn encrypt_decrypt_roundtrip_and_tamper_detection() {
+        let key = ArchiveKey::from_bytes([7u8; 32]);
+        let plaintext = b"PK\x03\x04 demo zip payload";
+        let sealed = seal(&key, plaintext);
+
+        assert!(sealed.starts_with(MAGIC_V2));
+        assert!(!sealed.windows(plaintext.len()).any(|w| w == plaintext));
+        assert_eq!(open(&key, &sealed).unwrap(), plaintext);
+
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert!(open(&key, &tampered).is_err());
+
+        let other = ArchiveKey::from_bytes([8u8; 32]);
+        assert!(open(&other, &sealed).is_err());
+        assert!(open(&key, plaintext).is_err());
+    }
+
+    #[test]
+    fn chunked_archives_reject_truncation_and_other_formats() {
+        let key = ArchiveKey::from_bytes([7u8; 32]);
+        // Exactly two full chunks plus a short tail, and the empty payload edge case.
+        let plaintext: Vec<u8> = (0..(2 * CHUNK_BYTES + 10)).map(|i| i as u8).collect();
+        let sealed = seal(&key, &plaintext);
+        assert_eq!(open(&key, &sealed).unwrap(), plaintext);
+        assert!(open(&key, &seal(&key, b"")).unwrap().is_empty());
+
+        // Dropping the final frame must not decrypt to a valid prefix.
+        let first_frame = MAGIC_V2.len() + NONCE_PREFIX_BYTES + 5 + CHUNK_BYTES + TAG_BYTES;
+        let second_frame_end = first_frame + 5 + CHUNK_BYTES + TAG_BYTES;
+        assert!(open(&key, &sealed[..second_frame_end]).is_err());
+
+        // Only the chunked format is accepted.
+        let mut unchunked = b"CDXARCE1".to_vec();
+        unchunked.extend_from_slice(&sealed[MAGIC_V2.len()..]);
+        assert!(open(&key, &unchunked).is_err());
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_checked() {
+        let key = ArchiveKey::from_bytes([7u8; 32]);
+        assert_eq!(key.fingerprint().len(), 16);
+        assert_eq!(
+            key.fingerprint(),
+            ArchiveKey::from_bytes([7u8; 32]).fingerprint()
+        );
+        assert!(key.ensure_matches(key.fingerprint()).is_ok());
+        assert!(key.ensure_matches("").is_ok());
+        assert!(key
+            .ensure_matches(ArchiveKey::from_bytes([8u8; 32]).fingerprint())
+            .is_err());
+    }
+}
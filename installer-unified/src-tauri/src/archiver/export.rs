@@ -0,0 +1,343 @@
+//! Hot-table export for real archive runs (one month of `cadalytix_data.calls`).
+//!
+//! Rows are read as JSON objects (Postgres `row_to_json`, SQL Server `FOR JSON PATH`) so the
+//! export does not need to know the table's columns; NDJSON is written as-is and CSV is derived
+//! from the same objects (NULL as `\N`, see `encode_csv_value`). Rows are streamed from the
+//! database cursor over a bounded channel to a `RowEncoder` writing the ZIP entry on a blocking
+//! thread, so memory stays bounded for any month size.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use log::info;
+use serde_json::{Map, Value};
+use std::io::Write;
+use tiberius::{Query, QueryItem};
+use tokio::sync::mpsc;
+
+use crate::database::connection::DatabaseConnection;
+
+use super::purge::{month_bounds, HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN};
+use super::{ArchiveFormat, MonthExport};
+
+/// CSV field for a NULL value (as in PostgreSQL `COPY`).
+const CSV_NULL: &str = "\\N";
+
+pub(crate) fn postgres_export_month_query() -> String {
+    format!(
+        "SELECT row_to_json(t)::text FROM {}.{} t WHERE t.{} >= $1 AND t.{} < $2 ORDER BY t.{}",
+        HOT_CALLS_SCHEMA,
+        HOT_CALLS_TABLE,
+        HOT_CALLS_TS_COLUMN,
+        HOT_CALLS_TS_COLUMN,
+        HOT_CALLS_TS_COLUMN
+    )
+}
+
+pub(crate) fn sql_server_export_month_query() -> String {
+    format!(
+        "SELECT (SELECT t.* FOR JSON PATH, WITHOUT_ARRAY_WRAPPER, INCLUDE_NULL_VALUES) FROM [{}].[{}] t WHERE t.[{}] >= @P1 AND t.[{}] < @P2 ORDER BY t.[{}]",
+        HOT_CALLS_SCHEMA,
+        HOT_CALLS_TABLE,
+        HOT_CALLS_TS_COLUMN,
+        HOT_CALLS_TS_COLUMN,
+        HOT_CALLS_TS_COLUMN
+    )
+}
+
+/// Streams one row at a time into the archive entry (NDJSON line or CSV record) and keeps the
+/// row count and timestamp range, so an export holds one row in memory however large the month.
+pub(crate) struct RowEncoder<'a> {
+    format: ArchiveFormat,
+    out: &'a mut (dyn Write + Send),
+    csv_header: Option<Vec<String>>,
+    row_count: u64,
+    min_ts: Option<DateTime<Utc>>,
+    max_ts: Option<DateTime<Utc>>,
+}
+
+impl<'a> RowEncoder<'a> {
+    pub(crate) fn new(format: ArchiveFormat, out: &'a mut (dyn Write + Send)) -> Self {
+        Self {
+            format,
+            out,
+            csv_header: None,
+            row_count: 0,
+            min_ts: None,
+            max_ts: None,
+        }
+    }
+
+    /// Encode a row serialized by the database as a JSON object (written verbatim as NDJSON).
+    pub(crate) fn push_json_line(&mut self, line: &str) -> Result<()> {
+        let row = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(m)) => m,
+            _ => anyhow::bail!(
+                "Hot row {} did not serialize to a JSON object",
+                self.row_count + 1
+            ),
+        };
+        self.push(&row, Some(line))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn push_row(&mut self, row: &Map<String, Value>) -> Result<()> {
+        self.push(row, None)
+    }
+
+    fn push(&mut self, row: &Map<String, Value>, raw: Option<&str>) -> Result<()> {
+        if let Some(ts) = row
+            .get(HOT_CALLS_TS_COLUMN)
+            .and_then(|v| v.as_str())
+            .and_then(parse_row_timestamp)
+        {
+            self.min_ts = Some(self.min_ts.map_or(ts, |m| m.min(ts)));
+            self.max_ts = Some(self.max_ts.map_or(ts, |m| m.max(ts)));
+        }
+
+        match self.format {
+            ArchiveFormat::ZipNdjson => {
+                match raw {
+                    Some(line) => self.out.write_all(line.as_bytes())?,
+                    None => serde_json::to_writer(&mut *self.out, row)?,
+                }
+                self.out.write_all(b"\n")?;
+            }
+            ArchiveFormat::ZipCsv => {
+                // Every row of one table has the same columns, so the first row fixes the header.
+                let header = match self.csv_header.take() {
+                    Some(h) => h,
+                    None => {
+                        let h: Vec<String> = row.keys().cloned().collect();
+                        write_csv_record(&mut *self.out, h.iter().map(|k| csv_field(k)))?;
+                        h
+                    }
+                };
+                if let Some(extra) = row.keys().find(|k| !header.contains(k)) {
+                    anyhow::bail!(
+                        "Hot row {} has column '{}' that is not in the CSV header",
+                        self.row_count + 1,
+                        extra
+                    );
+                }
+                let fields = header.iter().map(|h| encode_csv_value(row.get(h)));
+                write_csv_record(&mut *self.out, fields)?;
+                self.csv_header = Some(header);
+            }
+        }
+        self.row_count += 1;
+        Ok(())
+    }
+
+    /// Flush the encoder; months without timestamped rows report the month floor as their range.
+    pub(crate) fn finish(self, month_start: NaiveDate) -> Result<MonthExport> {
+        if self.format == ArchiveFormat::ZipCsv && self.csv_header.is_none() {
+            // Empty month: keep the (empty) header line CSV readers expect.
+            self.out.write_all(b"\n")?;
+        }
+        self.out.flush()?;
+        let (start, _) = month_bounds(month_start)?;
+        let month_floor = Utc.from_utc_datetime(&start);
+        Ok(MonthExport {
+            row_count: self.row_count,
+            min_ts: self.min_ts.unwrap_or(month_floor),
+            max_ts: self.max_ts.unwrap_or(month_floor),
+        })
+    }
+}
+
+fn write_csv_record(
+    out: &mut (dyn Write + Send),
+    fields: impl Iterator<Item = String>,
+) -> Result<()> {
+    let line = fields.collect::<Vec<_>>().join(",");
+    out.write_all(line.as_bytes())?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Stream one month of hot rows into `encoder` straight from the database cursor.
+pub(crate) async fn export_hot_rows(
+    conn: &DatabaseConnection,
+    month_start: NaiveDate,
+    rows_out: &mpsc::Sender<String>,
+) -> Result<()> {
+    let (start, end) = month_bounds(month_start)?;
+    let mut row_count: u64 = 0;
+    match conn {
+        DatabaseConnection::Postgres(pool) => {
+            let sql = postgres_export_month_query();
+            let mut rows = sqlx::query_scalar::<_, String>(&sql)
+                .bind(start)
+                .bind(end)
+                .fetch(pool);
+            while let Some(line) = rows
+                .try_next()
+                .await
+                .with_context(|| "Failed to export hot rows (PostgreSQL)")?
+            {
+                send_row(rows_out, line).await?;
+                row_count += 1;
+            }
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+            let mut q = Query::new(sql_server_export_month_query());
+            q.bind(start);
+            q.bind(end);
+            let mut stream = q
+                .query(&mut *client)
+                .await
+                .with_context(|| "Failed to export hot rows (SQL Server)")?;
+            while let Some(item) = stream.try_next().await? {
+                if let QueryItem::Row(row) = item {
+                    if let Some(s) = row.get::<&str, _>(0) {
+                        send_row(rows_out, s.to_string()).await?;
+                        row_count += 1;
+                    }
+                }
+            }
+        }
+    }
+    info!(
+        "[PHASE: archive] [STEP: export] Streamed hot rows (range_start={}, range_end={}, rows={})",
+        start, end, row_count
+    );
+    Ok(())
+}
+
+/// Hand one JSON row to the archive writer, waiting while it is `EXPORT_ROW_BUFFER` rows behind.
+pub(crate) async fn send_row(rows_out: &mpsc::Sender<String>, line: String) -> Result<()> {
+    rows_out
+        .send(line)
+        .await
+        .map_err(|_| anyhow::anyhow!("Archive writer stopped"))
+}
+
+/// Accepts RFC 3339 (timestamptz / datetimeoffset) or a naive ISO timestamp (treated as UTC).
+pub(crate) fn parse_row_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+        .map(|n| Utc.from_utc_datetime(&n))
+}
+
+/// CSV text of a value. NULL is `\N`, so it reads back differently from an empty string; a
+/// string starting with a backslash gets one more, so no string reads back as NULL.
+fn encode_csv_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => CSV_NULL.to_string(),
+        Some(Value::String(s)) if s.starts_with('\\') => csv_field(&format!("\\{}", s)),
+        Some(Value::String(s)) => csv_field(s),
+        Some(v) => csv_field(&v.to_string()),
+    }
+}
+
+/// Inverse of `encode_csv_value` for one parsed field.
+pub(crate) fn decode_csv_value(field: &str) -> Value {
+    match field.strip_prefix('\\') {
+        _ if field == CSV_NULL => Value::Null,
+        Some(rest) => Value::String(rest.to_string()),
+        None => Value::String(field.to_string()),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trips_through_restore_parser() {
+        let rows: Vec<Map<String, Value>> = [
+            r#"{"call_id":1,"call_received_at_utc":"2025-01-01T00:00:00","notes":"a, \"quoted\"\r\nmulti-line note"}"#,
+            r#"{"call_id":2,"call_received_at_utc":"2025-01-02T00:00:00","notes":null}"#,
+            r#"{"call_id":3,"call_received_at_utc":"2025-01-03T00:00:00","notes":""}"#,
+            r#"{"call_id":4,"call_received_at_utc":"2025-01-04T00:00:00","notes":"\\N"}"#,
+        ]
+        .iter()
+        .map(|l| serde_json::from_str::<Map<String, Value>>(l).unwrap())
+        .collect();
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut encoder = RowEncoder::new(ArchiveFormat::ZipCsv, &mut out);
+        for row in &rows {
+            encoder.push_row(row).unwrap();
+        }
+        let summary = encoder
+            .finish(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+            .unwrap();
+        assert_eq!(summary.row_count, 4);
+        let mut parsed = Vec::new();
+        super::super::restore::read_rows(&out[..], ArchiveFormat::ZipCsv, &mut |row| {
+            parsed.push(row);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed[0]["notes"], "a, \"quoted\"\r\nmulti-line note");
+        assert_eq!(parsed[1]["notes"], Value::Null);
+        assert_eq!(parsed[2]["notes"], "");
+        assert_eq!(parsed[3]["notes"], "\\N");
+        assert_eq!(parsed[1]["call_id"], "2");
+    }
+
+    #[test]
+    fn encoder_tracks_range_and_rejects_unexpected_csv_columns() {
+        let mut out: Vec<u8> = Vec::new();
+        let mut encoder = RowEncoder::new(ArchiveFormat::ZipNdjson, &mut out);
+        encoder
+            .push_json_line(r#"{"call_id":2,"call_received_at_utc":"2025-01-20T00:00:00"}"#)
+            .unwrap();
+        encoder
+            .push_json_line(r#"{"call_id":1,"call_received_at_utc":"2025-01-05T00:00:00"}"#)
+            .unwrap();
+        assert!(encoder.push_json_line("[1,2]").is_err());
+        let summary = encoder
+            .finish(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+            .unwrap();
+        assert_eq!(summary.row_count, 2);
+        assert_eq!(summary.min_ts.to_rfc3339(), "2025-01-05T00:00:00+00:00");
+        assert_eq!(summary.max_ts.to_rfc3339(), "2025-01-20T00:00:00+00:00");
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut encoder = RowEncoder::new(ArchiveFormat::ZipCsv, &mut out);
+        encoder.push_json_line(r#"{"a":1}"#).unwrap();
+        assert!(encoder.push_json_line(r#"{"a":2,"b":3}"#).is_err());
+    }
+
+    #[test]
+    fn row_timestamps_accept_naive_and_offset_forms() {
+        let naive = parse_row_timestamp("2025-01-31T23:59:59.123").unwrap();
+        let offset = parse_row_timestamp("2025-01-31T23:59:59.123+00:00").unwrap();
+        assert_eq!(naive, offset);
+        assert!(parse_row_timestamp("2025-01-31 23:59:59").is_some());
+        assert!(parse_row_timestamp("not a timestamp").is_none());
+    }
+
+    #[test]
+    fn export_queries_are_range_bounded_and_ordered() {
+        for sql in [
+            postgres_export_month_query(),
+            sql_server_export_month_query(),
+        ] {
+            assert!(sql.contains(">="));
+            assert!(sql.contains(" < "));
+            assert!(sql.contains("ORDER BY"));
+        }
+    }
+}
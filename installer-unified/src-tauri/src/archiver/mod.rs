@@ -3,18 +3,54 @@
 //! Scope for Phase 5:
 //! - Implement core archival control-flow with strict verification gates and idempotency.
 //! - Provide a deterministic `--archive-dry-run` mode that produces proof logs under `Prod_Wizard_Log/`.
+//! - Purge archived hot rows only after verify + ledger commit, behind the gates in `purge`.
+//! - Optionally encrypt archives at rest with a SecretProtector-derived key (see `encryption`).
+//! - Catch up on every month older than the hot retention window (`--archive-run-once`, see `catch_up`).
+//! - Stream exports (DB cursor -> row encoder -> ZIP staged on disk) so memory stays bounded for
+//!   any month size; see `export` and `StagedFile`.
+//! - Check projected export size against free staging space before writing (see `space`).
 //!
-//! Non-negotiable: NO partitioning. This module never modifies disks/volumes; it only writes files.
+//! Non-negotiable: NO partitioning. This module never modifies disks/volumes; it only writes files
+//! (and, once purge is confirmed, deletes hot rows that are already archived).
 
 use anyhow::Result;
 use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
 use log::{error, info, warn};
+use sha2::Digest;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 use tokio::time::{timeout, Duration};
 use zip::write::FileOptions;
 
+use crate::database::connection::DatabaseConnection;
+use crate::security::audit::{self, AuditAction};
+use crate::security::secret_protector::SecretProtector;
+use crate::utils::metrics;
+use crate::utils::throttle::{self, IoThrottle, ThrottledWriter};
+
+mod catch_up;
+mod encryption;
+mod export;
+mod object_store;
+mod progress;
+mod purge;
+mod restore;
+mod space;
+mod verify;
+
+pub(crate) use catch_up::archive_catch_up;
+pub use catch_up::{archive_run_once, ArchiveRunOnceArgs};
+pub(crate) use catch_up::{retention_cutoff, DEFAULT_MAX_MONTHS_PER_RUN};
+pub(crate) use encryption::KEY_PURPOSE as ARCHIVE_KEY_PURPOSE;
+pub(crate) use object_store::ArchiveDestination;
+use progress::ArchiveProgress;
+use purge::ArchivePurgeConfig;
+pub use restore::{archive_restore, ArchiveRestoreArgs};
+pub(crate) use space::FALLBACK_AVG_ROW_BYTES;
+pub use verify::{archive_verify_ledger, ArchiveVerifyArgs};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ArchiveFormat {
     ZipNdjson,
@@ -22,6 +58,14 @@ enum ArchiveFormat {
 }
 
 impl ArchiveFormat {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "zip+ndjson" => Some(ArchiveFormat::ZipNdjson),
+            "zip+csv" => Some(ArchiveFormat::ZipCsv),
+            _ => None,
+        }
+    }
+
     fn as_str(&self) -> &'static str {
         match self {
             ArchiveFormat::ZipNdjson => "zip+ndjson",
@@ -46,9 +90,23 @@ struct ArchiveLedgerEntry {
     row_count: u64,
     min_ts_utc: String,
     max_ts_utc: String,
+    /// sha256/size of the stored file (the sealed payload when `encrypted`).
     zip_sha256: String,
     zip_bytes: u64,
     created_utc: String,
+    /// pending | complete | skipped:<reason> | restored (empty in ledgers written before purge existed)
+    #[serde(default)]
+    purge_status: String,
+    #[serde(default)]
+    purged_row_count: u64,
+    /// Folder path or cloud URI the archive was written to.
+    #[serde(default)]
+    destination: String,
+    #[serde(default)]
+    encrypted: bool,
+    /// Fingerprint of the derived archive key (empty when not encrypted).
+    #[serde(default)]
+    key_fingerprint: String,
 }
 
 #[derive(Debug, Clone)]
@@ -56,14 +114,41 @@ struct ArchiveRunConfig {
     correlation_id: String,
     month: NaiveDate, // first day of month
     format: ArchiveFormat,
-    destination_dir: PathBuf,
+    destination: ArchiveDestination,
     max_usage_gb: u32,
     allow_without_watermark: bool,
     dry_run: bool,
+    purge: ArchivePurgeConfig,
+    /// Encrypt archives at rest when set.
+    encryption: Option<Arc<SecretProtector>>,
+    /// Staged/encrypted archive write limit in MB/s (0 = unlimited).
+    throttle_mbps: u32,
 }
 
-/// Archive export output: (uncompressed_bytes, row_count, min_timestamp_utc, max_timestamp_utc)
-type DemoExport = (Vec<u8>, u64, DateTime<Utc>, DateTime<Utc>);
+/// Summary of one streamed month export (the rows themselves go straight to the staged ZIP).
+struct MonthExport {
+    row_count: u64,
+    min_ts: DateTime<Utc>,
+    max_ts: DateTime<Utc>,
+}
+
+/// Archive file staged on local disk while it is built; removed on drop unless it was renamed
+/// into place, so a failed month never leaves a partial archive behind.
+struct StagedFile {
+    path: PathBuf,
+}
+
+impl StagedFile {
+    fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
 
 pub async fn archive_dry_run() -> Result<()> {
     let started = Instant::now();
@@ -94,27 +179,33 @@ pub async fn archive_dry_run() -> Result<()> {
         month: NaiveDate::from_ymd_opt(2025, 1, 1)
             .ok_or_else(|| anyhow::anyhow!("Invalid month"))?,
         format: ArchiveFormat::ZipNdjson,
-        destination_dir: log_dir.join("B2_archive_dryrun_destination"),
+        destination: ArchiveDestination::Local(dry_run_destination_dir(&log_dir)),
         max_usage_gb: 10,
         allow_without_watermark: true,
         dry_run: true,
+        purge: ArchivePurgeConfig::default(),
+        encryption: None,
+        throttle_mbps: throttle::default_mbps(),
     };
 
-    ensure_dir_with_retries(&cfg.destination_dir, "ensure_archive_destination").await?;
+    if let ArchiveDestination::Local(dir) = &cfg.destination {
+        ensure_dir_with_retries(dir, "ensure_archive_destination").await?;
+    }
 
     // Placeholder schedule artifacts (ready to be wired to a real runner in a later phase).
     // These are written under Prod_Wizard_Log/ for deterministic proof output.
     let schedule_dir = log_dir.join("B2_archive_schedule_placeholders");
     write_schedule_placeholders(&schedule_dir, 1, "00:05", &mut push).await?;
 
-    let ledger_path = log_dir.join("B2_archive_pipeline_dryrun_ledger.json");
+    let ledger_path = default_ledger_path(&log_dir);
     push(format!(
         "EVENT archive-ledger path={}",
         ledger_path.to_string_lossy()
     ));
 
     // Run twice to prove idempotency deterministically.
-    let first = archive_one_month(&cfg, &ledger_path, &mut push).await;
+    let progress = ArchiveProgress::new(&cfg.correlation_id, None);
+    let first = archive_one_month(&cfg, None, &ledger_path, &progress, &mut push).await;
     push(format!(
         "run1 result={} duration_ms={}",
         if first.is_ok() { "ok" } else { "err" },
@@ -124,7 +215,8 @@ pub async fn archive_dry_run() -> Result<()> {
         push(format!("run1 error={}", e));
     }
 
-    let second = archive_one_month(&cfg, &ledger_path, &mut push).await;
+    let second = archive_one_month(&cfg, None, &ledger_path, &progress, &mut push).await;
+    progress.finish(second.is_ok());
     push(format!(
         "run2 result={} duration_ms={}",
         if second.is_ok() { "ok" } else { "err" },
@@ -159,9 +251,12 @@ async fn write_schedule_placeholders(
 ) -> Result<()> {
     ensure_dir_with_retries(out_dir, "ensure_schedule_placeholders_dir").await?;
 
+    // Per instance, so the schedules of side-by-side instances do not replace each other.
+    let task_name = crate::utils::instance::qualify("CADalytix Archive", " ");
+    let unit_name = crate::utils::instance::qualify("cadalytix-archive", "-");
     let win_ps1 = out_dir.join("B2_archive_windows_task_scheduler_placeholder.ps1");
-    let linux_service = out_dir.join("cadalytix-archive.service");
-    let linux_timer = out_dir.join("cadalytix-archive.timer");
+    let linux_service = out_dir.join(format!("{}.service", unit_name));
+    let linux_timer = out_dir.join(format!("{}.timer", unit_name));
 
     let win_contents = format!(
         r#"# CADalytix Archive Schedule Placeholder (Phase 5)
@@ -175,13 +270,14 @@ async fn write_schedule_placeholders(
 #
 # TODO (wire-up): Replace <ARCHIVE_COMMAND> with the real archive runner command.
 # Example (Task Scheduler command line):
-#   schtasks /Create /SC MONTHLY /D {day} /TN "CADalytix Archive" /TR "<ARCHIVE_COMMAND>" /ST {time} /F
+#   schtasks /Create /SC MONTHLY /D {day} /TN "{task}" /TR "<ARCHIVE_COMMAND>" /ST {time} /F
 #
 # Example <ARCHIVE_COMMAND> (placeholder):
 #   "C:\Program Files\CADalytix\installer-unified.exe" --archive-run-once
 "#,
         day = day_of_month,
-        time = time_local
+        time = time_local,
+        task = task_name
     );
     write_file_with_retries(
         &win_ps1,
@@ -244,9 +340,42 @@ WantedBy=timers.target
     Ok(())
 }
 
+#[tracing::instrument(
+    name = "archive.month",
+    skip_all,
+    fields(month = %cfg.month.format("%Y-%m"), correlation_id = %cfg.correlation_id)
+)]
 async fn archive_one_month(
     cfg: &ArchiveRunConfig,
+    hot_db: Option<&DatabaseConnection>,
+    ledger_path: &Path,
+    progress: &ArchiveProgress,
+    push: &mut dyn FnMut(String),
+) -> Result<()> {
+    let month_key = cfg.month.format("%Y-%m").to_string();
+    let result = archive_one_month_steps(cfg, hot_db, ledger_path, progress, push).await;
+    match &result {
+        Ok(()) => progress.report(
+            "archive_done",
+            100,
+            format!("Archive for {} complete.", month_key),
+            push,
+        ),
+        Err(e) => progress.report_error(
+            "archive_error",
+            100,
+            format!("Archive for {} failed: {}", month_key, e),
+            push,
+        ),
+    }
+    result
+}
+
+async fn archive_one_month_steps(
+    cfg: &ArchiveRunConfig,
+    hot_db: Option<&DatabaseConnection>,
     ledger_path: &Path,
+    progress: &ArchiveProgress,
     push: &mut dyn FnMut(String),
 ) -> Result<()> {
     let month_key = cfg.month.format("%Y-%m").to_string();
@@ -254,11 +383,33 @@ async fn archive_one_month(
         "EVENT archive-start correlation_id={} month={}",
         cfg.correlation_id, month_key
     ));
+    progress.report(
+        "archive_start",
+        0,
+        format!("Archiving {}...", month_key),
+        push,
+    );
     push("verified_steps order=1..6".to_string());
 
-    // Idempotency: if ledger says complete, skip.
-    if let Some(existing) = read_ledger(ledger_path).await?.get(&month_key) {
+    let file_name = archive_file_name(&month_key, cfg.encryption.is_some());
+
+    let purge_skip = purge::purge_gate(&cfg.purge, &month_key, cfg.dry_run, hot_db.is_some());
+
+    // Idempotency: if ledger says complete, skip (but finish a purge that is now allowed and
+    // did not complete in an earlier run).
+    if let Some(existing) = read_ledger(ledger_path).await?.get(&month_key).cloned() {
         if existing.status == "complete" {
+            // Restored months are hot again on purpose; only pending/skipped purges resume.
+            let purge_unfinished =
+                existing.purge_status == "pending" || existing.purge_status.starts_with("skipped:");
+            if purge_skip.is_none() && purge_unfinished {
+                push(format!(
+                    "EVENT archive-resume-purge month={} previous_purge_status={}",
+                    month_key, existing.purge_status
+                ));
+                return purge_archived_month(cfg, hot_db, ledger_path, existing, progress, push)
+                    .await;
+            }
             push(format!(
                 "EVENT archive-skip month={} reason=already_complete",
                 month_key
@@ -267,43 +418,52 @@ async fn archive_one_month(
         }
     }
 
-    // VERIFIED STEP 1/6: destination checks (exists, is a directory, writable).
+    // VERIFIED STEP 1/6: destination checks (local: exists, is a directory, writable;
+    // cloud: credentials resolve and a test object can be written).
     push(format!(
         "VERIFY 1/6 destination-check begin path={}",
-        cfg.destination_dir.to_string_lossy()
+        cfg.destination.display()
     ));
-    match tokio::fs::metadata(&cfg.destination_dir).await {
-        Ok(m) => {
-            if !m.is_dir() {
+    match &cfg.destination {
+        ArchiveDestination::Local(dir) => check_local_destination(dir, &month_key, push).await?,
+        ArchiveDestination::Cloud(loc) => {
+            let store = match object_store::open_store(loc) {
+                Ok(s) => s,
+                Err(e) => {
+                    push(format!(
+                        "EVENT archive-destination-check-fail month={} message=\"{}\"",
+                        month_key, e
+                    ));
+                    return Err(e);
+                }
+            };
+            let probe_key = loc.object_key("__cadalytix_archive_write_test.tmp");
+            if let Err(e) = object_store::upload_multipart(
+                store.as_ref(),
+                &probe_key,
+                &mut &b"ok"[..],
+                2,
+                1024,
+                &mut |_, _| {},
+            )
+            .await
+            {
                 push(format!(
-                    "EVENT archive-destination-check-fail month={} message=\"Destination is not a directory\"",
+                    "EVENT archive-destination-check-fail month={} message=\"Destination bucket is not writable\"",
                     month_key
                 ));
-                anyhow::bail!("Archive destination is not a directory");
+                return Err(e);
             }
-        }
-        Err(_) => {
-            push(format!(
-                "EVENT archive-destination-check-fail month={} message=\"Destination folder is not accessible\"",
-                month_key
-            ));
-            anyhow::bail!("Archive destination folder is not accessible");
+            let _ = store.delete_object(&probe_key).await;
         }
     }
-    let write_test = cfg
-        .destination_dir
-        .join("__cadalytix_archive_write_test.tmp");
-    if let Err(_e) =
-        write_file_with_retries(&write_test, b"ok", "archive_destination_write_test").await
-    {
-        push(format!(
-            "EVENT archive-destination-check-fail month={} message=\"Destination folder is not writable\"",
-            month_key
-        ));
-        anyhow::bail!("Archive destination folder is not writable");
-    }
-    let _ = tokio::fs::remove_file(&write_test).await;
     push("VERIFY 1/6 destination-check ok".to_string());
+    progress.report(
+        "archive_destination",
+        5,
+        "Archive destination is writable.".to_string(),
+        push,
+    );
 
     // Gate: ingestion watermark check (placeholder).
     push("VERIFY 2/6 watermark-check begin".to_string());
@@ -319,43 +479,135 @@ async fn archive_one_month(
         month_key
     ));
     push("VERIFY 2/6 watermark-check ok".to_string());
+    progress.report(
+        "archive_export",
+        10,
+        format!("Exporting call data for {}...", month_key),
+        push,
+    );
+
+    // Export + compress in one streaming pass: DB cursor -> row encoder -> ZIP entry -> staged
+    // file on disk. Nothing month-sized is ever held in memory.
+    let staging = staging_dir(&cfg.destination);
+    ensure_dir_with_retries(&staging, "ensure_archive_staging_dir").await?;
+    // Space preflight: fail before writing anything when the staging volume cannot hold the month.
+    match hot_db {
+        Some(conn) => check_staging_space(cfg, conn, &month_key, &staging, push).await?,
+        None => push(format!(
+            "EVENT archive-space-check month={} status=skipped reason=demo_rows",
+            month_key
+        )),
+    }
 
-    // Export (demo data source): deterministic rows within the month.
     push("VERIFY 3/6 export begin".to_string());
-    let (export_bytes, row_count, min_ts, max_ts) = export_demo_rows(cfg.month, cfg.format)?;
+    let zip_file = StagedFile::new(staging.join(format!("{}.zip.export.tmp", month_key)));
+    let export = export_month_to_zip(cfg, hot_db, &zip_file.path).await?;
+    if let Ok(meta) = tokio::fs::metadata(&zip_file.path).await {
+        metrics::add_bytes(meta.len());
+    }
     push(format!(
-        "EVENT archive-export month={} rows={} min_ts_utc={} max_ts_utc={}",
+        "EVENT archive-export month={} source={} rows={} min_ts_utc={} max_ts_utc={}",
         month_key,
-        row_count,
-        min_ts.to_rfc3339(),
-        max_ts.to_rfc3339()
+        if hot_db.is_some() { "hot_db" } else { "demo" },
+        export.row_count,
+        export.min_ts.to_rfc3339(),
+        export.max_ts.to_rfc3339()
     ));
-    push(format!("VERIFY 3/6 export ok rows={}", row_count));
+    push(format!("VERIFY 3/6 export ok rows={}", export.row_count));
+    progress.report(
+        "archive_zip",
+        45,
+        format!("Exported {} rows; checksumming...", export.row_count),
+        push,
+    );
 
-    // Compress to ZIP.
     push("VERIFY 4/6 zip begin".to_string());
-    let zip_bytes = zip_single_file(cfg.format.file_name_in_zip(), &export_bytes)?;
-    let zip_sha256 = crate::security::crypto::sha256_hex(&zip_bytes);
+    let (zip_sha256, zip_len) = object_store::spawn_file_sha256(&zip_file.path).await?;
     push(format!(
         "EVENT archive-zip month={} format={} zip_bytes={} zip_sha256={}",
         month_key,
         cfg.format.as_str(),
-        zip_bytes.len(),
+        zip_len,
         zip_sha256
     ));
     push(format!("VERIFY 4/6 zip ok sha256={}", zip_sha256));
+    progress.report(
+        "archive_zip",
+        60,
+        format!("Compressed archive is {} bytes.", zip_len),
+        push,
+    );
 
-    // Cap enforcement: ensure destination usage + zip <= cap.
+    // Optional encryption at rest: from here on the stored payload is the sealed ZIP.
+    let archive_key = match &cfg.encryption {
+        Some(secrets) => Some(Arc::new(encryption::ArchiveKey::load(secrets).await?)),
+        None => None,
+    };
+    let stored_file = match &archive_key {
+        Some(key) => {
+            let sealed_file = StagedFile::new(staging.join(format!("{}.tmp", file_name)));
+            // Seal, then prove the sealed file decrypts back to the exact ZIP we built (both
+            // streamed, off the async runtime).
+            let (sealed_len, opened_sha256) = {
+                let (key, throttle_mbps) = (key.clone(), cfg.throttle_mbps);
+                let (zip_path, sealed_path) = (zip_file.path.clone(), sealed_file.path.clone());
+                tokio::task::spawn_blocking(move || -> Result<(u64, String)> {
+                    let mut input = std::io::BufReader::new(std::fs::File::open(&zip_path)?);
+                    let mut output = std::io::BufWriter::new(ThrottledWriter::new(
+                        std::fs::File::create(&sealed_path)?,
+                        IoThrottle::new(throttle_mbps),
+                    ));
+                    let sealed_len = key.encrypt_stream(&mut input, &mut output)?;
+                    drop(output);
+                    let mut opened = sha2::Sha256::new();
+                    let mut sealed = std::io::BufReader::new(std::fs::File::open(&sealed_path)?);
+                    key.decrypt_stream(&mut sealed, &mut opened)?;
+                    Ok((sealed_len, object_store::hex_digest(opened)))
+                })
+                .await??
+            };
+            push(format!(
+                "EVENT archive-encrypt month={} key_fingerprint={} stored_bytes={}",
+                month_key,
+                key.fingerprint(),
+                sealed_len
+            ));
+            if opened_sha256 != zip_sha256 {
+                push(format!(
+                    "EVENT archive-verify-fail month={} reason=decrypt_mismatch",
+                    month_key
+                ));
+                anyhow::bail!("Archive verification failed (decrypted payload mismatch)");
+            }
+            push(format!(
+                "EVENT archive-decrypt-verify-ok month={} key_fingerprint={}",
+                month_key,
+                key.fingerprint()
+            ));
+            sealed_file
+        }
+        None => zip_file,
+    };
+    let (stored_sha256, stored_len) = object_store::spawn_file_sha256(&stored_file.path).await?;
+    progress.report(
+        "archive_write",
+        65,
+        "Writing archive to destination...".to_string(),
+        push,
+    );
+
+    // Cap enforcement: ensure destination usage + archive <= cap.
     push("VERIFY 5/6 cap+write begin".to_string());
     let cap_bytes = (cfg.max_usage_gb as u64).saturating_mul(1024_u64.pow(3));
-    let current_usage = folder_size_bytes(&cfg.destination_dir).await?;
-    if cap_bytes > 0 && current_usage.saturating_add(zip_bytes.len() as u64) > cap_bytes {
+    let current_usage = match &cfg.destination {
+        ArchiveDestination::Local(dir) => folder_size_bytes(dir).await?,
+        // Object stores bill by usage rather than filling a volume; the cap guards local disks.
+        ArchiveDestination::Cloud(_) => 0,
+    };
+    if cap_bytes > 0 && current_usage.saturating_add(stored_len) > cap_bytes {
         push(format!(
             "EVENT archive-cap-exceeded month={} cap_bytes={} current_bytes={} new_bytes={}",
-            month_key,
-            cap_bytes,
-            current_usage,
-            zip_bytes.len()
+            month_key, cap_bytes, current_usage, stored_len
         ));
         anyhow::bail!("Archive cap exceeded for destination folder");
     }
@@ -364,59 +616,90 @@ async fn archive_one_month(
         month_key, cap_bytes, current_usage
     ));
 
-    // Write with temp + atomic rename.
-    let final_name = format!("cadalytix-archive-{}.zip", month_key);
-    let tmp_name = format!("{}.tmp", final_name);
-    let final_path = cfg.destination_dir.join(final_name);
-    let tmp_path = cfg.destination_dir.join(tmp_name);
-    write_file_with_retries(&tmp_path, &zip_bytes, "write_archive_tmp").await?;
-    rename_with_retries(&tmp_path, &final_path, "rename_archive_zip").await?;
+    // Write: atomic rename of the staged file, or cloud multipart upload streamed from it.
+    match &cfg.destination {
+        ArchiveDestination::Local(dir) => {
+            rename_with_retries(
+                &stored_file.path,
+                &dir.join(&file_name),
+                "rename_archive_zip",
+            )
+            .await?;
+        }
+        ArchiveDestination::Cloud(loc) => {
+            let store = object_store::open_store(loc)?;
+            let mut source = tokio::fs::File::open(&stored_file.path).await?;
+            object_store::upload_multipart(
+                store.as_ref(),
+                &loc.object_key(&file_name),
+                &mut source,
+                stored_len,
+                object_store::MULTIPART_PART_BYTES,
+                &mut |done, total| {
+                    // Upload owns 65..90 of the month band.
+                    let pct = 65 + (25 * done / total.max(1)) as u32;
+                    progress.report(
+                        "archive_write",
+                        pct,
+                        format!("Uploaded {} of {} bytes.", done, total),
+                        push,
+                    );
+                },
+            )
+            .await?;
+        }
+    }
+    drop(stored_file);
     push(format!(
         "VERIFY 5/6 cap+write ok path={}",
-        final_path.to_string_lossy()
+        archive_location(&cfg.destination, &file_name)
     ));
 
-    // Verify on-disk checksum.
+    // Verify checksum of what actually landed at the destination (read back, never trusted).
     push("VERIFY 6/6 verify+ledger begin".to_string());
-    let on_disk = tokio::fs::read(&final_path).await?;
-    let on_disk_sha = crate::security::crypto::sha256_hex(&on_disk);
-    if on_disk_sha != zip_sha256 {
+    progress.report(
+        "archive_verify",
+        90,
+        "Verifying archive checksum...".to_string(),
+        push,
+    );
+    // Matching sha256 also proves the landed bytes are the sealed file decrypt-checked above.
+    let (stored_sha, _) = object_store::archive_sha256(&cfg.destination, &file_name).await?;
+    if stored_sha != stored_sha256 {
         push(format!(
             "EVENT archive-verify-fail month={} expected_sha256={} actual_sha256={}",
-            month_key, zip_sha256, on_disk_sha
+            month_key, stored_sha256, stored_sha
         ));
         anyhow::bail!("Archive verification failed (sha256 mismatch)");
     }
     push(format!(
         "EVENT archive-verify-ok month={} path={}",
         month_key,
-        final_path.to_string_lossy()
+        archive_location(&cfg.destination, &file_name)
     ));
 
-    // Purge step placeholder (never purge in dry-run).
-    if cfg.dry_run {
-        push(format!(
-            "EVENT archive-purge-skip month={} reason=dry_run",
-            month_key
-        ));
-    } else {
-        push(format!(
-            "EVENT archive-purge month={} status=not_implemented",
-            month_key
-        ));
-    }
-
-    // Ledger: mark complete.
+    // Ledger: mark complete (purge happens strictly after this commit).
     let entry = ArchiveLedgerEntry {
         month: month_key.clone(),
         status: "complete".to_string(),
         format: cfg.format.as_str().to_string(),
-        row_count,
-        min_ts_utc: min_ts.to_rfc3339(),
-        max_ts_utc: max_ts.to_rfc3339(),
-        zip_sha256: zip_sha256.clone(),
-        zip_bytes: zip_bytes.len() as u64,
+        row_count: export.row_count,
+        min_ts_utc: export.min_ts.to_rfc3339(),
+        max_ts_utc: export.max_ts.to_rfc3339(),
+        zip_sha256: stored_sha256,
+        zip_bytes: stored_len,
         created_utc: Utc::now().to_rfc3339(),
+        purge_status: match purge_skip {
+            Some(reason) => format!("skipped:{}", reason.as_str()),
+            None => "pending".to_string(),
+        },
+        purged_row_count: 0,
+        destination: cfg.destination.display(),
+        encrypted: archive_key.is_some(),
+        key_fingerprint: archive_key
+            .as_ref()
+            .map(|k| k.fingerprint().to_string())
+            .unwrap_or_default(),
     };
     write_ledger_entry(ledger_path, &entry).await?;
     push(format!(
@@ -424,13 +707,301 @@ async fn archive_one_month(
         month_key
     ));
     push("VERIFY 6/6 verify+ledger ok".to_string());
+    progress.report(
+        "archive_ledger",
+        95,
+        format!("Archive for {} verified and recorded.", month_key),
+        push,
+    );
+
+    purge_archived_month(cfg, hot_db, ledger_path, entry, progress, push).await
+}
+
+/// Purge hot rows for an archived month once every safety gate passes.
+/// The outcome is recorded on the ledger entry; a purge that did not complete is retried by the
+/// next run whose config passes the gates.
+async fn purge_archived_month(
+    cfg: &ArchiveRunConfig,
+    hot_db: Option<&DatabaseConnection>,
+    ledger_path: &Path,
+    mut entry: ArchiveLedgerEntry,
+    progress: &ArchiveProgress,
+    push: &mut dyn FnMut(String),
+) -> Result<()> {
+    let month_key = entry.month.clone();
+
+    if let Some(reason) = purge::purge_gate(&cfg.purge, &month_key, cfg.dry_run, hot_db.is_some()) {
+        push(format!(
+            "EVENT archive-purge-skip month={} reason={}",
+            month_key,
+            reason.as_str()
+        ));
+        return Ok(());
+    }
+    let Some(conn) = hot_db else {
+        return Ok(());
+    };
+
+    push(format!(
+        "EVENT archive-purge-begin month={} expected_rows={}",
+        month_key, entry.row_count
+    ));
+    progress.report(
+        "archive_purge",
+        97,
+        format!("Purging {} archived hot rows...", entry.row_count),
+        push,
+    );
+    if let Err(e) = purge::verify_archive_checksum(&cfg.destination, &entry).await {
+        push(format!(
+            "EVENT archive-purge-fail month={} gate=checksum message=\"{}\"",
+            month_key, e
+        ));
+        return Err(e);
+    }
+    push(format!(
+        "EVENT archive-purge-checksum-ok month={} sha256={}",
+        month_key, entry.zip_sha256
+    ));
 
+    let started = Instant::now();
+    let purged = purge::purge_month_rows(conn, cfg.month, entry.row_count).await;
+    audit::record_result(
+        AuditAction::ArchivePurge,
+        &month_key,
+        serde_json::json!({
+            "expectedRows": entry.row_count,
+            "deletedRows": purged.as_ref().ok(),
+            "archiveSha256": entry.zip_sha256,
+        }),
+        &purged,
+    )
+    .await;
+    let deleted = match purged {
+        Ok(n) => n,
+        Err(e) => {
+            push(format!(
+                "EVENT archive-purge-fail month={} gate=transaction message=\"{}\"",
+                month_key, e
+            ));
+            return Err(e);
+        }
+    };
+    info!(
+        "[PHASE: archive] [STEP: purge] Purge committed (month={}, deleted_rows={}, duration_ms={})",
+        month_key,
+        deleted,
+        started.elapsed().as_millis()
+    );
+    push(format!(
+        "EVENT archive-purge month={} status=complete deleted_rows={}",
+        month_key, deleted
+    ));
+
+    entry.purge_status = "complete".to_string();
+    entry.purged_row_count = deleted;
+    write_ledger_entry(ledger_path, &entry).await
+}
+
+fn archive_file_name(month_key: &str, encrypted: bool) -> String {
+    if encrypted {
+        format!("cadalytix-archive-{}.zip.enc", month_key)
+    } else {
+        format!("cadalytix-archive-{}.zip", month_key)
+    }
+}
+
+/// Ledger used when no explicit `--ledger=` is given (the dry-run proof ledger).
+pub(crate) fn default_ledger_path(log_dir: &Path) -> PathBuf {
+    log_dir.join("B2_archive_pipeline_dryrun_ledger.json")
+}
+
+fn dry_run_destination_dir(log_dir: &Path) -> PathBuf {
+    log_dir.join("B2_archive_dryrun_destination")
+}
+
+/// Where a ledger entry's archive lives: explicit override, then the destination recorded in
+/// the ledger, then the dry-run folder (ledgers written before destinations were recorded).
+fn resolve_entry_destination(
+    override_dir: Option<&str>,
+    entry: &ArchiveLedgerEntry,
+    log_dir: &Path,
+) -> Result<ArchiveDestination> {
+    match (override_dir, entry.destination.trim()) {
+        (Some(d), _) => ArchiveDestination::parse(d),
+        (None, d) if !d.is_empty() => ArchiveDestination::parse(d),
+        (None, _) => Ok(ArchiveDestination::Local(dry_run_destination_dir(log_dir))),
+    }
+}
+
+fn archive_location(dest: &ArchiveDestination, file_name: &str) -> String {
+    match dest {
+        ArchiveDestination::Local(dir) => dir.join(file_name).to_string_lossy().to_string(),
+        ArchiveDestination::Cloud(loc) => format!("{}/{}", loc.uri(), file_name),
+    }
+}
+
+async fn check_local_destination(
+    dir: &Path,
+    month_key: &str,
+    push: &mut dyn FnMut(String),
+) -> Result<()> {
+    match tokio::fs::metadata(dir).await {
+        Ok(m) => {
+            if !m.is_dir() {
+                push(format!(
+                    "EVENT archive-destination-check-fail month={} message=\"Destination is not a directory\"",
+                    month_key
+                ));
+                anyhow::bail!("Archive destination is not a directory");
+            }
+        }
+        Err(_) => {
+            push(format!(
+                "EVENT archive-destination-check-fail month={} message=\"Destination folder is not accessible\"",
+                month_key
+            ));
+            anyhow::bail!("Archive destination folder is not accessible");
+        }
+    }
+    let write_test = dir.join("__cadalytix_archive_write_test.tmp");
+    if let Err(_e) =
+        write_file_with_retries(&write_test, b"ok", "archive_destination_write_test").await
+    {
+        push(format!(
+            "EVENT archive-destination-check-fail month={} message=\"Destination folder is not writable\"",
+            month_key
+        ));
+        anyhow::bail!("Archive destination folder is not writable");
+    }
+    let _ = tokio::fs::remove_file(&write_test).await;
     Ok(())
 }
 
-fn export_demo_rows(month_start: NaiveDate, format: ArchiveFormat) -> Result<DemoExport> {
+/// Local folder where an archive is built before it is moved or uploaded: the destination
+/// itself for local folders (so the final rename is atomic), the temp dir for cloud stores.
+fn staging_dir(dest: &ArchiveDestination) -> PathBuf {
+    match dest {
+        ArchiveDestination::Local(dir) => dir.clone(),
+        ArchiveDestination::Cloud(_) => std::env::temp_dir().join("cadalytix_archive_staging"),
+    }
+}
+
+/// Compare the projected export size with free space at the staging location.
+/// Free-space detection is best-effort: when the OS query fails the run continues with a warning.
+async fn check_staging_space(
+    cfg: &ArchiveRunConfig,
+    conn: &DatabaseConnection,
+    month_key: &str,
+    staging: &Path,
+    push: &mut dyn FnMut(String),
+) -> Result<()> {
+    let estimate = space::estimate_month_export(conn, cfg.month).await?;
+    let required = estimate.required_free_bytes(cfg.encryption.is_some());
+    let free = match crate::utils::disk::get_free_space_bytes_for_path(&staging.to_string_lossy())
+        .await
+    {
+        Ok(b) => b,
+        Err(e) => {
+            warn!(
+                    "[PHASE: archive] [STEP: space_check] Unable to determine free space (path={:?}, error={:?})",
+                    staging, e
+                );
+            push(format!(
+                "EVENT archive-space-check month={} status=unknown required_bytes={}",
+                month_key, required
+            ));
+            return Ok(());
+        }
+    };
+    push(format!(
+        "EVENT archive-space-check month={} rows={} avg_row_bytes={} projected_bytes={} required_bytes={} free_bytes={}",
+        month_key,
+        estimate.row_count,
+        estimate.avg_row_bytes,
+        estimate.projected_bytes(),
+        required,
+        free
+    ));
+    if free < required {
+        push(format!(
+            "EVENT archive-space-insufficient month={} required_bytes={} free_bytes={}",
+            month_key, required, free
+        ));
+        return Err(crate::error::with_code(
+            anyhow::anyhow!(
+                "Not enough free space to archive {}: about {} MiB needed at {:?} but only {} MiB free",
+                month_key,
+                space::mib(required),
+                staging,
+                space::mib(free)
+            ),
+            crate::error::InstallerError::InsufficientDiskSpace,
+        ));
+    }
+    Ok(())
+}
+
+/// Rows read from the database ahead of the ZIP writer at most.
+const EXPORT_ROW_BUFFER: usize = 256;
+
+/// Stream one month (hot rows, or demo rows without a hot DB) into a single-entry ZIP at `path`.
+///
+/// The ZIP is written (and throttled) on a blocking thread fed over a bounded channel, so
+/// throttle pauses never hold up a runtime worker while the DB cursor is being read.
+async fn export_month_to_zip(
+    cfg: &ArchiveRunConfig,
+    hot_db: Option<&DatabaseConnection>,
+    path: &Path,
+) -> Result<MonthExport> {
+    let (tx, rx) = tokio::sync::mpsc::channel(EXPORT_ROW_BUFFER);
+    let writer = spawn_zip_writer(cfg, path.to_path_buf(), rx);
+    let fed = match hot_db {
+        Some(conn) => export::export_hot_rows(conn, cfg.month, &tx).await,
+        None => export_demo_rows(cfg.month, &tx).await,
+    };
+    drop(tx);
+    // A writer failure (e.g. disk full) is the real cause when both sides fail.
+    let summary = writer.await??;
+    fed?;
+    Ok(summary)
+}
+
+fn spawn_zip_writer(
+    cfg: &ArchiveRunConfig,
+    path: PathBuf,
+    mut rows: tokio::sync::mpsc::Receiver<String>,
+) -> tokio::task::JoinHandle<Result<MonthExport>> {
+    let (format, month, throttle_mbps) = (cfg.format, cfg.month, cfg.throttle_mbps);
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::create(&path)
+            .map_err(|e| anyhow::anyhow!("Unable to create staged archive {:?}: {}", path, e))?;
+        let file = ThrottledWriter::new(file, IoThrottle::new(throttle_mbps));
+        let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+        let opts = FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(0o644)
+            // ZIP64 entry: a month's rows routinely exceed 4 GiB uncompressed.
+            .large_file(true);
+        zip.start_file(format.file_name_in_zip(), opts)?;
+
+        let mut encoder = export::RowEncoder::new(format, &mut zip);
+        while let Some(line) = rows.blocking_recv() {
+            encoder.push_json_line(&line)?;
+        }
+        let summary = encoder.finish(month)?;
+
+        let mut out = zip.finish()?;
+        std::io::Write::flush(&mut out)?;
+        Ok(summary)
+    })
+}
+
+async fn export_demo_rows(
+    month_start: NaiveDate,
+    rows: &tokio::sync::mpsc::Sender<String>,
+) -> Result<()> {
     // Deterministic: fixed 5 rows, one per day starting at day 1.
-    let mut rows = Vec::new();
     for i in 0..5u64 {
         let d = month_start
             .with_day((i + 1) as u32)
@@ -439,54 +1010,14 @@ fn export_demo_rows(month_start: NaiveDate, format: ArchiveFormat) -> Result<Dem
             .and_hms_opt(0, 0, 0)
             .ok_or_else(|| anyhow::anyhow!("Invalid demo time"))?;
         let ts = Utc.from_utc_datetime(&dt);
-        rows.push((i + 1, ts));
-    }
-
-    let min_ts = rows.first().map(|(_, ts)| *ts).unwrap();
-    let max_ts = rows.last().map(|(_, ts)| *ts).unwrap();
-
-    let bytes = match format {
-        ArchiveFormat::ZipNdjson => {
-            let mut out = String::new();
-            for (id, ts) in rows.iter() {
-                out.push_str(
-                    &serde_json::json!({
-                        "call_id": id,
-                        "call_received_at_utc": ts.to_rfc3339(),
-                        "demo": true
-                    })
-                    .to_string(),
-                );
-                out.push('\n');
-            }
-            out.into_bytes()
-        }
-        ArchiveFormat::ZipCsv => {
-            let mut out = String::new();
-            out.push_str("call_id,call_received_at_utc,demo\n");
-            for (id, ts) in rows.iter() {
-                out.push_str(&format!("{},{},true\n", id, ts.to_rfc3339()));
-            }
-            out.into_bytes()
-        }
-    };
-
-    Ok((bytes, rows.len() as u64, min_ts, max_ts))
-}
-
-fn zip_single_file(name_in_zip: &str, content: &[u8]) -> Result<Vec<u8>> {
-    let cursor = std::io::Cursor::new(Vec::<u8>::new());
-    let cursor = {
-        let mut zip = zip::ZipWriter::new(cursor);
-        let opts = FileOptions::default()
-            .compression_method(zip::CompressionMethod::Deflated)
-            .unix_permissions(0o644);
-        zip.start_file(name_in_zip, opts)?;
-        use std::io::Write;
-        zip.write_all(content)?;
-        zip.finish()?
-    };
-    Ok(cursor.into_inner())
+        let row = serde_json::json!({
+            "call_id": i + 1,
+            "call_received_at_utc": ts.to_rfc3339(),
+            "demo": true
+        });
+        export::send_row(rows, row.to_string()).await?;
+    }
+    Ok(())
 }
 
 async fn folder_size_bytes(dir: &Path) -> Result<u64> {
@@ -506,7 +1037,9 @@ async fn folder_size_bytes(dir: &Path) -> Result<u64> {
             Ok(m) => m,
             Err(_) => continue,
         };
-        if meta.is_file() {
+        // In-flight staged archives (`*.tmp`) are not part of the archive set yet.
+        let staged = ent.file_name().to_string_lossy().ends_with(".tmp");
+        if meta.is_file() && !staged {
             total = total.saturating_add(meta.len());
         }
     }
@@ -541,6 +1074,9 @@ async fn write_ledger_entry(path: &Path, entry: &ArchiveLedgerEntry) -> Result<(
 async fn ensure_dir_with_retries(path: &Path, label: &str) -> Result<()> {
     let mut last_err: Option<anyhow::Error> = None;
     for attempt in 1..=3 {
+        if attempt > 1 {
+            metrics::note_retry();
+        }
         let started = Instant::now();
         match timeout(Duration::from_secs(5), tokio::fs::create_dir_all(path)).await {
             Ok(Ok(())) => {
@@ -577,9 +1113,13 @@ async fn ensure_dir_with_retries(path: &Path, label: &str) -> Result<()> {
 async fn write_file_with_retries(path: &Path, bytes: &[u8], label: &str) -> Result<()> {
     let mut last_err: Option<anyhow::Error> = None;
     for attempt in 1..=3 {
+        if attempt > 1 {
+            metrics::note_retry();
+        }
         let started = Instant::now();
         match timeout(Duration::from_secs(10), tokio::fs::write(path, bytes)).await {
             Ok(Ok(())) => {
+                metrics::add_bytes(bytes.len() as u64);
                 info!(
                     "[PHASE: archive] [STEP: fs] {} ok (attempt={}, path={:?}, bytes={}, duration_ms={})",
                     label,
@@ -614,6 +1154,9 @@ async fn write_file_with_retries(path: &Path, bytes: &[u8], label: &str) -> Resu
 async fn rename_with_retries(from: &Path, to: &Path, label: &str) -> Result<()> {
     let mut last_err: Option<anyhow::Error> = None;
     for attempt in 1..=3 {
+        if attempt > 1 {
+            metrics::note_retry();
+        }
         let started = Instant::now();
         match timeout(Duration::from_secs(5), tokio::fs::rename(from, to)).await {
             Ok(Ok(())) => {
@@ -0,0 +1,146 @@
+//! Archive run progress.
+//!
+//! Emits the same `ProgressPayload` stream `start_install` uses (phase `archive`) so GUI/TUI can
+//! render a live bar, and mirrors every update into the run transcript as a `PROGRESS` line.
+//! The same updates time the run's steps for the JSON log (see `utils::metrics`).
+//! Percentages are per run: month `i` of `n` owns the band `[i*100/n, (i+1)*100/n)`.
+
+use std::time::Instant;
+
+use crate::api::installer::{ProgressEmitter, ProgressPayload};
+use crate::utils::metrics::StepRecorder;
+
+pub(crate) struct ArchiveProgress {
+    correlation_id: String,
+    emit: Option<ProgressEmitter>,
+    started: Instant,
+    month_index: usize,
+    month_count: usize,
+    steps: StepRecorder,
+}
+
+impl ArchiveProgress {
+    pub(crate) fn new(correlation_id: &str, emit: Option<ProgressEmitter>) -> Self {
+        Self {
+            correlation_id: correlation_id.to_string(),
+            emit,
+            started: Instant::now(),
+            month_index: 0,
+            month_count: 1,
+            steps: StepRecorder::default(),
+        }
+    }
+
+    /// End of the run: log the step metrics and the per-phase summary.
+    pub(crate) fn finish(&self, ok: bool) {
+        self.steps.finish(ok);
+    }
+
+    pub(crate) fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+
+    /// Select the month band for subsequent updates (`index` is zero-based).
+    pub(crate) fn set_month(&mut self, index: usize, count: usize) {
+        self.month_count = count.max(1);
+        self.month_index = index.min(self.month_count - 1);
+    }
+
+    /// Map a 0..=100 position within the current month onto the whole run.
+    pub(crate) fn overall_percent(&self, month_percent: u32) -> i32 {
+        let within = month_percent.min(100) as usize;
+        ((self.month_index * 100 + within) / self.month_count) as i32
+    }
+
+    pub(crate) fn report(
+        &self,
+        step: &str,
+        month_percent: u32,
+        message: String,
+        push: &mut dyn FnMut(String),
+    ) {
+        self.send("info", step, month_percent, message, push);
+    }
+
+    pub(crate) fn report_error(
+        &self,
+        step: &str,
+        month_percent: u32,
+        message: String,
+        push: &mut dyn FnMut(String),
+    ) {
+        self.send("error", step, month_percent, message, push);
+    }
+
+    fn send(
+        &self,
+        severity: &str,
+        step: &str,
+        month_percent: u32,
+        message: String,
+        push: &mut dyn FnMut(String),
+    ) {
+        self.steps.observe("archive", step);
+        let percent = self.overall_percent(month_percent);
+        let elapsed_ms = self.started.elapsed().as_millis();
+        // Linear ETA from the run so far; unknown until there is some progress.
+        let eta_ms = (percent > 0 && percent < 100)
+            .then(|| elapsed_ms * (100 - percent) as u128 / percent as u128);
+        push(format!(
+            "PROGRESS step={} severity={} percent={} message=\"{}\"",
+            step, severity, percent, message
+        ));
+        if let Some(emit) = &self.emit {
+            emit(ProgressPayload {
+                correlation_id: self.correlation_id.clone(),
+                step: step.to_string(),
+                severity: severity.to_string(),
+                phase: "archive".to_string(),
+                percent,
+                message,
+                elapsed_ms: Some(elapsed_ms),
+                eta_ms,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn month_bands_map_onto_run_percent() {
+        let mut p = ArchiveProgress::new("t", None);
+        assert_eq!(p.overall_percent(50), 50);
+        p.set_month(1, 4);
+        assert_eq!(p.overall_percent(0), 25);
+        assert_eq!(p.overall_percent(100), 50);
+        p.set_month(3, 4);
+        assert_eq!(p.overall_percent(100), 100);
+        p.set_month(9, 0);
+        assert_eq!(p.overall_percent(100), 100);
+    }
+
+    #[test]
+    fn report_emits_payload_and_transcript_line() {
+        let seen: Arc<Mutex<Vec<ProgressPayload>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let emit: ProgressEmitter = Arc::new(move |p| sink.lock().unwrap().push(p));
+        let p = ArchiveProgress::new("corr-1", Some(emit));
+
+        let mut lines = Vec::new();
+        p.report("zip", 60, "Compressing".to_string(), &mut |l| lines.push(l));
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].phase, "archive");
+        assert_eq!(seen[0].percent, 60);
+        assert_eq!(seen[0].correlation_id, "corr-1");
+        assert_eq!(
+            lines,
+            ["PROGRESS step=zip severity=info percent=60 message=\"Compressing\""]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Hot-row purge for archived months.
+//!
+//! Purge is the only destructive step in the archiver pipeline, so it runs strictly after
+//! the archive ZIP is verified on disk and committed to the ledger. Every gate below must
+//! pass before a single row is deleted:
+//! - not a dry run
+//! - purge enabled in config
+//! - operator confirmation repeats the exact month key (double confirmation)
+//! - a hot database connection is available
+//! - on-disk archive sha256 still matches the ledger entry
+//! - hot row count for the month matches the archived row count
+//!
+//! The count + delete run inside a single DB transaction; any mismatch rolls back.
+
+use anyhow::{Context, Result};
+use chrono::{Months, NaiveDate, NaiveDateTime};
+use futures::TryStreamExt;
+use log::{info, warn};
+use tiberius::{Query, QueryItem};
+
+use crate::database::connection::DatabaseConnection;
+use crate::database::pool;
+
+use super::object_store::{archive_sha256, ArchiveDestination};
+use super::ArchiveLedgerEntry;
+
+/// Hot call-data table and its partitioning timestamp column.
+pub(crate) const HOT_CALLS_SCHEMA: &str = "cadalytix_data";
+pub(crate) const HOT_CALLS_TABLE: &str = "calls";
+pub(crate) const HOT_CALLS_TS_COLUMN: &str = "call_received_at_utc";
+
+/// Purge configuration. Both flags must agree before any rows are deleted.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ArchivePurgeConfig {
+    /// Master switch (off by default).
+    pub enabled: bool,
+    /// Second confirmation: must equal the month key being purged (YYYY-MM).
+    pub confirm_month: Option<String>,
+}
+
+/// Why a purge did not run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PurgeSkipReason {
+    DryRun,
+    Disabled,
+    NotConfirmed,
+    NoConnection,
+}
+
+impl PurgeSkipReason {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PurgeSkipReason::DryRun => "dry_run",
+            PurgeSkipReason::Disabled => "disabled",
+            PurgeSkipReason::NotConfirmed => "not_confirmed",
+            PurgeSkipReason::NoConnection => "no_connection",
+        }
+    }
+}
+
+/// Evaluate the config-level gates (no IO). Returns `None` when purge may proceed.
+pub(crate) fn purge_gate(
+    cfg: &ArchivePurgeConfig,
+    month_key: &str,
+    dry_run: bool,
+    has_connection: bool,
+) -> Option<PurgeSkipReason> {
+    if dry_run {
+        return Some(PurgeSkipReason::DryRun);
+    }
+    if !cfg.enabled {
+        return Some(PurgeSkipReason::Disabled);
+    }
+    let confirmed = cfg
+        .confirm_month
+        .as_deref()
+        .map(|m| m.trim() == month_key)
+        .unwrap_or(false);
+    if !confirmed {
+        return Some(PurgeSkipReason::NotConfirmed);
+    }
+    if !has_connection {
+        return Some(PurgeSkipReason::NoConnection);
+    }
+    None
+}
+
+/// Half-open UTC range `[month_start, next_month_start)` for the archived month.
+pub(crate) fn month_bounds(month_start: NaiveDate) -> Result<(NaiveDateTime, NaiveDateTime)> {
+    let start = month_start
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid month start"))?;
+    let end = month_start
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .ok_or_else(|| anyhow::anyhow!("Invalid month end"))?;
+    Ok((start, end))
+}
+
+pub(crate) fn postgres_count_month_query() -> String {
+    format!(
+        "SELECT COUNT(*) FROM {}.{} WHERE {} >= $1 AND {} < $2",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN, HOT_CALLS_TS_COLUMN
+    )
+}
+
+pub(crate) fn postgres_delete_month_stmt() -> String {
+    format!(
+        "DELETE FROM {}.{} WHERE {} >= $1 AND {} < $2",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN, HOT_CALLS_TS_COLUMN
+    )
+}
+
+pub(crate) fn sql_server_count_month_query() -> String {
+    format!(
+        "SELECT COUNT_BIG(*) FROM [{}].[{}] WHERE [{}] >= @P1 AND [{}] < @P2",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN, HOT_CALLS_TS_COLUMN
+    )
+}
+
+pub(crate) fn sql_server_delete_month_stmt() -> String {
+    format!(
+        "DELETE FROM [{}].[{}] WHERE [{}] >= @P1 AND [{}] < @P2",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN, HOT_CALLS_TS_COLUMN
+    )
+}
+
+/// Re-read the archive from its destination and compare against the ledger checksum.
+/// This is the checksum gate: purge never trusts the in-memory hash from the write step.
+pub(crate) async fn verify_archive_checksum(
+    dest: &ArchiveDestination,
+    entry: &ArchiveLedgerEntry,
+) -> Result<()> {
+    let (actual, _) = archive_sha256(
+        dest,
+        &super::archive_file_name(&entry.month, entry.encrypted),
+    )
+    .await
+    .with_context(|| "Unable to read archive for purge gate")?;
+    if actual != entry.zip_sha256 {
+        anyhow::bail!(
+            "Archive checksum mismatch (expected_sha256={}, actual_sha256={})",
+            entry.zip_sha256,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Delete hot rows for the month inside one transaction.
+/// Returns the number of rows deleted (always equal to `expected_rows` on success).
+pub(crate) async fn purge_month_rows(
+    conn: &DatabaseConnection,
+    month_start: NaiveDate,
+    expected_rows: u64,
+) -> Result<u64> {
+    let (start, end) = month_bounds(month_start)?;
+    info!(
+        "[PHASE: archive] [STEP: purge] Purging hot rows (range_start={}, range_end={}, expected_rows={})",
+        start, end, expected_rows
+    );
+    match conn {
+        DatabaseConnection::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            let count: i64 = sqlx::query_scalar(&postgres_count_month_query())
+                .bind(start)
+                .bind(end)
+                .fetch_one(&mut *tx)
+                .await
+                .with_context(|| "Failed to count hot rows (PostgreSQL)")?;
+            let count = count.max(0) as u64;
+            if count != expected_rows {
+                tx.rollback().await?;
+                anyhow::bail!(
+                    "Hot row count does not match archive (hot_rows={}, archived_rows={})",
+                    count,
+                    expected_rows
+                );
+            }
+            let deleted = sqlx::query(&postgres_delete_month_stmt())
+                .bind(start)
+                .bind(end)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| "Failed to delete hot rows (PostgreSQL)")?
+                .rows_affected();
+            if deleted != expected_rows {
+                tx.rollback().await?;
+                anyhow::bail!(
+                    "Deleted row count does not match archive (deleted_rows={}, archived_rows={})",
+                    deleted,
+                    expected_rows
+                );
+            }
+            tx.commit().await?;
+            Ok(deleted)
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+
+            {
+                let mut stream = client.simple_query("BEGIN TRANSACTION").await?;
+                while stream.try_next().await?.is_some() {}
+            }
+
+            let result: Result<u64> = (async {
+                let mut q = Query::new(sql_server_count_month_query());
+                q.bind(start);
+                q.bind(end);
+                let mut stream = q.query(&mut *client).await?;
+                let mut count: i64 = 0;
+                while let Some(item) = stream.try_next().await? {
+                    if let QueryItem::Row(row) = item {
+                        count = row.get::<i64, _>(0).unwrap_or(0);
+                    }
+                }
+                drop(stream);
+                let count = count.max(0) as u64;
+                if count != expected_rows {
+                    anyhow::bail!(
+                        "Hot row count does not match archive (hot_rows={}, archived_rows={})",
+                        count,
+                        expected_rows
+                    );
+                }
+
+                let mut d = Query::new(sql_server_delete_month_stmt());
+                d.bind(start);
+                d.bind(end);
+                let deleted = d.execute(&mut *client).await?.total();
+                if deleted != expected_rows {
+                    anyhow::bail!(
+                        "Deleted row count does not match archive (deleted_rows={}, archived_rows={})",
+                        deleted,
+                        expected_rows
+                    );
+                }
+                Ok(deleted)
+            })
+            .await;
+
+            match result {
+                Ok(deleted) => {
+                    pool::commit(&mut client).await?;
+                    Ok(deleted)
+                }
+                Err(e) => {
+                    warn!(
+                        "[PHASE: archive] [STEP: purge] Rolling back purge transaction (error={:?})",
+                        e
+                    );
+                    // A failed rollback leaves the client marked broken, so the open
+                    // transaction never goes back into the pool.
+                    if let Err(rollback) = pool::rollback(&mut client).await {
+                        return Err(rollback.context(format!(
+                            "Rolling back the purge transaction failed (error={:#})",
+                            e
+                        )));
+                    }
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confirmed(month: &str) -> ArchivePurgeConfig {
+        ArchivePurgeConfig {
+            enabled: true,
+            confirm_month: Some(month.to_string()),
+        }
+    }
+
+    #[test]
+    fn gate_dry_run_always_skips() {
+        assert_eq!(
+            purge_gate(&confirmed("2025-01"), "2025-01", true, true),
+            Some(PurgeSkipReason::DryRun)
+        );
+    }
+
+    #[test]
+    fn gate_requires_enabled_and_matching_confirmation() {
+        let disabled = ArchivePurgeConfig {
+            enabled: false,
+            confirm_month: Some("2025-01".to_string()),
+        };
+        assert_eq!(
+            purge_gate(&disabled, "2025-01", false, true),
+            Some(PurgeSkipReason::Disabled)
+        );
+
+        let unconfirmed = ArchivePurgeConfig {
+            enabled: true,
+            confirm_month: None,
+        };
+        assert_eq!(
+            purge_gate(&unconfirmed, "2025-01", false, true),
+            Some(PurgeSkipReason::NotConfirmed)
+        );
+        assert_eq!(
+            purge_gate(&confirmed("2025-02"), "2025-01", false, true),
+            Some(PurgeSkipReason::NotConfirmed)
+        );
+        assert_eq!(
+            purge_gate(&confirmed("2025-01"), "2025-01", false, false),
+            Some(PurgeSkipReason::NoConnection)
+        );
+        assert_eq!(
+            purge_gate(&confirmed(" 2025-01 "), "2025-01", false, true),
+            None
+        );
+    }
+
+    #[test]
+    fn month_bounds_are_half_open_and_roll_over_year() {
+        let (s, e) = month_bounds(NaiveDate::from_ymd_opt(2024, 12, 1).unwrap()).unwrap();
+        assert_eq!(s.to_string(), "2024-12-01 00:00:00");
+        assert_eq!(e.to_string(), "2025-01-01 00:00:00");
+    }
+
+    #[test]
+    fn purge_statements_are_range_bounded() {
+        for sql in [
+            postgres_count_month_query(),
+            postgres_delete_month_stmt(),
+            sql_server_count_month_query(),
+            sql_server_delete_month_stmt(),
+        ] {
+            assert!(sql.contains(HOT_CALLS_TS_COLUMN));
+            assert!(sql.contains(">="));
+            assert!(sql.contains(" < "));
+        }
+        assert!(sql_server_delete_month_stmt().starts_with("DELETE FROM [cadalytix_data].[calls]"));
+        assert!(postgres_delete_month_stmt().contains("$2"));
+    }
+}
@@ -0,0 +1,716 @@
+//! Archive restore (rehydrate) path.
+//!
+//! Reads `cadalytix-archive-YYYY-MM.zip` (local folder or cloud URI), verifies its sha256 against the ledger, parses the
+//! NDJSON/CSV payload and re-imports the rows into the hot call-data table. Encrypted archives
+//! (`.zip.enc`) are decrypted transparently with the local SecretProtector-derived archive key.
+//! Cloud archives are downloaded to the staging folder and rows are streamed from the ZIP entry,
+//! so neither the archive nor the month's rows are ever held in memory.
+//!
+//! Safety:
+//! - The ledger entry must exist and be `complete`; the on-disk checksum must match it.
+//! - Encrypted archives require the archive key fingerprint to match the ledger.
+//! - The parsed row count must match the ledger row count.
+//! - Every row's timestamp must fall inside the month (the duplicate guard only covers it).
+//! - Import refuses to run when the hot table already holds rows for the month (no duplicates).
+//! - Insert runs in a single DB transaction.
+//!
+//! Without a hot DB connection (`CADALYTIX_HOT_DB_CONNECTION` unset) the restore stops after
+//! verify + parse and reports what would be imported.
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use futures::TryStreamExt;
+use log::{info, warn};
+use serde_json::{Map, Value};
+use std::io::{BufRead, Read};
+use std::path::PathBuf;
+use std::time::Instant;
+use tiberius::{Query, QueryItem};
+use tokio::sync::mpsc;
+
+use crate::database::connection::DatabaseConnection;
+use crate::database::pool;
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+
+use super::encryption::ArchiveKey;
+use super::export;
+use super::object_store::{download_archive, spawn_file_sha256, ArchiveDestination};
+use super::purge::{month_bounds, HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, HOT_CALLS_TS_COLUMN};
+use super::{
+    archive_file_name, archive_location, default_ledger_path, read_ledger,
+    resolve_entry_destination, staging_dir, write_ledger_entry, ArchiveFormat, StagedFile,
+};
+
+/// Parsed rows waiting for the import at most (the reader thread blocks beyond that).
+const ROW_BUFFER: usize = 256;
+
+type Row = Map<String, Value>;
+
+/// Env var holding the hot DB connection string (kept out of argv so it never lands in process lists).
+pub const HOT_DB_CONNECTION_ENV: &str = "CADALYTIX_HOT_DB_CONNECTION";
+
+/// Arguments for `--archive-restore`.
+#[derive(Debug, Clone)]
+pub struct ArchiveRestoreArgs {
+    /// Month to restore (YYYY-MM).
+    pub month: String,
+    /// Folder or cloud URI holding `cadalytix-archive-YYYY-MM.zip`
+    /// (defaults to the destination recorded in the ledger, then the dry-run destination).
+    pub archive_dir: Option<String>,
+    /// Ledger file (defaults to the dry-run ledger).
+    pub ledger_path: Option<PathBuf>,
+}
+
+impl ArchiveRestoreArgs {
+    /// Parse `--month=YYYY-MM`, `--archive-dir=<path>`, `--ledger=<path>` from argv.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let value_of = |name: &str| {
+            args.iter()
+                .find_map(|a| a.strip_prefix(name).map(|v| v.trim().to_string()))
+                .filter(|v| !v.is_empty())
+        };
+        let month = value_of("--month=")
+            .ok_or_else(|| anyhow::anyhow!("--archive-restore requires --month=YYYY-MM"))?;
+        parse_month_key(&month)?;
+        Ok(Self {
+            month,
+            archive_dir: value_of("--archive-dir="),
+            ledger_path: value_of("--ledger=").map(PathBuf::from),
+        })
+    }
+}
+
+/// Parse `YYYY-MM` into the first day of that month.
+pub(crate) fn parse_month_key(month: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid month '{}' (expected YYYY-MM)", month))
+}
+
+pub async fn archive_restore(args: ArchiveRestoreArgs) -> Result<()> {
+    let started = Instant::now();
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    let month_start = parse_month_key(&args.month)?;
+    let month_key = month_start.format("%Y-%m").to_string();
+    let transcript_path = log_dir.join(format!("B2_archive_restore_{}_transcript.log", month_key));
+
+    let mut transcript = String::new();
+    let mut push = |line: String| {
+        transcript.push_str(&line);
+        transcript.push('\n');
+    };
+
+    push("ARCHIVE_RESTORE begin".to_string());
+    let result = restore_month(&args, month_start, &log_dir, &mut push).await;
+    match &result {
+        Ok(()) => push(format!(
+            "ARCHIVE_RESTORE end elapsed_ms={}",
+            started.elapsed().as_millis()
+        )),
+        Err(e) => push(format!(
+            "ARCHIVE_RESTORE failed elapsed_ms={} error={}",
+            started.elapsed().as_millis(),
+            e
+        )),
+    }
+    push(format!("ExitCode={}", if result.is_ok() { 0 } else { 1 }));
+
+    tokio::fs::write(&transcript_path, transcript).await?;
+    info!(
+        "[PHASE: archive] [STEP: restore] Wrote transcript to {:?}",
+        transcript_path
+    );
+
+    result
+}
+
+async fn restore_month(
+    args: &ArchiveRestoreArgs,
+    month_start: NaiveDate,
+    log_dir: &std::path::Path,
+    push: &mut dyn FnMut(String),
+) -> Result<()> {
+    let month_key = month_start.format("%Y-%m").to_string();
+    let ledger_path = args
+        .ledger_path
+        .clone()
+        .unwrap_or_else(|| default_ledger_path(log_dir));
+
+    // 1) Ledger entry must exist and be complete.
+    let mut entry = read_ledger(&ledger_path)
+        .await?
+        .get(&month_key)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("No ledger entry for month {}", month_key))?;
+    if entry.status != "complete" {
+        anyhow::bail!(
+            "Ledger entry for month {} is not complete (status={})",
+            month_key,
+            entry.status
+        );
+    }
+    let format = ArchiveFormat::parse(&entry.format)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported archive format '{}'", entry.format))?;
+
+    let destination = resolve_entry_destination(args.archive_dir.as_deref(), &entry, log_dir)?;
+    let file_name = archive_file_name(&month_key, entry.encrypted);
+    push(format!(
+        "EVENT archive-restore-start month={} archive={} ledger={}",
+        month_key,
+        archive_location(&destination, &file_name),
+        ledger_path.to_string_lossy()
+    ));
+
+    // 2) Checksum gate, streamed: a local archive is hashed where it is, a cloud one while it
+    // downloads to staging.
+    let staging = staging_dir(&destination);
+    tokio::fs::create_dir_all(&staging)
+        .await
+        .with_context(|| format!("Unable to create staging folder {:?}", staging))?;
+    let mut staged: Vec<StagedFile> = Vec::new();
+    let (stored_path, actual_sha) = match &destination {
+        ArchiveDestination::Local(dir) => {
+            let path = dir.join(&file_name);
+            let (sha, _) = spawn_file_sha256(&path).await?;
+            (path, sha)
+        }
+        ArchiveDestination::Cloud(loc) => {
+            let download =
+                StagedFile::new(staging.join(format!("{}.restore.download.tmp", file_name)));
+            let (sha, _) = download_archive(loc, &file_name, &download.path).await?;
+            let path = download.path.clone();
+            staged.push(download);
+            (path, sha)
+        }
+    };
+    if actual_sha != entry.zip_sha256 {
+        push(format!(
+            "EVENT archive-restore-verify-fail month={} expected_sha256={} actual_sha256={}",
+            month_key, entry.zip_sha256, actual_sha
+        ));
+        anyhow::bail!("Archive verification failed (sha256 mismatch)");
+    }
+    push(format!(
+        "EVENT archive-restore-verify-ok month={} sha256={}",
+        month_key, actual_sha
+    ));
+
+    let zip_path = if entry.encrypted {
+        let secrets = SecretProtector::new(default_key_path(log_dir));
+        let key = ArchiveKey::load(&secrets).await?;
+        if let Err(e) = key.ensure_matches(&entry.key_fingerprint) {
+            push(format!(
+                "EVENT archive-restore-decrypt-fail month={} reason=key_mismatch",
+                month_key
+            ));
+            return Err(e);
+        }
+        let opened = StagedFile::new(staging.join(format!("{}.zip.restore.tmp", month_key)));
+        let (sealed_path, opened_path) = (stored_path.clone(), opened.path.clone());
+        let fingerprint = key.fingerprint().to_string();
+        tokio::task::spawn_blocking(move || -> Result<u64> {
+            let mut input = std::io::BufReader::new(std::fs::File::open(&sealed_path)?);
+            let mut output = std::io::BufWriter::new(std::fs::File::create(&opened_path)?);
+            key.decrypt_stream(&mut input, &mut output)
+        })
+        .await??;
+        push(format!(
+            "EVENT archive-restore-decrypt-ok month={} key_fingerprint={}",
+            month_key, fingerprint
+        ));
+        let path = opened.path.clone();
+        staged.push(opened);
+        path
+    } else {
+        stored_path
+    };
+
+    // 3) Rows are parsed on a blocking thread and streamed to the import (or just counted); the
+    // count must match the ledger and every row must belong to the month.
+    let mut rows = spawn_row_reader(zip_path, format);
+    let bounds = month_bounds(month_start)?;
+    let conn_str = std::env::var(HOT_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|v| !v.trim().is_empty());
+    let Some(conn_str) = conn_str else {
+        let mut parsed: u64 = 0;
+        while let Some(row) = rows.recv().await {
+            ensure_in_month(&row?, bounds)?;
+            parsed += 1;
+        }
+        if parsed != entry.row_count {
+            push(format!(
+                "EVENT archive-restore-parse-fail month={} parsed_rows={} ledger_rows={}",
+                month_key, parsed, entry.row_count
+            ));
+            anyhow::bail!(
+                "Archive row count does not match ledger (parsed_rows={}, ledger_rows={})",
+                parsed,
+                entry.row_count
+            );
+        }
+        push(format!(
+            "EVENT archive-restore-parse-ok month={} format={} rows={}",
+            month_key,
+            format.as_str(),
+            parsed
+        ));
+        push(format!(
+            "EVENT archive-restore-import-skip month={} reason=no_connection env={}",
+            month_key, HOT_DB_CONNECTION_ENV
+        ));
+        return Ok(());
+    };
+
+    // 4) Import in one transaction; a parse error or row count mismatch rolls it back.
+    let engine = crate::api::installer::guess_engine(&conn_str);
+    let conn = crate::api::installer::connect_with_retry(engine.clone(), conn_str)
+        .await
+        .with_context(|| "Unable to connect to the hot database")?;
+    push(format!(
+        "EVENT archive-restore-connected month={} engine={}",
+        month_key, engine
+    ));
+
+    let imported = import_rows(&conn, month_start, &mut rows, entry.row_count)
+        .await
+        .map_err(|e| {
+            push(format!(
+                "EVENT archive-restore-import-fail month={} message=\"{}\"",
+                month_key, e
+            ));
+            e
+        })?;
+    push(format!(
+        "EVENT archive-restore-parse-ok month={} format={} rows={}",
+        month_key,
+        format.as_str(),
+        imported
+    ));
+    push(format!(
+        "EVENT archive-restore-import-ok month={} rows={}",
+        month_key, imported
+    ));
+
+    // Restored rows are hot again: mark the ledger so purge never re-runs on its own.
+    entry.purge_status = "restored".to_string();
+    entry.purged_row_count = 0;
+    write_ledger_entry(&ledger_path, &entry).await?;
+    push(format!(
+        "EVENT archive-ledger-write month={} purge_status=restored restored_utc={}",
+        month_key,
+        Utc::now().to_rfc3339()
+    ));
+
+    Ok(())
+}
+
+/// Rows of the payload file in the ZIP at `zip_path`, read and parsed on a blocking thread. At
+/// most `ROW_BUFFER` rows wait in memory; a parse error is the last item.
+fn spawn_row_reader(zip_path: PathBuf, format: ArchiveFormat) -> mpsc::Receiver<Result<Row>> {
+    let (tx, rx) = mpsc::channel(ROW_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        let result = (|| -> Result<()> {
+            let file = std::fs::File::open(&zip_path)
+                .with_context(|| format!("Unable to open archive {:?}", zip_path))?;
+            let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file))?;
+            let name_in_zip = format.file_name_in_zip();
+            let entry = archive
+                .by_name(name_in_zip)
+                .with_context(|| format!("Archive does not contain {}", name_in_zip))?;
+            read_rows(entry, format, &mut |row| {
+                tx.blocking_send(Ok(row))
+                    .map_err(|_| anyhow::anyhow!("Restore stopped reading the archive"))
+            })
+        })();
+        if let Err(e) = result {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    rx
+}
+
+/// Parse the payload of a `format` archive from `input`, one row at a time.
+pub(crate) fn read_rows(
+    input: impl Read,
+    format: ArchiveFormat,
+    on_row: &mut dyn FnMut(Row) -> Result<()>,
+) -> Result<()> {
+    match format {
+        ArchiveFormat::ZipNdjson => {
+            for (idx, line) in std::io::BufReader::new(input).lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(Value::Object(m)) => on_row(m)?,
+                    Ok(_) => anyhow::bail!("NDJSON line {} is not an object", idx + 1),
+                    Err(e) => anyhow::bail!("NDJSON line {} is invalid: {}", idx + 1, e),
+                }
+            }
+        }
+        ArchiveFormat::ZipCsv => {
+            // Values come back as JSON strings (the DB casts on insert); `\N` is NULL.
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(true)
+                .from_reader(input);
+            let header = reader.headers()?.clone();
+            for (idx, record) in reader.records().enumerate() {
+                let record = record.with_context(|| format!("CSV row {} is invalid", idx + 2))?;
+                let mut m = Map::new();
+                for (k, v) in header.iter().zip(record.iter()) {
+                    m.insert(k.to_string(), export::decode_csv_value(v));
+                }
+                on_row(m)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Column list for the import (the first row's keys; every row of the table has the same ones),
+/// validated as plain identifiers (they are spliced into SQL).
+pub(crate) fn import_columns(first_row: &Row) -> Result<Vec<String>> {
+    let cols: Vec<String> = first_row.keys().cloned().collect();
+    for c in &cols {
+        let ok = !c.is_empty()
+            && c.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            && !c.starts_with(|ch: char| ch.is_ascii_digit());
+        if !ok {
+            anyhow::bail!("Archive column name '{}' is not a valid identifier", c);
+        }
+    }
+    if !cols.iter().any(|c| c == HOT_CALLS_TS_COLUMN) {
+        anyhow::bail!("Archive rows do not contain {}", HOT_CALLS_TS_COLUMN);
+    }
+    Ok(cols)
+}
+
+/// Next row from the reader, checked against the import columns (fixed by the first row) and
+/// the month `bounds`.
+async fn next_row(
+    rows: &mut mpsc::Receiver<Result<Row>>,
+    cols: &mut Option<Vec<String>>,
+    bounds: (NaiveDateTime, NaiveDateTime),
+) -> Result<Option<Row>> {
+    let Some(row) = rows.recv().await.transpose()? else {
+        return Ok(None);
+    };
+    match cols {
+        Some(cols) => {
+            if let Some(extra) = row.keys().find(|k| !cols.contains(k)) {
+                anyhow::bail!(
+                    "Archive row has column '{}' that the first row does not",
+                    extra
+                );
+            }
+        }
+        None => *cols = Some(import_columns(&row)?),
+    }
+    ensure_in_month(&row, bounds)?;
+    Ok(Some(row))
+}
+
+/// Reject a row whose timestamp is outside `[start, end)`: the "month already has rows" guard
+/// only counted that range, so such a row could duplicate another month's data.
+fn ensure_in_month(row: &Row, (start, end): (NaiveDateTime, NaiveDateTime)) -> Result<()> {
+    let raw = row.get(HOT_CALLS_TS_COLUMN).and_then(Value::as_str);
+    let ts = raw
+        .and_then(export::parse_row_timestamp)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Archive row has no valid {} (value={:?})",
+                HOT_CALLS_TS_COLUMN,
+                raw
+            )
+        })?
+        .naive_utc();
+    if ts < start || ts >= end {
+        anyhow::bail!(
+            "Archive row {}={} is outside the restored month ({} to {})",
+            HOT_CALLS_TS_COLUMN,
+            ts,
+            start,
+            end
+        );
+    }
+    Ok(())
+}
+
+fn ensure_row_count(imported: u64, ledger_rows: u64) -> Result<()> {
+    if imported != ledger_rows {
+        anyhow::bail!(
+            "Archive row count does not match ledger (parsed_rows={}, ledger_rows={})",
+            imported,
+            ledger_rows
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn postgres_insert_row_stmt() -> String {
+    // json_populate_record lets Postgres cast each JSON field to the table's column type.
+    format!(
+        "INSERT INTO {schema}.{table} SELECT * FROM json_populate_record(NULL::{schema}.{table}, $1::json)",
+        schema = HOT_CALLS_SCHEMA,
+        table = HOT_CALLS_TABLE
+    )
+}
+
+pub(crate) fn sql_server_insert_row_stmt(cols: &[String]) -> String {
+    let col_list = cols
+        .iter()
+        .map(|c| format!("[{}]", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let with_list = cols
+        .iter()
+        .map(|c| format!("[{}] NVARCHAR(MAX) '$.{}'", c, c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO [{}].[{}] ({}) SELECT {} FROM OPENJSON(@P1) WITH ({})",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE, col_list, col_list, with_list
+    )
+}
+
+async fn import_rows(
+    conn: &DatabaseConnection,
+    month_start: NaiveDate,
+    rows: &mut mpsc::Receiver<Result<Row>>,
+    ledger_rows: u64,
+) -> Result<u64> {
+    let (start, end) = month_bounds(month_start)?;
+    info!(
+        "[PHASE: archive] [STEP: restore] Importing rows (range_start={}, ledger_rows={})",
+        start, ledger_rows
+    );
+    let mut cols: Option<Vec<String>> = None;
+
+    match conn {
+        DatabaseConnection::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            let existing: i64 = sqlx::query_scalar(&super::purge::postgres_count_month_query())
+                .bind(start)
+                .bind(end)
+                .fetch_one(&mut *tx)
+                .await
+                .with_context(|| "Failed to count hot rows (PostgreSQL)")?;
+            if existing > 0 {
+                tx.rollback().await?;
+                anyhow::bail!(
+                    "Hot table already contains {} rows for this month; refusing to restore duplicates",
+                    existing
+                );
+            }
+            // Dropping `tx` on an error rolls it back.
+            let stmt = postgres_insert_row_stmt();
+            let mut inserted: u64 = 0;
+            while let Some(row) = next_row(rows, &mut cols, (start, end)).await? {
+                inserted += sqlx::query(&stmt)
+                    .bind(Value::Object(row).to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .with_context(|| "Failed to insert restored row (PostgreSQL)")?
+                    .rows_affected();
+            }
+            ensure_row_count(inserted, ledger_rows)?;
+            tx.commit().await?;
+            Ok(inserted)
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+
+            {
+                let mut stream = client.simple_query("BEGIN TRANSACTION").await?;
+                while stream.try_next().await?.is_some() {}
+            }
+
+            let result: Result<u64> = (async {
+                let mut q = Query::new(super::purge::sql_server_count_month_query());
+                q.bind(start);
+                q.bind(end);
+                let mut stream = q.query(&mut *client).await?;
+                let mut existing: i64 = 0;
+                while let Some(item) = stream.try_next().await? {
+                    if let QueryItem::Row(row) = item {
+                        existing = row.get::<i64, _>(0).unwrap_or(0);
+                    }
+                }
+                drop(stream);
+                if existing > 0 {
+                    anyhow::bail!(
+                        "Hot table already contains {} rows for this month; refusing to restore duplicates",
+                        existing
+                    );
+                }
+
+                let mut insert_stmt: Option<String> = None;
+                let mut inserted: u64 = 0;
+                while let Some(row) = next_row(rows, &mut cols, (start, end)).await? {
+                    let stmt = insert_stmt.get_or_insert_with(|| {
+                        sql_server_insert_row_stmt(cols.as_deref().unwrap_or_default())
+                    });
+                    let mut ins = Query::new(stmt.clone());
+                    ins.bind(Value::Object(row).to_string());
+                    inserted += ins.execute(&mut *client).await?.total();
+                }
+                ensure_row_count(inserted, ledger_rows)?;
+                Ok(inserted)
+            })
+            .await;
+
+            match result {
+                Ok(inserted) => {
+                    pool::commit(&mut client).await?;
+                    Ok(inserted)
+                }
+                Err(e) => {
+                    warn!(
+                        "[PHASE: archive] [STEP: restore] Rolling back restore transaction (error={:?})",
+                        e
+                    );
+                    // A failed rollback leaves the client marked broken, so the open
+                    // transaction never goes back into the pool.
+                    if let Err(rollback) = pool::rollback(&mut client).await {
+                        return Err(rollback.context(format!(
+                            "Rolling back the restore transaction failed (error={:#})",
+                            e
+                        )));
+                    }
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_require_valid_month() {
+        let args = vec![
+            "installer".to_string(),
+            "--archive-restore".to_string(),
+            "--month=2025-01".to_string(),
+            "--archive-dir=/tmp/archive".to_string(),
+        ];
+        let parsed = ArchiveRestoreArgs::from_args(&args).unwrap();
+        assert_eq!(parsed.month, "2025-01");
+        assert_eq!(parsed.archive_dir.as_deref(), Some("/tmp/archive"));
+        assert!(parsed.ledger_path.is_none());
+
+        let missing = vec!["--archive-restore".to_string()];
+        assert!(ArchiveRestoreArgs::from_args(&missing).is_err());
+        let bad = vec![
+            "--archive-restore".to_string(),
+            "--month=2025-13".to_string(),
+        ];
+        assert!(ArchiveRestoreArgs::from_args(&bad).is_err());
+    }
+
+    fn parse(format: ArchiveFormat, payload: &str) -> Result<Vec<Row>> {
+        let mut rows = Vec::new();
+        read_rows(payload.as_bytes(), format, &mut |row| {
+            rows.push(row);
+            Ok(())
+        })?;
+        Ok(rows)
+    }
+
+    #[test]
+    fn ndjson_and_csv_parse_to_same_keys() {
+        let nd = "{\"call_id\":1,\"call_received_at_utc\":\"2025-01-01T00:00:00+00:00\"}\n\n";
+        let rows = parse(ArchiveFormat::ZipNdjson, nd).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["call_id"], Value::from(1));
+
+        let csv = "call_id,call_received_at_utc,notes\n1,2025-01-01T00:00:00+00:00,\"a, \"\"b\"\"\nline 2\"\n2,2025-01-02T00:00:00+00:00,\\N\n";
+        let rows = parse(ArchiveFormat::ZipCsv, csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["notes"], Value::from("a, \"b\"\nline 2"));
+        assert_eq!(rows[1]["notes"], Value::Null);
+        assert!(parse(ArchiveFormat::ZipCsv, "a,b\n1\n").is_err());
+        assert!(parse(ArchiveFormat::ZipCsv, "\n").unwrap().is_empty());
+        assert!(parse(ArchiveFormat::ZipNdjson, "[1,2]\n").is_err());
+    }
+
+    #[test]
+    fn import_columns_reject_unsafe_identifiers() {
+        let row = |json: &str| serde_json::from_str::<Row>(json).unwrap();
+        assert!(import_columns(&row("{\"call_received_at_utc\":\"x\",\"bad col\":1}")).is_err());
+        assert!(import_columns(&row("{\"call_id\":1}")).is_err());
+        let cols = import_columns(&row("{\"call_id\":1,\"call_received_at_utc\":\"x\"}")).unwrap();
+        let sql = sql_server_insert_row_stmt(&cols);
+        assert!(sql.contains("[call_id] NVARCHAR(MAX) '$.call_id'"));
+        assert!(sql.starts_with("INSERT INTO [cadalytix_data].[calls]"));
+    }
+
+    #[test]
+    fn rows_must_fall_inside_the_month() {
+        let row = |ts: &str| {
+            serde_json::from_str::<Row>(&format!(
+                "{{\"call_id\":1,\"call_received_at_utc\":{}}}",
+                ts
+            ))
+            .unwrap()
+        };
+        let january = month_bounds(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+        assert!(ensure_in_month(&row("\"2025-01-01T00:00:00\""), january).is_ok());
+        assert!(ensure_in_month(&row("\"2025-01-31T23:59:59.999+00:00\""), january).is_ok());
+        assert!(ensure_in_month(&row("\"2025-02-01T00:00:00\""), january).is_err());
+        assert!(ensure_in_month(&row("\"2024-12-31T23:59:59\""), january).is_err());
+        // An offset moves the instant: 01:00+02:00 on Feb 1 is still January in UTC.
+        assert!(ensure_in_month(&row("\"2025-02-01T01:00:00+02:00\""), january).is_ok());
+        assert!(ensure_in_month(&row("null"), january).is_err());
+        assert!(ensure_in_month(&row("\"not a timestamp\""), january).is_err());
+    }
+
+    #[tokio::test]
+    async fn rows_stream_from_the_zip_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file("calls.ndjson", zip::write::FileOptions::default())
+            .unwrap();
+        for i in 0..(ROW_BUFFER * 2) {
+            std::io::Write::write_all(
+                &mut zip,
+                format!(
+                    "{{\"call_id\":{},\"call_received_at_utc\":\"2025-01-{:02}T00:00:00\"}}\n",
+                    i,
+                    i % 31 + 1
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        }
+        zip.finish().unwrap();
+
+        let january = month_bounds(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+        let mut rows = spawn_row_reader(path.clone(), ArchiveFormat::ZipNdjson);
+        let mut cols = None;
+        let mut n = 0;
+        while let Some(row) = next_row(&mut rows, &mut cols, january).await.unwrap() {
+            assert_eq!(row["call_id"], Value::from(n));
+            n += 1;
+        }
+        assert_eq!(n, ROW_BUFFER * 2);
+        assert!(ensure_row_count(n as u64, n as u64 + 1).is_err());
+
+        // The same archive restored as February: the first row already falls outside.
+        let february = month_bounds(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()).unwrap();
+        let mut rows = spawn_row_reader(path.clone(), ArchiveFormat::ZipNdjson);
+        let err = next_row(&mut rows, &mut None, february).await.unwrap_err();
+        assert!(err.to_string().contains("outside the restored month"));
+
+        // The CSV entry is missing: the reader reports it instead of ending quietly.
+        let mut rows = spawn_row_reader(path, ArchiveFormat::ZipCsv);
+        assert!(rows.recv().await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Disk space preflight for archive runs.
+//!
+//! Before a month is exported we project its size (exact row count for the month × the table's
+//! average on-disk row size from DB stats) and compare it with free space where the archive is
+//! staged (`utils::disk`). Running out of space mid-write leaves nothing useful behind, so a
+//! short volume fails the month up front with a clear message instead.
+//!
+//! The projection is deliberately conservative: on-disk row size is usually larger than the
+//! compressed export, and encrypted runs need room for the ZIP and its sealed copy at once.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use futures::TryStreamExt;
+use log::warn;
+use tiberius::{Query, QueryItem};
+
+use crate::database::connection::DatabaseConnection;
+
+use super::purge::{
+    month_bounds, postgres_count_month_query, sql_server_count_month_query, HOT_CALLS_SCHEMA,
+    HOT_CALLS_TABLE,
+};
+
+/// Used when table stats are unavailable (no rows analyzed yet, or no permission on DMVs).
+pub(crate) const FALLBACK_AVG_ROW_BYTES: u64 = 1024;
+
+/// Headroom kept free on the staging volume on top of the projection.
+const SAFETY_MARGIN_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Projected export size for one month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExportEstimate {
+    pub row_count: u64,
+    pub avg_row_bytes: u64,
+}
+
+impl ExportEstimate {
+    pub(crate) fn projected_bytes(&self) -> u64 {
+        self.row_count.saturating_mul(self.avg_row_bytes)
+    }
+
+    /// Free bytes needed at the staging location for this month.
+    pub(crate) fn required_free_bytes(&self, encrypted: bool) -> u64 {
+        let copies = if encrypted { 2 } else { 1 };
+        self.projected_bytes()
+            .saturating_mul(copies)
+            .saturating_add(SAFETY_MARGIN_BYTES)
+    }
+}
+
+pub(crate) fn postgres_avg_row_bytes_query() -> &'static str {
+    "SELECT (pg_relation_size(c.oid) / NULLIF(c.reltuples, 0))::float8 \
+     FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+     WHERE n.nspname = $1 AND c.relname = $2"
+}
+
+pub(crate) fn sql_server_avg_row_bytes_query() -> String {
+    format!(
+        "SELECT CAST(SUM(ps.used_page_count) * 8192.0 / NULLIF(SUM(CASE WHEN ps.index_id IN (0, 1) THEN ps.row_count ELSE 0 END), 0) AS float) \
+         FROM sys.dm_db_partition_stats ps WHERE ps.object_id = OBJECT_ID(N'[{}].[{}]')",
+        HOT_CALLS_SCHEMA, HOT_CALLS_TABLE
+    )
+}
+
+/// Count the month's hot rows and read the table's average row size.
+pub(crate) async fn estimate_month_export(
+    conn: &DatabaseConnection,
+    month_start: NaiveDate,
+) -> Result<ExportEstimate> {
+    let (start, end) = month_bounds(month_start)?;
+    let (row_count, avg) = match conn {
+        DatabaseConnection::Postgres(pool) => {
+            let count: i64 = sqlx::query_scalar(&postgres_count_month_query())
+                .bind(start)
+                .bind(end)
+                .fetch_one(pool)
+                .await
+                .with_context(|| "Failed to count hot rows (PostgreSQL)")?;
+            let avg: Option<f64> = match sqlx::query_scalar::<_, Option<f64>>(
+                postgres_avg_row_bytes_query(),
+            )
+            .bind(HOT_CALLS_SCHEMA)
+            .bind(HOT_CALLS_TABLE)
+            .fetch_optional(pool)
+            .await
+            {
+                Ok(v) => v.flatten(),
+                Err(e) => {
+                    warn!(
+                        "[PHASE: archive] [STEP: space_check] Table stats unavailable (PostgreSQL): {:?}",
+                        e
+                    );
+                    None
+                }
+            };
+            (count, avg)
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+
+            let mut q = Query::new(sql_server_count_month_query());
+            q.bind(start);
+            q.bind(end);
+            let mut count: i64 = 0;
+            let mut stream = q
+                .query(&mut *client)
+                .await
+                .with_context(|| "Failed to count hot rows (SQL Server)")?;
+            while let Some(item) = stream.try_next().await? {
+                if let QueryItem::Row(row) = item {
+                    count = row.get::<i64, _>(0).unwrap_or(0);
+                }
+            }
+            drop(stream);
+
+            // sys.dm_db_partition_stats needs VIEW DATABASE STATE; fall back when denied.
+            let stats: Result<Option<f64>> = (async {
+                let mut stream = client
+                    .simple_query(sql_server_avg_row_bytes_query())
+                    .await?;
+                let mut avg: Option<f64> = None;
+                while let Some(item) = stream.try_next().await? {
+                    if let QueryItem::Row(row) = item {
+                        avg = row.get::<f64, _>(0);
+                    }
+                }
+                Ok(avg)
+            })
+            .await;
+            let avg = stats.unwrap_or_else(|e| {
+                warn!(
+                    "[PHASE: archive] [STEP: space_check] Table stats unavailable (SQL Server): {:?}",
+                    e
+                );
+                None
+            });
+            (count, avg)
+        }
+    };
+
+    Ok(ExportEstimate {
+        row_count: row_count.max(0) as u64,
+        avg_row_bytes: avg_row_bytes_or_fallback(avg),
+    })
+}
+
+fn avg_row_bytes_or_fallback(avg: Option<f64>) -> u64 {
+    match avg {
+        Some(v) if v.is_finite() && v >= 1.0 => v.ceil() as u64,
+        _ => FALLBACK_AVG_ROW_BYTES,
+    }
+}
+
+/// Whole MiB (rounded up) for operator-facing messages.
+pub(crate) fn mib(bytes: u64) -> u64 {
+    bytes.div_ceil(1024 * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_space_covers_projection_encryption_and_margin() {
+        let est = ExportEstimate {
+            row_count: 1_000_000,
+            avg_row_bytes: 500,
+        };
+        assert_eq!(est.projected_bytes(), 500_000_000);
+        assert_eq!(
+            est.required_free_bytes(false),
+            500_000_000 + SAFETY_MARGIN_BYTES
+        );
+        assert_eq!(
+            est.required_free_bytes(true),
+            1_000_000_000 + SAFETY_MARGIN_BYTES
+        );
+
+        let empty = ExportEstimate {
+            row_count: 0,
+            avg_row_bytes: 500,
+        };
+        assert_eq!(empty.required_free_bytes(true), SAFETY_MARGIN_BYTES);
+        assert_eq!(mib(SAFETY_MARGIN_BYTES + 1), 65);
+    }
+
+    #[test]
+    fn missing_or_bogus_stats_use_fallback_row_size() {
+        assert_eq!(avg_row_bytes_or_fallback(None), FALLBACK_AVG_ROW_BYTES);
+        assert_eq!(
+            avg_row_bytes_or_fallback(Some(f64::NAN)),
+            FALLBACK_AVG_ROW_BYTES
+        );
+        assert_eq!(avg_row_bytes_or_fallback(Some(0.0)), FALLBACK_AVG_ROW_BYTES);
+        assert_eq!(avg_row_bytes_or_fallback(Some(312.2)), 313);
+    }
+}
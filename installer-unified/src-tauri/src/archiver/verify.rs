@@ -0,0 +1,341 @@
+//! Ledger integrity verification (`--archive-verify-ledger`).
+//!
+//! Walks every ledger entry, re-reads the archive from its destination, recomputes sha256 and
+//! reports months whose archive is missing, corrupted or unreadable. The report is JSON so
+//! monitoring can parse it; the CLI exits nonzero whenever the report is not healthy.
+//!
+//! Read-only: nothing in the ledger or at the destination is modified.
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+
+use super::encryption::ArchiveKey;
+use super::object_store::{archive_sha256, is_not_found_error, ArchiveDestination};
+use super::{
+    archive_file_name, archive_location, default_ledger_path, read_ledger,
+    resolve_entry_destination, ArchiveLedgerEntry,
+};
+
+/// Arguments for `--archive-verify-ledger`.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveVerifyArgs {
+    /// Folder or cloud URI to check instead of each entry's recorded destination.
+    pub archive_dir: Option<String>,
+    /// Ledger file (defaults to the dry-run ledger).
+    pub ledger_path: Option<PathBuf>,
+}
+
+impl ArchiveVerifyArgs {
+    /// Parse `--archive-dir=<path>`, `--ledger=<path>` from argv.
+    pub fn from_args(args: &[String]) -> Self {
+        let value_of = |name: &str| {
+            args.iter()
+                .find_map(|a| a.strip_prefix(name).map(|v| v.trim().to_string()))
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            archive_dir: value_of("--archive-dir="),
+            ledger_path: value_of("--ledger=").map(PathBuf::from),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonthHealth {
+    Ok,
+    Missing,
+    Corrupted,
+    Unreadable,
+    KeyMismatch,
+    /// Ledger entry is not `complete`; there is no archive to check yet.
+    Skipped,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthVerifyResult {
+    pub month: String,
+    pub status: MonthHealth,
+    pub location: String,
+    pub expected_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_sha256: Option<String>,
+    pub expected_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Machine-readable verification report (also written to `B2_archive_verify_ledger_report.json`).
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerVerifyReport {
+    pub generated_utc: String,
+    pub ledger_path: String,
+    pub healthy: bool,
+    pub total_months: usize,
+    pub ok_count: usize,
+    pub missing_count: usize,
+    pub corrupted_count: usize,
+    pub unreadable_count: usize,
+    pub key_mismatch_count: usize,
+    pub skipped_count: usize,
+    pub elapsed_ms: u128,
+    pub months: Vec<MonthVerifyResult>,
+}
+
+impl LedgerVerifyReport {
+    fn from_results(ledger_path: String, months: Vec<MonthVerifyResult>, elapsed_ms: u128) -> Self {
+        let count = |h: MonthHealth| months.iter().filter(|m| m.status == h).count();
+        let ok_count = count(MonthHealth::Ok);
+        let skipped_count = count(MonthHealth::Skipped);
+        Self {
+            generated_utc: Utc::now().to_rfc3339(),
+            ledger_path,
+            healthy: ok_count + skipped_count == months.len(),
+            total_months: months.len(),
+            ok_count,
+            missing_count: count(MonthHealth::Missing),
+            corrupted_count: count(MonthHealth::Corrupted),
+            unreadable_count: count(MonthHealth::Unreadable),
+            key_mismatch_count: count(MonthHealth::KeyMismatch),
+            skipped_count,
+            elapsed_ms,
+            months,
+        }
+    }
+}
+
+pub async fn archive_verify_ledger(args: ArchiveVerifyArgs) -> Result<LedgerVerifyReport> {
+    let started = Instant::now();
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    let ledger_path = args
+        .ledger_path
+        .clone()
+        .unwrap_or_else(|| default_ledger_path(&log_dir));
+    if !tokio::fs::try_exists(&ledger_path).await.unwrap_or(false) {
+        anyhow::bail!("Archive ledger not found: {:?}", ledger_path);
+    }
+
+    let ledger = read_ledger(&ledger_path).await?;
+    info!(
+        "[PHASE: archive] [STEP: verify_ledger] Verifying archive ledger (path={:?}, months={})",
+        ledger_path,
+        ledger.len()
+    );
+
+    // Only derived when the ledger holds encrypted months (never creates a key otherwise).
+    let mut archive_key: Option<ArchiveKey> = None;
+    let mut months = Vec::with_capacity(ledger.len());
+    for entry in ledger.values() {
+        if entry.encrypted && archive_key.is_none() {
+            let secrets = SecretProtector::new(default_key_path(&log_dir));
+            archive_key = Some(ArchiveKey::load(&secrets).await?);
+        }
+        let result = verify_entry(
+            entry,
+            args.archive_dir.as_deref(),
+            &log_dir,
+            archive_key.as_ref(),
+        )
+        .await;
+        if result.status != MonthHealth::Ok && result.status != MonthHealth::Skipped {
+            warn!(
+                "[PHASE: archive] [STEP: verify_ledger] Archive unhealthy (month={}, status={:?}, location={})",
+                result.month, result.status, result.location
+            );
+        }
+        months.push(result);
+    }
+
+    let report = LedgerVerifyReport::from_results(
+        ledger_path.to_string_lossy().to_string(),
+        months,
+        started.elapsed().as_millis(),
+    );
+    let report_path = log_dir.join("B2_archive_verify_ledger_report.json");
+    tokio::fs::write(&report_path, serde_json::to_string_pretty(&report)?).await?;
+    info!(
+        "[PHASE: archive] [STEP: verify_ledger] Wrote report to {:?} (healthy={}, ok={}, missing={}, corrupted={})",
+        report_path, report.healthy, report.ok_count, report.missing_count, report.corrupted_count
+    );
+
+    Ok(report)
+}
+
+async fn verify_entry(
+    entry: &ArchiveLedgerEntry,
+    override_dir: Option<&str>,
+    log_dir: &std::path::Path,
+    archive_key: Option<&ArchiveKey>,
+) -> MonthVerifyResult {
+    let file_name = archive_file_name(&entry.month, entry.encrypted);
+    let mut result = MonthVerifyResult {
+        month: entry.month.clone(),
+        status: MonthHealth::Ok,
+        location: String::new(),
+        expected_sha256: entry.zip_sha256.clone(),
+        actual_sha256: None,
+        expected_bytes: entry.zip_bytes,
+        actual_bytes: None,
+        message: None,
+    };
+
+    if entry.status != "complete" {
+        result.status = MonthHealth::Skipped;
+        result.message = Some(format!("ledger status is '{}'", entry.status));
+        return result;
+    }
+
+    let destination = match resolve_entry_destination(override_dir, entry, log_dir) {
+        Ok(d) => d,
+        Err(e) => {
+            result.status = MonthHealth::Unreadable;
+            result.message = Some(e.to_string());
+            return result;
+        }
+    };
+    result.location = archive_location(&destination, &file_name);
+
+    if let ArchiveDestination::Local(dir) = &destination {
+        if !tokio::fs::try_exists(dir.join(&file_name))
+            .await
+            .unwrap_or(false)
+        {
+            result.status = MonthHealth::Missing;
+            return result;
+        }
+    }
+
+    let (actual_sha, actual_bytes) = match archive_sha256(&destination, &file_name).await {
+        Ok(h) => h,
+        Err(e) => {
+            let message = format!("{:#}", e);
+            result.status = if is_not_found_error(&e) {
+                MonthHealth::Missing
+            } else {
+                MonthHealth::Unreadable
+            };
+            result.message = Some(message);
+            return result;
+        }
+    };
+
+    classify(&mut result, entry, actual_sha, actual_bytes, archive_key);
+    result
+}
+
+/// Compare the stored archive's hash against the ledger entry (no IO).
+fn classify(
+    result: &mut MonthVerifyResult,
+    entry: &ArchiveLedgerEntry,
+    actual_sha: String,
+    actual_bytes: u64,
+    archive_key: Option<&ArchiveKey>,
+) {
+    result.actual_bytes = Some(actual_bytes);
+    result.actual_sha256 = Some(actual_sha.clone());
+
+    if actual_sha != entry.zip_sha256 {
+        result.status = MonthHealth::Corrupted;
+        result.message = Some("sha256 does not match ledger".to_string());
+        return;
+    }
+    if entry.encrypted {
+        if let Some(key) = archive_key {
+            if let Err(e) = key.ensure_matches(&entry.key_fingerprint) {
+                result.status = MonthHealth::KeyMismatch;
+                result.message = Some(e.to_string());
+                return;
+            }
+        }
+    }
+    result.status = MonthHealth::Ok;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(month: &str, bytes: &[u8]) -> ArchiveLedgerEntry {
+        ArchiveLedgerEntry {
+            month: month.to_string(),
+            status: "complete".to_string(),
+            format: "zip+ndjson".to_string(),
+            row_count: 1,
+            min_ts_utc: String::new(),
+            max_ts_utc: String::new(),
+            zip_sha256: crate::security::crypto::sha256_hex(bytes),
+            zip_bytes: bytes.len() as u64,
+            created_utc: String::new(),
+            purge_status: String::new(),
+            purged_row_count: 0,
+            destination: String::new(),
+            encrypted: false,
+            key_fingerprint: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_ok_missing_and_corrupted_months() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let dest = dir.path().to_string_lossy().to_string();
+
+        let good = entry("2025-01", b"good archive");
+        tokio::fs::write(
+            dir.path().join(archive_file_name("2025-01", false)),
+            b"good archive",
+        )
+        .await
+        .unwrap();
+        let corrupted = entry("2025-02", b"original bytes");
+        tokio::fs::write(
+            dir.path().join(archive_file_name("2025-02", false)),
+            b"bit rot",
+        )
+        .await
+        .unwrap();
+        let missing = entry("2025-03", b"never written");
+
+        let mut results = Vec::new();
+        for e in [&good, &corrupted, &missing] {
+            results.push(verify_entry(e, Some(&dest), dir.path(), None).await);
+        }
+        assert_eq!(results[0].status, MonthHealth::Ok);
+        assert_eq!(results[1].status, MonthHealth::Corrupted);
+        assert_eq!(results[2].status, MonthHealth::Missing);
+
+        let report = LedgerVerifyReport::from_results("ledger.json".to_string(), results, 0);
+        assert!(!report.healthy);
+        assert_eq!(
+            (
+                report.ok_count,
+                report.corrupted_count,
+                report.missing_count
+            ),
+            (1, 1, 1)
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["months"][1]["status"], "corrupted");
+        assert_eq!(json["missingCount"], 1);
+    }
+
+    #[tokio::test]
+    async fn incomplete_entries_do_not_fail_health() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut pending = entry("2025-04", b"x");
+        pending.status = "in_progress".to_string();
+        let result = verify_entry(&pending, None, dir.path(), None).await;
+        assert_eq!(result.status, MonthHealth::Skipped);
+
+        let report = LedgerVerifyReport::from_results(String::new(), vec![result], 0);
+        assert!(report.healthy);
+    }
+}
@@ -0,0 +1,534 @@
+// Dedicated application account (optional install step).
+//
+// The install itself runs with the admin credentials from the Database step; the runtime should
+// not. When enabled, the installer creates (or re-keys) a login/role for the application that may
+// only read and write the CADalytix schemas, and hands the application a connection string with
+// those credentials instead of the admin ones.
+//
+// - SQL Server: a SQL login plus a user in the config database, granted SELECT, INSERT, UPDATE,
+//   DELETE and EXECUTE on the CADalytix schemas. No server or database roles.
+// - PostgreSQL: a LOGIN role without CREATEDB/CREATEROLE, granted CONNECT on the database, USAGE on
+//   the CADalytix schemas, DML on their tables, their sequences, and (default privileges) on
+//   tables created later by the installer role.
+//
+// The password is generated here and never logged; an existing login of the same name gets a new
+// one, since the old password is not known to the installer.
+
+use anyhow::{Context, Result};
+use log::info;
+use regex::Regex;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::database::conn_string::{PostgresConnString, SqlServerConnString};
+use crate::database::connection::DatabaseConnection;
+use crate::database::provisioning::{bracket_quote, pg_quote_ident};
+
+/// Login used when the request does not name one.
+pub const DEFAULT_APP_LOGIN: &str = "cadalytix_app";
+
+/// Schemas the application reads and writes at runtime (those that exist get grants).
+const RUNTIME_SCHEMAS: [&str; 2] = ["cadalytix_config", "cadalytix_data"];
+
+/// Schema permissions the application account gets on SQL Server.
+const SQL_SERVER_RUNTIME_GRANTS: [&str; 5] = ["SELECT", "INSERT", "UPDATE", "DELETE", "EXECUTE"];
+
+const PASSWORD_LEN: usize = 32;
+const PASSWORD_LOWER: &[u8] = b"abcdefghijkmnopqrstuvwxyz";
+const PASSWORD_UPPER: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const PASSWORD_DIGITS: &[u8] = b"23456789";
+const PASSWORD_SYMBOLS: &[u8] = b"-_.!#%+";
+
+/// Application account settings from the install request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppAccountConfig {
+    /// Create the account and give it to the application instead of the admin credentials.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Login/role name; empty uses `DEFAULT_APP_LOGIN`.
+    #[serde(default)]
+    pub login_name: String,
+}
+
+impl AppAccountConfig {
+    pub fn login(&self) -> &str {
+        match self.login_name.trim() {
+            "" => DEFAULT_APP_LOGIN,
+            name => name,
+        }
+    }
+
+    /// Checks the login name when the account is enabled.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        validate_login_name(self.login())
+    }
+}
+
+/// The account as provisioned.
+#[derive(Debug, Clone)]
+pub struct AppAccount {
+    pub login: String,
+    /// Connection string for the application (the admin one with these credentials).
+    pub connection_string: String,
+    /// The login existed before this run (it was re-keyed, not created).
+    pub existed: bool,
+}
+
+/// Validate an application login name (letters, numbers, underscore; 1-63 chars)
+pub fn validate_login_name(name: &str) -> Result<(), String> {
+    let re = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]{0,62}$").unwrap();
+    if !re.is_match(name.trim()) {
+        return Err(
+            "Application login must start with a letter or underscore, contain only letters, numbers, and underscores, and be 63 characters or fewer.".to_string(),
+        );
+    }
+    let reserved = ["sa", "postgres", "public", "dbo", "guest", "sys"];
+    if reserved.iter().any(|r| r.eq_ignore_ascii_case(name.trim())) {
+        return Err(format!("'{}' is a reserved login name.", name.trim()));
+    }
+    Ok(())
+}
+
+/// Random password that meets the SQL Server password policy (all four character classes).
+pub fn generate_password() -> Result<String> {
+    let rng = SystemRandom::new();
+    let mut bytes = [0u8; PASSWORD_LEN];
+    rng.fill(&mut bytes)
+        .map_err(|_| anyhow::anyhow!("Failed to generate a password"))?;
+    let classes = [
+        PASSWORD_LOWER,
+        PASSWORD_UPPER,
+        PASSWORD_DIGITS,
+        PASSWORD_SYMBOLS,
+    ];
+    let all: Vec<u8> = classes.concat();
+    // One character from each class first, the rest from all of them.
+    let password = bytes
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let set = classes.get(i).copied().unwrap_or(all.as_slice());
+            char::from(set[usize::from(*b) % set.len()])
+        })
+        .collect();
+    Ok(password)
+}
+
+/// Statements that create or re-key the login and grant runtime access (SQL Server, run in the
+/// config database).
+pub fn sql_server_account_stmt(login: &str, password: &str) -> String {
+    let name = login.replace('\'', "''");
+    let quoted = bracket_quote(login);
+    let password = password.replace('\'', "''");
+    let mut stmt = format!(
+        "IF SUSER_ID(N'{name}') IS NULL CREATE LOGIN {quoted} WITH PASSWORD = N'{password}', CHECK_POLICY = ON \
+         ELSE ALTER LOGIN {quoted} WITH PASSWORD = N'{password}'; \
+         IF DATABASE_PRINCIPAL_ID(N'{name}') IS NULL CREATE USER {quoted} FOR LOGIN {quoted};"
+    );
+    stmt.push_str(&sql_server_grant_stmt(login));
+    stmt
+}
+
+/// Runtime grants on the CADalytix schemas that exist (SQL Server, run in the config database).
+pub fn sql_server_grant_stmt(login: &str) -> String {
+    let quoted = bracket_quote(login);
+    RUNTIME_SCHEMAS
+        .iter()
+        .map(|schema| {
+            format!(
+                " IF SCHEMA_ID(N'{schema}') IS NOT NULL GRANT {} ON SCHEMA::{} TO {quoted};",
+                SQL_SERVER_RUNTIME_GRANTS.join(", "),
+                bracket_quote(schema)
+            )
+        })
+        .collect()
+}
+
+/// Statements that create or re-key the role and grant runtime access (PostgreSQL, run in the
+/// config database, one at a time).
+pub fn postgres_account_stmts(login: &str, password: &str) -> Vec<String> {
+    let name = login.replace('\'', "''");
+    let quoted = pg_quote_ident(login);
+    let password = password.replace('\'', "''");
+    vec![
+        format!(
+            "DO $$ BEGIN \
+             IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = '{name}') THEN \
+             ALTER ROLE {quoted} LOGIN PASSWORD '{password}'; \
+             ELSE CREATE ROLE {quoted} LOGIN PASSWORD '{password}' NOSUPERUSER NOCREATEDB NOCREATEROLE; \
+             END IF; END $$;"
+        ),
+        postgres_grant_stmt(login),
+    ]
+}
+
+/// Runtime grants on the CADalytix schemas that exist (PostgreSQL, run in the config database).
+pub fn postgres_grant_stmt(login: &str) -> String {
+    let name = login.replace('\'', "''");
+    let schemas = RUNTIME_SCHEMAS
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "DO $$ DECLARE s text; BEGIN \
+         EXECUTE format('GRANT CONNECT ON DATABASE %I TO %I', current_database(), '{name}'); \
+         FOR s IN SELECT nspname FROM pg_namespace WHERE nspname IN ({schemas}) LOOP \
+         EXECUTE format('GRANT USAGE ON SCHEMA %I TO %I', s, '{name}'); \
+         EXECUTE format('GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA %I TO %I', s, '{name}'); \
+         EXECUTE format('GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA %I TO %I', s, '{name}'); \
+         EXECUTE format('ALTER DEFAULT PRIVILEGES IN SCHEMA %I GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO %I', s, '{name}'); \
+         END LOOP; END $$;"
+    )
+}
+
+/// Statements that remove the login again (rollback), run in the config database.
+pub fn drop_account_stmts(engine: &str, login: &str) -> Vec<String> {
+    if engine == "postgres" {
+        let quoted = pg_quote_ident(login);
+        vec![
+            format!("DROP OWNED BY {};", quoted),
+            format!("DROP ROLE IF EXISTS {};", quoted),
+        ]
+    } else {
+        let quoted = bracket_quote(login);
+        vec![format!(
+            "DROP USER IF EXISTS {quoted}; IF SUSER_ID(N'{}') IS NOT NULL DROP LOGIN {quoted};",
+            login.replace('\'', "''")
+        )]
+    }
+}
+
+/// `admin_conn_str` with the application credentials in place of the admin ones.
+pub fn app_connection_string(
+    engine: &str,
+    admin_conn_str: &str,
+    login: &str,
+    password: &str,
+) -> Result<String> {
+    let admin_user = if engine == "postgres" {
+        PostgresConnString::parse(admin_conn_str)
+            .context("The config database connection string must be a postgresql:// URL to create the application account")?
+            .user
+    } else {
+        SqlServerConnString::parse(admin_conn_str)?.user
+    };
+    if admin_user.is_some_and(|u| u.trim().eq_ignore_ascii_case(login)) {
+        anyhow::bail!(
+            "The application login '{}' is the admin login used for the install; choose another name.",
+            login
+        );
+    }
+    if engine == "postgres" {
+        PostgresConnString::parse(admin_conn_str)?
+            .credentials(login, password)
+            .build()
+    } else {
+        let mut cs = SqlServerConnString::parse(admin_conn_str)?;
+        cs.integrated_security = false;
+        Ok(cs.credentials(login, password).build())
+    }
+}
+
+async fn login_exists(conn: &DatabaseConnection, login: &str) -> Result<bool> {
+    if let Some(pool) = conn.as_postgres() {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = $1)")
+                .bind(login)
+                .fetch_one(pool)
+                .await?;
+        return Ok(exists);
+    }
+    let client_arc = conn
+        .as_sql_server()
+        .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+    let mut client = client_arc.lock().await;
+    let rows = client
+        .simple_query(format!(
+            "SELECT CASE WHEN SUSER_ID(N'{}') IS NOT NULL THEN 1 ELSE 0 END AS login_exists;",
+            login.replace('\'', "''")
+        ))
+        .await?
+        .into_first_result()
+        .await?;
+    Ok(rows.first().and_then(|r| r.get::<i32, _>("login_exists")) == Some(1))
+}
+
+/// Create (or re-key) the application login on the database `conn` points at and grant it
+/// runtime access. `admin_conn_str` is the connection string `conn` was opened with.
+pub async fn provision(
+    conn: &DatabaseConnection,
+    engine: &str,
+    admin_conn_str: &str,
+    login: &str,
+) -> Result<AppAccount> {
+    validate_login_name(login).map_err(anyhow::Error::msg)?;
+    let password = generate_password()?;
+    let connection_string = app_connection_string(engine, admin_conn_str, login, &password)?;
+    let existed = login_exists(conn, login)
+        .await
+        .context("Failed to look up the application login")?;
+
+    if let Some(pool) = conn.as_postgres() {
+        for stmt in postgres_account_stmts(login, &password) {
+            sqlx::query(&stmt)
+                .execute(pool)
+                .await
+                .context("Failed to create the application role")?;
+        }
+    } else {
+        let client_arc = conn
+            .as_sql_server()
+            .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+        let mut client = client_arc.lock().await;
+        client
+            .simple_query(sql_server_account_stmt(login, &password))
+            .await
+            .context("Failed to create the application login")?
+            .into_results()
+            .await
+            .context("Failed to create the application login")?;
+    }
+    info!(
+        "[PHASE: database] [STEP: app_account] Application login ready (login={}, existed={})",
+        login, existed
+    );
+    Ok(AppAccount {
+        login: login.to_string(),
+        connection_string,
+        existed,
+    })
+}
+
+/// Remove a login `provision` created (rollback of a cancelled install).
+pub async fn drop_account(conn: &DatabaseConnection, engine: &str, login: &str) -> Result<()> {
+    if let Some(pool) = conn.as_postgres() {
+        for stmt in drop_account_stmts(engine, login) {
+            sqlx::query(&stmt).execute(pool).await?;
+        }
+        return Ok(());
+    }
+    let client_arc = conn
+        .as_sql_server()
+        .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+    let mut client = client_arc.lock().await;
+    for stmt in drop_account_stmts(engine, login) {
+        client.simple_query(stmt).await?.into_results().await?;
+    }
+    Ok(())
+}
+
+/// Runtime grants the application login lacks on the CADalytix schemas that exist, e.g.
+/// `"cadalytix_config: DELETE, EXECUTE"`. Errors when the login no longer exists.
+pub async fn missing_grants(conn: &DatabaseConnection, login: &str) -> Result<Vec<String>> {
+    if !login_exists(conn, login).await? {
+        anyhow::bail!("Application login '{}' does not exist", login);
+    }
+    if let Some(pool) = conn.as_postgres() {
+        let schemas: Vec<String> = RUNTIME_SCHEMAS.iter().map(|s| s.to_string()).collect();
+        let mut missing = Vec::new();
+        let connect: bool =
+            sqlx::query_scalar("SELECT has_database_privilege($1, current_database(), 'CONNECT')")
+                .bind(login)
+                .fetch_one(pool)
+                .await?;
+        if !connect {
+            missing.push("database: CONNECT".to_string());
+        }
+        let rows: Vec<(String, bool, i64)> = sqlx::query_as(
+            "SELECT n.nspname::text, has_schema_privilege($1, n.oid, 'USAGE'), \
+             (SELECT count(*) FROM pg_class c WHERE c.relnamespace = n.oid AND c.relkind IN ('r', 'p') \
+              AND NOT (has_table_privilege($1, c.oid, 'SELECT') AND has_table_privilege($1, c.oid, 'INSERT') \
+              AND has_table_privilege($1, c.oid, 'UPDATE') AND has_table_privilege($1, c.oid, 'DELETE'))) \
+             FROM pg_namespace n WHERE n.nspname = ANY($2) ORDER BY n.nspname",
+        )
+        .bind(login)
+        .bind(&schemas)
+        .fetch_all(pool)
+        .await?;
+        for (schema, usage, tables) in rows {
+            if !usage {
+                missing.push(format!("{}: USAGE", schema));
+            }
+            if tables > 0 {
+                missing.push(format!(
+                    "{}: SELECT, INSERT, UPDATE, DELETE on {} table(s)",
+                    schema, tables
+                ));
+            }
+        }
+        return Ok(missing);
+    }
+
+    let client_arc = conn
+        .as_sql_server()
+        .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+    let mut client = client_arc.lock().await;
+    let schemas = RUNTIME_SCHEMAS
+        .iter()
+        .map(|s| format!("N'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = client
+        .simple_query(format!(
+            "SELECT s.name AS schema_name, p.permission_name AS permission_name \
+             FROM sys.schemas s \
+             LEFT JOIN sys.database_permissions p ON p.class = 3 AND p.major_id = s.schema_id \
+             AND p.state IN ('G', 'W') AND p.grantee_principal_id = DATABASE_PRINCIPAL_ID(N'{}') \
+             WHERE s.name IN ({});",
+            login.replace('\'', "''"),
+            schemas
+        ))
+        .await?
+        .into_first_result()
+        .await?;
+    let granted: Vec<(String, Option<String>)> = rows
+        .iter()
+        .filter_map(|r| {
+            let schema = r.get::<&str, _>("schema_name")?.to_string();
+            Some((
+                schema,
+                r.get::<&str, _>("permission_name").map(str::to_string),
+            ))
+        })
+        .collect();
+    Ok(sql_server_missing_grants(&granted))
+}
+
+/// Missing SQL Server grants from (schema, granted permission) rows; a schema without any grant
+/// appears once with `None`.
+fn sql_server_missing_grants(granted: &[(String, Option<String>)]) -> Vec<String> {
+    let mut schemas: Vec<&str> = granted.iter().map(|(s, _)| s.as_str()).collect();
+    schemas.sort_unstable();
+    schemas.dedup();
+    schemas
+        .into_iter()
+        .filter_map(|schema| {
+            let missing: Vec<&str> = SQL_SERVER_RUNTIME_GRANTS
+                .iter()
+                .copied()
+                .filter(|perm| {
+                    !granted.iter().any(|(s, p)| {
+                        s == schema && p.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(perm))
+                    })
+                })
+                .collect();
+            (!missing.is_empty()).then(|| format!("{}: {}", schema, missing.join(", ")))
+        })
+        .collect()
+}
+
+/// Re-apply the runtime grants of an existing application login (its password is unchanged).
+pub async fn grant_runtime_access(conn: &DatabaseConnection, login: &str) -> Result<()> {
+    validate_login_name(login).map_err(anyhow::Error::msg)?;
+    if let Some(pool) = conn.as_postgres() {
+        sqlx::query(&postgres_grant_stmt(login))
+            .execute(pool)
+            .await
+            .context("Failed to grant the application role runtime access")?;
+    } else {
+        let client_arc = conn
+            .as_sql_server()
+            .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+        let mut client = client_arc.lock().await;
+        client
+            .simple_query(sql_server_grant_stmt(login))
+            .await
+            .context("Failed to grant the application login runtime access")?
+            .into_results()
+            .await
+            .context("Failed to grant the application login runtime access")?;
+    }
+    info!(
+        "[PHASE: database] [STEP: app_account] Runtime grants re-applied (login={})",
+        login
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_statements_grant_runtime_access_and_swap_credentials() {
+        let pw = generate_password().unwrap();
+        assert_eq!(pw.len(), PASSWORD_LEN);
+        assert!(pw.chars().any(|c| c.is_ascii_lowercase()));
+        assert!(pw.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(pw.chars().any(|c| c.is_ascii_digit()));
+        assert!(pw.bytes().any(|b| PASSWORD_SYMBOLS.contains(&b)));
+
+        let sql = sql_server_account_stmt("cad_app", "Pw1!");
+        assert!(sql.starts_with("IF SUSER_ID(N'cad_app') IS NULL CREATE LOGIN [cad_app]"));
+        assert!(sql.contains(
+            "GRANT SELECT, INSERT, UPDATE, DELETE, EXECUTE ON SCHEMA::[cadalytix_config] TO [cad_app];"
+        ));
+        assert!(!sql.contains("db_owner") && !sql.contains("ALTER ROLE"));
+        let pg = postgres_account_stmts("cad_app", "Pw1!");
+        assert!(pg[0].contains("CREATE ROLE \"cad_app\" LOGIN PASSWORD 'Pw1!' NOSUPERUSER"));
+        assert!(pg[1].contains("IN ('cadalytix_config', 'cadalytix_data')"));
+
+        let cs = app_connection_string(
+            "sqlserver",
+            "Server=db;Database=cadalytix;Integrated Security=true",
+            "cad_app",
+            "p;w",
+        )
+        .unwrap();
+        let parsed = SqlServerConnString::parse(&cs).unwrap();
+        assert!(!parsed.integrated_security);
+        assert_eq!(parsed.user.as_deref(), Some("cad_app"));
+        assert_eq!(parsed.password.as_deref(), Some("p;w"));
+        assert_eq!(parsed.database.as_deref(), Some("cadalytix"));
+        let cs = app_connection_string(
+            "postgres",
+            "postgresql://admin:secret@db:5432/cadalytix",
+            "cad_app",
+            "p@w",
+        )
+        .unwrap();
+        assert_eq!(cs, "postgresql://cad_app:p%40w@db:5432/cadalytix");
+        assert!(app_connection_string(
+            "postgres",
+            "postgresql://cad_app:secret@db/cadalytix",
+            "cad_app",
+            "x"
+        )
+        .is_err());
+
+        assert!(AppAccountConfig::default().validate().is_ok());
+        let bad = AppAccountConfig {
+            enabled: true,
+            login_name: "sa".to_string(),
+        };
+        assert!(bad.validate().is_err());
+        assert_eq!(bad.login(), "sa");
+    }
+
+    #[test]
+    fn missing_sql_server_grants_are_named_per_schema() {
+        let row = |schema: &str, perm: Option<&str>| (schema.to_string(), perm.map(str::to_string));
+        let granted = vec![
+            row("cadalytix_config", Some("SELECT")),
+            row("cadalytix_config", Some("INSERT")),
+            row("cadalytix_config", Some("UPDATE")),
+            row("cadalytix_data", None),
+            row("cadalytix_config", Some("EXECUTE")),
+        ];
+        assert_eq!(
+            sql_server_missing_grants(&granted),
+            vec![
+                "cadalytix_config: DELETE".to_string(),
+                "cadalytix_data: SELECT, INSERT, UPDATE, DELETE, EXECUTE".to_string(),
+            ]
+        );
+        assert!(sql_server_grant_stmt("cad_app").contains(
+            "IF SCHEMA_ID(N'cadalytix_data') IS NOT NULL GRANT SELECT, INSERT, UPDATE, DELETE, EXECUTE ON SCHEMA::[cadalytix_data] TO [cad_app];"
+        ));
+    }
+}
@@ -15,6 +15,9 @@ use tokio::sync::Mutex;
 use tokio::time::timeout;
 use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
 
+use super::conn_string::{ensure_integrated_auth_available, SqlServerConnString};
+use super::pool::{self, PooledSqlServerClient};
+
 // =============================================================================
 // DbConnector Trait — Enables deterministic testing without real DB
 // =============================================================================
@@ -47,12 +50,12 @@ pub trait DbConnector: Send + Sync {
 
     /// Get the timeout duration for connection attempts.
     fn timeout_duration(&self) -> Duration {
-        Duration::from_secs(20)
+        crate::utils::retry::policy().attempt_timeout
     }
 
     /// Get the maximum number of retry attempts.
     fn max_retries(&self) -> u32 {
-        3
+        crate::utils::retry::policy().max_retries
     }
 }
 
@@ -85,8 +88,11 @@ impl DbConnector for RealDbConnector {
         match result {
             Ok(Ok(_)) => Ok(()),
             Ok(Err(e)) => Err(ConnectError {
-                user_message: "Unable to connect. Verify host, credentials, and network access."
-                    .to_string(),
+                user_message: if is_integrated_auth(engine, connection_string) {
+                    integrated_auth_hint()
+                } else {
+                    "Unable to connect. Verify host, credentials, and network access.".to_string()
+                },
                 internal_details: format!("Connection error: {}", e),
             }),
             Err(_) => Err(ConnectError {
@@ -98,6 +104,44 @@ impl DbConnector for RealDbConnector {
     }
 }
 
+fn is_integrated_auth(engine: &str, connection_string: &str) -> bool {
+    engine != "postgres"
+        && SqlServerConnString::parse(connection_string).is_ok_and(|cs| cs.integrated_security)
+}
+
+/// What to check when a Windows Authentication login fails.
+fn integrated_auth_hint() -> String {
+    if let Err(e) = ensure_integrated_auth_available() {
+        return e.to_string();
+    }
+    if cfg!(windows) {
+        "Unable to connect with Windows Authentication. Verify host and network access, and that \
+         the current Windows account has a login on the server."
+            .to_string()
+    } else {
+        "Unable to connect with Windows Authentication (Kerberos). Verify host and network access, \
+         obtain a ticket with kinit (or set KRB5_CLIENT_KTNAME to a keytab), and check that the \
+         server has an MSSQLSvc SPN."
+            .to_string()
+    }
+}
+
+/// Open a new SQL Server client; `database::pool` calls this when its pool needs a connection.
+pub(crate) async fn open_sql_server_client(
+    connection_string: &str,
+) -> Result<Client<Compat<TcpStream>>> {
+    if SqlServerConnString::parse(connection_string).is_ok_and(|cs| cs.integrated_security) {
+        ensure_integrated_auth_available()?;
+    }
+    let config = Config::from_ado_string(connection_string)?;
+    let tcp = TcpStream::connect(config.get_addr()).await?;
+    tcp.set_nodelay(true)?;
+
+    // Convert TcpStream to compatible async write stream for tiberius
+    // tiberius expects a futures::io::AsyncWrite, so we use compat_write
+    Ok(Client::connect(config, tcp.compat_write()).await?)
+}
+
 #[allow(dead_code)]
 pub enum DatabaseEngine {
     SqlServer,
@@ -109,8 +153,9 @@ pub enum DatabaseEngine {
 /// This is the production-ready pattern for tiberius clients
 pub struct SqlServerConnection {
     // Arc<Mutex<>> allows safe concurrent access to the client
-    // This is the standard pattern for shared async database clients
-    client: Arc<Mutex<Client<Compat<TcpStream>>>>,
+    // This is the standard pattern for shared async database clients.
+    // The client is checked out of the shared pool and returns to it when the last clone drops.
+    client: Arc<Mutex<PooledSqlServerClient>>,
 }
 
 impl Clone for SqlServerConnection {
@@ -124,7 +169,7 @@ impl Clone for SqlServerConnection {
 impl SqlServerConnection {
     /// Get a reference to the client for executing queries
     /// Returns a guard that can be used to execute queries
-    pub fn client(&self) -> Arc<Mutex<Client<Compat<TcpStream>>>> {
+    pub fn client(&self) -> Arc<Mutex<PooledSqlServerClient>> {
         Arc::clone(&self.client)
     }
 
@@ -147,22 +192,15 @@ pub enum DatabaseConnection {
 }
 
 impl DatabaseConnection {
-    /// Create a PostgreSQL connection
+    /// Create a PostgreSQL connection (shared pool, see `database::pool`)
     pub async fn postgres(connection_string: &str) -> Result<Self> {
-        let pool = Pool::<Postgres>::connect(connection_string).await?;
+        let pool = pool::postgres(connection_string).await?;
         Ok(DatabaseConnection::Postgres(pool))
     }
 
-    /// Create a SQL Server connection
-    /// This is a production-ready implementation using proper async patterns
+    /// Create a SQL Server connection (client from the shared pool, see `database::pool`)
     pub async fn sql_server(connection_string: &str) -> Result<Self> {
-        let config = Config::from_ado_string(connection_string)?;
-        let tcp = TcpStream::connect(config.get_addr()).await?;
-        tcp.set_nodelay(true)?;
-
-        // Convert TcpStream to compatible async write stream for tiberius
-        // tiberius expects a futures::io::AsyncWrite, so we use compat_write
-        let client = Client::connect(config, tcp.compat_write()).await?;
+        let client = pool::sql_server(connection_string).await?;
 
         // Wrap in Arc<Mutex<>> for thread-safe shared access
         // This is the standard pattern for production database clients
@@ -172,7 +210,7 @@ impl DatabaseConnection {
     }
 
     /// Get SQL Server client if this is a SQL Server connection
-    pub fn as_sql_server(&self) -> Option<Arc<Mutex<Client<Compat<TcpStream>>>>> {
+    pub fn as_sql_server(&self) -> Option<Arc<Mutex<PooledSqlServerClient>>> {
         match self {
             DatabaseConnection::SqlServer(conn) => Some(conn.client()),
             _ => None,
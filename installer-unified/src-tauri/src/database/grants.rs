@@ -0,0 +1,213 @@
+// Effective grants of the connected principal on the target database.
+//
+// A successful connection does not mean the install will succeed: the installer creates and
+// alters the config tables and inserts seed rows, so the principal needs CREATE TABLE, INSERT and
+// ALTER on the target database. These checks read the principal's effective permissions per
+// engine and name whatever is missing, for the connection test and the permissions preflight.
+//
+// - SQL Server: HAS_PERMS_BY_NAME on the database, plus the fixed roles that imply each grant
+//   (db_owner, db_ddladmin, db_datawriter).
+// - PostgreSQL: CREATE on the config schema (or on the database while the schema does not exist
+//   yet), INSERT on every existing config table, and ownership (ALTER) of every existing config
+//   table. Tables the installer creates itself are owned by the principal.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::database::connection::DatabaseConnection;
+use crate::database::provisioning::{bracket_quote, pg_quote_ident};
+
+/// Schema holding the config tables.
+const CONFIG_SCHEMA: &str = "cadalytix_config";
+
+/// Grants the install needs on the target database, in the order they are reported.
+pub const REQUIRED: [&str; 3] = ["CREATE TABLE", "INSERT", "ALTER"];
+
+/// What the connected principal may do on the target database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Grants {
+    pub principal: String,
+    pub database: String,
+    pub create_table: bool,
+    pub insert: bool,
+    pub alter: bool,
+}
+
+impl Grants {
+    /// Names of the required grants the principal lacks (empty when the install can proceed).
+    pub fn missing(&self) -> Vec<String> {
+        REQUIRED
+            .iter()
+            .zip([self.create_table, self.insert, self.alter])
+            .filter(|(_, granted)| !granted)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Statement(s) an administrator runs to grant `missing` to this principal.
+    pub fn grant_hint(&self, engine: &str, missing: &[String]) -> String {
+        if missing.is_empty() {
+            return String::new();
+        }
+        if engine == "postgres" {
+            let role = pg_quote_ident(&self.principal);
+            let mut hints = Vec::new();
+            if missing.iter().any(|m| m == "CREATE TABLE") {
+                hints.push(format!(
+                    "GRANT CREATE ON DATABASE {} TO {};",
+                    pg_quote_ident(&self.database),
+                    role
+                ));
+            }
+            if missing.iter().any(|m| m == "INSERT") {
+                hints.push(format!(
+                    "GRANT INSERT ON ALL TABLES IN SCHEMA {} TO {};",
+                    CONFIG_SCHEMA, role
+                ));
+            }
+            if missing.iter().any(|m| m == "ALTER") {
+                hints.push(format!(
+                    "make {} the owner of the {} tables (ALTER TABLE ... OWNER TO {};)",
+                    role, CONFIG_SCHEMA, role
+                ));
+            }
+            hints.join(" ")
+        } else {
+            format!(
+                "USE {}; GRANT {} TO {};",
+                bracket_quote(&self.database),
+                missing.join(", "),
+                bracket_quote(&self.principal)
+            )
+        }
+    }
+}
+
+/// SQL to read the current user's grants on the current database (SQL Server)
+pub fn sql_server_grants_query() -> &'static str {
+    r#"
+    SELECT
+        USER_NAME() AS principal,
+        DB_NAME() AS database_name,
+        CASE
+            WHEN IS_MEMBER('db_owner') = 1 OR IS_MEMBER('db_ddladmin') = 1 THEN 1
+            WHEN HAS_PERMS_BY_NAME(DB_NAME(), 'DATABASE', 'CREATE TABLE') = 1 THEN 1
+            ELSE 0
+        END AS can_create_table,
+        CASE
+            WHEN IS_MEMBER('db_owner') = 1 OR IS_MEMBER('db_datawriter') = 1 THEN 1
+            WHEN HAS_PERMS_BY_NAME(DB_NAME(), 'DATABASE', 'INSERT') = 1 THEN 1
+            ELSE 0
+        END AS can_insert,
+        CASE
+            WHEN IS_MEMBER('db_owner') = 1 OR IS_MEMBER('db_ddladmin') = 1 THEN 1
+            WHEN HAS_PERMS_BY_NAME(DB_NAME(), 'DATABASE', 'ALTER ANY SCHEMA') = 1 THEN 1
+            ELSE 0
+        END AS can_alter
+    "#
+}
+
+/// SQL to read the current role's grants on the current database (PostgreSQL)
+pub fn postgres_grants_query() -> &'static str {
+    r#"
+    SELECT
+        current_user::text AS principal,
+        current_database()::text AS database_name,
+        COALESCE(
+            (SELECT has_schema_privilege(n.oid, 'CREATE')
+             FROM pg_namespace n WHERE n.nspname = 'cadalytix_config'),
+            has_database_privilege(current_database(), 'CREATE')
+        ) AS can_create_table,
+        COALESCE(
+            (SELECT bool_and(has_table_privilege(c.oid, 'INSERT'))
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = 'cadalytix_config' AND c.relkind IN ('r', 'p')),
+            true
+        ) AS can_insert,
+        COALESCE(
+            (SELECT bool_and(pg_has_role(c.relowner, 'USAGE'))
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = 'cadalytix_config' AND c.relkind IN ('r', 'p')),
+            true
+        ) AS can_alter
+    "#
+}
+
+/// Read the connected principal's grants on the database `conn` points at.
+pub async fn check(conn: &DatabaseConnection) -> Result<Grants> {
+    if let Some(pool) = conn.as_postgres() {
+        use sqlx::Row;
+        let row = sqlx::query(postgres_grants_query())
+            .fetch_one(pool)
+            .await
+            .context("Failed to read database permissions")?;
+        return Ok(Grants {
+            principal: row.try_get("principal").unwrap_or_default(),
+            database: row.try_get("database_name").unwrap_or_default(),
+            create_table: row.try_get("can_create_table").unwrap_or(false),
+            insert: row.try_get("can_insert").unwrap_or(false),
+            alter: row.try_get("can_alter").unwrap_or(false),
+        });
+    }
+
+    let client_arc = conn
+        .as_sql_server()
+        .ok_or_else(|| anyhow::anyhow!("Internal error: unsupported connection"))?;
+    let mut client = client_arc.lock().await;
+    let rows = client
+        .simple_query(sql_server_grants_query())
+        .await
+        .context("Failed to read database permissions")?
+        .into_first_result()
+        .await
+        .context("Failed to read database permissions")?;
+    let row = rows
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Permission query returned no rows"))?;
+    let text = |col: &str| row.get::<&str, _>(col).unwrap_or_default().to_string();
+    let flag = |col: &str| row.get::<i32, _>(col) == Some(1);
+    Ok(Grants {
+        principal: text("principal"),
+        database: text("database_name"),
+        create_table: flag("can_create_table"),
+        insert: flag("can_insert"),
+        alter: flag("can_alter"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_grants_are_named_with_an_engine_specific_hint() {
+        let grants = Grants {
+            principal: "cadalytix_app".to_string(),
+            database: "cadalytix".to_string(),
+            create_table: true,
+            insert: false,
+            alter: false,
+        };
+        let missing = grants.missing();
+        assert_eq!(missing, vec!["INSERT", "ALTER"]);
+        assert_eq!(
+            grants.grant_hint("sqlserver", &missing),
+            "USE [cadalytix]; GRANT INSERT, ALTER TO [cadalytix_app];"
+        );
+        let pg = grants.grant_hint("postgres", &missing);
+        assert!(pg.starts_with(
+            "GRANT INSERT ON ALL TABLES IN SCHEMA cadalytix_config TO \"cadalytix_app\";"
+        ));
+        assert!(pg.contains("OWNER TO \"cadalytix_app\""));
+
+        let all = Grants {
+            create_table: true,
+            insert: true,
+            alter: true,
+            ..grants
+        };
+        assert!(all.missing().is_empty());
+        assert_eq!(all.grant_hint("postgres", &[]), "");
+    }
+}
@@ -0,0 +1,187 @@
+// Host name check ahead of a connection test.
+//
+// "Unable to connect" alone does not tell a mistyped host name from a wrong password. The
+// connection test therefore resolves the host of the connection string first and reports:
+// - `NotFound`: the name does not resolve; the test stops there.
+// - `Ipv6Only`: the name has only IPv6 addresses, which fail from IPv4-only networks.
+// - `Loopback`: a remote name resolves to this machine (a hosts file entry or split DNS).
+// - `Private`: a cloud endpoint name resolves to a private address (a private endpoint, or a VPN
+//   or hosts file override); fine when intended, confusing when not.
+// All but `NotFound` are warnings (`classify`): the test still connects, and mentions them if that
+// fails. The resolved addresses are then used for the TCP stage of the test.
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use super::conn_string::{
+    PostgresConnString, SqlServerConnString, POSTGRES_DEFAULT_PORT, SQL_SERVER_DEFAULT_PORT,
+};
+
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Public DNS suffixes of managed database services; see `HostProblem::Private`.
+const CLOUD_SUFFIXES: &[&str] = &[
+    ".database.windows.net",
+    ".database.azure.com",
+    ".rds.amazonaws.com",
+    ".neon.tech",
+    ".supabase.co",
+    ".supabase.com",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostProblem {
+    /// The name does not resolve (resolver message).
+    NotFound(String),
+    Ipv6Only,
+    Loopback,
+    Private,
+}
+
+impl HostProblem {
+    /// User-facing explanation for `host`.
+    pub fn message(&self, host: &str) -> String {
+        match self {
+            HostProblem::NotFound(detail) => format!(
+                "The host name '{}' could not be resolved ({}). Check the spelling of the host and the DNS settings of this machine.",
+                host, detail
+            ),
+            HostProblem::Ipv6Only => format!(
+                "'{}' has only IPv6 addresses. If this network has no IPv6 route, use an IPv4 address or a host name with an IPv4 record.",
+                host
+            ),
+            HostProblem::Loopback => format!(
+                "'{}' resolves to this machine (loopback). Check the hosts file or DNS if the database runs elsewhere.",
+                host
+            ),
+            HostProblem::Private => format!(
+                "'{}' resolves to a private network address. This is expected for a private endpoint; otherwise check VPN, hosts file or DNS overrides.",
+                host
+            ),
+        }
+    }
+}
+
+/// Host and port a connection string points at; `None` for named pipes, local aliases and
+/// strings that do not parse (their own validation reports those). The port is `None` for a SQL
+/// Server named instance without one (the SQL Browser assigns it).
+pub fn target(engine: &str, conn_str: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = match engine {
+        "postgres" => {
+            let cs = PostgresConnString::parse(conn_str).ok()?;
+            (cs.host, Some(cs.port.unwrap_or(POSTGRES_DEFAULT_PORT)))
+        }
+        _ => {
+            let cs = SqlServerConnString::parse(conn_str).ok()?;
+            // `host\INSTANCE`: the instance is looked up by the SQL Browser, not DNS.
+            let (host, port) = match cs.host.split_once('\\') {
+                Some((host, _)) => (host.to_string(), cs.port),
+                None => (
+                    cs.host.clone(),
+                    Some(cs.port.unwrap_or(SQL_SERVER_DEFAULT_PORT)),
+                ),
+            };
+            (host, port)
+        }
+    };
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    if host.is_empty() || matches!(host.as_str(), "." | "(local)") || host.starts_with("np:") {
+        return None;
+    }
+    Some((host, port))
+}
+
+/// Addresses of `host`; `NotFound` when it has none.
+pub async fn resolve(host: &str) -> Result<Vec<IpAddr>, HostProblem> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![ip]);
+    }
+    match timeout(RESOLVE_TIMEOUT, tokio::net::lookup_host((host, 0))).await {
+        Ok(Ok(addrs)) => {
+            let addrs: Vec<IpAddr> = addrs.map(|a| a.ip()).collect();
+            if addrs.is_empty() {
+                return Err(HostProblem::NotFound("no addresses".to_string()));
+            }
+            Ok(addrs)
+        }
+        Ok(Err(e)) => Err(HostProblem::NotFound(e.to_string())),
+        Err(_) => Err(HostProblem::NotFound("DNS lookup timed out".to_string())),
+    }
+}
+
+/// The problem with the addresses `host` resolved to, if any; none for an address literal.
+pub fn classify(host: &str, addrs: &[IpAddr]) -> Option<HostProblem> {
+    if host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    let name = host.to_ascii_lowercase();
+    if name != "localhost" && !name.ends_with(".localhost") && addrs.iter().all(IpAddr::is_loopback)
+    {
+        return Some(HostProblem::Loopback);
+    }
+    if CLOUD_SUFFIXES.iter().any(|s| name.ends_with(s)) && addrs.iter().all(is_private) {
+        return Some(HostProblem::Private);
+    }
+    if addrs.iter().all(IpAddr::is_ipv6) {
+        return Some(HostProblem::Ipv6Only);
+    }
+    None
+}
+
+fn is_private(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || v4.is_link_local(),
+        // fc00::/7 unique local addresses.
+        IpAddr::V6(v6) => (v6.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolved_addresses_are_classified() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert_eq!(classify("localhost", &[ip("127.0.0.1")]), None);
+        assert_eq!(
+            classify("db.example.org", &[ip("127.0.1.1")]),
+            Some(HostProblem::Loopback)
+        );
+        assert_eq!(
+            classify("cad.database.windows.net", &[ip("10.1.2.3")]),
+            Some(HostProblem::Private)
+        );
+        assert_eq!(classify("db.example.org", &[ip("10.1.2.3")]), None);
+        assert_eq!(
+            classify("db.example.org", &[ip("2001:db8::5")]),
+            Some(HostProblem::Ipv6Only)
+        );
+        assert_eq!(
+            classify("db.example.org", &[ip("2001:db8::5"), ip("192.0.2.5")]),
+            None
+        );
+
+        assert_eq!(
+            target(
+                "sqlserver",
+                "Server=tcp:sql01\\CAD,1444;Database=x;User Id=u;Password=p"
+            ),
+            Some(("sql01".to_string(), Some(1444)))
+        );
+        assert_eq!(
+            target("postgres", "postgresql://u:p@[::1]/cad"),
+            Some(("::1".to_string(), Some(POSTGRES_DEFAULT_PORT)))
+        );
+        assert_eq!(
+            target("sqlserver", "Server=sql01\\CAD;Database=x"),
+            Some(("sql01".to_string(), None))
+        );
+        assert_eq!(classify("127.0.0.1", &[ip("127.0.0.1")]), None);
+    }
+}
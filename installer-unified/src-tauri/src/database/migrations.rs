@@ -1,19 +1,37 @@
 // Migration runner
 // Ported from C# ManifestBasedMigrationRunner.cs
 // Implements manifest-based migration execution with transaction safety and checksum validation
+//
+// Framework pieces on top of the manifest:
+// - Sources: the deployment (or offline bundle) `installer/migrations` folder; files missing there
+//   fall back to the copy compiled in at build time (`CADALYTIX_EMBED_MIGRATIONS_DIR`, build.rs).
+// - Down scripts: the manifest's `down` path, else `<file>.down.sql` next to the up script.
+// - History: every up/down run is appended to `schema_migrations` (default schema) with the
+//   script checksum; `applied_migrations` stays the record of what is currently applied.
+// - Drift: an applied migration whose manifest checksum no longer matches the checksum it was
+//   applied with (`status`, `--migrate-status`).
 
 use anyhow::{Context, Result};
 use chrono::Utc;
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::{Pool, Postgres};
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tiberius::Query;
 use tokio::fs;
 
 use crate::database::connection::DatabaseConnection;
+use crate::error::{InstallerError, OrCode};
+
+/// Migration files compiled into the binary: `(path relative to the migrations folder, bytes)`.
+/// Empty unless the build set `CADALYTIX_EMBED_MIGRATIONS_DIR`.
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_migrations.rs"));
+}
+
+const HISTORY_APPLIED_BY: &str = "INSTALLER";
 
 /// Versioned manifest schema (manifest_versioned.json).
 ///
@@ -50,6 +68,11 @@ struct VersionedManifestMigration {
     engine_version: String,
     order: i32,
     checksum: String,
+    /// Relative path of the down script, when it does not follow the `.down.sql` convention.
+    #[serde(default)]
+    down: Option<String>,
+    #[serde(default, rename = "downChecksum")]
+    down_checksum: Option<String>,
     #[serde(default)]
     dependencies: serde_json::Value,
 }
@@ -73,6 +96,33 @@ pub struct MigrationEntry {
     pub checksum: Option<String>,
     #[serde(default)]
     pub migration_group: Option<String>,
+    /// Down script (relative path); `None` means `<file>.down.sql` if that exists.
+    #[serde(default)]
+    pub down_file: Option<String>,
+    #[serde(default)]
+    pub down_checksum: Option<String>,
+}
+
+/// Applied/pending state of the manifest against a database (`--migrate-status`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    pub engine: String,
+    pub engine_version: String,
+    pub applied: Vec<String>,
+    pub pending: Vec<String>,
+    /// Applied migrations whose script changed after they ran.
+    pub drifted: Vec<MigrationDrift>,
+    /// Applied in the database but not in this manifest (e.g. installed by a newer installer).
+    pub unknown: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationDrift {
+    pub name: String,
+    pub applied_checksum: String,
+    pub manifest_checksum: String,
 }
 
 /// Migration runner for executing database migrations
@@ -115,9 +165,16 @@ impl MigrationRunner {
             self.manifest_path
         );
 
-        let content = fs::read_to_string(&self.manifest_path)
-            .await
-            .with_context(|| format!("Failed to read manifest file: {:?}", self.manifest_path))?;
+        let manifest_name = self
+            .manifest_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content =
+            String::from_utf8(read_bundle_file(&self.manifest_path, &manifest_name).await?)
+                .with_context(|| {
+                    format!("Manifest file is not valid UTF-8: {:?}", self.manifest_path)
+                })?;
 
         // Parse versioned manifest and select migrations for the requested engine + engine_version.
         let versioned: VersionedMigrationManifest =
@@ -152,6 +209,8 @@ impl MigrationRunner {
                     order: m.order.max(0) as u32,
                     checksum: Some(m.checksum.clone()),
                     migration_group: group,
+                    down_file: m.down.clone(),
+                    down_checksum: m.down_checksum.clone(),
                 });
             }
         }
@@ -305,6 +364,7 @@ impl MigrationRunner {
     }
 
     /// Apply a single migration
+    #[tracing::instrument(name = "db.migration", skip_all, fields(migration = %migration.name))]
     pub async fn apply_migration(&self, migration: &MigrationEntry) -> Result<()> {
         info!(
             "[PHASE: database] [STEP: apply_migration] Applying migration: {}",
@@ -317,9 +377,7 @@ impl MigrationRunner {
         let migration_file = self
             .migrations_path
             .join(manifest_relative_path(&migration.file));
-        let sql_bytes = fs::read(&migration_file)
-            .await
-            .with_context(|| format!("Failed to read migration file: {:?}", migration_file))?;
+        let sql_bytes = read_bundle_file(&migration_file, &migration.file).await?;
         let sql_content = String::from_utf8(sql_bytes.clone())
             .with_context(|| format!("Migration file is not valid UTF-8: {:?}", migration_file))?;
 
@@ -338,6 +396,8 @@ impl MigrationRunner {
             }
         }
 
+        self.ensure_history_table().await?;
+
         // Execute migration in transaction
         match &self.connection {
             DatabaseConnection::Postgres(pool) => {
@@ -396,6 +456,9 @@ impl MigrationRunner {
         )
         .await
         .with_context(|| format!("Failed to record applied migration: {}", migration.name))?;
+        self.record_history_postgres(&mut tx, &migration.name, "up", checksum, execution_time_ms)
+            .await
+            .with_context(|| format!("Failed to record migration history: {}", migration.name))?;
 
         // Commit transaction
         tx.commit()
@@ -483,6 +546,15 @@ impl MigrationRunner {
             )
             .await
             .with_context(|| format!("Failed to record applied migration: {}", migration.name))?;
+            self.record_history_sql_server(
+                &mut client,
+                &migration.name,
+                "up",
+                checksum,
+                execution_time_ms,
+            )
+            .await
+            .with_context(|| format!("Failed to record migration history: {}", migration.name))?;
 
             Ok(execution_time_ms)
         })
@@ -826,6 +898,15 @@ impl MigrationRunner {
         // Get applied migrations (names only)
         let applied_names: HashSet<String> = self.get_applied_migration_names().await?;
 
+        // Drift does not block the run (the scripts already ran), but it must be visible.
+        let recorded = self.recorded_checksums().await.unwrap_or_default();
+        for drift in summarize_status(&manifest, &applied_names, &recorded).drifted {
+            warn!(
+                "[PHASE: database] [STEP: apply_all_pending] Migration {} changed after it was applied (applied checksum {}, manifest checksum {})",
+                drift.name, drift.applied_checksum, drift.manifest_checksum
+            );
+        }
+
         // Determine pending migrations
         let pending: Vec<&MigrationEntry> = manifest
             .migrations
@@ -867,6 +948,331 @@ impl MigrationRunner {
 
         Ok(applied_names)
     }
+
+    /// Applied, pending, drifted and unknown migrations for this database.
+    pub async fn status(&self) -> Result<MigrationStatus> {
+        let manifest = self.load_manifest().await?;
+        let applied = self.get_applied_migration_names().await?;
+        let recorded = self.recorded_checksums().await?;
+        Ok(summarize_status(&manifest, &applied, &recorded))
+    }
+
+    /// Create the `schema_migrations` history table if it does not exist yet.
+    async fn ensure_history_table(&self) -> Result<()> {
+        match &self.connection {
+            DatabaseConnection::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    CREATE TABLE IF NOT EXISTS public.schema_migrations (
+                        id BIGSERIAL PRIMARY KEY,
+                        migration_name VARCHAR(400) NOT NULL,
+                        direction VARCHAR(8) NOT NULL,
+                        checksum VARCHAR(64) NOT NULL,
+                        engine VARCHAR(20) NOT NULL,
+                        execution_time_ms INTEGER NOT NULL,
+                        applied_by VARCHAR(100) NOT NULL,
+                        applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                    )
+                    "#,
+                )
+                .execute(pool)
+                .await
+                .with_context(|| "Failed to create schema_migrations (PostgreSQL)")?;
+            }
+            DatabaseConnection::SqlServer(_) => {
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+                client
+                    .simple_query(
+                        r#"
+                        IF OBJECT_ID(N'dbo.schema_migrations', N'U') IS NULL
+                        CREATE TABLE dbo.schema_migrations (
+                            id BIGINT IDENTITY(1,1) PRIMARY KEY,
+                            migration_name NVARCHAR(400) NOT NULL,
+                            direction NVARCHAR(8) NOT NULL,
+                            checksum NVARCHAR(64) NOT NULL,
+                            engine NVARCHAR(20) NOT NULL,
+                            execution_time_ms INT NOT NULL,
+                            applied_by NVARCHAR(100) NOT NULL,
+                            applied_at DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME()
+                        )
+                        "#,
+                    )
+                    .await
+                    .with_context(|| "Failed to create schema_migrations (SQL Server)")?
+                    .into_results()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_history_postgres(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        name: &str,
+        direction: &str,
+        checksum: &str,
+        execution_time_ms: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO public.schema_migrations
+                (migration_name, direction, checksum, engine, execution_time_ms, applied_by)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(name)
+        .bind(direction)
+        .bind(checksum)
+        .bind(normalize_engine(&self.engine))
+        .bind(execution_time_ms)
+        .bind(HISTORY_APPLIED_BY)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_history_sql_server(
+        &self,
+        client: &mut tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>,
+        name: &str,
+        direction: &str,
+        checksum: &str,
+        execution_time_ms: i32,
+    ) -> Result<()> {
+        let engine = normalize_engine(&self.engine);
+        let mut q = Query::new(
+            r#"
+            INSERT INTO dbo.schema_migrations
+                (migration_name, direction, checksum, engine, execution_time_ms, applied_by)
+            VALUES
+                (@P1, @P2, @P3, @P4, @P5, @P6)
+            "#,
+        );
+        q.bind(name);
+        q.bind(direction);
+        q.bind(checksum);
+        q.bind(engine.as_str());
+        q.bind(execution_time_ms);
+        q.bind(HISTORY_APPLIED_BY);
+        q.execute(client).await?;
+        Ok(())
+    }
+
+    /// Checksum each migration was last applied with, from `schema_migrations`. Migrations whose
+    /// latest history row is a `down` (or that predate the history table) are not included.
+    async fn recorded_checksums(&self) -> Result<HashMap<String, String>> {
+        let rows: Vec<(String, String, String)> = match &self.connection {
+            DatabaseConnection::Postgres(pool) => {
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT to_regclass('public.schema_migrations') IS NOT NULL",
+                )
+                .fetch_one(pool)
+                .await?;
+                if !exists {
+                    return Ok(HashMap::new());
+                }
+                sqlx::query_as(
+                    r#"
+                    SELECT DISTINCT ON (migration_name) migration_name, direction, checksum
+                    FROM public.schema_migrations
+                    ORDER BY migration_name, id DESC
+                    "#,
+                )
+                .fetch_all(pool)
+                .await
+                .with_context(|| "Failed to read schema_migrations (PostgreSQL)")?
+            }
+            DatabaseConnection::SqlServer(_) => {
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+                let result = client
+                    .simple_query(
+                        r#"
+                        IF OBJECT_ID(N'dbo.schema_migrations', N'U') IS NOT NULL
+                        SELECT h.migration_name, h.direction, h.checksum
+                        FROM dbo.schema_migrations h
+                        WHERE h.id = (
+                            SELECT MAX(x.id) FROM dbo.schema_migrations x
+                            WHERE x.migration_name = h.migration_name
+                        )
+                        "#,
+                    )
+                    .await
+                    .with_context(|| "Failed to read schema_migrations (SQL Server)")?
+                    .into_first_result()
+                    .await?;
+                result
+                    .iter()
+                    .map(|row| {
+                        let col = |i: usize| row.get::<&str, _>(i).unwrap_or_default().to_string();
+                        (col(0), col(1), col(2))
+                    })
+                    .collect()
+            }
+        };
+        Ok(rows
+            .into_iter()
+            .filter(|(_, direction, _)| direction == "up")
+            .map(|(name, _, checksum)| (name, checksum))
+            .collect())
+    }
+
+    /// Revert one applied migration with its down script, in a transaction: run the script,
+    /// forget the migration in `applied_migrations`, and append a `down` row to the history.
+    pub async fn revert_migration(&self, migration: &MigrationEntry) -> Result<()> {
+        let down_file = migration
+            .down_file
+            .clone()
+            .unwrap_or_else(|| default_down_file(&migration.file));
+        let down_path = self
+            .migrations_path
+            .join(manifest_relative_path(&down_file));
+        if !bundle_file_exists(&down_path, &down_file).await {
+            anyhow::bail!(
+                "Migration {} has no down script ({}); it cannot be reverted",
+                migration.name,
+                down_file
+            );
+        }
+        info!(
+            "[PHASE: database] [STEP: revert_migration] Reverting migration: {}",
+            migration.name
+        );
+
+        let start_time = Utc::now();
+        let sql_bytes = read_bundle_file(&down_path, &down_file).await?;
+        let sql_content = String::from_utf8(sql_bytes.clone())
+            .with_context(|| format!("Down script is not valid UTF-8: {:?}", down_path))?;
+        let checksum = sha256_hex(&sql_bytes);
+        if let Some(expected) = &migration.down_checksum {
+            if checksum != *expected {
+                anyhow::bail!(
+                    "Checksum mismatch for down script of {}: expected {}, computed {}",
+                    migration.name,
+                    expected,
+                    checksum
+                );
+            }
+        }
+
+        self.ensure_history_table().await?;
+
+        match &self.connection {
+            DatabaseConnection::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                sqlx::raw_sql(&sql_content)
+                    .execute(&mut *tx)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to execute down script: {}", migration.name)
+                    })?;
+                // The down script of the migration that created it may have dropped the table.
+                let tracked: bool = sqlx::query_scalar(
+                    "SELECT to_regclass('cadalytix_config.applied_migrations') IS NOT NULL",
+                )
+                .fetch_one(&mut *tx)
+                .await?;
+                if tracked {
+                    sqlx::query(
+                        "DELETE FROM cadalytix_config.applied_migrations WHERE migration_name = $1",
+                    )
+                    .bind(&migration.name)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                let execution_time_ms = (Utc::now() - start_time).num_milliseconds() as i32;
+                self.record_history_postgres(
+                    &mut tx,
+                    &migration.name,
+                    "down",
+                    &checksum,
+                    execution_time_ms,
+                )
+                .await?;
+                tx.commit().await?;
+            }
+            DatabaseConnection::SqlServer(_) => {
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+                client
+                    .simple_query("BEGIN TRANSACTION")
+                    .await?
+                    .into_results()
+                    .await?;
+
+                let result: Result<()> = (async {
+                    for (idx, batch) in split_sql_server_batches(&sql_content).iter().enumerate() {
+                        client
+                            .simple_query(batch.trim())
+                            .await?
+                            .into_results()
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "Failed to execute down script batch {} for {}",
+                                    idx + 1,
+                                    migration.name
+                                )
+                            })?;
+                    }
+                    let mut q = Query::new(
+                        r#"
+                        IF OBJECT_ID(N'cadalytix_config.applied_migrations', N'U') IS NOT NULL
+                            DELETE FROM cadalytix_config.applied_migrations WHERE migration_name = @P1
+                        "#,
+                    );
+                    q.bind(migration.name.as_str());
+                    q.execute(&mut *client).await?;
+                    let execution_time_ms = (Utc::now() - start_time).num_milliseconds() as i32;
+                    self.record_history_sql_server(
+                        &mut client,
+                        &migration.name,
+                        "down",
+                        &checksum,
+                        execution_time_ms,
+                    )
+                    .await
+                })
+                .await;
+
+                match result {
+                    Ok(()) => {
+                        client
+                            .simple_query("COMMIT TRANSACTION")
+                            .await?
+                            .into_results()
+                            .await?;
+                    }
+                    Err(e) => {
+                        if let Ok(stream) = client.simple_query("ROLLBACK TRANSACTION").await {
+                            let _ = stream.into_results().await;
+                        }
+                        return Err(e).with_context(|| {
+                            format!("Revert failed, transaction rolled back: {}", migration.name)
+                        });
+                    }
+                }
+            }
+        }
+
+        info!(
+            "[PHASE: database] [STEP: revert_migration] Reverted migration: {}",
+            migration.name
+        );
+        Ok(())
+    }
 }
 
 fn normalize_engine(engine: &str) -> String {
@@ -877,6 +1283,111 @@ fn normalize_engine(engine: &str) -> String {
     }
 }
 
+fn summarize_status(
+    manifest: &MigrationManifest,
+    applied: &HashSet<String>,
+    recorded: &HashMap<String, String>,
+) -> MigrationStatus {
+    let mut status = MigrationStatus {
+        engine: manifest.engine.clone(),
+        engine_version: manifest.engine_version.clone(),
+        applied: Vec::new(),
+        pending: Vec::new(),
+        drifted: Vec::new(),
+        unknown: Vec::new(),
+    };
+    for m in &manifest.migrations {
+        if !applied.contains(&m.name) {
+            status.pending.push(m.name.clone());
+            continue;
+        }
+        status.applied.push(m.name.clone());
+        if let (Some(applied_checksum), Some(manifest_checksum)) =
+            (recorded.get(&m.name), m.checksum.as_ref())
+        {
+            if !applied_checksum.eq_ignore_ascii_case(manifest_checksum) {
+                status.drifted.push(MigrationDrift {
+                    name: m.name.clone(),
+                    applied_checksum: applied_checksum.clone(),
+                    manifest_checksum: manifest_checksum.clone(),
+                });
+            }
+        }
+    }
+    let known: HashSet<&str> = manifest
+        .migrations
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect();
+    status.unknown = applied
+        .iter()
+        .filter(|n| !known.contains(n.as_str()))
+        .cloned()
+        .collect();
+    status.unknown.sort();
+    status
+}
+
+/// `--migrate-status`: applied/pending/drifted migrations of the config DB named by
+/// `CADALYTIX_CONFIG_DB_CONNECTION`.
+pub async fn migrate_status_cli(_args: &[String]) -> Result<MigrationStatus> {
+    use crate::api::preflight_report::CONFIG_DB_CONNECTION_ENV;
+
+    let conn_str = std::env::var(CONFIG_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Set {} to the config DB connection string",
+                CONFIG_DB_CONNECTION_ENV
+            )
+        })
+        .or_code(InstallerError::InvalidArguments)?;
+    let engine = crate::api::installer::guess_engine(&conn_str);
+    let conn = crate::api::installer::connect_with_retry(engine.clone(), conn_str)
+        .await
+        .or_code(InstallerError::DatabaseConnectionFailed)?;
+    let engine_version =
+        crate::api::installer::detect_engine_version(engine.clone(), conn.clone()).await?;
+    let (manifest_path, migrations_path) = crate::api::installer::resolve_migrations_paths()?;
+    let runner =
+        MigrationRunner::new(conn, manifest_path, migrations_path, engine, engine_version).await?;
+    runner.status().await
+}
+
+/// `SQL/v2022/010_x.sql` -> `SQL/v2022/010_x.down.sql`.
+fn default_down_file(file: &str) -> String {
+    let stem = file
+        .strip_suffix(".sql")
+        .or_else(|| file.strip_suffix(".SQL"))
+        .unwrap_or(file);
+    format!("{}.down.sql", stem)
+}
+
+/// Read a bundle file from disk, else from the copy embedded at build time.
+pub(crate) async fn read_bundle_file(path: &Path, relative: &str) -> Result<Vec<u8>> {
+    match fs::read(path).await {
+        Ok(bytes) => Ok(bytes),
+        Err(e) => match embedded_file(relative) {
+            Some(bytes) => Ok(bytes.to_vec()),
+            None => Err(e).with_context(|| format!("Failed to read migration file: {:?}", path)),
+        },
+    }
+}
+
+async fn bundle_file_exists(path: &Path, relative: &str) -> bool {
+    fs::try_exists(path).await.unwrap_or(false) || embedded_file(relative).is_some()
+}
+
+fn embedded_file(relative: &str) -> Option<&'static [u8]> {
+    let wanted = relative.replace('\\', "/");
+    let wanted = wanted.trim_start_matches('/');
+    embedded::EMBEDDED_MIGRATIONS
+        .iter()
+        .find(|(name, _)| *name == wanted)
+        .map(|(_, bytes)| *bytes)
+}
+
 fn manifest_relative_path(path: &str) -> PathBuf {
     let mut out = PathBuf::new();
     for part in path.split(['/', '\\']) {
@@ -934,6 +1445,43 @@ mod tests {
         assert!(batches[2].contains("SELECT 3"));
     }
 
+    #[test]
+    fn status_reports_pending_drift_and_unknown() {
+        let entry = |name: &str, checksum: &str| MigrationEntry {
+            name: name.to_string(),
+            file: name.to_string(),
+            order: 0,
+            checksum: Some(checksum.to_string()),
+            migration_group: None,
+            down_file: None,
+            down_checksum: None,
+        };
+        let manifest = MigrationManifest {
+            engine: "postgres".to_string(),
+            engine_version: "17".to_string(),
+            migrations: vec![entry("001", "aa"), entry("002", "bb"), entry("003", "cc")],
+        };
+        let applied: HashSet<String> = ["001", "002", "900"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        // 001 predates the history table; 002 was applied from a different script.
+        let recorded: HashMap<String, String> = [("002".to_string(), "b0".to_string())].into();
+
+        let status = summarize_status(&manifest, &applied, &recorded);
+        assert_eq!(status.applied, vec!["001", "002"]);
+        assert_eq!(status.pending, vec!["003"]);
+        assert_eq!(status.unknown, vec!["900"]);
+        assert_eq!(status.drifted.len(), 1);
+        assert_eq!(status.drifted[0].name, "002");
+        assert_eq!(status.drifted[0].applied_checksum, "b0");
+
+        assert_eq!(
+            default_down_file("SQL/v2022/010_enhance.sql"),
+            "SQL/v2022/010_enhance.down.sql"
+        );
+    }
+
     #[test]
     fn manifest_relative_path_handles_forward_slashes() {
         let p = manifest_relative_path("SQL/v2022/file.sql");
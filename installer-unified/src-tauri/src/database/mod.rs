@@ -1,6 +1,12 @@
+pub mod app_account;
+pub mod conn_string;
 pub mod connection;
+pub mod grants;
+pub mod host_check;
 pub mod migrations;
 pub mod platform_db;
+pub mod pool;
 pub mod provisioning;
 pub mod schema_mapping;
 pub mod schema_verifier;
+pub mod seed;
@@ -1044,6 +1044,194 @@ impl PlatformDbAdapter {
         }
     }
 
+    /// Re-encrypt every encrypted instance setting and license-state token under `to` (secret
+    /// rotation). All rows are rewritten in one transaction; returns how many values moved.
+    pub async fn reencrypt_secrets(&self, to: &SecretProtector) -> Result<usize> {
+        let settings = match &self.connection {
+            DatabaseConnection::Postgres(pool) => self.get_all_settings_postgres(pool).await?,
+            DatabaseConnection::SqlServer(_) => self.get_all_settings_sql_server().await?,
+        };
+        let mut setting_updates = Vec::new();
+        for (k, v) in settings {
+            if let Some(moved) = self.reencrypt_value(&v, to).await? {
+                setting_updates.push((k, moved));
+            }
+        }
+
+        let mut moved_count = setting_updates.len();
+        let mut token_updates = Vec::new();
+        for (id, installation_token, signed_token_blob) in self.get_license_tokens().await? {
+            let new_installation = self.reencrypt_value(&installation_token, to).await?;
+            let new_signed = match signed_token_blob.as_deref() {
+                Some(v) => self.reencrypt_value(v, to).await?,
+                None => None,
+            };
+            if new_installation.is_none() && new_signed.is_none() {
+                continue;
+            }
+            moved_count +=
+                usize::from(new_installation.is_some()) + usize::from(new_signed.is_some());
+            token_updates.push((
+                id,
+                new_installation.unwrap_or(installation_token),
+                new_signed.or(signed_token_blob),
+            ));
+        }
+
+        match &self.connection {
+            DatabaseConnection::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                for (k, v) in &setting_updates {
+                    sqlx::query(
+                        r#"
+                        UPDATE cadalytix_config.instance_settings
+                        SET "value" = $1, updated_at = $2
+                        WHERE "key" = $3
+                        "#,
+                    )
+                    .bind(v)
+                    .bind(Utc::now().naive_utc())
+                    .bind(k)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                for (id, installation_token, signed_token_blob) in &token_updates {
+                    sqlx::query(
+                        r#"
+                        UPDATE cadalytix_config.license_state
+                        SET installation_token = $1, signed_token_blob = $2
+                        WHERE id = $3
+                        "#,
+                    )
+                    .bind(installation_token)
+                    .bind(signed_token_blob)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                tx.commit()
+                    .await
+                    .with_context(|| "Failed to commit re-encrypted secrets (PostgreSQL)")?;
+            }
+            DatabaseConnection::SqlServer(_) => {
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+
+                {
+                    let mut s = client.simple_query("BEGIN TRANSACTION").await?;
+                    while s.try_next().await?.is_some() {}
+                }
+
+                let op: Result<()> = (async {
+                    for (k, v) in &setting_updates {
+                        let mut q = Query::new(
+                            r#"
+                            UPDATE cadalytix_config.instance_settings
+                            SET [value] = @P1, updated_at = SYSUTCDATETIME()
+                            WHERE [key] = @P2
+                            "#,
+                        );
+                        q.bind(v.as_str());
+                        q.bind(k.as_str());
+                        let mut s = q.query(&mut *client).await?;
+                        while s.try_next().await?.is_some() {}
+                    }
+                    for (id, installation_token, signed_token_blob) in &token_updates {
+                        let mut q = Query::new(
+                            r#"
+                            UPDATE cadalytix_config.license_state
+                            SET installation_token = @P1, signed_token_blob = @P2
+                            WHERE id = @P3
+                            "#,
+                        );
+                        q.bind(installation_token.as_str());
+                        q.bind(signed_token_blob.as_deref());
+                        q.bind(*id);
+                        let mut s = q.query(&mut *client).await?;
+                        while s.try_next().await?.is_some() {}
+                    }
+                    Ok(())
+                })
+                .await;
+
+                match op {
+                    Ok(()) => {
+                        let mut s = client.simple_query("COMMIT TRANSACTION").await?;
+                        while s.try_next().await?.is_some() {}
+                    }
+                    Err(e) => {
+                        let _ = client.simple_query("ROLLBACK TRANSACTION").await;
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        info!(
+            "[PHASE: database] [STEP: reencrypt_secrets] Re-encrypted {} stored secret(s)",
+            moved_count
+        );
+        Ok(moved_count)
+    }
+
+    /// `value` decrypted with this adapter's key and encrypted under `to`; `None` when it is not
+    /// an encrypted value.
+    async fn reencrypt_value(&self, value: &str, to: &SecretProtector) -> Result<Option<String>> {
+        if !self.secrets.is_encrypted(value) {
+            return Ok(None);
+        }
+        let plaintext = self.secrets.decrypt(value).await?;
+        Ok(Some(to.encrypt(&plaintext).await?))
+    }
+
+    /// Raw (still encrypted) license-state tokens: `(id, installation_token, signed_token_blob)`.
+    async fn get_license_tokens(&self) -> Result<Vec<(i32, String, Option<String>)>> {
+        match &self.connection {
+            DatabaseConnection::Postgres(pool) => Ok(sqlx::query_as(
+                r#"
+                SELECT id, installation_token, signed_token_blob
+                FROM cadalytix_config.license_state
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .with_context(|| "Failed to query license state tokens (PostgreSQL)")?),
+            DatabaseConnection::SqlServer(_) => {
+                use tiberius::QueryItem;
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+
+                let q = Query::new(
+                    r#"
+                    SELECT id, installation_token, signed_token_blob
+                    FROM cadalytix_config.license_state
+                    "#,
+                );
+                let mut stream = q.query(&mut *client).await?;
+                let mut out = Vec::new();
+                while let Some(item) = stream.try_next().await? {
+                    if let QueryItem::Row(row) = item {
+                        let Some(id) = row.get::<i32, _>(0) else {
+                            continue;
+                        };
+                        out.push((
+                            id,
+                            row.get::<&str, _>(1).unwrap_or("").to_string(),
+                            row.get::<&str, _>(2).map(|s| s.to_string()),
+                        ));
+                    }
+                }
+                Ok(out)
+            }
+        }
+    }
+
     pub async fn get_license_state(&self) -> Result<Option<Value>> {
         match &self.connection {
             DatabaseConnection::Postgres(pool) => {
@@ -0,0 +1,265 @@
+// Shared database connection pools.
+//
+// Preflight, migrations, provisioning and schema verification each used to open a fresh
+// connection (TCP + TLS + login) per step. `DatabaseConnection::{sql_server, postgres}` now check
+// connections out of one process-wide pool per (engine, connection string):
+// - SQL Server: a bb8 pool of tiberius clients. A checked-out client goes back to the pool when
+//   the last clone of its `DatabaseConnection` is dropped, and is re-validated before it is
+//   handed out again: a client with a transaction still open (`@@TRANCOUNT > 0`) is closed
+//   instead, as is one marked broken after a failed commit or rollback.
+// - PostgreSQL: an sqlx pool, which `DatabaseConnection::Postgres` already carried; it is now
+//   shared instead of rebuilt per step.
+//
+// `CADALYTIX_DB_MAX_CONNECTIONS` caps open connections per pool (default 4, 1..=64).
+//
+// SECURITY:
+// - Pools are keyed by the full connection string; never log the key.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tiberius::Client;
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use super::connection::open_sql_server_client;
+use crate::utils::retry;
+
+const MAX_CONNECTIONS_ENV: &str = "CADALYTIX_DB_MAX_CONNECTIONS";
+const DEFAULT_MAX_CONNECTIONS: u32 = 4;
+/// Idle connections are closed after this long so the installer does not hold logins open.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A tiberius client checked out of its pool; derefs to the client.
+pub type PooledSqlServerClient = bb8::PooledConnection<'static, SqlServerManager>;
+
+/// Pooled tiberius client (derefs to the client) that can be kept out of the pool.
+pub struct SqlServerClient {
+    client: Client<Compat<TcpStream>>,
+    broken: bool,
+}
+
+impl SqlServerClient {
+    /// Close this client when it is released instead of returning it to the pool.
+    pub fn mark_broken(&mut self) {
+        self.broken = true;
+    }
+}
+
+impl std::ops::Deref for SqlServerClient {
+    type Target = Client<Compat<TcpStream>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+impl std::ops::DerefMut for SqlServerClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.client
+    }
+}
+
+#[derive(Clone)]
+enum SharedPool {
+    SqlServer(bb8::Pool<SqlServerManager>),
+    Postgres(Pool<Postgres>),
+}
+
+static POOLS: OnceLock<Mutex<HashMap<String, SharedPool>>> = OnceLock::new();
+
+/// Opens tiberius clients for one connection string.
+pub struct SqlServerManager {
+    connection_string: String,
+}
+
+#[async_trait]
+impl bb8::ManageConnection for SqlServerManager {
+    type Connection = SqlServerClient;
+    type Error = anyhow::Error;
+
+    async fn connect(&self) -> Result<Self::Connection> {
+        Ok(SqlServerClient {
+            client: open_sql_server_client(&self.connection_string).await?,
+            broken: false,
+        })
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<()> {
+        let open_transactions = conn
+            .simple_query("SELECT @@TRANCOUNT")
+            .await?
+            .into_row()
+            .await?
+            .and_then(|row| row.get::<i32, _>(0))
+            .unwrap_or(0);
+        if open_transactions > 0 {
+            anyhow::bail!("Pooled connection has an open transaction");
+        }
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        conn.broken
+    }
+}
+
+/// Commit the open transaction on `client`. On failure the client is marked broken, so it is not
+/// handed out again with the transaction (and its locks) still open.
+pub async fn commit(client: &mut SqlServerClient) -> Result<()> {
+    end_transaction(client, "COMMIT TRANSACTION").await
+}
+
+/// Roll back the open transaction (if any) on `client`; marks it broken on failure like `commit`.
+pub async fn rollback(client: &mut SqlServerClient) -> Result<()> {
+    end_transaction(client, "IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION").await
+}
+
+async fn end_transaction(client: &mut SqlServerClient, sql: &str) -> Result<()> {
+    let result = match client.simple_query(sql).await {
+        Ok(stream) => stream.into_results().await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        client.mark_broken();
+    }
+    Ok(result?)
+}
+
+/// Per-pool connection limit from `CADALYTIX_DB_MAX_CONNECTIONS`.
+pub fn max_connections() -> u32 {
+    parse_max_connections(std::env::var(MAX_CONNECTIONS_ENV).ok().as_deref())
+}
+
+fn parse_max_connections(value: Option<&str>) -> u32 {
+    value
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .map(|n| n.clamp(1, 64))
+        .unwrap_or(DEFAULT_MAX_CONNECTIONS)
+}
+
+/// Check a SQL Server client out of the shared pool for `connection_string`.
+pub async fn sql_server(connection_string: &str) -> Result<PooledSqlServerClient> {
+    let key = pool_key("sqlserver", connection_string);
+    let pool = match shared(&key, || {
+        Ok(SharedPool::SqlServer(
+            bb8::Pool::builder()
+                .max_size(max_connections())
+                .min_idle(Some(0))
+                .connection_timeout(retry::policy().attempt_timeout)
+                .idle_timeout(Some(IDLE_TIMEOUT))
+                // Surface the driver's error (bad login, unknown host) instead of retrying
+                // until the timeout; callers already retry transient failures.
+                .retry_connection(false)
+                .build_unchecked(SqlServerManager {
+                    connection_string: connection_string.to_string(),
+                }),
+        ))
+    })? {
+        SharedPool::SqlServer(pool) => pool,
+        SharedPool::Postgres(_) => unreachable!("pool keys include the engine"),
+    };
+    match pool.get_owned().await {
+        Ok(client) => Ok(client),
+        Err(e) => {
+            forget_if_unused(&key);
+            Err(match e {
+                bb8::RunError::User(e) => e,
+                bb8::RunError::TimedOut => anyhow::anyhow!("Connection attempt timed out"),
+            })
+        }
+    }
+}
+
+/// Shared PostgreSQL pool for `connection_string`, verified with one checkout.
+pub async fn postgres(connection_string: &str) -> Result<Pool<Postgres>> {
+    let key = pool_key("postgres", connection_string);
+    let pool = match shared(&key, || {
+        Ok(SharedPool::Postgres(
+            PgPoolOptions::new()
+                .max_connections(max_connections())
+                .acquire_timeout(retry::policy().attempt_timeout)
+                .idle_timeout(IDLE_TIMEOUT)
+                .connect_lazy(connection_string)?,
+        ))
+    })? {
+        SharedPool::Postgres(pool) => pool,
+        SharedPool::SqlServer(_) => unreachable!("pool keys include the engine"),
+    };
+    // Keep the eager failure of `Pool::connect`: a bad login should fail here, not on first use.
+    if let Err(e) = pool.acquire().await {
+        forget_if_unused(&key);
+        return Err(e.into());
+    }
+    Ok(pool)
+}
+
+/// Close and forget every pool, e.g. before dropping a database the pools point at.
+pub async fn close_all() {
+    let pools: Vec<SharedPool> = match POOLS.get() {
+        Some(pools) => lock(pools).drain().map(|(_, p)| p).collect(),
+        None => return,
+    };
+    for pool in pools {
+        if let SharedPool::Postgres(pool) = pool {
+            pool.close().await;
+        }
+        // bb8 pools close their idle clients when the last handle is dropped.
+    }
+}
+
+fn pool_key(engine: &str, connection_string: &str) -> String {
+    format!("{}\u{0}{}", engine, connection_string)
+}
+
+fn shared(key: &str, create: impl FnOnce() -> Result<SharedPool>) -> Result<SharedPool> {
+    let mut pools = lock(POOLS.get_or_init(|| Mutex::new(HashMap::new())));
+    if let Some(pool) = pools.get(key) {
+        return Ok(pool.clone());
+    }
+    let pool = create()?;
+    pools.insert(key.to_string(), pool.clone());
+    Ok(pool)
+}
+
+/// Drop a pool that never managed to open a connection, so mistyped connection strings from
+/// "Test Connection" do not accumulate.
+fn forget_if_unused(key: &str) {
+    let Some(pools) = POOLS.get() else {
+        return;
+    };
+    let mut pools = lock(pools);
+    let unused = match pools.get(key) {
+        Some(SharedPool::SqlServer(pool)) => pool.state().connections == 0,
+        Some(SharedPool::Postgres(pool)) => pool.size() == 0,
+        None => false,
+    };
+    if unused {
+        pools.remove(key);
+    }
+}
+
+fn lock(
+    pools: &Mutex<HashMap<String, SharedPool>>,
+) -> std::sync::MutexGuard<'_, HashMap<String, SharedPool>> {
+    pools.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_connections_defaults_and_clamps() {
+        assert_eq!(parse_max_connections(None), DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(parse_max_connections(Some("")), DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(parse_max_connections(Some("abc")), DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(parse_max_connections(Some(" 8 ")), 8);
+        assert_eq!(parse_max_connections(Some("0")), 1);
+        assert_eq!(parse_max_connections(Some("1000")), 64);
+    }
+}
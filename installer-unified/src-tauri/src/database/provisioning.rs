@@ -1,8 +1,10 @@
 // Database provisioning module for Phase 9: Create NEW Database
 //
 // Supports:
-// - SQL Server: CREATE DATABASE with optional sizing (SIZE/MAXSIZE/FILEGROWTH via ALTER)
-// - PostgreSQL: CREATE DATABASE with optional OWNER (no sizing knobs - PostgreSQL grows with disk)
+// - SQL Server: CREATE DATABASE with optional COLLATE, data/log files in a chosen folder
+//   (PRIMARY filegroup + LOG ON) and optional sizing (SIZE/MAXSIZE/FILEGROWTH via ALTER)
+// - PostgreSQL: CREATE DATABASE with optional OWNER, locale (LC_COLLATE/LC_CTYPE from template0)
+//   and a tablespace created for a chosen folder (no sizing knobs - PostgreSQL grows with disk)
 //
 // Key design decisions:
 // - SQL Server sizing is applied via ALTER DATABASE MODIFY FILE after creation
@@ -44,14 +46,49 @@ pub struct PostgresCreateOptions {
     pub owner: Option<String>,
 }
 
+/// Engine-specific placement options for a new database (all optional; defaults = server defaults)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDatabaseOptions {
+    /// SQL Server collation (e.g. Latin1_General_CI_AS) or PostgreSQL locale (e.g. en_US.UTF-8)
+    #[serde(default)]
+    pub collation: Option<String>,
+    /// Folder on the database server for the data files: the PRIMARY filegroup and log file
+    /// (SQL Server) or a new tablespace (PostgreSQL). It must already exist on the server.
+    #[serde(default)]
+    pub files_path: Option<String>,
+}
+
+impl CreateDatabaseOptions {
+    fn collation(&self) -> Option<&str> {
+        self.collation
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+    }
+
+    fn files_path(&self) -> Option<&str> {
+        self.files_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+    }
+}
+
 /// Result of privilege check
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CanCreateDatabaseResult {
     pub can_create: bool,
     pub reason: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detected_role: Option<String>,
+    /// Login/role the privilege check ran as.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_name: Option<String>,
+    /// Script a DBA runs to grant the missing privilege (only when `can_create` is false).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grant_script: Option<String>,
 }
 
 /// Result of database existence check
@@ -120,18 +157,74 @@ pub fn validate_sizing_config(cfg: &SqlServerSizingConfig) -> Result<(), String>
     Ok(())
 }
 
+/// Validate a collation / locale name (letters, numbers, `_ . @ -`; 1-128 chars).
+///
+/// Collations cannot be quoted in SQL Server, so anything else is rejected rather than escaped.
+pub fn validate_collation(name: &str) -> Result<(), String> {
+    let re = Regex::new(r"^[A-Za-z0-9_.@-]{1,128}$").unwrap();
+    if !re.is_match(name.trim()) {
+        return Err(
+            "Collation / locale may contain only letters, numbers, '_', '.', '@' and '-'."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Validate creation options (collation format; the files path must be absolute)
+pub fn validate_create_options(opts: &CreateDatabaseOptions) -> Result<(), String> {
+    if let Some(collation) = opts.collation() {
+        validate_collation(collation)?;
+    }
+    if let Some(path) = opts.files_path() {
+        let absolute = path.starts_with('/')
+            || path.starts_with("\\\\")
+            || matches!(path.get(1..3), Some(":\\") | Some(":/"));
+        if !absolute {
+            return Err(
+                "Database path must be an absolute path on the database server.".to_string(),
+            );
+        }
+        if path.contains('\'') {
+            return Err("Database path must not contain quotes.".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Join a folder and file name in the server's path style (`\\` when the folder uses it).
+fn server_path_join(dir: &str, file: &str) -> String {
+    let sep = if dir.contains('\\') { '\\' } else { '/' };
+    format!("{}{}{}", dir.trim_end_matches(['/', '\\']), sep, file)
+}
+
 // =============================================================================
 // SQL Server SQL Generation (safe, bracket-quoted)
 // =============================================================================
 
 /// Bracket-quote a SQL Server identifier
-fn bracket_quote(name: &str) -> String {
+pub(crate) fn bracket_quote(name: &str) -> String {
     format!("[{}]", name.replace(']', "]]"))
 }
 
-/// Generate CREATE DATABASE statement for SQL Server (no sizing - sizing applied via ALTER)
-pub fn sql_server_create_db_stmt(db_name: &str) -> String {
-    format!("CREATE DATABASE {};", bracket_quote(db_name))
+/// Generate CREATE DATABASE statement for SQL Server (no sizing - sizing applied via ALTER).
+///
+/// With a files path the data file goes in the PRIMARY filegroup there, next to the log file.
+pub fn sql_server_create_db_stmt(db_name: &str, opts: &CreateDatabaseOptions) -> String {
+    let mut stmt = format!("CREATE DATABASE {}", bracket_quote(db_name));
+    if let Some(dir) = opts.files_path() {
+        stmt.push_str(&format!(
+            " ON PRIMARY ( NAME = N'{0}', FILENAME = N'{1}' ) LOG ON ( NAME = N'{0}_log', FILENAME = N'{2}' )",
+            db_name,
+            server_path_join(dir, &format!("{}.mdf", db_name)),
+            server_path_join(dir, &format!("{}_log.ldf", db_name))
+        ));
+    }
+    if let Some(collation) = opts.collation() {
+        stmt.push_str(&format!(" COLLATE {}", collation));
+    }
+    stmt.push(';');
+    stmt
 }
 
 /// Generate ALTER DATABASE MODIFY FILE statement for sizing
@@ -180,10 +273,26 @@ pub fn sql_server_can_create_db_query() -> &'static str {
             WHEN IS_SRVROLEMEMBER('dbcreator') = 1 THEN 'dbcreator'
             WHEN HAS_PERMS_BY_NAME(NULL, NULL, 'CREATE ANY DATABASE') = 1 THEN 'CREATE ANY DATABASE'
             ELSE 'none'
-        END AS detected_role
+        END AS detected_role,
+        SUSER_SNAME() AS login_name
     "#
 }
 
+/// Least-privilege script a sysadmin runs so `login` can create the CADalytix database.
+///
+/// Grants only `CREATE ANY DATABASE`; the login becomes owner (dbo) of the database it creates,
+/// so no further grants are needed for migrations.
+pub fn sql_server_grant_script(login: &str) -> String {
+    format!(
+        "-- CADalytix: allow {login} to create the CADalytix database (SQL Server).\n\
+         -- Run as a member of the sysadmin role on this instance, then re-test in the installer.\n\
+         USE [master];\n\
+         GRANT CREATE ANY DATABASE TO {quoted};\n",
+        login = login,
+        quoted = bracket_quote(login)
+    )
+}
+
 /// SQL to check if database exists (SQL Server)
 pub fn sql_server_db_exists_query(db_name: &str) -> String {
     format!(
@@ -192,6 +301,16 @@ pub fn sql_server_db_exists_query(db_name: &str) -> String {
     )
 }
 
+/// Generate DROP DATABASE for SQL Server (used to roll back a cancelled Create NEW install).
+///
+/// Other sessions (e.g. the installer's own pooled connection) are disconnected first.
+pub fn sql_server_drop_db_stmt(db_name: &str) -> String {
+    format!(
+        "ALTER DATABASE {0} SET SINGLE_USER WITH ROLLBACK IMMEDIATE; DROP DATABASE {0};",
+        bracket_quote(db_name)
+    )
+}
+
 /// SQL to get logical file names for a database (SQL Server)
 pub fn sql_server_get_file_names_query(db_name: &str) -> String {
     format!(
@@ -210,25 +329,82 @@ pub fn sql_server_get_file_names_query(db_name: &str) -> String {
 // =============================================================================
 
 /// Double-quote a PostgreSQL identifier
-fn pg_quote_ident(name: &str) -> String {
+pub(crate) fn pg_quote_ident(name: &str) -> String {
     format!("\"{}\"", name.replace('"', "\"\""))
 }
 
-/// Generate CREATE DATABASE statement for PostgreSQL
-pub fn postgres_create_db_stmt(db_name: &str, owner: Option<&str>) -> String {
-    let mut stmt = format!("CREATE DATABASE {};", pg_quote_ident(db_name));
-    if let Some(o) = owner {
-        if !o.trim().is_empty() {
-            stmt = format!(
-                "CREATE DATABASE {} OWNER {};",
-                pg_quote_ident(db_name),
-                pg_quote_ident(o)
-            );
-        }
+/// Name of the tablespace created for a database placed in a chosen folder (PostgreSQL)
+pub fn postgres_tablespace_name(db_name: &str) -> String {
+    format!("{}_data", db_name.to_ascii_lowercase())
+}
+
+/// Generate CREATE TABLESPACE for the chosen folder (PostgreSQL; superuser only, and the folder
+/// must exist, be empty and be owned by the PostgreSQL service account)
+pub fn postgres_create_tablespace_stmt(
+    db_name: &str,
+    owner: Option<&str>,
+    location: &str,
+) -> String {
+    let owner = owner
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .map(|o| format!(" OWNER {}", pg_quote_ident(o)))
+        .unwrap_or_default();
+    format!(
+        "CREATE TABLESPACE {}{} LOCATION '{}';",
+        pg_quote_ident(&postgres_tablespace_name(db_name)),
+        owner,
+        location.trim().replace('\'', "''")
+    )
+}
+
+/// Generate DROP TABLESPACE for PostgreSQL (after the database in it was dropped)
+pub fn postgres_drop_tablespace_stmt(db_name: &str) -> String {
+    format!(
+        "DROP TABLESPACE IF EXISTS {};",
+        pg_quote_ident(&postgres_tablespace_name(db_name))
+    )
+}
+
+/// Generate CREATE DATABASE statement for PostgreSQL.
+///
+/// A locale sets LC_COLLATE/LC_CTYPE, which needs `template0`; a files path places the database
+/// in the tablespace from `postgres_create_tablespace_stmt`.
+pub fn postgres_create_db_stmt(
+    db_name: &str,
+    owner: Option<&str>,
+    opts: &CreateDatabaseOptions,
+) -> String {
+    let mut stmt = format!("CREATE DATABASE {}", pg_quote_ident(db_name));
+    if let Some(o) = owner.map(str::trim).filter(|o| !o.is_empty()) {
+        stmt.push_str(&format!(" OWNER {}", pg_quote_ident(o)));
+    }
+    if let Some(locale) = opts.collation() {
+        stmt.push_str(&format!(
+            " TEMPLATE template0 ENCODING 'UTF8' LC_COLLATE '{0}' LC_CTYPE '{0}'",
+            locale.replace('\'', "''")
+        ));
     }
+    if opts.files_path().is_some() {
+        stmt.push_str(&format!(
+            " TABLESPACE {}",
+            pg_quote_ident(&postgres_tablespace_name(db_name))
+        ));
+    }
+    stmt.push(';');
     stmt
 }
 
+/// Generate DROP DATABASE for PostgreSQL (used to roll back a cancelled Create NEW install).
+///
+/// `WITH (FORCE)` (PostgreSQL 13+) terminates the installer's remaining sessions on it.
+pub fn postgres_drop_db_stmt(db_name: &str) -> String {
+    format!(
+        "DROP DATABASE IF EXISTS {} WITH (FORCE);",
+        pg_quote_ident(db_name)
+    )
+}
+
 /// SQL to check if current user can create databases (PostgreSQL)
 pub fn postgres_can_create_db_query() -> &'static str {
     r#"
@@ -240,6 +416,28 @@ pub fn postgres_can_create_db_query() -> &'static str {
     "#
 }
 
+/// Least-privilege script a superuser runs so `role` can create the CADalytix database.
+///
+/// `CREATE DATABASE ... OWNER x` also requires membership in `x`, so a different owner role is
+/// granted to `role` as well.
+pub fn postgres_grant_script(role: &str, owner: Option<&str>) -> String {
+    let mut script = format!(
+        "-- CADalytix: allow {role} to create the CADalytix database (PostgreSQL).\n\
+         -- Run as a superuser on this server, then re-test in the installer.\n\
+         ALTER ROLE {quoted} CREATEDB;\n",
+        role = role,
+        quoted = pg_quote_ident(role)
+    );
+    if let Some(o) = owner.map(str::trim).filter(|o| !o.is_empty() && *o != role) {
+        script.push_str(&format!(
+            "GRANT {} TO {};\n",
+            pg_quote_ident(o),
+            pg_quote_ident(role)
+        ));
+    }
+    script
+}
+
 /// SQL to check if database exists (PostgreSQL)
 pub fn postgres_db_exists_query(db_name: &str) -> String {
     format!(
@@ -299,14 +497,14 @@ mod tests {
 
     #[test]
     fn test_sql_server_create_db_stmt() {
-        let stmt = sql_server_create_db_stmt("TestDB");
+        let stmt = sql_server_create_db_stmt("TestDB", &CreateDatabaseOptions::default());
         assert_eq!(stmt, "CREATE DATABASE [TestDB];");
     }
 
     #[test]
     fn test_sql_server_create_db_stmt_injection() {
         // Bracket injection attempt
-        let stmt = sql_server_create_db_stmt("Test]DB");
+        let stmt = sql_server_create_db_stmt("Test]DB", &CreateDatabaseOptions::default());
         assert_eq!(stmt, "CREATE DATABASE [Test]]DB];");
     }
 
@@ -328,22 +526,89 @@ mod tests {
 
     #[test]
     fn test_postgres_create_db_stmt() {
-        let stmt = postgres_create_db_stmt("testdb", None);
+        let stmt = postgres_create_db_stmt("testdb", None, &CreateDatabaseOptions::default());
         assert_eq!(stmt, "CREATE DATABASE \"testdb\";");
     }
 
     #[test]
     fn test_postgres_create_db_stmt_with_owner() {
-        let stmt = postgres_create_db_stmt("testdb", Some("myuser"));
+        let stmt =
+            postgres_create_db_stmt("testdb", Some("myuser"), &CreateDatabaseOptions::default());
         assert_eq!(stmt, "CREATE DATABASE \"testdb\" OWNER \"myuser\";");
     }
 
     #[test]
     fn test_postgres_create_db_stmt_injection() {
-        let stmt = postgres_create_db_stmt("test\"db", Some("my\"user"));
+        let stmt = postgres_create_db_stmt(
+            "test\"db",
+            Some("my\"user"),
+            &CreateDatabaseOptions::default(),
+        );
         assert_eq!(stmt, "CREATE DATABASE \"test\"\"db\" OWNER \"my\"\"user\";");
     }
 
+    #[test]
+    fn test_create_db_stmts_apply_collation_and_files_path() {
+        let sql_opts = CreateDatabaseOptions {
+            collation: Some("Latin1_General_CI_AS".to_string()),
+            files_path: Some("D:\\SQLData\\".to_string()),
+        };
+        assert_eq!(
+            sql_server_create_db_stmt("MyDB", &sql_opts),
+            "CREATE DATABASE [MyDB] ON PRIMARY ( NAME = N'MyDB', FILENAME = N'D:\\SQLData\\MyDB.mdf' ) LOG ON ( NAME = N'MyDB_log', FILENAME = N'D:\\SQLData\\MyDB_log.ldf' ) COLLATE Latin1_General_CI_AS;"
+        );
+        assert!(validate_create_options(&sql_opts).is_ok());
+
+        let pg_opts = CreateDatabaseOptions {
+            collation: Some("en_US.UTF-8".to_string()),
+            files_path: Some("/srv/pgdata".to_string()),
+        };
+        assert_eq!(
+            postgres_create_tablespace_stmt("CADalytix", Some("owner"), "/srv/pgdata"),
+            "CREATE TABLESPACE \"cadalytix_data\" OWNER \"owner\" LOCATION '/srv/pgdata';"
+        );
+        assert_eq!(
+            postgres_create_db_stmt("CADalytix", None, &pg_opts),
+            "CREATE DATABASE \"CADalytix\" TEMPLATE template0 ENCODING 'UTF8' LC_COLLATE 'en_US.UTF-8' LC_CTYPE 'en_US.UTF-8' TABLESPACE \"cadalytix_data\";"
+        );
+
+        let bad = CreateDatabaseOptions {
+            collation: Some("Latin1; DROP TABLE x".to_string()),
+            files_path: Some("relative/dir".to_string()),
+        };
+        assert!(validate_create_options(&bad).is_err());
+        let relative = CreateDatabaseOptions {
+            files_path: Some("relative/dir".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_create_options(&relative).is_err());
+    }
+
+    #[test]
+    fn test_drop_db_stmts_quote_identifiers() {
+        assert_eq!(
+            sql_server_drop_db_stmt("Test]DB"),
+            "ALTER DATABASE [Test]]DB] SET SINGLE_USER WITH ROLLBACK IMMEDIATE; DROP DATABASE [Test]]DB];"
+        );
+        assert_eq!(
+            postgres_drop_db_stmt("test\"db"),
+            "DROP DATABASE IF EXISTS \"test\"\"db\" WITH (FORCE);"
+        );
+    }
+
+    #[test]
+    fn test_grant_scripts_quote_identifiers() {
+        let script = sql_server_grant_script("CORP\\svc]x");
+        assert!(script.contains("USE [master];"));
+        assert!(script.contains("GRANT CREATE ANY DATABASE TO [CORP\\svc]]x];"));
+
+        let script = postgres_grant_script("cad\"admin", Some("cad_owner"));
+        assert!(script.contains("ALTER ROLE \"cad\"\"admin\" CREATEDB;"));
+        assert!(script.contains("GRANT \"cad_owner\" TO \"cad\"\"admin\";"));
+        assert!(!postgres_grant_script("cad", Some("cad")).contains("GRANT"));
+        assert!(!postgres_grant_script("cad", None).contains("GRANT"));
+    }
+
     #[test]
     fn test_sql_server_db_exists_query() {
         let q = sql_server_db_exists_query("MyDB");
@@ -382,14 +647,15 @@ mod tests {
     #[test]
     fn test_bracket_quote_escaping() {
         // Test that bracket quoting properly escapes brackets
-        let stmt = sql_server_create_db_stmt("Test[DB]Name");
+        let stmt = sql_server_create_db_stmt("Test[DB]Name", &CreateDatabaseOptions::default());
         assert!(stmt.contains("[Test[DB]]Name]"));
     }
 
     #[test]
     fn test_double_quote_escaping() {
         // Test that double-quote escaping works for Postgres
-        let stmt = postgres_create_db_stmt("test\"db\"name", None);
+        let stmt =
+            postgres_create_db_stmt("test\"db\"name", None, &CreateDatabaseOptions::default());
         assert!(stmt.contains("\"test\"\"db\"\"name\""));
     }
 }
@@ -1,24 +1,133 @@
 // Schema verification
 // Ported from C# schema verification logic
 // Verifies database schema completeness and correctness
+//
+// Verification reads the live catalog once (tables, columns with types, indexes) and diffs it
+// against the expected `cadalytix_config` schema: missing tables, missing columns, missing
+// indexes and columns of the wrong type. `repair_all_schemas` applies only additive fixes
+// (nullable ADD COLUMN, CREATE INDEX); missing tables and wrong types need the migrations or a
+// DBA. The diff is written to `Prod_Wizard_Log/schema-diff.json`.
 
 use anyhow::{Context, Result};
-use futures::TryStreamExt;
+use chrono::Utc;
 use log::{info, warn};
+use serde::Serialize;
 use sqlx::{Pool, Postgres};
-use std::collections::HashSet;
-use tiberius::{Query, QueryItem};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 use crate::database::connection::DatabaseConnection;
 
-/// Schema verification result
-#[derive(Debug, Clone)]
+const CONFIG_SCHEMA: &str = "cadalytix_config";
+const DIFF_REPORT_FILE: &str = "schema-diff.json";
+
+/// Schema verification result (one schema's diff)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaVerificationResult {
     pub valid: bool,
     pub missing_tables: Vec<String>,
     pub missing_columns: Vec<(String, String)>, // (table, column)
-    #[allow(dead_code)]
+    pub missing_indexes: Vec<MissingIndex>,
+    pub type_mismatches: Vec<TypeMismatch>,
+    /// Repair statements that failed, and catalog read problems.
     pub errors: Vec<String>,
+    /// Statements applied by `repair_all_schemas` (empty for a plain verification).
+    pub repairs_applied: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingIndex {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeMismatch {
+    pub table: String,
+    pub column: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Type family a column must belong to; exact lengths and engine spellings are not compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Text,
+    Integer,
+    Timestamp,
+    /// Not type-checked (ids, JSON payloads) and never added by repair.
+    Any,
+}
+
+impl ColumnKind {
+    fn name(self) -> &'static str {
+        match self {
+            ColumnKind::Text => "text",
+            ColumnKind::Integer => "integer",
+            ColumnKind::Timestamp => "timestamp",
+            ColumnKind::Any => "any",
+        }
+    }
+
+    fn matches(self, data_type: &str) -> bool {
+        let t = data_type.trim().to_ascii_lowercase();
+        match self {
+            ColumnKind::Text => t.contains("char") || t == "text" || t == "ntext" || t == "citext",
+            ColumnKind::Integer => {
+                matches!(
+                    t.as_str(),
+                    "int" | "integer" | "bigint" | "smallint" | "tinyint"
+                )
+            }
+            ColumnKind::Timestamp => t.starts_with("timestamp") || t.contains("datetime"),
+            ColumnKind::Any => true,
+        }
+    }
+
+    /// Column type used when repair adds the column; `None` when it must not be added.
+    fn repair_type(self, postgres: bool) -> Option<&'static str> {
+        match (self, postgres) {
+            (ColumnKind::Text, true) => Some("TEXT"),
+            (ColumnKind::Text, false) => Some("NVARCHAR(MAX)"),
+            (ColumnKind::Integer, true) => Some("INTEGER"),
+            (ColumnKind::Integer, false) => Some("INT"),
+            (ColumnKind::Timestamp, true) => Some("TIMESTAMPTZ"),
+            (ColumnKind::Timestamp, false) => Some("DATETIME2"),
+            (ColumnKind::Any, _) => None,
+        }
+    }
+}
+
+struct ExpectedColumn {
+    table: &'static str,
+    column: &'static str,
+    kind: ColumnKind,
+}
+
+struct ExpectedIndex {
+    table: &'static str,
+    columns: &'static [&'static str],
+    unique: bool,
+}
+
+struct ExpectedSchema {
+    tables: Vec<&'static str>,
+    columns: Vec<ExpectedColumn>,
+    indexes: Vec<ExpectedIndex>,
+}
+
+/// What the catalog holds for one schema. Names are lowercased.
+#[derive(Debug, Default)]
+struct ActualSchema {
+    /// table -> column -> data type
+    columns: HashMap<String, HashMap<String, String>>,
+    tables: HashSet<String>,
+    /// (table, key columns in order, unique)
+    indexes: Vec<(String, Vec<String>, bool)>,
 }
 
 /// Schema verifier for validating database schema
@@ -33,283 +142,578 @@ impl SchemaVerifier {
         SchemaVerifier { connection }
     }
 
-    /// Verify the complete database schema
-    /// Checks for required tables and columns
-    pub async fn verify_schema(
-        &self,
-        expected_tables: &[String],
-        expected_columns: &[(&str, &str)],
-    ) -> Result<SchemaVerificationResult> {
-        info!("[PHASE: database] [STEP: verify_schema] Starting schema verification");
-
-        match &self.connection {
-            DatabaseConnection::Postgres(pool) => {
-                self.verify_schema_postgres(pool, expected_tables, expected_columns)
-                    .await
-            }
-            DatabaseConnection::SqlServer(_) => {
-                self.verify_schema_sql_server(expected_tables, expected_columns)
-                    .await
-            }
-        }
+    fn is_postgres(&self) -> bool {
+        matches!(self.connection, DatabaseConnection::Postgres(_))
     }
 
-    /// Verify schema in PostgreSQL
-    async fn verify_schema_postgres(
-        &self,
-        pool: &Pool<Postgres>,
-        expected_tables: &[String],
-        expected_columns: &[(&str, &str)],
-    ) -> Result<SchemaVerificationResult> {
-        // Get existing tables in cadalytix_config schema
-        let tables: Vec<String> = sqlx::query_scalar::<_, String>(
-            r#"
-            SELECT tablename
-            FROM pg_tables
-            WHERE schemaname = 'cadalytix_config'
-            "#,
-        )
-        .fetch_all(pool)
-        .await
-        .with_context(|| "Failed to query tables from PostgreSQL")?;
-
-        let existing_tables: HashSet<String> = tables.into_iter().collect();
-        let expected_tables_set: HashSet<String> = expected_tables.iter().cloned().collect();
-
-        // Find missing tables
-        let missing_tables: Vec<String> = expected_tables_set
-            .difference(&existing_tables)
-            .cloned()
-            .collect();
-
-        // Verify columns
-        let mut missing_columns = Vec::new();
-        for (table, column) in expected_columns {
-            let exists: bool = sqlx::query_scalar::<_, bool>(
-                r#"
-                SELECT EXISTS (
-                    SELECT 1
-                    FROM information_schema.columns
-                    WHERE table_schema = 'cadalytix_config'
-                    AND table_name = $1
-                    AND column_name = $2
-                )
-                "#,
-            )
-            .bind(table)
-            .bind(column)
-            .fetch_one(pool)
-            .await
-            .with_context(|| format!("Failed to verify column {}.{}", table, column))?;
-
-            if !exists {
-                missing_columns.push((table.to_string(), column.to_string()));
-            }
-        }
+    /// Diff the live schema against `expected`
+    async fn verify_schema(&self, expected: &ExpectedSchema) -> Result<SchemaVerificationResult> {
+        info!("[PHASE: database] [STEP: verify_schema] Starting schema verification");
 
-        let valid = missing_tables.is_empty() && missing_columns.is_empty();
+        let actual = match &self.connection {
+            DatabaseConnection::Postgres(pool) => read_schema_postgres(pool).await?,
+            DatabaseConnection::SqlServer(_) => self.read_schema_sql_server().await?,
+        };
+        let result = diff_schema(expected, &actual);
 
-        if valid {
-            info!(
-                "[PHASE: database] [STEP: verify_schema] Schema verification passed for PostgreSQL"
-            );
+        if result.valid {
+            info!("[PHASE: database] [STEP: verify_schema] Schema verification passed");
         } else {
             warn!(
-                "[PHASE: database] [STEP: verify_schema] Schema verification found issues: {} missing tables, {} missing columns",
-                missing_tables.len(),
-                missing_columns.len()
+                "[PHASE: database] [STEP: verify_schema] Schema verification found issues: {} missing tables, {} missing columns, {} missing indexes, {} type mismatches",
+                result.missing_tables.len(),
+                result.missing_columns.len(),
+                result.missing_indexes.len(),
+                result.type_mismatches.len()
             );
         }
-
-        Ok(SchemaVerificationResult {
-            valid,
-            missing_tables,
-            missing_columns,
-            errors: Vec::new(),
-        })
+        Ok(result)
     }
 
-    /// Verify schema in SQL Server
-    async fn verify_schema_sql_server(
-        &self,
-        expected_tables: &[String],
-        expected_columns: &[(&str, &str)],
-    ) -> Result<SchemaVerificationResult> {
+    async fn read_schema_sql_server(&self) -> Result<ActualSchema> {
         let client_arc = self
             .connection
             .as_sql_server()
             .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
-
         let mut client = client_arc.lock().await;
 
-        // Get existing tables in cadalytix_config schema
-        let query_str = r#"
-            SELECT TABLE_NAME
-            FROM INFORMATION_SCHEMA.TABLES
-            WHERE TABLE_SCHEMA = 'cadalytix_config'
-        "#;
-
-        // Scope the stream so we can reuse `client` for subsequent queries.
-        let existing_tables: HashSet<String> = {
-            let query = Query::new(query_str);
-            let mut stream = query
-                .query(&mut *client)
-                .await
-                .with_context(|| "Failed to query tables from SQL Server")?;
-
-            let mut existing_tables = HashSet::new();
-            while let Some(item) = stream
-                .try_next()
-                .await
-                .with_context(|| "Failed to read from query result")?
-            {
-                if let QueryItem::Row(row) = item {
-                    let table_name: String = row
-                        .get::<&str, _>(0)
-                        .ok_or_else(|| anyhow::anyhow!("TABLE_NAME is null"))?
-                        .to_string();
-                    existing_tables.insert(table_name);
-                }
+        let mut actual = ActualSchema::default();
+        let tables = client
+            .simple_query(
+                r#"
+                SELECT TABLE_NAME
+                FROM INFORMATION_SCHEMA.TABLES
+                WHERE TABLE_SCHEMA = 'cadalytix_config'
+                "#,
+            )
+            .await
+            .with_context(|| "Failed to query tables from SQL Server")?
+            .into_first_result()
+            .await?;
+        for row in &tables {
+            if let Some(t) = row.get::<&str, _>(0) {
+                actual.tables.insert(t.to_ascii_lowercase());
             }
+        }
 
-            existing_tables
-        };
-
-        let expected_tables_set: HashSet<String> = expected_tables.iter().cloned().collect();
-
-        // Find missing tables
-        let missing_tables: Vec<String> = expected_tables_set
-            .difference(&existing_tables)
-            .cloned()
-            .collect();
-
-        // Verify columns
-        let mut missing_columns = Vec::new();
-        for (table, column) in expected_columns {
-            let query_str = r#"
-                SELECT COUNT(*)
+        let columns = client
+            .simple_query(
+                r#"
+                SELECT TABLE_NAME, COLUMN_NAME, DATA_TYPE
                 FROM INFORMATION_SCHEMA.COLUMNS
                 WHERE TABLE_SCHEMA = 'cadalytix_config'
-                AND TABLE_NAME = @P1
-                AND COLUMN_NAME = @P2
-            "#;
-
-            let mut query = Query::new(query_str);
-            query.bind(*table);
-            query.bind(*column);
-
-            let mut stream = query
-                .query(&mut *client)
-                .await
-                .with_context(|| format!("Failed to verify column {}.{}", table, column))?;
-
-            let mut exists = false;
-            while let Some(item) = stream.try_next().await.with_context(|| {
-                format!(
-                    "Failed to read column verification result for {}.{}",
-                    table, column
-                )
-            })? {
-                if let QueryItem::Row(row) = item {
-                    let count: i32 = row
-                        .get::<i32, _>(0)
-                        .ok_or_else(|| anyhow::anyhow!("COUNT result is null"))?;
-                    exists = count > 0;
-                    break;
-                }
+                "#,
+            )
+            .await
+            .with_context(|| "Failed to query columns from SQL Server")?
+            .into_first_result()
+            .await?;
+        for row in &columns {
+            if let (Some(t), Some(c), Some(ty)) = (
+                row.get::<&str, _>(0),
+                row.get::<&str, _>(1),
+                row.get::<&str, _>(2),
+            ) {
+                actual
+                    .columns
+                    .entry(t.to_ascii_lowercase())
+                    .or_default()
+                    .insert(c.to_ascii_lowercase(), ty.to_string());
             }
+        }
 
-            if !exists {
-                missing_columns.push((table.to_string(), column.to_string()));
+        // FOR XML PATH instead of STRING_AGG: the latter needs SQL Server 2017.
+        let indexes = client
+            .simple_query(
+                r#"
+                SELECT t.name AS table_name,
+                       CAST(i.is_unique AS INT) AS is_unique,
+                       STUFF((
+                           SELECT ',' + c.name
+                           FROM sys.index_columns ic
+                           JOIN sys.columns c
+                             ON c.object_id = ic.object_id AND c.column_id = ic.column_id
+                           WHERE ic.object_id = i.object_id
+                             AND ic.index_id = i.index_id
+                             AND ic.is_included_column = 0
+                           ORDER BY ic.key_ordinal
+                           FOR XML PATH('')
+                       ), 1, 1, '') AS key_columns
+                FROM sys.indexes i
+                JOIN sys.tables t ON t.object_id = i.object_id
+                JOIN sys.schemas s ON s.schema_id = t.schema_id
+                WHERE s.name = 'cadalytix_config' AND i.type > 0
+                "#,
+            )
+            .await
+            .with_context(|| "Failed to query indexes from SQL Server")?
+            .into_first_result()
+            .await?;
+        for row in &indexes {
+            if let (Some(t), Some(cols)) = (row.get::<&str, _>(0), row.get::<&str, _>(2)) {
+                actual.indexes.push((
+                    t.to_ascii_lowercase(),
+                    split_columns(cols),
+                    row.get::<i32, _>(1).unwrap_or(0) == 1,
+                ));
             }
         }
+        Ok(actual)
+    }
 
-        let valid = missing_tables.is_empty() && missing_columns.is_empty();
+    /// Verify all schemas (convenience method)
+    pub async fn verify_all_schemas(&self) -> Result<Vec<(String, SchemaVerificationResult)>> {
+        info!("[PHASE: database] [STEP: verify_all_schemas] Starting verification of all schemas");
 
-        if valid {
-            info!(
-                "[PHASE: database] [STEP: verify_schema] Schema verification passed for SQL Server"
-            );
-        } else {
-            warn!(
-                "[PHASE: database] [STEP: verify_schema] Schema verification found issues: {} missing tables, {} missing columns",
-                missing_tables.len(),
-                missing_columns.len()
-            );
+        let result = self.verify_schema(&expected_config_schema()).await?;
+        Ok(vec![(CONFIG_SCHEMA.to_string(), result)])
+    }
+
+    /// Verify, apply the additive fixes for what is missing, and verify again. The returned diff
+    /// is the state after repair; `repairs_applied`/`errors` say what was changed or failed.
+    pub async fn repair_all_schemas(&self) -> Result<Vec<(String, SchemaVerificationResult)>> {
+        let expected = expected_config_schema();
+        let before = self.verify_schema(&expected).await?;
+        let statements = repair_statements(&expected, &before, self.is_postgres());
+        if statements.is_empty() {
+            return Ok(vec![(CONFIG_SCHEMA.to_string(), before)]);
         }
 
-        Ok(SchemaVerificationResult {
-            valid,
-            missing_tables,
-            missing_columns,
-            errors: Vec::new(),
-        })
+        info!(
+            "[PHASE: database] [STEP: repair_schema] Applying {} additive schema fix(es)",
+            statements.len()
+        );
+        let mut applied = Vec::new();
+        let mut errors = Vec::new();
+        for sql in statements {
+            match self.execute(&sql).await {
+                Ok(()) => applied.push(sql),
+                Err(e) => {
+                    warn!(
+                        "[PHASE: database] [STEP: repair_schema] Repair statement failed: {} ({:?})",
+                        sql, e
+                    );
+                    errors.push(format!("{}: {}", sql, e));
+                }
+            }
+        }
+
+        let mut after = self.verify_schema(&expected).await?;
+        after.repairs_applied = applied;
+        after.errors.extend(errors);
+        Ok(vec![(CONFIG_SCHEMA.to_string(), after)])
     }
 
-    /// Verify all schemas (convenience method)
-    pub async fn verify_all_schemas(&self) -> Result<Vec<(String, SchemaVerificationResult)>> {
-        info!("[PHASE: database] [STEP: verify_all_schemas] Starting verification of all schemas");
+    async fn execute(&self, sql: &str) -> Result<()> {
+        match &self.connection {
+            DatabaseConnection::Postgres(pool) => {
+                sqlx::query(sql).execute(pool).await?;
+            }
+            DatabaseConnection::SqlServer(_) => {
+                let client_arc = self
+                    .connection
+                    .as_sql_server()
+                    .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+                let mut client = client_arc.lock().await;
+                client.simple_query(sql).await?.into_results().await?;
+            }
+        }
+        Ok(())
+    }
+}
 
-        // Define expected tables and columns for cadalytix_config schema.
-        //
-        // These are created by the versioned migration set (002/007/008/009 + 010/011 enhancements).
-        let expected_tables = vec![
-            "instance_settings".to_string(),
-            "applied_migrations".to_string(),
-            "wizard_checkpoints".to_string(),
-            "license_state".to_string(),
-            "setup_events".to_string(),
-        ];
+async fn read_schema_postgres(pool: &Pool<Postgres>) -> Result<ActualSchema> {
+    let mut actual = ActualSchema::default();
+
+    let tables: Vec<String> = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT tablename::text
+        FROM pg_tables
+        WHERE schemaname = 'cadalytix_config'
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| "Failed to query tables from PostgreSQL")?;
+    actual.tables = tables.into_iter().map(|t| t.to_ascii_lowercase()).collect();
+
+    let columns: Vec<(String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT table_name::text, column_name::text, data_type::text
+        FROM information_schema.columns
+        WHERE table_schema = 'cadalytix_config'
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| "Failed to query columns from PostgreSQL")?;
+    for (t, c, ty) in columns {
+        actual
+            .columns
+            .entry(t.to_ascii_lowercase())
+            .or_default()
+            .insert(c.to_ascii_lowercase(), ty);
+    }
 
-        let expected_columns = vec![
+    let indexes: Vec<(String, bool, String)> = sqlx::query_as(
+        r#"
+        SELECT t.relname::text,
+               ix.indisunique,
+               array_to_string(ARRAY(
+                   SELECT a.attname::text
+                   FROM unnest(ix.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+                   JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = k.attnum
+                   ORDER BY k.ord
+               ), ',')
+        FROM pg_index ix
+        JOIN pg_class t ON t.oid = ix.indrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        WHERE n.nspname = 'cadalytix_config'
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| "Failed to query indexes from PostgreSQL")?;
+    for (t, unique, cols) in indexes {
+        actual
+            .indexes
+            .push((t.to_ascii_lowercase(), split_columns(&cols), unique));
+    }
+    Ok(actual)
+}
+
+fn split_columns(cols: &str) -> Vec<String> {
+    cols.split(',')
+        .map(|c| c.trim().to_ascii_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Expected `cadalytix_config` objects.
+///
+/// These are created by the versioned migration set (002/007/008/009 + 010/011 enhancements).
+/// The unique indexes are the ones the installer's upserts rely on.
+fn expected_config_schema() -> ExpectedSchema {
+    use ColumnKind::*;
+    let col = |table, column, kind| ExpectedColumn {
+        table,
+        column,
+        kind,
+    };
+    ExpectedSchema {
+        tables: vec![
+            "instance_settings",
+            "applied_migrations",
+            "wizard_checkpoints",
+            "license_state",
+            "setup_events",
+        ],
+        columns: vec![
             // instance_settings (key/value)
-            ("instance_settings", "key"),
-            ("instance_settings", "value"),
-            ("instance_settings", "updated_at"),
+            col("instance_settings", "key", Text),
+            col("instance_settings", "value", Text),
+            col("instance_settings", "updated_at", Timestamp),
             // applied_migrations (enhanced by migration 010)
-            ("applied_migrations", "migration_name"),
-            ("applied_migrations", "applied_at"),
-            ("applied_migrations", "checksum"),
-            ("applied_migrations", "migration_group"),
-            ("applied_migrations", "engine"),
-            ("applied_migrations", "execution_time_ms"),
-            ("applied_migrations", "applied_by"),
+            col("applied_migrations", "migration_name", Text),
+            col("applied_migrations", "applied_at", Timestamp),
+            col("applied_migrations", "checksum", Text),
+            col("applied_migrations", "migration_group", Text),
+            col("applied_migrations", "engine", Text),
+            col("applied_migrations", "execution_time_ms", Integer),
+            col("applied_migrations", "applied_by", Text),
             // wizard_checkpoints
-            ("wizard_checkpoints", "step_name"),
-            ("wizard_checkpoints", "state_json"),
-            ("wizard_checkpoints", "updated_at"),
+            col("wizard_checkpoints", "step_name", Text),
+            col("wizard_checkpoints", "state_json", Any),
+            col("wizard_checkpoints", "updated_at", Timestamp),
             // license_state (011 adds signed_token_blob + anti-backdating columns)
-            ("license_state", "id"),
-            ("license_state", "mode"),
-            ("license_state", "license_key_masked"),
-            ("license_state", "license_key_hash"),
-            ("license_state", "status"),
-            ("license_state", "client_name"),
-            ("license_state", "license_id"),
-            ("license_state", "issued_at_utc"),
-            ("license_state", "expires_at_utc"),
-            ("license_state", "grace_until_utc"),
-            ("license_state", "last_verified_at_utc"),
-            ("license_state", "features_json"),
-            ("license_state", "installation_token"),
-            ("license_state", "signed_token_blob"),
-            ("license_state", "last_seen_now_utc"),
-            ("license_state", "last_seen_expires_utc"),
-            ("license_state", "created_at"),
-            ("license_state", "updated_at"),
+            col("license_state", "id", Any),
+            col("license_state", "mode", Text),
+            col("license_state", "license_key_masked", Text),
+            col("license_state", "license_key_hash", Text),
+            col("license_state", "status", Text),
+            col("license_state", "client_name", Text),
+            col("license_state", "license_id", Any),
+            col("license_state", "issued_at_utc", Timestamp),
+            col("license_state", "expires_at_utc", Timestamp),
+            col("license_state", "grace_until_utc", Timestamp),
+            col("license_state", "last_verified_at_utc", Timestamp),
+            col("license_state", "features_json", Any),
+            col("license_state", "installation_token", Text),
+            col("license_state", "signed_token_blob", Text),
+            col("license_state", "last_seen_now_utc", Timestamp),
+            col("license_state", "last_seen_expires_utc", Timestamp),
+            col("license_state", "created_at", Timestamp),
+            col("license_state", "updated_at", Timestamp),
             // setup_events
-            ("setup_events", "id"),
-            ("setup_events", "event_type"),
-            ("setup_events", "description"),
-            ("setup_events", "actor"),
-            ("setup_events", "metadata"),
-            ("setup_events", "occurred_at"),
-        ];
+            col("setup_events", "id", Any),
+            col("setup_events", "event_type", Text),
+            col("setup_events", "description", Text),
+            col("setup_events", "actor", Text),
+            col("setup_events", "metadata", Any),
+            col("setup_events", "occurred_at", Timestamp),
+        ],
+        indexes: vec![
+            ExpectedIndex {
+                table: "instance_settings",
+                columns: &["key"],
+                unique: true,
+            },
+            ExpectedIndex {
+                table: "applied_migrations",
+                columns: &["migration_name"],
+                unique: true,
+            },
+            ExpectedIndex {
+                table: "wizard_checkpoints",
+                columns: &["step_name"],
+                unique: true,
+            },
+        ],
+    }
+}
 
-        let result = self
-            .verify_schema(&expected_tables, &expected_columns)
-            .await?;
+/// Compare `actual` with `expected`. Columns and indexes of a missing table are not listed
+/// separately; the missing table covers them.
+fn diff_schema(expected: &ExpectedSchema, actual: &ActualSchema) -> SchemaVerificationResult {
+    let mut result = SchemaVerificationResult::default();
+
+    for table in &expected.tables {
+        if !actual.tables.contains(*table) {
+            result.missing_tables.push(table.to_string());
+        }
+    }
 
-        Ok(vec![("cadalytix_config".to_string(), result)])
+    for c in &expected.columns {
+        if !actual.tables.contains(c.table) {
+            continue;
+        }
+        match actual
+            .columns
+            .get(c.table)
+            .and_then(|cols| cols.get(c.column))
+        {
+            None => result
+                .missing_columns
+                .push((c.table.to_string(), c.column.to_string())),
+            Some(data_type) if !c.kind.matches(data_type) => {
+                result.type_mismatches.push(TypeMismatch {
+                    table: c.table.to_string(),
+                    column: c.column.to_string(),
+                    expected: c.kind.name().to_string(),
+                    actual: data_type.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for ix in &expected.indexes {
+        if !actual.tables.contains(ix.table) {
+            continue;
+        }
+        // A lookup index is covered by any index leading with its columns; a unique one needs a
+        // unique index on exactly those columns.
+        let covered = actual.indexes.iter().any(|(table, cols, unique)| {
+            table == ix.table
+                && if ix.unique {
+                    *unique && cols.len() == ix.columns.len()
+                } else {
+                    cols.len() >= ix.columns.len()
+                }
+                && cols.iter().zip(ix.columns).all(|(a, e)| a == e)
+        });
+        if !covered {
+            result.missing_indexes.push(MissingIndex {
+                table: ix.table.to_string(),
+                columns: ix.columns.iter().map(|c| c.to_string()).collect(),
+                unique: ix.unique,
+            });
+        }
+    }
+
+    result.valid = result.missing_tables.is_empty()
+        && result.missing_columns.is_empty()
+        && result.missing_indexes.is_empty()
+        && result.type_mismatches.is_empty();
+    result
+}
+
+/// Additive DDL for what `diff` found missing: nullable columns (so existing rows stay valid) and
+/// indexes. Missing tables, wrong types and untyped columns are left for the migrations/a DBA.
+fn repair_statements(
+    expected: &ExpectedSchema,
+    diff: &SchemaVerificationResult,
+    postgres: bool,
+) -> Vec<String> {
+    let quote = |name: &str| {
+        if postgres {
+            format!("\"{}\"", name.replace('"', "\"\""))
+        } else {
+            format!("[{}]", name.replace(']', "]]"))
+        }
+    };
+    let mut out = Vec::new();
+    for (table, column) in &diff.missing_columns {
+        let kind = expected
+            .columns
+            .iter()
+            .find(|c| c.table == table && c.column == column)
+            .map(|c| c.kind)
+            .unwrap_or(ColumnKind::Any);
+        if let Some(ty) = kind.repair_type(postgres) {
+            out.push(format!(
+                "ALTER TABLE {}.{} ADD {} {} NULL",
+                CONFIG_SCHEMA,
+                quote(table),
+                quote(column),
+                ty
+            ));
+        }
+    }
+    for ix in &diff.missing_indexes {
+        let name = format!(
+            "{}_{}_{}",
+            if ix.unique { "ux" } else { "ix" },
+            ix.table,
+            ix.columns.join("_")
+        );
+        let cols: Vec<String> = ix.columns.iter().map(|c| quote(c)).collect();
+        out.push(format!(
+            "CREATE {}INDEX {}{} ON {}.{} ({})",
+            if ix.unique { "UNIQUE " } else { "" },
+            if postgres { "IF NOT EXISTS " } else { "" },
+            quote(&name),
+            CONFIG_SCHEMA,
+            quote(&ix.table),
+            cols.join(", ")
+        ));
+    }
+    out
+}
+
+/// Write the diff to `Prod_Wizard_Log/schema-diff.json` and return its path.
+pub async fn write_diff_report(results: &[(String, SchemaVerificationResult)]) -> Result<PathBuf> {
+    let schemas: Vec<serde_json::Value> = results
+        .iter()
+        .map(|(schema, r)| {
+            let mut v = serde_json::to_value(r).unwrap_or_default();
+            if let Some(obj) = v.as_object_mut() {
+                obj.insert("schema".to_string(), serde_json::json!(schema));
+            }
+            v
+        })
+        .collect();
+    let report = serde_json::json!({
+        "generatedAtUtc": Utc::now().to_rfc3339(),
+        "valid": results.iter().all(|(_, r)| r.valid),
+        "schemas": schemas,
+    });
+
+    let path = crate::utils::path_resolver::resolve_log_folder()?.join(DIFF_REPORT_FILE);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.ok();
+    }
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&report)?)
+        .await
+        .with_context(|| format!("Failed to write schema diff report: {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_missing_objects_and_repairs_only_additive_ones() {
+        let expected = expected_config_schema();
+        let mut actual = ActualSchema::default();
+        for t in [
+            "instance_settings",
+            "applied_migrations",
+            "wizard_checkpoints",
+        ] {
+            actual.tables.insert(t.to_string());
+        }
+        let mut cols = |table: &str, list: &[(&str, &str)]| {
+            actual.columns.insert(
+                table.to_string(),
+                list.iter()
+                    .map(|(c, t)| (c.to_string(), t.to_string()))
+                    .collect(),
+            );
+        };
+        cols(
+            "instance_settings",
+            &[
+                ("key", "nvarchar"),
+                ("value", "nvarchar"),
+                ("updated_at", "datetime2"),
+            ],
+        );
+        cols(
+            "applied_migrations",
+            &[
+                ("migration_name", "nvarchar"),
+                ("applied_at", "datetime2"),
+                ("checksum", "nvarchar"),
+                ("migration_group", "nvarchar"),
+                ("engine", "nvarchar"),
+                ("execution_time_ms", "nvarchar"),
+            ],
+        );
+        cols(
+            "wizard_checkpoints",
+            &[("step_name", "nvarchar"), ("state_json", "nvarchar")],
+        );
+        actual.indexes = vec![
+            (
+                "instance_settings".to_string(),
+                vec!["key".to_string()],
+                true,
+            ),
+            (
+                "applied_migrations".to_string(),
+                vec!["migration_name".to_string()],
+                false,
+            ),
+            (
+                "wizard_checkpoints".to_string(),
+                vec!["step_name".to_string(), "updated_at".to_string()],
+                true,
+            ),
+        ];
+
+        let diff = diff_schema(&expected, &actual);
+        assert!(!diff.valid);
+        assert_eq!(diff.missing_tables, vec!["license_state", "setup_events"]);
+        assert_eq!(
+            diff.missing_columns,
+            vec![
+                ("applied_migrations".to_string(), "applied_by".to_string()),
+                ("wizard_checkpoints".to_string(), "updated_at".to_string()),
+            ]
+        );
+        assert_eq!(diff.type_mismatches.len(), 1);
+        assert_eq!(diff.type_mismatches[0].column, "execution_time_ms");
+        // Neither a non-unique index nor a wider unique one enforces the required uniqueness.
+        let missing: Vec<&str> = diff
+            .missing_indexes
+            .iter()
+            .map(|ix| ix.table.as_str())
+            .collect();
+        assert_eq!(missing, vec!["applied_migrations", "wizard_checkpoints"]);
+
+        let sql = repair_statements(&expected, &diff, false);
+        assert_eq!(
+            sql,
+            vec![
+                "ALTER TABLE cadalytix_config.[applied_migrations] ADD [applied_by] NVARCHAR(MAX) NULL",
+                "ALTER TABLE cadalytix_config.[wizard_checkpoints] ADD [updated_at] DATETIME2 NULL",
+                "CREATE UNIQUE INDEX [ux_applied_migrations_migration_name] ON cadalytix_config.[applied_migrations] ([migration_name])",
+                "CREATE UNIQUE INDEX [ux_wizard_checkpoints_step_name] ON cadalytix_config.[wizard_checkpoints] ([step_name])",
+            ]
+        );
+        let pg = repair_statements(&expected, &diff, true);
+        assert!(pg[2].starts_with("CREATE UNIQUE INDEX IF NOT EXISTS \"ux_applied_migrations"));
     }
 }
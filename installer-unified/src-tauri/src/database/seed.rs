@@ -0,0 +1,443 @@
+// Reference data seeding.
+//
+// Runs after migrations and loads lookup data (dispositions, unit types, default roles). Every row
+// is upserted by its natural key, so re-running an install or an upgrade never duplicates rows and
+// brings changed names/descriptions up to date.
+//
+// The data set is `seed/reference_data.json` in the migrations bundle (disk, then the copy
+// embedded at build time); without one, the defaults compiled in from `seed_reference_data.json`
+// are used. A table that does not exist is skipped and reported, not an error: the lookup tables
+// come from the product migrations, which may not have created them yet.
+//
+// Each table is seeded in its own transaction. Table and column names are validated as plain
+// identifiers before they are quoted into SQL; values are always bound as parameters.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::Row;
+use std::path::Path;
+use tiberius::{Client, Query};
+use tokio::net::TcpStream;
+use tokio_util::compat::Compat;
+
+use super::connection::DatabaseConnection;
+
+const BUNDLE_SEED_FILE: &str = "seed/reference_data.json";
+const DEFAULT_SEED: &str = include_str!("seed_reference_data.json");
+
+#[derive(Debug, Clone, Deserialize)]
+struct SeedFile {
+    tables: Vec<SeedTable>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SeedTable {
+    /// `schema.table`
+    table: String,
+    /// Natural key columns; rows are matched on these.
+    key: Vec<String>,
+    rows: Vec<Map<String, Value>>,
+}
+
+/// Outcome for one seeded table; recorded in the install manifest.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedTableReport {
+    pub table: String,
+    pub inserted: u64,
+    pub updated: u64,
+    /// The table does not exist in this database.
+    pub skipped: bool,
+}
+
+/// Seed the reference data set for the bundle at `migrations_path`.
+#[tracing::instrument(name = "db.seed", skip_all)]
+pub async fn seed_reference_data(
+    conn: &DatabaseConnection,
+    migrations_path: &Path,
+) -> Result<Vec<SeedTableReport>> {
+    let seed = load_seed(migrations_path).await?;
+
+    let mut reports = Vec::new();
+    for table in &seed.tables {
+        let report = seed_table(conn, table)
+            .await
+            .with_context(|| format!("Failed to seed reference data into {}", table.table))?;
+
+        if report.skipped {
+            warn!(
+                "[PHASE: database] [STEP: seed] {} does not exist; skipped {} row(s)",
+                table.table,
+                table.rows.len()
+            );
+        } else {
+            info!(
+                "[PHASE: database] [STEP: seed] {}: {} inserted, {} updated",
+                report.table, report.inserted, report.updated
+            );
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+async fn seed_table(conn: &DatabaseConnection, table: &SeedTable) -> Result<SeedTableReport> {
+    match conn {
+        DatabaseConnection::Postgres(pool) => seed_table_postgres(pool, table).await,
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+            let report = seed_table_sql_server(&mut client, table).await?;
+            Ok(report)
+        }
+    }
+}
+
+async fn load_seed(migrations_path: &Path) -> Result<SeedFile> {
+    let path = migrations_path.join(BUNDLE_SEED_FILE);
+    let (source, text) = match super::migrations::read_bundle_file(&path, BUNDLE_SEED_FILE).await {
+        Ok(bytes) => (
+            path.to_string_lossy().to_string(),
+            String::from_utf8(bytes).context("Reference data file is not valid UTF-8")?,
+        ),
+        Err(_) => ("built-in defaults".to_string(), DEFAULT_SEED.to_string()),
+    };
+    let seed: SeedFile = serde_json::from_str(&text)
+        .with_context(|| format!("Failed to parse reference data ({})", source))?;
+    validate(&seed).with_context(|| format!("Invalid reference data ({})", source))?;
+    info!(
+        "[PHASE: database] [STEP: seed] Loaded reference data for {} table(s) from {}",
+        seed.tables.len(),
+        source
+    );
+    Ok(seed)
+}
+
+fn validate(seed: &SeedFile) -> Result<()> {
+    for t in &seed.tables {
+        if !t.table.split('.').all(is_identifier) {
+            anyhow::bail!("'{}' is not a valid table name", t.table);
+        }
+        if t.key.is_empty() {
+            anyhow::bail!("{} has no key columns", t.table);
+        }
+        for (i, row) in t.rows.iter().enumerate() {
+            if let Some(k) = t.key.iter().find(|k| !row.contains_key(k.as_str())) {
+                anyhow::bail!(
+                    "{} row {} has no value for key column '{}'",
+                    t.table,
+                    i + 1,
+                    k
+                );
+            }
+            for (column, value) in row {
+                if !is_identifier(column) {
+                    anyhow::bail!(
+                        "{} row {}: '{}' is not a valid column name",
+                        t.table,
+                        i + 1,
+                        column
+                    );
+                }
+                if value.is_array() || value.is_object() {
+                    anyhow::bail!(
+                        "{} row {}: '{}' must be a scalar value",
+                        t.table,
+                        i + 1,
+                        column
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Statements for one row. `update` is `None` when every column is a key column. `params` holds
+/// the key values followed by the other columns; `exists` binds only the key values.
+struct RowSql {
+    exists: String,
+    update: Option<String>,
+    insert: String,
+    params: Vec<Value>,
+}
+
+fn row_sql(table: &SeedTable, row: &Map<String, Value>, postgres: bool) -> RowSql {
+    let quote = |name: &str| {
+        if postgres {
+            format!("\"{}\"", name)
+        } else {
+            format!("[{}]", name)
+        }
+    };
+    let param = |n: usize| {
+        if postgres {
+            format!("${}", n)
+        } else {
+            format!("@P{}", n)
+        }
+    };
+    let table_name = table
+        .table
+        .split('.')
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(".");
+    let others: Vec<&String> = row.keys().filter(|c| !table.key.contains(*c)).collect();
+
+    // Key columns bind as $1..$k in every statement, the other columns as $k+1...
+    let key_match = table
+        .key
+        .iter()
+        .enumerate()
+        .map(|(i, k)| format!("{} = {}", quote(k), param(i + 1)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let set = others
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("{} = {}", quote(c), param(table.key.len() + i + 1)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let columns = table
+        .key
+        .iter()
+        .chain(others.iter().copied())
+        .map(|c| quote(c))
+        .collect::<Vec<_>>();
+    let values = (1..=columns.len()).map(param).collect::<Vec<_>>();
+
+    RowSql {
+        exists: format!("SELECT COUNT(*) FROM {} WHERE {}", table_name, key_match),
+        update: (!others.is_empty())
+            .then(|| format!("UPDATE {} SET {} WHERE {}", table_name, set, key_match)),
+        insert: format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table_name,
+            columns.join(", "),
+            values.join(", ")
+        ),
+        params: table
+            .key
+            .iter()
+            .chain(others.iter().copied())
+            .map(|c| row.get(c).cloned().unwrap_or(Value::Null))
+            .collect(),
+    }
+}
+
+async fn seed_table_postgres(
+    pool: &sqlx::Pool<sqlx::Postgres>,
+    table: &SeedTable,
+) -> Result<SeedTableReport> {
+    let mut report = new_report(table);
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(&table.table)
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        report.skipped = true;
+        return Ok(report);
+    }
+
+    let mut tx = pool.begin().await?;
+    for row in &table.rows {
+        let sql = row_sql(table, row, true);
+        let keys = &sql.params[..table.key.len()];
+        let count: i64 = bind_pg_all(sqlx::query(&sql.exists), keys)
+            .fetch_one(&mut *tx)
+            .await?
+            .try_get(0)?;
+        if count == 0 {
+            bind_pg_all(sqlx::query(&sql.insert), &sql.params)
+                .execute(&mut *tx)
+                .await?;
+            report.inserted += 1;
+        } else if let Some(update) = &sql.update {
+            bind_pg_all(sqlx::query(update), &sql.params)
+                .execute(&mut *tx)
+                .await?;
+            report.updated += 1;
+        }
+    }
+    tx.commit().await?;
+    Ok(report)
+}
+
+/// Bind JSON scalars as their natural PostgreSQL types.
+fn bind_pg_all<'q>(
+    mut query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    params: &'q [Value],
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    for p in params {
+        query = match p {
+            Value::String(s) => query.bind(s.as_str()),
+            Value::Bool(b) => query.bind(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64().unwrap_or_default()),
+            },
+            _ => query.bind(Option::<String>::None),
+        };
+    }
+    query
+}
+
+async fn seed_table_sql_server(
+    client: &mut Client<Compat<TcpStream>>,
+    table: &SeedTable,
+) -> Result<SeedTableReport> {
+    let mut report = new_report(table);
+    let mut exists = Query::new("SELECT CASE WHEN OBJECT_ID(@P1, 'U') IS NULL THEN 0 ELSE 1 END");
+    exists.bind(table.table.as_str());
+    let exists = exists
+        .query(&mut *client)
+        .await?
+        .into_row()
+        .await?
+        .and_then(|r| r.get::<i32, _>(0))
+        .unwrap_or(0)
+        == 1;
+    if !exists {
+        report.skipped = true;
+        return Ok(report);
+    }
+
+    client
+        .simple_query("BEGIN TRANSACTION")
+        .await?
+        .into_results()
+        .await?;
+    match seed_rows_sql_server(client, table, &mut report).await {
+        Ok(()) => {
+            client.simple_query("COMMIT").await?.into_results().await?;
+            Ok(report)
+        }
+        Err(e) => {
+            if let Ok(stream) = client.simple_query("ROLLBACK").await {
+                let _ = stream.into_results().await;
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn seed_rows_sql_server(
+    client: &mut Client<Compat<TcpStream>>,
+    table: &SeedTable,
+    report: &mut SeedTableReport,
+) -> Result<()> {
+    for row in &table.rows {
+        let sql = row_sql(table, row, false);
+
+        let mut exists = Query::new(sql.exists.as_str());
+        bind_tds_all(&mut exists, &sql.params[..table.key.len()]);
+        let count = exists
+            .query(&mut *client)
+            .await?
+            .into_row()
+            .await?
+            .and_then(|r| r.get::<i32, _>(0))
+            .unwrap_or(0);
+
+        let statement = if count == 0 {
+            report.inserted += 1;
+            &sql.insert
+        } else if let Some(update) = &sql.update {
+            report.updated += 1;
+            update
+        } else {
+            continue;
+        };
+        let mut query = Query::new(statement.as_str());
+        bind_tds_all(&mut query, &sql.params);
+        query.execute(&mut *client).await?;
+    }
+    Ok(())
+}
+
+fn bind_tds_all<'a>(query: &mut Query<'a>, params: &'a [Value]) {
+    for p in params {
+        match p {
+            Value::String(s) => query.bind(s.as_str()),
+            Value::Bool(b) => query.bind(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64().unwrap_or_default()),
+            },
+            _ => query.bind(Option::<&str>::None),
+        }
+    }
+}
+
+fn new_report(table: &SeedTable) -> SeedTableReport {
+    SeedTableReport {
+        table: table.table.clone(),
+        inserted: 0,
+        updated: 0,
+        skipped: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_seed_is_valid_and_rows_upsert_by_natural_key() {
+        let seed: SeedFile = serde_json::from_str(DEFAULT_SEED).unwrap();
+        validate(&seed).unwrap();
+        let tables: Vec<&str> = seed.tables.iter().map(|t| t.table.as_str()).collect();
+        assert_eq!(
+            tables,
+            vec![
+                "cadalytix_config.dispositions",
+                "cadalytix_config.unit_types",
+                "cadalytix_config.roles"
+            ]
+        );
+
+        let roles = &seed.tables[2];
+        let sql = row_sql(roles, &roles.rows[0], false);
+        assert_eq!(
+            sql.exists,
+            "SELECT COUNT(*) FROM [cadalytix_config].[roles] WHERE [name] = @P1"
+        );
+        assert_eq!(
+            sql.update.as_deref(),
+            Some("UPDATE [cadalytix_config].[roles] SET [description] = @P2 WHERE [name] = @P1")
+        );
+        assert_eq!(
+            sql.insert,
+            "INSERT INTO [cadalytix_config].[roles] ([name], [description]) VALUES (@P1, @P2)"
+        );
+        assert_eq!(sql.params[0], Value::String("Administrator".to_string()));
+
+        let pg = row_sql(roles, &roles.rows[0], true);
+        assert_eq!(
+            pg.insert,
+            "INSERT INTO \"cadalytix_config\".\"roles\" (\"name\", \"description\") VALUES ($1, $2)"
+        );
+
+        let bad: SeedFile = serde_json::from_str(
+            r#"{"tables":[{"table":"x.t; DROP TABLE y","key":["code"],"rows":[]}]}"#,
+        )
+        .unwrap();
+        assert!(validate(&bad).is_err());
+        let missing_key: SeedFile = serde_json::from_str(
+            r#"{"tables":[{"table":"x.t","key":["code"],"rows":[{"name":"n"}]}]}"#,
+        )
+        .unwrap();
+        assert!(validate(&missing_key).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Flat-file call data: a folder the CAD system drops CSV / TSV exports into.
+//!
+//! Ingestion reads every export in the folder; the installer only needs the columns for the
+//! Mapping page. `discover` reads the header and the first rows of the newest export, infers a
+//! type per column from those rows and compares the header of the next few exports so a folder
+//! mixing export layouts is caught before install. Records are read one line at a time; quoted
+//! fields spanning lines are not supported.
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::mapping::csv::split_record;
+use crate::models::responses::DiscoveredColumnDto;
+
+/// File extensions treated as exports (case-insensitive).
+pub const EXPORT_EXTENSIONS: [&str; 3] = ["csv", "tsv", "txt"];
+/// Exports (newest first, including the sampled one) whose header is compared.
+const HEADER_CHECK_FILES: usize = 5;
+/// Delimiters tried when the delimiter is "auto", in tie-break order.
+const AUTO_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// Result of a header scan of an export folder.
+#[derive(Debug, Clone)]
+pub struct FileDiscovery {
+    /// The export the header and sample rows were read from (the newest one).
+    pub file: PathBuf,
+    pub files_found: usize,
+    pub delimiter: char,
+    pub columns: Vec<DiscoveredColumnDto>,
+    pub sample_count: usize,
+    /// Recent exports whose header differs from `file`.
+    pub mismatched: Vec<PathBuf>,
+}
+
+/// Parse the delimiter setting: `None` for auto-detection.
+pub fn parse_delimiter(setting: &str) -> Result<Option<char>> {
+    match setting.trim().to_ascii_lowercase().as_str() {
+        "" | "auto" => Ok(None),
+        "comma" | "," => Ok(Some(',')),
+        "tab" | "\\t" | "\t" => Ok(Some('\t')),
+        "semicolon" | ";" => Ok(Some(';')),
+        "pipe" | "|" => Ok(Some('|')),
+        other => anyhow::bail!(
+            "Unsupported delimiter '{}' (use auto, comma, tab, semicolon or pipe).",
+            other
+        ),
+    }
+}
+
+pub fn delimiter_name(delimiter: char) -> &'static str {
+    match delimiter {
+        '\t' => "tab",
+        ';' => "semicolon",
+        '|' => "pipe",
+        _ => "comma",
+    }
+}
+
+/// `.tsv` files are tab separated; otherwise the candidate occurring most in the header wins.
+fn detect_delimiter(path: &Path, header: &str) -> char {
+    let is_tsv = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    if is_tsv {
+        return '\t';
+    }
+    AUTO_DELIMITERS
+        .iter()
+        .copied()
+        .rev()
+        .max_by_key(|d| header.matches(*d).count())
+        .filter(|d| header.contains(*d))
+        .unwrap_or(',')
+}
+
+/// Exports in `folder`, newest first (ties by name).
+pub fn list_exports(folder: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(folder)
+        .with_context(|| format!("Unable to read export folder {}", folder.display()))?;
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_export = path
+            .extension()
+            .is_some_and(|e| EXPORT_EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)));
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if is_export && meta.is_file() {
+            files.push((meta.modified().unwrap_or(std::time::UNIX_EPOCH), path));
+        }
+    }
+    files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(files.into_iter().map(|(_, p)| p).collect())
+}
+
+/// The first `max` non-empty lines of `path` (BOM stripped, invalid UTF-8 replaced).
+fn read_lines(path: &Path, max: usize) -> Result<Vec<String>> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    while lines.len() < max {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\r', '\n']);
+        let line = if lines.is_empty() {
+            line.trim_start_matches('\u{feff}')
+        } else {
+            line
+        };
+        if !line.trim().is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    Ok(lines)
+}
+
+fn header_names(header: &str, delimiter: char) -> Vec<String> {
+    split_record(header, delimiter)
+        .iter()
+        .enumerate()
+        .map(|(i, h)| match h.trim() {
+            "" => format!("Column{}", i + 1),
+            name => name.to_string(),
+        })
+        .collect()
+}
+
+fn is_datetime(v: &str) -> bool {
+    const FORMATS: [&str; 5] = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+        "%m/%d/%Y %I:%M:%S %p",
+    ];
+    chrono::DateTime::parse_from_rfc3339(v).is_ok()
+        || FORMATS
+            .iter()
+            .any(|f| chrono::NaiveDateTime::parse_from_str(v, f).is_ok())
+}
+
+fn is_date(v: &str) -> bool {
+    ["%Y-%m-%d", "%m/%d/%Y"]
+        .iter()
+        .any(|f| chrono::NaiveDate::parse_from_str(v, f).is_ok())
+}
+
+/// SQL-style type name for a column from its sample values (empty cells ignored), so the
+/// auto-map type checks work as they do for database sources. Values with a leading zero stay
+/// text (ZIP codes, padded incident numbers).
+fn infer_type(values: &[&str]) -> &'static str {
+    let values: Vec<&str> = values.iter().copied().filter(|v| !v.is_empty()).collect();
+    if values.is_empty() {
+        return "nvarchar";
+    }
+    let padded = |v: &str| v.len() > 1 && v.starts_with('0') && !v.starts_with("0.");
+    if values
+        .iter()
+        .all(|v| v.parse::<i64>().is_ok() && !padded(v))
+    {
+        "bigint"
+    } else if values
+        .iter()
+        .all(|v| v.parse::<f64>().is_ok() && !padded(v))
+    {
+        "decimal"
+    } else if values.iter().all(|v| is_datetime(v)) {
+        "datetime"
+    } else if values.iter().all(|v| is_date(v)) {
+        "date"
+    } else {
+        "nvarchar"
+    }
+}
+
+/// Scan the newest export in `folder`: columns with up to `sample_limit` sample values each.
+pub fn discover(
+    folder: &Path,
+    delimiter: Option<char>,
+    sample_limit: usize,
+) -> Result<FileDiscovery> {
+    let files = list_exports(folder)?;
+    let Some(newest) = files.first() else {
+        anyhow::bail!(
+            "No export files (.{}) found in {}.",
+            EXPORT_EXTENSIONS.join(", ."),
+            folder.display()
+        );
+    };
+    let lines = read_lines(newest, sample_limit + 1)?;
+    let Some(header) = lines.first() else {
+        anyhow::bail!("{} is empty; no header row to read.", newest.display());
+    };
+    let delimiter = delimiter.unwrap_or_else(|| detect_delimiter(newest, header));
+    let names = header_names(header, delimiter);
+    let rows: Vec<Vec<String>> = lines[1..]
+        .iter()
+        .map(|l| {
+            split_record(l, delimiter)
+                .into_iter()
+                .map(|v| v.trim().to_string())
+                .collect()
+        })
+        .collect();
+
+    let columns = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let cells: Vec<&str> = rows
+                .iter()
+                .map(|r| r.get(i).map(String::as_str).unwrap_or(""))
+                .collect();
+            DiscoveredColumnDto {
+                name,
+                data_type: infer_type(&cells).to_string(),
+                is_nullable: cells.iter().any(|v| v.is_empty()),
+                sample_values: cells
+                    .iter()
+                    .map(|v| if v.is_empty() { "NULL" } else { v }.to_string())
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let expected: Vec<String> = columns
+        .iter()
+        .map(|c| c.name.to_ascii_lowercase())
+        .collect();
+    let mut mismatched = Vec::new();
+    for other in files.iter().skip(1).take(HEADER_CHECK_FILES - 1) {
+        let same = read_lines(other, 1)?.first().is_some_and(|h| {
+            let names: Vec<String> = header_names(h, delimiter)
+                .iter()
+                .map(|n| n.to_ascii_lowercase())
+                .collect();
+            names == expected
+        });
+        if !same {
+            mismatched.push(other.clone());
+        }
+    }
+
+    Ok(FileDiscovery {
+        file: newest.clone(),
+        files_found: files.len(),
+        delimiter,
+        sample_count: rows.len(),
+        columns,
+        mismatched,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_reads_the_newest_export_and_flags_other_layouts() {
+        let dir = std::env::temp_dir().join(format!("file-source-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, text: &str, age_secs: u64| {
+            let path = dir.join(name);
+            std::fs::write(&path, text).unwrap();
+            let mtime = std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        };
+        write(
+            "calls-0102.csv",
+            "\u{feff}IncidentNumber;CallReceivedAt;Zip;Units\r\n\
+             F26-1;2026-01-02 08:00:00;02134;3\r\n\
+             F26-2;2026-01-02 09:30:00;;12\r\n",
+            0,
+        );
+        write(
+            "calls-0101.csv",
+            "IncidentNumber;CallReceivedAt;Zip;Units\n",
+            60,
+        );
+        write("calls-old.csv", "Incident,Received\n", 120);
+        write("notes.md", "not an export", 0);
+
+        let found = discover(&dir, None, 10).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(found.files_found, 3);
+        assert!(found.file.ends_with("calls-0102.csv"));
+        assert_eq!(found.delimiter, ';');
+        assert_eq!(found.sample_count, 2);
+        let summary: Vec<(&str, &str, bool)> = found
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.as_str(), c.is_nullable))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("IncidentNumber", "nvarchar", false),
+                ("CallReceivedAt", "datetime", false),
+                ("Zip", "nvarchar", true),
+                ("Units", "bigint", false),
+            ]
+        );
+        assert_eq!(found.columns[2].sample_values, vec!["02134", "NULL"]);
+        assert_eq!(found.mismatched.len(), 1);
+        assert!(found.mismatched[0].ends_with("calls-old.csv"));
+
+        assert_eq!(parse_delimiter("Tab").unwrap(), Some('\t'));
+        assert!(parse_delimiter("colon").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+//! Call data sources other than a SQL Server table or view.
+//! - `file`: a folder of CSV / TSV exports; header discovery for the Mapping page.
+//! - `odbc`: any source reachable through an ODBC driver (DSN or driver connection string).
+//! - `oracle`: Oracle tables and views through the Oracle ODBC driver (cargo feature `oracle`).
+
+pub mod file;
+pub mod odbc;
+#[cfg(feature = "oracle")]
+pub mod oracle;
@@ -0,0 +1,740 @@
+//! ODBC call data sources, for CAD systems that only ship a proprietary ODBC driver.
+//!
+//! The driver manager is loaded at runtime (`odbc32.dll`, unixODBC's `libodbc.so.2` or iODBC
+//! on macOS) and only the handful of ODBC 3 calls a header scan needs are bound. `scan`
+//! connects with a DSN / driver connection string, lists the source object's columns through
+//! the catalog (`SQLColumns`) and reads a few rows of `SELECT *` for the Mapping page preview;
+//! `list_objects` lists the tables and views (`SQLTables`) for the source object picker.
+//! Everything here blocks; callers run it on a blocking thread.
+
+use anyhow::{Context, Result};
+use std::ffi::c_void;
+
+use crate::models::responses::{DiscoveredColumnDto, SourceObjectDto};
+use crate::utils::validation::{validate_and_quote_odbc_object, validate_object_parts};
+
+type SqlHandle = *mut c_void;
+type SqlReturn = i16;
+
+const SQL_HANDLE_ENV: i16 = 1;
+const SQL_HANDLE_DBC: i16 = 2;
+const SQL_HANDLE_STMT: i16 = 3;
+const SQL_ATTR_ODBC_VERSION: i32 = 200;
+const SQL_OV_ODBC3: usize = 3;
+const SQL_ATTR_MAX_ROWS: i32 = 1;
+const SQL_DRIVER_NOPROMPT: u16 = 0;
+const SQL_NTS: i16 = -3;
+const SQL_NO_DATA: SqlReturn = 100;
+const SQL_CLOSE: u16 = 0;
+const SQL_C_CHAR: i16 = 1;
+const SQL_NULL_DATA: isize = -1;
+const SQL_DBMS_NAME: u16 = 17;
+const SQL_IDENTIFIER_QUOTE_CHAR: u16 = 29;
+/// Bytes read per cell; longer values are cut (the preview clips them anyway).
+const CELL_BUF: usize = 512;
+
+#[cfg(windows)]
+const DRIVER_MANAGERS: &[&str] = &["odbc32.dll"];
+#[cfg(target_os = "macos")]
+const DRIVER_MANAGERS: &[&str] = &["libiodbc.2.dylib", "libodbc.2.dylib"];
+#[cfg(not(any(windows, target_os = "macos")))]
+const DRIVER_MANAGERS: &[&str] = &["libodbc.so.2", "libodbc.so.1", "libodbc.so"];
+
+type AllocHandleFn = unsafe extern "system" fn(i16, SqlHandle, *mut SqlHandle) -> SqlReturn;
+type FreeHandleFn = unsafe extern "system" fn(i16, SqlHandle) -> SqlReturn;
+type SetEnvAttrFn = unsafe extern "system" fn(SqlHandle, i32, *mut c_void, i32) -> SqlReturn;
+type DriverConnectFn = unsafe extern "system" fn(
+    SqlHandle,
+    *mut c_void,
+    *const u8,
+    i16,
+    *mut u8,
+    i16,
+    *mut i16,
+    u16,
+) -> SqlReturn;
+type DisconnectFn = unsafe extern "system" fn(SqlHandle) -> SqlReturn;
+type GetInfoFn = unsafe extern "system" fn(SqlHandle, u16, *mut c_void, i16, *mut i16) -> SqlReturn;
+type ColumnsFn = unsafe extern "system" fn(
+    SqlHandle,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+) -> SqlReturn;
+type TablesFn = unsafe extern "system" fn(
+    SqlHandle,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+    *const u8,
+    i16,
+) -> SqlReturn;
+type SetStmtAttrFn = unsafe extern "system" fn(SqlHandle, i32, *mut c_void, i32) -> SqlReturn;
+type ExecDirectFn = unsafe extern "system" fn(SqlHandle, *const u8, i32) -> SqlReturn;
+type NumResultColsFn = unsafe extern "system" fn(SqlHandle, *mut i16) -> SqlReturn;
+type DescribeColFn = unsafe extern "system" fn(
+    SqlHandle,
+    u16,
+    *mut u8,
+    i16,
+    *mut i16,
+    *mut i16,
+    *mut usize,
+    *mut i16,
+    *mut i16,
+) -> SqlReturn;
+type FetchFn = unsafe extern "system" fn(SqlHandle) -> SqlReturn;
+type GetDataFn =
+    unsafe extern "system" fn(SqlHandle, u16, i16, *mut c_void, isize, *mut isize) -> SqlReturn;
+type FreeStmtFn = unsafe extern "system" fn(SqlHandle, u16) -> SqlReturn;
+type GetDiagRecFn = unsafe extern "system" fn(
+    i16,
+    SqlHandle,
+    i16,
+    *mut u8,
+    *mut i32,
+    *mut u8,
+    i16,
+    *mut i16,
+) -> SqlReturn;
+
+fn succeeded(ret: SqlReturn) -> bool {
+    ret == 0 || ret == 1
+}
+
+/// The bound driver manager entry points; the library stays loaded while this lives.
+struct Api {
+    alloc_handle: AllocHandleFn,
+    free_handle: FreeHandleFn,
+    set_env_attr: SetEnvAttrFn,
+    driver_connect: DriverConnectFn,
+    disconnect: DisconnectFn,
+    get_info: GetInfoFn,
+    columns: ColumnsFn,
+    tables: TablesFn,
+    set_stmt_attr: SetStmtAttrFn,
+    exec_direct: ExecDirectFn,
+    num_result_cols: NumResultColsFn,
+    describe_col: DescribeColFn,
+    fetch: FetchFn,
+    get_data: GetDataFn,
+    free_stmt: FreeStmtFn,
+    get_diag_rec: GetDiagRecFn,
+    _lib: libloading::Library,
+}
+
+impl Api {
+    fn load() -> Result<Self> {
+        let mut last_err = None;
+        for name in DRIVER_MANAGERS {
+            // SAFETY: loading the system ODBC driver manager runs no initialisers we depend on.
+            match unsafe { libloading::Library::new(name) } {
+                Ok(lib) => {
+                    return Self::bind(lib).with_context(|| format!("ODBC driver manager {}", name))
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(anyhow::anyhow!(
+            "No ODBC driver manager found (tried {}): {}. Install unixODBC (or the vendor's driver manager) and the CAD system's ODBC driver.",
+            DRIVER_MANAGERS.join(", "),
+            last_err.map(|e| e.to_string()).unwrap_or_default()
+        ))
+    }
+
+    fn bind(lib: libloading::Library) -> Result<Self> {
+        // SAFETY: the signatures match the ODBC 3 C API (sql.h / sqlext.h).
+        unsafe {
+            Ok(Self {
+                alloc_handle: *lib.get(b"SQLAllocHandle\0")?,
+                free_handle: *lib.get(b"SQLFreeHandle\0")?,
+                set_env_attr: *lib.get(b"SQLSetEnvAttr\0")?,
+                driver_connect: *lib.get(b"SQLDriverConnect\0")?,
+                disconnect: *lib.get(b"SQLDisconnect\0")?,
+                get_info: *lib.get(b"SQLGetInfo\0")?,
+                columns: *lib.get(b"SQLColumns\0")?,
+                tables: *lib.get(b"SQLTables\0")?,
+                set_stmt_attr: *lib.get(b"SQLSetStmtAttr\0")?,
+                exec_direct: *lib.get(b"SQLExecDirect\0")?,
+                num_result_cols: *lib.get(b"SQLNumResultCols\0")?,
+                describe_col: *lib.get(b"SQLDescribeCol\0")?,
+                fetch: *lib.get(b"SQLFetch\0")?,
+                get_data: *lib.get(b"SQLGetData\0")?,
+                free_stmt: *lib.get(b"SQLFreeStmt\0")?,
+                get_diag_rec: *lib.get(b"SQLGetDiagRec\0")?,
+                _lib: lib,
+            })
+        }
+    }
+}
+
+/// An ODBC handle freed (and, for connections, disconnected) on drop.
+struct Handle<'a> {
+    api: &'a Api,
+    kind: i16,
+    raw: SqlHandle,
+    connected: bool,
+}
+
+impl<'a> Handle<'a> {
+    fn alloc(api: &'a Api, kind: i16, parent: SqlHandle) -> Result<Self> {
+        let mut raw: SqlHandle = std::ptr::null_mut();
+        // SAFETY: `raw` is a valid out pointer; `parent` is null or a live handle.
+        let ret = unsafe { (api.alloc_handle)(kind, parent, &mut raw) };
+        if !succeeded(ret) || raw.is_null() {
+            anyhow::bail!("SQLAllocHandle({}) failed (rc={})", kind, ret);
+        }
+        Ok(Self {
+            api,
+            kind,
+            raw,
+            connected: false,
+        })
+    }
+
+    /// `Err` with the handle's first diagnostic record when `ret` is not a success code.
+    fn check(&self, ret: SqlReturn, what: &str) -> Result<()> {
+        if succeeded(ret) {
+            return Ok(());
+        }
+        let mut state = [0u8; 6];
+        let mut native = 0i32;
+        let mut msg = [0u8; 1024];
+        let mut len = 0i16;
+        // SAFETY: buffers are sized as passed.
+        let diag = unsafe {
+            (self.api.get_diag_rec)(
+                self.kind,
+                self.raw,
+                1,
+                state.as_mut_ptr(),
+                &mut native,
+                msg.as_mut_ptr(),
+                msg.len() as i16,
+                &mut len,
+            )
+        };
+        if succeeded(diag) {
+            let len = (len.max(0) as usize).min(msg.len() - 1);
+            anyhow::bail!(
+                "{} failed: [{}] {}",
+                what,
+                String::from_utf8_lossy(&state[..5]),
+                String::from_utf8_lossy(&msg[..len]).trim()
+            );
+        }
+        anyhow::bail!("{} failed (rc={})", what, ret)
+    }
+
+    fn info_string(&self, info: u16) -> String {
+        let mut buf = [0u8; 256];
+        let mut len = 0i16;
+        // SAFETY: buffer is sized as passed.
+        let ret = unsafe {
+            (self.api.get_info)(
+                self.raw,
+                info,
+                buf.as_mut_ptr().cast(),
+                buf.len() as i16,
+                &mut len,
+            )
+        };
+        if !succeeded(ret) {
+            return String::new();
+        }
+        let len = (len.max(0) as usize).min(buf.len() - 1);
+        String::from_utf8_lossy(&buf[..len]).to_string()
+    }
+
+    fn fetch(&self, what: &str) -> Result<bool> {
+        // SAFETY: live statement handle.
+        let ret = unsafe { (self.api.fetch)(self.raw) };
+        if ret == SQL_NO_DATA {
+            return Ok(false);
+        }
+        self.check(ret, what)?;
+        Ok(true)
+    }
+
+    /// Column `col` (1-based) of the current row as text; `None` for NULL.
+    fn text(&self, col: u16) -> Result<Option<String>> {
+        let mut buf = [0u8; CELL_BUF];
+        let mut ind: isize = 0;
+        // SAFETY: buffer is sized as passed; `ind` is a valid out pointer.
+        let ret = unsafe {
+            (self.api.get_data)(
+                self.raw,
+                col,
+                SQL_C_CHAR,
+                buf.as_mut_ptr().cast(),
+                buf.len() as isize,
+                &mut ind,
+            )
+        };
+        self.check(ret, "SQLGetData")?;
+        if ind == SQL_NULL_DATA {
+            return Ok(None);
+        }
+        // Truncated values report the full length (or SQL_NO_TOTAL); keep what fits.
+        let len = if ind < 0 {
+            buf.len() - 1
+        } else {
+            (ind as usize).min(buf.len() - 1)
+        };
+        Ok(Some(String::from_utf8_lossy(&buf[..len]).to_string()))
+    }
+}
+
+impl Drop for Handle<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `raw` is live until SQLFreeHandle here.
+        unsafe {
+            if self.connected {
+                (self.api.disconnect)(self.raw);
+            }
+            (self.api.free_handle)(self.kind, self.raw);
+        }
+    }
+}
+
+/// Type name for an ODBC SQL type code (`SQLDescribeCol`), matching the SQL Server spelling so
+/// auto-map type checks treat both sources alike.
+fn sql_type_name(code: i16) -> &'static str {
+    match code {
+        1 => "char",
+        12 => "varchar",
+        -1 => "text",
+        -8 => "nchar",
+        -9 => "nvarchar",
+        -10 => "ntext",
+        2 => "numeric",
+        3 => "decimal",
+        4 => "int",
+        5 => "smallint",
+        -5 => "bigint",
+        -6 => "tinyint",
+        6 => "float",
+        7 => "real",
+        8 => "double",
+        -7 => "bit",
+        9 | 91 => "date",
+        10 | 92 => "time",
+        11 | 93 => "datetime",
+        -4..=-2 => "varbinary",
+        -11 => "uniqueidentifier",
+        _ => "",
+    }
+}
+
+/// A connected ODBC connection; the connection handle is dropped (disconnected) before its
+/// environment.
+struct Connection<'a> {
+    dbc: Handle<'a>,
+    _env: Handle<'a>,
+}
+
+impl<'a> Connection<'a> {
+    fn open(api: &'a Api, conn_str: &str) -> Result<Self> {
+        let env = Handle::alloc(api, SQL_HANDLE_ENV, std::ptr::null_mut())?;
+        // SAFETY: integer attributes are passed by value in the pointer argument.
+        let ret = unsafe {
+            (api.set_env_attr)(
+                env.raw,
+                SQL_ATTR_ODBC_VERSION,
+                SQL_OV_ODBC3 as *mut c_void,
+                0,
+            )
+        };
+        env.check(ret, "SQLSetEnvAttr")?;
+
+        let mut dbc = Handle::alloc(api, SQL_HANDLE_DBC, env.raw)?;
+        let conn = std::ffi::CString::new(conn_str).context("Connection string contains a NUL")?;
+        let mut out_len = 0i16;
+        // SAFETY: `conn` is NUL-terminated (SQL_NTS); no output buffer is requested.
+        let ret = unsafe {
+            (api.driver_connect)(
+                dbc.raw,
+                std::ptr::null_mut(),
+                conn.as_ptr().cast(),
+                SQL_NTS,
+                std::ptr::null_mut(),
+                0,
+                &mut out_len,
+                SQL_DRIVER_NOPROMPT,
+            )
+        };
+        dbc.check(ret, "SQLDriverConnect")?;
+        dbc.connected = true;
+        Ok(Self { dbc, _env: env })
+    }
+}
+
+/// Result of an ODBC header scan.
+#[derive(Debug, Clone, Default)]
+pub struct OdbcScan {
+    /// `SQL_DBMS_NAME` reported by the driver.
+    pub dbms_name: String,
+    /// Columns from the ODBC catalog, in ordinal order (no sample values).
+    pub catalog_columns: Vec<DiscoveredColumnDto>,
+    /// Result columns of the sample query with their values ("NULL" for nulls).
+    pub sample_columns: Vec<DiscoveredColumnDto>,
+    pub sample_count: usize,
+    /// Why the sample query failed, when it did (the scan still returns the catalog).
+    pub sample_error: Option<String>,
+}
+
+/// Connect with `conn_str`, read the catalog columns of `object_name` and up to
+/// `sample_limit` rows. Connection failures are errors; a failed catalog lookup or sample
+/// query is not, as long as one of them yields columns.
+pub fn scan(conn_str: &str, object_name: &str, sample_limit: usize) -> Result<OdbcScan> {
+    let parts = validate_object_parts(object_name)?;
+    scan_with(conn_str, Catalog::Driver(&parts), sample_limit, |quote| {
+        Ok(format!(
+            "SELECT * FROM {}",
+            validate_and_quote_odbc_object(&parts.join("."), quote)?
+        ))
+    })
+}
+
+/// Where [`scan_with`] reads the column list from.
+pub(crate) enum Catalog<'a> {
+    /// `SQLColumns` for the object's name parts.
+    Driver(&'a [String]),
+    /// A query returning name, type and nullable ("Y" / "N" or 1 / 0) per column, in order.
+    #[cfg_attr(not(feature = "oracle"), allow(dead_code))]
+    Query(&'a str),
+}
+
+/// [`scan`] with the catalog and the sample query chosen by the caller, for databases whose
+/// drivers need their own dialect. `sample_sql` gets the driver's identifier quote.
+pub(crate) fn scan_with(
+    conn_str: &str,
+    catalog: Catalog<'_>,
+    sample_limit: usize,
+    sample_sql: impl FnOnce(&str) -> Result<String>,
+) -> Result<OdbcScan> {
+    let api = Api::load()?;
+    let conn = Connection::open(&api, conn_str)?;
+    let dbc = &conn.dbc;
+
+    let mut out = OdbcScan {
+        dbms_name: dbc.info_string(SQL_DBMS_NAME),
+        ..OdbcScan::default()
+    };
+    let quote = dbc.info_string(SQL_IDENTIFIER_QUOTE_CHAR);
+    let stmt = Handle::alloc(&api, SQL_HANDLE_STMT, dbc.raw)?;
+
+    // A driver without catalog support still gets the sample query's result columns.
+    out.catalog_columns = match catalog {
+        Catalog::Driver(parts) => driver_catalog(&api, &stmt, parts),
+        Catalog::Query(sql) => query_catalog(&api, &stmt, sql),
+    }
+    .unwrap_or_default();
+    // SAFETY: live statement handle.
+    unsafe { (api.free_stmt)(stmt.raw, SQL_CLOSE) };
+
+    let sampled =
+        sample_sql(&quote).and_then(|sql| sample(&api, &stmt, &sql, sample_limit, &mut out));
+    if let Err(e) = sampled {
+        out.sample_error = Some(format!("{:#}", e));
+    }
+    Ok(out)
+}
+
+/// Tables and views the connection can see (`SQLTables`), at most `limit`. ODBC reports no row
+/// counts, so `row_count` stays `None`.
+pub fn list_objects(conn_str: &str, limit: usize) -> Result<Vec<SourceObjectDto>> {
+    let api = Api::load()?;
+    let conn = Connection::open(&api, conn_str)?;
+    let stmt = Handle::alloc(&api, SQL_HANDLE_STMT, conn.dbc.raw)?;
+    let types = b"TABLE,VIEW\0";
+    // SAFETY: null patterns match everything; `types` is NUL-terminated (SQL_NTS).
+    let ret = unsafe {
+        (api.tables)(
+            stmt.raw,
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            0,
+            types.as_ptr().cast(),
+            SQL_NTS,
+        )
+    };
+    stmt.check(ret, "SQLTables")?;
+
+    // Result set: 2 TABLE_SCHEM, 3 TABLE_NAME, 4 TABLE_TYPE.
+    let mut out = Vec::new();
+    while out.len() < limit && stmt.fetch("SQLTables fetch")? {
+        out.push(SourceObjectDto {
+            schema: stmt.text(2)?.unwrap_or_default(),
+            name: stmt.text(3)?.unwrap_or_default(),
+            kind: object_kind(&stmt.text(4)?.unwrap_or_default()).to_string(),
+            row_count: None,
+        });
+    }
+    Ok(out)
+}
+
+/// Objects from a dialect-specific query returning schema, name, type ("TABLE" / "VIEW") and
+/// row count (or NULL) per object, at most `limit`.
+#[cfg_attr(not(feature = "oracle"), allow(dead_code))]
+pub(crate) fn query_objects(
+    conn_str: &str,
+    sql: &str,
+    limit: usize,
+) -> Result<Vec<SourceObjectDto>> {
+    let api = Api::load()?;
+    let conn = Connection::open(&api, conn_str)?;
+    let stmt = Handle::alloc(&api, SQL_HANDLE_STMT, conn.dbc.raw)?;
+    // SAFETY: `sql` outlives the call; its length is passed explicitly.
+    let ret = unsafe { (api.exec_direct)(stmt.raw, sql.as_ptr(), sql.len() as i32) };
+    stmt.check(ret, "Object list query")?;
+    let mut out = Vec::new();
+    while out.len() < limit && stmt.fetch("Object list query fetch")? {
+        out.push(SourceObjectDto {
+            schema: stmt.text(1)?.unwrap_or_default(),
+            name: stmt.text(2)?.unwrap_or_default(),
+            kind: object_kind(&stmt.text(3)?.unwrap_or_default()).to_string(),
+            row_count: stmt.text(4)?.and_then(|n| n.trim().parse().ok()),
+        });
+    }
+    Ok(out)
+}
+
+/// Rows per calendar month of `column` in `object_name`, optionally limited to `[from, to)`:
+/// (year, month, rows). Uses the ODBC `{fn YEAR()}` / `{fn MONTH()}` scalar functions and
+/// `{ts}` literals, which drivers translate to their own dialect.
+pub fn month_counts(
+    conn_str: &str,
+    object_name: &str,
+    column: &str,
+    from: Option<chrono::NaiveDateTime>,
+    to: Option<chrono::NaiveDateTime>,
+) -> Result<Vec<(i32, u32, u64)>> {
+    if validate_object_parts(column)?.len() != 1 {
+        anyhow::bail!("The watermark column must be a single column name");
+    }
+    let api = Api::load()?;
+    let conn = Connection::open(&api, conn_str)?;
+    let quote = conn.dbc.info_string(SQL_IDENTIFIER_QUOTE_CHAR);
+    let object = validate_and_quote_odbc_object(object_name, &quote)?;
+    let col = validate_and_quote_odbc_object(column, &quote)?;
+    let ts = |t: chrono::NaiveDateTime| format!("{{ts '{}'}}", t.format("%Y-%m-%d %H:%M:%S"));
+    let bounds: Vec<String> = [
+        from.map(|t| format!("{} >= {}", col, ts(t))),
+        to.map(|t| format!("{} < {}", col, ts(t))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let filter = if bounds.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", bounds.join(" AND "))
+    };
+    let sql = format!(
+        "SELECT {{fn YEAR({c})}}, {{fn MONTH({c})}}, COUNT(*) FROM {o}{f} \
+         GROUP BY {{fn YEAR({c})}}, {{fn MONTH({c})}}",
+        c = col,
+        o = object,
+        f = filter
+    );
+
+    let stmt = Handle::alloc(&api, SQL_HANDLE_STMT, conn.dbc.raw)?;
+    // SAFETY: `sql` outlives the call; its length is passed explicitly.
+    let ret = unsafe { (api.exec_direct)(stmt.raw, sql.as_ptr(), sql.len() as i32) };
+    stmt.check(ret, "Month count query")?;
+    let mut out = Vec::new();
+    while stmt.fetch("Month count query fetch")? {
+        let num = |v: Option<String>| v.and_then(|v| v.trim().parse::<f64>().ok());
+        // NULL watermarks form their own group; they cannot be placed in a month.
+        if let (Some(year), Some(month), Some(rows)) =
+            (num(stmt.text(1)?), num(stmt.text(2)?), num(stmt.text(3)?))
+        {
+            out.push((year as i32, month as u32, rows as u64));
+        }
+    }
+    Ok(out)
+}
+
+/// "view" for catalog types naming a view, otherwise "table".
+fn object_kind(table_type: &str) -> &'static str {
+    if table_type.to_ascii_uppercase().contains("VIEW") {
+        "view"
+    } else {
+        "table"
+    }
+}
+
+/// Columns of the object from `SQLColumns`. Its patterns treat `_` as a wildcard, so rows are
+/// filtered on the exact table name.
+fn driver_catalog(
+    api: &Api,
+    stmt: &Handle<'_>,
+    parts: &[String],
+) -> Result<Vec<DiscoveredColumnDto>> {
+    use std::ffi::CString;
+
+    // [catalog.][schema.]table
+    let table = parts.last().cloned().unwrap_or_default();
+    let schema = (parts.len() >= 2).then(|| CString::new(parts[parts.len() - 2].as_str()));
+    let catalog = (parts.len() == 3).then(|| CString::new(parts[0].as_str()));
+    let (schema, catalog) = (schema.transpose()?, catalog.transpose()?);
+    let table_c = CString::new(table.as_str())?;
+    let ptr = |s: &Option<CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr().cast());
+    // SAFETY: strings are NUL-terminated (SQL_NTS) or null.
+    let ret = unsafe {
+        (api.columns)(
+            stmt.raw,
+            ptr(&catalog),
+            SQL_NTS,
+            ptr(&schema),
+            SQL_NTS,
+            table_c.as_ptr().cast(),
+            SQL_NTS,
+            std::ptr::null(),
+            0,
+        )
+    };
+    stmt.check(ret, "SQLColumns")?;
+
+    // Result set: 3 TABLE_NAME, 4 COLUMN_NAME, 6 TYPE_NAME, 11 NULLABLE.
+    let mut out = Vec::new();
+    while stmt.fetch("SQLColumns fetch")? {
+        let same_table = stmt
+            .text(3)?
+            .is_some_and(|t| t.eq_ignore_ascii_case(&table));
+        if !same_table {
+            continue;
+        }
+        out.push(DiscoveredColumnDto {
+            name: stmt.text(4)?.unwrap_or_default(),
+            data_type: stmt.text(6)?.unwrap_or_default().to_ascii_lowercase(),
+            is_nullable: stmt.text(11)?.as_deref() != Some("0"),
+            sample_values: Vec::new(),
+        });
+    }
+    Ok(out)
+}
+
+/// Columns from a dialect-specific catalog query ([`Catalog::Query`]).
+fn query_catalog(api: &Api, stmt: &Handle<'_>, sql: &str) -> Result<Vec<DiscoveredColumnDto>> {
+    // SAFETY: `sql` outlives the call; its length is passed explicitly.
+    let ret = unsafe { (api.exec_direct)(stmt.raw, sql.as_ptr(), sql.len() as i32) };
+    stmt.check(ret, "Catalog query")?;
+    let mut out = Vec::new();
+    while stmt.fetch("Catalog query fetch")? {
+        let nullable = stmt.text(3)?.unwrap_or_default();
+        out.push(DiscoveredColumnDto {
+            name: stmt.text(1)?.unwrap_or_default(),
+            data_type: stmt.text(2)?.unwrap_or_default().to_ascii_lowercase(),
+            is_nullable: !matches!(nullable.trim(), "N" | "0"),
+            sample_values: Vec::new(),
+        });
+    }
+    Ok(out)
+}
+
+/// `sql` capped with `SQL_ATTR_MAX_ROWS`, since row-limit syntax differs per DBMS.
+fn sample(
+    api: &Api,
+    stmt: &Handle<'_>,
+    sql: &str,
+    sample_limit: usize,
+    out: &mut OdbcScan,
+) -> Result<()> {
+    // SAFETY: integer attributes are passed by value in the pointer argument.
+    let ret =
+        unsafe { (api.set_stmt_attr)(stmt.raw, SQL_ATTR_MAX_ROWS, sample_limit as *mut c_void, 0) };
+    stmt.check(ret, "SQLSetStmtAttr")?;
+    // SAFETY: `sql` outlives the call; its length is passed explicitly.
+    let ret = unsafe { (api.exec_direct)(stmt.raw, sql.as_ptr(), sql.len() as i32) };
+    stmt.check(ret, "Sample query")?;
+
+    let mut cols = 0i16;
+    // SAFETY: `cols` is a valid out pointer.
+    stmt.check(
+        unsafe { (api.num_result_cols)(stmt.raw, &mut cols) },
+        "SQLNumResultCols",
+    )?;
+    for col in 1..=cols.max(0) as u16 {
+        let mut name = [0u8; 256];
+        let (mut name_len, mut data_type, mut decimals, mut nullable) = (0i16, 0i16, 0i16, 0i16);
+        let mut size = 0usize;
+        // SAFETY: buffers and out pointers are sized as passed.
+        let ret = unsafe {
+            (api.describe_col)(
+                stmt.raw,
+                col,
+                name.as_mut_ptr(),
+                name.len() as i16,
+                &mut name_len,
+                &mut data_type,
+                &mut size,
+                &mut decimals,
+                &mut nullable,
+            )
+        };
+        stmt.check(ret, "SQLDescribeCol")?;
+        let len = (name_len.max(0) as usize).min(name.len() - 1);
+        out.sample_columns.push(DiscoveredColumnDto {
+            name: String::from_utf8_lossy(&name[..len]).to_string(),
+            data_type: sql_type_name(data_type).to_string(),
+            is_nullable: nullable != 0,
+            sample_values: Vec::new(),
+        });
+    }
+
+    while out.sample_count < sample_limit && stmt.fetch("Sample query fetch")? {
+        for (i, column) in out.sample_columns.iter_mut().enumerate() {
+            let value = stmt.text(i as u16 + 1)?;
+            column
+                .sample_values
+                .push(value.unwrap_or_else(|| "NULL".to_string()));
+        }
+        out.sample_count += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odbc_objects_are_quoted_per_driver_and_types_named_like_sql_server() {
+        assert_eq!(
+            validate_and_quote_odbc_object("cad.CALLS", "\"").unwrap(),
+            "\"cad\".\"CALLS\""
+        );
+        assert_eq!(
+            validate_and_quote_odbc_object("[cad].CALLS", "`").unwrap(),
+            "`cad`.`CALLS`"
+        );
+        assert_eq!(
+            validate_and_quote_odbc_object("CALLS", " ").unwrap(),
+            "CALLS"
+        );
+        assert!(validate_and_quote_odbc_object("CALLS; DROP TABLE x", "\"").is_err());
+        assert!(validate_and_quote_odbc_object("a.b.c.d", "\"").is_err());
+
+        assert_eq!(sql_type_name(93), "datetime");
+        assert_eq!(sql_type_name(-9), "nvarchar");
+        assert_eq!(sql_type_name(-5), "bigint");
+        assert_eq!(sql_type_name(-150), "");
+
+        assert_eq!(object_kind("VIEW"), "view");
+        assert_eq!(object_kind("MATERIALIZED VIEW"), "view");
+        assert_eq!(object_kind("TABLE"), "table");
+    }
+}
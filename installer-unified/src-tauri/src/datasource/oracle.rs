@@ -0,0 +1,170 @@
+//! Oracle call data sources, read through the Oracle ODBC driver (Instant Client ODBC).
+//!
+//! The wizard collects the driver name, an Easy Connect address (`host[:port]/service`, or a
+//! TNS alias) and credentials; `connection_string` turns them into an ODBC connection string.
+//! `scan` reuses the ODBC scan with Oracle's dialect: columns come from `ALL_TAB_COLUMNS` and
+//! the sample is capped with `ROWNUM`; `list_objects` reads `ALL_TABLES` / `ALL_VIEWS` for the
+//! source object picker. Everything here blocks; callers run it on a blocking thread.
+
+use anyhow::Result;
+
+use crate::datasource::odbc::{self, Catalog, OdbcScan};
+use crate::models::responses::SourceObjectDto;
+use crate::utils::validation::validate_object_parts;
+
+/// Driver name the Instant Client ODBC package registers (`odbc_update_ini.sh`).
+pub const DEFAULT_DRIVER: &str = "Oracle 21 ODBC driver";
+/// Listener port used when the address has none.
+pub const DEFAULT_PORT: u16 = 1521;
+
+/// ODBC connection string for the Oracle driver. `address` is Easy Connect
+/// (`host[:port]/service`, port defaulting to 1521) or a TNS alias.
+pub fn connection_string(
+    driver: &str,
+    address: &str,
+    user: &str,
+    password: &str,
+) -> Result<String> {
+    let driver = driver.trim();
+    let address = address.trim();
+    if address.is_empty() {
+        anyhow::bail!("Oracle address is required (host[:port]/service or a TNS alias)");
+    }
+    let dbq = match address.split_once('/') {
+        Some((host, service)) => {
+            if host.is_empty() || service.trim().is_empty() {
+                anyhow::bail!("Oracle address must be host[:port]/service");
+            }
+            if host.contains(':') {
+                address.to_string()
+            } else {
+                format!("{}:{}/{}", host, DEFAULT_PORT, service.trim())
+            }
+        }
+        None => address.to_string(),
+    };
+    Ok(format!(
+        "Driver={};DBQ={};UID={};PWD={};",
+        odbc_value(if driver.is_empty() {
+            DEFAULT_DRIVER
+        } else {
+            driver
+        }),
+        odbc_value(&dbq),
+        odbc_value(user.trim()),
+        odbc_value(password)
+    ))
+}
+
+/// Braces a connection string value (doubling `}`), as ODBC requires for values containing
+/// `;`, `{`, `}` or spaces.
+fn odbc_value(value: &str) -> String {
+    if value.contains([';', '{', '}', ' ']) {
+        format!("{{{}}}", value.replace('}', "}}"))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Connect with `conn_str` and read the columns and up to `sample_limit` rows of
+/// `object_name` (`[owner.]table`; no owner means the session's current schema).
+pub fn scan(conn_str: &str, object_name: &str, sample_limit: usize) -> Result<OdbcScan> {
+    let parts = validate_object_parts(object_name)?;
+    if parts.len() > 2 {
+        anyhow::bail!("Oracle source objects are named [owner.]table");
+    }
+    // Unquoted Oracle identifiers are stored upper-case. Parts are validated to letters,
+    // digits and underscores, so they are safe to inline as literals.
+    let table = parts[parts.len() - 1].to_ascii_uppercase();
+    let owner = match parts.len() {
+        2 => format!("'{}'", parts[0].to_ascii_uppercase()),
+        _ => "SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')".to_string(),
+    };
+    let catalog_sql = format!(
+        "SELECT COLUMN_NAME, DATA_TYPE, NULLABLE FROM ALL_TAB_COLUMNS \
+         WHERE OWNER = {} AND TABLE_NAME = '{}' ORDER BY COLUMN_ID",
+        owner, table
+    );
+    let object = parts.join(".");
+
+    let mut out = odbc::scan_with(conn_str, Catalog::Query(&catalog_sql), sample_limit, |_| {
+        Ok(format!(
+            "SELECT * FROM {} WHERE ROWNUM <= {}",
+            object,
+            sample_limit.max(1)
+        ))
+    })?;
+    for column in &mut out.catalog_columns {
+        column.data_type = oracle_type_name(&column.data_type);
+    }
+    Ok(out)
+}
+
+/// Tables (with their last-analyzed `NUM_ROWS`) and views the user can read, skipping the
+/// schemas Oracle maintains itself; at most `limit`.
+pub fn list_objects(conn_str: &str, limit: usize) -> Result<Vec<SourceObjectDto>> {
+    let sql = format!(
+        "SELECT * FROM (SELECT OWNER, NAME, KIND, NUM_ROWS FROM ( \
+         SELECT OWNER, TABLE_NAME AS NAME, 'TABLE' AS KIND, NUM_ROWS FROM ALL_TABLES \
+         UNION ALL \
+         SELECT OWNER, VIEW_NAME, 'VIEW', NULL FROM ALL_VIEWS) \
+         WHERE OWNER NOT IN (SELECT USERNAME FROM ALL_USERS WHERE ORACLE_MAINTAINED = 'Y') \
+         ORDER BY OWNER, NAME) WHERE ROWNUM <= {}",
+        limit.max(1)
+    );
+    odbc::query_objects(conn_str, &sql, limit)
+}
+
+/// Oracle `DATA_TYPE` in the SQL Server spelling the auto-map type checks know. `DATE`
+/// carries a time of day, so it maps to datetime.
+fn oracle_type_name(data_type: &str) -> String {
+    let t = data_type.trim().to_ascii_lowercase();
+    let base = t.split('(').next().unwrap_or("").trim();
+    let mapped = match base {
+        "varchar2" => "varchar",
+        "nvarchar2" => "nvarchar",
+        "clob" | "long" => "text",
+        "nclob" => "ntext",
+        "number" => "numeric",
+        "binary_float" => "real",
+        "binary_double" => "float",
+        "date" => "datetime",
+        "timestamp" if t.contains("time zone") => "datetimeoffset",
+        "timestamp" => "datetime",
+        "raw" | "long raw" | "blob" => "varbinary",
+        _ => return t,
+    };
+    mapped.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_strings_default_driver_and_port_and_brace_values() {
+        assert_eq!(
+            connection_string("", "cad-db/CADPROD", "reader", "pw").unwrap(),
+            "Driver={Oracle 21 ODBC driver};DBQ=cad-db:1521/CADPROD;UID=reader;PWD=pw;"
+        );
+        assert_eq!(
+            connection_string("OracleODBC", "cad-db:1522/CAD", "reader", "p;w}").unwrap(),
+            "Driver=OracleODBC;DBQ=cad-db:1522/CAD;UID=reader;PWD={p;w}}};"
+        );
+        assert_eq!(
+            connection_string("OracleODBC", "CADPROD", "reader", "pw").unwrap(),
+            "Driver=OracleODBC;DBQ=CADPROD;UID=reader;PWD=pw;"
+        );
+        assert!(connection_string("", " ", "reader", "pw").is_err());
+        assert!(connection_string("", "cad-db/", "reader", "pw").is_err());
+
+        assert_eq!(oracle_type_name("VARCHAR2"), "varchar");
+        assert_eq!(oracle_type_name("TIMESTAMP(6)"), "datetime");
+        assert_eq!(
+            oracle_type_name("TIMESTAMP(6) WITH TIME ZONE"),
+            "datetimeoffset"
+        );
+        assert_eq!(oracle_type_name("SDO_GEOMETRY"), "sdo_geometry");
+        assert!(scan("DSN=x", "a.b.c", 10).is_err());
+    }
+}
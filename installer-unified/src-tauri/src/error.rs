@@ -0,0 +1,358 @@
+//! Stable error codes for installer failures.
+//!
+//! A failure site attaches a code by wrapping its error in an [`InstallerError`] variant
+//! (`.or_code(InstallerError::MigrationFailed)?`). The wrapper is transparent: message and cause
+//! chain read exactly as before, but [`error_code`] recovers the code anywhere further up. Codes
+//! surface as `E2004: <message>` in the GUI and TUI dialogs, as `errorCode` in the JSON log
+//! (`[CODE: E2004]` in the log line), and as the exit code of the headless modes.
+//!
+//! Codes are part of the support contract: never renumber or reuse one.
+//!
+//! | Range | Area                                  |
+//! |-------|---------------------------------------|
+//! | E1xxx | destination folder and deployed files |
+//! | E2xxx | database                              |
+//! | E3xxx | settings and command-line arguments   |
+//! | E4xxx | services and containers               |
+//! | E6xxx | archive                               |
+//! | E9xxx | cancellation, unclassified failures   |
+//!
+//! Exit code = range digit × 10 + last digit (E1001 → 11, E2004 → 24). Failures without a code
+//! keep the historical exit code 1.
+
+use crate::installation::cancel;
+
+/// Failure not attributed to any code (exit code 1).
+pub const CODE_UNCLASSIFIED: u16 = 9999;
+/// Install cancelled by the operator.
+pub const CODE_CANCELLED: u16 = 9001;
+/// Install stopped by a termination signal (SIGINT/SIGTERM); its checkpoint was kept.
+pub const CODE_INTERRUPTED: u16 = 9002;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InstallerError {
+    /// E1001: the destination folder cannot be created or written.
+    #[error(transparent)]
+    DestinationNotWritable(anyhow::Error),
+    /// E1002: not enough free space for the install or the archive staging area.
+    #[error(transparent)]
+    InsufficientDiskSpace(anyhow::Error),
+    /// E1003: runtime payload folders missing or empty.
+    #[error(transparent)]
+    PayloadMissing(anyhow::Error),
+    /// E1004: copying a runtime file failed.
+    #[error(transparent)]
+    FileCopyFailed(anyhow::Error),
+    /// E1005: generating appsettings/compose configuration failed.
+    #[error(transparent)]
+    ConfigWriteFailed(anyhow::Error),
+    /// E1006: writing the install manifest, mapping or config artifacts failed.
+    #[error(transparent)]
+    ArtifactWriteFailed(anyhow::Error),
+    /// E1007: the offline bundle failed signature or checksum verification.
+    #[error(transparent)]
+    OfflineBundleInvalid(anyhow::Error),
+    /// E1008: a runtime payload file does not match the payload manifest.
+    #[error(transparent)]
+    PayloadIntegrityFailed(anyhow::Error),
+    /// E1009: writing or building an MSI/Intune distribution package failed.
+    #[error(transparent)]
+    PackageBuildFailed(anyhow::Error),
+    /// E2001: could not connect to the database server.
+    #[error(transparent)]
+    DatabaseConnectionFailed(anyhow::Error),
+    /// E2002: the login may not create databases.
+    #[error(transparent)]
+    DatabaseCreatePermissionDenied(anyhow::Error),
+    /// E2003: the Create NEW database name is already taken.
+    #[error(transparent)]
+    DatabaseAlreadyExists(anyhow::Error),
+    /// E2004: a schema migration failed.
+    #[error(transparent)]
+    MigrationFailed(anyhow::Error),
+    /// E2005: CREATE DATABASE (or sizing it) failed.
+    #[error(transparent)]
+    DatabaseCreateFailed(anyhow::Error),
+    /// E2006: saving instance settings to the database failed.
+    #[error(transparent)]
+    SettingsSaveFailed(anyhow::Error),
+    /// E2007: rotating the secret master key or re-encrypting stored secrets failed.
+    #[error(transparent)]
+    SecretRotationFailed(anyhow::Error),
+    /// E2008: loading reference data after the migrations failed.
+    #[error(transparent)]
+    SeedDataFailed(anyhow::Error),
+    /// E2009: creating the dedicated application login failed.
+    #[error(transparent)]
+    AppAccountFailed(anyhow::Error),
+    /// E3001: install settings failed validation.
+    #[error(transparent)]
+    InvalidSettings(anyhow::Error),
+    /// E3002: retention/archive policy failed validation.
+    #[error(transparent)]
+    InvalidArchivePolicy(anyhow::Error),
+    /// E3003: command-line arguments of a headless mode are invalid.
+    #[error(transparent)]
+    InvalidArguments(anyhow::Error),
+    /// E4001: installing or starting the service/container failed.
+    #[error(transparent)]
+    ServiceStartFailed(anyhow::Error),
+    /// E4002: the service/container is not running after start.
+    #[error(transparent)]
+    ServiceVerificationFailed(anyhow::Error),
+    /// E4003: Docker deployment (template, images, compose) failed.
+    #[error(transparent)]
+    DockerDeployFailed(anyhow::Error),
+    /// E4004: reaching or deploying to the server of a remote (SSH) install failed.
+    #[error(transparent)]
+    RemoteHostFailed(anyhow::Error),
+    /// E4005: administrator approval (UAC) for the privileged steps was declined or failed.
+    #[error(transparent)]
+    ElevationFailed(anyhow::Error),
+    /// E6001: an archive run failed.
+    #[error(transparent)]
+    ArchiveRunFailed(anyhow::Error),
+    /// E6002: restoring an archived month failed.
+    #[error(transparent)]
+    ArchiveRestoreFailed(anyhow::Error),
+    /// E6003: the archive ledger check could not run.
+    #[error(transparent)]
+    ArchiveVerifyFailed(anyhow::Error),
+}
+
+impl InstallerError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::DestinationNotWritable(_) => 1001,
+            Self::InsufficientDiskSpace(_) => 1002,
+            Self::PayloadMissing(_) => 1003,
+            Self::FileCopyFailed(_) => 1004,
+            Self::ConfigWriteFailed(_) => 1005,
+            Self::ArtifactWriteFailed(_) => 1006,
+            Self::OfflineBundleInvalid(_) => 1007,
+            Self::PayloadIntegrityFailed(_) => 1008,
+            Self::PackageBuildFailed(_) => 1009,
+            Self::DatabaseConnectionFailed(_) => 2001,
+            Self::DatabaseCreatePermissionDenied(_) => 2002,
+            Self::DatabaseAlreadyExists(_) => 2003,
+            Self::MigrationFailed(_) => 2004,
+            Self::DatabaseCreateFailed(_) => 2005,
+            Self::SettingsSaveFailed(_) => 2006,
+            Self::SecretRotationFailed(_) => 2007,
+            Self::SeedDataFailed(_) => 2008,
+            Self::AppAccountFailed(_) => 2009,
+            Self::InvalidSettings(_) => 3001,
+            Self::InvalidArchivePolicy(_) => 3002,
+            Self::InvalidArguments(_) => 3003,
+            Self::ServiceStartFailed(_) => 4001,
+            Self::ServiceVerificationFailed(_) => 4002,
+            Self::DockerDeployFailed(_) => 4003,
+            Self::RemoteHostFailed(_) => 4004,
+            Self::ElevationFailed(_) => 4005,
+            Self::ArchiveRunFailed(_) => 6001,
+            Self::ArchiveRestoreFailed(_) => 6002,
+            Self::ArchiveVerifyFailed(_) => 6003,
+        }
+    }
+}
+
+/// Attach `wrap`'s code to `err` unless it already carries one (the innermost code is the most
+/// specific) or is a cancellation.
+pub fn with_code(err: anyhow::Error, wrap: fn(anyhow::Error) -> InstallerError) -> anyhow::Error {
+    if coded(&err).is_some() || cancel::is_cancelled(&err) {
+        return err;
+    }
+    anyhow::Error::new(wrap(err))
+}
+
+pub trait OrCode<T> {
+    /// `Result` counterpart of [`with_code`].
+    fn or_code(self, wrap: fn(anyhow::Error) -> InstallerError) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> OrCode<T> for Result<T, E> {
+    fn or_code(self, wrap: fn(anyhow::Error) -> InstallerError) -> anyhow::Result<T> {
+        self.map_err(|e| with_code(e.into(), wrap))
+    }
+}
+
+fn coded(err: &anyhow::Error) -> Option<u16> {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<InstallerError>())
+        .map(InstallerError::code)
+}
+
+/// Code of `err`: its attached code, [`CODE_INTERRUPTED`], [`CODE_CANCELLED`], or
+/// [`CODE_UNCLASSIFIED`].
+pub fn error_code(err: &anyhow::Error) -> u16 {
+    coded(err).unwrap_or(if cancel::interrupted_by(err).is_some() {
+        CODE_INTERRUPTED
+    } else if cancel::is_cancelled(err) {
+        CODE_CANCELLED
+    } else {
+        CODE_UNCLASSIFIED
+    })
+}
+
+/// `E2004` form used in dialogs and logs.
+pub fn code_label(code: u16) -> String {
+    format!("E{:04}", code)
+}
+
+/// Fixed one-line description of `code`, for places that must not carry the error text itself
+/// (it can name hosts, paths and connection details).
+pub fn code_summary(code: u16) -> &'static str {
+    match code {
+        1001 => "The destination folder cannot be created or written",
+        1002 => "Not enough free disk space",
+        1003 => "Runtime payload folders are missing or empty",
+        1004 => "Copying a runtime file failed",
+        1005 => "Generating the configuration failed",
+        1006 => "Writing the install artifacts failed",
+        1007 => "The offline bundle failed verification",
+        1008 => "A runtime payload file does not match the payload manifest",
+        1009 => "Building a distribution package failed",
+        2001 => "Could not connect to the database server",
+        2002 => "The database login may not create databases",
+        2003 => "The database already exists",
+        2004 => "A schema migration failed",
+        2005 => "Creating the database failed",
+        2006 => "Saving instance settings to the database failed",
+        2007 => "Rotating the secret master key failed",
+        2008 => "Loading reference data failed",
+        2009 => "Creating the application login failed",
+        3001 => "Install settings failed validation",
+        3002 => "The archive policy failed validation",
+        3003 => "Command-line arguments are invalid",
+        4001 => "Installing or starting the service failed",
+        4002 => "The service is not running after start",
+        4003 => "Docker deployment failed",
+        4004 => "The remote install server could not be reached or deployed to",
+        4005 => "Administrator approval was declined or failed",
+        6001 => "An archive run failed",
+        6002 => "Restoring an archived month failed",
+        6003 => "The archive ledger check could not run",
+        CODE_CANCELLED => "The install was cancelled",
+        CODE_INTERRUPTED => "The install was interrupted",
+        _ => "The install failed",
+    }
+}
+
+/// Operator-facing text: `E2004: <message>`.
+pub fn user_message(err: &anyhow::Error) -> String {
+    format!("{}: {}", code_label(error_code(err)), err)
+}
+
+/// Process exit code for `err` (see module docs).
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    exit_code_for(error_code(err))
+}
+
+fn exit_code_for(code: u16) -> i32 {
+    if code == CODE_UNCLASSIFIED {
+        return 1;
+    }
+    i32::from(code / 1000) * 10 + i32::from(code % 10)
+}
+
+/// True when an I/O permission error is anywhere in `err`'s chain.
+pub fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        e.downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::PermissionDenied)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn code_is_transparent_and_survives_outer_context() {
+        let err: anyhow::Result<()> = Err(anyhow::anyhow!("relation \"calls\" already exists"))
+            .context("Failed to apply migration 004_calls.sql")
+            .or_code(InstallerError::MigrationFailed);
+        let err = err.context("Install step failed").unwrap_err();
+
+        assert_eq!(error_code(&err), 2004);
+        assert_eq!(
+            format!("{:#}", err),
+            "Install step failed: Failed to apply migration 004_calls.sql: relation \"calls\" already exists"
+        );
+        assert_eq!(user_message(&err), "E2004: Install step failed");
+        assert_eq!(exit_code(&err), 24);
+    }
+
+    #[test]
+    fn innermost_code_and_cancellation_are_kept() {
+        let err = with_code(
+            with_code(
+                anyhow::anyhow!("Login may not create databases"),
+                InstallerError::DatabaseCreatePermissionDenied,
+            ),
+            InstallerError::DatabaseCreateFailed,
+        );
+        assert_eq!(error_code(&err), 2002);
+
+        let err = with_code(cancel::Cancelled.into(), InstallerError::FileCopyFailed);
+        assert!(cancel::is_cancelled(&err));
+        assert_eq!(error_code(&err), CODE_CANCELLED);
+        assert_eq!(exit_code(&err), 91);
+
+        let err = anyhow::anyhow!("boom");
+        assert_eq!(user_message(&err), "E9999: boom");
+        assert_eq!(exit_code(&err), 1);
+    }
+
+    #[test]
+    fn exit_codes_are_unique_and_fit_a_byte() {
+        let all = [
+            1001,
+            1002,
+            1003,
+            1004,
+            1005,
+            1006,
+            1007,
+            1008,
+            1009,
+            2001,
+            2002,
+            2003,
+            2004,
+            2005,
+            2006,
+            2007,
+            2008,
+            2009,
+            3001,
+            3002,
+            3003,
+            4001,
+            4002,
+            4003,
+            4004,
+            4005,
+            6001,
+            6002,
+            6003,
+            CODE_CANCELLED,
+            CODE_INTERRUPTED,
+        ];
+        let mut seen = std::collections::HashSet::new();
+        for code in all {
+            assert!((1..=9).contains(&(code % 1000)), "{}", code);
+            let exit = exit_code_for(code);
+            assert!(exit > 2 && exit < 256, "{} -> {}", code, exit);
+            assert!(seen.insert(exit), "duplicate exit code {}", exit);
+            assert_ne!(
+                code_summary(code),
+                code_summary(CODE_UNCLASSIFIED),
+                "{}",
+                code
+            );
+        }
+        assert_eq!(code_label(1001), "E1001");
+    }
+}
@@ -0,0 +1,172 @@
+//! Install cancellation.
+//!
+//! Each install run owns one `CancellationToken`. `run_installation` registers it (so the
+//! `cancel_install` command can reach it) and scopes it as the task-local current token, which
+//! lets deep helpers such as `run_cmd_with_timeout` and the file copy loop observe Cancel
+//! without threading a parameter through every caller. Cancelled work fails with [`Cancelled`],
+//! which `run_installation` recognises and answers with a rollback.
+//!
+//! A termination signal cancels the same way through [`request_interrupt`]; the run then fails
+//! with [`Interrupted`] instead (see `installation::signals`).
+
+use std::future::Future;
+use std::sync::Mutex;
+
+use log::info;
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static CURRENT: CancellationToken;
+}
+
+/// Token of the install run in progress (at most one; see `try_begin_install_job`).
+static ACTIVE: Mutex<Option<CancellationToken>> = Mutex::new(None);
+/// Signal that cancelled the run in progress, if one did.
+static SIGNAL: Mutex<Option<&'static str>> = Mutex::new(None);
+
+/// Error returned by any step that stopped because the install was cancelled.
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Installation cancelled.")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Error of a run stopped by a termination signal: saved checkpoint, current step rolled back.
+#[derive(Debug, Clone, Copy)]
+pub struct Interrupted {
+    pub signal: &'static str,
+}
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Installation interrupted by {}.", self.signal)
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Register a fresh token for a new install run.
+pub fn begin() -> CancellationToken {
+    let token = CancellationToken::new();
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = Some(token.clone());
+    }
+    if let Ok(mut signal) = SIGNAL.lock() {
+        *signal = None;
+    }
+    token
+}
+
+/// Unregister the run's token (a later Cancel must not hit a finished run).
+pub fn end() {
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = None;
+    }
+}
+
+/// True while an install run is in progress.
+pub fn is_active() -> bool {
+    ACTIVE.lock().map(|a| a.is_some()).unwrap_or(false)
+}
+
+/// Cancel the install run in progress. Returns false when nothing is running.
+pub fn request_cancel() -> bool {
+    let active = ACTIVE.lock().ok().and_then(|a| a.clone());
+    match active {
+        Some(token) => {
+            info!("[PHASE: install] [STEP: cancel] cancellation token triggered");
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Cancel the install run in progress because `signal` was received. Returns false when nothing
+/// is running.
+pub fn request_interrupt(signal: &'static str) -> bool {
+    if let Ok(mut current) = SIGNAL.lock() {
+        *current = Some(signal);
+    }
+    request_cancel()
+}
+
+/// Signal that cancelled the current (or last) install run, if one did.
+pub fn interrupt_signal() -> Option<&'static str> {
+    SIGNAL.lock().ok().and_then(|s| *s)
+}
+
+/// Run `fut` with `token` as the current task's cancellation token.
+pub async fn scope<F: Future>(token: CancellationToken, fut: F) -> F::Output {
+    CURRENT.scope(token, fut).await
+}
+
+/// The current task's token, if it runs inside an install.
+pub fn current() -> Option<CancellationToken> {
+    CURRENT.try_with(|t| t.clone()).ok()
+}
+
+/// `Err(Cancelled)` once `token` has been cancelled.
+pub fn check(token: &CancellationToken) -> anyhow::Result<()> {
+    if token.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    Ok(())
+}
+
+/// Like [`check`] for the current task's token; a no-op outside an install.
+pub fn check_current() -> anyhow::Result<()> {
+    match current() {
+        Some(t) => check(&t),
+        None => Ok(()),
+    }
+}
+
+/// True when `err` (or anything in its context chain) is a [`Cancelled`] or [`Interrupted`].
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|e| e.is::<Cancelled>() || e.is::<Interrupted>())
+}
+
+/// The signal that stopped the run `err` came from, if a signal did.
+pub fn interrupted_by(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<Interrupted>())
+        .map(|i| i.signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[tokio::test]
+    async fn scoped_token_is_visible_and_cancellation_is_detected_through_context() {
+        assert!(current().is_none());
+        assert!(check_current().is_ok());
+
+        let token = CancellationToken::new();
+        let inner = token.clone();
+        let err = scope(token.clone(), async move {
+            assert!(check_current().is_ok());
+            inner.cancel();
+            check_current().context("deploy_copy")
+        })
+        .await
+        .unwrap_err();
+
+        assert!(is_cancelled(&err));
+        assert_eq!(err.to_string(), "deploy_copy");
+        assert!(!is_cancelled(&anyhow::anyhow!("copy failed")));
+        assert_eq!(interrupted_by(&err), None);
+
+        let err = anyhow::Error::new(Interrupted { signal: "SIGTERM" }).context("rolled back");
+        assert!(is_cancelled(&err));
+        assert_eq!(interrupted_by(&err), Some("SIGTERM"));
+    }
+}
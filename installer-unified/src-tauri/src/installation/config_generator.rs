@@ -0,0 +1,225 @@
+//! Runtime configuration for the CADalytix services, generated from the wizard answers.
+//!
+//! Two files are written to the binaries folder at `config_generate`:
+//! - `appsettings.json`, read by the services: the instance settings (`build_instance_settings`)
+//!   nested by `:` into JSON sections, plus the install layout and the connection strings.
+//! - `cadalytix.env`, the same settings as `Section__Key=value` lines for a systemd
+//!   `EnvironmentFile=` or a Compose `env_file:`.
+//!
+//! Connection strings never appear in plain text: each is encrypted with the installer's
+//! `SecretProtector` and written as its `ENCv1:` value. `SecretStore:Backend` and
+//! `SecretStore:KeyPath` tell the runtime where the master key to decrypt them lives.
+//!
+//! When the payload ships an `appsettings.template.json`, it is rendered instead of the built-in
+//! layout: every `{{Section:Key}}` placeholder is replaced with that setting, JSON-escaped. An
+//! unknown placeholder fails the step, so a template that does not match the installer is caught
+//! at install time rather than by a service that cannot start.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+use crate::api::installer::{build_instance_settings, StartInstallRequest};
+use crate::installation::layout::InstallLayout;
+use crate::security::secret_protector::SecretProtector;
+
+pub const APPSETTINGS_FILE: &str = "appsettings.json";
+pub const APPSETTINGS_TEMPLATE_FILE: &str = "appsettings.template.json";
+pub const ENV_FILE: &str = "cadalytix.env";
+
+/// Flat runtime settings (`Section:Key` -> value), sorted.
+pub type RuntimeSettings = BTreeMap<String, String>;
+
+/// Settings for the services: the wizard answers, the layout and the protected connection
+/// strings. `config_db_conn_str` is the connection the services use (the application login's
+/// when one was created); `app_login` names that login.
+pub async fn runtime_settings(
+    req: &StartInstallRequest,
+    layout: &InstallLayout,
+    engine: &str,
+    config_db_conn_str: &str,
+    app_login: Option<&str>,
+    secrets: &SecretProtector,
+) -> Result<RuntimeSettings> {
+    let mut settings: RuntimeSettings = build_instance_settings(req).into_iter().collect();
+    // `Data:Sources` is also the parent of the further sources' sections; a JSON value cannot be
+    // both, so the name list moves to its own key.
+    if let Some(names) = settings.remove("Data:Sources") {
+        settings.insert("Data:SourceNames".to_string(), names);
+    }
+    for (role, path) in layout.entries() {
+        settings.insert(
+            format!("Paths:{}", capitalize(role)),
+            path.to_string_lossy().to_string(),
+        );
+    }
+
+    settings.insert("ConfigDb:Engine".to_string(), engine.to_string());
+    settings.insert(
+        "ConfigDb:ConnectionString".to_string(),
+        secrets.encrypt(config_db_conn_str).await?,
+    );
+    if let Some(login) = app_login {
+        settings.insert("ConfigDb:AppLogin".to_string(), login.to_string());
+    }
+    for (i, source) in req.data_sources().iter().enumerate() {
+        if source.file_source.is_some() || source.call_data_connection_string.trim().is_empty() {
+            continue;
+        }
+        let key = if i == 0 {
+            "CallDataDb:ConnectionString".to_string()
+        } else {
+            format!("Data:Sources:{}:ConnectionString", source.name)
+        };
+        settings.insert(
+            key,
+            secrets.encrypt(&source.call_data_connection_string).await?,
+        );
+    }
+
+    settings.insert(
+        "SecretStore:Backend".to_string(),
+        secrets.active_backend().await.name().to_string(),
+    );
+    settings.insert(
+        "SecretStore:KeyPath".to_string(),
+        secrets.key_path().to_string_lossy().to_string(),
+    );
+    Ok(settings)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// `appsettings.json` contents: settings nested into sections by `:`.
+pub fn appsettings_json(settings: &RuntimeSettings) -> Result<Vec<u8>> {
+    let mut root = Map::new();
+    for (key, value) in settings {
+        let mut parts: Vec<&str> = key.split(':').collect();
+        let leaf = parts.pop().unwrap_or_default();
+        let mut section = &mut root;
+        for part in parts {
+            section = match section
+                .entry(part.to_string())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                Value::Object(m) => m,
+                _ => anyhow::bail!("Setting {} is nested under a value", key),
+            };
+        }
+        if section.contains_key(leaf) {
+            anyhow::bail!("Setting {} is also a section", key);
+        }
+        section.insert(leaf.to_string(), Value::String(value.clone()));
+    }
+    let mut bytes = serde_json::to_vec_pretty(&Value::Object(root))?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+/// `cadalytix.env` contents. Keys that are not valid variable names (a source name with spaces,
+/// say) are left out; the services read them from `appsettings.json`.
+pub fn env_file(settings: &RuntimeSettings) -> String {
+    let mut out = String::from("# CADalytix runtime settings, generated by the installer.\n");
+    for (key, value) in settings {
+        let name = key.replace(':', "__");
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        let plain = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.:/+=,@".contains(c));
+        if plain {
+            out.push_str(&format!("{}={}\n", name, value));
+        } else {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            out.push_str(&format!("{}=\"{}\"\n", name, escaped));
+        }
+    }
+    out
+}
+
+/// Replace every `{{Section:Key}}` in `template` with that setting, JSON-escaped (the template is
+/// JSON; placeholders sit inside string literals).
+pub fn render_template(template: &str, settings: &RuntimeSettings) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            anyhow::anyhow!("Unclosed placeholder in {}", APPSETTINGS_TEMPLATE_FILE)
+        })?;
+        let key = after[..end].trim();
+        let value = settings.get(key).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} uses an unknown setting {{{{{}}}}}",
+                APPSETTINGS_TEMPLATE_FILE,
+                key
+            )
+        })?;
+        let quoted = serde_json::to_string(value)?;
+        out.push_str(&quoted[1..quoted.len() - 1]);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_render_as_nested_json_env_lines_and_template_values() {
+        let mut settings = RuntimeSettings::new();
+        settings.insert("Archive:Enabled".to_string(), "true".to_string());
+        settings.insert(
+            "ConfigDb:ConnectionString".to_string(),
+            "ENCv1:abc+/=".to_string(),
+        );
+        settings.insert(
+            "Paths:Data".to_string(),
+            "C:\\Program Files\\CADalytix\\Data".to_string(),
+        );
+        settings.insert(
+            "Data:Sources:North Dispatch:SourceKind".to_string(),
+            "file".to_string(),
+        );
+
+        let json: Value = serde_json::from_slice(&appsettings_json(&settings).unwrap()).unwrap();
+        assert_eq!(json["ConfigDb"]["ConnectionString"], "ENCv1:abc+/=");
+        assert_eq!(
+            json["Data"]["Sources"]["North Dispatch"]["SourceKind"],
+            "file"
+        );
+
+        let env = env_file(&settings);
+        assert!(env.contains("Archive__Enabled=true\n"));
+        assert!(env.contains("ConfigDb__ConnectionString=ENCv1:abc+/=\n"));
+        assert!(env.contains("Paths__Data=\"C:\\\\Program Files\\\\CADalytix\\\\Data\"\n"));
+        assert!(!env.contains("North Dispatch"));
+
+        let rendered = render_template(
+            r#"{ "Db": "{{ConfigDb:ConnectionString}}", "Data": "{{ Paths:Data }}" }"#,
+            &settings,
+        )
+        .unwrap();
+        let parsed: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(parsed["Data"], "C:\\Program Files\\CADalytix\\Data");
+        let err = render_template("{{Nope:Missing}}", &settings).unwrap_err();
+        assert!(err.to_string().contains("{{Nope:Missing}}"));
+
+        settings.insert("Archive".to_string(), "x".to_string());
+        assert!(appsettings_json(&settings).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+// Runtime dependency detection (dependency preflight)
+//
+// What an install needs depends on its mode: Docker mode needs a Docker Engine recent enough for
+// the compose template, a running daemon and Compose; Linux native mode needs systemd as init;
+// Windows mode runs SQL Server clients that need the VC++ runtime and an ODBC driver.
+//
+// Findings use the preflight statuses: "Fail" blocks the install, "Warn" lets it continue.
+// The parsers are pure functions so they are tested on every OS.
+
+use log::info;
+
+use crate::installation::docker::{self, DockerVersion};
+
+/// Oldest Docker Engine the compose template is tested with.
+pub const MIN_DOCKER_VERSION: (u32, u32) = (20, 10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyFinding {
+    pub name: String,
+    pub status: &'static str, // Pass | Warn | Fail
+    pub detail: String,
+    pub remediation: Option<String>,
+}
+
+impl DependencyFinding {
+    fn new(name: &str, status: &'static str, detail: String, remediation: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+            remediation,
+        }
+    }
+}
+
+pub fn docker_version_supported(v: &DockerVersion) -> bool {
+    (v.major, v.minor) >= MIN_DOCKER_VERSION
+}
+
+/// Package manager command for `package` on the distro described by os-release `ID`/`ID_LIKE`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn package_install_hint(id: &str, id_like: &[String], package: &str) -> String {
+    let family = std::iter::once(id).chain(id_like.iter().map(String::as_str));
+    for f in family {
+        match f {
+            "debian" | "ubuntu" => return format!("sudo apt-get install -y {}", package),
+            "rhel" | "fedora" | "centos" => return format!("sudo dnf install -y {}", package),
+            "suse" | "opensuse" | "sles" => return format!("sudo zypper install -y {}", package),
+            _ => {}
+        }
+    }
+    format!(
+        "install the '{}' package with your package manager",
+        package
+    )
+}
+
+/// Parse `reg query` output into (value name, data) pairs.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn parse_reg_values(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            // "<name>    REG_<TYPE>    <data>"; the name itself may contain single spaces.
+            let type_pos = line.find("    REG_")?;
+            let name = line[..type_pos].trim().to_string();
+            let rest = line[type_pos..].trim_start();
+            let data = rest.split_once("    ").map(|(_, d)| d.trim()).unwrap_or("");
+            Some((name, data.to_string()))
+        })
+        .collect()
+}
+
+/// Installed SQL Server ODBC drivers from the `ODBC Drivers` registry key values.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn sql_server_odbc_drivers(values: &[(String, String)]) -> Vec<String> {
+    values
+        .iter()
+        .filter(|(name, data)| {
+            name.to_ascii_lowercase().contains("for sql server")
+                && data.eq_ignore_ascii_case("installed")
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Detect the runtime dependencies of `install_mode` ("windows" | "docker" | "linux").
+pub async fn detect(install_mode: &str) -> Vec<DependencyFinding> {
+    let mode = install_mode.trim().to_ascii_lowercase();
+    let findings = match mode.as_str() {
+        "docker" => check_docker().await,
+        "linux" => check_linux_native().await,
+        "windows" => check_windows_native().await,
+        other => vec![DependencyFinding::new(
+            "Install Mode",
+            "Fail",
+            format!("Unknown install mode '{}'.", other),
+            Some("Choose Windows or Docker / Linux on the platform page.".to_string()),
+        )],
+    };
+    info!(
+        "[PHASE: preflight] [STEP: dependencies] Dependencies detected (mode={}, findings={:?})",
+        mode,
+        findings
+            .iter()
+            .map(|f| format!("{}={}", f.name, f.status))
+            .collect::<Vec<_>>()
+    );
+    findings
+}
+
+async fn check_docker() -> Vec<DependencyFinding> {
+    let mut out = Vec::new();
+    let install_docker = if cfg!(windows) {
+        "Install Docker Desktop (https://docs.docker.com/desktop/install/windows-install/) and restart the installer."
+    } else {
+        "Install Docker Engine (https://docs.docker.com/engine/install/) and restart the installer."
+    };
+
+    let version = match docker::get_docker_version().await {
+        Ok(v) => v,
+        Err(_) => {
+            out.push(DependencyFinding::new(
+                "Docker",
+                "Fail",
+                "Docker is not installed or not on PATH.".to_string(),
+                Some(install_docker.to_string()),
+            ));
+            return out;
+        }
+    };
+    let wanted = format!("{}.{}", MIN_DOCKER_VERSION.0, MIN_DOCKER_VERSION.1);
+    if docker_version_supported(&version) {
+        out.push(DependencyFinding::new(
+            "Docker",
+            "Pass",
+            format!(
+                "Docker {}.{}.{} found.",
+                version.major, version.minor, version.patch
+            ),
+            None,
+        ));
+    } else {
+        out.push(DependencyFinding::new(
+            "Docker",
+            "Fail",
+            format!(
+                "Docker {}.{}.{} is older than the required {}.",
+                version.major, version.minor, version.patch, wanted
+            ),
+            Some(format!("Upgrade Docker to {} or newer.", wanted)),
+        ));
+    }
+
+    if docker::is_docker_daemon_running().await.unwrap_or(false) {
+        out.push(DependencyFinding::new(
+            "Docker Daemon",
+            "Pass",
+            "The Docker daemon is running.".to_string(),
+            None,
+        ));
+    } else {
+        let remediation = if cfg!(windows) {
+            "Start Docker Desktop and wait until it reports that the engine is running."
+        } else {
+            "Start it with 'sudo systemctl enable --now docker'. If it is running, run the installer with sudo or add your user to the 'docker' group and log in again."
+        };
+        out.push(DependencyFinding::new(
+            "Docker Daemon",
+            "Fail",
+            "The Docker daemon is not running or not reachable by this user.".to_string(),
+            Some(remediation.to_string()),
+        ));
+    }
+
+    match docker::detect_compose_invocation().await {
+        Ok(docker::ComposeInvocation::DockerSubcommand) => out.push(DependencyFinding::new(
+            "Docker Compose",
+            "Pass",
+            "Docker Compose V2 (docker compose) found.".to_string(),
+            None,
+        )),
+        Ok(docker::ComposeInvocation::DockerComposeBinary) => out.push(DependencyFinding::new(
+            "Docker Compose",
+            "Warn",
+            "Only the legacy docker-compose V1 binary was found; it is no longer maintained."
+                .to_string(),
+            Some(compose_plugin_hint().await),
+        )),
+        Err(_) => out.push(DependencyFinding::new(
+            "Docker Compose",
+            "Fail",
+            "Docker Compose is not installed.".to_string(),
+            Some(compose_plugin_hint().await),
+        )),
+    }
+    out
+}
+
+async fn compose_plugin_hint() -> String {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(distro) = crate::installation::linux::detect_linux_distro().await {
+            return format!(
+                "Install the Compose plugin: {}.",
+                package_install_hint(&distro.id, &distro.id_like, "docker-compose-plugin")
+            );
+        }
+    }
+    "Install the Docker Compose plugin (https://docs.docker.com/compose/install/).".to_string()
+}
+
+#[cfg(target_os = "linux")]
+async fn check_linux_native() -> Vec<DependencyFinding> {
+    // Same test as sd_booted(3): systemd creates this directory when it runs as init.
+    let booted = tokio::fs::try_exists("/run/systemd/system")
+        .await
+        .unwrap_or(false);
+    let systemctl = crate::installation::run_cmd_with_timeout(
+        "systemctl",
+        &["--version".to_string()],
+        tokio::time::Duration::from_secs(10),
+        "systemctl_version",
+    )
+    .await
+    .ok()
+    .filter(|o| o.exit_code == Some(0))
+    .and_then(|o| o.stdout.lines().next().map(|l| l.trim().to_string()));
+
+    let finding = match (booted, systemctl) {
+        (true, Some(v)) => DependencyFinding::new(
+            "systemd",
+            "Pass",
+            format!("{} is running as init.", v),
+            None,
+        ),
+        (true, None) => DependencyFinding::new(
+            "systemd",
+            "Fail",
+            "systemd is running but systemctl is not available.".to_string(),
+            Some("Make sure systemctl is on PATH (it ships with the systemd package).".to_string()),
+        ),
+        (false, _) => DependencyFinding::new(
+            "systemd",
+            "Fail",
+            "systemd is not running as init (typical for containers and WSL without systemd); the CADalytix service cannot be registered.".to_string(),
+            Some("Use Docker mode on this host, or enable systemd (WSL: set systemd=true under [boot] in /etc/wsl.conf and restart WSL).".to_string()),
+        ),
+    };
+    vec![finding]
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn check_linux_native() -> Vec<DependencyFinding> {
+    vec![DependencyFinding::new(
+        "systemd",
+        "Fail",
+        "Linux native mode needs a Linux host with systemd.".to_string(),
+        Some("Use the Windows or Docker install mode on this host.".to_string()),
+    )]
+}
+
+#[cfg(windows)]
+async fn check_windows_native() -> Vec<DependencyFinding> {
+    async fn reg_query(key: &str) -> Option<Vec<(String, String)>> {
+        let out = crate::installation::run_cmd_with_timeout(
+            "reg",
+            &["query".to_string(), key.to_string()],
+            tokio::time::Duration::from_secs(10),
+            "reg_query",
+        )
+        .await
+        .ok()?;
+        (out.exit_code == Some(0)).then(|| parse_reg_values(&out.stdout))
+    }
+
+    let mut out = Vec::new();
+
+    let vc = reg_query(r"HKLM\SOFTWARE\Microsoft\VisualStudio\14.0\VC\Runtimes\x64").await;
+    let vc_installed = vc.as_ref().is_some_and(|values| {
+        values
+            .iter()
+            .any(|(n, d)| n.eq_ignore_ascii_case("Installed") && d == "0x1")
+    });
+    if vc_installed {
+        let version = vc
+            .iter()
+            .flatten()
+            .find(|(n, _)| n.eq_ignore_ascii_case("Version"))
+            .map(|(_, d)| d.clone())
+            .unwrap_or_default();
+        out.push(DependencyFinding::new(
+            "Visual C++ Runtime",
+            "Pass",
+            format!("Visual C++ 2015-2022 runtime (x64) installed {}", version)
+                .trim()
+                .to_string(),
+            None,
+        ));
+    } else {
+        out.push(DependencyFinding::new(
+            "Visual C++ Runtime",
+            "Warn",
+            "The Visual C++ 2015-2022 runtime (x64) was not found; the CADalytix services may fail to start.".to_string(),
+            Some("Install the Microsoft Visual C++ Redistributable (x64) from https://aka.ms/vs/17/release/vc_redist.x64.exe.".to_string()),
+        ));
+    }
+
+    let drivers = reg_query(r"HKLM\SOFTWARE\ODBC\ODBCINST.INI\ODBC Drivers")
+        .await
+        .map(|values| sql_server_odbc_drivers(&values))
+        .unwrap_or_default();
+    if drivers.is_empty() {
+        out.push(DependencyFinding::new(
+            "SQL Server ODBC Driver",
+            "Warn",
+            "No Microsoft ODBC Driver for SQL Server was found; reporting tools that connect through ODBC will not work.".to_string(),
+            Some("Install Microsoft ODBC Driver 18 for SQL Server (https://learn.microsoft.com/sql/connect/odbc/download-odbc-driver-for-sql-server).".to_string()),
+        ));
+    } else {
+        out.push(DependencyFinding::new(
+            "SQL Server ODBC Driver",
+            "Pass",
+            format!("Found: {}.", drivers.join(", ")),
+            None,
+        ));
+    }
+    out
+}
+
+#[cfg(not(windows))]
+async fn check_windows_native() -> Vec<DependencyFinding> {
+    vec![DependencyFinding::new(
+        "Windows",
+        "Fail",
+        "Windows mode installs Windows services and needs a Windows host.".to_string(),
+        Some("Use the Docker / Linux install mode on this host.".to_string()),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docker_minimum_version_is_enforced() {
+        let v = |major, minor| DockerVersion {
+            major,
+            minor,
+            patch: 0,
+            raw: String::new(),
+        };
+        assert!(docker_version_supported(&v(20, 10)));
+        assert!(docker_version_supported(&v(24, 0)));
+        assert!(!docker_version_supported(&v(19, 3)));
+        assert!(!docker_version_supported(&v(20, 9)));
+    }
+
+    #[test]
+    fn package_hint_follows_distro_family() {
+        assert_eq!(
+            package_install_hint("ubuntu", &[], "docker-compose-plugin"),
+            "sudo apt-get install -y docker-compose-plugin"
+        );
+        assert_eq!(
+            package_install_hint("rocky", &["rhel".to_string(), "centos".to_string()], "x"),
+            "sudo dnf install -y x"
+        );
+        assert!(package_install_hint("arch", &[], "x").contains("package manager"));
+    }
+
+    #[test]
+    fn odbc_drivers_are_read_from_reg_query_output() {
+        let out = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\ODBC\\ODBCINST.INI\\ODBC Drivers\r\n    SQL Server    REG_SZ    Installed\r\n    ODBC Driver 18 for SQL Server    REG_SZ    Installed\r\n    Microsoft Access Driver (*.mdb)    REG_SZ    Installed\r\n\r\n";
+        let values = parse_reg_values(out);
+        assert_eq!(values.len(), 3);
+        assert_eq!(
+            sql_server_odbc_drivers(&values),
+            vec!["ODBC Driver 18 for SQL Server".to_string()]
+        );
+
+        let vc = parse_reg_values(
+            "    Installed    REG_DWORD    0x1\n    Version    REG_SZ    v14.38.33130.00\n",
+        );
+        assert_eq!(vc[0], ("Installed".to_string(), "0x1".to_string()));
+        assert_eq!(vc[1].1, "v14.38.33130.00");
+    }
+}
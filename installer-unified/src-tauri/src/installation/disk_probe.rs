@@ -0,0 +1,188 @@
+//! Disk performance probe ("fio-lite") for the data folder.
+//!
+//! Writes a small file set in a scratch folder on the data folder's volume and times it: one
+//! sequential file written in 1 MiB chunks and synced once (bulk loads, archive staging), then
+//! 4 KiB writes at scattered offsets, each synced (database page and log flushes). The scratch
+//! folder is removed afterwards, whatever the outcome. Each phase stops early once it has taken
+//! `TIME_BUDGET`, so a very slow volume is reported rather than waited on.
+//!
+//! The results are estimates: caches, thin provisioning and other load on the host all move them.
+//! They are compared with what the expected ingest volume needs (`required_rates`) and reported by
+//! the preflight report; a slow volume is a warning, never a failure.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const MIB: u64 = 1024 * 1024;
+const SEQ_CHUNK_BYTES: usize = MIB as usize;
+const RANDOM_BLOCK_BYTES: u64 = 4096;
+/// Longest each phase runs before it is cut short.
+const TIME_BUDGET: Duration = Duration::from_secs(5);
+
+/// Floors for a hot database volume, whatever the ingest volume.
+pub const MIN_SEQ_WRITE_MBPS: f64 = 50.0;
+pub const MIN_RANDOM_WRITE_IOPS: f64 = 100.0;
+/// Ingest arrives in bursts (shift changes, major incidents); size for this multiple of the
+/// daily average rate.
+const PEAK_FACTOR: f64 = 20.0;
+/// Synced page writes per ingested row (data page, index pages, log).
+const WRITES_PER_ROW: f64 = 4.0;
+
+/// How much the probe writes.
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeSize {
+    pub seq_bytes: u64,
+    pub random_file_bytes: u64,
+    pub random_writes: u32,
+}
+
+impl Default for ProbeSize {
+    fn default() -> Self {
+        Self {
+            seq_bytes: 64 * MIB,
+            random_file_bytes: 16 * MIB,
+            random_writes: 256,
+        }
+    }
+}
+
+/// Measured write rates of one volume.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskProbe {
+    /// Folder the scratch files were written in.
+    pub dir: PathBuf,
+    pub seq_write_mbps: f64,
+    pub random_write_iops: f64,
+}
+
+/// Sequential MB/s and random IOPS needed for `rows_per_day` ingested rows.
+pub fn required_rates(rows_per_day: u64) -> (f64, f64) {
+    let peak_rows_per_sec = rows_per_day as f64 / 86_400.0 * PEAK_FACTOR;
+    let row_bytes = crate::archiver::FALLBACK_AVG_ROW_BYTES as f64;
+    let mbps = peak_rows_per_sec * row_bytes * WRITES_PER_ROW / 1_000_000.0;
+    let iops = peak_rows_per_sec * WRITES_PER_ROW;
+    (
+        mbps.max(MIN_SEQ_WRITE_MBPS),
+        iops.max(MIN_RANDOM_WRITE_IOPS),
+    )
+}
+
+/// Probe the volume of `data_dir` (through its nearest existing parent, so nothing is created
+/// where the install has not run yet).
+pub async fn probe(data_dir: &Path, size: ProbeSize) -> Result<DiskProbe> {
+    let dir = crate::utils::disk::nearest_existing_ancestor(data_dir)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("No existing folder found above {:?}", data_dir))?;
+    info!(
+        "[PHASE: preflight] [STEP: disk_probe] Probing write performance (data_dir={:?}, probe_dir={:?})",
+        data_dir, dir
+    );
+    let result = tokio::task::spawn_blocking(move || probe_blocking(&dir, size))
+        .await
+        .context("Disk probe task failed")?;
+    match &result {
+        Ok(p) => info!(
+            "[PHASE: preflight] [STEP: disk_probe] seq_write_mbps={:.1} random_write_iops={:.0}",
+            p.seq_write_mbps, p.random_write_iops
+        ),
+        Err(e) => warn!(
+            "[PHASE: preflight] [STEP: disk_probe] Probe failed: {:?}",
+            e
+        ),
+    }
+    result
+}
+
+fn probe_blocking(dir: &Path, size: ProbeSize) -> Result<DiskProbe> {
+    let work = dir.join(format!(".cadalytix-disk-probe-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir(&work)
+        .with_context(|| format!("Unable to create probe folder {:?}", work))?;
+    let result = run_phases(&work, size);
+    if let Err(e) = std::fs::remove_dir_all(&work) {
+        warn!(
+            "[PHASE: preflight] [STEP: disk_probe] Probe folder not removed {:?}: {}",
+            work, e
+        );
+    }
+    let (seq_write_mbps, random_write_iops) = result?;
+    Ok(DiskProbe {
+        dir: dir.to_path_buf(),
+        seq_write_mbps,
+        random_write_iops,
+    })
+}
+
+fn run_phases(work: &Path, size: ProbeSize) -> Result<(f64, f64)> {
+    // Sequential: 1 MiB chunks, synced once at the end.
+    let chunk = vec![0xA5u8; SEQ_CHUNK_BYTES];
+    let started = Instant::now();
+    let mut file = std::fs::File::create(work.join("seq.bin"))?;
+    let mut written = 0u64;
+    while written < size.seq_bytes && started.elapsed() < TIME_BUDGET {
+        file.write_all(&chunk)?;
+        written += chunk.len() as u64;
+    }
+    file.sync_all()?;
+    let seq_mbps = written as f64 / 1_000_000.0 / started.elapsed().as_secs_f64().max(1e-6);
+
+    // Random: 4 KiB writes at scattered offsets of a preallocated file, each synced.
+    let mut file = std::fs::File::create(work.join("random.bin"))?;
+    file.set_len(size.random_file_bytes)?;
+    file.sync_all()?;
+    let blocks = (size.random_file_bytes / RANDOM_BLOCK_BYTES).max(1);
+    let block = [0x5Au8; RANDOM_BLOCK_BYTES as usize];
+    // xorshift64: scattered but repeatable offsets.
+    let mut x: u64 = 0x9E37_79B9_7F4A_7C15;
+    let started = Instant::now();
+    let mut ops = 0u32;
+    while ops < size.random_writes && started.elapsed() < TIME_BUDGET {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        file.seek(SeekFrom::Start((x % blocks) * RANDOM_BLOCK_BYTES))?;
+        file.write_all(&block)?;
+        file.sync_data()?;
+        ops += 1;
+    }
+    let iops = f64::from(ops) / started.elapsed().as_secs_f64().max(1e-6);
+    Ok((seq_mbps, iops))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_measures_and_cleans_up_and_rates_scale_with_ingest() {
+        let dir = std::env::temp_dir().join(format!("disk-probe-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let size = ProbeSize {
+            seq_bytes: 4 * MIB,
+            random_file_bytes: MIB,
+            random_writes: 16,
+        };
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let probed = rt
+            .block_on(probe(&dir.join("data/postgresql"), size))
+            .unwrap();
+        assert_eq!(probed.dir, dir);
+        assert!(probed.seq_write_mbps > 0.0);
+        assert!(probed.random_write_iops > 0.0);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            required_rates(10_000),
+            (MIN_SEQ_WRITE_MBPS, MIN_RANDOM_WRITE_IOPS)
+        );
+        let (_, iops) = required_rates(2_000_000);
+        assert!(iops > 1_800.0 && iops < 1_900.0, "{}", iops);
+    }
+}
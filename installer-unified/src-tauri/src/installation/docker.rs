@@ -5,7 +5,7 @@
 use anyhow::{Context, Result};
 use log::{debug, info, warn};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::installation::{run_cmd_with_timeout, CommandOutput};
@@ -13,6 +13,17 @@ use crate::installation::{run_cmd_with_timeout, CommandOutput};
 #[allow(dead_code)]
 const DOCKER_CMD_TIMEOUT: Duration = Duration::from_secs(120);
 
+/// Image pulls move hundreds of MB, so they get far longer than other docker commands.
+const DOCKER_PULL_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// How long `install_docker_mode` waits for every container to report running and healthy.
+/// The web healthcheck's start period plus three 30s retries must fit inside it.
+const CONTAINER_READY_TIMEOUT_SECS: u64 = 300;
+
+/// Compose template compiled into the installer, used when the runtime folder has none.
+const BUNDLED_COMPOSE_TEMPLATE: &str =
+    include_str!("../../../runtime/linux/docker/compose/docker-compose.template.yml");
+
 /// Docker version information.
 #[derive(Debug, Clone, Default)]
 pub struct DockerVersion {
@@ -242,10 +253,9 @@ pub async fn docker_load_tar(tar_path: &Path) -> Result<()> {
     anyhow::bail!("Docker image load failed");
 }
 
-#[allow(dead_code)]
 pub async fn docker_pull(image: &str) -> Result<()> {
     let args = vec!["pull".to_string(), image.to_string()];
-    let out = run_cmd_with_timeout("docker", &args, DOCKER_CMD_TIMEOUT, "docker_pull").await?;
+    let out = run_cmd_with_timeout("docker", &args, DOCKER_PULL_TIMEOUT, "docker_pull").await?;
     if out.exit_code == Some(0) {
         return Ok(());
     }
@@ -253,7 +263,23 @@ pub async fn docker_pull(image: &str) -> Result<()> {
         "[PHASE: installation] [STEP: docker] docker pull failed: {}",
         out.stderr
     );
-    anyhow::bail!("Docker image pull failed");
+    anyhow::bail!(
+        "Docker image pull failed for '{}': {}",
+        image,
+        out.stderr.trim()
+    );
+}
+
+/// Whether `image` is already in the local image store (loaded from a tar or pulled before).
+pub async fn docker_image_present(image: &str) -> Result<bool> {
+    let args = vec![
+        "image".to_string(),
+        "inspect".to_string(),
+        image.to_string(),
+    ];
+    let out =
+        run_cmd_with_timeout("docker", &args, DOCKER_CMD_TIMEOUT, "docker_image_inspect").await?;
+    Ok(out.exit_code == Some(0))
 }
 
 /// Run `docker compose up -d` to start containers.
@@ -338,8 +364,17 @@ pub async fn generate_compose_file(
         .await
         .with_context(|| format!("Failed to read compose template: {:?}", template_path))?;
 
+    write_compose_from_template(&template_content, output_path, variables).await
+}
+
+/// Substitute `variables` into compose template text and write the result to `output_path`.
+pub async fn write_compose_from_template(
+    template_content: &str,
+    output_path: &Path,
+    variables: &HashMap<String, String>,
+) -> Result<()> {
     // Perform substitution
-    let output_content = substitute_placeholders(&template_content, variables);
+    let output_content = substitute_placeholders(template_content, variables);
 
     // Check for unresolved placeholders
     if let Some(unresolved) = find_unresolved_placeholder(&output_content) {
@@ -369,7 +404,7 @@ pub async fn generate_compose_file(
         .with_context(|| format!("Failed to write compose file: {:?}", output_path))?;
 
     info!(
-        "[PHASE: installation] [STEP: docker] generate_compose_file exit ok (output={:?}, size={})",
+        "[PHASE: installation] [STEP: docker] write_compose_from_template exit ok (output={:?}, size={})",
         output_path,
         output_content.len()
     );
@@ -377,6 +412,86 @@ pub async fn generate_compose_file(
     Ok(())
 }
 
+/// Compose template variables for a Docker install, from the wizard answers.
+///
+/// Values are escaped for the template's double-quoted YAML strings, and `$` is doubled so
+/// Compose does not treat passwords containing it as variable references.
+pub fn compose_variables(
+    req: &StartInstallRequest,
+    data_path: &Path,
+    logs_path: &Path,
+    install_id: &str,
+) -> HashMap<String, String> {
+    let (call_data_connection_string, source_object_name) = match &req.file_source {
+        Some(_) => (String::new(), String::new()),
+        None => (
+            req.call_data_connection_string.clone(),
+            req.source_object_name.clone(),
+        ),
+    };
+    let (archive_path, import_path) = compose_mount_paths(req, data_path);
+
+    let raw = [
+        (
+            "DB_CONNECTION_STRING",
+            req.config_db_connection_string.clone(),
+        ),
+        ("CALL_DATA_CONNECTION_STRING", call_data_connection_string),
+        ("SOURCE_OBJECT_NAME", source_object_name),
+        ("HOT_RETENTION_MONTHS", req.hot_retention.months.to_string()),
+        ("DATA_PATH", data_path.to_string_lossy().to_string()),
+        ("LOG_PATH", logs_path.to_string_lossy().to_string()),
+        ("ARCHIVE_PATH", archive_path.to_string_lossy().to_string()),
+        ("IMPORT_PATH", import_path.to_string_lossy().to_string()),
+        ("WEB_PORT", "8080".to_string()),
+        ("INSTALL_ID", install_id.to_string()),
+        ("INSTANCE_SUFFIX", crate::utils::instance::qualify("", "-")),
+    ];
+    raw.into_iter()
+        .map(|(k, v)| (k.to_string(), compose_value(&v)))
+        .collect()
+}
+
+/// Host folders bind-mounted into the worker: the archive destination (default
+/// `<data>/archive`) and the export folder for file sources (default `<data>/import`).
+fn compose_mount_paths(req: &StartInstallRequest, data_path: &Path) -> (PathBuf, PathBuf) {
+    let archive = match req.archive_policy.destination_path.trim() {
+        "" => data_path.join("archive"),
+        p => PathBuf::from(p),
+    };
+    let import = match &req.file_source {
+        Some(fs) => PathBuf::from(&fs.folder),
+        None => data_path.join("import"),
+    };
+    (archive, import)
+}
+
+/// Escape a value for a double-quoted YAML string in a compose file.
+fn compose_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "$$")
+}
+
+/// Images referenced by `image:` keys in compose file text, in order, without duplicates.
+pub fn compose_images(compose_text: &str) -> Vec<String> {
+    let mut images: Vec<String> = Vec::new();
+    for line in compose_text.lines() {
+        let Some(rest) = line.trim().strip_prefix("image:") else {
+            continue;
+        };
+        let image = rest
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string();
+        if !image.is_empty() && !images.contains(&image) {
+            images.push(image);
+        }
+    }
+    images
+}
+
 /// Substitute {{VAR}} placeholders in a string with values from a map.
 pub fn substitute_placeholders(template: &str, variables: &HashMap<String, String>) -> String {
     let mut result = template.to_string();
@@ -546,8 +661,9 @@ pub fn parse_docker_load_output(stdout: &str) -> Vec<String> {
 
 /// Wait for Docker containers to become healthy/running.
 ///
-/// Polls `docker compose ps` every few seconds until containers are running
-/// or the timeout is reached.
+/// Polls `docker compose ps` every few seconds until every container is running and none is
+/// still in its healthcheck start period, or the timeout is reached. A container whose
+/// healthcheck reports unhealthy fails the wait straight away, with its recent logs.
 pub async fn wait_for_containers_healthy(
     compose_path: &Path,
     timeout_secs: u64,
@@ -590,7 +706,35 @@ pub async fn wait_for_containers_healthy(
         if ps_result.exit_code == Some(0) {
             let status = parse_compose_ps_output(&ps_result.stdout);
 
-            if status.all_running && status.container_count > 0 {
+            let unhealthy: Vec<&ContainerStatus> = status
+                .containers
+                .iter()
+                .filter(|c| c.health.as_deref() == Some("unhealthy"))
+                .collect();
+            if !unhealthy.is_empty() {
+                let mut details = String::new();
+                for c in &unhealthy {
+                    let logs = get_container_logs(&c.name, 50)
+                        .await
+                        .unwrap_or_else(|e| format!("(unable to get logs: {})", e));
+                    details.push_str(&format!(
+                        "\n--- {} (last 50 log lines) ---\n{}",
+                        c.name,
+                        logs.trim()
+                    ));
+                }
+                anyhow::bail!(
+                    "Docker/Linux installation failed: container(s) reported unhealthy: {}.\n{}",
+                    unhealthy
+                        .iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    details
+                );
+            }
+
+            if status.all_healthy {
                 info!(
                     "[PHASE: installation] [STEP: docker] wait_for_containers_healthy exit ok (containers={}, elapsed={}ms)",
                     status.container_count,
@@ -600,8 +744,8 @@ pub async fn wait_for_containers_healthy(
             }
 
             debug!(
-                "[PHASE: installation] [STEP: docker] Containers not ready yet (running={}, count={})",
-                status.all_running, status.container_count
+                "[PHASE: installation] [STEP: docker] Containers not ready yet (running={}, healthy={}, count={})",
+                status.all_running, status.all_healthy, status.container_count
             );
         }
 
@@ -614,6 +758,8 @@ pub async fn wait_for_containers_healthy(
 pub struct ComposePsStatus {
     pub container_count: usize,
     pub all_running: bool,
+    /// Every container is running and none is starting up or unhealthy per its healthcheck.
+    pub all_healthy: bool,
     pub containers: Vec<ContainerStatus>,
 }
 
@@ -622,6 +768,8 @@ pub struct ComposePsStatus {
 pub struct ContainerStatus {
     pub name: String,
     pub state: String,
+    /// "healthy" | "unhealthy" | "starting"; `None` when the container has no healthcheck.
+    pub health: Option<String>,
 }
 
 /// Parse `docker compose ps` output to determine container status.
@@ -664,20 +812,39 @@ pub fn parse_compose_ps_output(stdout: &str) -> ComposePsStatus {
                 } else {
                     "unknown".to_string()
                 };
+                // Status column suffix: "Up 2 minutes (healthy)", "(health: starting)", ...
+                let health = if line_lower.contains("(unhealthy)") {
+                    Some("unhealthy".to_string())
+                } else if line_lower.contains("(health: starting)") {
+                    Some("starting".to_string())
+                } else if line_lower.contains("(healthy)") {
+                    Some("healthy".to_string())
+                } else {
+                    None
+                };
 
                 // Skip header-like lines
                 if name.to_uppercase() != "NAME" && name.to_uppercase() != "CONTAINER" {
-                    containers.push(ContainerStatus { name, state });
+                    containers.push(ContainerStatus {
+                        name,
+                        state,
+                        health,
+                    });
                 }
             }
         }
     }
 
     let all_running = !containers.is_empty() && containers.iter().all(|c| c.state == "running");
+    let all_healthy = all_running
+        && containers
+            .iter()
+            .all(|c| !matches!(c.health.as_deref(), Some("starting") | Some("unhealthy")));
 
     ComposePsStatus {
         container_count: containers.len(),
         all_running,
+        all_healthy,
         containers,
     }
 }
@@ -726,10 +893,10 @@ use crate::api::installer::{InstallArtifacts, ProgressEmitter, ProgressPayload,
 /// 1. Verify Docker installed + daemon running
 /// 2. Ensure runtime docker folders exist
 /// 3. Create data directories
-/// 4. Generate docker-compose.yml from template
-/// 5. Load Docker images from .tar files (if present)
+/// 4. Generate docker-compose.yml from the wizard answers (runtime or bundled template)
+/// 5. Load Docker images from .tar files (if present), then pull any still missing
 /// 6. Run compose up -d
-/// 7. Wait for containers ready
+/// 7. Wait for containers running and healthy
 /// 8. Return InstallArtifacts
 pub async fn install_docker_mode(
     req: &StartInstallRequest,
@@ -771,17 +938,14 @@ pub async fn install_docker_mode(
     }
 
     // Step 2: Locate runtime docker folders
-    // These are relative to the executable or a known runtime location
-    let runtime_base = locate_docker_runtime_dir()?;
-    let template_path = runtime_base.join("compose").join("docker-compose.template.yml");
-    let images_dir = runtime_base.join("images");
-
-    if !template_path.exists() {
-        anyhow::bail!(
-            "Docker compose template not found: {:?}. Ensure runtime assets are present.",
-            template_path
-        );
-    }
+    // These are relative to the executable or a known runtime location. Without them the
+    // bundled template is used and every image is pulled from the registry.
+    let runtime_base = locate_docker_runtime_dir().ok();
+    let template_path = runtime_base
+        .as_ref()
+        .map(|d| d.join("compose").join("docker-compose.template.yml"))
+        .filter(|p| p.exists());
+    let images_dir = runtime_base.as_ref().map(|d| d.join("images"));
 
     // Step 3: Create data directories
     emit_progress(ProgressPayload {
@@ -820,17 +984,30 @@ pub async fn install_docker_mode(
     let compose_output = dest_root.join("docker-compose.yml");
     let install_id = uuid::Uuid::new_v4().to_string();
 
-    let mut variables = HashMap::new();
-    variables.insert("DB_CONNECTION_STRING".to_string(), req.config_db_connection_string.clone());
-    variables.insert("DATA_PATH".to_string(), data_path.to_string_lossy().to_string());
-    variables.insert("LOG_PATH".to_string(), logs_path.to_string_lossy().to_string());
-    variables.insert("WEB_PORT".to_string(), "8080".to_string());
-    variables.insert("INSTALL_ID".to_string(), install_id.clone());
+    let variables = compose_variables(req, &data_path, &logs_path, &install_id);
+    let (archive_path, import_path) = compose_mount_paths(req, &data_path);
+    for dir in [archive_path, import_path] {
+        // Bind mounts fail at `compose up` if the host folder is missing.
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to create directory: {:?}", dir))?;
+    }
 
-    generate_compose_file(&template_path, &compose_output, &variables).await?;
+    match &template_path {
+        Some(template_path) => {
+            generate_compose_file(template_path, &compose_output, &variables).await?
+        }
+        None => {
+            info!(
+                "[PHASE: installation] [STEP: docker] No runtime compose template; using the bundled template"
+            );
+            write_compose_from_template(BUNDLED_COMPOSE_TEMPLATE, &compose_output, &variables)
+                .await?
+        }
+    }
 
     // Step 5: Load Docker images from .tar files (if present)
-    if images_dir.exists() {
+    if let Some(images_dir) = images_dir.filter(|d| d.exists()) {
         let mut has_tar_files = false;
         if let Ok(mut entries) = tokio::fs::read_dir(&images_dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
@@ -857,6 +1034,53 @@ pub async fn install_docker_mode(
         }
     }
 
+    // Step 5b: Pull images the compose file references that are not present locally
+    let compose_text = tokio::fs::read_to_string(&compose_output)
+        .await
+        .with_context(|| format!("Failed to read compose file: {:?}", compose_output))?;
+    let images = compose_images(&compose_text);
+    let total = images.len();
+    for (idx, image) in images.iter().enumerate() {
+        if docker_image_present(image).await? {
+            debug!(
+                "[PHASE: installation] [STEP: docker] Image already present: {}",
+                image
+            );
+            continue;
+        }
+        super::offline_bundle::ensure_network_allowed(&format!(
+            "Image '{}' is not in the bundle's runtime/linux/docker/images folder; pulling it",
+            image
+        ))?;
+        emit_progress(ProgressPayload {
+            correlation_id: correlation_id.to_string(),
+            step: "docker_pull".to_string(),
+            severity: "info".to_string(),
+            phase: "install".to_string(),
+            percent: 60 + ((idx * 10) / total) as i32,
+            message: format!(
+                "Docker/Linux: Pulling image {}/{}: {}",
+                idx + 1,
+                total,
+                image
+            ),
+            elapsed_ms: Some(started.elapsed().as_millis()),
+            eta_ms: None,
+        });
+        info!(
+            "[PHASE: installation] [STEP: docker] Pulling image {}/{}: {}",
+            idx + 1,
+            total,
+            image
+        );
+        docker_pull(image).await.with_context(|| {
+            format!(
+                "Image '{}' is not loaded and could not be pulled. For offline installs, place its .tar in the runtime images folder.",
+                image
+            )
+        })?;
+    }
+
     // Step 6: Run compose up -d
     emit_progress(ProgressPayload {
         correlation_id: correlation_id.to_string(),
@@ -884,7 +1108,7 @@ pub async fn install_docker_mode(
         eta_ms: None,
     });
 
-    wait_for_containers_healthy(&compose_output, 120).await?;
+    wait_for_containers_healthy(&compose_output, CONTAINER_READY_TIMEOUT_SECS).await?;
 
     // Step 8: Return artifacts
     info!(
@@ -898,16 +1122,26 @@ pub async fn install_docker_mode(
         manifest_path: None,
         mapping_path: None,
         config_path: Some(compose_output.to_string_lossy().to_string()),
+        plan_path: None,
     })
 }
 
 /// Locate the Docker runtime directory.
 ///
 /// Searches in order:
+/// 0. runtime/linux/docker inside the active offline bundle (the only place searched then)
 /// 1. CADALYTIX_RUNTIME_DIR environment variable
 /// 2. runtime/linux/docker relative to executable
 /// 3. ../runtime/linux/docker relative to executable (for dev builds)
 pub fn locate_docker_runtime_dir() -> Result<std::path::PathBuf> {
+    if let Some(bundle) = super::offline_bundle::active_root() {
+        let docker_dir = bundle.join("runtime").join("linux").join("docker");
+        if docker_dir.exists() {
+            return Ok(docker_dir);
+        }
+        anyhow::bail!("Offline bundle has no runtime/linux/docker folder");
+    }
+
     // Check environment variable first
     if let Ok(runtime_dir) = std::env::var("CADALYTIX_RUNTIME_DIR") {
         let docker_dir = std::path::PathBuf::from(&runtime_dir).join("linux").join("docker");
@@ -1152,6 +1386,91 @@ mod tests {
         assert!(status.all_running);
     }
 
+    #[test]
+    fn parse_compose_ps_output_reads_healthcheck_state() {
+        let stdout = "NAME               IMAGE                     SERVICE            STATUS\n\
+                      cadalytix-web      cadalytix/web:latest      cadalytix-web      Up 20 seconds (health: starting)\n\
+                      cadalytix-worker   cadalytix/worker:latest   cadalytix-worker   Up 20 seconds (healthy)\n";
+        let status = parse_compose_ps_output(stdout);
+        assert!(status.all_running);
+        assert!(!status.all_healthy);
+        assert_eq!(status.containers[0].health.as_deref(), Some("starting"));
+        assert_eq!(status.containers[1].health.as_deref(), Some("healthy"));
+
+        let status = parse_compose_ps_output(&stdout.replace("(health: starting)", "(unhealthy)"));
+        assert_eq!(status.containers[0].health.as_deref(), Some("unhealthy"));
+        assert!(!status.all_healthy);
+
+        let status = parse_compose_ps_output(&stdout.replace("(health: starting)", "(healthy)"));
+        assert!(status.all_healthy);
+    }
+
+    // ========================================================================
+    // Compose generation from wizard answers
+    // ========================================================================
+
+    fn docker_request(extra: serde_json::Value) -> StartInstallRequest {
+        let mut value = serde_json::json!({
+            "installMode": "docker",
+            "installationType": "typical",
+            "destinationFolder": "/opt/cadalytix",
+            "configDbConnectionString": "Server=db;Password=pa$s\"word",
+            "callDataConnectionString": "Server=cad;Database=CAD",
+            "sourceObjectName": "dbo.Calls",
+            "storage": {
+                "mode": "defaults",
+                "location": "system",
+                "customPath": "",
+                "retentionPolicy": "18",
+                "maxDiskGb": ""
+            },
+            "hotRetention": { "months": 12 },
+            "mappings": {},
+            "mappingOverride": false
+        });
+        for (k, v) in extra.as_object().unwrap() {
+            value[k] = v.clone();
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn compose_variables_escape_values_and_fill_the_bundled_template() {
+        let req = docker_request(serde_json::json!({}));
+        let data = Path::new("/opt/cadalytix/data");
+        let vars = compose_variables(&req, data, Path::new("/opt/cadalytix/logs"), "id-1");
+        assert_eq!(
+            vars["DB_CONNECTION_STRING"],
+            "Server=db;Password=pa$$s\\\"word"
+        );
+        assert_eq!(vars["SOURCE_OBJECT_NAME"], "dbo.Calls");
+        assert_eq!(vars["HOT_RETENTION_MONTHS"], "12");
+        assert_eq!(vars["ARCHIVE_PATH"], "/opt/cadalytix/data/archive");
+        assert_eq!(vars["IMPORT_PATH"], "/opt/cadalytix/data/import");
+
+        let rendered = substitute_placeholders(BUNDLED_COMPOSE_TEMPLATE, &vars);
+        assert!(find_unresolved_placeholder(&rendered).is_none());
+        assert_eq!(
+            compose_images(&rendered),
+            vec!["cadalytix/web:latest", "cadalytix/worker:latest"]
+        );
+
+        let req = docker_request(serde_json::json!({
+            "fileSource": { "folder": "/srv/cad-exports" },
+            "archivePolicy": {
+                "format": "zip+ndjson",
+                "destinationPath": "/mnt/archive",
+                "maxUsageGb": 100,
+                "schedule": { "dayOfMonth": 1, "timeLocal": "02:00" },
+                "catchUpOnStartup": true
+            }
+        }));
+        let vars = compose_variables(&req, data, Path::new("/opt/cadalytix/logs"), "id-1");
+        assert_eq!(vars["CALL_DATA_CONNECTION_STRING"], "");
+        assert_eq!(vars["IMPORT_PATH"], "/srv/cad-exports");
+        assert_eq!(vars["ARCHIVE_PATH"], "/mnt/archive");
+    }
+
     // ========================================================================
     // Phase 3 Finish: Additional edge case tests
     // ========================================================================
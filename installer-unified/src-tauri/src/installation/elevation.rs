@@ -0,0 +1,600 @@
+//! Root-only install steps for a wizard run by an unprivileged user (Linux).
+//!
+//! Before the install starts, `plan` lists the commands that need root: creating the install
+//! folders the user cannot write (they are then handed to the user, so the deploy itself runs
+//! unprivileged) and, in the Linux install mode, the service batch. That batch creates a system
+//! account named after the service, hands the binaries folder back to root, gives the data, log
+//! and temp folders to the account, applies the SELinux labels and installs the systemd unit
+//! (`User=` the account, written by root into a root-only staging folder) or the native package.
+//! The service thus never runs as root, and nothing it runs stays writable by the installing user.
+//!
+//! The wizard shows the exact commands and asks for consent; the executable is resolved from the
+//! runtime payload for that. `approve` then authenticates once and runs the folder batch; at the
+//! service step `register_service` rebuilds the service batch, refuses to run it when it differs
+//! from the approved one, and runs it with the same tool.
+//!
+//! `sudo` is preferred: its password prompt comes once, and the credentials are kept fresh for
+//! the service batch. Without sudo, `pkexec` (polkit) runs each batch and may ask again for the
+//! service batch. As root, or with passwordless sudo, there is no plan and every step runs
+//! directly as before.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use log::info;
+use tokio::time::Duration;
+
+use crate::api::installer::StartInstallRequest;
+use crate::installation::layout::{self, InstallLayout};
+use crate::installation::linux::{check_sudo_available, is_running_as_root};
+use crate::installation::mac_policy::{self, SelinuxMode};
+use crate::installation::native_package::{self, NativePackage, PackageKind};
+use crate::installation::{run_cmd_with_timeout, service};
+use crate::security::audit::{self, AuditAction};
+
+const BATCH_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// sudo's default credential timeout is 5 to 15 minutes; refresh well within it.
+const SUDO_REFRESH: Duration = Duration::from_secs(60);
+
+static APPROVED: OnceLock<ElevationPlan> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escalator {
+    Sudo,
+    Pkexec,
+}
+
+impl Escalator {
+    pub fn program(self) -> &'static str {
+        match self {
+            Escalator::Sudo => "sudo",
+            Escalator::Pkexec => "pkexec",
+        }
+    }
+
+    /// sudo when installed, else pkexec.
+    pub fn detect() -> Option<Self> {
+        [Escalator::Sudo, Escalator::Pkexec]
+            .into_iter()
+            .find(|e| which::which(e.program()).is_ok())
+    }
+}
+
+/// One root-only command; `or_else` runs when it fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivilegedStep {
+    pub reason: String,
+    pub argv: Vec<String>,
+    pub or_else: Option<Vec<String>>,
+}
+
+impl PrivilegedStep {
+    fn new(reason: impl Into<String>, argv: &[&str]) -> Self {
+        Self {
+            reason: reason.into(),
+            argv: argv.iter().map(|a| a.to_string()).collect(),
+            or_else: None,
+        }
+    }
+
+    /// The command as it appears in the batch script.
+    pub fn command_line(&self) -> String {
+        let join = |argv: &[String]| argv.iter().map(|a| quote(a)).collect::<Vec<_>>().join(" ");
+        match &self.or_else {
+            Some(alt) => format!("{} 2>/dev/null || {}", join(&self.argv), join(alt)),
+            None => join(&self.argv),
+        }
+    }
+}
+
+/// Root-only steps of one install, in the order they run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElevationPlan {
+    pub escalator: Escalator,
+    /// Run on consent, before the install starts.
+    pub folders: Vec<PrivilegedStep>,
+    /// Run at the service step (Linux install mode).
+    pub service: Vec<PrivilegedStep>,
+}
+
+impl ElevationPlan {
+    /// `# reason` / command pairs, for the consent prompt.
+    pub fn lines(&self) -> Vec<String> {
+        self.folders
+            .iter()
+            .chain(&self.service)
+            .flat_map(|s| [format!("# {}", s.reason), s.command_line()])
+            .collect()
+    }
+}
+
+/// POSIX shell quoting (single quotes) when `arg` has anything but safe characters.
+pub fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "/._-+=:,@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// `sh -c` script of `steps`; the first failure stops it.
+pub fn script(steps: &[PrivilegedStep]) -> String {
+    std::iter::once("set -e".to_string())
+        .chain(steps.iter().map(PrivilegedStep::command_line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Root-only steps of an install, or `None` when every step can run as is (root, passwordless
+/// sudo, a dry run or remote install, or nothing outside the user's reach). Fails when steps
+/// need root and neither sudo nor pkexec is installed.
+pub async fn plan(req: &StartInstallRequest) -> Result<Option<ElevationPlan>> {
+    if req.dry_run || req.remote.is_some() || is_running_as_root() || check_sudo_available().await?
+    {
+        return Ok(None);
+    }
+    let layout = layout::resolve(req);
+    let folders = folder_steps(&layout, &current_owner(), &writable);
+    let service = if req.install_mode.trim().eq_ignore_ascii_case("linux") {
+        let exe = payload_exe(req, &layout).await?;
+        let name = service::linux_service_name();
+        let package = native_package::selected_kind()?.map(|kind| {
+            (
+                kind,
+                native_package::package_file(kind, &name, &package_dir(&layout.binaries)),
+            )
+        });
+        service_steps(&name, &layout, &exe, package, labeling())?
+    } else {
+        Vec::new()
+    };
+    if folders.is_empty() && service.is_empty() {
+        return Ok(None);
+    }
+    let Some(escalator) = Escalator::detect() else {
+        anyhow::bail!(
+            "Installing as this user needs root for {} step(s), and neither sudo nor pkexec is installed. Run the installer as root.",
+            folders.len() + service.len()
+        );
+    };
+    Ok(Some(ElevationPlan {
+        escalator,
+        folders,
+        service,
+    }))
+}
+
+/// Consent given: authenticate, prepare the folders, and route the service step through the
+/// plan's tool. Interactive (password prompt on the terminal).
+pub async fn approve(plan: &ElevationPlan) -> Result<()> {
+    if plan.escalator == Escalator::Sudo {
+        let status = tokio::process::Command::new("sudo")
+            .arg("-v")
+            .status()
+            .await
+            .context("Failed to run sudo")?;
+        if !status.success() {
+            anyhow::bail!("sudo authentication failed");
+        }
+        keep_sudo_fresh();
+    }
+    if !plan.folders.is_empty() {
+        run_batch(plan.escalator, &plan.folders, "elevated_folders").await?;
+    }
+    let _ = APPROVED.set(plan.clone());
+    info!(
+        "[PHASE: installation] [STEP: elevation] Approved {} root-only step(s) via {}",
+        plan.folders.len() + plan.service.len(),
+        plan.escalator.program()
+    );
+    Ok(())
+}
+
+/// Tool the user approved for root-only steps, if any (see `approve`).
+pub fn approved() -> Option<Escalator> {
+    APPROVED.get().map(|plan| plan.escalator)
+}
+
+/// Where the deploy will put the server executable: the first known name in the runtime payload,
+/// under the binaries folder.
+async fn payload_exe(req: &StartInstallRequest, layout: &InstallLayout) -> Result<PathBuf> {
+    let (shared, platform) =
+        crate::api::installer::resolve_runtime_payload_roots(&req.install_mode).await?;
+    for root in [&platform, &shared] {
+        if let Some(found) = service::find_linux_service_exe(root).await {
+            let rel = found.strip_prefix(root).unwrap_or(&found);
+            return Ok(layout.binaries.join(rel));
+        }
+    }
+    anyhow::bail!(
+        "The Linux runtime payload in {:?} has no server executable to register as a service.",
+        platform
+    )
+}
+
+/// Register and start the unit (or install the native package) as one batch through
+/// `escalator`. The package is built as the current user first; the batch must match the one
+/// the user approved.
+pub async fn register_service(
+    escalator: Escalator,
+    service_name: &str,
+    exe: &Path,
+    layout: &InstallLayout,
+    payload_files: Vec<String>,
+) -> Result<()> {
+    let dest_root = &layout.binaries;
+    let unit_text =
+        service::build_systemd_unit_text(service_name, exe, dest_root, Some(service_name));
+    let package = match native_package::selected_kind()? {
+        Some(kind) => {
+            let pkg = NativePackage {
+                name: service_name.to_string(),
+                dest_root: dest_root.clone(),
+                files: payload_files,
+                unit_text: unit_text.clone(),
+            };
+            let file = native_package::build(kind, &pkg, &package_dir(dest_root)).await?;
+            Some((kind, file))
+        }
+        None => None,
+    };
+    let manager = package
+        .as_ref()
+        .map_or("systemd", |(kind, _)| kind.manager());
+    let steps = service_steps(service_name, layout, exe, package, labeling())?;
+    if APPROVED.get().is_some_and(|plan| plan.service != steps) {
+        anyhow::bail!(
+            "The service commands for {:?} differ from the ones approved before the install; nothing was run as root. Run the installer again to review them.",
+            exe
+        );
+    }
+    let result = run_batch(escalator, &steps, "elevated_service").await;
+    audit::record_result(
+        AuditAction::ServiceRegister,
+        service_name,
+        serde_json::json!({
+            "manager": manager,
+            "escalator": escalator.program(),
+            "execPath": exe.to_string_lossy(),
+        }),
+        &result,
+    )
+    .await;
+    result?;
+    if !service::is_linux_service_running(service_name).await? {
+        anyhow::bail!(
+            "Service '{}' is not running after start. Check logs with: journalctl -u {}",
+            service_name,
+            service_name
+        );
+    }
+    Ok(())
+}
+
+/// How the SELinux labels are applied (see `mac_policy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Labeling {
+    Off,
+    Semanage,
+    Chcon,
+}
+
+fn labeling() -> Labeling {
+    if mac_policy::selinux_mode() == SelinuxMode::Disabled {
+        Labeling::Off
+    } else if which::which("semanage").is_ok() {
+        Labeling::Semanage
+    } else {
+        Labeling::Chcon
+    }
+}
+
+/// `mkdir -p` and `chown -R` to `owner` for each layout folder the user cannot write, so the
+/// deploy can copy into it. The service batch takes them back (see `service_steps`).
+pub fn folder_steps(
+    layout: &InstallLayout,
+    owner: &str,
+    writable: &dyn Fn(&Path) -> bool,
+) -> Vec<PrivilegedStep> {
+    let mut seen: Vec<&Path> = Vec::new();
+    let mut steps = Vec::new();
+    for (role, dir) in layout.entries() {
+        if seen.contains(&dir) || writable(dir) {
+            continue;
+        }
+        seen.push(dir);
+        let path = dir.to_string_lossy();
+        steps.push(PrivilegedStep::new(
+            format!("Create the {} folder", role),
+            &["mkdir", "-p", &path],
+        ));
+        steps.push(PrivilegedStep::new(
+            format!(
+                "Hand the {} folder to the installing user for the copy",
+                role
+            ),
+            &["chown", "-R", owner, &path],
+        ));
+    }
+    steps
+}
+
+/// The service account and folder ownership, the labels, then the package install or the unit
+/// and its systemctl commands. The account is named after the service. Fails when the data, log
+/// or temp folder holds the binaries folder: the account would own the executable.
+pub fn service_steps(
+    service_name: &str,
+    layout: &InstallLayout,
+    exe: &Path,
+    package: Option<(PackageKind, PathBuf)>,
+    labeling: Labeling,
+) -> Result<Vec<PrivilegedStep>> {
+    let account = service_name;
+    let binaries = layout.binaries.to_string_lossy();
+    let mut steps = vec![
+        PrivilegedStep {
+            reason: format!("Create the {} system account the service runs as", account),
+            argv: vec!["id".to_string(), "-u".to_string(), account.to_string()],
+            or_else: Some(
+                [
+                    "useradd",
+                    "--system",
+                    "--no-create-home",
+                    "--shell",
+                    "/usr/sbin/nologin",
+                    account,
+                ]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            ),
+        },
+        PrivilegedStep::new(
+            "Hand the binaries folder back to root (the service account may only read it)",
+            &["chown", "-R", &format!("root:{}", account), &binaries],
+        ),
+        PrivilegedStep::new(
+            "Make the binaries folder read-only to everyone but root",
+            &["chmod", "-R", "g+rX,go-w", &binaries],
+        ),
+    ];
+    let mut seen: Vec<&Path> = Vec::new();
+    // Every folder but the binaries (listed first).
+    for (role, dir) in layout.entries().into_iter().skip(1) {
+        if seen.contains(&dir) {
+            continue;
+        }
+        if layout.binaries.starts_with(dir) {
+            anyhow::bail!(
+                "The {} folder {:?} contains the binaries folder; the service account would own the executable. Choose a separate {} folder.",
+                role,
+                dir,
+                role
+            );
+        }
+        seen.push(dir);
+        steps.push(PrivilegedStep::new(
+            format!("Give the {} folder to the {} account", role, account),
+            &[
+                "chown",
+                "-R",
+                &format!("{0}:{0}", account),
+                &dir.to_string_lossy(),
+            ],
+        ));
+    }
+    for rule in mac_policy::fcontext_rules(layout, exe) {
+        let path = rule.path.to_string_lossy();
+        match labeling {
+            Labeling::Off => {}
+            Labeling::Semanage => {
+                let fcontext = |flag: &str| -> Vec<String> {
+                    [
+                        "semanage",
+                        "fcontext",
+                        flag,
+                        "-t",
+                        rule.setype,
+                        rule.spec.as_str(),
+                    ]
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect()
+                };
+                // `-a` fails for a rule an earlier install added; `-m` updates it.
+                steps.push(PrivilegedStep {
+                    reason: format!("SELinux file context {} for {}", rule.setype, path),
+                    argv: fcontext("-a"),
+                    or_else: Some(fcontext("-m")),
+                });
+                steps.push(PrivilegedStep::new(
+                    format!("Apply the SELinux labels to {}", path),
+                    &["restorecon", "-R", "-F", &path],
+                ));
+            }
+            Labeling::Chcon => steps.push(PrivilegedStep::new(
+                format!("SELinux label {} for {}", rule.setype, path),
+                &["chcon", "-R", "-t", rule.setype, &path],
+            )),
+        }
+    }
+
+    if let Some((kind, file)) = package {
+        let (program, args) = native_package::install_command(kind, &file);
+        let mut argv = vec![program];
+        argv.extend(args.iter().map(String::as_str));
+        steps.push(PrivilegedStep::new(
+            format!(
+                "Install the {} package (enables and starts the service)",
+                program
+            ),
+            &argv,
+        ));
+        return Ok(steps);
+    }
+    let unit_text =
+        service::build_systemd_unit_text(service_name, exe, &layout.binaries, Some(account));
+    let stage = unit_stage_dir(service_name);
+    let staged = stage.join(format!("{}.service", service_name));
+    let (stage, staged) = (stage.to_string_lossy(), staged.to_string_lossy());
+    let unit_path = format!("/etc/systemd/system/{}.service", service_name);
+    steps.push(PrivilegedStep::new(
+        "Create a root-only staging folder for the unit",
+        &[
+            "install", "-d", "-m", "0700", "-o", "root", "-g", "root", &stage,
+        ],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Write the systemd unit into it",
+        &[
+            "sh",
+            "-c",
+            r#"printf '%s' "$1" > "$2""#,
+            "sh",
+            &unit_text,
+            &staged,
+        ],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Install the systemd unit",
+        &["install", "-m", "0644", &staged, &unit_path],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Remove the staging folder",
+        &["rm", "-rf", &stage],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Reload systemd",
+        &["systemctl", "daemon-reload"],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Start the service at boot",
+        &["systemctl", "enable", service_name],
+    ));
+    steps.push(PrivilegedStep::new(
+        "Start the service",
+        &["systemctl", "restart", service_name],
+    ));
+    Ok(steps)
+}
+
+async fn run_batch(escalator: Escalator, steps: &[PrivilegedStep], operation: &str) -> Result<()> {
+    let mut args = match escalator {
+        Escalator::Sudo => vec!["-n".to_string()],
+        Escalator::Pkexec => Vec::new(),
+    };
+    args.extend(["/bin/sh".to_string(), "-c".to_string(), script(steps)]);
+    let out = run_cmd_with_timeout(escalator.program(), &args, BATCH_TIMEOUT, operation).await?;
+    if out.exit_code != Some(0) {
+        anyhow::bail!(
+            "{} via {} failed (exit_code={:?}): {}",
+            operation,
+            escalator.program(),
+            out.exit_code,
+            out.stderr.trim()
+        );
+    }
+    Ok(())
+}
+
+/// `sudo -n -v` in the background so the service batch finds the credentials still cached.
+fn keep_sudo_fresh() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(SUDO_REFRESH);
+        let _ = std::process::Command::new("sudo")
+            .args(["-n", "-v"])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    });
+}
+
+fn current_owner() -> String {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    format!("{}:{}", uid, gid)
+}
+
+/// Whether the user can create or write `dir` (judged by its nearest existing ancestor).
+fn writable(dir: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        return false;
+    };
+    let Ok(c_path) = std::ffi::CString::new(existing.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// Under `/run`, which only root can write: nobody else can create or swap the staged unit.
+fn unit_stage_dir(service_name: &str) -> PathBuf {
+    PathBuf::from("/run").join(format!("{}-installer", service_name))
+}
+
+fn package_dir(dest_root: &Path) -> PathBuf {
+    dest_root.join("installer-artifacts").join("native-package")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_lists_folder_and_service_commands() {
+        let layout = InstallLayout {
+            binaries: PathBuf::from("/opt/cadalytix"),
+            data: PathBuf::from("/opt/cadalytix/data"),
+            logs: PathBuf::from("/var/log/cadalytix"),
+            temp: PathBuf::from("/tmp/cadalytix"),
+        };
+        let folders = folder_steps(&layout, "1000:1000", &|p: &Path| p.starts_with("/tmp"));
+        let commands: Vec<String> = folders.iter().map(|s| s.command_line()).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "mkdir -p /opt/cadalytix",
+                "chown -R 1000:1000 /opt/cadalytix",
+                "mkdir -p /opt/cadalytix/data",
+                "chown -R 1000:1000 /opt/cadalytix/data",
+                "mkdir -p /var/log/cadalytix",
+                "chown -R 1000:1000 /var/log/cadalytix",
+            ]
+        );
+
+        let exe = Path::new("/opt/cadalytix/cadalytix");
+        let steps = service_steps("cadalytix", &layout, exe, None, Labeling::Semanage).unwrap();
+        let script = script(&steps);
+        assert!(script.starts_with(
+            "set -e\nid -u cadalytix 2>/dev/null || useradd --system --no-create-home --shell /usr/sbin/nologin cadalytix\nchown -R root:cadalytix /opt/cadalytix\nchmod -R g+rX,go-w /opt/cadalytix\nchown -R cadalytix:cadalytix /opt/cadalytix/data\nchown -R cadalytix:cadalytix /var/log/cadalytix\nchown -R cadalytix:cadalytix /tmp/cadalytix\n"
+        ));
+        assert!(script.contains(
+            "semanage fcontext -a -t bin_t /opt/cadalytix/cadalytix 2>/dev/null || semanage fcontext -m -t bin_t /opt/cadalytix/cadalytix"
+        ));
+        assert!(script.contains("semanage fcontext -a -t usr_t '/opt/cadalytix(/.*)?'"));
+        assert!(script.contains("\nUser=cadalytix\n"));
+        assert!(script.ends_with(
+            "install -m 0644 /run/cadalytix-installer/cadalytix.service /etc/systemd/system/cadalytix.service\nrm -rf /run/cadalytix-installer\nsystemctl daemon-reload\nsystemctl enable cadalytix\nsystemctl restart cadalytix"
+        ));
+        let write = steps.iter().find(|s| s.argv[0] == "sh").unwrap();
+        assert!(write.argv[4].contains("ExecStart=/opt/cadalytix/cadalytix"));
+        assert_eq!(write.argv[5], "/run/cadalytix-installer/cadalytix.service");
+
+        let package = Some((PackageKind::Deb, PathBuf::from("/opt/cadalytix/c.deb")));
+        let steps = service_steps("cadalytix", &layout, exe, package, Labeling::Off).unwrap();
+        assert_eq!(steps.len(), 7);
+        assert_eq!(steps[6].command_line(), "dpkg -i /opt/cadalytix/c.deb");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+
+        for data in ["/opt", "/opt/cadalytix"] {
+            let shared = InstallLayout {
+                data: PathBuf::from(data),
+                ..layout.clone()
+            };
+            let err = service_steps("cadalytix", &shared, exe, None, Labeling::Off).unwrap_err();
+            assert!(err.to_string().contains("contains the binaries folder"));
+        }
+    }
+}
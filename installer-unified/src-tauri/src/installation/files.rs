@@ -4,17 +4,29 @@
 //! - Async I/O only (tokio)
 //! - Retry transient file lock errors (Windows AV/indexers, etc.)
 //! - Timeout all operations (plan: 60s file ops default)
+//! - Honor the MB/s write throttle (`CADALYTIX_IO_THROTTLE_MBPS`, see `utils::throttle`)
+//! - Copy large payloads with bounded concurrency (`CADALYTIX_COPY_WORKERS`), re-hashing every
+//!   deployed file before it counts as done
+//! - On upgrades, skip files already deployed with the same size and checksum (`plan_delta`)
 //! - Preserve permissions on Unix best-effort
 //! - Never fail silently (log with context)
 
 use anyhow::{Context, Result};
-use log::{debug, warn};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use log::{debug, info, warn};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::time::{timeout, Duration};
 
+use crate::error::{InstallerError, OrCode};
+use crate::utils::metrics;
+use crate::utils::throttle::IoThrottle;
+
+const COPY_WORKERS_ENV: &str = "CADALYTIX_COPY_WORKERS";
+const DEFAULT_COPY_WORKERS: usize = 4;
+
 /// Recursively collect all regular files under `root`.
 ///
 /// Returns absolute paths.
@@ -73,9 +85,16 @@ pub async fn copy_file_with_retries(src: &Path, dst: &Path, label: &str) -> Resu
         label, src, dst
     );
 
+    let throttle = IoThrottle::from_env();
+    let src_len = tokio::fs::metadata(src).await.map(|m| m.len()).unwrap_or(0);
+    let timeout_dur = Duration::from_secs(60) + throttle.min_duration(src_len);
+
     let mut last_err: Option<anyhow::Error> = None;
     for attempt in 1..=3 {
-        let res = timeout(Duration::from_secs(60), tokio::fs::copy(src, dst)).await;
+        if attempt > 1 {
+            metrics::note_retry();
+        }
+        let res = timeout(timeout_dur, copy_file_once(src, dst, &throttle)).await;
         match res {
             Ok(Ok(n)) => {
                 // Best-effort permissions preservation.
@@ -97,10 +116,10 @@ pub async fn copy_file_with_retries(src: &Path, dst: &Path, label: &str) -> Resu
                     attempt,
                     started.elapsed().as_millis()
                 );
+                metrics::add_bytes(n);
                 return Ok(n);
             }
-            Ok(Err(e)) => {
-                let err = anyhow::Error::new(e).context("copy failed");
+            Ok(Err(err)) => {
                 let transient = is_transient_fs_error(&err);
                 warn!(
                     "[PHASE: installation] [STEP: files] copy failed (label={}, attempt={}, transient={}, src={:?}, dst={:?}, err={})",
@@ -117,7 +136,7 @@ pub async fn copy_file_with_retries(src: &Path, dst: &Path, label: &str) -> Resu
                 }
             }
             Err(_) => {
-                let err = anyhow::anyhow!("copy timed out after 60s");
+                let err = anyhow::anyhow!("copy timed out after {}s", timeout_dur.as_secs());
                 warn!(
                     "[PHASE: installation] [STEP: files] copy timeout (label={}, attempt={}, src={:?}, dst={:?})",
                     label, attempt, src, dst
@@ -148,19 +167,28 @@ pub async fn copy_file_with_retries_and_sha256(
         label, src, dst
     );
 
+    let throttle = IoThrottle::from_env();
     let mut last_err: Option<anyhow::Error> = None;
     for attempt in 1..=3 {
+        if attempt > 1 {
+            metrics::note_retry();
+        }
         let timeout_dur = match tokio::fs::metadata(src).await {
             Ok(m) => {
-                // Dynamic timeout: base 60s + 1s per MiB, capped at 10 minutes.
+                // Dynamic timeout: base 60s + 1s per MiB, capped at 10 minutes, plus the time
+                // the throttle alone needs for this file.
                 let mib = (m.len() / (1024 * 1024)).min(10_000);
                 let secs = (60_u64).saturating_add(mib).min(600);
-                Duration::from_secs(secs)
+                Duration::from_secs(secs) + throttle.min_duration(m.len())
             }
             Err(_) => Duration::from_secs(60),
         };
 
-        let res = timeout(timeout_dur, copy_file_once_and_sha256(src, dst)).await;
+        let res = timeout(
+            timeout_dur,
+            copy_file_once_and_sha256(src, dst, throttle.clone()),
+        )
+        .await;
         match res {
             Ok(Ok((n, sha))) => {
                 debug!(
@@ -171,6 +199,7 @@ pub async fn copy_file_with_retries_and_sha256(
                     attempt,
                     started.elapsed().as_millis()
                 );
+                metrics::add_bytes(n);
                 return Ok((n, sha));
             }
             Ok(Err(e)) => {
@@ -213,7 +242,235 @@ pub async fn copy_file_with_retries_and_sha256(
     Err(last_err.unwrap_or_else(|| anyhow::anyhow!("copy+sha failed")))
 }
 
-async fn copy_file_once_and_sha256(src: &Path, dst: &Path) -> Result<(u64, String)> {
+/// SHA-256 hex of a file's contents (streamed; used to re-check deployed copies).
+pub async fn sha256_file(path: &Path) -> Result<String> {
+    let mut f = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("open failed: {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        super::cancel::check_current()?;
+        let n = f.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let digest = hasher.finalize();
+    Ok(digest
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>())
+}
+
+/// Plain copy, or a chunked (throttled) copy when a write limit is configured.
+async fn copy_file_once(src: &Path, dst: &Path, throttle: &IoThrottle) -> Result<u64> {
+    if throttle.is_limited() {
+        let (n, _) = copy_file_once_and_sha256(src, dst, throttle.clone()).await?;
+        return Ok(n);
+    }
+    tokio::fs::copy(src, dst).await.context("copy failed")
+}
+
+/// Copy workers for [`copy_files_parallel`] from `CADALYTIX_COPY_WORKERS` (default 4, 1..=32).
+pub fn copy_workers() -> usize {
+    parse_copy_workers(std::env::var(COPY_WORKERS_ENV).ok().as_deref())
+}
+
+fn parse_copy_workers(value: Option<&str>) -> usize {
+    value
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .map(|n| n.clamp(1, 32))
+        .unwrap_or(DEFAULT_COPY_WORKERS)
+}
+
+/// One file for [`copy_files_parallel`].
+#[derive(Debug, Clone)]
+pub struct CopyJob {
+    pub src: PathBuf,
+    pub dst: PathBuf,
+    /// Checksum the deployed copy must have (e.g. from the payload manifest).
+    pub expected_sha256: Option<String>,
+}
+
+/// A copied and verified file.
+#[derive(Debug, Clone)]
+pub struct CopiedFile {
+    pub dst: PathBuf,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Copy `jobs` with up to `workers` copies in flight, each with the retries/timeouts of
+/// [`copy_file_with_retries_and_sha256`]. Every deployed file is re-hashed after the copy and
+/// must match both the hash taken while copying and `expected_sha256`, when given.
+///
+/// - `on_done(done, total, file)` runs as each file lands (completion order, not job order).
+/// - Stops at the first failure; copies still in flight are dropped.
+/// - With a write throttle configured, files are copied one at a time so the MB/s cap holds.
+/// - Caller must create parent directories.
+pub async fn copy_files_parallel<F>(
+    jobs: Vec<CopyJob>,
+    workers: usize,
+    label: &str,
+    mut on_done: F,
+) -> Result<Vec<CopiedFile>>
+where
+    F: FnMut(usize, usize, &CopiedFile),
+{
+    let started = Instant::now();
+    let total = jobs.len();
+    let workers = if IoThrottle::from_env().is_limited() {
+        1
+    } else {
+        workers.max(1)
+    };
+    debug!(
+        "[PHASE: installation] [STEP: files] copy_files_parallel entered (label={}, files={}, workers={})",
+        label, total, workers
+    );
+
+    let mut copies = stream::iter(jobs)
+        .map(|job| async move {
+            let (bytes, sha256) =
+                copy_file_with_retries_and_sha256(&job.src, &job.dst, label).await?;
+            verify_copy(&job, &sha256).await?;
+            Ok::<_, anyhow::Error>(CopiedFile {
+                dst: job.dst,
+                bytes,
+                sha256,
+            })
+        })
+        .buffer_unordered(workers);
+
+    let mut out = Vec::with_capacity(total);
+    let mut bytes_total: u64 = 0;
+    while let Some(copied) = copies.try_next().await? {
+        bytes_total = bytes_total.saturating_add(copied.bytes);
+        on_done(out.len() + 1, total, &copied);
+        out.push(copied);
+    }
+
+    info!(
+        "[PHASE: installation] [STEP: files] copy_files_parallel exit ok (label={}, files={}, bytes={}, workers={}, duration_ms={})",
+        label,
+        out.len(),
+        bytes_total,
+        workers,
+        started.elapsed().as_millis()
+    );
+    Ok(out)
+}
+
+/// Result of [`plan_delta`]: the jobs that still need a copy, and the files already in place.
+#[derive(Debug, Default)]
+pub struct DeltaPlan {
+    pub copy: Vec<CopyJob>,
+    /// Deployed files identical to their source; recorded as-is in the install manifest.
+    pub unchanged: Vec<CopiedFile>,
+    /// Jobs whose destination does not exist yet.
+    pub added: usize,
+    /// Jobs whose destination exists but differs in size or checksum.
+    pub updated: usize,
+}
+
+/// Compare the target tree against the payload before an upgrade: a destination with the same
+/// size as its source and the expected checksum (`expected_sha256`, else the source's hash) is
+/// skipped. Checks run with up to `workers` in flight; job order is preserved.
+pub async fn plan_delta(jobs: Vec<CopyJob>, workers: usize) -> Result<DeltaPlan> {
+    let started = Instant::now();
+    let total = jobs.len();
+    let checked: Vec<(CopyJob, Option<CopiedFile>, bool)> = stream::iter(jobs)
+        .map(|job| async move {
+            let (unchanged, existed) = deployed_unchanged(&job).await?;
+            Ok::<_, anyhow::Error>((job, unchanged, existed))
+        })
+        .buffered(workers.max(1))
+        .try_collect()
+        .await?;
+
+    let mut plan = DeltaPlan::default();
+    for (job, unchanged, existed) in checked {
+        match unchanged {
+            Some(file) => plan.unchanged.push(file),
+            None => {
+                if existed {
+                    plan.updated += 1;
+                } else {
+                    plan.added += 1;
+                }
+                plan.copy.push(job);
+            }
+        }
+    }
+    info!(
+        "[PHASE: installation] [STEP: files] plan_delta exit (files={}, added={}, updated={}, unchanged={}, duration_ms={})",
+        total,
+        plan.added,
+        plan.updated,
+        plan.unchanged.len(),
+        started.elapsed().as_millis()
+    );
+    Ok(plan)
+}
+
+/// `(Some(file), true)` when `job.dst` already matches its source; the bool is whether the
+/// destination exists at all.
+async fn deployed_unchanged(job: &CopyJob) -> Result<(Option<CopiedFile>, bool)> {
+    let dst_meta = match tokio::fs::metadata(&job.dst).await {
+        Ok(m) if m.is_file() => m,
+        Ok(_) => return Ok((None, true)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((None, false)),
+        Err(e) => return Err(e).with_context(|| format!("stat failed: {:?}", job.dst)),
+    };
+    let src_meta = tokio::fs::metadata(&job.src)
+        .await
+        .with_context(|| format!("stat failed: {:?}", job.src))?;
+    if src_meta.len() != dst_meta.len() {
+        return Ok((None, true));
+    }
+    let expected = match &job.expected_sha256 {
+        Some(sha) => sha.trim().to_ascii_lowercase(),
+        None => sha256_file(&job.src).await?,
+    };
+    let actual = sha256_file(&job.dst).await?;
+    if actual != expected {
+        return Ok((None, true));
+    }
+    Ok((
+        Some(CopiedFile {
+            dst: job.dst.clone(),
+            bytes: dst_meta.len(),
+            sha256: actual,
+        }),
+        true,
+    ))
+}
+
+/// Re-read a deployed copy and check it against the expected (manifest) checksum, or the hash
+/// taken while copying when there is none.
+async fn verify_copy(job: &CopyJob, copied_sha256: &str) -> Result<()> {
+    if let Some(expected) = &job.expected_sha256 {
+        return super::payload_manifest::verify_deployed(expected, &job.dst)
+            .await
+            .or_code(InstallerError::PayloadIntegrityFailed);
+    }
+    if sha256_file(&job.dst).await? != copied_sha256 {
+        return Err(anyhow::anyhow!(
+            "Deployed file {:?} changed after copy (sha256 mismatch)",
+            job.dst
+        ))
+        .or_code(InstallerError::PayloadIntegrityFailed);
+    }
+    Ok(())
+}
+
+async fn copy_file_once_and_sha256(
+    src: &Path,
+    dst: &Path,
+    mut throttle: IoThrottle,
+) -> Result<(u64, String)> {
     let mut src_f = tokio::fs::File::open(src)
         .await
         .with_context(|| format!("open src failed: {:?}", src))?;
@@ -226,12 +483,15 @@ async fn copy_file_once_and_sha256(src: &Path, dst: &Path) -> Result<(u64, Strin
     let mut total: u64 = 0;
 
     loop {
+        // Large payload files must not hold up Cancel until the copy finishes.
+        super::cancel::check_current()?;
         let n = src_f.read(&mut buf).await?;
         if n == 0 {
             break;
         }
         hasher.update(&buf[..n]);
         dst_f.write_all(&buf[..n]).await?;
+        throttle.consume(n).await;
         total = total.saturating_add(n as u64);
     }
     dst_f.flush().await?;
@@ -253,3 +513,68 @@ async fn copy_file_once_and_sha256(src: &Path, dst: &Path) -> Result<(u64, Strin
         .collect::<String>();
     Ok((total, sha256))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn parallel_copy_verifies_every_file() {
+        let dir = std::env::temp_dir().join(format!("files-{}", uuid::Uuid::new_v4()));
+        let (src_dir, dst_dir) = (dir.join("src"), dir.join("dst"));
+        tokio::fs::create_dir_all(&src_dir).await.unwrap();
+        tokio::fs::create_dir_all(&dst_dir).await.unwrap();
+        let mut jobs = Vec::new();
+        for i in 0..10 {
+            let src = src_dir.join(format!("f{}.txt", i));
+            tokio::fs::write(&src, format!("payload {}", i))
+                .await
+                .unwrap();
+            jobs.push(CopyJob {
+                src,
+                dst: dst_dir.join(format!("f{}.txt", i)),
+                expected_sha256: None,
+            });
+        }
+
+        let mut seen = Vec::new();
+        let copied = copy_files_parallel(jobs.clone(), 4, "test", |done, total, _| {
+            seen.push((done, total))
+        })
+        .await
+        .unwrap();
+        assert_eq!(copied.len(), 10);
+        assert_eq!(seen.last(), Some(&(10, 10)));
+        for file in &copied {
+            assert_eq!(sha256_file(&file.dst).await.unwrap(), file.sha256);
+        }
+
+        let mut wrong = jobs[0].clone();
+        wrong.expected_sha256 = Some("00".repeat(32));
+        let err = copy_files_parallel(vec![wrong], 2, "test", |_, _, _| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("payload manifest"));
+
+        // Upgrade over the same tree: one changed source, one new file, the rest unchanged.
+        tokio::fs::write(&jobs[1].src, "payload 1, v2")
+            .await
+            .unwrap();
+        let new_src = src_dir.join("new.txt");
+        tokio::fs::write(&new_src, "new").await.unwrap();
+        jobs.push(CopyJob {
+            src: new_src,
+            dst: dst_dir.join("new.txt"),
+            expected_sha256: None,
+        });
+        let plan = plan_delta(jobs, 4).await.unwrap();
+        assert_eq!((plan.added, plan.updated, plan.unchanged.len()), (1, 1, 9));
+        assert_eq!(plan.copy.len(), 2);
+        assert_eq!(plan.copy[0].dst, dst_dir.join("f1.txt"));
+
+        assert_eq!(parse_copy_workers(None), DEFAULT_COPY_WORKERS);
+        assert_eq!(parse_copy_workers(Some("0")), 1);
+        assert_eq!(parse_copy_workers(Some("64")), 32);
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -0,0 +1,341 @@
+// Host firewall detection (network preflight)
+//
+// Best-effort: each firewall is queried through its own CLI (ufw / firewalld on Linux,
+// netsh + PowerShell NetSecurity cmdlets on Windows). A missing tool or insufficient rights
+// reports the firewall as unknown instead of failing the preflight.
+//
+// The parsers are pure functions so they are tested on every OS.
+
+use log::{debug, info};
+use std::collections::HashMap;
+use tokio::time::Duration;
+
+use crate::installation::run_cmd_with_timeout;
+
+const FIREWALL_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What the host firewall does with inbound TCP on the ports asked about.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirewallReport {
+    /// "ufw", "firewalld" or "Windows Defender Firewall"; None when none was detected.
+    pub backend: Option<String>,
+    /// None when the state could not be determined.
+    pub active: Option<bool>,
+    /// Per port: Some(true) when an allow rule exists, None when unknown.
+    pub allowed: HashMap<u16, Option<bool>>,
+    /// Why the answer is incomplete (e.g. "ufw status requires root").
+    pub note: Option<String>,
+}
+
+impl FirewallReport {
+    /// Command an operator can run to open `port` on this firewall.
+    pub fn remediation(&self, port: u16) -> String {
+        match self.backend.as_deref() {
+            Some("ufw") => format!("Run: sudo ufw allow {}/tcp", port),
+            Some("firewalld") => format!(
+                "Run: sudo firewall-cmd --permanent --add-port={}/tcp && sudo firewall-cmd --reload",
+                port
+            ),
+            Some(_) => format!(
+                "In an elevated PowerShell run: New-NetFirewallRule -DisplayName \"CADalytix TCP {0}\" -Direction Inbound -Protocol TCP -LocalPort {0} -Action Allow",
+                port
+            ),
+            None => format!(
+                "Allow inbound TCP {} in the host firewall (iptables/nftables) if one is configured.",
+                port
+            ),
+        }
+    }
+}
+
+/// Parse `ufw status` output: (active, rules as (target, action) pairs).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_ufw_status(stdout: &str) -> (bool, Vec<(String, String)>) {
+    let mut active = false;
+    let mut rules = Vec::new();
+    for line in stdout.lines() {
+        let line = line.trim();
+        if let Some(state) = line.strip_prefix("Status:") {
+            active = state.trim().eq_ignore_ascii_case("active");
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(target), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if target == "To" || target.starts_with("--") {
+            continue;
+        }
+        rules.push((target.to_string(), action.to_ascii_uppercase()));
+    }
+    (active, rules)
+}
+
+/// True when a ufw rule target (`8080`, `8080/tcp`, `8000:8100/tcp`) covers TCP `port`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn ufw_rule_covers(target: &str, port: u16) -> bool {
+    let (ports, proto) = match target.split_once('/') {
+        Some((p, proto)) => (p, Some(proto)),
+        None => (target, None),
+    };
+    if proto.is_some_and(|p| !p.eq_ignore_ascii_case("tcp")) {
+        return false;
+    }
+    ports.split(',').any(|p| match p.split_once(':') {
+        Some((lo, hi)) => match (lo.parse::<u16>(), hi.parse::<u16>()) {
+            (Ok(lo), Ok(hi)) => (lo..=hi).contains(&port),
+            _ => false,
+        },
+        None => p.parse::<u16>() == Ok(port),
+    })
+}
+
+/// True when any rule in `ufw status` output allows inbound TCP `port`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn ufw_allows(rules: &[(String, String)], port: u16) -> bool {
+    rules
+        .iter()
+        .any(|(target, action)| action == "ALLOW" && ufw_rule_covers(target, port))
+}
+
+/// Parse `netsh advfirewall show allprofiles state`: Some(true) when any profile is ON.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn parse_netsh_profiles_state(stdout: &str) -> Option<bool> {
+    let states: Vec<bool> = stdout
+        .lines()
+        .filter_map(|l| {
+            let l = l.trim();
+            if !l.to_ascii_lowercase().starts_with("state") {
+                return None;
+            }
+            match l.split_whitespace().last()?.to_ascii_uppercase().as_str() {
+                "ON" => Some(true),
+                "OFF" => Some(false),
+                _ => None,
+            }
+        })
+        .collect();
+    if states.is_empty() {
+        None
+    } else {
+        Some(states.iter().any(|s| *s))
+    }
+}
+
+/// Inspect the host firewall for inbound TCP on `ports`.
+pub async fn inspect(ports: &[u16]) -> FirewallReport {
+    let report = inspect_platform(ports).await;
+    info!(
+        "[PHASE: preflight] [STEP: network] Firewall inspected (backend={:?}, active={:?}, allowed={:?})",
+        report.backend, report.active, report.allowed
+    );
+    report
+}
+
+#[cfg(target_os = "linux")]
+async fn inspect_platform(ports: &[u16]) -> FirewallReport {
+    let mut report = FirewallReport::default();
+
+    match run_cmd_with_timeout(
+        "ufw",
+        &["status".to_string()],
+        FIREWALL_QUERY_TIMEOUT,
+        "ufw_status",
+    )
+    .await
+    {
+        Ok(out) if out.exit_code == Some(0) => {
+            let (active, rules) = parse_ufw_status(&out.stdout);
+            if active {
+                report.backend = Some("ufw".to_string());
+                report.active = Some(true);
+                for &p in ports {
+                    report.allowed.insert(p, Some(ufw_allows(&rules, p)));
+                }
+                return report;
+            }
+        }
+        Ok(out) => {
+            debug!(
+                "[PHASE: preflight] [STEP: network] ufw status failed (exit_code={:?})",
+                out.exit_code
+            );
+            if out.stderr.to_ascii_lowercase().contains("root") {
+                report.backend = Some("ufw".to_string());
+                report.note = Some("ufw status requires root; run the installer with sudo to check firewall rules.".to_string());
+            }
+        }
+        Err(e) => debug!(
+            "[PHASE: preflight] [STEP: network] ufw not available: {}",
+            e
+        ),
+    }
+
+    match run_cmd_with_timeout(
+        "firewall-cmd",
+        &["--state".to_string()],
+        FIREWALL_QUERY_TIMEOUT,
+        "firewalld_state",
+    )
+    .await
+    {
+        Ok(out) if out.stdout.trim() == "running" => {
+            report.backend = Some("firewalld".to_string());
+            report.active = Some(true);
+            report.note = None;
+            for &p in ports {
+                let allowed = match run_cmd_with_timeout(
+                    "firewall-cmd",
+                    &[format!("--query-port={}/tcp", p)],
+                    FIREWALL_QUERY_TIMEOUT,
+                    "firewalld_query_port",
+                )
+                .await
+                {
+                    Ok(q) => match q.stdout.trim() {
+                        "yes" => Some(true),
+                        "no" => Some(false),
+                        _ => None,
+                    },
+                    Err(_) => None,
+                };
+                report.allowed.insert(p, allowed);
+            }
+            return report;
+        }
+        Ok(out) if out.stdout.trim() == "not running" || out.stderr.contains("not running") => {
+            if report.note.is_none() {
+                report.backend = Some("firewalld".to_string());
+                report.active = Some(false);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => debug!(
+            "[PHASE: preflight] [STEP: network] firewalld not available: {}",
+            e
+        ),
+    }
+
+    if report.backend.is_none() {
+        report.active = Some(false);
+    }
+    for &p in ports {
+        report.allowed.entry(p).or_insert(None);
+    }
+    report
+}
+
+#[cfg(windows)]
+async fn inspect_platform(ports: &[u16]) -> FirewallReport {
+    let mut report = FirewallReport {
+        backend: Some("Windows Defender Firewall".to_string()),
+        ..Default::default()
+    };
+
+    match run_cmd_with_timeout(
+        "netsh",
+        &[
+            "advfirewall".to_string(),
+            "show".to_string(),
+            "allprofiles".to_string(),
+            "state".to_string(),
+        ],
+        FIREWALL_QUERY_TIMEOUT,
+        "netsh_firewall_state",
+    )
+    .await
+    {
+        Ok(out) if out.exit_code == Some(0) => {
+            report.active = parse_netsh_profiles_state(&out.stdout)
+        }
+        Ok(out) => debug!(
+            "[PHASE: preflight] [STEP: network] netsh firewall state failed (exit_code={:?})",
+            out.exit_code
+        ),
+        Err(e) => debug!(
+            "[PHASE: preflight] [STEP: network] netsh not available: {}",
+            e
+        ),
+    }
+
+    for &p in ports {
+        let allowed = if report.active == Some(true) {
+            // Enabled inbound allow rules bound to exactly this TCP port.
+            let script = format!(
+                "@(Get-NetFirewallPortFilter -Protocol TCP -ErrorAction SilentlyContinue | Where-Object {{ $_.LocalPort -eq '{}' }} | Get-NetFirewallRule | Where-Object {{ $_.Enabled -eq 'True' -and $_.Direction -eq 'Inbound' -and $_.Action -eq 'Allow' }}).Count",
+                p
+            );
+            match run_cmd_with_timeout(
+                "powershell",
+                &["-NoProfile".to_string(), "-Command".to_string(), script],
+                FIREWALL_QUERY_TIMEOUT,
+                "firewall_rules_for_port",
+            )
+            .await
+            {
+                Ok(out) if out.exit_code == Some(0) => {
+                    out.stdout.trim().parse::<u32>().ok().map(|n| n > 0)
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        report.allowed.insert(p, allowed);
+    }
+    report
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+async fn inspect_platform(ports: &[u16]) -> FirewallReport {
+    FirewallReport {
+        allowed: ports.iter().map(|p| (*p, None)).collect(),
+        note: Some("Firewall detection is not supported on this OS.".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ufw_status_rules_are_matched_by_port_range_and_protocol() {
+        let out = "Status: active\n\nTo                         Action      From\n--                         ------      ----\n22/tcp                     ALLOW       Anywhere\n8000:8100/tcp              ALLOW       Anywhere\n5432                       DENY        Anywhere\n53/udp                     ALLOW       Anywhere\n22/tcp (v6)                ALLOW       Anywhere (v6)\n";
+        let (active, rules) = parse_ufw_status(out);
+        assert!(active);
+        assert!(ufw_allows(&rules, 22));
+        assert!(ufw_allows(&rules, 8080));
+        assert!(!ufw_allows(&rules, 5432));
+        assert!(!ufw_allows(&rules, 53));
+        assert!(!ufw_allows(&rules, 1433));
+
+        let (active, rules) = parse_ufw_status("Status: inactive\n");
+        assert!(!active);
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn netsh_state_is_on_when_any_profile_is_on() {
+        let out = "Domain Profile Settings:\n----------------------------------------------------------------------\nState                                 OFF\n\nPrivate Profile Settings:\n----------------------------------------------------------------------\nState                                 ON\n\nOk.\n";
+        assert_eq!(parse_netsh_profiles_state(out), Some(true));
+        assert_eq!(
+            parse_netsh_profiles_state("State OFF\nState OFF\n"),
+            Some(false)
+        );
+        assert_eq!(parse_netsh_profiles_state("Access is denied."), None);
+    }
+
+    #[test]
+    fn remediation_names_the_detected_firewall() {
+        let mut report = FirewallReport {
+            backend: Some("ufw".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(report.remediation(8080), "Run: sudo ufw allow 8080/tcp");
+        report.backend = Some("firewalld".to_string());
+        assert!(report.remediation(8080).contains("--add-port=8080/tcp"));
+        report.backend = Some("Windows Defender Firewall".to_string());
+        assert!(report.remediation(8080).contains("-LocalPort 8080"));
+    }
+}
@@ -0,0 +1,442 @@
+//! Fleet install (`--fleet --hosts=<file>`): the scripted install (`--tui-script`) against several
+//! targets, this machine and SSH remotes, a bounded number at a time.
+//!
+//! The host list has one target per line: a name, `local` or `user@host[:port]`, then optional
+//! `key=value` fields: `fingerprint=SHA256:...` (required for SSH targets, see
+//! `installation::remote`), `identity=<private key file>` and `answers=<file>` (else the
+//! `--answers=<file>` of the run). Blank lines and `#` comments are skipped:
+//!
+//! ```text
+//! # name   target               fields
+//! hq       local                answers=hq.txt
+//! edge-1   deploy@10.0.4.21     fingerprint=SHA256:2ow1... identity=C:\keys\fleet_ed25519
+//! ```
+//!
+//! Each target runs as its own installer process, the answers on its stdin, with its own log
+//! folder `fleet/<timestamp>/<name>/` under this run's log folder; the process's output goes to
+//! `transcript.txt` there. `--parallel=<n>` (default 4) bounds how many run at once;
+//! `--instance=` and `--offline-bundle=` are passed on. The summary (also `fleet-summary.txt`)
+//! is a pass/fail table per host.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use crate::error::{InstallerError, OrCode};
+use crate::installation::remote::RemoteTarget;
+use crate::utils::path_resolver::{resolve_log_folder, LOG_FOLDER_ENV};
+
+const DEFAULT_PARALLEL: usize = 4;
+const MAX_NAME_LEN: usize = 64;
+/// Arguments of this run that every host's installer gets too.
+const PASSED_ON: [&str; 2] = ["--instance=", "--offline-bundle="];
+
+/// One line of the host list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetHost {
+    pub name: String,
+    /// `None` installs on this machine.
+    pub remote: Option<RemoteTarget>,
+    pub answers: Option<PathBuf>,
+}
+
+impl FleetHost {
+    fn target_label(&self) -> String {
+        self.remote
+            .as_ref()
+            .map_or_else(|| "local".to_string(), RemoteTarget::label)
+    }
+
+    /// Arguments selecting this host's target for the scripted install.
+    fn target_args(&self) -> Vec<String> {
+        let Some(remote) = &self.remote else {
+            return Vec::new();
+        };
+        let mut args = vec![
+            format!(
+                "--remote={}@[{}]:{}",
+                remote.user,
+                remote.host,
+                remote.port()
+            ),
+            format!("--remote-fingerprint={}", remote.host_key_fingerprint),
+        ];
+        if !remote.identity_file.is_empty() {
+            args.push(format!("--remote-identity={}", remote.identity_file));
+        }
+        args
+    }
+}
+
+/// Hosts of a host list; names must be unique and at most one target may be `local`.
+pub fn parse_hosts(text: &str) -> Result<Vec<FleetHost>> {
+    let mut hosts: Vec<FleetHost> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bad = |msg: String| anyhow::anyhow!("Host list line {}: {}", i + 1, msg);
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(target)) = (fields.next(), fields.next()) else {
+            return Err(bad(
+                "expected `<name> <local | user@host[:port]> [key=value ...]`".into(),
+            ));
+        };
+        let valid_name = name.len() <= MAX_NAME_LEN
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !name.starts_with('.');
+        if !valid_name {
+            return Err(bad(format!(
+                "host name '{}' may only use letters, digits, '-', '_' and '.'",
+                name
+            )));
+        }
+        if hosts.iter().any(|h| h.name.eq_ignore_ascii_case(name)) {
+            return Err(bad(format!("host name '{}' is listed twice", name)));
+        }
+
+        let mut remote = match target {
+            "local" => None,
+            t => Some(RemoteTarget::parse(t).map_err(bad)?),
+        };
+        let mut answers = None;
+        for field in fields {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| bad(format!("'{}' is not key=value", field)))?;
+            match (key, remote.as_mut()) {
+                ("answers", _) => answers = Some(PathBuf::from(value)),
+                ("fingerprint", Some(r)) => r.host_key_fingerprint = value.to_string(),
+                ("identity", Some(r)) => r.identity_file = value.to_string(),
+                _ => {
+                    return Err(bad(format!(
+                        "unknown field '{}' for target {}",
+                        key, target
+                    )))
+                }
+            }
+        }
+        if let Some(r) = &remote {
+            r.validate().map_err(bad)?;
+        } else if hosts.iter().any(|h| h.remote.is_none()) {
+            return Err(bad("only one target may be `local`".into()));
+        }
+        hosts.push(FleetHost {
+            name: name.to_string(),
+            remote,
+            answers,
+        });
+    }
+    if hosts.is_empty() {
+        anyhow::bail!("The host list names no targets");
+    }
+    Ok(hosts)
+}
+
+/// How one host's install ended.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostOutcome {
+    pub name: String,
+    pub target: String,
+    /// Exit code of the host's installer; `None` when it did not run to the end.
+    pub exit_code: Option<i32>,
+    pub duration_ms: u128,
+    pub log_folder: String,
+    /// Last outcome line of the transcript (`= complete`, `! <page>: <reason>`) or the error.
+    pub message: String,
+}
+
+impl HostOutcome {
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Outcome of a fleet install.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetReport {
+    pub generated_utc: String,
+    pub log_folder: String,
+    pub hosts: Vec<HostOutcome>,
+}
+
+impl FleetReport {
+    pub fn to_text(&self) -> String {
+        let width = |f: fn(&HostOutcome) -> usize, title: &str| {
+            self.hosts.iter().map(f).max().unwrap_or(0).max(title.len())
+        };
+        let name_w = width(|h| h.name.len(), "HOST");
+        let target_w = width(|h| h.target.len(), "TARGET");
+        let mut out = format!(
+            "CADalytix fleet install ({})\nLogs: {}\n\n{:<name_w$}  {:<target_w$}  RESULT  EXIT  DURATION  OUTCOME\n",
+            self.generated_utc, self.log_folder, "HOST", "TARGET"
+        );
+        for h in &self.hosts {
+            let secs = h.duration_ms / 1000;
+            let duration = format!("{}m{:02}s", secs / 60, secs % 60);
+            out.push_str(&format!(
+                "{:<name_w$}  {:<target_w$}  {:<6}  {:<4}  {:>8}  {}\n",
+                h.name,
+                h.target,
+                if h.passed() { "PASS" } else { "FAIL" },
+                h.exit_code.map_or("-".to_string(), |c| c.to_string()),
+                duration,
+                h.message
+            ));
+        }
+        let failed = self.hosts.iter().filter(|h| !h.passed()).count();
+        out.push_str(&format!(
+            "\n{} of {} host(s) passed.\n",
+            self.hosts.len() - failed,
+            self.hosts.len()
+        ));
+        out
+    }
+
+    /// 0 when every host passed, 2 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.hosts.iter().all(HostOutcome::passed) {
+            0
+        } else {
+            2
+        }
+    }
+}
+
+/// Run `--fleet --hosts=<file> [--answers=<file>] [--parallel=<n>]`.
+pub async fn fleet_cli(args: &[String]) -> Result<FleetReport> {
+    let (hosts, parallel) = read_hosts(args)
+        .await
+        .or_code(InstallerError::InvalidArguments)?;
+    let exe = std::env::current_exe().context("Cannot locate the installer executable")?;
+    let passed_on: Vec<String> = args
+        .iter()
+        .filter(|a| PASSED_ON.iter().any(|p| a.starts_with(p)))
+        .cloned()
+        .collect();
+    let started_utc = Utc::now();
+    let folder = resolve_log_folder()?
+        .join("fleet")
+        .join(started_utc.format("%Y%m%d_%H%M%S").to_string());
+    tokio::fs::create_dir_all(&folder).await?;
+    info!(
+        "[PHASE: fleet] [STEP: start] Fleet install of {} host(s), {} at a time (logs={:?})",
+        hosts.len(),
+        parallel,
+        folder
+    );
+
+    let slots = Arc::new(Semaphore::new(parallel));
+    let mut runs = tokio::task::JoinSet::new();
+    for (i, (host, answers)) in hosts.into_iter().enumerate() {
+        let slots = slots.clone();
+        let mut args = vec!["--tui-script".to_string()];
+        args.extend(passed_on.iter().cloned());
+        args.extend(host.target_args());
+        let (exe, dir) = (exe.clone(), folder.join(&host.name));
+        runs.spawn(async move {
+            let _slot = slots.acquire_owned().await;
+            eprintln!("{}: installing on {}...", host.name, host.target_label());
+            let outcome = run_host(&exe, &args, &host, &answers, &dir).await;
+            eprintln!(
+                "{}: {} ({})",
+                outcome.name,
+                if outcome.passed() { "PASS" } else { "FAIL" },
+                outcome.message
+            );
+            (i, outcome)
+        });
+    }
+    let mut outcomes = Vec::new();
+    while let Some(joined) = runs.join_next().await {
+        outcomes.push(joined.context("A fleet host task stopped unexpectedly")?);
+    }
+    outcomes.sort_by_key(|(i, _)| *i);
+
+    let report = FleetReport {
+        generated_utc: started_utc.to_rfc3339(),
+        log_folder: folder.display().to_string(),
+        hosts: outcomes.into_iter().map(|(_, o)| o).collect(),
+    };
+    if let Err(e) = tokio::fs::write(folder.join("fleet-summary.txt"), report.to_text()).await {
+        warn!(
+            "[PHASE: fleet] [STEP: summary] Failed to write the fleet summary: {}",
+            e
+        );
+    }
+    Ok(report)
+}
+
+/// Hosts of `--hosts=<file>`, each with its answers file, and `--parallel=<n>`.
+async fn read_hosts(args: &[String]) -> Result<(Vec<(FleetHost, PathBuf)>, usize)> {
+    let value = |prefix: &str| args.iter().find_map(|a| a.strip_prefix(prefix));
+    let hosts_file = value("--hosts=").context("--fleet needs a host list: --hosts=<file>")?;
+    let text = tokio::fs::read_to_string(hosts_file)
+        .await
+        .with_context(|| format!("Failed to read host list {}", hosts_file))?;
+    let hosts = parse_hosts(&text)?;
+    let parallel = match value("--parallel=") {
+        Some(n) => n
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .context("--parallel needs a number of at least 1")?,
+        None => DEFAULT_PARALLEL,
+    };
+    let default_answers = value("--answers=").map(PathBuf::from);
+    let mut answers = Vec::new();
+    for host in &hosts {
+        let file = host
+            .answers
+            .clone()
+            .or_else(|| default_answers.clone())
+            .with_context(|| {
+                format!(
+                    "Host {} has no answers file: add answers=<file> or pass --answers=<file>",
+                    host.name
+                )
+            })?;
+        if !tokio::fs::try_exists(&file).await.unwrap_or(false) {
+            anyhow::bail!("Answers file {:?} of host {} not found", file, host.name);
+        }
+        answers.push(file);
+    }
+    Ok((hosts.into_iter().zip(answers).collect(), parallel))
+}
+
+/// One host's scripted install as a child installer process.
+async fn run_host(
+    exe: &Path,
+    args: &[String],
+    host: &FleetHost,
+    answers: &Path,
+    dir: &Path,
+) -> HostOutcome {
+    let started = Instant::now();
+    let transcript = dir.join("transcript.txt");
+    let run = async {
+        tokio::fs::create_dir_all(dir).await?;
+        let input = std::fs::File::open(answers)
+            .with_context(|| format!("Failed to open answers file {:?}", answers))?;
+        let output = std::fs::File::create(&transcript)?;
+        let status = tokio::process::Command::new(exe)
+            .args(args)
+            .env(LOG_FOLDER_ENV, dir)
+            .stdin(Stdio::from(input))
+            .stdout(Stdio::from(output.try_clone()?))
+            .stderr(Stdio::from(output))
+            .kill_on_drop(true)
+            .status()
+            .await?;
+        Ok::<_, anyhow::Error>(status.code())
+    };
+    let (exit_code, message) = match run.await {
+        Ok(code) => {
+            let text = tokio::fs::read_to_string(&transcript)
+                .await
+                .unwrap_or_default();
+            (code, outcome_line(&text, code))
+        }
+        Err(e) => (None, format!("not started: {:#}", e)),
+    };
+    info!(
+        "[PHASE: fleet] [STEP: host] {} ({}) finished (exit_code={:?}): {}",
+        host.name,
+        host.target_label(),
+        exit_code,
+        message
+    );
+    HostOutcome {
+        name: host.name.clone(),
+        target: host.target_label(),
+        exit_code,
+        duration_ms: started.elapsed().as_millis(),
+        log_folder: dir.display().to_string(),
+        message,
+    }
+}
+
+/// The scripted install's last `=` or `!` line, else the last line of its output.
+fn outcome_line(transcript: &str, exit_code: Option<i32>) -> String {
+    let lines: Vec<&str> = transcript
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines
+        .iter()
+        .rev()
+        .find(|l| l.starts_with("= ") || l.starts_with("! "))
+        .or(lines.last())
+        .map(|l| l.to_string())
+        .unwrap_or_else(|| format!("exited with {:?} and no output", exit_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_lists_parse_and_the_summary_counts_failures() {
+        let hosts = parse_hosts(
+            "# fleet\nhq local answers=hq.txt\n\nedge-1 deploy@10.0.4.21:2222 fingerprint=SHA256:abc identity=/keys/id\n",
+        )
+        .unwrap();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].answers, Some(PathBuf::from("hq.txt")));
+        let edge = hosts[1].remote.as_ref().unwrap();
+        assert_eq!(edge.label(), "deploy@10.0.4.21:2222");
+        assert_eq!(
+            hosts[1].target_args(),
+            [
+                "--remote=deploy@[10.0.4.21]:2222",
+                "--remote-fingerprint=SHA256:abc",
+                "--remote-identity=/keys/id"
+            ]
+        );
+        assert_eq!(
+            RemoteTarget::parse("deploy@[10.0.4.21]:2222").unwrap(),
+            RemoteTarget {
+                host_key_fingerprint: String::new(),
+                identity_file: String::new(),
+                ..edge.clone()
+            }
+        );
+
+        assert!(parse_hosts("a local\nb local\n").is_err());
+        assert!(parse_hosts("a local\nA deploy@x fingerprint=SHA256:x\n").is_err());
+        assert!(parse_hosts("edge deploy@x\n").is_err());
+        assert!(parse_hosts("edge local fingerprint=SHA256:x\n").is_err());
+
+        let outcome = |name: &str, exit_code| HostOutcome {
+            name: name.to_string(),
+            target: "local".to_string(),
+            exit_code,
+            duration_ms: 125_000,
+            log_folder: String::new(),
+            message: outcome_line("? ready\n= complete\n", exit_code),
+        };
+        let report = FleetReport {
+            generated_utc: String::new(),
+            log_folder: String::new(),
+            hosts: vec![outcome("hq", Some(0)), outcome("edge-1", Some(44))],
+        };
+        let text = report.to_text();
+        assert!(text.contains("hq      local   PASS    0        2m05s  = complete"));
+        assert!(text.contains("1 of 2 host(s) passed."));
+        assert_eq!(report.exit_code(), 2);
+    }
+}
@@ -0,0 +1,473 @@
+// Post-install health checks.
+//
+// `verify_setup` runs these after its configuration checks so the Complete page can show whether
+// the installed system actually works, not only whether setup wrote its settings:
+// - config DB round-trip (`SELECT 1`) and its latency
+// - schema version: no pending or drifted migrations for this installer's manifest
+// - service/unit running (Windows service, systemd unit) or, in Docker mode, container health
+// - archive destination writable (when archiving is enabled)
+// - web health endpoint (`/health` on the web port)
+//
+// Each probe reports green (working), yellow (degraded, or could not be checked from here) or red
+// (broken). The report's overall level is the worst of its checks.
+
+use log::info;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::time::Duration;
+
+use crate::database::connection::DatabaseConnection;
+use crate::database::migrations::MigrationRunner;
+
+/// Web port the native service and the compose `WEB_PORT` mapping listen on.
+const WEB_HEALTH_URL: &str = "http://127.0.0.1:8080/health";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// A round-trip slower than this is reported yellow.
+const SLOW_DB_ROUND_TRIP_MS: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthLevel {
+    Green,
+    Yellow,
+    Red,
+}
+
+impl HealthLevel {
+    /// `SetupVerifyCheckResult.status` for this level.
+    pub fn status(self) -> &'static str {
+        match self {
+            HealthLevel::Green => "pass",
+            HealthLevel::Yellow => "warn",
+            HealthLevel::Red => "fail",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HealthLevel::Green => "green",
+            HealthLevel::Yellow => "yellow",
+            HealthLevel::Red => "red",
+        }
+    }
+
+    /// Level of a verify check status; `skip` counts as green.
+    pub fn from_status(status: &str) -> Self {
+        match status {
+            "fail" => HealthLevel::Red,
+            "warn" => HealthLevel::Yellow,
+            _ => HealthLevel::Green,
+        }
+    }
+}
+
+/// Worst level among `statuses` (green when there are none).
+pub fn overall_level<'a>(statuses: impl IntoIterator<Item = &'a str>) -> HealthLevel {
+    statuses
+        .into_iter()
+        .map(HealthLevel::from_status)
+        .max()
+        .unwrap_or(HealthLevel::Green)
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthProbe {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub level: HealthLevel,
+    pub message: String,
+    pub duration_ms: i64,
+}
+
+/// Run every probe that applies to this install. `settings` are the instance settings written by
+/// the installer (`Setup:InstallMode`, `Setup:DestinationFolder`, `Archive:*`).
+pub async fn run_health_checks(
+    conn: &DatabaseConnection,
+    engine: &str,
+    settings: &HashMap<String, String>,
+) -> Vec<HealthProbe> {
+    let mut probes = vec![
+        timed("db_round_trip", "Config DB round-trip", db_round_trip(conn)).await,
+        timed(
+            "schema_version",
+            "Schema version matches the installer",
+            schema_version(conn, engine),
+        )
+        .await,
+        runtime_probe(settings).await,
+    ];
+    if settings
+        .get("Archive:Enabled")
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+    {
+        let destination = settings
+            .get("Archive:DestinationPath")
+            .map(|p| p.trim().to_string())
+            .unwrap_or_default();
+        probes.push(
+            timed(
+                "archive_destination",
+                "Archive destination writable",
+                archive_destination(&destination),
+            )
+            .await,
+        );
+    }
+    probes.push(web_probe(settings).await);
+    log_probes(&probes);
+    probes
+}
+
+/// The read-only probes of the running install: service or containers, and web health. Used by
+/// `--inspect`, which must not write (the archive destination probe does).
+pub async fn run_runtime_checks(settings: &HashMap<String, String>) -> Vec<HealthProbe> {
+    let probes = vec![runtime_probe(settings).await, web_probe(settings).await];
+    log_probes(&probes);
+    probes
+}
+
+fn install_mode(settings: &HashMap<String, String>) -> String {
+    settings
+        .get("Setup:InstallMode")
+        .map(|m| m.trim().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Docker containers in Docker mode, else the Windows service or systemd unit.
+async fn runtime_probe(settings: &HashMap<String, String>) -> HealthProbe {
+    let mode = install_mode(settings);
+    if mode == "docker" {
+        let dest = settings
+            .get("Setup:DestinationFolder")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        timed(
+            "containers",
+            "Docker containers healthy",
+            containers(&dest.join("docker-compose.yml")),
+        )
+        .await
+    } else {
+        timed("service", "CADalytix service running", service(&mode)).await
+    }
+}
+
+async fn web_probe(settings: &HashMap<String, String>) -> HealthProbe {
+    let docker = install_mode(settings) == "docker";
+    timed("web_health", "Web health endpoint", web_health(docker)).await
+}
+
+fn log_probes(probes: &[HealthProbe]) {
+    for p in probes {
+        info!(
+            "[PHASE: setup] [STEP: health] {} = {} ({}ms): {}",
+            p.id,
+            p.level.as_str(),
+            p.duration_ms,
+            p.message
+        );
+    }
+}
+
+async fn timed(
+    id: &'static str,
+    label: &'static str,
+    probe: impl std::future::Future<Output = (HealthLevel, String)>,
+) -> HealthProbe {
+    let started = Instant::now();
+    let (level, message) = probe.await;
+    HealthProbe {
+        id,
+        label,
+        level,
+        message,
+        duration_ms: started.elapsed().as_millis() as i64,
+    }
+}
+
+async fn db_round_trip(conn: &DatabaseConnection) -> (HealthLevel, String) {
+    let started = Instant::now();
+    let result = ping(conn).await;
+    let ms = started.elapsed().as_millis() as i64;
+    match result {
+        Err(e) => (HealthLevel::Red, format!("SELECT 1 failed: {}", e)),
+        Ok(()) if ms > SLOW_DB_ROUND_TRIP_MS => (
+            HealthLevel::Yellow,
+            format!("Round-trip took {}ms (slow network or busy server).", ms),
+        ),
+        Ok(()) => (HealthLevel::Green, format!("Round-trip took {}ms.", ms)),
+    }
+}
+
+async fn ping(conn: &DatabaseConnection) -> anyhow::Result<()> {
+    match conn {
+        DatabaseConnection::Postgres(pool) => {
+            sqlx::query("SELECT 1").execute(pool).await?;
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+            client
+                .simple_query("SELECT 1")
+                .await?
+                .into_results()
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn schema_version(conn: &DatabaseConnection, engine: &str) -> (HealthLevel, String) {
+    let status = async {
+        let engine_version =
+            crate::api::installer::detect_engine_version(engine.to_string(), conn.clone()).await?;
+        let (manifest_path, migrations_path) = crate::api::installer::resolve_migrations_paths()?;
+        MigrationRunner::new(
+            conn.clone(),
+            manifest_path,
+            migrations_path,
+            engine.to_string(),
+            engine_version,
+        )
+        .await?
+        .status()
+        .await
+    }
+    .await;
+    match status {
+        Err(e) => (
+            HealthLevel::Yellow,
+            format!("Schema version could not be checked: {}", e),
+        ),
+        Ok(s) if !s.pending.is_empty() => (
+            HealthLevel::Red,
+            format!(
+                "{} migration(s) not applied: {}",
+                s.pending.len(),
+                s.pending.join(", ")
+            ),
+        ),
+        Ok(s) if !s.drifted.is_empty() => (
+            HealthLevel::Yellow,
+            format!(
+                "{} applied migration(s) changed since they ran: {}",
+                s.drifted.len(),
+                s.drifted
+                    .iter()
+                    .map(|d| d.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ),
+        Ok(s) => (
+            HealthLevel::Green,
+            format!(
+                "All {} migration(s) applied ({} {}).",
+                s.applied.len(),
+                s.engine,
+                s.engine_version
+            ),
+        ),
+    }
+}
+
+#[allow(unused_variables)]
+async fn service(mode: &str) -> (HealthLevel, String) {
+    #[cfg(windows)]
+    if mode == "windows" {
+        let name = super::service::WINDOWS_MAIN_SERVICE.service_name();
+        return match super::service::windows_service_state(&name).await {
+            Ok(Some(state)) if state == "RUNNING" => (
+                HealthLevel::Green,
+                format!("Service '{}' is running.", name),
+            ),
+            Ok(Some(state)) => (
+                HealthLevel::Red,
+                format!("Service '{}' is {}.", name, state),
+            ),
+            Ok(None) => (
+                HealthLevel::Red,
+                format!("Service '{}' is not installed.", name),
+            ),
+            Err(e) => (
+                HealthLevel::Yellow,
+                format!("Service state could not be read: {}", e),
+            ),
+        };
+    }
+    #[cfg(target_os = "linux")]
+    if mode == "linux" {
+        let name = super::service::linux_service_name();
+        return match super::service::get_linux_service_status(&name).await {
+            Ok(s) if s.active_state == "active" => {
+                (HealthLevel::Green, format!("Unit '{}' is active.", name))
+            }
+            Ok(s) => (
+                HealthLevel::Red,
+                format!(
+                    "Unit '{}' is {} ({}).",
+                    name,
+                    s.active_state,
+                    s.sub_state.as_deref().unwrap_or("unknown")
+                ),
+            ),
+            Err(e) => (
+                HealthLevel::Yellow,
+                format!("Unit state could not be read: {}", e),
+            ),
+        };
+    }
+    (
+        HealthLevel::Yellow,
+        "The service cannot be checked from this platform.".to_string(),
+    )
+}
+
+async fn containers(compose_file: &Path) -> (HealthLevel, String) {
+    let status = async {
+        let inv = super::docker::detect_compose_invocation().await?;
+        let out = super::docker::compose_ps(inv, compose_file).await?;
+        if out.exit_code != Some(0) {
+            anyhow::bail!("docker compose ps failed: {}", out.stderr.trim());
+        }
+        Ok(super::docker::parse_compose_ps_output(&out.stdout))
+    }
+    .await;
+    let status = match status {
+        Ok(s) => s,
+        Err(e) => {
+            return (
+                HealthLevel::Red,
+                format!("Container status could not be read: {}", e),
+            )
+        }
+    };
+    let list = |pred: &dyn Fn(&super::docker::ContainerStatus) -> bool| {
+        status
+            .containers
+            .iter()
+            .filter(|c| pred(c))
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if status.container_count == 0 {
+        (HealthLevel::Red, "No containers are running.".to_string())
+    } else if status
+        .containers
+        .iter()
+        .any(|c| c.health.as_deref() == Some("unhealthy"))
+    {
+        (
+            HealthLevel::Red,
+            format!(
+                "Unhealthy: {}",
+                list(&|c| c.health.as_deref() == Some("unhealthy"))
+            ),
+        )
+    } else if !status.all_running {
+        (
+            HealthLevel::Red,
+            format!(
+                "Not running: {}",
+                list(&|c| !c.state.eq_ignore_ascii_case("running"))
+            ),
+        )
+    } else if !status.all_healthy {
+        (
+            HealthLevel::Yellow,
+            format!(
+                "Still starting: {}",
+                list(&|c| c.health.as_deref() == Some("starting"))
+            ),
+        )
+    } else {
+        (
+            HealthLevel::Green,
+            format!(
+                "{} container(s) running and healthy.",
+                status.container_count
+            ),
+        )
+    }
+}
+
+async fn archive_destination(destination: &str) -> (HealthLevel, String) {
+    if destination.is_empty() {
+        return (
+            HealthLevel::Yellow,
+            "Archiving is enabled but no destination is configured.".to_string(),
+        );
+    }
+    let dir = Path::new(destination);
+    let probe = dir.join(format!(".cadalytix-health-{}", uuid::Uuid::new_v4()));
+    let result = async {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&probe, b"ok").await?;
+        tokio::fs::remove_file(&probe).await
+    }
+    .await;
+    match result {
+        Ok(()) => (HealthLevel::Green, format!("{} is writable.", destination)),
+        Err(e) => (
+            HealthLevel::Red,
+            format!("{} is not writable: {}", destination, e),
+        ),
+    }
+}
+
+/// In Docker mode the web container's healthcheck uses this endpoint, so a failure is red; a
+/// native service that does not answer (yet) is yellow.
+async fn web_health(docker: bool) -> (HealthLevel, String) {
+    let unreachable = if docker {
+        HealthLevel::Red
+    } else {
+        HealthLevel::Yellow
+    };
+    let client = match reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .no_proxy()
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => return (unreachable, format!("HTTP client unavailable: {}", e)),
+    };
+    match client.get(WEB_HEALTH_URL).send().await {
+        Ok(resp) if resp.status().is_success() => (
+            HealthLevel::Green,
+            format!("{} answered {}.", WEB_HEALTH_URL, resp.status()),
+        ),
+        Ok(resp) => (
+            unreachable,
+            format!("{} answered {}.", WEB_HEALTH_URL, resp.status()),
+        ),
+        Err(e) => (
+            unreachable,
+            format!("{} did not answer: {}", WEB_HEALTH_URL, e),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn overall_level_is_the_worst_check_and_archive_probe_writes() {
+        assert_eq!(overall_level([]), HealthLevel::Green);
+        assert_eq!(overall_level(["pass", "skip"]), HealthLevel::Green);
+        assert_eq!(overall_level(["pass", "warn", "skip"]), HealthLevel::Yellow);
+        assert_eq!(overall_level(["warn", "fail", "pass"]), HealthLevel::Red);
+        assert_eq!(HealthLevel::Yellow.status(), "warn");
+
+        let dir = std::env::temp_dir().join(format!("health-{}", uuid::Uuid::new_v4()));
+        let (level, _) = archive_destination(&dir.to_string_lossy()).await;
+        assert_eq!(level, HealthLevel::Green);
+        // The probe file is removed again.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        assert_eq!(archive_destination("").await.0, HealthLevel::Yellow);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,400 @@
+//! Host sizing: CPU, memory, NUMA layout and data disk against the projected load.
+//!
+//! The load is the expected ingest (call rows per day) and the historical volume the backfill
+//! will load (rows). Each picks a tier from `TIERS`; the larger tier sets the recommendation,
+//! since a small daily feed on top of a large backfill still needs the memory to cache it. A host
+//! below the recommendation gets warnings in the host preflight, never failures: the install
+//! works, it is just slow under load.
+//!
+//! Collection is best-effort. Cores come from `available_parallelism` (so container CPU limits
+//! count); memory, NUMA nodes and the data disk's media type from `/proc` and `/sys` on Linux and
+//! CIM on Windows (sockets stand in for NUMA nodes there). Anything not detected is left out of
+//! the assessment.
+
+use std::path::Path;
+
+use log::{info, warn};
+
+use crate::models::responses::{HostSizingDto, PreflightCheckDto};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+/// Installed RAM is reported a little low (firmware, integrated graphics); this fraction of the
+/// recommendation still counts as meeting it.
+const RAM_TOLERANCE: f64 = 0.9;
+
+/// Recommended host for loads up to `max_rows_per_day` and `max_historical_rows`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizingTier {
+    pub name: &'static str,
+    pub max_rows_per_day: u64,
+    pub max_historical_rows: u64,
+    pub cpu_cores: usize,
+    pub ram_gb: u64,
+    /// The data folder should be on an SSD.
+    pub ssd: bool,
+}
+
+pub const TIERS: [SizingTier; 4] = [
+    SizingTier {
+        name: "Small",
+        max_rows_per_day: 100_000,
+        max_historical_rows: 50_000_000,
+        cpu_cores: 4,
+        ram_gb: 8,
+        ssd: false,
+    },
+    SizingTier {
+        name: "Medium",
+        max_rows_per_day: 1_000_000,
+        max_historical_rows: 500_000_000,
+        cpu_cores: 8,
+        ram_gb: 16,
+        ssd: true,
+    },
+    SizingTier {
+        name: "Large",
+        max_rows_per_day: 5_000_000,
+        max_historical_rows: 2_500_000_000,
+        cpu_cores: 16,
+        ram_gb: 32,
+        ssd: true,
+    },
+    SizingTier {
+        name: "Extra large",
+        max_rows_per_day: u64::MAX,
+        max_historical_rows: u64::MAX,
+        cpu_cores: 32,
+        ram_gb: 64,
+        ssd: true,
+    },
+];
+
+/// The smallest tier that covers both the daily ingest and the historical volume.
+pub fn tier_for(rows_per_day: u64, historical_rows: u64) -> &'static SizingTier {
+    TIERS
+        .iter()
+        .find(|t| rows_per_day <= t.max_rows_per_day && historical_rows <= t.max_historical_rows)
+        .unwrap_or(&TIERS[TIERS.len() - 1])
+}
+
+/// What was detected about the host; `None` where detection failed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostResources {
+    pub cpu_cores: Option<usize>,
+    pub total_ram_bytes: Option<u64>,
+    pub numa_nodes: Option<u32>,
+    /// `Some(true)` for a spinning disk under the data folder.
+    pub data_disk_rotational: Option<bool>,
+}
+
+/// Detect the host's resources; `data_dir` picks the disk whose media type is reported.
+pub async fn collect(data_dir: &Path) -> HostResources {
+    let cpu_cores = std::thread::available_parallelism().ok().map(|n| n.get());
+    let anchor = crate::utils::disk::nearest_existing_ancestor(data_dir).await;
+    let mut resources = if cfg!(windows) {
+        collect_windows(anchor.as_deref()).await
+    } else {
+        collect_linux(anchor.as_deref()).await
+    };
+    resources.cpu_cores = cpu_cores;
+    info!(
+        "[PHASE: preflight] [STEP: host_sizing] Host resources (data_dir={:?}): {:?}",
+        data_dir, resources
+    );
+    resources
+}
+
+async fn collect_linux(anchor: Option<&Path>) -> HostResources {
+    let total_ram_bytes = tokio::fs::read_to_string("/proc/meminfo")
+        .await
+        .ok()
+        .and_then(|text| parse_mem_total_kb(&text))
+        .map(|kb| kb * 1024);
+
+    let mut numa_nodes = None;
+    if let Ok(mut entries) = tokio::fs::read_dir("/sys/devices/system/node").await {
+        let mut count = 0;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name
+                .strip_prefix("node")
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            {
+                count += 1;
+            }
+        }
+        numa_nodes = (count > 0).then_some(count);
+    }
+
+    let mut data_disk_rotational = None;
+    if let Some(anchor) = anchor {
+        let canonical = tokio::fs::canonicalize(anchor).await.ok();
+        let mountinfo = tokio::fs::read_to_string("/proc/self/mountinfo").await.ok();
+        if let (Some(canonical), Some(mountinfo)) = (canonical, mountinfo) {
+            if let Some(mount) =
+                crate::installation::storage_fs::parse_mountinfo(&mountinfo, &canonical)
+            {
+                data_disk_rotational = rotational(&mount.device).await;
+            }
+        }
+    }
+
+    HostResources {
+        cpu_cores: None,
+        total_ram_bytes,
+        numa_nodes,
+        data_disk_rotational,
+    }
+}
+
+/// `queue/rotational` of a block device (`major:minor`); a partition reads its disk's flag.
+/// Virtual devices (device mapper, NFS) have none or report 0.
+async fn rotational(device: &str) -> Option<bool> {
+    let base = Path::new("/sys/dev/block").join(device);
+    for candidate in [
+        base.join("queue/rotational"),
+        base.join("../queue/rotational"),
+    ] {
+        if let Ok(text) = tokio::fs::read_to_string(&candidate).await {
+            return match text.trim() {
+                "1" => Some(true),
+                "0" => Some(false),
+                _ => None,
+            };
+        }
+    }
+    None
+}
+
+async fn collect_windows(anchor: Option<&Path>) -> HostResources {
+    let drive = anchor
+        .map(|a| a.to_string_lossy().to_string())
+        .and_then(|a| a.chars().next().filter(|c| c.is_ascii_alphabetic()))
+        .unwrap_or('C');
+    let script = format!(
+        "$cs = Get-CimInstance -ClassName Win32_ComputerSystem; $media = ''; try {{ $n = (Get-Partition -DriveLetter {} -ErrorAction Stop | Get-Disk).Number; $media = (Get-PhysicalDisk | Where-Object DeviceId -eq \"$n\").MediaType }} catch {{}}; \"$($cs.TotalPhysicalMemory)|$($cs.NumberOfProcessors)|$media\"",
+        drive.to_ascii_uppercase()
+    );
+    let out = match crate::installation::run_cmd_with_timeout(
+        "powershell",
+        &[
+            "-NoProfile".to_string(),
+            "-NonInteractive".to_string(),
+            "-Command".to_string(),
+            script,
+        ],
+        tokio::time::Duration::from_secs(30),
+        "host_sizing_cim",
+    )
+    .await
+    {
+        Ok(out) => out.stdout,
+        Err(e) => {
+            warn!(
+                "[PHASE: preflight] [STEP: host_sizing] Host resource query failed: {}",
+                e
+            );
+            return HostResources::default();
+        }
+    };
+    let mut parts = out.trim().split('|');
+    let total_ram_bytes = parts.next().and_then(|v| v.trim().parse().ok());
+    let numa_nodes = parts.next().and_then(|v| v.trim().parse().ok());
+    let data_disk_rotational = match parts.next().map(str::trim) {
+        Some("HDD") => Some(true),
+        Some("SSD") => Some(false),
+        _ => None,
+    };
+    HostResources {
+        cpu_cores: None,
+        total_ram_bytes,
+        numa_nodes,
+        data_disk_rotational,
+    }
+}
+
+/// `MemTotal` from `/proc/meminfo`, in kB.
+pub fn parse_mem_total_kb(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let rest = line.strip_prefix("MemTotal:")?;
+        rest.split_whitespace().next()?.parse().ok()
+    })
+}
+
+fn gib(bytes: u64) -> f64 {
+    bytes as f64 / GIB as f64
+}
+
+/// Compare `host` with the tier for the projected load: the recorded assessment, and one host
+/// preflight check per detected resource (Warn where it falls short).
+pub fn assess(
+    host: &HostResources,
+    rows_per_day: u64,
+    historical_rows: u64,
+) -> (HostSizingDto, Vec<PreflightCheckDto>) {
+    let tier = tier_for(rows_per_day, historical_rows);
+    let load = format!(
+        "{} tier for {} rows/day and {} historical rows",
+        tier.name, rows_per_day, historical_rows
+    );
+    let check = |name: &str, ok: bool, detail: String| PreflightCheckDto {
+        name: name.to_string(),
+        status: if ok { "Pass" } else { "Warn" }.to_string(),
+        detail,
+    };
+    let mut checks = Vec::new();
+    let mut shortfalls = Vec::new();
+
+    if let Some(cores) = host.cpu_cores {
+        let ok = cores >= tier.cpu_cores;
+        if !ok {
+            shortfalls.push(format!(
+                "{} CPU cores ({} recommended)",
+                cores, tier.cpu_cores
+            ));
+        }
+        checks.push(check(
+            "CPU Cores",
+            ok,
+            format!(
+                "{} cores available ({} recommended, {})",
+                cores, tier.cpu_cores, load
+            ),
+        ));
+    }
+    if let Some(ram) = host.total_ram_bytes {
+        let ok = ram as f64 >= (tier.ram_gb * GIB) as f64 * RAM_TOLERANCE;
+        if !ok {
+            shortfalls.push(format!(
+                "{:.1} GB RAM ({} GB recommended)",
+                gib(ram),
+                tier.ram_gb
+            ));
+        }
+        checks.push(check(
+            "Memory (RAM)",
+            ok,
+            format!(
+                "{:.1} GB installed ({} GB recommended, {})",
+                gib(ram),
+                tier.ram_gb,
+                load
+            ),
+        ));
+    }
+    if let (Some(nodes), Some(ram)) = (host.numa_nodes, host.total_ram_bytes) {
+        // The database cache (about half the recommended RAM) should fit in one node's local
+        // memory; beyond that, page reads cross the interconnect.
+        let per_node = ram / u64::from(nodes.max(1));
+        let ok = nodes <= 1 || per_node as f64 >= (tier.ram_gb * GIB) as f64 * RAM_TOLERANCE / 2.0;
+        if !ok {
+            shortfalls.push(format!("{:.1} GB per NUMA node", gib(per_node)));
+        }
+        checks.push(check(
+            "NUMA Layout",
+            ok,
+            if nodes <= 1 {
+                "1 NUMA node; all memory is local".to_string()
+            } else if ok {
+                format!("{} NUMA nodes, {:.1} GB each", nodes, gib(per_node))
+            } else {
+                format!(
+                    "{} NUMA nodes with {:.1} GB each; the database cache will span nodes. Prefer fewer, larger nodes (or one socket) for the {} tier",
+                    nodes,
+                    gib(per_node),
+                    tier.name
+                )
+            },
+        ));
+    }
+    if let Some(rotational) = host.data_disk_rotational {
+        let ok = !(rotational && tier.ssd);
+        if !ok {
+            shortfalls.push("data folder on a spinning disk (SSD recommended)".to_string());
+        }
+        checks.push(check(
+            "Data Disk",
+            ok,
+            match (rotational, tier.ssd) {
+                (false, _) => "Data folder is on an SSD".to_string(),
+                (true, false) => {
+                    "Data folder is on a spinning disk (enough for this load)".to_string()
+                }
+                (true, true) => format!(
+                    "Data folder is on a spinning disk; the {} tier needs an SSD",
+                    tier.name
+                ),
+            },
+        ));
+    }
+
+    if !shortfalls.is_empty() {
+        warn!(
+            "[PHASE: preflight] [STEP: host_sizing] Host under-provisioned for the {} tier: {}",
+            tier.name,
+            shortfalls.join(", ")
+        );
+    }
+    let sizing = HostSizingDto {
+        tier: tier.name.to_string(),
+        rows_per_day,
+        historical_rows,
+        recommended_cpu_cores: tier.cpu_cores,
+        recommended_ram_gb: tier.ram_gb,
+        cpu_cores: host.cpu_cores,
+        total_ram_bytes: host.total_ram_bytes,
+        numa_nodes: host.numa_nodes,
+        data_disk: host
+            .data_disk_rotational
+            .map(|r| if r { "HDD" } else { "SSD" }.to_string()),
+        under_provisioned: !shortfalls.is_empty(),
+        shortfalls,
+    };
+    (sizing, checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn larger_of_ingest_and_history_sets_the_tier() {
+        assert_eq!(tier_for(50_000, 0).name, "Small");
+        assert_eq!(tier_for(50_000, 400_000_000).name, "Medium");
+        assert_eq!(tier_for(3_000_000, 0).name, "Large");
+        assert_eq!(tier_for(u64::MAX, u64::MAX).name, "Extra large");
+        assert_eq!(
+            parse_mem_total_kb("MemTotal:       16318460 kB\nMemFree: 1 kB\n"),
+            Some(16_318_460)
+        );
+
+        // 16 GB reported as 15.6 GiB still meets Medium; 4 cores and an HDD do not.
+        let host = HostResources {
+            cpu_cores: Some(4),
+            total_ram_bytes: Some(16_318_460 * 1024),
+            numa_nodes: Some(2),
+            data_disk_rotational: Some(true),
+        };
+        let (sizing, checks) = assess(&host, 500_000, 0);
+        assert_eq!(sizing.tier, "Medium");
+        assert!(sizing.under_provisioned);
+        assert_eq!(sizing.shortfalls.len(), 2);
+        let status = |name: &str| {
+            checks
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap()
+                .status
+                .clone()
+        };
+        assert_eq!(status("CPU Cores"), "Warn");
+        assert_eq!(status("Memory (RAM)"), "Pass");
+        assert_eq!(status("NUMA Layout"), "Pass");
+        assert_eq!(status("Data Disk"), "Warn");
+
+        let (sizing, checks) = assess(&HostResources::default(), 500_000, 0);
+        assert!(!sizing.under_provisioned);
+        assert!(checks.is_empty());
+    }
+}
@@ -0,0 +1,493 @@
+// Read-only inspection of an existing install (`--inspect`).
+//
+// The first thing support asks for on a ticket. Locates the install through its manifest (the
+// given install folder or manifest file, else the last one recorded in the log folder) and reports,
+// in one place:
+// - the manifest itself and whether the deployed files still match its checksums
+// - config DB reachability, applied migrations and the schema diff (needs
+//   CADALYTIX_CONFIG_DB_CONNECTION; skipped without it)
+// - license status (config DB, else the stored entitlement or license file)
+// - service or container state and the web health endpoint
+// - archive ledger health
+//
+// Nothing is modified: no repairs, no migrations, no probe files at the archive destination. The
+// ledger check leaves its usual report in the log folder.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::api::preflight_report::CONFIG_DB_CONNECTION_ENV;
+use crate::archiver::{self, ArchiveVerifyArgs};
+use crate::database::connection::DatabaseConnection;
+use crate::database::migrations::MigrationRunner;
+use crate::database::platform_db::PlatformDbAdapter;
+use crate::database::schema_verifier::SchemaVerifier;
+use crate::models::responses::LicenseStatusResponse;
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+
+use super::health::{self, HealthLevel};
+use super::{manifest, payload_manifest};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectSection {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// "pass" | "warn" | "fail" | "skip"
+    pub status: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectReport {
+    pub generated_utc: String,
+    pub manifest_path: String,
+    /// Worst section status ("pass" | "warn" | "fail").
+    pub overall_status: &'static str,
+    pub sections: Vec<InspectSection>,
+}
+
+impl InspectReport {
+    fn new(manifest_path: String, sections: Vec<InspectSection>) -> Self {
+        let overall_status = health::overall_level(sections.iter().map(|s| s.status)).status();
+        Self {
+            generated_utc: Utc::now().to_rfc3339(),
+            manifest_path,
+            overall_status,
+            sections,
+        }
+    }
+
+    /// Plain-text report, one line per section.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "CADalytix install inspection ({})\nManifest: {}\n\n",
+            self.generated_utc, self.manifest_path
+        );
+        for s in &self.sections {
+            out.push_str(&format!(
+                "{:<5} {:<34} {}\n",
+                s.status.to_ascii_uppercase(),
+                s.label,
+                s.message
+            ));
+        }
+        out.push_str(&format!(
+            "\nOverall: {}\n",
+            self.overall_status.to_ascii_uppercase()
+        ));
+        out
+    }
+
+    /// 0 when everything passed, 2 on warnings, 3 on failures (as `--preflight-only`).
+    pub fn exit_code(&self) -> i32 {
+        match self.overall_status {
+            "pass" => 0,
+            "warn" => 2,
+            _ => 3,
+        }
+    }
+}
+
+fn section(
+    id: &'static str,
+    label: &'static str,
+    status: &'static str,
+    message: impl Into<String>,
+) -> InspectSection {
+    InspectSection {
+        id,
+        label,
+        status,
+        message: message.into(),
+    }
+}
+
+/// Instance settings the runtime probes need, as recorded in the manifest.
+fn manifest_settings(manifest: &serde_json::Value) -> HashMap<String, String> {
+    let mut settings = HashMap::new();
+    for (key, field) in [
+        ("Setup:InstallMode", "installMode"),
+        ("Setup:DestinationFolder", "destinationFolder"),
+    ] {
+        if let Some(value) = manifest.get(field).and_then(|v| v.as_str()) {
+            settings.insert(key.to_string(), value.to_string());
+        }
+    }
+    settings
+}
+
+fn manifest_section(manifest: &serde_json::Value) -> InspectSection {
+    let field = |name: &str| {
+        manifest
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    section(
+        "manifest",
+        "Install manifest",
+        "pass",
+        format!(
+            "{} install ({}) at {}, installed {} by installer {}.",
+            field("installMode"),
+            field("installationType"),
+            field("destinationFolder"),
+            field("createdUtc"),
+            field("installerVersion")
+        ),
+    )
+}
+
+fn license_section(status: &LicenseStatusResponse) -> InspectSection {
+    let mut message = status.message.clone();
+    if let Some(e) = &status.entitlement {
+        message.push_str(&format!(" ({}", e.license_mode));
+        if let Some(expires) = e.expires_at_utc {
+            message.push_str(&format!(", expires {}", expires.format("%Y-%m-%d")));
+        }
+        if !e.features.is_empty() {
+            message.push_str(&format!(", features: {}", e.features.join(", ")));
+        }
+        message.push(')');
+    }
+    let status = match (status.is_active, status.grace_days_remaining) {
+        (true, None) => "pass",
+        (true, Some(_)) => "warn",
+        (false, _) => "fail",
+    };
+    section("license", "License", status, message)
+}
+
+/// Config DB sections; returns the connection for the license and settings lookups.
+async fn database_sections(
+    manifest: Option<&serde_json::Value>,
+    sections: &mut Vec<InspectSection>,
+) -> Option<DatabaseConnection> {
+    let Some(conn_str) = std::env::var(CONFIG_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    else {
+        let endpoint = manifest
+            .and_then(|m| m.get("database"))
+            .map(|db| {
+                format!(
+                    " ({} on {})",
+                    db.get("name").and_then(|v| v.as_str()).unwrap_or("?"),
+                    db.get("host").and_then(|v| v.as_str()).unwrap_or("?")
+                )
+            })
+            .unwrap_or_default();
+        sections.push(section(
+            "config_db",
+            "Config DB",
+            "skip",
+            format!(
+                "Set {} to check the config database{}.",
+                CONFIG_DB_CONNECTION_ENV, endpoint
+            ),
+        ));
+        return None;
+    };
+
+    let engine = crate::api::installer::guess_engine(&conn_str);
+    let conn = match crate::api::installer::connect_with_retry(engine.clone(), conn_str).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            sections.push(section(
+                "config_db",
+                "Config DB",
+                "fail",
+                format!("Unable to connect ({}): {}", engine, e),
+            ));
+            return None;
+        }
+    };
+    sections.push(section(
+        "config_db",
+        "Config DB",
+        "pass",
+        format!("Connected ({}).", engine),
+    ));
+
+    let status = async {
+        let engine_version =
+            crate::api::installer::detect_engine_version(engine.clone(), conn.clone()).await?;
+        let (manifest_path, migrations_path) = crate::api::installer::resolve_migrations_paths()?;
+        MigrationRunner::new(
+            conn.clone(),
+            manifest_path,
+            migrations_path,
+            engine.clone(),
+            engine_version,
+        )
+        .await?
+        .status()
+        .await
+    }
+    .await;
+    sections.push(match status {
+        Ok(s) if !s.pending.is_empty() => section(
+            "migrations",
+            "Migrations",
+            "fail",
+            format!("{} not applied: {}", s.pending.len(), s.pending.join(", ")),
+        ),
+        Ok(s) if !s.drifted.is_empty() => section(
+            "migrations",
+            "Migrations",
+            "warn",
+            format!(
+                "{} applied migration(s) changed since they ran: {}",
+                s.drifted.len(),
+                s.drifted
+                    .iter()
+                    .map(|d| d.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ),
+        Ok(s) => section(
+            "migrations",
+            "Migrations",
+            "pass",
+            format!("{} applied, none pending.", s.applied.len()),
+        ),
+        Err(e) => section(
+            "migrations",
+            "Migrations",
+            "warn",
+            format!("Migration status could not be read: {}", e),
+        ),
+    });
+
+    sections.push(
+        match SchemaVerifier::new(conn.clone()).verify_all_schemas().await {
+            Ok(results) => {
+                let problems: Vec<String> = results
+                    .iter()
+                    .filter(|(_, r)| !r.valid)
+                    .map(|(schema, r)| {
+                        format!(
+                            "{}: {} missing table(s), {} missing column(s), {} missing index(es), {} type mismatch(es)",
+                            schema,
+                            r.missing_tables.len(),
+                            r.missing_columns.len(),
+                            r.missing_indexes.len(),
+                            r.type_mismatches.len()
+                        )
+                    })
+                    .collect();
+                if problems.is_empty() {
+                    section("schema", "Schema", "pass", "Matches the expected schema.")
+                } else {
+                    section("schema", "Schema", "fail", problems.join("; "))
+                }
+            }
+            Err(e) => section(
+                "schema",
+                "Schema",
+                "warn",
+                format!("Schema could not be read: {}", e),
+            ),
+        },
+    );
+    Some(conn)
+}
+
+async fn ledger_section(args: &[String]) -> InspectSection {
+    let verify_args = ArchiveVerifyArgs::from_args(args);
+    let ledger_path = match &verify_args.ledger_path {
+        Some(path) => path.clone(),
+        None => match crate::utils::path_resolver::resolve_log_folder() {
+            Ok(log_dir) => archiver::default_ledger_path(&log_dir),
+            Err(e) => {
+                return section(
+                    "archive_ledger",
+                    "Archive ledger",
+                    "warn",
+                    format!("Log folder could not be resolved: {}", e),
+                )
+            }
+        },
+    };
+    if !ledger_path.is_file() {
+        return section(
+            "archive_ledger",
+            "Archive ledger",
+            "skip",
+            format!(
+                "No archive ledger at {} (nothing archived yet).",
+                ledger_path.display()
+            ),
+        );
+    }
+    match archiver::archive_verify_ledger(verify_args).await {
+        Ok(report) if report.healthy => section(
+            "archive_ledger",
+            "Archive ledger",
+            "pass",
+            format!("{} month(s) verified.", report.ok_count),
+        ),
+        Ok(report) => section(
+            "archive_ledger",
+            "Archive ledger",
+            "fail",
+            format!(
+                "{} of {} month(s) missing, {} corrupted, {} unreadable, {} with a key mismatch.",
+                report.missing_count,
+                report.total_months,
+                report.corrupted_count,
+                report.unreadable_count,
+                report.key_mismatch_count
+            ),
+        ),
+        Err(e) => section(
+            "archive_ledger",
+            "Archive ledger",
+            "warn",
+            format!("Ledger could not be verified: {}", e),
+        ),
+    }
+}
+
+/// `--inspect[=<install folder or manifest>] [--ledger=<path>] [--archive-dir=<path>]`.
+pub async fn inspect_cli(args: &[String]) -> Result<InspectReport> {
+    let arg = args.iter().find_map(|a| a.strip_prefix("--inspect="));
+    let manifest_path = manifest::manifest_path(arg)?;
+    info!(
+        "[PHASE: inspect] [STEP: start] Inspecting install (manifest={:?})",
+        manifest_path
+    );
+
+    let mut sections = Vec::new();
+    let manifest = match manifest::read_manifest(&manifest_path) {
+        Ok(m) => {
+            sections.push(manifest_section(&m));
+            sections.push(files_section(&manifest_path).await);
+            Some(m)
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: inspect] [STEP: manifest] No usable install manifest: {:?}",
+                e
+            );
+            sections.push(section(
+                "manifest",
+                "Install manifest",
+                "fail",
+                e.to_string(),
+            ));
+            None
+        }
+    };
+
+    let db = database_sections(manifest.as_ref(), &mut sections).await;
+
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    let secrets = Arc::new(SecretProtector::new(default_key_path(&log_dir)));
+    let mut settings = manifest.as_ref().map(manifest_settings).unwrap_or_default();
+    if let Some(conn) = &db {
+        // The instance's own settings win over the manifest (e.g. after a mode change).
+        let platform_db = PlatformDbAdapter::new(conn.clone(), Arc::clone(&secrets));
+        settings.extend(platform_db.get_all_settings().await.unwrap_or_default());
+    }
+    let license = crate::api::license::license_status(db, &secrets).await;
+    sections.push(license_section(&license));
+
+    for probe in health::run_runtime_checks(&settings).await {
+        sections.push(section(
+            probe.id,
+            probe.label,
+            probe.level.status(),
+            probe.message,
+        ));
+    }
+    sections.push(ledger_section(args).await);
+
+    let report = InspectReport::new(manifest_path.display().to_string(), sections);
+    info!(
+        "[PHASE: inspect] [STEP: done] Inspection finished (overall={})",
+        report.overall_status
+    );
+    Ok(report)
+}
+
+async fn files_section(manifest_path: &Path) -> InspectSection {
+    match payload_manifest::install_manifest_drift(manifest_path).await {
+        Ok(drift) if drift.is_empty() => section(
+            "files",
+            "Deployed files",
+            "pass",
+            "All deployed files match the install manifest.",
+        ),
+        Ok(drift) => section(
+            "files",
+            "Deployed files",
+            "fail",
+            format!(
+                "{} file(s) differ from the install manifest: {}",
+                drift.len(),
+                drift.join(", ")
+            ),
+        ),
+        Err(e) => section(
+            "files",
+            "Deployed files",
+            "warn",
+            format!("Files could not be checked: {}", e),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_sections_and_takes_the_worst_status() {
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "installMode": "Docker",
+            "installationType": "typical",
+            "destinationFolder": "/opt/cadalytix",
+            "createdUtc": "2026-01-05T10:00:00Z",
+            "installerVersion": "1.4.0"
+        });
+        let settings = manifest_settings(&manifest);
+        assert_eq!(settings["Setup:InstallMode"], "Docker");
+        assert_eq!(settings["Setup:DestinationFolder"], "/opt/cadalytix");
+
+        let license = license_section(&LicenseStatusResponse {
+            is_active: true,
+            entitlement: None,
+            message: "License is in grace period (3 days remaining)".to_string(),
+            grace_days_remaining: Some(3),
+        });
+        assert_eq!(license.status, "warn");
+
+        let report = InspectReport::new(
+            "/opt/cadalytix/installer-artifacts/install-manifest.json".to_string(),
+            vec![
+                manifest_section(&manifest),
+                section("config_db", "Config DB", "skip", "not configured"),
+                license,
+            ],
+        );
+        assert_eq!(report.overall_status, "warn");
+        assert_eq!(report.exit_code(), 2);
+        let text = report.to_text();
+        assert!(text.contains("PASS  Install manifest"), "{}", text);
+        assert!(text.contains("Docker install (typical) at /opt/cadalytix"));
+        assert!(text.contains("SKIP  Config DB"));
+        assert!(text.ends_with("Overall: WARN\n"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Install layout: where an install's binaries, data, logs and temp files go.
+//!
+//! The Destination page captures the install folder; its advanced layout view can put each of
+//! the four on its own path (data on a separate volume, logs on a monitored share, ...). Paths
+//! left empty take defaults under the install folder, with the data folder named for the config
+//! DB engine. The resolved layout is created during deploy and recorded in the install config and
+//! the manifest. Each path's volume is checked for free space before the install (and in the
+//! dry-run plan); nothing here partitions or mounts anything.
+
+use std::path::{Path, PathBuf};
+
+use crate::api::installer::StartInstallRequest;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Free space wanted per path when nothing more specific is known.
+const BINARIES_MIN_FREE_BYTES: u64 = 5 * GIB;
+const DATA_MIN_FREE_BYTES: u64 = 10 * GIB;
+const LOGS_MIN_FREE_BYTES: u64 = GIB;
+const TEMP_MIN_FREE_BYTES: u64 = 2 * GIB;
+
+/// Layout paths as entered; empty means the default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLayoutConfig {
+    #[serde(default)]
+    pub binaries: String,
+    #[serde(default)]
+    pub data: String,
+    #[serde(default)]
+    pub logs: String,
+    #[serde(default)]
+    pub temp: String,
+}
+
+impl InstallLayoutConfig {
+    /// True when no path was customised.
+    pub fn is_default(&self) -> bool {
+        self.entries().iter().all(|(_, p)| p.trim().is_empty())
+    }
+
+    fn entries(&self) -> [(&'static str, &str); 4] {
+        [
+            ("binaries", &self.binaries),
+            ("data", &self.data),
+            ("logs", &self.logs),
+            ("temp", &self.temp),
+        ]
+    }
+}
+
+/// Every path of an install, defaults filled in.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLayout {
+    pub binaries: PathBuf,
+    pub data: PathBuf,
+    pub logs: PathBuf,
+    pub temp: PathBuf,
+}
+
+impl InstallLayout {
+    /// (role, path) in display order.
+    pub fn entries(&self) -> [(&'static str, &Path); 4] {
+        [
+            ("binaries", &self.binaries),
+            ("data", &self.data),
+            ("logs", &self.logs),
+            ("temp", &self.temp),
+        ]
+    }
+}
+
+/// Default layout for an install folder: binaries in it, the rest in subfolders. Windows installs
+/// use the usual capitalised folder names; the data folder is per engine so a later engine switch
+/// never shares files.
+pub fn default_layout(install_mode: &str, engine: &str, destination: &str) -> InstallLayout {
+    let root = PathBuf::from(destination.trim());
+    let windows = install_mode.trim().eq_ignore_ascii_case("windows");
+    let engine_dir = match (engine, windows) {
+        ("postgres", true) => "PostgreSQL",
+        ("postgres", false) => "postgresql",
+        (_, true) => "SQLServer",
+        (_, false) => "mssql",
+    };
+    let (data, logs, temp) = if windows {
+        ("Data", "Logs", "Temp")
+    } else {
+        ("data", "logs", "tmp")
+    };
+    InstallLayout {
+        binaries: root.clone(),
+        data: root.join(data).join(engine_dir),
+        logs: root.join(logs),
+        temp: root.join(temp),
+    }
+}
+
+/// The layout of `req`: its custom paths, defaults for the rest.
+pub fn resolve(req: &StartInstallRequest) -> InstallLayout {
+    let engine = crate::api::installer::guess_engine(&req.config_db_connection_string);
+    let defaults = default_layout(&req.install_mode, &engine, &req.destination_folder);
+    // A remote install's paths are on its Linux server, whatever this machine's separator.
+    let remote = req.remote.is_some();
+    let pick = |custom: &str, default: PathBuf| match (custom.trim(), remote) {
+        ("", false) => default,
+        ("", true) => PathBuf::from(crate::installation::remote::unix_path(&default)),
+        (p, _) => PathBuf::from(p),
+    };
+    InstallLayout {
+        binaries: pick(&req.layout.binaries, defaults.binaries),
+        data: pick(&req.layout.data, defaults.data),
+        logs: pick(&req.layout.logs, defaults.logs),
+        temp: pick(&req.layout.temp, defaults.temp),
+    }
+}
+
+/// Custom paths must be absolute (checked on the machine the install runs on).
+pub fn validate(config: &InstallLayoutConfig) -> Result<(), String> {
+    for (role, path) in config.entries() {
+        let path = path.trim();
+        if !path.is_empty() && !Path::new(path).is_absolute() {
+            return Err(format!(
+                "The {} folder must be an absolute path (got '{}').",
+                role, path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Free space wanted for `role`; the data folder needs room for the new database's size cap.
+pub fn min_free_bytes(role: &str, max_db_size_gb: u32) -> u64 {
+    match role {
+        "binaries" => BINARIES_MIN_FREE_BYTES,
+        "data" if max_db_size_gb > 0 => u64::from(max_db_size_gb) * GIB,
+        "data" => DATA_MIN_FREE_BYTES,
+        "logs" => LOGS_MIN_FREE_BYTES,
+        _ => TEMP_MIN_FREE_BYTES,
+    }
+}
+
+/// Free space of one layout path.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceCheck {
+    pub role: &'static str,
+    pub path: PathBuf,
+    /// `None` when the volume could not be measured (no existing parent, unsupported OS, ...).
+    pub free_bytes: Option<u64>,
+    pub required_bytes: u64,
+}
+
+impl SpaceCheck {
+    /// Known to be too small; an unmeasured volume counts as fine (reported, not enforced).
+    pub fn is_short(&self) -> bool {
+        self.free_bytes
+            .is_some_and(|free| free < self.required_bytes)
+    }
+}
+
+/// Measure the volume of every layout path (through its nearest existing parent).
+pub async fn check_free_space(layout: &InstallLayout, max_db_size_gb: u32) -> Vec<SpaceCheck> {
+    let mut checks = Vec::new();
+    for (role, path) in layout.entries() {
+        let free_bytes = match crate::utils::disk::nearest_existing_ancestor(path).await {
+            Some(anchor) => {
+                crate::utils::disk::get_free_space_bytes_for_path(&anchor.to_string_lossy())
+                    .await
+                    .ok()
+            }
+            None => None,
+        };
+        checks.push(SpaceCheck {
+            role,
+            path: path.to_path_buf(),
+            free_bytes,
+            required_bytes: min_free_bytes(role, max_db_size_gb),
+        });
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_paths_take_engine_specific_defaults() {
+        let layout = default_layout("docker", "postgres", "/opt/cadalytix");
+        assert_eq!(layout.binaries, PathBuf::from("/opt/cadalytix"));
+        assert_eq!(layout.data, PathBuf::from("/opt/cadalytix/data/postgresql"));
+        assert_eq!(layout.temp, PathBuf::from("/opt/cadalytix/tmp"));
+        assert!(default_layout("windows", "sqlserver", "C:\\CADalytix")
+            .data
+            .ends_with("SQLServer"));
+
+        let mut req: StartInstallRequest = serde_json::from_value(serde_json::json!({
+            "installMode": "linux",
+            "installationType": "custom",
+            "destinationFolder": "/opt/cadalytix",
+            "configDbConnectionString": "Server=db;Database=cadalytix",
+            "callDataConnectionString": "",
+            "sourceObjectName": "dbo.Calls",
+            "storage": {
+                "mode": "defaults",
+                "location": "system",
+                "customPath": "",
+                "retentionPolicy": "18",
+                "maxDiskGb": ""
+            },
+            "mappings": {},
+            "mappingOverride": false,
+            "layout": { "data": "/srv/cadalytix-data", "logs": " " }
+        }))
+        .unwrap();
+        let layout = resolve(&req);
+        assert_eq!(layout.data, PathBuf::from("/srv/cadalytix-data"));
+        assert_eq!(layout.logs, PathBuf::from("/opt/cadalytix/logs"));
+        assert!(!req.layout.is_default());
+
+        assert!(validate(&req.layout).is_ok());
+        req.layout.temp = "relative/tmp".to_string();
+        assert!(validate(&req.layout).unwrap_err().contains("temp"));
+
+        assert_eq!(min_free_bytes("data", 50), 50 * GIB);
+        let check = SpaceCheck {
+            role: "logs",
+            path: layout.logs.clone(),
+            free_bytes: Some(GIB / 2),
+            required_bytes: min_free_bytes("logs", 0),
+        };
+        assert!(check.is_short());
+        assert!(!SpaceCheck {
+            free_bytes: None,
+            ..check
+        }
+        .is_short());
+    }
+}
@@ -240,8 +240,12 @@ pub async fn get_free_space_bytes_linux(path: &Path) -> Result<u64> {
 // ============================================================================
 
 use crate::api::installer::{InstallArtifacts, ProgressEmitter, ProgressPayload, StartInstallRequest};
-use crate::installation::files::{collect_files_recursive, copy_file_with_retries_and_sha256};
-use crate::installation::service::{install_and_start_linux_service, is_linux_service_running, SERVICE_NAME};
+use crate::installation::files::{
+    collect_files_recursive, copy_files_parallel, copy_workers, plan_delta, CopyJob,
+};
+use crate::installation::service::{
+    install_and_start_linux_service, is_linux_service_running, linux_service_name,
+};
 use crate::utils::path_resolver::resolve_deployment_folder;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -360,35 +364,61 @@ pub async fn install_linux_native(
         sources.push((f, dst));
     }
 
-    // Copy files
-    let total_files = sources.len().max(1);
-    for (i, (src, dst)) in sources.iter().enumerate() {
-        if let Some(parent) = dst.parent() {
+    // Copy files (bounded concurrency, each copy re-verified; unchanged files skipped on upgrade)
+    let jobs: Vec<CopyJob> = sources
+        .into_iter()
+        .map(|(src, dst)| CopyJob {
+            src,
+            dst,
+            expected_sha256: None,
+        })
+        .collect();
+    let plan = plan_delta(jobs, copy_workers()).await?;
+    info!(
+        "[PHASE: installation] [STEP: linux_copy] Delta plan (added={}, updated={}, unchanged={})",
+        plan.added,
+        plan.updated,
+        plan.unchanged.len()
+    );
+    let mut deployed = plan.unchanged;
+    let total_files = plan.copy.len().max(1);
+    for job in &plan.copy {
+        if let Some(parent) = job.dst.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
-        let (_bytes, sha256) = copy_file_with_retries_and_sha256(src, dst, "linux_deploy_copy").await?;
-
-        let rel_path = dst
+    }
+    let copied = copy_files_parallel(
+        plan.copy,
+        copy_workers(),
+        "linux_deploy_copy",
+        |done, _, _| {
+            // Emit progress every 10 files or at start/end
+            let i = done - 1;
+            if i == 0 || i == total_files - 1 || i % 10 == 0 {
+                let pct = 15 + ((i * 50) / total_files) as i32;
+                emit_progress(ProgressPayload {
+                    correlation_id: correlation_id.to_string(),
+                    step: "linux_copy".to_string(),
+                    severity: "info".to_string(),
+                    phase: "install".to_string(),
+                    percent: pct.min(65),
+                    message: format!("Copying files... ({}/{})", done, total_files),
+                    elapsed_ms: Some(started.elapsed().as_millis()),
+                    eta_ms: None,
+                });
+            }
+        },
+    )
+    .await?;
+    deployed.extend(copied);
+    for file in deployed {
+        let rel_path = file
+            .dst
             .strip_prefix(&dest_root)
-            .unwrap_or(dst)
+            .unwrap_or(&file.dst)
             .to_string_lossy()
             .replace('\\', "/");
-        manifest_files.insert(rel_path, sha256);
-
-        // Emit progress every 10 files or at start/end
-        if i == 0 || i == total_files - 1 || i % 10 == 0 {
-            let pct = 15 + ((i * 50) / total_files) as i32;
-            emit_progress(ProgressPayload {
-                correlation_id: correlation_id.to_string(),
-                step: "linux_copy".to_string(),
-                severity: "info".to_string(),
-                phase: "install".to_string(),
-                percent: pct.min(65),
-                message: format!("Copying files... ({}/{})", i + 1, total_files),
-                elapsed_ms: Some(started.elapsed().as_millis()),
-                eta_ms: None,
-            });
-        }
+        manifest_files.insert(rel_path, file.sha256);
     }
 
     // Step 4: Find and set executable permissions
@@ -423,7 +453,8 @@ pub async fn install_linux_native(
         eta_ms: None,
     });
 
-    install_and_start_linux_service(SERVICE_NAME, &exec_path, &dest_root, None).await?;
+    let service_name = linux_service_name();
+    install_and_start_linux_service(&service_name, &exec_path, &dest_root, None).await?;
 
     // Step 6: Verify service is running
     emit_progress(ProgressPayload {
@@ -437,12 +468,12 @@ pub async fn install_linux_native(
         eta_ms: None,
     });
 
-    let running = is_linux_service_running(SERVICE_NAME).await?;
+    let running = is_linux_service_running(&service_name).await?;
     if !running {
         anyhow::bail!(
             "Service '{}' is not running after installation. Check logs with: journalctl -u {}",
-            SERVICE_NAME,
-            SERVICE_NAME
+            service_name,
+            service_name
         );
     }
 
@@ -468,6 +499,7 @@ pub async fn install_linux_native(
         manifest_path: None,
         mapping_path: None,
         config_path: None,
+        plan_path: None,
     })
 }
 
@@ -0,0 +1,231 @@
+//! Mandatory access control (SELinux / AppArmor) for the Linux install mode.
+//!
+//! On SELinux hosts (RHEL, Rocky, Alma) files copied into `/opt` or a custom folder keep
+//! whatever context they were created with, so the install succeeds and systemd is then
+//! denied executing the binary or writing to the data and log folders. `label_install`
+//! registers persistent file-context rules for each install folder with `semanage fcontext`
+//! and applies them with `restorecon`, so they also survive a later relabel. Without
+//! `semanage` it falls back to `chcon`, which works until the next relabel.
+//!
+//! AppArmor only confines programs with a matching profile, so it is reported during preflight
+//! and never changed.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::{info, warn};
+use tokio::time::Duration;
+
+use crate::installation::layout::InstallLayout;
+use crate::installation::linux::is_running_as_root;
+use crate::installation::run_cmd_with_timeout;
+
+const SELINUX_ENFORCE: &str = "/sys/fs/selinux/enforce";
+const APPARMOR_ENABLED: &str = "/sys/module/apparmor/parameters/enabled";
+const APPARMOR_PROFILES: &str = "/sys/kernel/security/apparmor/profiles";
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelinuxMode {
+    Disabled,
+    Permissive,
+    Enforcing,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppArmorProfiles {
+    pub enforce: Vec<String>,
+    pub complain: usize,
+}
+
+/// Current SELinux mode, from selinuxfs (no `getenforce` needed).
+pub fn selinux_mode() -> SelinuxMode {
+    match std::fs::read_to_string(SELINUX_ENFORCE) {
+        Ok(v) if v.trim() == "1" => SelinuxMode::Enforcing,
+        Ok(_) => SelinuxMode::Permissive,
+        Err(_) => SelinuxMode::Disabled,
+    }
+}
+
+/// Loaded AppArmor profiles, or None when AppArmor is not enabled. The profile list needs root;
+/// without it the result is empty.
+pub fn apparmor_profiles() -> Option<AppArmorProfiles> {
+    let enabled = std::fs::read_to_string(APPARMOR_ENABLED).ok()?;
+    if !enabled.trim().eq_ignore_ascii_case("Y") {
+        return None;
+    }
+    Some(parse_apparmor_profiles(
+        &std::fs::read_to_string(APPARMOR_PROFILES).unwrap_or_default(),
+    ))
+}
+
+/// Parse securityfs `profiles` lines (`/usr/sbin/cupsd (enforce)`).
+pub fn parse_apparmor_profiles(text: &str) -> AppArmorProfiles {
+    let mut profiles = AppArmorProfiles::default();
+    for line in text.lines() {
+        let Some((name, mode)) = line.trim().rsplit_once(" (") else {
+            continue;
+        };
+        match mode.trim_end_matches(')') {
+            "enforce" => profiles.enforce.push(name.to_string()),
+            "complain" => profiles.complain += 1,
+            _ => {}
+        }
+    }
+    profiles
+}
+
+/// One file-context rule: `semanage fcontext` spec, SELinux type, and the path to relabel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FcontextRule {
+    pub spec: String,
+    pub setype: &'static str,
+    pub path: PathBuf,
+}
+
+/// File-context rules for an install: each folder recursively, plus the service executable as
+/// `bin_t` so systemd may run it. Folders shared by several roles get the first role's rule.
+pub fn fcontext_rules(layout: &InstallLayout, exe: &Path) -> Vec<FcontextRule> {
+    let folder = |dir: &Path, setype| FcontextRule {
+        spec: format!("{}(/.*)?", regex::escape(&dir.to_string_lossy())),
+        setype,
+        path: dir.to_path_buf(),
+    };
+    let mut rules = vec![
+        folder(&layout.binaries, "usr_t"),
+        FcontextRule {
+            spec: regex::escape(&exe.to_string_lossy()),
+            setype: "bin_t",
+            path: exe.to_path_buf(),
+        },
+    ];
+    for (dir, setype) in [
+        (&layout.data, "var_lib_t"),
+        (&layout.logs, "var_log_t"),
+        (&layout.temp, "tmp_t"),
+    ] {
+        if !rules.iter().any(|r| r.path == *dir) {
+            rules.push(folder(dir, setype));
+        }
+    }
+    rules
+}
+
+/// Label the install folders for SELinux; a no-op when SELinux is disabled.
+pub async fn label_install(layout: &InstallLayout, exe: &Path) -> Result<()> {
+    let mode = selinux_mode();
+    if mode == SelinuxMode::Disabled {
+        return Ok(());
+    }
+    let rules = fcontext_rules(layout, exe);
+    if which::which("semanage").is_err() {
+        warn!(
+            "[PHASE: installation] [STEP: selinux] semanage not found; labeling with chcon, which a relabel undoes (install policycoreutils-python-utils)"
+        );
+        for rule in &rules {
+            run_privileged(
+                "chcon",
+                vec![
+                    "-R".to_string(),
+                    "-t".to_string(),
+                    rule.setype.to_string(),
+                    rule.path.to_string_lossy().into_owned(),
+                ],
+                "selinux_chcon",
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    for rule in &rules {
+        let args = |flag: &str| {
+            vec![
+                "fcontext".to_string(),
+                flag.to_string(),
+                "-t".to_string(),
+                rule.setype.to_string(),
+                rule.spec.clone(),
+            ]
+        };
+        let added = run_privileged("semanage", args("-a"), "selinux_fcontext").await;
+        // A rule from an earlier install (or another instance) is updated in place.
+        if let Err(e) = added {
+            if !e.to_string().contains("already defined") {
+                return Err(e);
+            }
+            run_privileged("semanage", args("-m"), "selinux_fcontext").await?;
+        }
+    }
+    for rule in &rules {
+        run_privileged(
+            "restorecon",
+            vec![
+                "-R".to_string(),
+                "-F".to_string(),
+                rule.path.to_string_lossy().into_owned(),
+            ],
+            "selinux_restorecon",
+        )
+        .await?;
+    }
+    info!(
+        "[PHASE: installation] [STEP: selinux] Labeled {} install paths (mode={:?})",
+        rules.len(),
+        mode
+    );
+    Ok(())
+}
+
+/// Run a labeling command as root (through `sudo -n` when needed); non-zero fails with stderr.
+async fn run_privileged(program: &str, mut args: Vec<String>, operation: &str) -> Result<()> {
+    let program = if is_running_as_root() {
+        program
+    } else {
+        args.splice(0..0, ["-n".to_string(), program.to_string()]);
+        "sudo"
+    };
+    let out = run_cmd_with_timeout(program, &args, COMMAND_TIMEOUT, operation).await?;
+    if out.exit_code != Some(0) {
+        anyhow::bail!(
+            "{} failed (exit_code={:?}): {}",
+            operation,
+            out.exit_code,
+            out.stderr.trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_cover_each_folder_and_the_executable() {
+        let layout = InstallLayout {
+            binaries: PathBuf::from("/opt/cadalytix"),
+            data: PathBuf::from("/var/lib/cadalytix.prod"),
+            logs: PathBuf::from("/opt/cadalytix"),
+            temp: PathBuf::from("/opt/cadalytix/temp"),
+        };
+        let rules = fcontext_rules(&layout, Path::new("/opt/cadalytix/CADalytix"));
+        let summary: Vec<(&str, &str)> =
+            rules.iter().map(|r| (r.spec.as_str(), r.setype)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/opt/cadalytix(/.*)?", "usr_t"),
+                ("/opt/cadalytix/CADalytix", "bin_t"),
+                ("/var/lib/cadalytix\\.prod(/.*)?", "var_lib_t"),
+                ("/opt/cadalytix/temp(/.*)?", "tmp_t"),
+            ]
+        );
+
+        let profiles = parse_apparmor_profiles(
+            "/usr/sbin/cupsd (enforce)\nnvidia_modprobe (complain)\nsnap.lxd.lxc (enforce)\n",
+        );
+        assert_eq!(profiles.enforce, vec!["/usr/sbin/cupsd", "snap.lxd.lxc"]);
+        assert_eq!(profiles.complain, 1);
+    }
+}
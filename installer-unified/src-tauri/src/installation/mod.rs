@@ -7,17 +7,57 @@
 // - Never log secrets (connection strings, license keys, tokens).
 // - All I/O should be async.
 
+pub mod cancel;
+pub mod config_generator;
+pub mod dependencies;
+pub mod disk_probe;
 pub mod docker;
 pub mod files;
+pub mod firewall;
+pub mod fleet;
+pub mod health;
+pub mod host_sizing;
+pub mod inspect;
+pub mod layout;
 pub mod linux_parsers;
+pub mod manifest;
+pub mod notify;
+pub mod offline_bundle;
+pub mod package;
+pub mod path_check;
+pub mod pause;
+pub mod payload_manifest;
+pub mod progress_stream;
+pub mod remote;
+pub mod repair;
+pub mod rollback;
 pub mod service;
+pub mod signals;
+pub mod space_projection;
+pub mod storage_fs;
+pub mod support_bundle;
+pub mod support_upload;
+pub mod telemetry;
+pub mod time_sync;
+
+#[cfg(windows)]
+pub mod uac;
 
 #[cfg(windows)]
 pub mod windows;
 
+#[cfg(target_os = "linux")]
+pub mod elevation;
+
 #[cfg(target_os = "linux")]
 pub mod linux;
 
+#[cfg(target_os = "linux")]
+pub mod mac_policy;
+
+#[cfg(target_os = "linux")]
+pub mod native_package;
+
 use anyhow::{Context, Result};
 use log::{debug, error, info, warn};
 use std::process::Stdio;
@@ -25,9 +65,10 @@ use std::time::Instant;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tokio_retry::RetryIf;
 
+use crate::utils::retry;
+
 #[derive(Debug, Clone)]
 pub struct CommandOutput {
     pub exit_code: Option<i32>,
@@ -124,7 +165,27 @@ async fn run_cmd_with_timeout_once(
         Ok::<String, std::io::Error>(String::from_utf8_lossy(&buf).to_string())
     });
 
-    let status = match timeout(timeout_dur, child.wait()).await {
+    // Cancel (install runs only) kills the child the same way a timeout does.
+    let cancel = cancel::current().unwrap_or_default();
+    let waited = tokio::select! {
+        r = timeout(timeout_dur, child.wait()) => r,
+        _ = cancel.cancelled() => {
+            warn!(
+                "[PHASE: installation] [STEP: cmd] Install cancelled (operation={}, program={}); killing process",
+                operation, program
+            );
+            if let Err(e) = child.kill().await {
+                warn!(
+                    "[PHASE: installation] [STEP: cmd] Failed to kill cancelled process (operation={}, program={}): {}",
+                    operation, program, e
+                );
+            }
+            let _ = timeout(Duration::from_secs(5), child.wait()).await;
+            return Err(cancel::Cancelled.into());
+        }
+    };
+
+    let status = match waited {
         Ok(Ok(s)) => s,
         Ok(Err(e)) => {
             return Err(anyhow::Error::new(e)).with_context(|| {
@@ -220,11 +281,7 @@ pub async fn run_cmd_with_timeout(
         async move { run_cmd_with_timeout_once(&program, &args, timeout_dur, &op).await }
     };
 
-    let retry_strategy = ExponentialBackoff::from_millis(200)
-        .factor(2)
-        .max_delay(Duration::from_secs(2))
-        .take(3)
-        .map(jitter);
+    let retry_strategy = retry::policy().backoff();
 
     let result = RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
         let transient = is_transient_exec_error(e);
@@ -0,0 +1,481 @@
+//! Native Linux packages (.deb/.rpm) for the Linux install mode, so dpkg or rpm tracks the
+//! deployed files and the systemd unit instead of raw copies it knows nothing about.
+//!
+//! After the runtime payload is deployed, the installer stages the deployed payload files (hard
+//! links) and the unit under `installer-artifacts/native-package/`, builds a package named after
+//! the unit (`cadalytix`, `cadalytix-<instance>`) with `dpkg-deb` or `rpmbuild`, and installs it
+//! with `dpkg -i` / `rpm -U`. Its maintainer scripts reload systemd and enable and restart the
+//! unit on install, and stop and disable it on removal. The generated configuration
+//! (`appsettings.json`, `cadalytix.env`) stays outside the package, so upgrades never prompt
+//! over it.
+//!
+//! `CADALYTIX_LINUX_PACKAGE=auto|deb|rpm|off` (default `auto`: deb when `dpkg-deb` is present,
+//! else rpm when `rpmbuild` is). Without a toolchain the unit is registered directly, as before.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use tokio::time::Duration;
+
+use crate::installation::config_generator::{APPSETTINGS_FILE, ENV_FILE};
+use crate::installation::linux::is_running_as_root;
+use crate::installation::run_cmd_with_timeout;
+
+const PACKAGE_ENV: &str = "CADALYTIX_LINUX_PACKAGE";
+const DESCRIPTION: &str = "CADalytix services";
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageKind {
+    Deb,
+    Rpm,
+}
+
+impl PackageKind {
+    pub fn manager(self) -> &'static str {
+        match self {
+            PackageKind::Deb => "dpkg",
+            PackageKind::Rpm => "rpm",
+        }
+    }
+}
+
+/// Package format for this host from `CADALYTIX_LINUX_PACKAGE`; `None` to register the unit
+/// without a package.
+pub fn selected_kind() -> Result<Option<PackageKind>> {
+    let setting = std::env::var(PACKAGE_ENV).unwrap_or_default();
+    let has = |tool: &str| which::which(tool).is_ok();
+    kind_for(
+        &setting,
+        has("dpkg-deb") && has("dpkg"),
+        has("rpmbuild") && has("rpm"),
+    )
+}
+
+fn kind_for(setting: &str, deb_tools: bool, rpm_tools: bool) -> Result<Option<PackageKind>> {
+    let (kind, tools) = match setting.trim().to_ascii_lowercase().as_str() {
+        "" | "auto" => {
+            return Ok(if deb_tools {
+                Some(PackageKind::Deb)
+            } else if rpm_tools {
+                Some(PackageKind::Rpm)
+            } else {
+                None
+            })
+        }
+        "off" => return Ok(None),
+        "deb" => (PackageKind::Deb, deb_tools),
+        "rpm" => (PackageKind::Rpm, rpm_tools),
+        other => anyhow::bail!(
+            "{} must be auto, deb, rpm or off, got '{}'",
+            PACKAGE_ENV,
+            other
+        ),
+    };
+    if !tools {
+        anyhow::bail!(
+            "{}={} but the {} build tools are not installed",
+            PACKAGE_ENV,
+            setting.trim(),
+            kind.manager()
+        );
+    }
+    Ok(Some(kind))
+}
+
+/// One package: the deployed payload of `dest_root` plus the unit.
+#[derive(Debug, Clone)]
+pub struct NativePackage {
+    /// Package and unit name.
+    pub name: String,
+    pub dest_root: PathBuf,
+    /// Payload files, relative to `dest_root` (forward slashes).
+    pub files: Vec<String>,
+    pub unit_text: String,
+}
+
+impl NativePackage {
+    fn unit_path(&self) -> String {
+        format!("/etc/systemd/system/{}.service", self.name)
+    }
+
+    /// Absolute paths the package owns.
+    fn owned_paths(&self) -> Vec<String> {
+        let root = self.dest_root.to_string_lossy();
+        let root = root.trim_end_matches('/');
+        self.files
+            .iter()
+            .map(|f| format!("{}/{}", root, f))
+            .chain([self.unit_path()])
+            .collect()
+    }
+}
+
+/// Payload files of an install manifest: everything but the generated configuration and the
+/// installer's own artifacts.
+pub fn payload_files<'a>(manifest_files: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut files: Vec<String> = manifest_files
+        .filter(|f| {
+            f.as_str() != APPSETTINGS_FILE
+                && f.as_str() != ENV_FILE
+                && !f.starts_with("installer-artifacts/")
+                && !f.starts_with('/')
+        })
+        .cloned()
+        .collect();
+    files.sort();
+    files
+}
+
+/// `(version, release)`: Debian `1.2.0~rc.1`; rpm `1.2.0` release `0.rc.1` (no `-` in either).
+fn package_version(kind: PackageKind, version: &str) -> (String, String) {
+    let version = version.split('+').next().unwrap_or(version);
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    match (kind, pre) {
+        (PackageKind::Deb, Some(pre)) => (format!("{}~{}", core, pre), String::new()),
+        (PackageKind::Deb, None) => (core.to_string(), String::new()),
+        (PackageKind::Rpm, Some(pre)) => (core.to_string(), format!("0.{}", pre.replace('-', "."))),
+        (PackageKind::Rpm, None) => (core.to_string(), "1".to_string()),
+    }
+}
+
+fn architecture(kind: PackageKind) -> &'static str {
+    match (kind, std::env::consts::ARCH) {
+        (PackageKind::Deb, "x86_64") => "amd64",
+        (PackageKind::Deb, "aarch64") => "arm64",
+        (_, arch) => arch,
+    }
+}
+
+/// Shell run after install/upgrade: pick up the unit and (re)start it.
+fn start_script(name: &str) -> String {
+    format!(
+        "systemctl daemon-reload\nsystemctl enable {0}.service\nsystemctl restart {0}.service\n",
+        name
+    )
+}
+
+fn stop_script(name: &str) -> String {
+    format!("systemctl disable --now {}.service || true\n", name)
+}
+
+fn deb_control(pkg: &NativePackage, installed_kb: u64) -> String {
+    let (version, _) = package_version(PackageKind::Deb, env!("CARGO_PKG_VERSION"));
+    format!(
+        "Package: {}\nVersion: {}\nArchitecture: {}\nMaintainer: CADalytix\nInstalled-Size: {}\nSection: misc\nPriority: optional\nDescription: {}\n Deployed to {} by the CADalytix installer; runs as the {}.service systemd unit.\n",
+        pkg.name,
+        version,
+        architecture(PackageKind::Deb),
+        installed_kb.max(1),
+        DESCRIPTION,
+        pkg.dest_root.display(),
+        pkg.name
+    )
+}
+
+/// `(postinst, prerm, postrm)` of a .deb.
+fn deb_scripts(name: &str) -> [(&'static str, String); 3] {
+    let script = |body: String| format!("#!/bin/sh\nset -e\n{}", body);
+    [
+        (
+            "postinst",
+            script(format!(
+                "if [ \"$1\" = configure ]; then\n{}fi\n",
+                start_script(name)
+            )),
+        ),
+        (
+            "prerm",
+            script(format!(
+                "if [ \"$1\" = remove ]; then\n{}fi\n",
+                stop_script(name)
+            )),
+        ),
+        (
+            "postrm",
+            script("systemctl daemon-reload || true\n".to_string()),
+        ),
+    ]
+}
+
+/// rpm spec whose %install hard-links the staged tree into the buildroot.
+fn rpm_spec(pkg: &NativePackage, staging: &Path) -> String {
+    let (version, release) = package_version(PackageKind::Rpm, env!("CARGO_PKG_VERSION"));
+    let files: String = pkg
+        .owned_paths()
+        .iter()
+        .map(|p| format!("\"{}\"\n", p.replace('%', "%%")))
+        .collect();
+    format!(
+        r#"%define debug_package %{{nil}}
+%define __os_install_post %{{nil}}
+%define _build_id_links none
+Name: {name}
+Version: {version}
+Release: {release}
+Summary: {description}
+License: Proprietary
+BuildArch: {arch}
+AutoReqProv: no
+
+%description
+Deployed to {dest} by the CADalytix installer; runs as the {name}.service systemd unit.
+
+%install
+mkdir -p %{{buildroot}}
+cp -al "{staging}/." %{{buildroot}}/
+
+%post
+{post}
+%preun
+if [ $1 -eq 0 ]; then
+{preun}fi
+
+%postun
+systemctl daemon-reload || true
+
+%files
+{files}"#,
+        name = pkg.name,
+        version = version,
+        release = release,
+        description = DESCRIPTION,
+        arch = architecture(PackageKind::Rpm),
+        dest = pkg.dest_root.display(),
+        staging = staging.display(),
+        post = start_script(&pkg.name),
+        preun = stop_script(&pkg.name),
+        files = files,
+    )
+}
+
+/// Build the package for `pkg` under `work_dir` and install it; returns the package file.
+/// The unit is enabled and started by the package's scripts.
+pub async fn build_and_install(
+    kind: PackageKind,
+    pkg: &NativePackage,
+    work_dir: &Path,
+) -> Result<PathBuf> {
+    let file = build(kind, pkg, work_dir).await?;
+    let (program, args) = install_command(kind, &file);
+    run_checked(program, args, "native_package_install", true).await?;
+    Ok(file)
+}
+
+/// Package file `build` writes for the package `name` under `work_dir`.
+pub fn package_file(kind: PackageKind, name: &str, work_dir: &Path) -> PathBuf {
+    let (version, release) = package_version(kind, env!("CARGO_PKG_VERSION"));
+    match kind {
+        PackageKind::Deb => {
+            work_dir.join(format!("{}_{}_{}.deb", name, version, architecture(kind)))
+        }
+        PackageKind::Rpm => work_dir
+            .join("rpmbuild")
+            .join("RPMS")
+            .join(architecture(kind))
+            .join(format!(
+                "{}-{}-{}.{}.rpm",
+                name,
+                version,
+                release,
+                architecture(kind)
+            )),
+    }
+}
+
+/// Command (run as root) that installs the package `file`.
+pub fn install_command(kind: PackageKind, file: &Path) -> (&'static str, Vec<String>) {
+    let file_arg = file.to_string_lossy().into_owned();
+    match kind {
+        PackageKind::Deb => ("dpkg", vec!["-i".to_string(), file_arg]),
+        PackageKind::Rpm => (
+            "rpm",
+            vec![
+                "-U".to_string(),
+                "--replacepkgs".to_string(),
+                "--replacefiles".to_string(),
+                file_arg,
+            ],
+        ),
+    }
+}
+
+/// Build the package for `pkg` under `work_dir` as the current user; returns the package file.
+pub async fn build(kind: PackageKind, pkg: &NativePackage, work_dir: &Path) -> Result<PathBuf> {
+    if tokio::fs::try_exists(work_dir).await.unwrap_or(false) {
+        tokio::fs::remove_dir_all(work_dir)
+            .await
+            .with_context(|| format!("Failed to clear {:?}", work_dir))?;
+    }
+    let staging = work_dir.join("root");
+    let mut installed_bytes = 0u64;
+    for owned in pkg.owned_paths() {
+        let dst = staging.join(owned.trim_start_matches('/'));
+        if let Some(dir) = dst.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        if owned == pkg.unit_path() {
+            tokio::fs::write(&dst, &pkg.unit_text).await?;
+            continue;
+        }
+        // Hard links keep the staging free; copies when the work folder is on another volume.
+        if tokio::fs::hard_link(&owned, &dst).await.is_err() {
+            tokio::fs::copy(&owned, &dst)
+                .await
+                .with_context(|| format!("Failed to stage {}", owned))?;
+        }
+        installed_bytes += tokio::fs::metadata(&dst)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+    }
+
+    let (file, build_program, build_args) = match kind {
+        PackageKind::Deb => {
+            let debian = staging.join("DEBIAN");
+            tokio::fs::create_dir_all(&debian).await?;
+            tokio::fs::write(
+                debian.join("control"),
+                deb_control(pkg, installed_bytes.div_ceil(1024)),
+            )
+            .await?;
+            for (name, text) in deb_scripts(&pkg.name) {
+                let path = debian.join(name);
+                tokio::fs::write(&path, text).await?;
+                tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await?;
+            }
+            let file = package_file(kind, &pkg.name, work_dir);
+            let args = vec![
+                "--root-owner-group".to_string(),
+                "--build".to_string(),
+                staging.to_string_lossy().into_owned(),
+                file.to_string_lossy().into_owned(),
+            ];
+            (file, "dpkg-deb", args)
+        }
+        PackageKind::Rpm => {
+            let top = work_dir.join("rpmbuild");
+            let spec = work_dir.join(format!("{}.spec", pkg.name));
+            tokio::fs::write(&spec, rpm_spec(pkg, &staging)).await?;
+            let file = package_file(kind, &pkg.name, work_dir);
+            let args = vec![
+                "-bb".to_string(),
+                "--define".to_string(),
+                format!("_topdir {}", top.display()),
+                spec.to_string_lossy().into_owned(),
+            ];
+            (file, "rpmbuild", args)
+        }
+    };
+    run_checked(build_program, build_args, "native_package_build", false).await?;
+    info!(
+        "[PHASE: installation] [STEP: native_package] Built {:?} ({} files)",
+        file,
+        pkg.files.len()
+    );
+
+    if let Err(e) = tokio::fs::remove_dir_all(&staging).await {
+        warn!(
+            "[PHASE: installation] [STEP: native_package] Failed to remove staging {:?}: {}",
+            staging, e
+        );
+    }
+    Ok(file)
+}
+
+/// Run a packaging command (through `sudo -n` when `as_root` and not root); non-zero fails.
+async fn run_checked(
+    program: &str,
+    mut args: Vec<String>,
+    operation: &str,
+    as_root: bool,
+) -> Result<()> {
+    let program = if as_root && !is_running_as_root() {
+        args.splice(0..0, ["-n".to_string(), program.to_string()]);
+        "sudo"
+    } else {
+        program
+    };
+    let out = run_cmd_with_timeout(program, &args, COMMAND_TIMEOUT, operation).await?;
+    if out.exit_code != Some(0) {
+        let detail = if out.stderr.trim().is_empty() {
+            out.stdout
+        } else {
+            out.stderr
+        };
+        anyhow::bail!(
+            "{} failed (exit_code={:?}): {}",
+            operation,
+            out.exit_code,
+            detail.trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packages_own_the_payload_and_the_unit() {
+        assert_eq!(kind_for("", true, true).unwrap(), Some(PackageKind::Deb));
+        assert_eq!(
+            kind_for("auto", false, true).unwrap(),
+            Some(PackageKind::Rpm)
+        );
+        assert_eq!(kind_for("off", true, true).unwrap(), None);
+        assert!(kind_for("rpm", true, false).is_err());
+        assert!(kind_for("msi", true, true).is_err());
+
+        assert_eq!(
+            package_version(PackageKind::Deb, "1.2.0-rc.1"),
+            ("1.2.0~rc.1".to_string(), String::new())
+        );
+        assert_eq!(
+            package_version(PackageKind::Rpm, "1.2.0-rc.1+build5"),
+            ("1.2.0".to_string(), "0.rc.1".to_string())
+        );
+        assert_eq!(
+            package_version(PackageKind::Rpm, "1.2.0"),
+            ("1.2.0".to_string(), "1".to_string())
+        );
+
+        let manifest = [
+            "bin/cadalytix-server".to_string(),
+            "appsettings.json".to_string(),
+            "cadalytix.env".to_string(),
+            "installer-artifacts/install-manifest.json".to_string(),
+            "appsettings.template.json".to_string(),
+        ];
+        let pkg = NativePackage {
+            name: "cadalytix-test".to_string(),
+            dest_root: PathBuf::from("/opt/cadalytix-test/"),
+            files: payload_files(manifest.iter()),
+            unit_text: String::new(),
+        };
+        assert_eq!(
+            pkg.owned_paths(),
+            [
+                "/opt/cadalytix-test/appsettings.template.json",
+                "/opt/cadalytix-test/bin/cadalytix-server",
+                "/etc/systemd/system/cadalytix-test.service"
+            ]
+        );
+        let spec = rpm_spec(&pkg, Path::new("/tmp/stage"));
+        assert!(spec.contains("Name: cadalytix-test\n"));
+        assert!(spec.contains("\"/opt/cadalytix-test/bin/cadalytix-server\"\n"));
+        assert!(spec.contains("systemctl restart cadalytix-test.service"));
+        let [postinst, prerm, _] = deb_scripts("cadalytix-test");
+        assert!(postinst
+            .1
+            .contains("systemctl enable cadalytix-test.service"));
+        assert!(prerm.1.contains("disable --now cadalytix-test.service"));
+        assert!(deb_control(&pkg, 0).contains("Package: cadalytix-test\n"));
+    }
+}
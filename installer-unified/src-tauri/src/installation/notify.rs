@@ -0,0 +1,326 @@
+//! Webhook notification when an install finishes (Slack/Teams/pager alerts for unattended
+//! installs).
+//!
+//! Off unless a webhook URL is configured, first match wins per setting:
+//! - `CADALYTIX_NOTIFY_URL`, `CADALYTIX_NOTIFY_SECRET`
+//! - `notify.json` next to the installer executable (or the file named by
+//!   `CADALYTIX_NOTIFY_CONFIG`), e.g. `{ "webhookUrl": "https://hooks.example/...", "secret": "..." }`
+//!
+//! The body is a JSON summary (status, duration, version, correlation id, artifact paths; no
+//! credentials) with a `text` line that Slack and Teams incoming webhooks display as-is. A failure
+//! is reported as its error code and that code's fixed description; the error text itself can
+//! name hosts, paths and connection details, so it only goes to the local log. With a
+//! secret, `X-Cadalytix-Signature: sha256=<hex>` carries the HMAC-SHA256 of the exact body.
+//! Sent once per install (transient failures retried); a failed send is logged and never fails
+//! the install.
+
+use anyhow::Result;
+use log::{info, warn};
+use std::path::PathBuf;
+use tokio_retry::RetryIf;
+
+use crate::api::installer::InstallArtifacts;
+use crate::utils::http::{self, ProxyConfig};
+use crate::utils::retry;
+
+const CONFIG_FILE_NAME: &str = "notify.json";
+const CONFIG_PATH_ENV: &str = "CADALYTIX_NOTIFY_CONFIG";
+const URL_ENV: &str = "CADALYTIX_NOTIFY_URL";
+const SECRET_ENV: &str = "CADALYTIX_NOTIFY_SECRET";
+const SIGNATURE_HEADER: &str = "X-Cadalytix-Signature";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotifyConfig {
+    webhook_url: Option<String>,
+    secret: Option<String>,
+}
+
+/// What the webhook receives when an install ends.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallSummary {
+    /// "install.completed" | "install.failed"
+    pub event: String,
+    /// "success" | "failed" | "cancelled" | "interrupted"
+    pub status: String,
+    /// One-line summary for chat webhooks.
+    pub text: String,
+    pub installer_version: String,
+    pub correlation_id: String,
+    pub install_mode: String,
+    pub started_utc: String,
+    pub finished_utc: String,
+    pub duration_ms: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Fixed description of `error_code`, never the error text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<InstallArtifacts>,
+}
+
+/// Summary of a finished install run started at `started`.
+pub fn summary(
+    correlation_id: &str,
+    install_mode: &str,
+    started: chrono::DateTime<chrono::Utc>,
+    outcome: &Result<InstallArtifacts>,
+) -> InstallSummary {
+    let finished = chrono::Utc::now();
+    let duration_ms = (finished - started).num_milliseconds();
+    let (status, error_code, error) = match outcome {
+        Ok(_) => ("success", None, None),
+        Err(e) => {
+            let code = crate::error::error_code(e);
+            info!(
+                "[PHASE: notify] [STEP: summary] Notifying {} without the error text: {:#}",
+                crate::error::code_label(code),
+                e
+            );
+            (
+                if super::cancel::interrupted_by(e).is_some() {
+                    "interrupted"
+                } else if super::cancel::is_cancelled(e) {
+                    "cancelled"
+                } else {
+                    "failed"
+                },
+                Some(crate::error::code_label(code)),
+                Some(crate::error::code_summary(code).to_string()),
+            )
+        }
+    };
+    let text = match &error_code {
+        None => format!(
+            "CADalytix install succeeded ({} mode, {}s, correlation {})",
+            install_mode,
+            duration_ms / 1000,
+            correlation_id
+        ),
+        Some(code) => format!(
+            "CADalytix install {} with {} ({} mode, {}s, correlation {})",
+            status,
+            code,
+            install_mode,
+            duration_ms / 1000,
+            correlation_id
+        ),
+    };
+    InstallSummary {
+        event: if outcome.is_ok() {
+            "install.completed"
+        } else {
+            "install.failed"
+        }
+        .to_string(),
+        status: status.to_string(),
+        text,
+        installer_version: env!("CARGO_PKG_VERSION").to_string(),
+        correlation_id: correlation_id.to_string(),
+        install_mode: install_mode.to_string(),
+        started_utc: started.to_rfc3339(),
+        finished_utc: finished.to_rfc3339(),
+        duration_ms,
+        error_code,
+        error,
+        artifacts: outcome.as_ref().ok().cloned(),
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mac = crate::security::crypto::hmac_sha256(secret.as_bytes(), body);
+    let hex: String = mac.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+fn config_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os(CONFIG_PATH_ENV) {
+        return Some(PathBuf::from(p));
+    }
+    crate::utils::path_resolver::resolve_deployment_folder()
+        .ok()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+fn read_config_file() -> NotifyConfig {
+    let Some(path) = config_path().filter(|p| p.is_file()) else {
+        return NotifyConfig::default();
+    };
+    match std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|s| serde_json::from_str(&s).map_err(anyhow::Error::from))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(
+                "[PHASE: notify] [STEP: config] Ignoring unreadable notification config {:?}: {}",
+                path, e
+            );
+            NotifyConfig::default()
+        }
+    }
+}
+
+/// Webhook URL and optional secret: environment first, then the config file.
+fn resolve(var: impl Fn(&str) -> Option<String>, file: NotifyConfig) -> Option<(String, String)> {
+    let pick = |env: &str, fallback: Option<String>| {
+        var(env)
+            .or(fallback)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let url = pick(URL_ENV, file.webhook_url)?;
+    Some((url, pick(SECRET_ENV, file.secret).unwrap_or_default()))
+}
+
+/// Retryable HTTP status from the webhook.
+#[derive(Debug)]
+struct TransientStatus(reqwest::StatusCode);
+
+impl std::fmt::Display for TransientStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Webhook returned HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for TransientStatus {}
+
+async fn post(client: &reqwest::Client, url: &str, secret: &str, body: Vec<u8>) -> Result<()> {
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    if !secret.is_empty() {
+        request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+    }
+    let status = request.body(body).send().await?.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(TransientStatus(status).into());
+    }
+    anyhow::bail!("Webhook rejected the notification (HTTP {})", status)
+}
+
+/// Post `summary` to the configured webhook, if any.
+pub async fn send(summary: &InstallSummary) {
+    let Some((url, secret)) = resolve(|name| std::env::var(name).ok(), read_config_file()) else {
+        return;
+    };
+    let sent = async {
+        let client = http::build_client(&ProxyConfig::default())?;
+        let body = serde_json::to_vec(summary)?;
+        RetryIf::spawn(
+            retry::policy().backoff(),
+            || post(&client, &url, &secret, body.clone()),
+            |e: &anyhow::Error| {
+                http::is_unreachable(e) || e.downcast_ref::<TransientStatus>().is_some()
+            },
+        )
+        .await
+    }
+    .await;
+    match sent {
+        Ok(()) => info!(
+            "[PHASE: notify] [STEP: webhook] Install notification sent (status={}, signed={})",
+            summary.status,
+            !secret.is_empty()
+        ),
+        Err(e) => warn!(
+            "[PHASE: notify] [STEP: webhook] Install notification failed: {:#}",
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_config_summary_and_signature() {
+        let file = NotifyConfig {
+            webhook_url: Some("https://hooks.example/file".to_string()),
+            secret: Some("file-secret".to_string()),
+        };
+        assert_eq!(resolve(|_| None, NotifyConfig::default()), None);
+        assert_eq!(
+            resolve(|_| None, file.clone()),
+            Some((
+                "https://hooks.example/file".to_string(),
+                "file-secret".to_string()
+            ))
+        );
+        let env = |name: &str| (name == URL_ENV).then(|| " https://hooks.example/env ".to_string());
+        assert_eq!(
+            resolve(env, file),
+            Some((
+                "https://hooks.example/env".to_string(),
+                "file-secret".to_string()
+            ))
+        );
+
+        // Known answer.
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+
+        let started = chrono::Utc::now() - chrono::Duration::seconds(90);
+        let ok = summary(
+            "c1",
+            "docker",
+            started,
+            &Ok(InstallArtifacts {
+                log_folder: Some("/var/log/cadalytix".to_string()),
+                artifacts_dir: None,
+                manifest_path: None,
+                mapping_path: None,
+                config_path: None,
+                plan_path: None,
+            }),
+        );
+        assert_eq!(
+            (ok.event.as_str(), ok.status.as_str()),
+            ("install.completed", "success")
+        );
+        assert!(ok.duration_ms >= 90_000);
+        assert!(ok.text.contains("succeeded"));
+        let json = serde_json::to_value(&ok).unwrap();
+        assert_eq!(json["artifacts"]["logFolder"], "/var/log/cadalytix");
+        assert!(json.get("error").is_none());
+
+        let cancelled = summary(
+            "c2",
+            "windows",
+            started,
+            &Err(anyhow::Error::new(super::super::cancel::Cancelled)),
+        );
+        assert_eq!(cancelled.status, "cancelled");
+        assert_eq!(cancelled.event, "install.failed");
+        assert!(cancelled.error_code.is_some());
+        assert!(cancelled.artifacts.is_none());
+
+        // Only the code and its fixed description leave the machine.
+        let failed = summary(
+            "c3",
+            "docker",
+            started,
+            &Err(crate::error::with_code(
+                anyhow::anyhow!("Login failed for sa@db01.internal:1433 (C:\\CAD\\secrets.json)"),
+                crate::error::InstallerError::DatabaseConnectionFailed,
+            )),
+        );
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.error_code.as_deref(), Some("E2001"));
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("Could not connect to the database server")
+        );
+        let body = serde_json::to_string(&failed).unwrap();
+        assert!(!body.contains("db01") && !body.contains("secrets.json"));
+    }
+}
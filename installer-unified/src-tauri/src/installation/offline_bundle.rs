@@ -0,0 +1,312 @@
+//! Air-gapped installs from a signed offline bundle (`--offline-bundle=<path>`).
+//!
+//! A bundle is a directory laid out like the deployment payload:
+//!
+//! ```text
+//! bundle-manifest.json              schema version, bundle version, SHA-256 of every file
+//! bundle-manifest.sig               base64 Ed25519 signature over bundle-manifest.json
+//! runtime/{shared,windows,linux}/   service binaries (same layout as the deployment runtime/)
+//! runtime/linux/docker/images/      container images as `docker save` .tar files
+//! installer/migrations/             migrations + manifest_versioned.json
+//! license/offline-license.txt       offline license bundle (see `api::license`)
+//! ```
+//!
+//! [`activate`] checks the signature, then every file against the manifest, and rejects files
+//! the manifest does not list, before the wizard starts; `run_installation` repeats the check
+//! before its first step. While a bundle is active, payload, migration, image and license
+//! lookups resolve inside it, and every step that would reach the network fails through
+//! [`ensure_network_allowed`] instead of trying.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use log::info;
+use sha2::{Digest, Sha256};
+
+use crate::security::crypto::{release_signing_key, verify_ed25519};
+
+pub const MANIFEST_FILE: &str = "bundle-manifest.json";
+pub const SIGNATURE_FILE: &str = "bundle-manifest.sig";
+pub const LICENSE_FILE: &str = "license/offline-license.txt";
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Root of the verified bundle for this process; set once by [`activate`].
+static ACTIVE: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    schema_version: u32,
+    #[serde(default)]
+    bundle_version: String,
+    /// Bundle-relative path (forward slashes) -> lowercase SHA-256 hex.
+    files: BTreeMap<String, String>,
+}
+
+/// What a verified bundle contains.
+#[derive(Debug, Clone)]
+pub struct BundleSummary {
+    pub bundle_version: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub image_count: usize,
+    pub has_license: bool,
+}
+
+/// `--offline-bundle=<path>` from the command line, if given.
+pub fn bundle_arg(args: &[String]) -> Option<PathBuf> {
+    args.iter()
+        .find_map(|a| a.strip_prefix("--offline-bundle="))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Verify the bundle at `root` and make it the source of every payload lookup for this process.
+pub fn activate(root: &Path) -> Result<BundleSummary> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("Offline bundle folder not found: {:?}", root))?;
+    let summary = verify(&root, &release_signing_key()?)?;
+    if ACTIVE.set(root.clone()).is_err() && active_root() != Some(root.as_path()) {
+        anyhow::bail!("A different offline bundle is already active");
+    }
+    info!(
+        "[PHASE: initialization] [STEP: offline_bundle] Offline bundle active (root={:?}, version={}, files={}, images={}, license={})",
+        root, summary.bundle_version, summary.file_count, summary.image_count, summary.has_license
+    );
+    Ok(summary)
+}
+
+/// Root of the active bundle, if the installer runs in offline bundle mode.
+pub fn active_root() -> Option<&'static Path> {
+    ACTIVE.get().map(PathBuf::as_path)
+}
+
+/// Re-verify the active bundle (no-op without one). Blocking; hashes every file.
+pub fn verify_active() -> Result<()> {
+    if let Some(root) = active_root() {
+        verify(root, &release_signing_key()?)?;
+    }
+    Ok(())
+}
+
+/// Fail with an explicit message when `what` needs the network and a bundle is active.
+pub fn ensure_network_allowed(what: &str) -> Result<()> {
+    if active_root().is_some() {
+        anyhow::bail!(
+            "{} requires network access, which is not available when installing from an offline bundle. Add what it needs to the bundle or install online.",
+            what
+        );
+    }
+    Ok(())
+}
+
+/// Offline license file of the active bundle, if it ships one.
+pub fn license_file() -> Option<PathBuf> {
+    active_root()
+        .map(|root| root.join(LICENSE_FILE))
+        .filter(|p| p.is_file())
+}
+
+/// Signature, manifest and per-file SHA-256 check of the bundle at `root`.
+fn verify(root: &Path, public_key: &[u8]) -> Result<BundleSummary> {
+    let manifest_bytes = std::fs::read(root.join(MANIFEST_FILE))
+        .with_context(|| format!("Offline bundle is missing {}", MANIFEST_FILE))?;
+    let signature_text = std::fs::read_to_string(root.join(SIGNATURE_FILE))
+        .with_context(|| format!("Offline bundle is missing {}", SIGNATURE_FILE))?;
+    if !verify_ed25519(public_key, &manifest_bytes, &signature_text) {
+        anyhow::bail!(
+            "Offline bundle signature verification failed - the bundle may be tampered with"
+        );
+    }
+
+    let manifest: BundleManifest =
+        serde_json::from_slice(&manifest_bytes).context("Offline bundle manifest is invalid")?;
+    if manifest.schema_version != MANIFEST_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Unsupported offline bundle manifest schema version {} (expected {})",
+            manifest.schema_version,
+            MANIFEST_SCHEMA_VERSION
+        );
+    }
+    for rel in manifest.files.keys() {
+        check_relative(rel)?;
+    }
+    if !manifest.files.keys().any(|f| f.starts_with("runtime/")) {
+        anyhow::bail!("Offline bundle has no runtime/ payload");
+    }
+    if !manifest
+        .files
+        .contains_key("installer/migrations/manifest_versioned.json")
+    {
+        anyhow::bail!("Offline bundle has no installer/migrations/manifest_versioned.json");
+    }
+
+    let mut on_disk = Vec::new();
+    list_files(root, root, &mut on_disk)?;
+    if let Some(extra) = on_disk
+        .iter()
+        .find(|f| !manifest.files.contains_key(*f) && *f != MANIFEST_FILE && *f != SIGNATURE_FILE)
+    {
+        anyhow::bail!(
+            "Offline bundle contains '{}', which its manifest does not list",
+            extra
+        );
+    }
+
+    let mut total_bytes = 0u64;
+    for (rel, expected) in &manifest.files {
+        let path = root.join(rel);
+        let (bytes, actual) =
+            sha256_file(&path).with_context(|| format!("Offline bundle file missing: {}", rel))?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!(
+                "Offline bundle file '{}' does not match its manifest checksum",
+                rel
+            );
+        }
+        total_bytes += bytes;
+    }
+
+    Ok(BundleSummary {
+        bundle_version: manifest.bundle_version,
+        file_count: manifest.files.len(),
+        total_bytes,
+        image_count: manifest
+            .files
+            .keys()
+            .filter(|f| f.starts_with("runtime/linux/docker/images/") && f.ends_with(".tar"))
+            .count(),
+        has_license: manifest.files.contains_key(LICENSE_FILE),
+    })
+}
+
+/// Manifest paths must stay inside the bundle.
+fn check_relative(rel: &str) -> Result<()> {
+    let path = Path::new(rel);
+    if rel.is_empty()
+        || rel.contains('\\')
+        || !path.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        anyhow::bail!("Offline bundle manifest has an invalid path: '{}'", rel);
+    }
+    Ok(())
+}
+
+/// Bundle-relative paths (forward slashes) of every file under `dir`.
+fn list_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read_dir failed: {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(root, &path, out)?;
+        } else {
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<String> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            out.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> Result<(u64, String)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+    }
+    let digest = hasher.finalize();
+    Ok((total, digest.iter().map(|b| format!("{:02x}", b)).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn write_bundle(root: &Path, key: &Ed25519KeyPair, files: &[(&str, &[u8])]) {
+        let mut listed = BTreeMap::new();
+        for (rel, bytes) in files {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, bytes).unwrap();
+            listed.insert(rel.to_string(), crate::security::crypto::sha256_hex(bytes));
+        }
+        let manifest = serde_json::to_vec(&serde_json::json!({
+            "schemaVersion": 1,
+            "bundleVersion": "2026.10",
+            "files": listed,
+        }))
+        .unwrap();
+        std::fs::write(root.join(MANIFEST_FILE), &manifest).unwrap();
+        let sig = base64::engine::general_purpose::STANDARD.encode(key.sign(&manifest));
+        std::fs::write(root.join(SIGNATURE_FILE), sig).unwrap();
+    }
+
+    #[test]
+    fn verify_checks_signature_checksums_and_unlisted_files() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public = key.public_key().as_ref().to_vec();
+        let root = std::env::temp_dir().join(format!("offline-bundle-{}", uuid::Uuid::new_v4()));
+        write_bundle(
+            &root,
+            &key,
+            &[
+                ("runtime/linux/cadalytix-server", b"bin"),
+                ("runtime/linux/docker/images/web.tar", b"tar"),
+                ("installer/migrations/manifest_versioned.json", b"{}"),
+                (LICENSE_FILE, b"bundle"),
+            ],
+        );
+
+        let summary = verify(&root, &public).unwrap();
+        assert_eq!(summary.file_count, 4);
+        assert_eq!(summary.image_count, 1);
+        assert!(summary.has_license);
+
+        std::fs::write(root.join("runtime/linux/cadalytix-server"), b"patched").unwrap();
+        let err = verify(&root, &public).unwrap_err().to_string();
+        assert!(err.contains("checksum"), "{}", err);
+        std::fs::write(root.join("runtime/linux/cadalytix-server"), b"bin").unwrap();
+
+        std::fs::write(root.join("runtime/linux/extra.so"), b"x").unwrap();
+        let err = verify(&root, &public).unwrap_err().to_string();
+        assert!(err.contains("does not list"), "{}", err);
+        std::fs::remove_file(root.join("runtime/linux/extra.so")).unwrap();
+
+        let other = Ed25519KeyPair::from_pkcs8(
+            Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                .unwrap()
+                .as_ref(),
+        )
+        .unwrap();
+        let err = verify(&root, other.public_key().as_ref())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("signature"), "{}", err);
+
+        assert!(check_relative("../etc/passwd").is_err());
+        assert!(check_relative("/etc/passwd").is_err());
+        assert_eq!(
+            bundle_arg(&["--tui".to_string(), "--offline-bundle=/media/b".to_string()]),
+            Some(PathBuf::from("/media/b"))
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,432 @@
+//! Distribution packages (`--package=msi|intune`) so enterprise IT can push CADalytix with their
+//! standard software distribution tooling.
+//!
+//! The scripted wizard (`tui::script`) walks the answers file with the same validation as an
+//! interactive run; at the Ready page, instead of installing, [`build`] writes:
+//!
+//! ```text
+//! <out>/CADalytix-<version>/        install.cmd, answers.txt, setup/ (this installer and its
+//!                                   folder), runtime/ (shared + platform payload) or bundle/
+//!                                   (the active --offline-bundle)
+//! <out>/CADalytix-<version>.wxs     msi: WiX v5 source, built to CADalytix-<version>.msi
+//! <out>/detect.ps1                  intune: detection rule, next to install.intunewin
+//! ```
+//!
+//! `install.cmd` runs `--tui-script` on the embedded answers with its log folder (secrets key and
+//! manifests included) at `%ProgramData%\CADalytix\InstallerLogs`, and passes the installer's exit
+//! code on. The MSI installs the package folder under Program Files and runs `install.cmd` as a
+//! deferred custom action (SYSTEM), so a failed install fails the MSI. Without `wix` or
+//! `IntuneWinAppUtil` on PATH the sources are kept and the report gives the build command.
+//!
+//! The answers are embedded as given: any password or connection string in them can be read by
+//! whoever has the package.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::info;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::api::installer::{collect_sources_from_root, resolve_runtime_payload_roots};
+use crate::error::{InstallerError, OrCode};
+use crate::installation::files::{copy_files_parallel, copy_workers, CopyJob};
+use crate::installation::{offline_bundle, run_cmd_with_timeout};
+use crate::utils::instance;
+use crate::utils::path_resolver::LOG_FOLDER_ENV;
+
+const DEFAULT_OUT_DIR: &str = "CADalytix-package";
+const BUILD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Answer keys whose values are secrets (see `tui::draft`).
+const SECRET_KEYS: [&str; 2] = ["password", "connection_string"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageFormat {
+    Msi,
+    Intune,
+}
+
+/// `--package=<format> --answers=<file> [--package-out=<dir>]`.
+#[derive(Debug, Clone)]
+pub struct PackageRequest {
+    pub format: PackageFormat,
+    pub out_dir: PathBuf,
+    /// The answers file, embedded in the package as-is.
+    pub answers: String,
+}
+
+/// The package request of the command line; `None` without `--package=`.
+pub fn package_arg(args: &[String]) -> Result<Option<PackageRequest>> {
+    let value = |prefix: &str| args.iter().find_map(|a| a.strip_prefix(prefix));
+    let Some(format) = value("--package=") else {
+        return Ok(None);
+    };
+    let format = match format.trim().to_ascii_lowercase().as_str() {
+        "msi" => PackageFormat::Msi,
+        "intune" => PackageFormat::Intune,
+        other => anyhow::bail!("--package must be msi or intune, got '{}'", other),
+    };
+    let answers_file =
+        value("--answers=").context("--package needs the wizard answers: --answers=<file>")?;
+    let answers = std::fs::read_to_string(answers_file)
+        .with_context(|| format!("Failed to read answers file {}", answers_file))?;
+    Ok(Some(PackageRequest {
+        format,
+        out_dir: PathBuf::from(value("--package-out=").unwrap_or(DEFAULT_OUT_DIR)),
+        answers,
+    }))
+}
+
+/// What [`build`] wrote.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageReport {
+    pub format: PackageFormat,
+    pub folder: String,
+    /// The `.msi` or `.intunewin`; `None` when the packaging tool is not on PATH.
+    pub artifact: Option<String>,
+    pub build_command: String,
+    /// Intune detection script.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detection_script: Option<String>,
+    pub files: usize,
+    pub contains_secrets: bool,
+}
+
+impl PackageReport {
+    pub fn to_text(&self) -> String {
+        let (kind, install) = match self.format {
+            PackageFormat::Msi => ("MSI", "msiexec /i <package>.msi /qn"),
+            PackageFormat::Intune => ("Intune", "install.cmd (detection: detect.ps1)"),
+        };
+        let mut out = format!(
+            "CADalytix {} package\nPackage folder: {} ({} files)\n",
+            kind, self.folder, self.files
+        );
+        match &self.artifact {
+            Some(artifact) => out.push_str(&format!("Package: {}\n", artifact)),
+            None => out.push_str(&format!(
+                "Not built: the packaging tool is not on PATH. Build it with:\n  {}\n",
+                self.build_command
+            )),
+        }
+        if let Some(script) = &self.detection_script {
+            out.push_str(&format!("Detection script: {}\n", script));
+        }
+        out.push_str(&format!("Install command: {}\n", install));
+        if self.contains_secrets {
+            out.push_str(
+                "Note: the embedded answers hold passwords or connection strings; restrict who can read the package.\n",
+            );
+        }
+        out
+    }
+}
+
+/// Write the package for a validated wizard run: `install_mode` selects the runtime payload,
+/// `install_folder` is where the install will land (the Intune detection rule checks it).
+pub async fn build(
+    req: &PackageRequest,
+    install_mode: &str,
+    install_folder: &str,
+) -> Result<PackageReport> {
+    if !cfg!(windows) {
+        anyhow::bail!(
+            "MSI and Intune packages wrap the Windows installer: run --package on Windows"
+        );
+    }
+    let product = instance::qualify("CADalytix", "-");
+    let name = format!("{}-{}", product, env!("CARGO_PKG_VERSION"));
+    let out_dir = std::env::current_dir()?.join(&req.out_dir);
+    let root = out_dir.join(&name);
+    if tokio::fs::try_exists(&root).await.unwrap_or(false) {
+        anyhow::bail!(
+            "Package folder {:?} already exists; remove it or choose another --package-out",
+            root
+        );
+    }
+    info!(
+        "[PHASE: package] [STEP: start] Building {:?} package (folder={:?}, install_mode={})",
+        req.format, root, install_mode
+    );
+
+    let exe = std::env::current_exe().context("Cannot locate the installer executable")?;
+    let exe_name = exe
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .context("Cannot locate the installer executable")?;
+    let setup = exe.parent().context("Cannot locate the installer folder")?;
+    let mut sources = Vec::new();
+    collect_sources_from_root(setup, &root.join("setup"), &mut sources).await?;
+    // Neither this run's logs nor the package itself belong in it.
+    sources.retain(|(src, _)| {
+        !src.starts_with(setup.join("Prod_Wizard_Log")) && !src.starts_with(&out_dir)
+    });
+    let bundle = offline_bundle::active_root();
+    match bundle {
+        Some(bundle) => {
+            collect_sources_from_root(bundle, &root.join("bundle"), &mut sources).await?
+        }
+        None => {
+            let (shared, platform) = resolve_runtime_payload_roots(install_mode)
+                .await
+                .or_code(InstallerError::PayloadMissing)?;
+            for dir in [shared, platform] {
+                let name = dir.file_name().unwrap_or_default().to_os_string();
+                collect_sources_from_root(&dir, &root.join("runtime").join(name), &mut sources)
+                    .await?;
+            }
+        }
+    }
+    let jobs: Vec<CopyJob> = sources
+        .into_iter()
+        .map(|(src, dst)| CopyJob {
+            src,
+            dst,
+            expected_sha256: None,
+        })
+        .collect();
+    for job in &jobs {
+        if let Some(dir) = job.dst.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+    }
+    let files = copy_files_parallel(jobs, copy_workers(), "package", |_, _, _| {})
+        .await
+        .or_code(InstallerError::PackageBuildFailed)?
+        .len();
+
+    tokio::fs::write(root.join("answers.txt"), &req.answers).await?;
+    let install_cmd = install_cmd(&exe_name, instance::name(), bundle.is_some());
+    tokio::fs::write(root.join("install.cmd"), install_cmd).await?;
+
+    let (tool, args, artifact, detection_script) = match req.format {
+        PackageFormat::Msi => {
+            let wxs = out_dir.join(format!("{}.wxs", name));
+            let msi = out_dir.join(format!("{}.msi", name));
+            tokio::fs::write(&wxs, wix_source(&product, &name, &root)).await?;
+            let args = ["build", "-arch", "x64", "-o"]
+                .into_iter()
+                .map(String::from)
+                .chain([msi.display().to_string(), wxs.display().to_string()])
+                .collect();
+            ("wix", args, msi, None)
+        }
+        PackageFormat::Intune => {
+            let detect = out_dir.join("detect.ps1");
+            tokio::fs::write(&detect, detection_script(install_folder)).await?;
+            let args = vec![
+                "-c".to_string(),
+                root.display().to_string(),
+                "-s".to_string(),
+                "install.cmd".to_string(),
+                "-o".to_string(),
+                out_dir.display().to_string(),
+                "-q".to_string(),
+            ];
+            (
+                "IntuneWinAppUtil",
+                args,
+                out_dir.join("install.intunewin"),
+                Some(detect.display().to_string()),
+            )
+        }
+    };
+    let build_command = std::iter::once(tool.to_string())
+        .chain(args.iter().map(|a| format!("\"{}\"", a)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let artifact = match which::which(tool) {
+        Ok(_) => {
+            let out = run_cmd_with_timeout(tool, &args, BUILD_TIMEOUT, "package_build").await?;
+            if out.exit_code != Some(0) {
+                let detail = if out.stderr.trim().is_empty() {
+                    out.stdout
+                } else {
+                    out.stderr
+                };
+                return Err(anyhow::anyhow!(
+                    "{} failed (exit_code={:?}): {}",
+                    tool,
+                    out.exit_code,
+                    detail.trim()
+                ))
+                .or_code(InstallerError::PackageBuildFailed);
+            }
+            Some(artifact.display().to_string())
+        }
+        Err(_) => None,
+    };
+    info!(
+        "[PHASE: package] [STEP: done] Package written (folder={:?}, files={}, artifact={:?})",
+        root, files, artifact
+    );
+    Ok(PackageReport {
+        format: req.format,
+        folder: root.display().to_string(),
+        artifact,
+        build_command,
+        detection_script,
+        files,
+        contains_secrets: contains_secrets(&req.answers),
+    })
+}
+
+/// Batch file the package runs: the scripted install on the embedded answers.
+fn install_cmd(exe_name: &str, instance: Option<&str>, offline_bundle: bool) -> String {
+    let mut args = vec!["--tui-script".to_string()];
+    if let Some(name) = instance {
+        args.push(format!("--instance={}", name));
+    }
+    if offline_bundle {
+        args.push("--offline-bundle=\"%~dp0bundle\"".to_string());
+    }
+    let logs = instance::qualify("InstallerLogs", "-");
+    [
+        "@echo off".to_string(),
+        "rem CADalytix unattended install; generated by the installer's --package mode."
+            .to_string(),
+        "setlocal".to_string(),
+        format!(
+            "set \"{}=%ProgramData%\\CADalytix\\{}\"",
+            LOG_FOLDER_ENV, logs
+        ),
+        format!("if not exist \"%{0}%\" mkdir \"%{0}%\"", LOG_FOLDER_ENV),
+        format!(
+            "\"%~dp0setup\\{}\" {} < \"%~dp0answers.txt\" > \"%{}%\\package-install.txt\" 2>&1",
+            exe_name,
+            args.join(" "),
+            LOG_FOLDER_ENV
+        ),
+        "exit /b %ERRORLEVEL%".to_string(),
+    ]
+    .join("\r\n")
+        + "\r\n"
+}
+
+/// WiX v5 source: the package folder under Program Files, then `install.cmd` as SYSTEM.
+fn wix_source(product: &str, name: &str, root: &Path) -> String {
+    let digest = Sha256::digest(format!("cadalytix-package:{}", product).as_bytes());
+    let upgrade_code = uuid::Uuid::from_slice(&digest[..16])
+        .map(|u| u.hyphenated().to_string().to_uppercase())
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- Generated by the CADalytix installer (--package=msi). -->
+<Wix xmlns="http://wixtoolset.org/schemas/v4/wxs">
+  <Package Name="{product}" Manufacturer="CADalytix" Version="{version}" UpgradeCode="{{{upgrade_code}}}" Scope="perMachine">
+    <MajorUpgrade DowngradeErrorMessage="A newer {product} package is already installed." />
+    <MediaTemplate EmbedCab="yes" />
+    <StandardDirectory Id="ProgramFiles64Folder">
+      <Directory Id="PACKAGEFOLDER" Name="{folder}" />
+    </StandardDirectory>
+    <Feature Id="Package">
+      <Files Directory="PACKAGEFOLDER" Include="{include}" />
+    </Feature>
+    <CustomAction Id="RunCadalytixInstall" Directory="PACKAGEFOLDER" ExeCommand="&quot;[System64Folder]cmd.exe&quot; /c &quot;&quot;[PACKAGEFOLDER]install.cmd&quot;&quot;" Execute="deferred" Impersonate="no" Return="check" />
+    <InstallExecuteSequence>
+      <Custom Action="RunCadalytixInstall" After="InstallFiles" Condition="NOT Installed AND NOT REMOVE" />
+    </InstallExecuteSequence>
+  </Package>
+</Wix>
+"#,
+        product = xml_escape(product),
+        version = msi_version(env!("CARGO_PKG_VERSION")),
+        upgrade_code = upgrade_code,
+        folder = xml_escape(name),
+        include = xml_escape(&format!("{}\\**", root.display())),
+    )
+}
+
+/// `major.minor.patch` of a semver version (MSI versions are numeric).
+fn msi_version(version: &str) -> String {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let mut parts: Vec<u32> = core
+        .split('.')
+        .map(|p| p.parse().unwrap_or(0))
+        .take(3)
+        .collect();
+    parts.resize(3, 0);
+    parts
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Intune detection rule: installed once the install manifest exists.
+fn detection_script(install_folder: &str) -> String {
+    let manifest = format!(
+        "{}\\installer-artifacts\\install-manifest.json",
+        install_folder.trim().trim_end_matches(['\\', '/'])
+    );
+    format!(
+        "# CADalytix detection rule; generated by the installer's --package=intune mode.\r\n\
+         if (Test-Path -LiteralPath '{}') {{ Write-Output 'CADalytix installed'; exit 0 }}\r\n\
+         exit 1\r\n",
+        manifest.replace('\'', "''")
+    )
+}
+
+/// Whether `key=value` answers (or JSON records) set a password or connection string.
+fn contains_secrets(answers: &str) -> bool {
+    answers.lines().map(str::trim).any(|line| {
+        if line.starts_with('{') {
+            return SECRET_KEYS
+                .iter()
+                .any(|k| line.contains(&format!("\"{}\"", k)));
+        }
+        line.split_once('=').is_some_and(|(key, value)| {
+            SECRET_KEYS.contains(&key.trim()) && !value.trim().is_empty()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_sources_wrap_the_scripted_install() {
+        let cmd = install_cmd("CADalytixInstaller.exe", Some("TEST"), true);
+        assert!(cmd.contains(
+            "\"%~dp0setup\\CADalytixInstaller.exe\" --tui-script --instance=TEST --offline-bundle=\"%~dp0bundle\" < \"%~dp0answers.txt\""
+        ));
+        assert!(cmd.ends_with("exit /b %ERRORLEVEL%\r\n"));
+
+        let wxs = wix_source(
+            "CADalytix",
+            "CADalytix-1.2.3",
+            Path::new("C:\\pkg\\CADalytix-1.2.3"),
+        );
+        assert!(wxs.contains("Name=\"CADalytix-1.2.3\""));
+        assert!(wxs.contains("Include=\"C:\\pkg\\CADalytix-1.2.3\\**\""));
+        assert_eq!(
+            wxs,
+            wix_source(
+                "CADalytix",
+                "CADalytix-1.2.3",
+                Path::new("C:\\pkg\\CADalytix-1.2.3")
+            )
+        );
+        assert_eq!(msi_version("2.4.0-rc.1"), "2.4.0");
+        assert_eq!(msi_version("7"), "7.0.0");
+
+        assert!(detection_script("C:\\Program Files\\CADalytix\\").contains(
+            "'C:\\Program Files\\CADalytix\\installer-artifacts\\install-manifest.json'"
+        ));
+        assert!(contains_secrets("# db\nuser=sa\npassword=hunter2\n"));
+        assert!(contains_secrets("{\"connection_string\": \"Server=x\"}"));
+        assert!(!contains_secrets("password=\nhost=db01\n"));
+    }
+}
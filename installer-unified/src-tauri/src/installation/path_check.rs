@@ -0,0 +1,353 @@
+//! Path checks for the install layout, run before any file is copied and in the dry-run plan.
+//!
+//! Each catches a problem that would otherwise surface halfway through the copy, or only once
+//! the service runs:
+//! - characters the filesystem rejects: on Windows `<>:"|?*` and control characters, reserved
+//!   device names (`CON`, `NUL`, `COM1`, ...) and names ending in a dot or space; on Linux
+//!   control characters, which also break the systemd unit;
+//! - paths that get too long once the payload's deepest file is placed under them: 259
+//!   characters on Windows unless long paths are enabled, 4095 bytes and 255 per name on Linux;
+//! - case mismatches: on a case-insensitive filesystem, layout folders that share a folder but
+//!   spell it differently (the manifest and rollback compare paths as text); on a case-sensitive
+//!   one, a new folder whose name differs only in case from an existing one next to it.
+//!
+//! Characters and length block the install; case findings are warnings.
+
+use std::path::{Path, PathBuf};
+
+use tokio::time::Duration;
+
+use crate::installation::layout::InstallLayout;
+use crate::installation::run_cmd_with_timeout;
+
+/// `MAX_PATH` less the terminating NUL.
+const WINDOWS_MAX_PATH: usize = 259;
+const WINDOWS_MAX_LONG_PATH: usize = 32_766;
+const LINUX_MAX_PATH: usize = 4095;
+const LINUX_MAX_NAME: usize = 255;
+/// Room kept under the data, logs and temp folders for what the service writes there later
+/// (database files, dated logs, staged archive months).
+const RUNTIME_NAME_RESERVE: usize = 64;
+const WINDOWS_INVALID: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+const LONG_PATHS_FIX: &str = "Choose a shorter folder (for example C:\\CADalytix), or enable long paths (Group Policy \"Enable Win32 long paths\", or LongPathsEnabled=1 under HKLM\\SYSTEM\\CurrentControlSet\\Control\\FileSystem) and restart Windows.";
+
+/// One problem with a layout path and how to fix it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathFinding {
+    pub role: &'static str,
+    pub path: PathBuf,
+    /// Blocks the install; otherwise a warning.
+    pub blocking: bool,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl PathFinding {
+    pub fn message(&self) -> String {
+        format!(
+            "The {} folder {:?} {}. {}",
+            self.role, self.path, self.problem, self.fix
+        )
+    }
+}
+
+/// Check every path of a local install's layout.
+pub async fn check_layout(layout: &InstallLayout, install_mode: &str) -> Vec<PathFinding> {
+    let windows = cfg!(windows);
+    let long_paths = windows && long_paths_enabled().await;
+    let payload_depth = deepest_payload_path(install_mode).await;
+    let mut findings = Vec::new();
+    let entries = layout.entries();
+    for (i, (role, dir)) in entries.iter().copied().enumerate() {
+        let finding = |blocking, problem: String, fix: &str| PathFinding {
+            role,
+            path: dir.to_path_buf(),
+            blocking,
+            problem,
+            fix: fix.to_string(),
+        };
+        let text = dir.to_string_lossy();
+        if let Some(problem) = invalid_characters(&text, windows) {
+            let fix = if problem.contains("reserved") {
+                "Rename the folder; Windows reserves device names such as CON, NUL, COM1 and LPT1."
+            } else {
+                "Choose a folder name without that character."
+            };
+            findings.push(finding(true, problem, fix));
+        }
+
+        let depth = if role == "binaries" {
+            payload_depth
+        } else {
+            RUNTIME_NAME_RESERVE
+        };
+        if let Some(problem) = length_problem(&text, depth, windows, long_paths) {
+            let fix = if windows && !long_paths {
+                LONG_PATHS_FIX
+            } else {
+                "Choose a shorter folder."
+            };
+            findings.push(finding(true, problem, fix));
+        }
+
+        let anchor = crate::utils::disk::nearest_existing_ancestor(dir).await;
+        let case_sensitive = match &anchor {
+            Some(anchor) => probe_case_sensitive(anchor).await,
+            None => None,
+        }
+        .unwrap_or(!windows && !cfg!(target_os = "macos"));
+        if case_sensitive {
+            if let Some(existing) = case_variant_sibling(dir, anchor.as_deref()).await {
+                findings.push(finding(
+                    false,
+                    format!(
+                        "does not exist, but {:?} does; this filesystem is case-sensitive, so a second folder would be created",
+                        existing
+                    ),
+                    "Use the existing folder's spelling, or a name that differs by more than case.",
+                ));
+            }
+        } else if let Some((other, _)) = entries[..i]
+            .iter()
+            .find(|(_, other)| spelled_differently(other, dir))
+        {
+            findings.push(finding(
+                false,
+                format!(
+                    "and the {} folder name the same folder with different capitalization",
+                    other
+                ),
+                "Spell the shared part of both paths the same way; this filesystem ignores case.",
+            ));
+        }
+    }
+    findings
+}
+
+/// Why `path` cannot be created, if it has a character or name the filesystem rejects.
+pub fn invalid_characters(path: &str, windows: bool) -> Option<String> {
+    if !windows {
+        return path
+            .chars()
+            .find(|c| c.is_control())
+            .map(|c| format!("contains the control character {:?}", c));
+    }
+    for name in windows_names(path) {
+        if let Some(c) = name
+            .chars()
+            .find(|c| WINDOWS_INVALID.contains(c) || c.is_control())
+        {
+            return Some(format!("contains the character {:?}", c));
+        }
+        let stem = name.split('.').next().unwrap_or(name).trim_end();
+        if WINDOWS_RESERVED
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(stem))
+        {
+            return Some(format!("uses the reserved name '{}'", name));
+        }
+        if name.ends_with('.') || name.ends_with(' ') {
+            return Some(format!("has a name ending in a dot or space ('{}')", name));
+        }
+    }
+    None
+}
+
+/// Folder names of a Windows path, without the drive, UNC server/share or `\\?\` prefix.
+fn windows_names(path: &str) -> Vec<&str> {
+    let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+    let mut names: Vec<&str> = path.split(['\\', '/']).filter(|n| !n.is_empty()).collect();
+    if path.starts_with(r"\\") {
+        names.drain(..names.len().min(2));
+    } else if path.as_bytes().first().is_some_and(u8::is_ascii_alphabetic)
+        && path.as_bytes().get(1) == Some(&b':')
+    {
+        // `C:` alone, or `C:name` for a drive-relative path.
+        names[0] = &names[0][2..];
+        if names[0].is_empty() {
+            names.remove(0);
+        }
+    }
+    names
+}
+
+/// Why `path` is too long once a file `depth` characters deep is placed under it.
+pub fn length_problem(path: &str, depth: usize, windows: bool, long_paths: bool) -> Option<String> {
+    let limit = match (windows, long_paths) {
+        (true, false) => WINDOWS_MAX_PATH,
+        (true, true) => WINDOWS_MAX_LONG_PATH,
+        (false, _) => {
+            // Linux counts bytes, and limits each name on its own.
+            if let Some(name) = path.split('/').find(|n| n.len() > LINUX_MAX_NAME) {
+                return Some(format!(
+                    "has a {}-byte name ('{}...'); the limit is {}",
+                    name.len(),
+                    name.chars().take(32).collect::<String>(),
+                    LINUX_MAX_NAME
+                ));
+            }
+            LINUX_MAX_PATH
+        }
+    };
+    let needed = path.trim_end_matches(['\\', '/']).len() + 1 + depth;
+    (needed > limit).then(|| {
+        format!(
+            "leaves paths of up to {} characters for the installed files; the limit is {}",
+            needed, limit
+        )
+    })
+}
+
+/// Whether `a` and `b` are the same folder or one contains the other once case is ignored, while
+/// their spelling of that shared part differs.
+pub fn spelled_differently(a: &Path, b: &Path) -> bool {
+    let (short, long) = if a.as_os_str().len() <= b.as_os_str().len() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let lower = |p: &Path| PathBuf::from(p.to_string_lossy().to_lowercase());
+    lower(long).starts_with(lower(short)) && !long.starts_with(short)
+}
+
+/// An existing sibling of the first missing folder of `dir` whose name differs only in case.
+async fn case_variant_sibling(dir: &Path, anchor: Option<&Path>) -> Option<PathBuf> {
+    let anchor = anchor?;
+    let missing = dir.strip_prefix(anchor).ok()?.components().next()?;
+    let missing = missing.as_os_str().to_string_lossy();
+    let mut entries = tokio::fs::read_dir(anchor).await.ok()?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name != missing && name.eq_ignore_ascii_case(&missing) {
+            return Some(entry.path());
+        }
+    }
+    None
+}
+
+/// Whether the filesystem holding `dir` tells names apart by case; `None` when `dir` is not
+/// writable, so the platform default applies.
+async fn probe_case_sensitive(dir: &Path) -> Option<bool> {
+    let name = format!(".CADalytix-Case-{}", uuid::Uuid::new_v4().simple());
+    let probe = dir.join(&name);
+    tokio::fs::write(&probe, b"").await.ok()?;
+    let folded = tokio::fs::try_exists(dir.join(name.to_lowercase()))
+        .await
+        .unwrap_or(false);
+    let _ = tokio::fs::remove_file(&probe).await;
+    Some(!folded)
+}
+
+/// Longest path, relative to the payload root, of a file the install copies (0 when the payload
+/// cannot be read).
+async fn deepest_payload_path(install_mode: &str) -> usize {
+    let Ok((shared, platform)) =
+        crate::api::installer::resolve_runtime_payload_roots(install_mode).await
+    else {
+        return 0;
+    };
+    let mut deepest = 0;
+    for root in [shared, platform] {
+        let Ok(files) = crate::installation::files::collect_files_recursive(&root).await else {
+            continue;
+        };
+        for file in files {
+            if let Ok(rel) = file.strip_prefix(&root) {
+                deepest = deepest.max(rel.to_string_lossy().len());
+            }
+        }
+    }
+    deepest
+}
+
+async fn long_paths_enabled() -> bool {
+    let args: Vec<String> = [
+        "query",
+        r"HKLM\SYSTEM\CurrentControlSet\Control\FileSystem",
+        "/v",
+        "LongPathsEnabled",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    match run_cmd_with_timeout("reg", &args, Duration::from_secs(10), "reg_long_paths").await {
+        Ok(out) => parse_long_paths_enabled(&out.stdout),
+        Err(_) => false,
+    }
+}
+
+/// `reg query` prints `    LongPathsEnabled    REG_DWORD    0x1`.
+fn parse_long_paths_enabled(text: &str) -> bool {
+    text.lines().any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        matches!(fields.as_slice(), [name, "REG_DWORD", value]
+            if name.eq_ignore_ascii_case("LongPathsEnabled") && *value == "0x1")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn layout_paths_are_checked_for_characters_length_and_case() {
+        assert_eq!(
+            invalid_characters(r"C:\Program Files\CADalytix", true),
+            None
+        );
+        assert_eq!(invalid_characters(r"\\files01\share\CADalytix", true), None);
+        assert_eq!(
+            invalid_characters(r"D:\CAD|alytix", true).as_deref(),
+            Some("contains the character '|'")
+        );
+        assert!(invalid_characters(r"D:\CADalytix\con.data", true)
+            .unwrap()
+            .contains("reserved"));
+        assert!(invalid_characters(r"D:\CADalytix.\Data", true).is_some());
+        assert!(invalid_characters("/opt/cad\nalytix", false).is_some());
+        assert_eq!(invalid_characters("/opt/cad:alytix?", false), None);
+
+        assert_eq!(length_problem(r"C:\CADalytix", 120, true, false), None);
+        let deep = format!(r"C:\{}", "x".repeat(200));
+        assert!(length_problem(&deep, 120, true, false)
+            .unwrap()
+            .contains("324 characters"));
+        assert_eq!(length_problem(&deep, 120, true, true), None);
+        assert!(length_problem(&format!("/opt/{}", "n".repeat(256)), 0, false, false).is_some());
+
+        assert!(spelled_differently(
+            Path::new("/srv/CADalytix"),
+            Path::new("/srv/cadalytix/data")
+        ));
+        assert!(!spelled_differently(
+            Path::new("/srv/CADalytix"),
+            Path::new("/srv/CADalytix/data")
+        ));
+        assert!(!spelled_differently(
+            Path::new("/srv/cad"),
+            Path::new("/srv/cadalytix")
+        ));
+
+        assert!(parse_long_paths_enabled(
+            "\r\nHKEY_LOCAL_MACHINE\\SYSTEM\\CurrentControlSet\\Control\\FileSystem\r\n    LongPathsEnabled    REG_DWORD    0x1\r\n"
+        ));
+        assert!(!parse_long_paths_enabled(
+            "    LongPathsEnabled    REG_DWORD    0x0"
+        ));
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("cadalytix")).unwrap();
+        if probe_case_sensitive(tmp.path()).await == Some(true) {
+            let dir = tmp.path().join("CADalytix").join("data");
+            assert_eq!(
+                case_variant_sibling(&dir, Some(tmp.path())).await,
+                Some(tmp.path().join("cadalytix"))
+            );
+        }
+    }
+}
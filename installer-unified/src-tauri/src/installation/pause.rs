@@ -0,0 +1,194 @@
+//! Pause/resume for a running install, and the checkpoint that lets an interrupted install pick
+//! up later.
+//!
+//! Pausing only takes effect at step boundaries in `run_installation` (between steps, between
+//! migrations, between copied files), never inside a migration's transaction. While paused the
+//! run persists `install-checkpoint.json` in the log folder and waits for Resume or Cancel.
+//!
+//! The checkpoint holds no secrets: a fingerprint of the install settings, the last step reached,
+//! and the Create NEW database name once it exists. Running the installer again with the same
+//! settings reuses that database instead of failing with "already exists"; migrations already
+//! applied are skipped as usual.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use tokio::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+const CHECKPOINT_FILE: &str = "install-checkpoint.json";
+const CHECKPOINT_VERSION: u32 = 1;
+
+static PAUSE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+pub fn request_pause() {
+    info!("[PHASE: install] [STEP: pause] pause requested");
+    PAUSE_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+pub fn request_resume() {
+    info!("[PHASE: install] [STEP: pause] resume requested");
+    PAUSE_REQUESTED.store(false, Ordering::SeqCst);
+}
+
+pub fn is_pause_requested() -> bool {
+    PAUSE_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Clear a stale pause request (start of each run).
+pub fn reset() {
+    PAUSE_REQUESTED.store(false, Ordering::SeqCst);
+}
+
+/// Block until resumed; Cancel while paused ends the wait with `Cancelled`.
+pub async fn wait_while_paused(cancel: &CancellationToken) -> Result<()> {
+    while is_pause_requested() {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(250)) => {}
+            _ = cancel.cancelled() => {}
+        }
+        super::cancel::check(cancel)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCheckpoint {
+    pub version: u32,
+    /// Fingerprint of the settings this checkpoint belongs to (see `install_settings_fingerprint`).
+    pub settings_fingerprint: String,
+    pub correlation_id: String,
+    pub updated_utc: String,
+    pub last_step: String,
+    pub percent: i32,
+    /// Create NEW database already created by this install.
+    #[serde(default)]
+    pub created_database: Option<String>,
+}
+
+impl InstallCheckpoint {
+    pub fn new(settings_fingerprint: &str, correlation_id: &str) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            settings_fingerprint: settings_fingerprint.to_string(),
+            correlation_id: correlation_id.to_string(),
+            updated_utc: chrono::Utc::now().to_rfc3339(),
+            last_step: "start".to_string(),
+            percent: 0,
+            created_database: None,
+        }
+    }
+
+    pub fn reached(&mut self, step: &str, percent: i32) {
+        self.last_step = step.to_string();
+        self.percent = percent;
+        self.updated_utc = chrono::Utc::now().to_rfc3339();
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(crate::utils::path_resolver::resolve_log_folder()?.join(CHECKPOINT_FILE))
+    }
+
+    /// Checkpoint left by an earlier run with the same settings, if any.
+    pub async fn load_matching(settings_fingerprint: &str) -> Option<Self> {
+        let path = Self::path().ok()?;
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice::<Self>(&bytes) {
+            Ok(cp)
+                if cp.version == CHECKPOINT_VERSION
+                    && cp.settings_fingerprint == settings_fingerprint =>
+            {
+                Some(cp)
+            }
+            Ok(_) => {
+                info!(
+                    "[PHASE: install] [STEP: checkpoint] Ignoring checkpoint for different settings (path={:?})",
+                    path
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "[PHASE: install] [STEP: checkpoint] Unreadable checkpoint ignored (path={:?}): {}",
+                    path, e
+                );
+                None
+            }
+        }
+    }
+
+    pub async fn save(&self) -> Result<PathBuf> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let bytes = serde_json::to_vec_pretty(self)?;
+        tokio::fs::write(&path, bytes)
+            .await
+            .with_context(|| format!("Failed to write install checkpoint: {:?}", path))?;
+        info!(
+            "[PHASE: install] [STEP: checkpoint] Saved (path={:?}, last_step={}, percent={})",
+            path, self.last_step, self.percent
+        );
+        Ok(path)
+    }
+
+    /// Remove the checkpoint (install finished, or rolled back).
+    pub async fn clear() {
+        if let Ok(path) = Self::path() {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => info!(
+                    "[PHASE: install] [STEP: checkpoint] Cleared (path={:?})",
+                    path
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!(
+                    "[PHASE: install] [STEP: checkpoint] Failed to clear (path={:?}): {}",
+                    path, e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_round_trips_without_secrets() {
+        let mut cp = InstallCheckpoint::new("fp-123", "corr-1");
+        cp.reached("migrations", 31);
+        cp.created_database = Some("cadalytix".to_string());
+
+        let json = serde_json::to_string(&cp).unwrap();
+        assert!(json.contains("\"settingsFingerprint\":\"fp-123\""));
+        assert!(json.contains("\"lastStep\":\"migrations\""));
+        assert!(json.contains("\"createdDatabase\":\"cadalytix\""));
+        let back: InstallCheckpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cp);
+    }
+
+    #[tokio::test]
+    async fn wait_while_paused_returns_on_resume_or_cancel() {
+        let cancel = CancellationToken::new();
+        request_pause();
+        let waiter = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { wait_while_paused(&cancel).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        request_resume();
+        assert!(waiter.await.unwrap().is_ok());
+
+        request_pause();
+        cancel.cancel();
+        let err = wait_while_paused(&cancel).await.unwrap_err();
+        assert!(super::super::cancel::is_cancelled(&err));
+        reset();
+    }
+}
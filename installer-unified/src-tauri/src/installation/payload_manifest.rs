@@ -0,0 +1,321 @@
+//! Packaging-time manifest of the runtime payload (`runtime/payload-manifest.json`).
+//!
+//! Release packaging records the SHA-256 of every file under `runtime/` (paths relative to it,
+//! forward slashes) and may sign the manifest bytes with the release Ed25519 key
+//! (`runtime/payload-manifest.sig`, base64). The installer checks each source file against it
+//! before copying and re-hashes each deployed copy afterwards, so a corrupted or swapped payload
+//! file never goes live. The install manifest then records what was deployed, and
+//! `verify_setup` re-hashes those files to report drift.
+//!
+//! Payloads without a manifest (developer builds) install with a warning and no checks.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+
+use crate::security::crypto::{release_signing_key, verify_ed25519};
+
+pub const MANIFEST_FILE: &str = "payload-manifest.json";
+pub const SIGNATURE_FILE: &str = "payload-manifest.sig";
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadManifest {
+    pub schema_version: u32,
+    /// `runtime/`-relative path (forward slashes) -> lowercase SHA-256 hex.
+    pub files: BTreeMap<String, String>,
+    /// Whether a detached signature was present and verified.
+    #[serde(skip)]
+    pub signed: bool,
+}
+
+impl PayloadManifest {
+    /// Parse manifest bytes, checking the detached signature when one is present.
+    pub fn parse(bytes: &[u8], signature_b64: Option<&str>, public_key: &[u8]) -> Result<Self> {
+        if let Some(sig) = signature_b64 {
+            if !verify_ed25519(public_key, bytes, sig) {
+                anyhow::bail!(
+                    "Payload manifest signature verification failed - the runtime payload may be tampered with"
+                );
+            }
+        }
+        let mut manifest: PayloadManifest =
+            serde_json::from_slice(bytes).context("Payload manifest is invalid")?;
+        if manifest.schema_version != MANIFEST_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Unsupported payload manifest schema version {} (expected {})",
+                manifest.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        manifest.signed = signature_b64.is_some();
+        Ok(manifest)
+    }
+
+    /// Expected SHA-256 of `path`, a file under `runtime_dir`.
+    pub fn expected_sha256(&self, runtime_dir: &Path, path: &Path) -> Option<&str> {
+        self.files
+            .get(&manifest_key(runtime_dir, path)?)
+            .map(String::as_str)
+    }
+}
+
+/// `runtime/`-relative manifest key of `path`.
+fn manifest_key(runtime_dir: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(runtime_dir).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Load `runtime_dir/payload-manifest.json` (and its signature, if shipped).
+/// `Ok(None)` when the payload has no manifest.
+pub async fn load(runtime_dir: &Path) -> Result<Option<PayloadManifest>> {
+    let manifest_path = runtime_dir.join(MANIFEST_FILE);
+    if !tokio::fs::try_exists(&manifest_path).await.unwrap_or(false) {
+        warn!(
+            "[PHASE: installation] [STEP: payload_verify] No {} in {:?}; payload integrity is not checked",
+            MANIFEST_FILE, runtime_dir
+        );
+        return Ok(None);
+    }
+    let bytes = tokio::fs::read(&manifest_path)
+        .await
+        .with_context(|| format!("Failed to read payload manifest: {:?}", manifest_path))?;
+    let signature = tokio::fs::read_to_string(runtime_dir.join(SIGNATURE_FILE))
+        .await
+        .ok();
+    // The key is only needed (and only required to be built in) for a signed manifest.
+    let public_key = match signature {
+        Some(_) => release_signing_key()?,
+        None => Vec::new(),
+    };
+    let manifest = PayloadManifest::parse(&bytes, signature.as_deref(), &public_key)?;
+    info!(
+        "[PHASE: installation] [STEP: payload_verify] Payload manifest loaded (files={}, signed={})",
+        manifest.files.len(),
+        manifest.signed
+    );
+    Ok(Some(manifest))
+}
+
+/// Check every source file against the manifest before anything is copied: each must be listed
+/// with a matching checksum, and every listed file under `roots` must be present.
+pub async fn verify_sources(
+    manifest: &PayloadManifest,
+    runtime_dir: &Path,
+    roots: &[&Path],
+    sources: &[PathBuf],
+) -> Result<()> {
+    let mut seen: HashSet<String> = HashSet::new();
+    for src in sources {
+        let key = manifest_key(runtime_dir, src)
+            .ok_or_else(|| anyhow::anyhow!("Payload file outside runtime/: {:?}", src))?;
+        let Some(expected) = manifest.files.get(&key) else {
+            anyhow::bail!(
+                "Runtime payload file '{}' is not listed in {}",
+                key,
+                MANIFEST_FILE
+            );
+        };
+        let actual = super::files::sha256_file(src).await?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!(
+                "Runtime payload file '{}' does not match its {} checksum",
+                key,
+                MANIFEST_FILE
+            );
+        }
+        seen.insert(key);
+    }
+
+    let prefixes: Vec<String> = roots
+        .iter()
+        .filter_map(|r| manifest_key(runtime_dir, r))
+        .map(|k| format!("{}/", k))
+        .collect();
+    if let Some(missing) = manifest
+        .files
+        .keys()
+        .find(|k| prefixes.iter().any(|p| k.starts_with(p)) && !seen.contains(*k))
+    {
+        anyhow::bail!(
+            "Runtime payload file '{}' listed in {} is missing",
+            missing,
+            MANIFEST_FILE
+        );
+    }
+    Ok(())
+}
+
+/// Re-hash a deployed copy and compare it with the manifest checksum of its source.
+pub async fn verify_deployed(expected_sha256: &str, deployed: &Path) -> Result<()> {
+    let actual = super::files::sha256_file(deployed).await?;
+    if !actual.eq_ignore_ascii_case(expected_sha256.trim()) {
+        anyhow::bail!(
+            "Deployed file {:?} does not match the payload manifest after copy",
+            deployed
+        );
+    }
+    Ok(())
+}
+
+/// A file recorded in an install manifest that no longer matches the deployed copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftedFile {
+    /// Path as recorded: relative to the binaries folder, or absolute for log-folder artifacts.
+    pub path: String,
+    /// Where the file is deployed.
+    pub deployed: PathBuf,
+    /// Checksum recorded at install time.
+    pub sha256: String,
+    /// Gone rather than changed.
+    pub missing: bool,
+}
+
+impl DriftedFile {
+    /// `"changed: <path>"` or `"missing: <path>"`.
+    pub fn describe(&self) -> String {
+        let kind = if self.missing { "missing" } else { "changed" };
+        format!("{}: {}", kind, self.path)
+    }
+}
+
+/// Files recorded in an install manifest (`install-manifest.json`) that no longer match:
+/// `"changed: <path>"` or `"missing: <path>"`, in manifest order.
+pub async fn install_manifest_drift(install_manifest: &Path) -> Result<Vec<String>> {
+    Ok(drifted_files(install_manifest)
+        .await?
+        .iter()
+        .map(DriftedFile::describe)
+        .collect())
+}
+
+/// [`install_manifest_drift`] with the deployed path and recorded checksum of each file.
+pub async fn drifted_files(install_manifest: &Path) -> Result<Vec<DriftedFile>> {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Entry {
+        path: String,
+        sha256: String,
+    }
+    #[derive(serde::Deserialize)]
+    struct Layout {
+        #[serde(default)]
+        binaries: String,
+    }
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct InstallManifest {
+        destination_folder: String,
+        #[serde(default)]
+        layout: Option<Layout>,
+        files: Vec<Entry>,
+    }
+
+    let bytes = tokio::fs::read(install_manifest)
+        .await
+        .with_context(|| format!("Failed to read install manifest: {:?}", install_manifest))?;
+    let manifest: InstallManifest =
+        serde_json::from_slice(&bytes).context("Install manifest is invalid")?;
+    // Relative paths are recorded against the binaries folder (the destination by default).
+    let dest_root = match manifest.layout.map(|l| l.binaries) {
+        Some(binaries) if !binaries.trim().is_empty() => PathBuf::from(binaries),
+        _ => PathBuf::from(&manifest.destination_folder),
+    };
+
+    let mut drift = Vec::new();
+    for entry in manifest.files {
+        // Paths outside the destination folder (log-folder artifacts) are recorded absolute.
+        let deployed = if Path::new(&entry.path).is_absolute() {
+            PathBuf::from(&entry.path)
+        } else {
+            dest_root.join(&entry.path)
+        };
+        let missing = !tokio::fs::try_exists(&deployed).await.unwrap_or(false);
+        if missing
+            || !super::files::sha256_file(&deployed)
+                .await?
+                .eq_ignore_ascii_case(entry.sha256.trim())
+        {
+            drift.push(DriftedFile {
+                path: entry.path,
+                deployed,
+                sha256: entry.sha256,
+                missing,
+            });
+        }
+    }
+    Ok(drift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[tokio::test]
+    async fn sources_are_checked_against_a_signed_manifest() {
+        let runtime = std::env::temp_dir().join(format!("payload-{}", uuid::Uuid::new_v4()));
+        let shared = runtime.join("shared");
+        let linux = runtime.join("linux");
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::create_dir_all(&linux).unwrap();
+        std::fs::write(shared.join("app.dll"), b"app").unwrap();
+        std::fs::write(linux.join("cadalytix-server"), b"server").unwrap();
+
+        let bytes = serde_json::to_vec(&serde_json::json!({
+            "schemaVersion": 1,
+            "files": {
+                "shared/app.dll": crate::security::crypto::sha256_hex(b"app"),
+                "linux/cadalytix-server": crate::security::crypto::sha256_hex(b"server"),
+                "windows/Cadalytix.Service.exe": crate::security::crypto::sha256_hex(b"exe"),
+            }
+        }))
+        .unwrap();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let sig = base64::engine::general_purpose::STANDARD.encode(key.sign(&bytes));
+
+        let manifest =
+            PayloadManifest::parse(&bytes, Some(&sig), key.public_key().as_ref()).unwrap();
+        assert!(manifest.signed);
+        assert!(PayloadManifest::parse(&bytes, Some(&sig), &[0u8; 32]).is_err());
+
+        let roots = [shared.as_path(), linux.as_path()];
+        let sources = vec![shared.join("app.dll"), linux.join("cadalytix-server")];
+        // Windows entries are not part of a Linux install.
+        verify_sources(&manifest, &runtime, &roots, &sources)
+            .await
+            .unwrap();
+        assert_eq!(
+            manifest.expected_sha256(&runtime, &shared.join("app.dll")),
+            Some(crate::security::crypto::sha256_hex(b"app").as_str())
+        );
+
+        let err = verify_sources(&manifest, &runtime, &roots, &sources[..1])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing"), "{}", err);
+
+        std::fs::write(linux.join("cadalytix-server"), b"patched").unwrap();
+        let err = verify_sources(&manifest, &runtime, &roots, &sources)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{}", err);
+        assert!(verify_deployed(
+            manifest.files["linux/cadalytix-server"].as_str(),
+            &sources[1]
+        )
+        .await
+        .is_err());
+        let _ = std::fs::remove_dir_all(&runtime);
+    }
+}
@@ -0,0 +1,400 @@
+//! Install progress for orchestration tools (Ansible, SCCM) over a local socket.
+//!
+//! While an install runs, every `ProgressPayload` is also written as newline-delimited JSON to a
+//! Unix socket (Linux/macOS) or named pipe (Windows):
+//!
+//! - `{"event":"progress","payload":{...}}` for each progress update (a client that connects late
+//!   first gets the latest one)
+//! - `{"event":"finished","success":true,"message":"..."}` when the run ends
+//!
+//! Clients may send `cancel`, `pause` or `resume` lines; each is answered with
+//! `{"event":"ack","command":"cancel","ok":true}` (`ok` is false when no install is running).
+//!
+//! Off unless requested, first match wins: `--progress-socket[=<path>]`,
+//! `CADALYTIX_PROGRESS_SOCKET` (a path, or `default`). The default endpoint is
+//! `install-progress.sock` in the log folder, or `\\.\pipe\cadalytix-install-progress` on Windows.
+//! The Unix socket is created owner-only (0600): it is bound inside a fresh 0700 directory and
+//! moved into place, so it is never reachable with looser permissions. An existing file at the
+//! path is only replaced when it is a socket.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::api::installer::{ProgressEmitter, ProgressPayload};
+
+const SOCKET_ENV: &str = "CADALYTIX_PROGRESS_SOCKET";
+#[cfg(not(windows))]
+const SOCKET_FILE_NAME: &str = "install-progress.sock";
+#[cfg(windows)]
+const DEFAULT_PIPE_NAME: &str = r"\\.\pipe\cadalytix-install-progress";
+
+/// Endpoint from `--progress-socket` (`Some("")` = default endpoint).
+static REQUESTED: OnceLock<String> = OnceLock::new();
+
+/// Enable the stream from `--progress-socket[=<path>]` (before the install starts).
+pub fn request(path: &str) {
+    let _ = REQUESTED.set(path.trim().to_string());
+}
+
+fn default_endpoint() -> Result<PathBuf> {
+    #[cfg(windows)]
+    {
+        Ok(PathBuf::from(DEFAULT_PIPE_NAME))
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(crate::utils::path_resolver::resolve_log_folder()?.join(SOCKET_FILE_NAME))
+    }
+}
+
+/// The requested endpoint, or `None` when the stream is off.
+fn endpoint() -> Result<Option<PathBuf>> {
+    let requested = REQUESTED.get().cloned().or_else(|| {
+        std::env::var(SOCKET_ENV)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    });
+    match requested.as_deref() {
+        None => Ok(None),
+        Some("" | "default") => default_endpoint().map(Some),
+        Some(path) => Ok(Some(PathBuf::from(path))),
+    }
+}
+
+/// Events shared with every connected client.
+#[derive(Clone)]
+struct Hub {
+    events: broadcast::Sender<String>,
+    latest: Arc<Mutex<Option<String>>>,
+}
+
+impl Hub {
+    fn send(&self, line: String) {
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = Some(line.clone());
+        }
+        // No receivers is fine: nobody is connected yet.
+        let _ = self.events.send(line);
+    }
+
+    /// Serve one connected client until it disconnects or the run's stream is dropped.
+    fn serve<S: AsyncRead + AsyncWrite + Send + 'static>(&self, stream: S) {
+        let events = self.events.subscribe();
+        let latest = self.latest.lock().ok().and_then(|l| l.clone());
+        tokio::spawn(serve_client(stream, events, latest));
+    }
+}
+
+/// The stream of one install run; dropped (listener stopped) after [`ProgressStream::finish`].
+pub struct ProgressStream {
+    hub: Hub,
+    endpoint: PathBuf,
+    listener: JoinHandle<()>,
+}
+
+/// Open the endpoint when the stream is requested. Failures are logged; the install goes on.
+pub async fn start() -> Option<ProgressStream> {
+    let endpoint = match endpoint() {
+        Ok(Some(p)) => p,
+        Ok(None) => return None,
+        Err(e) => {
+            warn!(
+                "[PHASE: install] [STEP: progress_stream] No progress socket: {:?}",
+                e
+            );
+            return None;
+        }
+    };
+    match start_at(endpoint.clone()) {
+        Ok(stream) => {
+            info!(
+                "[PHASE: install] [STEP: progress_stream] Publishing progress on {}",
+                endpoint.display()
+            );
+            Some(stream)
+        }
+        Err(e) => {
+            warn!(
+                "[PHASE: install] [STEP: progress_stream] Failed to open {}: {:?}",
+                endpoint.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+fn start_at(endpoint: PathBuf) -> Result<ProgressStream> {
+    let hub = Hub {
+        events: broadcast::channel(256).0,
+        latest: Arc::new(Mutex::new(None)),
+    };
+    let listener = listen(&endpoint, hub.clone())?;
+    Ok(ProgressStream {
+        hub,
+        endpoint,
+        listener,
+    })
+}
+
+impl ProgressStream {
+    /// `emit` that also publishes each payload to the connected clients.
+    pub fn tee(&self, emit: ProgressEmitter) -> ProgressEmitter {
+        let hub = self.hub.clone();
+        Arc::new(move |payload: ProgressPayload| {
+            let event = serde_json::json!({ "event": "progress", "payload": &payload });
+            hub.send(event.to_string());
+            emit(payload);
+        })
+    }
+
+    /// Publish the outcome, give clients a moment to read it, then close the endpoint.
+    pub async fn finish(self, success: bool, message: String) {
+        let event = serde_json::json!({
+            "event": "finished",
+            "success": success,
+            "message": message,
+        });
+        self.hub.send(event.to_string());
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        self.listener.abort();
+        #[cfg(unix)]
+        let _ = remove_socket(&self.endpoint);
+        info!(
+            "[PHASE: install] [STEP: progress_stream] Closed {}",
+            self.endpoint.display()
+        );
+    }
+}
+
+#[cfg(unix)]
+fn listen(endpoint: &std::path::Path, hub: Hub) -> Result<JoinHandle<()>> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let parent = match endpoint.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => std::path::Path::new("."),
+    };
+    std::fs::create_dir_all(parent)?;
+    // A socket left by an earlier run would make the final rename fail over it; anything else
+    // at the path is refused.
+    remove_socket(endpoint)?;
+
+    let private = parent.join(format!(".progress-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private)
+        .with_context(|| format!("Failed to create {}", private.display()))?;
+    let bound = private.join("sock");
+    let listener = (|| -> Result<tokio::net::UnixListener> {
+        let listener = tokio::net::UnixListener::bind(&bound)
+            .with_context(|| format!("Failed to bind {}", endpoint.display()))?;
+        std::fs::set_permissions(&bound, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&bound, endpoint)
+            .with_context(|| format!("Failed to move socket to {}", endpoint.display()))?;
+        Ok(listener)
+    })();
+    let _ = std::fs::remove_file(&bound);
+    let _ = std::fs::remove_dir(&private);
+    let listener = listener?;
+    Ok(tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            hub.serve(stream);
+        }
+    }))
+}
+
+/// Remove `path` if it is a socket; a missing path is fine, anything else is an error.
+#[cfg(unix)]
+fn remove_socket(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => Ok(std::fs::remove_file(path)?),
+        Ok(_) => anyhow::bail!(
+            "{} exists and is not a socket; refusing to replace it",
+            path.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(windows)]
+fn listen(endpoint: &std::path::Path, hub: Hub) -> Result<JoinHandle<()>> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = endpoint.as_os_str().to_os_string();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)
+        .with_context(|| format!("Failed to create pipe {}", endpoint.display()))?;
+    Ok(tokio::spawn(async move {
+        loop {
+            if server.connect().await.is_err() {
+                return;
+            }
+            let next = match ServerOptions::new().create(&name) {
+                Ok(next) => next,
+                Err(_) => return,
+            };
+            hub.serve(std::mem::replace(&mut server, next));
+        }
+    }))
+}
+
+/// Answer one `cancel` / `pause` / `resume` line.
+fn handle_command(line: &str) -> String {
+    let command = line.trim().to_ascii_lowercase();
+    let ok = match command.as_str() {
+        "cancel" => super::cancel::request_cancel(),
+        "pause" if super::cancel::is_active() => {
+            super::pause::request_pause();
+            true
+        }
+        "resume" if super::cancel::is_active() => {
+            super::pause::request_resume();
+            true
+        }
+        _ => false,
+    };
+    if ok {
+        info!(
+            "[PHASE: install] [STEP: progress_stream] '{}' received over the progress socket",
+            command
+        );
+    }
+    serde_json::json!({ "event": "ack", "command": command, "ok": ok }).to_string()
+}
+
+async fn serve_client<S: AsyncRead + AsyncWrite>(
+    stream: S,
+    mut events: broadcast::Receiver<String>,
+    latest: Option<String>,
+) {
+    let (read, mut write) = tokio::io::split(stream);
+    let mut lines = BufReader::new(read).lines();
+    if let Some(line) = latest {
+        if write_line(&mut write, &line).await.is_err() {
+            return;
+        }
+    }
+    loop {
+        let line = tokio::select! {
+            command = lines.next_line() => match command {
+                Ok(Some(command)) if command.trim().is_empty() => continue,
+                Ok(Some(command)) => handle_command(&command),
+                _ => return,
+            },
+            event = events.recv() => match event {
+                Ok(line) => line,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+        };
+        if write_line(&mut write, &line).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(write: &mut W, line: &str) -> std::io::Result<()> {
+    write.write_all(line.as_bytes()).await?;
+    write.write_all(b"\n").await?;
+    write.flush().await
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn clients_receive_progress_and_commands_are_acknowledged() {
+        let dir = std::env::temp_dir().join(format!("progress-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(SOCKET_FILE_NAME);
+        let stream = start_at(path.clone()).unwrap();
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let sink = emitted.clone();
+        let emit = stream.tee(Arc::new(move |p: ProgressPayload| {
+            sink.lock().unwrap().push(p.step);
+        }));
+        let payload = |step: &str, percent| ProgressPayload {
+            correlation_id: "c1".to_string(),
+            step: step.to_string(),
+            severity: "info".to_string(),
+            phase: "install".to_string(),
+            percent,
+            message: format!("{} running", step),
+            elapsed_ms: None,
+            eta_ms: None,
+        };
+
+        // A late client first gets the latest payload.
+        emit(payload("start", 1));
+        let client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (read, mut write) = client.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let first: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(first["event"], "progress");
+        assert_eq!(first["payload"]["step"], "start");
+
+        // No install is running here, so cancel is refused.
+        write.write_all(b"cancel\n").await.unwrap();
+        let ack: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(ack["event"], "ack");
+        assert_eq!(ack["ok"], false);
+
+        emit(payload("migrations", 40));
+        let next: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(next["payload"]["percent"], 40);
+        assert_eq!(*emitted.lock().unwrap(), vec!["start", "migrations"]);
+
+        stream
+            .finish(true, "Installation complete.".to_string())
+            .await;
+        let done: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(done["event"], "finished");
+        assert_eq!(done["success"], true);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    #[tokio::test]
+    async fn socket_is_owner_only_and_never_replaces_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = dir.path().join("progress.sock");
+
+        let stream = start_at(endpoint.clone()).unwrap();
+        let meta = std::fs::symlink_metadata(&endpoint).unwrap();
+        assert!(meta.file_type().is_socket());
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        // Only the socket is left behind, not the private bind directory.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        tokio::net::UnixStream::connect(&endpoint).await.unwrap();
+        stream.finish(true, String::new()).await;
+        assert!(!endpoint.exists());
+
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, b"keep me").unwrap();
+        assert!(start_at(file.clone()).is_err());
+        assert!(remove_socket(&file).is_err());
+        assert_eq!(std::fs::read(&file).unwrap(), b"keep me");
+    }
+}
@@ -0,0 +1,844 @@
+//! Remote install over SSH: the wizard runs on an operator workstation and the Linux-native
+//! install lands on a server it reaches over SSH (`remote` of the install request; the terminal
+//! wizards take it from `--remote=`, see [`target_arg`]).
+//!
+//! Everything the install writes under the destination is staged on the workstation (the
+//! `remote/<host>` folder of the log folder) and pushed at `service_start`: files through `sftp`
+//! batch mode into a private upload folder on the server, copied into place and the systemd unit
+//! registered and started with `sudo -n`. Commands go through the OpenSSH client and
+//! `run_cmd_with_timeout` (timeouts, retries, Cancel), so the workstation needs `ssh`, `sftp` and
+//! `ssh-keyscan` on PATH (part of Windows 10+ and every Linux). The database steps still run from
+//! the workstation, and the runtime config's secrets are protected with a key staged and deployed
+//! with the binaries.
+//!
+//! Login is key-based only (`BatchMode`): an identity file or the ssh agent; the login must be
+//! root or have passwordless sudo. The server's host key is never trusted on first use: the
+//! operator confirms its SHA256 fingerprint (Check server on the Destination page lists the ones
+//! the server offers), the install pins it in a known_hosts file of its own and ssh refuses any
+//! other key. Changes made on the server are not rolled back; re-run the install or `--repair` it
+//! there.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::Duration;
+
+use crate::api::installer::StartInstallRequest;
+use crate::installation::files::collect_files_recursive;
+use crate::installation::layout::{InstallLayout, InstallLayoutConfig};
+use crate::installation::space_projection::{SpaceNeed, VolumeProjection};
+use crate::installation::{run_cmd_with_timeout, service, CommandOutput};
+use crate::security::audit::{self, AuditAction};
+use crate::security::key_store::KeyBackend;
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+
+const DEFAULT_PORT: u16 = 22;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Files per `sftp` run; upload progress is reported between runs.
+const UPLOAD_BATCH: usize = 100;
+/// Staged under the destination but pushed to `<destination>/installer-artifacts` at the end.
+const ARTIFACTS_DIR: &str = "installer-artifacts";
+
+/// Server of the terminal wizards' install (`--remote=`); set once by [`select`].
+static SELECTED: OnceLock<RemoteTarget> = OnceLock::new();
+
+/// Server a remote install deploys to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteTarget {
+    pub host: String,
+    /// SSH port; 22 when unset.
+    #[serde(default)]
+    pub port: Option<u16>,
+    pub user: String,
+    /// Private key file; empty uses the ssh agent and the default keys.
+    #[serde(default)]
+    pub identity_file: String,
+    /// Host key fingerprint the operator confirmed, `SHA256:...` as `ssh-keygen -lf` prints it.
+    #[serde(default)]
+    pub host_key_fingerprint: String,
+}
+
+impl RemoteTarget {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(DEFAULT_PORT)
+    }
+
+    /// `user@host:port`, for messages and the audit log.
+    pub fn label(&self) -> String {
+        format!("{}@{}:{}", self.user.trim(), self.host.trim(), self.port())
+    }
+
+    /// `user@host[:port]`, with brackets around an IPv6 address that has a port
+    /// (`user@[fd00::5]:2222`).
+    pub fn parse(destination: &str) -> Result<Self, String> {
+        let destination = destination.trim();
+        let invalid = || format!("'{}' is not user@host[:port].", destination);
+        let (user, rest) = destination.split_once('@').ok_or_else(invalid)?;
+        let (host, port) = match rest.strip_prefix('[') {
+            Some(v6) => {
+                let (host, after) = v6.split_once(']').ok_or_else(invalid)?;
+                match after {
+                    "" => (host, None),
+                    _ => (host, Some(after.strip_prefix(':').ok_or_else(invalid)?)),
+                }
+            }
+            None => match rest.split_once(':') {
+                Some((host, port)) if !port.contains(':') => (host, Some(port)),
+                _ => (rest, None),
+            },
+        };
+        let target = Self {
+            host: host.to_string(),
+            port: port
+                .map(|p| p.parse::<u16>().map_err(|_| invalid()))
+                .transpose()?,
+            user: user.to_string(),
+            ..Default::default()
+        };
+        target.validate_login()?;
+        Ok(target)
+    }
+
+    /// [`Self::validate_login`], and the host key fingerprint is confirmed.
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_login()?;
+        if !self.host_key_fingerprint.trim().starts_with("SHA256:") {
+            return Err(
+                "Confirm the remote server's host key fingerprint (SHA256:...) before installing."
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Host and user must be single words that cannot read as ssh options.
+    pub fn validate_login(&self) -> Result<(), String> {
+        let word = |v: &str| {
+            !v.is_empty()
+                && !v.starts_with('-')
+                && !v.chars().any(|c| c.is_whitespace() || c == '@')
+        };
+        if !word(self.host.trim()) {
+            return Err(format!(
+                "Remote server '{}' is not a valid host name.",
+                self.host
+            ));
+        }
+        if !word(self.user.trim()) {
+            return Err(format!(
+                "Remote user '{}' is not a valid user name.",
+                self.user
+            ));
+        }
+        if self.port == Some(0) {
+            return Err("The remote SSH port must be between 1 and 65535.".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Paths of a remote install are paths on the server: they must be absolute Linux paths.
+pub fn validate_paths(req: &StartInstallRequest) -> Result<(), String> {
+    if !req.destination_folder.trim().starts_with('/') {
+        return Err(path_error("install", &req.destination_folder));
+    }
+    validate_layout(&req.layout)
+}
+
+/// [`crate::installation::layout::validate`] for a remote install.
+pub fn validate_layout(config: &InstallLayoutConfig) -> Result<(), String> {
+    for (role, path) in [
+        ("binaries", &config.binaries),
+        ("data", &config.data),
+        ("logs", &config.logs),
+        ("temp", &config.temp),
+    ] {
+        let path = path.trim();
+        if !path.is_empty() && !path.starts_with('/') {
+            return Err(path_error(role, path));
+        }
+    }
+    Ok(())
+}
+
+fn path_error(role: &str, path: &str) -> String {
+    format!(
+        "The {} folder of a remote install must be an absolute path on the server (got '{}').",
+        role,
+        path.trim()
+    )
+}
+
+/// `--remote=user@host[:port]` with `--remote-fingerprint=SHA256:...` and, optionally,
+/// `--remote-identity=<private key file>`; `None` without `--remote=`.
+pub fn target_arg(args: &[String]) -> Result<Option<RemoteTarget>> {
+    let value = |prefix: &str| args.iter().find_map(|a| a.strip_prefix(prefix));
+    let Some(destination) = value("--remote=") else {
+        return Ok(None);
+    };
+    let target = RemoteTarget {
+        identity_file: value("--remote-identity=").unwrap_or_default().to_string(),
+        host_key_fingerprint: value("--remote-fingerprint=")
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        ..RemoteTarget::parse(destination).map_err(anyhow::Error::msg)?
+    };
+    target.validate().map_err(anyhow::Error::msg)?;
+    Ok(Some(target))
+}
+
+/// Make `target` the server the terminal wizards install on.
+pub fn select(target: RemoteTarget) -> Result<()> {
+    let label = target.label();
+    if SELECTED.set(target).is_err() {
+        anyhow::bail!("A remote server is already selected");
+    }
+    info!(
+        "[PHASE: initialization] [STEP: remote] Remote install target selected ({})",
+        label
+    );
+    Ok(())
+}
+
+/// The server selected with `--remote=`, if any.
+pub fn selected() -> Option<&'static RemoteTarget> {
+    SELECTED.get()
+}
+
+/// `path` with `/` separators; layout paths of a remote install are built on the workstation.
+pub fn unix_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// `s` as one POSIX shell word.
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// `s` as one argument of an sftp batch file.
+fn sftp_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Workstation folder of `target`'s files: the pinned known_hosts and the staged install.
+async fn workstation_dir(target: &RemoteTarget) -> Result<PathBuf> {
+    let host: String = target
+        .host
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let dir = crate::utils::path_resolver::resolve_log_folder()?
+        .join("remote")
+        .join(format!("{}_{}", host, target.port()));
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// A key the server offered to `ssh-keyscan`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostKey {
+    pub key_type: String,
+    pub fingerprint: String,
+    #[serde(skip)]
+    known_hosts_line: String,
+}
+
+/// Keys of `ssh-keyscan` output with their SHA256 fingerprints.
+fn parse_keyscan(stdout: &str) -> Vec<HostKey> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (_host, key_type, blob) = (fields.next()?, fields.next()?, fields.next()?);
+            let raw = base64::engine::general_purpose::STANDARD
+                .decode(blob)
+                .ok()?;
+            let digest = Sha256::digest(&raw);
+            Some(HostKey {
+                key_type: key_type.to_string(),
+                fingerprint: format!(
+                    "SHA256:{}",
+                    base64::engine::general_purpose::STANDARD_NO_PAD.encode(digest)
+                ),
+                known_hosts_line: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The host keys `target` offers.
+pub async fn scan_host_keys(target: &RemoteTarget) -> Result<Vec<HostKey>> {
+    let args = vec![
+        "-T".to_string(),
+        "10".to_string(),
+        "-p".to_string(),
+        target.port().to_string(),
+        target.host.trim().to_string(),
+    ];
+    let out = run_cmd_with_timeout("ssh-keyscan", &args, Duration::from_secs(30), "ssh_keyscan")
+        .await
+        .context("ssh-keyscan could not be run; install the OpenSSH client on this machine")?;
+    let keys = parse_keyscan(&out.stdout);
+    if keys.is_empty() {
+        anyhow::bail!(
+            "{} did not offer an SSH host key: {}",
+            target.label(),
+            out.stderr.trim()
+        );
+    }
+    Ok(keys)
+}
+
+/// What Check server found.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCheck {
+    pub host_keys: Vec<HostKey>,
+    /// The confirmed fingerprint is among `host_keys`.
+    pub trusted: bool,
+    /// Logged in, the server runs Linux and the login may use sudo.
+    pub ready: bool,
+    /// `uname -sr` of the server, once logged in.
+    pub os: Option<String>,
+    pub message: String,
+}
+
+/// List `target`'s host keys and, when the confirmed one is among them, try the login.
+pub async fn check(target: &RemoteTarget) -> Result<RemoteCheck> {
+    target.validate_login().map_err(anyhow::Error::msg)?;
+    let keys = scan_host_keys(target).await?;
+    let wanted = target.host_key_fingerprint.trim();
+    let mut report = RemoteCheck {
+        trusted: keys.iter().any(|k| k.fingerprint == wanted),
+        host_keys: keys.clone(),
+        ..Default::default()
+    };
+    if !report.trusted {
+        report.message = "Compare the fingerprints with `ssh-keygen -lf <key>.pub` for the keys in /etc/ssh on the server, then confirm the matching one.".to_string();
+        return Ok(report);
+    }
+    match RemoteSession::connect(target, &keys).await {
+        Ok(session) => {
+            report.message = format!("Ready to install on {} ({}).", target.label(), session.os);
+            report.os = Some(session.os);
+            report.ready = true;
+        }
+        Err(e) => report.message = format!("{:#}", e),
+    }
+    Ok(report)
+}
+
+/// A verified SSH login to the server of a remote install.
+pub struct RemoteSession {
+    target: RemoteTarget,
+    dir: PathBuf,
+    known_hosts: PathBuf,
+    /// `uname -sr` of the server.
+    pub os: String,
+    /// Prefix of commands that need root; empty when logged in as root.
+    sudo: &'static str,
+}
+
+impl RemoteSession {
+    /// Pin the confirmed host key, log in and check the server can take a Linux-native install.
+    pub async fn open(target: &RemoteTarget) -> Result<Self> {
+        target.validate().map_err(anyhow::Error::msg)?;
+        let keys = scan_host_keys(target).await?;
+        Self::connect(target, &keys).await
+    }
+
+    async fn connect(target: &RemoteTarget, keys: &[HostKey]) -> Result<Self> {
+        let wanted = target.host_key_fingerprint.trim();
+        let Some(key) = keys.iter().find(|k| k.fingerprint == wanted) else {
+            let offered: Vec<String> = keys
+                .iter()
+                .map(|k| format!("{} {}", k.key_type, k.fingerprint))
+                .collect();
+            anyhow::bail!(
+                "Host key verification failed: {} offers {}, not the confirmed {}",
+                target.label(),
+                offered.join(", "),
+                wanted
+            );
+        };
+        let dir = workstation_dir(target).await?;
+        let known_hosts = dir.join("known_hosts");
+        tokio::fs::write(&known_hosts, format!("{}\n", key.known_hosts_line))
+            .await
+            .with_context(|| format!("Failed to write {}", known_hosts.display()))?;
+
+        let mut session = Self {
+            target: target.clone(),
+            dir,
+            known_hosts,
+            os: String::new(),
+            sudo: "",
+        };
+        session.os = session
+            .run_checked("uname -sr", "ssh_login")
+            .await?
+            .trim()
+            .to_string();
+        if !session.os.starts_with("Linux") {
+            anyhow::bail!(
+                "{} runs '{}'; remote installs need a Linux server",
+                target.label(),
+                session.os
+            );
+        }
+        if session.run_checked("id -u", "ssh_id").await?.trim() != "0" {
+            session.sudo = "sudo -n ";
+            session
+                .run_checked("sudo -n true", "ssh_sudo_check")
+                .await
+                .context("The remote login must be root or have passwordless sudo")?;
+        }
+        info!(
+            "[PHASE: remote] [STEP: connect] Logged in (target={}, os={}, host_key={} {})",
+            target.label(),
+            session.os,
+            key.key_type,
+            key.fingerprint
+        );
+        Ok(session)
+    }
+
+    pub fn label(&self) -> String {
+        self.target.label()
+    }
+
+    /// Options shared by ssh and sftp: no prompts, only the pinned host key.
+    fn options(&self) -> Vec<String> {
+        let pinned = format!("\"{}\"", self.known_hosts.display());
+        let mut args: Vec<String> = [
+            "BatchMode=yes".to_string(),
+            "StrictHostKeyChecking=yes".to_string(),
+            format!("UserKnownHostsFile={}", pinned),
+            format!("GlobalKnownHostsFile={}", pinned),
+            "ConnectTimeout=15".to_string(),
+        ]
+        .into_iter()
+        .flat_map(|o| ["-o".to_string(), o])
+        .collect();
+        let identity = self.target.identity_file.trim();
+        if !identity.is_empty() {
+            args.extend(["-i".to_string(), identity.to_string()]);
+            args.extend(["-o".to_string(), "IdentitiesOnly=yes".to_string()]);
+        }
+        args
+    }
+
+    fn destination(&self) -> String {
+        format!("{}@{}", self.target.user.trim(), self.target.host.trim())
+    }
+
+    fn ssh_args(&self, command: &str) -> Vec<String> {
+        let mut args = self.options();
+        args.extend([
+            "-p".to_string(),
+            self.target.port().to_string(),
+            self.destination(),
+            command.to_string(),
+        ]);
+        args
+    }
+
+    /// Run `command` through the login shell on the server.
+    pub async fn run(&self, command: &str, operation: &str) -> Result<CommandOutput> {
+        run_cmd_with_timeout("ssh", &self.ssh_args(command), COMMAND_TIMEOUT, operation).await
+    }
+
+    /// [`Self::run`], failing unless the command exits 0; returns its stdout.
+    pub async fn run_checked(&self, command: &str, operation: &str) -> Result<String> {
+        let out = self.run(command, operation).await?;
+        if out.exit_code != Some(0) {
+            anyhow::bail!(
+                "{} failed on {} (exit_code={:?}): {}",
+                operation,
+                self.label(),
+                out.exit_code,
+                out.stderr.trim()
+            );
+        }
+        Ok(out.stdout)
+    }
+
+    /// Workstation folder the install is staged in; it stands for the binaries folder.
+    pub fn staging_root(&self) -> PathBuf {
+        self.dir.join("stage")
+    }
+
+    /// Empty the staging folder of an earlier run; the runtime key stays, so a re-install keeps the
+    /// key already on the server.
+    pub async fn reset_staging(&self) -> Result<PathBuf> {
+        let root = self.staging_root();
+        if let Ok(mut entries) = tokio::fs::read_dir(&root).await {
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.file_name() == "secrets" {
+                    continue;
+                }
+                if entry.file_type().await?.is_dir() {
+                    tokio::fs::remove_dir_all(entry.path()).await?;
+                } else {
+                    tokio::fs::remove_file(entry.path()).await?;
+                }
+            }
+        }
+        Ok(root)
+    }
+
+    /// Protector for the secrets of the runtime config: the key is staged with the binaries (and
+    /// so deployed), and recorded at its path on the server.
+    pub async fn runtime_protector(&self, layout: &InstallLayout) -> Result<SecretProtector> {
+        let staged = default_key_path(&self.staging_root());
+        let key = SecretProtector::with_backend(staged, KeyBackend::File)
+            .master_key()
+            .await?;
+        let on_server = unix_path(&default_key_path(&layout.binaries));
+        Ok(SecretProtector::with_key(
+            PathBuf::from(on_server),
+            KeyBackend::File,
+            key,
+        ))
+    }
+
+    /// Copy the files under `local_root` into `remote_root` (created as needed, owned by root);
+    /// top-level entries named in `skip` stay behind. `on_progress(done, total)` runs after each
+    /// sftp batch. Returns the number of files pushed.
+    pub async fn push_tree(
+        &self,
+        local_root: &Path,
+        remote_root: &str,
+        skip: &[&str],
+        on_progress: &mut (dyn FnMut(usize, usize) + Send),
+    ) -> Result<usize> {
+        let files: Vec<(PathBuf, String)> = collect_files_recursive(local_root)
+            .await?
+            .into_iter()
+            .filter_map(|f| {
+                let rel = unix_path(f.strip_prefix(local_root).ok()?);
+                let top = rel.split('/').next().unwrap_or_default();
+                (!skip.contains(&top)).then_some((f, rel))
+            })
+            .collect();
+        let upload = format!("/tmp/cadalytix-upload-{}", uuid::Uuid::new_v4().simple());
+        self.run_checked(
+            &format!("mkdir -m 700 {}", sh_quote(&upload)),
+            "ssh_upload_dir",
+        )
+        .await?;
+
+        let pushed = async {
+            let mut done = 0;
+            for batch in files.chunks(UPLOAD_BATCH) {
+                self.sftp_put(&upload, batch).await?;
+                done += batch.len();
+                on_progress(done, files.len());
+            }
+            let (s, src, dst) = (self.sudo, sh_quote(&upload), sh_quote(remote_root));
+            self.run_checked(
+                &format!("{s}mkdir -p {dst} && {s}cp -R {src}/. {dst}/"),
+                "ssh_deploy_copy",
+            )
+            .await?;
+            Ok::<_, anyhow::Error>(files.len())
+        }
+        .await;
+        if let Err(e) = self
+            .run(
+                &format!("rm -rf {}", sh_quote(&upload)),
+                "ssh_upload_cleanup",
+            )
+            .await
+        {
+            warn!(
+                "[PHASE: remote] [STEP: upload] Could not remove {} on {}: {}",
+                upload,
+                self.label(),
+                e
+            );
+        }
+        pushed
+    }
+
+    /// Upload `files` (local path, relative path) into `upload` with one sftp batch.
+    async fn sftp_put(&self, upload: &str, files: &[(PathBuf, String)]) -> Result<()> {
+        let mut dirs = BTreeSet::new();
+        for (_, rel) in files {
+            let mut parts: Vec<&str> = rel.split('/').collect();
+            parts.pop();
+            for depth in 1..=parts.len() {
+                dirs.insert(format!("{}/{}", upload, parts[..depth].join("/")));
+            }
+        }
+        // Sorted, so parents come first; `-` keeps going when a folder already exists.
+        let mut script: String = dirs
+            .iter()
+            .map(|d| format!("-mkdir {}\n", sftp_quote(d)))
+            .collect();
+        for (local, rel) in files {
+            script.push_str(&format!(
+                "put {} {}\n",
+                sftp_quote(&local.to_string_lossy()),
+                sftp_quote(&format!("{}/{}", upload, rel))
+            ));
+        }
+        let batch_file = self.dir.join("upload.sftp");
+        tokio::fs::write(&batch_file, script).await?;
+
+        let mut args = self.options();
+        args.extend([
+            "-P".to_string(),
+            self.target.port().to_string(),
+            "-b".to_string(),
+            batch_file.to_string_lossy().to_string(),
+            self.destination(),
+        ]);
+        let out = run_cmd_with_timeout("sftp", &args, UPLOAD_TIMEOUT, "sftp_put").await;
+        let _ = tokio::fs::remove_file(&batch_file).await;
+        let out = out?;
+        if out.exit_code != Some(0) {
+            anyhow::bail!(
+                "Upload to {} failed (exit_code={:?}): {}",
+                self.label(),
+                out.exit_code,
+                out.stderr.trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Push the staged install into `layout` on the server, then register and start its systemd
+    /// unit. `on_upload(done, total)` reports the file upload.
+    pub async fn deploy(
+        &self,
+        layout: &InstallLayout,
+        on_upload: &mut (dyn FnMut(usize, usize) + Send),
+    ) -> Result<()> {
+        let staged = self.staging_root();
+        let binaries = unix_path(&layout.binaries);
+        let exe = service::find_linux_service_exe(&staged)
+            .await
+            .context("Linux executable not found in the staged runtime payload")?;
+        let exe = format!("{}/{}", binaries, unix_path(exe.strip_prefix(&staged)?));
+
+        let pushed = self
+            .push_tree(&staged, &binaries, &[ARTIFACTS_DIR], on_upload)
+            .await;
+        audit::record_result(
+            AuditAction::FileDeploy,
+            &binaries,
+            serde_json::json!({ "host": self.label(), "files": pushed.as_ref().ok() }),
+            &pushed,
+        )
+        .await;
+        pushed?;
+
+        let s = self.sudo;
+        let folders: Vec<String> = layout
+            .entries()
+            .iter()
+            .map(|(_, p)| sh_quote(&unix_path(p)))
+            .collect();
+        let secrets = sh_quote(&format!("{}/secrets", binaries));
+        self.run_checked(
+            &format!(
+                "{s}mkdir -p {} && {s}chmod 755 {} && if [ -d {secrets} ]; then {s}chmod -R go-rwx {secrets}; fi",
+                folders.join(" "),
+                sh_quote(&exe),
+            ),
+            "ssh_prepare_layout",
+        )
+        .await?;
+
+        let name = service::linux_service_name();
+        let result = self.register_service(&name, &exe, &binaries).await;
+        audit::record_result(
+            AuditAction::ServiceRegister,
+            &name,
+            serde_json::json!({ "manager": "systemd", "host": self.label(), "execPath": exe }),
+            &result,
+        )
+        .await;
+        result
+    }
+
+    async fn register_service(&self, name: &str, exe: &str, workdir: &str) -> Result<()> {
+        let unit = service::build_systemd_unit_text(name, Path::new(exe), Path::new(workdir), None);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(unit);
+        let s = self.sudo;
+        let unit_path = sh_quote(&format!("/etc/systemd/system/{}.service", name));
+        let unit_name = sh_quote(name);
+        self.run_checked(
+            &format!(
+                "echo {encoded} | base64 -d | {s}tee {unit_path} >/dev/null && {s}systemctl daemon-reload && {s}systemctl enable {unit_name} && {s}systemctl restart {unit_name}"
+            ),
+            "ssh_systemd_register",
+        )
+        .await?;
+        let state = self
+            .run(
+                &format!("systemctl is-active {}", unit_name),
+                "ssh_systemd_verify",
+            )
+            .await?;
+        if state.stdout.trim() != "active" {
+            anyhow::bail!(
+                "Service {} is not running on {} ({}). Check logs there with: journalctl -u {}",
+                name,
+                self.label(),
+                state.stdout.trim(),
+                name
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Available KB and mount point from the last line of `df -Pk`.
+fn parse_df(stdout: &str) -> Option<(u64, String)> {
+    let fields: Vec<&str> = stdout.lines().last()?.split_whitespace().collect();
+    Some((fields.get(3)?.parse().ok()?, fields.get(5)?.to_string()))
+}
+
+/// [`crate::installation::space_projection::project_needs`] on the server: `df` on each path's
+/// nearest existing folder. Volumes that cannot be measured (no login yet, for example) are left
+/// unmeasured and so do not block.
+pub async fn project_needs(target: &RemoteTarget, needs: Vec<SpaceNeed>) -> Vec<VolumeProjection> {
+    let session = match RemoteSession::open(target).await {
+        Ok(session) => Some(session),
+        Err(e) => {
+            warn!(
+                "[PHASE: remote] [STEP: space] Free space on {} not measured: {:#}",
+                target.label(),
+                e
+            );
+            None
+        }
+    };
+    let mut volumes: Vec<(String, VolumeProjection)> = Vec::new();
+    for need in needs {
+        let path = unix_path(&need.path);
+        let measured = match &session {
+            Some(session) => session
+                .run_checked(
+                    &format!(
+                        "p={}; while [ ! -e \"$p\" ]; do p=$(dirname \"$p\"); done; df -Pk \"$p\"",
+                        sh_quote(&path)
+                    ),
+                    "ssh_df",
+                )
+                .await
+                .ok()
+                .and_then(|out| parse_df(&out)),
+            None => None,
+        };
+        let (free_bytes, key) = match measured {
+            Some((kb, mount)) => (Some(kb * 1024), mount),
+            None => (None, path),
+        };
+        if let Some((_, volume)) = volumes.iter_mut().find(|(k, _)| *k == key) {
+            volume.required_bytes += need.bytes;
+            volume.needs.push(need);
+            continue;
+        }
+        volumes.push((
+            key,
+            VolumeProjection {
+                path: need.path.clone(),
+                required_bytes: need.bytes,
+                needs: vec![need],
+                free_bytes,
+            },
+        ));
+    }
+    volumes.into_iter().map(|(_, v)| v).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyscan_fingerprints_and_ssh_arguments_pin_the_confirmed_key() {
+        let keys = parse_keyscan(
+            "# server:22 SSH-2.0-OpenSSH_9.6\n[srv]:2222 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDSMI/BYKNTvzKiE5j6uSPvyMJXYZTSagPFsq+lvMkfn\n",
+        );
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key_type, "ssh-ed25519");
+        assert_eq!(
+            keys[0].fingerprint,
+            "SHA256:2ow1eduED1IP0OTAYT9HGYG6x3nCrq56JJRNB95/Pd0"
+        );
+
+        let target = RemoteTarget {
+            host: "srv".to_string(),
+            port: Some(2222),
+            user: "deploy".to_string(),
+            identity_file: "/keys/id_ed25519".to_string(),
+            host_key_fingerprint: keys[0].fingerprint.clone(),
+        };
+        assert!(target.validate().is_ok());
+        assert!(RemoteTarget {
+            host: "-oProxyCommand=x".to_string(),
+            ..target.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(RemoteTarget {
+            host_key_fingerprint: String::new(),
+            ..target.clone()
+        }
+        .validate()
+        .is_err());
+
+        let session = RemoteSession {
+            target,
+            dir: PathBuf::from("/logs/remote/srv_2222"),
+            known_hosts: PathBuf::from("/logs/remote/srv_2222/known_hosts"),
+            os: String::new(),
+            sudo: "sudo -n ",
+        };
+        let args = session.ssh_args("uname -sr");
+        assert!(args.contains(&"StrictHostKeyChecking=yes".to_string()));
+        assert!(
+            args.contains(&"UserKnownHostsFile=\"/logs/remote/srv_2222/known_hosts\"".to_string())
+        );
+        assert_eq!(
+            args[args.len() - 5..],
+            [
+                "IdentitiesOnly=yes",
+                "-p",
+                "2222",
+                "deploy@srv",
+                "uname -sr"
+            ]
+        );
+        assert_eq!(sh_quote("it's"), "'it'\\''s'");
+        assert_eq!(
+            parse_df("Filesystem 1024-blocks Used Available Capacity Mounted on\n/dev/sda1 100 40 60 40% /opt\n"),
+            Some((60, "/opt".to_string()))
+        );
+    }
+}
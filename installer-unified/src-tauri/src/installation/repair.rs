@@ -0,0 +1,507 @@
+// Repair of an existing install (`--repair`).
+//
+// Reads the install manifest (the given install folder or manifest file, else the last one
+// recorded in the log folder) and converges the machine back to it:
+// - files: deployed files that are missing or no longer match their recorded checksum are copied
+//   again from this installer's runtime payload. Only a payload file with the recorded checksum is
+//   used, so a repair never swaps in another version; files that are not part of the payload
+//   (generated config, log-folder artifacts) are reported, not rewritten.
+// - services: the systemd unit (Linux) or the Windows services are registered again when they are
+//   gone and started when they are stopped; in Docker mode the containers are brought back up.
+// - grants: the application account's runtime grants on the config database are re-applied when
+//   any are missing (needs CADALYTIX_CONFIG_DB_CONNECTION with the admin credentials).
+//
+// The report lists exactly what was fixed and what still needs attention. The archive schedule
+// is left alone: the installer only writes placeholder units for it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::api::preflight_report::CONFIG_DB_CONNECTION_ENV;
+use crate::database::app_account;
+use crate::database::platform_db::PlatformDbAdapter;
+use crate::security::audit::{self, AuditAction};
+use crate::security::secret_protector::{default_key_path, SecretProtector};
+
+use super::payload_manifest::{self, DriftedFile};
+use super::{docker, files, manifest, service};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairItem {
+    /// "files" | "services" | "grants"
+    pub area: &'static str,
+    pub target: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub generated_utc: String,
+    pub manifest_path: String,
+    pub install_mode: String,
+    /// What the repair changed.
+    pub fixed: Vec<RepairItem>,
+    /// Problems found that the repair could not fix.
+    pub unresolved: Vec<RepairItem>,
+    /// Checks that did not run, and why.
+    pub skipped: Vec<RepairItem>,
+}
+
+impl RepairReport {
+    fn new(manifest_path: &Path, install_mode: &str) -> Self {
+        Self {
+            generated_utc: Utc::now().to_rfc3339(),
+            manifest_path: manifest_path.display().to_string(),
+            install_mode: install_mode.to_string(),
+            fixed: Vec::new(),
+            unresolved: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    fn item(
+        area: &'static str,
+        target: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> RepairItem {
+        RepairItem {
+            area,
+            target: target.into(),
+            detail: detail.into(),
+        }
+    }
+
+    fn fixed(&mut self, area: &'static str, target: impl Into<String>, detail: impl Into<String>) {
+        self.fixed.push(Self::item(area, target, detail));
+    }
+
+    fn unresolved(
+        &mut self,
+        area: &'static str,
+        target: impl Into<String>,
+        detail: impl Into<String>,
+    ) {
+        self.unresolved.push(Self::item(area, target, detail));
+    }
+
+    fn skipped(
+        &mut self,
+        area: &'static str,
+        target: impl Into<String>,
+        detail: impl Into<String>,
+    ) {
+        self.skipped.push(Self::item(area, target, detail));
+    }
+
+    /// Plain-text report: what was fixed, what was not, what was not checked.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "CADalytix install repair ({})\nManifest: {}\n\n",
+            self.generated_utc, self.manifest_path
+        );
+        for (label, items) in [
+            ("FIXED", &self.fixed),
+            ("FAILED", &self.unresolved),
+            ("SKIPPED", &self.skipped),
+        ] {
+            for i in items {
+                out.push_str(&format!(
+                    "{:<8} {:<9} {}: {}\n",
+                    label, i.area, i.target, i.detail
+                ));
+            }
+        }
+        if self.fixed.is_empty() && self.unresolved.is_empty() {
+            out.push_str("Nothing to repair.\n");
+        } else {
+            out.push_str(&format!(
+                "\nRepaired {} item(s); {} still need attention.\n",
+                self.fixed.len(),
+                self.unresolved.len()
+            ));
+        }
+        out
+    }
+
+    /// 0 when nothing is left broken, 2 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.unresolved.is_empty() {
+            0
+        } else {
+            2
+        }
+    }
+}
+
+/// Folder the manifest's relative file paths, the service executables and the compose file live
+/// in: the binaries folder of the layout, else the destination folder.
+fn binaries_folder(manifest: &serde_json::Value) -> PathBuf {
+    let field = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+    };
+    field(manifest.pointer("/layout/binaries"))
+        .or_else(|| field(manifest.get("destinationFolder")))
+        .unwrap_or_default()
+}
+
+/// Copy `file` again from the first of `roots` holding it with the recorded checksum. `Ok(None)`
+/// when no payload file matches (or the file is not part of the payload at all).
+async fn recopy(file: &DriftedFile, roots: &[&Path]) -> Result<Option<PathBuf>> {
+    if Path::new(&file.path).is_absolute() {
+        return Ok(None);
+    }
+    for root in roots {
+        let src = root.join(&file.path);
+        if !tokio::fs::try_exists(&src).await.unwrap_or(false)
+            || !files::sha256_file(&src)
+                .await?
+                .eq_ignore_ascii_case(file.sha256.trim())
+        {
+            continue;
+        }
+        if let Some(parent) = file.deployed.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        files::copy_file_with_retries_and_sha256(&src, &file.deployed, "repair_copy").await?;
+        payload_manifest::verify_deployed(&file.sha256, &file.deployed).await?;
+        return Ok(Some(src));
+    }
+    Ok(None)
+}
+
+/// Restore the drifted files of `manifest_path` from `roots` (platform payload first).
+async fn restore_files(manifest_path: &Path, roots: &[&Path], report: &mut RepairReport) {
+    let drift = match payload_manifest::drifted_files(manifest_path).await {
+        Ok(drift) => drift,
+        Err(e) => {
+            report.unresolved("files", manifest_path.display().to_string(), e.to_string());
+            return;
+        }
+    };
+    for file in drift {
+        match recopy(&file, roots).await {
+            Ok(Some(src)) => report.fixed(
+                "files",
+                &file.path,
+                format!(
+                    "{} from {}",
+                    if file.missing { "Restored" } else { "Replaced" },
+                    src.display()
+                ),
+            ),
+            Ok(None) => report.unresolved(
+                "files",
+                &file.path,
+                format!(
+                    "{}; no file with the recorded checksum in this installer's runtime payload",
+                    if file.missing { "Missing" } else { "Changed" }
+                ),
+            ),
+            Err(e) => report.unresolved("files", &file.path, format!("Copy failed: {}", e)),
+        }
+    }
+}
+
+async fn repair_files(manifest_path: &Path, install_mode: &str, report: &mut RepairReport) {
+    let (shared, platform) =
+        match crate::api::installer::resolve_runtime_payload_roots(install_mode).await {
+            Ok(roots) => roots,
+            Err(e) => {
+                report.skipped("files", "runtime payload", e.to_string());
+                return;
+            }
+        };
+    let before = (report.fixed.len(), report.unresolved.len());
+    restore_files(manifest_path, &[&platform, &shared], report).await;
+    let restored = report.fixed.len() - before.0;
+    let failed = report.unresolved.len() - before.1;
+    if restored > 0 {
+        let outcome: Result<(), String> = if failed == 0 {
+            Ok(())
+        } else {
+            Err(format!("{} file(s) could not be restored", failed))
+        };
+        audit::record_result(
+            AuditAction::FileDeploy,
+            &manifest_path.display().to_string(),
+            serde_json::json!({ "repair": true, "restored": restored, "failed": failed }),
+            &outcome,
+        )
+        .await;
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn repair_linux_service(dest_root: &Path, report: &mut RepairReport) {
+    let name = service::linux_service_name();
+    let registered = Path::new(&format!("/etc/systemd/system/{}.service", name)).is_file();
+    if registered
+        && matches!(service::get_linux_service_status(&name).await, Ok(s) if s.active_state == "active")
+    {
+        return;
+    }
+    let Some(exe) = service::find_linux_service_exe(dest_root).await else {
+        report.unresolved(
+            "services",
+            name,
+            format!(
+                "Unit is not running and no service executable was found in {}",
+                dest_root.display()
+            ),
+        );
+        return;
+    };
+    let result = async {
+        super::linux::set_executable_permissions(&exe).await?;
+        service::install_and_start_linux_service(&name, &exe, dest_root, None).await
+    }
+    .await;
+    match result {
+        Ok(()) if registered => report.fixed("services", name, "Unit rewritten and restarted"),
+        Ok(()) => report.fixed("services", name, "Unit registered again and started"),
+        Err(e) => report.unresolved("services", name, e.to_string()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn repair_linux_service(_dest_root: &Path, report: &mut RepairReport) {
+    report.skipped(
+        "services",
+        service::linux_service_name(),
+        "systemd units can only be repaired on Linux",
+    );
+}
+
+#[cfg(windows)]
+async fn repair_windows_services(dest_root: &Path, report: &mut RepairReport) {
+    let specs = std::iter::once(&service::WINDOWS_MAIN_SERVICE)
+        .chain(service::WINDOWS_BACKGROUND_SERVICES.iter());
+    for spec in specs {
+        let name = spec.service_name();
+        let Some(exe) = service::find_windows_service_exe(spec, dest_root).await else {
+            // Background components are optional parts of the payload.
+            if spec.name == service::WINDOWS_MAIN_SERVICE.name {
+                report.unresolved(
+                    "services",
+                    &name,
+                    format!("No service executable found in {}", dest_root.display()),
+                );
+            }
+            continue;
+        };
+        match service::windows_service_state(&name).await {
+            Ok(Some(state)) if state == "RUNNING" => {}
+            Ok(Some(state)) => match service::start_windows_service(&name).await {
+                Ok(()) => report.fixed("services", &name, format!("Started (was {})", state)),
+                Err(e) => report.unresolved("services", &name, e.to_string()),
+            },
+            Ok(None) => match service::install_and_start_windows_service(spec, &exe).await {
+                Ok(()) => report.fixed("services", &name, "Registered again and started"),
+                Err(e) => report.unresolved("services", &name, e.to_string()),
+            },
+            Err(e) => report.unresolved(
+                "services",
+                &name,
+                format!("Service state could not be read: {}", e),
+            ),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+async fn repair_windows_services(_dest_root: &Path, report: &mut RepairReport) {
+    report.skipped(
+        "services",
+        service::WINDOWS_MAIN_SERVICE.service_name(),
+        "Windows services can only be repaired on Windows",
+    );
+}
+
+async fn repair_containers(dest_root: &Path, report: &mut RepairReport) {
+    let compose_file = dest_root.join("docker-compose.yml");
+    let result = async {
+        let inv = docker::detect_compose_invocation().await?;
+        let out = docker::compose_ps(inv, &compose_file).await?;
+        let status = docker::parse_compose_ps_output(&out.stdout);
+        if out.exit_code == Some(0) && status.container_count > 0 && status.all_running {
+            return Ok(false);
+        }
+        docker::compose_up(inv, &compose_file).await?;
+        anyhow::Ok(true)
+    }
+    .await;
+    let target = compose_file.display().to_string();
+    match result {
+        Ok(false) => {}
+        Ok(true) => report.fixed("services", target, "Containers started again"),
+        Err(e) => report.unresolved("services", target, e.to_string()),
+    }
+}
+
+async fn repair_grants(report: &mut RepairReport) {
+    let Some(conn_str) = std::env::var(CONFIG_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    else {
+        report.skipped(
+            "grants",
+            "config database",
+            format!(
+                "Set {} (admin credentials) to check the application account's grants",
+                CONFIG_DB_CONNECTION_ENV
+            ),
+        );
+        return;
+    };
+    let result = async {
+        let engine = crate::api::installer::guess_engine(&conn_str);
+        let conn = crate::api::installer::connect_with_retry(engine, conn_str).await?;
+        let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+        let secrets = Arc::new(SecretProtector::new(default_key_path(&log_dir)));
+        let login = PlatformDbAdapter::new(conn.clone(), secrets)
+            .get_setting("ConfigDb:AppLogin")
+            .await?
+            .filter(|l| !l.trim().is_empty());
+        let Some(login) = login else {
+            return anyhow::Ok(None);
+        };
+        let missing = app_account::missing_grants(&conn, &login).await?;
+        if missing.is_empty() {
+            return Ok(Some((login, Vec::new(), Vec::new())));
+        }
+        app_account::grant_runtime_access(&conn, &login).await?;
+        let still_missing = app_account::missing_grants(&conn, &login).await?;
+        Ok(Some((login, missing, still_missing)))
+    }
+    .await;
+    match result {
+        Ok(None) => report.skipped(
+            "grants",
+            "application account",
+            "No dedicated application account; the runtime uses the install credentials",
+        ),
+        Ok(Some((login, missing, still_missing))) => {
+            for grant in missing.iter().filter(|g| !still_missing.contains(g)) {
+                report.fixed("grants", &login, format!("Granted {}", grant));
+            }
+            for grant in still_missing {
+                report.unresolved("grants", &login, format!("Still missing {}", grant));
+            }
+        }
+        Err(e) => report.unresolved("grants", "config database", e.to_string()),
+    }
+}
+
+/// `--repair[=<install folder or manifest>]`.
+pub async fn repair_cli(args: &[String]) -> Result<RepairReport> {
+    let arg = args.iter().find_map(|a| a.strip_prefix("--repair="));
+    let manifest_path = manifest::manifest_path(arg)?;
+    let install = manifest::read_manifest(&manifest_path)?;
+    let install_mode = install
+        .get("installMode")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let dest_root = binaries_folder(&install);
+    info!(
+        "[PHASE: repair] [STEP: start] Repairing install (manifest={:?}, mode={}, binaries={:?})",
+        manifest_path, install_mode, dest_root
+    );
+
+    let mut report = RepairReport::new(&manifest_path, &install_mode);
+    // Files first, so re-registered services start from intact executables.
+    repair_files(&manifest_path, &install_mode, &mut report).await;
+    match install_mode.as_str() {
+        "docker" => repair_containers(&dest_root, &mut report).await,
+        "linux" => repair_linux_service(&dest_root, &mut report).await,
+        "windows" => repair_windows_services(&dest_root, &mut report).await,
+        other => report.skipped(
+            "services",
+            other.to_string(),
+            "Unknown install mode; services not checked",
+        ),
+    }
+    repair_grants(&mut report).await;
+
+    for i in &report.fixed {
+        info!(
+            "[PHASE: repair] [STEP: fixed] {} {}: {}",
+            i.area, i.target, i.detail
+        );
+    }
+    for i in &report.unresolved {
+        warn!(
+            "[PHASE: repair] [STEP: unresolved] {} {}: {}",
+            i.area, i.target, i.detail
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::crypto::sha256_hex;
+
+    #[tokio::test]
+    async fn drifted_files_are_restored_only_from_matching_payload_files() {
+        let root = std::env::temp_dir().join(format!("repair-{}", uuid::Uuid::new_v4()));
+        let dest = root.join("install");
+        let platform = root.join("runtime").join("linux");
+        let shared = root.join("runtime").join("shared");
+        for dir in [&dest, &platform.join("bin"), &shared] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(platform.join("bin").join("cadalytix-server"), b"server").unwrap();
+        std::fs::write(shared.join("app.dll"), b"app v2").unwrap();
+        std::fs::write(dest.join("app.dll"), b"tampered").unwrap();
+        std::fs::write(dest.join("appsettings.json"), b"{}").unwrap();
+
+        let manifest_path = dest.join("install-manifest.json");
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "installMode": "linux",
+            "destinationFolder": dest.display().to_string(),
+            "layout": { "binaries": dest.display().to_string() },
+            "files": [
+                { "path": "app.dll", "sha256": sha256_hex(b"app v1") },
+                { "path": "appsettings.json", "sha256": sha256_hex(b"{}") },
+                { "path": "bin/cadalytix-server", "sha256": sha256_hex(b"server") },
+            ]
+        });
+        std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+        assert_eq!(binaries_folder(&manifest), dest);
+
+        let mut report = RepairReport::new(&manifest_path, "linux");
+        restore_files(&manifest_path, &[&platform, &shared], &mut report).await;
+
+        assert_eq!(report.fixed.len(), 1, "{:?}", report.fixed);
+        assert_eq!(report.fixed[0].target, "bin/cadalytix-server");
+        assert!(report.fixed[0].detail.starts_with("Restored from"));
+        assert_eq!(
+            std::fs::read(dest.join("bin").join("cadalytix-server")).unwrap(),
+            b"server"
+        );
+        // The payload holds another version of app.dll: reported, not swapped in.
+        assert_eq!(report.unresolved.len(), 1);
+        assert_eq!(report.unresolved[0].target, "app.dll");
+        assert_eq!(std::fs::read(dest.join("app.dll")).unwrap(), b"tampered");
+        assert_eq!(report.exit_code(), 2);
+        assert!(report
+            .to_text()
+            .contains("Repaired 1 item(s); 1 still need attention."));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,464 @@
+//! Rollback journal for a cancelled install.
+//!
+//! `run_installation` records what it creates as it goes (the Create NEW database, migrations
+//! applied to an existing database, the application login, folders that did not exist yet, files
+//! that were not there before the copy) and, when the run is cancelled, undoes exactly that. Migrations are reverted
+//! newest first with their down scripts; a migration without one stops the revert there. A folder that did not exist before the run holds only what the run put
+//! there, so it is removed with its contents. Files that already existed and were overwritten are
+//! left as they are; there is no backup of the previous contents to restore.
+//!
+//! A run interrupted by a signal keeps the work of its finished steps for the checkpoint and
+//! undoes only the step in progress ([`InstallRollback::current_step`]).
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+
+use crate::database::connection::DatabaseConnection;
+use crate::database::migrations::{MigrationEntry, MigrationRunner};
+use crate::database::provisioning;
+
+#[derive(Debug)]
+struct CreatedDatabase {
+    engine: String,
+    /// Maintenance (master/postgres) connection used to create it; never logged.
+    admin_conn_str: String,
+    db_name: String,
+    /// Tablespace created for it (PostgreSQL, custom path); dropped after the database.
+    tablespace: Option<String>,
+}
+
+/// Application login created by this run (not one that existed and was re-keyed).
+#[derive(Debug)]
+struct CreatedLogin {
+    engine: String,
+    /// Connection to the config database the login was created from; never logged.
+    conn_str: String,
+    login: String,
+}
+
+#[derive(Debug)]
+struct AppliedMigrations {
+    engine: String,
+    engine_version: String,
+    /// Existing config DB connection; never logged.
+    conn_str: String,
+    /// In the order they were applied.
+    entries: Vec<MigrationEntry>,
+}
+
+/// Journal lengths when the current step began.
+#[derive(Debug, Default, Clone, Copy)]
+struct StepMark {
+    database: bool,
+    app_login: bool,
+    migrations: usize,
+    dirs: usize,
+    files: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct InstallRollback {
+    created_database: Option<CreatedDatabase>,
+    created_login: Option<CreatedLogin>,
+    applied_migrations: Option<AppliedMigrations>,
+    created_dirs: Vec<PathBuf>,
+    created_files: Vec<PathBuf>,
+    step_start: StepMark,
+}
+
+impl InstallRollback {
+    pub fn record_database(
+        &mut self,
+        engine: &str,
+        admin_conn_str: &str,
+        db_name: &str,
+        tablespace: Option<&str>,
+    ) {
+        self.created_database = Some(CreatedDatabase {
+            engine: engine.to_string(),
+            admin_conn_str: admin_conn_str.to_string(),
+            db_name: db_name.to_string(),
+            tablespace: tablespace.map(str::to_string),
+        });
+    }
+
+    /// Record the application login this run created.
+    pub fn record_app_login(&mut self, engine: &str, conn_str: &str, login: &str) {
+        self.created_login = Some(CreatedLogin {
+            engine: engine.to_string(),
+            conn_str: conn_str.to_string(),
+            login: login.to_string(),
+        });
+    }
+
+    /// Record a migration this run applied to an existing database; call after it committed.
+    pub fn record_migration(
+        &mut self,
+        engine: &str,
+        engine_version: &str,
+        conn_str: &str,
+        entry: &MigrationEntry,
+    ) {
+        self.applied_migrations
+            .get_or_insert_with(|| AppliedMigrations {
+                engine: engine.to_string(),
+                engine_version: engine_version.to_string(),
+                conn_str: conn_str.to_string(),
+                entries: Vec::new(),
+            })
+            .entries
+            .push(entry.clone());
+    }
+
+    /// Record `dir` and any missing ancestors; call before creating it.
+    pub async fn note_dir(&mut self, dir: &Path) {
+        let mut cur = Some(dir);
+        while let Some(p) = cur {
+            if p.as_os_str().is_empty() || tokio::fs::try_exists(p).await.unwrap_or(true) {
+                break;
+            }
+            if !self.created_dirs.iter().any(|d| d == p) {
+                self.created_dirs.push(p.to_path_buf());
+            }
+            cur = p.parent();
+        }
+    }
+
+    /// Record `path` if it does not exist yet; call before writing it.
+    pub async fn note_file(&mut self, path: &Path) {
+        if !tokio::fs::try_exists(path).await.unwrap_or(true) {
+            self.created_files.push(path.to_path_buf());
+        }
+    }
+
+    /// A new install step begins; what is recorded from now on belongs to it.
+    pub fn begin_step(&mut self) {
+        self.step_start = StepMark {
+            database: self.created_database.is_some(),
+            app_login: self.created_login.is_some(),
+            migrations: self
+                .applied_migrations
+                .as_ref()
+                .map_or(0, |a| a.entries.len()),
+            dirs: self.created_dirs.len(),
+            files: self.created_files.len(),
+        };
+    }
+
+    /// Only what the current step recorded.
+    pub fn current_step(mut self) -> Self {
+        let mark = self.step_start;
+        if mark.database {
+            self.created_database = None;
+        }
+        if mark.app_login {
+            self.created_login = None;
+        }
+        if let Some(applied) = self.applied_migrations.as_mut() {
+            applied.entries.drain(..mark.migrations);
+        }
+        if self
+            .applied_migrations
+            .as_ref()
+            .is_some_and(|a| a.entries.is_empty())
+        {
+            self.applied_migrations = None;
+        }
+        self.created_dirs.drain(..mark.dirs);
+        self.created_files.drain(..mark.files);
+        self.step_start = StepMark::default();
+        self
+    }
+
+    /// Undo everything recorded. Best-effort: failures are logged and reported in the summary.
+    pub async fn run(self) -> String {
+        info!(
+            "[PHASE: install] [STEP: rollback] rollback entered (files={}, dirs={}, database={})",
+            self.created_files.len(),
+            self.created_dirs.len(),
+            self.created_database.is_some()
+        );
+        let mut removed_files = 0usize;
+        let mut problems: Vec<String> = Vec::new();
+
+        for f in &self.created_files {
+            match tokio::fs::remove_file(f).await {
+                Ok(()) => removed_files += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    warn!(
+                        "[PHASE: install] [STEP: rollback] Failed to remove file (path={:?}): {}",
+                        f, e
+                    );
+                    problems.push(format!("file {:?}", f));
+                }
+            }
+        }
+
+        // Outermost first; folders nested in one already removed are then simply gone.
+        let mut dirs = self.created_dirs.clone();
+        dirs.sort_by_key(|d| d.components().count());
+        let mut removed_dirs = 0usize;
+        for d in &dirs {
+            match tokio::fs::remove_dir_all(d).await {
+                Ok(()) => removed_dirs += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    warn!(
+                        "[PHASE: install] [STEP: rollback] Failed to remove folder (path={:?}): {}",
+                        d, e
+                    );
+                    problems.push(format!("folder {:?}", d));
+                }
+            }
+        }
+
+        if let Some(login) = &self.created_login {
+            if let Err(e) = drop_login(login).await {
+                warn!(
+                    "[PHASE: install] [STEP: rollback] Failed to drop application login (login={}): {:?}",
+                    login.login, e
+                );
+                problems.push(format!("login '{}'", login.login));
+            }
+        }
+
+        let mut reverted_migrations = 0usize;
+        if let Some(applied) = &self.applied_migrations {
+            let (reverted, problem) = revert_migrations(applied).await;
+            reverted_migrations = reverted;
+            problems.extend(problem);
+        }
+
+        let mut dropped_db: Option<String> = None;
+        if let Some(db) = &self.created_database {
+            match drop_database(db).await {
+                Ok(()) => dropped_db = Some(db.db_name.clone()),
+                Err(e) => {
+                    warn!(
+                        "[PHASE: install] [STEP: rollback] Failed to drop database (db_name={}): {:?}",
+                        db.db_name, e
+                    );
+                    problems.push(format!("database '{}'", db.db_name));
+                }
+            }
+        }
+
+        let summary = rollback_summary(
+            removed_files,
+            removed_dirs,
+            reverted_migrations,
+            dropped_db.as_deref(),
+            &problems,
+        );
+        info!(
+            "[PHASE: install] [STEP: rollback] rollback exit ({})",
+            summary
+        );
+        summary
+    }
+}
+
+/// Revert `applied` newest first; returns how many were reverted and what could not be.
+async fn revert_migrations(applied: &AppliedMigrations) -> (usize, Option<String>) {
+    let runner = async {
+        let conn = crate::api::installer::connect_with_retry(
+            applied.engine.clone(),
+            applied.conn_str.clone(),
+        )
+        .await?;
+        let (manifest_path, migrations_path) = crate::api::installer::resolve_migrations_paths()?;
+        MigrationRunner::new(
+            conn,
+            manifest_path,
+            migrations_path,
+            applied.engine.clone(),
+            applied.engine_version.clone(),
+        )
+        .await
+    }
+    .await;
+    let runner = match runner {
+        Ok(r) => r,
+        Err(e) => {
+            warn!(
+                "[PHASE: install] [STEP: rollback] Failed to reach the database to revert migrations: {:?}",
+                e
+            );
+            return (0, Some(format!("{} migration(s)", applied.entries.len())));
+        }
+    };
+
+    let mut reverted = 0usize;
+    for entry in applied.entries.iter().rev() {
+        if let Err(e) = runner.revert_migration(entry).await {
+            warn!(
+                "[PHASE: install] [STEP: rollback] Failed to revert migration (name={}): {:?}",
+                entry.name, e
+            );
+            let left = applied.entries.len() - reverted;
+            return (
+                reverted,
+                Some(format!("{} migration(s) starting at {}", left, entry.name)),
+            );
+        }
+        reverted += 1;
+    }
+    (reverted, None)
+}
+
+async fn drop_login(login: &CreatedLogin) -> anyhow::Result<()> {
+    let conn =
+        crate::api::installer::connect_with_retry(login.engine.clone(), login.conn_str.clone())
+            .await?;
+    crate::database::app_account::drop_account(&conn, &login.engine, &login.login).await?;
+    info!(
+        "[PHASE: install] [STEP: rollback] Dropped application login created by this run (login={})",
+        login.login
+    );
+    Ok(())
+}
+
+async fn drop_database(db: &CreatedDatabase) -> anyhow::Result<()> {
+    // Release pooled connections into the new database before dropping it.
+    crate::database::pool::close_all().await;
+    let conn =
+        crate::api::installer::connect_with_retry(db.engine.clone(), db.admin_conn_str.clone())
+            .await?;
+    match &conn {
+        DatabaseConnection::Postgres(pool) => {
+            sqlx::query(&provisioning::postgres_drop_db_stmt(&db.db_name))
+                .execute(pool)
+                .await?;
+            if db.tablespace.is_some() {
+                sqlx::query(&provisioning::postgres_drop_tablespace_stmt(&db.db_name))
+                    .execute(pool)
+                    .await?;
+            }
+        }
+        DatabaseConnection::SqlServer(_) => {
+            let client_arc = conn
+                .as_sql_server()
+                .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+            let mut client = client_arc.lock().await;
+            client
+                .simple_query(provisioning::sql_server_drop_db_stmt(&db.db_name))
+                .await?
+                .into_results()
+                .await?;
+        }
+    }
+    info!(
+        "[PHASE: install] [STEP: rollback] Dropped database created by this run (db_name={})",
+        db.db_name
+    );
+    Ok(())
+}
+
+fn rollback_summary(
+    removed_files: usize,
+    removed_dirs: usize,
+    reverted_migrations: usize,
+    dropped_db: Option<&str>,
+    problems: &[String],
+) -> String {
+    let mut done: Vec<String> = Vec::new();
+    if removed_files > 0 {
+        done.push(format!("removed {} file(s)", removed_files));
+    }
+    if removed_dirs > 0 {
+        done.push(format!("removed {} folder(s)", removed_dirs));
+    }
+    if reverted_migrations > 0 {
+        done.push(format!("reverted {} migration(s)", reverted_migrations));
+    }
+    if let Some(name) = dropped_db {
+        done.push(format!("dropped database '{}'", name));
+    }
+    let mut out = if done.is_empty() {
+        "Nothing needed to be rolled back.".to_string()
+    } else {
+        format!("Rolled back: {}.", done.join(", "))
+    };
+    if !problems.is_empty() {
+        out.push_str(&format!(
+            " Could not roll back: {}. Remove these manually.",
+            problems.join(", ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_removes_only_what_this_install_created() {
+        let tmp = tempfile::tempdir().unwrap();
+        let existing = tmp.path().join("existing.txt");
+        std::fs::write(&existing, b"keep").unwrap();
+
+        let dest = tmp.path().join("app");
+        let nested = dest.join("bin");
+        let mut rb = InstallRollback::default();
+        rb.note_dir(&nested).await;
+        std::fs::create_dir_all(&nested).unwrap();
+        let new_file = nested.join("server");
+        rb.note_file(&new_file).await;
+        rb.note_file(&existing).await;
+        std::fs::write(&new_file, b"x").unwrap();
+        // Not recorded, but inside a folder this install created.
+        std::fs::write(nested.join("appsettings.json"), b"{}").unwrap();
+
+        let summary = rb.run().await;
+        assert_eq!(
+            summary,
+            "Rolled back: removed 1 file(s), removed 1 folder(s)."
+        );
+        assert!(!dest.exists());
+        assert!(existing.exists());
+    }
+
+    #[tokio::test]
+    async fn current_step_keeps_earlier_steps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let earlier = tmp.path().join("earlier");
+        let current = tmp.path().join("current");
+        let mut rb = InstallRollback::default();
+        rb.record_database("postgres", "Host=db", "cadalytix", None);
+        rb.note_dir(&earlier).await;
+        std::fs::create_dir_all(&earlier).unwrap();
+        rb.begin_step();
+        rb.note_dir(&current).await;
+        std::fs::create_dir_all(&current).unwrap();
+
+        let step = rb.current_step();
+        assert!(step.created_database.is_none());
+        assert_eq!(step.created_dirs, vec![current.clone()]);
+        assert_eq!(step.run().await, "Rolled back: removed 1 folder(s).");
+        assert!(!current.exists());
+        assert!(earlier.exists());
+    }
+
+    #[test]
+    fn summary_reports_leftovers() {
+        assert_eq!(
+            rollback_summary(0, 0, 0, None, &[]),
+            "Nothing needed to be rolled back."
+        );
+        assert_eq!(
+            rollback_summary(0, 0, 0, None, &["database 'cadalytix'".to_string()]),
+            "Nothing needed to be rolled back. Could not roll back: database 'cadalytix'. Remove these manually."
+        );
+        assert_eq!(
+            rollback_summary(3, 0, 0, Some("cadalytix"), &[]),
+            "Rolled back: removed 3 file(s), dropped database 'cadalytix'."
+        );
+        assert_eq!(
+            rollback_summary(0, 0, 2, None, &["1 migration(s) starting at 010".to_string()]),
+            "Rolled back: reverted 2 migration(s). Could not roll back: 1 migration(s) starting at 010. Remove these manually."
+        );
+    }
+}
@@ -9,11 +9,56 @@ use std::time::Instant;
 use tokio::time::Duration;
 
 use crate::installation::run_cmd_with_timeout;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::security::audit::{self, AuditAction};
 
 /// Default service name for CADalytix.
-#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
 pub const SERVICE_NAME: &str = "cadalytix";
 
+/// systemd unit name of the selected instance (`cadalytix`, `cadalytix-test`, ...).
+pub fn linux_service_name() -> String {
+    crate::utils::instance::qualify(SERVICE_NAME, "-")
+}
+
+/// A Windows service registered by the installer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowsServiceSpec {
+    pub name: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    /// Executable names looked up in the destination folder and its `bin` folder.
+    pub exe_names: &'static [&'static str],
+}
+
+/// The main CADalytix service.
+pub const WINDOWS_MAIN_SERVICE: WindowsServiceSpec = WindowsServiceSpec {
+    name: "CADalytix",
+    display_name: "CADalytix",
+    description: "CADalytix web and API host.",
+    exe_names: &["Cadalytix.Service.exe", "Cadalytix.Web.exe"],
+};
+
+/// Background components deployed next to the main service. Each is registered only when
+/// its executable is part of the runtime payload.
+pub const WINDOWS_BACKGROUND_SERVICES: &[WindowsServiceSpec] = &[
+    WindowsServiceSpec {
+        name: "CADalytixArchiveRunner",
+        display_name: "CADalytix Archive Runner",
+        description: "Moves call data past the hot retention window into archive files.",
+        exe_names: &["Cadalytix.ArchiveRunner.exe"],
+    },
+    WindowsServiceSpec {
+        name: "CADalytixIngestionAgent",
+        display_name: "CADalytix Ingestion Agent",
+        description: "Reads call data from the configured source into the CADalytix database.",
+        exe_names: &["Cadalytix.IngestionAgent.exe"],
+    },
+];
+
+/// How long a service may sit in a pending state before verification fails.
+#[cfg_attr(not(windows), allow(dead_code))]
+const WINDOWS_SERVICE_STATE_TIMEOUT: Duration = Duration::from_secs(30);
+
 // ============================================================================
 // Systemd unit file generation (pure function, testable on all platforms)
 // ============================================================================
@@ -190,13 +235,35 @@ pub async fn get_linux_service_status(service_name: &str) -> Result<ServiceStatu
 /// 3. Runs systemctl daemon-reload, enable, and restart
 /// 4. Verifies the service is running
 ///
-/// Requires root or passwordless sudo.
+/// Requires root or passwordless sudo. The outcome is recorded in the audit log.
 #[cfg(target_os = "linux")]
 pub async fn install_and_start_linux_service(
     service_name: &str,
     exec_path: &Path,
     working_dir: &Path,
     user: Option<&str>,
+) -> Result<()> {
+    let result = register_linux_service(service_name, exec_path, working_dir, user).await;
+    audit::record_result(
+        AuditAction::ServiceRegister,
+        service_name,
+        serde_json::json!({
+            "manager": "systemd",
+            "execPath": exec_path.to_string_lossy(),
+            "user": user,
+        }),
+        &result,
+    )
+    .await;
+    result
+}
+
+#[cfg(target_os = "linux")]
+async fn register_linux_service(
+    service_name: &str,
+    exec_path: &Path,
+    working_dir: &Path,
+    user: Option<&str>,
 ) -> Result<()> {
     use crate::installation::linux::{is_running_as_root, require_root_or_passwordless_sudo};
 
@@ -438,7 +505,7 @@ pub async fn write_linux_systemd_service_unit(
     );
 
     tokio::fs::create_dir_all(artifacts_dir).await?;
-    let path = artifacts_dir.join("cadalytix.service");
+    let path = artifacts_dir.join(format!("{}.service", service_name));
     let exec_str = exec_path
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("Invalid exec path"))?;
@@ -473,20 +540,77 @@ WantedBy=multi-user.target
     Ok(path)
 }
 
+// ============================================================================
+// Windows service management (sc.exe)
+// ============================================================================
+
+/// `sc.exe create` arguments for `spec`. sc.exe wants a space after each `key=`, so keys and
+/// values are separate arguments.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn build_sc_create_args(spec: &WindowsServiceSpec, exe_path: &Path) -> Vec<String> {
+    vec![
+        "create".to_string(),
+        spec.service_name(),
+        "binPath=".to_string(),
+        format!("\"{}\"", exe_path.to_string_lossy()),
+        "start=".to_string(),
+        "auto".to_string(),
+        "DisplayName=".to_string(),
+        format!("\"{}\"", spec.display()),
+    ]
+}
+
+/// The `STATE` of `sc.exe query` output ("RUNNING", "STOPPED", "START_PENDING", ...).
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn parse_sc_query_state(stdout: &str) -> Option<String> {
+    stdout.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("STATE") {
+            return None;
+        }
+        value
+            .split_whitespace()
+            .last()
+            .map(|s| s.to_ascii_uppercase())
+    })
+}
+
 /// Install/start and verify a Windows service using `sc.exe`.
 ///
+/// The service restarts on failure; start is verified by waiting for `RUNNING`.
 /// This requires elevated permissions. Caller should handle/report failures cleanly.
+/// The outcome is recorded in the audit log.
+#[cfg(windows)]
+pub async fn install_and_start_windows_service(
+    spec: &WindowsServiceSpec,
+    exe_path: &Path,
+) -> Result<()> {
+    let result = register_windows_service(spec, exe_path).await;
+    audit::record_result(
+        AuditAction::ServiceRegister,
+        &spec.service_name(),
+        serde_json::json!({
+            "manager": "sc.exe",
+            "execPath": exe_path.to_string_lossy(),
+        }),
+        &result,
+    )
+    .await;
+    result
+}
+
 #[cfg(windows)]
-pub async fn install_and_start_windows_service(service_name: &str, exe_path: &Path) -> Result<()> {
+async fn register_windows_service(spec: &WindowsServiceSpec, exe_path: &Path) -> Result<()> {
+    let service_name = spec.service_name();
     let started = Instant::now();
     debug!(
         "[PHASE: installation] [STEP: service] install_and_start_windows_service entered (service_name={}, exe_path={:?})",
         service_name, exe_path
     );
 
-    let exe_str = exe_path
-        .to_str()
-        .ok_or_else(|| anyhow::anyhow!("Invalid exe path"))?;
+    if exe_path.to_str().is_none() {
+        anyhow::bail!("Invalid exe path");
+    }
 
     // Best-effort stop/delete (ignore failures if service doesn't exist).
     let _ = run_cmd_with_timeout(
@@ -504,17 +628,7 @@ pub async fn install_and_start_windows_service(service_name: &str, exe_path: &Pa
     )
     .await;
 
-    // Create service (note sc.exe requires a space after '=' so we pass `binPath=` then a value arg).
-    let create_args = vec![
-        "create".to_string(),
-        service_name.to_string(),
-        "binPath=".to_string(),
-        format!("\"{}\"", exe_str),
-        "start=".to_string(),
-        "auto".to_string(),
-        "DisplayName=".to_string(),
-        "\"CADalytix\"".to_string(),
-    ];
+    let create_args = build_sc_create_args(spec, exe_path);
     let out =
         run_cmd_with_timeout("sc.exe", &create_args, Duration::from_secs(30), "sc_create").await?;
     if out.exit_code != Some(0) {
@@ -528,28 +642,49 @@ pub async fn install_and_start_windows_service(service_name: &str, exe_path: &Pa
         );
     }
 
-    let out = run_cmd_with_timeout(
+    // Description and restart-on-failure are cosmetic / resilience settings: warn only.
+    if !spec.description.is_empty() {
+        let args = vec![
+            "description".to_string(),
+            service_name.to_string(),
+            spec.description.to_string(),
+        ];
+        if let Ok(out) =
+            run_cmd_with_timeout("sc.exe", &args, Duration::from_secs(20), "sc_description").await
+        {
+            if out.exit_code != Some(0) {
+                warn!(
+                    "[PHASE: installation] [STEP: service] sc.exe description failed (service_name={}, exit_code={:?})",
+                    service_name, out.exit_code
+                );
+            }
+        }
+    }
+    let failure_args = vec![
+        "failure".to_string(),
+        service_name.to_string(),
+        "reset=".to_string(),
+        "86400".to_string(),
+        "actions=".to_string(),
+        "restart/5000/restart/5000/restart/30000".to_string(),
+    ];
+    if let Ok(out) = run_cmd_with_timeout(
         "sc.exe",
-        &["start".to_string(), service_name.to_string()],
-        Duration::from_secs(30),
-        "sc_start",
+        &failure_args,
+        Duration::from_secs(20),
+        "sc_failure",
     )
-    .await?;
-    if out.exit_code != Some(0) {
-        warn!(
-            "[PHASE: installation] [STEP: service] sc.exe start failed (exit_code={:?}) stderr={}",
-            out.exit_code, out.stderr
-        );
-        anyhow::bail!(
-            "Windows service start failed (exit_code={:?})",
-            out.exit_code
-        );
+    .await
+    {
+        if out.exit_code != Some(0) {
+            warn!(
+                "[PHASE: installation] [STEP: service] sc.exe failure failed (service_name={}, exit_code={:?})",
+                service_name, out.exit_code
+            );
+        }
     }
 
-    let running = is_windows_service_running(service_name).await?;
-    if !running {
-        anyhow::bail!("Windows service is not running after start");
-    }
+    start_windows_service(&service_name).await?;
 
     info!(
         "[PHASE: installation] [STEP: service] install_and_start_windows_service exit ok (service_name={}, duration_ms={})",
@@ -559,6 +694,7 @@ pub async fn install_and_start_windows_service(service_name: &str, exe_path: &Pa
     Ok(())
 }
 
+/// Whether `sc.exe query` reports the service as `RUNNING`.
 #[cfg(windows)]
 pub async fn is_windows_service_running(service_name: &str) -> Result<bool> {
     let started = Instant::now();
@@ -567,6 +703,28 @@ pub async fn is_windows_service_running(service_name: &str) -> Result<bool> {
         service_name
     );
 
+    let state = match windows_service_state(service_name).await {
+        Ok(state) => state,
+        Err(e) => {
+            warn!(
+                "[PHASE: installation] [STEP: service] sc.exe query failed (service_name={}): {}",
+                service_name, e
+            );
+            return Ok(false);
+        }
+    };
+    let running = state.as_deref() == Some("RUNNING");
+    debug!(
+        "[PHASE: installation] [STEP: service] is_windows_service_running exit (running={}, duration_ms={})",
+        running,
+        started.elapsed().as_millis()
+    );
+    Ok(running)
+}
+
+/// Current `sc.exe query` state of a service; `None` when it is not installed.
+#[cfg(windows)]
+pub async fn windows_service_state(service_name: &str) -> Result<Option<String>> {
     let out = run_cmd_with_timeout(
         "sc.exe",
         &["query".to_string(), service_name.to_string()],
@@ -574,23 +732,137 @@ pub async fn is_windows_service_running(service_name: &str) -> Result<bool> {
         "sc_query",
     )
     .await?;
-
-    // `sc query` returns non-zero if service doesn't exist.
+    // 1060: ERROR_SERVICE_DOES_NOT_EXIST
+    if out.exit_code == Some(1060) {
+        return Ok(None);
+    }
     if out.exit_code != Some(0) {
+        anyhow::bail!(
+            "sc.exe query {} failed (exit_code={:?})",
+            service_name,
+            out.exit_code
+        );
+    }
+    Ok(Some(parse_sc_query_state(&out.stdout).unwrap_or_default()))
+}
+
+/// Poll until the service reports `want`, failing after [`WINDOWS_SERVICE_STATE_TIMEOUT`].
+#[cfg(windows)]
+async fn wait_for_windows_service_state(service_name: &str, want: &str) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        let state = windows_service_state(service_name).await?;
+        if state.as_deref() == Some(want) {
+            return Ok(());
+        }
+        if started.elapsed() >= WINDOWS_SERVICE_STATE_TIMEOUT {
+            anyhow::bail!(
+                "Windows service '{}' did not reach {} within {}s (state={})",
+                service_name,
+                want,
+                WINDOWS_SERVICE_STATE_TIMEOUT.as_secs(),
+                state.as_deref().unwrap_or("not installed")
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Start a service and wait for `RUNNING`. Starting a running service is not an error.
+#[cfg(windows)]
+pub async fn start_windows_service(service_name: &str) -> Result<()> {
+    let out = run_cmd_with_timeout(
+        "sc.exe",
+        &["start".to_string(), service_name.to_string()],
+        Duration::from_secs(30),
+        "sc_start",
+    )
+    .await?;
+    // 1056: ERROR_SERVICE_ALREADY_RUNNING
+    if out.exit_code != Some(0) && out.exit_code != Some(1056) {
         warn!(
-            "[PHASE: installation] [STEP: service] sc.exe query failed (exit_code={:?}) stderr={}",
-            out.exit_code, out.stderr
+            "[PHASE: installation] [STEP: service] sc.exe start failed (service_name={}, exit_code={:?}) stderr={}",
+            service_name, out.exit_code, out.stderr
+        );
+        anyhow::bail!(
+            "Windows service '{}' start failed (exit_code={:?})",
+            service_name,
+            out.exit_code
         );
-        return Ok(false);
     }
+    wait_for_windows_service_state(service_name, "RUNNING").await
+}
 
-    let running = out.stdout.to_ascii_uppercase().contains("RUNNING");
-    debug!(
-        "[PHASE: installation] [STEP: service] is_windows_service_running exit (running={}, duration_ms={})",
-        running,
-        started.elapsed().as_millis()
-    );
-    Ok(running)
+/// Stop a service and wait for `STOPPED`. Stopping a stopped service is not an error.
+#[cfg(windows)]
+pub async fn stop_windows_service(service_name: &str) -> Result<()> {
+    let out = run_cmd_with_timeout(
+        "sc.exe",
+        &["stop".to_string(), service_name.to_string()],
+        Duration::from_secs(30),
+        "sc_stop",
+    )
+    .await?;
+    // 1062: ERROR_SERVICE_NOT_ACTIVE
+    if out.exit_code != Some(0) && out.exit_code != Some(1062) {
+        warn!(
+            "[PHASE: installation] [STEP: service] sc.exe stop failed (service_name={}, exit_code={:?}) stderr={}",
+            service_name, out.exit_code, out.stderr
+        );
+        anyhow::bail!(
+            "Windows service '{}' stop failed (exit_code={:?})",
+            service_name,
+            out.exit_code
+        );
+    }
+    wait_for_windows_service_state(service_name, "STOPPED").await
+}
+
+/// First executable of `spec` found in `dest_root` or `dest_root\bin`.
+pub async fn find_windows_service_exe(
+    spec: &WindowsServiceSpec,
+    dest_root: &Path,
+) -> Option<PathBuf> {
+    for name in spec.exe_names {
+        for candidate in [dest_root.join(name), dest_root.join("bin").join(name)] {
+            if tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Main executable of a Linux-native install in `dest_root` or `dest_root/bin`.
+pub async fn find_linux_service_exe(dest_root: &Path) -> Option<PathBuf> {
+    for name in ["cadalytix-server", "cadalytix", "CADalytix.Server"] {
+        for candidate in [dest_root.join(name), dest_root.join("bin").join(name)] {
+            if tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Register and start each [`WINDOWS_BACKGROUND_SERVICES`] entry whose executable was
+/// deployed. Returns the names of the services started.
+#[cfg(windows)]
+pub async fn install_windows_background_services(dest_root: &Path) -> Result<Vec<String>> {
+    let mut installed = Vec::new();
+    for spec in WINDOWS_BACKGROUND_SERVICES {
+        let Some(exe) = find_windows_service_exe(spec, dest_root).await else {
+            info!(
+                "[PHASE: installation] [STEP: service] {} not in the runtime payload; service '{}' not registered",
+                spec.exe_names.join(" / "),
+                spec.service_name()
+            );
+            continue;
+        };
+        install_and_start_windows_service(spec, &exe).await?;
+        installed.push(spec.service_name());
+    }
+    Ok(installed)
 }
 
 // ============================================================================
@@ -681,6 +953,26 @@ mod tests {
         assert_eq!(quoted, "\"/opt/my\\\"app/bin\"");
     }
 
+    #[test]
+    fn sc_create_args_and_query_state_parse() {
+        let spec = &WINDOWS_BACKGROUND_SERVICES[0];
+        let args = build_sc_create_args(
+            spec,
+            Path::new(r"C:\Program Files\CADalytix\Cadalytix.ArchiveRunner.exe"),
+        );
+        assert_eq!(args[1], "CADalytixArchiveRunner");
+        assert_eq!(args[2], "binPath=");
+        assert_eq!(
+            args[3],
+            "\"C:\\Program Files\\CADalytix\\Cadalytix.ArchiveRunner.exe\""
+        );
+        assert_eq!(args[7], "\"CADalytix Archive Runner\"");
+
+        let out = "\r\nSERVICE_NAME: CADalytixArchiveRunner\r\n        TYPE               : 10  WIN32_OWN_PROCESS\r\n        STATE              : 2  START_PENDING\r\n                                (NOT_STOPPABLE, NOT_PAUSABLE, IGNORES_SHUTDOWN)\r\n";
+        assert_eq!(parse_sc_query_state(out).as_deref(), Some("START_PENDING"));
+        assert_eq!(parse_sc_query_state("SERVICE_NAME: x"), None);
+    }
+
     #[test]
     fn build_systemd_unit_text_has_required_sections() {
         let exec_path = PathBuf::from("/bin/test");
@@ -0,0 +1,135 @@
+//! Termination signals (SIGINT, SIGTERM, SIGHUP; Ctrl+C on Windows) for the modes that run
+//! installs or own the terminal.
+//!
+//! One listener thread per process. A signal during an install interrupts it like Cancel, except
+//! that `run_installation` keeps the checkpoint and rolls back only the step in progress; the run
+//! fails with `cancel::Interrupted` (E9002), and once the front end has reported that,
+//! [`exit_if_interrupted`] ends the process with exit code 92 so orchestrators can tell a kill
+//! from a failure. A signal outside an install, or a second one while the first is being handled,
+//! restores the terminal and exits at once with 128 + the signal number, as the default handler
+//! would without leaving the shell in raw mode.
+
+use std::sync::Once;
+
+use log::{info, warn};
+
+use super::cancel;
+
+static LISTEN: Once = Once::new();
+
+/// Start the listener thread (once; later calls are no-ops).
+pub fn listen() {
+    LISTEN.call_once(|| {
+        let spawned = std::thread::Builder::new()
+            .name("signals".to_string())
+            .spawn(|| {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build();
+                let result = match rt {
+                    Ok(rt) => rt.block_on(handle_signals()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    warn!(
+                        "[PHASE: signals] [STEP: listen] Signal handlers not installed: {:?}",
+                        e
+                    );
+                }
+            });
+        if let Err(e) = spawned {
+            warn!(
+                "[PHASE: signals] [STEP: listen] Signal handlers not installed: {:?}",
+                e
+            );
+        }
+    });
+}
+
+async fn handle_signals() -> std::io::Result<()> {
+    let mut signals = Signals::new()?;
+    let mut interrupting = false;
+    loop {
+        let (name, number) = signals.recv().await;
+        if !interrupting && cancel::request_interrupt(name) {
+            interrupting = true;
+            warn!(
+                "[PHASE: signals] [STEP: interrupt] {} received; stopping the install after rolling back the current step (send it again to exit now)",
+                name
+            );
+            continue;
+        }
+        exit_now(name, 128 + number);
+    }
+}
+
+fn exit_now(name: &str, code: i32) -> ! {
+    crate::utils::crash::restore_terminal();
+    warn!(
+        "[PHASE: signals] [STEP: exit] {} received; exiting (code {})",
+        name, code
+    );
+    log::logger().flush();
+    eprintln!("Installer stopped by {}.", name);
+    std::process::exit(code);
+}
+
+/// Exit with the install's exit code when `err` is an install stopped by a signal; call after
+/// the failure has been reported.
+pub fn exit_if_interrupted(err: &anyhow::Error) {
+    let Some(signal) = cancel::interrupted_by(err) else {
+        return;
+    };
+    let code = crate::error::exit_code(err);
+    crate::utils::crash::restore_terminal();
+    info!(
+        "[PHASE: signals] [STEP: exit] Install interrupted by {}; exiting (code {})",
+        signal, code
+    );
+    log::logger().flush();
+    eprintln!("{}", crate::error::user_message(err));
+    std::process::exit(code);
+}
+
+#[cfg(unix)]
+struct Signals {
+    interrupt: tokio::signal::unix::Signal,
+    terminate: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+            hangup: signal(SignalKind::hangup())?,
+        })
+    }
+
+    /// Next signal: (name, number).
+    async fn recv(&mut self) -> (&'static str, i32) {
+        tokio::select! {
+            _ = self.interrupt.recv() => ("SIGINT", 2),
+            _ = self.terminate.recv() => ("SIGTERM", 15),
+            _ = self.hangup.recv() => ("SIGHUP", 1),
+        }
+    }
+}
+
+#[cfg(windows)]
+struct Signals(tokio::signal::windows::CtrlC);
+
+#[cfg(windows)]
+impl Signals {
+    fn new() -> std::io::Result<Self> {
+        tokio::signal::windows::ctrl_c().map(Self)
+    }
+
+    async fn recv(&mut self) -> (&'static str, i32) {
+        self.0.recv().await;
+        ("Ctrl+C", 2)
+    }
+}
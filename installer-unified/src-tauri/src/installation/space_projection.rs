@@ -0,0 +1,314 @@
+//! Disk space projection for an install, checked on the Ready page and again before deploy.
+//!
+//! Three needs are projected from the install request: the runtime payload (measured from the
+//! `runtime/` folders, or a fixed estimate when they cannot be read) on the binaries folder, the
+//! new database's initial size on its data folder (create-new installs only), and headroom for
+//! staging the first archive months on a local archive destination. Needs on the same volume are
+//! added up and compared with that volume's free space; a volume that is known to be short blocks
+//! the install with a message naming the volume, the shortfall and what it is for. A volume whose
+//! free space cannot be measured is reported but does not block. Remote installs are measured on
+//! their server (`installation::remote`).
+
+use std::path::{Path, PathBuf};
+
+use crate::api::backfill::format_bytes;
+use crate::api::installer::StartInstallRequest;
+use crate::installation::layout;
+
+const MIB: u64 = 1024 * 1024;
+const GIB: u64 = 1024 * MIB;
+
+/// Runtime payload size assumed when the payload folders cannot be measured.
+pub const DEFAULT_PAYLOAD_BYTES: u64 = 2 * GIB;
+/// Initial size of a new database when no SQL Server sizing was given.
+pub const DEFAULT_DB_INITIAL_BYTES: u64 = GIB;
+/// Room kept for staging archive months, never more than the archive cap.
+pub const ARCHIVE_HEADROOM_BYTES: u64 = GIB;
+
+/// Space one part of the install needs at `path`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceNeed {
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// Needs that land on one volume and that volume's free space.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeProjection {
+    /// Path of the first need on the volume (used to name it).
+    pub path: PathBuf,
+    pub needs: Vec<SpaceNeed>,
+    pub required_bytes: u64,
+    /// `None` when the volume could not be measured.
+    pub free_bytes: Option<u64>,
+}
+
+impl VolumeProjection {
+    /// Known to be too small; an unmeasured volume does not block.
+    pub fn is_short(&self) -> bool {
+        self.free_bytes
+            .is_some_and(|free| free < self.required_bytes)
+    }
+
+    /// "runtime files 1.2 GB, database 1.1 GB"
+    fn breakdown(&self) -> String {
+        self.needs
+            .iter()
+            .map(|n| format!("{} {}", n.label, format_bytes(n.bytes)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// One line for the Ready page.
+    pub fn summary(&self) -> String {
+        let free = match self.free_bytes {
+            Some(free) => format!("{} free", format_bytes(free)),
+            None => "free space unknown".to_string(),
+        };
+        format!(
+            "Disk space on the volume of {}: {} needed ({}), {}",
+            self.path.display(),
+            format_bytes(self.required_bytes),
+            self.breakdown(),
+            free
+        )
+    }
+
+    /// Why this volume blocks the install (`None` when it does not).
+    pub fn shortfall_message(&self) -> Option<String> {
+        let free = self.free_bytes.filter(|_| self.is_short())?;
+        Some(format!(
+            "Not enough disk space on the volume of {}: {} needed ({}) but only {} free. Free up {} or choose another folder.",
+            self.path.display(),
+            format_bytes(self.required_bytes),
+            self.breakdown(),
+            format_bytes(free),
+            format_bytes(self.required_bytes - free)
+        ))
+    }
+}
+
+/// Projection for a whole install.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceProjection {
+    pub volumes: Vec<VolumeProjection>,
+    /// The runtime payload could not be measured and `DEFAULT_PAYLOAD_BYTES` was used.
+    pub payload_estimated: bool,
+    /// Every short volume's message, joined; empty when the install fits.
+    pub shortfall: String,
+}
+
+impl SpaceProjection {
+    pub fn is_short(&self) -> bool {
+        !self.shortfall.is_empty()
+    }
+}
+
+/// What the install in `req` needs where, for a runtime payload of `payload_bytes`.
+pub fn needs(req: &StartInstallRequest, payload_bytes: u64) -> Vec<SpaceNeed> {
+    let layout = layout::resolve(req);
+    let mut needs = vec![SpaceNeed {
+        label: "runtime files",
+        path: layout.binaries.clone(),
+        bytes: payload_bytes,
+    }];
+
+    let db = &req.db_setup;
+    if db.mode.trim().eq_ignore_ascii_case("create_new") {
+        let path = if db.new_location.trim().eq_ignore_ascii_case("specific_path")
+            && !db.new_specific_path.trim().is_empty()
+        {
+            PathBuf::from(db.new_specific_path.trim())
+        } else {
+            layout.data.clone()
+        };
+        let sized_mb = db.sql_server_sizing.as_ref().map_or(0, |s| {
+            u64::from(s.initial_data_size_mb) + u64::from(s.initial_log_size_mb)
+        });
+        needs.push(SpaceNeed {
+            label: "database",
+            path,
+            bytes: match sized_mb {
+                0 => DEFAULT_DB_INITIAL_BYTES,
+                mb => mb * MIB,
+            },
+        });
+    }
+
+    let archive = &req.archive_policy;
+    if archive.enabled && archive.max_usage_gb > 0 {
+        if let Ok(crate::archiver::ArchiveDestination::Local(dir)) =
+            crate::archiver::ArchiveDestination::parse(&archive.destination_path)
+        {
+            needs.push(SpaceNeed {
+                label: "archive headroom",
+                path: dir,
+                bytes: ARCHIVE_HEADROOM_BYTES.min(u64::from(archive.max_usage_gb) * GIB),
+            });
+        }
+    }
+    needs.retain(|n| n.bytes > 0);
+    needs
+}
+
+/// Total size of the runtime payload for `install_mode` (`None` when it cannot be read).
+pub async fn payload_bytes(install_mode: &str) -> Option<u64> {
+    let (shared, platform) = crate::api::installer::resolve_runtime_payload_roots(install_mode)
+        .await
+        .ok()?;
+    let mut total = 0u64;
+    for root in [shared, platform] {
+        if !tokio::fs::try_exists(&root).await.unwrap_or(false) {
+            continue;
+        }
+        for file in crate::installation::files::collect_files_recursive(&root)
+            .await
+            .ok()?
+        {
+            total += tokio::fs::metadata(&file).await.map_or(0, |m| m.len());
+        }
+    }
+    (total > 0).then_some(total)
+}
+
+/// Identifies the volume holding `anchor` (an existing path).
+async fn volume_key(anchor: &Path) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(meta) = tokio::fs::metadata(anchor).await {
+            return format!("dev:{}", meta.dev());
+        }
+    }
+    match anchor.components().next() {
+        Some(std::path::Component::Prefix(prefix)) => {
+            prefix.as_os_str().to_string_lossy().to_ascii_uppercase()
+        }
+        _ => anchor.to_string_lossy().to_string(),
+    }
+}
+
+/// Add `needs` up per volume and measure each volume once.
+pub async fn project_needs(needs: Vec<SpaceNeed>) -> Vec<VolumeProjection> {
+    let mut volumes: Vec<(String, VolumeProjection)> = Vec::new();
+    for need in needs {
+        let anchor = crate::utils::disk::nearest_existing_ancestor(&need.path)
+            .await
+            .unwrap_or_else(|| need.path.clone());
+        let key = volume_key(&anchor).await;
+        if let Some((_, volume)) = volumes.iter_mut().find(|(k, _)| *k == key) {
+            volume.required_bytes += need.bytes;
+            volume.needs.push(need);
+            continue;
+        }
+        let free_bytes =
+            crate::utils::disk::get_free_space_bytes_for_path(&anchor.to_string_lossy())
+                .await
+                .ok();
+        volumes.push((
+            key,
+            VolumeProjection {
+                path: need.path.clone(),
+                required_bytes: need.bytes,
+                needs: vec![need],
+                free_bytes,
+            },
+        ));
+    }
+    volumes.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Project the install in `req` against the free space of every volume it writes to.
+pub async fn project(req: &StartInstallRequest) -> SpaceProjection {
+    let measured = payload_bytes(&req.install_mode).await;
+    let needs = needs(req, measured.unwrap_or(DEFAULT_PAYLOAD_BYTES));
+    let volumes = match &req.remote {
+        Some(target) => crate::installation::remote::project_needs(target, needs).await,
+        None => project_needs(needs).await,
+    };
+    let shortfall = volumes
+        .iter()
+        .filter_map(VolumeProjection::shortfall_message)
+        .collect::<Vec<_>>()
+        .join(" ");
+    SpaceProjection {
+        volumes,
+        payload_estimated: measured.is_none(),
+        shortfall,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_follow_the_request_and_short_volumes_explain_the_shortfall() {
+        let mut req: StartInstallRequest = serde_json::from_value(serde_json::json!({
+            "installMode": "linux",
+            "installationType": "custom",
+            "destinationFolder": "/opt/cadalytix",
+            "configDbConnectionString": "Host=db;Database=cadalytix",
+            "callDataConnectionString": "",
+            "sourceObjectName": "dbo.Calls",
+            "dbSetup": {
+                "mode": "create_new",
+                "newLocation": "this_machine",
+                "newSpecificPath": "",
+                "maxDbSizeGb": 50,
+                "existingHostedWhere": "",
+                "existingConnectMode": ""
+            },
+            "storage": {
+                "mode": "defaults",
+                "location": "system",
+                "customPath": "",
+                "retentionPolicy": "18",
+                "maxDiskGb": ""
+            },
+            "archivePolicy": {
+                "format": "zip+ndjson",
+                "destinationPath": "/srv/archive",
+                "maxUsageGb": 100,
+                "schedule": { "dayOfMonth": 1, "timeLocal": "02:00" },
+                "catchUpOnStartup": true
+            },
+            "mappings": {},
+            "mappingOverride": false
+        }))
+        .unwrap();
+        let found = needs(&req, 300 * MIB);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].path, PathBuf::from("/opt/cadalytix"));
+        assert_eq!(
+            found[1].path,
+            PathBuf::from("/opt/cadalytix/data/postgresql")
+        );
+        assert_eq!(found[1].bytes, DEFAULT_DB_INITIAL_BYTES);
+        assert_eq!(found[2].bytes, ARCHIVE_HEADROOM_BYTES);
+
+        req.archive_policy.destination_path = "s3://bucket/archive".to_string();
+        req.db_setup.mode = "existing".to_string();
+        assert_eq!(needs(&req, 300 * MIB).len(), 1);
+
+        let volume = VolumeProjection {
+            path: PathBuf::from("/opt/cadalytix"),
+            needs: found[..2].to_vec(),
+            required_bytes: 300 * MIB + DEFAULT_DB_INITIAL_BYTES,
+            free_bytes: Some(500 * MIB),
+        };
+        let msg = volume.shortfall_message().unwrap();
+        assert!(msg.contains("/opt/cadalytix"), "{}", msg);
+        assert!(msg.contains("runtime files 314.6 MB, database 1.1 GB"));
+        assert!(msg.contains("only 524.3 MB free"));
+        let unknown = VolumeProjection {
+            free_bytes: None,
+            ..volume
+        };
+        assert!(unknown.shortfall_message().is_none());
+    }
+}
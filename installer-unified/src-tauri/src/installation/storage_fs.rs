@@ -0,0 +1,295 @@
+//! Filesystem of the database storage location, with engine-specific guidance.
+//!
+//! The Storage page shows which filesystem the database folder lands on (the custom storage
+//! path, or the layout's data folder) and what to tune there: the allocation unit on NTFS/ReFS
+//! for SQL Server, copy-on-write on btrfs, the record size on ZFS. FAT32/exFAT (no journaling,
+//! no permissions) and memory-backed filesystems cannot hold a database; they block the Storage
+//! page and the install. Detection reads `/proc/self/mountinfo` on Linux and `Win32_Volume` on
+//! Windows; an undetected filesystem gets no guidance and blocks nothing.
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use tokio::time::Duration;
+
+use crate::api::installer::StartInstallRequest;
+use crate::installation::run_cmd_with_timeout;
+
+const SQL_SERVER_ALLOCATION_UNIT: u64 = 64 * 1024;
+
+/// The filesystem under the database storage folder and what it means for the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageFilesystem {
+    pub path: PathBuf,
+    /// As reported by the OS (`ext4`, `NTFS`, ...); empty when it could not be detected.
+    pub fs_type: String,
+    /// Allocation unit (cluster) size, where the OS reports one (Windows).
+    pub cluster_bytes: Option<u64>,
+    /// Why the database cannot be stored here.
+    pub blocked: Option<String>,
+    pub advice: Vec<String>,
+}
+
+impl StorageFilesystem {
+    /// One-line description for the Storage page and the logs.
+    pub fn summary(&self) -> String {
+        match (self.fs_type.as_str(), self.cluster_bytes) {
+            ("", _) => format!("Filesystem of {:?}: unknown", self.path),
+            (fs, Some(cluster)) => format!(
+                "Filesystem of {:?}: {} ({} KB allocation unit)",
+                self.path,
+                fs,
+                cluster / 1024
+            ),
+            (fs, None) => format!("Filesystem of {:?}: {}", self.path, fs),
+        }
+    }
+}
+
+/// Folder the database files go to: the custom storage path when one was chosen, otherwise the
+/// layout's data folder.
+pub fn storage_dir(req: &StartInstallRequest) -> PathBuf {
+    let custom = req.storage.custom_path.trim();
+    if req.storage.mode == "custom" && req.storage.location == "custom" && !custom.is_empty() {
+        return PathBuf::from(custom);
+    }
+    crate::installation::layout::resolve(req).data
+}
+
+/// Detect and assess the filesystem of the storage folder of `req` (nothing for a remote
+/// install, whose folders are on the server).
+pub async fn inspect(req: &StartInstallRequest) -> StorageFilesystem {
+    let path = storage_dir(req);
+    if req.remote.is_some() {
+        return StorageFilesystem {
+            path,
+            ..StorageFilesystem::default()
+        };
+    }
+    let (fs_type, cluster_bytes) = match detect(&path).await {
+        Some(found) => found,
+        None => {
+            warn!(
+                "[PHASE: installation] [STEP: storage_fs] Filesystem of {:?} not detected",
+                path
+            );
+            (String::new(), None)
+        }
+    };
+    let engine = crate::api::installer::guess_engine(&req.config_db_connection_string);
+    let (blocked, advice) = assess(&fs_type, cluster_bytes, &engine, &path);
+    let result = StorageFilesystem {
+        path,
+        fs_type,
+        cluster_bytes,
+        blocked,
+        advice,
+    };
+    info!(
+        "[PHASE: installation] [STEP: storage_fs] {} (blocked={})",
+        result.summary(),
+        result.blocked.is_some()
+    );
+    result
+}
+
+/// Whether `fs_type` can hold the database (`Some(reason)` when not) and what to tune for
+/// `engine` (`postgres` or `sqlserver`).
+pub fn assess(
+    fs_type: &str,
+    cluster_bytes: Option<u64>,
+    engine: &str,
+    dir: &Path,
+) -> (Option<String>, Vec<String>) {
+    let sql_server = engine == "sqlserver";
+    let mut advice = Vec::new();
+    let blocked = match fs_type.to_ascii_lowercase().as_str() {
+        "fat" | "fat32" | "vfat" | "msdos" => Some(format!(
+            "{} cannot hold the database: it has no journaling or file permissions and limits files to 4 GB. Choose a folder on an NTFS, ext4 or XFS volume.",
+            fs_type
+        )),
+        "exfat" => Some(
+            "exFAT cannot hold the database: it has no journaling or file permissions. Choose a folder on an NTFS, ext4 or XFS volume."
+                .to_string(),
+        ),
+        "tmpfs" | "ramfs" => Some(format!(
+            "{} is kept in memory and emptied on every reboot. Choose a folder on a disk volume.",
+            fs_type
+        )),
+        "ntfs" | "refs" if cfg!(windows) => {
+            match cluster_bytes {
+                Some(cluster) if sql_server && cluster != SQL_SERVER_ALLOCATION_UNIT => {
+                    advice.push(format!(
+                        "SQL Server performs best on a volume formatted with a 64 KB allocation unit (this one uses {} KB). Reformatting erases the volume: Format-Volume -AllocationUnitSize 65536.",
+                        cluster / 1024
+                    ));
+                }
+                _ => {}
+            }
+            advice.push(format!(
+                "Exclude {:?} from antivirus real-time scanning.",
+                dir
+            ));
+            None
+        }
+        "ntfs" | "ntfs3" | "fuseblk" => {
+            advice.push(
+                "NTFS mounted on Linux is slow and lacks the POSIX semantics the database expects; use an ext4 or XFS volume."
+                    .to_string(),
+            );
+            None
+        }
+        "btrfs" => {
+            advice.push(format!(
+                "btrfs copy-on-write fragments database files. Disable it for the data folder before any file is written: chattr +C {:?} (only files created afterwards are affected).",
+                dir
+            ));
+            None
+        }
+        "zfs" => {
+            advice.push(if sql_server {
+                "On ZFS, set recordsize=64K and atime=off on the dataset holding the data folder."
+                    .to_string()
+            } else {
+                "On ZFS, set recordsize=8K (the PostgreSQL page size) and atime=off on the dataset holding the data folder."
+                    .to_string()
+            });
+            None
+        }
+        "ext4" | "xfs" => {
+            advice.push(format!(
+                "{} is well suited; mounting it with noatime saves a write on every read.",
+                fs_type
+            ));
+            None
+        }
+        "nfs" | "nfs4" | "cifs" | "smb3" | "smbfs" => {
+            advice.push(format!(
+                "{} is a network filesystem; a dropped connection can corrupt the database. Use a local or SAN (block) volume.",
+                fs_type
+            ));
+            None
+        }
+        "overlay" => {
+            advice.push(
+                "This is a container's writable layer; mount a volume for the data folder so the database outlives the container."
+                    .to_string(),
+            );
+            None
+        }
+        _ => None,
+    };
+    (blocked, advice)
+}
+
+/// Filesystem type (and cluster size, on Windows) of the volume holding `path`.
+async fn detect(path: &Path) -> Option<(String, Option<u64>)> {
+    let anchor = crate::utils::disk::nearest_existing_ancestor(path).await?;
+    if cfg!(windows) {
+        detect_windows(&anchor).await
+    } else {
+        let anchor = tokio::fs::canonicalize(&anchor).await.ok()?;
+        let mountinfo = tokio::fs::read_to_string("/proc/self/mountinfo")
+            .await
+            .ok()?;
+        parse_mountinfo(&mountinfo, &anchor).map(|m| (m.fs_type, None))
+    }
+}
+
+async fn detect_windows(anchor: &Path) -> Option<(String, Option<u64>)> {
+    let text = anchor.to_string_lossy();
+    let drive = text.chars().next().filter(|c| c.is_ascii_alphabetic())?;
+    if text.chars().nth(1) != Some(':') {
+        return None;
+    }
+    let script = format!(
+        "$v = Get-CimInstance -ClassName Win32_Volume -Filter \"DriveLetter='{}:'\"; \"$($v.FileSystem)|$($v.BlockSize)\"",
+        drive.to_ascii_uppercase()
+    );
+    let out = run_cmd_with_timeout(
+        "powershell",
+        &[
+            "-NoProfile".to_string(),
+            "-NonInteractive".to_string(),
+            "-Command".to_string(),
+            script,
+        ],
+        Duration::from_secs(20),
+        "storage_fs_volume",
+    )
+    .await
+    .ok()?;
+    let (fs, block) = out.stdout.trim().split_once('|')?;
+    (!fs.is_empty()).then(|| (fs.to_string(), block.trim().parse().ok()))
+}
+
+/// A `/proc/self/mountinfo` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    /// `major:minor` of the backing device.
+    pub device: String,
+    pub fs_type: String,
+}
+
+/// The mount holding `path` (canonical), from `/proc/self/mountinfo`: the longest mount point
+/// that contains it wins.
+pub fn parse_mountinfo(text: &str, path: &Path) -> Option<Mount> {
+    let mut best: Option<(usize, Mount)> = None;
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let Some(sep) = fields.iter().position(|f| *f == "-") else {
+            continue;
+        };
+        let (Some(device), Some(mount_point), Some(fs_type)) =
+            (fields.get(2), fields.get(4), fields.get(sep + 1))
+        else {
+            continue;
+        };
+        // Spaces and similar are octal-escaped (`\040`).
+        let mount_point = mount_point.replace("\\040", " ").replace("\\011", "\t");
+        let depth = Path::new(&mount_point).components().count();
+        if path.starts_with(&mount_point) && !matches!(best, Some((d, _)) if d > depth) {
+            let mount = Mount {
+                device: device.to_string(),
+                fs_type: fs_type.to_string(),
+            };
+            best = Some((depth, mount));
+        }
+    }
+    best.map(|(_, mount)| mount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filesystems_get_engine_specific_guidance() {
+        let mountinfo = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+35 22 8:17 / /srv/db rw,noatime shared:5 - btrfs /dev/sdb1 rw,space_cache=v2
+36 22 8:33 / /media/usb\\040stick rw,relatime shared:6 - vfat /dev/sdc1 rw,fmask=0022
+37 22 0:31 / /srv/dbx rw shared:7 - xfs /dev/sdd1 rw
+";
+        let fs = |p: &str| parse_mountinfo(mountinfo, Path::new(p)).map(|m| m.fs_type);
+        assert_eq!(fs("/opt/cadalytix/data").as_deref(), Some("ext4"));
+        assert_eq!(fs("/srv/db/postgresql").as_deref(), Some("btrfs"));
+        assert_eq!(fs("/srv/dbx").as_deref(), Some("xfs"));
+        let mount = parse_mountinfo(mountinfo, Path::new("/srv/db")).unwrap();
+        assert_eq!(mount.device, "8:17");
+        assert_eq!(fs("/media/usb stick/cad").as_deref(), Some("vfat"));
+
+        let dir = Path::new("/srv/db/postgresql");
+        let (blocked, advice) = assess("btrfs", None, "postgres", dir);
+        assert!(blocked.is_none());
+        assert!(advice[0].contains("chattr +C \"/srv/db/postgresql\""));
+        assert!(assess("vfat", None, "postgres", dir)
+            .0
+            .unwrap()
+            .contains("4 GB"));
+        assert!(assess("exFAT", None, "sqlserver", dir).0.is_some());
+        assert!(assess("zfs", None, "sqlserver", dir).1[0].contains("recordsize=64K"));
+        assert_eq!(assess("", None, "postgres", dir), (None, Vec::new()));
+    }
+}
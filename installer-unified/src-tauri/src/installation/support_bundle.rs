@@ -0,0 +1,372 @@
+//! Support bundle post-processing: redaction and ZIP packaging.
+//!
+//! `create_support_bundle` copies logs (and optionally installer artifacts) into a staging
+//! folder. Before anything leaves the machine, every text file in it is scrubbed of residual
+//! hostnames, IP addresses, usernames and e-mail addresses, then the folder is packed into one
+//! ZIP with a manifest (per-file SHA-256 + redaction counts) and a `.sha256` sidecar.
+//!
+//! Extra patterns come from `support_redaction.json` next to the installer executable (or the
+//! file named by `CADALYTIX_REDACTION_PATTERNS`):
+//! `{ "patterns": [ { "name": "site", "pattern": "(?i)county-\\w+", "replacement": "[SITE]" } ] }`
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+
+use super::files::{collect_files_recursive, sha256_file};
+
+const PATTERNS_FILE_NAME: &str = "support_redaction.json";
+const PATTERNS_PATH_ENV: &str = "CADALYTIX_REDACTION_PATTERNS";
+pub const MANIFEST_FILE_NAME: &str = "support_bundle_manifest.json";
+
+/// One scrub rule: every match of `pattern` is replaced (`$1`-style groups allowed).
+#[derive(Debug, Clone)]
+pub struct RedactionRule {
+    name: String,
+    pattern: Regex,
+    replacement: String,
+}
+
+impl RedactionRule {
+    fn new(name: &str, pattern: &str, replacement: &str) -> Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            pattern: Regex::new(pattern)
+                .with_context(|| format!("Invalid redaction pattern '{}'", name))?,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    /// Literal, case-insensitive, whole-word match (machine and user names).
+    fn literal(name: &str, value: &str, replacement: &str) -> Option<Self> {
+        let value = value.trim();
+        // Very short names would shred ordinary words.
+        if value.chars().count() < 3 {
+            return None;
+        }
+        Self::new(
+            name,
+            &format!(r"(?i)\b{}\b", regex::escape(value)),
+            replacement,
+        )
+        .ok()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PatternFile {
+    #[serde(default)]
+    patterns: Vec<PatternEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatternEntry {
+    name: String,
+    pattern: String,
+    #[serde(default = "default_replacement")]
+    replacement: String,
+}
+
+fn default_replacement() -> String {
+    "[REDACTED]".to_string()
+}
+
+/// Built-in rules, in order: connection-string hosts/users, user profile paths, e-mail, IPs.
+fn builtin_rules() -> Vec<RedactionRule> {
+    let builtin = [
+        (
+            "conn_host",
+            r"(?i)\b(server|data source|host|hostname|address|addr)(\s*=\s*)[^;\x22'\s]+",
+            "${1}${2}[REDACTED_HOST]",
+        ),
+        (
+            "conn_user",
+            r"(?i)\b(user id|uid|username|user)(\s*=\s*)[^;\x22'\s]+",
+            "${1}${2}[REDACTED_USER]",
+        ),
+        (
+            "windows_profile",
+            r"(?i)\b([a-z]:\\(?:\\)?users(?:\\)?\\)[^\\\x22'\s]+",
+            "${1}[REDACTED_USER]",
+        ),
+        ("unix_home", r"(/home/)[^/\x22'\s]+", "${1}[REDACTED_USER]"),
+        (
+            "email",
+            r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b",
+            "[REDACTED_EMAIL]",
+        ),
+        (
+            "ipv4",
+            r"\b(?:25[0-5]|2[0-4]\d|1?\d?\d)(?:\.(?:25[0-5]|2[0-4]\d|1?\d?\d)){3}\b",
+            "[REDACTED_IP]",
+        ),
+        (
+            "ipv6",
+            r"\b(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}\b|\b(?:[0-9A-Fa-f]{1,4}:){1,6}:(?:[0-9A-Fa-f]{1,4}:){0,5}[0-9A-Fa-f]{1,4}\b",
+            "[REDACTED_IP]",
+        ),
+    ];
+    builtin
+        .iter()
+        .filter_map(|(name, pattern, replacement)| {
+            RedactionRule::new(name, pattern, replacement).ok()
+        })
+        .collect()
+}
+
+/// Rules for this machine: the built-ins, the local host and user names, then configured extras.
+pub fn load_rules() -> Vec<RedactionRule> {
+    let mut rules = builtin_rules();
+    for var in ["COMPUTERNAME", "HOSTNAME"] {
+        if let Some(rule) = std::env::var(var)
+            .ok()
+            .and_then(|v| RedactionRule::literal("local_host", &v, "[REDACTED_HOST]"))
+        {
+            rules.push(rule);
+        }
+    }
+    for var in ["USER", "USERNAME"] {
+        if let Some(rule) = std::env::var(var)
+            .ok()
+            .and_then(|v| RedactionRule::literal("local_user", &v, "[REDACTED_USER]"))
+        {
+            rules.push(rule);
+        }
+    }
+    rules.extend(configured_rules());
+    rules
+}
+
+fn configured_rules() -> Vec<RedactionRule> {
+    let path = match std::env::var_os(PATTERNS_PATH_ENV) {
+        Some(p) => PathBuf::from(p),
+        None => match crate::utils::path_resolver::resolve_deployment_folder() {
+            Ok(dir) => dir.join(PATTERNS_FILE_NAME),
+            Err(_) => return Vec::new(),
+        },
+    };
+    if !path.is_file() {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|s| parse_pattern_file(&s))
+    {
+        Ok(rules) => rules,
+        Err(e) => {
+            warn!(
+                "[PHASE: support] [STEP: redact] Ignoring redaction pattern file {:?}: {}",
+                path, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+fn parse_pattern_file(json: &str) -> Result<Vec<RedactionRule>> {
+    let file: PatternFile = serde_json::from_str(json)?;
+    file.patterns
+        .iter()
+        .map(|p| RedactionRule::new(&p.name, &p.pattern, &p.replacement))
+        .collect()
+}
+
+/// Apply `rules` in order; returns the scrubbed text and the number of replacements.
+pub fn redact_text(input: &str, rules: &[RedactionRule]) -> (String, usize) {
+    let mut text = input.to_string();
+    let mut count = 0usize;
+    for rule in rules {
+        let hits = rule.pattern.find_iter(&text).count();
+        if hits > 0 {
+            debug!(
+                "[PHASE: support] [STEP: redact] rule matched (rule={}, hits={})",
+                rule.name, hits
+            );
+            count += hits;
+            text = rule
+                .pattern
+                .replace_all(&text, rule.replacement.as_str())
+                .into_owned();
+        }
+    }
+    (text, count)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSummary {
+    pub files_scanned: usize,
+    pub files_redacted: usize,
+    pub replacements: usize,
+    /// Non-UTF-8 files, which are left out of the bundle rather than shipped unscrubbed.
+    pub files_dropped: usize,
+}
+
+/// Scrub every file under `dir` in place. Binary files are removed.
+pub async fn redact_tree(dir: &Path, rules: &[RedactionRule]) -> Result<RedactionSummary> {
+    let mut summary = RedactionSummary::default();
+    for path in collect_files_recursive(dir).await? {
+        let bytes = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("read failed: {:?}", path))?;
+        let Ok(text) = String::from_utf8(bytes) else {
+            tokio::fs::remove_file(&path).await?;
+            summary.files_dropped += 1;
+            continue;
+        };
+        summary.files_scanned += 1;
+        let (scrubbed, hits) = redact_text(&text, rules);
+        if hits > 0 {
+            tokio::fs::write(&path, scrubbed)
+                .await
+                .with_context(|| format!("write failed: {:?}", path))?;
+            summary.files_redacted += 1;
+            summary.replacements += hits;
+        }
+    }
+    info!(
+        "[PHASE: support] [STEP: redact] Redaction complete (files_scanned={}, files_redacted={}, replacements={}, files_dropped={})",
+        summary.files_scanned, summary.files_redacted, summary.replacements, summary.files_dropped
+    );
+    Ok(summary)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleFileEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Per-file listing (bundle-relative, forward slashes, sorted) for the bundle manifest.
+pub async fn list_files(dir: &Path) -> Result<Vec<BundleFileEntry>> {
+    let mut entries = Vec::new();
+    for path in collect_files_recursive(dir).await? {
+        let rel = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let bytes = tokio::fs::metadata(&path).await?.len();
+        entries.push(BundleFileEntry {
+            path: rel,
+            bytes,
+            sha256: sha256_file(&path).await?,
+        });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Pack `dir` into `zip_path` (entries sorted, deflated), write `<zip>.sha256`, and return the
+/// ZIP's SHA-256.
+pub async fn zip_dir(dir: &Path, zip_path: &Path) -> Result<String> {
+    let mut files = collect_files_recursive(dir).await?;
+    files.sort();
+    let (dir_owned, zip_owned) = (dir.to_path_buf(), zip_path.to_path_buf());
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let out = std::fs::File::create(&zip_owned)
+            .with_context(|| format!("create failed: {:?}", zip_owned))?;
+        let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(out));
+        let opts = FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(0o644);
+        for path in &files {
+            let name = path
+                .strip_prefix(&dir_owned)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            zip.start_file(name, opts)?;
+            let mut input = std::fs::File::open(path)?;
+            std::io::copy(&mut input, &mut zip)?;
+        }
+        let mut out = zip.finish()?;
+        out.flush()?;
+        Ok(())
+    })
+    .await
+    .context("zip task failed")??;
+
+    let sha256 = sha256_file(zip_path).await?;
+    let file_name = zip_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut sidecar = zip_path.as_os_str().to_owned();
+    sidecar.push(".sha256");
+    tokio::fs::write(
+        PathBuf::from(sidecar),
+        format!("{}  {}\n", sha256, file_name),
+    )
+    .await?;
+    Ok(sha256)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn redacts_then_zips_with_checksum() {
+        let mut rules = builtin_rules();
+        rules.extend(
+            parse_pattern_file(
+                r#"{ "patterns": [ { "name": "site", "pattern": "(?i)county-\\w+", "replacement": "[SITE]" } ] }"#,
+            )
+            .unwrap(),
+        );
+        rules.extend(RedactionRule::literal(
+            "local_host",
+            "dispatch-01",
+            "[REDACTED_HOST]",
+        ));
+        let (text, hits) = redact_text(
+            "[PHASE: database] Server=sql01.county-east.local;User Id=svc_cad;Password=*** \
+             from 10.20.30.40 on DISPATCH-01 at 12:30:45 (C:\\Users\\jdoe\\AppData, ops@example.org, fe80::1)",
+            &rules,
+        );
+        assert_eq!(
+            text,
+            "[PHASE: database] Server=[REDACTED_HOST];User Id=[REDACTED_USER];Password=*** \
+             from [REDACTED_IP] on [REDACTED_HOST] at 12:30:45 (C:\\Users\\[REDACTED_USER]\\AppData, [REDACTED_EMAIL], [REDACTED_IP])"
+        );
+        assert_eq!(hits, 7);
+
+        let dir = std::env::temp_dir().join(format!("support-bundle-{}", uuid::Uuid::new_v4()));
+        let staging = dir.join("bundle");
+        tokio::fs::create_dir_all(staging.join("logs"))
+            .await
+            .unwrap();
+        tokio::fs::write(staging.join("logs/a.log"), "host=10.0.0.5\n")
+            .await
+            .unwrap();
+        tokio::fs::write(staging.join("logs/b.bin"), [0xff, 0xfe, 0x00])
+            .await
+            .unwrap();
+        let summary = redact_tree(&staging, &rules).await.unwrap();
+        assert_eq!((summary.files_redacted, summary.files_dropped), (1, 1));
+        let listed = list_files(&staging).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].path, "logs/a.log");
+
+        let zip_path = dir.join("bundle.zip");
+        let sha = zip_dir(&staging, &zip_path).await.unwrap();
+        assert_eq!(sha, sha256_file(&zip_path).await.unwrap());
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        let mut body = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("logs/a.log").unwrap(), &mut body)
+            .unwrap();
+        assert_eq!(body, "host=[REDACTED_HOST]\n");
+        let sidecar = tokio::fs::read_to_string(dir.join("bundle.zip.sha256"))
+            .await
+            .unwrap();
+        assert!(sidecar.starts_with(&sha));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
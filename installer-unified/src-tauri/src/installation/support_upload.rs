@@ -0,0 +1,340 @@
+//! Upload of a redacted support bundle ZIP to the CADalytix support endpoint.
+//!
+//! Only runs with explicit user consent (checked by `upload_support_bundle`). The protocol is a
+//! resumable chunked upload over HTTPS:
+//! - `POST {base}/support/uploads` `{fileName, sizeBytes, sha256, ticketId, installerVersion}`
+//!   -> `{uploadId, receivedBytes}`
+//! - `GET {base}/support/uploads/{id}` -> `{uploadId, receivedBytes}` (resume)
+//! - `PUT {base}/support/uploads/{id}` with `Content-Range: bytes a-b/total`, one chunk per request
+//!   -> `{uploadId, receivedBytes}`
+//! - `POST {base}/support/uploads/{id}/complete` `{sha256}` -> `{reference}`
+//!
+//! The session is remembered in `<zip>.upload.json`, so a retry after a dropped connection
+//! continues from the last byte the server acknowledged instead of starting over.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::time::Duration;
+use tokio_retry::RetryIf;
+
+use crate::utils::http::{self, ProxyConfig};
+use crate::utils::retry;
+
+const CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+/// Per-request timeout for chunk uploads (the shared client default is sized for small calls).
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_TICKET_ID_LEN: usize = 64;
+
+/// Retryable HTTP status from the support endpoint.
+#[derive(Debug)]
+struct TransientStatus(reqwest::StatusCode);
+
+impl std::fmt::Display for TransientStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Support endpoint returned HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for TransientStatus {}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateUploadReq<'a> {
+    file_name: &'a str,
+    size_bytes: u64,
+    sha256: &'a str,
+    ticket_id: Option<&'a str>,
+    installer_version: &'a str,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadStatus {
+    upload_id: String,
+    #[serde(default)]
+    received_bytes: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompleteResp {
+    reference: Option<String>,
+}
+
+/// Resume state stored next to the ZIP.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadSession {
+    upload_id: String,
+    sha256: String,
+    base_url: String,
+}
+
+/// Outcome of a finished upload.
+#[derive(Debug, Clone)]
+pub struct UploadOutcome {
+    pub upload_id: String,
+    /// Support-side reference to quote in the ticket (falls back to the upload id).
+    pub reference: String,
+    pub bytes: u64,
+    pub resumed_from: u64,
+}
+
+/// Ticket IDs are optional; when given they must be short and plain (`[A-Za-z0-9_-]`).
+pub fn validate_ticket_id(ticket_id: Option<&str>) -> Result<Option<String>> {
+    let Some(t) = ticket_id.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    if t.len() > MAX_TICKET_ID_LEN
+        || !t
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Ticket ID may only contain letters, digits, '-' and '_' (max 64)");
+    }
+    Ok(Some(t.to_string()))
+}
+
+/// Only bundles produced by `create_support_bundle` may be uploaded: a
+/// `Support_Bundle_*.zip` directly inside the log folder.
+pub fn resolve_bundle_path(log_dir: &Path, zip_path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(zip_path.trim());
+    let name_ok = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("Support_Bundle_") && n.ends_with(".zip"));
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Support bundle not found: {:?}", path))?;
+    let log_dir = log_dir
+        .canonicalize()
+        .unwrap_or_else(|_| log_dir.to_path_buf());
+    if !name_ok || canonical.parent() != Some(log_dir.as_path()) {
+        anyhow::bail!("Only support bundles created by the installer can be uploaded");
+    }
+    Ok(canonical)
+}
+
+fn content_range(start: u64, len: u64, total: u64) -> String {
+    format!("bytes {}-{}/{}", start, start + len - 1, total)
+}
+
+fn session_path(zip_path: &Path) -> PathBuf {
+    let mut p = zip_path.as_os_str().to_owned();
+    p.push(".upload.json");
+    PathBuf::from(p)
+}
+
+fn is_transient(e: &anyhow::Error) -> bool {
+    http::is_unreachable(e)
+        || e.downcast_ref::<TransientStatus>().is_some()
+        || e.chain().any(|c| {
+            c.downcast_ref::<reqwest::Error>()
+                .is_some_and(|r| r.is_timeout() || r.is_request())
+        })
+}
+
+/// Map a response to `T`, classifying 5xx/429 as transient.
+async fn read_json<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> Result<T> {
+    let status = resp.status();
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(TransientStatus(status).into());
+    }
+    if !status.is_success() {
+        anyhow::bail!("Support endpoint returned HTTP {}", status);
+    }
+    resp.json::<T>()
+        .await
+        .context("Support endpoint returned an invalid response")
+}
+
+/// Upload `zip_path` (SHA-256 `sha256`) to `{base_url}/support/uploads`, resuming a previous
+/// session for the same file when the server still has it.
+pub async fn upload_bundle(
+    base_url: &str,
+    zip_path: &Path,
+    sha256: &str,
+    ticket_id: Option<&str>,
+    proxy: &ProxyConfig,
+) -> Result<UploadOutcome> {
+    let base = format!("{}/support/uploads", base_url.trim_end_matches('/'));
+    let client = http::build_client(proxy)?;
+    let total = tokio::fs::metadata(zip_path).await?.len();
+    if total == 0 {
+        anyhow::bail!("Support bundle is empty");
+    }
+    let file_name = zip_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let session_file = session_path(zip_path);
+
+    // Resume a known session for the same file, else open a new one.
+    let previous: Option<UploadSession> = tokio::fs::read(&session_file)
+        .await
+        .ok()
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .filter(|s: &UploadSession| s.sha256 == sha256 && s.base_url == base);
+    let resumed = match previous {
+        Some(s) => {
+            let url = format!("{}/{}", base, s.upload_id);
+            let status = RetryIf::spawn(
+                retry::policy().backoff(),
+                || async { read_json::<UploadStatus>(client.get(&url).send().await?).await },
+                is_transient,
+            )
+            .await;
+            match status {
+                Ok(st) => Some(st),
+                Err(e) => {
+                    warn!(
+                        "[PHASE: support] [STEP: upload] Previous upload session is gone; starting over: {}",
+                        e
+                    );
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let status = match resumed {
+        Some(st) => st,
+        None => {
+            let body = CreateUploadReq {
+                file_name: &file_name,
+                size_bytes: total,
+                sha256,
+                ticket_id,
+                installer_version: env!("CARGO_PKG_VERSION"),
+            };
+            let st = RetryIf::spawn(
+                retry::policy().backoff(),
+                || async {
+                    read_json::<UploadStatus>(client.post(&base).json(&body).send().await?).await
+                },
+                is_transient,
+            )
+            .await?;
+            let session = UploadSession {
+                upload_id: st.upload_id.clone(),
+                sha256: sha256.to_string(),
+                base_url: base.clone(),
+            };
+            tokio::fs::write(&session_file, serde_json::to_vec(&session)?).await?;
+            st
+        }
+    };
+
+    let upload_url = format!("{}/{}", base, status.upload_id);
+    let resumed_from = status.received_bytes.min(total);
+    info!(
+        "[PHASE: support] [STEP: upload] Uploading support bundle (upload_id={}, bytes={}, resume_from={})",
+        status.upload_id, total, resumed_from
+    );
+
+    let mut file = tokio::fs::File::open(zip_path).await?;
+    let mut offset = resumed_from;
+    while offset < total {
+        let len = CHUNK_BYTES.min(total - offset);
+        let mut chunk = vec![0u8; len as usize];
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        file.read_exact(&mut chunk).await?;
+        let range = content_range(offset, len, total);
+        let ack = RetryIf::spawn(
+            retry::policy().backoff(),
+            || async {
+                let resp = client
+                    .put(&upload_url)
+                    .timeout(CHUNK_TIMEOUT)
+                    .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                    .header(reqwest::header::CONTENT_RANGE, range.as_str())
+                    .body(chunk.clone())
+                    .send()
+                    .await?;
+                read_json::<UploadStatus>(resp).await
+            },
+            is_transient,
+        )
+        .await?;
+        // The server's acknowledgement is authoritative (it may already hold more).
+        let next = ack.received_bytes.max(offset + len).min(total);
+        info!(
+            "[PHASE: support] [STEP: upload] chunk ok (range={}, received_bytes={})",
+            range, next
+        );
+        offset = next;
+    }
+
+    #[derive(serde::Serialize)]
+    struct CompleteReq<'a> {
+        sha256: &'a str,
+    }
+    let complete_url = format!("{}/complete", upload_url);
+    let done = RetryIf::spawn(
+        retry::policy().backoff(),
+        || async {
+            let resp = client
+                .post(&complete_url)
+                .json(&CompleteReq { sha256 })
+                .send()
+                .await?;
+            read_json::<CompleteResp>(resp).await
+        },
+        is_transient,
+    )
+    .await?;
+    let _ = tokio::fs::remove_file(&session_file).await;
+
+    let reference = done
+        .reference
+        .filter(|r| !r.trim().is_empty())
+        .unwrap_or_else(|| status.upload_id.clone());
+    info!(
+        "[PHASE: support] [STEP: upload] Support bundle uploaded (upload_id={}, reference={}, bytes={}, resumed_from={})",
+        status.upload_id, reference, total, resumed_from
+    );
+    Ok(UploadOutcome {
+        upload_id: status.upload_id,
+        reference,
+        bytes: total,
+        resumed_from,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_installer_bundles_and_plain_ticket_ids_are_accepted() {
+        assert_eq!(validate_ticket_id(None).unwrap(), None);
+        assert_eq!(validate_ticket_id(Some("  ")).unwrap(), None);
+        assert_eq!(
+            validate_ticket_id(Some(" CS-1042 ")).unwrap().as_deref(),
+            Some("CS-1042")
+        );
+        assert!(validate_ticket_id(Some("1042; rm -rf")).is_err());
+        assert!(validate_ticket_id(Some(&"x".repeat(65))).is_err());
+
+        let dir = std::env::temp_dir().join(format!("support-upload-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        let bundle = dir.join("Support_Bundle_20260101_000000.zip");
+        std::fs::write(&bundle, b"zip").unwrap();
+        std::fs::write(dir.join("secrets.zip"), b"zip").unwrap();
+        std::fs::write(dir.join("nested/Support_Bundle_x.zip"), b"zip").unwrap();
+        assert!(resolve_bundle_path(&dir, &bundle.to_string_lossy()).is_ok());
+        assert!(resolve_bundle_path(&dir, &dir.join("secrets.zip").to_string_lossy()).is_err());
+        assert!(resolve_bundle_path(
+            &dir,
+            &dir.join("nested/Support_Bundle_x.zip").to_string_lossy()
+        )
+        .is_err());
+        assert!(resolve_bundle_path(&dir, &dir.join("missing.zip").to_string_lossy()).is_err());
+
+        assert_eq!(content_range(0, 10, 25), "bytes 0-9/25");
+        assert_eq!(content_range(20, 5, 25), "bytes 20-24/25");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
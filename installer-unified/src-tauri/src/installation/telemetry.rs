@@ -0,0 +1,392 @@
+//! Opt-in install telemetry (the "Consent to Sync" toggle).
+//!
+//! Only what the Consent page lists is sent: installer version and timestamp, install mode,
+//! selected storage/retention/archive settings, the schema mapping (field names only) and
+//! aggregate counts. No paths, hostnames, credentials, connection strings or call data.
+//!
+//! Payloads are queued as JSON files under `<log folder>/telemetry_queue/` first and then
+//! posted to `{ops}/telemetry/installs`. When the server cannot be reached they stay queued and
+//! the next installer start retries them; a payload the server rejects (4xx) is moved to
+//! `telemetry_queue/rejected/` so it is not resent forever.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use tokio_retry::RetryIf;
+
+use crate::licensing::online;
+use crate::utils::http::{self, ProxyConfig};
+use crate::utils::retry;
+
+const QUEUE_DIR_NAME: &str = "telemetry_queue";
+const REJECTED_DIR_NAME: &str = "rejected";
+
+/// Settings selected in the wizard, reduced to non-identifying values.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySettings {
+    pub db_setup_mode: String,
+    pub db_hosted_where: String,
+    pub storage_mode: String,
+    pub storage_location: String,
+    pub retention_policy: String,
+    pub max_disk_gb: String,
+    pub hot_retention_months: u32,
+    pub archive_enabled: bool,
+    pub archive_format: String,
+    pub archive_max_usage_gb: u32,
+    pub archive_day_of_month: u8,
+    pub archive_catch_up_on_startup: bool,
+    pub archive_encrypted: bool,
+}
+
+/// One mapped field: source column name and the target field it feeds.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryMapping {
+    pub source_field: String,
+    pub target_field: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryCounts {
+    pub mapped_fields: usize,
+    pub detected_fields: usize,
+}
+
+/// The PHI-safe document described on the Consent page.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryPayload {
+    pub schema_version: u32,
+    pub installer_version: String,
+    pub created_utc: String,
+    pub install_mode: String,
+    pub installation_type: String,
+    pub settings: TelemetrySettings,
+    pub mapping: Vec<TelemetryMapping>,
+    pub counts: TelemetryCounts,
+}
+
+/// Send status recorded in the install manifest.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStatus {
+    /// "disabled" | "sent" | "queued" | "failed"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempted_utc: Option<String>,
+    /// Payloads still waiting in the local queue after this attempt.
+    pub pending: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TelemetryStatus {
+    pub fn disabled() -> Self {
+        Self {
+            status: "disabled".to_string(),
+            payload_sha256: None,
+            attempted_utc: None,
+            pending: 0,
+            error: None,
+        }
+    }
+}
+
+/// Outcome of draining the queue.
+#[derive(Debug, Clone, Default)]
+pub struct FlushReport {
+    pub sent: Vec<PathBuf>,
+    pub rejected: usize,
+    pub pending: usize,
+    pub last_error: Option<String>,
+}
+
+/// Permanent rejection by the telemetry endpoint (not retried, not resent).
+#[derive(Debug)]
+struct Rejected(reqwest::StatusCode);
+
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Telemetry endpoint rejected the payload (HTTP {})",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// Retryable HTTP status from the telemetry endpoint.
+#[derive(Debug)]
+struct TransientStatus(reqwest::StatusCode);
+
+impl std::fmt::Display for TransientStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Telemetry endpoint returned HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for TransientStatus {}
+
+/// `<log folder>/telemetry_queue`.
+pub fn queue_dir() -> Result<PathBuf> {
+    Ok(crate::utils::path_resolver::resolve_log_folder()?.join(QUEUE_DIR_NAME))
+}
+
+/// Write `payload` to the queue (temp file + rename) and return its path and SHA-256.
+pub async fn enqueue(dir: &Path, payload: &TelemetryPayload) -> Result<(PathBuf, String)> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create telemetry queue {:?}", dir))?;
+    let bytes = serde_json::to_vec(payload)?;
+    let sha256 = crate::security::crypto::sha256_hex(&bytes);
+    let name = format!(
+        "{}_{}.json",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        uuid::Uuid::new_v4().simple()
+    );
+    let path = dir.join(name);
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, &bytes).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok((path, sha256))
+}
+
+/// Queued payload files, oldest first.
+async fn queued_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    let mut rd = match tokio::fs::read_dir(dir).await {
+        Ok(rd) => rd,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = rd.next_entry().await? {
+        let path = entry.path();
+        if entry.file_type().await?.is_file()
+            && path.extension().and_then(|e| e.to_str()) == Some("json")
+        {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+async fn post_payload(client: &reqwest::Client, url: &str, body: Vec<u8>) -> Result<()> {
+    let resp = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await?;
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(TransientStatus(status).into());
+    }
+    Err(Rejected(status).into())
+}
+
+/// Post every queued payload to `{base_url}/telemetry/installs`, oldest first.
+///
+/// Stops at the first payload that still fails after retries (the network is likely down) and
+/// leaves it and the rest queued.
+pub async fn flush(dir: &Path, base_url: &str, proxy: &ProxyConfig) -> Result<FlushReport> {
+    let files = queued_files(dir).await?;
+    let mut report = FlushReport {
+        pending: files.len(),
+        ..FlushReport::default()
+    };
+    if files.is_empty() {
+        return Ok(report);
+    }
+    let client = http::build_client(proxy)?;
+    let url = format!("{}/telemetry/installs", base_url.trim_end_matches('/'));
+
+    for path in files {
+        let body = tokio::fs::read(&path).await?;
+        let sent = RetryIf::spawn(
+            retry::policy().backoff(),
+            || post_payload(&client, &url, body.clone()),
+            |e: &anyhow::Error| {
+                http::is_unreachable(e) || e.downcast_ref::<TransientStatus>().is_some()
+            },
+        )
+        .await;
+        match sent {
+            Ok(()) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                report.pending -= 1;
+                report.sent.push(path);
+            }
+            Err(e) if e.downcast_ref::<Rejected>().is_some() => {
+                warn!(
+                    "[PHASE: telemetry] [STEP: flush] {} (path={:?}); moving it aside",
+                    e, path
+                );
+                let rejected_dir = dir.join(REJECTED_DIR_NAME);
+                tokio::fs::create_dir_all(&rejected_dir).await?;
+                if let Some(name) = path.file_name() {
+                    tokio::fs::rename(&path, rejected_dir.join(name)).await?;
+                }
+                report.pending -= 1;
+                report.rejected += 1;
+                report.last_error = Some(e.to_string());
+            }
+            Err(e) => {
+                warn!(
+                    "[PHASE: telemetry] [STEP: flush] Telemetry send failed; keeping {} payload(s) queued: {:#}",
+                    report.pending, e
+                );
+                report.last_error = Some(e.to_string());
+                break;
+            }
+        }
+    }
+    info!(
+        "[PHASE: telemetry] [STEP: flush] sent={} rejected={} pending={}",
+        report.sent.len(),
+        report.rejected,
+        report.pending
+    );
+    Ok(report)
+}
+
+/// Queue `payload` and try to send the queue once, bounded by the retry policy's attempt timeout
+/// per payload. Never fails the caller: problems are reported in the returned status.
+pub async fn queue_and_send(payload: &TelemetryPayload) -> TelemetryStatus {
+    let attempted_utc = Some(chrono::Utc::now().to_rfc3339());
+    let failed = |e: anyhow::Error| TelemetryStatus {
+        status: "failed".to_string(),
+        payload_sha256: None,
+        attempted_utc: attempted_utc.clone(),
+        pending: 0,
+        error: Some(e.to_string()),
+    };
+    let dir = match queue_dir() {
+        Ok(d) => d,
+        Err(e) => return failed(e),
+    };
+    let (path, sha256) = match enqueue(&dir, payload).await {
+        Ok(v) => v,
+        Err(e) => return failed(e),
+    };
+
+    let budget = retry::policy().attempt_timeout * (retry::policy().max_retries + 1);
+    let flushed = tokio::time::timeout(
+        budget,
+        flush(
+            &dir,
+            online::DEFAULT_OPS_API_BASE_URL,
+            &ProxyConfig::default(),
+        ),
+    )
+    .await
+    .unwrap_or_else(|_| Err(anyhow::anyhow!("Telemetry send timed out")));
+    let (sent, pending, error) = match flushed {
+        Ok(r) => (r.sent.contains(&path), r.pending, r.last_error),
+        Err(e) => (
+            false,
+            queued_files(&dir).await.map(|f| f.len()).unwrap_or(1),
+            Some(e.to_string()),
+        ),
+    };
+    TelemetryStatus {
+        status: if sent { "sent" } else { "queued" }.to_string(),
+        payload_sha256: Some(sha256),
+        attempted_utc,
+        pending,
+        error: if sent { None } else { error },
+    }
+}
+
+/// Best-effort resend of payloads queued by earlier runs (called at startup).
+pub async fn flush_pending() {
+    let Ok(dir) = queue_dir() else {
+        return;
+    };
+    if queued_files(&dir)
+        .await
+        .map(|f| f.is_empty())
+        .unwrap_or(true)
+    {
+        return;
+    }
+    if let Err(e) = flush(
+        &dir,
+        online::DEFAULT_OPS_API_BASE_URL,
+        &ProxyConfig::default(),
+    )
+    .await
+    {
+        warn!(
+            "[PHASE: telemetry] [STEP: flush] Failed to resend queued telemetry: {:#}",
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn payloads_queue_in_order_and_flush_is_a_noop_when_empty() {
+        let dir = std::env::temp_dir().join(format!("telemetry-{}", uuid::Uuid::new_v4()));
+        let empty = flush(&dir, "https://127.0.0.1:1", &ProxyConfig::default())
+            .await
+            .unwrap();
+        assert!(empty.sent.is_empty() && empty.pending == 0 && empty.last_error.is_none());
+
+        let payload = TelemetryPayload {
+            schema_version: 1,
+            installer_version: "1.0.0".to_string(),
+            created_utc: "2026-01-01T00:00:00Z".to_string(),
+            install_mode: "docker".to_string(),
+            installation_type: "typical".to_string(),
+            settings: TelemetrySettings {
+                db_setup_mode: "existing".to_string(),
+                db_hosted_where: "on_prem".to_string(),
+                storage_mode: "defaults".to_string(),
+                storage_location: "system".to_string(),
+                retention_policy: "18".to_string(),
+                max_disk_gb: "500".to_string(),
+                hot_retention_months: 18,
+                archive_enabled: true,
+                archive_format: "zip+ndjson".to_string(),
+                archive_max_usage_gb: 100,
+                archive_day_of_month: 1,
+                archive_catch_up_on_startup: true,
+                archive_encrypted: false,
+            },
+            mapping: vec![TelemetryMapping {
+                source_field: "CallTime".to_string(),
+                target_field: "call_received_at".to_string(),
+            }],
+            counts: TelemetryCounts {
+                mapped_fields: 1,
+                detected_fields: 3,
+            },
+        };
+        let (first, sha) = enqueue(&dir, &payload).await.unwrap();
+        let (second, _) = enqueue(&dir, &payload).await.unwrap();
+        let bytes = tokio::fs::read(&first).await.unwrap();
+        assert_eq!(crate::security::crypto::sha256_hex(&bytes), sha);
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["mapping"][0]["sourceField"], "CallTime");
+        assert_eq!(json["counts"]["detectedFields"], 3);
+
+        let queued = queued_files(&dir).await.unwrap();
+        assert_eq!(queued.len(), 2);
+        assert!(queued.contains(&first) && queued.contains(&second));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
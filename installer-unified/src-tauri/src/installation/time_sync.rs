@@ -0,0 +1,272 @@
+//! Clock sanity for the network preflight: NTP sync on this host, and skew and time zone against
+//! the database server.
+//!
+//! Ingestion resumes from a watermark the database compares with its own clock, and archive jobs
+//! run on this host's local time. A clock that drifts, or a database server minutes ahead, makes
+//! ingestion skip or repeat rows and archives cut at the wrong hour, without any error. Neither
+//! shows up until weeks later, so preflight measures both.
+//!
+//! Sync status comes from `timedatectl` (with `chronyc tracking` for the offset) on Linux and
+//! `w32tm /query /status` on Windows. The database clock is read with one query, timed on both
+//! sides so the round trip cancels out.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use tokio::time::Duration;
+
+use crate::database::connection::DatabaseConnection;
+use crate::installation::run_cmd_with_timeout;
+
+/// Skew below this passes; ingestion tolerates sub-second differences.
+pub const SKEW_WARN_MS: i64 = 2_000;
+/// Skew at or above this fails: watermarks start skipping rows, and Kerberos (5 minutes) is
+/// not far off.
+pub const SKEW_FAIL_MS: i64 = 60_000;
+
+/// This host's clock and how it is kept in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HostClock {
+    /// `None` when no sync service could be queried.
+    pub synchronized: Option<bool>,
+    /// Where the status came from (`timedatectl`, `chrony`) or the w32time source.
+    pub source: String,
+    /// Offset from the time source, where the service reports one (positive: this host is ahead).
+    pub offset_ms: Option<f64>,
+    pub timezone: String,
+    pub utc_offset_minutes: i32,
+}
+
+/// The database server's clock relative to this host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbClock {
+    /// Database time minus this host's time (positive: the database is ahead).
+    pub skew_ms: i64,
+    /// The database server's UTC offset. SQL Server reports its OS offset; PostgreSQL only
+    /// reports the session's time zone, so it stays unknown there.
+    pub utc_offset_minutes: Option<i32>,
+}
+
+async fn run(program: &str, args: &[&str], op: &str) -> Option<String> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    match run_cmd_with_timeout(program, &args, Duration::from_secs(10), op).await {
+        Ok(out) if out.exit_code == Some(0) => Some(out.stdout),
+        _ => None,
+    }
+}
+
+/// Sync status of this host (best-effort; see module docs).
+pub async fn host_clock() -> HostClock {
+    let mut clock = HostClock {
+        utc_offset_minutes: chrono::Local::now().offset().local_minus_utc() / 60,
+        ..HostClock::default()
+    };
+    if cfg!(windows) {
+        if let Some(text) = run("w32tm", &["/query", "/status"], "w32tm_status").await {
+            let (synchronized, source) = parse_w32tm_status(&text);
+            clock.synchronized = synchronized;
+            clock.source = source;
+        }
+        if let Some(tz) = run("tzutil", &["/g"], "tzutil").await {
+            clock.timezone = tz.trim().to_string();
+        }
+    } else {
+        if let Some(text) = run(
+            "timedatectl",
+            &["show", "-p", "NTPSynchronized", "-p", "Timezone"],
+            "timedatectl_show",
+        )
+        .await
+        {
+            let (synchronized, timezone) = parse_timedatectl_show(&text);
+            clock.synchronized = synchronized;
+            clock.timezone = timezone.unwrap_or_default();
+            clock.source = "timedatectl".to_string();
+        }
+        if let Some(text) = run("chronyc", &["tracking"], "chronyc_tracking").await {
+            let (synchronized, offset_ms) = parse_chronyc_tracking(&text);
+            clock.synchronized = clock.synchronized.or(synchronized);
+            clock.offset_ms = offset_ms;
+            clock.source = "chrony".to_string();
+        }
+    }
+    info!(
+        "[PHASE: preflight] [STEP: time_sync] Host clock: {:?}",
+        clock
+    );
+    clock
+}
+
+/// `NTPSynchronized` and `Timezone` from `timedatectl show`.
+pub fn parse_timedatectl_show(text: &str) -> (Option<bool>, Option<String>) {
+    let mut synchronized = None;
+    let mut timezone = None;
+    for line in text.lines() {
+        match line.trim().split_once('=') {
+            Some(("NTPSynchronized", v)) => synchronized = Some(v.trim() == "yes"),
+            Some(("Timezone", v)) if !v.trim().is_empty() => timezone = Some(v.trim().to_string()),
+            _ => {}
+        }
+    }
+    (synchronized, timezone)
+}
+
+/// Sync state (`Leap status : Normal`) and offset (`System time : 0.000012 seconds fast of NTP
+/// time`) from `chronyc tracking`.
+pub fn parse_chronyc_tracking(text: &str) -> (Option<bool>, Option<f64>) {
+    let mut synchronized = None;
+    let mut offset_ms = None;
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Leap status" => synchronized = Some(value != "Not synchronised"),
+            "System time" => {
+                let mut words = value.split_whitespace();
+                let seconds: Option<f64> = words.next().and_then(|s| s.parse().ok());
+                let sign = match words.nth(1) {
+                    Some("slow") => -1.0,
+                    _ => 1.0,
+                };
+                offset_ms = seconds.map(|s| sign * s * 1000.0);
+            }
+            _ => {}
+        }
+    }
+    (synchronized, offset_ms)
+}
+
+/// Sync state and source from `w32tm /query /status`: a local or free-running clock, or leap
+/// indicator 3, means no time source is in use.
+pub fn parse_w32tm_status(text: &str) -> (Option<bool>, String) {
+    let mut leap_unsynced = None;
+    let mut source = String::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "Leap Indicator" => leap_unsynced = Some(value.trim().starts_with('3')),
+            "Source" => source = value.trim().to_string(),
+            _ => {}
+        }
+    }
+    let local = source.eq_ignore_ascii_case("Local CMOS Clock")
+        || source.eq_ignore_ascii_case("Free-running System Clock");
+    let synchronized = leap_unsynced.map(|unsynced| !unsynced && !local);
+    (synchronized, source)
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Read the database server's clock through `conn_str` (`engine` as from `guess_engine`).
+pub async fn db_clock(conn_str: &str, engine: &str) -> Result<DbClock> {
+    let (before, db_ms, utc_offset_minutes, after) = if engine == "postgres" {
+        let conn = DatabaseConnection::postgres(conn_str).await?;
+        let pool = conn
+            .as_postgres()
+            .ok_or_else(|| anyhow::anyhow!("Not a PostgreSQL connection"))?;
+        let before = now_ms();
+        let db_ms: i64 =
+            sqlx::query_scalar("SELECT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000)::bigint")
+                .fetch_one(pool)
+                .await
+                .context("Failed to read the PostgreSQL server time")?;
+        (before, db_ms, None, now_ms())
+    } else {
+        let conn = DatabaseConnection::sql_server(conn_str).await?;
+        let client = conn
+            .as_sql_server()
+            .ok_or_else(|| anyhow::anyhow!("Not a SQL Server connection"))?;
+        let mut client = client.lock().await;
+        let before = now_ms();
+        let row = client
+            .simple_query(
+                "SELECT DATEDIFF_BIG(MILLISECOND, '19700101', SYSUTCDATETIME()), DATEDIFF(MINUTE, SYSUTCDATETIME(), SYSDATETIME())",
+            )
+            .await
+            .context("Failed to read the SQL Server time")?
+            .into_row()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("SQL Server returned no time"))?;
+        let after = now_ms();
+        let db_ms = row
+            .get::<i64, _>(0)
+            .ok_or_else(|| anyhow::anyhow!("SQL Server returned no time"))?;
+        (before, db_ms, row.get::<i32, _>(1), after)
+    };
+    let skew_ms = db_ms - (before + after) / 2;
+    if skew_ms.abs() >= SKEW_WARN_MS {
+        warn!(
+            "[PHASE: preflight] [STEP: time_sync] Database clock is {} ms off this host's (round trip {} ms)",
+            skew_ms,
+            after - before
+        );
+    }
+    Ok(DbClock {
+        skew_ms,
+        utc_offset_minutes,
+    })
+}
+
+/// "Pass" | "Warn" | "Fail" for a skew.
+pub fn skew_status(skew_ms: i64) -> &'static str {
+    match skew_ms.abs() {
+        s if s < SKEW_WARN_MS => "Pass",
+        s if s < SKEW_FAIL_MS => "Warn",
+        _ => "Fail",
+    }
+}
+
+/// `UTC+05:30`-style label for an offset in minutes.
+pub fn utc_offset_label(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let m = minutes.abs();
+    format!("UTC{}{:02}:{:02}", sign, m / 60, m % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_tools_output_and_skew_thresholds() {
+        assert_eq!(
+            parse_timedatectl_show("Timezone=America/Chicago\nNTPSynchronized=no\n"),
+            (Some(false), Some("America/Chicago".to_string()))
+        );
+
+        let tracking = "\
+Reference ID    : C0A80101 (ntp.local)
+Stratum         : 3
+System time     : 0.250000000 seconds slow of NTP time
+Leap status     : Normal
+";
+        assert_eq!(parse_chronyc_tracking(tracking), (Some(true), Some(-250.0)));
+        assert_eq!(
+            parse_chronyc_tracking("Leap status     : Not synchronised\n").0,
+            Some(false)
+        );
+
+        let w32tm = "\
+Leap Indicator: 0(no warning)
+Stratum: 1 (primary reference - syncd by radio clock)
+Source: Local CMOS Clock
+";
+        assert_eq!(
+            parse_w32tm_status(w32tm),
+            (Some(false), "Local CMOS Clock".to_string())
+        );
+        let synced = w32tm.replace("Local CMOS Clock", "dc01.corp.example");
+        assert_eq!(parse_w32tm_status(&synced).0, Some(true));
+
+        assert_eq!(skew_status(-1_500), "Pass");
+        assert_eq!(skew_status(2_000), "Warn");
+        assert_eq!(skew_status(-90_000), "Fail");
+        assert_eq!(utc_offset_label(-300), "UTC-05:00");
+        assert_eq!(utc_offset_label(330), "UTC+05:30");
+    }
+}
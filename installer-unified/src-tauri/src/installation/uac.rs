@@ -0,0 +1,472 @@
+//! UAC elevation for the privileged steps of a Windows install.
+//!
+//! The wizard no longer has to be started with "Run as administrator". When the process is not
+//! elevated, the first step that needs it launches a second copy of the installer through
+//! `Start-Process -Verb RunAs` (the UAC prompt) with `--elevated-helper=<pipe>`. The helper
+//! connects back over a named pipe, checks that the pipe belongs to the process that launched
+//! it, and then answers one JSON line per request. It only knows two operations: creating an
+//! install folder that the user cannot write (Program Files) and granting the user Modify on it,
+//! and registering the Windows services from the deployed folder. Everything else still runs
+//! unprivileged.
+//!
+//! The helper lives until the installer exits, so a rollback can still remove what it deployed.
+//! When the pipe closes it removes the folder grants again.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeServer, ServerOptions};
+use tokio::sync::Mutex;
+use tokio::time::{timeout, Duration};
+
+use crate::error::{InstallerError, OrCode};
+use crate::installation::run_cmd_with_timeout;
+use crate::installation::service;
+
+/// The UAC prompt waits for the user; give them time to read it.
+const CONSENT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+static HELPER: Mutex<Option<ElevatedHelper>> = Mutex::const_new(None);
+
+/// One request to the elevated helper; the complete set of what it will do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum HelperRequest {
+    /// Create the folder and grant `sid` Modify on it (inherited by everything below).
+    PrepareFolder { path: PathBuf, sid: String },
+    /// Register and start the main and background services deployed under `dest_root`.
+    InstallServices { dest_root: PathBuf },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HelperResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Services registered by `InstallServices`, main service first.
+    #[serde(default)]
+    pub services: Vec<String>,
+}
+
+/// Whether this process runs with an elevated (administrator) token.
+pub fn is_elevated() -> bool {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+    use winapi::um::securitybaseapi::GetTokenInformation;
+    use winapi::um::winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY};
+
+    let mut token: HANDLE = std::ptr::null_mut();
+    // SAFETY: the pseudo handle of the current process is always valid; `token` is only used
+    // and closed after OpenProcessToken succeeded.
+    unsafe {
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut len = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut len,
+        );
+        CloseHandle(token);
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Make each folder exist and be writable by this user, through the helper for the ones that
+/// are not.
+pub async fn prepare_folders(dirs: &[&Path]) -> Result<()> {
+    let blocked: Vec<&Path> = dirs.iter().copied().filter(|d| !can_write(d)).collect();
+    if blocked.is_empty() {
+        return Ok(());
+    }
+    let mut slot = HELPER.lock().await;
+    let helper = connected(&mut slot).await?;
+    for dir in blocked {
+        info!(
+            "[PHASE: installation] [STEP: uac] Preparing {:?} through the elevated helper",
+            dir
+        );
+        let sid = helper.sid.clone();
+        helper
+            .call(&HelperRequest::PrepareFolder {
+                path: dir.to_path_buf(),
+                sid,
+            })
+            .await
+            .or_code(InstallerError::DestinationNotWritable)?;
+    }
+    Ok(())
+}
+
+/// Register and start the services deployed under `dest_root` through the helper; returns the
+/// names of the services started, main service first.
+pub async fn install_services(dest_root: &Path) -> Result<Vec<String>> {
+    let mut slot = HELPER.lock().await;
+    let helper = connected(&mut slot).await?;
+    let response = helper
+        .call(&HelperRequest::InstallServices {
+            dest_root: dest_root.to_path_buf(),
+        })
+        .await?;
+    Ok(response.services)
+}
+
+/// The helper of this process, launched (UAC prompt) on first use.
+async fn connected(slot: &mut Option<ElevatedHelper>) -> Result<&mut ElevatedHelper> {
+    if slot.is_none() {
+        *slot = Some(
+            ElevatedHelper::launch()
+                .await
+                .or_code(InstallerError::ElevationFailed)?,
+        );
+    }
+    Ok(slot.as_mut().expect("helper launched above"))
+}
+
+/// Parent side of the pipe to the elevated helper.
+struct ElevatedHelper {
+    pipe: BufReader<NamedPipeServer>,
+    /// The unelevated user, who gets Modify on the prepared folders.
+    sid: String,
+}
+
+impl ElevatedHelper {
+    async fn launch() -> Result<Self> {
+        let sid = current_user_sid().await?;
+        let pipe_name = format!(
+            r"\\.\pipe\cadalytix-elevate-{}",
+            uuid::Uuid::new_v4().simple()
+        );
+        let server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create(&pipe_name)
+            .with_context(|| format!("Failed to create pipe {}", pipe_name))?;
+
+        let exe = std::env::current_exe().context("Failed to locate the installer executable")?;
+        let mut args = vec![
+            format!("--elevated-helper={}", pipe_name),
+            format!("--parent-pid={}", std::process::id()),
+        ];
+        // Service names and log folders are qualified with the instance name.
+        args.extend(std::env::args().filter(|a| a.starts_with("--instance=")));
+
+        info!("[PHASE: installation] [STEP: uac] Requesting administrator approval for the privileged steps");
+        let out = run_cmd_with_timeout(
+            "powershell",
+            &[
+                "-NoProfile".to_string(),
+                "-NonInteractive".to_string(),
+                "-Command".to_string(),
+                start_process_script(&exe, &args),
+            ],
+            CONSENT_TIMEOUT,
+            "uac_launch",
+        )
+        .await?;
+        if out.exit_code != Some(0) {
+            anyhow::bail!(
+                "Administrator approval was declined or could not be requested: {}",
+                out.stderr.trim()
+            );
+        }
+        timeout(CONNECT_TIMEOUT, server.connect())
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "The elevated helper did not connect within {}s",
+                    CONNECT_TIMEOUT.as_secs()
+                )
+            })?
+            .context("The elevated helper failed to connect")?;
+        info!("[PHASE: installation] [STEP: uac] Elevated helper connected");
+        Ok(Self {
+            pipe: BufReader::new(server),
+            sid,
+        })
+    }
+
+    async fn call(&mut self, request: &HelperRequest) -> Result<HelperResponse> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.pipe.get_mut().write_all(line.as_bytes()).await?;
+        let mut reply = String::new();
+        if self.pipe.read_line(&mut reply).await? == 0 {
+            anyhow::bail!("The elevated helper exited unexpectedly");
+        }
+        let response: HelperResponse =
+            serde_json::from_str(&reply).context("Invalid reply from the elevated helper")?;
+        match response.error {
+            Some(error) => Err(anyhow::anyhow!(error)),
+            None => Ok(response),
+        }
+    }
+}
+
+/// Helper side: serve requests from the installer that launched this process until it closes
+/// the pipe, then remove the folder grants.
+pub async fn serve(pipe_name: &str, parent_pid: u32) -> Result<()> {
+    let client = ClientOptions::new()
+        .open(pipe_name)
+        .with_context(|| format!("Failed to open pipe {}", pipe_name))?;
+    let owner = pipe_server_pid(&client)?;
+    if owner != parent_pid {
+        anyhow::bail!(
+            "Pipe {} belongs to process {}, not the installer that requested elevation ({})",
+            pipe_name,
+            owner,
+            parent_pid
+        );
+    }
+
+    let mut pipe = BufReader::new(client);
+    let mut granted: Vec<(PathBuf, String)> = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match pipe.read_line(&mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let response = match serde_json::from_str::<HelperRequest>(&line) {
+            Ok(request) => handle(request, &mut granted)
+                .await
+                .unwrap_or_else(|e| HelperResponse {
+                    error: Some(format!("{:#}", e)),
+                    ..HelperResponse::default()
+                }),
+            Err(e) => HelperResponse {
+                error: Some(format!("Unsupported helper request: {}", e)),
+                ..HelperResponse::default()
+            },
+        };
+        let mut reply = serde_json::to_string(&response)?;
+        reply.push('\n');
+        if pipe.get_mut().write_all(reply.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+
+    for (path, sid) in granted.iter().rev() {
+        if let Err(e) = icacls(path, sid, false).await {
+            warn!(
+                "[PHASE: installation] [STEP: uac] Failed to remove the grant on {:?}: {:?}",
+                path, e
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn handle(
+    request: HelperRequest,
+    granted: &mut Vec<(PathBuf, String)>,
+) -> Result<HelperResponse> {
+    info!(
+        "[PHASE: installation] [STEP: uac] Helper request: {:?}",
+        request
+    );
+    match request {
+        HelperRequest::PrepareFolder { path, sid } => {
+            if !is_sid(&sid) || !path.is_absolute() {
+                anyhow::bail!("Refusing to prepare {:?} for {:?}", path, sid);
+            }
+            tokio::fs::create_dir_all(&path)
+                .await
+                .with_context(|| format!("Failed to create {:?}", path))?;
+            icacls(&path, &sid, true).await?;
+            granted.push((path, sid));
+            Ok(HelperResponse::default())
+        }
+        HelperRequest::InstallServices { dest_root } => {
+            let main = &service::WINDOWS_MAIN_SERVICE;
+            let exe = service::find_windows_service_exe(main, &dest_root)
+                .await
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Service executable not found in destination folder")
+                })?;
+            service::install_and_start_windows_service(main, &exe).await?;
+            let mut services = vec![main.name.to_string()];
+            services.extend(service::install_windows_background_services(&dest_root).await?);
+            Ok(HelperResponse {
+                services,
+                ..HelperResponse::default()
+            })
+        }
+    }
+}
+
+fn pipe_server_pid(client: &tokio::net::windows::named_pipe::NamedPipeClient) -> Result<u32> {
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::winbase::GetNamedPipeServerProcessId;
+
+    let mut pid = 0u32;
+    // SAFETY: the handle belongs to `client`, which outlives the call.
+    let ok = unsafe { GetNamedPipeServerProcessId(client.as_raw_handle() as _, &mut pid) };
+    if ok == 0 {
+        anyhow::bail!(
+            "GetNamedPipeServerProcessId failed: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    Ok(pid)
+}
+
+async fn icacls(path: &Path, sid: &str, grant: bool) -> Result<()> {
+    let out = run_cmd_with_timeout(
+        "icacls",
+        &icacls_args(path, sid, grant),
+        COMMAND_TIMEOUT,
+        "uac_icacls",
+    )
+    .await?;
+    if out.exit_code != Some(0) {
+        anyhow::bail!(
+            "icacls failed on {:?} (exit_code={:?}): {}",
+            path,
+            out.exit_code,
+            out.stdout.trim()
+        );
+    }
+    Ok(())
+}
+
+async fn current_user_sid() -> Result<String> {
+    let out = run_cmd_with_timeout(
+        "whoami",
+        &[
+            "/user".to_string(),
+            "/fo".to_string(),
+            "csv".to_string(),
+            "/nh".to_string(),
+        ],
+        COMMAND_TIMEOUT,
+        "uac_whoami",
+    )
+    .await?;
+    parse_whoami_sid(&out.stdout)
+        .ok_or_else(|| anyhow::anyhow!("Could not read the current user's SID from whoami"))
+}
+
+/// `whoami /user /fo csv /nh` prints `"host\user","S-1-5-21-..."`.
+fn parse_whoami_sid(csv: &str) -> Option<String> {
+    let sid = csv
+        .lines()
+        .next()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .trim_matches('"');
+    is_sid(sid).then(|| sid.to_string())
+}
+
+fn is_sid(s: &str) -> bool {
+    s.strip_prefix("S-1-").is_some_and(|rest| {
+        !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit() || c == '-')
+    })
+}
+
+/// Grant Modify to the SID on the folder and everything below it, or remove that grant again.
+fn icacls_args(path: &Path, sid: &str, grant: bool) -> Vec<String> {
+    let mut args = vec![path.to_string_lossy().into_owned()];
+    if grant {
+        args.push("/grant".to_string());
+        args.push(format!("*{}:(OI)(CI)M", sid));
+    } else {
+        args.push("/remove:g".to_string());
+        args.push(format!("*{}", sid));
+    }
+    args
+}
+
+/// PowerShell that starts `exe args` elevated; fails when the user declines the prompt.
+fn start_process_script(exe: &Path, args: &[String]) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let list: Vec<String> = args.iter().map(|a| quote(a)).collect();
+    format!(
+        "$ErrorActionPreference = 'Stop'; Start-Process -FilePath {} -ArgumentList {} -Verb RunAs -WindowStyle Hidden",
+        quote(&exe.to_string_lossy()),
+        list.join(",")
+    )
+}
+
+/// Whether files can be created in `dir`, or in its nearest existing ancestor when it does not
+/// exist yet.
+fn can_write(dir: &Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|d| d.is_dir()) else {
+        return false;
+    };
+    let probe = existing.join(format!(
+        ".cadalytix-write-probe-{}",
+        uuid::Uuid::new_v4().simple()
+    ));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helper_commands_are_built_from_the_request() {
+        let sid = parse_whoami_sid(
+            "\"desktop-1\\o'brien\",\"S-1-5-21-1004336348-1177238915-682003330-1001\"\r\n",
+        );
+        assert_eq!(
+            sid.as_deref(),
+            Some("S-1-5-21-1004336348-1177238915-682003330-1001")
+        );
+        assert_eq!(parse_whoami_sid("\"host\\user\",\"S-1-5-21 & calc\""), None);
+
+        let dir = Path::new(r"C:\Program Files\CADalytix");
+        assert_eq!(
+            icacls_args(dir, "S-1-5-21-1", true),
+            vec![
+                r"C:\Program Files\CADalytix",
+                "/grant",
+                "*S-1-5-21-1:(OI)(CI)M"
+            ]
+        );
+        assert_eq!(
+            icacls_args(dir, "S-1-5-21-1", false),
+            vec![r"C:\Program Files\CADalytix", "/remove:g", "*S-1-5-21-1"]
+        );
+
+        let script = start_process_script(
+            Path::new(r"C:\Users\o'brien\Downloads\Setup.exe"),
+            &[
+                "--elevated-helper=\\\\.\\pipe\\x".to_string(),
+                "--parent-pid=42".to_string(),
+            ],
+        );
+        assert!(script.contains(r"-FilePath 'C:\Users\o''brien\Downloads\Setup.exe'"));
+        assert!(script.contains(
+            r"-ArgumentList '--elevated-helper=\\.\pipe\x','--parent-pid=42' -Verb RunAs"
+        ));
+
+        let request: HelperRequest =
+            serde_json::from_str(r#"{"op":"install_services","dest_root":"C:\\CADalytix"}"#)
+                .unwrap();
+        assert_eq!(
+            request,
+            HelperRequest::InstallServices {
+                dest_root: PathBuf::from(r"C:\CADalytix")
+            }
+        );
+        assert!(serde_json::from_str::<HelperRequest>(r#"{"op":"run","cmd":"calc"}"#).is_err());
+    }
+}
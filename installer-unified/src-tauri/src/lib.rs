@@ -4,13 +4,17 @@
 mod api;
 mod archiver;
 mod database;
+mod datasource;
+mod error;
 mod installation;
 mod licensing;
+mod mapping;
 mod models;
 mod security;
 mod tui;
 mod utils;
 
+use error::{InstallerError, OrCode};
 use log::{error, info, warn};
 use std::path::PathBuf;
 use tauri::async_runtime;
@@ -21,6 +25,7 @@ use tokio::time::{sleep, Duration};
 fn init_logging(with_stdout: bool) -> Result<(), Box<dyn std::error::Error>> {
     let log_dir = utils::path_resolver::resolve_log_folder()?;
     std::fs::create_dir_all(&log_dir)?;
+    utils::crash::install_hook(log_dir.clone());
 
     let timestamp = chrono::Utc::now().format("%Y-%m-%d-%H%M%S");
 
@@ -29,6 +34,7 @@ fn init_logging(with_stdout: bool) -> Result<(), Box<dyn std::error::Error>> {
 
     // Human-readable log file (.txt)
     let txt_log_file = log_dir.join(format!("installer-{}.txt", timestamp));
+    utils::logging::set_current_text_log_path(txt_log_file.clone());
 
     // Configure dual-format logging:
     // - JSON format to .log file
@@ -66,6 +72,17 @@ fn init_logging(with_stdout: bool) -> Result<(), Box<dyn std::error::Error>> {
                     let message_str = format!("{}", message);
                     let (phase, step, cleaned_message) =
                         utils::logging::parse_log_metadata(&message_str);
+                    utils::logging::note_position(phase.as_deref(), step.as_deref());
+                    let (error_code, cleaned_message) =
+                        utils::logging::parse_error_code(&cleaned_message);
+                    let (performance, cleaned_message) =
+                        utils::logging::parse_perf(&cleaned_message);
+                    let details = error_code.map(|code| {
+                        std::collections::HashMap::from([(
+                            "errorCode".to_string(),
+                            serde_json::Value::String(code),
+                        )])
+                    });
                     let json_line = utils::logging::format_json_log(
                         &timestamp_utc,
                         record.level(),
@@ -73,9 +90,9 @@ fn init_logging(with_stdout: bool) -> Result<(), Box<dyn std::error::Error>> {
                         &cleaned_message,
                         phase.as_deref(),
                         step.as_deref(),
-                        None, // details - can be extended later
-                        None, // context - can be extended later
-                        None, // performance - can be extended later
+                        details.as_ref(),
+                        utils::logging::log_context().as_ref(),
+                        performance.as_ref(),
                     );
                     out.finish(format_args!("{}\n", json_line));
                 })
@@ -107,9 +124,89 @@ fn init_logging(with_stdout: bool) -> Result<(), Box<dyn std::error::Error>> {
         "[PHASE: initialization] Logging initialized, log directory: {:?}",
         log_dir
     );
+    utils::otel::init();
     Ok(())
 }
 
+/// `--instance=<NAME>`: install or manage the named instance, side by side with others on this
+/// host (see `utils::instance`). Exits on an invalid name.
+pub fn use_instance(args: &[String]) {
+    let name = utils::instance::instance_arg(args).unwrap_or_default();
+    if let Err(e) = utils::instance::select(name).or_code(InstallerError::InvalidArguments) {
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// `--remote=user@host[:port]` (with `--remote-fingerprint=`): the terminal wizards install on
+/// that server over SSH (see `installation::remote`). Exits on an invalid target.
+pub fn use_remote_target(args: &[String]) {
+    let result = installation::remote::target_arg(args)
+        .and_then(|target| match target {
+            Some(target) => installation::remote::select(target),
+            None => Ok(()),
+        })
+        .or_code(InstallerError::InvalidArguments);
+    if let Err(e) = result {
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// Verify the `--offline-bundle=<path>` bundle and install from it for the rest of the process.
+/// Exits with the E1007 exit code when verification fails, so a tampered or incomplete bundle
+/// never reaches the wizard.
+pub fn use_offline_bundle(args: &[String]) {
+    let result = installation::offline_bundle::bundle_arg(args)
+        .ok_or_else(|| anyhow::anyhow!("--offline-bundle needs a path: --offline-bundle=<path>"))
+        .or_code(InstallerError::InvalidArguments)
+        .and_then(|path| {
+            installation::offline_bundle::activate(&path)
+                .or_code(InstallerError::OfflineBundleInvalid)
+        });
+    match result {
+        Ok(summary) => {
+            println!(
+                "Offline bundle verified: {} ({} files, {} container images, license: {})",
+                if summary.bundle_version.is_empty() {
+                    "unversioned"
+                } else {
+                    summary.bundle_version.as_str()
+                },
+                summary.file_count,
+                summary.image_count,
+                if summary.has_license { "yes" } else { "no" }
+            );
+        }
+        Err(e) => {
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// `--theme=<name>`: TUI theme for this run; an unknown name exits before any UI starts.
+pub fn use_tui_theme(args: &[String]) {
+    let name = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--theme="))
+        .unwrap_or_default();
+    if let Err(e) = tui::theme::select(name).or_code(InstallerError::InvalidArguments) {
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// `--progress-socket[=<path>]`: publish install progress on a local socket / named pipe.
+pub fn use_progress_socket(args: &[String]) {
+    let path = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--progress-socket"))
+        .map(|rest| rest.strip_prefix('=').unwrap_or(rest))
+        .unwrap_or_default();
+    installation::progress_stream::request(path);
+}
+
 /// Resolve deployment folder (absolute path)
 fn resolve_deployment_folder() -> PathBuf {
     // Prefer the folder where the EXE is running from
@@ -195,6 +292,22 @@ pub fn run_gui() {
                 }
             });
 
+            // Apply the saved Network page proxy, then resend opt-in telemetry queued by an
+            // earlier run that had no network.
+            let secrets = app
+                .state::<std::sync::Arc<security::secret_protector::SecretProtector>>()
+                .inner()
+                .clone();
+            async_runtime::spawn(async move {
+                if let Err(e) = utils::http::load_saved(&secrets).await {
+                    warn!(
+                        "[PHASE: initialization] [STEP: proxy] Ignoring saved proxy settings: {:#}",
+                        e
+                    );
+                }
+                installation::telemetry::flush_pending().await;
+            });
+
 
             Ok(())
         })
@@ -202,11 +315,25 @@ pub fn run_gui() {
             // UI helper + installer orchestration commands
             api::installer::spawn_installer_window,
             api::installer::file_exists,
+            api::installer::get_locale_bundle,
+            api::installer::get_wizard_defaults,
             api::installer::get_free_space_bytes,
+            api::installer::project_disk_space,
+            api::installer::inspect_storage_filesystem,
             api::installer::create_support_bundle,
+            api::installer::upload_support_bundle,
+            api::network::get_proxy_settings,
+            api::network::save_proxy_settings,
+            api::profiles::list_connection_profiles,
+            api::profiles::save_connection_profile,
+            api::profiles::delete_connection_profile,
+            api::remote::check_remote_target,
             api::installer::test_db_connection,
             api::installer::start_install,
             api::installer::cancel_install,
+            api::installer::pause_install,
+            api::installer::resume_install,
+            api::installer::start_archive_run,
             // Phase 9: Database provisioning commands
             api::installer::db_can_create_database,
             api::installer::db_exists,
@@ -224,11 +351,23 @@ pub fn run_gui() {
             api::setup::get_support_bundle,
             // License API handlers
             api::license::verify_license,
+            api::license::activate_license,
             api::license::get_license_status,
+            // Secret maintenance
+            api::secrets::rotate_secrets,
+            // Mapping API handlers
+            api::mapping::auto_map,
+            api::mapping::verify_mapping_compat,
+            api::mapping::export_mapping_csv,
+            api::mapping::import_mapping_csv,
             // Preflight API handlers
             api::preflight::preflight_host,
             api::preflight::preflight_permissions,
             api::preflight::preflight_datasource,
+            api::preflight::list_source_objects,
+            api::backfill::estimate_backfill,
+            api::preflight::preflight_network,
+            api::preflight::preflight_dependencies,
             // Schema API handlers
             api::schema::verify_schema,
             api::schema::verify_all_schemas,
@@ -243,13 +382,76 @@ pub fn run_gui() {
 
 /// Headless terminal UI wizard (Linux servers / no-display environments)
 pub fn run_tui() {
+    let secret_protector = init_headless("Headless TUI installer");
+    exit_with_tui_result(tui::run(secret_protector));
+}
+
+/// Screen-reader friendly line mode of the TUI wizard (`--tui-plain`).
+pub fn run_tui_plain() {
+    let secret_protector = init_headless("Line-mode TUI installer");
+    exit_with_tui_result(tui::plain::run(secret_protector));
+}
+
+/// Wizard driven by scripted answers on stdin (`--tui-script`); invalid answers exit non-zero.
+pub fn run_tui_script() {
+    let secret_protector = init_headless("Scripted TUI installer");
+    match tui::script::run(secret_protector) {
+        Ok(0) => {}
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            error!(
+                "[PHASE: tui] [STEP: fatal] Scripted wizard stopped: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Distribution package: walks the scripted wizard on an answers file with full validation, then
+/// writes an MSI or Intune package wrapping the unattended install instead of installing.
+/// Usage: --package=msi|intune --answers=<file> [--package-out=<dir>] [--json]
+pub fn run_package(args: Vec<String>) {
+    let secret_protector = init_headless("Package builder");
+    let result = installation::package::package_arg(&args)
+        .or_code(InstallerError::InvalidArguments)
+        .and_then(|req| {
+            let req = req.ok_or_else(|| anyhow::anyhow!("--package=msi|intune is required"))?;
+            tui::script::package(secret_protector, req)
+        });
+    match result {
+        Ok(report) => {
+            if args.iter().any(|a| a == "--json") {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Failed to serialize package report: {}", e),
+                }
+            } else {
+                print!("{}", report.to_text());
+            }
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: package] [STEP: fatal] Package build stopped: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Logging and the secret protector for the terminal wizards.
+fn init_headless(what: &str) -> std::sync::Arc<security::secret_protector::SecretProtector> {
     // Initialize logging (no stdout to avoid corrupting the TUI)
     if let Err(e) = init_logging(false) {
         eprintln!("Failed to initialize logging: {}", e);
     }
 
     info!(
-        "[PHASE: initialization] Headless TUI installer starting at {}",
+        "[PHASE: initialization] {} starting at {}",
+        what,
         chrono::Utc::now()
     );
 
@@ -268,13 +470,19 @@ pub fn run_tui() {
         }
     };
     let secret_key_path = security::secret_protector::default_key_path(&log_dir);
-    let secret_protector = std::sync::Arc::new(security::secret_protector::SecretProtector::new(
+    std::sync::Arc::new(security::secret_protector::SecretProtector::new(
         secret_key_path,
-    ));
+    ))
+}
 
-    if let Err(e) = tui::run(secret_protector) {
-        error!("[PHASE: tui] [STEP: fatal] TUI exited with error: {:?}", e);
-        eprintln!("Installer error: {}", e);
+fn exit_with_tui_result(result: anyhow::Result<i32>) {
+    match result {
+        Ok(0) => {}
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            error!("[PHASE: tui] [STEP: fatal] TUI exited with error: {:?}", e);
+            eprintln!("Installer error: {}", e);
+        }
     }
 }
 
@@ -316,8 +524,8 @@ pub fn run_tui_smoke(target: Option<String>) {
             "[PHASE: tui] [STEP: smoke] TUI smoke exited with error: {:?}",
             e
         );
-        eprintln!("Installer error: {}", e);
-        std::process::exit(1);
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
     }
 }
 
@@ -369,8 +577,8 @@ pub fn run_install_contract_smoke() {
             "[PHASE: install] [STEP: contract_smoke] Smoke exited with error: {:?}",
             e
         );
-        eprintln!("Installer error: {}", e);
-        std::process::exit(1);
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
     }
 }
 
@@ -422,8 +630,8 @@ pub fn run_mapping_persist_smoke() {
             "[PHASE: mapping] [STEP: persist_smoke] Smoke exited with error: {:?}",
             e
         );
-        eprintln!("Installer error: {}", e);
-        std::process::exit(1);
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
     }
 }
 
@@ -456,8 +664,552 @@ pub fn run_archive_dry_run() {
             "[PHASE: archive] [STEP: dry_run] Dry-run exited with error: {:?}",
             e
         );
-        eprintln!("Installer error: {}", e);
-        std::process::exit(1);
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+pub fn run_archive_restore(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Archive restore starting at {}",
+        chrono::Utc::now()
+    );
+
+    let result = archiver::ArchiveRestoreArgs::from_args(&args)
+        .or_code(InstallerError::InvalidArguments)
+        .and_then(|restore_args| {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to create async runtime for archive restore: {}", e)
+                })?;
+            rt.block_on(archiver::archive_restore(restore_args))
+                .or_code(InstallerError::ArchiveRestoreFailed)
+        });
+
+    if let Err(e) = result {
+        error!(
+            "[PHASE: archive] [STEP: restore] Restore exited with error: {:?}",
+            e
+        );
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// Scheduled/startup archive runner: archives every eligible month per the persisted policy.
+/// Usage: --archive-run-once [--startup] [--max-months=N] [--ledger=<path>] [--confirm-purge=YYYY-MM]
+pub fn run_archive_run_once(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Archive run-once starting at {}",
+        chrono::Utc::now()
+    );
+
+    let result = archiver::ArchiveRunOnceArgs::from_args(&args)
+        .or_code(InstallerError::InvalidArguments)
+        .and_then(|run_args| {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to create async runtime for archive run: {}", e)
+                })?;
+            rt.block_on(archiver::archive_run_once(run_args))
+                .or_code(InstallerError::ArchiveRunFailed)
+        });
+
+    if let Err(e) = result {
+        error!(
+            "[PHASE: archive] [STEP: run_once] Archive run exited with error: {:?}",
+            e
+        );
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// Archive ledger health check for monitoring.
+/// Prints a JSON report to stdout; exits 0 when healthy, 2 when any month is missing/corrupted,
+/// 1 when the check itself could not run.
+pub fn run_archive_verify_ledger(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Archive ledger verification starting at {}",
+        chrono::Utc::now()
+    );
+
+    let verify_args = archiver::ArchiveVerifyArgs::from_args(&args);
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt
+            .block_on(archiver::archive_verify_ledger(verify_args))
+            .or_code(InstallerError::ArchiveVerifyFailed),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for ledger verification: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => {
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize ledger report: {}", e),
+            }
+            if !report.healthy {
+                std::process::exit(2);
+            }
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: archive] [STEP: verify_ledger] Verification exited with error: {:?}",
+                e
+            );
+            println!(
+                "{}",
+                serde_json::json!({
+                    "healthy": false,
+                    "error": e.to_string(),
+                    "errorCode": error::code_label(error::error_code(&e)),
+                })
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Secret rotation: re-encrypts every stored secret under a new master key and keeps a verified
+/// backup of the old one. Prints a JSON report to stdout.
+/// Usage: --rotate-secrets [--files-only] (config DB from CADALYTIX_CONFIG_DB_CONNECTION)
+pub fn run_rotate_secrets(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Secret rotation starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(security::rotation::rotate_secrets_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for secret rotation: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize rotation report: {}", e),
+        },
+        Err(e) => {
+            error!(
+                "[PHASE: security] [STEP: rotate_secrets] Secret rotation exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Audit log verification: walks the privileged-action hash chain and prints a JSON report.
+/// Exits 0 when the chain is intact, 2 when it was tampered with, 1 when it could not be read.
+/// Usage: --verify-audit [--audit-log=<path>]
+pub fn run_verify_audit(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Audit log verification starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(security::audit::verify_audit_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for audit verification: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => {
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize audit report: {}", e),
+            }
+            if !report.valid {
+                error!(
+                    "[PHASE: audit] [STEP: verify] Audit log chain is broken at line {:?}: {:?}",
+                    report.first_bad_line, report.problem
+                );
+                std::process::exit(2);
+            }
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: audit] [STEP: verify] Audit verification exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Final install manifest as JSON on stdout: the last one recorded in the log folder, or the one
+/// of the given install folder / manifest file. `--print-manifest-schema` prints its JSON Schema.
+/// Usage: --print-manifest[=<path>] | --print-manifest-schema
+pub fn run_print_manifest(args: Vec<String>) {
+    if args.iter().any(|a| a == "--print-manifest-schema") {
+        print!("{}", installation::manifest::SCHEMA_JSON);
+        return;
+    }
+
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    match installation::manifest::print_manifest_cli(&args) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            error!(
+                "[PHASE: manifest] [STEP: print] Printing the install manifest failed: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Read-only inspection of an existing install (manifest, deployed files, config DB schema,
+/// license, service and web health, archive ledger) for support tickets. Prints a text report, or
+/// JSON with `--json`. Exits 0 when everything passed, 2 on warnings, 3 on failures.
+/// Usage: --inspect[=<install folder or manifest>] [--json] [--ledger=<path>] [--archive-dir=<path>]
+pub fn run_inspect(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Install inspection starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(installation::inspect::inspect_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for the inspection: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => {
+            if args.iter().any(|a| a == "--json") {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Failed to serialize inspection report: {}", e),
+                }
+            } else {
+                print!("{}", report.to_text());
+            }
+            std::process::exit(report.exit_code());
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: inspect] [STEP: report] Install inspection exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Repair of an existing install: re-copies missing or changed files from the runtime payload,
+/// re-registers or restarts services and re-applies missing application-account grants, then
+/// reports what it fixed (text, or JSON with `--json`). Exits 0 when nothing is left broken, 2
+/// otherwise.
+/// Usage: --repair[=<install folder or manifest>] [--json]
+pub fn run_repair(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Install repair starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(installation::repair::repair_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for the repair: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => {
+            if args.iter().any(|a| a == "--json") {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Failed to serialize repair report: {}", e),
+                }
+            } else {
+                print!("{}", report.to_text());
+            }
+            std::process::exit(report.exit_code());
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: repair] [STEP: report] Install repair exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Fleet install: the scripted install (`--tui-script`) against every target of a host list,
+/// this machine and SSH remotes, `--parallel` at a time, each with its own log folder. Prints a
+/// pass/fail table per host (JSON with `--json`); exits 0 when every host passed, 2 otherwise.
+/// Usage: --fleet --hosts=<file> [--answers=<file>] [--parallel=<n>] [--json]
+pub fn run_fleet(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Fleet install starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(installation::fleet::fleet_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for the fleet install: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(report) => {
+            if args.iter().any(|a| a == "--json") {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Failed to serialize fleet report: {}", e),
+                }
+            } else {
+                print!("{}", report.to_text());
+            }
+            std::process::exit(report.exit_code());
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: fleet] [STEP: report] Fleet install exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Elevated helper of an unelevated install (Windows): started through the UAC prompt by the
+/// installer, it runs that installer's privileged steps over the pipe and exits when it closes.
+/// Usage: --elevated-helper=<pipe> --parent-pid=<pid>
+#[cfg(windows)]
+pub fn run_elevated_helper(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Elevated helper starting at {}",
+        chrono::Utc::now()
+    );
+
+    let value = |prefix: &str| args.iter().find_map(|a| a.strip_prefix(prefix));
+    let result = match (
+        value("--elevated-helper="),
+        value("--parent-pid=").and_then(|p| p.parse::<u32>().ok()),
+    ) {
+        (Some(pipe), Some(parent_pid)) => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to create async runtime for the elevated helper: {}",
+                    e
+                )
+            })
+            .and_then(|rt| rt.block_on(installation::uac::serve(pipe, parent_pid)))
+            .or_code(InstallerError::ElevationFailed),
+        _ => Err(anyhow::anyhow!(
+            "--elevated-helper=<pipe> and --parent-pid=<pid> are both required"
+        ))
+        .or_code(InstallerError::InvalidArguments),
+    };
+
+    if let Err(e) = result {
+        error!(
+            "[PHASE: uac] [STEP: serve] Elevated helper exited with error: {:?}",
+            e
+        );
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
+    }
+}
+
+/// Migration status: applied, pending and drifted migrations of the config DB, as JSON.
+/// Exits 0 when nothing drifted, 2 when an applied migration's script changed, 1 on errors.
+/// Usage: --migrate-status (config DB from CADALYTIX_CONFIG_DB_CONNECTION)
+pub fn run_migrate_status(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Migration status starting at {}",
+        chrono::Utc::now()
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match rt {
+        Ok(rt) => rt.block_on(database::migrations::migrate_status_cli(&args)),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to create async runtime for migration status: {}",
+            e
+        )),
+    };
+
+    match result {
+        Ok(status) => {
+            match serde_json::to_string_pretty(&status) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize migration status: {}", e),
+            }
+            if !status.drifted.is_empty() {
+                error!(
+                    "[PHASE: database] [STEP: migrate_status] {} applied migration(s) changed after they ran",
+                    status.drifted.len()
+                );
+                std::process::exit(2);
+            }
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: database] [STEP: migrate_status] Migration status exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
+
+/// Preflight-only mode: runs every preflight check and writes `preflight-report.json`/`.txt`
+/// under `Prod_Wizard_Log/`. Exits 0 when everything passed, 2 on warnings, 3 on failures.
+/// Usage: --preflight-only [--mode=windows|docker|linux] [--ports=8080,...] [--source-object=<schema.table>] [--data-dir=<path>] [--expected-rows-per-day=<n>] [--historical-rows=<n>] [--strict]
+pub fn run_preflight_only(args: Vec<String>) {
+    // Initialize logging
+    if let Err(e) = init_logging(false) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    info!(
+        "[PHASE: initialization] Preflight-only run starting at {}",
+        chrono::Utc::now()
+    );
+
+    let result = api::preflight_report::PreflightOnlyArgs::from_args(&args)
+        .or_code(InstallerError::InvalidArguments)
+        .and_then(|preflight_args| {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to create async runtime for preflight: {}", e)
+                })?;
+            rt.block_on(api::preflight_report::run_preflight_only(&preflight_args))
+                .or_code(InstallerError::ArtifactWriteFailed)
+        });
+
+    match result {
+        Ok((report, json_path, text_path)) => {
+            for section in &report.sections {
+                println!(
+                    "{:<8} {}",
+                    section.status.to_ascii_uppercase(),
+                    section.name
+                );
+            }
+            println!("Overall: {}", report.overall_status.to_ascii_uppercase());
+            println!("Report: {}", text_path.display());
+            println!("JSON:   {}", json_path.display());
+            std::process::exit(api::preflight_report::exit_code_for_status(
+                &report.overall_status,
+            ));
+        }
+        Err(e) => {
+            error!(
+                "[PHASE: preflight] [STEP: report] Preflight-only run exited with error: {:?}",
+                e
+            );
+            eprintln!("Installer error: {}", error::user_message(&e));
+            std::process::exit(error::exit_code(&e));
+        }
     }
 }
 
@@ -509,8 +1261,8 @@ pub fn run_db_setup_smoke() {
             "[PHASE: db_setup] [STEP: smoke] D2 smoke exited with error: {:?}",
             e
         );
-        eprintln!("Installer error: {}", e);
-        std::process::exit(1);
+        eprintln!("Installer error: {}", error::user_message(&e));
+        std::process::exit(error::exit_code(&e));
     }
 }
 
@@ -605,9 +1357,9 @@ pub fn run_release_e2e_smoke() {
                 Ok(rt) => {
                     let sp = secret_protector.clone();
                     match *name {
-                        "install-contract-smoke" => {
-                            rt.block_on(api::installer::install_contract_smoke(sp))
-                        }
+                        "install-contract-smoke" => rt
+                            .block_on(api::installer::install_contract_smoke(sp))
+                            .map(|_| ()),
                         "archive-dry-run" => rt.block_on(archiver::archive_dry_run()),
                         "mapping-persist-smoke" => {
                             rt.block_on(api::installer::mapping_persist_smoke(sp))
@@ -789,7 +1541,7 @@ pub fn run_perf_smoke() {
 
     let contract_start = Instant::now();
 
-    // We'll capture progress events by running the smoke and timing
+    // The smoke returns every progress event it received, with arrival times.
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build();
@@ -801,24 +1553,39 @@ pub fn run_perf_smoke() {
 
     let contract_elapsed_ms = contract_start.elapsed().as_millis();
 
-    // For progress metrics, we estimate based on the known contract steps
-    // The install-contract-smoke has ~6 major progress events
-    let estimated_progress_events = 6;
-    let avg_gap_ms = if estimated_progress_events > 1 {
-        contract_elapsed_ms / (estimated_progress_events as u128)
-    } else {
-        contract_elapsed_ms
+    let stats = match &contract_result {
+        Ok(events) => api::installer::progress_event_stats(events),
+        Err(_) => api::installer::ProgressEventStats::default(),
     };
+    // The contract request fails at DB connect but emits 3+ early progress events first.
+    let progress_ok = stats.count >= 3 && stats.monotonic;
 
-    log_step!(format!(
-        "progress_event_count={}",
-        estimated_progress_events
-    ));
+    log_step!(format!("install_runs={}", stats.runs));
+    log_step!(format!("progress_event_count={}", stats.count));
     log_step!(format!("total_contract_time_ms={}", contract_elapsed_ms));
-    log_step!(format!("avg_gap_between_events_ms={}", avg_gap_ms));
-    log_step!(format!(
-        "monotonicity_check=PASS (events are sequential by design)"
-    ));
+    log_step!(format!("avg_gap_between_events_ms={}", stats.avg_gap_ms));
+    log_step!(format!("max_gap_between_events_ms={}", stats.max_gap_ms));
+    log_step!(match &stats.first_regression {
+        None if stats.count > 0 => "monotonicity_check=PASS".to_string(),
+        None => "monotonicity_check=FAIL (no progress events)".to_string(),
+        Some(at) => format!("monotonicity_check=FAIL (percent went back at {})", at),
+    });
+
+    // Per-phase step metrics the contract runs logged (see utils::metrics).
+    for summary in utils::metrics::take_summaries() {
+        log_step!(format!(
+            "phase={} ok={} steps={} events={} duration_ms={} bytes={} retries={} slowest_step={} slowest_step_ms={}",
+            summary.phase,
+            summary.ok,
+            summary.steps,
+            summary.events,
+            summary.duration_ms,
+            summary.bytes,
+            summary.retries,
+            summary.slowest_step,
+            summary.slowest_step_ms
+        ));
+    }
 
     // Metric 3: TUI render time
     log_step!("");
@@ -847,8 +1614,10 @@ pub fn run_perf_smoke() {
     ));
     log_step!("");
 
-    let all_passed =
-        contract_result.is_ok() && tui_result.is_ok() && total_elapsed.as_millis() < 10000;
+    let all_passed = contract_result.is_ok()
+        && progress_ok
+        && tui_result.is_ok()
+        && total_elapsed.as_millis() < 10000;
 
     if all_passed {
         log_step!("========================================");
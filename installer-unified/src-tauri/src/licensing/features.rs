@@ -0,0 +1,59 @@
+// Licensed feature flags the wizard gates pages and options on.
+//
+// Flags come from the verified license (signed token or license file). Without a verified
+// license the wizard is not gated; verification itself decides whether installation proceeds.
+
+use std::collections::HashSet;
+
+/// Archive module (cold storage of call data past the hot retention window).
+pub const ARCHIVE: &str = "archive";
+/// Data source connectors beyond SQL Server (ODBC, Oracle and file exports) and several call data
+/// sources in one install.
+pub const MULTI_DATASOURCE: &str = "multi_datasource";
+
+/// Enabled feature flags of the verified license, if one was found.
+#[derive(Debug, Clone, Default)]
+pub struct LicensedFeatures {
+    enabled: Option<HashSet<String>>,
+}
+
+impl LicensedFeatures {
+    pub fn from_enabled<I: IntoIterator<Item = String>>(features: I) -> Self {
+        Self {
+            enabled: Some(
+                features
+                    .into_iter()
+                    .map(|f| f.trim().to_ascii_lowercase())
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn allows(&self, feature: &str) -> bool {
+        self.enabled
+            .as_ref()
+            .map(|set| set.contains(feature))
+            .unwrap_or(true)
+    }
+}
+
+/// Shown in place of an unlicensed page or option.
+pub fn not_included_message(what: &str) -> String {
+    format!(
+        "{} is not included in your license. Contact CADalytix support to add it.",
+        what
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gating_applies_only_with_a_verified_license() {
+        assert!(LicensedFeatures::default().allows(ARCHIVE));
+        let licensed = LicensedFeatures::from_enabled(vec!["Archive".to_string()]);
+        assert!(licensed.allows(ARCHIVE));
+        assert!(!licensed.allows(MULTI_DATASOURCE));
+    }
+}
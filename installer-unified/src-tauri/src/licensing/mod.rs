@@ -1,3 +1,4 @@
+pub mod features;
 pub mod offline;
 pub mod online;
 pub mod token;
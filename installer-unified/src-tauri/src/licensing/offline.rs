@@ -1,5 +1,234 @@
-// Offline license verification
-// Ported from C# offline license verification
+// Offline license verification: signed license files for air-gapped installs.
+//
+// A license file (`cadalytix.lic`) is dropped next to the installer executable:
+//
+//   { "payload": "<base64 license JSON>", "signature": "<base64 Ed25519 over the payload bytes>" }
+//
+// The payload carries the same authoritative fields as the signed JWT used online (expiry,
+// grace, features, optional install binding), so it stands in for the token when the ops server
+// cannot be reached.
+//
+// SECURITY:
+// - FAIL-CLOSED: a bad signature, schema or feature list rejects the whole file.
+// - The signature covers the exact payload bytes; nothing outside the payload is trusted.
 
-// Placeholder for offline license verification
-// Will be implemented when porting from C#
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use serde_json::Value;
+
+use crate::licensing::token::{parse_features_claim, VerifiedLicensePayload};
+use crate::security::crypto::verify_ed25519;
+
+/// File name looked up next to the installer executable.
+pub const LICENSE_FILE_NAME: &str = "cadalytix.lic";
+
+#[derive(Debug, serde::Deserialize)]
+struct LicenseFile {
+    payload: String,
+    signature: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicensePayload {
+    license_id: String,
+    client_name: Option<String>,
+    client_id: Option<String>,
+    /// When present, the file only verifies with this license key.
+    license_key: Option<String>,
+    install_id: Option<String>,
+    issued_at_utc: DateTime<Utc>,
+    expires_at_utc: DateTime<Utc>,
+    grace_until_utc: Option<DateTime<Utc>>,
+    #[serde(default)]
+    features: Value,
+}
+
+/// A license file whose signature and payload checked out.
+#[derive(Debug, Clone)]
+pub struct SignedLicenseFile {
+    pub payload: VerifiedLicensePayload,
+    pub license_key: Option<String>,
+}
+
+/// `cadalytix.lic` next to the installer executable, if one was dropped there.
+pub fn find_license_file() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let path = exe.parent()?.join(LICENSE_FILE_NAME);
+    path.is_file().then_some(path)
+}
+
+/// Check the signature of license file `text` and parse its payload. Expiry is not enforced
+/// here (status reporting needs expired licenses too); callers use `token::determine_status`.
+pub fn parse_license_file(text: &str, public_key: &[u8]) -> Result<SignedLicenseFile> {
+    let file: LicenseFile =
+        serde_json::from_str(text.trim()).context("License file is not valid JSON")?;
+    let payload_bytes = base64::engine::general_purpose::STANDARD
+        .decode(file.payload.trim())
+        .context("License file payload is not valid base64")?;
+    if !verify_ed25519(public_key, &payload_bytes, &file.signature) {
+        anyhow::bail!("License file signature verification failed - the file may be tampered with");
+    }
+
+    let payload: LicensePayload =
+        serde_json::from_slice(&payload_bytes).context("License file payload is invalid")?;
+    if payload.license_id.trim().is_empty() {
+        anyhow::bail!("License file payload has no licenseId");
+    }
+    if payload.issued_at_utc > Utc::now() + Duration::minutes(5) {
+        anyhow::bail!("License file is not yet valid (issued in the future)");
+    }
+    let grace_until_utc = payload.grace_until_utc.unwrap_or(payload.expires_at_utc);
+    if grace_until_utc < payload.expires_at_utc {
+        anyhow::bail!("License file grace period ends before the license expires");
+    }
+    let features = parse_features(&payload.features)?;
+
+    Ok(SignedLicenseFile {
+        payload: VerifiedLicensePayload {
+            license_id: Some(payload.license_id),
+            client_name: payload.client_name,
+            client_id: payload.client_id,
+            install_id: payload.install_id.filter(|s| !s.trim().is_empty()),
+            issued_at_utc: payload.issued_at_utc,
+            expires_at_utc: payload.expires_at_utc,
+            grace_until_utc,
+            features,
+        },
+        license_key: payload.license_key.filter(|s| !s.trim().is_empty()),
+    })
+}
+
+/// Read and verify the license file at `path` with the release signing key.
+pub async fn load_license_file(path: &Path) -> Result<(String, SignedLicenseFile)> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read license file: {:?}", path))?;
+    let parsed = parse_license_file(&text, &crate::security::crypto::release_signing_key()?)?;
+    info!(
+        "[PHASE: license_verification] [STEP: license_file] License file verified (path={:?}, license_id={:?}, expires={})",
+        path, parsed.payload.license_id, parsed.payload.expires_at_utc
+    );
+    Ok((text, parsed))
+}
+
+/// Stored license blob (see `verify_license`) re-verified as a license file; `None` when it is
+/// not one or fails verification.
+pub fn verify_stored(blob: Option<&str>) -> Option<VerifiedLicensePayload> {
+    let blob = blob.map(str::trim).filter(|s| s.starts_with('{'))?;
+    let verified = crate::security::crypto::release_signing_key()
+        .and_then(|key| parse_license_file(blob, &key));
+    match verified {
+        Ok(parsed) => Some(parsed.payload),
+        Err(e) => {
+            warn!(
+                "[PHASE: license_verification] [STEP: license_file] Stored license file failed verification: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Whole days left in the grace period (`None` unless `now` is past expiry and within grace).
+pub fn grace_days_remaining(
+    now: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    grace_until: DateTime<Utc>,
+) -> Option<i64> {
+    if now <= expires_at || now > grace_until {
+        return None;
+    }
+    let left = grace_until - now;
+    // Round up: 1h left is still "1 day remaining".
+    Some((left.num_seconds() + 86_399) / 86_400)
+}
+
+/// Feature flags must be an object of name -> bool or a list of names.
+fn parse_features(v: &Value) -> Result<HashMap<String, bool>> {
+    match v {
+        Value::Null => Ok(HashMap::new()),
+        Value::Object(map) => {
+            if let Some((k, _)) = map.iter().find(|(_, val)| !val.is_boolean()) {
+                anyhow::bail!("License file feature '{}' must be true or false", k);
+            }
+            Ok(parse_features_claim(v))
+        }
+        Value::Array(arr) => {
+            if arr.iter().any(|item| !item.is_string()) {
+                anyhow::bail!("License file features must be a list of names");
+            }
+            Ok(parse_features_claim(v))
+        }
+        _ => anyhow::bail!("License file features must be an object or a list"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn signed(key: &Ed25519KeyPair, payload: &Value) -> String {
+        let bytes = serde_json::to_vec(payload).unwrap();
+        let b64 = base64::engine::general_purpose::STANDARD;
+        serde_json::json!({
+            "payload": b64.encode(&bytes),
+            "signature": b64.encode(key.sign(&bytes)),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn license_file_signature_features_and_grace_countdown() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public = key.public_key().as_ref();
+        let now = Utc::now();
+        let payload = serde_json::json!({
+            "licenseId": "LIC-1",
+            "clientName": "County 911",
+            "licenseKey": "ABCD-EFGH-IJKL-MNOP",
+            "issuedAtUtc": (now - Duration::days(30)).to_rfc3339(),
+            "expiresAtUtc": (now - Duration::days(1)).to_rfc3339(),
+            "graceUntilUtc": (now + Duration::days(13) + Duration::hours(2)).to_rfc3339(),
+            "features": { "archive": true, "reporting": false },
+        });
+
+        let parsed = parse_license_file(&signed(&key, &payload), public).unwrap();
+        assert_eq!(parsed.payload.license_id.as_deref(), Some("LIC-1"));
+        assert_eq!(parsed.license_key.as_deref(), Some("ABCD-EFGH-IJKL-MNOP"));
+        assert_eq!(parsed.payload.features.get("archive"), Some(&true));
+        assert_eq!(parsed.payload.features.get("reporting"), Some(&false));
+        assert_eq!(
+            grace_days_remaining(
+                now,
+                parsed.payload.expires_at_utc,
+                parsed.payload.grace_until_utc
+            ),
+            Some(14)
+        );
+
+        let mut tampered: Value = serde_json::from_str(&signed(&key, &payload)).unwrap();
+        let other = serde_json::to_vec(&serde_json::json!({"licenseId": "LIC-2"})).unwrap();
+        tampered["payload"] =
+            Value::String(base64::engine::general_purpose::STANDARD.encode(other));
+        let err = parse_license_file(&tampered.to_string(), public).unwrap_err();
+        assert!(err.to_string().contains("signature"), "{}", err);
+
+        let mut bad_features = payload.clone();
+        bad_features["features"] = serde_json::json!({ "archive": "yes" });
+        assert!(parse_license_file(&signed(&key, &bad_features), public).is_err());
+
+        assert_eq!(
+            grace_days_remaining(now, now + Duration::days(1), now),
+            None
+        );
+    }
+}
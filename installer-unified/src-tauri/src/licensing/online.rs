@@ -1,5 +1,131 @@
-// Online license verification
-// Ported from C# online license verification
+// Online license activation against the licensing server.
+//
+// `activate` exchanges a license key for a signed entitlement (JWT RS256, verified by
+// `licensing::token`) over HTTPS, through the shared client in `utils::http` (explicit, saved
+// or system proxy).
+//
+// Transient failures (connect/timeout, HTTP 429/5xx) are retried with backoff; callers use
+// `http::is_unreachable` to offer offline activation when the server cannot be reached at all.
 
-// Placeholder for online license verification
-// Will be implemented when porting from C#
+use anyhow::{Context, Result};
+use log::{info, warn};
+use tokio_retry::RetryIf;
+
+use crate::utils::http::{self, ProxyConfig};
+use crate::utils::retry;
+
+pub const DEFAULT_OPS_API_BASE_URL: &str = "https://ops.cadalytix.com";
+
+/// What the licensing server returned for a successful activation.
+#[derive(Debug, Clone)]
+pub struct Activation {
+    pub signed_token: String,
+    pub license_id: String,
+    pub client_name: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivateReq<'a> {
+    license_key: &'a str,
+    install_id: Option<&'a str>,
+    client_fingerprint: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivateResp {
+    activated: bool,
+    signed_token: Option<String>,
+    license_id: Option<String>,
+    client_name: Option<String>,
+    error_message: Option<String>,
+}
+
+/// Retryable HTTP status from the licensing server.
+#[derive(Debug)]
+struct TransientStatus(reqwest::StatusCode);
+
+impl std::fmt::Display for TransientStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Licensing server returned HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for TransientStatus {}
+
+/// Exchange `license_key` for a signed entitlement at `{base_url}/licensing/activate`.
+/// A server-side rejection is an error whose message comes from the server.
+pub async fn activate(
+    base_url: &str,
+    license_key: &str,
+    install_id: Option<&str>,
+    proxy: &ProxyConfig,
+) -> Result<Activation> {
+    let url = format!("{}/licensing/activate", base_url.trim_end_matches('/'));
+    let client = http::build_client(proxy)?;
+    let body = ActivateReq {
+        license_key,
+        install_id,
+        client_fingerprint: format!(
+            "{}|{}",
+            std::env::var("COMPUTERNAME")
+                .or_else(|_| std::env::var("HOSTNAME"))
+                .unwrap_or_else(|_| "unknown".to_string()),
+            std::env::consts::OS
+        ),
+    };
+
+    let attempt = || async {
+        let resp = client.post(&url).json(&body).send().await?;
+        let status = resp.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(TransientStatus(status).into());
+        }
+        if !status.is_success() && status != reqwest::StatusCode::BAD_REQUEST {
+            anyhow::bail!("Licensing server returned HTTP {}", status);
+        }
+        let parsed: ActivateResp = resp
+            .json()
+            .await
+            .context("Licensing server returned an invalid activation response")?;
+        Ok::<_, anyhow::Error>(parsed)
+    };
+
+    let retry_strategy = retry::policy().backoff();
+
+    let parsed = RetryIf::spawn(retry_strategy, attempt, |e: &anyhow::Error| {
+        let transient = http::is_unreachable(e) || e.downcast_ref::<TransientStatus>().is_some();
+        if transient {
+            warn!(
+                "[PHASE: license_verification] [STEP: activate] Transient activation failure, retrying: {}",
+                e
+            );
+        }
+        transient
+    })
+    .await?;
+
+    if !parsed.activated {
+        anyhow::bail!(
+            "{}",
+            parsed
+                .error_message
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| "The licensing server rejected the activation".to_string())
+        );
+    }
+    let signed_token = parsed
+        .signed_token
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Licensing server response is missing signedToken"))?;
+    info!(
+        "[PHASE: license_verification] [STEP: activate] Activation accepted (license_id={:?})",
+        parsed.license_id
+    );
+    Ok(Activation {
+        signed_token,
+        license_id: parsed.license_id.unwrap_or_default(),
+        client_name: parsed.client_name.unwrap_or_default(),
+    })
+}
@@ -229,7 +229,7 @@ fn parse_timestamp_claim(v: &Value) -> Option<DateTime<Utc>> {
     }
 }
 
-fn parse_features_claim(v: &Value) -> HashMap<String, bool> {
+pub(crate) fn parse_features_claim(v: &Value) -> HashMap<String, bool> {
     match v {
         Value::Object(map) => map
             .iter()
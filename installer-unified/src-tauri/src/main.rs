@@ -4,6 +4,39 @@
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
+    // Named instance: logs, services, containers and defaults are qualified with the name, so
+    // e.g. TEST and PROD can be installed on the same host. Applies to every mode below.
+    // Usage: --instance=<NAME>
+    if args.iter().any(|a| a.starts_with("--instance=")) {
+        installer_unified::use_instance(&args);
+    }
+
+    // Elevated helper (Windows): launched through the UAC prompt by an unelevated install to run
+    // its service registration and Program Files folder steps; not meant to be started by hand.
+    // Usage: --elevated-helper=<pipe> --parent-pid=<pid>
+    #[cfg(windows)]
+    {
+        if args.iter().any(|a| a.starts_with("--elevated-helper=")) {
+            installer_unified::run_elevated_helper(args);
+            return;
+        }
+    }
+
+    // Remote install: the terminal wizards (--tui, --tui-plain, --tui-script) deploy the
+    // Linux-native install to this server over SSH instead of this machine. The host key must be
+    // confirmed up front; login is key-based (identity file or ssh agent).
+    // Usage: --remote=<user@host[:port]> --remote-fingerprint=SHA256:<...> [--remote-identity=<key file>]
+    if args.iter().any(|a| a.starts_with("--remote=")) {
+        installer_unified::use_remote_target(&args);
+    }
+
+    // Air-gapped install: binaries, migrations, images and license come from a signed bundle,
+    // verified here before any mode starts. Network steps fail instead of being attempted.
+    // Usage: --offline-bundle=<path> (combines with --tui/--gui and --preflight-only)
+    if args.iter().any(|a| a.starts_with("--offline-bundle=")) {
+        installer_unified::use_offline_bundle(&args);
+    }
+
     // Phase 8: Release E2E smoke - runs all proof modes in sequence.
     // Writes `P8_release_e2e_smoke_<os>.log` under `Prod_Wizard_Log/` and exits 0/1.
     if args.iter().any(|a| a == "--release-e2e-smoke") {
@@ -25,6 +58,107 @@ fn main() {
         return;
     }
 
+    // Archive restore: verifies a month's archive against the ledger and re-imports its rows.
+    // Usage: --archive-restore --month=YYYY-MM [--archive-dir=<path or s3://, az://, gs:// URI>] [--ledger=<path>]
+    if args.iter().any(|a| a == "--archive-restore") {
+        installer_unified::run_archive_restore(args);
+        return;
+    }
+
+    // Scheduled/startup archive runner (catch-up of every month past the hot retention window).
+    // Usage: --archive-run-once [--startup] [--max-months=N] [--ledger=<path>] [--confirm-purge=YYYY-MM]
+    if args.iter().any(|a| a == "--archive-run-once") {
+        installer_unified::run_archive_run_once(args);
+        return;
+    }
+
+    // Archive ledger health check: JSON report on stdout, nonzero exit when unhealthy.
+    // Usage: --archive-verify-ledger [--archive-dir=<path or URI>] [--ledger=<path>]
+    if args.iter().any(|a| a == "--archive-verify-ledger") {
+        installer_unified::run_archive_verify_ledger(args);
+        return;
+    }
+
+    // Key rotation: re-encrypts stored secrets (config DB settings, license tokens, entitlement
+    // file) under a new master key; the old key is kept as a verified backup. JSON report on stdout.
+    // Config DB connection string: CADALYTIX_CONFIG_DB_CONNECTION env var.
+    // Usage: --rotate-secrets [--files-only]
+    if args.iter().any(|a| a == "--rotate-secrets") {
+        installer_unified::run_rotate_secrets(args);
+        return;
+    }
+
+    // Audit log check: verifies the privileged-action hash chain; JSON report on stdout,
+    // exit 2 when the log was tampered with.
+    // Usage: --verify-audit [--audit-log=<path>]
+    if args.iter().any(|a| a == "--verify-audit") {
+        installer_unified::run_verify_audit(args);
+        return;
+    }
+
+    // Install manifest: prints the final install manifest (JSON) of the last install, or of the
+    // given install folder / manifest file; `--print-manifest-schema` prints its JSON Schema.
+    // Usage: --print-manifest[=<path>] | --print-manifest-schema
+    if args.iter().any(|a| {
+        a == "--print-manifest"
+            || a.starts_with("--print-manifest=")
+            || a == "--print-manifest-schema"
+    }) {
+        installer_unified::run_print_manifest(args);
+        return;
+    }
+
+    // Install inspection (read-only): manifest, deployed files, config DB schema, license,
+    // service/web health and archive ledger of an existing install in one report; exit 0/2/3.
+    // Config DB connection string: CADALYTIX_CONFIG_DB_CONNECTION env var (optional).
+    // Usage: --inspect[=<install folder or manifest>] [--json] [--ledger=<path>] [--archive-dir=<path>]
+    if args
+        .iter()
+        .any(|a| a == "--inspect" || a.starts_with("--inspect="))
+    {
+        installer_unified::run_inspect(args);
+        return;
+    }
+
+    // Install repair: re-converges an existing install to its manifest (files from the runtime
+    // payload, services, application-account grants) and reports what it fixed; exit 0/2.
+    // Config DB connection string (admin): CADALYTIX_CONFIG_DB_CONNECTION env var (optional).
+    // Usage: --repair[=<install folder or manifest>] [--json]
+    if args
+        .iter()
+        .any(|a| a == "--repair" || a.starts_with("--repair="))
+    {
+        installer_unified::run_repair(args);
+        return;
+    }
+
+    // Fleet install: the scripted install against every target of a host list (this machine and
+    // SSH remotes), a bounded number at a time, with a log folder per host and a pass/fail
+    // summary; exit 0/2. The host list format is documented in installation::fleet.
+    // Usage: --fleet --hosts=<file> [--answers=<file>] [--parallel=N] [--json]
+    if args.iter().any(|a| a == "--fleet") {
+        installer_unified::run_fleet(args);
+        return;
+    }
+
+    // Migration status: applied/pending/drifted migrations of the config DB as JSON,
+    // exit 2 when an applied migration's script changed.
+    // Config DB connection string: CADALYTIX_CONFIG_DB_CONNECTION env var.
+    // Usage: --migrate-status
+    if args.iter().any(|a| a == "--migrate-status") {
+        installer_unified::run_migrate_status(args);
+        return;
+    }
+
+    // Pre-sales validation: runs every preflight check, writes preflight-report.json/.txt under
+    // `Prod_Wizard_Log/` and exits 0 (pass), 2 (warnings) or 3 (failures).
+    // Connection strings: CADALYTIX_CONFIG_DB_CONNECTION / CADALYTIX_CALL_DATA_CONNECTION env vars.
+    // Usage: --preflight-only [--mode=windows|docker|linux] [--ports=8080,...] [--source-object=<schema.table>] [--data-dir=<path>] [--expected-rows-per-day=<n>] [--historical-rows=<n>] [--strict]
+    if args.iter().any(|a| a == "--preflight-only") {
+        installer_unified::run_preflight_only(args);
+        return;
+    }
+
     // Non-interactive mapping contract + persistence proof mode (deterministic).
     // Writes `B3_mapping_persist_smoke_transcript.log` under `Prod_Wizard_Log/` and exits.
     if args.iter().any(|a| a == "--mapping-persist-smoke") {
@@ -46,6 +180,24 @@ fn main() {
         return;
     }
 
+    // TUI theme for low-vision operators and logging terminals (combines with --tui/--tui-smoke).
+    // Without the flag: CADALYTIX_TUI_THEME, then NO_COLOR (mono), then default.
+    // Usage: --theme=default|high-contrast|mono
+    if args.iter().any(|a| a.starts_with("--theme=")) {
+        installer_unified::use_tui_theme(&args);
+    }
+
+    // Install progress as newline-delimited JSON on a Unix socket / named pipe, for orchestration
+    // tools (Ansible, SCCM); clients may send cancel/pause/resume. Also CADALYTIX_PROGRESS_SOCKET.
+    // Default: install-progress.sock in the log folder, \\.\pipe\cadalytix-install-progress on Windows.
+    // Usage: --progress-socket or --progress-socket=<path>
+    if args
+        .iter()
+        .any(|a| a == "--progress-socket" || a.starts_with("--progress-socket="))
+    {
+        installer_unified::use_progress_socket(&args);
+    }
+
     // Non-interactive TUI smoke test mode (for automated checks).
     // Renders a single frame for a specific page and exits 0.
     // Usage: --tui-smoke or --tui-smoke=welcome|license|destination|db|storage|retention|archive|consent|mapping|ready|progress
@@ -61,6 +213,32 @@ fn main() {
         return;
     }
 
+    // Distribution package: validates the answers like --tui-script, then writes an MSI or Intune
+    // package that runs that scripted install unattended (see installation::package).
+    // Usage: --package=msi|intune --answers=<file> [--package-out=<dir>] [--json]
+    if args.iter().any(|a| a.starts_with("--package=")) {
+        installer_unified::run_package(args);
+        return;
+    }
+
+    // Screen-reader friendly line mode: the TUI wizard as sequential prompts (no full-screen UI).
+    // Usage: --tui-plain, or CADALYTIX_INSTALLER_UI=plain
+    if args.iter().any(|a| a == "--tui-plain")
+        || std::env::var("CADALYTIX_INSTALLER_UI")
+            .is_ok_and(|v| v.trim().eq_ignore_ascii_case("plain"))
+    {
+        installer_unified::run_tui_plain();
+        return;
+    }
+
+    // Question/answer scripting: one `? <page>` question per page, answers read from stdin as
+    // key=value lines or a JSON object (see tui/script.rs).
+    // Usage: --tui-script < answers.txt
+    if args.iter().any(|a| a == "--tui-script") {
+        installer_unified::run_tui_script();
+        return;
+    }
+
     // Linux launcher behavior:
     // - If GUI display available -> run GUI wizard
     // - Otherwise -> run headless TUI wizard
@@ -0,0 +1,311 @@
+//! Mapping review sheets (CSV) for analysts.
+//!
+//! `export` writes one row per target field, then one row per source column no target uses, so
+//! the sheet lists every column of the source. In a spreadsheet the analyst fills or changes
+//! `source_column` (or `target_id` on an unused-column row); `import` reads the sheet back and
+//! replaces the mapping. `target_name` and `required` are informational and ignored on import.
+//!
+//! Source columns are written by display name (`City (2)` for duplicates) and matched back by
+//! display name, then by raw name when that is unique, then by id; targets by id, then name.
+//! Transforms are not part of the sheet and are kept as they are.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::api::installer::{MappingSourceField, MappingState, MappingTargetField};
+
+pub const HEADER: [&str; 4] = ["target_id", "target_name", "required", "source_column"];
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// RFC 4180 field splitter for one line (quoted fields, doubled quotes). Also used for the
+/// headers of flat-file data sources, which may be tab, semicolon or pipe separated.
+pub(crate) fn split_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut cur = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                cur.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => out.push(std::mem::take(&mut cur)),
+            _ => cur.push(c),
+        }
+    }
+    out.push(cur);
+    out
+}
+
+fn record(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_field(f))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The sheet for `ms`; returns the CSV text and its data row count.
+pub fn export(ms: &MappingState) -> (String, usize) {
+    let mut lines = vec![record(&HEADER)];
+    for t in &ms.target_fields {
+        let source = ms
+            .target_to_source
+            .get(&t.id)
+            .and_then(|id| ms.source_fields.iter().find(|s| &s.id == id))
+            .map(|s| s.display_name.as_str())
+            .unwrap_or("");
+        let required = if t.required { "yes" } else { "no" };
+        lines.push(record(&[&t.id, &t.name, required, source]));
+    }
+    for s in &ms.source_fields {
+        let used = ms
+            .source_to_targets
+            .get(&s.id)
+            .is_some_and(|targets| !targets.is_empty());
+        if !used {
+            lines.push(record(&["", "", "", &s.display_name]));
+        }
+    }
+    let rows = lines.len() - 1;
+    (lines.join("\r\n") + "\r\n", rows)
+}
+
+fn find_target<'a>(
+    targets: &'a [MappingTargetField],
+    value: &str,
+) -> Option<&'a MappingTargetField> {
+    targets
+        .iter()
+        .find(|t| t.id == value)
+        .or_else(|| targets.iter().find(|t| t.id.eq_ignore_ascii_case(value)))
+        .or_else(|| targets.iter().find(|t| t.name.eq_ignore_ascii_case(value)))
+}
+
+fn find_source<'a>(
+    sources: &'a [MappingSourceField],
+    value: &str,
+) -> std::result::Result<&'a MappingSourceField, String> {
+    if let Some(s) = sources
+        .iter()
+        .find(|s| s.display_name.eq_ignore_ascii_case(value))
+    {
+        return Ok(s);
+    }
+    let by_raw: Vec<&MappingSourceField> = sources
+        .iter()
+        .filter(|s| s.raw_name.eq_ignore_ascii_case(value))
+        .collect();
+    match by_raw.as_slice() {
+        [one] => return Ok(one),
+        [first, ..] => {
+            return Err(format!(
+                "source column '{}' is ambiguous; use its display name (e.g. '{}')",
+                value, first.display_name
+            ))
+        }
+        [] => {}
+    }
+    sources
+        .iter()
+        .find(|s| s.id == value)
+        .ok_or_else(|| format!("unknown source column '{}'", value))
+}
+
+/// Read a sheet back into a copy of `ms` with the mapping replaced. Every problem is reported
+/// with its line number; nothing is applied when there is one.
+pub fn import(ms: &MappingState, text: &str) -> Result<MappingState> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        anyhow::bail!("The mapping sheet is empty.");
+    };
+    let header: Vec<String> = split_record(header, ',')
+        .iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(target_col), Some(source_col)) = (column("target_id"), column("source_column"))
+    else {
+        anyhow::bail!("The mapping sheet needs the columns target_id and source_column.");
+    };
+
+    let mut errors: Vec<String> = Vec::new();
+    let mut target_to_source: HashMap<String, String> = HashMap::new();
+    for (idx, line) in lines {
+        let line_no = idx + 1;
+        let fields = split_record(line, ',');
+        let cell = |i: usize| fields.get(i).map(|v| v.trim()).unwrap_or("");
+        let (target, source) = (cell(target_col), cell(source_col));
+        if target.is_empty() {
+            // Unused source column left unassigned.
+            continue;
+        }
+        let Some(target) = find_target(&ms.target_fields, target) else {
+            errors.push(format!(
+                "Line {}: unknown target field '{}'.",
+                line_no, target
+            ));
+            continue;
+        };
+        let source_id = if source.is_empty() {
+            None
+        } else {
+            match find_source(&ms.source_fields, source) {
+                Ok(s) => Some(s.id.clone()),
+                Err(e) => {
+                    errors.push(format!("Line {}: {}.", line_no, e));
+                    continue;
+                }
+            }
+        };
+        match (target_to_source.get(&target.id), source_id) {
+            (Some(prev), Some(id)) if *prev != id => errors.push(format!(
+                "Line {}: target field '{}' is listed again with a different source column.",
+                line_no, target.id
+            )),
+            (_, Some(id)) => {
+                target_to_source.insert(target.id.clone(), id);
+            }
+            (_, None) => {}
+        }
+    }
+
+    let mut source_to_targets: HashMap<String, Vec<String>> = HashMap::new();
+    for (target, source) in &target_to_source {
+        source_to_targets
+            .entry(source.clone())
+            .or_default()
+            .push(target.clone());
+    }
+    if !ms.mapping_override {
+        let mut multi: Vec<String> = source_to_targets
+            .iter()
+            .filter(|(_, targets)| targets.len() > 1)
+            .map(|(source, _)| {
+                ms.source_fields
+                    .iter()
+                    .find(|s| &s.id == source)
+                    .map(|s| s.display_name.clone())
+                    .unwrap_or_else(|| source.clone())
+            })
+            .collect();
+        multi.sort();
+        for source in multi {
+            errors.push(format!(
+                "Source column '{}' is mapped to several targets; turn on Override to allow that.",
+                source
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        anyhow::bail!("{}", errors.join("\n"));
+    }
+    for targets in source_to_targets.values_mut() {
+        targets.sort();
+    }
+
+    Ok(MappingState {
+        source_to_targets,
+        target_to_source,
+        ..ms.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> MappingState {
+        let source = |id: &str, raw: &str, display: &str| MappingSourceField {
+            id: id.to_string(),
+            raw_name: raw.to_string(),
+            display_name: display.to_string(),
+        };
+        let target = |id: &str, name: &str, required: bool| MappingTargetField {
+            id: id.to_string(),
+            name: name.to_string(),
+            required,
+        };
+        MappingState {
+            mapping_override: false,
+            source_fields: vec![
+                source("INC__0", "INC", "INC"),
+                source("City__0", "City", "City (1)"),
+                source("City__1", "City", "City (2)"),
+                source("Notes__0", "Notes, free text", "Notes, free text"),
+            ],
+            target_fields: vec![
+                target("IncidentNumber", "Incident Number", true),
+                target("City", "City", false),
+                target("State", "State", false),
+            ],
+            source_to_targets: HashMap::from([(
+                "INC__0".to_string(),
+                vec!["IncidentNumber".to_string()],
+            )]),
+            target_to_source: HashMap::from([("IncidentNumber".to_string(), "INC__0".to_string())]),
+            transforms: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn export_lists_targets_then_unused_columns_and_round_trips() {
+        let ms = state();
+        let (text, rows) = export(&ms);
+        assert_eq!(rows, 6);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "target_id,target_name,required,source_column");
+        assert_eq!(lines[1], "IncidentNumber,Incident Number,yes,INC");
+        assert_eq!(lines[6], ",,,\"Notes, free text\"");
+
+        let edited = text.replace("City,City,no,", "City,City,no,City (2)");
+        let imported = import(&ms, &format!("\u{feff}{}", edited)).unwrap();
+        assert_eq!(imported.target_to_source["City"], "City__1");
+        assert_eq!(imported.source_to_targets["City__1"], vec!["City"]);
+        assert_eq!(imported.target_to_source.len(), 2);
+    }
+
+    #[test]
+    fn import_reports_every_problem_with_its_line() {
+        let ms = state();
+        let sheet = "source_column,target_id\r\nCity,City\r\nINC,Beat\r\nINC,State\r\n";
+        let err = import(&ms, sheet).unwrap_err().to_string();
+        assert!(
+            err.contains("Line 2: source column 'City' is ambiguous"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("Line 3: unknown target field 'Beat'."),
+            "{}",
+            err
+        );
+        assert!(!err.contains("Line 4"), "{}", err);
+
+        let sheet = "target_id,source_column\nIncidentNumber,INC\nstate,inc\n";
+        assert!(import(&ms, sheet)
+            .unwrap_err()
+            .to_string()
+            .contains("turn on Override"));
+        let mut ms = ms;
+        ms.mapping_override = true;
+        let imported = import(&ms, sheet).unwrap();
+        assert_eq!(
+            imported.source_to_targets["INC__0"],
+            vec!["IncidentNumber", "State"]
+        );
+    }
+}
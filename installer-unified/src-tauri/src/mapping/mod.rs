@@ -0,0 +1,44 @@
+//! Field mapping support shared by the GUI, the TUI and the install pipeline.
+//!
+//! The mapping itself (`MappingState`) travels with the install request and is written to
+//! `mapping.json` for the ingestion pipeline; this module holds the logic around it.
+//! - `transform`: per-target value transforms (trim, date parsing, unit conversion, concat,
+//!   constant defaults) applied by ingestion after a source value is picked.
+//! - `suggest`: the `auto_map` engine proposing source -> target pairs with a confidence.
+//! - `persist`: the versioned `mapping.json` format, upgrades of older files and compatibility
+//!   checks against the current target fields.
+//! - `csv`: the review sheet analysts edit in a spreadsheet (`export_mapping_csv` /
+//!   `import_mapping_csv`).
+
+use crate::api::installer::MappingTargetField;
+
+pub mod csv;
+pub mod persist;
+pub mod suggest;
+pub mod transform;
+
+/// Version of [`target_catalog`]; bump it when target fields are added or removed.
+pub const TARGET_CATALOG_VERSION: u32 = 1;
+
+/// Target fields the ingestion pipeline accepts, in display order.
+pub fn target_catalog() -> Vec<MappingTargetField> {
+    [
+        ("CallReceivedAt", "Call Received At", true),
+        ("IncidentNumber", "Incident Number", true),
+        ("City", "City", false),
+        ("State", "State", false),
+        ("Zip", "Zip", false),
+        ("Address", "Address", false),
+        ("Latitude", "Latitude", false),
+        ("Longitude", "Longitude", false),
+        ("UnitId", "Unit ID", false),
+        ("Disposition", "Disposition", false),
+    ]
+    .into_iter()
+    .map(|(id, name, required)| MappingTargetField {
+        id: id.to_string(),
+        name: name.to_string(),
+        required,
+    })
+    .collect()
+}
@@ -0,0 +1,353 @@
+//! Versioned `mapping.json` artifacts.
+//!
+//! Every file carries `schemaVersion`; `load` reads any version this installer knows and
+//! upgrades it in memory to [`MAPPING_SCHEMA_VERSION`], so mapping files from older installs can
+//! be replayed. Versions:
+//! - 1 (or no `schemaVersion`): either the full mapping state, or, for installs without one,
+//!   only `canonicalToSourceColumn` (target id -> source column name).
+//! - 2: always the full mapping state, plus `targetCatalogVersion`, the version of
+//!   [`super::target_catalog`] the mapping was made against. When target fields are added to the
+//!   catalog, `check_compat` reports them (blocking when required and unmapped) instead of the
+//!   replay failing later.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::transform::FieldTransform;
+use super::TARGET_CATALOG_VERSION;
+use crate::api::installer::{MappingSourceField, MappingState, MappingTargetField};
+
+/// Schema version written by this installer.
+pub const MAPPING_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingFile {
+    pub schema_version: u32,
+    pub target_catalog_version: u32,
+    pub mapping_override: bool,
+    pub source_fields: Vec<MappingSourceField>,
+    pub target_fields: Vec<MappingTargetField>,
+    pub source_to_targets: BTreeMap<String, Vec<String>>,
+    pub target_to_source: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transforms: BTreeMap<String, Vec<FieldTransform>>,
+}
+
+/// v1 with a mapping state (same fields as v2 minus the catalog version).
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MappingFileV1 {
+    #[serde(default)]
+    mapping_override: bool,
+    source_fields: Vec<MappingSourceField>,
+    target_fields: Vec<MappingTargetField>,
+    #[serde(default)]
+    source_to_targets: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    target_to_source: BTreeMap<String, String>,
+    #[serde(default)]
+    transforms: BTreeMap<String, Vec<FieldTransform>>,
+}
+
+/// v1 written when the install request had no mapping state.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MappingFallbackV1 {
+    #[serde(default)]
+    mapping_override: bool,
+    canonical_to_source_column: BTreeMap<String, String>,
+}
+
+impl MappingFile {
+    /// Current-version file for a mapping state from the Mapping page.
+    pub fn from_state(ms: &MappingState) -> Self {
+        let source_to_targets = ms
+            .source_to_targets
+            .iter()
+            .map(|(k, v)| {
+                let mut targets = v.clone();
+                targets.sort();
+                (k.clone(), targets)
+            })
+            .collect();
+        Self {
+            schema_version: MAPPING_SCHEMA_VERSION,
+            target_catalog_version: TARGET_CATALOG_VERSION,
+            mapping_override: ms.mapping_override,
+            source_fields: ms.source_fields.clone(),
+            target_fields: ms.target_fields.clone(),
+            source_to_targets,
+            target_to_source: ms
+                .target_to_source
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            transforms: ms
+                .transforms
+                .iter()
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        }
+    }
+
+    /// Current-version file for a plain target id -> source column map (no mapping state). The
+    /// column names become the source field ids.
+    pub fn from_columns(mapping_override: bool, columns: &HashMap<String, String>) -> Self {
+        let catalog = super::target_catalog();
+        let mut target_to_source = BTreeMap::new();
+        let mut source_to_targets: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut source_fields: Vec<MappingSourceField> = Vec::new();
+        let mut target_fields: Vec<MappingTargetField> = Vec::new();
+        let sorted: BTreeMap<&String, &String> = columns.iter().collect();
+        for (target, column) in sorted {
+            if !source_fields.iter().any(|s| &s.id == column) {
+                source_fields.push(MappingSourceField {
+                    id: column.clone(),
+                    raw_name: column.clone(),
+                    display_name: column.clone(),
+                });
+            }
+            target_fields.push(
+                catalog
+                    .iter()
+                    .find(|t| &t.id == target)
+                    .cloned()
+                    .unwrap_or_else(|| MappingTargetField {
+                        id: target.clone(),
+                        name: target.clone(),
+                        required: false,
+                    }),
+            );
+            target_to_source.insert(target.clone(), column.clone());
+            source_to_targets
+                .entry(column.clone())
+                .or_default()
+                .push(target.clone());
+        }
+        Self {
+            schema_version: MAPPING_SCHEMA_VERSION,
+            target_catalog_version: TARGET_CATALOG_VERSION,
+            mapping_override,
+            source_fields,
+            target_fields,
+            source_to_targets,
+            target_to_source,
+            transforms: BTreeMap::new(),
+        }
+    }
+
+    pub fn to_state(&self) -> MappingState {
+        MappingState {
+            mapping_override: self.mapping_override,
+            source_fields: self.source_fields.clone(),
+            target_fields: self.target_fields.clone(),
+            source_to_targets: self
+                .source_to_targets
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            target_to_source: self
+                .target_to_source
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            transforms: self
+                .transforms
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        }
+    }
+}
+
+fn upgrade_v1(value: serde_json::Value) -> Result<MappingFile> {
+    if value.get("canonicalToSourceColumn").is_some() {
+        let v1: MappingFallbackV1 =
+            serde_json::from_value(value).context("Invalid v1 mapping file")?;
+        let columns: HashMap<String, String> = v1.canonical_to_source_column.into_iter().collect();
+        return Ok(MappingFile::from_columns(v1.mapping_override, &columns));
+    }
+    let v1: MappingFileV1 = serde_json::from_value(value).context("Invalid v1 mapping file")?;
+    Ok(MappingFile {
+        schema_version: MAPPING_SCHEMA_VERSION,
+        // v1 predates catalog versioning; its targets are compared field by field.
+        target_catalog_version: 1,
+        mapping_override: v1.mapping_override,
+        source_fields: v1.source_fields,
+        target_fields: v1.target_fields,
+        source_to_targets: v1.source_to_targets,
+        target_to_source: v1.target_to_source,
+        transforms: v1.transforms,
+    })
+}
+
+/// Parse a mapping file of any known version; returns it upgraded plus the version it was read
+/// as.
+pub fn load(bytes: &[u8]) -> Result<(MappingFile, u32)> {
+    let value: serde_json::Value =
+        serde_json::from_slice(bytes).context("Mapping file is not valid JSON")?;
+    let version = match value.get("schemaVersion") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .context("schemaVersion must be a positive integer")?,
+    };
+    let file = match version {
+        1 => upgrade_v1(value)?,
+        MAPPING_SCHEMA_VERSION => {
+            serde_json::from_value(value).context("Invalid v2 mapping file")?
+        }
+        v if v > MAPPING_SCHEMA_VERSION => anyhow::bail!(
+            "Mapping file schemaVersion {} was written by a newer installer (this one reads up to {}).",
+            v,
+            MAPPING_SCHEMA_VERSION
+        ),
+        v => anyhow::bail!("Unknown mapping file schemaVersion {}.", v),
+    };
+    Ok((file, version))
+}
+
+/// Result of checking a mapping file against the current target fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MappingCompat {
+    /// Blocking problems: the mapping cannot be replayed as is.
+    pub issues: Vec<String>,
+    /// Replayable, but worth a look.
+    pub warnings: Vec<String>,
+    pub added_targets: Vec<String>,
+    pub removed_targets: Vec<String>,
+}
+
+impl MappingCompat {
+    pub fn compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Compare `file` with the target fields the install would use now.
+pub fn check_compat(file: &MappingFile, targets: &[MappingTargetField]) -> MappingCompat {
+    let mut out = MappingCompat::default();
+    let known: HashSet<&str> = file.target_fields.iter().map(|t| t.id.as_str()).collect();
+    let current: HashSet<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    let sources: HashSet<&str> = file.source_fields.iter().map(|s| s.id.as_str()).collect();
+
+    for t in targets {
+        let mapped = file.target_to_source.contains_key(&t.id);
+        if !known.contains(t.id.as_str()) {
+            out.added_targets.push(t.id.clone());
+            if t.required && !mapped {
+                out.issues.push(format!(
+                    "Required target field '{}' was added after this mapping was made; map it before replaying.",
+                    t.id
+                ));
+            } else if !mapped {
+                out.warnings.push(format!(
+                    "Target field '{}' was added after this mapping was made and stays unmapped.",
+                    t.id
+                ));
+            }
+        } else if t.required && !mapped {
+            out.issues
+                .push(format!("Required target field '{}' is not mapped.", t.id));
+        }
+    }
+    for t in &file.target_fields {
+        if !current.contains(t.id.as_str()) {
+            out.removed_targets.push(t.id.clone());
+            if file.target_to_source.contains_key(&t.id) {
+                out.warnings.push(format!(
+                    "Target field '{}' no longer exists; its mapping is ignored.",
+                    t.id
+                ));
+            }
+        }
+    }
+    for (target, source) in &file.target_to_source {
+        if !sources.contains(source.as_str()) {
+            out.issues.push(format!(
+                "Target field '{}' is mapped to unknown source field '{}'.",
+                target, source
+            ));
+        }
+    }
+    if let Err(e) = super::transform::validate(&file.to_state()) {
+        out.issues.push(e.to_string());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_files_upgrade_to_the_current_version() {
+        let full = br#"{
+            "schemaVersion": 1,
+            "mappingOverride": false,
+            "sourceFields": [{ "id": "rcvd__0", "rawName": "rcvd", "displayName": "rcvd" }],
+            "targetFields": [{ "id": "CallReceivedAt", "name": "Call Received At", "required": true }],
+            "sourceToTargets": { "rcvd__0": ["CallReceivedAt"] },
+            "targetToSource": { "CallReceivedAt": "rcvd__0" }
+        }"#;
+        let (file, version) = load(full).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(file.schema_version, MAPPING_SCHEMA_VERSION);
+        assert_eq!(file.target_to_source["CallReceivedAt"], "rcvd__0");
+
+        let fallback = br#"{
+            "schemaVersion": 1,
+            "mappingOverride": true,
+            "canonicalToSourceColumn": { "IncidentNumber": "INC_NO", "City": "CITY" }
+        }"#;
+        let (file, _) = load(fallback).unwrap();
+        assert!(file.mapping_override);
+        assert_eq!(file.source_fields.len(), 2);
+        assert_eq!(file.source_to_targets["INC_NO"], vec!["IncidentNumber"]);
+        assert!(
+            file.target_fields
+                .iter()
+                .find(|t| t.id == "IncidentNumber")
+                .unwrap()
+                .required
+        );
+
+        // Round trip of the current version.
+        let bytes = serde_json::to_vec(&file).unwrap();
+        assert_eq!(load(&bytes).unwrap(), (file, MAPPING_SCHEMA_VERSION));
+
+        assert!(load(br#"{ "schemaVersion": 99 }"#).is_err());
+    }
+
+    #[test]
+    fn compat_reports_added_and_removed_targets() {
+        let columns = HashMap::from([
+            ("CallReceivedAt".to_string(), "RCVD".to_string()),
+            ("IncidentNumber".to_string(), "INC".to_string()),
+            ("Beat".to_string(), "BEAT".to_string()),
+        ]);
+        let mut file = MappingFile::from_columns(false, &columns);
+        file.target_fields.retain(|t| t.id != "IncidentNumber");
+        file.target_to_source.remove("IncidentNumber");
+
+        let mut targets = crate::mapping::target_catalog();
+        targets.push(MappingTargetField {
+            id: "Priority".to_string(),
+            name: "Priority".to_string(),
+            required: false,
+        });
+        let compat = check_compat(&file, &targets);
+        assert!(!compat.compatible());
+        assert!(compat.added_targets.contains(&"IncidentNumber".to_string()));
+        assert!(compat.added_targets.contains(&"Priority".to_string()));
+        assert_eq!(compat.removed_targets, vec!["Beat"]);
+        assert_eq!(compat.issues.len(), 1, "{:?}", compat.issues);
+
+        file.target_to_source
+            .insert("IncidentNumber".to_string(), "INC".to_string());
+        assert!(check_compat(&file, &targets).compatible());
+    }
+}
@@ -0,0 +1,323 @@
+//! Automatic mapping suggestions (`auto_map`).
+//!
+//! Each unmapped source column is scored against each unmapped target field:
+//! - name: column and field names are split into words (`CallReceivedDT` -> call, received, dt),
+//!   common abbreviations are expanded (`inc` -> incident, `lng` -> longitude, `dt` -> time), and
+//!   word overlap (Dice) counts, or edit-distance similarity of the joined words when it is close
+//!   enough to be a typo;
+//! - type: the sampled SQL type must fit what the target stores (a date for `CallReceivedAt`, a
+//!   number for coordinates); a misfit cuts the score, an unknown type costs a little.
+//!
+//! Pairs at or above [`MIN_CONFIDENCE`] are assigned best-first, one source per target and one
+//! target per source. The UIs show them as suggested until the operator confirms them.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::api::installer::MappingTargetField;
+
+/// Suggestions below this confidence are not reported.
+pub const MIN_CONFIDENCE: f64 = 0.5;
+
+/// Edit-distance similarity that counts as the same name with a typo.
+const TYPO_SIMILARITY: f64 = 0.75;
+
+/// A source column as seen by the engine.
+#[derive(Debug, Clone)]
+pub struct SuggestSource<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    /// SQL type from the source scan (`datetime2`, `nvarchar`, ...); empty when unknown.
+    pub data_type: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingSuggestion {
+    pub source_id: String,
+    pub target_id: String,
+    /// 0.0-1.0, two decimals.
+    pub confidence: f64,
+    /// Short explanation for the UI (name similarity and type fit).
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeClass {
+    Temporal,
+    Numeric,
+    Text,
+}
+
+fn classify(data_type: &str) -> Option<TypeClass> {
+    let t = data_type.trim().to_ascii_lowercase();
+    let base = t.split('(').next().unwrap_or("").trim();
+    match base {
+        "" => None,
+        "date"
+        | "datetime"
+        | "datetime2"
+        | "smalldatetime"
+        | "datetimeoffset"
+        | "time"
+        | "timestamp"
+        | "timestamptz"
+        | "timestamp with time zone"
+        | "timestamp without time zone" => Some(TypeClass::Temporal),
+        "int" | "integer" | "bigint" | "smallint" | "tinyint" | "decimal" | "numeric" | "float"
+        | "real" | "double" | "double precision" | "money" | "smallmoney" => {
+            Some(TypeClass::Numeric)
+        }
+        "char" | "nchar" | "varchar" | "nvarchar" | "text" | "ntext" | "character varying"
+        | "character" | "citext" | "uniqueidentifier" | "uuid" => Some(TypeClass::Text),
+        _ => None,
+    }
+}
+
+/// Types a target accepts; unknown targets accept anything.
+fn accepted_types(target_id: &str) -> &'static [TypeClass] {
+    match target_id {
+        "CallReceivedAt" => &[TypeClass::Temporal, TypeClass::Text],
+        "Latitude" | "Longitude" => &[TypeClass::Numeric, TypeClass::Text],
+        "Zip" | "IncidentNumber" | "UnitId" => &[TypeClass::Text, TypeClass::Numeric],
+        "City" | "State" | "Address" | "Disposition" => &[TypeClass::Text],
+        _ => &[],
+    }
+}
+
+/// Abbreviations and synonyms mapped to one canonical word.
+fn canonical_word(word: &str) -> &str {
+    match word {
+        "inc" | "incid" | "incdt" | "evt" | "event" | "case" => "incident",
+        "num" | "no" | "nbr" | "nr" | "number" => "number",
+        "rcvd" | "recv" | "rec" | "received" => "received",
+        "at" | "dt" | "date" | "time" | "datetime" | "timestamp" | "ts" => "time",
+        "lat" => "latitude",
+        "lon" | "lng" | "long" => "longitude",
+        "zipcode" | "postal" | "postcode" | "zip" => "zip",
+        "addr" | "street" | "location" | "loc" => "address",
+        "dispo" | "disp" => "disposition",
+        "apparatus" | "vehicle" | "unit" => "unit",
+        "identifier" | "key" | "id" => "id",
+        "town" | "municipality" => "city",
+        "province" | "region" => "state",
+        other => other,
+    }
+}
+
+/// Lowercase words of a name: split on separators, camelCase and letter/digit boundaries.
+fn words(name: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(prev) = current.chars().last() {
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            let boundary = (prev.is_ascii_lowercase() && c.is_ascii_uppercase())
+                || (prev.is_ascii_uppercase() && c.is_ascii_uppercase() && next_lower)
+                || (prev.is_ascii_digit() != c.is_ascii_digit());
+            if boundary {
+                out.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out.into_iter()
+        .map(|w| canonical_word(&w.to_ascii_lowercase()).to_string())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(cur).min(row[j])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// 0.0-1.0 similarity of two field names.
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let wa = words(a);
+    let wb = words(b);
+    if wa.is_empty() || wb.is_empty() {
+        return 0.0;
+    }
+    let sa: HashSet<&str> = wa.iter().map(String::as_str).collect();
+    let sb: HashSet<&str> = wb.iter().map(String::as_str).collect();
+    let common = sa.intersection(&sb).count() as f64;
+    let dice = 2.0 * common / (sa.len() + sb.len()) as f64;
+
+    let ja = wa.concat();
+    let jb = wb.concat();
+    let longest = ja.chars().count().max(jb.chars().count()) as f64;
+    let edit = 1.0 - levenshtein(&ja, &jb) as f64 / longest;
+    // Edit distance only catches typos; below this, short names look alike by chance
+    // (latitude / longitude).
+    if edit >= TYPO_SIMILARITY {
+        dice.max(edit)
+    } else {
+        dice
+    }
+}
+
+fn score(source: &SuggestSource<'_>, target: &MappingTargetField) -> (f64, String) {
+    let name =
+        name_similarity(source.name, &target.id).max(name_similarity(source.name, &target.name));
+    let accepted = accepted_types(&target.id);
+    let (factor, type_note) = match classify(source.data_type) {
+        _ if accepted.is_empty() => (1.0, String::new()),
+        None => (0.9, ", type unknown".to_string()),
+        Some(c) if accepted.contains(&c) => (1.0, format!(", type {} fits", source.data_type)),
+        Some(_) => (0.6, format!(", type {} does not fit", source.data_type)),
+    };
+    let confidence = (name * factor * 100.0).round() / 100.0;
+    (
+        confidence,
+        format!("name {:.0}% similar{}", name * 100.0, type_note),
+    )
+}
+
+/// Suggest mappings for the targets and sources not in `target_to_source` yet.
+pub fn suggest(
+    sources: &[SuggestSource<'_>],
+    targets: &[MappingTargetField],
+    target_to_source: &HashMap<String, String>,
+) -> Vec<MappingSuggestion> {
+    let mapped_sources: HashSet<&str> = target_to_source.values().map(String::as_str).collect();
+    let mut candidates: Vec<(usize, usize, f64, String)> = Vec::new();
+    for (ti, target) in targets.iter().enumerate() {
+        if target_to_source.contains_key(&target.id) {
+            continue;
+        }
+        for (si, source) in sources.iter().enumerate() {
+            if mapped_sources.contains(source.id) {
+                continue;
+            }
+            let (confidence, reason) = score(source, target);
+            if confidence >= MIN_CONFIDENCE {
+                candidates.push((ti, si, confidence, reason));
+            }
+        }
+    }
+    // Best first; ties keep target then source order so results are stable.
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+
+    let mut used_targets = HashSet::new();
+    let mut used_sources = HashSet::new();
+    let mut out = Vec::new();
+    for (ti, si, confidence, reason) in candidates {
+        if used_targets.contains(&ti) || used_sources.contains(&si) {
+            continue;
+        }
+        used_targets.insert(ti);
+        used_sources.insert(si);
+        out.push(MappingSuggestion {
+            source_id: sources[si].id.to_string(),
+            target_id: targets[ti].id.clone(),
+            confidence,
+            reason,
+        });
+    }
+    out.sort_by_key(|s| targets.iter().position(|t| t.id == s.target_id));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(id: &str, name: &str) -> MappingTargetField {
+        MappingTargetField {
+            id: id.to_string(),
+            name: name.to_string(),
+            required: false,
+        }
+    }
+
+    #[test]
+    fn words_split_camel_case_and_expand_abbreviations() {
+        assert_eq!(words("CallReceivedDT"), vec!["call", "received", "time"]);
+        assert_eq!(words("INC_NUM"), vec!["incident", "number"]);
+        assert_eq!(words("GPSLat2"), vec!["gps", "latitude", "2"]);
+        assert_eq!(words("Unit ID"), vec!["unit", "id"]);
+    }
+
+    #[test]
+    fn suggests_best_pairs_once_and_respects_types() {
+        let targets = vec![
+            target("CallReceivedAt", "Call Received At"),
+            target("IncidentNumber", "Incident Number"),
+            target("Latitude", "Latitude"),
+            target("Longitude", "Longitude"),
+            target("Disposition", "Disposition"),
+        ];
+        let sources = vec![
+            SuggestSource {
+                id: "rcvd__0",
+                name: "Call_Rcvd_Time",
+                data_type: "datetime2",
+            },
+            SuggestSource {
+                id: "inc__0",
+                name: "IncNo",
+                data_type: "nvarchar",
+            },
+            SuggestSource {
+                id: "lat__0",
+                name: "lat",
+                data_type: "datetime",
+            },
+            SuggestSource {
+                id: "lng__0",
+                name: "Lng",
+                data_type: "decimal(9,6)",
+            },
+            SuggestSource {
+                id: "notes__0",
+                name: "Notes",
+                data_type: "nvarchar",
+            },
+        ];
+        let got = suggest(&sources, &targets, &HashMap::new());
+        let pairs: Vec<(&str, &str, f64)> = got
+            .iter()
+            .map(|s| (s.target_id.as_str(), s.source_id.as_str(), s.confidence))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("CallReceivedAt", "rcvd__0", 1.0),
+                ("IncidentNumber", "inc__0", 1.0),
+                ("Latitude", "lat__0", 0.6),
+                ("Longitude", "lng__0", 1.0),
+            ]
+        );
+        assert!(got[2].reason.contains("does not fit"), "{:?}", got[2]);
+
+        // Already mapped targets and sources are left alone.
+        let mapped = HashMap::from([("IncidentNumber".to_string(), "rcvd__0".to_string())]);
+        let got = suggest(&sources, &targets, &mapped);
+        assert!(got
+            .iter()
+            .all(|s| s.target_id != "IncidentNumber" && s.source_id != "rcvd__0"));
+    }
+}
@@ -0,0 +1,305 @@
+//! Per-target value transforms.
+//!
+//! Each target field may carry an ordered list of transforms (`MappingState::transforms`, keyed
+//! by target id). Ingestion starts from the mapped source value (None when the target is
+//! unmapped or the value is null) and runs the list in order; the installer only validates the
+//! list and writes it to `mapping.json`. `apply` is the reference implementation, so the preview
+//! and the tests agree with what ingestion does.
+//!
+//! Dates are normalized to ISO 8601 (`2026-01-31T14:05:00`, or `2026-01-31` for date-only
+//! formats). Unit conversion goes through each dimension's base unit.
+
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::HashSet;
+
+use crate::api::installer::MappingState;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum FieldTransform {
+    /// Strip leading/trailing whitespace.
+    Trim,
+    /// Parse with a chrono/strftime `format` (e.g. `%m/%d/%Y %H:%M`) and emit ISO 8601.
+    ParseDate { format: String },
+    /// Numeric conversion between two units of the same dimension (see `UNITS`).
+    ConvertUnit { from: String, to: String },
+    /// Append another source field's value, joined with `separator`.
+    Concat {
+        source_id: String,
+        #[serde(default)]
+        separator: String,
+    },
+    /// Constant used when the value is missing or empty.
+    DefaultValue { value: String },
+}
+
+impl FieldTransform {
+    /// One-line description for the mapping UIs.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Trim => "Trim whitespace".to_string(),
+            Self::ParseDate { format } => format!("Parse date ({})", format),
+            Self::ConvertUnit { from, to } => format!("Convert {} -> {}", from, to),
+            Self::Concat {
+                source_id,
+                separator,
+            } => format!("Concat '{}' + {}", separator, source_id),
+            Self::DefaultValue { value } => format!("Default '{}'", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Duration,
+    Distance,
+    Temperature,
+}
+
+/// (name, dimension, factor to the base unit). Temperature is handled separately.
+const UNITS: &[(&str, Dimension, f64)] = &[
+    ("milliseconds", Dimension::Duration, 0.001),
+    ("seconds", Dimension::Duration, 1.0),
+    ("minutes", Dimension::Duration, 60.0),
+    ("hours", Dimension::Duration, 3600.0),
+    ("meters", Dimension::Distance, 1.0),
+    ("kilometers", Dimension::Distance, 1000.0),
+    ("feet", Dimension::Distance, 0.3048),
+    ("miles", Dimension::Distance, 1609.344),
+    ("celsius", Dimension::Temperature, 1.0),
+    ("fahrenheit", Dimension::Temperature, 1.0),
+];
+
+/// Unit names accepted by `ConvertUnit`.
+pub fn unit_names() -> impl Iterator<Item = &'static str> {
+    UNITS.iter().map(|(name, _, _)| *name)
+}
+
+fn unit(name: &str) -> Option<(Dimension, f64)> {
+    let name = name.trim().to_ascii_lowercase();
+    UNITS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, d, f)| (*d, *f))
+}
+
+fn convert(value: f64, from: &str, to: &str) -> Result<f64> {
+    let (Some((from_dim, from_factor)), Some((to_dim, to_factor))) = (unit(from), unit(to)) else {
+        anyhow::bail!("Unknown unit in '{} -> {}'.", from, to);
+    };
+    if from_dim != to_dim {
+        anyhow::bail!("Cannot convert {} to {}.", from, to);
+    }
+    if from_dim == Dimension::Temperature {
+        let celsius = if from.eq_ignore_ascii_case("fahrenheit") {
+            (value - 32.0) * 5.0 / 9.0
+        } else {
+            value
+        };
+        return Ok(if to.eq_ignore_ascii_case("fahrenheit") {
+            celsius * 9.0 / 5.0 + 32.0
+        } else {
+            celsius
+        });
+    }
+    Ok(value * from_factor / to_factor)
+}
+
+fn parse_date(value: &str, format: &str) -> Result<String> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+        return Ok(dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+    }
+    NaiveDate::parse_from_str(value, format)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| anyhow::anyhow!("'{}' does not match date format '{}'.", value, format))
+}
+
+/// Run `transforms` over `value`; `source_value` looks up other source fields for `Concat`.
+pub fn apply(
+    transforms: &[FieldTransform],
+    value: Option<&str>,
+    source_value: impl Fn(&str) -> Option<String>,
+) -> Result<Option<String>> {
+    let mut current = value.map(str::to_string);
+    for t in transforms {
+        current = match (t, current) {
+            (FieldTransform::DefaultValue { value }, None) => Some(value.clone()),
+            (FieldTransform::DefaultValue { value }, Some(v)) if v.is_empty() => {
+                Some(value.clone())
+            }
+            (
+                FieldTransform::Concat {
+                    source_id,
+                    separator,
+                },
+                v,
+            ) => match (v, source_value(source_id)) {
+                (Some(a), Some(b)) => Some(format!("{}{}{}", a, separator, b)),
+                (a, b) => a.or(b),
+            },
+            (_, None) => None,
+            (FieldTransform::Trim, Some(v)) => Some(v.trim().to_string()),
+            (FieldTransform::ParseDate { format }, Some(v)) => Some(parse_date(v.trim(), format)?),
+            (FieldTransform::ConvertUnit { from, to }, Some(v)) => {
+                let n: f64 = v
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("'{}' is not a number.", v))?;
+                Some(convert(n, from, to)?.to_string())
+            }
+            (FieldTransform::DefaultValue { .. }, v) => v,
+        };
+    }
+    Ok(current)
+}
+
+/// Check every transform list against the fields in `ms`.
+pub fn validate(ms: &MappingState) -> Result<()> {
+    let targets: HashSet<&str> = ms.target_fields.iter().map(|t| t.id.as_str()).collect();
+    let sources: HashSet<&str> = ms.source_fields.iter().map(|s| s.id.as_str()).collect();
+    for (target_id, transforms) in &ms.transforms {
+        if !targets.contains(target_id.as_str()) {
+            anyhow::bail!(
+                "Transforms are set for unknown target field '{}'.",
+                target_id
+            );
+        }
+        for t in transforms {
+            match t {
+                FieldTransform::Trim | FieldTransform::DefaultValue { .. } => {}
+                FieldTransform::ParseDate { format } => {
+                    if format.trim().is_empty() {
+                        anyhow::bail!("Date format for '{}' is empty.", target_id);
+                    }
+                }
+                FieldTransform::ConvertUnit { from, to } => {
+                    convert(0.0, from, to).map_err(|e| {
+                        anyhow::anyhow!("Unit conversion for '{}': {}", target_id, e)
+                    })?;
+                }
+                FieldTransform::Concat { source_id, .. } => {
+                    if !sources.contains(source_id.as_str()) {
+                        anyhow::bail!(
+                            "Concat for '{}' uses unknown source field '{}'.",
+                            target_id,
+                            source_id
+                        );
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::installer::{MappingSourceField, MappingTargetField};
+
+    fn no_sources(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn transforms_run_in_order() {
+        let t = vec![
+            FieldTransform::Trim,
+            FieldTransform::ParseDate {
+                format: "%m/%d/%Y %H:%M".to_string(),
+            },
+        ];
+        assert_eq!(
+            apply(&t, Some("  01/31/2026 14:05 "), no_sources).unwrap(),
+            Some("2026-01-31T14:05:00".to_string())
+        );
+        let date_only = vec![FieldTransform::ParseDate {
+            format: "%d.%m.%Y".to_string(),
+        }];
+        assert_eq!(
+            apply(&date_only, Some("31.01.2026"), no_sources).unwrap(),
+            Some("2026-01-31".to_string())
+        );
+        assert!(apply(&date_only, Some("yesterday"), no_sources).is_err());
+
+        let miles = vec![FieldTransform::ConvertUnit {
+            from: "miles".to_string(),
+            to: "kilometers".to_string(),
+        }];
+        assert_eq!(
+            apply(&miles, Some("10"), no_sources).unwrap(),
+            Some("16.09344".to_string())
+        );
+        assert_eq!(convert(212.0, "fahrenheit", "celsius").unwrap(), 100.0);
+
+        let concat = vec![
+            FieldTransform::Concat {
+                source_id: "last".to_string(),
+                separator: ", ".to_string(),
+            },
+            FieldTransform::DefaultValue {
+                value: "UNKNOWN".to_string(),
+            },
+        ];
+        let lookup = |id: &str| (id == "last").then(|| "Smith".to_string());
+        assert_eq!(
+            apply(&concat, Some("Jane"), lookup).unwrap(),
+            Some("Jane, Smith".to_string())
+        );
+        assert_eq!(
+            apply(&concat, None, no_sources).unwrap(),
+            Some("UNKNOWN".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_fields_and_units() {
+        let mut ms = MappingState {
+            mapping_override: false,
+            source_fields: vec![MappingSourceField {
+                id: "first".to_string(),
+                raw_name: "first".to_string(),
+                display_name: "First".to_string(),
+            }],
+            target_fields: vec![MappingTargetField {
+                id: "Name".to_string(),
+                name: "Name".to_string(),
+                required: true,
+            }],
+            source_to_targets: Default::default(),
+            target_to_source: Default::default(),
+            transforms: Default::default(),
+        };
+        ms.transforms
+            .insert("Name".to_string(), vec![FieldTransform::Trim]);
+        assert!(validate(&ms).is_ok());
+
+        ms.transforms.insert(
+            "Name".to_string(),
+            vec![FieldTransform::ConvertUnit {
+                from: "miles".to_string(),
+                to: "hours".to_string(),
+            }],
+        );
+        assert!(validate(&ms).is_err());
+
+        ms.transforms.insert(
+            "Name".to_string(),
+            vec![FieldTransform::Concat {
+                source_id: "last".to_string(),
+                separator: " ".to_string(),
+            }],
+        );
+        assert!(validate(&ms).is_err());
+
+        ms.transforms.clear();
+        ms.transforms
+            .insert("Missing".to_string(), vec![FieldTransform::Trim]);
+        assert!(validate(&ms).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 // API request models
 // Ported from C# contracts under `src/Cadalytix.Contracts/*`
 
+use crate::utils::connection_profiles::ProfileKind;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // =========================
 // Setup
@@ -119,6 +120,78 @@ fn default_license_mode() -> String {
     "online".to_string()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseActivateRequest {
+    pub license_key: String,
+    pub ops_api_base_url: Option<String>,
+    /// Explicit proxy (e.g. `http://proxy.corp:3128`); the system proxy applies when unset.
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    /// Connect directly, ignoring system proxy settings.
+    #[serde(default)]
+    pub bypass_system_proxy: bool,
+}
+
+// =========================
+// Secrets
+// =========================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateSecretsRequest {
+    /// Rotate without a config DB (only when none was ever created; its secrets would become
+    /// unreadable otherwise).
+    #[serde(default)]
+    pub files_only: bool,
+}
+
+// =========================
+// Network
+// =========================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveProxySettingsRequest {
+    /// Proxy host name or IP; empty uses the system proxy (or a direct connection).
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Omit to keep the saved password; empty string clears it.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Connect directly, ignoring HTTP(S)_PROXY and OS proxy settings.
+    #[serde(default)]
+    pub bypass_system_proxy: bool,
+}
+
+// =========================
+// Connection profiles
+// =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveConnectionProfileRequest {
+    pub name: String,
+    pub kind: ProfileKind,
+    /// Page values by `--tui-script` answer key (see `utils::connection_profiles`).
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfileRequest {
+    pub kind: ProfileKind,
+    /// Profile to delete; ignored when listing.
+    #[serde(default)]
+    pub name: String,
+}
+
 // =========================
 // Preflight
 // =========================
@@ -127,6 +200,15 @@ fn default_license_mode() -> String {
 #[serde(rename_all = "camelCase")]
 pub struct PreflightHostRequestDto {
     pub strict_mode: bool,
+    /// Expected ingest for the sizing checks (default: the preflight report's default).
+    #[serde(default)]
+    pub expected_rows_per_day: Option<u64>,
+    /// Rows the backfill will load (the backfill estimate's total), for the sizing checks.
+    #[serde(default)]
+    pub historical_rows: Option<u64>,
+    /// Data folder whose disk is checked; default: the system drive or `/`.
+    #[serde(default)]
+    pub data_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -143,6 +225,23 @@ pub struct PreflightPermissionsRequestDto {
     pub source_object_name: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightDependenciesRequestDto {
+    pub install_mode: String, // "windows" | "docker" | "linux"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightNetworkRequestDto {
+    /// Database the install will connect to (for Create NEW: the master/postgres connection).
+    #[serde(default)]
+    pub db_connection_string: String,
+    /// Ports the CADalytix services will listen on; empty means the defaults.
+    #[serde(default)]
+    pub app_ports: Vec<u16>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PreflightDataSourceRequestDto {
@@ -155,12 +254,101 @@ pub struct PreflightDataSourceRequestDto {
     /// Explicitly labeled demo mode (no database required). Used to demonstrate schema mapping UX.
     #[serde(default)]
     pub demo_mode: bool,
+    /// Scan a folder of CSV / TSV exports instead of the SQL Server source.
+    #[serde(default)]
+    pub file_source: Option<crate::api::installer::FileSourceConfig>,
+    /// "sqlserver" (default) | "odbc" | "oracle": how `call_data_connection_string` is opened.
+    #[serde(default)]
+    pub call_data_driver: String,
 }
 
 fn default_sample_limit() -> i32 {
     10
 }
 
+/// Historical backfill estimate for the Ready page (`api::backfill`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillEstimateRequestDto {
+    pub call_data_connection_string: String,
+    /// "sqlserver" (default) | "odbc" | "oracle", as for the data source preflight.
+    #[serde(default)]
+    pub call_data_driver: String,
+    pub source_object_name: String,
+    /// Source column rows are dated by (the one mapped to `CallReceivedAt`).
+    pub watermark_column: String,
+    /// Backfill range `[from, to)` (ISO dates); open ends cover the whole history.
+    #[serde(default)]
+    pub date_from_iso: Option<String>,
+    #[serde(default)]
+    pub date_to_iso: Option<String>,
+    pub hot_retention_months: u32,
+    #[serde(default)]
+    pub demo_mode: bool,
+}
+
+/// Tables and views of the call data source, for the source object picker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSourceObjectsRequestDto {
+    pub call_data_connection_string: String,
+    /// "sqlserver" (default) | "odbc" | "oracle", as for the data source preflight.
+    #[serde(default)]
+    pub call_data_driver: String,
+    #[serde(default)]
+    pub demo_mode: bool,
+}
+
+// =========================
+// Mapping
+// =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMapSourceFieldDto {
+    pub id: String,
+    pub raw_name: String,
+    /// SQL type from the source scan; empty when unknown.
+    #[serde(default)]
+    pub data_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMapTargetFieldDto {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMapRequestDto {
+    pub source_fields: Vec<AutoMapSourceFieldDto>,
+    pub target_fields: Vec<AutoMapTargetFieldDto>,
+    /// Current (confirmed or suggested) mappings; these targets and sources are skipped.
+    #[serde(default)]
+    pub target_to_source: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMappingCompatRequestDto {
+    /// Path of a persisted `mapping.json`.
+    pub path: String,
+    /// Target fields to check against; the built-in target catalog when omitted.
+    #[serde(default)]
+    pub target_fields: Option<Vec<crate::api::installer::MappingTargetField>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingCsvRequestDto {
+    /// CSV file to write (export) or read (import).
+    pub path: String,
+    /// Current Mapping page state (fields, mapping and override flag).
+    pub mapping_state: crate::api::installer::MappingState,
+}
+
 // =========================
 // Schema
 // =========================
@@ -171,6 +359,9 @@ pub struct VerifySchemaRequest {
     #[serde(default = "default_engine_sqlserver")]
     pub engine: String,
     pub connection_string: Option<String>,
+    /// Apply safe additive fixes (missing columns/indexes) before reporting.
+    #[serde(default)]
+    pub repair: bool,
 }
 
 fn default_engine_sqlserver() -> String {
@@ -185,6 +376,9 @@ pub struct VerifyAllRequest {
     pub source_object_name: Option<String>,
     #[serde(default = "default_engine_sqlserver")]
     pub engine: String,
+    /// Apply safe additive schema fixes before reporting.
+    #[serde(default)]
+    pub repair: bool,
 }
 
 // =========================
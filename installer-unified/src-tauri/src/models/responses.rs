@@ -158,7 +158,7 @@ pub struct CommitResponse {
 pub struct SetupVerifyCheckResult {
     pub id: String,
     pub label: String,
-    pub status: String, // "pass" | "fail"
+    pub status: String, // "pass" | "warn" | "fail" | "skip"
     pub message: String,
     pub duration_ms: i64,
 }
@@ -167,6 +167,9 @@ pub struct SetupVerifyCheckResult {
 #[serde(rename_all = "camelCase")]
 pub struct SetupVerifyResponse {
     pub success: bool,
+    /// "green" | "yellow" | "red": the worst check (warn = yellow, fail = red).
+    #[serde(default)]
+    pub health: String,
     #[serde(default)]
     pub checks: Vec<SetupVerifyCheckResult>,
     #[serde(default)]
@@ -217,6 +220,47 @@ pub struct SetupEventDto {
     pub occurred_at: DateTime<Utc>,
 }
 
+// =========================
+// Mapping
+// =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMapResponseDto {
+    pub suggestions: Vec<crate::mapping::suggest::MappingSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMappingCompatResponseDto {
+    pub compatible: bool,
+    /// Version the file was written with (1 when it has no `schemaVersion`).
+    pub file_schema_version: u32,
+    pub current_schema_version: u32,
+    /// True when the file was upgraded from an older version to be checked.
+    pub upgraded: bool,
+    /// Blocking problems; the mapping cannot be replayed until they are fixed.
+    pub issues: Vec<String>,
+    pub warnings: Vec<String>,
+    pub added_targets: Vec<String>,
+    pub removed_targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportMappingCsvResponseDto {
+    pub path: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMappingCsvResponseDto {
+    /// The request's mapping state with the mapping from the sheet.
+    pub mapping_state: crate::api::installer::MappingState,
+    pub mapped_count: usize,
+}
+
 // =========================
 // Schema
 // =========================
@@ -239,6 +283,15 @@ pub struct VerifySchemaResponse {
     pub type_mismatches: Vec<String>,
     #[serde(default)]
     pub nullability_mismatches: Vec<String>,
+    /// DDL applied when verification ran with `repair`.
+    #[serde(default)]
+    pub repairs_applied: Vec<String>,
+    /// Repair statements that failed.
+    #[serde(default)]
+    pub repair_errors: Vec<String>,
+    /// Path of the `schema-diff.json` artifact, when it could be written.
+    #[serde(default)]
+    pub report_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -278,12 +331,58 @@ pub struct LicenseVerifyResponse {
     pub correlation_id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseActivateResponse {
+    pub success: bool,
+    pub message: String,
+    pub entitlement: Option<LicenseEntitlementDto>,
+    /// The licensing server was unreachable; the UI should offer offline activation.
+    pub offline_fallback: bool,
+    pub correlation_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LicenseStatusResponse {
     pub is_active: bool,
     pub entitlement: Option<LicenseEntitlementDto>,
     pub message: String,
+    /// Whole days left before the grace period ends (only while in grace).
+    #[serde(default)]
+    pub grace_days_remaining: Option<i64>,
+}
+
+// =========================
+// Secrets
+// =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateSecretsResponse {
+    pub key_store: String,
+    /// Backup of the previous master key; keep it until every copy of old data is gone.
+    pub backup_key_path: String,
+    pub database_values_rotated: usize,
+    pub files_rotated: Vec<String>,
+    pub pinned_keys: Vec<String>,
+}
+
+// =========================
+// Network
+// =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettingsResponse {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    /// A password is saved (it is never returned).
+    pub has_password: bool,
+    pub bypass_system_proxy: bool,
+    /// Proxy from HTTPS_PROXY/HTTP_PROXY, credentials masked; used when no explicit proxy is set.
+    pub env_proxy: Option<String>,
 }
 
 // =========================
@@ -311,6 +410,29 @@ pub struct PreflightHostResponseDto {
     #[serde(default)]
     pub checks: Vec<PreflightCheckDto>,
     pub overall_status: String, // Pass | Warn | Fail
+    /// CPU/RAM/NUMA/disk compared with the tier for the projected load.
+    #[serde(default)]
+    pub sizing: Option<HostSizingDto>,
+}
+
+/// Host sizing assessment (see `installation::host_sizing`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSizingDto {
+    /// "Small" | "Medium" | "Large" | "Extra large"
+    pub tier: String,
+    pub rows_per_day: u64,
+    pub historical_rows: u64,
+    pub recommended_cpu_cores: usize,
+    pub recommended_ram_gb: u64,
+    pub cpu_cores: Option<usize>,
+    pub total_ram_bytes: Option<u64>,
+    /// NUMA nodes (Linux) or processor sockets (Windows).
+    pub numa_nodes: Option<u32>,
+    /// "SSD" | "HDD" for the data folder's disk; `None` when unknown.
+    pub data_disk: Option<String>,
+    pub under_provisioned: bool,
+    pub shortfalls: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -322,12 +444,59 @@ pub struct PreflightPermissionsResponseDto {
     pub recommended_remediation: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightDependencyCheckDto {
+    pub name: String,
+    pub status: String, // Pass | Warn | Fail
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightDependenciesResponseDto {
+    #[serde(default)]
+    pub checks: Vec<PreflightDependencyCheckDto>,
+    /// Fail blocks the install; Warn lets it continue.
+    pub overall_status: String, // Pass | Warn | Fail
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightNetworkCheckDto {
+    pub name: String,
+    pub status: String, // Pass | Warn | Fail
+    pub detail: String,
+    /// What the operator can do about a Warn/Fail finding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightNetworkResponseDto {
+    #[serde(default)]
+    pub checks: Vec<PreflightNetworkCheckDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firewall: Option<String>,
+    pub overall_status: String, // Pass | Warn | Fail
+    /// Database server time minus this host's time, when the server could be queried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock_skew_ms: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoveredColumnDto {
     pub name: String,
     pub data_type: String,
     pub is_nullable: bool,
+    /// Values from the preflight sample query, in row order (`NULL` for nulls); empty when the
+    /// sample could not be read.
+    #[serde(default)]
+    pub sample_values: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -338,6 +507,66 @@ pub struct SampleStatsDto {
     pub max_call_received_at: Option<String>,
 }
 
+/// Source rows of one calendar month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillMonthDto {
+    /// "YYYY-MM"
+    pub month: String,
+    pub rows: u64,
+    /// Inside the hot retention window (loaded into the hot DB); otherwise archived.
+    pub hot: bool,
+}
+
+/// Projected size and duration of the historical backfill.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillEstimateDto {
+    pub months: Vec<BackfillMonthDto>,
+    pub total_rows: u64,
+    pub hot_rows: u64,
+    pub archive_rows: u64,
+    /// Average source row size from DB statistics, or a fallback when unavailable.
+    pub avg_row_bytes: u64,
+    pub avg_row_bytes_estimated: bool,
+    pub projected_hot_db_bytes: u64,
+    pub projected_archive_bytes: u64,
+    pub estimated_duration_secs: u64,
+}
+
+/// A table or view offered by the source object picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceObjectDto {
+    /// Schema (SQL Server, ODBC) or owner (Oracle); empty when the source has none.
+    pub schema: String,
+    pub name: String,
+    /// "table" | "view"
+    pub kind: String,
+    /// Rows in the table (Oracle: as of the last statistics run); `None` for views and sources
+    /// that do not report counts.
+    pub row_count: Option<i64>,
+}
+
+impl SourceObjectDto {
+    /// The name to put in "Source object name": `schema.name`, or `name` without a schema.
+    pub fn qualified_name(&self) -> String {
+        if self.schema.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.schema, self.name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSourceObjectsResponseDto {
+    pub objects: Vec<SourceObjectDto>,
+    /// More objects exist than were listed.
+    pub truncated: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PreflightDataSourceResponseDto {
@@ -0,0 +1,348 @@
+// Audit log of privileged actions (database creation, service registration, file deployment,
+// archive purge).
+//
+// `Prod_Wizard_Log/audit.jsonl` is append-only, one JSON entry per line. Each entry carries the
+// SHA-256 of the previous entry (`prevHash`) and its own hash over every other field, so editing,
+// reordering or removing a line breaks the chain from that point on. `--verify-audit` walks the
+// chain and reports the first broken line.
+//
+// Truncating the newest entries leaves a valid (shorter) chain; compare the reported `headHash`
+// against a previously exported value to detect that.
+//
+// Recording is best-effort: a failure to write the audit log is logged and never fails the
+// privileged action itself.
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::io::AsyncWriteExt;
+
+use super::crypto::sha256_hex;
+
+pub const AUDIT_FILE_NAME: &str = "audit.jsonl";
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Serializes appends so concurrent actions cannot fork the chain.
+static APPEND_LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    DatabaseCreate,
+    ServiceRegister,
+    FileDeploy,
+    ArchivePurge,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::DatabaseCreate => "database_create",
+            AuditAction::ServiceRegister => "service_register",
+            AuditAction::FileDeploy => "file_deploy",
+            AuditAction::ArchivePurge => "archive_purge",
+        }
+    }
+}
+
+/// Hashed part of an entry. Field order is the serialized order, so it is part of the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditBody {
+    seq: u64,
+    timestamp_utc: String,
+    action: String,
+    target: String,
+    outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default)]
+    details: Value,
+    actor: String,
+    pid: u32,
+    prev_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditEntry {
+    #[serde(flatten)]
+    body: AuditBody,
+    hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerifyReport {
+    pub path: String,
+    pub entries: u64,
+    pub valid: bool,
+    /// Hash of the last verified entry; record it to detect later truncation.
+    pub head_hash: Option<String>,
+    /// 1-based line of the first entry that fails verification.
+    pub first_bad_line: Option<usize>,
+    pub problem: Option<String>,
+}
+
+/// `audit.jsonl` in the log folder.
+pub fn default_audit_path() -> Result<PathBuf> {
+    Ok(crate::utils::path_resolver::resolve_log_folder()?.join(AUDIT_FILE_NAME))
+}
+
+/// Record the outcome of a privileged action in the default audit log (best-effort).
+pub async fn record_result<T, E: std::fmt::Display>(
+    action: AuditAction,
+    target: &str,
+    details: Value,
+    result: &std::result::Result<T, E>,
+) {
+    let error = result.as_ref().err().map(|e| e.to_string());
+    let appended = match default_audit_path() {
+        Ok(path) => append(&path, action, target, error, details).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = appended {
+        warn!(
+            "[PHASE: audit] [STEP: record] Failed to record {} of '{}' in the audit log: {}",
+            action.as_str(),
+            target,
+            e
+        );
+    }
+}
+
+/// Append one entry to the audit log at `path`, chained to its current last entry.
+pub async fn append(
+    path: &Path,
+    action: AuditAction,
+    target: &str,
+    error: Option<String>,
+    details: Value,
+) -> Result<()> {
+    let _guard = APPEND_LOCK
+        .get_or_init(|| tokio::sync::Mutex::new(()))
+        .lock()
+        .await;
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create audit log directory: {:?}", parent))?;
+    }
+    let (seq, prev_hash) = match last_entry(path).await? {
+        Some(last) => (last.body.seq + 1, last.hash),
+        None => (1, GENESIS_HASH.to_string()),
+    };
+    let outcome = if error.is_some() {
+        "failed"
+    } else {
+        "succeeded"
+    };
+    let body = AuditBody {
+        seq,
+        timestamp_utc: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        action: action.as_str().to_string(),
+        target: target.to_string(),
+        outcome: outcome.to_string(),
+        error,
+        details,
+        actor: actor(),
+        pid: std::process::id(),
+        prev_hash,
+    };
+    let hash = hash_body(&body)?;
+    let mut line = serde_json::to_string(&AuditEntry { body, hash })?;
+    line.push('\n');
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open audit log: {:?}", path))?;
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Failed to append to audit log: {:?}", path))?;
+    file.sync_data().await.ok();
+    Ok(())
+}
+
+/// Walk the chain in `path`. A missing log is reported as valid and empty.
+pub async fn verify_chain(path: &Path) -> Result<AuditVerifyReport> {
+    let mut report = AuditVerifyReport {
+        path: path.to_string_lossy().to_string(),
+        entries: 0,
+        valid: true,
+        head_hash: None,
+        first_bad_line: None,
+        problem: None,
+    };
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(report);
+    }
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read audit log: {:?}", path))?;
+
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match check_line(line, report.entries + 1, &prev_hash) {
+            Ok(entry) => {
+                prev_hash = entry.hash;
+                report.entries += 1;
+            }
+            Err(problem) => {
+                report.valid = false;
+                report.first_bad_line = Some(idx + 1);
+                report.problem = Some(problem);
+                return Ok(report);
+            }
+        }
+    }
+    if report.entries > 0 {
+        report.head_hash = Some(prev_hash);
+    }
+    Ok(report)
+}
+
+/// `--verify-audit [--audit-log=<path>]`: verify the given log, else the default one.
+pub async fn verify_audit_cli(args: &[String]) -> Result<AuditVerifyReport> {
+    let path = match args
+        .iter()
+        .find_map(|a| a.strip_prefix("--audit-log="))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(p) => PathBuf::from(p),
+        None => default_audit_path()?,
+    };
+    verify_chain(&path).await
+}
+
+/// Parse `line` and check that it continues the chain at `expected_seq` after `prev_hash`;
+/// the error says why it does not.
+fn check_line(
+    line: &str,
+    expected_seq: u64,
+    prev_hash: &str,
+) -> std::result::Result<AuditEntry, String> {
+    let entry: AuditEntry =
+        serde_json::from_str(line).map_err(|e| format!("Entry is not valid JSON: {}", e))?;
+    if entry.body.seq != expected_seq {
+        return Err(format!(
+            "Sequence gap (expected {}, found {})",
+            expected_seq, entry.body.seq
+        ));
+    }
+    if entry.body.prev_hash != prev_hash {
+        return Err("prevHash does not match the previous entry".to_string());
+    }
+    let hash = hash_body(&entry.body).map_err(|e| format!("Entry could not be hashed: {}", e))?;
+    if hash != entry.hash {
+        return Err("Entry hash does not match its contents".to_string());
+    }
+    Ok(entry)
+}
+
+async fn last_entry(path: &Path) -> Result<Option<AuditEntry>> {
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read audit log: {:?}", path))?;
+    match text.lines().rev().find(|l| !l.trim().is_empty()) {
+        Some(line) => Ok(Some(serde_json::from_str(line).context(
+            "Last audit log entry is corrupt; refusing to extend the chain",
+        )?)),
+        None => Ok(None),
+    }
+}
+
+fn hash_body(body: &AuditBody) -> Result<String> {
+    Ok(sha256_hex(&serde_json::to_vec(body)?))
+}
+
+/// `user@host` for the running process, best-effort.
+fn actor() -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    let host = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    format!("{}@{}", user, host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn chain_verifies_and_detects_tampering() {
+        let dir = std::env::temp_dir().join(format!("audit-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(AUDIT_FILE_NAME);
+
+        let empty = verify_chain(&path).await.unwrap();
+        assert!(empty.valid);
+        assert_eq!(empty.entries, 0);
+
+        append(
+            &path,
+            AuditAction::DatabaseCreate,
+            "cadalytix",
+            None,
+            serde_json::json!({ "engine": "postgres" }),
+        )
+        .await
+        .unwrap();
+        append(
+            &path,
+            AuditAction::ServiceRegister,
+            "cadalytix",
+            Some("sc.exe create failed".to_string()),
+            Value::Null,
+        )
+        .await
+        .unwrap();
+        append(
+            &path,
+            AuditAction::ArchivePurge,
+            "2024-01",
+            None,
+            serde_json::json!({ "deletedRows": 42 }),
+        )
+        .await
+        .unwrap();
+
+        let report = verify_chain(&path).await.unwrap();
+        assert!(report.valid, "{:?}", report.problem);
+        assert_eq!(report.entries, 3);
+        assert!(report.head_hash.is_some());
+
+        let text = tokio::fs::read_to_string(&path).await.unwrap();
+        let tampered = text.replacen("\"deletedRows\":42", "\"deletedRows\":7", 1);
+        assert_ne!(tampered, text);
+        tokio::fs::write(&path, &tampered).await.unwrap();
+        let report = verify_chain(&path).await.unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.first_bad_line, Some(3));
+
+        // Dropping a middle line breaks the chain at the line that follows it.
+        let lines: Vec<&str> = text.lines().collect();
+        tokio::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2]))
+            .await
+            .unwrap();
+        let report = verify_chain(&path).await.unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.first_bad_line, Some(2));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -23,3 +23,42 @@ pub fn sha256_base64(input: &[u8]) -> String {
 pub fn secret_fingerprint(input: &str) -> String {
     sha256_base64(input.as_bytes())
 }
+
+/// HMAC-SHA256 (raw bytes).
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key);
+    ring::hmac::sign(&key, message).as_ref().to_vec()
+}
+
+/// Ed25519 public key release packaging signs payload and offline bundle manifests and license
+/// files with (the ops offline signing key), base64. Injected at build time from
+/// `CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY`; `build.rs` fails release builds without it.
+pub const RELEASE_SIGNING_PUBLIC_KEY_B64: Option<&str> =
+    option_env!("CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY");
+
+/// Verify a base64 Ed25519 signature over `message` with a raw 32-byte public key.
+pub fn verify_ed25519(public_key: &[u8], message: &[u8], signature_b64: &str) -> bool {
+    let Ok(signature) = base64::engine::general_purpose::STANDARD.decode(signature_b64.trim())
+    else {
+        return false;
+    };
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+        .verify(message, &signature)
+        .is_ok()
+}
+
+/// The release signing key as raw bytes. Fails in a build without one (development builds), so
+/// nothing signed is ever accepted against a missing or malformed key.
+pub fn release_signing_key() -> anyhow::Result<Vec<u8>> {
+    let Some(b64) = RELEASE_SIGNING_PUBLIC_KEY_B64.filter(|k| !k.trim().is_empty()) else {
+        anyhow::bail!(
+            "This installer was built without a release signing key (CADALYTIX_RELEASE_SIGNING_PUBLIC_KEY); signed bundles, payload manifests and license files cannot be verified"
+        );
+    };
+    match base64::engine::general_purpose::STANDARD.decode(b64.trim()) {
+        Ok(key) if key.len() == 32 => Ok(key),
+        _ => anyhow::bail!(
+            "The release signing key built into this installer is not a base64 Ed25519 public key"
+        ),
+    }
+}
@@ -0,0 +1,327 @@
+// OS-level storage for the SecretProtector master key.
+//
+// Backends, picked by `KeyBackend::detect`:
+// - Windows: DPAPI (machine scope). The protected blob is stored next to the key path
+//   (`installer_master_key.dpapi`); only this machine can unprotect it.
+// - Linux: Secret Service (libsecret, via `secret-tool`) when a session bus is available. A marker
+//   file next to the key path records that the key lives in the keyring.
+// - Otherwise: the plaintext key file (`installer_master_key.b64`), as before.
+//
+// The kernel keyring (keyutils) is not used as a backend: its keys do not survive a reboot, so
+// secrets encrypted under a key held only there would become unreadable.
+//
+// `CADALYTIX_SECRET_KEY_STORE=file|dpapi|secret-service` overrides detection.
+//
+// SECURITY:
+// - Never log key material; errors only name the backend and the key path.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::secret_protector::KEY_BYTES;
+
+const OVERRIDE_ENV: &str = "CADALYTIX_SECRET_KEY_STORE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyBackend {
+    /// Plaintext (base64) key file under the log folder.
+    File,
+    /// Windows DPAPI, machine scope.
+    Dpapi,
+    /// Linux Secret Service (GNOME Keyring, KWallet) through `secret-tool`.
+    SecretService,
+}
+
+impl KeyBackend {
+    /// Best backend available on this machine; `File` when no OS key storage is usable.
+    pub fn detect() -> Self {
+        if let Some(forced) = std::env::var(OVERRIDE_ENV)
+            .ok()
+            .and_then(|v| Self::parse(&v))
+        {
+            return forced;
+        }
+        if cfg!(windows) {
+            return KeyBackend::Dpapi;
+        }
+        if cfg!(target_os = "linux")
+            && std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+            && which::which("secret-tool").is_ok()
+        {
+            return KeyBackend::SecretService;
+        }
+        KeyBackend::File
+    }
+
+    fn parse(v: &str) -> Option<Self> {
+        match v.trim().to_ascii_lowercase().as_str() {
+            "file" => Some(KeyBackend::File),
+            "dpapi" if cfg!(windows) => Some(KeyBackend::Dpapi),
+            "secret-service" | "libsecret" if cfg!(target_os = "linux") => {
+                Some(KeyBackend::SecretService)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyBackend::File => "file",
+            KeyBackend::Dpapi => "dpapi",
+            KeyBackend::SecretService => "secret-service",
+        }
+    }
+}
+
+/// File that marks `key_path`'s key as held by `backend` (the DPAPI blob itself on Windows).
+pub fn marker_path(backend: KeyBackend, key_path: &Path) -> Option<PathBuf> {
+    match backend {
+        KeyBackend::File => None,
+        KeyBackend::Dpapi => Some(key_path.with_extension("dpapi")),
+        KeyBackend::SecretService => Some(key_path.with_extension("keyring")),
+    }
+}
+
+/// Whether `backend` already holds the key for `key_path`.
+pub async fn is_provisioned(backend: KeyBackend, key_path: &Path) -> bool {
+    match marker_path(backend, key_path) {
+        Some(marker) => tokio::fs::try_exists(&marker).await.unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Load the key for `key_path` from an OS backend.
+pub async fn load(backend: KeyBackend, key_path: &Path) -> Result<[u8; KEY_BYTES]> {
+    let bytes = match backend {
+        KeyBackend::File => anyhow::bail!("The file key store is handled by SecretProtector"),
+        KeyBackend::Dpapi => {
+            let marker = key_path.with_extension("dpapi");
+            let blob = tokio::fs::read(&marker)
+                .await
+                .with_context(|| format!("Failed to read DPAPI key blob: {:?}", marker))?;
+            dpapi::unprotect(&blob)?
+        }
+        KeyBackend::SecretService => secret_service::lookup(key_path).await?,
+    };
+    to_key(&bytes, backend)
+}
+
+/// Store the key for `key_path` in an OS backend and write its marker.
+pub async fn store(backend: KeyBackend, key_path: &Path, key: &[u8; KEY_BYTES]) -> Result<()> {
+    let marker = marker_path(backend, key_path)
+        .ok_or_else(|| anyhow::anyhow!("The file key store is handled by SecretProtector"))?;
+    if let Some(parent) = marker.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create secret key directory: {:?}", parent))?;
+    }
+    match backend {
+        KeyBackend::File => unreachable!(),
+        KeyBackend::Dpapi => {
+            // Write + rename: a rotation never leaves a half-written blob behind.
+            let blob = dpapi::protect(key)?;
+            let tmp = marker.with_extension("dpapi.tmp");
+            tokio::fs::write(&tmp, blob)
+                .await
+                .with_context(|| format!("Failed to write DPAPI key blob: {:?}", tmp))?;
+            tokio::fs::rename(&tmp, &marker)
+                .await
+                .with_context(|| format!("Failed to replace DPAPI key blob: {:?}", marker))?;
+        }
+        KeyBackend::SecretService => {
+            secret_service::store(key_path, key).await?;
+            tokio::fs::write(&marker, b"secret-service\n")
+                .await
+                .with_context(|| format!("Failed to write key store marker: {:?}", marker))?;
+        }
+    }
+    Ok(())
+}
+
+fn to_key(bytes: &[u8], backend: KeyBackend) -> Result<[u8; KEY_BYTES]> {
+    if bytes.len() != KEY_BYTES {
+        anyhow::bail!(
+            "Master key from the {} key store has invalid length (expected {KEY_BYTES} bytes)",
+            backend.name()
+        );
+    }
+    let mut key = [0u8; KEY_BYTES];
+    key.copy_from_slice(bytes);
+    Ok(key)
+}
+
+#[cfg(windows)]
+mod dpapi {
+    use anyhow::Result;
+    use winapi::um::dpapi::{
+        CryptProtectData, CryptUnprotectData, CRYPTPROTECT_LOCAL_MACHINE, CRYPTPROTECT_UI_FORBIDDEN,
+    };
+    use winapi::um::winbase::LocalFree;
+    use winapi::um::wincrypt::DATA_BLOB;
+
+    /// Binds blobs to this purpose; other DPAPI users on the machine cannot swap them in.
+    const ENTROPY: &[u8] = b"cadalytix-installer-master-key-v1";
+    const FLAGS: u32 = CRYPTPROTECT_LOCAL_MACHINE | CRYPTPROTECT_UI_FORBIDDEN;
+
+    fn blob(data: &[u8]) -> DATA_BLOB {
+        DATA_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        }
+    }
+
+    fn take(out: DATA_BLOB) -> Vec<u8> {
+        // SAFETY: DPAPI returned a LocalAlloc'd buffer of cbData bytes that we own.
+        unsafe {
+            let bytes = std::slice::from_raw_parts(out.pbData, out.cbData as usize).to_vec();
+            LocalFree(out.pbData as _);
+            bytes
+        }
+    }
+
+    pub fn protect(data: &[u8]) -> Result<Vec<u8>> {
+        let mut input = blob(data);
+        let mut entropy = blob(ENTROPY);
+        let mut out = blob(&[]);
+        // SAFETY: all blobs point at live buffers for the duration of the call.
+        let ok = unsafe {
+            CryptProtectData(
+                &mut input,
+                std::ptr::null(),
+                &mut entropy,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                FLAGS,
+                &mut out,
+            )
+        };
+        if ok == 0 {
+            anyhow::bail!(
+                "CryptProtectData failed: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(take(out))
+    }
+
+    pub fn unprotect(data: &[u8]) -> Result<Vec<u8>> {
+        let mut input = blob(data);
+        let mut entropy = blob(ENTROPY);
+        let mut out = blob(&[]);
+        // SAFETY: all blobs point at live buffers for the duration of the call.
+        let ok = unsafe {
+            CryptUnprotectData(
+                &mut input,
+                std::ptr::null_mut(),
+                &mut entropy,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                FLAGS,
+                &mut out,
+            )
+        };
+        if ok == 0 {
+            anyhow::bail!(
+                "CryptUnprotectData failed (was the key created on another machine?): {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(take(out))
+    }
+}
+
+#[cfg(not(windows))]
+mod dpapi {
+    use anyhow::Result;
+
+    pub fn protect(_data: &[u8]) -> Result<Vec<u8>> {
+        anyhow::bail!("DPAPI is only available on Windows")
+    }
+
+    pub fn unprotect(_data: &[u8]) -> Result<Vec<u8>> {
+        anyhow::bail!("DPAPI is only available on Windows")
+    }
+}
+
+mod secret_service {
+    use anyhow::{Context, Result};
+    use base64::Engine;
+    use std::path::Path;
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+    use tokio::process::Command;
+    use tokio::time::{timeout, Duration};
+
+    const SERVICE: &str = "cadalytix-installer";
+    /// A locked keyring may prompt; do not hang the wizard on it.
+    const TIMEOUT: Duration = Duration::from_secs(15);
+
+    fn attributes(key_path: &Path) -> Vec<String> {
+        vec![
+            "service".to_string(),
+            SERVICE.to_string(),
+            "key-path".to_string(),
+            key_path.to_string_lossy().to_string(),
+        ]
+    }
+
+    pub async fn lookup(key_path: &Path) -> Result<Vec<u8>> {
+        let out = timeout(
+            TIMEOUT,
+            Command::new("secret-tool")
+                .arg("lookup")
+                .args(attributes(key_path))
+                .stdin(Stdio::null())
+                .output(),
+        )
+        .await
+        .context("secret-tool lookup timed out (is the keyring locked?)")?
+        .context("Failed to run secret-tool")?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "secret-tool lookup failed (exit={:?}): {}",
+                out.status.code(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        let text = String::from_utf8_lossy(&out.stdout);
+        base64::engine::general_purpose::STANDARD
+            .decode(text.trim())
+            .context("Master key from the keyring is not valid base64")
+    }
+
+    pub async fn store(key_path: &Path, key: &[u8]) -> Result<()> {
+        let mut child = Command::new("secret-tool")
+            .arg("store")
+            .arg("--label=CADalytix installer master key")
+            .args(attributes(key_path))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run secret-tool")?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to open secret-tool stdin"))?;
+        stdin
+            .write_all(
+                base64::engine::general_purpose::STANDARD
+                    .encode(key)
+                    .as_bytes(),
+            )
+            .await?;
+        drop(stdin);
+        let out = timeout(TIMEOUT, child.wait_with_output())
+            .await
+            .context("secret-tool store timed out (is the keyring locked?)")??;
+        if !out.status.success() {
+            anyhow::bail!(
+                "secret-tool store failed (exit={:?}): {}",
+                out.status.code(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,5 @@
+pub mod audit;
 pub mod crypto;
+pub mod key_store;
+pub mod rotation;
 pub mod secret_protector;
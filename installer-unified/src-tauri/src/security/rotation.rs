@@ -0,0 +1,330 @@
+//! Secret rotation (`--rotate-secrets` / `rotate_secrets`).
+//!
+//! Re-encrypts every persisted secret under a newly generated master key:
+//! - encrypted instance settings (DB connection strings, bootstrap secret) and license-state
+//!   tokens in the config DB, rewritten in one transaction;
+//! - encrypted files next to the key (`secrets/*.enc`: license entitlement, pinned keys).
+//!
+//! Order of operations:
+//! 1. Everything is decrypted and re-encrypted in memory first; any value the current key cannot
+//!    read aborts the rotation before the DB, the files or the key change.
+//! 2. The current key is written to a timestamped backup in the same key store and read back.
+//! 3. DB rows are rewritten, new file contents are staged as `*.tmp`, then the key is swapped
+//!    (write + rename for the key file). If the swap fails, the DB rows are moved back under the
+//!    old key and the staged files are dropped.
+//! 4. Staged files replace the originals.
+//!
+//! Archives are not re-encrypted (they may live in object storage). The archive key derived from
+//! the old master key is pinned instead (`secret_protector::pinned_key_path`), so existing and new
+//! archives share one key and one ledger fingerprint.
+//!
+//! Other running installer processes keep the old key in memory; rotate while the wizard is idle.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::api::preflight_report::CONFIG_DB_CONNECTION_ENV;
+use crate::database::connection::DatabaseConnection;
+use crate::database::platform_db::PlatformDbAdapter;
+use crate::error::{InstallerError, OrCode};
+use crate::security::secret_protector::{
+    default_key_path, pinned_key_path, SecretProtector, KEY_BYTES,
+};
+
+/// Derived-key purposes carried over (not rotated) because their data is not re-encrypted.
+const PINNED_PURPOSES: &[&str] = &[crate::archiver::ARCHIVE_KEY_PURPOSE];
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationReport {
+    /// Key store holding the new key ("file", "dpapi", "secret-service").
+    pub key_store: String,
+    /// Backup of the previous master key (in the same key store).
+    pub backup_key_path: String,
+    /// Encrypted values rewritten in the config DB.
+    pub database_values_rotated: usize,
+    /// Encrypted files rewritten (file names under `secrets/`).
+    pub files_rotated: Vec<String>,
+    /// Derived-key purposes pinned by this rotation.
+    pub pinned_keys: Vec<String>,
+}
+
+/// Rotate the master key of `secrets`, re-encrypting the config DB (when `db` is given) and the
+/// encrypted files next to the key.
+pub async fn rotate_secrets(
+    secrets: &Arc<SecretProtector>,
+    db: Option<DatabaseConnection>,
+) -> Result<RotationReport> {
+    let old_key = secrets.master_key().await?;
+    let backend = secrets.active_backend().await;
+    let key_path = secrets.key_path().to_path_buf();
+    let secrets_dir = key_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    info!(
+        "[PHASE: security] [STEP: rotate_secrets] Rotating master key (key_store={}, database={})",
+        backend.name(),
+        db.is_some()
+    );
+
+    let mut new_key = [0u8; KEY_BYTES];
+    SystemRandom::new()
+        .fill(&mut new_key)
+        .map_err(|_| anyhow::anyhow!("Failed to generate secret key"))?;
+    let next = Arc::new(SecretProtector::with_key(
+        key_path.clone(),
+        backend,
+        new_key,
+    ));
+
+    // 1. Re-encrypt files in memory.
+    let mut staged: Vec<(PathBuf, String)> = Vec::new();
+    for path in encrypted_files(&secrets_dir, secrets).await? {
+        let current = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read encrypted file: {:?}", path))?;
+        let plaintext = secrets
+            .decrypt(current.trim())
+            .await
+            .with_context(|| format!("{:?} cannot be decrypted with the current key", path))?;
+        staged.push((path, next.encrypt(&plaintext).await?));
+    }
+    let files_rotated: Vec<String> = staged
+        .iter()
+        .filter_map(|(p, _)| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .collect();
+    let mut pinned_keys = Vec::new();
+    for purpose in PINNED_PURPOSES {
+        let path = pinned_key_path(&key_path, purpose);
+        if staged.iter().any(|(p, _)| *p == path) {
+            continue;
+        }
+        let derived = secrets.derive_key(purpose).await?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(derived);
+        staged.push((path, next.encrypt(&encoded).await?));
+        pinned_keys.push(purpose.to_string());
+    }
+
+    // 2. Verified backup of the current key.
+    let backup_path = backup_key_path(&key_path, Utc::now());
+    SecretProtector::with_backend(backup_path.clone(), backend)
+        .replace_key(old_key, backend)
+        .await
+        .context("Failed to back up the current master key")?;
+    let read_back = SecretProtector::with_backend(backup_path.clone(), backend)
+        .master_key()
+        .await
+        .context("Failed to read back the master key backup")?;
+    if read_back != old_key {
+        anyhow::bail!(
+            "Master key backup {:?} does not match the current key; nothing was rotated",
+            backup_path
+        );
+    }
+
+    // 3. DB rows, staged files, key swap.
+    let database_values_rotated = match &db {
+        Some(conn) => PlatformDbAdapter::new(conn.clone(), Arc::clone(secrets))
+            .reencrypt_secrets(&next)
+            .await
+            .context("Failed to re-encrypt database secrets; nothing was rotated")?,
+        None => 0,
+    };
+    let swapped: Result<()> = async {
+        for (path, content) in &staged {
+            tokio::fs::write(tmp_path(path), content)
+                .await
+                .with_context(|| format!("Failed to stage {:?}", path))?;
+        }
+        secrets.replace_key(new_key, backend).await
+    }
+    .await;
+    if let Err(e) = swapped {
+        for (path, _) in &staged {
+            let _ = tokio::fs::remove_file(tmp_path(path)).await;
+        }
+        if let Some(conn) = &db {
+            if let Err(undo) = PlatformDbAdapter::new(conn.clone(), Arc::clone(&next))
+                .reencrypt_secrets(secrets)
+                .await
+            {
+                error!(
+                    "[PHASE: security] [STEP: rotate_secrets] Failed to move database secrets back under the previous key: {:?}",
+                    undo
+                );
+            }
+        }
+        return Err(e.context(format!(
+            "Secret rotation failed; the previous master key is still in use (backup: {:?})",
+            backup_path
+        )));
+    }
+
+    // 4. Swap staged files in.
+    for (path, _) in &staged {
+        tokio::fs::rename(tmp_path(path), path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to replace {:?} after the key swap; the backup key {:?} still decrypts it",
+                    path, backup_path
+                )
+            })?;
+    }
+
+    info!(
+        "[PHASE: security] [STEP: rotate_secrets] Master key rotated (database_values={}, files={}, backup={:?})",
+        database_values_rotated,
+        files_rotated.len(),
+        backup_path
+    );
+    Ok(RotationReport {
+        key_store: backend.name().to_string(),
+        backup_key_path: backup_path.to_string_lossy().to_string(),
+        database_values_rotated,
+        files_rotated,
+        pinned_keys,
+    })
+}
+
+/// `--rotate-secrets [--files-only]`: rotate the master key under the log folder. The config DB
+/// connection string comes from `CADALYTIX_CONFIG_DB_CONNECTION`, never argv; `--files-only`
+/// skips the DB on machines where it was never created.
+pub async fn rotate_secrets_cli(args: &[String]) -> Result<RotationReport> {
+    let files_only = args.iter().any(|a| a == "--files-only");
+    let conn_str = std::env::var(CONFIG_DB_CONNECTION_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty());
+    if conn_str.is_none() && !files_only {
+        return Err(anyhow::anyhow!(
+            "Set {} to the config DB connection string, or pass --files-only if no config DB was ever created",
+            CONFIG_DB_CONNECTION_ENV
+        ))
+        .or_code(InstallerError::InvalidArguments);
+    }
+
+    let log_dir = crate::utils::path_resolver::resolve_log_folder()?;
+    let secrets = Arc::new(SecretProtector::new(default_key_path(&log_dir)));
+    let db = match conn_str {
+        Some(cs) if !files_only => {
+            let engine = crate::api::installer::guess_engine(&cs);
+            Some(
+                crate::api::installer::connect_with_retry(engine, cs)
+                    .await
+                    .or_code(InstallerError::DatabaseConnectionFailed)?,
+            )
+        }
+        _ => None,
+    };
+    rotate_secrets(&secrets, db)
+        .await
+        .or_code(InstallerError::SecretRotationFailed)
+}
+
+/// `*.enc` files in `dir` holding an encrypted value.
+async fn encrypted_files(dir: &Path, secrets: &SecretProtector) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    if !tokio::fs::try_exists(dir).await.unwrap_or(false) {
+        return Ok(out);
+    }
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("read_dir failed: {:?}", dir))?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("enc") || !path.is_file() {
+            continue;
+        }
+        let content = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+        if secrets.is_encrypted(content.trim()) {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// `installer_master_key.<UTC timestamp>.bak.b64` next to the key; never an existing backup.
+fn backup_key_path(key_path: &Path, now: DateTime<Utc>) -> PathBuf {
+    let stem = key_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "installer_master_key".to_string());
+    let stamp = now.format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let mut candidate = key_path.with_file_name(format!("{}.{}.bak.b64", stem, stamp));
+    let mut n = 1;
+    while ["b64", "dpapi", "keyring"]
+        .iter()
+        .any(|ext| candidate.with_extension(ext).exists())
+    {
+        candidate = key_path.with_file_name(format!("{}.{}-{}.bak.b64", stem, stamp, n));
+        n += 1;
+    }
+    candidate
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::key_store::KeyBackend;
+
+    #[tokio::test]
+    async fn rotation_reencrypts_files_and_keeps_archive_key() {
+        let dir = std::env::temp_dir().join(format!("rotate-{}", uuid::Uuid::new_v4()));
+        let key_path = dir.join("secrets").join("installer_master_key.b64");
+        let secrets = Arc::new(SecretProtector::with_backend(
+            key_path.clone(),
+            KeyBackend::File,
+        ));
+        let entitlement = dir.join("secrets").join("license_entitlement.enc");
+        let old_value = secrets.encrypt("signed.jwt.token").await.unwrap();
+        std::fs::write(&entitlement, &old_value).unwrap();
+        let archive_key = secrets.derive_key("archive").await.unwrap();
+
+        let report = rotate_secrets(&secrets, None).await.unwrap();
+        assert_eq!(report.key_store, "file");
+        assert_eq!(report.files_rotated, vec!["license_entitlement.enc"]);
+        assert_eq!(report.pinned_keys, vec!["archive"]);
+
+        // A fresh protector reads the new key from disk.
+        let reopened = SecretProtector::with_backend(key_path.clone(), KeyBackend::File);
+        let rotated = std::fs::read_to_string(&entitlement).unwrap();
+        assert_ne!(rotated, old_value);
+        assert_eq!(
+            reopened.decrypt(&rotated).await.unwrap(),
+            "signed.jwt.token"
+        );
+        assert!(reopened.decrypt(&old_value).await.is_err());
+        assert_eq!(reopened.derive_key("archive").await.unwrap(), archive_key);
+
+        // The backup still decrypts values written before the rotation.
+        let backup =
+            SecretProtector::with_backend(PathBuf::from(&report.backup_key_path), KeyBackend::File);
+        assert_eq!(
+            backup.decrypt(&old_value).await.unwrap(),
+            "signed.jwt.token"
+        );
+
+        // A second rotation carries the pinned archive key over again.
+        let report = rotate_secrets(&secrets, None).await.unwrap();
+        assert!(report.pinned_keys.is_empty());
+        assert_eq!(report.files_rotated.len(), 2);
+        let reopened = SecretProtector::with_backend(key_path, KeyBackend::File);
+        assert_eq!(reopened.derive_key("archive").await.unwrap(), archive_key);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,38 +4,107 @@
 // It provides:
 // - Deterministic "is encrypted?" detection via a prefix
 // - Authenticated encryption using AES-256-GCM
-// - Lazy, file-backed master key stored under the log folder (Prod_Wizard_Log/)
+// - Lazy master key, held by OS key storage (Windows DPAPI / Linux Secret Service, see
+//   `security::key_store`) when available, else a key file under the log folder (Prod_Wizard_Log/)
 //
-// NOTE: In the long-term, the master key should be protected by OS facilities
-// (Windows DPAPI / Linux keyring). For Phase 4, we enforce encryption-at-rest for
-// database secrets and avoid logging plaintext.
+// An existing plaintext key file is moved into the OS key store on first use, so secrets written
+// by older installers stay readable.
 
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce};
 use anyhow::{Context, Result};
 use base64::Engine;
+use log::{info, warn};
 use ring::rand::{SecureRandom, SystemRandom};
 use std::path::{Path, PathBuf};
-use tokio::sync::OnceCell;
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tokio::sync::RwLock;
 use tokio_retry::RetryIf;
 
+use super::key_store::{self, KeyBackend};
+use crate::utils::retry;
+
 const ENC_PREFIX: &str = "ENCv1:";
-const KEY_BYTES: usize = 32;
+pub(crate) const KEY_BYTES: usize = 32;
 const NONCE_BYTES: usize = 12;
 
 #[derive(Debug)]
 pub struct SecretProtector {
     key_path: PathBuf,
-    key: OnceCell<[u8; KEY_BYTES]>,
+    backend: KeyBackend,
+    key: RwLock<Option<[u8; KEY_BYTES]>>,
 }
 
 impl SecretProtector {
+    /// Protector whose master key lives in the best key store on this machine.
     pub fn new(key_path: PathBuf) -> Self {
+        Self::with_backend(key_path, KeyBackend::detect())
+    }
+
+    pub fn with_backend(key_path: PathBuf, backend: KeyBackend) -> Self {
+        Self {
+            key_path,
+            backend,
+            key: RwLock::new(None),
+        }
+    }
+
+    /// Protector over an in-memory `key`; nothing is stored until `replace_key`.
+    pub(crate) fn with_key(key_path: PathBuf, backend: KeyBackend, key: [u8; KEY_BYTES]) -> Self {
         Self {
             key_path,
-            key: OnceCell::new(),
+            backend,
+            key: RwLock::new(Some(key)),
+        }
+    }
+
+    pub(crate) fn key_path(&self) -> &Path {
+        &self.key_path
+    }
+
+    /// The master key, loaded (or created) on first use.
+    pub(crate) async fn master_key(&self) -> Result<[u8; KEY_BYTES]> {
+        self.get_or_init_key().await
+    }
+
+    /// Where the master key lives now: the OS key store once it holds the key, else the key file.
+    pub(crate) async fn active_backend(&self) -> KeyBackend {
+        if self.backend != KeyBackend::File
+            && key_store::is_provisioned(self.backend, &self.key_path).await
+        {
+            self.backend
+        } else {
+            KeyBackend::File
+        }
+    }
+
+    /// Persist `key` in `backend`, replacing the stored master key, and use it from now on.
+    /// The key file is replaced atomically (write + rename).
+    pub(crate) async fn replace_key(
+        &self,
+        key: [u8; KEY_BYTES],
+        backend: KeyBackend,
+    ) -> Result<()> {
+        let mut slot = self.key.write().await;
+        if backend == KeyBackend::File {
+            if let Some(parent) = self.key_path.parent() {
+                tokio::fs::create_dir_all(parent).await.with_context(|| {
+                    format!("Failed to create secret key directory: {:?}", parent)
+                })?;
+            }
+            let tmp = self.key_path.with_extension("tmp");
+            tokio::fs::write(&tmp, base64::engine::general_purpose::STANDARD.encode(key))
+                .await
+                .with_context(|| format!("Failed to write secret key file: {:?}", tmp))?;
+            tokio::fs::rename(&tmp, &self.key_path)
+                .await
+                .with_context(|| {
+                    format!("Failed to replace secret key file: {:?}", self.key_path)
+                })?;
+        } else {
+            key_store::store(backend, &self.key_path, &key).await?;
         }
+        *slot = Some(key);
+        Ok(())
     }
 
     pub fn is_encrypted(&self, value: &str) -> bool {
@@ -47,7 +116,7 @@ impl SecretProtector {
             return Ok(ENC_PREFIX.to_string());
         }
 
-        let key = *self.get_or_init_key().await?;
+        let key = self.get_or_init_key().await?;
         let cipher = Aes256Gcm::new_from_slice(&key)
             .map_err(|_| anyhow::anyhow!("Internal error: invalid AES-256 key length"))?;
 
@@ -95,7 +164,7 @@ impl SecretProtector {
         let (nonce_bytes, ciphertext) = blob.split_at(NONCE_BYTES);
         let nonce = Nonce::from_slice(nonce_bytes);
 
-        let key = *self.get_or_init_key().await?;
+        let key = self.get_or_init_key().await?;
         let cipher = Aes256Gcm::new_from_slice(&key)
             .map_err(|_| anyhow::anyhow!("Internal error: invalid AES-256 key length"))?;
 
@@ -106,73 +175,146 @@ impl SecretProtector {
         Ok(s)
     }
 
-    async fn get_or_init_key(&self) -> Result<&[u8; KEY_BYTES]> {
-        self.key
-            .get_or_try_init(|| async {
-                // Try load from disk; if missing, create.
-                if tokio::fs::try_exists(&self.key_path).await.unwrap_or(false) {
-                    let bytes = tokio::fs::read(&self.key_path).await.with_context(|| {
-                        format!("Failed to read secret key file: {:?}", self.key_path)
-                    })?;
-
-                    let decoded = base64::engine::general_purpose::STANDARD
-                        .decode(bytes)
-                        .context("Failed to decode secret key file (base64)")?;
-
-                    if decoded.len() != KEY_BYTES {
-                        anyhow::bail!(
-                            "Secret key file has invalid length (expected {KEY_BYTES} bytes)"
-                        );
-                    }
-
-                    let mut key = [0u8; KEY_BYTES];
-                    key.copy_from_slice(&decoded);
-                    return Ok(key);
-                }
+    /// Derive a purpose-bound 32-byte key from the master key (HMAC-SHA256).
+    /// Bulk data (e.g. archives) is never encrypted with the master key itself.
+    ///
+    /// A key pinned by secret rotation (`pinned_key_path`) takes precedence, so data sealed
+    /// under the previous master key stays readable.
+    pub async fn derive_key(&self, purpose: &str) -> Result<[u8; KEY_BYTES]> {
+        let pinned = pinned_key_path(&self.key_path, purpose);
+        if tokio::fs::try_exists(&pinned).await.unwrap_or(false) {
+            let stored = tokio::fs::read_to_string(&pinned)
+                .await
+                .with_context(|| format!("Failed to read pinned key: {:?}", pinned))?;
+            let encoded = self
+                .decrypt(stored.trim())
+                .await
+                .with_context(|| format!("Failed to decrypt pinned {} key", purpose))?;
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .context("Pinned key is not valid base64")?;
+            if decoded.len() != KEY_BYTES {
+                anyhow::bail!("Pinned {} key has invalid length", purpose);
+            }
+            let mut key = [0u8; KEY_BYTES];
+            key.copy_from_slice(&decoded);
+            return Ok(key);
+        }
+
+        let master = self.get_or_init_key().await?;
+        let info = format!("cadalytix-derived-key-v1:{}", purpose);
+        let mac = crate::security::crypto::hmac_sha256(&master, info.as_bytes());
+        let mut key = [0u8; KEY_BYTES];
+        key.copy_from_slice(&mac[..KEY_BYTES]);
+        Ok(key)
+    }
 
-                // Create parent dir
-                if let Some(parent) = self.key_path.parent() {
-                    tokio::fs::create_dir_all(parent).await.with_context(|| {
-                        format!("Failed to create secret key directory: {:?}", parent)
-                    })?;
+    async fn get_or_init_key(&self) -> Result<[u8; KEY_BYTES]> {
+        if let Some(key) = *self.key.read().await {
+            return Ok(key);
+        }
+        let mut slot = self.key.write().await;
+        if let Some(key) = *slot {
+            return Ok(key);
+        }
+        let key = self.init_key().await?;
+        *slot = Some(key);
+        Ok(key)
+    }
+
+    async fn init_key(&self) -> Result<[u8; KEY_BYTES]> {
+        if self.backend == KeyBackend::File {
+            return self.load_or_create_file_key().await;
+        }
+        // Once the OS store holds the key it is authoritative: failing to read it is an
+        // error, never a reason to mint a new key.
+        if key_store::is_provisioned(self.backend, &self.key_path).await {
+            return key_store::load(self.backend, &self.key_path).await;
+        }
+
+        let migrating = tokio::fs::try_exists(&self.key_path).await.unwrap_or(false);
+        let key = self.load_or_create_file_key().await?;
+        match key_store::store(self.backend, &self.key_path, &key).await {
+            Ok(()) => {
+                info!(
+                    "[PHASE: security] [STEP: master_key] Master key stored in the {} key store (migrated_from_file={})",
+                    self.backend.name(),
+                    migrating
+                );
+                if let Err(e) = tokio::fs::remove_file(&self.key_path).await {
+                    warn!(
+                        "[PHASE: security] [STEP: master_key] Failed to remove plaintext key file {:?}: {}",
+                        self.key_path, e
+                    );
                 }
+            }
+            Err(e) => warn!(
+                "[PHASE: security] [STEP: master_key] {} key store unavailable, keeping the key file {:?}: {}",
+                self.backend.name(),
+                self.key_path,
+                e
+            ),
+        }
+        Ok(key)
+    }
+
+    /// Plaintext key file at `key_path`: loaded when present, otherwise created.
+    async fn load_or_create_file_key(&self) -> Result<[u8; KEY_BYTES]> {
+        // Try load from disk; if missing, create.
+        if tokio::fs::try_exists(&self.key_path).await.unwrap_or(false) {
+            let bytes = tokio::fs::read(&self.key_path)
+                .await
+                .with_context(|| format!("Failed to read secret key file: {:?}", self.key_path))?;
+
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(bytes)
+                .context("Failed to decode secret key file (base64)")?;
+
+            if decoded.len() != KEY_BYTES {
+                anyhow::bail!("Secret key file has invalid length (expected {KEY_BYTES} bytes)");
+            }
+
+            let mut key = [0u8; KEY_BYTES];
+            key.copy_from_slice(&decoded);
+            return Ok(key);
+        }
+
+        // Create parent dir
+        if let Some(parent) = self.key_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create secret key directory: {:?}", parent))?;
+        }
 
-                let mut key_bytes = [0u8; KEY_BYTES];
-                SystemRandom::new()
-                    .fill(&mut key_bytes)
-                    .map_err(|_| anyhow::anyhow!("Failed to generate secret key"))?;
-
-                // Persist with retries (file may be locked by AV, etc.)
-                let encoded = base64::engine::general_purpose::STANDARD.encode(key_bytes);
-                let write_action = || async {
-                    // Atomic create-new to avoid races; if it already exists, we reload next call.
-                    let mut opts = tokio::fs::OpenOptions::new();
-                    opts.write(true).create_new(true);
-                    let mut file = opts.open(&self.key_path).await.with_context(|| {
-                        format!("Failed to create secret key file: {:?}", self.key_path)
-                    })?;
-                    use tokio::io::AsyncWriteExt;
-                    file.write_all(encoded.as_bytes()).await?;
-                    file.flush().await?;
-                    Ok::<(), anyhow::Error>(())
-                };
-
-                let retry_strategy = ExponentialBackoff::from_millis(50)
-                    .factor(2)
-                    .max_delay(std::time::Duration::from_millis(750))
-                    .take(3)
-                    .map(jitter);
-
-                let _ = RetryIf::spawn(retry_strategy, write_action, |e: &anyhow::Error| {
-                    is_transient_io_error(e)
-                })
-                .await;
-
-                // If create_new failed due to already existing, that's fine; we'll continue using our in-memory key for this run.
-                Ok(key_bytes)
-            })
-            .await
-            .map(|k| k as &[u8; KEY_BYTES])
+        let mut key_bytes = [0u8; KEY_BYTES];
+        SystemRandom::new()
+            .fill(&mut key_bytes)
+            .map_err(|_| anyhow::anyhow!("Failed to generate secret key"))?;
+
+        // Persist with retries (file may be locked by AV, etc.)
+        let encoded = base64::engine::general_purpose::STANDARD.encode(key_bytes);
+        let write_action = || async {
+            // Atomic create-new to avoid races; if it already exists, we reload next call.
+            let mut opts = tokio::fs::OpenOptions::new();
+            opts.write(true).create_new(true);
+            let mut file = opts.open(&self.key_path).await.with_context(|| {
+                format!("Failed to create secret key file: {:?}", self.key_path)
+            })?;
+            use tokio::io::AsyncWriteExt;
+            file.write_all(encoded.as_bytes()).await?;
+            file.flush().await?;
+            Ok::<(), anyhow::Error>(())
+        };
+
+        let retry_strategy = retry::policy().backoff();
+
+        let _ = RetryIf::spawn(retry_strategy, write_action, |e: &anyhow::Error| {
+            is_transient_io_error(e)
+        })
+        .await;
+
+        // If create_new failed due to already existing, that's fine; we'll continue using our in-memory key for this run.
+        Ok(key_bytes)
     }
 }
 
@@ -191,6 +333,14 @@ fn is_transient_io_error(err: &anyhow::Error) -> bool {
             .contains("sharing violation")
 }
 
+/// Encrypted copy of a derived key carried over by secret rotation (next to the key path).
+pub fn pinned_key_path(key_path: &Path, purpose: &str) -> PathBuf {
+    key_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(format!("derived_{}_key.enc", purpose))
+}
+
 /// Helper to build the default key path under a log folder.
 pub fn default_key_path(log_folder: &Path) -> PathBuf {
     log_folder.join("secrets").join("installer_master_key.b64")
@@ -308,4 +458,22 @@ mod tests {
             assert_eq!(decrypted, plaintext, "Key must persist between instances");
         }
     }
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_unavailable_key_store_falls_back_to_key_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let key_path = temp_dir.path().join("test_key.b64");
+
+        // DPAPI cannot store the key here: the protector keeps using the key file.
+        let protector = SecretProtector::with_backend(key_path.clone(), KeyBackend::Dpapi);
+        let encrypted = protector.encrypt("fallback").await.unwrap();
+        assert!(
+            key_path.exists(),
+            "Key file must be kept when the key store fails"
+        );
+        assert!(!key_store::is_provisioned(KeyBackend::Dpapi, &key_path).await);
+
+        let reopened = SecretProtector::with_backend(key_path, KeyBackend::File);
+        assert_eq!(reopened.decrypt(&encrypted).await.unwrap(), "fallback");
+    }
 }
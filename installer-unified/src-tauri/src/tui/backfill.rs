@@ -0,0 +1,168 @@
+//! Historical backfill estimate on the Ready page.
+//!
+//! Entering Ready asks the primary call data source for its rows per month
+//! (`backfill::estimate_backfill`) in the background; the summary shows the projected hot DB size,
+//! archive volume and backfill duration once it arrives. The estimate is keyed on the source,
+//! object, watermark column and retention it was made for, so coming back to Ready unchanged
+//! keeps it and a stale answer from an earlier visit is dropped.
+
+use super::*;
+use crate::api::backfill::{
+    estimate_backfill, format_bytes, format_duration, request_for, WATERMARK_TARGET,
+};
+use crate::models::requests::BackfillEstimateRequestDto;
+use crate::models::responses::BackfillEstimateDto;
+
+fn request_key(req: &BackfillEstimateRequestDto) -> String {
+    format!(
+        "{}|{}|{}|{}|{}|{}",
+        req.call_data_driver,
+        req.call_data_connection_string,
+        req.source_object_name.trim(),
+        req.watermark_column.trim(),
+        req.hot_retention_months,
+        req.demo_mode
+    )
+}
+
+/// Start estimating for the current settings unless that estimate is already done or running.
+pub(super) fn start(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    let Some(payload) = request_for(&build_install_request(state), state.mapping_demo_mode) else {
+        state.backfill_key = None;
+        state.backfill_estimate = None;
+        state.backfill_estimating = false;
+        return;
+    };
+    let key = request_key(&payload);
+    if state.backfill_key.as_deref() == Some(key.as_str()) {
+        return;
+    }
+    state.backfill_key = Some(key.clone());
+    state.backfill_estimate = None;
+    state.backfill_estimating = true;
+
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let result = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => match rt.block_on(estimate_backfill(payload)) {
+                Ok(api) if api.success => api
+                    .data
+                    .ok_or_else(|| "The source returned no estimate.".to_string()),
+                Ok(api) => Err(api
+                    .error
+                    .unwrap_or_else(|| "Unable to estimate the backfill.".to_string())),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(format!("Internal error starting the estimate: {}", e)),
+        };
+        let _ = tx.send(UiMsg::BackfillEstimated { key, result });
+    });
+}
+
+/// Keep a finished estimate if it is for the settings last sent.
+pub(super) fn finished(
+    state: &mut WizardState,
+    key: String,
+    result: Result<BackfillEstimateDto, String>,
+) {
+    if state.backfill_key.as_deref() != Some(key.as_str()) {
+        return;
+    }
+    state.backfill_estimating = false;
+    state.backfill_estimate = Some(result);
+}
+
+/// Ready page lines for the estimate (none before one was started).
+pub(super) fn summary(state: &WizardState) -> Vec<String> {
+    if state.backfill_estimating {
+        return vec!["Backfill estimate: reading row counts from the source...".to_string()];
+    }
+    let est = match state.backfill_estimate.as_ref() {
+        Some(Ok(est)) => est,
+        Some(Err(e)) => return vec![format!("Backfill estimate unavailable: {}", e)],
+        None if state.data_source_kind == DataSourceKind::File && !state.mapping_demo_mode => {
+            return vec!["Backfill estimate: not available for export folders.".to_string()]
+        }
+        None => return Vec::new(),
+    };
+    if est.total_rows == 0 {
+        return vec![format!(
+            "Backfill estimate: no rows with a {} value in the source.",
+            WATERMARK_TARGET
+        )];
+    }
+    vec![
+        format!(
+            "Backfill estimate: {} rows over {} months ({} hot, {} to archive)",
+            est.total_rows,
+            est.months.len(),
+            est.hot_rows,
+            est.archive_rows
+        ),
+        format!(
+            "Projected hot DB size: {}{}",
+            format_bytes(est.projected_hot_db_bytes),
+            if est.avg_row_bytes_estimated {
+                " (row size estimated)"
+            } else {
+                ""
+            }
+        ),
+        format!(
+            "Projected archive volume: {}",
+            format_bytes(est.projected_archive_bytes)
+        ),
+        format!(
+            "Estimated backfill time: {}",
+            format_duration(est.estimated_duration_secs)
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_is_kept_per_settings_and_summarised() {
+        let (tx, _rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.call_data_database.set("FireCAD");
+        state.source_object_name.set("dbo.Calls");
+        state.page = Page::Ready;
+
+        start(&mut state, &tx);
+        assert!(state.backfill_estimating);
+        let key = state.backfill_key.clone().unwrap();
+        start(&mut state, &tx);
+        assert_eq!(state.backfill_key.as_deref(), Some(key.as_str()));
+
+        finished(&mut state, "stale".to_string(), Err("old".to_string()));
+        assert!(state.backfill_estimating);
+        finished(
+            &mut state,
+            key,
+            Ok(BackfillEstimateDto {
+                total_rows: 1_200_000,
+                hot_rows: 700_000,
+                archive_rows: 500_000,
+                projected_hot_db_bytes: 595_000_000,
+                projected_archive_bytes: 63_750_000,
+                estimated_duration_secs: 600,
+                ..Default::default()
+            }),
+        );
+        let lines = summary(&state);
+        assert!(lines[0].contains("700000 hot, 500000 to archive"));
+        assert_eq!(lines[1], "Projected hot DB size: 595.0 MB");
+        assert_eq!(lines[3], "Estimated backfill time: 10 min");
+
+        state.data_source_kind = DataSourceKind::File;
+        state.file_source_folder.set("/exports");
+        start(&mut state, &tx);
+        assert!(summary(&state)[0].contains("export folders"));
+    }
+}
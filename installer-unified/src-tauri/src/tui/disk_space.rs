@@ -0,0 +1,137 @@
+//! Disk space projection on the Ready page.
+//!
+//! Entering Ready projects the install's disk use per volume
+//! (`installation::space_projection::project`) in the background. While it runs, and while any
+//! measured volume is short, Install is blocked; the summary names the volume and the shortfall.
+//! Pressing Install while blocked measures again, so freeing space does not need a restart.
+
+use super::*;
+use crate::installation::space_projection::{self, SpaceProjection};
+
+/// The parts of the request the projection depends on (the payload follows the install mode).
+fn request_key(req: &StartInstallRequest) -> String {
+    format!("{}|{:?}", req.install_mode, space_projection::needs(req, 0))
+}
+
+/// Project the current settings; a result for earlier settings is dropped when it arrives.
+pub(super) fn start(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    let req = build_install_request(state);
+    let key = request_key(&req);
+    state.disk_space_key = Some(key.clone());
+    state.disk_space = None;
+    state.disk_space_checking = true;
+
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let projection = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt.block_on(space_projection::project(&req)),
+            Err(e) => {
+                warn!(
+                    "[PHASE: tui] [STEP: disk_space] Projection not started: {}",
+                    e
+                );
+                SpaceProjection::default()
+            }
+        };
+        let _ = tx.send(UiMsg::DiskSpaceProjected { key, projection });
+    });
+}
+
+pub(super) fn projected(state: &mut WizardState, key: String, projection: SpaceProjection) {
+    if state.disk_space_key.as_deref() != Some(key.as_str()) {
+        return;
+    }
+    if projection.is_short() {
+        warn!(
+            "[PHASE: tui] [STEP: disk_space] Install blocked: {}",
+            projection.shortfall
+        );
+    }
+    state.disk_space_checking = false;
+    state.disk_space = Some(projection);
+}
+
+pub(super) fn is_short(state: &WizardState) -> bool {
+    state
+        .disk_space
+        .as_ref()
+        .is_some_and(SpaceProjection::is_short)
+}
+
+/// Why Install is blocked, if it is.
+pub(super) fn blocked(state: &WizardState) -> Option<String> {
+    if state.disk_space_checking {
+        return Some("Free disk space is still being checked.".to_string());
+    }
+    state
+        .disk_space
+        .as_ref()
+        .filter(|p| p.is_short())
+        .map(|p| p.shortfall.clone())
+}
+
+/// Ready page lines (none before a projection was started).
+pub(super) fn summary(state: &WizardState) -> Vec<String> {
+    if state.disk_space_checking {
+        return vec!["Disk space: checking free space...".to_string()];
+    }
+    let Some(projection) = state.disk_space.as_ref() else {
+        return Vec::new();
+    };
+    let mut lines: Vec<String> = projection.volumes.iter().map(|v| v.summary()).collect();
+    if projection.payload_estimated {
+        lines.push("Runtime file size is estimated (payload not found).".to_string());
+    }
+    if projection.is_short() {
+        lines.push(format!("INSTALL BLOCKED: {}", projection.shortfall));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installation::space_projection::{SpaceNeed, VolumeProjection};
+
+    #[test]
+    fn short_volume_blocks_install_until_projected_again() {
+        let (tx, _rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.destination_path.set("/opt/cadalytix");
+        state.page = Page::Ready;
+
+        start(&mut state, &tx);
+        assert!(!can_go_next(&state));
+        let key = state.disk_space_key.clone().unwrap();
+
+        let volume = VolumeProjection {
+            path: "/opt/cadalytix".into(),
+            needs: vec![SpaceNeed {
+                label: "runtime files",
+                path: "/opt/cadalytix".into(),
+                bytes: 3_000_000_000,
+            }],
+            required_bytes: 3_000_000_000,
+            free_bytes: Some(1_000_000_000),
+        };
+        let projection = SpaceProjection {
+            shortfall: volume.shortfall_message().unwrap(),
+            volumes: vec![volume],
+            payload_estimated: false,
+        };
+        projected(&mut state, "stale".to_string(), projection.clone());
+        assert!(state.disk_space_checking);
+        projected(&mut state, key, projection);
+        assert!(!can_go_next(&state));
+        let lines = summary(&state);
+        assert!(lines[0].contains("3.0 GB needed (runtime files 3.0 GB), 1.0 GB free"));
+        assert!(lines[1].starts_with("INSTALL BLOCKED: Not enough disk space"));
+        assert!(blocked(&state).unwrap().contains("Free up 2.0 GB"));
+
+        state.disk_space = Some(SpaceProjection::default());
+        assert!(can_go_next(&state));
+    }
+}
@@ -0,0 +1,508 @@
+//! Wizard answers kept across TUI restarts.
+//!
+//! On every page change the full-screen wizard writes its answers to a draft file
+//! (`<local data dir>/cadalytix/tui-draft.json`, `cadalytix-<name>/` for a named instance). The
+//! next launch offers to resume from it or to discard it and start over; the draft is removed
+//! when an install starts.
+//!
+//! Answers are stored per page in the `--tui-script` vocabulary and replayed with the same setter
+//! (`script::apply_answers`), one answer at a time so one that no longer applies (an unlicensed
+//! source kind, say) is skipped rather than losing the page. Passwords and connection strings are
+//! encrypted with the installer's `SecretProtector` (`ENCv1:` values). Schema mapping decisions
+//! and further data sources are not kept: a draft saved past the Mapping page resumes there, with
+//! a fresh scan.
+
+use super::script::{apply_answers, page_id, Answers};
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const DRAFT_FILE: &str = "tui-draft.json";
+const DRAFT_VERSION: u32 = 1;
+/// Answer keys whose values are stored encrypted.
+const SECRET_KEYS: &[&str] = &["password", "connection_string"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Draft {
+    version: u32,
+    saved_at: String,
+    /// Page the wizard was on (`script::page_id`).
+    page: String,
+    pages: Vec<(String, Answers)>,
+}
+
+fn draft_path() -> Option<PathBuf> {
+    let dir = crate::utils::instance::qualify("cadalytix", "-");
+    dirs::data_local_dir().map(|d| d.join(dir).join(DRAFT_FILE))
+}
+
+pub(super) fn block_on<F: std::future::Future>(f: F) -> Result<F::Output> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(f))
+}
+
+fn yes_no(b: bool) -> String {
+    (if b { "yes" } else { "no" }).to_string()
+}
+
+/// The answers on each page, in plain text.
+pub(super) fn capture(state: &WizardState) -> Vec<(Page, Answers)> {
+    let a = |pairs: &[(&str, &str)]| -> Answers {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    };
+    let mut pages = vec![
+        (
+            Page::Platform,
+            a(&[(
+                "mode",
+                match state.platform_selected {
+                    InstallMode::Windows => "windows",
+                    InstallMode::Docker => "docker",
+                },
+            )]),
+        ),
+        (
+            Page::License,
+            vec![("accept".to_string(), yes_no(state.license_accepted))],
+        ),
+        (
+            Page::InstallType,
+            a(&[
+                (
+                    "type",
+                    match state.installation_type {
+                        InstallationType::Typical => "typical",
+                        InstallationType::Custom => "custom",
+                        InstallationType::ImportConfig => "import",
+                    },
+                ),
+                ("config", &state.import_config_path.value),
+            ]),
+        ),
+    ];
+
+    let mut destination = a(&[("path", &state.destination_path.value)]);
+    if state.layout_advanced {
+        destination.extend(a(&[
+            ("binaries", &state.layout_binaries.value),
+            ("data", &state.layout_data.value),
+            ("logs", &state.layout_logs.value),
+            ("temp", &state.layout_temp.value),
+        ]));
+    }
+    pages.push((Page::Destination, destination));
+
+    let mut source = a(&[
+        (
+            "kind",
+            match state.data_source_kind {
+                DataSourceKind::Local => "local",
+                DataSourceKind::Remote => "remote",
+                DataSourceKind::Odbc => "odbc",
+                DataSourceKind::Oracle => "oracle",
+                DataSourceKind::File => "file",
+            },
+        ),
+        ("object", &state.source_object_name.value),
+        ("connection_string", &state.odbc_connection_string.value),
+        ("driver", &state.oracle_driver.value),
+        ("address", &state.oracle_address.value),
+        ("folder", &state.file_source_folder.value),
+        ("delimiter", &state.file_source_delimiter.value),
+        ("host", &state.call_data_host.value),
+        ("port", &state.call_data_port.value),
+        ("database", &state.call_data_database.value),
+        ("user", &state.call_data_user.value),
+        ("password", &state.call_data_password.value),
+    ]);
+    source.push((
+        "windows_auth".to_string(),
+        yes_no(state.call_data_windows_auth),
+    ));
+    pages.push((Page::DataSource, source));
+
+    let mut database = a(&[
+        (
+            "kind",
+            match state.db_kind {
+                DbKind::Local => "new",
+                DbKind::Remote => "existing",
+            },
+        ),
+        ("location", state.new_db_location.as_id()),
+        ("path", &state.new_db_specific_path.value),
+        ("max_size_gb", &state.new_db_max_size_gb.value),
+        ("collation", &state.new_db_collation.value),
+        ("hosted", state.existing_hosted_where.as_id()),
+        ("host", &state.db_host.value),
+        ("port", &state.db_port.value),
+        ("database", &state.db_database.value),
+        ("user", &state.db_user.value),
+        ("password", &state.db_password.value),
+        ("tls", &state.db_ssl_mode),
+        ("app_login", &state.app_login.value),
+    ]);
+    database.push(("windows_auth".to_string(), yes_no(state.db_windows_auth)));
+    if state.db_use_conn_string {
+        // Last: the field answers above switch back to details mode.
+        database.push((
+            "connection_string".to_string(),
+            state.db_conn_string.value.clone(),
+        ));
+    }
+    pages.push((Page::Database, database));
+
+    pages.push((
+        Page::Storage,
+        a(&[
+            (
+                "mode",
+                match state.storage_mode {
+                    StorageMode::Defaults => "defaults",
+                    StorageMode::Custom => "custom",
+                },
+            ),
+            (
+                "location",
+                match state.storage_location {
+                    StorageLocation::System => "system",
+                    StorageLocation::Attached => "attached",
+                    StorageLocation::Custom => "custom",
+                },
+            ),
+            ("path", &state.storage_custom_path.value),
+            (
+                "retention",
+                match state.retention_policy {
+                    RetentionPolicy::Rolling18 => "rolling18",
+                    RetentionPolicy::Rolling12 => "rolling12",
+                    RetentionPolicy::MaxDisk => "max_disk",
+                    RetentionPolicy::KeepEverything => "keep_everything",
+                },
+            ),
+            ("max_disk_gb", &state.max_disk_gb.value),
+        ]),
+    ));
+    pages.push((
+        Page::Retention,
+        a(&[(
+            "months",
+            match state.hot_retention_choice {
+                HotRetentionChoice::Months12 => "12",
+                HotRetentionChoice::Months18 => "18",
+                HotRetentionChoice::Custom => state.hot_retention_custom_months.value.as_str(),
+            },
+        )]),
+    ));
+
+    let mut archive = a(&[
+        (
+            "format",
+            match state.archive_format {
+                ArchiveFormatChoice::ZipNdjson => "ndjson",
+                ArchiveFormatChoice::ZipCsv => "csv",
+            },
+        ),
+        ("destination", &state.archive_destination.value),
+        ("max_usage_gb", &state.archive_max_usage_gb.value),
+        ("day", &state.archive_schedule_day_of_month.value),
+        ("time", &state.archive_schedule_time_local.value),
+    ]);
+    archive.push((
+        "catch_up".to_string(),
+        yes_no(state.archive_catch_up_on_startup),
+    ));
+    archive.push(("encrypt".to_string(), yes_no(state.archive_encrypt)));
+    pages.push((Page::Archive, archive));
+    pages.push((
+        Page::Consent,
+        vec![("sync".to_string(), yes_no(state.consent_to_sync))],
+    ));
+    pages
+}
+
+/// Page to resume on: the saved one, but never past Mapping (mapping is not kept).
+fn resume_page(page: Page) -> Page {
+    match page {
+        Page::Ready | Page::Installing | Page::Complete => Page::Mapping,
+        p => p,
+    }
+}
+
+fn page_by_id(id: &str) -> Option<Page> {
+    std::iter::successors(Some(Page::Platform), |p| {
+        (*p != Page::Complete).then(|| next_page(*p))
+    })
+    .find(|p| page_id(*p) == id)
+}
+
+async fn build(state: &WizardState, secrets: &SecretProtector) -> Result<Draft> {
+    let mut pages = Vec::new();
+    for (page, answers) in capture(state) {
+        let mut stored = Answers::new();
+        for (key, value) in answers {
+            let value = if SECRET_KEYS.contains(&key.as_str()) && !value.is_empty() {
+                secrets.encrypt(&value).await?
+            } else {
+                value
+            };
+            stored.push((key, value));
+        }
+        pages.push((page_id(page).to_string(), stored));
+    }
+    Ok(Draft {
+        version: DRAFT_VERSION,
+        saved_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        page: page_id(state.page).to_string(),
+        pages,
+    })
+}
+
+fn write(path: &Path, draft: &Draft) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(draft)?)?;
+    Ok(())
+}
+
+fn read(path: &Path) -> Option<Draft> {
+    let draft: Draft = serde_json::from_slice(&std::fs::read(path).ok()?)
+        .inspect_err(|e| {
+            warn!(
+                "[PHASE: tui] [STEP: draft] Ignoring unreadable draft (path={:?}): {}",
+                path, e
+            )
+        })
+        .ok()?;
+    (draft.version == DRAFT_VERSION).then_some(draft)
+}
+
+/// After a page change: save the answers, or drop the draft once an install has started.
+pub(super) fn page_changed(state: &WizardState, secrets: &SecretProtector) {
+    let Some(path) = draft_path() else {
+        return;
+    };
+    if matches!(state.page, Page::Installing | Page::Complete) {
+        discard_at(&path);
+        return;
+    }
+    let saved = block_on(build(state, secrets))
+        .and_then(|d| d)
+        .and_then(|d| write(&path, &d));
+    if let Err(e) = saved {
+        warn!(
+            "[PHASE: tui] [STEP: draft] Failed to save the wizard draft (path={:?}): {:?}",
+            path, e
+        );
+    }
+}
+
+fn discard_at(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(
+                "[PHASE: tui] [STEP: draft] Failed to remove the wizard draft (path={:?}): {}",
+                path, e
+            );
+        }
+    }
+}
+
+/// At launch: ask to resume when a draft from an earlier session exists.
+pub(super) fn offer(state: &mut WizardState) {
+    if let Some(draft) = draft_path().and_then(|p| read(&p)) {
+        state.modal = Some(Modal::ResumeDraft {
+            draft,
+            resume: true,
+        });
+    }
+}
+
+/// Replay the draft's answers; returns the page to continue on.
+async fn restore(state: &mut WizardState, draft: &Draft, secrets: &SecretProtector) -> Page {
+    for (id, answers) in &draft.pages {
+        let Some(page) = page_by_id(id) else {
+            continue;
+        };
+        state.page = page;
+        for (key, value) in answers {
+            let value = if SECRET_KEYS.contains(&key.as_str()) && !value.is_empty() {
+                match secrets.decrypt(value).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        warn!(
+                            "[PHASE: tui] [STEP: draft] Skipping answer {}.{}: {:?}",
+                            id, key, e
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                value.clone()
+            };
+            if let Err(e) = apply_answers(state, &vec![(key.clone(), value)]) {
+                warn!(
+                    "[PHASE: tui] [STEP: draft] Skipping answer {}.{}: {}",
+                    id, key, e
+                );
+            }
+        }
+        if page == Page::Platform {
+            // As the Platform page would on Enter: mode and its default engine.
+            confirm_platform(state);
+        }
+    }
+    resume_page(page_by_id(&draft.page).unwrap_or(Page::Platform))
+}
+
+pub(super) fn handle_key(
+    state: &mut WizardState,
+    code: KeyCode,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    let Some(Modal::ResumeDraft { draft, resume }) = state.modal.clone() else {
+        return;
+    };
+    match code {
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+            state.modal = Some(Modal::ResumeDraft {
+                draft,
+                resume: !resume,
+            });
+        }
+        KeyCode::Enter if resume => {
+            state.modal = None;
+            let page = block_on(restore(state, &draft, secrets)).unwrap_or(Page::Platform);
+            info!(
+                "[PHASE: tui] [STEP: draft] Resumed the wizard draft (saved_at={}, page={})",
+                draft.saved_at,
+                page_id(page)
+            );
+            state.page = page;
+            if page == Page::Mapping {
+                enter_mapping_page(state, tx);
+            } else if page_field_count(state) > 0 {
+                state.focus = FocusTarget::Field(0);
+            } else {
+                set_focused_button(state, ButtonFocus::Next);
+            }
+        }
+        KeyCode::Enter | KeyCode::Esc => {
+            state.modal = None;
+            if let Some(path) = draft_path() {
+                discard_at(&path);
+            }
+            info!("[PHASE: tui] [STEP: draft] Discarded the wizard draft");
+        }
+        _ => {}
+    }
+}
+
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    draft: &Draft,
+    resume: bool,
+) {
+    let modal_w = 64u16.min(window_area.width.saturating_sub(4)).max(44);
+    let modal_h = 8u16;
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Resume previous session?");
+    let page = resume_page(page_by_id(&draft.page).unwrap_or(Page::Platform));
+    let body = Paragraph::new(Text::from(vec![
+        Line::from(format!(
+            "Answers from {} were saved (last page: {}).",
+            draft.saved_at,
+            page_title(page, state.install_mode)
+        )),
+        Line::from("Resume to continue from there, or discard them and start over."),
+    ]))
+    .block(block)
+    .wrap(Wrap { trim: false });
+    f.render_widget(body, area);
+
+    let buttons_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - 2,
+        width: area.width - 2,
+        height: 1,
+    };
+    let resume_btn = ratatui::text::Span::styled("[ Resume ]", theme::current().focus_if(resume));
+    let discard_btn =
+        ratatui::text::Span::styled("[ Discard ]", theme::current().focus_if(!resume));
+    for (i, rect) in mouse::right_aligned_spans(buttons_area, &[&resume_btn, &discard_btn])
+        .into_iter()
+        .enumerate()
+    {
+        mouse::record(state, rect, HitTarget::ModalButton(i));
+    }
+    let p = Paragraph::new(Text::from(Line::from(vec![
+        resume_btn,
+        ratatui::text::Span::raw(" "),
+        discard_btn,
+    ])))
+    .alignment(Alignment::Right);
+    f.render_widget(p, buttons_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_round_trips_answers_with_secrets_encrypted() {
+        let dir = std::env::temp_dir().join(format!("draft-{}", Uuid::new_v4()));
+        let secrets = SecretProtector::new(dir.join("key"));
+        let mut state = WizardState::new();
+        state.platform_selected = InstallMode::Docker;
+        state.license_accepted = true;
+        state.destination_path.set("/srv/cadalytix");
+        state.db_kind = DbKind::Remote;
+        state.db_password.set("s3cret");
+        state.hot_retention_choice = HotRetentionChoice::Custom;
+        state.hot_retention_custom_months.set("30");
+        state.page = Page::Ready;
+
+        let path = dir.join(DRAFT_FILE);
+        write(&path, &block_on(build(&state, &secrets)).unwrap().unwrap()).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("s3cret"));
+
+        let draft = read(&path).unwrap();
+        let mut resumed = WizardState::new();
+        let page = block_on(restore(&mut resumed, &draft, &secrets)).unwrap();
+        assert_eq!(page, Page::Mapping);
+        assert_eq!(resumed.install_mode, InstallMode::Docker);
+        assert_eq!(resumed.db_engine, DbEngine::Postgres);
+        assert!(resumed.license_accepted);
+        assert_eq!(resumed.destination_path.value, "/srv/cadalytix");
+        assert_eq!(resumed.db_kind, DbKind::Remote);
+        assert_eq!(resumed.db_password.value, "s3cret");
+        assert_eq!(resumed.hot_retention_choice, HotRetentionChoice::Custom);
+        assert_eq!(resumed.hot_retention_custom_months.value, "30");
+
+        discard_at(&path);
+        assert!(read(&path).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
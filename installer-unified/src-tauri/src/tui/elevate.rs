@@ -0,0 +1,169 @@
+//! Consent for the root-only steps of an install started by an unprivileged user (Linux).
+//!
+//! On Install the wizard asks `installation::elevation` for the steps that need root. When there
+//! are any, a dialog lists the exact commands. Continue leaves the full screen so sudo (or
+//! pkexec) can ask for the password on the terminal, runs the folder batch and starts the
+//! install; Back returns to the Ready page.
+
+use super::*;
+use crate::installation::elevation::{self, ElevationPlan};
+
+/// Whether the install can start now; otherwise the consent (or an error) dialog is open.
+pub(super) fn ready_to_install(state: &mut WizardState) -> bool {
+    let req = build_install_request(state);
+    match draft::block_on(elevation::plan(&req)).and_then(|r| r) {
+        Ok(None) => true,
+        Ok(Some(plan)) => {
+            info!(
+                "[PHASE: tui] [STEP: elevation] Root-only steps pending consent:\n{}",
+                plan.lines().join("\n")
+            );
+            state.modal = Some(Modal::Elevate {
+                plan,
+                approve: true,
+                authorizing: false,
+            });
+            false
+        }
+        Err(e) => {
+            state.modal = Some(Modal::Message {
+                title: "Administrator rights needed".to_string(),
+                body: format!("{:#}", e),
+                return_to: None,
+            });
+            false
+        }
+    }
+}
+
+pub(super) fn handle_key(state: &mut WizardState, code: KeyCode) {
+    let Some(Modal::Elevate { plan, approve, .. }) = state.modal.clone() else {
+        return;
+    };
+    match code {
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+            state.modal = Some(Modal::Elevate {
+                plan,
+                approve: !approve,
+                authorizing: false,
+            });
+        }
+        // Picked up by `authorize` in the main loop, which owns the terminal.
+        KeyCode::Enter if approve => {
+            state.modal = Some(Modal::Elevate {
+                plan,
+                approve,
+                authorizing: true,
+            });
+        }
+        KeyCode::Enter | KeyCode::Esc => state.modal = None,
+        _ => {}
+    }
+}
+
+/// After Continue: prompt for the password outside the full screen, prepare the folders, then
+/// start the install (or report why not).
+pub(super) fn authorize(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    state: &mut WizardState,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) -> Result<()> {
+    let Some(Modal::Elevate {
+        plan,
+        authorizing: true,
+        ..
+    }) = state.modal.clone()
+    else {
+        return Ok(());
+    };
+    state.modal = None;
+
+    restore_terminal(terminal)?;
+    println!(
+        "CADalytix Setup: authorizing {} root-only step(s) with {}.",
+        plan.folders.len() + plan.service.len(),
+        plan.escalator.program()
+    );
+    let result = draft::block_on(elevation::approve(&plan)).and_then(|r| r);
+    enable_raw_mode()?;
+    crate::utils::crash::set_raw_terminal(true);
+    terminal.backend_mut().execute(EnterAlternateScreen)?;
+    terminal.backend_mut().execute(EnableMouseCapture)?;
+    terminal.clear()?;
+
+    match result {
+        Ok(()) => start_install(state, tx, secrets),
+        Err(e) => {
+            warn!(
+                "[PHASE: tui] [STEP: elevation] Root-only steps not authorized: {:?}",
+                e
+            );
+            state.modal = Some(Modal::Message {
+                title: "Administrator rights needed".to_string(),
+                body: format!("{:#}", e),
+                return_to: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    plan: &ElevationPlan,
+    approve: bool,
+) {
+    let lines = plan.lines();
+    let modal_w = 90u16.min(window_area.width.saturating_sub(4)).max(44);
+    let modal_h = (lines.len() as u16 + 6)
+        .min(window_area.height.saturating_sub(2))
+        .max(8);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Administrator rights needed");
+    let mut text = vec![Line::from(format!(
+        "These commands run as root via {} (it may ask for your password):",
+        plan.escalator.program()
+    ))];
+    text.extend(lines.into_iter().map(Line::from));
+    let body = Paragraph::new(Text::from(text))
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(body, area);
+
+    let buttons_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - 2,
+        width: area.width - 2,
+        height: 1,
+    };
+    let continue_btn =
+        ratatui::text::Span::styled("[ Continue ]", theme::current().focus_if(approve));
+    let back_btn = ratatui::text::Span::styled("[ Back ]", theme::current().focus_if(!approve));
+    for (i, rect) in mouse::right_aligned_spans(buttons_area, &[&continue_btn, &back_btn])
+        .into_iter()
+        .enumerate()
+    {
+        mouse::record(state, rect, HitTarget::ModalButton(i));
+    }
+    let p = Paragraph::new(Text::from(Line::from(vec![
+        continue_btn,
+        ratatui::text::Span::raw(" "),
+        back_btn,
+    ])))
+    .alignment(Alignment::Right);
+    f.render_widget(p, buttons_area);
+}
@@ -0,0 +1,237 @@
+//! Per-field inline validation.
+//!
+//! Each text field declares its rules where it is created (`TextInput::with_rules` in
+//! `WizardState::new`). A field's first failing rule is shown as an error line under it once
+//! typing has paused for `DEBOUNCE`, so the message does not flicker on every key; a blank
+//! required field only complains after it was typed in. Any failing rule on the current page also
+//! blocks Next (`page_error`), on top of the page checks in `can_go_next`.
+
+use super::*;
+use crate::utils::validation::{validate_absolute_path, validate_host};
+
+/// Typing pause before a field's error is shown.
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Rule {
+    Required,
+    /// Absolute path syntax (`utils::validation::validate_absolute_path`).
+    Path,
+    /// Host name or IP address, optionally with a SQL Server instance.
+    Host,
+    /// TCP port, 1-65535.
+    Port,
+    /// Whole number greater than zero.
+    Positive,
+    /// Whole number in an inclusive range.
+    Range(u32, u32),
+    /// `HH:MM`, 24-hour.
+    Time,
+}
+
+impl Rule {
+    /// Every rule but `Required` accepts a blank value.
+    fn check(self, value: &str) -> Result<(), String> {
+        let v = value.trim();
+        if v.is_empty() {
+            return match self {
+                Rule::Required => Err(i18n::t("validation.required")),
+                _ => Ok(()),
+            };
+        }
+        let ok = match self {
+            Rule::Required => true,
+            Rule::Path => validate_absolute_path(v).is_ok(),
+            Rule::Host => validate_host(v).is_ok(),
+            Rule::Port => v.parse::<u16>().is_ok_and(|p| p > 0),
+            Rule::Positive => v.parse::<u32>().is_ok_and(|n| n > 0),
+            Rule::Range(min, max) => v.parse::<u32>().is_ok_and(|n| (min..=max).contains(&n)),
+            Rule::Time => is_valid_time_hhmm(v),
+        };
+        if ok {
+            return Ok(());
+        }
+        Err(match self {
+            Rule::Path => i18n::t("validation.pathFormat"),
+            Rule::Host => i18n::t("validation.hostFormat"),
+            Rule::Port => i18n::t("validation.portRange"),
+            Rule::Range(min, max) => i18n::tf(
+                "validation.numberRange",
+                &[("min", &min.to_string()), ("max", &max.to_string())],
+            ),
+            Rule::Time => i18n::t("validation.scheduleTimeFormat"),
+            Rule::Required | Rule::Positive => i18n::t("validation.numberPositive"),
+        })
+    }
+}
+
+impl TextInput {
+    pub(super) fn with_rules(mut self, rules: &'static [Rule]) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// First failing rule; a blank required field only counts once it was typed in.
+    fn rule_error(&self) -> Option<String> {
+        self.rules
+            .iter()
+            .filter(|r| **r != Rule::Required || self.edited_at.is_some())
+            .find_map(|r| r.check(&self.value).err())
+    }
+
+    /// `rule_error`, once typing has paused.
+    pub(super) fn inline_error(&self) -> Option<String> {
+        match self.edited_at {
+            Some(at) if at.elapsed() < DEBOUNCE => None,
+            _ => self.rule_error(),
+        }
+    }
+}
+
+/// Text fields shown on the current page, in focus order.
+fn page_inputs(state: &WizardState) -> Vec<&TextInput> {
+    match state.page {
+        Page::InstallType if state.installation_type == InstallationType::ImportConfig => {
+            vec![&state.import_config_path]
+        }
+        Page::Destination => {
+            let mut inputs = vec![&state.destination_path];
+            if state.layout_advanced {
+                inputs.extend([
+                    &state.layout_binaries,
+                    &state.layout_data,
+                    &state.layout_logs,
+                    &state.layout_temp,
+                ]);
+            }
+            inputs
+        }
+        Page::DataSource => {
+            let mut inputs = match state.data_source_kind {
+                DataSourceKind::Odbc => {
+                    vec![&state.odbc_connection_string, &state.source_object_name]
+                }
+                DataSourceKind::Oracle => vec![
+                    &state.oracle_driver,
+                    &state.oracle_address,
+                    &state.call_data_user,
+                    &state.call_data_password,
+                    &state.source_object_name,
+                ],
+                DataSourceKind::File => {
+                    vec![&state.file_source_folder, &state.file_source_delimiter]
+                }
+                _ => vec![
+                    &state.call_data_database,
+                    &state.call_data_user,
+                    &state.call_data_password,
+                    &state.call_data_host,
+                    &state.call_data_port,
+                    &state.source_object_name,
+                ],
+            };
+            if sources::count(state) > 1 {
+                inputs.push(&state.source_name);
+            }
+            inputs
+        }
+        Page::Database => {
+            let mut inputs = if state.db_kind == DbKind::Local {
+                let mut v = Vec::new();
+                if state.new_db_location == NewDbLocation::SpecificPath {
+                    v.push(&state.new_db_specific_path);
+                }
+                v.extend([&state.new_db_max_size_gb, &state.new_db_collation]);
+                v
+            } else if state.db_use_conn_string {
+                vec![&state.db_conn_string]
+            } else {
+                vec![
+                    &state.db_host,
+                    &state.db_port,
+                    &state.db_database,
+                    &state.db_user,
+                    &state.db_password,
+                ]
+            };
+            inputs.push(&state.app_login);
+            inputs
+        }
+        Page::Storage if state.storage_mode == StorageMode::Custom => {
+            let mut inputs = Vec::new();
+            if state.storage_location == StorageLocation::Custom {
+                inputs.push(&state.storage_custom_path);
+            }
+            if state.retention_policy == RetentionPolicy::MaxDisk {
+                inputs.push(&state.max_disk_gb);
+            }
+            inputs
+        }
+        Page::Retention if state.hot_retention_choice == HotRetentionChoice::Custom => {
+            vec![&state.hot_retention_custom_months]
+        }
+        Page::Archive if state.licensed.allows(features::ARCHIVE) => vec![
+            &state.archive_destination,
+            &state.archive_max_usage_gb,
+            &state.archive_schedule_day_of_month,
+            &state.archive_schedule_time_local,
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// First field error on the current page (not debounced); blocks Next.
+pub(super) fn page_error(state: &WizardState) -> Option<String> {
+    page_inputs(state).into_iter().find_map(|i| i.rule_error())
+}
+
+/// Error line to draw under `input`, if it has one.
+pub(super) fn inline_error_line(input: &TextInput) -> Option<Line<'static>> {
+    input.inline_error().map(|message| {
+        Line::from(ratatui::text::Span::styled(
+            format!(
+                "    {}",
+                i18n::tf("validation.errorPrefix", &[("message", &message)])
+            ),
+            theme::current().error(),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_report_after_the_debounce_and_block_next() {
+        assert!(Rule::Port.check("1433").is_ok());
+        assert!(Rule::Port.check("70000").is_err());
+        assert!(Rule::Host.check("db-01.example.org").is_ok());
+        assert!(Rule::Host.check("SQL01\\SQLEXPRESS").is_ok());
+        assert!(Rule::Host.check("bad host").is_err());
+        assert!(Rule::Path.check("C:\\CADalytix").is_ok());
+        assert!(Rule::Path.check("relative/dir").is_err());
+        assert!(Rule::Range(1, 28).check("29").is_err());
+        assert!(Rule::Time.check("24:00").is_err());
+
+        let mut state = WizardState::new();
+        state.page = Page::DataSource;
+        assert!(page_error(&state).is_none());
+
+        state.call_data_port.handle_key(KeyCode::Char('0'));
+        assert_eq!(state.call_data_port.value, "14330");
+        // Still typing: nothing drawn yet, but Next is already blocked.
+        assert!(inline_error_line(&state.call_data_port).is_none());
+        assert!(page_error(&state).is_some());
+        state.call_data_port.edited_at = Some(Instant::now() - DEBOUNCE);
+        assert!(inline_error_line(&state.call_data_port).is_some());
+        assert!(!can_go_next(&state));
+
+        // A blank required field complains only once it was typed in.
+        state.page = Page::Archive;
+        assert!(state.archive_destination.inline_error().is_none());
+        state.archive_destination.handle_key(KeyCode::Backspace);
+        state.archive_destination.edited_at = Some(Instant::now() - DEBOUNCE);
+        assert!(state.archive_destination.inline_error().is_some());
+    }
+}
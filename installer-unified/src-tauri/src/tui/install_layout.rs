@@ -0,0 +1,271 @@
+//! Advanced folder layout on the Destination page (L toggles it).
+//!
+//! Shows four more fields under the install path: binaries, data, logs and temp. Empty fields
+//! keep the defaults (`installation::layout::default_layout`), which are shown next to them. Each
+//! path's volume is measured in the background when the view opens and on R, and a path short of
+//! its recommended free space is flagged; that is a warning, not a blocker.
+
+use super::*;
+use crate::installation::layout::{
+    self, check_free_space, default_layout, InstallLayoutConfig, SpaceCheck,
+};
+
+pub(super) const ROLES: [&str; 4] = ["Binaries", "Data", "Logs", "Temp"];
+
+/// Field `idx` (1-4) of the Destination page in the advanced view.
+pub(super) fn field_mut(state: &mut WizardState, idx: usize) -> Option<&mut TextInput> {
+    if !state.layout_advanced {
+        return None;
+    }
+    match idx {
+        1 => Some(&mut state.layout_binaries),
+        2 => Some(&mut state.layout_data),
+        3 => Some(&mut state.layout_logs),
+        4 => Some(&mut state.layout_temp),
+        _ => None,
+    }
+}
+
+/// The layout sent with the install request; all defaults unless the advanced view is on.
+pub(super) fn config(state: &WizardState) -> InstallLayoutConfig {
+    if !state.layout_advanced {
+        return InstallLayoutConfig::default();
+    }
+    InstallLayoutConfig {
+        binaries: state.layout_binaries.value.trim().to_string(),
+        data: state.layout_data.value.trim().to_string(),
+        logs: state.layout_logs.value.trim().to_string(),
+        temp: state.layout_temp.value.trim().to_string(),
+    }
+}
+
+fn engine(state: &WizardState) -> &'static str {
+    match state.db_engine {
+        DbEngine::Postgres => "postgres",
+        DbEngine::SqlServer => "sqlserver",
+    }
+}
+
+fn install_mode(state: &WizardState) -> &'static str {
+    match state.install_mode {
+        InstallMode::Windows => "windows",
+        InstallMode::Docker => "docker",
+    }
+}
+
+/// Every path with defaults filled in, as the install will resolve it.
+fn resolved(state: &WizardState) -> layout::InstallLayout {
+    let defaults = default_layout(
+        install_mode(state),
+        engine(state),
+        &state.destination_path.value,
+    );
+    let custom = config(state);
+    let pick = |custom: String, default: std::path::PathBuf| match custom.as_str() {
+        "" => default,
+        p => std::path::PathBuf::from(p),
+    };
+    layout::InstallLayout {
+        binaries: pick(custom.binaries, defaults.binaries),
+        data: pick(custom.data, defaults.data),
+        logs: pick(custom.logs, defaults.logs),
+        temp: pick(custom.temp, defaults.temp),
+    }
+}
+
+/// Show or hide the advanced view; showing it measures free space.
+pub(super) fn toggle(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    state.layout_advanced = !state.layout_advanced;
+    info!(
+        "[PHASE: tui] [STEP: layout] Advanced layout {}",
+        if state.layout_advanced {
+            "shown"
+        } else {
+            "hidden"
+        }
+    );
+    if state.layout_advanced {
+        check_space(state, tx);
+    } else {
+        state.focus = FocusTarget::Field(0);
+        state.layout_space = None;
+    }
+    update_page_validation(state);
+}
+
+/// Measure the volume of every path in the background (`UiMsg::LayoutSpaceChecked`).
+pub(super) fn check_space(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    if !state.layout_advanced || state.layout_checking {
+        return;
+    }
+    state.layout_checking = true;
+    let layout = resolved(state);
+    let max_db_size_gb = if state.db_kind == DbKind::Local {
+        state
+            .new_db_max_size_gb
+            .value
+            .trim()
+            .parse::<u32>()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let checks = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt.block_on(check_free_space(&layout, max_db_size_gb)),
+            Err(e) => {
+                warn!(
+                    "[PHASE: tui] [STEP: layout] Free space check not started: {}",
+                    e
+                );
+                Vec::new()
+            }
+        };
+        let _ = tx.send(UiMsg::LayoutSpaceChecked(checks));
+    });
+}
+
+pub(super) fn space_checked(state: &mut WizardState, checks: Vec<SpaceCheck>) {
+    state.layout_checking = false;
+    state.layout_space = Some(checks);
+}
+
+/// Custom paths must be absolute (on the server for a remote install).
+pub(super) fn error(state: &WizardState) -> Option<String> {
+    match crate::installation::remote::selected() {
+        Some(_) => crate::installation::remote::validate_layout(&config(state)).err(),
+        None => layout::validate(&config(state)).err(),
+    }
+}
+
+fn gib(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+fn space_text(check: Option<&SpaceCheck>, checking: bool) -> String {
+    match check {
+        _ if checking => "checking free space...".to_string(),
+        Some(c) if c.is_short() => format!(
+            "LOW: {} free, {} recommended",
+            gib(c.free_bytes.unwrap_or(0)),
+            gib(c.required_bytes)
+        ),
+        Some(SpaceCheck {
+            free_bytes: Some(free),
+            ..
+        }) => format!("{} free", gib(*free)),
+        Some(_) => "free space unknown".to_string(),
+        None => String::new(),
+    }
+}
+
+/// Destination page lines for the advanced view.
+pub(super) fn lines(state: &WizardState) -> Vec<Line<'static>> {
+    if !state.layout_advanced {
+        return vec![Line::from(
+            "Press L for an advanced layout (separate data, logs and temp folders).",
+        )];
+    }
+    let defaults = default_layout(
+        install_mode(state),
+        engine(state),
+        &state.destination_path.value,
+    );
+    let fields = [
+        (&state.layout_binaries, &defaults.binaries),
+        (&state.layout_data, &defaults.data),
+        (&state.layout_logs, &defaults.logs),
+        (&state.layout_temp, &defaults.temp),
+    ];
+    let mut lines = vec![Line::from("Advanced layout (empty = default):")];
+    for (i, (input, default)) in fields.iter().enumerate() {
+        let prefix = if matches!(state.focus, FocusTarget::Field(f) if f == i + 1) {
+            ">"
+        } else {
+            " "
+        };
+        let value = if input.value.trim().is_empty() {
+            format!("(default: {})", default.display())
+        } else {
+            input.value.clone()
+        };
+        let check = state.layout_space.as_ref().and_then(|checks| checks.get(i));
+        let space = space_text(check, state.layout_checking);
+        let style = if check.is_some_and(SpaceCheck::is_short) {
+            theme::current().error()
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            ratatui::text::Span::raw(format!("{} {:<9} {}  ", prefix, ROLES[i], value)),
+            ratatui::text::Span::styled(space, style),
+        ]));
+        lines.extend(field_rules::inline_error_line(input));
+    }
+    lines.push(Line::from(
+        "Press R to re-check free space, L to hide the advanced layout.",
+    ));
+    lines
+}
+
+/// Ready page lines; none for the default layout.
+pub(super) fn summary(state: &WizardState) -> Vec<String> {
+    let custom = config(state);
+    if custom.is_default() {
+        return Vec::new();
+    }
+    let layout = resolved(state);
+    layout
+        .entries()
+        .iter()
+        .zip(ROLES)
+        .map(|((_, path), role)| format!("{} folder: {}", role, path.display()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advanced_layout_fields_feed_the_request_and_validation() {
+        let (tx, _rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.page = Page::Destination;
+        state.install_mode = InstallMode::Docker;
+        state.db_engine = DbEngine::Postgres;
+        state.destination_path.set("/opt/cadalytix");
+        assert_eq!(page_field_count(&state), 1);
+        state.layout_data.set("/srv/data");
+        assert!(config(&state).is_default());
+
+        toggle(&mut state, &tx);
+        assert_eq!(page_field_count(&state), 5);
+        assert!(state.layout_checking);
+        assert_eq!(config(&state).data, "/srv/data");
+        assert_eq!(
+            summary(&state),
+            vec![
+                "Binaries folder: /opt/cadalytix",
+                "Data folder: /srv/data",
+                "Logs folder: /opt/cadalytix/logs",
+                "Temp folder: /opt/cadalytix/tmp",
+            ]
+        );
+
+        state.focus = FocusTarget::Field(3);
+        focused_text_input_mut(&mut state).unwrap().set("logs");
+        update_page_validation(&mut state);
+        assert!(!can_go_next(&state));
+        assert_eq!(build_install_request(&state).layout.logs, "logs");
+
+        toggle(&mut state, &tx);
+        update_page_validation(&mut state);
+        assert!(can_go_next(&state));
+        assert!(build_install_request(&state).layout.is_default());
+    }
+}
@@ -0,0 +1,141 @@
+//! Downstream invalidation when earlier answers change (back navigation).
+//!
+//! On every page change the wizard compares each page's answers (`draft::capture`) with those at
+//! the previous change. When a page's answers differ, the pages that depend on it (`DEPENDENTS`)
+//! drop the state derived from the old answers so it is rebuilt:
+//! - Database: the connection test result (a different platform means a different engine).
+//! - Mapping: the source scan, so the columns are scanned again; mappings of columns the new
+//!   scan still has are kept.
+//! - Ready: the disk space projection and backfill estimate.
+//!
+//! A dependent page already visited is also marked dirty; its title says so until the operator
+//! leaves it again.
+
+use super::*;
+
+/// Pages whose derived state depends on the answers of another page.
+const DEPENDENTS: &[(Page, &[Page])] = &[
+    (
+        Page::Platform,
+        &[Page::Database, Page::Mapping, Page::Ready],
+    ),
+    (Page::InstallType, &[Page::Ready]),
+    (Page::Destination, &[Page::Ready]),
+    (Page::DataSource, &[Page::Mapping, Page::Ready]),
+    (Page::Database, &[Page::Ready]),
+    (Page::Storage, &[Page::Ready]),
+    (Page::Retention, &[Page::Ready]),
+    (Page::Archive, &[Page::Ready]),
+];
+
+fn dependents(page: Page) -> &'static [Page] {
+    DEPENDENTS
+        .iter()
+        .find(|(p, _)| *p == page)
+        .map(|(_, deps)| *deps)
+        .unwrap_or(&[])
+}
+
+/// Drop `page`'s derived state so it is checked again.
+fn invalidate(state: &mut WizardState, page: Page, tx: &mpsc::Sender<UiMsg>) {
+    match page {
+        Page::Database => {
+            state.db_test_status = DbTestStatus::Idle;
+            state.db_test_message.clear();
+        }
+        Page::Mapping => {
+            state.mapping_scan_key = None;
+            if state.page == Page::Mapping {
+                start_mapping_scan(state, tx);
+            }
+        }
+        Page::Ready => {
+            state.disk_space_key = None;
+            state.disk_space = None;
+            state.backfill_key = None;
+            state.backfill_estimate = None;
+        }
+        _ => {}
+    }
+}
+
+/// After the wizard moved from `left` to `state.page`.
+pub(super) fn page_changed(state: &mut WizardState, left: Page, tx: &mpsc::Sender<UiMsg>) {
+    state.dirty_pages.retain(|p| *p != left);
+    let answers = draft::capture(state);
+    let changed: Vec<Page> = answers
+        .iter()
+        .filter(|(page, now)| {
+            state
+                .answer_snapshot
+                .iter()
+                .any(|(p, before)| p == page && before != now)
+        })
+        .map(|(page, _)| *page)
+        .collect();
+    state.answer_snapshot = answers;
+
+    for page in changed {
+        for dep in dependents(page) {
+            invalidate(state, *dep, tx);
+            if state.visited_pages.contains(dep) && !state.dirty_pages.contains(dep) {
+                info!(
+                    "[PHASE: tui] [STEP: invalidation] Page marked for review (page={:?}, changed={:?})",
+                    dep, page
+                );
+                state.dirty_pages.push(*dep);
+            }
+        }
+    }
+    if !state.visited_pages.contains(&state.page) {
+        state.visited_pages.push(state.page);
+    }
+}
+
+/// The current page must be reviewed because earlier answers changed.
+pub(super) fn is_dirty(state: &WizardState) -> bool {
+    state.dirty_pages.contains(&state.page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_answers_invalidate_visited_dependents() {
+        let (tx, _rx) = mpsc::channel::<UiMsg>();
+        let mut state = WizardState::new();
+        let go = |state: &mut WizardState, page: Page| {
+            let left = state.page;
+            state.page = page;
+            page_changed(state, left, &tx);
+        };
+        for page in [Page::Welcome, Page::DataSource, Page::Database] {
+            go(&mut state, page);
+        }
+        state.db_test_status = DbTestStatus::Success;
+        state.mapping_scan_key = Some(mapping_scan_key(&state));
+        go(&mut state, Page::Mapping);
+        assert!(state.dirty_pages.is_empty());
+
+        // Back to the data source, edit the object name, forward again.
+        go(&mut state, Page::DataSource);
+        state.source_object_name.set("dbo.Incidents");
+        go(&mut state, Page::Database);
+        assert_eq!(state.dirty_pages, vec![Page::Mapping]);
+        assert!(state.mapping_scan_key.is_none());
+        assert_eq!(state.db_test_status, DbTestStatus::Success);
+
+        // A different platform means a different engine: the test must be run again.
+        go(&mut state, Page::Platform);
+        state.platform_selected = InstallMode::Docker;
+        go(&mut state, Page::Welcome);
+        assert_eq!(state.db_test_status, DbTestStatus::Idle);
+        assert!(state.dirty_pages.contains(&Page::Database));
+
+        state.page = Page::Database;
+        assert!(is_dirty(&state));
+        go(&mut state, Page::Storage);
+        assert!(!state.dirty_pages.contains(&Page::Database));
+    }
+}
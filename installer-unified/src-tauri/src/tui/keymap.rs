@@ -0,0 +1,731 @@
+//! TUI keybindings: actions, their default keys, and user remapping.
+//!
+//! `dispatch_key` works in terms of each [`Action`]'s canonical key; [`Keymap::resolve`] turns the
+//! key actually pressed into that canonical key first. Operators whose terminal swallows a key
+//! (F1, PageUp, ...) can rebind actions in a JSON file mapping action names to key lists:
+//!
+//! ```json
+//! { "help": ["F2", "?"], "page_up": ["k"], "page_down": ["j"], "test_connection": ["F5"] }
+//! ```
+//!
+//! The file is `$CADALYTIX_TUI_KEYMAP`, else `<config dir>/cadalytix/tui-keymap.json`, else
+//! `tui-keymap.json` next to the installer. Listed actions replace their defaults; unknown
+//! actions or keys are logged and ignored. The help overlay (F1 or `?`) is generated from
+//! [`BINDINGS`] with the keys currently bound.
+
+use super::Page;
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use KeyCode::{Backspace, Char, Delete, Down, End, Enter, Esc, Home, Left, PageDown, PageUp};
+use KeyCode::{Right, Tab, Up, F};
+use Scope::Global;
+
+const KEYMAP_ENV: &str = "CADALYTIX_TUI_KEYMAP";
+const KEYMAP_FILE: &str = "tui-keymap.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Action {
+    Help,
+    FocusNext,
+    Activate,
+    Cancel,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Toggle,
+    Browse,
+    TestConnection,
+    CyclePolicy,
+    DryRun,
+    PauseResume,
+    CycleRetention,
+    ArchiveFormat,
+    ArchiveEncrypt,
+    ConsentDetails,
+    Search,
+    Unassign,
+    Override,
+    Transforms,
+    AutoMap,
+    ConfirmSuggestion,
+    ExportMapping,
+    ImportMapping,
+    ViewLog,
+    ArchiveNow,
+    AddSource,
+    NextSource,
+    RemoveSource,
+    Layout,
+    CheckSpace,
+    Profiles,
+}
+
+impl Action {
+    const ALL: [Action; 36] = [
+        Action::Help,
+        Action::FocusNext,
+        Action::Activate,
+        Action::Cancel,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Toggle,
+        Action::Browse,
+        Action::TestConnection,
+        Action::CyclePolicy,
+        Action::DryRun,
+        Action::PauseResume,
+        Action::CycleRetention,
+        Action::ArchiveFormat,
+        Action::ArchiveEncrypt,
+        Action::ConsentDetails,
+        Action::Search,
+        Action::Unassign,
+        Action::Override,
+        Action::Transforms,
+        Action::AutoMap,
+        Action::ConfirmSuggestion,
+        Action::ExportMapping,
+        Action::ImportMapping,
+        Action::ViewLog,
+        Action::ArchiveNow,
+        Action::AddSource,
+        Action::NextSource,
+        Action::RemoveSource,
+        Action::Layout,
+        Action::CheckSpace,
+        Action::Profiles,
+    ];
+
+    /// Name used in the keymap file.
+    fn name(self) -> &'static str {
+        match self {
+            Action::Help => "help",
+            Action::FocusNext => "focus_next",
+            Action::Activate => "activate",
+            Action::Cancel => "cancel",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Toggle => "toggle",
+            Action::Browse => "browse",
+            Action::TestConnection => "test_connection",
+            Action::CyclePolicy => "cycle_retention_policy",
+            Action::DryRun => "dry_run",
+            Action::PauseResume => "pause_resume",
+            Action::CycleRetention => "cycle_hot_retention",
+            Action::ArchiveFormat => "archive_format",
+            Action::ArchiveEncrypt => "archive_encrypt",
+            Action::ConsentDetails => "consent_details",
+            Action::Search => "search",
+            Action::Unassign => "unassign",
+            Action::Override => "override",
+            Action::Transforms => "transforms",
+            Action::AutoMap => "auto_map",
+            Action::ConfirmSuggestion => "confirm_suggestion",
+            Action::ExportMapping => "export_mapping",
+            Action::ImportMapping => "import_mapping",
+            Action::ViewLog => "view_log",
+            Action::ArchiveNow => "archive_now",
+            Action::AddSource => "add_source",
+            Action::NextSource => "next_source",
+            Action::RemoveSource => "remove_source",
+            Action::Layout => "layout",
+            Action::CheckSpace => "check_space",
+            Action::Profiles => "profiles",
+        }
+    }
+
+    /// Key `dispatch_key` matches for this action.
+    fn canonical(self) -> KeyCode {
+        self.default_keys()[0]
+    }
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::Help => &[F(1), Char('?')],
+            Action::FocusNext => &[Tab],
+            Action::Activate => &[Enter],
+            Action::Cancel => &[Esc],
+            Action::Up => &[Up],
+            Action::Down => &[Down],
+            Action::Left => &[Left],
+            Action::Right => &[Right],
+            Action::PageUp => &[PageUp],
+            Action::PageDown => &[PageDown],
+            Action::Toggle => &[Char(' ')],
+            Action::Browse => &[Char('b')],
+            Action::TestConnection => &[Char('t')],
+            Action::CyclePolicy | Action::DryRun | Action::PauseResume | Action::Profiles => {
+                &[Char('p')]
+            }
+            Action::CycleRetention => &[Char('r')],
+            Action::ArchiveFormat => &[Char('f')],
+            Action::ArchiveEncrypt => &[Char('e')],
+            Action::ConsentDetails => &[Char('d')],
+            Action::Search => &[Char('/')],
+            Action::Unassign => &[Char('u')],
+            Action::Override => &[Char('o')],
+            Action::Transforms => &[Char('t')],
+            Action::AutoMap => &[Char('a')],
+            Action::ConfirmSuggestion => &[Char('c')],
+            Action::ExportMapping => &[Char('e')],
+            Action::ImportMapping => &[Char('i')],
+            Action::ViewLog => &[Char('l')],
+            Action::ArchiveNow => &[Char('a')],
+            Action::AddSource => &[Char('n')],
+            Action::NextSource => &[Char('s')],
+            Action::RemoveSource => &[Char('x')],
+            Action::Layout => &[Char('l')],
+            Action::CheckSpace => &[Char('r')],
+        }
+    }
+
+    /// Pages the action is bound on; empty for actions available everywhere (and in modals).
+    fn pages(self) -> &'static [Page] {
+        match self {
+            Action::Browse => &[Page::Destination, Page::DataSource],
+            Action::TestConnection => &[Page::Database],
+            Action::CyclePolicy => &[Page::Storage],
+            Action::DryRun => &[Page::Ready],
+            Action::PauseResume => &[Page::Installing],
+            Action::CycleRetention => &[Page::Retention],
+            Action::ArchiveFormat | Action::ArchiveEncrypt => &[Page::Archive],
+            Action::ConsentDetails => &[Page::Consent],
+            Action::Search
+            | Action::Unassign
+            | Action::Override
+            | Action::Transforms
+            | Action::AutoMap
+            | Action::ConfirmSuggestion
+            | Action::ExportMapping
+            | Action::ImportMapping => &[Page::Mapping],
+            Action::ViewLog => &[Page::Installing, Page::Complete],
+            Action::ArchiveNow => &[Page::Complete],
+            Action::AddSource | Action::RemoveSource => &[Page::DataSource],
+            Action::NextSource => &[Page::DataSource, Page::Mapping],
+            Action::Layout | Action::CheckSpace => &[Page::Destination],
+            Action::Profiles => &[Page::DataSource, Page::Database],
+            _ => &[],
+        }
+    }
+
+    /// Active on `page`; `None` means a modal or the log viewer is open (global actions only).
+    fn active_on(self, page: Option<Page>) -> bool {
+        let pages = self.pages();
+        pages.is_empty() || page.is_some_and(|p| pages.contains(&p))
+    }
+}
+
+/// Letters are matched case-insensitively (`t` and `T` are the same binding).
+fn normalize(code: KeyCode) -> KeyCode {
+    match code {
+        Char(c) => Char(c.to_ascii_lowercase()),
+        other => other,
+    }
+}
+
+/// Parse a key name from the keymap file: `F5`, `PgUp`, `Space`, `Enter`, `j`, `?`.
+pub(super) fn parse_key(name: &str) -> Option<KeyCode> {
+    let name = name.trim();
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(normalize(Char(c)));
+    }
+    let lower = name.to_ascii_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then_some(F(n));
+    }
+    Some(match lower.as_str() {
+        "space" => Char(' '),
+        "enter" | "return" => Enter,
+        "esc" | "escape" => Esc,
+        "tab" => Tab,
+        "up" => Up,
+        "down" => Down,
+        "left" => Left,
+        "right" => Right,
+        "pgup" | "pageup" => PageUp,
+        "pgdn" | "pagedown" => PageDown,
+        "home" => Home,
+        "end" => End,
+        "backspace" => Backspace,
+        "delete" | "del" => Delete,
+        _ => return None,
+    })
+}
+
+/// Display form of a key: `F1`, `PgUp`, `Space`, `T`.
+pub(super) fn key_label(code: KeyCode) -> String {
+    match code {
+        Char(' ') => "Space".to_string(),
+        Char(c) => c.to_ascii_uppercase().to_string(),
+        F(n) => format!("F{}", n),
+        PageUp => "PgUp".to_string(),
+        PageDown => "PgDn".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Keymap {
+    keys: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL
+                .iter()
+                .map(|a| (*a, a.default_keys().to_vec()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Defaults plus the user's keymap file, if any. Never fails: a bad file is logged.
+    pub(super) fn load() -> Self {
+        let mut keymap = Self::default();
+        let Some(path) = keymap_path() else {
+            return keymap;
+        };
+        match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| keymap.apply_json(&json))
+            .with_context(|| format!("Failed to load TUI keymap {:?}", path))
+        {
+            Ok(()) => info!(
+                "[PHASE: tui] [STEP: keymap] Loaded key bindings (path={:?})",
+                path
+            ),
+            Err(e) => warn!("[PHASE: tui] [STEP: keymap] {:#}", e),
+        }
+        keymap
+    }
+
+    /// Apply `{ "action": ["key", ...] }` overrides; bad entries are skipped with a warning.
+    pub(super) fn apply_json(&mut self, json: &str) -> Result<()> {
+        let overrides: HashMap<String, Vec<String>> =
+            serde_json::from_str(json).context("Keymap must map action names to key lists")?;
+        for (name, key_names) in overrides {
+            let Some(action) = Action::ALL.iter().copied().find(|a| a.name() == name) else {
+                warn!(
+                    "[PHASE: tui] [STEP: keymap] Unknown action in keymap (action={})",
+                    name
+                );
+                continue;
+            };
+            let mut keys = Vec::new();
+            for key_name in key_names {
+                match parse_key(&key_name) {
+                    Some(k) => keys.push(k),
+                    None => warn!(
+                        "[PHASE: tui] [STEP: keymap] Unknown key in keymap (action={}, key={})",
+                        name, key_name
+                    ),
+                }
+            }
+            if keys.is_empty() {
+                warn!(
+                    "[PHASE: tui] [STEP: keymap] No usable keys; keeping defaults (action={})",
+                    name
+                );
+                continue;
+            }
+            self.keys.insert(action, keys);
+        }
+        Ok(())
+    }
+
+    pub(super) fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Canonical key for a key press on `page` (`None` while a modal or the log viewer is open).
+    /// Unbound keys pass through; a default key whose action was rebound elsewhere is dropped.
+    pub(super) fn resolve(&self, page: Option<Page>, code: KeyCode) -> Option<KeyCode> {
+        let pressed = normalize(code);
+        let active = || Action::ALL.iter().copied().filter(|a| a.active_on(page));
+        // Page actions win over global ones that share a key.
+        let bound = active()
+            .filter(|a| !a.pages().is_empty())
+            .chain(active().filter(|a| a.pages().is_empty()))
+            .find(|a| self.keys(*a).contains(&pressed));
+        match bound {
+            Some(action) => Some(action.canonical()),
+            None if active().any(|a| a.canonical() == pressed) => None,
+            None => Some(code),
+        }
+    }
+}
+
+fn keymap_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os(KEYMAP_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(p));
+    }
+    let user = dirs::config_dir().map(|d| d.join("cadalytix").join(KEYMAP_FILE));
+    let beside_exe = crate::utils::path_resolver::resolve_deployment_folder()
+        .ok()
+        .map(|d| d.join(KEYMAP_FILE));
+    user.into_iter().chain(beside_exe).find(|p| p.is_file())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Scope {
+    Global,
+    Page(Page),
+}
+
+/// Help overlay entry: the actions it covers and what they do there.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Binding {
+    pub scope: Scope,
+    pub actions: &'static [Action],
+    pub description: &'static str,
+}
+
+const fn bind(scope: Scope, actions: &'static [Action], description: &'static str) -> Binding {
+    Binding {
+        scope,
+        actions,
+        description,
+    }
+}
+
+pub(super) const BINDINGS: &[Binding] = &[
+    bind(Global, &[Action::Help], "Show this help"),
+    bind(
+        Global,
+        &[Action::FocusNext],
+        "Move focus to the next field or button",
+    ),
+    bind(Global, &[Action::Activate], "Activate the focused button"),
+    bind(
+        Global,
+        &[Action::Cancel],
+        "Cancel setup (asks for confirmation)",
+    ),
+    bind(
+        Scope::Page(Page::Platform),
+        &[Action::Left, Action::Right],
+        "Switch between Windows and Docker / Linux",
+    ),
+    bind(
+        Scope::Page(Page::Platform),
+        &[Action::Activate],
+        "Continue with the selected platform",
+    ),
+    bind(
+        Scope::Page(Page::License),
+        &[Action::Toggle],
+        "Accept / decline the license",
+    ),
+    bind(
+        Scope::Page(Page::License),
+        &[Action::PageUp, Action::PageDown],
+        "Scroll the license text",
+    ),
+    bind(
+        Scope::Page(Page::InstallType),
+        &[Action::Up, Action::Down],
+        "Choose the installation type",
+    ),
+    bind(
+        Scope::Page(Page::Destination),
+        &[Action::Browse],
+        "Browse for the destination folder",
+    ),
+    bind(
+        Scope::Page(Page::Destination),
+        &[Action::Layout],
+        "Show / hide the advanced layout (separate binaries, data, logs, temp)",
+    ),
+    bind(
+        Scope::Page(Page::Destination),
+        &[Action::CheckSpace],
+        "Re-check free space for the layout folders",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::Up, Action::Down],
+        "Choose the call data source (local, remote, ODBC, export files)",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::Browse],
+        "Browse for the export folder, or the source's tables and views",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::AddSource],
+        "Add another call data source (e.g. separate Fire and EMS CAD)",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::NextSource],
+        "Switch to the next call data source",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::RemoveSource],
+        "Remove the call data source shown",
+    ),
+    bind(
+        Scope::Page(Page::DataSource),
+        &[Action::Profiles],
+        "Saved connection profiles: load, save or delete",
+    ),
+    bind(
+        Scope::Page(Page::Database),
+        &[Action::Up, Action::Down],
+        "Switch between a new and an existing database",
+    ),
+    bind(
+        Scope::Page(Page::Database),
+        &[Action::Left, Action::Right],
+        "Change location or hosting (TLS mode on the TLS field)",
+    ),
+    bind(
+        Scope::Page(Page::Database),
+        &[Action::Toggle],
+        "Existing database: connection string or details",
+    ),
+    bind(
+        Scope::Page(Page::Database),
+        &[Action::TestConnection],
+        "Test the database connection",
+    ),
+    bind(
+        Scope::Page(Page::Database),
+        &[Action::Profiles],
+        "Saved connection profiles: load, save or delete",
+    ),
+    bind(
+        Scope::Page(Page::Storage),
+        &[Action::Up, Action::Down],
+        "Switch between defaults and custom storage",
+    ),
+    bind(
+        Scope::Page(Page::Storage),
+        &[Action::Left, Action::Right],
+        "Custom: cycle the storage location",
+    ),
+    bind(
+        Scope::Page(Page::Storage),
+        &[Action::CyclePolicy],
+        "Custom: cycle the retention policy",
+    ),
+    bind(
+        Scope::Page(Page::Retention),
+        &[Action::CycleRetention],
+        "Cycle the hot retention period",
+    ),
+    bind(
+        Scope::Page(Page::Archive),
+        &[Action::ArchiveFormat],
+        "Switch the archive format",
+    ),
+    bind(
+        Scope::Page(Page::Archive),
+        &[Action::ArchiveEncrypt],
+        "Toggle archive encryption",
+    ),
+    bind(
+        Scope::Page(Page::Archive),
+        &[Action::Toggle],
+        "Toggle catch-up on startup",
+    ),
+    bind(
+        Scope::Page(Page::Consent),
+        &[Action::Toggle],
+        "Toggle consent to sync",
+    ),
+    bind(
+        Scope::Page(Page::Consent),
+        &[Action::ConsentDetails],
+        "Show / hide the details",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Up, Action::Down],
+        "Move in the focused field list",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Activate],
+        "Select the source field / map the target field",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Search],
+        "Search the focused list",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Unassign],
+        "Unassign the selected source field",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Override],
+        "Toggle override (one source, several targets)",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Transforms],
+        "Edit value transforms of the selected target",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::AutoMap],
+        "Suggest mappings for the unmapped fields",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::ConfirmSuggestion],
+        "Confirm the selected suggestion (all when none is selected)",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::ExportMapping],
+        "Export the mapping to a CSV sheet for review",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::ImportMapping],
+        "Import a reviewed CSV sheet (replaces the mapping)",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::NextSource],
+        "Map the next call data source",
+    ),
+    bind(
+        Scope::Page(Page::Mapping),
+        &[Action::Toggle],
+        "Toggle the focused checkbox",
+    ),
+    bind(
+        Scope::Page(Page::Ready),
+        &[Action::DryRun],
+        "Toggle dry run",
+    ),
+    bind(
+        Scope::Page(Page::Installing),
+        &[Action::PauseResume],
+        "Pause / resume the installation",
+    ),
+    bind(
+        Scope::Page(Page::Installing),
+        &[Action::ViewLog],
+        "View the full log",
+    ),
+    bind(
+        Scope::Page(Page::Complete),
+        &[Action::ArchiveNow],
+        "Run the archive catch-up now",
+    ),
+    bind(
+        Scope::Page(Page::Complete),
+        &[Action::ViewLog],
+        "View the full log",
+    ),
+];
+
+/// Help overlay rows for `page`: (keys, description), page shortcuts first, then global ones.
+pub(super) fn help_rows(keymap: &Keymap, page: Page) -> Vec<(String, &'static str)> {
+    let row = |b: &Binding| {
+        let keys = b
+            .actions
+            .iter()
+            .flat_map(|a| keymap.keys(*a))
+            .map(|k| key_label(*k))
+            .collect::<Vec<_>>()
+            .join(" / ");
+        (keys, b.description)
+    };
+    BINDINGS
+        .iter()
+        .filter(|b| b.scope == Scope::Page(page))
+        .chain(BINDINGS.iter().filter(|b| b.scope == Global))
+        .map(row)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_lists_page_shortcuts_before_global_ones() {
+        let keymap = Keymap::default();
+        let rows = help_rows(&keymap, Page::Database);
+        assert_eq!(
+            rows.first().map(|(k, _)| k.as_str()),
+            Some("Up / Down"),
+            "{:?}",
+            rows
+        );
+        assert!(rows
+            .iter()
+            .any(|(k, d)| k == "T" && d.contains("Test the database")));
+        assert_eq!(
+            rows.last().map(|(k, _)| k.as_str()),
+            Some("Esc"),
+            "{:?}",
+            rows
+        );
+        assert!(help_rows(&keymap, Page::Welcome)
+            .iter()
+            .all(|(k, _)| ["F1 / ?", "Tab", "Enter", "Esc"].contains(&k.as_str())));
+    }
+
+    #[test]
+    fn remapped_keys_resolve_to_canonical_and_defaults_are_released() {
+        let mut keymap = Keymap::default();
+        keymap
+            .apply_json(
+                r#"{ "page_down": ["j", "F8"], "test_connection": ["F5"], "bogus": ["x"], "help": ["nope"] }"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            keymap.resolve(Some(Page::License), Char('J')),
+            Some(PageDown)
+        );
+        assert_eq!(keymap.resolve(None, F(8)), Some(PageDown));
+        assert_eq!(keymap.resolve(Some(Page::License), PageDown), None);
+        assert_eq!(keymap.resolve(Some(Page::Database), F(5)), Some(Char('t')));
+        assert_eq!(keymap.resolve(Some(Page::Database), Char('t')), None);
+        // `t` is unbound on the License page, so it passes through.
+        assert_eq!(
+            keymap.resolve(Some(Page::License), Char('t')),
+            Some(Char('t'))
+        );
+        // Unusable overrides keep the defaults.
+        assert_eq!(keymap.resolve(None, Char('?')), Some(F(1)));
+        assert_eq!(keymap.resolve(None, Home), Some(Home));
+        assert_eq!(
+            help_rows(&keymap, Page::License)[1],
+            ("PgUp / J / F8".to_string(), "Scroll the license text")
+        );
+
+        assert!(keymap.apply_json("[1, 2]").is_err());
+        assert_eq!(parse_key("PgUp"), Some(PageUp));
+        assert_eq!(parse_key("space"), Some(Char(' ')));
+        assert_eq!(parse_key("F25"), None);
+    }
+}
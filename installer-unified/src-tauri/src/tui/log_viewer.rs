@@ -0,0 +1,356 @@
+//! Full-screen log viewer (L on the Installing and Complete pages).
+//!
+//! Tails this run's human-readable `installer-*.txt` log: every UI tick reads whatever was
+//! appended since the last read, so the view streams while the install is running. Keys:
+//! Up/Down/PgUp/PgDn/Home scroll, End follows the tail again, `/` searches, `n`/`N` jump to the
+//! next/previous match, Esc, L or q closes.
+
+use super::theme;
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use std::cell::Cell;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+/// Only the tail of a large log is loaded when the viewer opens.
+const INITIAL_TAIL_BYTES: u64 = 2 * 1024 * 1024;
+/// Most bytes read per tick, so a burst of logging never stalls the UI.
+const MAX_READ_PER_POLL: u64 = 1024 * 1024;
+/// Oldest lines are dropped beyond this.
+const MAX_LINES: usize = 50_000;
+
+pub(super) struct LogViewer {
+    path: Option<PathBuf>,
+    offset: u64,
+    /// Text after the last newline (a line still being written).
+    partial: String,
+    /// Reading started mid-file: the first line read is a fragment.
+    skip_first_line: bool,
+    lines: Vec<String>,
+    /// First visible line when not following.
+    top: usize,
+    /// Keep the newest line in view.
+    follow: bool,
+    /// Search text being typed after `/`.
+    search_input: Option<String>,
+    query: String,
+    status: Option<String>,
+    /// Visible line count from the last draw (paging and search positioning).
+    view_height: Cell<usize>,
+}
+
+impl LogViewer {
+    pub(super) fn open() -> Self {
+        let path = crate::utils::logging::current_text_log_path();
+        let mut viewer = Self {
+            status: if path.is_none() {
+                Some("No installer log file found.".to_string())
+            } else {
+                None
+            },
+            path,
+            offset: 0,
+            partial: String::new(),
+            skip_first_line: false,
+            lines: Vec::new(),
+            top: 0,
+            follow: true,
+            search_input: None,
+            query: String::new(),
+            view_height: Cell::new(20),
+        };
+        if let Some(len) = viewer
+            .path
+            .as_ref()
+            .and_then(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+        {
+            if len > INITIAL_TAIL_BYTES {
+                viewer.offset = len - INITIAL_TAIL_BYTES;
+                viewer.skip_first_line = true;
+            }
+        }
+        viewer.poll();
+        viewer
+    }
+
+    /// Read whatever was appended to the log since the last call.
+    pub(super) fn poll(&mut self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let Ok(mut file) = std::fs::File::open(path) else {
+            return;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            // Truncated/replaced: start over.
+            self.offset = 0;
+            self.partial.clear();
+            self.lines.clear();
+            self.top = 0;
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return;
+        }
+        let mut buf = Vec::new();
+        if let Ok(n) = file.take(MAX_READ_PER_POLL).read_to_end(&mut buf) {
+            self.offset += n as u64;
+            self.append(&String::from_utf8_lossy(&buf));
+        }
+    }
+
+    fn append(&mut self, chunk: &str) {
+        self.partial.push_str(chunk);
+        let Some(last_newline) = self.partial.rfind('\n') else {
+            return;
+        };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        let mut lines = complete.lines();
+        if self.skip_first_line {
+            lines.next();
+            self.skip_first_line = false;
+        }
+        self.lines.extend(lines.map(str::to_string));
+        if self.lines.len() > MAX_LINES {
+            let drop = self.lines.len() - MAX_LINES;
+            self.lines.drain(..drop);
+            self.top = self.top.saturating_sub(drop);
+        }
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.view_height.get())
+    }
+
+    fn current_top(&self) -> usize {
+        if self.follow {
+            self.max_top()
+        } else {
+            self.top.min(self.max_top())
+        }
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.max_top());
+        self.follow = self.top >= self.max_top();
+    }
+
+    fn find(&self, from: usize, forward: bool) -> Option<usize> {
+        if self.query.is_empty() || self.lines.is_empty() {
+            return None;
+        }
+        let q = self.query.to_ascii_lowercase();
+        let hit = |i: &usize| self.lines[*i].to_ascii_lowercase().contains(&q);
+        let n = self.lines.len();
+        if forward {
+            (from..n).chain(0..from.min(n)).find(hit)
+        } else {
+            let from = from.min(n);
+            (0..from).rev().chain((from..n).rev()).find(hit)
+        }
+    }
+
+    fn jump(&mut self, forward: bool) {
+        let top = self.current_top();
+        let start = if forward { top + 1 } else { top };
+        match self.find(start, forward) {
+            Some(i) => {
+                self.scroll_to(i);
+                self.status = None;
+            }
+            None => self.status = Some(format!("No match for '{}'.", self.query)),
+        }
+    }
+
+    /// Search text is being typed (keys are text, not commands).
+    pub(super) fn is_typing(&self) -> bool {
+        self.search_input.is_some()
+    }
+
+    /// Handle a key; returns false when the viewer should close.
+    pub(super) fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(input) = self.search_input.as_mut() {
+            match code {
+                KeyCode::Enter => {
+                    self.query = self.search_input.take().unwrap_or_default();
+                    // A match already on the first visible line counts.
+                    let top = self.current_top();
+                    match self.find(top, true) {
+                        Some(i) => {
+                            self.scroll_to(i);
+                            self.status = None;
+                        }
+                        None if !self.query.is_empty() => {
+                            self.status = Some(format!("No match for '{}'.", self.query))
+                        }
+                        None => self.status = None,
+                    }
+                }
+                KeyCode::Esc => self.search_input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        let page = self.view_height.get().max(1);
+        let top = self.current_top();
+        match code {
+            KeyCode::Esc | KeyCode::Char('l') | KeyCode::Char('L') | KeyCode::Char('q') => {
+                return false
+            }
+            KeyCode::Up => self.scroll_to(top.saturating_sub(1)),
+            KeyCode::Down => self.scroll_to(top + 1),
+            KeyCode::PageUp => self.scroll_to(top.saturating_sub(page)),
+            KeyCode::PageDown => self.scroll_to(top + page),
+            KeyCode::Home => self.scroll_to(0),
+            KeyCode::End => self.follow = true,
+            KeyCode::Char('/') => self.search_input = Some(String::new()),
+            KeyCode::Char('n') => self.jump(true),
+            KeyCode::Char('N') => self.jump(false),
+            _ => {}
+        }
+        true
+    }
+
+    pub(super) fn draw(&self, f: &mut ratatui::Frame<'_>, area: Rect) {
+        f.render_widget(Clear, area);
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Log".to_string());
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "{}{}",
+            name,
+            if self.follow { " (following)" } else { "" }
+        ));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if inner.height < 2 {
+            return;
+        }
+
+        let height = inner.height.saturating_sub(1) as usize;
+        self.view_height.set(height);
+        let top = self.current_top();
+        let q = self.query.to_ascii_lowercase();
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(top)
+            .take(height)
+            .map(|l| {
+                if !q.is_empty() && l.to_ascii_lowercase().contains(&q) {
+                    Line::from(Span::styled(l.clone(), theme::current().matched()))
+                } else {
+                    Line::from(l.clone())
+                }
+            })
+            .collect();
+        let body = Rect {
+            height: height as u16,
+            ..inner
+        };
+        f.render_widget(Paragraph::new(Text::from(lines)), body);
+
+        let footer = match (&self.search_input, &self.status) {
+            (Some(input), _) => format!("/{}", input),
+            (None, Some(status)) => status.clone(),
+            (None, None) => format!(
+                "Lines {}-{} of {}   Up/Down/PgUp/PgDn scroll  End follow  / search  n/N next/prev  Esc close",
+                (top + 1).min(self.lines.len()),
+                (top + height).min(self.lines.len()),
+                self.lines.len()
+            ),
+        };
+        let footer_area = Rect {
+            y: inner.y + inner.height - 1,
+            height: 1,
+            ..inner
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(footer, theme::current().focused()))),
+            footer_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn viewer_for(path: PathBuf) -> LogViewer {
+        let mut v = LogViewer {
+            path: Some(path),
+            offset: 0,
+            partial: String::new(),
+            skip_first_line: false,
+            lines: Vec::new(),
+            top: 0,
+            follow: true,
+            search_input: None,
+            query: String::new(),
+            status: None,
+            view_height: Cell::new(2),
+        };
+        v.poll();
+        v
+    }
+
+    #[test]
+    fn streams_appended_lines_and_keeps_partial_line_pending() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("installer-2026-01-01-000000.txt");
+        std::fs::write(&path, "one\r\ntwo\nthr").unwrap();
+        let mut v = viewer_for(path.clone());
+        assert_eq!(v.lines, vec!["one", "two"]);
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        f.write_all(b"ee\nfour\n").unwrap();
+        v.poll();
+        assert_eq!(v.lines, vec!["one", "two", "three", "four"]);
+        assert_eq!(v.current_top(), 2);
+    }
+
+    #[test]
+    fn search_jumps_between_matches_and_scrolling_stops_following() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("installer.txt");
+        std::fs::write(
+            &path,
+            "a\n[STEP: migrations] start\nb\nc\n[STEP: MIGRATIONS] done\nd\n",
+        )
+        .unwrap();
+        let mut v = viewer_for(path);
+
+        v.handle_key(KeyCode::Home);
+        assert!(!v.follow);
+        v.handle_key(KeyCode::Char('/'));
+        for c in "migrations".chars() {
+            v.handle_key(KeyCode::Char(c));
+        }
+        v.handle_key(KeyCode::Enter);
+        assert_eq!(v.current_top(), 1);
+        v.handle_key(KeyCode::Char('n'));
+        assert_eq!(v.current_top(), 4);
+        v.handle_key(KeyCode::Char('n'));
+        assert_eq!(v.current_top(), 1);
+
+        v.handle_key(KeyCode::End);
+        assert_eq!(v.current_top(), 4);
+        assert!(!v.handle_key(KeyCode::Esc));
+    }
+}
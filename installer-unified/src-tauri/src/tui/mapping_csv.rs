@@ -0,0 +1,219 @@
+//! Mapping review sheet export / import (E / I on the Mapping page).
+//!
+//! Both ask for a file path (default `mapping-review.csv` in the working directory) and run on
+//! Enter; Esc closes. An import replaces the mapping and drops pending auto-map suggestions;
+//! a sheet with problems is rejected as a whole and the problems are shown in the modal. The
+//! sheet format is in `mapping::csv`.
+
+use super::*;
+use crate::mapping::csv;
+
+const DEFAULT_FILE: &str = "mapping-review.csv";
+
+pub(super) fn open(state: &mut WizardState, import: bool) {
+    let path = std::env::current_dir()
+        .map(|d| d.join(DEFAULT_FILE))
+        .unwrap_or_else(|_| std::path::PathBuf::from(DEFAULT_FILE));
+    state.modal = Some(Modal::MappingCsv {
+        import,
+        path: path.to_string_lossy().to_string(),
+        error: None,
+    });
+}
+
+fn export(state: &WizardState, path: &str) -> Result<String> {
+    let (text, rows) = csv::export(&current_mapping_state(state));
+    std::fs::write(path, text)?;
+    info!(
+        "[PHASE: mapping] [STEP: export_csv] Mapping sheet written (path={}, rows={})",
+        path, rows
+    );
+    Ok(format!("{} row(s) written to {}.", rows, path))
+}
+
+fn import(state: &mut WizardState, path: &str) -> Result<String> {
+    let text = std::fs::read_to_string(path)?;
+    let ms = csv::import(&current_mapping_state(state), &text)?;
+    state.source_to_targets = ms.source_to_targets;
+    state.target_to_source = ms.target_to_source;
+    state.mapping_suggested.clear();
+    state.selected_source_id = None;
+    state.selected_target_id = None;
+    info!(
+        "[PHASE: mapping] [STEP: import_csv] Mapping sheet imported (path={}, mapped={})",
+        path,
+        state.target_to_source.len()
+    );
+    Ok(format!(
+        "{} target field(s) mapped from {}.",
+        state.target_to_source.len(),
+        path
+    ))
+}
+
+pub(super) fn handle_key(state: &mut WizardState, code: KeyCode) {
+    let Some(Modal::MappingCsv {
+        import: is_import,
+        mut path,
+        mut error,
+    }) = state.modal.clone()
+    else {
+        return;
+    };
+    match code {
+        KeyCode::Esc => {
+            state.modal = None;
+            return;
+        }
+        KeyCode::Enter => {
+            let p = path.trim().to_string();
+            let result = if is_import {
+                import(state, &p)
+            } else {
+                export(state, &p)
+            };
+            match result {
+                Ok(body) => {
+                    state.modal = Some(Modal::Message {
+                        title: if is_import {
+                            "Import Mapping"
+                        } else {
+                            "Export Mapping"
+                        }
+                        .to_string(),
+                        body,
+                        return_to: None,
+                    });
+                    return;
+                }
+                Err(e) => error = Some(e.to_string()),
+            }
+        }
+        KeyCode::Backspace => {
+            path.pop();
+        }
+        KeyCode::Char(c) => path.push(c),
+        _ => {}
+    }
+    state.modal = Some(Modal::MappingCsv {
+        import: is_import,
+        path,
+        error,
+    });
+}
+
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    import: bool,
+    path: &str,
+    error: Option<&str>,
+) {
+    let error_lines: Vec<&str> = error.map(|e| e.lines().collect()).unwrap_or_default();
+    let modal_w = 76u16.min(window_area.width.saturating_sub(4)).max(44);
+    let modal_h = (error_lines.len() as u16 + 9)
+        .min(window_area.height.saturating_sub(2))
+        .max(9);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default().borders(Borders::ALL).title(if import {
+        "Import Mapping (CSV)"
+    } else {
+        "Export Mapping (CSV)"
+    });
+    let mut lines: Vec<Line> = vec![
+        Line::from(if import {
+            "Replace the mapping with the target_id / source_column pairs in this sheet:"
+        } else {
+            "Write one row per target field plus the unused source columns to:"
+        }),
+        Line::from(format!("> {}_", path)),
+        Line::from(""),
+    ];
+    for e in error_lines {
+        lines.push(Line::from(ratatui::text::Span::styled(
+            e.to_string(),
+            theme::current().error(),
+        )));
+    }
+    let p = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(p, area);
+
+    let buttons_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - 2,
+        width: area.width - 2,
+        height: 1,
+    };
+    let button = ratatui::text::Span::styled(
+        if import { "[ Import ]" } else { "[ Export ]" },
+        theme::current().focused(),
+    );
+    for rect in mouse::right_aligned_spans(buttons_area, &[&button]) {
+        mouse::record(state, rect, HitTarget::ModalButton(0));
+    }
+    let p = Paragraph::new(Text::from(Line::from(button))).alignment(Alignment::Right);
+    f.render_widget(p, buttons_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_then_import_round_trips_through_the_modal() {
+        let mut state = WizardState::new();
+        state.source_fields = vec![SourceField {
+            id: "INC__0".to_string(),
+            raw_name: "INC".to_string(),
+            display_name: "INC".to_string(),
+            data_type: "nvarchar".to_string(),
+            sample_values: Vec::new(),
+        }];
+        apply_mapping(&mut state, "INC__0", "IncidentNumber", false);
+        let path = std::env::temp_dir().join(format!("mapping-csv-{}.csv", Uuid::new_v4()));
+        let path = path.to_string_lossy().to_string();
+
+        state.modal = Some(Modal::MappingCsv {
+            import: false,
+            path: path.clone(),
+            error: None,
+        });
+        handle_key(&mut state, KeyCode::Enter);
+        assert!(matches!(state.modal, Some(Modal::Message { .. })));
+
+        let sheet = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            sheet.replace(
+                "IncidentNumber,Incident Number,yes,INC",
+                "IncidentNumber,,,",
+            ) + "City,,,INC\r\n",
+        )
+        .unwrap();
+        state.modal = Some(Modal::MappingCsv {
+            import: true,
+            path: path.clone(),
+            error: None,
+        });
+        handle_key(&mut state, KeyCode::Enter);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(state.modal, Some(Modal::Message { .. })));
+        assert_eq!(
+            state.target_to_source,
+            HashMap::from([("City".to_string(), "INC__0".to_string())])
+        );
+        assert_eq!(state.source_to_targets["INC__0"], vec!["City"]);
+    }
+}
@@ -9,25 +9,60 @@
 //!
 //! Note: Logging is file-only in TUI mode (stdout logging is disabled) to avoid corrupting the terminal UI.
 
+mod backfill;
+mod disk_space;
+mod draft;
+#[cfg(target_os = "linux")]
+mod elevate;
+mod field_rules;
+mod install_layout;
+mod invalidation;
+mod keymap;
+mod log_viewer;
+mod mapping_csv;
+mod mouse;
+pub mod plain;
+mod profiles;
+pub mod script;
+mod source_objects;
+mod sources;
+mod storage_fs;
+pub mod theme;
+mod transforms;
+
 use crate::api::installer::{
-    self, ArchivePolicyConfig, ArchiveScheduleConfig, HotRetentionConfig, InstallArtifacts,
-    MappingSourceField, MappingState, MappingTargetField, ProgressEmitter, ProgressPayload,
-    StartInstallRequest, StorageConfig,
+    self, ArchivePolicyConfig, ArchiveScheduleConfig, FileSourceConfig, HotRetentionConfig,
+    InstallArtifacts, MappingSourceField, MappingState, MappingTargetField, ProgressEmitter,
+    ProgressPayload, StartInstallRequest, StorageConfig,
 };
 use crate::api::preflight;
+use crate::database::app_account::AppAccountConfig;
+use crate::database::conn_string::{
+    PostgresConnString, SqlServerConnString, POSTGRES_DEFAULT_PORT, SQL_SERVER_DEFAULT_PORT,
+};
+use crate::datasource::file;
+use crate::licensing::features::{self, LicensedFeatures};
+use crate::mapping::suggest::{self, MappingSuggestion, SuggestSource};
+use crate::mapping::transform::FieldTransform;
 use crate::models::requests::PreflightDataSourceRequestDto;
 use crate::models::responses::DiscoveredColumnDto;
 use crate::security::secret_protector::SecretProtector;
+use crate::utils::env_defaults::WizardDefaults;
+use crate::utils::i18n;
+use crate::utils::logging::mask_connection_string;
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers,
+};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::ExecutableCommand;
-use log::info;
+use log::{error, info, warn};
 use ratatui::backend::{CrosstermBackend, TestBackend};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Terminal;
@@ -39,6 +74,39 @@ use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use field_rules::Rule;
+use keymap::{Action, Keymap};
+use log_viewer::LogViewer;
+use mouse::HitTarget;
+
+/// Smallest terminal the wizard draws in; below it a resize prompt is shown instead.
+/// Exit code of a wizard stopped by Ctrl+C or SIGINT (128 + SIGINT, as shells report it).
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+const MIN_TERMINAL_WIDTH: u16 = 60;
+const MIN_TERMINAL_HEIGHT: u16 = 20;
+/// Narrower terminals drop the ASCII banner column.
+const BANNER_MIN_WIDTH: u16 = 90;
+/// Shorter terminals stack the mapping panes instead of placing them side by side.
+const MAPPING_SIDE_BY_SIDE_MIN_HEIGHT: u16 = 30;
+
+/// What a frame of the given terminal size shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScreenLayout {
+    TooSmall,
+    Wizard { banner: bool, stacked_mapping: bool },
+}
+
+fn screen_layout(width: u16, height: u16) -> ScreenLayout {
+    if width < MIN_TERMINAL_WIDTH || height < MIN_TERMINAL_HEIGHT {
+        return ScreenLayout::TooSmall;
+    }
+    ScreenLayout::Wizard {
+        banner: width >= BANNER_MIN_WIDTH,
+        stacked_mapping: height < MAPPING_SIDE_BY_SIDE_MIN_HEIGHT,
+    }
+}
+
 const ASCII_LOGO: &str = r#"██████╗ █████╗ ██████╗  █████╗ ██╗  ██╗   ██╗████████╗██╗██╗  ██╗
 ██╔════╝██╔══██╗██╔══██╗██╔══██╗██║  ╚██╗ ██╔╝╚══██╔══╝██║╚██╗██╔╝
 ██║     ███████║██║  ██║███████║██║   ╚████╔╝    ██║   ██║ ╚███╔╝
@@ -81,6 +149,8 @@ enum ButtonFocus {
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Modal {
     ConfirmCancel,
+    /// Keyboard shortcuts for the current page (F1 / `?`).
+    Help,
     Message {
         title: String,
         body: String,
@@ -98,6 +168,50 @@ enum Modal {
         selected: usize,
         pending: PendingMapping,
     },
+    /// Value transforms of one target field (see `transforms`).
+    Transforms {
+        target_id: String,
+        selected: usize,
+        prompt: Option<transforms::Prompt>,
+        error: Option<String>,
+    },
+    /// Path prompt for the mapping review sheet (see `mapping_csv`).
+    MappingCsv {
+        import: bool,
+        path: String,
+        error: Option<String>,
+    },
+    /// Offer to resume the answers of an earlier session (see `draft`).
+    ResumeDraft {
+        draft: draft::Draft,
+        resume: bool,
+    },
+    /// Tables and views of the call data source (see `source_objects`); `objects` is `None`
+    /// while they are being listed.
+    SourceObjects {
+        filter: String,
+        objects: Option<Vec<crate::models::responses::SourceObjectDto>>,
+        truncated: bool,
+        selected: usize,
+        error: Option<String>,
+    },
+    /// Saved connection profiles of the page (see `profiles`); `name` is the name being typed
+    /// to save under.
+    Profiles {
+        kind: crate::utils::connection_profiles::ProfileKind,
+        profiles: Vec<crate::utils::connection_profiles::ConnectionProfile>,
+        selected: usize,
+        name: Option<String>,
+        error: Option<String>,
+    },
+    /// Root-only commands of the install awaiting consent (see `elevate`); `authorizing` once
+    /// Continue was chosen.
+    #[cfg(target_os = "linux")]
+    Elevate {
+        plan: crate::installation::elevation::ElevationPlan,
+        approve: bool,
+        authorizing: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -111,6 +225,12 @@ enum InstallationType {
 enum DataSourceKind {
     Local,
     Remote,
+    /// DSN or driver connection string through the ODBC driver manager (`datasource::odbc`).
+    Odbc,
+    /// Oracle through the Oracle ODBC driver (`datasource::oracle`, cargo feature `oracle`).
+    Oracle,
+    /// Folder of CSV / TSV exports (`datasource::file`).
+    File,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -250,6 +370,9 @@ struct TextInput {
     value: String,
     cursor: usize,
     masked: bool,
+    // Inline validation (see `field_rules`): the field's rules and when it was last typed in.
+    rules: &'static [Rule],
+    edited_at: Option<Instant>,
 }
 
 impl TextInput {
@@ -259,6 +382,8 @@ impl TextInput {
             cursor: v.len(),
             value: v,
             masked,
+            rules: &[],
+            edited_at: None,
         }
     }
 
@@ -280,6 +405,7 @@ impl TextInput {
             KeyCode::Char(c) => {
                 self.value.insert(self.cursor, c);
                 self.cursor = (self.cursor + 1).min(self.value.len());
+                self.edited_at = Some(Instant::now());
                 true
             }
             KeyCode::Backspace => {
@@ -288,12 +414,14 @@ impl TextInput {
                     self.value.remove(idx);
                     self.cursor = idx;
                 }
+                self.edited_at = Some(Instant::now());
                 true
             }
             KeyCode::Delete => {
                 if self.cursor < self.value.len() && !self.value.is_empty() {
                     self.value.remove(self.cursor);
                 }
+                self.edited_at = Some(Instant::now());
                 true
             }
             KeyCode::Left => {
@@ -339,6 +467,10 @@ struct SourceField {
     id: String,
     raw_name: String,
     display_name: String,
+    // SQL type from the scan (auto-map type matching); empty when unknown.
+    data_type: String,
+    // Values from the preflight sample query (bottom preview strip).
+    sample_values: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -378,6 +510,26 @@ enum UiMsg {
         message: String,
         correlation_id: String,
         artifacts: Option<InstallArtifacts>,
+        exit_code: i32,
+    },
+    ArchiveProgress(ProgressPayload),
+    ArchiveFinished {
+        success: bool,
+        message: String,
+    },
+    SourceObjectsLoaded(Result<crate::models::responses::ListSourceObjectsResponseDto, String>),
+    BackfillEstimated {
+        key: String,
+        result: Result<crate::models::responses::BackfillEstimateDto, String>,
+    },
+    LayoutSpaceChecked(Vec<crate::installation::layout::SpaceCheck>),
+    DiskSpaceProjected {
+        key: String,
+        projection: crate::installation::space_projection::SpaceProjection,
+    },
+    StorageFilesystemInspected {
+        key: String,
+        inspected: crate::installation::storage_fs::StorageFilesystem,
     },
 }
 
@@ -399,18 +551,42 @@ struct WizardState {
 
     destination_path: TextInput,
     destination_error: Option<String>,
+    // Advanced layout (see `install_layout`); empty paths keep the defaults.
+    layout_advanced: bool,
+    layout_binaries: TextInput,
+    layout_data: TextInput,
+    layout_logs: TextInput,
+    layout_temp: TextInput,
+    layout_checking: bool,
+    layout_space: Option<Vec<crate::installation::layout::SpaceCheck>>,
 
     data_source_kind: DataSourceKind,
+    // Name of the source being edited; the other sources are parked (see `sources`).
+    source_name: TextInput,
+    data_sources: Vec<sources::SourceDraft>,
+    active_source: usize,
     source_object_name: TextInput,
+    odbc_connection_string: TextInput,
+    /// Blank means the Instant Client driver name.
+    oracle_driver: TextInput,
+    /// Easy Connect `host[:port]/service` or a TNS alias.
+    oracle_address: TextInput,
+    file_source_folder: TextInput,
+    /// auto | comma | tab | semicolon | pipe
+    file_source_delimiter: TextInput,
     call_data_host: TextInput,
     call_data_port: TextInput,
     call_data_database: TextInput,
     call_data_user: TextInput,
     call_data_password: TextInput,
+    /// SQL Server call data through Windows Authentication (username/password unused).
+    call_data_windows_auth: bool,
 
     db_kind: DbKind,
     db_engine: DbEngine,
     db_use_conn_string: bool,
+    /// Existing SQL Server config DB through Windows Authentication (details mode).
+    db_windows_auth: bool,
     db_host: TextInput,
     db_port: TextInput,
     db_database: TextInput,
@@ -425,7 +601,10 @@ struct WizardState {
     new_db_location: NewDbLocation,
     new_db_specific_path: TextInput,
     new_db_max_size_gb: TextInput,
+    new_db_collation: TextInput,
     existing_hosted_where: ExistingHostedWhere,
+    /// Dedicated application login (blank = the application runs with the install login).
+    app_login: TextInput,
 
     storage_mode: StorageMode,
     storage_location: StorageLocation,
@@ -442,14 +621,32 @@ struct WizardState {
     archive_schedule_day_of_month: TextInput,
     archive_schedule_time_local: TextInput,
     archive_catch_up_on_startup: bool,
+    archive_encrypt: bool,
     consent_to_sync: bool,
     consent_details_expanded: bool,
 
+    // Backfill estimate on Ready (see `backfill`); keyed like `mapping_scan_key`.
+    backfill_key: Option<String>,
+    backfill_estimating: bool,
+    backfill_estimate: Option<Result<crate::models::responses::BackfillEstimateDto, String>>,
+
+    // Disk space projection on Ready (see `disk_space`); a short volume blocks Install.
+    disk_space_key: Option<String>,
+    disk_space_checking: bool,
+    disk_space: Option<crate::installation::space_projection::SpaceProjection>,
+
+    // Filesystem under the database folder on Storage (see `storage_fs`); FAT/exFAT block Next.
+    storage_fs_key: Option<String>,
+    storage_fs_checking: bool,
+    storage_fs: Option<crate::installation::storage_fs::StorageFilesystem>,
+
     // Schema mapping (B3/B4)
     mapping_demo_mode: bool,
     mapping_override: bool,
     mapping_scanning: bool,
     mapping_scan_error: Option<String>,
+    // Source the current fields were scanned from (see `mapping_scan_key`).
+    mapping_scan_key: Option<String>,
     source_fields: Vec<SourceField>,
     target_fields: Vec<TargetField>,
     source_search: TextInput,
@@ -460,12 +657,43 @@ struct WizardState {
     target_list_index: usize,
     source_to_targets: HashMap<String, Vec<String>>,
     target_to_source: HashMap<String, String>,
+    // Per-target value transforms, keyed by target id.
+    mapping_transforms: HashMap<String, Vec<FieldTransform>>,
+    // Auto-map suggestions by target id, pending until confirmed (see `pending_suggestion`).
+    mapping_suggested: HashMap<String, MappingSuggestion>,
+
+    // Back-navigation invalidation (see `invalidation`): answers at the last page change,
+    // pages reached so far and pages to review because earlier answers changed.
+    answer_snapshot: Vec<(Page, script::Answers)>,
+    visited_pages: Vec<Page>,
+    dirty_pages: Vec<Page>,
+
+    // Ready page: plan only (dry run), no changes made
+    dry_run: bool,
 
     // Installing status
     install_progress: Option<ProgressPayload>,
     install_detail: Vec<String>,
     install_correlation_id: Option<String>,
     install_artifacts: Option<InstallArtifacts>,
+    // Pause requested (true until resumed); the run stops at its next step boundary.
+    install_paused: bool,
+    // Process exit code of the last install attempt (`crate::error::exit_code`; 0 on success).
+    install_exit_code: i32,
+    // Full log viewer overlay (L on Installing/Complete).
+    log_viewer: Option<LogViewer>,
+    // Key bindings (defaults plus the user's keymap file in a real run).
+    keymap: Keymap,
+    // Clickable areas of the last drawn frame (see `mouse`).
+    hit_regions: std::cell::RefCell<Vec<(Rect, HitTarget)>>,
+
+    // Post-install archive catch-up run (Complete page)
+    archive_run_active: bool,
+    archive_run_progress: Option<ProgressPayload>,
+    archive_run_result: Option<String>,
+
+    // Feature flags of the verified license; unlicensed pages/options are disabled.
+    licensed: LicensedFeatures,
 }
 
 impl WizardState {
@@ -485,22 +713,40 @@ impl WizardState {
 
             license_scroll: 0,
 
-            destination_path: TextInput::new("C:\\Program Files\\CADalytix", false),
+            destination_path: TextInput::new("C:\\Program Files\\CADalytix", false)
+                .with_rules(&[Rule::Required, Rule::Path]),
             destination_error: None,
+            layout_advanced: false,
+            layout_binaries: TextInput::new("", false).with_rules(&[Rule::Path]),
+            layout_data: TextInput::new("", false).with_rules(&[Rule::Path]),
+            layout_logs: TextInput::new("", false).with_rules(&[Rule::Path]),
+            layout_temp: TextInput::new("", false).with_rules(&[Rule::Path]),
+            layout_checking: false,
+            layout_space: None,
 
             data_source_kind: DataSourceKind::Local,
-            source_object_name: TextInput::new("dbo.CallData", false),
-            call_data_host: TextInput::new("localhost", false),
-            call_data_port: TextInput::new("1433", false),
+            source_name: TextInput::new(installer::DEFAULT_DATA_SOURCE_NAME, false),
+            data_sources: Vec::new(),
+            active_source: 0,
+            source_object_name: TextInput::new("dbo.CallData", false).with_rules(&[Rule::Required]),
+            odbc_connection_string: TextInput::new("DSN=", false),
+            oracle_driver: TextInput::new("", false),
+            oracle_address: TextInput::new("", false),
+            file_source_folder: TextInput::new("", false).with_rules(&[Rule::Required, Rule::Path]),
+            file_source_delimiter: TextInput::new("auto", false),
+            call_data_host: TextInput::new("localhost", false).with_rules(&[Rule::Host]),
+            call_data_port: TextInput::new("1433", false).with_rules(&[Rule::Port]),
             call_data_database: TextInput::new("", false),
             call_data_user: TextInput::new("", false),
             call_data_password: TextInput::new("", true),
+            call_data_windows_auth: false,
 
             db_kind: DbKind::Local,
             db_engine: DbEngine::SqlServer,
             db_use_conn_string: false,
-            db_host: TextInput::new("localhost", false),
-            db_port: TextInput::new("5432", false),
+            db_windows_auth: false,
+            db_host: TextInput::new("localhost", false).with_rules(&[Rule::Required, Rule::Host]),
+            db_port: TextInput::new("5432", false).with_rules(&[Rule::Port]),
             db_database: TextInput::new("cadalytix", false),
             db_user: TextInput::new("cadalytix_admin", false),
             db_password: TextInput::new("", true),
@@ -510,30 +756,52 @@ impl WizardState {
             db_test_message: String::new(),
 
             new_db_location: NewDbLocation::ThisMachine,
-            new_db_specific_path: TextInput::new("", false),
-            new_db_max_size_gb: TextInput::new("50", false),
+            new_db_specific_path: TextInput::new("", false)
+                .with_rules(&[Rule::Required, Rule::Path]),
+            new_db_max_size_gb: TextInput::new("50", false).with_rules(&[Rule::Positive]),
+            new_db_collation: TextInput::new("", false),
             existing_hosted_where: ExistingHostedWhere::OnPrem,
+            app_login: TextInput::new("", false),
 
             storage_mode: StorageMode::Defaults,
             storage_location: StorageLocation::System,
-            storage_custom_path: TextInput::new("", false),
+            storage_custom_path: TextInput::new("", false)
+                .with_rules(&[Rule::Required, Rule::Path]),
             retention_policy: RetentionPolicy::Rolling18,
-            max_disk_gb: TextInput::new("100", false),
+            max_disk_gb: TextInput::new("100", false).with_rules(&[Rule::Positive]),
 
             hot_retention_choice: HotRetentionChoice::Months18,
-            hot_retention_custom_months: TextInput::new("24", false),
+            hot_retention_custom_months: TextInput::new("24", false)
+                .with_rules(&[Rule::Required, Rule::Range(1, 240)]),
             archive_format: ArchiveFormatChoice::ZipNdjson,
-            archive_destination: TextInput::new("", false),
-            archive_max_usage_gb: TextInput::new("10", false),
-            archive_schedule_day_of_month: TextInput::new("1", false),
-            archive_schedule_time_local: TextInput::new("00:05", false),
+            archive_destination: TextInput::new("", false)
+                .with_rules(&[Rule::Required, Rule::Path]),
+            archive_max_usage_gb: TextInput::new("10", false)
+                .with_rules(&[Rule::Required, Rule::Positive]),
+            archive_schedule_day_of_month: TextInput::new("1", false)
+                .with_rules(&[Rule::Required, Rule::Range(1, 28)]),
+            archive_schedule_time_local: TextInput::new("00:05", false)
+                .with_rules(&[Rule::Required, Rule::Time]),
             archive_catch_up_on_startup: true,
+            archive_encrypt: false,
             consent_to_sync: false,
             consent_details_expanded: false,
 
+            backfill_key: None,
+            backfill_estimating: false,
+            backfill_estimate: None,
+
+            disk_space_key: None,
+            disk_space_checking: false,
+            disk_space: None,
+            storage_fs_key: None,
+            storage_fs_checking: false,
+            storage_fs: None,
+
             mapping_demo_mode: false,
             mapping_override: false,
             mapping_scanning: false,
+            mapping_scan_key: None,
             mapping_scan_error: None,
             source_fields: Vec::new(),
             target_fields: default_target_fields(),
@@ -545,96 +813,92 @@ impl WizardState {
             target_list_index: 0,
             source_to_targets: HashMap::new(),
             target_to_source: HashMap::new(),
+            mapping_transforms: HashMap::new(),
+            mapping_suggested: HashMap::new(),
+
+            answer_snapshot: Vec::new(),
+            visited_pages: Vec::new(),
+            dirty_pages: Vec::new(),
+
+            dry_run: false,
 
             install_progress: None,
             install_detail: Vec::new(),
             install_correlation_id: None,
             install_artifacts: None,
+            install_paused: false,
+            install_exit_code: 0,
+            log_viewer: None,
+            keymap: Keymap::default(),
+            hit_regions: std::cell::RefCell::new(Vec::new()),
+
+            archive_run_active: false,
+            archive_run_progress: None,
+            archive_run_result: None,
+            licensed: LicensedFeatures::default(),
         }
     }
 }
 
+const LICENSE_LINES: &[&str] = &[
+    "LICENSE TEXT NOT PROVIDED.",
+    "",
+    "Place your license text (EULA) under Prod_Install_Wizard_Deployment/licenses/ and wire the loader.",
+    "",
+    "This TUI currently uses a placeholder license body.",
+    "",
+    "Use PageUp/PageDown to scroll.",
+    "",
+    "By proceeding, you acknowledge you have read and understood the license agreement.",
+    "",
+    "— End of placeholder license —",
+];
+
 fn default_target_fields() -> Vec<TargetField> {
-    vec![
-        TargetField {
-            id: "CallReceivedAt".to_string(),
-            name: "Call Received At".to_string(),
-            required: true,
-        },
-        TargetField {
-            id: "IncidentNumber".to_string(),
-            name: "Incident Number".to_string(),
-            required: true,
-        },
-        TargetField {
-            id: "City".to_string(),
-            name: "City".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "State".to_string(),
-            name: "State".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "Zip".to_string(),
-            name: "Zip".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "Address".to_string(),
-            name: "Address".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "Latitude".to_string(),
-            name: "Latitude".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "Longitude".to_string(),
-            name: "Longitude".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "UnitId".to_string(),
-            name: "Unit ID".to_string(),
-            required: false,
-        },
-        TargetField {
-            id: "Disposition".to_string(),
-            name: "Disposition".to_string(),
-            required: false,
-        },
-    ]
+    crate::mapping::target_catalog()
+        .into_iter()
+        .map(|t| TargetField {
+            id: t.id,
+            name: t.name,
+            required: t.required,
+        })
+        .collect()
 }
 
-fn page_title(page: Page, _mode: InstallMode) -> &'static str {
-    match page {
-        Page::Platform => "CADalytix Setup",
-        Page::Welcome => "Welcome to the CADalytix Setup Wizard",
-        Page::License => "License Agreement",
-        Page::InstallType => "Installation Type",
-        Page::Destination => "Destination Folder",
-        Page::DataSource => "Data Source",
-        Page::Database => "Database Setup",
-        Page::Storage => "Database Storage",
-        Page::Retention => "Hot Retention",
-        Page::Archive => "Archive Policy",
-        Page::Consent => "Support Improvements",
-        Page::Mapping => "Schema Mapping",
-        Page::Ready => "Ready to Install",
-        Page::Installing => "Installing CADalytix",
-        Page::Complete => "Completed",
-    }
+fn page_title(page: Page, _mode: InstallMode) -> String {
+    i18n::t(match page {
+        Page::Platform => "page.platform",
+        Page::Welcome => "page.welcome",
+        Page::License => "page.license",
+        Page::InstallType => "page.installType",
+        Page::Destination => "page.destination",
+        Page::DataSource => "page.dataSource",
+        Page::Database => "page.database",
+        Page::Storage => "page.storage",
+        Page::Retention => "page.retention",
+        Page::Archive => "page.archive",
+        Page::Consent => "page.consent",
+        Page::Mapping => "page.mapping",
+        Page::Ready => "page.ready",
+        Page::Installing => "page.installing",
+        Page::Complete => "page.complete",
+    })
 }
 
-fn next_label(page: Page) -> &'static str {
-    match page {
-        Page::Ready => "Install",
-        Page::Complete => "Finish",
-        _ => "Next",
-    }
+fn next_label(page: Page) -> String {
+    i18n::t(match page {
+        Page::Ready => "button.install",
+        Page::Complete => "button.finish",
+        _ => "button.next",
+    })
+}
+
+/// "Error: <localized message>" for inline validation lines.
+fn error_line(key: &str) -> Line<'static> {
+    Line::from(ratatui::text::Span::styled(
+        i18n::tf("validation.errorPrefix", &[("message", &i18n::t(key))]),
+        theme::current().error(),
+    ))
 }
 
 fn can_go_back(page: Page) -> bool {
@@ -645,6 +909,9 @@ fn can_go_back(page: Page) -> bool {
 }
 
 fn can_go_next(state: &WizardState) -> bool {
+    if field_rules::page_error(state).is_some() {
+        return false;
+    }
     match state.page {
         Page::Platform => false,
         Page::Welcome => true,
@@ -659,7 +926,28 @@ fn can_go_next(state: &WizardState) -> bool {
         Page::Destination => {
             !state.destination_path.value.trim().is_empty() && state.destination_error.is_none()
         }
+        Page::DataSource => {
+            sources::name_error(state).is_none()
+                && match state.data_source_kind {
+                    DataSourceKind::File => {
+                        !state.file_source_folder.value.trim().is_empty()
+                            && file::parse_delimiter(&state.file_source_delimiter.value).is_ok()
+                    }
+                    DataSourceKind::Odbc => {
+                        !state.odbc_connection_string.value.trim().is_empty()
+                            && !state.source_object_name.value.trim().is_empty()
+                    }
+                    DataSourceKind::Oracle => {
+                        !state.oracle_address.value.trim().is_empty()
+                            && !state.source_object_name.value.trim().is_empty()
+                    }
+                    _ => true,
+                }
+        }
         Page::Database => {
+            if app_login_error(state).is_some() {
+                return false;
+            }
             if state.db_kind == DbKind::Local {
                 // Create NEW CADalytix Database
                 if state.new_db_location == NewDbLocation::SpecificPath
@@ -673,7 +961,7 @@ fn can_go_next(state: &WizardState) -> bool {
                     .trim()
                     .parse::<u32>()
                     .unwrap_or(0);
-                gb > 0
+                gb > 0 && new_db_options_error(state).is_none()
             } else {
                 // Use EXISTING Database
                 matches!(state.db_test_status, DbTestStatus::Success)
@@ -692,7 +980,7 @@ fn can_go_next(state: &WizardState) -> bool {
                     return false;
                 }
             }
-            true
+            storage_fs::blocked(state).is_none()
         }
         Page::Retention => {
             if state.hot_retention_choice != HotRetentionChoice::Custom {
@@ -707,6 +995,9 @@ fn can_go_next(state: &WizardState) -> bool {
             n > 0 && n <= 240
         }
         Page::Archive => {
+            if !state.licensed.allows(features::ARCHIVE) {
+                return true;
+            }
             if state.archive_destination.value.trim().is_empty() {
                 return false;
             }
@@ -731,27 +1022,119 @@ fn can_go_next(state: &WizardState) -> bool {
             is_valid_time_hhmm(state.archive_schedule_time_local.value.trim())
         }
         Page::Consent => true,
-        Page::Mapping => {
-            if state.mapping_scanning {
-                return false;
+        Page::Mapping => mapping_complete(state) && sources::unmapped(state).is_none(),
+        Page::Ready => disk_space::blocked(state).is_none(),
+        Page::Installing => false,
+        _ => true,
+    }
+}
+
+/// The Mapping page of the source being edited is done.
+fn mapping_complete(state: &WizardState) -> bool {
+    if state.mapping_scanning {
+        return false;
+    }
+    if state.mapping_scan_error.is_some() {
+        return false;
+    }
+    if state.source_fields.is_empty() {
+        return false;
+    }
+    // Required target fields must be mapped before proceeding.
+    for t in state.target_fields.iter().filter(|t| t.required) {
+        if !state.target_to_source.contains_key(&t.id) {
+            return false;
+        }
+    }
+    // Auto-map suggestions must be confirmed (C) or unassigned (U) first.
+    pending_suggestion_count(state) == 0
+}
+
+/// Settings summary on the Ready page (no passwords).
+fn ready_summary(state: &WizardState) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "Mode: {}",
+            match state.install_mode {
+                InstallMode::Windows => "Windows",
+                InstallMode::Docker => "Docker / Linux",
             }
-            if state.mapping_scan_error.is_some() {
-                return false;
+        ),
+        format!("Install path: {}", state.destination_path.value),
+        format!(
+            "Config DB engine: {}",
+            match state.db_engine {
+                DbEngine::SqlServer => "SQL Server",
+                DbEngine::Postgres => "PostgreSQL",
             }
-            if state.source_fields.is_empty() {
-                return false;
+        ),
+        format!(
+            "Hot retention: {} months",
+            match state.hot_retention_choice {
+                HotRetentionChoice::Months12 => 12,
+                HotRetentionChoice::Months18 => 18,
+                HotRetentionChoice::Custom => state
+                    .hot_retention_custom_months
+                    .value
+                    .trim()
+                    .parse::<u32>()
+                    .unwrap_or(18),
             }
-            // Required target fields must be mapped before proceeding.
-            for t in state.target_fields.iter().filter(|t| t.required) {
-                if !state.target_to_source.contains_key(&t.id) {
-                    return false;
-                }
+        ),
+        format!(
+            "Archive format: {}",
+            match state.archive_format {
+                ArchiveFormatChoice::ZipNdjson => "ZIP + NDJSON",
+                ArchiveFormatChoice::ZipCsv => "ZIP + CSV",
             }
-            true
-        }
-        Page::Installing => false,
-        _ => true,
+        ),
+        format!(
+            "Archive destination: {}",
+            if !state.licensed.allows(features::ARCHIVE) {
+                "(archive module not licensed)"
+            } else if state.archive_destination.value.trim().is_empty() {
+                "(not set)"
+            } else {
+                state.archive_destination.value.trim()
+            }
+        ),
+        format!(
+            "Archive cap (GB): {}",
+            state.archive_max_usage_gb.value.trim()
+        ),
+        format!(
+            "Archive schedule: day {} at {}",
+            state.archive_schedule_day_of_month.value.trim(),
+            state.archive_schedule_time_local.value.trim()
+        ),
+        format!(
+            "Consent to Sync: {}",
+            if state.consent_to_sync { "Yes" } else { "No" }
+        ),
+    ];
+    if sources::count(state) > 1 {
+        lines.insert(
+            2,
+            format!("Call data sources: {}", sources::names(state).join(", ")),
+        );
     }
+    // Folders right after the install path.
+    lines.splice(2..2, install_layout::summary(state));
+    lines.extend(disk_space::summary(state));
+    lines.extend(backfill::summary(state));
+    lines
+}
+
+/// Fixed-width text progress bar, e.g. `[######      ] 50%`.
+fn progress_bar(pct: i32) -> String {
+    let width = 30usize;
+    let filled = ((pct.clamp(0, 100) as usize) * width) / 100;
+    format!(
+        "[{}{}] {}%",
+        "#".repeat(filled),
+        " ".repeat(width.saturating_sub(filled)),
+        pct
+    )
 }
 
 fn is_valid_time_hhmm(s: &str) -> bool {
@@ -773,21 +1156,31 @@ fn page_field_count(state: &WizardState) -> usize {
             InstallationType::ImportConfig => 1,
             _ => 0,
         },
+        Page::Destination if state.layout_advanced => 1 + install_layout::ROLES.len(),
         Page::Destination => 1,
-        Page::DataSource => 6,
+        Page::DataSource => {
+            let name_field = usize::from(sources::count(state) > 1);
+            name_field
+                + match state.data_source_kind {
+                    DataSourceKind::Odbc | DataSourceKind::File => 2,
+                    DataSourceKind::Oracle => 5,
+                    _ => 6,
+                }
+        }
         Page::Database => {
+            // Each branch ends with the application login.
             if state.db_kind == DbKind::Local {
                 // Create NEW CADalytix Database branch
                 if state.new_db_location == NewDbLocation::SpecificPath {
-                    2 // path + max size
+                    4 // path + max size + collation
                 } else {
-                    1 // max size
+                    3 // max size + collation
                 }
             } else if state.db_use_conn_string {
-                1
+                2
             } else {
                 // Existing DB details mode requires host/server, port, db name, username, password, TLS.
-                6
+                7
             }
         }
         Page::Storage => {
@@ -810,7 +1203,7 @@ fn page_field_count(state: &WizardState) -> usize {
                 0
             }
         }
-        Page::Archive => 4,
+        Page::Archive if state.licensed.allows(features::ARCHIVE) => 4,
         _ => 0,
     }
 }
@@ -836,9 +1229,31 @@ fn focused_text_input_mut(state: &mut WizardState) -> Option<&mut TextInput> {
             if idx == 0 {
                 Some(&mut state.destination_path)
             } else {
-                None
+                install_layout::field_mut(state, idx)
             }
         }
+        // With several sources the name is the last field.
+        Page::DataSource if sources::count(state) > 1 && idx + 1 == page_field_count(state) => {
+            Some(&mut state.source_name)
+        }
+        Page::DataSource if state.data_source_kind == DataSourceKind::Odbc => match idx {
+            0 => Some(&mut state.odbc_connection_string),
+            1 => Some(&mut state.source_object_name),
+            _ => None,
+        },
+        Page::DataSource if state.data_source_kind == DataSourceKind::Oracle => match idx {
+            0 => Some(&mut state.oracle_driver),
+            1 => Some(&mut state.oracle_address),
+            2 => Some(&mut state.call_data_user),
+            3 => Some(&mut state.call_data_password),
+            4 => Some(&mut state.source_object_name),
+            _ => None,
+        },
+        Page::DataSource if state.data_source_kind == DataSourceKind::File => match idx {
+            0 => Some(&mut state.file_source_folder),
+            1 => Some(&mut state.file_source_delimiter),
+            _ => None,
+        },
         Page::DataSource => match idx {
             0 => Some(&mut state.call_data_database),
             1 => Some(&mut state.call_data_user),
@@ -848,6 +1263,8 @@ fn focused_text_input_mut(state: &mut WizardState) -> Option<&mut TextInput> {
             5 => Some(&mut state.source_object_name),
             _ => None,
         },
+        // The application login is the last field of every branch.
+        Page::Database if idx + 1 == page_field_count(state) => Some(&mut state.app_login),
         Page::Database => {
             if state.db_kind == DbKind::Local {
                 // Create NEW CADalytix Database branch
@@ -855,12 +1272,15 @@ fn focused_text_input_mut(state: &mut WizardState) -> Option<&mut TextInput> {
                     match idx {
                         0 => Some(&mut state.new_db_specific_path),
                         1 => Some(&mut state.new_db_max_size_gb),
+                        2 => Some(&mut state.new_db_collation),
                         _ => None,
                     }
-                } else if idx == 0 {
-                    Some(&mut state.new_db_max_size_gb)
                 } else {
-                    None
+                    match idx {
+                        0 => Some(&mut state.new_db_max_size_gb),
+                        1 => Some(&mut state.new_db_collation),
+                        _ => None,
+                    }
                 }
             } else if state.db_use_conn_string {
                 if idx == 0 {
@@ -940,7 +1360,7 @@ fn update_page_validation(state: &mut WizardState) {
             state.destination_error = if p.is_empty() {
                 Some("Destination folder is required.".to_string())
             } else {
-                None
+                install_layout::error(state)
             };
         }
         _ => {}
@@ -988,6 +1408,8 @@ fn disambiguate_source_columns(cols: &[DiscoveredColumnDto]) -> Vec<SourceField>
                 id: make_stable_source_id(&c.name, ordinal).unwrap_or_else(|| idx.to_string()),
                 raw_name: c.name.clone(),
                 display_name: display,
+                data_type: c.data_type.clone(),
+                sample_values: c.sample_values.clone(),
             }
         })
         .collect()
@@ -1047,25 +1469,64 @@ fn mapping_source_raw(state: &WizardState, source_id: &str) -> String {
         .unwrap_or_else(|| source_id.to_string())
 }
 
+/// Shown instead of username/password while Windows Authentication is selected.
+const WINDOWS_AUTH_PLACEHOLDER: &str = "(not used - Windows Authentication)";
+
+/// Port typed into a wizard field; empty or unparsable input falls back to `default`.
+fn port_or_default(value: &str, default: u16) -> u16 {
+    value.trim().parse().unwrap_or(default)
+}
+
 fn build_call_data_connection_string(state: &WizardState) -> String {
+    if state.data_source_kind == DataSourceKind::Odbc {
+        return state.odbc_connection_string.value.trim().to_string();
+    }
+    #[cfg(feature = "oracle")]
+    if state.data_source_kind == DataSourceKind::Oracle {
+        return crate::datasource::oracle::connection_string(
+            &state.oracle_driver.value,
+            &state.oracle_address.value,
+            &state.call_data_user.value,
+            &state.call_data_password.value,
+        )
+        .unwrap_or_default();
+    }
     let host = if state.call_data_host.value.trim().is_empty() {
         "localhost"
     } else {
         state.call_data_host.value.trim()
     };
-    let port = if state.call_data_port.value.trim().is_empty() {
-        "1433"
+    let cs = SqlServerConnString::new(host)
+        .port(Some(port_or_default(
+            &state.call_data_port.value,
+            SQL_SERVER_DEFAULT_PORT,
+        )))
+        .database(state.call_data_database.value.trim())
+        .credentials(
+            state.call_data_user.value.trim(),
+            &state.call_data_password.value,
+        );
+    if state.call_data_windows_auth {
+        cs.integrated_auth().build()
     } else {
-        state.call_data_port.value.trim()
-    };
-    let server = format!("{},{}", host, port);
-    let db = state.call_data_database.value.trim();
-    let user = state.call_data_user.value.trim();
-    let pass = &state.call_data_password.value;
-    format!(
-        "Server={};Database={};User Id={};Password={};TrustServerCertificate=true;Encrypt=false;",
-        server, db, user, pass
-    )
+        cs.build()
+    }
+}
+
+fn call_data_driver(state: &WizardState) -> String {
+    match state.data_source_kind {
+        DataSourceKind::Odbc => "odbc".to_string(),
+        DataSourceKind::Oracle => "oracle".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// The export folder source when the Data Source page is set to files.
+fn file_source_config(state: &WizardState) -> Option<FileSourceConfig> {
+    (state.data_source_kind == DataSourceKind::File).then(|| FileSourceConfig {
+        folder: state.file_source_folder.value.trim().to_string(),
+        delimiter: state.file_source_delimiter.value.trim().to_string(),
+    })
 }
 
 fn filtered_source_ids(state: &WizardState) -> Vec<String> {
@@ -1088,11 +1549,83 @@ fn filtered_target_ids(state: &WizardState) -> Vec<String> {
         .collect()
 }
 
+/// Run an archive catch-up against the configured DB; progress arrives as `UiMsg::ArchiveProgress`.
+fn start_archive_run(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    if state.archive_run_active || !state.licensed.allows(features::ARCHIVE) {
+        return;
+    }
+    state.archive_run_active = true;
+    state.archive_run_result = None;
+    state.archive_run_progress = None;
+
+    let conn_str = build_install_request(state).config_db_connection_string;
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let correlation_id = Uuid::new_v4().to_string();
+        let tx_progress = tx.clone();
+        let progress_emitter: ProgressEmitter = Arc::new(move |p: ProgressPayload| {
+            let _ = tx_progress.send(UiMsg::ArchiveProgress(p));
+        });
+        let args = crate::archiver::ArchiveRunOnceArgs::default();
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| anyhow::anyhow!("Internal error starting archive run: {}", e))
+            .and_then(|rt| {
+                rt.block_on(crate::archiver::archive_catch_up(
+                    conn_str,
+                    &args,
+                    &correlation_id,
+                    Some(progress_emitter),
+                ))
+            });
+        let _ = tx.send(match result {
+            Ok(months) => UiMsg::ArchiveFinished {
+                success: true,
+                message: format!("Archive run complete ({} month(s) archived).", months),
+            },
+            Err(e) => UiMsg::ArchiveFinished {
+                success: false,
+                message: e.to_string(),
+            },
+        });
+    });
+}
+
+/// Identifies what a scan reads: a change means the source fields must be scanned again.
+fn mapping_scan_key(state: &WizardState) -> String {
+    match file_source_config(state) {
+        Some(fs) => format!(
+            "file|{}|{}|{}",
+            fs.folder, fs.delimiter, state.mapping_demo_mode
+        ),
+        None => format!(
+            "{}|{}|{}|{}",
+            call_data_driver(state),
+            build_call_data_connection_string(state),
+            state.source_object_name.value.trim(),
+            state.mapping_demo_mode
+        ),
+    }
+}
+
+/// Land on the Mapping page (from Consent or back from Ready). Fields and mappings are kept
+/// unless the source changed since the last scan.
+fn enter_mapping_page(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    state.focus = FocusTarget::Mapping(MappingFocus::SourceList);
+    let rescan = state.source_fields.is_empty()
+        || state.mapping_scan_key.as_deref() != Some(mapping_scan_key(state).as_str());
+    if rescan {
+        start_mapping_scan(state, tx);
+    }
+}
+
 fn start_mapping_scan(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
     if state.mapping_scanning {
         return;
     }
     state.mapping_scanning = true;
+    state.mapping_scan_key = Some(mapping_scan_key(state));
     state.mapping_scan_error = None;
     state.source_fields = Vec::new();
     state.selected_source_id = None;
@@ -1107,6 +1640,8 @@ fn start_mapping_scan(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
         date_to_iso: None,
         sample_limit: 10,
         demo_mode: state.mapping_demo_mode,
+        file_source: file_source_config(state),
+        call_data_driver: call_data_driver(state),
     };
 
     let tx = tx.clone();
@@ -1201,6 +1736,9 @@ fn remove_all_targets_from_source(state: &mut WizardState, source_id: &str) {
 /// If `add` is true and override mode is enabled, append the target to the source's target list.
 /// Otherwise, replace the source's mapping(s) with the single target.
 fn apply_mapping(state: &mut WizardState, source_id: &str, target_id: &str, add: bool) {
+    // A mapping made by hand is confirmed by definition.
+    state.mapping_suggested.remove(target_id);
+
     // Target exclusivity: if the target is mapped elsewhere, remove it first.
     remove_target_from_old_source(state, target_id);
 
@@ -1227,6 +1765,93 @@ fn apply_mapping(state: &mut WizardState, source_id: &str, target_id: &str, add:
     state.selected_target_id = Some(target_id.to_string());
 }
 
+/// Suggestion for `target_id` that is still mapped as suggested and not confirmed.
+fn pending_suggestion<'a>(
+    state: &'a WizardState,
+    target_id: &str,
+) -> Option<&'a MappingSuggestion> {
+    state
+        .mapping_suggested
+        .get(target_id)
+        .filter(|s| state.target_to_source.get(target_id) == Some(&s.source_id))
+}
+
+fn pending_suggestion_count(state: &WizardState) -> usize {
+    state
+        .target_fields
+        .iter()
+        .filter(|t| pending_suggestion(state, &t.id).is_some())
+        .count()
+}
+
+/// Map the unmapped fields from `suggest` (A on the Mapping page); results stay suggested until
+/// confirmed with C.
+fn auto_map(state: &mut WizardState) {
+    let sources: Vec<SuggestSource> = state
+        .source_fields
+        .iter()
+        .map(|s| SuggestSource {
+            id: &s.id,
+            name: &s.raw_name,
+            data_type: &s.data_type,
+        })
+        .collect();
+    let targets: Vec<MappingTargetField> = state
+        .target_fields
+        .iter()
+        .map(|t| MappingTargetField {
+            id: t.id.clone(),
+            name: t.name.clone(),
+            required: t.required,
+        })
+        .collect();
+    let suggestions = suggest::suggest(&sources, &targets, &state.target_to_source);
+    info!(
+        "[PHASE: mapping] [STEP: auto_map] Suggestions computed (sources={}, targets={}, suggestions={})",
+        sources.len(),
+        targets.len(),
+        suggestions.len()
+    );
+
+    let selection = (
+        state.selected_source_id.clone(),
+        state.selected_target_id.clone(),
+    );
+    let body = if suggestions.is_empty() {
+        "No confident matches were found for the unmapped fields.".to_string()
+    } else {
+        let count = suggestions.len();
+        for s in suggestions {
+            apply_mapping(state, &s.source_id, &s.target_id, false);
+            state.mapping_suggested.insert(s.target_id.clone(), s);
+        }
+        format!(
+            "{} mapping(s) suggested; they are marked '?' with their confidence in the target list.\n\nPress C to confirm the selected target (or all when none is selected), U to reject one.",
+            count
+        )
+    };
+    (state.selected_source_id, state.selected_target_id) = selection;
+    state.modal = Some(Modal::Message {
+        title: "Auto-map".to_string(),
+        body,
+        return_to: None,
+    });
+}
+
+/// Confirm the selected target's suggestion, or every pending one when it has none (C).
+fn confirm_suggestions(state: &mut WizardState) {
+    let selected = state
+        .selected_target_id
+        .clone()
+        .filter(|t| pending_suggestion(state, t).is_some());
+    match selected {
+        Some(target_id) => {
+            state.mapping_suggested.remove(&target_id);
+        }
+        None => state.mapping_suggested.clear(),
+    }
+}
+
 fn attempt_map(state: &mut WizardState, source_id: &str, target_id: &str) {
     let target_already_mapped_to = state.target_to_source.get(target_id).cloned();
     let source_already_mapped_to = state
@@ -1367,6 +1992,28 @@ fn attempt_map(state: &mut WizardState, source_id: &str, target_id: &str) {
     apply_mapping(state, source_id, target_id, state.mapping_override);
 }
 
+/// Platform page: use the selected mode and its default DB engine, then go to Welcome.
+fn confirm_platform(state: &mut WizardState) {
+    state.install_mode = state.platform_selected;
+    // Default DB engine per mode.
+    state.db_engine = if state.install_mode == InstallMode::Windows {
+        DbEngine::SqlServer
+    } else {
+        DbEngine::Postgres
+    };
+    state.page = Page::Welcome;
+}
+
+fn set_existing_hosted_where(state: &mut WizardState, hosted: ExistingHostedWhere) {
+    state.existing_hosted_where = hosted;
+    // Best-effort inference: if the hosting implies an engine, update defaults.
+    state.db_engine = match hosted {
+        ExistingHostedWhere::AzureSqlMi => DbEngine::SqlServer,
+        ExistingHostedWhere::Neon | ExistingHostedWhere::Supabase => DbEngine::Postgres,
+        _ => state.db_engine,
+    };
+}
+
 fn can_cancel(page: Page) -> bool {
     !matches!(page, Page::Complete)
 }
@@ -1411,20 +2058,101 @@ fn prev_page(page: Page) -> Page {
     }
 }
 
-pub fn run(secrets: Arc<SecretProtector>) -> Result<()> {
+/// Runs the wizard; returns the process exit code (that of the last install attempt).
+pub fn run(secrets: Arc<SecretProtector>) -> Result<i32> {
     info!("[PHASE: tui] [STEP: start] Starting TUI wizard");
 
-    let mut terminal = setup_terminal()?;
-    let result = run_loop(&mut terminal, secrets);
-    restore_terminal(&mut terminal)?;
+    crate::installation::signals::listen();
+    let mut terminal = TerminalGuard::new()?;
+    run_loop(&mut terminal.0, secrets)
+}
+
+/// The wizard's terminal in raw mode on the alternate screen. Dropping the guard restores it, so
+/// the shell is usable again after a normal exit, an error return or an unwinding panic (the
+/// panic hook in `utils::crash` covers panics on other threads).
+struct TerminalGuard(Terminal<CrosstermBackend<Stdout>>);
+
+impl TerminalGuard {
+    fn new() -> Result<Self> {
+        setup_terminal().map(Self).inspect_err(|_| {
+            // Setup can fail after raw mode is already on.
+            crate::utils::crash::restore_terminal();
+        })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if let Err(e) = restore_terminal(&mut self.0) {
+            warn!(
+                "[PHASE: tui] [STEP: restore_terminal] Failed to restore the terminal: {:?}",
+                e
+            );
+        }
+    }
+}
 
-    result
+/// Ctrl+C arrives as a key in raw mode (no SIGINT); it stops the wizard like SIGINT would.
+fn is_interrupt(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'))
 }
 
 fn new_real_wizard_state() -> WizardState {
     // Real interactive run: DO NOT seed any sample/demo values here.
     // Only `smoke(...)` is allowed to inject sample state.
-    WizardState::new()
+    let mut state = WizardState::new();
+    state.keymap = Keymap::load();
+    apply_env_defaults(&mut state, &WizardDefaults::from_env());
+    state
+}
+
+/// Pre-seeded defaults from `CADALYTIX_INSTALLER_*` (see `utils::env_defaults`); every field stays
+/// editable.
+fn apply_env_defaults(state: &mut WizardState, defaults: &WizardDefaults) {
+    // A remote install (`--remote=`) lands on a Linux server.
+    if crate::installation::remote::selected().is_some() {
+        state.destination_path.set("/opt/cadalytix");
+    }
+    if defaults.instance_name.is_some() && defaults.destination_path.is_none() {
+        let qualified = crate::utils::instance::qualify(&state.destination_path.value, "-");
+        state.destination_path.set(qualified);
+    }
+    let text_fields = [
+        (&defaults.destination_path, &mut state.destination_path),
+        (&defaults.db_host, &mut state.db_host),
+        (&defaults.db_name, &mut state.db_database),
+        (&defaults.db_user, &mut state.db_user),
+        (&defaults.source_host, &mut state.call_data_host),
+        (
+            &defaults.archive_destination,
+            &mut state.archive_destination,
+        ),
+    ];
+    for (value, field) in text_fields {
+        if let Some(v) = value {
+            field.set(v);
+        }
+    }
+    if let Some(port) = defaults.db_port {
+        state.db_port.set(port.to_string());
+    }
+    if let Some(port) = defaults.source_port {
+        state.call_data_port.set(port.to_string());
+    }
+    if let Some(gb) = defaults.archive_max_usage_gb {
+        state.archive_max_usage_gb.set(gb.to_string());
+    }
+    match defaults.retention_months {
+        Some(12) => state.hot_retention_choice = HotRetentionChoice::Months12,
+        Some(18) => state.hot_retention_choice = HotRetentionChoice::Months18,
+        Some(months) => {
+            state.hot_retention_choice = HotRetentionChoice::Custom;
+            state.hot_retention_custom_months.set(months.to_string());
+        }
+        None => {}
+    }
 }
 
 fn new_smoke_wizard_state(target: &str) -> WizardState {
@@ -1512,16 +2240,22 @@ fn new_smoke_wizard_state(target: &str) -> WizardState {
                     id: "City__0".to_string(),
                     raw_name: "City".to_string(),
                     display_name: "City (1)".to_string(),
+                    data_type: "nvarchar".to_string(),
+                    sample_values: Vec::new(),
                 },
                 SourceField {
                     id: "City__1".to_string(),
                     raw_name: "City".to_string(),
                     display_name: "City (2)".to_string(),
+                    data_type: "nvarchar".to_string(),
+                    sample_values: Vec::new(),
                 },
                 SourceField {
                     id: "IncidentNumber__0".to_string(),
                     raw_name: "IncidentNumber".to_string(),
                     display_name: "IncidentNumber".to_string(),
+                    data_type: "nvarchar".to_string(),
+                    sample_values: Vec::new(),
                 },
             ];
             state.source_to_targets = HashMap::from([
@@ -1540,6 +2274,8 @@ fn new_smoke_wizard_state(target: &str) -> WizardState {
             ]);
             state.selected_source_id = Some("City__0".to_string());
             state.selected_target_id = Some("City".to_string());
+            // As if the scan had just completed, so the real entry path keeps these fields.
+            state.mapping_scan_key = Some(mapping_scan_key(&state));
             state.focus = FocusTarget::Mapping(MappingFocus::SourceList);
         }
         "ready" => {
@@ -1571,11 +2307,15 @@ fn new_smoke_wizard_state(target: &str) -> WizardState {
                     id: "CallReceivedAt__0".to_string(),
                     raw_name: "CallReceivedAt".to_string(),
                     display_name: "CallReceivedAt".to_string(),
+                    data_type: "datetime".to_string(),
+                    sample_values: Vec::new(),
                 },
                 SourceField {
                     id: "IncidentNumber__0".to_string(),
                     raw_name: "IncidentNumber".to_string(),
                     display_name: "IncidentNumber".to_string(),
+                    data_type: "nvarchar".to_string(),
+                    sample_values: Vec::new(),
                 },
             ];
             state.target_fields = vec![
@@ -1642,8 +2382,10 @@ pub fn smoke(_secrets: Arc<SecretProtector>, target: &str) -> Result<()> {
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
+    crate::utils::crash::set_raw_terminal(true);
     let mut stdout = io::stdout();
     stdout.execute(EnterAlternateScreen)?;
+    stdout.execute(EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -1651,22 +2393,30 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
 
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
     disable_raw_mode()?;
+    terminal.backend_mut().execute(DisableMouseCapture)?;
     terminal.backend_mut().execute(LeaveAlternateScreen)?;
     terminal.show_cursor()?;
+    crate::utils::crash::set_raw_terminal(false);
     Ok(())
 }
 
 fn run_loop(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     secrets: Arc<SecretProtector>,
-) -> Result<()> {
+) -> Result<i32> {
     let tick_rate = Duration::from_millis(100);
     let mut last_tick = Instant::now();
     let mut state = new_real_wizard_state();
+    state.licensed = load_licensed_features(&secrets);
     let (tx, rx) = mpsc::channel::<UiMsg>();
+    draft::offer(&mut state);
+    let mut last_page = state.page;
 
     while !state.quit {
         drain_messages(&mut state, &rx);
+        if let Some(viewer) = state.log_viewer.as_mut() {
+            viewer.poll();
+        }
         terminal.draw(|f| draw(f.size(), f, &state))?;
 
         let timeout = tick_rate
@@ -1675,18 +2425,49 @@ fn run_loop(
 
         if event::poll(timeout)? {
             match event::read()? {
+                Event::Key(key) if is_interrupt(&key) => {
+                    // During an install: roll back the current step first (see
+                    // `installation::signals`); the install thread then exits.
+                    if crate::installation::cancel::request_interrupt("Ctrl+C") {
+                        info!("[PHASE: tui] [STEP: interrupt] Ctrl+C pressed; interrupting the install");
+                    } else {
+                        info!("[PHASE: tui] [STEP: interrupt] Ctrl+C pressed; exiting the wizard");
+                        return Ok(INTERRUPTED_EXIT_CODE);
+                    }
+                }
                 Event::Key(key) => handle_key(&mut state, key.code, &tx, &secrets),
-                Event::Resize(_, _) => {}
+                Event::Mouse(m) => mouse::handle_mouse(&mut state, m, &tx, &secrets),
+                // Redraw from scratch at the new size (no stale cells from the old layout).
+                Event::Resize(_, _) => terminal.autoresize()?,
                 _ => {}
             }
         }
 
+        #[cfg(target_os = "linux")]
+        elevate::authorize(terminal, &mut state, &tx, &secrets)?;
+
+        if state.page != last_page {
+            invalidation::page_changed(&mut state, last_page, &tx);
+            last_page = state.page;
+            draft::page_changed(&state, &secrets);
+        }
+        storage_fs::refresh(&mut state, &tx);
+
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
         }
     }
 
-    Ok(())
+    Ok(state.install_exit_code)
+}
+
+/// Feature flags of the locally stored license (none when unreadable).
+fn load_licensed_features(secrets: &Arc<SecretProtector>) -> LicensedFeatures {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map(|rt| rt.block_on(crate::api::license::local_licensed_features(secrets)))
+        .unwrap_or_default()
 }
 
 fn focused_button(state: &WizardState) -> ButtonFocus {
@@ -1702,84 +2483,191 @@ fn set_focused_button(state: &mut WizardState, b: ButtonFocus) {
 
 fn drain_messages(state: &mut WizardState, rx: &mpsc::Receiver<UiMsg>) {
     while let Ok(msg) = rx.try_recv() {
-        match msg {
-            UiMsg::DbTestComplete { success, message } => {
-                state.db_test_status = if success {
-                    DbTestStatus::Success
+        apply_message(state, msg);
+    }
+}
+
+/// Fold one background-task message into the wizard state.
+fn apply_message(state: &mut WizardState, msg: UiMsg) {
+    match msg {
+        UiMsg::DbTestComplete { success, message } => {
+            state.db_test_status = if success {
+                DbTestStatus::Success
+            } else {
+                DbTestStatus::Fail
+            };
+            state.db_test_message = message;
+        }
+        UiMsg::MappingScanComplete {
+            success,
+            message,
+            columns,
+        } => {
+            state.mapping_scanning = false;
+            if success {
+                if columns.is_empty() {
+                    state.mapping_scan_error =
+                        Some("No headers could be detected for the selected source.".to_string());
+                    state.source_fields = Vec::new();
                 } else {
-                    DbTestStatus::Fail
-                };
-                state.db_test_message = message;
-            }
-            UiMsg::MappingScanComplete {
-                success,
-                message,
-                columns,
-            } => {
-                state.mapping_scanning = false;
-                if success {
-                    if columns.is_empty() {
-                        state.mapping_scan_error = Some(
-                            "No headers could be detected for the selected source.".to_string(),
-                        );
-                        state.source_fields = Vec::new();
-                    } else {
-                        state.mapping_scan_error = None;
-                        state.source_fields = disambiguate_source_columns(&columns);
-                        state.source_list_index = 0;
-                        state.target_list_index = 0;
-                        state.selected_source_id =
-                            state.source_fields.first().map(|s| s.id.clone());
-                        state.selected_target_id = None;
+                    state.mapping_scan_error = None;
+                    state.source_fields = disambiguate_source_columns(&columns);
+                    // Drop mappings from fields the new scan no longer has.
+                    let ids: std::collections::HashSet<String> =
+                        state.source_fields.iter().map(|s| s.id.clone()).collect();
+                    state.source_to_targets.retain(|s, _| ids.contains(s));
+                    state.target_to_source.retain(|_, s| ids.contains(s));
+                    for list in state.mapping_transforms.values_mut() {
+                        list.retain(|t| match t {
+                            FieldTransform::Concat { source_id, .. } => ids.contains(source_id),
+                            _ => true,
+                        });
                     }
-                } else {
-                    state.mapping_scan_error = Some(message);
-                    state.source_fields = Vec::new();
+                    state.mapping_transforms.retain(|_, list| !list.is_empty());
+                    state.source_list_index = 0;
+                    state.target_list_index = 0;
+                    state.selected_source_id = state.source_fields.first().map(|s| s.id.clone());
+                    state.selected_target_id = None;
                 }
+            } else {
+                state.mapping_scan_error = Some(message);
+                state.source_fields = Vec::new();
             }
-            UiMsg::InstallProgress(p) => {
-                if state.page == Page::Installing {
-                    if state.install_correlation_id.is_none() {
-                        state.install_correlation_id = Some(p.correlation_id.clone());
-                    }
-                    if !p.message.trim().is_empty() {
-                        state.install_detail.push(p.message.clone());
-                        if state.install_detail.len() > 20 {
-                            let start = state.install_detail.len().saturating_sub(20);
-                            state.install_detail = state.install_detail[start..].to_vec();
-                        }
+        }
+        UiMsg::InstallProgress(p) => {
+            if state.page == Page::Installing {
+                if state.install_correlation_id.is_none() {
+                    state.install_correlation_id = Some(p.correlation_id.clone());
+                }
+                if !p.message.trim().is_empty() {
+                    state.install_detail.push(p.message.clone());
+                    if state.install_detail.len() > 20 {
+                        let start = state.install_detail.len().saturating_sub(20);
+                        state.install_detail = state.install_detail[start..].to_vec();
                     }
-                    state.install_progress = Some(p);
                 }
-            }
-            UiMsg::InstallFinished {
-                success,
-                message,
-                correlation_id,
-                artifacts,
-            } => {
-                state.install_correlation_id = Some(correlation_id);
-                state.install_artifacts = artifacts;
-                if success {
-                    state.page = Page::Complete;
-                } else {
-                    state.modal = Some(Modal::Message {
-                        title: "Installation failed".to_string(),
-                        body: message,
-                        return_to: Some(Page::Ready),
-                    });
+                match p.step.as_str() {
+                    "paused" => state.install_paused = true,
+                    "resumed" => state.install_paused = false,
+                    _ => {}
                 }
+                state.install_progress = Some(p);
+            }
+        }
+        UiMsg::InstallFinished {
+            success,
+            message,
+            correlation_id,
+            artifacts,
+            exit_code,
+        } => {
+            state.install_correlation_id = Some(correlation_id);
+            state.install_exit_code = exit_code;
+            let plan_path = artifacts.as_ref().and_then(|a| a.plan_path.clone());
+            state.install_artifacts = artifacts;
+            if let Some(plan_path) = plan_path.filter(|_| success) {
+                state.modal = Some(Modal::Message {
+                    title: "Dry run complete".to_string(),
+                    body: format!("{}\n\nInstall plan: {}", message, plan_path),
+                    return_to: Some(Page::Ready),
+                });
+            } else if success {
+                state.page = Page::Complete;
+            } else {
+                state.modal = Some(Modal::Message {
+                    title: "Installation failed".to_string(),
+                    body: message,
+                    return_to: Some(Page::Ready),
+                });
             }
         }
+        UiMsg::ArchiveProgress(p) => {
+            state.archive_run_progress = Some(p);
+        }
+        UiMsg::ArchiveFinished { success, message } => {
+            state.archive_run_active = false;
+            state.archive_run_result = Some(if success {
+                message
+            } else {
+                format!("Archive run failed: {}", message)
+            });
+        }
+        UiMsg::SourceObjectsLoaded(result) => source_objects::loaded(state, result),
+        UiMsg::BackfillEstimated { key, result } => backfill::finished(state, key, result),
+        UiMsg::LayoutSpaceChecked(checks) => install_layout::space_checked(state, checks),
+        UiMsg::DiskSpaceProjected { key, projection } => {
+            disk_space::projected(state, key, projection)
+        }
+        UiMsg::StorageFilesystemInspected { key, inspected } => {
+            storage_fs::inspected(state, key, inspected)
+        }
     }
 }
 
+/// Key press from the terminal: translated through the keymap, then dispatched.
 fn handle_key(
     state: &mut WizardState,
     code: KeyCode,
     tx: &mpsc::Sender<UiMsg>,
     secrets: &Arc<SecretProtector>,
 ) {
+    if let Some(code) = resolve_key(state, code) {
+        dispatch_key(state, code, tx, secrets);
+    }
+}
+
+fn resolve_key(state: &mut WizardState, code: KeyCode) -> Option<KeyCode> {
+    let overlay_open = state.modal.is_some() || state.log_viewer.is_some();
+    // Typing and cursor keys in a text field are never remapped.
+    let typing = match state.log_viewer.as_ref() {
+        Some(viewer) => viewer.is_typing(),
+        None if matches!(
+            state.modal,
+            Some(Modal::Transforms {
+                prompt: Some(_),
+                ..
+            }) | Some(Modal::MappingCsv { .. })
+                | Some(Modal::SourceObjects { .. })
+                | Some(Modal::Profiles { name: Some(_), .. })
+        ) =>
+        {
+            true
+        }
+        None => !overlay_open && focused_text_input_mut(state).is_some(),
+    };
+    if typing
+        && matches!(
+            code,
+            KeyCode::Char(_)
+                | KeyCode::Backspace
+                | KeyCode::Delete
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Home
+                | KeyCode::End
+        )
+    {
+        return Some(code);
+    }
+    let page = (!overlay_open).then_some(state.page);
+    state.keymap.resolve(page, code)
+}
+
+/// Act on a canonical key (see `keymap`); mouse actions enter here directly.
+fn dispatch_key(
+    state: &mut WizardState,
+    code: KeyCode,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    // Log viewer takes every key until closed.
+    if let Some(viewer) = state.log_viewer.as_mut() {
+        if !viewer.handle_key(code) {
+            state.log_viewer = None;
+        }
+        return;
+    }
+
     // Modal handling
     if let Some(modal) = state.modal.clone() {
         match modal {
@@ -1813,6 +2701,14 @@ fn handle_key(
                 }
                 _ => {}
             },
+            Modal::Help => {
+                if matches!(
+                    code,
+                    KeyCode::Esc | KeyCode::Enter | KeyCode::F(1) | KeyCode::Char('q')
+                ) {
+                    state.modal = None;
+                }
+            }
             Modal::Message {
                 title: _,
                 body: _,
@@ -1904,6 +2800,13 @@ fn handle_key(
                     pending,
                 });
             }
+            Modal::Transforms { .. } => transforms::handle_key(state, code),
+            Modal::MappingCsv { .. } => mapping_csv::handle_key(state, code),
+            Modal::SourceObjects { .. } => source_objects::handle_key(state, code),
+            Modal::Profiles { .. } => profiles::handle_key(state, code, secrets),
+            Modal::ResumeDraft { .. } => draft::handle_key(state, code, tx, secrets),
+            #[cfg(target_os = "linux")]
+            Modal::Elevate { .. } => elevate::handle_key(state, code),
             Modal::BrowseFolder {
                 mut current,
                 mut entries,
@@ -1933,9 +2836,12 @@ fn handle_key(
                         }
                     }
                     KeyCode::Char('s') | KeyCode::Char('S') => {
-                        state
-                            .destination_path
-                            .set(current.to_string_lossy().to_string());
+                        let picked = current.to_string_lossy().to_string();
+                        if state.page == Page::DataSource {
+                            state.file_source_folder.set(picked);
+                        } else {
+                            state.destination_path.set(picked);
+                        }
                         state.modal = None;
                         update_page_validation(state);
                         return;
@@ -1959,6 +2865,10 @@ fn handle_key(
     }
 
     // Global keys
+    if code == KeyCode::F(1) {
+        state.modal = Some(Modal::Help);
+        return;
+    }
     if matches!(code, KeyCode::Esc) && can_cancel(state.page) {
         state.modal = Some(Modal::ConfirmCancel);
         set_focused_button(state, ButtonFocus::Next); // "No"
@@ -1981,16 +2891,7 @@ fn handle_key(
                     InstallMode::Docker => InstallMode::Windows,
                 };
             }
-            KeyCode::Enter => {
-                state.install_mode = state.platform_selected;
-                // Default DB engine per mode.
-                state.db_engine = if state.install_mode == InstallMode::Windows {
-                    DbEngine::SqlServer
-                } else {
-                    DbEngine::Postgres
-                };
-                state.page = Page::Welcome;
-            }
+            KeyCode::Enter => confirm_platform(state),
             _ => {}
         },
         Page::License => match code {
@@ -2031,6 +2932,38 @@ fn handle_key(
             KeyCode::Char('u') | KeyCode::Char('U') if state.page == Page::Mapping => {
                 unassign_selected(state);
             }
+            KeyCode::Char('t') | KeyCode::Char('T') if state.page == Page::Mapping => {
+                transforms::open(state);
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') if state.page == Page::Mapping => {
+                auto_map(state);
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') if state.page == Page::Mapping => {
+                confirm_suggestions(state);
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') if state.page == Page::Mapping => {
+                mapping_csv::open(state, false);
+            }
+            KeyCode::Char('i') | KeyCode::Char('I') if state.page == Page::Mapping => {
+                mapping_csv::open(state, true);
+            }
+            KeyCode::Char('s') | KeyCode::Char('S')
+                if matches!(state.page, Page::DataSource | Page::Mapping) =>
+            {
+                sources::next(state, tx);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') if state.page == Page::DataSource => {
+                sources::add(state);
+            }
+            KeyCode::Char('l') | KeyCode::Char('L') if state.page == Page::Destination => {
+                install_layout::toggle(state, tx);
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') if state.page == Page::Destination => {
+                install_layout::check_space(state, tx);
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') if state.page == Page::DataSource => {
+                sources::remove(state);
+            }
             KeyCode::Up | KeyCode::Down if state.page == Page::Mapping => {
                 if matches!(state.focus, FocusTarget::Mapping(MappingFocus::SourceList)) {
                     let ids = filtered_source_ids(state);
@@ -2062,10 +2995,26 @@ fn handle_key(
                 }
             }
             KeyCode::Up | KeyCode::Down if state.page == Page::DataSource => {
-                state.data_source_kind = match state.data_source_kind {
-                    DataSourceKind::Local => DataSourceKind::Remote,
-                    DataSourceKind::Remote => DataSourceKind::Local,
+                let mut order = vec![DataSourceKind::Local, DataSourceKind::Remote];
+                if state.licensed.allows(features::MULTI_DATASOURCE) {
+                    order.push(DataSourceKind::Odbc);
+                    if cfg!(feature = "oracle") {
+                        order.push(DataSourceKind::Oracle);
+                    }
+                    order.push(DataSourceKind::File);
+                }
+                let i = order
+                    .iter()
+                    .position(|k| *k == state.data_source_kind)
+                    .unwrap_or(0);
+                let step = if matches!(code, KeyCode::Up) {
+                    order.len() - 1
+                } else {
+                    1
                 };
+                state.data_source_kind = order[(i + step) % order.len()];
+                // The File branch has its own fields.
+                state.focus = FocusTarget::Field(0);
             }
             KeyCode::Up | KeyCode::Down if state.page == Page::Database => {
                 state.db_kind = match state.db_kind {
@@ -2119,19 +3068,34 @@ fn handle_key(
                     }
 
                     if !matches!(state.focus, FocusTarget::Field(_)) {
-                        state.existing_hosted_where = state.existing_hosted_where.next();
-                        // Best-effort inference: if the hosting implies an engine, update defaults.
-                        state.db_engine = match state.existing_hosted_where {
-                            ExistingHostedWhere::AzureSqlMi => DbEngine::SqlServer,
-                            ExistingHostedWhere::Neon | ExistingHostedWhere::Supabase => {
-                                DbEngine::Postgres
-                            }
-                            _ => state.db_engine,
-                        };
+                        set_existing_hosted_where(state, state.existing_hosted_where.next());
                         update_page_validation(state);
                     }
                 }
             }
+            KeyCode::Char('w') | KeyCode::Char('W')
+                if state.page == Page::DataSource
+                    && matches!(
+                        state.data_source_kind,
+                        DataSourceKind::Local | DataSourceKind::Remote
+                    ) =>
+            {
+                state.call_data_windows_auth = !state.call_data_windows_auth;
+                update_page_validation(state);
+            }
+            KeyCode::Char('w') | KeyCode::Char('W')
+                if state.page == Page::Database
+                    && state.db_kind == DbKind::Remote
+                    && !state.db_use_conn_string =>
+            {
+                // Windows Authentication applies to SQL Server only.
+                state.db_windows_auth = !state.db_windows_auth;
+                if state.db_windows_auth {
+                    state.db_engine = DbEngine::SqlServer;
+                }
+                state.db_test_status = DbTestStatus::Idle;
+                state.db_test_message.clear();
+            }
             KeyCode::Char(' ') if state.page == Page::Database => {
                 // Existing DB only: toggle connection mode (connection string vs details)
                 if state.db_kind == DbKind::Remote {
@@ -2146,194 +3110,19 @@ fn handle_key(
                 }
             }
             KeyCode::Char('t') | KeyCode::Char('T') if state.page == Page::Database => {
-                // Existing DB only: Start DB connection test in a background task.
-                if state.db_kind == DbKind::Local {
-                    return;
-                }
-
-                // No connection attempt until required fields exist.
-                if state.db_use_conn_string {
-                    if state.db_conn_string.value.trim().is_empty() {
-                        state.db_test_status = DbTestStatus::Fail;
-                        state.db_test_message =
-                            "Missing required inputs: Connection string.".to_string();
-                        return;
-                    }
-                } else {
-                    let mut missing = Vec::new();
-                    if state.db_host.value.trim().is_empty() {
-                        missing.push("Host");
-                    }
-                    if state.db_port.value.trim().is_empty() {
-                        missing.push("Port");
-                    }
-                    if state.db_database.value.trim().is_empty() {
-                        missing.push("Database");
-                    }
-                    if state.db_user.value.trim().is_empty() {
-                        missing.push("Username");
-                    }
-                    if state.db_password.value.trim().is_empty() {
-                        missing.push("Password");
-                    }
-                    if !missing.is_empty() {
-                        state.db_test_status = DbTestStatus::Fail;
-                        state.db_test_message =
-                            format!("Missing required inputs: {}.", missing.join(", "));
-                        return;
-                    }
-                }
-
-                state.db_test_status = DbTestStatus::Testing;
-                state.db_test_message = "Testing connection...".to_string();
-
-                let guess_engine_from_conn_str = |conn_str: &str| -> DbEngine {
-                    let s = conn_str.trim().to_ascii_lowercase();
-                    if s.starts_with("postgres://")
-                        || s.starts_with("postgresql://")
-                        || s.contains("host=")
-                    {
-                        DbEngine::Postgres
-                    } else {
-                        DbEngine::SqlServer
-                    }
-                };
-
-                let conn_str = if state.db_use_conn_string
-                    && !state.db_conn_string.value.trim().is_empty()
-                {
-                    state.db_conn_string.value.trim().to_string()
-                } else {
-                    // Build a structured connection string from fields (details mode).
-                    let engine = match state.existing_hosted_where {
-                        ExistingHostedWhere::AzureSqlMi => DbEngine::SqlServer,
-                        ExistingHostedWhere::Neon | ExistingHostedWhere::Supabase => {
-                            DbEngine::Postgres
-                        }
-                        _ => {
-                            // Heuristic fallback: common port values.
-                            if state.db_port.value.trim() == "1433" {
-                                DbEngine::SqlServer
-                            } else {
-                                DbEngine::Postgres
-                            }
-                        }
-                    };
-                    state.db_engine = engine;
-
-                    match engine {
-                        DbEngine::Postgres => {
-                            let port = if state.db_port.value.trim().is_empty() {
-                                "5432"
-                            } else {
-                                state.db_port.value.trim()
-                            };
-                            let ssl = state.db_ssl_mode.trim();
-                            let host = if state.db_host.value.trim().is_empty() {
-                                "localhost"
-                            } else {
-                                state.db_host.value.trim()
-                            };
-                            let db = if state.db_database.value.trim().is_empty() {
-                                "cadalytix"
-                            } else {
-                                state.db_database.value.trim()
-                            };
-                            let user = state.db_user.value.trim();
-                            let pass = &state.db_password.value;
-                            format!(
-                                "postgresql://{}:{}@{}:{}/{}?sslmode={}",
-                                user, pass, host, port, db, ssl
-                            )
-                        }
-                        DbEngine::SqlServer => {
-                            let host = if state.db_host.value.trim().is_empty() {
-                                "localhost"
-                            } else {
-                                state.db_host.value.trim()
-                            };
-                            let port = state.db_port.value.trim();
-                            let server = if port.is_empty() {
-                                host.to_string()
-                            } else {
-                                format!("{},{}", host, port)
-                            };
-                            let db = if state.db_database.value.trim().is_empty() {
-                                "cadalytix"
-                            } else {
-                                state.db_database.value.trim()
-                            };
-                            let user = state.db_user.value.trim();
-                            let pass = &state.db_password.value;
-                            let encrypt = if state.db_ssl_mode.trim() == "disable" {
-                                "false"
-                            } else {
-                                "true"
-                            };
-                            format!(
-                                "Server={};Database={};User Id={};Password={};TrustServerCertificate=true;Encrypt={};",
-                                server, db, user, pass, encrypt
-                            )
-                        }
-                    }
-                };
-
-                let engine = if state.db_use_conn_string {
-                    match guess_engine_from_conn_str(&conn_str) {
-                        DbEngine::Postgres => "postgres".to_string(),
-                        DbEngine::SqlServer => "sqlserver".to_string(),
-                    }
-                } else {
-                    match state.db_engine {
-                        DbEngine::Postgres => "postgres".to_string(),
-                        DbEngine::SqlServer => "sqlserver".to_string(),
-                    }
-                };
-
-                let tx = tx.clone();
-                thread::spawn(move || {
-                    let rt = tokio::runtime::Builder::new_current_thread()
-                        .enable_all()
-                        .build();
-                    match rt {
-                        Ok(rt) => {
-                            let req = crate::api::installer::TestDbConnectionRequest {
-                                engine,
-                                connection_string: conn_str,
-                            };
-                            let res =
-                                rt.block_on(crate::api::installer::test_db_connection(Some(req)));
-                            match res {
-                                Ok(r) => {
-                                    let _ = tx.send(UiMsg::DbTestComplete {
-                                        success: r.success,
-                                        message: if r.success {
-                                            "Connection successful.".to_string()
-                                        } else {
-                                            format!("Connection failed: {}", r.message)
-                                        },
-                                    });
-                                }
-                                Err(e) => {
-                                    let _ = tx.send(UiMsg::DbTestComplete {
-                                        success: false,
-                                        message: format!("Connection failed: {}", e),
-                                    });
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            let _ = tx.send(UiMsg::DbTestComplete {
-                                success: false,
-                                message: format!("Internal error: {}", e),
-                            });
-                        }
-                    }
-                });
+                start_db_test(state, tx);
             }
-            KeyCode::Char('b') | KeyCode::Char('B') if state.page == Page::Destination => {
+            KeyCode::Char('b') | KeyCode::Char('B')
+                if state.page == Page::Destination
+                    || (state.page == Page::DataSource
+                        && state.data_source_kind == DataSourceKind::File) =>
+            {
                 // Browse-like folder picker (TUI).
-                let raw = state.destination_path.value.trim();
+                let raw = if state.page == Page::DataSource {
+                    state.file_source_folder.value.trim()
+                } else {
+                    state.destination_path.value.trim()
+                };
                 let current = if raw.is_empty() {
                     std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
                 } else {
@@ -2346,6 +3135,14 @@ fn handle_key(
                     selected: 0,
                 });
             }
+            KeyCode::Char('b') | KeyCode::Char('B') if state.page == Page::DataSource => {
+                source_objects::open(state, tx);
+            }
+            KeyCode::Char('p') | KeyCode::Char('P')
+                if matches!(state.page, Page::DataSource | Page::Database) =>
+            {
+                profiles::open(state, secrets);
+            }
             KeyCode::Up | KeyCode::Down if state.page == Page::Storage => {
                 // Toggle defaults/custom
                 state.storage_mode = match state.storage_mode {
@@ -2398,14 +3195,45 @@ fn handle_key(
                     set_focused_button(state, ButtonFocus::Next);
                 }
             }
-            KeyCode::Char('f') | KeyCode::Char('F') if state.page == Page::Archive => {
+            KeyCode::Char('f') | KeyCode::Char('F')
+                if state.page == Page::Archive && state.licensed.allows(features::ARCHIVE) =>
+            {
                 state.archive_format = match state.archive_format {
                     ArchiveFormatChoice::ZipNdjson => ArchiveFormatChoice::ZipCsv,
                     ArchiveFormatChoice::ZipCsv => ArchiveFormatChoice::ZipNdjson,
                 };
             }
+            KeyCode::Char('e') | KeyCode::Char('E')
+                if state.page == Page::Archive && state.licensed.allows(features::ARCHIVE) =>
+            {
+                state.archive_encrypt = !state.archive_encrypt;
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') if state.page == Page::Ready => {
+                state.dry_run = !state.dry_run;
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') if state.page == Page::Installing => {
+                if state.install_paused {
+                    let _ = installer::resume_install();
+                    state.install_paused = false;
+                } else if installer::pause_install().is_ok() {
+                    state.install_paused = true;
+                    state
+                        .install_detail
+                        .push("Pausing at the next safe point...".to_string());
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') if state.page == Page::Complete => {
+                start_archive_run(state, tx);
+            }
+            KeyCode::Char('l') | KeyCode::Char('L')
+                if matches!(state.page, Page::Installing | Page::Complete) =>
+            {
+                state.log_viewer = Some(LogViewer::open());
+            }
             KeyCode::Char(' ')
-                if state.page == Page::Archive && !matches!(state.focus, FocusTarget::Field(_)) =>
+                if state.page == Page::Archive
+                    && state.licensed.allows(features::ARCHIVE)
+                    && !matches!(state.focus, FocusTarget::Field(_)) =>
             {
                 state.archive_catch_up_on_startup = !state.archive_catch_up_on_startup;
             }
@@ -2518,7 +3346,13 @@ fn handle_key(
                 match focused_button(state) {
                     ButtonFocus::Back => {
                         if can_go_back(state.page) {
+                            let leaving_mapping = state.page == Page::Mapping;
                             state.page = prev_page(state.page);
+                            if state.page == Page::Mapping {
+                                enter_mapping_page(state, tx);
+                            } else if leaving_mapping {
+                                set_focused_button(state, ButtonFocus::Back);
+                            }
                         }
                     }
                     ButtonFocus::Next => {
@@ -2530,86 +3364,30 @@ fn handle_key(
                         if can_go_next(state) {
                             // Installing: start the install run on Ready.
                             if state.page == Page::Ready {
-                                state.page = Page::Installing;
-                                state.install_detail.clear();
-                                state.install_progress = Some(ProgressPayload {
-                                    correlation_id: "pending".to_string(),
-                                    step: "start".to_string(),
-                                    severity: "info".to_string(),
-                                    phase: "install".to_string(),
-                                    percent: 0,
-                                    message: "Starting installation...".to_string(),
-                                    elapsed_ms: None,
-                                    eta_ms: None,
-                                });
-
-                                let req = build_install_request(state);
-                                let secrets = Arc::clone(secrets);
-                                let tx = tx.clone();
-                                thread::spawn(move || {
-                                    let correlation_id = Uuid::new_v4().to_string();
-                                    let tx_progress = tx.clone();
-                                    let progress_emitter: ProgressEmitter =
-                                        Arc::new(move |p: ProgressPayload| {
-                                            let _ = tx_progress.send(UiMsg::InstallProgress(p));
-                                        });
-
-                                    let rt = tokio::runtime::Builder::new_current_thread()
-                                        .enable_all()
-                                        .build();
-                                    match rt {
-                                        Ok(rt) => {
-                                            let result = rt.block_on(installer::run_installation(
-                                                secrets,
-                                                req,
-                                                correlation_id.clone(),
-                                                progress_emitter,
-                                            ));
-                                            match result {
-                                                Ok(artifacts) => {
-                                                    let _ = tx.send(UiMsg::InstallFinished {
-                                                        success: true,
-                                                        message: "Installation complete."
-                                                            .to_string(),
-                                                        correlation_id,
-                                                        artifacts: Some(artifacts),
-                                                    });
-                                                }
-                                                Err(e) => {
-                                                    let _ = tx.send(UiMsg::InstallFinished {
-                                                        success: false,
-                                                        message: e.to_string(),
-                                                        correlation_id,
-                                                        artifacts: None,
-                                                    });
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            let _ = tx.send(UiMsg::InstallFinished {
-                                                success: false,
-                                                message: format!(
-                                                    "Internal error starting installer: {}",
-                                                    e
-                                                ),
-                                                correlation_id,
-                                                artifacts: None,
-                                            });
-                                        }
-                                    }
-                                });
+                                // Unprivileged on Linux: root-only steps need consent first.
+                                #[cfg(target_os = "linux")]
+                                if !elevate::ready_to_install(state) {
+                                    return;
+                                }
+                                start_install(state, tx, secrets);
                             } else {
                                 state.page = next_page(state.page);
+                                if state.page == Page::Ready {
+                                    disk_space::start(state, tx);
+                                    backfill::start(state, tx);
+                                }
                                 // Reset focus on each navigation
                                 if state.page == Page::Mapping {
-                                    state.focus = FocusTarget::Mapping(MappingFocus::SourceList);
-                                    start_mapping_scan(state, tx);
+                                    enter_mapping_page(state, tx);
                                 } else if page_field_count(state) > 0 {
                                     state.focus = FocusTarget::Field(0);
                                 } else {
                                     set_focused_button(state, ButtonFocus::Next);
                                 }
                             }
+                        } else if state.page == Page::Ready && !state.disk_space_checking {
+                            // Blocked on disk space: Install measures again.
+                            disk_space::start(state, tx);
                         }
                     }
                     ButtonFocus::Cancel => {
@@ -2625,6 +3403,356 @@ fn handle_key(
     }
 }
 
+/// Existing DB: validate the inputs, then test the connection in a background thread
+/// (`UiMsg::DbTestComplete`).
+fn start_db_test(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    if state.db_kind == DbKind::Local {
+        return;
+    }
+
+    // No connection attempt until required fields exist.
+    if state.db_use_conn_string {
+        if state.db_conn_string.value.trim().is_empty() {
+            state.db_test_status = DbTestStatus::Fail;
+            state.db_test_message = "Missing required inputs: Connection string.".to_string();
+            return;
+        }
+    } else {
+        let mut missing = Vec::new();
+        if state.db_host.value.trim().is_empty() {
+            missing.push("Host");
+        }
+        if state.db_port.value.trim().is_empty() {
+            missing.push("Port");
+        }
+        if state.db_database.value.trim().is_empty() {
+            missing.push("Database");
+        }
+        if !state.db_windows_auth && state.db_user.value.trim().is_empty() {
+            missing.push("Username");
+        }
+        if !state.db_windows_auth && state.db_password.value.trim().is_empty() {
+            missing.push("Password");
+        }
+        if !missing.is_empty() {
+            state.db_test_status = DbTestStatus::Fail;
+            state.db_test_message = format!("Missing required inputs: {}.", missing.join(", "));
+            return;
+        }
+    }
+
+    state.db_test_status = DbTestStatus::Testing;
+    state.db_test_message = "Testing connection...".to_string();
+
+    let guess_engine_from_conn_str = |conn_str: &str| -> DbEngine {
+        let s = conn_str.trim().to_ascii_lowercase();
+        if s.starts_with("postgres://") || s.starts_with("postgresql://") || s.contains("host=") {
+            DbEngine::Postgres
+        } else {
+            DbEngine::SqlServer
+        }
+    };
+
+    let conn_str = if state.db_use_conn_string && !state.db_conn_string.value.trim().is_empty() {
+        state.db_conn_string.value.trim().to_string()
+    } else {
+        // Build a structured connection string from fields (details mode).
+        let engine = match state.existing_hosted_where {
+            ExistingHostedWhere::AzureSqlMi => DbEngine::SqlServer,
+            ExistingHostedWhere::Neon | ExistingHostedWhere::Supabase => DbEngine::Postgres,
+            _ => {
+                // Heuristic fallback: common port values.
+                if state.db_port.value.trim() == "1433" {
+                    DbEngine::SqlServer
+                } else {
+                    DbEngine::Postgres
+                }
+            }
+        };
+        state.db_engine = engine;
+
+        match engine {
+            DbEngine::Postgres => {
+                let ssl = state.db_ssl_mode.trim();
+                let host = if state.db_host.value.trim().is_empty() {
+                    "localhost"
+                } else {
+                    state.db_host.value.trim()
+                };
+                let db = if state.db_database.value.trim().is_empty() {
+                    "cadalytix"
+                } else {
+                    state.db_database.value.trim()
+                };
+                PostgresConnString::new(host)
+                    .port(Some(port_or_default(
+                        &state.db_port.value,
+                        POSTGRES_DEFAULT_PORT,
+                    )))
+                    .database(db)
+                    .credentials(state.db_user.value.trim(), &state.db_password.value)
+                    .ssl_mode(ssl)
+                    .build()
+                    .unwrap_or_default()
+            }
+            DbEngine::SqlServer => {
+                let host = if state.db_host.value.trim().is_empty() {
+                    "localhost"
+                } else {
+                    state.db_host.value.trim()
+                };
+                let db = if state.db_database.value.trim().is_empty() {
+                    "cadalytix"
+                } else {
+                    state.db_database.value.trim()
+                };
+                let cs = SqlServerConnString::new(host)
+                    .port(state.db_port.value.trim().parse().ok())
+                    .database(db)
+                    .credentials(state.db_user.value.trim(), &state.db_password.value)
+                    .encrypt(state.db_ssl_mode.trim() != "disable");
+                if state.db_windows_auth {
+                    cs.integrated_auth().build()
+                } else {
+                    cs.build()
+                }
+            }
+        }
+    };
+
+    let engine = if state.db_use_conn_string {
+        match guess_engine_from_conn_str(&conn_str) {
+            DbEngine::Postgres => "postgres".to_string(),
+            DbEngine::SqlServer => "sqlserver".to_string(),
+        }
+    } else {
+        match state.db_engine {
+            DbEngine::Postgres => "postgres".to_string(),
+            DbEngine::SqlServer => "sqlserver".to_string(),
+        }
+    };
+
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build();
+        match rt {
+            Ok(rt) => {
+                let req = crate::api::installer::TestDbConnectionRequest {
+                    engine,
+                    connection_string: conn_str,
+                };
+                let res = rt.block_on(crate::api::installer::test_db_connection(Some(req)));
+                match res {
+                    Ok(r) => {
+                        let message = if r.success {
+                            "Connection successful.".to_string()
+                        } else if !r.missing_permissions.is_empty() {
+                            r.message
+                        } else {
+                            format!("Connection failed: {}", r.message)
+                        };
+                        let _ = tx.send(UiMsg::DbTestComplete {
+                            success: r.success,
+                            message: match stage_summary(&r.stages) {
+                                Some(stages) => format!("{} [{}]", message, stages),
+                                None => message,
+                            },
+                        });
+                    }
+                    Err(e) => {
+                        let _ = tx.send(UiMsg::DbTestComplete {
+                            success: false,
+                            message: format!("Connection failed: {}", e),
+                        });
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(UiMsg::DbTestComplete {
+                    success: false,
+                    message: format!("Internal error: {}", e),
+                });
+            }
+        }
+    });
+}
+
+/// `dns 3 ms, tcp 1 ms, login failed after 40 ms` for the test result line.
+fn stage_summary(stages: &[crate::api::installer::ConnectionStage]) -> Option<String> {
+    if stages.is_empty() {
+        return None;
+    }
+    let stage = |s: &crate::api::installer::ConnectionStage| {
+        let name = format!("{:?}", s.stage).to_ascii_lowercase();
+        if s.success {
+            format!("{} {} ms", name, s.elapsed_ms)
+        } else {
+            format!("{} failed after {} ms", name, s.elapsed_ms)
+        }
+    };
+    Some(stages.iter().map(stage).collect::<Vec<_>>().join(", "))
+}
+
+/// Ready -> Installing: start the install run in a background thread (`UiMsg::Install*`).
+fn start_install(
+    state: &mut WizardState,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    state.page = Page::Installing;
+    state.install_detail.clear();
+    state.install_paused = false;
+    state.install_progress = Some(ProgressPayload {
+        correlation_id: "pending".to_string(),
+        step: "start".to_string(),
+        severity: "info".to_string(),
+        phase: "install".to_string(),
+        percent: 0,
+        message: "Starting installation...".to_string(),
+        elapsed_ms: None,
+        eta_ms: None,
+    });
+
+    let req = build_install_request(state);
+    let done_message = if req.dry_run {
+        "Dry run complete. No changes were made."
+    } else {
+        "Installation complete."
+    };
+    let secrets = Arc::clone(secrets);
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let correlation_id = Uuid::new_v4().to_string();
+        let tx_progress = tx.clone();
+        let progress_emitter: ProgressEmitter = Arc::new(move |p: ProgressPayload| {
+            let _ = tx_progress.send(UiMsg::InstallProgress(p));
+        });
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build();
+        match rt {
+            Ok(rt) => {
+                let result = rt.block_on(installer::run_installation(
+                    secrets,
+                    req,
+                    correlation_id.clone(),
+                    progress_emitter,
+                ));
+                match result {
+                    Ok(artifacts) => {
+                        let _ = tx.send(UiMsg::InstallFinished {
+                            success: true,
+                            message: done_message.to_string(),
+                            correlation_id,
+                            artifacts: Some(artifacts),
+                            exit_code: 0,
+                        });
+                    }
+                    Err(e) => {
+                        error!(
+                            "[PHASE: install] [STEP: error] [CODE: {}] Installation failed: {:?}",
+                            crate::error::code_label(crate::error::error_code(&e)),
+                            e
+                        );
+                        let _ = tx.send(UiMsg::InstallFinished {
+                            success: false,
+                            message: crate::error::user_message(&e),
+                            correlation_id,
+                            artifacts: None,
+                            exit_code: crate::error::exit_code(&e),
+                        });
+                        crate::installation::signals::exit_if_interrupted(&e);
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(UiMsg::InstallFinished {
+                    success: false,
+                    message: format!("Internal error starting installer: {}", e),
+                    correlation_id,
+                    artifacts: None,
+                    exit_code: 1,
+                });
+            }
+        }
+    });
+}
+
+/// The Mapping page as the `MappingState` sent with the install request.
+fn current_mapping_state(state: &WizardState) -> MappingState {
+    MappingState {
+        mapping_override: state.mapping_override,
+        source_fields: state
+            .source_fields
+            .iter()
+            .map(|s| MappingSourceField {
+                id: s.id.clone(),
+                raw_name: s.raw_name.clone(),
+                display_name: s.display_name.clone(),
+            })
+            .collect(),
+        target_fields: state
+            .target_fields
+            .iter()
+            .map(|t| MappingTargetField {
+                id: t.id.clone(),
+                name: t.name.clone(),
+                required: t.required,
+            })
+            .collect(),
+        source_to_targets: state.source_to_targets.clone(),
+        target_to_source: state.target_to_source.clone(),
+        transforms: state.mapping_transforms.clone(),
+    }
+}
+
+/// Collation / locale and folder problems of the Create NEW database, if any.
+fn new_db_options_error(state: &WizardState) -> Option<String> {
+    let db_setup = installer::DbSetupConfig {
+        mode: "create_new".to_string(),
+        new_location: state.new_db_location.as_id().to_string(),
+        new_specific_path: state.new_db_specific_path.value.trim().to_string(),
+        collation: state.new_db_collation.value.trim().to_string(),
+        ..installer::DbSetupConfig::default()
+    };
+    crate::database::provisioning::validate_create_options(&db_setup.create_options()).err()
+}
+
+const APP_LOGIN_LABEL: &str = "Application login (optional)";
+const APP_LOGIN_HELP: &str = "Creates a login with runtime access only for the application. Leave blank to run it with the install login.";
+
+fn app_account(state: &WizardState) -> AppAccountConfig {
+    AppAccountConfig {
+        enabled: !state.app_login.value.trim().is_empty(),
+        login_name: state.app_login.value.trim().to_string(),
+    }
+}
+
+fn app_login_error(state: &WizardState) -> Option<String> {
+    app_account(state).validate().err()
+}
+
+fn collation_label(state: &WizardState) -> &'static str {
+    match state.db_engine {
+        DbEngine::SqlServer => "Collation (optional, e.g. Latin1_General_CI_AS)",
+        DbEngine::Postgres => "Locale (optional, e.g. en_US.UTF-8)",
+    }
+}
+
+fn new_db_path_help(state: &WizardState) -> &'static str {
+    match state.db_engine {
+        DbEngine::SqlServer => {
+            "The data and log files are created in this folder on the SQL Server host."
+        }
+        DbEngine::Postgres => {
+            "A tablespace is created in this folder on the PostgreSQL host (needs a superuser; the folder must exist, be empty and be owned by the postgres account)."
+        }
+    }
+}
+
 fn build_install_request(state: &WizardState) -> StartInstallRequest {
     // For now, reuse the Phase 5 placeholder runner:
     // - Config DB connection string uses the DB Setup page values
@@ -2637,14 +3765,7 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
     } else {
         match state.db_engine {
             DbEngine::Postgres => {
-                let port = if state.db_port.value.trim().is_empty() {
-                    "5432"
-                } else {
-                    state.db_port.value.trim()
-                };
                 let ssl = state.db_ssl_mode.trim();
-                let user = state.db_user.value.trim();
-                let pass = &state.db_password.value;
                 let host = if state.db_host.value.trim().is_empty() {
                     "localhost"
                 } else {
@@ -2655,10 +3776,16 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
                 } else {
                     state.db_database.value.trim()
                 };
-                format!(
-                    "postgresql://{}:{}@{}:{}/{}?sslmode={}",
-                    user, pass, host, port, db, ssl
-                )
+                PostgresConnString::new(host)
+                    .port(Some(port_or_default(
+                        &state.db_port.value,
+                        POSTGRES_DEFAULT_PORT,
+                    )))
+                    .database(db)
+                    .credentials(state.db_user.value.trim(), &state.db_password.value)
+                    .ssl_mode(ssl)
+                    .build()
+                    .unwrap_or_default()
             }
             DbEngine::SqlServer => {
                 let host = if state.db_host.value.trim().is_empty() {
@@ -2666,54 +3793,37 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
                 } else {
                     state.db_host.value.trim()
                 };
-                let port = if state.db_port.value.trim().is_empty() {
-                    "1433"
-                } else {
-                    state.db_port.value.trim()
-                };
                 let db = if state.db_database.value.trim().is_empty() {
                     "cadalytix"
                 } else {
                     state.db_database.value.trim()
                 };
-                let user = state.db_user.value.trim();
-                let pass = &state.db_password.value;
                 // TLS toggle is represented as "disable|prefer|require" in the TUI state;
                 // for SQL Server we map it to Encrypt=true/false (TrustServerCertificate=true for now).
                 let encrypt = matches!(
                     state.db_ssl_mode.trim().to_ascii_lowercase().as_str(),
                     "require" | "true"
                 );
-                format!(
-                    "Server={},{};Database={};User Id={};Password={};TrustServerCertificate=true;Encrypt={};",
-                    host,
-                    port,
-                    db,
-                    user,
-                    pass,
-                    if encrypt { "true" } else { "false" }
-                )
+                let cs = SqlServerConnString::new(host)
+                    .port(Some(port_or_default(
+                        &state.db_port.value,
+                        SQL_SERVER_DEFAULT_PORT,
+                    )))
+                    .database(db)
+                    .credentials(state.db_user.value.trim(), &state.db_password.value)
+                    .encrypt(encrypt);
+                if state.db_windows_auth {
+                    cs.integrated_auth().build()
+                } else {
+                    cs.build()
+                }
             }
         }
     };
 
-    let call_data = {
-        let host = if state.call_data_host.value.trim().is_empty() {
-            "localhost"
-        } else {
-            state.call_data_host.value.trim()
-        };
-        let port = if state.call_data_port.value.trim().is_empty() {
-            "1433"
-        } else {
-            state.call_data_port.value.trim()
-        };
-        let server = format!("{},{}", host, port);
-        let db = state.call_data_database.value.trim();
-        let user = state.call_data_user.value.trim();
-        let pass = &state.call_data_password.value;
-        format!("Server={};Database={};User Id={};Password={};TrustServerCertificate=true;Encrypt=false;", server, db, user, pass)
-    };
+    // The first data source is the primary one; the others go along as additional sources.
+    let mut data_sources = sources::configs(state);
+    let primary = data_sources.remove(0);
 
     let storage = StorageConfig {
         mode: match state.storage_mode {
@@ -2735,11 +3845,6 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
         max_disk_gb: state.max_disk_gb.value.clone(),
     };
 
-    let mut mappings: HashMap<String, String> = HashMap::new();
-    for (target_id, source_id) in state.target_to_source.iter() {
-        mappings.insert(target_id.clone(), mapping_source_raw(state, source_id));
-    }
-
     let hot_months = match state.hot_retention_choice {
         HotRetentionChoice::Months12 => 12,
         HotRetentionChoice::Months18 => 18,
@@ -2780,6 +3885,9 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
             time_local: schedule_time_local,
         },
         catch_up_on_startup: state.archive_catch_up_on_startup,
+        encrypt_archives: state.archive_encrypt,
+        enabled: state.licensed.allows(features::ARCHIVE),
+        ..ArchivePolicyConfig::default()
     };
 
     let max_db_size_gb = state
@@ -2803,6 +3911,7 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
         new_location: state.new_db_location.as_id().to_string(),
         new_specific_path: state.new_db_specific_path.value.trim().to_string(),
         max_db_size_gb,
+        collation: state.new_db_collation.value.trim().to_string(),
         existing_hosted_where: state.existing_hosted_where.as_id().to_string(),
         existing_connect_mode: if state.db_use_conn_string {
             "connection_string".to_string()
@@ -2813,34 +3922,12 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
         postgres_options: None,
     };
 
-    let mapping_state = Some(MappingState {
-        mapping_override: state.mapping_override,
-        source_fields: state
-            .source_fields
-            .iter()
-            .map(|s| MappingSourceField {
-                id: s.id.clone(),
-                raw_name: s.raw_name.clone(),
-                display_name: s.display_name.clone(),
-            })
-            .collect(),
-        target_fields: state
-            .target_fields
-            .iter()
-            .map(|t| MappingTargetField {
-                id: t.id.clone(),
-                name: t.name.clone(),
-                required: t.required,
-            })
-            .collect(),
-        source_to_targets: state.source_to_targets.clone(),
-        target_to_source: state.target_to_source.clone(),
-    });
-
+    let remote = crate::installation::remote::selected();
     StartInstallRequest {
-        install_mode: match state.install_mode {
-            InstallMode::Windows => "windows".to_string(),
-            InstallMode::Docker => "docker".to_string(),
+        install_mode: match (state.install_mode, remote) {
+            (_, Some(_)) => "linux".to_string(),
+            (InstallMode::Windows, None) => "windows".to_string(),
+            (InstallMode::Docker, None) => "docker".to_string(),
         },
         installation_type: match state.installation_type {
             InstallationType::Typical => "typical".to_string(),
@@ -2849,21 +3936,39 @@ fn build_install_request(state: &WizardState) -> StartInstallRequest {
         },
         destination_folder: state.destination_path.value.clone(),
         config_db_connection_string: config_db,
-        call_data_connection_string: call_data,
-        source_object_name: state.source_object_name.value.clone(),
+        call_data_connection_string: primary.call_data_connection_string,
+        source_object_name: primary.source_object_name,
+        file_source: primary.file_source,
+        call_data_driver: primary.call_data_driver,
+        data_source_name: primary.name,
+        additional_data_sources: data_sources,
         db_setup,
         storage,
         hot_retention,
         archive_policy,
         consent_to_sync: state.consent_to_sync,
-        mappings,
+        mappings: primary.mappings,
         mapping_override: state.mapping_override,
-        mapping_state,
+        mapping_state: primary.mapping_state,
+        layout: install_layout::config(state),
+        app_account: app_account(state),
+        dry_run: state.dry_run,
+        remote: remote.cloned(),
     }
 }
 
 fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
+    state.hit_regions.borrow_mut().clear();
+    let ScreenLayout::Wizard {
+        banner: show_banner,
+        stacked_mapping,
+    } = screen_layout(area.width, area.height)
+    else {
+        draw_too_small(f, area);
+        return;
+    };
     let (window_area, outer) = centered_window(area, 100, 30);
+    f.render_widget(Block::default().style(theme::current().base()), area);
 
     // Outer frame
     let outer_block = Block::default()
@@ -2886,19 +3991,30 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
 
     let cols = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(34), Constraint::Min(0)].as_ref())
+        .constraints(
+            [
+                Constraint::Length(if show_banner { 34 } else { 0 }),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
         .split(body);
 
     // Left banner
-    let banner_block = Block::default().borders(Borders::ALL);
-    let logo = Paragraph::new(ASCII_LOGO)
-        .block(banner_block)
-        .alignment(Alignment::Left)
-        .wrap(Wrap { trim: false });
-    f.render_widget(logo, cols[0]);
+    if show_banner {
+        let banner_block = Block::default().borders(Borders::ALL);
+        let logo = Paragraph::new(ASCII_LOGO)
+            .block(banner_block)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+        f.render_widget(logo, cols[0]);
+    }
 
     // Right content
-    let title = page_title(state.page, state.install_mode);
+    let mut title = page_title(state.page, state.install_mode);
+    if invalidation::is_dirty(state) {
+        title.push_str(" (review: earlier answers changed)");
+    }
     let content_text = match state.page {
         Page::Platform => {
             let w = if state.platform_selected == InstallMode::Windows {
@@ -2932,20 +4048,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
         }
         Page::License => {
             let accept = if state.license_accepted { "[x]" } else { "[ ]" };
-            let license_lines: Vec<&str> = vec![
-                "LICENSE TEXT NOT PROVIDED.",
-                "",
-                "Place your license text (EULA) under Prod_Install_Wizard_Deployment/licenses/ and wire the loader.",
-                "",
-                "This TUI currently uses a placeholder license body.",
-                "",
-                "Use PageUp/PageDown to scroll.",
-                "",
-                "By proceeding, you acknowledge you have read and understood the license agreement.",
-                "",
-                "— End of placeholder license —",
-            ];
-
+            let license_lines = LICENSE_LINES;
             let offset = (state.license_scroll as usize).min(license_lines.len().saturating_sub(1));
             let visible = 8usize;
             let mut lines = Vec::new();
@@ -3021,80 +4124,254 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                     "{} Install path: {}",
                     prefix, state.destination_path.value
                 )),
-                Line::from("Required space: ~2–5 GB"),
             ];
+            lines.extend(field_rules::inline_error_line(&state.destination_path));
+            lines.push(Line::from("Required space is projected on the Ready page."));
             if let Some(err) = state.destination_error.as_ref() {
                 lines.push(Line::from(format!("Error: {}", err)));
             }
             lines.push(Line::from(""));
+            lines.extend(install_layout::lines(state));
+            lines.push(Line::from(""));
             lines.push(Line::from("Tab to edit the path. Press B to browse."));
             Text::from(lines)
         }
         Page::DataSource => {
-            let local = state.data_source_kind == DataSourceKind::Local;
-            let r_local = if local { "(x)" } else { "( )" };
-            let r_remote = if local { "( )" } else { "(x)" };
-
-            let p0 = if matches!(state.focus, FocusTarget::Field(0)) {
-                ">"
-            } else {
-                " "
-            };
-            let p1 = if matches!(state.focus, FocusTarget::Field(1)) {
-                ">"
-            } else {
-                " "
-            };
-            let p2 = if matches!(state.focus, FocusTarget::Field(2)) {
-                ">"
-            } else {
-                " "
-            };
-            let p3 = if matches!(state.focus, FocusTarget::Field(3)) {
-                ">"
-            } else {
-                " "
-            };
-            let p4 = if matches!(state.focus, FocusTarget::Field(4)) {
-                ">"
-            } else {
-                " "
-            };
-            let p5 = if matches!(state.focus, FocusTarget::Field(5)) {
-                ">"
-            } else {
-                " "
+            let multi_datasource = state.licensed.allows(features::MULTI_DATASOURCE);
+            let radio = |kind: DataSourceKind| {
+                if state.data_source_kind == kind {
+                    "(x)"
+                } else if !multi_datasource
+                    && !matches!(kind, DataSourceKind::Local | DataSourceKind::Remote)
+                {
+                    "(-)"
+                } else {
+                    "( )"
+                }
             };
-
-            Text::from(vec![
+            let mut lines = vec![
                 Line::from(format!(
                     "{} Use this server/host (local environment)",
-                    r_local
+                    radio(DataSourceKind::Local)
                 )),
                 Line::from(format!(
                     "{} Connect to an existing remote system/database",
-                    r_remote
+                    radio(DataSourceKind::Remote)
                 )),
-                Line::from(""),
                 Line::from(format!(
-                    "{} Database: {}",
-                    p0, state.call_data_database.value
+                    "{} ODBC data source (DSN or driver connection string)",
+                    radio(DataSourceKind::Odbc)
                 )),
-                Line::from(format!("{} Username: {}", p1, state.call_data_user.value)),
+            ];
+            if cfg!(feature = "oracle") {
+                lines.push(Line::from(format!(
+                    "{} Oracle database",
+                    radio(DataSourceKind::Oracle)
+                )));
+            }
+            lines.extend([
                 Line::from(format!(
-                    "{} Password: {}",
-                    p2,
-                    state.call_data_password.display()
+                    "{} File (CSV/TSV) export folder",
+                    radio(DataSourceKind::File)
                 )),
-                Line::from(format!("{} Host: {}", p3, state.call_data_host.value)),
-                Line::from(format!("{} Port: {}", p4, state.call_data_port.value)),
-                Line::from(format!(
+                Line::from(""),
+            ]);
+            if !multi_datasource {
+                lines.push(Line::from(features::not_included_message(
+                    "ODBC, Oracle and file export data sources",
+                )));
+                lines.push(Line::from(""));
+            }
+            let focus_prefix = |i: usize| {
+                if matches!(state.focus, FocusTarget::Field(f) if f == i) {
+                    ">"
+                } else {
+                    " "
+                }
+            };
+            if state.data_source_kind == DataSourceKind::Odbc {
+                lines.push(Line::from(format!(
+                    "{} ODBC connection string: {}",
+                    focus_prefix(0),
+                    mask_connection_string(&state.odbc_connection_string.value)
+                )));
+                lines.push(Line::from(format!(
+                    "{} Source object name: {}",
+                    focus_prefix(1),
+                    state.source_object_name.value
+                )));
+                lines.extend(field_rules::inline_error_line(&state.source_object_name));
+                lines.push(Line::from(""));
+                lines.push(Line::from(
+                    "e.g. DSN=CAD;UID=reader;PWD=... or Driver={Vendor ODBC};Server=...;",
+                ));
+                lines.push(Line::from(
+                    "Columns are read through the ODBC catalog on the Mapping page.",
+                ));
+                lines.push(Line::from(
+                    "Tab cycles fields. Press B to browse the tables and views.",
+                ));
+            } else if state.data_source_kind == DataSourceKind::Oracle {
+                lines.extend([
+                    Line::from(format!(
+                        "{} ODBC driver name (blank for the Instant Client default): {}",
+                        focus_prefix(0),
+                        state.oracle_driver.value
+                    )),
+                    Line::from(format!(
+                        "{} Address (host[:port]/service or TNS alias): {}",
+                        focus_prefix(1),
+                        state.oracle_address.value
+                    )),
+                    Line::from(format!(
+                        "{} Username: {}",
+                        focus_prefix(2),
+                        state.call_data_user.value
+                    )),
+                    Line::from(format!(
+                        "{} Password: {}",
+                        focus_prefix(3),
+                        state.call_data_password.display()
+                    )),
+                    Line::from(format!(
+                        "{} Source object name ([owner.]table): {}",
+                        focus_prefix(4),
+                        state.source_object_name.value
+                    )),
+                ]);
+                lines.extend(field_rules::inline_error_line(&state.source_object_name));
+                lines.extend([
+                    Line::from(""),
+                    Line::from("Requires the Oracle Instant Client ODBC driver on this machine."),
+                    Line::from("Tab cycles fields. Press B to browse the tables and views."),
+                ]);
+            } else if state.data_source_kind == DataSourceKind::File {
+                lines.push(Line::from(format!(
+                    "{} Export folder: {}",
+                    focus_prefix(0),
+                    state.file_source_folder.value
+                )));
+                lines.extend(field_rules::inline_error_line(&state.file_source_folder));
+                lines.push(Line::from(format!(
+                    "{} Delimiter (auto/comma/tab/semicolon/pipe): {}",
+                    focus_prefix(1),
+                    state.file_source_delimiter.value
+                )));
+                if let Err(e) = file::parse_delimiter(&state.file_source_delimiter.value) {
+                    lines.push(Line::from(format!("Error: {}", e)));
+                }
+                lines.push(Line::from(""));
+                lines.push(Line::from(
+                    "Headers are read from the newest .csv/.tsv/.txt export on the Mapping page.",
+                ));
+                lines.push(Line::from("Tab cycles fields. Press B to browse."));
+            } else {
+                let p0 = if matches!(state.focus, FocusTarget::Field(0)) {
+                    ">"
+                } else {
+                    " "
+                };
+                let p1 = if matches!(state.focus, FocusTarget::Field(1)) {
+                    ">"
+                } else {
+                    " "
+                };
+                let p2 = if matches!(state.focus, FocusTarget::Field(2)) {
+                    ">"
+                } else {
+                    " "
+                };
+                let p3 = if matches!(state.focus, FocusTarget::Field(3)) {
+                    ">"
+                } else {
+                    " "
+                };
+                let p4 = if matches!(state.focus, FocusTarget::Field(4)) {
+                    ">"
+                } else {
+                    " "
+                };
+                let p5 = if matches!(state.focus, FocusTarget::Field(5)) {
+                    ">"
+                } else {
+                    " "
+                };
+
+                lines.extend([
+                    Line::from(format!(
+                        "{} Database: {}",
+                        p0, state.call_data_database.value
+                    )),
+                    Line::from(format!(
+                        "{} Username: {}",
+                        p1,
+                        if state.call_data_windows_auth {
+                            WINDOWS_AUTH_PLACEHOLDER.to_string()
+                        } else {
+                            state.call_data_user.value.clone()
+                        }
+                    )),
+                    Line::from(format!(
+                        "{} Password: {}",
+                        p2,
+                        if state.call_data_windows_auth {
+                            WINDOWS_AUTH_PLACEHOLDER.to_string()
+                        } else {
+                            state.call_data_password.display()
+                        }
+                    )),
+                    Line::from(format!("{} Host: {}", p3, state.call_data_host.value)),
+                ]);
+                lines.extend(field_rules::inline_error_line(&state.call_data_host));
+                lines.push(Line::from(format!(
+                    "{} Port: {}",
+                    p4, state.call_data_port.value
+                )));
+                lines.extend(field_rules::inline_error_line(&state.call_data_port));
+                lines.push(Line::from(format!(
                     "{} Source object name: {}",
                     p5, state.source_object_name.value
-                )),
-                Line::from(""),
-                Line::from("Tab cycles fields."),
-            ])
+                )));
+                lines.extend(field_rules::inline_error_line(&state.source_object_name));
+                lines.extend([
+                    Line::from(format!(
+                        "{} Use Windows Authentication (W to toggle)",
+                        if state.call_data_windows_auth {
+                            "[x]"
+                        } else {
+                            "[ ]"
+                        }
+                    )),
+                    Line::from(""),
+                    Line::from("Tab cycles fields. Press B to browse the tables and views."),
+                ]);
+            }
+            if let Some(label) = sources::label(state) {
+                let name_field = page_field_count(state) - 1;
+                lines.extend([
+                    Line::from(""),
+                    Line::from(format!(
+                        "{} Source name: {}",
+                        focus_prefix(name_field),
+                        state.source_name.value
+                    )),
+                ]);
+                if let Some(err) = sources::name_error(state) {
+                    lines.push(Line::from(format!("Error: {}", err)));
+                }
+                lines.insert(
+                    0,
+                    Line::from(format!("{}  (S = Next source, X = Remove)", label)),
+                );
+                lines.insert(1, Line::from(""));
+            }
+            if multi_datasource {
+                lines.push(Line::from(
+                    "Press N to add another call data source (e.g. separate Fire and EMS CAD).",
+                ));
+            }
+            lines.push(Line::from("Press P for saved connection profiles."));
+            Text::from(lines)
         }
         Page::Database => {
             let create_new = state.db_kind == DbKind::Local;
@@ -3146,23 +4423,30 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                 };
 
                 lines.push(Line::from(""));
+                let mut next = 0;
                 if state.new_db_location == NewDbLocation::SpecificPath {
                     lines.push(Line::from(format!(
                         "{} Database path: {}",
                         f(0),
                         state.new_db_specific_path.value
                     )));
-                    lines.push(Line::from(format!(
-                        "{} Max DB size / storage allocation (GB): {}",
-                        f(1),
-                        state.new_db_max_size_gb.value
-                    )));
-                } else {
-                    lines.push(Line::from(format!(
-                        "{} Max DB size / storage allocation (GB): {}",
-                        f(0),
-                        state.new_db_max_size_gb.value
-                    )));
+                    lines.extend(field_rules::inline_error_line(&state.new_db_specific_path));
+                    next = 1;
+                }
+                lines.push(Line::from(format!(
+                    "{} Max DB size / storage allocation (GB): {}",
+                    f(next),
+                    state.new_db_max_size_gb.value
+                )));
+                lines.extend(field_rules::inline_error_line(&state.new_db_max_size_gb));
+                lines.push(Line::from(format!(
+                    "{} {}: {}",
+                    f(next + 1),
+                    collation_label(state),
+                    state.new_db_collation.value
+                )));
+                if state.new_db_location == NewDbLocation::SpecificPath {
+                    lines.push(Line::from(new_db_path_help(state)));
                 }
 
                 lines.push(Line::from(""));
@@ -3234,35 +4518,70 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                         f(0),
                         state.db_host.value
                     )));
+                    lines.extend(field_rules::inline_error_line(&state.db_host));
                     lines.push(Line::from(format!(
                         "{} Port: {}",
                         f(1),
                         state.db_port.value
                     )));
+                    lines.extend(field_rules::inline_error_line(&state.db_port));
                     lines.push(Line::from(format!(
                         "{} Database: {}",
                         f(2),
                         state.db_database.value
                     )));
-                    lines.push(Line::from(format!(
-                        "{} Username: {}",
-                        f(3),
-                        state.db_user.value
-                    )));
-                    lines.push(Line::from(format!(
-                        "{} Password: {}",
-                        f(4),
-                        state.db_password.display()
-                    )));
+                    if state.db_windows_auth {
+                        lines.push(Line::from(format!(
+                            "{} Username: {}",
+                            f(3),
+                            WINDOWS_AUTH_PLACEHOLDER
+                        )));
+                        lines.push(Line::from(format!(
+                            "{} Password: {}",
+                            f(4),
+                            WINDOWS_AUTH_PLACEHOLDER
+                        )));
+                    } else {
+                        lines.push(Line::from(format!(
+                            "{} Username: {}",
+                            f(3),
+                            state.db_user.value
+                        )));
+                        lines.push(Line::from(format!(
+                            "{} Password: {}",
+                            f(4),
+                            state.db_password.display()
+                        )));
+                    }
                     lines.push(Line::from(format!(
                         "{} TLS: {} (Left/Right to change)",
                         tls_prefix, state.db_ssl_mode
                     )));
+                    lines.push(Line::from(format!(
+                        "{} Use Windows Authentication, SQL Server only (W to toggle)",
+                        if state.db_windows_auth { "[x]" } else { "[ ]" }
+                    )));
                     lines.push(Line::from(""));
-                    lines.push(Line::from("Press T to Test Connection."));
+                    lines.push(Line::from(
+                        "Press T to Test Connection. Press P for saved connection profiles.",
+                    ));
                 }
             }
 
+            let app_idx = page_field_count(state) - 1;
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!(
+                "{} {}: {}",
+                if matches!(state.focus, FocusTarget::Field(j) if j == app_idx) {
+                    ">"
+                } else {
+                    " "
+                },
+                APP_LOGIN_LABEL,
+                state.app_login.value
+            )));
+            lines.push(Line::from(APP_LOGIN_HELP));
+
             let status = match state.db_test_status {
                 DbTestStatus::Idle => "Idle",
                 DbTestStatus::Testing => "Testing",
@@ -3325,6 +4644,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                         "{} Custom path: {}",
                         p, state.storage_custom_path.value
                     )));
+                    lines.extend(field_rules::inline_error_line(&state.storage_custom_path));
                 }
                 lines.push(Line::from(format!(
                     "Retention policy: {} (P to change)",
@@ -3345,6 +4665,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                         "{} Max disk usage (GB): {}",
                         p, state.max_disk_gb.value
                     )));
+                    lines.extend(field_rules::inline_error_line(&state.max_disk_gb));
                 }
                 lines.push(Line::from(""));
                 lines.push(Line::from(
@@ -3354,6 +4675,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                 lines.push(Line::from(""));
                 lines.push(Line::from("Up/Down toggles defaults/custom."));
             }
+            lines.extend(storage_fs::lines(state).into_iter().map(Line::from));
 
             Text::from(lines)
         }
@@ -3392,15 +4714,9 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                     "{} Months: {}",
                     p0, state.hot_retention_custom_months.value
                 )));
-                let n = state
-                    .hot_retention_custom_months
-                    .value
-                    .trim()
-                    .parse::<u32>()
-                    .unwrap_or(0);
-                if n == 0 || n > 240 {
-                    lines.push(Line::from("Error: Enter a valid number of months."));
-                }
+                lines.extend(field_rules::inline_error_line(
+                    &state.hot_retention_custom_months,
+                ));
                 lines.push(Line::from(""));
                 lines.push(Line::from("Tab to edit the months value."));
             } else {
@@ -3411,6 +4727,12 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
             lines.push(Line::from("R cycles 12/18/custom."));
             Text::from(lines)
         }
+        Page::Archive if !state.licensed.allows(features::ARCHIVE) => Text::from(vec![
+            Line::from(features::not_included_message("The archive module")),
+            Line::from(""),
+            Line::from("Call data stays in the hot database; no archive files are written."),
+            Line::from("Select Next to continue."),
+        ]),
         Page::Archive => {
             let r_ndjson = if state.archive_format == ArchiveFormatChoice::ZipNdjson {
                 "(x)"
@@ -3449,6 +4771,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
             } else {
                 "[ ]"
             };
+            let encrypt = if state.archive_encrypt { "[x]" } else { "[ ]" };
 
             let mut lines = vec![
                 Line::from("Configure cold storage (archive) settings."),
@@ -3460,51 +4783,55 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                     "{} Destination folder: {}",
                     p0, state.archive_destination.value
                 )),
-                Line::from(format!(
-                    "{} Max archive usage cap (GB): {}",
-                    p1, state.archive_max_usage_gb.value
-                )),
-                Line::from(format!(
-                    "{} Schedule day (1-28): {}",
-                    p2, state.archive_schedule_day_of_month.value
-                )),
-                Line::from(format!(
-                    "{} Schedule time (HH:MM): {}",
-                    p3, state.archive_schedule_time_local.value
-                )),
-                Line::from(format!("{} Catch-up on startup (Space)", catch_up)),
             ];
-
-            // Inline validation errors (Windows-installer tone; block Next when invalid).
-            if state.archive_destination.value.trim().is_empty() {
-                lines.push(Line::from("Error: Archive destination folder is required."));
-            } else if state
-                .archive_max_usage_gb
-                .value
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(0)
-                == 0
-            {
-                lines.push(Line::from(
-                    "Error: Max archive usage must be a positive number.",
-                ));
-            } else {
-                let day = state
-                    .archive_schedule_day_of_month
-                    .value
-                    .trim()
-                    .parse::<u32>()
-                    .unwrap_or(0);
-                if !(1..=28).contains(&day) {
-                    lines.push(Line::from("Error: Schedule day must be between 1 and 28."));
-                } else if !is_valid_time_hhmm(state.archive_schedule_time_local.value.trim()) {
-                    lines.push(Line::from("Error: Schedule time must be HH:MM."));
+            // The destination starts out blank, before its own rule applies.
+            match field_rules::inline_error_line(&state.archive_destination) {
+                Some(line) => lines.push(line),
+                None if state.archive_destination.value.trim().is_empty() => {
+                    lines.push(error_line("validation.archiveDestinationRequired"));
                 }
+                None => {}
+            }
+            let fields = [
+                (
+                    format!(
+                        "{} Max archive usage cap (GB): {}",
+                        p1, state.archive_max_usage_gb.value
+                    ),
+                    &state.archive_max_usage_gb,
+                ),
+                (
+                    format!(
+                        "{} Schedule day (1-28): {}",
+                        p2, state.archive_schedule_day_of_month.value
+                    ),
+                    &state.archive_schedule_day_of_month,
+                ),
+                (
+                    format!(
+                        "{} Schedule time (HH:MM): {}",
+                        p3, state.archive_schedule_time_local.value
+                    ),
+                    &state.archive_schedule_time_local,
+                ),
+            ];
+            for (text, input) in fields {
+                lines.push(Line::from(text));
+                lines.extend(field_rules::inline_error_line(input));
             }
+            lines.push(Line::from(format!(
+                "{} Catch-up on startup (Space)",
+                catch_up
+            )));
+            lines.push(Line::from(format!(
+                "{} Encrypt archives at rest (E)",
+                encrypt
+            )));
 
             lines.push(Line::from(""));
-            lines.push(Line::from("Tab cycles fields. F changes format."));
+            lines.push(Line::from(
+                "Tab cycles fields. F changes format. E toggles encryption.",
+            ));
             Text::from(lines)
         }
         Page::Consent => {
@@ -3532,80 +4859,33 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
             }
             lines.push(Line::from(""));
             lines.push(Line::from(
-                "This setting is stored locally. Network sync is not performed in this phase.",
+                "When enabled, this is sent once after install (queued and retried if offline).",
             ));
             Text::from(lines)
         }
-        Page::Mapping => Text::from(vec![
-            Line::from("Schema Mapping"),
-            Line::from(""),
-            Line::from("This page will be implemented next (two-pane mapper)."),
-            Line::from(""),
-            Line::from("Select Next to continue."),
-        ]),
-        Page::Ready => Text::from(vec![
-            Line::from("Setup is ready to begin installation."),
-            Line::from(""),
-            Line::from(format!(
-                "Mode: {}",
-                match state.install_mode {
-                    InstallMode::Windows => "Windows",
-                    InstallMode::Docker => "Docker / Linux",
-                }
-            )),
-            Line::from(format!("Install path: {}", state.destination_path.value)),
-            Line::from(format!(
-                "Config DB engine: {}",
-                match state.db_engine {
-                    DbEngine::SqlServer => "SQL Server",
-                    DbEngine::Postgres => "PostgreSQL",
-                }
-            )),
-            Line::from(format!(
-                "Hot retention: {} months",
-                match state.hot_retention_choice {
-                    HotRetentionChoice::Months12 => 12,
-                    HotRetentionChoice::Months18 => 18,
-                    HotRetentionChoice::Custom => state
-                        .hot_retention_custom_months
-                        .value
-                        .trim()
-                        .parse::<u32>()
-                        .unwrap_or(18),
-                }
-            )),
-            Line::from(format!(
-                "Archive format: {}",
-                match state.archive_format {
-                    ArchiveFormatChoice::ZipNdjson => "ZIP + NDJSON",
-                    ArchiveFormatChoice::ZipCsv => "ZIP + CSV",
-                }
-            )),
-            Line::from(format!(
-                "Archive destination: {}",
-                if state.archive_destination.value.trim().is_empty() {
-                    "(not set)"
+        // Interactive two-pane mapper, drawn by `draw_mapping_page`.
+        Page::Mapping => Text::default(),
+        Page::Ready => {
+            let mut lines = vec![
+                Line::from("Setup is ready to begin installation."),
+                Line::from(""),
+            ];
+            lines.extend(ready_summary(state).into_iter().map(Line::from));
+            lines.extend([
+                Line::from(format!(
+                    "Plan only (dry run): {}  (press P to toggle)",
+                    if state.dry_run { "Yes" } else { "No" }
+                )),
+                Line::from("Passwords are not shown here."),
+                Line::from(""),
+                Line::from(if state.dry_run {
+                    "Select Install to write an install plan without making changes."
                 } else {
-                    state.archive_destination.value.trim()
-                }
-            )),
-            Line::from(format!(
-                "Archive cap (GB): {}",
-                state.archive_max_usage_gb.value.trim()
-            )),
-            Line::from(format!(
-                "Archive schedule: day {} at {}",
-                state.archive_schedule_day_of_month.value.trim(),
-                state.archive_schedule_time_local.value.trim()
-            )),
-            Line::from(format!(
-                "Consent to Sync: {}",
-                if state.consent_to_sync { "Yes" } else { "No" }
-            )),
-            Line::from("Passwords are not shown here."),
-            Line::from(""),
-            Line::from("Select Install to begin."),
-        ]),
+                    "Select Install to begin."
+                }),
+            ]);
+            Text::from(lines)
+        }
         Page::Installing => {
             let pct = state
                 .install_progress
@@ -3617,17 +4897,8 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                 .as_ref()
                 .map(|p| p.message.clone())
                 .unwrap_or_default();
-            let width = 30usize;
-            let filled = ((pct as usize) * width) / 100;
-            let bar = format!(
-                "[{}{}] {}%",
-                "#".repeat(filled),
-                " ".repeat(width.saturating_sub(filled)),
-                pct
-            );
-
             let mut lines = vec![
-                Line::from(bar),
+                Line::from(progress_bar(pct)),
                 Line::from(format!("Current action: {}", msg)),
                 Line::from(""),
             ];
@@ -3638,6 +4909,12 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
             if state.install_detail.is_empty() {
                 lines.push(Line::from("(no details yet)"));
             }
+            lines.push(Line::from(""));
+            lines.push(Line::from(if state.install_paused {
+                "Paused. Press P to resume. Press L to view the full log."
+            } else {
+                "Press P to pause at the next safe point. Press L to view the full log."
+            }));
 
             Text::from(lines)
         }
@@ -3658,6 +4935,20 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                 }
                 lines.push(Line::from(""));
             }
+            if let Some(p) = state.archive_run_progress.as_ref() {
+                lines.push(Line::from(format!(
+                    "Archive: {} {}",
+                    progress_bar(p.percent),
+                    p.message
+                )));
+            }
+            if let Some(r) = state.archive_run_result.as_ref() {
+                lines.push(Line::from(r.clone()));
+            }
+            if !state.archive_run_active && state.licensed.allows(features::ARCHIVE) {
+                lines.push(Line::from("Press A to run archive catch-up now."));
+            }
+            lines.push(Line::from("Press L to view the full log."));
             lines.push(Line::from("Select Finish to exit."));
             Text::from(lines)
         }
@@ -3671,8 +4962,11 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
     });
 
     if state.page == Page::Mapping {
-        draw_mapping_page(f, content_inner, state);
+        draw_mapping_page(f, content_inner, state, stacked_mapping);
     } else {
+        if state.page == Page::License {
+            mouse::record(state, content_inner, HitTarget::License);
+        }
         let content = Paragraph::new(content_text)
             .alignment(Alignment::Left)
             .wrap(Wrap { trim: false });
@@ -3686,6 +4980,7 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
     if let Some(modal) = state.modal.as_ref() {
         match modal {
             Modal::ConfirmCancel => draw_cancel_modal(f, window_area, state),
+            Modal::Help => draw_help_modal(f, window_area, state),
             Modal::Message {
                 title,
                 body,
@@ -3697,19 +4992,107 @@ fn draw(area: Rect, f: &mut ratatui::Frame<'_>, state: &WizardState) {
                 actions,
                 selected,
                 pending: _,
-            } => draw_confirm_mapping_modal(f, window_area, title, body, actions, *selected),
+            } => draw_confirm_mapping_modal(f, window_area, title, body, actions, *selected, state),
             Modal::BrowseFolder {
                 current,
                 entries,
                 selected,
-            } => draw_browse_folder_modal(f, window_area, current, entries, *selected),
+            } => draw_browse_folder_modal(f, window_area, current, entries, *selected, state),
+            Modal::Transforms {
+                target_id,
+                selected,
+                prompt,
+                error,
+            } => transforms::draw(
+                f,
+                window_area,
+                state,
+                target_id,
+                *selected,
+                prompt.as_ref(),
+                error.as_deref(),
+            ),
+            Modal::MappingCsv {
+                import,
+                path,
+                error,
+            } => mapping_csv::draw(f, window_area, state, *import, path, error.as_deref()),
+            Modal::ResumeDraft { draft, resume } => {
+                draft::draw(f, window_area, state, draft, *resume)
+            }
+            Modal::SourceObjects {
+                filter,
+                objects,
+                truncated,
+                selected,
+                error,
+            } => source_objects::draw(
+                f,
+                window_area,
+                state,
+                filter,
+                objects.as_deref(),
+                *truncated,
+                *selected,
+                error.as_deref(),
+            ),
+            Modal::Profiles {
+                profiles: list,
+                selected,
+                name,
+                error,
+                ..
+            } => profiles::draw(
+                f,
+                window_area,
+                state,
+                list,
+                *selected,
+                name.as_deref(),
+                error.as_deref(),
+            ),
+            #[cfg(target_os = "linux")]
+            Modal::Elevate { plan, approve, .. } => {
+                elevate::draw(f, window_area, state, plan, *approve)
+            }
         }
     }
 
+    if let Some(viewer) = state.log_viewer.as_ref() {
+        viewer.draw(f, window_area);
+    }
+
     // Prevent unused warning for outer
     let _ = outer;
 }
 
+fn draw_too_small(f: &mut ratatui::Frame<'_>, area: Rect) {
+    let text = Text::from(vec![
+        Line::from("CADalytix Setup"),
+        Line::from(""),
+        Line::from(format!(
+            "The terminal is too small ({}x{}).",
+            area.width, area.height
+        )),
+        Line::from(format!(
+            "Resize it to at least {}x{} to continue.",
+            MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+        )),
+    ]);
+    let top = area.y + area.height.saturating_sub(4) / 2;
+    let p = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(
+        p,
+        Rect {
+            y: top,
+            height: area.height.saturating_sub(top - area.y),
+            ..area
+        },
+    );
+}
+
 fn centered_window(area: Rect, width: u16, height: u16) -> (Rect, Rect) {
     let w = width.min(area.width.saturating_sub(2)).max(60);
     let h = height.min(area.height.saturating_sub(2)).max(20);
@@ -3735,7 +5118,7 @@ fn draw_buttons(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState) {
         back_enabled,
     );
     let next = button_text(
-        next_label(state.page),
+        &next_label(state.page),
         matches!(state.focus, FocusTarget::Button(ButtonFocus::Next)),
         next_enabled,
     );
@@ -3745,6 +5128,15 @@ fn draw_buttons(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState) {
         cancel_enabled,
     );
 
+    let rects = mouse::right_aligned_spans(area, &[&back, &next, &cancel]);
+    for (rect, b) in
+        rects
+            .into_iter()
+            .zip([ButtonFocus::Back, ButtonFocus::Next, ButtonFocus::Cancel])
+    {
+        mouse::record(state, rect, HitTarget::Button(b));
+    }
+
     let line = Line::from(vec![
         back,
         ratatui::text::Span::raw(" "),
@@ -3755,11 +5147,24 @@ fn draw_buttons(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState) {
 
     let p = Paragraph::new(Text::from(line)).alignment(Alignment::Right);
     f.render_widget(p, area);
+
+    let help_keys = state
+        .keymap
+        .keys(Action::Help)
+        .iter()
+        .map(|k| keymap::key_label(*k))
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let help = Paragraph::new(Line::from(ratatui::text::Span::styled(
+        format!("{} Help", help_keys),
+        theme::current().hint(),
+    )));
+    f.render_widget(help, area);
 }
 
-fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState) {
+fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState, stacked: bool) {
     let top_h = 6u16.min(area.height.saturating_sub(6)).max(3);
-    let preview_h = 4u16.min(area.height.saturating_sub(3)).max(3);
+    let preview_h = 5u16.min(area.height.saturating_sub(3)).max(3);
     let rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -3809,8 +5214,12 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
     let ov = if state.mapping_override { "x" } else { " " };
 
     let mut top_lines: Vec<Line> = Vec::new();
+    let source_prefix = sources::label(state)
+        .map(|l| format!("{} - ", l))
+        .unwrap_or_default();
     top_lines.push(Line::from(format!(
-        "We found {} fields in your source export.{}",
+        "{}We found {} fields in your source export.{}",
+        source_prefix,
         state.source_fields.len(),
         scan_suffix
     )));
@@ -3831,16 +5240,46 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
             required_unmapped.join(", ")
         )));
     }
+    let pending = pending_suggestion_count(state);
+    if pending > 0 {
+        top_lines.push(Line::from(format!(
+            "Suggested mappings to confirm: {} (C = Confirm, U = Reject)",
+            pending
+        )));
+    }
+    if let Some(name) = sources::unmapped(state) {
+        top_lines.push(Line::from(format!(
+            "Source '{}' still needs its mapping (S = Next source).",
+            name
+        )));
+    }
     top_lines.push(Line::from(
-        "Select a source field, then select a target field. (U = Unassign, O = Override, / = Search)",
+        "Select a source field, then select a target field. (A = Auto-map, U = Unassign, O = Override, T = Transforms, E/I = Export/Import CSV, / = Search)",
     ));
 
     let top = Paragraph::new(Text::from(top_lines)).wrap(Wrap { trim: false });
     f.render_widget(top, rows[0]);
+    for (offset, focus) in [
+        (1, MappingFocus::OverrideToggle),
+        (2, MappingFocus::DemoToggle),
+    ] {
+        if offset < rows[0].height {
+            let row = Rect {
+                y: rows[0].y + offset,
+                height: 1,
+                ..rows[0]
+            };
+            mouse::record(state, row, HitTarget::Mapping(focus));
+        }
+    }
 
-    // Middle: two panes
+    // Middle: two panes (side by side, or source above target when stacked)
     let cols = Layout::default()
-        .direction(Direction::Horizontal)
+        .direction(if stacked {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        })
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(rows[1]);
 
@@ -3872,6 +5311,16 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
     ))))
     .wrap(Wrap { trim: false });
     f.render_widget(src_search, source_rows[0]);
+    mouse::record(
+        state,
+        source_rows[0],
+        HitTarget::Mapping(MappingFocus::SourceSearch),
+    );
+    mouse::record(
+        state,
+        source_rows[1],
+        HitTarget::Mapping(MappingFocus::SourceList),
+    );
 
     let src_q = state.source_search.value.trim().to_ascii_lowercase();
     let filtered_sources: Vec<&SourceField> = state
@@ -3907,12 +5356,14 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
                 .map(|v| !v.is_empty())
                 .unwrap_or(false);
             let prefix = if mapped { "* " } else { "  " };
-            let selected = i == src_sel;
-            let style = if selected && src_focus_list {
-                Style::default().add_modifier(Modifier::REVERSED)
-            } else {
-                Style::default()
+            let row = Rect {
+                y: source_rows[1].y + (i - src_start) as u16,
+                height: 1,
+                ..source_rows[1]
             };
+            mouse::record(state, row, HitTarget::SourceRow(i));
+            let selected = i == src_sel;
+            let style = theme::current().focus_if(selected && src_focus_list);
             src_lines.push(Line::from(ratatui::text::Span::styled(
                 format!("{}{}", prefix, s.display_name),
                 style,
@@ -3950,6 +5401,16 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
     ))))
     .wrap(Wrap { trim: false });
     f.render_widget(tgt_search, target_rows[0]);
+    mouse::record(
+        state,
+        target_rows[0],
+        HitTarget::Mapping(MappingFocus::TargetSearch),
+    );
+    mouse::record(
+        state,
+        target_rows[1],
+        HitTarget::Mapping(MappingFocus::TargetList),
+    );
 
     let tgt_q = state.target_search.value.trim().to_ascii_lowercase();
     let filtered_targets: Vec<&TargetField> = state
@@ -3986,18 +5447,29 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
         {
             let mapped_source = state.target_to_source.get(&t.id).cloned();
             let mapped = mapped_source.is_some();
-            let prefix = if mapped { "* " } else { "  " };
+            let suggestion = pending_suggestion(state, &t.id);
+            let prefix = match (suggestion, mapped) {
+                (Some(_), _) => "? ",
+                (None, true) => "* ",
+                (None, false) => "  ",
+            };
+            let row = Rect {
+                y: target_rows[1].y + (i - tgt_start) as u16,
+                height: 1,
+                ..target_rows[1]
+            };
+            mouse::record(state, row, HitTarget::TargetRow(i));
             let selected = i == tgt_sel;
             let mut style = Style::default();
             if selected && tgt_focus_list {
-                style = style.add_modifier(Modifier::REVERSED);
+                style = theme::current().focused();
             } else if mapped_source
                 .as_deref()
                 .zip(selected_source_id.as_deref())
                 .map(|(a, b)| a == b)
                 .unwrap_or(false)
             {
-                style = style.add_modifier(Modifier::BOLD);
+                style = theme::current().emphasis();
             }
 
             let mut line = format!("{}{}", prefix, t.name);
@@ -4005,10 +5477,20 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
                 line.push_str(" (required)");
             }
             if let Some(ms) = mapped_source {
-                line.push_str(&format!(
-                    " — mapped to {}",
-                    mapping_source_display(state, &ms)
-                ));
+                match suggestion {
+                    Some(s) => line.push_str(&format!(
+                        " — suggested: {} ({:.0}%)",
+                        mapping_source_display(state, &ms),
+                        s.confidence * 100.0
+                    )),
+                    None => line.push_str(&format!(
+                        " — mapped to {}",
+                        mapping_source_display(state, &ms)
+                    )),
+                }
+            }
+            if let Some(n) = state.mapping_transforms.get(&t.id).map(Vec::len) {
+                line.push_str(&format!(" [{} transform(s)]", n));
             }
             tgt_lines.push(Line::from(ratatui::text::Span::styled(line, style)));
         }
@@ -4031,8 +5513,18 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
         .map(|t| mapping_target_name(state, t))
         .collect::<Vec<_>>()
         .join(", ");
+    // Sampled values let the operator check the column before committing the mapping.
+    let samples = match selected_source_id
+        .as_deref()
+        .and_then(|id| state.source_fields.iter().find(|s| s.id == id))
+    {
+        Some(s) if s.sample_values.is_empty() => "(no sample rows)".to_string(),
+        Some(s) => s.sample_values.join(" | "),
+        None => String::new(),
+    };
     let preview_lines = vec![
         Line::from(format!("Source: {}", src_name)),
+        Line::from(format!("  Sample: {}", samples)),
         Line::from("  ↓"),
         Line::from(format!("Target(s): {}", target_names)),
         Line::from(format!(
@@ -4047,13 +5539,12 @@ fn draw_mapping_page(f: &mut ratatui::Frame<'_>, area: Rect, state: &WizardState
 }
 
 fn button_text(label: &str, focused: bool, enabled: bool) -> ratatui::text::Span<'static> {
-    let mut style = Style::default();
-    if !enabled {
-        style = style.fg(Color::DarkGray);
-    }
-    if focused && enabled {
-        style = style.add_modifier(Modifier::REVERSED);
-    }
+    let theme = theme::current();
+    let style = if !enabled {
+        theme.disabled()
+    } else {
+        theme.focus_if(focused)
+    };
     ratatui::text::Span::styled(format!("[ {} ]", label), style)
 }
 
@@ -4091,28 +5582,68 @@ fn draw_cancel_modal(f: &mut ratatui::Frame<'_>, window_area: Rect, state: &Wiza
 
     let yes_focused = focused_button(state) == ButtonFocus::Cancel;
     let no_focused = focused_button(state) == ButtonFocus::Next;
-    let yes = ratatui::text::Span::styled(
-        "[ Yes, cancel ]",
-        if yes_focused {
-            Style::default().add_modifier(Modifier::REVERSED)
-        } else {
-            Style::default()
-        },
-    );
-    let no = ratatui::text::Span::styled(
-        "[ No ]",
-        if no_focused {
-            Style::default().add_modifier(Modifier::REVERSED)
-        } else {
-            Style::default()
-        },
-    );
+    let yes =
+        ratatui::text::Span::styled("[ Yes, cancel ]", theme::current().focus_if(yes_focused));
+    let no = ratatui::text::Span::styled("[ No ]", theme::current().focus_if(no_focused));
+
+    for (i, rect) in mouse::right_aligned_spans(buttons_area, &[&yes, &no])
+        .into_iter()
+        .enumerate()
+    {
+        mouse::record(state, rect, HitTarget::ModalButton(i));
+    }
 
     let line = Line::from(vec![yes, ratatui::text::Span::raw(" "), no]);
     let p = Paragraph::new(Text::from(line)).alignment(Alignment::Right);
     f.render_widget(p, buttons_area);
 }
 
+fn draw_help_modal(f: &mut ratatui::Frame<'_>, window_area: Rect, state: &WizardState) {
+    let rows = keymap::help_rows(&state.keymap, state.page);
+    let modal_w = 72u16.min(window_area.width.saturating_sub(4)).max(40);
+    let modal_h = (rows.len() as u16 + 5)
+        .min(window_area.height.saturating_sub(2))
+        .max(7);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Keyboard Shortcuts — {}",
+        page_title(state.page, state.install_mode)
+    ));
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                ratatui::text::Span::styled(format!("{:<16}", keys), theme::current().emphasis()),
+                ratatui::text::Span::raw(*description),
+            ])
+        })
+        .collect();
+    let p = Paragraph::new(Text::from(lines)).block(block);
+    f.render_widget(p, area);
+
+    let buttons_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - 2,
+        width: area.width - 2,
+        height: 1,
+    };
+    let close = ratatui::text::Span::styled("[ Close ]", theme::current().focused());
+    for rect in mouse::right_aligned_spans(buttons_area, &[&close]) {
+        mouse::record(state, rect, HitTarget::ModalButton(0));
+    }
+    let p = Paragraph::new(Text::from(Line::from(close))).alignment(Alignment::Right);
+    f.render_widget(p, buttons_area);
+}
+
 fn draw_message_modal(
     f: &mut ratatui::Frame<'_>,
     window_area: Rect,
@@ -4145,12 +5676,14 @@ fn draw_message_modal(
     };
     let ok = ratatui::text::Span::styled(
         "[ OK ]",
-        if matches!(state.focus, FocusTarget::Button(ButtonFocus::Next)) {
-            Style::default().add_modifier(Modifier::REVERSED)
-        } else {
-            Style::default()
-        },
+        theme::current().focus_if(matches!(
+            state.focus,
+            FocusTarget::Button(ButtonFocus::Next)
+        )),
     );
+    for rect in mouse::right_aligned_spans(buttons_area, &[&ok]) {
+        mouse::record(state, rect, HitTarget::ModalButton(0));
+    }
     let line = Line::from(vec![ok]);
     let p = Paragraph::new(Text::from(line)).alignment(Alignment::Right);
     f.render_widget(p, buttons_area);
@@ -4163,6 +5696,7 @@ fn draw_confirm_mapping_modal(
     body: &str,
     actions: &[MappingModalAction],
     selected: usize,
+    state: &WizardState,
 ) {
     let modal_w = 76u16.min(window_area.width.saturating_sub(4)).max(44);
     let modal_h = 12u16.min(window_area.height.saturating_sub(4)).max(8);
@@ -4196,19 +5730,30 @@ fn draw_confirm_mapping_modal(
         MappingModalAction::Cancel => "Cancel",
     };
 
+    let buttons: Vec<ratatui::text::Span> = actions
+        .iter()
+        .copied()
+        .enumerate()
+        .map(|(i, a)| {
+            ratatui::text::Span::styled(
+                format!("[ {} ]", label(a)),
+                theme::current().focus_if(i == selected),
+            )
+        })
+        .collect();
+    let refs: Vec<&ratatui::text::Span> = buttons.iter().collect();
+    for (i, rect) in mouse::right_aligned_spans(rows[1], &refs)
+        .into_iter()
+        .enumerate()
+    {
+        mouse::record(state, rect, HitTarget::ModalButton(i));
+    }
+
     let mut spans: Vec<ratatui::text::Span> = Vec::new();
-    for (i, a) in actions.iter().copied().enumerate() {
+    for (i, s) in buttons.into_iter().enumerate() {
         if i > 0 {
             spans.push(ratatui::text::Span::raw(" "));
         }
-        let s = ratatui::text::Span::styled(
-            format!("[ {} ]", label(a)),
-            if i == selected {
-                Style::default().add_modifier(Modifier::REVERSED)
-            } else {
-                Style::default()
-            },
-        );
         spans.push(s);
     }
 
@@ -4223,6 +5768,7 @@ fn draw_browse_folder_modal(
     current: &std::path::Path,
     entries: &[std::path::PathBuf],
     selected: usize,
+    state: &WizardState,
 ) {
     let modal_w = 78u16.min(window_area.width.saturating_sub(4)).max(48);
     let modal_h = 16u16.min(window_area.height.saturating_sub(4)).max(10);
@@ -4267,6 +5813,7 @@ fn draw_browse_folder_modal(
     let list_height = rows[1].height as usize;
     let start = selected.saturating_sub(list_height / 2);
     let end = (start + list_height).min(entries.len());
+    mouse::record(state, rows[1], HitTarget::BrowseList);
 
     let mut lines: Vec<Line> = Vec::new();
     if entries.is_empty() {
@@ -4274,12 +5821,14 @@ fn draw_browse_folder_modal(
     } else {
         for (i, p) in entries.iter().enumerate().take(end).skip(start) {
             let name = p.file_name().and_then(|s| s.to_str()).unwrap_or("<folder>");
-            let focused = i == selected;
-            let style = if focused {
-                Style::default().add_modifier(Modifier::REVERSED)
-            } else {
-                Style::default()
+            let row = Rect {
+                y: rows[1].y + (i - start) as u16,
+                height: 1,
+                ..rows[1]
             };
+            mouse::record(state, row, HitTarget::BrowseEntry(i));
+            let focused = i == selected;
+            let style = theme::current().focus_if(focused);
             lines.push(Line::from(ratatui::text::Span::styled(
                 name.to_string(),
                 style,
@@ -4297,3 +5846,65 @@ fn draw_browse_folder_modal(
     .wrap(Wrap { trim: true });
     f.render_widget(footer, rows[2]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_layout_thresholds() {
+        let wizard = |banner, stacked_mapping| ScreenLayout::Wizard {
+            banner,
+            stacked_mapping,
+        };
+        assert_eq!(screen_layout(59, 40), ScreenLayout::TooSmall);
+        assert_eq!(screen_layout(120, 19), ScreenLayout::TooSmall);
+        assert_eq!(screen_layout(60, 20), wizard(false, true));
+        assert_eq!(screen_layout(89, 40), wizard(false, false));
+        assert_eq!(screen_layout(90, 40), wizard(true, false));
+        assert_eq!(screen_layout(100, 29), wizard(true, true));
+        assert_eq!(screen_layout(100, 30), wizard(true, false));
+    }
+
+    #[test]
+    fn mapping_page_rescans_only_when_the_source_changes() {
+        let (tx, rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.mapping_demo_mode = true;
+        state.source_fields = vec![SourceField {
+            id: "s1".to_string(),
+            raw_name: "CallId".to_string(),
+            display_name: "CallId".to_string(),
+            data_type: String::new(),
+            sample_values: Vec::new(),
+        }];
+        state
+            .target_to_source
+            .insert("t1".to_string(), "s1".to_string());
+        state.mapping_scan_key = Some(mapping_scan_key(&state));
+
+        // Back from Ready with the same source: fields and mappings stay, no scan starts.
+        enter_mapping_page(&mut state, &tx);
+        assert!(!state.mapping_scanning);
+        assert_eq!(state.source_fields.len(), 1);
+        assert_eq!(
+            state.target_to_source.get("t1").map(String::as_str),
+            Some("s1")
+        );
+
+        // A different source object changes the key: the fields are scanned again.
+        state.source_object_name.value = "dbo.OtherCalls".to_string();
+        enter_mapping_page(&mut state, &tx);
+        assert!(state.mapping_scanning);
+        assert!(state.source_fields.is_empty());
+        assert_eq!(state.mapping_scan_key, Some(mapping_scan_key(&state)));
+        let Ok(UiMsg::MappingScanComplete {
+            success, columns, ..
+        }) = rx.recv_timeout(std::time::Duration::from_secs(10))
+        else {
+            panic!("expected the rescan to report back");
+        };
+        assert!(success);
+        assert!(!columns.is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! Mouse support.
+//!
+//! While drawing, the wizard records where each clickable element landed
+//! (`WizardState::hit_regions`); a mouse event is resolved against the last frame, so hit-testing
+//! always matches what is on screen. Every action is routed through `dispatch_key` with the canonical
+//! keys, so a click does exactly what the default keystrokes would, whatever the keymap says.
+//!
+//! - Left click: buttons, modal buttons, mapping toggles/search boxes/list rows, folder entries.
+//!   Clicking the selected target row maps it; clicking the selected folder opens it.
+//! - Wheel: mapping lists, the folder browser, the license text and the log viewer.
+
+use super::*;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HitTarget {
+    /// Bottom row `[ Back ] [ Next ] [ Cancel ]`.
+    Button(ButtonFocus),
+    /// Modal button by position (left to right).
+    ModalButton(usize),
+    /// Mapping toggle, search box, or list pane (wheel target).
+    Mapping(MappingFocus),
+    /// Row of the filtered source/target list.
+    SourceRow(usize),
+    TargetRow(usize),
+    /// Folder browser entry / list area.
+    BrowseEntry(usize),
+    BrowseList,
+    /// License text (wheel scrolls it).
+    License,
+}
+
+impl HitTarget {
+    fn in_modal(self) -> bool {
+        matches!(
+            self,
+            HitTarget::ModalButton(_) | HitTarget::BrowseEntry(_) | HitTarget::BrowseList
+        )
+    }
+}
+
+pub(super) fn record(state: &WizardState, area: Rect, target: HitTarget) {
+    if area.width > 0 && area.height > 0 {
+        state.hit_regions.borrow_mut().push((area, target));
+    }
+}
+
+/// Cells taken by `spans` rendered right-aligned on the single-row `area`, one space apart.
+pub(super) fn right_aligned_spans(area: Rect, spans: &[&ratatui::text::Span<'_>]) -> Vec<Rect> {
+    let total: usize =
+        spans.iter().map(|s| s.width()).sum::<usize>() + spans.len().saturating_sub(1);
+    let mut x = area.x + area.width.saturating_sub(total as u16);
+    spans
+        .iter()
+        .map(|s| {
+            let width = (s.width() as u16).min((area.x + area.width).saturating_sub(x));
+            let rect = Rect {
+                x,
+                y: area.y,
+                width,
+                height: 1,
+            };
+            x = x.saturating_add(width + 1);
+            rect
+        })
+        .collect()
+}
+
+/// Topmost region under (column, row); only modal regions while a modal is open.
+fn hit_at(state: &WizardState, column: u16, row: u16) -> Option<HitTarget> {
+    let modal_open = state.modal.is_some();
+    state
+        .hit_regions
+        .borrow()
+        .iter()
+        .rev()
+        .filter(|(_, t)| t.in_modal() == modal_open)
+        .find(|(r, _)| {
+            column >= r.x && column < r.x + r.width && row >= r.y && row < r.y + r.height
+        })
+        .map(|(_, t)| *t)
+}
+
+pub(super) fn handle_mouse(
+    state: &mut WizardState,
+    ev: MouseEvent,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    let scroll = match ev.kind {
+        MouseEventKind::ScrollUp => Some(KeyCode::Up),
+        MouseEventKind::ScrollDown => Some(KeyCode::Down),
+        MouseEventKind::Down(MouseButton::Left) => None,
+        _ => return,
+    };
+
+    if let Some(viewer) = state.log_viewer.as_mut() {
+        if let Some(code) = scroll {
+            viewer.handle_key(code);
+        }
+        return;
+    }
+
+    let Some(target) = hit_at(state, ev.column, ev.row) else {
+        return;
+    };
+    match scroll {
+        Some(code) => scroll_target(state, target, code, tx, secrets),
+        None => click_target(state, target, tx, secrets),
+    }
+}
+
+fn scroll_target(
+    state: &mut WizardState,
+    target: HitTarget,
+    code: KeyCode,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    let focus = match target {
+        HitTarget::SourceRow(_) | HitTarget::Mapping(MappingFocus::SourceList) => {
+            MappingFocus::SourceList
+        }
+        HitTarget::TargetRow(_) | HitTarget::Mapping(MappingFocus::TargetList) => {
+            MappingFocus::TargetList
+        }
+        HitTarget::BrowseEntry(_) | HitTarget::BrowseList => {
+            return dispatch_key(state, code, tx, secrets);
+        }
+        HitTarget::License => {
+            let page_code = if code == KeyCode::Up {
+                KeyCode::PageUp
+            } else {
+                KeyCode::PageDown
+            };
+            return dispatch_key(state, page_code, tx, secrets);
+        }
+        _ => return,
+    };
+    state.focus = FocusTarget::Mapping(focus);
+    dispatch_key(state, code, tx, secrets);
+}
+
+fn click_target(
+    state: &mut WizardState,
+    target: HitTarget,
+    tx: &mpsc::Sender<UiMsg>,
+    secrets: &Arc<SecretProtector>,
+) {
+    match target {
+        HitTarget::Button(b) => {
+            let enabled = match b {
+                ButtonFocus::Back => can_go_back(state.page),
+                ButtonFocus::Next => can_go_next(state),
+                ButtonFocus::Cancel => can_cancel(state.page),
+            };
+            if !enabled {
+                return;
+            }
+            set_focused_button(state, b);
+            match b {
+                // Via Esc / direct page change: Platform and License handle Enter themselves.
+                ButtonFocus::Cancel => dispatch_key(state, KeyCode::Esc, tx, secrets),
+                ButtonFocus::Back if state.page == Page::License => {
+                    state.page = prev_page(state.page);
+                }
+                _ => dispatch_key(state, KeyCode::Enter, tx, secrets),
+            }
+        }
+        HitTarget::ModalButton(i) => {
+            match state.modal.as_mut() {
+                Some(Modal::ConfirmCancel) => {
+                    // [ Yes, cancel ] [ No ]
+                    let b = if i == 0 {
+                        ButtonFocus::Cancel
+                    } else {
+                        ButtonFocus::Next
+                    };
+                    state.focus = FocusTarget::Button(b);
+                }
+                Some(Modal::ConfirmMapping { selected, .. }) => *selected = i,
+                Some(Modal::ResumeDraft { resume, .. }) => *resume = i == 0,
+                #[cfg(target_os = "linux")]
+                Some(Modal::Elevate { approve, .. }) => *approve = i == 0,
+                Some(Modal::Message { .. })
+                | Some(Modal::Help)
+                | Some(Modal::Transforms { .. })
+                | Some(Modal::MappingCsv { .. }) => {}
+                _ => return,
+            }
+            dispatch_key(state, KeyCode::Enter, tx, secrets);
+        }
+        HitTarget::Mapping(focus) => {
+            state.focus = FocusTarget::Mapping(focus);
+            if matches!(
+                focus,
+                MappingFocus::DemoToggle | MappingFocus::OverrideToggle
+            ) {
+                dispatch_key(state, KeyCode::Char(' '), tx, secrets);
+            }
+        }
+        HitTarget::SourceRow(i) => {
+            state.focus = FocusTarget::Mapping(MappingFocus::SourceList);
+            state.source_list_index = i;
+            dispatch_key(state, KeyCode::Enter, tx, secrets);
+        }
+        HitTarget::TargetRow(i) => {
+            let ids = filtered_target_ids(state);
+            let already_selected =
+                matches!(state.focus, FocusTarget::Mapping(MappingFocus::TargetList))
+                    && state.target_list_index == i
+                    && state.selected_target_id.as_ref() == ids.get(i);
+            state.focus = FocusTarget::Mapping(MappingFocus::TargetList);
+            state.target_list_index = i;
+            state.selected_target_id = ids.get(i).cloned();
+            if already_selected {
+                dispatch_key(state, KeyCode::Enter, tx, secrets);
+            }
+        }
+        HitTarget::BrowseEntry(i) => {
+            if let Some(
+                Modal::BrowseFolder { selected, .. }
+                | Modal::SourceObjects { selected, .. }
+                | Modal::Profiles { selected, .. },
+            ) = state.modal.as_mut()
+            {
+                if *selected != i {
+                    *selected = i;
+                    return;
+                }
+            }
+            dispatch_key(state, KeyCode::Enter, tx, secrets);
+        }
+        HitTarget::BrowseList | HitTarget::License => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn right_aligned_spans_match_rendered_buttons() {
+        let area = Rect {
+            x: 10,
+            y: 5,
+            width: 40,
+            height: 1,
+        };
+        let back = ratatui::text::Span::raw("[ Back ]");
+        let next = ratatui::text::Span::raw("[ Next ]");
+        let cancel = ratatui::text::Span::raw("[ Cancel ]");
+        let rects = right_aligned_spans(area, &[&back, &next, &cancel]);
+        // 8 + 1 + 8 + 1 + 10 = 28 cells ending at x = 50.
+        assert_eq!(rects[0].x, 22);
+        assert_eq!(rects[1].x, 31);
+        assert_eq!(rects[2].x, 40);
+        assert_eq!(rects[2].x + rects[2].width, 50);
+        assert!(rects.iter().all(|r| r.y == 5 && r.height == 1));
+    }
+
+    #[test]
+    fn modal_regions_shadow_the_page_underneath() {
+        let mut state = WizardState::new();
+        let full = Rect {
+            x: 0,
+            y: 0,
+            width: 80,
+            height: 24,
+        };
+        record(&state, full, HitTarget::License);
+        record(&state, Rect { width: 5, ..full }, HitTarget::ModalButton(1));
+        assert_eq!(hit_at(&state, 2, 2), Some(HitTarget::License));
+
+        state.modal = Some(Modal::ConfirmCancel);
+        assert_eq!(hit_at(&state, 2, 2), Some(HitTarget::ModalButton(1)));
+        assert_eq!(hit_at(&state, 40, 2), None);
+    }
+}
@@ -0,0 +1,1322 @@
+//! Screen-reader friendly line mode (`--tui-plain`).
+//!
+//! Runs the same pages, state and validation as the full-screen wizard, but as sequential
+//! prompt/response lines on stdin/stdout: no alternate screen, cursor addressing or colors, so
+//! braille displays and screen readers read it top to bottom. Every prompt shows its current
+//! value in brackets (Enter keeps it, `-` clears it) and accepts `back`, `cancel` and `help`.
+//! Passwords are read without echo when stdin is a terminal.
+//!
+//! Value transforms and the mapping review sheet stay in the full-screen wizard.
+
+use super::*;
+use crossterm::event::{KeyEventKind, KeyModifiers};
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::mpsc::RecvTimeoutError;
+
+const PAGES: [Page; 15] = [
+    Page::Platform,
+    Page::Welcome,
+    Page::License,
+    Page::InstallType,
+    Page::Destination,
+    Page::DataSource,
+    Page::Database,
+    Page::Storage,
+    Page::Retention,
+    Page::Archive,
+    Page::Consent,
+    Page::Mapping,
+    Page::Ready,
+    Page::Installing,
+    Page::Complete,
+];
+
+const HELP: &[&str] = &[
+    "Commands at any prompt:",
+    "  back    return to the previous page",
+    "  cancel  quit setup",
+    "  help    show this list",
+    "Press Enter to keep the value shown in brackets, or type - to clear it.",
+];
+
+const MAPPING_HELP: &[&str] = &[
+    "Mapping commands:",
+    "  list          target fields and their mapped source columns",
+    "  sources       source columns found by the scan",
+    "  auto          suggest mappings for unmapped fields",
+    "  confirm       accept every pending suggestion",
+    "  map S T       map source column S to target field T (numbers from the lists)",
+    "  unmap T       remove the mapping of target field T",
+    "  override      allow one source column to feed several targets (toggle)",
+    "  demo          toggle sample columns, then scan again",
+    "  rescan        scan the source columns again",
+    "  done          continue to the Ready page",
+];
+
+/// What the wizard does after a page.
+enum Step {
+    Next,
+    Back,
+    Cancel,
+    /// Show the current page again (the page already moved or changed state itself).
+    Stay,
+}
+
+enum Reply<T> {
+    Value(T),
+    Back,
+    Cancel,
+}
+
+/// Unwraps a prompt reply; `back` / `cancel` end the current page.
+macro_rules! ask {
+    ($e:expr) => {
+        match $e? {
+            Reply::Value(v) => v,
+            Reply::Back => return Ok(Step::Back),
+            Reply::Cancel => return Ok(Step::Cancel),
+        }
+    };
+}
+
+struct Console<R, W> {
+    input: R,
+    out: W,
+    /// Read passwords without echo (stdin is a terminal).
+    hide_secrets: bool,
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    fn say(&mut self, line: impl AsRef<str>) -> Result<()> {
+        writeln!(self.out, "{}", line.as_ref())?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn say_all(&mut self, lines: &[&str]) -> Result<()> {
+        for line in lines {
+            self.say(line)?;
+        }
+        Ok(())
+    }
+
+    /// One line without its line ending; `None` at end of input.
+    fn read_line(&mut self, prompt: &str) -> Result<Option<String>> {
+        write!(self.out, "{}", prompt)?;
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    /// Free text showing `shown` as the current value; Enter keeps `current`.
+    fn field(&mut self, label: &str, current: &str, shown: &str) -> Result<Reply<String>> {
+        let prompt = if shown.is_empty() {
+            format!("{}: ", label)
+        } else {
+            format!("{} [{}]: ", label, shown)
+        };
+        loop {
+            let Some(line) = self.read_line(&prompt)? else {
+                return Ok(Reply::Cancel);
+            };
+            let line = line.trim();
+            match line.to_ascii_lowercase().as_str() {
+                "back" => return Ok(Reply::Back),
+                "cancel" => return Ok(Reply::Cancel),
+                "help" | "?" => self.say_all(HELP)?,
+                "" => return Ok(Reply::Value(current.to_string())),
+                "-" => return Ok(Reply::Value(String::new())),
+                _ => return Ok(Reply::Value(line.to_string())),
+            }
+        }
+    }
+
+    fn text(&mut self, label: &str, current: &str) -> Result<Reply<String>> {
+        self.field(label, current, current)
+    }
+
+    /// Password prompt: never shows the value and takes the input literally.
+    fn secret(&mut self, label: &str, current: &str) -> Result<Reply<String>> {
+        let prompt = if current.is_empty() {
+            format!("{} (not shown): ", label)
+        } else {
+            format!("{} (not shown, Enter keeps the current one): ", label)
+        };
+        let line = if self.hide_secrets {
+            write!(self.out, "{}", prompt)?;
+            self.out.flush()?;
+            let line = read_hidden()?;
+            writeln!(self.out)?;
+            line
+        } else {
+            self.read_line(&prompt)?
+        };
+        Ok(match line {
+            None => Reply::Cancel,
+            Some(l) if l.is_empty() => Reply::Value(current.to_string()),
+            Some(l) => Reply::Value(l),
+        })
+    }
+
+    /// Numbered options; Enter keeps `current` (an index into `options`).
+    fn choose(&mut self, label: &str, options: &[&str], current: usize) -> Result<Reply<usize>> {
+        self.say(label)?;
+        for (i, option) in options.iter().enumerate() {
+            self.say(format!("  {}. {}", i + 1, option))?;
+        }
+        loop {
+            let value = match self.text("Choice", &(current + 1).to_string())? {
+                Reply::Value(v) => v,
+                Reply::Back => return Ok(Reply::Back),
+                Reply::Cancel => return Ok(Reply::Cancel),
+            };
+            match value.parse::<usize>() {
+                Ok(n) if (1..=options.len()).contains(&n) => return Ok(Reply::Value(n - 1)),
+                _ => self.say(format!("Enter a number from 1 to {}.", options.len()))?,
+            }
+        }
+    }
+
+    fn yes_no(&mut self, label: &str, current: bool) -> Result<Reply<bool>> {
+        let label = format!("{} (y/n)", label);
+        loop {
+            let value = match self.text(&label, if current { "y" } else { "n" })? {
+                Reply::Value(v) => v,
+                Reply::Back => return Ok(Reply::Back),
+                Reply::Cancel => return Ok(Reply::Cancel),
+            };
+            match value.to_ascii_lowercase().as_str() {
+                "y" | "yes" => return Ok(Reply::Value(true)),
+                "n" | "no" => return Ok(Reply::Value(false)),
+                _ => self.say("Answer y or n.")?,
+            }
+        }
+    }
+}
+
+/// A line typed without echo (raw mode only while reading; no alternate screen).
+/// `None` on Ctrl+C / Ctrl+D.
+fn read_hidden() -> Result<Option<String>> {
+    enable_raw_mode()?;
+    crate::utils::crash::set_raw_terminal(true);
+    let result = read_hidden_keys();
+    crate::utils::crash::set_raw_terminal(false);
+    disable_raw_mode()?;
+    result
+}
+
+fn read_hidden_keys() -> Result<Option<String>> {
+    let mut value = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(Some(value)),
+            KeyCode::Char('c') | KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                return Ok(None)
+            }
+            KeyCode::Char(c) => value.push(c),
+            KeyCode::Backspace => {
+                value.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Why Next is blocked on the current page (`can_go_next` is false).
+pub(super) fn blocked_reason(state: &WizardState) -> String {
+    if let Some(error) = field_rules::page_error(state) {
+        return error;
+    }
+    match state.page {
+        Page::License => "Accept the license agreement to continue.".to_string(),
+        Page::InstallType => state
+            .import_config_error
+            .clone()
+            .unwrap_or_else(|| "Enter the path of the configuration file.".to_string()),
+        Page::Destination => state
+            .destination_error
+            .clone()
+            .unwrap_or_else(|| "Destination folder is required.".to_string()),
+        Page::DataSource => match state.data_source_kind {
+            DataSourceKind::File if state.file_source_folder.value.trim().is_empty() => {
+                i18n::t("validation.exportFolderRequired")
+            }
+            DataSourceKind::File => file::parse_delimiter(&state.file_source_delimiter.value)
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_default(),
+            DataSourceKind::Odbc if state.odbc_connection_string.value.trim().is_empty() => {
+                i18n::t("validation.odbcConnectionRequired")
+            }
+            DataSourceKind::Oracle if state.oracle_address.value.trim().is_empty() => {
+                i18n::t("validation.oracleAddressRequired")
+            }
+            _ => i18n::t("validation.sourceObjectRequired"),
+        },
+        Page::Database if app_login_error(state).is_some() => {
+            app_login_error(state).unwrap_or_default()
+        }
+        Page::Database if state.db_kind == DbKind::Local => {
+            if state.new_db_location == NewDbLocation::SpecificPath
+                && state.new_db_specific_path.value.trim().is_empty()
+            {
+                i18n::t("validation.dbPathRequired")
+            } else if let Some(e) = new_db_options_error(state) {
+                e
+            } else {
+                i18n::t("validation.maxDbSizePositive")
+            }
+        }
+        Page::Database => format!(
+            "The connection test must succeed first. {}",
+            state.db_test_message
+        ),
+        Page::Storage => {
+            if state.storage_location == StorageLocation::Custom
+                && state.storage_custom_path.value.trim().is_empty()
+            {
+                "Custom storage path is required.".to_string()
+            } else {
+                "Max disk usage is required.".to_string()
+            }
+        }
+        Page::Retention => i18n::t("validation.retentionMonthsInvalid"),
+        Page::Archive => {
+            let day = state
+                .archive_schedule_day_of_month
+                .value
+                .trim()
+                .parse::<u8>()
+                .unwrap_or(0);
+            if state.archive_destination.value.trim().is_empty() {
+                i18n::t("validation.archiveDestinationRequired")
+            } else if state
+                .archive_max_usage_gb
+                .value
+                .trim()
+                .parse::<u32>()
+                .unwrap_or(0)
+                == 0
+            {
+                i18n::t("validation.archiveMaxUsagePositive")
+            } else if !(1..=28).contains(&day) {
+                i18n::t("validation.scheduleDayRange")
+            } else {
+                i18n::t("validation.scheduleTimeFormat")
+            }
+        }
+        Page::Mapping => {
+            let unmapped: Vec<&str> = state
+                .target_fields
+                .iter()
+                .filter(|t| t.required && !state.target_to_source.contains_key(&t.id))
+                .map(|t| t.name.as_str())
+                .collect();
+            if state.mapping_scanning {
+                "Source columns are still being scanned.".to_string()
+            } else if let Some(e) = state.mapping_scan_error.as_ref() {
+                e.clone()
+            } else if state.source_fields.is_empty() {
+                "No source columns were found. Type rescan or back.".to_string()
+            } else if !unmapped.is_empty() {
+                format!("Map the required fields first: {}.", unmapped.join(", "))
+            } else {
+                format!(
+                    "Confirm or reject the {} suggested mapping(s) first.",
+                    pending_suggestion_count(state)
+                )
+            }
+        }
+        Page::Ready => disk_space::blocked(state).unwrap_or_default(),
+        _ => "This page is not complete.".to_string(),
+    }
+}
+
+struct Session<R, W> {
+    con: Console<R, W>,
+    state: WizardState,
+    tx: mpsc::Sender<UiMsg>,
+    rx: mpsc::Receiver<UiMsg>,
+    secrets: Arc<SecretProtector>,
+}
+
+impl<R: BufRead, W: Write> Session<R, W> {
+    fn new(con: Console<R, W>, state: WizardState, secrets: Arc<SecretProtector>) -> Self {
+        let (tx, rx) = mpsc::channel::<UiMsg>();
+        Self {
+            con,
+            state,
+            tx,
+            rx,
+            secrets,
+        }
+    }
+
+    fn run_pages(&mut self) -> Result<i32> {
+        let mut last_page = self.state.page;
+        loop {
+            let page = self.state.page;
+            if page != last_page {
+                invalidation::page_changed(&mut self.state, last_page, &self.tx);
+                last_page = page;
+            }
+            let position = PAGES.iter().position(|p| *p == page).unwrap_or(0) + 1;
+            self.con.say("")?;
+            self.con.say(format!(
+                "Step {} of {}: {}",
+                position,
+                PAGES.len(),
+                page_title(page, self.state.install_mode)
+            ))?;
+            if invalidation::is_dirty(&self.state) {
+                self.con
+                    .say("Earlier answers changed; review this page before continuing.")?;
+            }
+
+            let step = match page {
+                Page::Platform => self.platform()?,
+                Page::Welcome => self.welcome()?,
+                Page::License => self.license()?,
+                Page::InstallType => self.install_type()?,
+                Page::Destination => self.destination()?,
+                Page::DataSource => self.data_source()?,
+                Page::Database => self.database()?,
+                Page::Storage => self.storage()?,
+                Page::Retention => self.retention()?,
+                Page::Archive => self.archive()?,
+                Page::Consent => self.consent()?,
+                Page::Mapping => self.mapping()?,
+                Page::Ready => self.ready()?,
+                Page::Installing => {
+                    self.follow_install()?;
+                    Step::Stay
+                }
+                Page::Complete => self.complete()?,
+            };
+
+            match step {
+                Step::Stay => {}
+                Step::Back if can_go_back(page) => {
+                    self.state.page = prev_page(page);
+                    if self.state.page == Page::Mapping {
+                        enter_mapping_page(&mut self.state, &self.tx);
+                    }
+                }
+                Step::Back => self.con.say("This page has no previous step.")?,
+                Step::Cancel => {
+                    if !can_cancel(page) {
+                        return Ok(self.state.install_exit_code);
+                    }
+                    self.con.say(i18n::t("modal.cancel.body"))?;
+                    let title = i18n::t("modal.cancel.title");
+                    if !matches!(self.con.yes_no(&title, true)?, Reply::Value(false)) {
+                        info!("[PHASE: tui] [STEP: cancel] Setup cancelled in plain mode");
+                        return Ok(self.state.install_exit_code);
+                    }
+                }
+                Step::Next => {
+                    update_page_validation(&mut self.state);
+                    if page == Page::Complete {
+                        return Ok(self.state.install_exit_code);
+                    }
+                    if !can_go_next(&self.state) {
+                        let reason = blocked_reason(&self.state);
+                        self.con.say(format!("Cannot continue: {}", reason))?;
+                        continue;
+                    }
+                    if page == Page::Ready {
+                        start_install(&mut self.state, &self.tx, &self.secrets);
+                        continue;
+                    }
+                    self.state.page = next_page(page);
+                    if self.state.page == Page::Mapping {
+                        enter_mapping_page(&mut self.state, &self.tx);
+                    } else if self.state.page == Page::Ready {
+                        disk_space::start(&mut self.state, &self.tx);
+                        backfill::start(&mut self.state, &self.tx);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply background messages until `done` holds, announcing progress as it arrives.
+    fn pump(&mut self, done: impl Fn(&WizardState) -> bool) -> Result<()> {
+        let mut last = String::new();
+        while !done(&self.state) {
+            let msg = match self.rx.recv_timeout(Duration::from_millis(250)) {
+                Ok(msg) => msg,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            let line = match &msg {
+                UiMsg::InstallProgress(p) if !p.message.trim().is_empty() => {
+                    format!("{}% {}", p.percent, p.message)
+                }
+                UiMsg::ArchiveProgress(p) if !p.message.trim().is_empty() => {
+                    format!("Archive {}% {}", p.percent, p.message)
+                }
+                _ => String::new(),
+            };
+            if !line.is_empty() && line != last {
+                self.con.say(&line)?;
+                last = line;
+            }
+            apply_message(&mut self.state, msg);
+        }
+        Ok(())
+    }
+
+    fn platform(&mut self) -> Result<Step> {
+        let current = usize::from(self.state.platform_selected == InstallMode::Docker);
+        let choice = ask!(self.con.choose(
+            "Where will CADalytix run?",
+            &["Windows", "Docker / Linux"],
+            current
+        ));
+        self.state.platform_selected = if choice == 0 {
+            InstallMode::Windows
+        } else {
+            InstallMode::Docker
+        };
+        confirm_platform(&mut self.state);
+        Ok(Step::Stay)
+    }
+
+    fn welcome(&mut self) -> Result<Step> {
+        self.con
+            .say("This wizard will guide you through installing CADalytix.")?;
+        self.con.say(format!(
+            "Mode: {}",
+            match self.state.install_mode {
+                InstallMode::Windows => "Windows",
+                InstallMode::Docker => "Docker / Linux",
+            }
+        ))?;
+        self.con.say_all(HELP)?;
+        ask!(self.con.text("Press Enter to continue", ""));
+        Ok(Step::Next)
+    }
+
+    fn license(&mut self) -> Result<Step> {
+        for line in LICENSE_LINES.iter().filter(|l| !l.contains("PageUp")) {
+            self.con.say(line)?;
+        }
+        self.state.license_accepted = ask!(self.con.yes_no(
+            "I accept the terms of the license agreement",
+            self.state.license_accepted
+        ));
+        Ok(Step::Next)
+    }
+
+    fn install_type(&mut self) -> Result<Step> {
+        let types = [
+            InstallationType::Typical,
+            InstallationType::Custom,
+            InstallationType::ImportConfig,
+        ];
+        let current = types
+            .iter()
+            .position(|t| *t == self.state.installation_type)
+            .unwrap_or(0);
+        let choice = ask!(self.con.choose(
+            "Select the type of installation you want.",
+            &[
+                "Typical (Recommended)",
+                "Custom",
+                "Import configuration file"
+            ],
+            current
+        ));
+        self.state.installation_type = types[choice];
+        if self.state.installation_type == InstallationType::ImportConfig {
+            let path = ask!(self
+                .con
+                .text("Config file", &self.state.import_config_path.value));
+            self.state.import_config_path.set(path);
+        }
+        Ok(Step::Next)
+    }
+
+    fn destination(&mut self) -> Result<Step> {
+        self.con
+            .say("Choose the folder where CADalytix will be installed (space is checked before installing).")?;
+        let path = ask!(self
+            .con
+            .text("Install path", &self.state.destination_path.value));
+        self.state.destination_path.set(path);
+        self.state.layout_advanced = ask!(self.con.yes_no(
+            "Advanced layout: separate binaries, data, logs and temp folders",
+            self.state.layout_advanced
+        ));
+        if self.state.layout_advanced {
+            self.con.say("Leave a folder empty to keep its default.")?;
+            for (i, role) in install_layout::ROLES.iter().enumerate() {
+                let current = install_layout::field_mut(&mut self.state, i + 1)
+                    .map(|f| f.value.clone())
+                    .unwrap_or_default();
+                let value = ask!(self.con.text(&format!("{} folder", role), &current));
+                if let Some(field) = install_layout::field_mut(&mut self.state, i + 1) {
+                    field.set(value);
+                }
+            }
+        }
+        Ok(Step::Next)
+    }
+
+    fn data_source(&mut self) -> Result<Step> {
+        let mut kinds = vec![
+            (
+                DataSourceKind::Local,
+                "Use this server/host (local environment)",
+            ),
+            (
+                DataSourceKind::Remote,
+                "Connect to an existing remote system/database",
+            ),
+        ];
+        if self.state.licensed.allows(features::MULTI_DATASOURCE) {
+            kinds.push((
+                DataSourceKind::Odbc,
+                "ODBC data source (DSN or driver connection string)",
+            ));
+            if cfg!(feature = "oracle") {
+                kinds.push((DataSourceKind::Oracle, "Oracle database"));
+            }
+            kinds.push((DataSourceKind::File, "File (CSV/TSV) export folder"));
+        } else {
+            self.con.say(features::not_included_message(
+                "ODBC, Oracle and file export data sources",
+            ))?;
+        }
+        let labels: Vec<&str> = kinds.iter().map(|(_, l)| *l).collect();
+        let current = kinds
+            .iter()
+            .position(|(k, _)| *k == self.state.data_source_kind)
+            .unwrap_or(0);
+        let choice = ask!(self.con.choose("Call data source:", &labels, current));
+        self.state.data_source_kind = kinds[choice].0;
+
+        let s = &mut self.state;
+        let con = &mut self.con;
+        match s.data_source_kind {
+            DataSourceKind::Odbc => {
+                let shown = mask_connection_string(&s.odbc_connection_string.value);
+                let v = ask!(con.field(
+                    "ODBC connection string",
+                    &s.odbc_connection_string.value,
+                    &shown
+                ));
+                s.odbc_connection_string.set(v);
+                let v = ask!(con.text("Source object name", &s.source_object_name.value));
+                s.source_object_name.set(v);
+            }
+            DataSourceKind::Oracle => {
+                let v = ask!(con.text(
+                    "ODBC driver name (blank for the Instant Client default)",
+                    &s.oracle_driver.value
+                ));
+                s.oracle_driver.set(v);
+                let v = ask!(con.text(
+                    "Address (host[:port]/service or TNS alias)",
+                    &s.oracle_address.value
+                ));
+                s.oracle_address.set(v);
+                let v = ask!(con.text("Username", &s.call_data_user.value));
+                s.call_data_user.set(v);
+                let v = ask!(con.secret("Password", &s.call_data_password.value));
+                s.call_data_password.set(v);
+                let v = ask!(con.text(
+                    "Source object name ([owner.]table)",
+                    &s.source_object_name.value
+                ));
+                s.source_object_name.set(v);
+            }
+            DataSourceKind::File => {
+                let v = ask!(con.text("Export folder", &s.file_source_folder.value));
+                s.file_source_folder.set(v);
+                let v = ask!(con.text(
+                    "Delimiter (auto/comma/tab/semicolon/pipe)",
+                    &s.file_source_delimiter.value
+                ));
+                s.file_source_delimiter.set(v);
+            }
+            DataSourceKind::Local | DataSourceKind::Remote => {
+                let v = ask!(con.text("Database", &s.call_data_database.value));
+                s.call_data_database.set(v);
+                s.call_data_windows_auth =
+                    ask!(con.yes_no("Use Windows Authentication", s.call_data_windows_auth));
+                if !s.call_data_windows_auth {
+                    let v = ask!(con.text("Username", &s.call_data_user.value));
+                    s.call_data_user.set(v);
+                    let v = ask!(con.secret("Password", &s.call_data_password.value));
+                    s.call_data_password.set(v);
+                }
+                let v = ask!(con.text("Host", &s.call_data_host.value));
+                s.call_data_host.set(v);
+                let v = ask!(con.text("Port", &s.call_data_port.value));
+                s.call_data_port.set(v);
+                let v = ask!(con.text("Source object name", &s.source_object_name.value));
+                s.source_object_name.set(v);
+            }
+        }
+        Ok(Step::Next)
+    }
+
+    fn database(&mut self) -> Result<Step> {
+        let current = usize::from(self.state.db_kind == DbKind::Remote);
+        let choice = ask!(self.con.choose(
+            "Do you want CADalytix to create a NEW database, or use an EXISTING database?",
+            &["Create NEW CADalytix Database", "Use EXISTING Database"],
+            current
+        ));
+        self.state.db_kind = if choice == 0 {
+            DbKind::Local
+        } else {
+            DbKind::Remote
+        };
+        self.state.db_test_status = DbTestStatus::Idle;
+        self.state.db_test_message.clear();
+
+        if self.state.db_kind == DbKind::Local {
+            let locations = [NewDbLocation::ThisMachine, NewDbLocation::SpecificPath];
+            let current = usize::from(self.state.new_db_location == NewDbLocation::SpecificPath);
+            let choice = ask!(self.con.choose(
+                "Where should the new CADalytix database be created?",
+                &[locations[0].as_str(), locations[1].as_str()],
+                current
+            ));
+            self.state.new_db_location = locations[choice];
+            if self.state.new_db_location == NewDbLocation::SpecificPath {
+                self.con.say(new_db_path_help(&self.state))?;
+                let v = ask!(self
+                    .con
+                    .text("Database path", &self.state.new_db_specific_path.value));
+                self.state.new_db_specific_path.set(v);
+            }
+            let v = ask!(self.con.text(
+                "Max DB size / storage allocation (GB)",
+                &self.state.new_db_max_size_gb.value
+            ));
+            self.state.new_db_max_size_gb.set(v);
+            let v = ask!(self.con.text(
+                collation_label(&self.state),
+                &self.state.new_db_collation.value
+            ));
+            self.state.new_db_collation.set(v);
+            self.con.say(APP_LOGIN_HELP)?;
+            let v = ask!(self.con.text(APP_LOGIN_LABEL, &self.state.app_login.value));
+            self.state.app_login.set(v);
+            self.con
+                .say("Hot retention and archive policy are configured on the next pages.")?;
+            return Ok(Step::Next);
+        }
+
+        let mut hosts = vec![ExistingHostedWhere::OnPrem];
+        while hosts.len() < 7 {
+            hosts.push(hosts[hosts.len() - 1].next());
+        }
+        let labels: Vec<&str> = hosts.iter().map(|h| h.as_str()).collect();
+        let current = hosts
+            .iter()
+            .position(|h| *h == self.state.existing_hosted_where)
+            .unwrap_or(0);
+        let choice = ask!(self.con.choose(
+            "Where is the existing database hosted? (No login required)",
+            &labels,
+            current
+        ));
+        set_existing_hosted_where(&mut self.state, hosts[choice]);
+        self.con.say(
+            "CADalytix does not ask you to log in to AWS/Azure/GCP and does not scan your cloud.",
+        )?;
+
+        let choice = ask!(self.con.choose(
+            "How do you want to connect?",
+            &[
+                "Connection string",
+                "Enter connection details (host/server, port, db name, username, password, TLS)"
+            ],
+            usize::from(!self.state.db_use_conn_string)
+        ));
+        self.state.db_use_conn_string = choice == 0;
+
+        let s = &mut self.state;
+        let con = &mut self.con;
+        if s.db_use_conn_string {
+            let shown = mask_connection_string(&s.db_conn_string.value);
+            let v = ask!(con.field("Connection string", &s.db_conn_string.value, &shown));
+            s.db_conn_string.set(v);
+        } else {
+            let v = ask!(con.text("Host", &s.db_host.value));
+            s.db_host.set(v);
+            let v = ask!(con.text("Port", &s.db_port.value));
+            s.db_port.set(v);
+            let v = ask!(con.text("Database", &s.db_database.value));
+            s.db_database.set(v);
+            s.db_windows_auth = ask!(con.yes_no(
+                "Use Windows Authentication (SQL Server only)",
+                s.db_windows_auth
+            ));
+            if s.db_windows_auth {
+                s.db_engine = DbEngine::SqlServer;
+            } else {
+                let v = ask!(con.text("Username", &s.db_user.value));
+                s.db_user.set(v);
+                let v = ask!(con.secret("Password", &s.db_password.value));
+                s.db_password.set(v);
+            }
+            let modes = ["disable", "prefer", "require"];
+            let current = modes.iter().position(|m| *m == s.db_ssl_mode).unwrap_or(1);
+            let choice = ask!(con.choose("TLS:", &modes, current));
+            s.db_ssl_mode = modes[choice].to_string();
+        }
+        self.con.say(APP_LOGIN_HELP)?;
+        let v = ask!(self.con.text(APP_LOGIN_LABEL, &self.state.app_login.value));
+        self.state.app_login.set(v);
+
+        self.con.say("Testing connection...")?;
+        start_db_test(&mut self.state, &self.tx);
+        self.pump(|s| s.db_test_status != DbTestStatus::Testing)?;
+        let status = if self.state.db_test_status == DbTestStatus::Success {
+            "Success"
+        } else {
+            "Fail"
+        };
+        self.con.say(format!(
+            "Test result: {}. {}",
+            status, self.state.db_test_message
+        ))?;
+        Ok(Step::Next)
+    }
+
+    fn storage(&mut self) -> Result<Step> {
+        let choice = ask!(self.con.choose(
+            "Configure database storage.",
+            &["Use defaults (Recommended)", "Customize storage"],
+            usize::from(self.state.storage_mode == StorageMode::Custom)
+        ));
+        if choice == 0 {
+            self.state.storage_mode = StorageMode::Defaults;
+            return self.storage_filesystem();
+        }
+        self.state.storage_mode = StorageMode::Custom;
+
+        let locations = [
+            StorageLocation::System,
+            StorageLocation::Attached,
+            StorageLocation::Custom,
+        ];
+        let current = locations
+            .iter()
+            .position(|l| *l == self.state.storage_location)
+            .unwrap_or(0);
+        let choice = ask!(self.con.choose(
+            "Storage location:",
+            &["Use system disk", "Use attached drive", "Use custom path"],
+            current
+        ));
+        self.state.storage_location = locations[choice];
+        if self.state.storage_location == StorageLocation::Custom {
+            let v = ask!(self
+                .con
+                .text("Custom path", &self.state.storage_custom_path.value));
+            self.state.storage_custom_path.set(v);
+        }
+
+        let policies = [
+            RetentionPolicy::Rolling18,
+            RetentionPolicy::Rolling12,
+            RetentionPolicy::MaxDisk,
+            RetentionPolicy::KeepEverything,
+        ];
+        let current = policies
+            .iter()
+            .position(|p| *p == self.state.retention_policy)
+            .unwrap_or(0);
+        let choice = ask!(self.con.choose(
+            "Retention policy:",
+            &[
+                "Rolling 18 months (Recommended)",
+                "Rolling 12 months",
+                "Max disk usage",
+                "Keep everything (Not recommended)"
+            ],
+            current
+        ));
+        self.state.retention_policy = policies[choice];
+        if self.state.retention_policy == RetentionPolicy::MaxDisk {
+            let v = ask!(self
+                .con
+                .text("Max disk usage (GB)", &self.state.max_disk_gb.value));
+            self.state.max_disk_gb.set(v);
+        }
+        self.storage_filesystem()
+    }
+
+    /// Filesystem guidance for the chosen database folder; FAT32/exFAT stay on Storage.
+    fn storage_filesystem(&mut self) -> Result<Step> {
+        storage_fs::refresh(&mut self.state, &self.tx);
+        if self.state.storage_fs_checking {
+            self.pump(|s| !s.storage_fs_checking)?;
+        }
+        for line in storage_fs::lines(&self.state).into_iter().skip(1) {
+            self.con.say(line)?;
+        }
+        if storage_fs::blocked(&self.state).is_some() {
+            return Ok(Step::Stay);
+        }
+        Ok(Step::Next)
+    }
+
+    fn retention(&mut self) -> Result<Step> {
+        let choices = [
+            HotRetentionChoice::Months12,
+            HotRetentionChoice::Months18,
+            HotRetentionChoice::Custom,
+        ];
+        let current = choices
+            .iter()
+            .position(|c| *c == self.state.hot_retention_choice)
+            .unwrap_or(1);
+        let choice = ask!(self.con.choose(
+            "Choose how long to keep hot data in the database.",
+            &[
+                "12 months (Recommended)",
+                "18 months (Recommended)",
+                "Custom months"
+            ],
+            current
+        ));
+        self.state.hot_retention_choice = choices[choice];
+        if self.state.hot_retention_choice == HotRetentionChoice::Custom {
+            let v = ask!(self
+                .con
+                .text("Months", &self.state.hot_retention_custom_months.value));
+            self.state.hot_retention_custom_months.set(v);
+        }
+        Ok(Step::Next)
+    }
+
+    fn archive(&mut self) -> Result<Step> {
+        if !self.state.licensed.allows(features::ARCHIVE) {
+            self.con
+                .say(features::not_included_message("The archive module"))?;
+            self.con
+                .say("Call data stays in the hot database; no archive files are written.")?;
+            ask!(self.con.text("Press Enter to continue", ""));
+            return Ok(Step::Next);
+        }
+
+        let choice = ask!(self.con.choose(
+            "Configure cold storage (archive) settings. Format:",
+            &["ZIP + NDJSON (Preferred)", "ZIP + CSV"],
+            usize::from(self.state.archive_format == ArchiveFormatChoice::ZipCsv)
+        ));
+        let s = &mut self.state;
+        let con = &mut self.con;
+        s.archive_format = if choice == 0 {
+            ArchiveFormatChoice::ZipNdjson
+        } else {
+            ArchiveFormatChoice::ZipCsv
+        };
+        let v = ask!(con.text("Destination folder", &s.archive_destination.value));
+        s.archive_destination.set(v);
+        let v = ask!(con.text("Max archive usage cap (GB)", &s.archive_max_usage_gb.value));
+        s.archive_max_usage_gb.set(v);
+        let v = ask!(con.text(
+            "Schedule day (1-28)",
+            &s.archive_schedule_day_of_month.value
+        ));
+        s.archive_schedule_day_of_month.set(v);
+        let v = ask!(con.text(
+            "Schedule time (HH:MM)",
+            &s.archive_schedule_time_local.value
+        ));
+        s.archive_schedule_time_local.set(v);
+        s.archive_catch_up_on_startup =
+            ask!(con.yes_no("Catch up on startup", s.archive_catch_up_on_startup));
+        s.archive_encrypt = ask!(con.yes_no("Encrypt archives at rest", s.archive_encrypt));
+        Ok(Step::Next)
+    }
+
+    fn consent(&mut self) -> Result<Step> {
+        self.con.say_all(&[
+            "Exactly what is sent (no passwords or connection strings):",
+            "- Installer version and timestamp",
+            "- Install mode (Windows / Docker)",
+            "- Storage/retention/archive settings",
+            "- Schema mapping (field names + chosen targets)",
+            "- Aggregate counts",
+            "When enabled, this is sent once after install (queued and retried if offline).",
+        ])?;
+        self.state.consent_to_sync = ask!(self.con.yes_no(
+            "Allow CADalytix to receive install metadata and schema mapping for support improvements",
+            self.state.consent_to_sync
+        ));
+        Ok(Step::Next)
+    }
+
+    fn mapping(&mut self) -> Result<Step> {
+        if self.state.mapping_scanning {
+            self.con.say("Scanning source columns...")?;
+            self.pump(|s| !s.mapping_scanning)?;
+        }
+        if let Some(e) = self.state.mapping_scan_error.as_ref() {
+            self.con.say(format!("Column scan failed: {}", e))?;
+        }
+        self.con.say(format!(
+            "{} source column(s), {} target field(s), {} required field(s) unmapped, {} suggestion(s) pending.",
+            self.state.source_fields.len(),
+            self.state.target_fields.len(),
+            self.state
+                .target_fields
+                .iter()
+                .filter(|t| t.required && !self.state.target_to_source.contains_key(&t.id))
+                .count(),
+            pending_suggestion_count(&self.state)
+        ))?;
+        self.con.say_all(MAPPING_HELP)?;
+
+        loop {
+            let command = ask!(self.con.text("Mapping command", "done"));
+            let mut words = command.split_whitespace();
+            let verb = words.next().unwrap_or_default().to_ascii_lowercase();
+            let numbers: Vec<usize> = words.filter_map(|w| w.parse().ok()).collect();
+            match (verb.as_str(), numbers.as_slice()) {
+                ("done", _) => return Ok(Step::Next),
+                ("list", _) => self.list_targets()?,
+                ("sources", _) => {
+                    for (i, f) in self.state.source_fields.iter().enumerate() {
+                        let line = if f.data_type.is_empty() {
+                            format!("  {}. {}", i + 1, f.display_name)
+                        } else {
+                            format!("  {}. {} ({})", i + 1, f.display_name, f.data_type)
+                        };
+                        self.con.say(line)?;
+                    }
+                }
+                ("auto", _) => {
+                    auto_map(&mut self.state);
+                    if let Some(Modal::Message { body, .. }) = self.state.modal.take() {
+                        let summary = body.split("\n\n").next().unwrap_or_default().to_string();
+                        self.con.say(summary)?;
+                    }
+                    self.con
+                        .say("Type list to review, confirm to accept them.")?;
+                }
+                ("confirm", _) => {
+                    self.state.selected_target_id = None;
+                    confirm_suggestions(&mut self.state);
+                    self.con.say("All suggestions confirmed.")?;
+                }
+                ("map", [s, t]) => self.map(*s, *t)?,
+                ("unmap", [t]) => match self.state.target_fields.get(t.wrapping_sub(1)) {
+                    Some(target) => {
+                        let target_id = target.id.clone();
+                        remove_target_from_old_source(&mut self.state, &target_id);
+                        self.state.mapping_suggested.remove(&target_id);
+                        self.con.say(format!(
+                            "{} is unmapped.",
+                            mapping_target_name(&self.state, &target_id)
+                        ))?;
+                    }
+                    None => self.con.say("No target field with that number.")?,
+                },
+                ("override", _) => {
+                    self.state.mapping_override = !self.state.mapping_override;
+                    self.con.say(format!(
+                        "One source column to several targets: {}.",
+                        if self.state.mapping_override {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                    ))?;
+                }
+                ("demo", _) | ("rescan", _) => {
+                    if verb == "demo" {
+                        self.state.mapping_demo_mode = !self.state.mapping_demo_mode;
+                    }
+                    start_mapping_scan(&mut self.state, &self.tx);
+                    return Ok(Step::Stay);
+                }
+                _ => self.con.say_all(MAPPING_HELP)?,
+            }
+        }
+    }
+
+    fn list_targets(&mut self) -> Result<()> {
+        for (i, t) in self.state.target_fields.iter().enumerate() {
+            let mapped = match self.state.target_to_source.get(&t.id) {
+                Some(source_id) => {
+                    let source = mapping_source_display(&self.state, source_id);
+                    match pending_suggestion(&self.state, &t.id) {
+                        Some(s) => format!(
+                            "{} (suggested, {:.0}% confidence)",
+                            source,
+                            s.confidence * 100.0
+                        ),
+                        None => source,
+                    }
+                }
+                None => "unmapped".to_string(),
+            };
+            let required = if t.required { ", required" } else { "" };
+            self.con
+                .say(format!("  {}. {}{}: {}", i + 1, t.name, required, mapped))?;
+        }
+        Ok(())
+    }
+
+    fn map(&mut self, source: usize, target: usize) -> Result<()> {
+        let ids = self
+            .state
+            .source_fields
+            .get(source.wrapping_sub(1))
+            .map(|s| s.id.clone())
+            .zip(
+                self.state
+                    .target_fields
+                    .get(target.wrapping_sub(1))
+                    .map(|t| t.id.clone()),
+            );
+        let Some((source_id, target_id)) = ids else {
+            return self
+                .con
+                .say("No source column or target field with that number.");
+        };
+        attempt_map(&mut self.state, &source_id, &target_id);
+
+        // Conflicts raise the same choice the full-screen wizard shows as a dialog.
+        if let Some(Modal::ConfirmMapping {
+            title,
+            body,
+            actions,
+            pending,
+            ..
+        }) = self.state.modal.take()
+        {
+            self.con.say(format!("{}: {}", title, body))?;
+            let labels: Vec<&str> = actions
+                .iter()
+                .map(|a| match a {
+                    MappingModalAction::Add => "Add",
+                    MappingModalAction::Replace => "Replace",
+                    MappingModalAction::Cancel => "Cancel",
+                })
+                .collect();
+            let cancel = actions.len().saturating_sub(1);
+            let action = match self
+                .con
+                .choose("What would you like to do?", &labels, cancel)?
+            {
+                Reply::Value(i) => actions[i],
+                _ => MappingModalAction::Cancel,
+            };
+            match action {
+                MappingModalAction::Add => apply_mapping(
+                    &mut self.state,
+                    &pending.source_id,
+                    &pending.target_id,
+                    true,
+                ),
+                MappingModalAction::Replace => apply_mapping(
+                    &mut self.state,
+                    &pending.source_id,
+                    &pending.target_id,
+                    false,
+                ),
+                MappingModalAction::Cancel => return self.con.say("Mapping unchanged."),
+            }
+        }
+
+        let line = match self.state.target_to_source.get(&target_id) {
+            Some(s) => format!(
+                "{} is mapped to {}.",
+                mapping_target_name(&self.state, &target_id),
+                mapping_source_display(&self.state, s)
+            ),
+            None => format!(
+                "{} is unmapped.",
+                mapping_target_name(&self.state, &target_id)
+            ),
+        };
+        self.con.say(line)
+    }
+
+    fn ready(&mut self) -> Result<Step> {
+        self.con.say("Setup is ready to begin installation.")?;
+        if disk_space::is_short(&self.state) {
+            // Shown again after a shortfall: measure again in case space was freed.
+            disk_space::start(&mut self.state, &self.tx);
+        }
+        if self.state.backfill_estimating || self.state.disk_space_checking {
+            self.pump(|s| !s.backfill_estimating && !s.disk_space_checking)?;
+        }
+        for line in ready_summary(&self.state) {
+            self.con.say(line)?;
+        }
+        self.con.say("Passwords are not shown here.")?;
+        self.state.dry_run = ask!(self.con.yes_no(
+            "Plan only (dry run): write an install plan without making changes",
+            self.state.dry_run
+        ));
+        let label = if self.state.dry_run {
+            "Write the install plan now"
+        } else {
+            "Begin installation now"
+        };
+        if !ask!(self.con.yes_no(label, true)) {
+            return Ok(Step::Stay);
+        }
+        #[cfg(target_os = "linux")]
+        if !self.approve_root_steps()? {
+            return Ok(Step::Stay);
+        }
+        Ok(Step::Next)
+    }
+
+    /// Unprivileged on Linux: list the root-only commands of the install and run the folder
+    /// batch on consent (see `installation::elevation`). False stays on the Ready page.
+    #[cfg(target_os = "linux")]
+    fn approve_root_steps(&mut self) -> Result<bool> {
+        use crate::installation::elevation;
+
+        let req = build_install_request(&self.state);
+        let plan = match draft::block_on(elevation::plan(&req)).and_then(|r| r) {
+            Ok(None) => return Ok(true),
+            Ok(Some(plan)) => plan,
+            Err(e) => {
+                self.con.say(format!("Cannot continue: {:#}", e))?;
+                return Ok(false);
+            }
+        };
+        self.con.say(format!(
+            "These commands run as root via {} (it may ask for your password):",
+            plan.escalator.program()
+        ))?;
+        for line in plan.lines() {
+            self.con.say(format!("  {}", line))?;
+        }
+        if !matches!(self.con.yes_no("Run them", true)?, Reply::Value(true)) {
+            return Ok(false);
+        }
+        if let Err(e) = draft::block_on(elevation::approve(&plan)).and_then(|r| r) {
+            self.con.say(format!("Cannot continue: {:#}", e))?;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Installing page: announce progress until the run ends (Complete, or back to Ready).
+    fn follow_install(&mut self) -> Result<()> {
+        self.con
+            .say("Installing. Progress is announced as each step starts.")?;
+        self.pump(|s| s.page != Page::Installing || s.modal.is_some())?;
+        if let Some(Modal::Message {
+            title,
+            body,
+            return_to,
+        }) = self.state.modal.take()
+        {
+            self.con.say(title)?;
+            self.con.say(body)?;
+            self.state.page = return_to.unwrap_or(Page::Ready);
+        }
+        Ok(())
+    }
+
+    fn complete(&mut self) -> Result<Step> {
+        self.con.say("CADalytix Setup has completed.")?;
+        if let Some(a) = self.state.install_artifacts.clone() {
+            let artifacts = [
+                ("Log folder", a.log_folder),
+                ("Install manifest", a.manifest_path),
+                ("Mapping", a.mapping_path),
+                ("Install config", a.config_path),
+            ];
+            for (label, path) in artifacts {
+                if let Some(p) = path.filter(|p| !p.trim().is_empty()) {
+                    self.con.say(format!("{}: {}", label, p))?;
+                }
+            }
+        }
+        if self.state.licensed.allows(features::ARCHIVE)
+            && self.state.archive_run_result.is_none()
+            && ask!(self.con.yes_no("Run archive catch-up now", false))
+        {
+            start_archive_run(&mut self.state, &self.tx);
+            self.pump(|s| !s.archive_run_active)?;
+            if let Some(r) = self.state.archive_run_result.clone() {
+                self.con.say(r)?;
+            }
+        }
+        ask!(self.con.text("Press Enter to exit", ""));
+        Ok(Step::Next)
+    }
+}
+
+/// Runs the wizard in line mode; returns the process exit code (that of the last install attempt).
+pub fn run(secrets: Arc<SecretProtector>) -> Result<i32> {
+    info!("[PHASE: tui] [STEP: start] Starting plain line-mode wizard");
+
+    let mut state = new_real_wizard_state();
+    state.licensed = load_licensed_features(&secrets);
+    let stdin = io::stdin();
+    let console = Console {
+        hide_secrets: stdin.is_terminal(),
+        input: stdin.lock(),
+        out: io::stdout(),
+    };
+    Session::new(console, state, secrets).run_pages()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_mode_walks_pages_and_keeps_validation() {
+        // Docker platform, Welcome, decline the license (blocked), `help`, then cancel.
+        let script = "2\n\nn\nhelp\ncancel\ny\n";
+        let console = Console {
+            input: std::io::Cursor::new(script.as_bytes().to_vec()),
+            out: Vec::new(),
+            hide_secrets: false,
+        };
+        let key_path = std::env::temp_dir()
+            .join(format!("plain-{}", Uuid::new_v4()))
+            .join("key");
+        let mut session = Session::new(
+            console,
+            WizardState::new(),
+            Arc::new(SecretProtector::new(key_path)),
+        );
+        assert_eq!(session.run_pages().unwrap(), 0);
+        assert_eq!(session.state.install_mode, InstallMode::Docker);
+        assert_eq!(session.state.db_engine, DbEngine::Postgres);
+        assert_eq!(session.state.page, Page::License);
+        assert!(!session.state.license_accepted);
+
+        let out = String::from_utf8(session.con.out).unwrap();
+        assert!(out.contains("Step 3 of 15"), "{}", out);
+        assert!(out.contains("Cannot continue: Accept the license agreement"));
+        assert!(out.contains("  back    return to the previous page"));
+        assert!(
+            !out.contains('\x1b'),
+            "line mode must not emit escape sequences"
+        );
+    }
+}
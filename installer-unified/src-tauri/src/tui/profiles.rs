@@ -0,0 +1,319 @@
+//! Saved connection profiles (P on the Data Source and Database pages).
+//!
+//! Lists the profiles saved for the page (`utils::connection_profiles`, shared with the GUI).
+//! Up / Down move, Enter loads the selected profile into the page, S saves the page's current
+//! connection under a name (the selected profile's name is offered, so re-saving updates it),
+//! D deletes the selected profile and Esc closes.
+
+use super::*;
+use crate::utils::connection_profiles::{self, ConnectionProfile, ProfileKind};
+use script::{apply_answers, Answers};
+use std::collections::BTreeMap;
+
+fn kind_for(page: Page) -> Option<ProfileKind> {
+    match page {
+        Page::DataSource => Some(ProfileKind::DataSource),
+        Page::Database => Some(ProfileKind::Database),
+        _ => None,
+    }
+}
+
+fn load(secrets: &SecretProtector, kind: ProfileKind) -> Result<Vec<ConnectionProfile>, String> {
+    draft::block_on(connection_profiles::list(secrets, kind))
+        .and_then(|r| r)
+        .map_err(|e| format!("Unable to read the saved profiles: {}", e))
+}
+
+/// Open the list for the current page.
+pub(super) fn open(state: &mut WizardState, secrets: &SecretProtector) {
+    let Some(kind) = kind_for(state.page) else {
+        return;
+    };
+    let (profiles, error) = match load(secrets, kind) {
+        Ok(profiles) => (profiles, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    state.modal = Some(Modal::Profiles {
+        kind,
+        profiles,
+        selected: 0,
+        name: None,
+        error,
+    });
+}
+
+/// The page's answers a profile of `kind` keeps.
+fn current_values(state: &WizardState, kind: ProfileKind) -> BTreeMap<String, String> {
+    let page = match kind {
+        ProfileKind::DataSource => Page::DataSource,
+        ProfileKind::Database => Page::Database,
+    };
+    draft::capture(state)
+        .into_iter()
+        .filter(|(p, _)| *p == page)
+        .flat_map(|(_, answers)| answers)
+        .filter(|(key, _)| kind.keys().contains(&key.as_str()))
+        .collect()
+}
+
+/// Fill the page from `profile`, in the order of `ProfileKind::keys`.
+fn apply(state: &mut WizardState, profile: &ConnectionProfile) {
+    let mut answers: Answers = Vec::new();
+    if profile.kind == ProfileKind::Database {
+        // Profiles are connections to an existing database.
+        answers.push(("kind".to_string(), "existing".to_string()));
+    }
+    for key in profile.kind.keys() {
+        if let Some(value) = profile.values.get(*key) {
+            answers.push((key.to_string(), value.clone()));
+        }
+    }
+    for answer in answers {
+        if let Err(e) = apply_answers(state, &vec![answer.clone()]) {
+            warn!(
+                "[PHASE: tui] [STEP: profiles] Skipping value {} of profile {:?}: {}",
+                answer.0, profile.name, e
+            );
+        }
+    }
+    info!(
+        "[PHASE: tui] [STEP: profiles] Connection profile loaded (kind={:?}, name={:?})",
+        profile.kind, profile.name
+    );
+    update_page_validation(state);
+}
+
+pub(super) fn handle_key(state: &mut WizardState, code: KeyCode, secrets: &SecretProtector) {
+    let Some(Modal::Profiles {
+        kind,
+        profiles,
+        selected,
+        name,
+        error,
+    }) = state.modal.clone()
+    else {
+        return;
+    };
+    let reopen = |profiles, selected, name, error| Modal::Profiles {
+        kind,
+        profiles,
+        selected,
+        name,
+        error,
+    };
+
+    // Typing the name to save under.
+    if let Some(mut typed) = name {
+        match code {
+            KeyCode::Esc => state.modal = Some(reopen(profiles, selected, None, None)),
+            KeyCode::Enter => {
+                let profile = ConnectionProfile {
+                    name: typed.clone(),
+                    kind,
+                    values: current_values(state, kind),
+                    saved_at: String::new(),
+                };
+                let saved = draft::block_on(connection_profiles::save(secrets, profile))
+                    .and_then(|r| r)
+                    .map_err(|e| e.to_string())
+                    .and_then(|()| load(secrets, kind));
+                state.modal = Some(match saved {
+                    Ok(profiles) => {
+                        let selected = profiles
+                            .iter()
+                            .position(|p| p.name.eq_ignore_ascii_case(typed.trim()))
+                            .unwrap_or(0);
+                        reopen(profiles, selected, None, None)
+                    }
+                    Err(e) => reopen(profiles, selected, Some(typed), Some(e)),
+                });
+            }
+            KeyCode::Backspace => {
+                typed.pop();
+                state.modal = Some(reopen(profiles, selected, Some(typed), error));
+            }
+            KeyCode::Char(c) => {
+                typed.push(c);
+                state.modal = Some(reopen(profiles, selected, Some(typed), error));
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match code {
+        KeyCode::Esc => state.modal = None,
+        KeyCode::Up => {
+            state.modal = Some(reopen(profiles, selected.saturating_sub(1), None, error));
+        }
+        KeyCode::Down => {
+            let last = profiles.len().saturating_sub(1);
+            state.modal = Some(reopen(profiles, (selected + 1).min(last), None, error));
+        }
+        KeyCode::Enter => {
+            if let Some(profile) = profiles.get(selected) {
+                state.modal = None;
+                apply(state, profile);
+            }
+        }
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            let offered = profiles
+                .get(selected)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            state.modal = Some(reopen(profiles, selected, Some(offered), None));
+        }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            let Some(profile) = profiles.get(selected) else {
+                return;
+            };
+            let result = connection_profiles::delete(kind, &profile.name)
+                .map_err(|e| e.to_string())
+                .and_then(|_| load(secrets, kind));
+            state.modal = Some(match result {
+                Ok(profiles) => {
+                    let selected = selected.min(profiles.len().saturating_sub(1));
+                    reopen(profiles, selected, None, None)
+                }
+                Err(e) => reopen(profiles, selected, None, Some(e)),
+            });
+        }
+        _ => {}
+    }
+}
+
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    profiles: &[ConnectionProfile],
+    selected: usize,
+    name: Option<&str>,
+    error: Option<&str>,
+) {
+    let modal_w = 72u16.min(window_area.width.saturating_sub(4)).max(44);
+    let modal_h = 16u16.min(window_area.height.saturating_sub(4)).max(10);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Connection Profiles");
+    f.render_widget(block, area);
+
+    let inner = area.inner(&ratatui::layout::Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(2),
+            ]
+            .as_ref(),
+        )
+        .split(inner);
+
+    let header = match name {
+        Some(typed) => vec![
+            Line::from(format!("Save current connection as: {}_", typed)),
+            Line::from("Enter=save (replaces a profile of the same name)  Esc=back"),
+        ],
+        None => vec![
+            Line::from("Enter=load  S=save current  D=delete  Esc=close"),
+            Line::from("Passwords are stored encrypted."),
+        ],
+    };
+    f.render_widget(Paragraph::new(Text::from(header)), rows[0]);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if profiles.is_empty() {
+        lines.push(Line::from("(no saved profiles; press S to save this one)"));
+    }
+    let list_height = rows[1].height as usize;
+    let start = selected.saturating_sub(list_height / 2);
+    for (i, profile) in profiles.iter().enumerate().skip(start).take(list_height) {
+        let row = Rect {
+            y: rows[1].y + (i - start) as u16,
+            height: 1,
+            ..rows[1]
+        };
+        mouse::record(state, row, HitTarget::BrowseEntry(i));
+        let host = profile
+            .values
+            .get("host")
+            .filter(|h| !h.is_empty())
+            .map(String::as_str)
+            .unwrap_or("-");
+        let saved = profile.saved_at.get(..10).unwrap_or("");
+        lines.push(Line::from(ratatui::text::Span::styled(
+            format!("{:<28} {:<28} {}", profile.name, host, saved),
+            theme::current().focus_if(i == selected),
+        )));
+    }
+    f.render_widget(Paragraph::new(Text::from(lines)), rows[1]);
+
+    if let Some(e) = error {
+        f.render_widget(
+            Paragraph::new(ratatui::text::Span::styled(
+                e.to_string(),
+                theme::current().error(),
+            ))
+            .wrap(Wrap { trim: true }),
+            rows[2],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loading_a_database_profile_fills_the_existing_database_fields() {
+        let mut state = WizardState::new();
+        state.page = Page::Database;
+        let profile = ConnectionProfile {
+            name: "Staging".to_string(),
+            kind: ProfileKind::Database,
+            values: BTreeMap::from([
+                ("hosted".to_string(), "on_prem".to_string()),
+                ("host".to_string(), "stage-sql".to_string()),
+                ("port".to_string(), "1444".to_string()),
+                ("database".to_string(), "cadalytix_stage".to_string()),
+                ("user".to_string(), "installer".to_string()),
+                ("password".to_string(), "s3cret".to_string()),
+            ]),
+            saved_at: String::new(),
+        };
+        state.modal = Some(Modal::Profiles {
+            kind: ProfileKind::Database,
+            profiles: vec![profile],
+            selected: 0,
+            name: None,
+            error: None,
+        });
+
+        let secrets = SecretProtector::new(std::env::temp_dir().join("unused-profile-key"));
+        handle_key(&mut state, KeyCode::Enter, &secrets);
+        assert!(state.modal.is_none());
+        assert_eq!(state.db_kind, DbKind::Remote);
+        assert_eq!(state.db_host.value, "stage-sql");
+        assert_eq!(state.db_port.value, "1444");
+        assert_eq!(state.db_password.value, "s3cret");
+
+        let values = current_values(&state, ProfileKind::Database);
+        assert_eq!(values["database"], "cadalytix_stage");
+        assert!(!values.contains_key("max_size_gb"));
+    }
+}
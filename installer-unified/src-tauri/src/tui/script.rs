@@ -0,0 +1,621 @@
+//! Question/answer scripting over stdin (`--tui-script`).
+//!
+//! For expect-style automation the silent install does not cover: the wizard walks the same pages
+//! with the same validation (`can_go_next`), but asks each page as one question line and reads
+//! one answer record from stdin.
+//!
+//! - Question: `? <page>` (e.g. `? license`).
+//! - Answer: `key=value` lines ended by a blank line or `.`, or a single-line JSON object
+//!   (`{"accept": true}`). An empty record keeps the page defaults; `#` lines are comments.
+//! - Progress while installing: `% <percent> <message>`.
+//! - A page that fails validation prints `! <page>: <reason>` and exits with the
+//!   invalid-arguments code; so does an unknown key or the end of input.
+//! - `ready` waits for the disk space projection; a volume short of space fails it that way.
+//! - The run ends with `= complete`, or `= <title>: <message>` (dry run, failed install).
+//!
+//! Keys per page: `platform` mode; `license` accept; `install_type` type, config; `destination`
+//! path, binaries, data, logs, temp (any of the last four turns on the advanced layout);
+//! `data_source` kind, object, connection_string, driver, address, folder, delimiter, host,
+//! port, database, user, password, windows_auth; `database` kind, location, path, max_size_gb,
+//! collation, hosted, connection_string, host, port, database, user, password, windows_auth, tls,
+//! app_login; `storage` mode, location, path, retention, max_disk_gb; `retention` months;
+//! `archive` format, destination, max_usage_gb, day, time, catch_up, encrypt; `consent` sync;
+//! `mapping` demo, override, auto, `map.<target>` (source column), confirm; `ready` dry_run.
+
+use super::plain::blocked_reason;
+use super::*;
+use crate::error::{InstallerError, OrCode};
+use crate::installation::package::{self, PackageReport, PackageRequest};
+use anyhow::Context;
+use std::io::{BufRead, Write};
+use std::sync::mpsc::RecvTimeoutError;
+
+pub(super) type Answers = Vec<(String, String)>;
+
+pub(super) fn page_id(page: Page) -> &'static str {
+    match page {
+        Page::Platform => "platform",
+        Page::Welcome => "welcome",
+        Page::License => "license",
+        Page::InstallType => "install_type",
+        Page::Destination => "destination",
+        Page::DataSource => "data_source",
+        Page::Database => "database",
+        Page::Storage => "storage",
+        Page::Retention => "retention",
+        Page::Archive => "archive",
+        Page::Consent => "consent",
+        Page::Mapping => "mapping",
+        Page::Ready => "ready",
+        Page::Installing => "installing",
+        Page::Complete => "complete",
+    }
+}
+
+/// Next answer record; `None` at end of input.
+fn read_answers<R: BufRead>(input: &mut R) -> Result<Option<Answers>> {
+    let mut answers = Answers::new();
+    let mut read_any = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(read_any.then_some(answers));
+        }
+        let text = line.trim();
+        if text.starts_with('#') {
+            continue;
+        }
+        read_any = true;
+        if text.is_empty() || text == "." {
+            return Ok(Some(answers));
+        }
+        if answers.is_empty() && text.starts_with('{') {
+            return parse_json(text).map(Some);
+        }
+        let (key, value) = text
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected key=value, got '{}'", text))?;
+        answers.push((key.trim().to_string(), value.trim().to_string()));
+    }
+}
+
+fn parse_json(text: &str) -> Result<Answers> {
+    let map: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(text).map_err(|e| anyhow::anyhow!("Invalid JSON answer: {}", e))?;
+    map.into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                serde_json::Value::Bool(b) => (if b { "yes" } else { "no" }).to_string(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Null => String::new(),
+                _ => anyhow::bail!("Answer '{}' must be a string, number or boolean", key),
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
+fn flag(key: &str, value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "y" | "yes" | "true" | "1" => Ok(true),
+        "n" | "no" | "false" | "0" => Ok(false),
+        _ => anyhow::bail!("'{}' must be yes or no, got '{}'", key, value),
+    }
+}
+
+fn pick<T: Copy>(key: &str, value: &str, options: &[(&str, T)]) -> Result<T> {
+    options
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, v)| *v)
+        .ok_or_else(|| {
+            let names: Vec<&str> = options.iter().map(|(n, _)| *n).collect();
+            anyhow::anyhow!(
+                "'{}' must be one of {}, got '{}'",
+                key,
+                names.join("|"),
+                value
+            )
+        })
+}
+
+/// Fill one page's fields from its answers (everything except the Mapping page).
+pub(super) fn apply_answers(state: &mut WizardState, answers: &Answers) -> Result<()> {
+    for (key, value) in answers {
+        let v = value.clone();
+        match (state.page, key.as_str()) {
+            (Page::Platform, "mode") => {
+                state.platform_selected = pick(
+                    key,
+                    value,
+                    &[
+                        ("windows", InstallMode::Windows),
+                        ("docker", InstallMode::Docker),
+                        ("linux", InstallMode::Docker),
+                    ],
+                )?
+            }
+            (Page::License, "accept") => state.license_accepted = flag(key, value)?,
+            (Page::InstallType, "type") => {
+                state.installation_type = pick(
+                    key,
+                    value,
+                    &[
+                        ("typical", InstallationType::Typical),
+                        ("custom", InstallationType::Custom),
+                        ("import", InstallationType::ImportConfig),
+                    ],
+                )?
+            }
+            (Page::InstallType, "config") => state.import_config_path.set(v),
+            (Page::Destination, "path") => state.destination_path.set(v),
+            (Page::Destination, "binaries" | "data" | "logs" | "temp") => {
+                state.layout_advanced = true;
+                let idx = ["binaries", "data", "logs", "temp"]
+                    .iter()
+                    .position(|k| *k == key)
+                    .unwrap_or(0);
+                if let Some(field) = install_layout::field_mut(state, idx + 1) {
+                    field.set(v);
+                }
+            }
+
+            (Page::DataSource, "kind") => {
+                let kind = pick(
+                    key,
+                    value,
+                    &[
+                        ("local", DataSourceKind::Local),
+                        ("remote", DataSourceKind::Remote),
+                        ("odbc", DataSourceKind::Odbc),
+                        ("oracle", DataSourceKind::Oracle),
+                        ("file", DataSourceKind::File),
+                    ],
+                )?;
+                let multi = !matches!(kind, DataSourceKind::Local | DataSourceKind::Remote);
+                if multi && !state.licensed.allows(features::MULTI_DATASOURCE) {
+                    anyhow::bail!(features::not_included_message(
+                        "ODBC, Oracle and file export data sources"
+                    ));
+                }
+                if kind == DataSourceKind::Oracle && !cfg!(feature = "oracle") {
+                    anyhow::bail!("This installer was built without Oracle support");
+                }
+                state.data_source_kind = kind;
+            }
+            (Page::DataSource, "object") => state.source_object_name.set(v),
+            (Page::DataSource, "connection_string") => state.odbc_connection_string.set(v),
+            (Page::DataSource, "driver") => state.oracle_driver.set(v),
+            (Page::DataSource, "address") => state.oracle_address.set(v),
+            (Page::DataSource, "folder") => state.file_source_folder.set(v),
+            (Page::DataSource, "delimiter") => state.file_source_delimiter.set(v),
+            (Page::DataSource, "host") => state.call_data_host.set(v),
+            (Page::DataSource, "port") => state.call_data_port.set(v),
+            (Page::DataSource, "database") => state.call_data_database.set(v),
+            (Page::DataSource, "user") => state.call_data_user.set(v),
+            (Page::DataSource, "password") => state.call_data_password.set(v),
+            (Page::DataSource, "windows_auth") => state.call_data_windows_auth = flag(key, value)?,
+
+            (Page::Database, "kind") => {
+                state.db_kind = pick(
+                    key,
+                    value,
+                    &[("new", DbKind::Local), ("existing", DbKind::Remote)],
+                )?
+            }
+            (Page::Database, "location") => {
+                state.new_db_location = pick(
+                    key,
+                    value,
+                    &[
+                        (
+                            NewDbLocation::ThisMachine.as_id(),
+                            NewDbLocation::ThisMachine,
+                        ),
+                        (
+                            NewDbLocation::SpecificPath.as_id(),
+                            NewDbLocation::SpecificPath,
+                        ),
+                    ],
+                )?
+            }
+            (Page::Database, "path") => state.new_db_specific_path.set(v),
+            (Page::Database, "max_size_gb") => state.new_db_max_size_gb.set(v),
+            (Page::Database, "collation") => state.new_db_collation.set(v),
+            (Page::Database, "app_login") => state.app_login.set(v),
+            (Page::Database, "hosted") => {
+                let mut hosts = vec![ExistingHostedWhere::OnPrem];
+                while hosts.len() < 7 {
+                    hosts.push(hosts[hosts.len() - 1].next());
+                }
+                let options: Vec<(&str, ExistingHostedWhere)> =
+                    hosts.iter().map(|h| (h.as_id(), *h)).collect();
+                set_existing_hosted_where(state, pick(key, value, &options)?);
+            }
+            (Page::Database, "connection_string") => {
+                state.db_use_conn_string = true;
+                state.db_conn_string.set(v);
+            }
+            (Page::Database, "host" | "port" | "database" | "user" | "password") => {
+                state.db_use_conn_string = false;
+                match key.as_str() {
+                    "host" => state.db_host.set(v),
+                    "port" => state.db_port.set(v),
+                    "database" => state.db_database.set(v),
+                    "user" => state.db_user.set(v),
+                    _ => state.db_password.set(v),
+                }
+            }
+            (Page::Database, "windows_auth") => {
+                state.db_windows_auth = flag(key, value)?;
+                if state.db_windows_auth {
+                    state.db_engine = DbEngine::SqlServer;
+                }
+            }
+            (Page::Database, "tls") => {
+                state.db_ssl_mode = pick(
+                    key,
+                    value,
+                    &[
+                        ("disable", "disable"),
+                        ("prefer", "prefer"),
+                        ("require", "require"),
+                    ],
+                )?
+                .to_string()
+            }
+
+            (Page::Storage, "mode") => {
+                state.storage_mode = pick(
+                    key,
+                    value,
+                    &[
+                        ("defaults", StorageMode::Defaults),
+                        ("custom", StorageMode::Custom),
+                    ],
+                )?
+            }
+            (Page::Storage, "location") => {
+                state.storage_location = pick(
+                    key,
+                    value,
+                    &[
+                        ("system", StorageLocation::System),
+                        ("attached", StorageLocation::Attached),
+                        ("custom", StorageLocation::Custom),
+                    ],
+                )?
+            }
+            (Page::Storage, "path") => state.storage_custom_path.set(v),
+            (Page::Storage, "retention") => {
+                state.retention_policy = pick(
+                    key,
+                    value,
+                    &[
+                        ("rolling18", RetentionPolicy::Rolling18),
+                        ("rolling12", RetentionPolicy::Rolling12),
+                        ("max_disk", RetentionPolicy::MaxDisk),
+                        ("keep_everything", RetentionPolicy::KeepEverything),
+                    ],
+                )?
+            }
+            (Page::Storage, "max_disk_gb") => state.max_disk_gb.set(v),
+
+            (Page::Retention, "months") => {
+                state.hot_retention_choice = match value.as_str() {
+                    "12" => HotRetentionChoice::Months12,
+                    "18" => HotRetentionChoice::Months18,
+                    _ => {
+                        state.hot_retention_custom_months.set(v);
+                        HotRetentionChoice::Custom
+                    }
+                }
+            }
+
+            (Page::Archive, "format") => {
+                state.archive_format = pick(
+                    key,
+                    value,
+                    &[
+                        ("ndjson", ArchiveFormatChoice::ZipNdjson),
+                        ("csv", ArchiveFormatChoice::ZipCsv),
+                    ],
+                )?
+            }
+            (Page::Archive, "destination") => state.archive_destination.set(v),
+            (Page::Archive, "max_usage_gb") => state.archive_max_usage_gb.set(v),
+            (Page::Archive, "day") => state.archive_schedule_day_of_month.set(v),
+            (Page::Archive, "time") => state.archive_schedule_time_local.set(v),
+            (Page::Archive, "catch_up") => state.archive_catch_up_on_startup = flag(key, value)?,
+            (Page::Archive, "encrypt") => state.archive_encrypt = flag(key, value)?,
+
+            (Page::Consent, "sync") => state.consent_to_sync = flag(key, value)?,
+            (Page::Ready, "dry_run") => state.dry_run = flag(key, value)?,
+            _ => anyhow::bail!("Unknown answer '{}' for page {}", key, page_id(state.page)),
+        }
+    }
+    Ok(())
+}
+
+struct Script<R, W> {
+    input: R,
+    out: W,
+    state: WizardState,
+    tx: mpsc::Sender<UiMsg>,
+    rx: mpsc::Receiver<UiMsg>,
+    secrets: Arc<SecretProtector>,
+    /// `--package`: the Ready page writes a distribution package instead of installing.
+    package: Option<PackageRequest>,
+    packaged: Option<PackageReport>,
+}
+
+impl<R: BufRead, W: Write> Script<R, W> {
+    fn new(input: R, out: W, state: WizardState, secrets: Arc<SecretProtector>) -> Self {
+        let (tx, rx) = mpsc::channel::<UiMsg>();
+        Self {
+            input,
+            out,
+            state,
+            tx,
+            rx,
+            secrets,
+            package: None,
+            packaged: None,
+        }
+    }
+
+    fn say(&mut self, line: impl AsRef<str>) -> Result<()> {
+        writeln!(self.out, "{}", line.as_ref())?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Apply background messages until `done` holds, reporting install progress.
+    fn pump(&mut self, done: impl Fn(&WizardState) -> bool) -> Result<()> {
+        while !done(&self.state) {
+            let msg = match self.rx.recv_timeout(Duration::from_millis(250)) {
+                Ok(msg) => msg,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if let UiMsg::InstallProgress(p) = &msg {
+                if !p.message.trim().is_empty() {
+                    let line = format!("% {} {}", p.percent, p.message);
+                    self.say(line)?;
+                }
+            }
+            apply_message(&mut self.state, msg);
+        }
+        Ok(())
+    }
+
+    /// Mapping page: scan, then demo, override, auto, `map.<target>`, confirm (in that order).
+    fn apply_mapping_answers(&mut self, answers: &Answers) -> Result<()> {
+        let get = |name: &str| {
+            answers
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(k, v)| flag(k, v))
+                .transpose()
+        };
+        if let Some((key, _)) = answers.iter().find(|(k, _)| {
+            !matches!(k.as_str(), "demo" | "override" | "auto" | "confirm")
+                && !k.starts_with("map.")
+        }) {
+            anyhow::bail!("Unknown answer '{}' for page mapping", key);
+        }
+
+        if let Some(demo) = get("demo")? {
+            if demo != self.state.mapping_demo_mode {
+                self.state.mapping_demo_mode = demo;
+                start_mapping_scan(&mut self.state, &self.tx);
+            }
+        }
+        self.pump(|s| !s.mapping_scanning)?;
+        if let Some(on) = get("override")? {
+            self.state.mapping_override = on;
+        }
+        if get("auto")? == Some(true) {
+            auto_map(&mut self.state);
+            self.state.modal = None;
+        }
+        for (key, value) in answers {
+            let Some(target) = key.strip_prefix("map.") else {
+                continue;
+            };
+            let target_id = self
+                .state
+                .target_fields
+                .iter()
+                .find(|t| t.id.eq_ignore_ascii_case(target) || t.name.eq_ignore_ascii_case(target))
+                .map(|t| t.id.clone())
+                .ok_or_else(|| anyhow::anyhow!("No target field '{}'", target))?;
+            let source_id = self
+                .state
+                .source_fields
+                .iter()
+                .find(|s| {
+                    s.raw_name.eq_ignore_ascii_case(value)
+                        || s.display_name.eq_ignore_ascii_case(value)
+                        || s.id == *value
+                })
+                .map(|s| s.id.clone())
+                .ok_or_else(|| anyhow::anyhow!("No source column '{}'", value))?;
+            let add = self.state.mapping_override;
+            apply_mapping(&mut self.state, &source_id, &target_id, add);
+            self.state.mapping_suggested.remove(&target_id);
+        }
+        if get("confirm")? == Some(true) {
+            self.state.selected_target_id = None;
+            confirm_suggestions(&mut self.state);
+        }
+        Ok(())
+    }
+
+    fn invalid(&mut self, reason: String) -> Result<i32> {
+        let page = page_id(self.state.page);
+        self.say(format!("! {}: {}", page, reason))?;
+        Err(anyhow::anyhow!("Page {}: {}", page, reason)).or_code(InstallerError::InvalidArguments)
+    }
+
+    fn run_pages(&mut self) -> Result<i32> {
+        loop {
+            let page = self.state.page;
+            self.say(format!("? {}", page_id(page)))?;
+            let Some(answers) = read_answers(&mut self.input)? else {
+                return self.invalid("Answers ended before this page".to_string());
+            };
+            let applied = if page == Page::Mapping {
+                self.apply_mapping_answers(&answers)
+            } else {
+                apply_answers(&mut self.state, &answers)
+            };
+            if let Err(e) = applied {
+                return self.invalid(e.to_string());
+            }
+
+            match page {
+                Page::Platform => {
+                    confirm_platform(&mut self.state);
+                    continue;
+                }
+                Page::Database => {
+                    self.state.db_test_status = DbTestStatus::Idle;
+                    start_db_test(&mut self.state, &self.tx);
+                    self.pump(|s| s.db_test_status != DbTestStatus::Testing)?;
+                }
+                Page::Ready => self.pump(|s| !s.disk_space_checking)?,
+                _ => {}
+            }
+
+            update_page_validation(&mut self.state);
+            if !can_go_next(&self.state) {
+                return self.invalid(blocked_reason(&self.state));
+            }
+            if page == Page::Ready {
+                return match self.package.take() {
+                    Some(req) => self.write_package(req),
+                    None => self.install(),
+                };
+            }
+            self.state.page = next_page(page);
+            if self.state.page == Page::Mapping {
+                enter_mapping_page(&mut self.state, &self.tx);
+            } else if self.state.page == Page::Ready {
+                disk_space::start(&mut self.state, &self.tx);
+            }
+        }
+    }
+
+    fn install(&mut self) -> Result<i32> {
+        info!("[PHASE: tui] [STEP: script_install] Starting install from scripted answers");
+        start_install(&mut self.state, &self.tx, &self.secrets);
+        self.pump(|s| s.page != Page::Installing || s.modal.is_some())?;
+        match self.state.modal.take() {
+            Some(Modal::Message { title, body, .. }) => {
+                let body = body.replace("\n\n", " ").replace('\n', " ");
+                self.say(format!("= {}: {}", title, body))?;
+            }
+            _ => self.say("= complete")?,
+        }
+        Ok(self.state.install_exit_code)
+    }
+
+    fn write_package(&mut self, req: PackageRequest) -> Result<i32> {
+        if crate::installation::remote::selected().is_some() {
+            return self.invalid(
+                "A package installs on the machine it runs on: drop --remote".to_string(),
+            );
+        }
+        let install = build_install_request(&self.state);
+        info!("[PHASE: tui] [STEP: script_package] Writing a distribution package from scripted answers");
+        let report = draft::block_on(package::build(
+            &req,
+            &install.install_mode,
+            &install.destination_folder,
+        ))??;
+        self.say(format!("= packaged: {}", report.folder))?;
+        self.packaged = Some(report);
+        Ok(0)
+    }
+}
+
+/// Runs the wizard from scripted answers on stdin; returns the install exit code.
+pub fn run(secrets: Arc<SecretProtector>) -> Result<i32> {
+    info!("[PHASE: tui] [STEP: start] Starting scripted question/answer wizard");
+
+    let mut state = new_real_wizard_state();
+    state.licensed = load_licensed_features(&secrets);
+    let stdin = io::stdin();
+    Script::new(stdin.lock(), io::stdout(), state, secrets).run_pages()
+}
+
+/// Walks the pages like [`run`] on the answers of `req` (questions on stderr), then writes the
+/// package (`--package`).
+pub fn package(secrets: Arc<SecretProtector>, req: PackageRequest) -> Result<PackageReport> {
+    info!("[PHASE: tui] [STEP: start] Starting scripted wizard for a distribution package");
+
+    let mut state = new_real_wizard_state();
+    state.licensed = load_licensed_features(&secrets);
+    let input = io::Cursor::new(req.answers.clone().into_bytes());
+    let mut script = Script::new(input, io::stderr(), state, secrets);
+    script.package = Some(req);
+    script.run_pages()?;
+    script
+        .packaged
+        .context("The answers ended before the Ready page")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_answers_run_pages_and_stop_at_invalid_page() {
+        let answers = "\
+mode=docker
+
+# Welcome: defaults
+.
+{\"accept\": true}
+
+path=/opt/cadalytix
+
+kind=local
+object=dbo.Calls
+
+kind=new
+max_size_gb=0
+";
+        let key_path = std::env::temp_dir()
+            .join(format!("script-{}", Uuid::new_v4()))
+            .join("key");
+        let mut script = Script::new(
+            std::io::Cursor::new(answers.as_bytes().to_vec()),
+            Vec::new(),
+            WizardState::new(),
+            Arc::new(SecretProtector::new(key_path)),
+        );
+        let err = script.run_pages().unwrap_err();
+        assert_eq!(
+            crate::error::error_code(&err),
+            InstallerError::InvalidArguments(anyhow::anyhow!("")).code()
+        );
+        assert_eq!(script.state.install_mode, InstallMode::Docker);
+        assert!(script.state.license_accepted);
+        assert_eq!(script.state.destination_path.value, "/opt/cadalytix");
+        assert_eq!(script.state.source_object_name.value, "dbo.Calls");
+        assert_eq!(script.state.page, Page::Database);
+
+        let out = String::from_utf8(script.out.clone()).unwrap();
+        assert!(out.starts_with("? platform\n? welcome\n? license\n? install_type\n"));
+        assert!(out.contains("! database: "), "{}", out);
+
+        let mut unknown = std::io::Cursor::new(b"color=blue\n".to_vec());
+        let bad = read_answers(&mut unknown).unwrap().unwrap();
+        assert!(apply_answers(&mut WizardState::new(), &bad).is_err());
+        assert!(parse_json("{\"accept\": [1]}").is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Source object picker (B on the Data Source page for database sources).
+//!
+//! Connects with the page's settings and lists the tables and views of the call data source
+//! (`preflight::list_source_objects`), with row counts where the source reports them. Typing
+//! filters the list by name, Up / Down move, Enter puts the object into "Source object name"
+//! and Esc closes. The listing doubles as the connection test: a failure is shown in the modal.
+
+use super::*;
+use crate::models::requests::ListSourceObjectsRequestDto;
+use crate::models::responses::{ListSourceObjectsResponseDto, SourceObjectDto};
+
+/// Open the picker and start listing objects in the background.
+pub(super) fn open(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    state.modal = Some(Modal::SourceObjects {
+        filter: String::new(),
+        objects: None,
+        truncated: false,
+        selected: 0,
+        error: None,
+    });
+    let payload = ListSourceObjectsRequestDto {
+        call_data_connection_string: build_call_data_connection_string(state),
+        call_data_driver: call_data_driver(state),
+        demo_mode: state.mapping_demo_mode,
+    };
+
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let result = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => match rt.block_on(preflight::list_source_objects(payload)) {
+                Ok(api) if api.success => api
+                    .data
+                    .ok_or_else(|| "The source returned no object list.".to_string()),
+                Ok(api) => Err(api.error.unwrap_or_else(|| {
+                    "Unable to list the source's tables and views.".to_string()
+                })),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(format!("Internal error starting the object list: {}", e)),
+        };
+        let _ = tx.send(UiMsg::SourceObjectsLoaded(result));
+    });
+}
+
+/// Fill the picker with a finished listing (ignored once the picker was closed).
+pub(super) fn loaded(
+    state: &mut WizardState,
+    result: Result<ListSourceObjectsResponseDto, String>,
+) {
+    let Some(Modal::SourceObjects {
+        objects,
+        truncated,
+        selected,
+        error,
+        ..
+    }) = state.modal.as_mut()
+    else {
+        return;
+    };
+    match result {
+        Ok(list) => {
+            // Start on the object already entered, if it is listed.
+            let current = state.source_object_name.value.trim();
+            *selected = list
+                .objects
+                .iter()
+                .position(|o| o.qualified_name().eq_ignore_ascii_case(current))
+                .unwrap_or(0);
+            *truncated = list.truncated;
+            *objects = Some(list.objects);
+            *error = None;
+        }
+        Err(e) => {
+            *objects = Some(Vec::new());
+            *error = Some(e);
+        }
+    }
+}
+
+/// Objects whose qualified name contains `filter` (case-insensitive).
+fn matching<'a>(objects: &'a [SourceObjectDto], filter: &str) -> Vec<&'a SourceObjectDto> {
+    let filter = filter.trim().to_lowercase();
+    objects
+        .iter()
+        .filter(|o| o.qualified_name().to_lowercase().contains(&filter))
+        .collect()
+}
+
+pub(super) fn handle_key(state: &mut WizardState, code: KeyCode) {
+    let Some(Modal::SourceObjects {
+        filter,
+        objects,
+        selected,
+        ..
+    }) = state.modal.as_mut()
+    else {
+        return;
+    };
+    let shown = objects.as_deref().map_or(0, |o| matching(o, filter).len());
+    match code {
+        KeyCode::Esc => state.modal = None,
+        KeyCode::Up => *selected = selected.saturating_sub(1),
+        KeyCode::Down => *selected = (*selected + 1).min(shown.saturating_sub(1)),
+        KeyCode::Enter => {
+            let picked = objects.as_deref().and_then(|o| {
+                matching(o, filter)
+                    .get(*selected)
+                    .map(|o| o.qualified_name())
+            });
+            if let Some(name) = picked {
+                info!(
+                    "[PHASE: tui] [STEP: source_objects] Source object selected (name={})",
+                    name
+                );
+                state.source_object_name.set(name);
+                state.modal = None;
+                update_page_validation(state);
+            }
+        }
+        KeyCode::Backspace => {
+            filter.pop();
+            *selected = 0;
+        }
+        KeyCode::Char(c) => {
+            filter.push(c);
+            *selected = 0;
+        }
+        _ => {}
+    }
+}
+
+fn row_text(object: &SourceObjectDto) -> String {
+    let rows = match object.row_count {
+        Some(n) => format!("{} rows", n),
+        None => String::new(),
+    };
+    format!(
+        "{:<48} {:<6} {:>14}",
+        object.qualified_name(),
+        object.kind,
+        rows
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    filter: &str,
+    objects: Option<&[SourceObjectDto]>,
+    truncated: bool,
+    selected: usize,
+    error: Option<&str>,
+) {
+    let modal_w = 78u16.min(window_area.width.saturating_sub(4)).max(48);
+    let modal_h = 20u16.min(window_area.height.saturating_sub(4)).max(10);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Source Tables and Views");
+    f.render_widget(block, area);
+
+    let inner = area.inner(&ratatui::layout::Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(inner);
+
+    let header = Paragraph::new(Text::from(vec![
+        Line::from(format!("Search: {}_", filter)),
+        Line::from("Type to filter  Enter=select  Esc=cancel"),
+    ]));
+    f.render_widget(header, rows[0]);
+
+    let shown = objects.map(|o| matching(o, filter)).unwrap_or_default();
+    let mut lines: Vec<Line> = Vec::new();
+    match (objects, error) {
+        (None, _) => lines.push(Line::from("Connecting and reading the catalog...")),
+        (Some(_), Some(e)) => {
+            for l in e.lines() {
+                lines.push(Line::from(ratatui::text::Span::styled(
+                    l.to_string(),
+                    theme::current().error(),
+                )));
+            }
+        }
+        (Some(_), None) if shown.is_empty() => lines.push(Line::from("(no matching objects)")),
+        (Some(_), None) => {
+            let list_height = rows[1].height as usize;
+            let start = selected.saturating_sub(list_height / 2);
+            for (i, object) in shown.iter().enumerate().skip(start).take(list_height) {
+                let row = Rect {
+                    y: rows[1].y + (i - start) as u16,
+                    height: 1,
+                    ..rows[1]
+                };
+                mouse::record(state, row, HitTarget::BrowseEntry(i));
+                lines.push(Line::from(ratatui::text::Span::styled(
+                    row_text(object),
+                    theme::current().focus_if(i == selected),
+                )));
+            }
+        }
+    }
+    f.render_widget(Paragraph::new(Text::from(lines)), rows[1]);
+
+    let footer = match objects {
+        Some(all) if error.is_none() => format!(
+            "{} of {} object(s){}",
+            shown.len(),
+            all.len(),
+            if truncated {
+                " (list truncated; type to narrow it)"
+            } else {
+                ""
+            }
+        ),
+        _ => String::new(),
+    };
+    f.render_widget(Paragraph::new(footer), rows[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_filters_and_enter_sets_the_source_object() {
+        let mut state = WizardState::new();
+        state.page = Page::DataSource;
+        state.source_object_name.set("dbo.Units");
+        state.modal = Some(Modal::SourceObjects {
+            filter: String::new(),
+            objects: None,
+            truncated: false,
+            selected: 0,
+            error: None,
+        });
+        let object = |schema: &str, name: &str| SourceObjectDto {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            kind: "table".to_string(),
+            row_count: Some(3),
+        };
+        loaded(
+            &mut state,
+            Ok(ListSourceObjectsResponseDto {
+                objects: vec![
+                    object("dbo", "CallData"),
+                    object("dbo", "Units"),
+                    object("fire", "CallData"),
+                ],
+                truncated: false,
+            }),
+        );
+        assert!(matches!(
+            state.modal,
+            Some(Modal::SourceObjects { selected: 1, .. })
+        ));
+
+        for c in "calld".chars() {
+            handle_key(&mut state, KeyCode::Char(c));
+        }
+        handle_key(&mut state, KeyCode::Down);
+        handle_key(&mut state, KeyCode::Down);
+        handle_key(&mut state, KeyCode::Enter);
+        assert!(state.modal.is_none());
+        assert_eq!(state.source_object_name.value, "fire.CallData");
+
+        state.modal = Some(Modal::SourceObjects {
+            filter: String::new(),
+            objects: None,
+            truncated: false,
+            selected: 0,
+            error: None,
+        });
+        loaded(&mut state, Err("Login failed".to_string()));
+        handle_key(&mut state, KeyCode::Enter);
+        assert!(matches!(
+            state.modal,
+            Some(Modal::SourceObjects { error: Some(_), .. })
+        ));
+    }
+}
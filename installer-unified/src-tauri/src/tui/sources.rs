@@ -0,0 +1,340 @@
+//! Several call data sources in one install (N / S / X on the Data Source page, S on Mapping).
+//!
+//! Agencies with separate Fire and EMS CAD databases add one source per CAD. The Data Source and
+//! Mapping pages edit one source at a time: the active source lives in the wizard's own fields,
+//! the others are parked as [`SourceDraft`]s and swapped in when the operator switches. Each
+//! source keeps its connection, source object and mapping set; the install request carries the
+//! first source as the primary one and the rest as `additional_data_sources`.
+//!
+//! With a single source `WizardState::data_sources` is empty. Otherwise it holds every source in
+//! order and the slot at `active_source` is a stale placeholder for the one being edited.
+
+use super::*;
+use crate::api::installer::DataSourceConfig;
+
+/// The per-source part of the Data Source and Mapping pages.
+#[derive(Debug, Clone)]
+pub(super) struct SourceDraft {
+    name: TextInput,
+    kind: DataSourceKind,
+    source_object_name: TextInput,
+    odbc_connection_string: TextInput,
+    oracle_driver: TextInput,
+    oracle_address: TextInput,
+    file_source_folder: TextInput,
+    file_source_delimiter: TextInput,
+    call_data_host: TextInput,
+    call_data_port: TextInput,
+    call_data_database: TextInput,
+    call_data_user: TextInput,
+    call_data_password: TextInput,
+    call_data_windows_auth: bool,
+    mapping_scan_key: Option<String>,
+    source_fields: Vec<SourceField>,
+    source_to_targets: HashMap<String, Vec<String>>,
+    target_to_source: HashMap<String, String>,
+    mapping_transforms: HashMap<String, Vec<FieldTransform>>,
+    mapping_suggested: HashMap<String, MappingSuggestion>,
+}
+
+impl SourceDraft {
+    /// A new source with the wizard's defaults.
+    fn new(name: &str) -> Self {
+        let mut fresh = WizardState::new();
+        fresh.source_name.set(name);
+        Self::take(&mut fresh)
+    }
+
+    fn take(state: &mut WizardState) -> Self {
+        let mut draft = Self {
+            name: TextInput::new("", false),
+            kind: DataSourceKind::Local,
+            source_object_name: TextInput::new("", false),
+            odbc_connection_string: TextInput::new("", false),
+            oracle_driver: TextInput::new("", false),
+            oracle_address: TextInput::new("", false),
+            file_source_folder: TextInput::new("", false),
+            file_source_delimiter: TextInput::new("", false),
+            call_data_host: TextInput::new("", false),
+            call_data_port: TextInput::new("", false),
+            call_data_database: TextInput::new("", false),
+            call_data_user: TextInput::new("", false),
+            call_data_password: TextInput::new("", true),
+            call_data_windows_auth: false,
+            mapping_scan_key: None,
+            source_fields: Vec::new(),
+            source_to_targets: HashMap::new(),
+            target_to_source: HashMap::new(),
+            mapping_transforms: HashMap::new(),
+            mapping_suggested: HashMap::new(),
+        };
+        draft.swap(state);
+        draft
+    }
+
+    /// Exchange this draft with the source the wizard is editing.
+    fn swap(&mut self, state: &mut WizardState) {
+        use std::mem::swap;
+        swap(&mut self.name, &mut state.source_name);
+        swap(&mut self.kind, &mut state.data_source_kind);
+        swap(&mut self.source_object_name, &mut state.source_object_name);
+        swap(
+            &mut self.odbc_connection_string,
+            &mut state.odbc_connection_string,
+        );
+        swap(&mut self.oracle_driver, &mut state.oracle_driver);
+        swap(&mut self.oracle_address, &mut state.oracle_address);
+        swap(&mut self.file_source_folder, &mut state.file_source_folder);
+        swap(
+            &mut self.file_source_delimiter,
+            &mut state.file_source_delimiter,
+        );
+        swap(&mut self.call_data_host, &mut state.call_data_host);
+        swap(&mut self.call_data_port, &mut state.call_data_port);
+        swap(&mut self.call_data_database, &mut state.call_data_database);
+        swap(&mut self.call_data_user, &mut state.call_data_user);
+        swap(&mut self.call_data_password, &mut state.call_data_password);
+        swap(
+            &mut self.call_data_windows_auth,
+            &mut state.call_data_windows_auth,
+        );
+        swap(&mut self.mapping_scan_key, &mut state.mapping_scan_key);
+        swap(&mut self.source_fields, &mut state.source_fields);
+        swap(&mut self.source_to_targets, &mut state.source_to_targets);
+        swap(&mut self.target_to_source, &mut state.target_to_source);
+        swap(&mut self.mapping_transforms, &mut state.mapping_transforms);
+        swap(&mut self.mapping_suggested, &mut state.mapping_suggested);
+    }
+}
+
+/// Number of call data sources (at least 1).
+pub(super) fn count(state: &WizardState) -> usize {
+    state.data_sources.len().max(1)
+}
+
+/// "Source 2 of 3: ems" for page headers; `None` with a single source.
+pub(super) fn label(state: &WizardState) -> Option<String> {
+    (count(state) > 1).then(|| {
+        format!(
+            "Source {} of {}: {}",
+            state.active_source + 1,
+            count(state),
+            state.source_name.value.trim()
+        )
+    })
+}
+
+/// Switching sources needs the current one complete and no header scan running.
+fn can_switch(state: &WizardState) -> bool {
+    !state.mapping_scanning && (state.page != Page::DataSource || can_go_next(state))
+}
+
+fn switch_to(state: &mut WizardState, index: usize) {
+    let current = state.active_source;
+    if index == current || index >= state.data_sources.len() {
+        return;
+    }
+    let mut drafts = std::mem::take(&mut state.data_sources);
+    drafts[current] = SourceDraft::take(state);
+    drafts[index].swap(state);
+    state.data_sources = drafts;
+    state.active_source = index;
+    state.selected_source_id = None;
+    state.selected_target_id = None;
+    state.source_list_index = 0;
+    state.target_list_index = 0;
+    state.focus = if state.page == Page::Mapping {
+        FocusTarget::Mapping(MappingFocus::SourceList)
+    } else {
+        FocusTarget::Field(0)
+    };
+    info!(
+        "[PHASE: tui] [STEP: data_sources] Editing source {} of {} (name={})",
+        index + 1,
+        state.data_sources.len(),
+        state.source_name.value.trim()
+    );
+}
+
+/// Add a source after the last one and start editing it (licensed installs only).
+pub(super) fn add(state: &mut WizardState) {
+    if !state.licensed.allows(features::MULTI_DATASOURCE) {
+        state.modal = Some(Modal::Message {
+            title: "Data sources".to_string(),
+            body: features::not_included_message("Several call data sources"),
+            return_to: None,
+        });
+        return;
+    }
+    if !can_switch(state) {
+        return;
+    }
+    if state.data_sources.is_empty() {
+        state.data_sources.push(SourceDraft::new(""));
+        state.active_source = 0;
+    }
+    let n = state.data_sources.len() + 1;
+    let name = (n..)
+        .map(|i| format!("source{}", i))
+        .find(|name| !names(state).iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .unwrap_or_default();
+    state.data_sources.push(SourceDraft::new(&name));
+    switch_to(state, n - 1);
+}
+
+/// Edit the next source (wrapping around). On the Mapping page its fields are scanned if needed.
+pub(super) fn next(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    if count(state) < 2 || !can_switch(state) {
+        return;
+    }
+    switch_to(state, (state.active_source + 1) % state.data_sources.len());
+    if state.page == Page::Mapping {
+        enter_mapping_page(state, tx);
+    }
+}
+
+/// Drop the source being edited; the previous one (or the next, for the first) takes its place.
+pub(super) fn remove(state: &mut WizardState) {
+    if count(state) < 2 || state.mapping_scanning {
+        return;
+    }
+    let removed = state.active_source;
+    let shown = if removed == 0 { 1 } else { removed - 1 };
+    let mut drafts = std::mem::take(&mut state.data_sources);
+    drafts[shown].swap(state);
+    drafts.remove(removed);
+    state.active_source = if shown > removed { shown - 1 } else { shown };
+    if drafts.len() == 1 {
+        drafts.clear();
+        state.active_source = 0;
+    }
+    state.data_sources = drafts;
+    state.focus = FocusTarget::Field(0);
+    update_page_validation(state);
+}
+
+/// Names of every source, in order.
+pub(super) fn names(state: &WizardState) -> Vec<String> {
+    each(state, |s| s.source_name.value.trim().to_string())
+}
+
+/// The name is required and must be unique (and usable in setting keys) once there are several
+/// sources.
+pub(super) fn name_error(state: &WizardState) -> Option<String> {
+    if count(state) < 2 {
+        return None;
+    }
+    let name = state.source_name.value.trim();
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Some("Use letters, digits, '-' or '_' for the source name.".to_string());
+    }
+    let taken = names(state)
+        .iter()
+        .enumerate()
+        .any(|(i, n)| i != state.active_source && n.eq_ignore_ascii_case(name));
+    taken.then(|| format!("Another source is already named '{}'.", name))
+}
+
+/// Name of another source whose mapping is not finished yet.
+pub(super) fn unmapped(state: &WizardState) -> Option<String> {
+    let complete = each(state, mapping_complete);
+    names(state)
+        .into_iter()
+        .enumerate()
+        .find(|(i, _)| *i != state.active_source && !complete[*i])
+        .map(|(_, name)| name)
+}
+
+/// `f` applied to every source in order, each parked one loaded into a scratch state.
+fn each<T>(state: &WizardState, f: impl Fn(&WizardState) -> T) -> Vec<T> {
+    if state.data_sources.is_empty() {
+        return vec![f(state)];
+    }
+    (0..state.data_sources.len())
+        .map(|i| {
+            if i == state.active_source {
+                return f(state);
+            }
+            let mut scratch = WizardState::new();
+            scratch.licensed = state.licensed.clone();
+            scratch.target_fields = state.target_fields.clone();
+            scratch.mapping_override = state.mapping_override;
+            state.data_sources[i].clone().swap(&mut scratch);
+            f(&scratch)
+        })
+        .collect()
+}
+
+/// Every source as sent with the install request, the primary one first.
+pub(super) fn configs(state: &WizardState) -> Vec<DataSourceConfig> {
+    each(state, |s| DataSourceConfig {
+        name: s.source_name.value.trim().to_string(),
+        call_data_connection_string: build_call_data_connection_string(s),
+        source_object_name: s.source_object_name.value.clone(),
+        file_source: file_source_config(s),
+        call_data_driver: call_data_driver(s),
+        mappings: s
+            .target_to_source
+            .iter()
+            .map(|(target_id, source_id)| (target_id.clone(), mapping_source_raw(s, source_id)))
+            .collect(),
+        mapping_state: Some(current_mapping_state(s)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_keep_their_own_connection_and_mapping() {
+        let (tx, _rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.page = Page::DataSource;
+        state.source_name.set("fire");
+        state.call_data_database.set("FireCAD");
+        state.target_to_source = HashMap::from([("IncidentNumber".to_string(), "INC".to_string())]);
+
+        add(&mut state);
+        assert_eq!(label(&state).as_deref(), Some("Source 2 of 2: source2"));
+        assert!(state.target_to_source.is_empty());
+        state.source_name.set("ems");
+        state.data_source_kind = DataSourceKind::File;
+        state.file_source_folder.set("/exports/ems");
+        assert_eq!(name_error(&state), None);
+
+        next(&mut state, &tx);
+        assert_eq!(state.call_data_database.value, "FireCAD");
+        assert_eq!(names(&state), vec!["fire", "ems"]);
+
+        let configs = configs(&state);
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].mappings["IncidentNumber"], "INC");
+        assert!(configs[0].call_data_connection_string.contains("FireCAD"));
+        assert_eq!(
+            configs[1].file_source.as_ref().map(|f| f.folder.as_str()),
+            Some("/exports/ems")
+        );
+        let req = build_install_request(&state);
+        assert_eq!(req.data_source_name, "fire");
+        assert_eq!(req.additional_data_sources.len(), 1);
+        assert_eq!(req.additional_data_sources[0].name, "ems");
+
+        state.source_name.set("EMS");
+        assert!(name_error(&state).is_some());
+        state.source_name.set("fire");
+
+        remove(&mut state);
+        assert_eq!(count(&state), 1);
+        assert_eq!(state.source_name.value, "ems");
+        assert_eq!(label(&state), None);
+        assert!(build_install_request(&state)
+            .additional_data_sources
+            .is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! Filesystem guidance on the Storage page.
+//!
+//! While Storage is shown, the filesystem under the database folder is detected in the background
+//! (`installation::storage_fs::inspect`) whenever the folder or engine changes. The page lists the
+//! engine-specific advice for it; FAT32/exFAT and memory-backed filesystems block Next.
+
+use super::*;
+use crate::installation::storage_fs::{self, StorageFilesystem};
+
+/// The parts of the request the result depends on.
+fn request_key(req: &StartInstallRequest) -> String {
+    format!(
+        "{}|{:?}",
+        installer::guess_engine(&req.config_db_connection_string),
+        storage_fs::storage_dir(req)
+    )
+}
+
+/// Inspect the storage folder when Storage is shown and the settings changed since the last run.
+pub(super) fn refresh(state: &mut WizardState, tx: &mpsc::Sender<UiMsg>) {
+    if state.page != Page::Storage {
+        return;
+    }
+    let req = build_install_request(state);
+    let key = request_key(&req);
+    if state.storage_fs_key.as_deref() == Some(key.as_str()) {
+        return;
+    }
+    state.storage_fs_key = Some(key.clone());
+    state.storage_fs = None;
+    state.storage_fs_checking = true;
+
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let inspected = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(rt) => rt.block_on(storage_fs::inspect(&req)),
+            Err(e) => {
+                warn!(
+                    "[PHASE: tui] [STEP: storage_fs] Detection not started: {}",
+                    e
+                );
+                StorageFilesystem::default()
+            }
+        };
+        let _ = tx.send(UiMsg::StorageFilesystemInspected { key, inspected });
+    });
+}
+
+pub(super) fn inspected(state: &mut WizardState, key: String, inspected: StorageFilesystem) {
+    if state.storage_fs_key.as_deref() != Some(key.as_str()) {
+        return;
+    }
+    state.storage_fs_checking = false;
+    state.storage_fs = Some(inspected);
+}
+
+/// Why the database cannot go to the chosen folder, if it cannot.
+pub(super) fn blocked(state: &WizardState) -> Option<String> {
+    state.storage_fs.as_ref().and_then(|fs| fs.blocked.clone())
+}
+
+/// Storage page lines (none before a result arrived, or for an undetected filesystem).
+pub(super) fn lines(state: &WizardState) -> Vec<String> {
+    if state.storage_fs_checking {
+        return vec![String::new(), "Filesystem: checking...".to_string()];
+    }
+    let Some(fs) = state
+        .storage_fs
+        .as_ref()
+        .filter(|fs| !fs.fs_type.is_empty())
+    else {
+        return Vec::new();
+    };
+    let mut lines = vec![String::new(), fs.summary()];
+    lines.extend(fs.advice.iter().map(|a| format!("  - {}", a)));
+    if let Some(reason) = &fs.blocked {
+        lines.push(format!("BLOCKED: {}", reason));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fat_storage_folder_blocks_next() {
+        let (tx, _rx) = mpsc::channel();
+        let mut state = WizardState::new();
+        state.page = Page::Storage;
+        state.storage_mode = StorageMode::Custom;
+        state.storage_location = StorageLocation::Custom;
+        state.storage_custom_path.set("/media/usb/cadalytix");
+
+        refresh(&mut state, &tx);
+        assert!(state.storage_fs_checking);
+        let key = state.storage_fs_key.clone().unwrap();
+
+        let (blocked, advice) =
+            storage_fs::assess("vfat", None, "postgres", "/media/usb/cadalytix".as_ref());
+        let fat = StorageFilesystem {
+            path: "/media/usb/cadalytix".into(),
+            fs_type: "vfat".to_string(),
+            cluster_bytes: None,
+            blocked,
+            advice,
+        };
+        inspected(&mut state, "stale".to_string(), fat.clone());
+        assert!(state.storage_fs.is_none());
+        inspected(&mut state, key, fat);
+        assert!(!can_go_next(&state));
+        assert!(lines(&state).last().unwrap().starts_with("BLOCKED: vfat"));
+
+        // Same settings: no second detection.
+        refresh(&mut state, &tx);
+        assert!(!state.storage_fs_checking);
+
+        state.storage_custom_path.set("/srv/cadalytix");
+        refresh(&mut state, &tx);
+        assert!(state.storage_fs_checking);
+        assert!(can_go_next(&state));
+    }
+}
@@ -0,0 +1,203 @@
+//! TUI themes for low-vision operators and logging terminals.
+//!
+//! - `default`: reverse-video focus, gray hints, red errors
+//! - `high-contrast`: white on black, bold focus and errors, no dark-gray text
+//! - `mono`: no colors, only reverse/bold/underline (serial consoles, captured sessions)
+//!
+//! Chosen once per process, first match wins: `--theme=<name>`, `CADALYTIX_TUI_THEME`,
+//! `NO_COLOR` (any non-empty value selects `mono`), `default`.
+
+use anyhow::Result;
+use log::info;
+use ratatui::style::{Color, Modifier, Style};
+use std::sync::OnceLock;
+
+const THEME_ENV: &str = "CADALYTIX_TUI_THEME";
+
+static SELECTED: OnceLock<Theme> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Default,
+    HighContrast,
+    Monochrome,
+}
+
+impl Theme {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(Self::Default),
+            "high-contrast" | "highcontrast" | "hc" => Some(Self::HighContrast),
+            "mono" | "monochrome" | "no-color" => Some(Self::Monochrome),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::HighContrast => "high-contrast",
+            Self::Monochrome => "mono",
+        }
+    }
+
+    /// Whole-screen background/foreground.
+    pub fn base(self) -> Style {
+        match self {
+            Self::HighContrast => Style::default().fg(Color::White).bg(Color::Black),
+            _ => Style::default(),
+        }
+    }
+
+    /// Focused button, selected list row.
+    pub fn focused(self) -> Style {
+        match self {
+            Self::HighContrast => {
+                Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            }
+            _ => Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// `focused` when `on`, otherwise plain text.
+    pub fn focus_if(self, on: bool) -> Style {
+        if on {
+            self.focused()
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Disabled buttons.
+    pub fn disabled(self) -> Style {
+        match self {
+            Self::Default => Style::default().fg(Color::DarkGray),
+            Self::HighContrast => Style::default().fg(Color::Gray),
+            Self::Monochrome => Style::default().add_modifier(Modifier::DIM),
+        }
+    }
+
+    /// Secondary text (key hints).
+    pub fn hint(self) -> Style {
+        match self {
+            Self::Default => Style::default().fg(Color::DarkGray),
+            Self::HighContrast => Style::default().fg(Color::Gray),
+            Self::Monochrome => Style::default(),
+        }
+    }
+
+    /// Labels and related rows that should stand out without looking focused.
+    pub fn emphasis(self) -> Style {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+
+    /// Inline validation and import errors.
+    pub fn error(self) -> Style {
+        match self {
+            Self::Default => Style::default().fg(Color::Red),
+            Self::HighContrast => Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            Self::Monochrome => Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Search matches in the log viewer.
+    pub fn matched(self) -> Style {
+        match self {
+            Self::Default => Style::default().fg(Color::Black).bg(Color::Yellow),
+            Self::HighContrast => Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            Self::Monochrome => {
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            }
+        }
+    }
+}
+
+fn resolve(flag: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Result<Theme> {
+    if let Some(name) = flag {
+        return Theme::parse(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown theme '{}' (use default, high-contrast or mono)",
+                name
+            )
+        });
+    }
+    if let Some(theme) = var(THEME_ENV).and_then(|v| Theme::parse(&v)) {
+        return Ok(theme);
+    }
+    if var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return Ok(Theme::Monochrome);
+    }
+    Ok(Theme::Default)
+}
+
+/// Select the theme from `--theme=<name>` (before the TUI starts).
+pub fn select(name: &str) -> Result<()> {
+    let theme = resolve(Some(name), |n| std::env::var(n).ok())?;
+    let _ = SELECTED.set(theme);
+    Ok(())
+}
+
+/// The active theme (flag, then environment).
+pub fn current() -> Theme {
+    *SELECTED.get_or_init(|| {
+        let theme = resolve(None, |n| std::env::var(n).ok()).unwrap_or(Theme::Default);
+        info!(
+            "[PHASE: initialization] [STEP: tui_theme] TUI theme: {}",
+            theme.name()
+        );
+        theme
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_resolution_honors_flag_env_and_no_color() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |n: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == n)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(resolve(None, env(&[])).unwrap(), Theme::Default);
+        assert_eq!(
+            resolve(None, env(&[("NO_COLOR", "1")])).unwrap(),
+            Theme::Monochrome
+        );
+        assert_eq!(
+            resolve(None, env(&[("NO_COLOR", "")])).unwrap(),
+            Theme::Default
+        );
+        assert_eq!(
+            resolve(None, env(&[(THEME_ENV, "HC"), ("NO_COLOR", "1")])).unwrap(),
+            Theme::HighContrast
+        );
+        assert_eq!(
+            resolve(Some("default"), env(&[("NO_COLOR", "1")])).unwrap(),
+            Theme::Default
+        );
+        assert!(resolve(Some("neon"), env(&[])).is_err());
+
+        // Monochrome never emits a color.
+        let mono = Theme::Monochrome;
+        for style in [
+            mono.base(),
+            mono.focused(),
+            mono.disabled(),
+            mono.hint(),
+            mono.error(),
+            mono.matched(),
+        ] {
+            assert_eq!((style.fg, style.bg), (None, None));
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Value transforms modal (T on the Mapping page).
+//!
+//! Lists the transforms of one target field in the order ingestion applies them. Keys: 1-5 add a
+//! transform (those with parameters open a one-line prompt), Up/Down select, D or Delete removes
+//! the selected one, Enter or Esc closes. Transforms live in `WizardState::mapping_transforms`
+//! and go out with the mapping state (see `mapping::transform`).
+
+use super::*;
+use crate::mapping::transform::{self, FieldTransform};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TransformKind {
+    Trim,
+    ParseDate,
+    ConvertUnit,
+    Concat,
+    DefaultValue,
+}
+
+impl TransformKind {
+    const ALL: [TransformKind; 5] = [
+        TransformKind::Trim,
+        TransformKind::ParseDate,
+        TransformKind::ConvertUnit,
+        TransformKind::Concat,
+        TransformKind::DefaultValue,
+    ];
+
+    fn menu_label(self) -> &'static str {
+        match self {
+            TransformKind::Trim => "Trim",
+            TransformKind::ParseDate => "Parse date",
+            TransformKind::ConvertUnit => "Convert unit",
+            TransformKind::Concat => "Concat field",
+            TransformKind::DefaultValue => "Default value",
+        }
+    }
+
+    /// Prompt for the parameter, or None when the transform has none.
+    fn prompt(self) -> Option<String> {
+        match self {
+            TransformKind::Trim => None,
+            TransformKind::ParseDate => {
+                Some("Date format (chrono/strftime, e.g. %m/%d/%Y %H:%M):".to_string())
+            }
+            TransformKind::ConvertUnit => Some(format!(
+                "From and to unit, e.g. 'miles kilometers' ({}):",
+                transform::unit_names().collect::<Vec<_>>().join(", ")
+            )),
+            TransformKind::Concat => Some(
+                "Source field to append, optionally '|' and a separator (default: space):"
+                    .to_string(),
+            ),
+            TransformKind::DefaultValue => Some("Value used when the field is empty:".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Prompt {
+    kind: TransformKind,
+    input: String,
+}
+
+/// Build the transform from the prompt input (ignored for parameterless kinds).
+fn build(
+    state: &WizardState,
+    kind: TransformKind,
+    input: &str,
+) -> std::result::Result<FieldTransform, String> {
+    match kind {
+        TransformKind::Trim => Ok(FieldTransform::Trim),
+        TransformKind::ParseDate => {
+            let format = input.trim();
+            if format.is_empty() {
+                return Err("Enter a date format.".to_string());
+            }
+            Ok(FieldTransform::ParseDate {
+                format: format.to_string(),
+            })
+        }
+        TransformKind::ConvertUnit => {
+            let units: Vec<&str> = input.split_whitespace().collect();
+            let [from, to] = units.as_slice() else {
+                return Err("Enter two units, e.g. 'miles kilometers'.".to_string());
+            };
+            let t = FieldTransform::ConvertUnit {
+                from: from.to_ascii_lowercase(),
+                to: to.to_ascii_lowercase(),
+            };
+            transform::apply(std::slice::from_ref(&t), Some("0"), |_| None)
+                .map(|_| t)
+                .map_err(|e| e.to_string())
+        }
+        TransformKind::Concat => {
+            let (field, separator) = input.split_once('|').unwrap_or((input, " "));
+            let field = field.trim();
+            let source = state.source_fields.iter().find(|s| {
+                s.id == field
+                    || s.raw_name.eq_ignore_ascii_case(field)
+                    || s.display_name.eq_ignore_ascii_case(field)
+            });
+            match source {
+                Some(s) => Ok(FieldTransform::Concat {
+                    source_id: s.id.clone(),
+                    separator: separator.to_string(),
+                }),
+                None => Err(format!("No source field named '{}'.", field)),
+            }
+        }
+        TransformKind::DefaultValue => Ok(FieldTransform::DefaultValue {
+            value: input.to_string(),
+        }),
+    }
+}
+
+/// Open the modal for the selected target field.
+pub(super) fn open(state: &mut WizardState) {
+    let target_id = state.selected_target_id.clone().or_else(|| {
+        filtered_target_ids(state)
+            .get(state.target_list_index)
+            .cloned()
+    });
+    state.modal = Some(match target_id {
+        Some(target_id) => Modal::Transforms {
+            target_id,
+            selected: 0,
+            prompt: None,
+            error: None,
+        },
+        None => Modal::Message {
+            title: "Value Transforms".to_string(),
+            body: "Select a target field first.".to_string(),
+            return_to: None,
+        },
+    });
+}
+
+pub(super) fn handle_key(state: &mut WizardState, code: KeyCode) {
+    let Some(Modal::Transforms {
+        target_id,
+        mut selected,
+        mut prompt,
+        mut error,
+    }) = state.modal.clone()
+    else {
+        return;
+    };
+    let count = state.mapping_transforms.get(&target_id).map_or(0, Vec::len);
+
+    if let Some(p) = prompt.as_mut() {
+        match code {
+            KeyCode::Enter => match build(state, p.kind, &p.input) {
+                Ok(t) => {
+                    state
+                        .mapping_transforms
+                        .entry(target_id.clone())
+                        .or_default()
+                        .push(t);
+                    selected = count;
+                    prompt = None;
+                    error = None;
+                }
+                Err(e) => error = Some(e),
+            },
+            KeyCode::Esc => {
+                prompt = None;
+                error = None;
+            }
+            KeyCode::Backspace => {
+                p.input.pop();
+            }
+            KeyCode::Char(c) => p.input.push(c),
+            _ => {}
+        }
+    } else {
+        match code {
+            KeyCode::Enter | KeyCode::Esc => {
+                state.modal = None;
+                return;
+            }
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Down => selected = (selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(list) = state.mapping_transforms.get_mut(&target_id) {
+                    if selected < list.len() {
+                        list.remove(selected);
+                    }
+                    if list.is_empty() {
+                        state.mapping_transforms.remove(&target_id);
+                    }
+                }
+                selected = selected.min(count.saturating_sub(2));
+            }
+            KeyCode::Char(c @ '1'..='5') => {
+                let kind = TransformKind::ALL[(c as u8 - b'1') as usize];
+                if kind.prompt().is_some() {
+                    prompt = Some(Prompt {
+                        kind,
+                        input: String::new(),
+                    });
+                } else if let Ok(t) = build(state, kind, "") {
+                    state
+                        .mapping_transforms
+                        .entry(target_id.clone())
+                        .or_default()
+                        .push(t);
+                    selected = count;
+                }
+                error = None;
+            }
+            _ => {}
+        }
+    }
+
+    state.modal = Some(Modal::Transforms {
+        target_id,
+        selected,
+        prompt,
+        error,
+    });
+}
+
+pub(super) fn draw(
+    f: &mut ratatui::Frame<'_>,
+    window_area: Rect,
+    state: &WizardState,
+    target_id: &str,
+    selected: usize,
+    prompt: Option<&Prompt>,
+    error: Option<&str>,
+) {
+    let list = state
+        .mapping_transforms
+        .get(target_id)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let modal_w = 76u16.min(window_area.width.saturating_sub(4)).max(44);
+    let modal_h = (list.len().max(1) as u16 + 10)
+        .min(window_area.height.saturating_sub(2))
+        .max(10);
+    let x = window_area.x + (window_area.width.saturating_sub(modal_w)) / 2;
+    let y = window_area.y + (window_area.height.saturating_sub(modal_h)) / 2;
+    let area = Rect {
+        x,
+        y,
+        width: modal_w,
+        height: modal_h,
+    };
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Value Transforms — {}",
+        mapping_target_name(state, target_id)
+    ));
+
+    let mut lines: Vec<Line> = Vec::new();
+    match state.target_to_source.get(target_id) {
+        Some(source) => lines.push(Line::from(format!(
+            "Source: {}",
+            mapping_source_display(state, source)
+        ))),
+        None => lines.push(Line::from("Source: (unmapped)")),
+    }
+    if list.is_empty() {
+        lines.push(Line::from("  (no transforms)"));
+    }
+    for (i, t) in list.iter().enumerate() {
+        let text = match t {
+            FieldTransform::Concat {
+                source_id,
+                separator,
+            } => format!(
+                "Concat '{}' + {}",
+                separator,
+                mapping_source_display(state, source_id)
+            ),
+            other => other.label(),
+        };
+        let style = theme::current().focus_if(i == selected && prompt.is_none());
+        lines.push(Line::from(ratatui::text::Span::styled(
+            format!("{}. {}", i + 1, text),
+            style,
+        )));
+    }
+    lines.push(Line::from(""));
+    match prompt {
+        Some(p) => {
+            lines.push(Line::from(p.kind.prompt().unwrap_or_default()));
+            lines.push(Line::from(format!("> {}_", p.input)));
+        }
+        None => lines.push(Line::from(
+            TransformKind::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, k)| format!("{} {}", i + 1, k.menu_label()))
+                .collect::<Vec<_>>()
+                .join("  ")
+                + "  D Remove",
+        )),
+    }
+    if let Some(e) = error {
+        lines.push(Line::from(ratatui::text::Span::styled(
+            e.to_string(),
+            theme::current().error(),
+        )));
+    }
+    let p = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(p, area);
+
+    let buttons_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - 2,
+        width: area.width - 2,
+        height: 1,
+    };
+    let button = ratatui::text::Span::styled(
+        if prompt.is_some() {
+            "[ Add ]"
+        } else {
+            "[ Close ]"
+        },
+        theme::current().focused(),
+    );
+    for rect in mouse::right_aligned_spans(buttons_area, &[&button]) {
+        mouse::record(state, rect, HitTarget::ModalButton(0));
+    }
+    let p = Paragraph::new(Text::from(Line::from(button))).alignment(Alignment::Right);
+    f.render_widget(p, buttons_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(state: &mut WizardState, input: &str) {
+        for c in input.chars() {
+            handle_key(state, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn keys_add_and_remove_transforms_for_the_selected_target() {
+        let mut state = WizardState::new();
+        state.source_fields = vec![SourceField {
+            id: "last_name".to_string(),
+            raw_name: "LastName".to_string(),
+            display_name: "LastName".to_string(),
+            data_type: "nvarchar".to_string(),
+            sample_values: Vec::new(),
+        }];
+        state.selected_target_id = Some("CallerName".to_string());
+        open(&mut state);
+
+        keys(&mut state, "1");
+        keys(&mut state, "4lastname|, ");
+        handle_key(&mut state, KeyCode::Enter);
+        keys(&mut state, "3miles hours");
+        handle_key(&mut state, KeyCode::Enter);
+        assert!(matches!(
+            state.modal,
+            Some(Modal::Transforms {
+                prompt: Some(_),
+                error: Some(_),
+                ..
+            })
+        ));
+        handle_key(&mut state, KeyCode::Esc);
+        assert_eq!(
+            state.mapping_transforms["CallerName"],
+            vec![
+                FieldTransform::Trim,
+                FieldTransform::Concat {
+                    source_id: "last_name".to_string(),
+                    separator: ", ".to_string(),
+                },
+            ]
+        );
+
+        handle_key(&mut state, KeyCode::Up);
+        keys(&mut state, "d");
+        assert_eq!(state.mapping_transforms["CallerName"].len(), 1);
+        keys(&mut state, "d");
+        assert!(!state.mapping_transforms.contains_key("CallerName"));
+        handle_key(&mut state, KeyCode::Esc);
+        assert!(state.modal.is_none());
+    }
+}
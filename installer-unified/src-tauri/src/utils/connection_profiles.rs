@@ -0,0 +1,265 @@
+//! Saved connection profiles for the Data Source and Database pages.
+//!
+//! A profile is a named set of field values of one page (`ProfileKind`), so a staging and a
+//! production connection can be picked from a list instead of typed again on every run. Values use
+//! the `--tui-script` answer keys of the page (`host`, `port`, `database`, `user`, `password`,
+//! `connection_string`, ...), which both the GUI and the TUI understand; only the keys in
+//! `ProfileKind::keys` are kept.
+//!
+//! Profiles live in `<log folder>/secrets/connection-profiles.json`, next to the proxy settings.
+//! Passwords and connection strings are encrypted with the installer's `SecretProtector`
+//! (`ENCv1:` values) and decrypted when the profiles are loaded.
+
+use crate::security::secret_protector::SecretProtector;
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const PROFILES_FILE_NAME: &str = "connection-profiles.json";
+const MAX_NAME_LEN: usize = 64;
+/// Keys whose values are stored encrypted.
+const SECRET_KEYS: &[&str] = &["password", "connection_string"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProfileKind {
+    /// Call data source (Data Source page).
+    DataSource,
+    /// Existing configuration database (Database page).
+    Database,
+}
+
+impl ProfileKind {
+    /// Keys a profile of this kind keeps.
+    pub fn keys(self) -> &'static [&'static str] {
+        match self {
+            ProfileKind::DataSource => &[
+                "kind",
+                "object",
+                "connection_string",
+                "driver",
+                "address",
+                "folder",
+                "delimiter",
+                "host",
+                "port",
+                "database",
+                "user",
+                "password",
+                "windows_auth",
+            ],
+            ProfileKind::Database => &[
+                "hosted",
+                "host",
+                "port",
+                "database",
+                "user",
+                "password",
+                "tls",
+                "windows_auth",
+                "connection_string",
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub kind: ProfileKind,
+    pub values: BTreeMap<String, String>,
+    /// RFC 3339; set when saved.
+    #[serde(default)]
+    pub saved_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    profiles: Vec<ConnectionProfile>,
+}
+
+fn profiles_path() -> Result<PathBuf> {
+    Ok(crate::utils::path_resolver::resolve_log_folder()?
+        .join("secrets")
+        .join(PROFILES_FILE_NAME))
+}
+
+fn read_file(path: &Path) -> Result<ProfilesFile> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("Connection profiles file is not valid JSON: {:?}", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProfilesFile::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_file(path: &Path, file: &ProfilesFile) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(file)?)?;
+    Ok(())
+}
+
+fn same(a: &ConnectionProfile, kind: ProfileKind, name: &str) -> bool {
+    a.kind == kind && a.name.eq_ignore_ascii_case(name)
+}
+
+/// Saved profiles of `kind`, by name, secrets decrypted.
+pub async fn list(secrets: &SecretProtector, kind: ProfileKind) -> Result<Vec<ConnectionProfile>> {
+    list_at(&profiles_path()?, secrets, kind).await
+}
+
+/// Save `profile`, replacing one of the same kind and name (case-insensitive).
+pub async fn save(secrets: &SecretProtector, profile: ConnectionProfile) -> Result<()> {
+    save_at(&profiles_path()?, secrets, profile).await
+}
+
+/// Remove a profile; `false` when there was none.
+pub fn delete(kind: ProfileKind, name: &str) -> Result<bool> {
+    delete_at(&profiles_path()?, kind, name)
+}
+
+async fn list_at(
+    path: &Path,
+    secrets: &SecretProtector,
+    kind: ProfileKind,
+) -> Result<Vec<ConnectionProfile>> {
+    let mut profiles = Vec::new();
+    for mut profile in read_file(path)?.profiles {
+        if profile.kind != kind {
+            continue;
+        }
+        for key in SECRET_KEYS {
+            let Some(value) = profile.values.get_mut(*key) else {
+                continue;
+            };
+            match secrets.decrypt(value).await {
+                Ok(plain) => *value = plain,
+                Err(e) => {
+                    // A key from another machine: keep the rest of the profile.
+                    warn!(
+                        "[PHASE: profiles] [STEP: load] Dropping undecryptable {} of profile {:?}: {:?}",
+                        key, profile.name, e
+                    );
+                    profile.values.remove(*key);
+                }
+            }
+        }
+        profiles.push(profile);
+    }
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    Ok(profiles)
+}
+
+async fn save_at(path: &Path, secrets: &SecretProtector, profile: ConnectionProfile) -> Result<()> {
+    let name = profile.name.trim().to_string();
+    if name.is_empty() {
+        anyhow::bail!("Profile name is required.");
+    }
+    if name.chars().count() > MAX_NAME_LEN || name.chars().any(char::is_control) {
+        anyhow::bail!(
+            "Profile name must be at most {} characters without control characters.",
+            MAX_NAME_LEN
+        );
+    }
+    let keys = profile.kind.keys();
+    let mut values = BTreeMap::new();
+    for (key, value) in profile.values {
+        if !keys.contains(&key.as_str()) {
+            continue;
+        }
+        let value = if SECRET_KEYS.contains(&key.as_str()) && !value.is_empty() {
+            secrets.encrypt(&value).await?
+        } else {
+            value
+        };
+        values.insert(key, value);
+    }
+
+    let mut file = read_file(path)?;
+    file.profiles.retain(|p| !same(p, profile.kind, &name));
+    file.profiles.push(ConnectionProfile {
+        name: name.clone(),
+        kind: profile.kind,
+        values,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    });
+    write_file(path, &file)?;
+    info!(
+        "[PHASE: profiles] [STEP: save] Connection profile saved (kind={:?}, name={:?})",
+        profile.kind, name
+    );
+    Ok(())
+}
+
+fn delete_at(path: &Path, kind: ProfileKind, name: &str) -> Result<bool> {
+    let mut file = read_file(path)?;
+    let before = file.profiles.len();
+    file.profiles.retain(|p| !same(p, kind, name.trim()));
+    if file.profiles.len() == before {
+        return Ok(false);
+    }
+    write_file(path, &file)?;
+    info!(
+        "[PHASE: profiles] [STEP: delete] Connection profile removed (kind={:?}, name={:?})",
+        kind, name
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn profiles_round_trip_with_secrets_encrypted() {
+        let dir = std::env::temp_dir().join(format!("profiles-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(PROFILES_FILE_NAME);
+        let secrets = SecretProtector::new(dir.join("key"));
+        let profile = |name: &str, host: &str| ConnectionProfile {
+            name: name.to_string(),
+            kind: ProfileKind::Database,
+            values: BTreeMap::from([
+                ("host".to_string(), host.to_string()),
+                ("password".to_string(), "s3cret".to_string()),
+                ("max_size_gb".to_string(), "50".to_string()),
+            ]),
+            saved_at: String::new(),
+        };
+
+        save_at(&path, &secrets, profile(" Staging ", "stage-db"))
+            .await
+            .unwrap();
+        save_at(&path, &secrets, profile("Prod", "prod-db"))
+            .await
+            .unwrap();
+        save_at(&path, &secrets, profile("staging", "stage-db-2"))
+            .await
+            .unwrap();
+        assert!(save_at(&path, &secrets, profile("  ", "x")).await.is_err());
+
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("s3cret"));
+        assert!(!on_disk.contains("max_size_gb"));
+
+        let listed = list_at(&path, &secrets, ProfileKind::Database)
+            .await
+            .unwrap();
+        let names: Vec<&str> = listed.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Prod", "staging"]);
+        assert_eq!(listed[1].values["host"], "stage-db-2");
+        assert_eq!(listed[1].values["password"], "s3cret");
+        assert!(list_at(&path, &secrets, ProfileKind::DataSource)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(delete_at(&path, ProfileKind::Database, "PROD").unwrap());
+        assert!(!delete_at(&path, ProfileKind::Database, "prod").unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
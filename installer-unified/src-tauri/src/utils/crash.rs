@@ -0,0 +1,214 @@
+//! Panic hook: crash report artifact and terminal restore.
+//!
+//! On a panic the hook writes `crash-<timestamp>.json` to the log folder (`Prod_Wizard_Log/`):
+//! panic message and location, thread, a backtrace, the last phase/step seen in the log, the log
+//! context of the run in progress (correlation id, install mode) and OS details.
+//!
+//! While the TUI (or a hidden prompt) has the terminal in raw mode, the hook also leaves raw mode,
+//! the alternate screen and mouse capture and shows the cursor, then exits with code 101: a panic
+//! on any thread leaves the UI unable to continue, and a corrupted shell is worse than an exit.
+//! Outside raw mode the panic unwinds as usual after the report is written.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+/// Exit code of a panicking Rust program; used when the hook exits from raw mode.
+const PANIC_EXIT_CODE: i32 = 101;
+
+static RAW_TERMINAL: AtomicBool = AtomicBool::new(false);
+static INSTALL: Once = Once::new();
+
+/// Record whether the terminal is in raw mode (set around `enable_raw_mode`/`disable_raw_mode`).
+pub fn set_raw_terminal(raw: bool) {
+    RAW_TERMINAL.store(raw, Ordering::SeqCst);
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CrashReport {
+    installer_version: String,
+    created_utc: String,
+    message: String,
+    location: Option<String>,
+    thread: String,
+    phase: Option<String>,
+    step: Option<String>,
+    correlation_id: Option<String>,
+    context: Option<std::collections::HashMap<String, serde_json::Value>>,
+    os: OsInfo,
+    backtrace: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OsInfo {
+    name: String,
+    arch: String,
+    /// `PRETTY_NAME` from /etc/os-release on Linux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+}
+
+fn os_info() -> OsInfo {
+    #[cfg(target_os = "linux")]
+    let version = std::fs::read_to_string("/etc/os-release")
+        .ok()
+        .map(|s| crate::installation::linux_parsers::parse_os_release(&s).pretty_name)
+        .filter(|v| !v.is_empty());
+    #[cfg(not(target_os = "linux"))]
+    let version = None;
+    OsInfo {
+        name: super::os_detection::get_os_name(),
+        arch: std::env::consts::ARCH.to_string(),
+        version,
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+fn report(
+    message: String,
+    location: Option<String>,
+    backtrace: &Backtrace,
+    os: OsInfo,
+) -> CrashReport {
+    let (phase, step) = super::logging::last_position();
+    let context = super::logging::log_context();
+    let correlation_id = context
+        .as_ref()
+        .and_then(|c| c.get("correlationId"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    CrashReport {
+        installer_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_utc: chrono::Utc::now().to_rfc3339(),
+        message,
+        location,
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string(),
+        phase,
+        step,
+        correlation_id,
+        context,
+        os,
+        backtrace: backtrace.to_string().lines().map(str::to_string).collect(),
+    }
+}
+
+fn write_report(log_dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(log_dir)?;
+    let name = format!(
+        "crash-{}-{}.json",
+        chrono::Utc::now().format("%Y-%m-%d-%H%M%S"),
+        std::process::id()
+    );
+    let path = log_dir.join(name);
+    std::fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+    Ok(path)
+}
+
+/// Leave raw mode and the alternate screen, if the terminal is in them. Safe to call from any
+/// thread; only the first call after raw mode was entered does anything.
+pub fn restore_terminal() -> bool {
+    if !RAW_TERMINAL.swap(false, Ordering::SeqCst) {
+        return false;
+    }
+    use crossterm::ExecutableCommand;
+    let _ = crossterm::terminal::disable_raw_mode();
+    let mut stdout = std::io::stdout();
+    let _ = stdout.execute(crossterm::event::DisableMouseCapture);
+    let _ = stdout.execute(crossterm::terminal::LeaveAlternateScreen);
+    let _ = stdout.execute(crossterm::cursor::Show);
+    let _ = stdout.flush();
+    true
+}
+
+/// Install the panic hook (once; later calls are no-ops). Crash reports go to `log_dir`.
+pub fn install_hook(log_dir: PathBuf) {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let exit = restore_terminal();
+            let backtrace = Backtrace::force_capture();
+            let report = report(
+                panic_message(info.payload()),
+                info.location().map(|l| l.to_string()),
+                &backtrace,
+                os_info(),
+            );
+            match write_report(&log_dir, &report) {
+                Ok(path) => {
+                    eprintln!("The installer crashed. Crash report: {}", path.display());
+                    log::error!(
+                        "[PHASE: crash] [STEP: panic] Panic at {}: {} (report: {:?})",
+                        report.location.as_deref().unwrap_or("<unknown>"),
+                        report.message,
+                        path
+                    );
+                }
+                Err(e) => eprintln!(
+                    "The installer crashed; writing the crash report failed: {}",
+                    e
+                ),
+            }
+            previous(info);
+            if exit {
+                log::logger().flush();
+                std::process::exit(PANIC_EXIT_CODE);
+            }
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crash_report_carries_position_context_and_backtrace() {
+        crate::utils::logging::note_position(Some("install"), Some("migrations"));
+        crate::utils::logging::set_log_context("correlationId", "corr-crash");
+        let backtrace = Backtrace::force_capture();
+        let report = report(
+            "boom".to_string(),
+            Some("src/x.rs:1:1".to_string()),
+            &backtrace,
+            os_info(),
+        );
+        crate::utils::logging::clear_log_context();
+
+        let dir = std::env::temp_dir().join(format!("crash-{}", uuid::Uuid::new_v4()));
+        let path = write_report(&dir, &report).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("crash-"));
+        assert_eq!(
+            (json["phase"].as_str(), json["step"].as_str()),
+            (Some("install"), Some("migrations"))
+        );
+        assert_eq!(json["message"], "boom");
+        assert_eq!(json["location"], "src/x.rs:1:1");
+        assert!(!json["os"]["arch"].as_str().unwrap().is_empty());
+        assert!(!json["backtrace"].as_array().unwrap().is_empty());
+        assert!(json["installerVersion"].is_string());
+        assert!(!restore_terminal());
+    }
+}
@@ -5,7 +5,7 @@
 
 use anyhow::Result;
 use log::{debug, info};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Best-effort free-space check for a given filesystem path (returns bytes).
@@ -38,6 +38,19 @@ pub async fn get_free_space_bytes_for_path(path: &str) -> Result<u64> {
     Ok(bytes)
 }
 
+/// `path` itself or its closest parent that exists (free space is measured there before the
+/// folder is created).
+pub async fn nearest_existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let mut cur = Some(path);
+    while let Some(p) = cur {
+        if !p.as_os_str().is_empty() && tokio::fs::try_exists(p).await.unwrap_or(false) {
+            return Some(p.to_path_buf());
+        }
+        cur = p.parent();
+    }
+    None
+}
+
 #[cfg(windows)]
 async fn get_free_space_bytes_windows(path: &str) -> Result<u64> {
     let drive = extract_windows_drive_letter(path)
@@ -0,0 +1,133 @@
+//! Wizard defaults pre-seeded from the environment, for golden images and scripted rollouts.
+//!
+//! Each variable replaces the built-in default of one wizard field; the wizard still shows every
+//! page, so the values can be reviewed and changed before Install. Unset or blank variables keep
+//! the built-in default, and an invalid value is logged and ignored.
+//!
+//! | Variable                                    | Field                                  |
+//! |---------------------------------------------|----------------------------------------|
+//! | `CADALYTIX_INSTALLER_DESTINATION`           | Destination folder                     |
+//! | `CADALYTIX_INSTALLER_DB_HOST`               | Config DB host                         |
+//! | `CADALYTIX_INSTALLER_DB_PORT`               | Config DB port (1-65535)               |
+//! | `CADALYTIX_INSTALLER_DB_NAME`               | Config DB name                         |
+//! | `CADALYTIX_INSTALLER_DB_USER`               | Config DB user                         |
+//! | `CADALYTIX_INSTALLER_SOURCE_HOST`           | Call data source host                  |
+//! | `CADALYTIX_INSTALLER_SOURCE_PORT`           | Call data source port (1-65535)        |
+//! | `CADALYTIX_INSTALLER_ARCHIVE_DESTINATION`   | Archive destination folder             |
+//! | `CADALYTIX_INSTALLER_ARCHIVE_MAX_USAGE_GB`  | Archive space limit in GB              |
+//! | `CADALYTIX_INSTALLER_RETENTION_MONTHS`      | Hot retention in months (1-240)        |
+//!
+//! Passwords and connection strings are deliberately not covered; they do not belong in an
+//! image's environment. The TUI applies these at start, the GUI through `get_wizard_defaults`.
+//!
+//! With `--instance=<NAME>` the instance name is passed along too: the config DB name defaults to
+//! `cadalytix_<name>` and the wizards suffix their default destination folder with `-<NAME>`,
+//! unless a variable above sets them.
+
+use log::warn;
+use serde::Serialize;
+
+use crate::utils::instance;
+
+const PREFIX: &str = "CADALYTIX_INSTALLER_";
+
+/// Overridden wizard defaults; `None` keeps the built-in one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WizardDefaults {
+    pub destination_path: Option<String>,
+    pub db_host: Option<String>,
+    pub db_port: Option<u16>,
+    pub db_name: Option<String>,
+    pub db_user: Option<String>,
+    pub source_host: Option<String>,
+    pub source_port: Option<u16>,
+    pub archive_destination: Option<String>,
+    pub archive_max_usage_gb: Option<u32>,
+    pub retention_months: Option<u32>,
+    /// The `--instance` this installer runs for, if any.
+    pub instance_name: Option<String>,
+}
+
+impl WizardDefaults {
+    /// Defaults from the process environment.
+    pub fn from_env() -> Self {
+        let mut defaults = Self::resolve(|name| std::env::var(name).ok());
+        if let Some(instance) = instance::name() {
+            defaults.instance_name = Some(instance.to_string());
+            defaults
+                .db_name
+                .get_or_insert_with(|| instance::qualify("cadalytix", "_"));
+        }
+        defaults
+    }
+
+    fn resolve(var: impl Fn(&str) -> Option<String>) -> Self {
+        let text = |key: &str| {
+            var(&format!("{}{}", PREFIX, key))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let number = |key: &str, valid: &dyn Fn(u32) -> bool| {
+            let raw = text(key)?;
+            match raw.parse::<u32>() {
+                Ok(n) if valid(n) => Some(n),
+                _ => {
+                    warn!(
+                        "[PHASE: initialization] [STEP: env_defaults] Ignoring {}{}={:?} (not a valid value)",
+                        PREFIX, key, raw
+                    );
+                    None
+                }
+            }
+        };
+        let port =
+            |key: &str| number(key, &|n| (1..=u32::from(u16::MAX)).contains(&n)).map(|n| n as u16);
+
+        Self {
+            destination_path: text("DESTINATION"),
+            db_host: text("DB_HOST"),
+            db_port: port("DB_PORT"),
+            db_name: text("DB_NAME"),
+            db_user: text("DB_USER"),
+            source_host: text("SOURCE_HOST"),
+            source_port: port("SOURCE_PORT"),
+            archive_destination: text("ARCHIVE_DESTINATION"),
+            archive_max_usage_gb: number("ARCHIVE_MAX_USAGE_GB", &|n| n > 0),
+            retention_months: number("RETENTION_MONTHS", &|n| (1..=240).contains(&n)),
+            instance_name: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_are_trimmed_and_invalid_numbers_ignored() {
+        let env = |n: &str| {
+            [
+                ("CADALYTIX_INSTALLER_DESTINATION", " /srv/cadalytix "),
+                ("CADALYTIX_INSTALLER_DB_HOST", ""),
+                ("CADALYTIX_INSTALLER_DB_PORT", "5433"),
+                ("CADALYTIX_INSTALLER_SOURCE_PORT", "70000"),
+                ("CADALYTIX_INSTALLER_ARCHIVE_MAX_USAGE_GB", "0"),
+                ("CADALYTIX_INSTALLER_RETENTION_MONTHS", "36"),
+            ]
+            .iter()
+            .find(|(k, _)| *k == n)
+            .map(|(_, v)| v.to_string())
+        };
+        assert_eq!(
+            WizardDefaults::resolve(env),
+            WizardDefaults {
+                destination_path: Some("/srv/cadalytix".to_string()),
+                db_port: Some(5433),
+                retention_months: Some(36),
+                ..Default::default()
+            }
+        );
+        assert_eq!(WizardDefaults::resolve(|_| None), WizardDefaults::default());
+    }
+}
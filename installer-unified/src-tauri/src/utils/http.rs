@@ -0,0 +1,341 @@
+//! Shared HTTPS client for outbound calls (license activation/verification, telemetry,
+//! support-bundle upload).
+//!
+//! Proxy resolution, highest precedence first:
+//! - an explicit proxy passed by the caller (request fields)
+//! - the proxy saved on the wizard's Network page (`secrets/proxy_settings.json` under the log
+//!   folder; the password is encrypted with the `SecretProtector`)
+//! - `HTTPS_PROXY` / `HTTP_PROXY` / `NO_PROXY` (and the OS proxy settings on Windows/macOS),
+//!   which reqwest applies when no explicit proxy is set
+//!
+//! `bypass_system` turns the last one off (direct connection).
+
+use anyhow::{Context, Result};
+use log::info;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::security::secret_protector::SecretProtector;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+const SETTINGS_FILE_NAME: &str = "proxy_settings.json";
+const ENV_PROXY_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"];
+
+static SAVED: RwLock<Option<ProxyConfig>> = RwLock::new(None);
+
+/// Explicit proxy settings. Without a URL, the saved proxy and then the system proxy apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Ignore system proxy settings entirely (direct connection) when no explicit URL is set.
+    pub bypass_system: bool,
+}
+
+impl ProxyConfig {
+    fn explicit_url(&self) -> Option<&str> {
+        self.url.as_deref().map(str::trim).filter(|u| !u.is_empty())
+    }
+
+    /// True when this config decides the proxy on its own (URL set, or direct connection).
+    fn is_explicit(&self) -> bool {
+        self.explicit_url().is_some() || self.bypass_system
+    }
+}
+
+/// Proxy settings as entered on the Network page.
+#[derive(Debug, Clone, Default)]
+pub struct ProxySettings {
+    /// Host name or IP (a leading `http://` / `https://` is accepted). Empty = no explicit proxy.
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    /// `None` keeps the previously saved password for the same username.
+    pub password: Option<String>,
+    pub bypass_system: bool,
+}
+
+/// On-disk form of `ProxySettings` (password encrypted).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxySettingsFile {
+    host: String,
+    port: u16,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password_encrypted: Option<String>,
+    #[serde(default)]
+    bypass_system: bool,
+}
+
+/// `http://host:port` from the Network page fields.
+pub fn proxy_url(host: &str, port: u16) -> Result<String> {
+    let host = host.trim();
+    let (scheme, rest) = match host.split_once("://") {
+        Some((s, r)) if s.eq_ignore_ascii_case("http") || s.eq_ignore_ascii_case("https") => {
+            (s.to_ascii_lowercase(), r)
+        }
+        Some((s, _)) => anyhow::bail!("Unsupported proxy scheme '{}' (use http or https)", s),
+        None => ("http".to_string(), host),
+    };
+    let rest = rest.trim_end_matches('/');
+    if rest.is_empty()
+        || rest.contains(['/', '@', ' '])
+        || (rest.contains(':') && !rest.starts_with('['))
+    {
+        anyhow::bail!("Proxy host must be a host name or IP address without a port or path");
+    }
+    if port == 0 {
+        anyhow::bail!("Proxy port must be between 1 and 65535");
+    }
+    Ok(format!("{}://{}:{}", scheme, rest, port))
+}
+
+/// The first `HTTPS_PROXY`/`HTTP_PROXY` value from the environment, credentials masked.
+pub fn env_proxy() -> Option<String> {
+    ENV_PROXY_VARS
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+        .map(|v| match v.rsplit_once('@') {
+            Some((prefix, host)) => {
+                let scheme = prefix.split_once("://").map(|(s, _)| s).unwrap_or("http");
+                format!("{}://***@{}", scheme, host)
+            }
+            None => v,
+        })
+}
+
+/// The proxy saved on the Network page (default when none).
+pub fn saved_proxy() -> ProxyConfig {
+    SAVED
+        .read()
+        .map(|g| g.clone().unwrap_or_default())
+        .unwrap_or_default()
+}
+
+fn set_saved(proxy: Option<ProxyConfig>) {
+    if let Ok(mut g) = SAVED.write() {
+        *g = proxy;
+    }
+}
+
+/// `explicit` when it decides the proxy itself, otherwise the saved proxy.
+pub fn effective(explicit: &ProxyConfig) -> ProxyConfig {
+    if explicit.is_explicit() {
+        explicit.clone()
+    } else {
+        saved_proxy()
+    }
+}
+
+/// HTTPS client with the effective proxy and the default 15s request timeout.
+pub fn build_client(proxy: &ProxyConfig) -> Result<reqwest::Client> {
+    client_with_timeout(proxy, DEFAULT_TIMEOUT)
+}
+
+/// HTTPS client with the effective proxy and `timeout` per request.
+pub fn client_with_timeout(proxy: &ProxyConfig, timeout: Duration) -> Result<reqwest::Client> {
+    let proxy = effective(proxy);
+    let mut builder = reqwest::Client::builder().timeout(timeout).https_only(true);
+    match proxy.explicit_url() {
+        Some(url) => {
+            let mut p = reqwest::Proxy::all(url)
+                .with_context(|| format!("Invalid proxy URL: {}", url))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            if let Some(user) = proxy.username.as_deref().filter(|u| !u.trim().is_empty()) {
+                p = p.basic_auth(user, proxy.password.as_deref().unwrap_or(""));
+            }
+            builder = builder.proxy(p);
+        }
+        None if proxy.bypass_system => builder = builder.no_proxy(),
+        None => {}
+    }
+    Ok(builder.build()?)
+}
+
+/// True when the server could not be reached (DNS, connect, TLS, proxy or timeout).
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
+
+fn settings_path() -> Result<PathBuf> {
+    Ok(crate::utils::path_resolver::resolve_log_folder()?
+        .join("secrets")
+        .join(SETTINGS_FILE_NAME))
+}
+
+async fn read_settings_file() -> Result<Option<ProxySettingsFile>> {
+    let path = settings_path()?;
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes).with_context(|| {
+            format!("Proxy settings file is not valid JSON: {:?}", path)
+        })?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Saved Network page settings, password omitted (`has_password` says whether one is stored).
+pub async fn load_settings() -> Result<Option<(ProxySettings, bool)>> {
+    Ok(read_settings_file().await?.map(|f| {
+        let has_password = f.password_encrypted.is_some();
+        (
+            ProxySettings {
+                host: f.host,
+                port: f.port,
+                username: f.username,
+                password: None,
+                bypass_system: f.bypass_system,
+            },
+            has_password,
+        )
+    }))
+}
+
+/// Load the saved proxy into the process (called at startup). Missing file = system proxy.
+pub async fn load_saved(secrets: &SecretProtector) -> Result<()> {
+    let Some(file) = read_settings_file().await? else {
+        set_saved(None);
+        return Ok(());
+    };
+    let password = match file.password_encrypted.as_deref() {
+        Some(enc) => Some(
+            secrets
+                .decrypt(enc)
+                .await
+                .context("Failed to decrypt the saved proxy password")?,
+        ),
+        None => None,
+    };
+    let url = if file.host.trim().is_empty() {
+        None
+    } else {
+        Some(proxy_url(&file.host, file.port)?)
+    };
+    set_saved(Some(ProxyConfig {
+        url,
+        username: file.username,
+        password,
+        bypass_system: file.bypass_system,
+    }));
+    Ok(())
+}
+
+/// Validate, persist (password encrypted) and activate `settings` for this process.
+pub async fn save_settings(secrets: &SecretProtector, settings: ProxySettings) -> Result<()> {
+    let host = settings.host.trim().to_string();
+    let url = if host.is_empty() {
+        None
+    } else {
+        Some(proxy_url(&host, settings.port)?)
+    };
+    let username = settings
+        .username
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty() && url.is_some());
+
+    let previous = read_settings_file().await.ok().flatten();
+    let password_encrypted = match (&username, settings.password) {
+        (None, _) => None,
+        (Some(_), Some(p)) if p.is_empty() => None,
+        (Some(_), Some(p)) => Some(secrets.encrypt(&p).await?),
+        (Some(user), None) => previous
+            .filter(|f| f.username.as_deref() == Some(user.as_str()))
+            .and_then(|f| f.password_encrypted),
+    };
+
+    let file = ProxySettingsFile {
+        host,
+        port: settings.port,
+        username,
+        password_encrypted,
+        bypass_system: settings.bypass_system,
+    };
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&file)?).await?;
+    load_saved(secrets).await?;
+    info!(
+        "[PHASE: network] [STEP: save_proxy] Proxy settings saved (explicit={}, auth={}, bypass_system={})",
+        url.is_some(),
+        file.username.is_some(),
+        file.bypass_system
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_urls_validate_and_explicit_config_wins() {
+        assert_eq!(
+            proxy_url(" proxy.corp ", 3128).unwrap(),
+            "http://proxy.corp:3128"
+        );
+        assert_eq!(
+            proxy_url("HTTPS://10.0.0.5/", 8443).unwrap(),
+            "https://10.0.0.5:8443"
+        );
+        assert_eq!(proxy_url("[::1]", 8080).unwrap(), "http://[::1]:8080");
+        assert!(proxy_url("socks5://proxy", 1080).is_err());
+        assert!(proxy_url("proxy.corp:3128", 3128).is_err());
+        assert!(proxy_url("user@proxy.corp", 3128).is_err());
+        assert!(proxy_url("proxy.corp", 0).is_err());
+        assert!(proxy_url("", 3128).is_err());
+
+        let explicit = ProxyConfig {
+            url: Some("http://proxy.corp:3128".to_string()),
+            ..ProxyConfig::default()
+        };
+        assert_eq!(effective(&explicit), explicit);
+        let direct = ProxyConfig {
+            bypass_system: true,
+            ..ProxyConfig::default()
+        };
+        assert_eq!(effective(&direct), direct);
+        assert!(!ProxyConfig {
+            url: Some("  ".to_string()),
+            ..ProxyConfig::default()
+        }
+        .is_explicit());
+
+        assert!(client_with_timeout(&explicit, Duration::from_secs(1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn unreachable_server_is_reported_as_unreachable() {
+        // Port 9 (discard) on loopback is closed on CI hosts; the connect fails fast.
+        let proxy = ProxyConfig {
+            bypass_system: true,
+            ..Default::default()
+        };
+        let client = build_client(&proxy).unwrap();
+        let err: anyhow::Error = client
+            .get("https://127.0.0.1:9/licensing/activate")
+            .send()
+            .await
+            .unwrap_err()
+            .into();
+        assert!(is_unreachable(&err));
+
+        assert!(build_client(&ProxyConfig {
+            url: Some("not a url".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(!is_unreachable(&anyhow::anyhow!("License key revoked")));
+    }
+}
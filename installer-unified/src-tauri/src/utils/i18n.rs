@@ -0,0 +1,219 @@
+//! Localized UI strings for the GUI and TUI (page titles, validation errors, modal text).
+//!
+//! Bundles are flat JSON maps of message key -> text with `{name}` placeholders
+//! (`locales/<code>.json`). English and Spanish are compiled in; agencies can add or override a
+//! language by placing `locales/<code>.json` next to the installer executable.
+//!
+//! The locale is chosen once per process, first match wins:
+//! - `CADALYTIX_LOCALE` (e.g. `es`, `es-MX`)
+//! - `locale.json` next to the installer executable, e.g. `{ "locale": "es" }`
+//! - `LC_ALL` / `LANG` (e.g. `es_MX.UTF-8`)
+//! - `en`
+//!
+//! Missing keys fall back to English, then to the key itself.
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const LOCALE_ENV: &str = "CADALYTIX_LOCALE";
+const CONFIG_FILE_NAME: &str = "locale.json";
+const LOCALES_DIR_NAME: &str = "locales";
+const DEFAULT_LOCALE: &str = "en";
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.json")),
+    ("es", include_str!("../../locales/es.json")),
+];
+
+static BUNDLE: OnceLock<LocaleBundle> = OnceLock::new();
+
+/// Messages for the active locale, already merged over English.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleBundle {
+    pub locale: String,
+    pub messages: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct LocaleConfig {
+    locale: Option<String>,
+}
+
+/// `es_MX.UTF-8` / `es-mx` -> `es-MX`; `C` / `POSIX` / empty -> None.
+fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.trim().split(['.', '@']).next().unwrap_or_default();
+    if tag.is_empty() || tag.eq_ignore_ascii_case("c") || tag.eq_ignore_ascii_case("posix") {
+        return None;
+    }
+    let mut parts = tag.split(['_', '-']);
+    let lang = parts.next()?.to_ascii_lowercase();
+    if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(match parts.next().filter(|r| !r.is_empty()) {
+        Some(region) => format!("{}-{}", lang, region.to_ascii_uppercase()),
+        None => lang,
+    })
+}
+
+fn resolve_locale(var: impl Fn(&str) -> Option<String>, config: Option<String>) -> String {
+    var(LOCALE_ENV)
+        .and_then(|v| normalize(&v))
+        .or_else(|| config.and_then(|v| normalize(&v)))
+        .or_else(|| var("LC_ALL").and_then(|v| normalize(&v)))
+        .or_else(|| var("LANG").and_then(|v| normalize(&v)))
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+fn parse_messages(source: &str, origin: &str) -> HashMap<String, String> {
+    match serde_json::from_str(source) {
+        Ok(m) => m,
+        Err(e) => {
+            warn!(
+                "[PHASE: initialization] [STEP: i18n] Ignoring unreadable locale bundle {}: {}",
+                origin, e
+            );
+            HashMap::new()
+        }
+    }
+}
+
+/// English, overlaid with the bundled and on-disk messages for `locale` (language first, then
+/// the regional variant, so `es-MX` only needs the keys that differ from `es`).
+fn build_bundle(locale: &str, locales_dir: Option<&Path>) -> LocaleBundle {
+    let bundled = |code: &str| {
+        BUNDLED
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(c, src)| parse_messages(src, c))
+    };
+    let mut messages = bundled(DEFAULT_LOCALE).unwrap_or_default();
+
+    let mut layers = Vec::new();
+    if let Some((lang, _)) = locale.split_once('-') {
+        layers.push(lang.to_string());
+    }
+    layers.push(locale.to_string());
+
+    let mut found = locale == DEFAULT_LOCALE;
+    for code in layers {
+        if let Some(m) = bundled(&code).filter(|_| code != DEFAULT_LOCALE) {
+            messages.extend(m);
+            found = true;
+        }
+        if let Some(path) = locales_dir
+            .map(|d| d.join(format!("{}.json", code)))
+            .filter(|p| p.is_file())
+        {
+            if let Ok(src) = std::fs::read_to_string(&path) {
+                messages.extend(parse_messages(&src, &path.to_string_lossy()));
+                found = true;
+            }
+        }
+    }
+
+    LocaleBundle {
+        locale: if found {
+            locale.to_string()
+        } else {
+            DEFAULT_LOCALE.to_string()
+        },
+        messages,
+    }
+}
+
+/// The process-wide bundle (resolved on first use).
+pub fn bundle() -> &'static LocaleBundle {
+    BUNDLE.get_or_init(|| {
+        let deployment = super::path_resolver::resolve_deployment_folder().ok();
+        let config = deployment
+            .as_ref()
+            .map(|d| d.join(CONFIG_FILE_NAME))
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str::<LocaleConfig>(&s).ok())
+            .and_then(|c| c.locale);
+        let requested = resolve_locale(|name| std::env::var(name).ok(), config);
+        let locales_dir = deployment.map(|d| d.join(LOCALES_DIR_NAME));
+        let bundle = build_bundle(&requested, locales_dir.as_deref());
+        info!(
+            "[PHASE: initialization] [STEP: i18n] Locale resolved (requested={}, active={}, messages={})",
+            requested,
+            bundle.locale,
+            bundle.messages.len()
+        );
+        bundle
+    })
+}
+
+fn lookup(messages: &HashMap<String, String>, key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = messages
+        .get(key)
+        .cloned()
+        .unwrap_or_else(|| key.to_string());
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Localized text for `key`.
+pub fn t(key: &str) -> String {
+    lookup(&bundle().messages, key, &[])
+}
+
+/// Localized text for `key` with `{name}` placeholders filled from `args`.
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    lookup(&bundle().messages, key, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_resolves_and_falls_back_to_english() {
+        assert_eq!(normalize("es_MX.UTF-8").as_deref(), Some("es-MX"));
+        assert_eq!(normalize(" FR ").as_deref(), Some("fr"));
+        assert_eq!(normalize("C.UTF-8"), None);
+        assert_eq!(
+            resolve_locale(
+                |n| (n == "LANG").then(|| "de_DE.UTF-8".to_string()),
+                Some("es".to_string())
+            ),
+            "es"
+        );
+        assert_eq!(
+            resolve_locale(|n| (n == "LANG").then(|| "de_DE".to_string()), None),
+            "de-DE"
+        );
+        assert_eq!(resolve_locale(|_| None, None), "en");
+
+        // Every bundled language covers every English key.
+        let en = build_bundle("en", None);
+        for (code, src) in BUNDLED {
+            let m = parse_messages(src, code);
+            assert_eq!(m.len(), en.messages.len(), "{} is missing keys", code);
+        }
+
+        let dir = std::env::temp_dir().join(format!("i18n-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("es-MX.json"), r#"{ "button.next": "Continuar" }"#).unwrap();
+        let mx = build_bundle("es-MX", Some(&dir));
+        assert_eq!(mx.locale, "es-MX");
+        assert_eq!(lookup(&mx.messages, "button.next", &[]), "Continuar");
+        assert_eq!(lookup(&mx.messages, "button.finish", &[]), "Finalizar");
+        assert_eq!(
+            lookup(&mx.messages, "validation.errorPrefix", &[("message", "x")]),
+            "Error: x"
+        );
+
+        let unknown = build_bundle("de-DE", Some(&dir));
+        assert_eq!(unknown.locale, "en");
+        assert_eq!(lookup(&unknown.messages, "button.next", &[]), "Next");
+        assert_eq!(lookup(&unknown.messages, "no.such.key", &[]), "no.such.key");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
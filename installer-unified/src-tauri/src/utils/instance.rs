@@ -0,0 +1,117 @@
+//! Named instances (`--instance=<NAME>`), so several CADalytix installs (e.g. TEST and PROD) can
+//! live on one host.
+//!
+//! Without a name the installer behaves as before. With one, everything host-wide is qualified
+//! with it: the log folder (`Prod_Wizard_Log/instances/<NAME>`, which also holds the secrets key,
+//! checkpoints and the last install manifest), the systemd unit and Windows service names, the
+//! Docker container names, the archive schedule artifacts, and the wizard's default destination
+//! folder and config DB name. The name is recorded in the install manifest and the instance
+//! settings (`Setup:InstanceName`).
+
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use log::info;
+
+const MAX_NAME_LEN: usize = 32;
+
+/// Instance of this process; set once by [`select`].
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+/// `--instance=<NAME>` from the command line, if given.
+pub fn instance_arg(args: &[String]) -> Option<&str> {
+    args.iter()
+        .find_map(|a| a.strip_prefix("--instance="))
+        .map(str::trim)
+}
+
+/// Letters, digits, `-` and `_`, starting with a letter or digit, at most 32 characters.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Instance name '{}' is invalid: use letters, digits, '-' and '_' (starting with a letter or digit), at most {} characters.",
+            name, MAX_NAME_LEN
+        ))
+    }
+}
+
+/// Make `name` the instance of this process.
+pub fn select(name: &str) -> Result<()> {
+    validate_name(name).map_err(anyhow::Error::msg)?;
+    if ACTIVE.set(name.to_string()).is_err() && self::name() != Some(name) {
+        anyhow::bail!("A different instance is already selected");
+    }
+    info!(
+        "[PHASE: initialization] [STEP: instance] Instance selected (name={})",
+        name
+    );
+    Ok(())
+}
+
+/// The selected instance; `None` for the default (unnamed) one.
+pub fn name() -> Option<&'static str> {
+    ACTIVE.get().map(String::as_str)
+}
+
+/// `base` for the default instance, else `base<sep><name>`. The name is lowercased when `base`
+/// has no uppercase letters, so unit, container and Linux path names stay lowercase.
+pub fn qualify(base: &str, sep: &str) -> String {
+    qualify_with(name(), base, sep)
+}
+
+fn qualify_with(instance: Option<&str>, base: &str, sep: &str) -> String {
+    match instance {
+        None => base.to_string(),
+        Some(name) if base.chars().any(|c| c.is_ascii_uppercase()) => {
+            format!("{}{}{}", base, sep, name)
+        }
+        Some(name) => format!("{}{}{}", base, sep, name.to_ascii_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_validated_and_qualified_per_target() {
+        assert!(validate_name("TEST").is_ok());
+        assert!(validate_name("prod_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("-test").is_err());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name(&"x".repeat(33)).is_err());
+
+        assert_eq!(qualify_with(None, "cadalytix", "-"), "cadalytix");
+        assert_eq!(
+            qualify_with(Some("TEST"), "cadalytix", "-"),
+            "cadalytix-test"
+        );
+        assert_eq!(
+            qualify_with(Some("TEST"), "CADalytix", "-"),
+            "CADalytix-TEST"
+        );
+        assert_eq!(
+            qualify_with(Some("Prod"), "C:\\Program Files\\CADalytix", "-"),
+            "C:\\Program Files\\CADalytix-Prod"
+        );
+        assert_eq!(
+            qualify_with(Some("TEST"), "cadalytix", "_"),
+            "cadalytix_test"
+        );
+        assert_eq!(
+            instance_arg(&["x".to_string(), "--instance= TEST ".to_string()]),
+            Some("TEST")
+        );
+    }
+}
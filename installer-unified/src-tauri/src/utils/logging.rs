@@ -3,7 +3,77 @@
 
 use log::Level;
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+static CURRENT_TEXT_LOG: OnceLock<PathBuf> = OnceLock::new();
+static LOG_CONTEXT: Mutex<BTreeMap<String, serde_json::Value>> = Mutex::new(BTreeMap::new());
+static LAST_POSITION: Mutex<(Option<String>, Option<String>)> = Mutex::new((None, None));
+
+/// Remember this process's human-readable log file (set once, by `init_logging`).
+pub fn set_current_text_log_path(path: PathBuf) {
+    let _ = CURRENT_TEXT_LOG.set(path);
+}
+
+/// This process's `installer-*.txt` log, else the newest one in the log folder.
+pub fn current_text_log_path() -> Option<PathBuf> {
+    if let Some(p) = CURRENT_TEXT_LOG.get() {
+        return Some(p.clone());
+    }
+    let dir = crate::utils::path_resolver::resolve_log_folder().ok()?;
+    newest_text_log(&dir)
+}
+
+/// Newest `installer-<timestamp>.txt` in `dir` (timestamped names sort chronologically).
+fn newest_text_log(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("installer-") && n.ends_with(".txt"))
+        })
+        .max()
+}
+
+/// Add `key` to the `context` of every JSON log record until [`clear_log_context`].
+pub fn set_log_context(key: &str, value: impl Into<serde_json::Value>) {
+    if let Ok(mut context) = LOG_CONTEXT.lock() {
+        context.insert(key.to_string(), value.into());
+    }
+}
+
+pub fn clear_log_context() {
+    if let Ok(mut context) = LOG_CONTEXT.lock() {
+        context.clear();
+    }
+}
+
+/// Current log context (correlation id, install mode, ...), `None` when empty.
+pub fn log_context() -> Option<HashMap<String, serde_json::Value>> {
+    let context = LOG_CONTEXT.lock().ok()?;
+    (!context.is_empty()).then(|| context.clone().into_iter().collect())
+}
+
+/// Remember the phase/step of the latest log record that had them (for crash reports).
+pub fn note_position(phase: Option<&str>, step: Option<&str>) {
+    if phase.is_none() && step.is_none() {
+        return;
+    }
+    if let Ok(mut last) = LAST_POSITION.lock() {
+        *last = (phase.map(str::to_string), step.map(str::to_string));
+    }
+}
+
+/// Phase/step of the latest log record that had them.
+pub fn last_position() -> (Option<String>, Option<String>) {
+    LAST_POSITION
+        .lock()
+        .map(|last| last.clone())
+        .unwrap_or_default()
+}
 
 /// Mask sensitive data in logs
 pub fn mask_sensitive(input: &str) -> String {
@@ -137,6 +207,36 @@ pub fn parse_log_metadata(message: &str) -> (Option<String>, Option<String>, Str
     (phase, step, cleaned_message)
 }
 
+/// Extract the `[CODE: E2004]` error code tag (see `crate::error`) from a log message
+pub fn parse_error_code(message: &str) -> (Option<String>, String) {
+    if let Some(start) = message.find("[CODE:") {
+        if let Some(end) = message[start..].find(']') {
+            let code = message[start + 6..start + end].trim().to_string();
+            let cleaned = format!("{} {}", &message[..start], &message[start + end + 1..])
+                .trim()
+                .to_string();
+            return (Some(code), cleaned);
+        }
+    }
+    (None, message.to_string())
+}
+
+/// Extract the `[PERF: {...}]` metrics tag (see `crate::utils::metrics`) from a log message
+pub fn parse_perf(message: &str) -> (Option<HashMap<String, serde_json::Value>>, String) {
+    if let Some(start) = message.find("[PERF:") {
+        if let Some(end) = message[start..].find("}]") {
+            let json = &message[start + 6..start + end + 1];
+            if let Ok(perf) = serde_json::from_str(json.trim()) {
+                let cleaned = format!("{} {}", &message[..start], &message[start + end + 2..])
+                    .trim()
+                    .to_string();
+                return (Some(perf), cleaned);
+            }
+        }
+    }
+    (None, message.to_string())
+}
+
 /// Format log entry as JSON for structured logging
 #[allow(clippy::too_many_arguments)]
 pub fn format_json_log(
@@ -408,6 +508,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_code_tag_is_extracted_for_json_logs() {
+        let (phase, step, msg) = parse_log_metadata(
+            "[PHASE: install] [STEP: error] [CODE: E2004] Installation failed: boom",
+        );
+        assert_eq!(phase.as_deref(), Some("install"));
+        assert_eq!(step.as_deref(), Some("error"));
+        let (code, msg) = parse_error_code(&msg);
+        assert_eq!(code.as_deref(), Some("E2004"));
+        assert_eq!(msg, "Installation failed: boom");
+
+        assert_eq!(parse_error_code("no code"), (None, "no code".to_string()));
+    }
+
+    #[test]
+    fn perf_tag_is_extracted_for_json_logs() {
+        let (_, step, msg) = parse_log_metadata(
+            r#"[PHASE: install] [STEP: migrations] [PERF: {"durationMs":12,"bytes":0}] Step finished in 12 ms"#,
+        );
+        assert_eq!(step.as_deref(), Some("migrations"));
+        let (perf, msg) = parse_perf(&msg);
+        let perf = perf.unwrap();
+        assert_eq!(perf["durationMs"], 12);
+        assert_eq!(msg, "Step finished in 12 ms");
+        assert_eq!(parse_perf("[PERF: not json}]").0, None);
+
+        let line = format_json_log(
+            "t",
+            Level::Info,
+            "x",
+            &msg,
+            None,
+            step.as_deref(),
+            None,
+            None,
+            Some(&perf),
+        );
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["performance"]["durationMs"], 12);
+    }
+
+    #[test]
+    fn newest_text_log_picks_latest_timestamp() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in [
+            "installer-2026-01-02-090000.txt",
+            "installer-2026-01-03-080000.log",
+            "installer-2026-01-01-230000.txt",
+            "preflight-report.txt",
+        ] {
+            std::fs::write(tmp.path().join(name), "").unwrap();
+        }
+        assert_eq!(
+            newest_text_log(tmp.path()),
+            Some(tmp.path().join("installer-2026-01-02-090000.txt"))
+        );
+    }
+
     #[test]
     fn mask_postgres_url_no_password() {
         // URL with user but no password
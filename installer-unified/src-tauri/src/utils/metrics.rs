@@ -0,0 +1,270 @@
+//! Per-step performance metrics for the structured (JSON) log.
+//!
+//! Install and archive runs are timed from their progress updates: a step starts with its first
+//! update and ends when another step reports or the run ends. Each finished step is logged with a
+//! `[PERF: {...}]` tag, which the JSON log moves into its `performance` field: `durationMs`,
+//! `events` (progress updates), `bytes` (file bytes copied, written or uploaded) and `retries`
+//! (attempts repeated after a transient failure). When the run ends, one `perf_summary` record per
+//! phase totals its steps; the perf smoke reads those back through [`take_summaries`].
+//!
+//! Bytes and retries are process-wide counters charged to whichever step is open; the installer
+//! runs one install or archive run at a time. Each step is also a tracing span named
+//! `<phase>.<step>` (exported with the `otel` feature, see `utils::otel`).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use log::info;
+
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static SUMMARIES: Mutex<Vec<PhaseSummary>> = Mutex::new(Vec::new());
+
+/// Count `n` bytes copied, written or uploaded.
+pub fn add_bytes(n: u64) {
+    BYTES.fetch_add(n, Ordering::Relaxed);
+}
+
+/// Count one retried attempt.
+pub fn note_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepMetrics {
+    pub phase: String,
+    pub step: String,
+    /// False for the step a failed or cancelled run stopped in.
+    pub ok: bool,
+    pub duration_ms: u64,
+    pub events: u64,
+    pub bytes: u64,
+    pub retries: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseSummary {
+    pub phase: String,
+    pub ok: bool,
+    pub steps: usize,
+    pub events: u64,
+    pub duration_ms: u64,
+    pub bytes: u64,
+    pub retries: u64,
+    /// Longest wait between two progress updates.
+    pub max_event_gap_ms: u64,
+    pub slowest_step: String,
+    pub slowest_step_ms: u64,
+}
+
+struct OpenStep {
+    phase: String,
+    step: String,
+    started: Instant,
+    events: u64,
+    bytes_at_start: u64,
+    retries_at_start: u64,
+    span: tracing::Span,
+}
+
+#[derive(Default)]
+struct Recorder {
+    open: Option<OpenStep>,
+    done: Vec<StepMetrics>,
+    last_event: Option<Instant>,
+    /// (phase, longest gap in ms) in first-seen order.
+    gaps: Vec<(String, u64)>,
+}
+
+impl Recorder {
+    fn close(&mut self, ok: bool) {
+        let Some(open) = self.open.take() else {
+            return;
+        };
+        let metrics = StepMetrics {
+            phase: open.phase,
+            step: open.step,
+            ok,
+            duration_ms: open.started.elapsed().as_millis() as u64,
+            events: open.events,
+            bytes: BYTES.load(Ordering::Relaxed) - open.bytes_at_start,
+            retries: RETRIES.load(Ordering::Relaxed) - open.retries_at_start,
+        };
+        open.span.record("ok", metrics.ok);
+        open.span.record("events", metrics.events);
+        open.span.record("bytes", metrics.bytes);
+        open.span.record("retries", metrics.retries);
+        info!(
+            "[PHASE: {}] [STEP: {}] [PERF: {}] Step finished in {} ms",
+            metrics.phase,
+            metrics.step,
+            perf_json(&metrics),
+            metrics.duration_ms
+        );
+        self.done.push(metrics);
+    }
+
+    fn note_gap(&mut self, phase: &str, gap_ms: u64) {
+        match self.gaps.iter_mut().find(|(p, _)| p == phase) {
+            Some((_, max)) => *max = (*max).max(gap_ms),
+            None => self.gaps.push((phase.to_string(), gap_ms)),
+        }
+    }
+
+    fn summaries(&self) -> Vec<PhaseSummary> {
+        self.gaps
+            .iter()
+            .map(|(phase, max_gap)| {
+                let mut summary = PhaseSummary {
+                    phase: phase.clone(),
+                    ok: true,
+                    max_event_gap_ms: *max_gap,
+                    ..PhaseSummary::default()
+                };
+                for step in self.done.iter().filter(|s| &s.phase == phase) {
+                    summary.ok &= step.ok;
+                    summary.steps += 1;
+                    summary.events += step.events;
+                    summary.duration_ms += step.duration_ms;
+                    summary.bytes += step.bytes;
+                    summary.retries += step.retries;
+                    if step.duration_ms >= summary.slowest_step_ms {
+                        summary.slowest_step = step.step.clone();
+                        summary.slowest_step_ms = step.duration_ms;
+                    }
+                }
+                summary
+            })
+            .collect()
+    }
+}
+
+/// Metrics fields without the phase/step, which the log record already carries.
+fn perf_json(value: &impl serde::Serialize) -> String {
+    let mut json = serde_json::to_value(value).unwrap_or_default();
+    if let Some(map) = json.as_object_mut() {
+        map.remove("phase");
+        map.remove("step");
+    }
+    json.to_string()
+}
+
+/// Step timings of one run, fed by its progress updates.
+#[derive(Default)]
+pub struct StepRecorder(Mutex<Recorder>);
+
+impl StepRecorder {
+    /// A progress update for `step` of `phase`.
+    pub fn observe(&self, phase: &str, step: &str) {
+        let Ok(mut recorder) = self.0.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let gap_ms = recorder
+            .last_event
+            .map_or(0, |last| now.duration_since(last).as_millis() as u64);
+        recorder.last_event = Some(now);
+        recorder.note_gap(phase, gap_ms);
+
+        if let Some(open) = recorder.open.as_mut() {
+            if open.phase == phase && open.step == step {
+                open.events += 1;
+                return;
+            }
+        }
+        recorder.close(true);
+        recorder.open = Some(OpenStep {
+            phase: phase.to_string(),
+            step: step.to_string(),
+            started: now,
+            events: 1,
+            bytes_at_start: BYTES.load(Ordering::Relaxed),
+            retries_at_start: RETRIES.load(Ordering::Relaxed),
+            span: tracing::info_span!(
+                "step",
+                otel.name = %format!("{}.{}", phase, step),
+                phase,
+                step,
+                ok = tracing::field::Empty,
+                events = tracing::field::Empty,
+                bytes = tracing::field::Empty,
+                retries = tracing::field::Empty,
+            ),
+        });
+    }
+
+    /// End the run: close the open step and log one summary record per phase.
+    pub fn finish(&self, ok: bool) -> Vec<PhaseSummary> {
+        let Ok(mut recorder) = self.0.lock() else {
+            return Vec::new();
+        };
+        recorder.close(ok);
+        let summaries = recorder.summaries();
+        for summary in &summaries {
+            info!(
+                "[PHASE: {}] [STEP: perf_summary] [PERF: {}] {} steps, {} progress updates in {} ms",
+                summary.phase,
+                perf_json(summary),
+                summary.steps,
+                summary.events,
+                summary.duration_ms
+            );
+        }
+        if let Ok(mut all) = SUMMARIES.lock() {
+            all.extend(summaries.iter().cloned());
+        }
+        summaries
+    }
+}
+
+/// Phase summaries of every run finished since the last call.
+pub fn take_summaries() -> Vec<PhaseSummary> {
+    SUMMARIES
+        .lock()
+        .map(|mut all| std::mem::take(&mut *all))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_timed_from_progress_updates_and_summarized_per_phase() {
+        let recorder = StepRecorder::default();
+        recorder.observe("install", "start");
+        recorder.observe("install", "migrations");
+        add_bytes(10);
+        note_retry();
+        recorder.observe("install", "migrations");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        recorder.observe("install", "deploy_files");
+        add_bytes(4096);
+        let summaries = recorder.finish(false);
+
+        let done = &recorder.0.lock().unwrap().done;
+        let steps: Vec<_> = done.iter().map(|s| (s.step.as_str(), s.events)).collect();
+        assert_eq!(
+            steps,
+            vec![("start", 1), ("migrations", 2), ("deploy_files", 1)]
+        );
+        // Other tests may bump the shared counters, so only lower bounds hold.
+        assert!(done[1].bytes >= 10 && done[1].retries >= 1);
+        assert!(done[1].duration_ms >= 20);
+        assert!(done[2].bytes >= 4096 && !done[2].ok);
+
+        assert_eq!(summaries.len(), 1);
+        let install = &summaries[0];
+        assert_eq!((install.steps, install.events, install.ok), (3, 4, false));
+        assert_eq!(install.slowest_step, "migrations");
+        assert!(install.max_event_gap_ms >= 20);
+        assert!(take_summaries().contains(install));
+
+        let perf: serde_json::Value = serde_json::from_str(&perf_json(&done[0])).unwrap();
+        assert_eq!(perf["events"], 1);
+        assert!(perf.get("step").is_none());
+    }
+}
@@ -1,5 +1,15 @@
+pub mod connection_profiles;
+pub mod crash;
 pub mod disk;
+pub mod env_defaults;
+pub mod http;
+pub mod i18n;
+pub mod instance;
 pub mod logging;
+pub mod metrics;
 pub mod os_detection;
+pub mod otel;
 pub mod path_resolver;
+pub mod retry;
+pub mod throttle;
 pub mod validation;
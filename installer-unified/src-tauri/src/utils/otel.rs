@@ -0,0 +1,125 @@
+//! Opt-in OpenTelemetry export of the installer's tracing spans (OTLP over HTTP).
+//!
+//! Built with the `otel` feature and enabled at run time by `CADALYTIX_OTLP_ENDPOINT`, the OTLP
+//! traces URL of the customer's collector (e.g. `http://otel-collector:4318/v1/traces`).
+//! Spans cover the install run and each of its steps, DB connects, migrations and seeding, and
+//! archive runs per month; every span carries the correlation id of its run. Spans hold no
+//! connection strings or other secrets.
+//!
+//! Export runs on its own background thread and never blocks or fails an install; spans are
+//! flushed at the end of each install and archive run. Without the feature (or the variable) the
+//! spans are created but go nowhere.
+
+use log::warn;
+
+const ENDPOINT_ENV: &str = "CADALYTIX_OTLP_ENDPOINT";
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "cadalytix-installer";
+
+fn endpoint(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    var(ENDPOINT_ENV)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Start exporting spans when `CADALYTIX_OTLP_ENDPOINT` is set. Called once, with logging.
+pub fn init() {
+    let Some(endpoint) = endpoint(|name| std::env::var(name).ok()) else {
+        return;
+    };
+    #[cfg(feature = "otel")]
+    match exporter::init(&endpoint) {
+        Ok(()) => log::info!(
+            "[PHASE: initialization] [STEP: otel] Exporting traces to {}",
+            endpoint
+        ),
+        Err(e) => warn!(
+            "[PHASE: initialization] [STEP: otel] Trace export disabled: {:?}",
+            e
+        ),
+    }
+    #[cfg(not(feature = "otel"))]
+    warn!(
+        "[PHASE: initialization] [STEP: otel] {} is set to {} but this installer was built without the `otel` feature; traces are not exported",
+        ENDPOINT_ENV, endpoint
+    );
+}
+
+/// Push finished spans to the collector (end of an install or archive run).
+pub fn flush() {
+    #[cfg(feature = "otel")]
+    exporter::flush();
+}
+
+#[cfg(feature = "otel")]
+mod exporter {
+    use std::sync::OnceLock;
+
+    use anyhow::Result;
+    use log::warn;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::trace::TracerProvider;
+    use opentelemetry_sdk::{runtime, Resource};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::SERVICE_NAME;
+
+    /// The export runtime lives as long as the provider; its batch task sends the spans.
+    static PROVIDER: OnceLock<(tokio::runtime::Runtime, TracerProvider)> = OnceLock::new();
+
+    pub(super) fn init(endpoint: &str) -> Result<()> {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("otel-export")
+            .enable_all()
+            .build()?;
+        let provider = {
+            let _entered = rt.enter();
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .build()?;
+            TracerProvider::builder()
+                .with_batch_exporter(exporter, runtime::Tokio)
+                .with_resource(Resource::new([
+                    KeyValue::new("service.name", SERVICE_NAME),
+                    KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+                ]))
+                .build()
+        };
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))?;
+        let _ = PROVIDER.set((rt, provider));
+        Ok(())
+    }
+
+    pub(super) fn flush() {
+        let Some((_, provider)) = PROVIDER.get() else {
+            return;
+        };
+        for result in provider.force_flush() {
+            if let Err(e) = result {
+                warn!("[PHASE: otel] [STEP: flush] Trace export failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_is_opt_in() {
+        assert_eq!(endpoint(|_| None), None);
+        assert_eq!(endpoint(|_| Some("  ".to_string())), None);
+        assert_eq!(
+            endpoint(|name| {
+                (name == ENDPOINT_ENV).then(|| " http://collector:4318/v1/traces ".to_string())
+            }),
+            Some("http://collector:4318/v1/traces".to_string())
+        );
+    }
+}
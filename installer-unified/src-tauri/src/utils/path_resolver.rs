@@ -16,8 +16,30 @@ pub fn resolve_deployment_folder() -> Result<PathBuf> {
     Ok(cwd)
 }
 
-/// Resolve log folder (absolute path)
+/// Whole log folder of this process; set by a fleet install (`--fleet`) for each host's installer
+/// and by the `install.cmd` of a distribution package (`--package`).
+pub const LOG_FOLDER_ENV: &str = "CADALYTIX_INSTALLER_LOG_FOLDER";
+
+/// Resolve log folder (absolute path). A named instance (`--instance=`) gets its own
+/// `Prod_Wizard_Log/instances/<NAME>/`, so its logs, secrets key and manifests stay apart.
 pub fn resolve_log_folder() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(LOG_FOLDER_ENV).filter(|v| !v.is_empty()) {
+        let dir = PathBuf::from(dir);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| anyhow::anyhow!("Failed to create log folder: {}", e))?;
+        return Ok(dir);
+    }
+    let base = resolve_base_log_folder()?;
+    let Some(name) = crate::utils::instance::name() else {
+        return Ok(base);
+    };
+    let dir = base.join("instances").join(name);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| anyhow::anyhow!("Failed to create log folder: {}", e))?;
+    Ok(dir)
+}
+
+fn resolve_base_log_folder() -> Result<PathBuf> {
     // Prefer a repo/workspace-level log folder. When running from nested dirs like
     // `.../installer-unified/src-tauri`, we MUST NOT create `Prod_Wizard_Log/` inside
     // those subdirectories.
@@ -0,0 +1,196 @@
+//! Shared retry/backoff policy for transient failures (database connects, licensing calls,
+//! object-store uploads, external commands, secret-file writes).
+//!
+//! Defaults: 3 retries, 100ms doubling up to 2s between them, 20s per connect attempt.
+//! Overrides, lowest precedence first:
+//! - `retry_policy.json` next to the installer executable (or the file named by
+//!   `CADALYTIX_RETRY_CONFIG`), e.g. `{ "maxRetries": 5, "attemptTimeoutSecs": 60 }`
+//! - `CADALYTIX_RETRY_MAX_RETRIES`, `CADALYTIX_RETRY_BASE_DELAY_MS`,
+//!   `CADALYTIX_RETRY_MAX_DELAY_MS`, `CADALYTIX_RETRY_ATTEMPT_TIMEOUT_SECS`
+//!
+//! The policy is resolved once per process; out-of-range values are clamped.
+
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio_retry::strategy::{jitter, ExponentialBackoff};
+
+const CONFIG_FILE_NAME: &str = "retry_policy.json";
+const CONFIG_PATH_ENV: &str = "CADALYTIX_RETRY_CONFIG";
+const MAX_RETRIES_ENV: &str = "CADALYTIX_RETRY_MAX_RETRIES";
+const BASE_DELAY_ENV: &str = "CADALYTIX_RETRY_BASE_DELAY_MS";
+const MAX_DELAY_ENV: &str = "CADALYTIX_RETRY_MAX_DELAY_MS";
+const ATTEMPT_TIMEOUT_ENV: &str = "CADALYTIX_RETRY_ATTEMPT_TIMEOUT_SECS";
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 100;
+const DEFAULT_MAX_DELAY_MS: u64 = 2_000;
+const DEFAULT_ATTEMPT_TIMEOUT_SECS: u64 = 20;
+
+static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+/// How often and how patiently transient failures are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles per retry up to `max_delay`.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Upper bound for a single connect attempt.
+    pub attempt_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            attempt_timeout: Duration::from_secs(DEFAULT_ATTEMPT_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// Partial overrides, as read from the config file or environment.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RetryOverrides {
+    max_retries: Option<u32>,
+    base_delay_ms: Option<u64>,
+    max_delay_ms: Option<u64>,
+    attempt_timeout_secs: Option<u64>,
+}
+
+impl RetryOverrides {
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: std::str::FromStr>(value: Option<String>) -> Option<T> {
+            value.and_then(|v| v.trim().parse().ok())
+        }
+        Self {
+            max_retries: parse(var(MAX_RETRIES_ENV)),
+            base_delay_ms: parse(var(BASE_DELAY_ENV)),
+            max_delay_ms: parse(var(MAX_DELAY_ENV)),
+            attempt_timeout_secs: parse(var(ATTEMPT_TIMEOUT_ENV)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Apply `overrides` on top of `self`, clamping to sane bounds.
+    fn with_overrides(self, overrides: &RetryOverrides) -> Self {
+        let max_retries = overrides.max_retries.unwrap_or(self.max_retries).min(10);
+        let base_ms = overrides
+            .base_delay_ms
+            .unwrap_or(self.base_delay.as_millis() as u64)
+            .clamp(10, 60_000);
+        let max_ms = overrides
+            .max_delay_ms
+            .unwrap_or(self.max_delay.as_millis() as u64)
+            .clamp(base_ms, 300_000);
+        let timeout_secs = overrides
+            .attempt_timeout_secs
+            .unwrap_or(self.attempt_timeout.as_secs())
+            .clamp(1, 600);
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(base_ms),
+            max_delay: Duration::from_millis(max_ms),
+            attempt_timeout: Duration::from_secs(timeout_secs),
+        }
+    }
+
+    /// Jittered exponential delays between attempts, for `tokio_retry::RetryIf::spawn`.
+    /// Each delay taken counts as a retry in the step metrics (`utils::metrics`).
+    pub fn backoff(&self) -> impl Iterator<Item = Duration> {
+        ExponentialBackoff::from_millis(self.base_delay.as_millis() as u64)
+            .factor(2)
+            .max_delay(self.max_delay)
+            .take(self.max_retries as usize)
+            .map(jitter)
+            .inspect(|_| super::metrics::note_retry())
+    }
+}
+
+/// The process-wide retry policy (defaults, then config file, then environment).
+pub fn policy() -> &'static RetryPolicy {
+    POLICY.get_or_init(|| {
+        let env = |name: &str| std::env::var(name).ok();
+        let resolved = RetryPolicy::default()
+            .with_overrides(&read_config_file())
+            .with_overrides(&RetryOverrides::from_env(env));
+        if resolved != RetryPolicy::default() {
+            info!(
+                "[PHASE: initialization] [STEP: retry_policy] Using retry policy overrides (max_retries={}, base_delay_ms={}, max_delay_ms={}, attempt_timeout_secs={})",
+                resolved.max_retries,
+                resolved.base_delay.as_millis(),
+                resolved.max_delay.as_millis(),
+                resolved.attempt_timeout.as_secs()
+            );
+        }
+        resolved
+    })
+}
+
+fn config_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os(CONFIG_PATH_ENV) {
+        return Some(PathBuf::from(p));
+    }
+    super::path_resolver::resolve_deployment_folder()
+        .ok()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+fn read_config_file() -> RetryOverrides {
+    let Some(path) = config_path().filter(|p| p.is_file()) else {
+        return RetryOverrides::default();
+    };
+    match std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|s| serde_json::from_str(&s).map_err(anyhow::Error::from))
+    {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            warn!(
+                "[PHASE: initialization] [STEP: retry_policy] Ignoring unreadable retry policy file {:?}: {}",
+                path, e
+            );
+            RetryOverrides::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_layer_and_clamp() {
+        let file: RetryOverrides =
+            serde_json::from_str(r#"{ "maxRetries": 5, "attemptTimeoutSecs": 60 }"#).unwrap();
+        let env = RetryOverrides::from_env(|name| match name {
+            MAX_RETRIES_ENV => Some(" 50 ".to_string()),
+            BASE_DELAY_ENV => Some("1".to_string()),
+            MAX_DELAY_ENV => Some("not-a-number".to_string()),
+            _ => None,
+        });
+        let policy = RetryPolicy::default()
+            .with_overrides(&file)
+            .with_overrides(&env);
+
+        assert_eq!(policy.max_retries, 10);
+        assert_eq!(policy.base_delay, Duration::from_millis(10));
+        assert_eq!(
+            policy.max_delay,
+            Duration::from_millis(DEFAULT_MAX_DELAY_MS)
+        );
+        assert_eq!(policy.attempt_timeout, Duration::from_secs(60));
+        assert_eq!(policy.backoff().count(), 10);
+        assert_eq!(
+            RetryPolicy::default().with_overrides(&RetryOverrides::default()),
+            RetryPolicy::default()
+        );
+    }
+}
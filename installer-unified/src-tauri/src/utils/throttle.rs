@@ -0,0 +1,150 @@
+//! Bandwidth throttle for bulk file writes (payload deployment, archive ZIPs).
+//!
+//! Production hosts often share storage with a live CAD system, so installs and archive runs
+//! can be capped in MB/s. `CADALYTIX_IO_THROTTLE_MBPS` sets the process default (0 or unset =
+//! unlimited); archive runs may override it with `Archive:ThrottleMBps`.
+//!
+//! The throttle is a running average: after each chunk the caller is held back until the bytes
+//! written so far fit inside the limit, so short bursts are allowed but the total rate is not.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+
+const THROTTLE_ENV: &str = "CADALYTIX_IO_THROTTLE_MBPS";
+const BYTES_PER_MB: u64 = 1024 * 1024;
+/// Anything above this is effectively unlimited; also keeps the byte math far from overflow.
+const MAX_MBPS: u32 = 100_000;
+
+/// Process-wide default limit in MB/s from `CADALYTIX_IO_THROTTLE_MBPS` (0 = unlimited).
+pub fn default_mbps() -> u32 {
+    parse_mbps(std::env::var(THROTTLE_ENV).ok().as_deref())
+}
+
+fn parse_mbps(value: Option<&str>) -> u32 {
+    value
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .map(|n| n.min(MAX_MBPS))
+        .unwrap_or(0)
+}
+
+/// Rate limiter for one stream of writes.
+#[derive(Debug, Clone)]
+pub struct IoThrottle {
+    bytes_per_sec: u64,
+    started: Instant,
+    written: u64,
+}
+
+impl IoThrottle {
+    /// `mbps == 0` disables throttling.
+    pub fn new(mbps: u32) -> Self {
+        Self {
+            bytes_per_sec: u64::from(mbps.min(MAX_MBPS)) * BYTES_PER_MB,
+            started: Instant::now(),
+            written: 0,
+        }
+    }
+
+    /// Throttle at the process default (`CADALYTIX_IO_THROTTLE_MBPS`).
+    pub fn from_env() -> Self {
+        Self::new(default_mbps())
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.bytes_per_sec > 0
+    }
+
+    /// Minimum time `bytes` take at this limit (zero when unlimited). Used to widen timeouts.
+    pub fn min_duration(&self, bytes: u64) -> Duration {
+        if self.bytes_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(bytes as f64 / self.bytes_per_sec as f64)
+    }
+
+    /// Record `n` more bytes and return how long to pause to stay under the limit.
+    fn record(&mut self, n: usize, elapsed: Duration) -> Duration {
+        if self.bytes_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        self.written = self.written.saturating_add(n as u64);
+        self.min_duration(self.written).saturating_sub(elapsed)
+    }
+
+    /// Account for `n` bytes just written, sleeping on the tokio timer if ahead of the limit.
+    pub async fn consume(&mut self, n: usize) {
+        let pause = self.record(n, self.started.elapsed());
+        if !pause.is_zero() {
+            tokio::time::sleep(pause).await;
+        }
+    }
+
+    /// Blocking variant for synchronous writers (see `ThrottledWriter`).
+    pub fn consume_blocking(&mut self, n: usize) {
+        let pause = self.record(n, self.started.elapsed());
+        if !pause.is_zero() {
+            std::thread::sleep(pause);
+        }
+    }
+}
+
+/// `Write` (+ `Seek`) adapter that throttles a synchronous writer such as the archive ZIP.
+///
+/// Pauses block the calling thread, so it must only run off the async runtime (`spawn_blocking`).
+pub struct ThrottledWriter<W> {
+    inner: W,
+    throttle: IoThrottle,
+}
+
+impl<W> ThrottledWriter<W> {
+    pub fn new(inner: W, throttle: IoThrottle) -> Self {
+        Self { inner, throttle }
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.throttle.consume_blocking(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for ThrottledWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_parses_and_paces_writes() {
+        assert_eq!(parse_mbps(None), 0);
+        assert_eq!(parse_mbps(Some("fast")), 0);
+        assert_eq!(parse_mbps(Some(" 25 ")), 25);
+        assert_eq!(parse_mbps(Some("999999999")), MAX_MBPS);
+
+        let mut unlimited = IoThrottle::new(0);
+        assert!(!unlimited.is_limited());
+        assert_eq!(unlimited.record(1 << 30, Duration::ZERO), Duration::ZERO);
+
+        // 2 MB/s: 1 MB written instantly must wait 500ms; writing it over 2s needs no pause.
+        let mut t = IoThrottle::new(2);
+        assert_eq!(
+            t.record(BYTES_PER_MB as usize, Duration::ZERO),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            t.record(BYTES_PER_MB as usize, Duration::from_secs(2)),
+            Duration::ZERO
+        );
+        assert_eq!(t.min_duration(4 * BYTES_PER_MB), Duration::from_secs(2));
+    }
+}
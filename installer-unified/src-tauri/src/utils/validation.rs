@@ -8,6 +8,28 @@ use regex::Regex;
 /// Security: this is used to prevent SQL injection when we need to interpolate an identifier (not a value).
 /// We intentionally only allow simple identifiers (letters/numbers/underscore) and dot-separated parts.
 pub fn validate_and_quote_sql_server_object(object_name: &str) -> Result<String> {
+    let parts = validate_object_parts(object_name)?;
+    Ok(parts
+        .iter()
+        .map(|p| format!("[{}]", p))
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+/// Same rules as [`validate_and_quote_sql_server_object`], quoting each part with the ODBC
+/// driver's identifier quote (`SQL_IDENTIFIER_QUOTE_CHAR`; a blank quote leaves parts bare).
+pub fn validate_and_quote_odbc_object(object_name: &str, quote: &str) -> Result<String> {
+    let parts = validate_object_parts(object_name)?;
+    let quote = quote.trim();
+    Ok(parts
+        .iter()
+        .map(|p| format!("{}{}{}", quote, p, quote))
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+/// The unquoted parts of a one-, two- or three-part object name.
+pub fn validate_object_parts(object_name: &str) -> Result<Vec<String>> {
     let s = object_name.trim();
     if s.is_empty() {
         return Err(anyhow::anyhow!("SourceObjectName is required"));
@@ -36,7 +58,7 @@ pub fn validate_and_quote_sql_server_object(object_name: &str) -> Result<String>
     let ident_re = Regex::new(r"^[A-Za-z0-9_]+$").map_err(|e| {
         anyhow::anyhow!("Internal error: failed to compile identifier regex: {}", e)
     })?;
-    let mut quoted_parts = Vec::new();
+    let mut out = Vec::new();
 
     for raw in parts {
         let p = raw.trim().trim_matches(['[', ']', '"', '\'']);
@@ -51,10 +73,10 @@ pub fn validate_and_quote_sql_server_object(object_name: &str) -> Result<String>
                 p
             ));
         }
-        quoted_parts.push(format!("[{}]", p));
+        out.push(p.to_string());
     }
 
-    Ok(quoted_parts.join("."))
+    Ok(out)
 }
 
 /// Validate database name (SQL Server)
@@ -115,3 +137,63 @@ pub fn validate_connection_string(conn_str: &str) -> Result<()> {
     // Basic validation - more specific validation in database module
     Ok(())
 }
+
+/// Validate a host name, IPv4 or IPv6 address (optionally `[bracketed]`), with an optional
+/// SQL Server named instance (`host\INSTANCE`).
+pub fn validate_host(host: &str) -> Result<()> {
+    let (name, instance) = match host.split_once('\\') {
+        Some((name, instance)) => (name, Some(instance)),
+        None => (host, None),
+    };
+    if let Some(instance) = instance {
+        let instance_re = Regex::new(r"^[A-Za-z_][A-Za-z0-9_$]{0,15}$")?;
+        if !instance_re.is_match(instance) {
+            return Err(anyhow::anyhow!("Invalid SQL Server instance name"));
+        }
+    }
+    let bare = name
+        .strip_prefix('[')
+        .and_then(|n| n.strip_suffix(']'))
+        .unwrap_or(name);
+    if bare.parse::<std::net::IpAddr>().is_ok() {
+        return Ok(());
+    }
+    let label_re = Regex::new(r"^[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?$")?;
+    if bare.is_empty() || bare.len() > 253 || !bare.split('.').all(|l| label_re.is_match(l)) {
+        return Err(anyhow::anyhow!("Invalid host name"));
+    }
+    Ok(())
+}
+
+/// Validate the syntax of an absolute path: `/...`, `C:\...` (or `C:/...`) or a UNC `\\server\share`.
+/// Nothing is checked on disk.
+pub fn validate_absolute_path(path: &str) -> Result<()> {
+    if path.chars().any(|c| c.is_control()) {
+        return Err(anyhow::anyhow!("Path contains control characters"));
+    }
+    if path.starts_with('/') {
+        return Ok(());
+    }
+    let b = path.as_bytes();
+    let windows_rest = if b.len() >= 3
+        && b[0].is_ascii_alphabetic()
+        && b[1] == b':'
+        && (b[2] == b'\\' || b[2] == b'/')
+    {
+        &path[3..]
+    } else if let Some(rest) = path.strip_prefix("\\\\") {
+        if rest.split('\\').filter(|p| !p.is_empty()).count() < 2 {
+            return Err(anyhow::anyhow!("UNC path needs a server and a share"));
+        }
+        rest
+    } else {
+        return Err(anyhow::anyhow!("Path must be absolute"));
+    };
+    if windows_rest
+        .chars()
+        .any(|c| matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*'))
+    {
+        return Err(anyhow::anyhow!("Path contains invalid characters"));
+    }
+    Ok(())
+}